@@ -17,8 +17,10 @@ pub mod transactional;
 
 use crate::storage::storage_api::{
     block_aux_data::{BlockAuxData, BlockWithExtraData},
-    ApiServerStorageError, BlockInfo, CoinOrTokenStatistic, Delegation, FungibleTokenData,
-    LockedUtxo, PoolBlockStats, TransactionInfo, Utxo, UtxoLock, UtxoWithExtraInfo,
+    scan_checkpoint::ScanCheckpoint,
+    AddressCluster, ApiServerStorageError, BlockInfo, CoinOrTokenStatistic, Delegation,
+    DelegationReward, FungibleTokenData, LockedUtxo, PoolBlockStats, TokenMetadataCacheEntry,
+    TransactionInfo, Utxo, UtxoLock, UtxoSpender, UtxoWithExtraInfo,
 };
 use common::{
     chain::{
@@ -29,6 +31,7 @@ use common::{
     primitives::{id::WithId, Amount, BlockHeight, CoinOrTokenId, Id},
 };
 use pos_accounting::PoolData;
+use serialization::Encode;
 use std::{
     cmp::Reverse,
     collections::{BTreeMap, BTreeSet},
@@ -45,7 +48,11 @@ struct ApiServerInMemoryStorage {
     address_balance_table: BTreeMap<String, BTreeMap<(CoinOrTokenId, BlockHeight), Amount>>,
     address_locked_balance_table: BTreeMap<String, BTreeMap<(CoinOrTokenId, BlockHeight), Amount>>,
     address_transactions_table: BTreeMap<String, BTreeMap<BlockHeight, Vec<Id<Transaction>>>>,
+    /// The parent of each clustered address in its cluster's union-find tree,
+    /// and the size of the cluster for the roots
+    address_cluster_table: BTreeMap<String, BTreeMap<BlockHeight, (String, u64)>>,
     delegation_table: BTreeMap<DelegationId, BTreeMap<BlockHeight, Delegation>>,
+    delegation_rewards_table: BTreeMap<DelegationId, BTreeMap<BlockHeight, DelegationReward>>,
     main_chain_blocks_table: BTreeMap<BlockHeight, Id<Block>>,
     pool_data_table: BTreeMap<PoolId, BTreeMap<BlockHeight, PoolData>>,
     transaction_table: BTreeMap<Id<Transaction>, (Option<Id<Block>>, TransactionInfo)>,
@@ -55,11 +62,14 @@ struct ApiServerInMemoryStorage {
     address_locked_utxos: BTreeMap<String, BTreeSet<UtxoOutPoint>>,
     fungible_token_issuances: BTreeMap<TokenId, BTreeMap<BlockHeight, FungibleTokenData>>,
     nft_token_issuances: BTreeMap<TokenId, BTreeMap<BlockHeight, NftIssuance>>,
+    token_metadata_cache: BTreeMap<TokenId, TokenMetadataCacheEntry>,
     statistics:
         BTreeMap<CoinOrTokenStatistic, BTreeMap<CoinOrTokenId, BTreeMap<BlockHeight, Amount>>>,
     best_block: BlockAuxData,
     genesis_block: Arc<WithId<Genesis>>,
     storage_version: u32,
+    pruned_height: Option<BlockHeight>,
+    scan_checkpoint: Option<ScanCheckpoint>,
 }
 
 impl ApiServerInMemoryStorage {
@@ -70,7 +80,9 @@ impl ApiServerInMemoryStorage {
             address_balance_table: BTreeMap::new(),
             address_locked_balance_table: BTreeMap::new(),
             address_transactions_table: BTreeMap::new(),
+            address_cluster_table: BTreeMap::new(),
             delegation_table: BTreeMap::new(),
+            delegation_rewards_table: BTreeMap::new(),
             main_chain_blocks_table: BTreeMap::new(),
             pool_data_table: BTreeMap::new(),
             transaction_table: BTreeMap::new(),
@@ -80,6 +92,7 @@ impl ApiServerInMemoryStorage {
             address_locked_utxos: BTreeMap::new(),
             fungible_token_issuances: BTreeMap::new(),
             nft_token_issuances: BTreeMap::new(),
+            token_metadata_cache: BTreeMap::new(),
             statistics: BTreeMap::new(),
             genesis_block: chain_config.genesis_block().clone(),
             best_block: BlockAuxData::new(
@@ -88,6 +101,8 @@ impl ApiServerInMemoryStorage {
                 chain_config.genesis_block().timestamp(),
             ),
             storage_version: super::CURRENT_STORAGE_VERSION,
+            pruned_height: None,
+            scan_checkpoint: None,
         };
         result
             .initialize_storage(chain_config)
@@ -131,6 +146,59 @@ impl ApiServerInMemoryStorage {
         )
     }
 
+    fn get_coin_or_token_holders(
+        &self,
+        coin_or_token_id: CoinOrTokenId,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(String, Amount)>, ApiServerStorageError> {
+        let mut holders = self
+            .address_balance_table
+            .iter()
+            .filter_map(|(address, balance)| {
+                let range_begin = (coin_or_token_id, BlockHeight::zero());
+                let range_end = (coin_or_token_id, BlockHeight::max());
+                balance
+                    .range(range_begin..=range_end)
+                    .last()
+                    .map(|(_, amount)| (address.clone(), *amount))
+            })
+            .filter(|(_, amount)| *amount != Amount::ZERO)
+            .collect::<Vec<_>>();
+        holders.sort_by_key(|(_, amount)| Reverse(*amount));
+
+        Ok(holders.into_iter().skip(offset as usize).take(len as usize).collect())
+    }
+
+    fn get_address_balance_at_height(
+        &self,
+        address: &str,
+        coin_or_token_id: CoinOrTokenId,
+        block_height: BlockHeight,
+    ) -> Result<Option<Amount>, ApiServerStorageError> {
+        if let Some(pruned_height) = self.pruned_height.filter(|h| block_height < *h) {
+            return Err(ApiServerStorageError::HistoryPruned(pruned_height));
+        }
+
+        self.address_balance_table.get(address).map_or_else(
+            || Ok(None),
+            |balance| {
+                let range_begin = (coin_or_token_id, BlockHeight::zero());
+                let range_end = (coin_or_token_id, block_height);
+                let range = balance.range(range_begin..=range_end);
+                Ok(range.last().map(|(_, v)| *v))
+            },
+        )
+    }
+
+    fn get_pruned_height(&self) -> Result<Option<BlockHeight>, ApiServerStorageError> {
+        Ok(self.pruned_height)
+    }
+
+    fn get_scan_checkpoint(&self) -> Result<Option<ScanCheckpoint>, ApiServerStorageError> {
+        Ok(self.scan_checkpoint)
+    }
+
     fn get_address_transactions(
         &self,
         address: &str,
@@ -143,6 +211,60 @@ impl ApiServerInMemoryStorage {
             }))
     }
 
+    fn get_address_activity_heights(
+        &self,
+        address: &str,
+    ) -> Result<Option<(BlockHeight, BlockHeight)>, ApiServerStorageError> {
+        let mut heights = self
+            .address_transactions_table
+            .get(address)
+            .into_iter()
+            .flat_map(|transactions| transactions.iter())
+            .filter(|(_, txs)| !txs.is_empty())
+            .map(|(height, _)| *height);
+
+        Ok(heights.next().map(|first| (first, heights.last().unwrap_or(first))))
+    }
+
+    fn get_address_cluster(
+        &self,
+        address: &str,
+    ) -> Result<Option<AddressCluster>, ApiServerStorageError> {
+        let mut current = address;
+        while let Some((parent, size)) = self
+            .address_cluster_table
+            .get(current)
+            .and_then(|parents| parents.values().last())
+        {
+            if parent == current {
+                return Ok(Some(AddressCluster {
+                    id: parent.clone(),
+                    size: *size,
+                }));
+            }
+            current = parent;
+        }
+
+        Ok(None)
+    }
+
+    fn get_address_cluster_members(
+        &self,
+        cluster_id: &str,
+    ) -> Result<Vec<String>, ApiServerStorageError> {
+        let mut members = Vec::new();
+        for address in self.address_cluster_table.keys() {
+            if self
+                .get_address_cluster(address)?
+                .is_some_and(|cluster| cluster.id == cluster_id)
+            {
+                members.push(address.clone());
+            }
+        }
+
+        Ok(members)
+    }
+
     fn get_block(&self, block_id: Id<Block>) -> Result<Option<BlockInfo>, ApiServerStorageError> {
         let block_result = self.block_table.get(&block_id);
         let block = match block_result {
@@ -334,6 +456,27 @@ impl ApiServerInMemoryStorage {
             .collect())
     }
 
+    fn get_delegation_rewards(
+        &self,
+        delegation_id: DelegationId,
+        from_height: BlockHeight,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(BlockHeight, DelegationReward)>, ApiServerStorageError> {
+        Ok(self
+            .delegation_rewards_table
+            .get(&delegation_id)
+            .map(|by_height| {
+                by_height
+                    .range(from_height..)
+                    .skip(offset as usize)
+                    .take(len as usize)
+                    .map(|(height, reward)| (*height, reward.clone()))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
     fn get_latest_pool_ids(
         &self,
         len: u32,
@@ -475,6 +618,47 @@ impl ApiServerInMemoryStorage {
         Ok(result)
     }
 
+    fn get_address_spent_utxos(
+        &self,
+        address: &str,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(UtxoOutPoint, UtxoWithExtraInfo, UtxoSpender)>, ApiServerStorageError> {
+        let mut spent_utxos = self.address_utxos.get(address).map_or(vec![], |outpoints| {
+            outpoints
+                .iter()
+                .filter_map(|outpoint| {
+                    let (spent_at, utxo) = self
+                        .utxo_table
+                        .get(outpoint)
+                        .and_then(|by_height| by_height.last_key_value())
+                        .expect("must exist");
+                    utxo.spent_by().map(|spender| {
+                        (
+                            *spent_at,
+                            outpoint.clone(),
+                            utxo.utxo_with_extra_info().clone(),
+                            spender,
+                        )
+                    })
+                })
+                .collect::<Vec<_>>()
+        });
+
+        // Same order as in the postgres storage, where the outpoints are compared as encoded bytes
+        spent_utxos.sort_by_cached_key(|(spent_at, outpoint, _, _)| {
+            (Reverse(*spent_at), outpoint.encode())
+        });
+
+        let result = spent_utxos
+            .into_iter()
+            .skip(offset as usize)
+            .take(len as usize)
+            .map(|(_, outpoint, utxo, spender)| (outpoint, utxo, spender))
+            .collect();
+        Ok(result)
+    }
+
     fn get_locked_utxos_until_now(
         &self,
         block_height: BlockHeight,
@@ -552,6 +736,22 @@ impl ApiServerInMemoryStorage {
             .collect())
     }
 
+    fn get_token_ids_changed_above_height(
+        &self,
+        block_height: BlockHeight,
+    ) -> Result<Vec<TokenId>, ApiServerStorageError> {
+        let fungible = self
+            .fungible_token_issuances
+            .iter()
+            .filter(|(_, v)| v.last_key_value().is_some_and(|(k, _)| k > &block_height));
+        let nft = self
+            .nft_token_issuances
+            .iter()
+            .filter(|(_, v)| v.last_key_value().is_some_and(|(k, _)| k > &block_height));
+
+        Ok(fungible.map(|(key, _)| *key).chain(nft.map(|(key, _)| *key)).collect())
+    }
+
     fn get_token_ids_by_ticker(
         &self,
         len: u32,
@@ -576,6 +776,13 @@ impl ApiServerInMemoryStorage {
             .collect())
     }
 
+    fn get_token_metadata_cache(
+        &self,
+        token_id: TokenId,
+    ) -> Result<Option<TokenMetadataCacheEntry>, ApiServerStorageError> {
+        Ok(self.token_metadata_cache.get(&token_id).cloned())
+    }
+
     fn get_statistic(
         &self,
         statistic: CoinOrTokenStatistic,
@@ -651,6 +858,7 @@ impl ApiServerInMemoryStorage {
         self.address_locked_balance_table.clear();
         self.address_transactions_table.clear();
         self.delegation_table.clear();
+        self.delegation_rewards_table.clear();
         self.main_chain_blocks_table.clear();
         self.pool_data_table.clear();
         self.transaction_table.clear();
@@ -658,6 +866,8 @@ impl ApiServerInMemoryStorage {
         self.address_utxos.clear();
         self.fungible_token_issuances.clear();
         self.nft_token_issuances.clear();
+        self.token_metadata_cache.clear();
+        self.scan_checkpoint = None;
 
         self.initialize_storage(chain_config)
     }
@@ -724,6 +934,59 @@ impl ApiServerInMemoryStorage {
         Ok(())
     }
 
+    fn set_scan_checkpoint(
+        &mut self,
+        checkpoint: ScanCheckpoint,
+    ) -> Result<(), ApiServerStorageError> {
+        self.scan_checkpoint = Some(checkpoint);
+        Ok(())
+    }
+
+    fn del_address_clusters_above_height(
+        &mut self,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        self.address_cluster_table.retain(|_, parents| {
+            parents.retain(|height, _| *height <= block_height);
+            !parents.is_empty()
+        });
+
+        Ok(())
+    }
+
+    fn prune_address_balance_history(
+        &mut self,
+        below_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        fn prune(
+            table: &mut BTreeMap<String, BTreeMap<(CoinOrTokenId, BlockHeight), Amount>>,
+            below_height: BlockHeight,
+        ) {
+            table.values_mut().for_each(|balance| {
+                // The entries are sorted by the currency first, so an old entry is superseded
+                // if the next one is for the same currency and is still below the height
+                let superseded = balance
+                    .keys()
+                    .zip(balance.keys().skip(1))
+                    .filter(|((coin, height), (next_coin, next_height))| {
+                        coin == next_coin && *height < below_height && *next_height < below_height
+                    })
+                    .map(|(key, _)| *key)
+                    .collect::<Vec<_>>();
+
+                for key in superseded {
+                    balance.remove(&key);
+                }
+            });
+        }
+
+        prune(&mut self.address_balance_table, below_height);
+        prune(&mut self.address_locked_balance_table, below_height);
+        self.pruned_height = std::cmp::max(self.pruned_height, Some(below_height));
+
+        Ok(())
+    }
+
     fn set_address_balance_at_height(
         &mut self,
         address: &str,
@@ -772,6 +1035,21 @@ impl ApiServerInMemoryStorage {
         Ok(())
     }
 
+    fn set_address_cluster_parent_at_height(
+        &mut self,
+        address: &str,
+        parent: &str,
+        size: u64,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        self.address_cluster_table
+            .entry(address.to_string())
+            .or_default()
+            .insert(block_height, (parent.to_string(), size));
+
+        Ok(())
+    }
+
     fn set_mainchain_block(
         &mut self,
         block_id: Id<Block>,
@@ -861,6 +1139,31 @@ impl ApiServerInMemoryStorage {
         Ok(())
     }
 
+    fn set_delegation_reward_at_height(
+        &mut self,
+        delegation_id: DelegationId,
+        reward: &DelegationReward,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        self.delegation_rewards_table
+            .entry(delegation_id)
+            .or_default()
+            .insert(block_height, reward.clone());
+        Ok(())
+    }
+
+    fn del_delegation_rewards_above_height(
+        &mut self,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        self.delegation_rewards_table.retain(|_, v| {
+            v.retain(|k, _| k <= &block_height);
+            !v.is_empty()
+        });
+
+        Ok(())
+    }
+
     fn del_pools_above_height(
         &mut self,
         block_height: BlockHeight,
@@ -962,6 +1265,15 @@ impl ApiServerInMemoryStorage {
         Ok(())
     }
 
+    fn set_token_metadata_cache(
+        &mut self,
+        token_id: TokenId,
+        entry: &TokenMetadataCacheEntry,
+    ) -> Result<(), ApiServerStorageError> {
+        self.token_metadata_cache.insert(token_id, entry.clone());
+        Ok(())
+    }
+
     fn del_token_issuance_above_height(
         &mut self,
         block_height: BlockHeight,
@@ -26,9 +26,10 @@ use common::{
 use pos_accounting::PoolData;
 
 use crate::storage::storage_api::{
-    block_aux_data::BlockAuxData, ApiServerStorageError, ApiServerStorageRead, BlockInfo,
-    CoinOrTokenStatistic, Delegation, FungibleTokenData, PoolBlockStats, TransactionInfo, Utxo,
-    UtxoWithExtraInfo,
+    block_aux_data::BlockAuxData, scan_checkpoint::ScanCheckpoint, AddressCluster,
+    ApiServerStorageError, ApiServerStorageRead, BlockInfo, CoinOrTokenStatistic, Delegation,
+    DelegationReward, FungibleTokenData, PoolBlockStats, TokenMetadataCacheEntry, TransactionInfo,
+    Utxo, UtxoSpender, UtxoWithExtraInfo,
 };
 
 use super::ApiServerInMemoryStorageTransactionalRo;
@@ -55,6 +56,15 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRo<'t> {
         self.transaction.get_address_locked_balance(address, coin_or_token_id)
     }
 
+    async fn get_coin_or_token_holders(
+        &self,
+        coin_or_token_id: CoinOrTokenId,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(String, Amount)>, ApiServerStorageError> {
+        self.transaction.get_coin_or_token_holders(coin_or_token_id, len, offset)
+    }
+
     async fn get_address_transactions(
         &self,
         address: &str,
@@ -62,6 +72,45 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRo<'t> {
         self.transaction.get_address_transactions(address)
     }
 
+    async fn get_address_balance_at_height(
+        &self,
+        address: &str,
+        coin_or_token_id: CoinOrTokenId,
+        block_height: BlockHeight,
+    ) -> Result<Option<Amount>, ApiServerStorageError> {
+        self.transaction
+            .get_address_balance_at_height(address, coin_or_token_id, block_height)
+    }
+
+    async fn get_pruned_height(&self) -> Result<Option<BlockHeight>, ApiServerStorageError> {
+        self.transaction.get_pruned_height()
+    }
+
+    async fn get_scan_checkpoint(&self) -> Result<Option<ScanCheckpoint>, ApiServerStorageError> {
+        self.transaction.get_scan_checkpoint()
+    }
+
+    async fn get_address_activity_heights(
+        &self,
+        address: &str,
+    ) -> Result<Option<(BlockHeight, BlockHeight)>, ApiServerStorageError> {
+        self.transaction.get_address_activity_heights(address)
+    }
+
+    async fn get_address_cluster(
+        &self,
+        address: &str,
+    ) -> Result<Option<AddressCluster>, ApiServerStorageError> {
+        self.transaction.get_address_cluster(address)
+    }
+
+    async fn get_address_cluster_members(
+        &self,
+        cluster_id: &str,
+    ) -> Result<Vec<String>, ApiServerStorageError> {
+        self.transaction.get_address_cluster_members(cluster_id)
+    }
+
     async fn get_block(
         &self,
         block_id: Id<Block>,
@@ -113,6 +162,16 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRo<'t> {
         self.transaction.get_pool_delegations(pool_id)
     }
 
+    async fn get_delegation_rewards(
+        &self,
+        delegation_id: DelegationId,
+        from_height: BlockHeight,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(BlockHeight, DelegationReward)>, ApiServerStorageError> {
+        self.transaction.get_delegation_rewards(delegation_id, from_height, len, offset)
+    }
+
     async fn get_latest_pool_data(
         &self,
         len: u32,
@@ -192,6 +251,15 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRo<'t> {
         self.transaction.get_address_all_utxos(address)
     }
 
+    async fn get_address_spent_utxos(
+        &self,
+        address: &str,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(UtxoOutPoint, UtxoWithExtraInfo, UtxoSpender)>, ApiServerStorageError> {
+        self.transaction.get_address_spent_utxos(address, len, offset)
+    }
+
     async fn get_locked_utxos_until_now(
         &self,
         block_height: BlockHeight,
@@ -245,6 +313,20 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRo<'t> {
         self.transaction.get_token_ids_by_ticker(len, offset, ticker)
     }
 
+    async fn get_token_ids_changed_above_height(
+        &self,
+        block_height: BlockHeight,
+    ) -> Result<Vec<TokenId>, ApiServerStorageError> {
+        self.transaction.get_token_ids_changed_above_height(block_height)
+    }
+
+    async fn get_token_metadata_cache(
+        &self,
+        token_id: TokenId,
+    ) -> Result<Option<TokenMetadataCacheEntry>, ApiServerStorageError> {
+        self.transaction.get_token_metadata_cache(token_id)
+    }
+
     async fn get_statistic(
         &self,
         statistic: CoinOrTokenStatistic,
@@ -27,9 +27,10 @@ use pos_accounting::PoolData;
 
 use crate::storage::storage_api::{
     block_aux_data::{BlockAuxData, BlockWithExtraData},
-    ApiServerStorageError, ApiServerStorageRead, ApiServerStorageWrite, BlockInfo,
-    CoinOrTokenStatistic, Delegation, FungibleTokenData, LockedUtxo, PoolBlockStats,
-    TransactionInfo, Utxo, UtxoWithExtraInfo,
+    scan_checkpoint::ScanCheckpoint,
+    AddressCluster, ApiServerStorageError, ApiServerStorageRead, ApiServerStorageWrite, BlockInfo,
+    CoinOrTokenStatistic, Delegation, DelegationReward, FungibleTokenData, LockedUtxo,
+    PoolBlockStats, TokenMetadataCacheEntry, TransactionInfo, Utxo, UtxoSpender, UtxoWithExtraInfo,
 };
 
 use super::ApiServerInMemoryStorageTransactionalRw;
@@ -57,6 +58,13 @@ impl<'t> ApiServerStorageWrite for ApiServerInMemoryStorageTransactionalRw<'t> {
         self.transaction.del_address_locked_balance_above_height(block_height)
     }
 
+    async fn prune_address_balance_history(
+        &mut self,
+        below_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        self.transaction.prune_address_balance_history(below_height)
+    }
+
     async fn del_address_transactions_above_height(
         &mut self,
         block_height: BlockHeight,
@@ -64,6 +72,20 @@ impl<'t> ApiServerStorageWrite for ApiServerInMemoryStorageTransactionalRw<'t> {
         self.transaction.del_address_transactions_above_height(block_height)
     }
 
+    async fn set_scan_checkpoint(
+        &mut self,
+        checkpoint: ScanCheckpoint,
+    ) -> Result<(), ApiServerStorageError> {
+        self.transaction.set_scan_checkpoint(checkpoint)
+    }
+
+    async fn del_address_clusters_above_height(
+        &mut self,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        self.transaction.del_address_clusters_above_height(block_height)
+    }
+
     async fn set_address_balance_at_height(
         &mut self,
         address: &str,
@@ -104,6 +126,17 @@ impl<'t> ApiServerStorageWrite for ApiServerInMemoryStorageTransactionalRw<'t> {
             .set_address_transactions_at_height(address, transactions, block_height)
     }
 
+    async fn set_address_cluster_parent_at_height(
+        &mut self,
+        address: &str,
+        parent: &str,
+        size: u64,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        self.transaction
+            .set_address_cluster_parent_at_height(address, parent, size, block_height)
+    }
+
     async fn set_mainchain_block(
         &mut self,
         block_id: Id<Block>,
@@ -163,6 +196,23 @@ impl<'t> ApiServerStorageWrite for ApiServerInMemoryStorageTransactionalRw<'t> {
         self.transaction.del_delegations_above_height(block_height)
     }
 
+    async fn set_delegation_reward_at_height(
+        &mut self,
+        delegation_id: DelegationId,
+        reward: &DelegationReward,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        self.transaction
+            .set_delegation_reward_at_height(delegation_id, reward, block_height)
+    }
+
+    async fn del_delegation_rewards_above_height(
+        &mut self,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        self.transaction.del_delegation_rewards_above_height(block_height)
+    }
+
     async fn del_pools_above_height(
         &mut self,
         block_height: BlockHeight,
@@ -237,6 +287,14 @@ impl<'t> ApiServerStorageWrite for ApiServerInMemoryStorageTransactionalRw<'t> {
         self.transaction.del_nft_issuance_above_height(block_height)
     }
 
+    async fn set_token_metadata_cache(
+        &mut self,
+        token_id: TokenId,
+        entry: &TokenMetadataCacheEntry,
+    ) -> Result<(), ApiServerStorageError> {
+        self.transaction.set_token_metadata_cache(token_id, entry)
+    }
+
     async fn set_statistic(
         &mut self,
         statistic: CoinOrTokenStatistic,
@@ -282,6 +340,15 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRw<'t> {
         self.transaction.get_address_locked_balance(address, coin_or_token_id)
     }
 
+    async fn get_coin_or_token_holders(
+        &self,
+        coin_or_token_id: CoinOrTokenId,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(String, Amount)>, ApiServerStorageError> {
+        self.transaction.get_coin_or_token_holders(coin_or_token_id, len, offset)
+    }
+
     async fn get_address_transactions(
         &self,
         address: &str,
@@ -289,6 +356,45 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRw<'t> {
         self.transaction.get_address_transactions(address)
     }
 
+    async fn get_address_balance_at_height(
+        &self,
+        address: &str,
+        coin_or_token_id: CoinOrTokenId,
+        block_height: BlockHeight,
+    ) -> Result<Option<Amount>, ApiServerStorageError> {
+        self.transaction
+            .get_address_balance_at_height(address, coin_or_token_id, block_height)
+    }
+
+    async fn get_pruned_height(&self) -> Result<Option<BlockHeight>, ApiServerStorageError> {
+        self.transaction.get_pruned_height()
+    }
+
+    async fn get_scan_checkpoint(&self) -> Result<Option<ScanCheckpoint>, ApiServerStorageError> {
+        self.transaction.get_scan_checkpoint()
+    }
+
+    async fn get_address_activity_heights(
+        &self,
+        address: &str,
+    ) -> Result<Option<(BlockHeight, BlockHeight)>, ApiServerStorageError> {
+        self.transaction.get_address_activity_heights(address)
+    }
+
+    async fn get_address_cluster(
+        &self,
+        address: &str,
+    ) -> Result<Option<AddressCluster>, ApiServerStorageError> {
+        self.transaction.get_address_cluster(address)
+    }
+
+    async fn get_address_cluster_members(
+        &self,
+        cluster_id: &str,
+    ) -> Result<Vec<String>, ApiServerStorageError> {
+        self.transaction.get_address_cluster_members(cluster_id)
+    }
+
     async fn get_latest_blocktimestamps(
         &self,
     ) -> Result<Vec<BlockTimestamp>, ApiServerStorageError> {
@@ -342,6 +448,16 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRw<'t> {
         self.transaction.get_pool_delegations(pool_id)
     }
 
+    async fn get_delegation_rewards(
+        &self,
+        delegation_id: DelegationId,
+        from_height: BlockHeight,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(BlockHeight, DelegationReward)>, ApiServerStorageError> {
+        self.transaction.get_delegation_rewards(delegation_id, from_height, len, offset)
+    }
+
     async fn get_main_chain_block_id(
         &self,
         block_height: BlockHeight,
@@ -415,6 +531,15 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRw<'t> {
         self.transaction.get_address_all_utxos(address)
     }
 
+    async fn get_address_spent_utxos(
+        &self,
+        address: &str,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(UtxoOutPoint, UtxoWithExtraInfo, UtxoSpender)>, ApiServerStorageError> {
+        self.transaction.get_address_spent_utxos(address, len, offset)
+    }
+
     async fn get_locked_utxos_until_now(
         &self,
         block_height: BlockHeight,
@@ -468,6 +593,20 @@ impl<'t> ApiServerStorageRead for ApiServerInMemoryStorageTransactionalRw<'t> {
         self.transaction.get_token_ids_by_ticker(len, offset, ticker)
     }
 
+    async fn get_token_ids_changed_above_height(
+        &self,
+        block_height: BlockHeight,
+    ) -> Result<Vec<TokenId>, ApiServerStorageError> {
+        self.transaction.get_token_ids_changed_above_height(block_height)
+    }
+
+    async fn get_token_metadata_cache(
+        &self,
+        token_id: TokenId,
+    ) -> Result<Option<TokenMetadataCacheEntry>, ApiServerStorageError> {
+        self.transaction.get_token_metadata_cache(token_id)
+    }
+
     async fn get_statistic(
         &self,
         statistic: CoinOrTokenStatistic,
@@ -38,12 +38,16 @@ use crate::storage::{
     impls::CURRENT_STORAGE_VERSION,
     storage_api::{
         block_aux_data::{BlockAuxData, BlockWithExtraData},
-        ApiServerStorageError, BlockInfo, CoinOrTokenStatistic, Delegation, FungibleTokenData,
-        LockedUtxo, PoolBlockStats, TransactionInfo, Utxo, UtxoWithExtraInfo,
+        scan_checkpoint::ScanCheckpoint,
+        AddressCluster, ApiServerStorageError, BlockInfo, CoinOrTokenStatistic, Delegation,
+        DelegationReward, FungibleTokenData, LockedUtxo, PoolBlockStats, TokenMetadataCacheEntry,
+        TransactionInfo, Utxo, UtxoSpender, UtxoWithExtraInfo,
     },
 };
 
 const VERSION_STR: &str = "version";
+const PRUNED_HEIGHT_STR: &str = "pruned_height";
+const SCAN_CHECKPOINT_STR: &str = "scan_checkpoint";
 
 pub struct QueryFromConnection<'a, 'b> {
     tx: &'a PooledConnection<'b, PostgresConnectionManager<NoTls>>,
@@ -170,6 +174,44 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
             )
     }
 
+    pub async fn get_coin_or_token_holders(
+        &self,
+        coin_or_token_id: CoinOrTokenId,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(String, Amount)>, ApiServerStorageError> {
+        let len = len as i64;
+        let offset = offset as i64;
+        self.tx
+            .query(
+                r#"
+                    SELECT address, amount
+                    FROM ml.latest_address_balance
+                    WHERE coin_or_token_id = $1 AND amount_str != $2
+                    ORDER BY amount_str DESC, address
+                    OFFSET $3
+                    LIMIT $4;
+                "#,
+                &[&coin_or_token_id.encode(), &amount_to_str(Amount::ZERO), &offset, &len],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?
+            .into_iter()
+            .map(|row| -> Result<(String, Amount), ApiServerStorageError> {
+                let address: String = row.get(0);
+                let amount: Vec<u8> = row.get(1);
+                let amount = Amount::decode_all(&mut amount.as_slice()).map_err(|e| {
+                    ApiServerStorageError::DeserializationError(format!(
+                        "Amount deserialization failed: {}",
+                        e
+                    ))
+                })?;
+
+                Ok((address, amount))
+            })
+            .collect()
+    }
+
     pub async fn get_address_locked_balance(
         &self,
         address: &str,
@@ -204,12 +246,207 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
             )
     }
 
+    pub async fn get_address_balance_at_height(
+        &self,
+        address: &str,
+        coin_or_token_id: CoinOrTokenId,
+        block_height: BlockHeight,
+    ) -> Result<Option<Amount>, ApiServerStorageError> {
+        if let Some(pruned_height) = self.get_pruned_height().await?.filter(|h| block_height < *h) {
+            return Err(ApiServerStorageError::HistoryPruned(pruned_height));
+        }
+
+        let height = Self::block_height_to_postgres_friendly(block_height);
+
+        self.tx
+            .query_opt(
+                r#"
+                    SELECT amount
+                    FROM ml.address_balance
+                    WHERE address = $1 AND coin_or_token_id = $2 AND block_height <= $3
+                    ORDER BY block_height DESC
+                    LIMIT 1;
+                "#,
+                &[&address, &coin_or_token_id.encode(), &height],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?
+            .map_or_else(
+                || Ok(None),
+                |row| {
+                    let amount: Vec<u8> = row.get(0);
+                    let amount = Amount::decode_all(&mut amount.as_slice()).map_err(|e| {
+                        ApiServerStorageError::DeserializationError(format!(
+                            "Amount deserialization failed: {}",
+                            e
+                        ))
+                    })?;
+
+                    Ok(Some(amount))
+                },
+            )
+    }
+
+    pub async fn get_pruned_height(&self) -> Result<Option<BlockHeight>, ApiServerStorageError> {
+        let query_result = self
+            .tx
+            .query_opt(
+                "SELECT value FROM ml.misc_data WHERE name = $1;",
+                &[&PRUNED_HEIGHT_STR],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        query_result
+            .map(|row| {
+                let data: Vec<u8> = row.get(0);
+                BlockHeight::decode_all(&mut data.as_slice()).map_err(|e| {
+                    ApiServerStorageError::DeserializationError(format!(
+                        "Pruned height deserialization failed: {}",
+                        e
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    pub async fn get_scan_checkpoint(
+        &self,
+    ) -> Result<Option<ScanCheckpoint>, ApiServerStorageError> {
+        let query_result = self
+            .tx
+            .query_opt(
+                "SELECT value FROM ml.misc_data WHERE name = $1;",
+                &[&SCAN_CHECKPOINT_STR],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        query_result
+            .map(|row| {
+                let data: Vec<u8> = row.get(0);
+                ScanCheckpoint::decode_all(&mut data.as_slice()).map_err(|e| {
+                    ApiServerStorageError::DeserializationError(format!(
+                        "Scan checkpoint deserialization failed: {}",
+                        e
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    pub async fn set_scan_checkpoint(
+        &mut self,
+        checkpoint: ScanCheckpoint,
+    ) -> Result<(), ApiServerStorageError> {
+        self.tx
+            .execute(
+                r#"
+                    INSERT INTO ml.misc_data (name, value)
+                    VALUES ($1, $2)
+                    ON CONFLICT (name)
+                    DO UPDATE SET value = $2;
+                "#,
+                &[&SCAN_CHECKPOINT_STR, &checkpoint.encode()],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    pub async fn prune_address_balance_history(
+        &mut self,
+        below_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        let height = Self::block_height_to_postgres_friendly(below_height);
+
+        // Delete every entry below the height that is superseded by a newer one, which is
+        // also below the height; the newest one is kept as the checkpoint
+        for table in ["ml.address_balance", "ml.address_locked_balance"] {
+            self.tx
+                .execute(
+                    &format!(
+                        r#"
+                        DELETE FROM {table} AS old
+                        WHERE old.block_height < $1 AND EXISTS (
+                            SELECT 1
+                            FROM {table} AS new
+                            WHERE new.address = old.address
+                                AND new.coin_or_token_id = old.coin_or_token_id
+                                AND new.block_height > old.block_height
+                                AND new.block_height < $1
+                        );
+                    "#
+                    ),
+                    &[&height],
+                )
+                .await
+                .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+        }
+
+        if self.get_pruned_height().await?.map_or(true, |h| h < below_height) {
+            self.tx
+                .execute(
+                    r#"
+                        INSERT INTO ml.misc_data (name, value)
+                        VALUES ($1, $2)
+                        ON CONFLICT (name)
+                        DO UPDATE SET value = $2;
+                    "#,
+                    &[&PRUNED_HEIGHT_STR, &below_height.encode()],
+                )
+                .await
+                .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
     pub async fn del_address_balance_above_height(
         &mut self,
         block_height: BlockHeight,
     ) -> Result<(), ApiServerStorageError> {
         let height = Self::block_height_to_postgres_friendly(block_height);
 
+        // Roll the latest balances back to the newest entries that remain after the deletion
+        self.tx
+            .execute(
+                r#"
+                    DELETE FROM ml.latest_address_balance AS latest
+                    WHERE latest.block_height > $1 AND NOT EXISTS (
+                        SELECT 1
+                        FROM ml.address_balance AS b
+                        WHERE b.address = latest.address
+                            AND b.coin_or_token_id = latest.coin_or_token_id
+                            AND b.block_height <= $1
+                    );
+                "#,
+                &[&height],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        self.tx
+            .execute(
+                r#"
+                    UPDATE ml.latest_address_balance AS latest
+                    SET (amount, amount_str, block_height) = (
+                        SELECT b.amount, b.amount_str, b.block_height
+                        FROM ml.address_balance AS b
+                        WHERE b.address = latest.address
+                            AND b.coin_or_token_id = latest.coin_or_token_id
+                            AND b.block_height <= $1
+                        ORDER BY b.block_height DESC
+                        LIMIT 1
+                    )
+                    WHERE latest.block_height > $1;
+                "#,
+                &[&height],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
         self.tx
             .execute(
                 "DELETE FROM ml.address_balance WHERE block_height > $1;",
@@ -250,12 +487,38 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
         self.tx
             .execute(
                 r#"
-                    INSERT INTO ml.address_balance (address, block_height, coin_or_token_id, amount)
-                    VALUES ($1, $2, $3, $4)
+                    INSERT INTO ml.address_balance (address, block_height, coin_or_token_id, amount, amount_str)
+                    VALUES ($1, $2, $3, $4, $5)
                     ON CONFLICT (address, block_height, coin_or_token_id)
-                    DO UPDATE SET amount = $4;
+                    DO UPDATE SET amount = $4, amount_str = $5;
                 "#,
-                &[&address.to_string(), &height, &coin_or_token_id.encode(), &amount.encode()],
+                &[
+                    &address.to_string(),
+                    &height,
+                    &coin_or_token_id.encode(),
+                    &amount.encode(),
+                    &amount_to_str(amount),
+                ],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        self.tx
+            .execute(
+                r#"
+                    INSERT INTO ml.latest_address_balance (address, coin_or_token_id, block_height, amount, amount_str)
+                    VALUES ($1, $2, $3, $4, $5)
+                    ON CONFLICT (address, coin_or_token_id)
+                    DO UPDATE SET block_height = $3, amount = $4, amount_str = $5
+                    WHERE ml.latest_address_balance.block_height <= $3;
+                "#,
+                &[
+                    &address.to_string(),
+                    &coin_or_token_id.encode(),
+                    &height,
+                    &amount.encode(),
+                    &amount_to_str(amount),
+                ],
             )
             .await
             .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
@@ -324,6 +587,102 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
         Ok(transaction_ids)
     }
 
+    pub async fn get_address_activity_heights(
+        &self,
+        address: &str,
+    ) -> Result<Option<(BlockHeight, BlockHeight)>, ApiServerStorageError> {
+        let row = self
+            .tx
+            .query_one(
+                r#"
+                    SELECT MIN(block_height), MAX(block_height)
+                    FROM ml.address_transactions
+                    WHERE address = $1;
+                "#,
+                &[&address],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        let first_height: Option<i64> = row.get(0);
+        let last_height: Option<i64> = row.get(1);
+
+        let heights = first_height.zip(last_height).map(|(first, last)| {
+            (
+                BlockHeight::new(first as u64),
+                BlockHeight::new(last as u64),
+            )
+        });
+
+        Ok(heights)
+    }
+
+    pub async fn get_address_cluster(
+        &self,
+        address: &str,
+    ) -> Result<Option<AddressCluster>, ApiServerStorageError> {
+        // Follow the parents up to the root of the union-find tree
+        let row = self
+            .tx
+            .query_opt(
+                r#"
+                    WITH RECURSIVE path (address, parent, size) AS (
+                        SELECT address, parent, size
+                        FROM ml.latest_address_clusters
+                        WHERE address = $1
+                        UNION ALL
+                        SELECT c.address, c.parent, c.size
+                        FROM ml.latest_address_clusters c
+                        JOIN path p ON c.address = p.parent
+                        WHERE p.address != p.parent
+                    )
+                    SELECT address, size
+                    FROM path
+                    WHERE address = parent;
+                "#,
+                &[&address],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        Ok(row.map(|row| {
+            let size: i64 = row.get(1);
+            AddressCluster {
+                id: row.get(0),
+                size: size as u64,
+            }
+        }))
+    }
+
+    pub async fn get_address_cluster_members(
+        &self,
+        cluster_id: &str,
+    ) -> Result<Vec<String>, ApiServerStorageError> {
+        // Walk the union-find tree down from its root
+        let rows = self
+            .tx
+            .query(
+                r#"
+                    WITH RECURSIVE members (address) AS (
+                        SELECT address
+                        FROM ml.latest_address_clusters
+                        WHERE address = $1 AND parent = $1
+                        UNION ALL
+                        SELECT c.address
+                        FROM ml.latest_address_clusters c
+                        JOIN members m ON c.parent = m.address
+                        WHERE c.address != c.parent
+                    )
+                    SELECT address FROM members;
+                "#,
+                &[&cluster_id],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|row| row.get(0)).collect())
+    }
+
     pub async fn del_address_transactions_above_height(
         &mut self,
         block_height: BlockHeight,
@@ -367,6 +726,97 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
         Ok(())
     }
 
+    pub async fn del_address_clusters_above_height(
+        &mut self,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        let height = Self::block_height_to_postgres_friendly(block_height);
+
+        // Roll the latest parents back to the newest entries that remain after the deletion
+        self.tx
+            .execute(
+                r#"
+                    DELETE FROM ml.latest_address_clusters AS latest
+                    WHERE latest.block_height > $1 AND NOT EXISTS (
+                        SELECT 1
+                        FROM ml.address_clusters AS c
+                        WHERE c.address = latest.address AND c.block_height <= $1
+                    );
+                "#,
+                &[&height],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        self.tx
+            .execute(
+                r#"
+                    UPDATE ml.latest_address_clusters AS latest
+                    SET (parent, size, block_height) = (
+                        SELECT c.parent, c.size, c.block_height
+                        FROM ml.address_clusters AS c
+                        WHERE c.address = latest.address AND c.block_height <= $1
+                        ORDER BY c.block_height DESC
+                        LIMIT 1
+                    )
+                    WHERE latest.block_height > $1;
+                "#,
+                &[&height],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        self.tx
+            .execute(
+                "DELETE FROM ml.address_clusters WHERE block_height > $1;",
+                &[&height],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    pub async fn set_address_cluster_parent_at_height(
+        &mut self,
+        address: &str,
+        parent: &str,
+        size: u64,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        let height = Self::block_height_to_postgres_friendly(block_height);
+        let size = size as i64;
+
+        self.tx
+            .execute(
+                r#"
+                    INSERT INTO ml.address_clusters (address, block_height, parent, size)
+                    VALUES ($1, $2, $3, $4)
+                    ON CONFLICT (address, block_height)
+                    DO UPDATE SET parent = $3, size = $4;
+                "#,
+                &[&address, &height, &parent, &size],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        self.tx
+            .execute(
+                r#"
+                    INSERT INTO ml.latest_address_clusters (address, block_height, parent, size)
+                    VALUES ($1, $2, $3, $4)
+                    ON CONFLICT (address)
+                    DO UPDATE SET block_height = $2, parent = $3, size = $4
+                    WHERE ml.latest_address_clusters.block_height <= $2;
+                "#,
+                &[&address, &height, &parent, &size],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        Ok(())
+    }
+
     pub async fn get_latest_blocktimestamps(
         &self,
     ) -> Result<Vec<BlockTimestamp>, ApiServerStorageError> {
@@ -518,11 +968,38 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
                     block_height bigint NOT NULL,
                     coin_or_token_id bytea NOT NULL,
                     amount bytea NOT NULL,
+                    amount_str TEXT NOT NULL,
                     PRIMARY KEY (address, block_height, coin_or_token_id)
                 );",
         )
         .await?;
 
+        // The newest entry of ml.address_balance per address and currency, kept up to date
+        // on every balance update and reorg, so the holders queries don't scan the history
+        self.just_execute(
+            "CREATE TABLE ml.latest_address_balance (
+                    address TEXT NOT NULL,
+                    coin_or_token_id bytea NOT NULL,
+                    block_height bigint NOT NULL,
+                    amount bytea NOT NULL,
+                    amount_str TEXT NOT NULL,
+                    PRIMARY KEY (address, coin_or_token_id)
+                );",
+        )
+        .await?;
+
+        // Add ml.latest_address_balance index for the holders queries
+        self.just_execute(
+            "CREATE INDEX latest_address_balance_holders_index ON ml.latest_address_balance (coin_or_token_id, amount_str DESC, address);",
+        )
+        .await?;
+
+        // Add ml.latest_address_balance index for the reorgs
+        self.just_execute(
+            "CREATE INDEX latest_address_balance_block_height_index ON ml.latest_address_balance (block_height);",
+        )
+        .await?;
+
         self.just_execute(
             "CREATE TABLE ml.address_locked_balance (
                     address TEXT NOT NULL,
@@ -544,11 +1021,47 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
         )
         .await?;
 
+        // The union-find trees of the address clusters: the parent of each address, which is
+        // the address itself for the root of a cluster, and the size of the cluster for the roots
+        self.just_execute(
+            "CREATE TABLE ml.address_clusters (
+                    address TEXT NOT NULL,
+                    block_height bigint NOT NULL,
+                    parent TEXT NOT NULL,
+                    size bigint NOT NULL,
+                    PRIMARY KEY (address, block_height)
+                );",
+        )
+        .await?;
+
+        // The newest entry of ml.address_clusters per address, kept up to date on every update
+        // and reorg, so the trees can be walked without scanning the history
+        self.just_execute(
+            "CREATE TABLE ml.latest_address_clusters (
+                    address TEXT PRIMARY KEY,
+                    block_height bigint NOT NULL,
+                    parent TEXT NOT NULL,
+                    size bigint NOT NULL
+                );",
+        )
+        .await?;
+
+        self.just_execute(
+            "CREATE INDEX latest_address_clusters_parent_index ON ml.latest_address_clusters (parent);",
+        )
+        .await?;
+
+        self.just_execute(
+            "CREATE INDEX latest_address_clusters_block_height_index ON ml.latest_address_clusters (block_height);",
+        )
+        .await?;
+
         self.just_execute(
             "CREATE TABLE ml.utxo (
                     outpoint bytea NOT NULL,
                     block_height bigint,
                     spent BOOLEAN NOT NULL,
+                    spent_by bytea,
                     address TEXT NOT NULL,
                     utxo bytea NOT NULL,
                     PRIMARY KEY (outpoint, block_height)
@@ -608,6 +1121,18 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
         )
         .await?;
 
+        self.just_execute(
+            "CREATE TABLE ml.delegation_rewards (
+                    delegation_id TEXT NOT NULL,
+                    block_height bigint NOT NULL,
+                    block_id bytea NOT NULL,
+                    pool_id TEXT NOT NULL,
+                    amount TEXT NOT NULL,
+                    PRIMARY KEY (delegation_id, block_height)
+                );",
+        )
+        .await?;
+
         self.just_execute(
             "CREATE TABLE ml.fungible_token (
                     token_id bytea NOT NULL,
@@ -642,6 +1167,14 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
         )
         .await?;
 
+        self.just_execute(
+            "CREATE TABLE ml.token_metadata_cache (
+                    token_id bytea PRIMARY KEY,
+                    data bytea NOT NULL
+                );",
+        )
+        .await?;
+
         self.just_execute(
             "CREATE TABLE ml.statistics (
             statistic TEXT NOT NULL,
@@ -1055,6 +1588,118 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
         Ok(())
     }
 
+    pub async fn get_delegation_rewards(
+        &mut self,
+        delegation_id: DelegationId,
+        from_height: BlockHeight,
+        len: u32,
+        offset: u32,
+        chain_config: &ChainConfig,
+    ) -> Result<Vec<(BlockHeight, DelegationReward)>, ApiServerStorageError> {
+        let delegation_id = Address::new(chain_config, delegation_id)
+            .map_err(|_| ApiServerStorageError::AddressableError)?;
+        let from_height = Self::block_height_to_postgres_friendly(from_height);
+        let len = len as i64;
+        let offset = offset as i64;
+
+        let rows = self
+            .tx
+            .query(
+                r#"SELECT block_height, block_id, pool_id, amount
+                FROM ml.delegation_rewards
+                WHERE delegation_id = $1 AND block_height >= $2
+                ORDER BY block_height
+                OFFSET $3
+                LIMIT $4;
+                "#,
+                &[&delegation_id.as_str(), &from_height, &offset, &len],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                let block_height: i64 = row.get(0);
+                let block_id: Vec<u8> = row.get(1);
+                let pool_id: String = row.get(2);
+                let amount: String = row.get(3);
+
+                let block_id = Id::<Block>::decode_all(&mut block_id.as_slice()).map_err(|e| {
+                    ApiServerStorageError::DeserializationError(format!(
+                        "Block id deserialization failed: {e}"
+                    ))
+                })?;
+                let pool_id = Address::<PoolId>::from_string(chain_config, pool_id)
+                    .map_err(|_| ApiServerStorageError::AddressableError)?
+                    .into_object();
+                let amount = Amount::from_fixedpoint_str(&amount, 0).ok_or_else(|| {
+                    ApiServerStorageError::DeserializationError(format!(
+                        "Delegation {delegation_id} reward deserialization failed invalid amount {amount}"
+                    ))
+                })?;
+
+                let reward = DelegationReward {
+                    block_id,
+                    pool_id,
+                    amount,
+                };
+                Ok((BlockHeight::new(block_height as u64), reward))
+            })
+            .collect()
+    }
+
+    pub async fn set_delegation_reward_at_height(
+        &mut self,
+        delegation_id: DelegationId,
+        reward: &DelegationReward,
+        block_height: BlockHeight,
+        chain_config: &ChainConfig,
+    ) -> Result<(), ApiServerStorageError> {
+        let height = Self::block_height_to_postgres_friendly(block_height);
+        let pool_id = Address::new(chain_config, reward.pool_id)
+            .map_err(|_| ApiServerStorageError::AddressableError)?;
+        let delegation_id = Address::new(chain_config, delegation_id)
+            .map_err(|_| ApiServerStorageError::AddressableError)?;
+
+        self.tx
+            .execute(
+                r#"
+                    INSERT INTO ml.delegation_rewards (delegation_id, block_height, block_id, pool_id, amount)
+                    VALUES($1, $2, $3, $4, $5)
+                    ON CONFLICT (delegation_id, block_height) DO UPDATE
+                    SET block_id = $3, pool_id = $4, amount = $5;
+                "#,
+                &[
+                    &delegation_id.as_str(),
+                    &height,
+                    &reward.block_id.encode(),
+                    &pool_id.as_str(),
+                    &amount_to_str(reward.amount),
+                ],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    pub async fn del_delegation_rewards_above_height(
+        &mut self,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        let height = Self::block_height_to_postgres_friendly(block_height);
+
+        self.tx
+            .execute(
+                "DELETE FROM ml.delegation_rewards WHERE block_height > $1;",
+                &[&height],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        Ok(())
+    }
+
     pub async fn del_pools_above_height(
         &mut self,
         block_height: BlockHeight,
@@ -1505,7 +2150,7 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
         let row = self
             .tx
             .query_opt(
-                "SELECT utxo, spent FROM ml.utxo WHERE outpoint = $1 ORDER BY block_height DESC LIMIT 1;",
+                "SELECT utxo, spent_by FROM ml.utxo WHERE outpoint = $1 ORDER BY block_height DESC LIMIT 1;",
                 &[&outpoint.encode()],
             )
             .await
@@ -1517,7 +2162,7 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
         };
 
         let serialized_data: Vec<u8> = row.get(0);
-        let spent: bool = row.get(1);
+        let spent_by: Option<Vec<u8>> = row.get(1);
 
         let output =
             UtxoWithExtraInfo::decode_all(&mut serialized_data.as_slice()).map_err(|e| {
@@ -1527,7 +2172,18 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
                 ))
             })?;
 
-        Ok(Some(Utxo::new_with_info(output, spent)))
+        let spent_by = spent_by
+            .map(|spent_by| {
+                UtxoSpender::decode_all(&mut spent_by.as_slice()).map_err(|e| {
+                    ApiServerStorageError::DeserializationError(format!(
+                        "Utxo spender for outpoint {:?} deserialization failed: {}",
+                        outpoint, e
+                    ))
+                })
+            })
+            .transpose()?;
+
+        Ok(Some(Utxo::new_with_info(output, spent_by)))
     }
 
     pub async fn get_address_available_utxos(
@@ -1619,6 +2275,63 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
             .collect()
     }
 
+    pub async fn get_address_spent_utxos(
+        &self,
+        address: &str,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(UtxoOutPoint, UtxoWithExtraInfo, UtxoSpender)>, ApiServerStorageError> {
+        let len = len as i64;
+        let offset = offset as i64;
+        let rows = self
+            .tx
+            .query(
+                r#"SELECT outpoint, utxo, spent_by
+                FROM (
+                    SELECT outpoint, utxo, spent_by, block_height, ROW_NUMBER() OVER(PARTITION BY outpoint ORDER BY block_height DESC) as newest
+                    FROM ml.utxo
+                    WHERE address = $1
+                ) AS sub
+                WHERE newest = 1 AND spent_by IS NOT NULL
+                ORDER BY block_height DESC, outpoint
+                OFFSET $2
+                LIMIT $3;"#,
+                &[&address, &offset, &len],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                let outpoint: Vec<u8> = row.get(0);
+                let utxo: Vec<u8> = row.get(1);
+                let spent_by: Vec<u8> = row.get(2);
+
+                let outpoint = UtxoOutPoint::decode_all(&mut outpoint.as_slice()).map_err(|e| {
+                    ApiServerStorageError::DeserializationError(format!(
+                        "Outpoint for address {:?} deserialization failed: {}",
+                        address, e
+                    ))
+                })?;
+
+                let output = UtxoWithExtraInfo::decode_all(&mut utxo.as_slice()).map_err(|e| {
+                    ApiServerStorageError::DeserializationError(format!(
+                        "Utxo for address {:?} deserialization failed: {}",
+                        address, e
+                    ))
+                })?;
+
+                let spent_by = UtxoSpender::decode_all(&mut spent_by.as_slice()).map_err(|e| {
+                    ApiServerStorageError::DeserializationError(format!(
+                        "Utxo spender for address {:?} deserialization failed: {}",
+                        address, e
+                    ))
+                })?;
+                Ok((outpoint, output, spent_by))
+            })
+            .collect()
+    }
+
     pub async fn get_locked_utxos_until_now(
         &self,
         block_height: BlockHeight,
@@ -1670,13 +2383,14 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
         logging::log::debug!("Inserting utxo {:?} for outpoint {:?}", utxo, outpoint);
         let height = Self::block_height_to_postgres_friendly(block_height);
         let spent = utxo.spent();
+        let spent_by = utxo.spent_by().map(|spent_by| spent_by.encode());
 
         self.tx
             .execute(
-                "INSERT INTO ml.utxo (outpoint, utxo, spent, address, block_height) VALUES ($1, $2, $3, $4, $5)
+                "INSERT INTO ml.utxo (outpoint, utxo, spent, spent_by, address, block_height) VALUES ($1, $2, $3, $4, $5, $6)
                     ON CONFLICT (outpoint, block_height) DO UPDATE
-                    SET utxo = $2, spent = $3;",
-                &[&outpoint.encode(), &utxo.utxo_with_extra_info().encode(), &spent, &address, &height],
+                    SET utxo = $2, spent = $3, spent_by = $4;",
+                &[&outpoint.encode(), &utxo.utxo_with_extra_info().encode(), &spent, &spent_by, &address, &height],
             )
             .await
             .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
@@ -1847,6 +2561,37 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
             .collect()
     }
 
+    pub async fn get_token_ids_changed_above_height(
+        &self,
+        block_height: BlockHeight,
+    ) -> Result<Vec<TokenId>, ApiServerStorageError> {
+        let height = Self::block_height_to_postgres_friendly(block_height);
+
+        self.tx
+            .query(
+                r#"
+                (SELECT DISTINCT token_id
+                 FROM ml.fungible_token
+                 WHERE block_height > $1)
+                UNION ALL
+                (SELECT nft_id
+                 FROM ml.nft_issuance
+                 WHERE block_height > $1);
+            "#,
+                &[&height],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?
+            .into_iter()
+            .map(|row| -> Result<TokenId, ApiServerStorageError> {
+                let token_id: Vec<u8> = row.get(0);
+                let token_id = TokenId::decode_all(&mut token_id.as_slice())
+                    .map_err(|_| ApiServerStorageError::AddressableError)?;
+                Ok(token_id)
+            })
+            .collect()
+    }
+
     pub async fn get_token_ids_by_ticker(
         &self,
         len: u32,
@@ -2059,6 +2804,52 @@ impl<'a, 'b> QueryFromConnection<'a, 'b> {
         Ok(())
     }
 
+    pub async fn get_token_metadata_cache(
+        &self,
+        token_id: TokenId,
+    ) -> Result<Option<TokenMetadataCacheEntry>, ApiServerStorageError> {
+        let row = self
+            .tx
+            .query_opt(
+                "SELECT data FROM ml.token_metadata_cache WHERE token_id = $1;",
+                &[&token_id.encode()],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        row.map(|row| {
+            let serialized_data: Vec<u8> = row.get(0);
+            TokenMetadataCacheEntry::decode_all(&mut serialized_data.as_slice()).map_err(|e| {
+                ApiServerStorageError::DeserializationError(format!(
+                    "Token metadata cache for token id {} deserialization failed: {}",
+                    token_id, e
+                ))
+            })
+        })
+        .transpose()
+    }
+
+    pub async fn set_token_metadata_cache(
+        &mut self,
+        token_id: TokenId,
+        entry: &TokenMetadataCacheEntry,
+    ) -> Result<(), ApiServerStorageError> {
+        self.tx
+            .execute(
+                r#"
+                    INSERT INTO ml.token_metadata_cache (token_id, data)
+                    VALUES ($1, $2)
+                    ON CONFLICT (token_id)
+                    DO UPDATE SET data = $2;
+                "#,
+                &[&token_id.encode(), &entry.encode()],
+            )
+            .await
+            .map_err(|e| ApiServerStorageError::LowLevelStorageError(e.to_string()))?;
+
+        Ok(())
+    }
+
     pub async fn del_token_issuance_above_height(
         &mut self,
         block_height: BlockHeight,
@@ -25,9 +25,10 @@ use common::{
 use crate::storage::{
     impls::postgres::queries::QueryFromConnection,
     storage_api::{
-        block_aux_data::BlockAuxData, ApiServerStorageError, ApiServerStorageRead, BlockInfo,
-        CoinOrTokenStatistic, Delegation, FungibleTokenData, PoolBlockStats, TransactionInfo, Utxo,
-        UtxoWithExtraInfo,
+        block_aux_data::BlockAuxData, scan_checkpoint::ScanCheckpoint, AddressCluster,
+        ApiServerStorageError, ApiServerStorageRead, BlockInfo, CoinOrTokenStatistic, Delegation,
+        DelegationReward, FungibleTokenData, PoolBlockStats, TokenMetadataCacheEntry,
+        TransactionInfo, Utxo, UtxoSpender, UtxoWithExtraInfo,
     },
 };
 use std::collections::BTreeMap;
@@ -75,6 +76,18 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRo<'a> {
         Ok(res)
     }
 
+    async fn get_coin_or_token_holders(
+        &self,
+        coin_or_token_id: CoinOrTokenId,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(String, Amount)>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_coin_or_token_holders(coin_or_token_id, len, offset).await?;
+
+        Ok(res)
+    }
+
     async fn get_address_transactions(
         &self,
         address: &str,
@@ -85,6 +98,64 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRo<'a> {
         Ok(res)
     }
 
+    async fn get_address_balance_at_height(
+        &self,
+        address: &str,
+        coin_or_token_id: CoinOrTokenId,
+        block_height: BlockHeight,
+    ) -> Result<Option<Amount>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn
+            .get_address_balance_at_height(address, coin_or_token_id, block_height)
+            .await?;
+
+        Ok(res)
+    }
+
+    async fn get_pruned_height(&self) -> Result<Option<BlockHeight>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_pruned_height().await?;
+
+        Ok(res)
+    }
+
+    async fn get_scan_checkpoint(&self) -> Result<Option<ScanCheckpoint>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_scan_checkpoint().await?;
+
+        Ok(res)
+    }
+
+    async fn get_address_activity_heights(
+        &self,
+        address: &str,
+    ) -> Result<Option<(BlockHeight, BlockHeight)>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_address_activity_heights(address).await?;
+
+        Ok(res)
+    }
+
+    async fn get_address_cluster(
+        &self,
+        address: &str,
+    ) -> Result<Option<AddressCluster>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_address_cluster(address).await?;
+
+        Ok(res)
+    }
+
+    async fn get_address_cluster_members(
+        &self,
+        cluster_id: &str,
+    ) -> Result<Vec<String>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_address_cluster_members(cluster_id).await?;
+
+        Ok(res)
+    }
+
     async fn get_latest_blocktimestamps(
         &self,
     ) -> Result<Vec<BlockTimestamp>, ApiServerStorageError> {
@@ -165,6 +236,21 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRo<'a> {
         Ok(res)
     }
 
+    async fn get_delegation_rewards(
+        &self,
+        delegation_id: DelegationId,
+        from_height: BlockHeight,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(BlockHeight, DelegationReward)>, ApiServerStorageError> {
+        let mut conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn
+            .get_delegation_rewards(delegation_id, from_height, len, offset, &self.chain_config)
+            .await?;
+
+        Ok(res)
+    }
+
     async fn get_main_chain_block_id(
         &self,
         block_height: BlockHeight,
@@ -271,6 +357,18 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRo<'a> {
         Ok(res)
     }
 
+    async fn get_address_spent_utxos(
+        &self,
+        address: &str,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(UtxoOutPoint, UtxoWithExtraInfo, UtxoSpender)>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_address_spent_utxos(address, len, offset).await?;
+
+        Ok(res)
+    }
+
     async fn get_locked_utxos_until_now(
         &self,
         block_height: BlockHeight,
@@ -345,6 +443,26 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRo<'a> {
         Ok(res)
     }
 
+    async fn get_token_ids_changed_above_height(
+        &self,
+        block_height: BlockHeight,
+    ) -> Result<Vec<TokenId>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_token_ids_changed_above_height(block_height).await?;
+
+        Ok(res)
+    }
+
+    async fn get_token_metadata_cache(
+        &self,
+        token_id: TokenId,
+    ) -> Result<Option<TokenMetadataCacheEntry>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_token_metadata_cache(token_id).await?;
+
+        Ok(res)
+    }
+
     async fn get_statistic(
         &self,
         statistic: CoinOrTokenStatistic,
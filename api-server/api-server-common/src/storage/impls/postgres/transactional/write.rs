@@ -29,9 +29,11 @@ use crate::storage::{
     impls::postgres::queries::QueryFromConnection,
     storage_api::{
         block_aux_data::{BlockAuxData, BlockWithExtraData},
-        ApiServerStorageError, ApiServerStorageRead, ApiServerStorageWrite, BlockInfo,
-        CoinOrTokenStatistic, Delegation, FungibleTokenData, LockedUtxo, PoolBlockStats,
-        TransactionInfo, Utxo, UtxoWithExtraInfo,
+        scan_checkpoint::ScanCheckpoint,
+        AddressCluster, ApiServerStorageError, ApiServerStorageRead, ApiServerStorageWrite,
+        BlockInfo, CoinOrTokenStatistic, Delegation, DelegationReward, FungibleTokenData,
+        LockedUtxo, PoolBlockStats, TokenMetadataCacheEntry, TransactionInfo, Utxo, UtxoSpender,
+        UtxoWithExtraInfo,
     },
 };
 
@@ -69,6 +71,16 @@ impl<'a> ApiServerStorageWrite for ApiServerPostgresTransactionalRw<'a> {
         Ok(())
     }
 
+    async fn prune_address_balance_history(
+        &mut self,
+        below_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        let mut conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        conn.prune_address_balance_history(below_height).await?;
+
+        Ok(())
+    }
+
     async fn del_address_transactions_above_height(
         &mut self,
         block_height: BlockHeight,
@@ -79,6 +91,26 @@ impl<'a> ApiServerStorageWrite for ApiServerPostgresTransactionalRw<'a> {
         Ok(())
     }
 
+    async fn set_scan_checkpoint(
+        &mut self,
+        checkpoint: ScanCheckpoint,
+    ) -> Result<(), ApiServerStorageError> {
+        let mut conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        conn.set_scan_checkpoint(checkpoint).await?;
+
+        Ok(())
+    }
+
+    async fn del_address_clusters_above_height(
+        &mut self,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        let mut conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        conn.del_address_clusters_above_height(block_height).await?;
+
+        Ok(())
+    }
+
     async fn set_address_balance_at_height(
         &mut self,
         address: &str,
@@ -120,6 +152,20 @@ impl<'a> ApiServerStorageWrite for ApiServerPostgresTransactionalRw<'a> {
         Ok(())
     }
 
+    async fn set_address_cluster_parent_at_height(
+        &mut self,
+        address: &str,
+        parent: &str,
+        size: u64,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        let mut conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        conn.set_address_cluster_parent_at_height(address, parent, size, block_height)
+            .await?;
+
+        Ok(())
+    }
+
     async fn set_mainchain_block(
         &mut self,
         block_id: Id<Block>,
@@ -201,6 +247,34 @@ impl<'a> ApiServerStorageWrite for ApiServerPostgresTransactionalRw<'a> {
         Ok(())
     }
 
+    async fn set_delegation_reward_at_height(
+        &mut self,
+        delegation_id: DelegationId,
+        reward: &DelegationReward,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        let mut conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        conn.set_delegation_reward_at_height(
+            delegation_id,
+            reward,
+            block_height,
+            &self.chain_config,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn del_delegation_rewards_above_height(
+        &mut self,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError> {
+        let mut conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        conn.del_delegation_rewards_above_height(block_height).await?;
+
+        Ok(())
+    }
+
     async fn del_pools_above_height(
         &mut self,
         block_height: BlockHeight,
@@ -301,6 +375,17 @@ impl<'a> ApiServerStorageWrite for ApiServerPostgresTransactionalRw<'a> {
         Ok(())
     }
 
+    async fn set_token_metadata_cache(
+        &mut self,
+        token_id: TokenId,
+        entry: &TokenMetadataCacheEntry,
+    ) -> Result<(), ApiServerStorageError> {
+        let mut conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        conn.set_token_metadata_cache(token_id, entry).await?;
+
+        Ok(())
+    }
+
     async fn set_statistic(
         &mut self,
         statistic: CoinOrTokenStatistic,
@@ -363,6 +448,18 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRw<'a> {
         Ok(res)
     }
 
+    async fn get_coin_or_token_holders(
+        &self,
+        coin_or_token_id: CoinOrTokenId,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(String, Amount)>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_coin_or_token_holders(coin_or_token_id, len, offset).await?;
+
+        Ok(res)
+    }
+
     async fn get_address_transactions(
         &self,
         address: &str,
@@ -373,6 +470,64 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRw<'a> {
         Ok(res)
     }
 
+    async fn get_address_balance_at_height(
+        &self,
+        address: &str,
+        coin_or_token_id: CoinOrTokenId,
+        block_height: BlockHeight,
+    ) -> Result<Option<Amount>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn
+            .get_address_balance_at_height(address, coin_or_token_id, block_height)
+            .await?;
+
+        Ok(res)
+    }
+
+    async fn get_pruned_height(&self) -> Result<Option<BlockHeight>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_pruned_height().await?;
+
+        Ok(res)
+    }
+
+    async fn get_scan_checkpoint(&self) -> Result<Option<ScanCheckpoint>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_scan_checkpoint().await?;
+
+        Ok(res)
+    }
+
+    async fn get_address_activity_heights(
+        &self,
+        address: &str,
+    ) -> Result<Option<(BlockHeight, BlockHeight)>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_address_activity_heights(address).await?;
+
+        Ok(res)
+    }
+
+    async fn get_address_cluster(
+        &self,
+        address: &str,
+    ) -> Result<Option<AddressCluster>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_address_cluster(address).await?;
+
+        Ok(res)
+    }
+
+    async fn get_address_cluster_members(
+        &self,
+        cluster_id: &str,
+    ) -> Result<Vec<String>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_address_cluster_members(cluster_id).await?;
+
+        Ok(res)
+    }
+
     async fn get_latest_blocktimestamps(
         &self,
     ) -> Result<Vec<BlockTimestamp>, ApiServerStorageError> {
@@ -515,6 +670,21 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRw<'a> {
         Ok(res)
     }
 
+    async fn get_delegation_rewards(
+        &self,
+        delegation_id: DelegationId,
+        from_height: BlockHeight,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(BlockHeight, DelegationReward)>, ApiServerStorageError> {
+        let mut conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn
+            .get_delegation_rewards(delegation_id, from_height, len, offset, &self.chain_config)
+            .await?;
+
+        Ok(res)
+    }
+
     async fn get_transaction(
         &self,
         transaction_id: Id<Transaction>,
@@ -555,6 +725,18 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRw<'a> {
         Ok(res)
     }
 
+    async fn get_address_spent_utxos(
+        &self,
+        address: &str,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(UtxoOutPoint, UtxoWithExtraInfo, UtxoSpender)>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_address_spent_utxos(address, len, offset).await?;
+
+        Ok(res)
+    }
+
     async fn get_locked_utxos_until_now(
         &self,
         block_height: BlockHeight,
@@ -629,6 +811,26 @@ impl<'a> ApiServerStorageRead for ApiServerPostgresTransactionalRw<'a> {
         Ok(res)
     }
 
+    async fn get_token_ids_changed_above_height(
+        &self,
+        block_height: BlockHeight,
+    ) -> Result<Vec<TokenId>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_token_ids_changed_above_height(block_height).await?;
+
+        Ok(res)
+    }
+
+    async fn get_token_metadata_cache(
+        &self,
+        token_id: TokenId,
+    ) -> Result<Option<TokenMetadataCacheEntry>, ApiServerStorageError> {
+        let conn = QueryFromConnection::new(self.connection.as_ref().expect(CONN_ERR));
+        let res = conn.get_token_metadata_cache(token_id).await?;
+
+        Ok(res)
+    }
+
     async fn get_statistic(
         &self,
         statistic: CoinOrTokenStatistic,
@@ -35,9 +35,13 @@ use common::{
 use pos_accounting::PoolData;
 use serialization::{Decode, Encode};
 
-use self::block_aux_data::{BlockAuxData, BlockWithExtraData};
+use self::{
+    block_aux_data::{BlockAuxData, BlockWithExtraData},
+    scan_checkpoint::ScanCheckpoint,
+};
 
 pub mod block_aux_data;
+pub mod scan_checkpoint;
 
 #[allow(dead_code)]
 #[derive(Debug, thiserror::Error)]
@@ -66,6 +70,8 @@ pub enum ApiServerStorageError {
     AddressableError,
     #[error("Block timestamp to high {0}")]
     TimestampToHigh(BlockTimestamp),
+    #[error("The history before block height {0} has been pruned")]
+    HistoryPruned(BlockHeight),
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -261,24 +267,37 @@ impl LockedUtxo {
     }
 }
 
+/// What has spent a UTXO
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum UtxoSpender {
+    /// The UTXO was spent by an input of a transaction
+    Transaction(Id<Transaction>),
+    /// The UTXO was spent by a kernel input of a PoS block
+    Block(Id<Block>),
+}
+
 #[derive(Debug, Clone, Encode, Decode)]
 pub struct Utxo {
     utxo: UtxoWithExtraInfo,
-    spent: bool,
+    spent_by: Option<UtxoSpender>,
 }
 
 impl Utxo {
-    pub fn new_with_info(utxo: UtxoWithExtraInfo, spent: bool) -> Self {
-        Self { utxo, spent }
+    pub fn new_with_info(utxo: UtxoWithExtraInfo, spent_by: Option<UtxoSpender>) -> Self {
+        Self { utxo, spent_by }
     }
 
-    pub fn new(output: TxOutput, token_decimals: Option<u8>, spent: bool) -> Self {
+    pub fn new(
+        output: TxOutput,
+        token_decimals: Option<u8>,
+        spent_by: Option<UtxoSpender>,
+    ) -> Self {
         Self {
             utxo: UtxoWithExtraInfo {
                 output,
                 token_decimals,
             },
-            spent,
+            spent_by,
         }
     }
 
@@ -295,7 +314,11 @@ impl Utxo {
     }
 
     pub fn spent(&self) -> bool {
-        self.spent
+        self.spent_by.is_some()
+    }
+
+    pub fn spent_by(&self) -> Option<UtxoSpender> {
+        self.spent_by
     }
 }
 
@@ -309,6 +332,8 @@ pub struct FungibleTokenData {
     pub is_locked: bool,
     pub frozen: IsTokenFrozen,
     pub authority: Destination,
+    /// The nonce to be used by the next account command of the token
+    pub next_nonce: AccountNonce,
 }
 
 impl FungibleTokenData {
@@ -326,38 +351,45 @@ impl FungibleTokenData {
         }
     }
 
-    pub fn mint_tokens(mut self, amount: Amount) -> Self {
+    pub fn mint_tokens(mut self, amount: Amount, nonce: AccountNonce) -> Self {
         self.circulating_supply = (self.circulating_supply + amount).expect("no overflow");
+        self.next_nonce = nonce.increment().expect("no overflow");
         self
     }
 
-    pub fn unmint_tokens(mut self, amount: Amount) -> Self {
+    pub fn unmint_tokens(mut self, amount: Amount, nonce: AccountNonce) -> Self {
         self.circulating_supply = (self.circulating_supply - amount).expect("no underflow");
+        self.next_nonce = nonce.increment().expect("no overflow");
         self
     }
 
-    pub fn freeze(mut self, is_token_unfreezable: IsTokenUnfreezable) -> Self {
+    pub fn freeze(mut self, is_token_unfreezable: IsTokenUnfreezable, nonce: AccountNonce) -> Self {
         self.frozen = IsTokenFrozen::Yes(is_token_unfreezable);
+        self.next_nonce = nonce.increment().expect("no overflow");
         self
     }
 
-    pub fn unfreeze(mut self) -> Self {
+    pub fn unfreeze(mut self, nonce: AccountNonce) -> Self {
         self.frozen = IsTokenFrozen::No(IsTokenFreezable::Yes);
+        self.next_nonce = nonce.increment().expect("no overflow");
         self
     }
 
-    pub fn lock(mut self) -> Self {
+    pub fn lock(mut self, nonce: AccountNonce) -> Self {
         self.is_locked = true;
+        self.next_nonce = nonce.increment().expect("no overflow");
         self
     }
 
-    pub fn change_authority(mut self, authority: Destination) -> Self {
+    pub fn change_authority(mut self, authority: Destination, nonce: AccountNonce) -> Self {
         self.authority = authority;
+        self.next_nonce = nonce.increment().expect("no overflow");
         self
     }
 
-    pub fn change_metadata_uri(mut self, metadata_uri: Vec<u8>) -> Self {
+    pub fn change_metadata_uri(mut self, metadata_uri: Vec<u8>, nonce: AccountNonce) -> Self {
         self.metadata_uri = metadata_uri;
+        self.next_nonce = nonce.increment().expect("no overflow");
         self
     }
 }
@@ -379,6 +411,44 @@ pub struct PoolBlockStats {
     pub block_count: u64,
 }
 
+/// The part of the reward of a block produced by a pool that was credited to a delegation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DelegationReward {
+    pub block_id: Id<Block>,
+    pub pool_id: PoolId,
+    pub amount: Amount,
+}
+
+/// A common-input-ownership cluster of addresses
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressCluster {
+    /// The address at the root of the cluster's union-find tree, which identifies the cluster
+    pub id: String,
+    /// The number of addresses in the cluster
+    pub size: u64,
+}
+
+/// The result of fetching the contents of a token's metadata URI
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum TokenMetadataContent {
+    /// The sanitized contents and their content type
+    Fetched {
+        content_type: String,
+        content: Vec<u8>,
+    },
+    /// The contents could not be fetched or were rejected, with the reason
+    Failed(String),
+}
+
+/// A cached copy of the contents of a token's metadata URI
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct TokenMetadataCacheEntry {
+    /// The URI the contents were fetched from
+    pub uri: Vec<u8>,
+    pub content: TokenMetadataContent,
+    pub fetched_at: BlockTimestamp,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BlockInfo {
     pub block: BlockWithExtraData,
@@ -403,11 +473,58 @@ pub trait ApiServerStorageRead: Sync {
         coin_or_token_id: CoinOrTokenId,
     ) -> Result<Option<Amount>, ApiServerStorageError>;
 
+    /// Return the addresses with a non-zero balance of the coin or token and their balances,
+    /// ordered from the largest balance to the smallest
+    async fn get_coin_or_token_holders(
+        &self,
+        coin_or_token_id: CoinOrTokenId,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(String, Amount)>, ApiServerStorageError>;
+
+    /// Return the balance of the address as of the block at the specified height.
+    /// Fails with `HistoryPruned` if the height is below the retention window.
+    async fn get_address_balance_at_height(
+        &self,
+        address: &str,
+        coin_or_token_id: CoinOrTokenId,
+        block_height: BlockHeight,
+    ) -> Result<Option<Amount>, ApiServerStorageError>;
+
+    /// Return the height below which the address balance history has been aggregated,
+    /// or None if nothing has been pruned yet.
+    async fn get_pruned_height(&self) -> Result<Option<BlockHeight>, ApiServerStorageError>;
+
+    /// Return the last checkpoint of the scanner, or None if no block has been scanned
+    /// since the storage was initialized.
+    async fn get_scan_checkpoint(&self) -> Result<Option<ScanCheckpoint>, ApiServerStorageError>;
+
     async fn get_address_transactions(
         &self,
         address: &str,
     ) -> Result<Vec<Id<Transaction>>, ApiServerStorageError>;
 
+    /// Return the heights of the first and the last blocks containing transactions of the address,
+    /// or None if it has no transactions.
+    async fn get_address_activity_heights(
+        &self,
+        address: &str,
+    ) -> Result<Option<(BlockHeight, BlockHeight)>, ApiServerStorageError>;
+
+    /// Return the common-input-ownership cluster the address currently belongs to,
+    /// or None if the address hasn't been clustered, i.e. it has never spent an output
+    /// or the clustering is disabled in the scanner.
+    async fn get_address_cluster(
+        &self,
+        address: &str,
+    ) -> Result<Option<AddressCluster>, ApiServerStorageError>;
+
+    /// Return all the addresses that currently belong to the cluster
+    async fn get_address_cluster_members(
+        &self,
+        cluster_id: &str,
+    ) -> Result<Vec<String>, ApiServerStorageError>;
+
     async fn get_best_block(&self) -> Result<BlockAuxData, ApiServerStorageError>;
 
     async fn get_latest_blocktimestamps(
@@ -439,6 +556,16 @@ pub trait ApiServerStorageRead: Sync {
         pool_id: PoolId,
     ) -> Result<BTreeMap<DelegationId, Delegation>, ApiServerStorageError>;
 
+    /// Return a page of the rewards credited to the delegation in the blocks starting from the
+    /// specified height, ordered by the block height
+    async fn get_delegation_rewards(
+        &self,
+        delegation_id: DelegationId,
+        from_height: BlockHeight,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(BlockHeight, DelegationReward)>, ApiServerStorageError>;
+
     async fn get_main_chain_block_id(
         &self,
         block_height: BlockHeight,
@@ -498,6 +625,15 @@ pub trait ApiServerStorageRead: Sync {
         address: &str,
     ) -> Result<Vec<(UtxoOutPoint, UtxoWithExtraInfo)>, ApiServerStorageError>;
 
+    /// The spent UTXOs of the address, together with what has spent them,
+    /// starting from the most recently spent ones
+    async fn get_address_spent_utxos(
+        &self,
+        address: &str,
+        len: u32,
+        offset: u32,
+    ) -> Result<Vec<(UtxoOutPoint, UtxoWithExtraInfo, UtxoSpender)>, ApiServerStorageError>;
+
     async fn get_locked_utxos_until_now(
         &self,
         block_height: BlockHeight,
@@ -537,6 +673,18 @@ pub trait ApiServerStorageRead: Sync {
         ticker: &[u8],
     ) -> Result<Vec<TokenId>, ApiServerStorageError>;
 
+    /// Return the ids of the tokens whose issuance data has changed above the given height,
+    /// e.g. because they have been issued or their metadata URI has been changed there
+    async fn get_token_ids_changed_above_height(
+        &self,
+        block_height: BlockHeight,
+    ) -> Result<Vec<TokenId>, ApiServerStorageError>;
+
+    async fn get_token_metadata_cache(
+        &self,
+        token_id: TokenId,
+    ) -> Result<Option<TokenMetadataCacheEntry>, ApiServerStorageError>;
+
     async fn get_statistic(
         &self,
         statistic: CoinOrTokenStatistic,
@@ -566,11 +714,29 @@ pub trait ApiServerStorageWrite: ApiServerStorageRead {
         block_height: BlockHeight,
     ) -> Result<(), ApiServerStorageError>;
 
+    /// Aggregate the address balance history (both the regular and the locked balances) below
+    /// the specified height into checkpoints: only the last balance of each address and currency
+    /// before the height is kept, so the balances at the height and above stay accurate.
+    async fn prune_address_balance_history(
+        &mut self,
+        below_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError>;
+
     async fn del_address_transactions_above_height(
         &mut self,
         block_height: BlockHeight,
     ) -> Result<(), ApiServerStorageError>;
 
+    async fn set_scan_checkpoint(
+        &mut self,
+        checkpoint: ScanCheckpoint,
+    ) -> Result<(), ApiServerStorageError>;
+
+    async fn del_address_clusters_above_height(
+        &mut self,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError>;
+
     async fn set_address_balance_at_height(
         &mut self,
         address: &str,
@@ -594,6 +760,17 @@ pub trait ApiServerStorageWrite: ApiServerStorageRead {
         block_height: BlockHeight,
     ) -> Result<(), ApiServerStorageError>;
 
+    /// Set the parent of the address in the union-find tree of its cluster as of the block
+    /// at the specified height. The root of the tree is its own parent and holds the size
+    /// of the cluster.
+    async fn set_address_cluster_parent_at_height(
+        &mut self,
+        address: &str,
+        parent: &str,
+        size: u64,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError>;
+
     async fn set_mainchain_block(
         &mut self,
         block_id: Id<Block>,
@@ -638,6 +815,18 @@ pub trait ApiServerStorageWrite: ApiServerStorageRead {
         block_height: BlockHeight,
     ) -> Result<(), ApiServerStorageError>;
 
+    async fn set_delegation_reward_at_height(
+        &mut self,
+        delegation_id: DelegationId,
+        reward: &DelegationReward,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError>;
+
+    async fn del_delegation_rewards_above_height(
+        &mut self,
+        block_height: BlockHeight,
+    ) -> Result<(), ApiServerStorageError>;
+
     async fn del_pools_above_height(
         &mut self,
         block_height: BlockHeight,
@@ -693,6 +882,14 @@ pub trait ApiServerStorageWrite: ApiServerStorageRead {
         block_height: BlockHeight,
     ) -> Result<(), ApiServerStorageError>;
 
+    /// Store the fetched metadata of a token; the cache is not tied to the chain state,
+    /// so it's not affected by reorgs
+    async fn set_token_metadata_cache(
+        &mut self,
+        token_id: TokenId,
+        entry: &TokenMetadataCacheEntry,
+    ) -> Result<(), ApiServerStorageError>;
+
     async fn set_statistic(
         &mut self,
         statistic: CoinOrTokenStatistic,
@@ -42,6 +42,20 @@ pub struct ApiServerScannerArgs {
     #[clap(long)]
     pub node_rpc_password: Option<String>,
 
+    /// Keep the full address balance history only for this number of the latest blocks and
+    /// aggregate the older history to limit the database growth. Must not be less than
+    /// the max reorg depth. The full history is kept if not set.
+    #[clap(long)]
+    pub keep_history_blocks: Option<u64>,
+
+    /// Group the addresses into clusters using the common-input-ownership heuristic, i.e.
+    /// assume that all the addresses spending in the same transaction belong to the same entity.
+    /// The clusters are served by the `/address/:address/cluster` endpoint of the web server.
+    /// Enabling or disabling it requires rescanning the chain for consistent results.
+    #[cfg(feature = "address-clustering")]
+    #[clap(long)]
+    pub enable_address_clustering: bool,
+
     /// Postgres config values
     #[clap(flatten)]
     pub postgres_config: PostgresConfig,
@@ -13,9 +13,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use api_blockchain_scanner_lib::blockchain_state::BlockchainState;
+use api_blockchain_scanner_lib::{blockchain_state::BlockchainState, retention::RetentionPolicy};
 use api_server_common::storage::{
     impls::{postgres::TransactionalApiServerPostgresStorage, CURRENT_STORAGE_VERSION},
     storage_api::{
@@ -31,6 +31,9 @@ use rpc::RpcAuthData;
 use utils::{cookie::COOKIE_FILENAME, default_data_dir::default_data_dir_for_chain};
 mod config;
 
+/// How often to check whether the history out of the retention window should be aggregated
+const PRUNING_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
 pub async fn make_postgres_storage(
     postgres_host: String,
     postgres_port: u16,
@@ -53,10 +56,31 @@ pub async fn make_postgres_storage(
     .map_err(ApiServerScannerError::PostgresConnectionError)
 }
 
+#[cfg(feature = "address-clustering")]
+fn new_blockchain_state<S: ApiServerStorage>(
+    chain_config: &Arc<ChainConfig>,
+    storage: S,
+    enable_address_clustering: bool,
+) -> BlockchainState<S> {
+    BlockchainState::new(Arc::clone(chain_config), storage)
+        .with_address_clustering(enable_address_clustering)
+}
+
+#[cfg(not(feature = "address-clustering"))]
+fn new_blockchain_state<S: ApiServerStorage>(
+    chain_config: &Arc<ChainConfig>,
+    storage: S,
+    _enable_address_clustering: bool,
+) -> BlockchainState<S> {
+    BlockchainState::new(Arc::clone(chain_config), storage)
+}
+
 pub async fn run<S: ApiServerStorage>(
     chain_config: &Arc<ChainConfig>,
     rpc_client: &NodeRpcClient,
     mut storage: S,
+    retention_policy: Option<RetentionPolicy>,
+    enable_address_clustering: bool,
 ) -> Result<(), ApiServerScannerError> {
     // TODO: move this storage initialization into a separate function... the trait bounds are gonna be painful
 
@@ -80,7 +104,8 @@ pub async fn run<S: ApiServerStorage>(
                 .await
                 .unwrap_or_else(|e| panic!("Storage initialization commit failed {}", e));
 
-            let mut local_block = BlockchainState::new(Arc::clone(chain_config), storage);
+            let mut local_block =
+                new_blockchain_state(chain_config, storage, enable_address_clustering);
             local_block
                 .scan_genesis(chain_config.genesis_block().as_ref())
                 .await
@@ -102,7 +127,8 @@ pub async fn run<S: ApiServerStorage>(
                     .commit()
                     .await
                     .unwrap_or_else(|e| panic!("Storage initialization commit failed {}", e));
-                let mut local_block = BlockchainState::new(Arc::clone(chain_config), storage);
+                let mut local_block =
+                    new_blockchain_state(chain_config, storage, enable_address_clustering);
                 local_block
                     .scan_genesis(chain_config.genesis_block().as_ref())
                     .await
@@ -113,11 +139,13 @@ pub async fn run<S: ApiServerStorage>(
                     .commit()
                     .await
                     .unwrap_or_else(|e| panic!("Storage initialization commit failed {}", e));
-                BlockchainState::new(Arc::clone(chain_config), storage)
+                new_blockchain_state(chain_config, storage, enable_address_clustering)
             }
         }
     };
 
+    let mut next_pruning_check = tokio::time::Instant::now();
+
     loop {
         let sync_result =
             api_blockchain_scanner_lib::sync::sync_once(chain_config, rpc_client, &mut local_block)
@@ -127,6 +155,16 @@ pub async fn run<S: ApiServerStorage>(
             Ok(_) => (),
             Err(err) => logging::log::error!("Scanner sync error: {}", err),
         }
+
+        // The history is aggregated between the syncs, because both need a RW transaction
+        if let Some(retention_policy) = &retention_policy {
+            if tokio::time::Instant::now() >= next_pruning_check {
+                if let Err(err) = local_block.prune_history(retention_policy).await {
+                    logging::log::error!("Scanner history pruning error: {}", err);
+                }
+                next_pruning_check = tokio::time::Instant::now() + PRUNING_CHECK_INTERVAL;
+            }
+        }
     }
 }
 
@@ -159,12 +197,22 @@ async fn main() -> Result<(), ApiServerScannerError> {
         node_rpc_cookie_file,
         node_rpc_username,
         node_rpc_password,
+        keep_history_blocks,
+        #[cfg(feature = "address-clustering")]
+        enable_address_clustering,
         postgres_config,
     } = args;
+    #[cfg(not(feature = "address-clustering"))]
+    let enable_address_clustering = false;
 
     let chain_type: ChainType = network.into();
     let chain_config = Arc::new(common::chain::config::Builder::new(chain_type).build());
 
+    let retention_policy = keep_history_blocks
+        .map(|keep_blocks| RetentionPolicy::new(&chain_config, keep_blocks))
+        .transpose()
+        .map_err(|e| ApiServerScannerError::InvalidConfig(e.to_string()))?;
+
     let node_rpc_auth = match (node_rpc_cookie_file, node_rpc_username, node_rpc_password) {
         (None, None, None) => {
             let cookie_file_path =
@@ -210,7 +258,14 @@ async fn main() -> Result<(), ApiServerScannerError> {
     )
     .await?;
 
-    run(&chain_config, &rpc_client, storage).await?;
+    run(
+        &chain_config,
+        &rpc_client,
+        storage,
+        retention_policy,
+        enable_address_clustering,
+    )
+    .await?;
 
     Ok(())
 }
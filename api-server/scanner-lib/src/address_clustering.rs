@@ -0,0 +1,256 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Address clustering based on the common-input-ownership heuristic: all the addresses whose
+//! outputs are spent in the same transaction are assumed to be controlled by the same entity.
+//!
+//! The clusters are maintained incrementally while the blocks are scanned, as union-find trees
+//! of addresses. Each cluster is identified by the address at the root of its tree, which also
+//! holds the size of the cluster; when clusters are merged, the roots of the smaller ones are
+//! attached to the root of the largest one, so a merge only updates the roots. The trees are
+//! versioned by block height, so disconnected blocks are rolled back together with the rest
+//! of the tables.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use api_server_common::storage::storage_api::{
+    ApiServerStorageError, ApiServerStorageRead, ApiServerStorageWrite,
+};
+use common::{
+    address::Address,
+    chain::{ChainConfig, Destination, Transaction, TxInput, TxOutput},
+    primitives::BlockHeight,
+};
+
+/// Collect the addresses that own the UTXOs spent by the transaction.
+/// Only the regular transfer outputs are taken into account, because the others
+/// (e.g. the stake pool outputs) don't necessarily mean ownership of the funds.
+pub async fn input_addresses<T: ApiServerStorageRead>(
+    chain_config: &ChainConfig,
+    db_tx: &T,
+    tx: &Transaction,
+) -> Result<BTreeSet<String>, ApiServerStorageError> {
+    let mut addresses = BTreeSet::new();
+
+    for input in tx.inputs() {
+        let outpoint = match input {
+            TxInput::Utxo(outpoint) => outpoint,
+            TxInput::Account(_) | TxInput::AccountCommand(_, _) => continue,
+        };
+
+        let utxo = match db_tx.get_utxo(outpoint.clone()).await? {
+            Some(utxo) => utxo,
+            None => continue,
+        };
+
+        let destination = match utxo.output() {
+            TxOutput::Transfer(_, destination) | TxOutput::LockThenTransfer(_, destination, _) => {
+                destination
+            }
+            TxOutput::Burn(_)
+            | TxOutput::CreateStakePool(_, _)
+            | TxOutput::ProduceBlockFromStake(_, _)
+            | TxOutput::CreateDelegationId(_, _)
+            | TxOutput::DelegateStaking(_, _)
+            | TxOutput::IssueFungibleToken(_)
+            | TxOutput::IssueNft(_, _, _)
+            | TxOutput::DataDeposit(_)
+            | TxOutput::Htlc(_, _)
+            | TxOutput::AnyoneCanTake(_) => continue,
+        };
+
+        match destination {
+            // Anyone can spend these, so they say nothing about the ownership
+            Destination::AnyoneCanSpend => continue,
+            Destination::PublicKeyHash(_)
+            | Destination::PublicKey(_)
+            | Destination::ScriptHash(_)
+            | Destination::ClassicMultisig(_) => {}
+        }
+
+        let address = Address::<Destination>::new(chain_config, destination.clone())
+            .expect("Unable to encode destination");
+        addresses.insert(address.into_string());
+    }
+
+    Ok(addresses)
+}
+
+/// Put all the addresses into the same cluster at the specified height.
+///
+/// The clusters the addresses already belong to are merged into the largest one of them
+/// (the one with the smallest id if there are several), and the addresses that haven't been
+/// clustered yet join it too. An address that is spent alone starts its own cluster.
+pub async fn merge_address_clusters<T: ApiServerStorageWrite>(
+    db_tx: &mut T,
+    addresses: &BTreeSet<String>,
+    block_height: BlockHeight,
+) -> Result<(), ApiServerStorageError> {
+    let mut cluster_sizes = BTreeMap::<String, u64>::new();
+    let mut unclustered = Vec::new();
+
+    for address in addresses {
+        match db_tx.get_address_cluster(address).await? {
+            Some(cluster) => {
+                cluster_sizes.insert(cluster.id, cluster.size);
+            }
+            None => unclustered.push(address),
+        }
+    }
+
+    if cluster_sizes.len() == 1 && unclustered.is_empty() {
+        return Ok(());
+    }
+
+    // The clusters are sorted by id, so on ties the first one is chosen
+    let target_cluster_id = match cluster_sizes
+        .iter()
+        .rev()
+        .max_by_key(|(_, size)| **size)
+        .map(|(cluster_id, _)| cluster_id.clone())
+    {
+        Some(cluster_id) => cluster_id,
+        None => match unclustered.first() {
+            Some(address) => (*address).clone(),
+            None => return Ok(()),
+        },
+    };
+
+    let target_cluster_size = cluster_sizes.values().sum::<u64>() + unclustered.len() as u64;
+
+    for (cluster_id, size) in &cluster_sizes {
+        if *cluster_id != target_cluster_id {
+            db_tx
+                .set_address_cluster_parent_at_height(
+                    cluster_id,
+                    &target_cluster_id,
+                    *size,
+                    block_height,
+                )
+                .await?;
+        }
+    }
+
+    for address in unclustered {
+        if *address != target_cluster_id {
+            db_tx
+                .set_address_cluster_parent_at_height(address, &target_cluster_id, 1, block_height)
+                .await?;
+        }
+    }
+
+    db_tx
+        .set_address_cluster_parent_at_height(
+            &target_cluster_id,
+            &target_cluster_id,
+            target_cluster_size,
+            block_height,
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use api_server_common::storage::{
+        impls::in_memory::transactional::TransactionalApiServerInMemoryStorage,
+        storage_api::{ApiServerTransactionRw, Transactional},
+    };
+    use common::chain::config::create_unit_test_config;
+
+    fn addresses(addresses: &[&str]) -> BTreeSet<String> {
+        addresses.iter().map(|address| address.to_string()).collect()
+    }
+
+    async fn cluster_of<T: ApiServerStorageRead>(db_tx: &T, address: &str) -> Option<String> {
+        db_tx.get_address_cluster(address).await.unwrap().map(|cluster| cluster.id)
+    }
+
+    async fn size_of<T: ApiServerStorageRead>(db_tx: &T, address: &str) -> Option<u64> {
+        db_tx.get_address_cluster(address).await.unwrap().map(|cluster| cluster.size)
+    }
+
+    async fn members_of<T: ApiServerStorageRead>(db_tx: &T, cluster_id: &str) -> BTreeSet<String> {
+        db_tx
+            .get_address_cluster_members(cluster_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn merge_clusters() {
+        let chain_config = create_unit_test_config();
+        let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+        let mut db_tx = storage.transaction_rw().await.unwrap();
+
+        // A single address starts its own cluster
+        merge_address_clusters(&mut db_tx, &addresses(&["a"]), BlockHeight::new(1))
+            .await
+            .unwrap();
+        assert_eq!(cluster_of(&db_tx, "a").await, Some("a".to_owned()));
+        assert_eq!(cluster_of(&db_tx, "b").await, None);
+
+        // New addresses join the existing cluster
+        merge_address_clusters(
+            &mut db_tx,
+            &addresses(&["a", "b", "c"]),
+            BlockHeight::new(2),
+        )
+        .await
+        .unwrap();
+        assert_eq!(members_of(&db_tx, "a").await, addresses(&["a", "b", "c"]));
+
+        // Unrelated addresses form a new cluster, whose id is the smallest address
+        merge_address_clusters(&mut db_tx, &addresses(&["e", "d"]), BlockHeight::new(3))
+            .await
+            .unwrap();
+        assert_eq!(members_of(&db_tx, "d").await, addresses(&["d", "e"]));
+
+        // The smaller cluster is merged into the larger one
+        merge_address_clusters(&mut db_tx, &addresses(&["e", "c"]), BlockHeight::new(4))
+            .await
+            .unwrap();
+        assert_eq!(
+            members_of(&db_tx, "a").await,
+            addresses(&["a", "b", "c", "d", "e"])
+        );
+        assert_eq!(members_of(&db_tx, "d").await, BTreeSet::new());
+        assert_eq!(cluster_of(&db_tx, "d").await, Some("a".to_owned()));
+        assert_eq!(cluster_of(&db_tx, "e").await, Some("a".to_owned()));
+        assert_eq!(size_of(&db_tx, "e").await, Some(5));
+
+        // Merging the addresses of the same cluster changes nothing
+        merge_address_clusters(&mut db_tx, &addresses(&["b", "e"]), BlockHeight::new(5))
+            .await
+            .unwrap();
+        assert_eq!(size_of(&db_tx, "a").await, Some(5));
+
+        // The merge is rolled back when its block is disconnected
+        db_tx.del_address_clusters_above_height(BlockHeight::new(3)).await.unwrap();
+        assert_eq!(members_of(&db_tx, "a").await, addresses(&["a", "b", "c"]));
+        assert_eq!(members_of(&db_tx, "d").await, addresses(&["d", "e"]));
+        assert_eq!(size_of(&db_tx, "a").await, Some(3));
+        assert_eq!(size_of(&db_tx, "e").await, Some(2));
+
+        db_tx.del_address_clusters_above_height(BlockHeight::new(1)).await.unwrap();
+        assert_eq!(members_of(&db_tx, "a").await, addresses(&["a"]));
+        assert_eq!(cluster_of(&db_tx, "d").await, None);
+
+        db_tx.commit().await.unwrap();
+    }
+}
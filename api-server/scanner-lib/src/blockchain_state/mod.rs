@@ -13,12 +13,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::sync::local_state::LocalBlockchainState;
+#[cfg(feature = "address-clustering")]
+use crate::address_clustering;
+use crate::{retention::RetentionPolicy, sync::local_state::LocalBlockchainState};
 use api_server_common::storage::storage_api::{
     block_aux_data::{BlockAuxData, BlockWithExtraData},
+    scan_checkpoint::ScanCheckpoint,
     ApiServerStorage, ApiServerStorageError, ApiServerStorageRead, ApiServerStorageWrite,
-    ApiServerTransactionRw, CoinOrTokenStatistic, Delegation, FungibleTokenData, LockedUtxo,
-    TransactionInfo, TxAdditionalInfo, Utxo, UtxoLock,
+    ApiServerTransactionRw, CoinOrTokenStatistic, Delegation, DelegationReward, FungibleTokenData,
+    LockedUtxo, TransactionInfo, TxAdditionalInfo, Utxo, UtxoLock, UtxoSpender,
 };
 use chainstate::{
     calculate_median_time_past_from_blocktimestamps,
@@ -62,6 +65,9 @@ pub enum BlockchainStateError {
 pub struct BlockchainState<S: ApiServerStorage> {
     chain_config: Arc<ChainConfig>,
     storage: S,
+    /// Whether the addresses are grouped into clusters by the common-input-ownership heuristic
+    #[cfg(feature = "address-clustering")]
+    address_clustering: bool,
 }
 
 impl<S: ApiServerStorage> BlockchainState<S> {
@@ -69,9 +75,17 @@ impl<S: ApiServerStorage> BlockchainState<S> {
         Self {
             chain_config,
             storage,
+            #[cfg(feature = "address-clustering")]
+            address_clustering: false,
         }
     }
 
+    #[cfg(feature = "address-clustering")]
+    pub fn with_address_clustering(mut self, enabled: bool) -> Self {
+        self.address_clustering = enabled;
+        self
+    }
+
     pub fn storage(&self) -> &S {
         &self.storage
     }
@@ -89,11 +103,44 @@ impl<S: ApiServerStorage> BlockchainState<S> {
         .await
         .expect("Unable to update tables from block reward");
 
+        db_tx
+            .set_scan_checkpoint(ScanCheckpoint::new(
+                genesis.get_id().into(),
+                BlockHeight::new(0),
+            ))
+            .await?;
+
         db_tx.commit().await.expect("Unable to commit transaction");
         logging::log::info!("Database commit completed successfully");
 
         Ok(())
     }
+
+    /// Aggregate the history that is out of the retention window, if enough new blocks
+    /// have been connected since the last time. Return the new pruned height if anything
+    /// has been pruned.
+    pub async fn prune_history(
+        &mut self,
+        policy: &RetentionPolicy,
+    ) -> Result<Option<BlockHeight>, BlockchainStateError> {
+        let mut db_tx = self.storage.transaction_rw().await?;
+
+        let best_block_height = db_tx.get_best_block().await?.block_height();
+        let pruned_height = db_tx.get_pruned_height().await?;
+        let below_height = match policy.prune_below_height(best_block_height, pruned_height) {
+            Some(height) => height,
+            None => {
+                db_tx.rollback().await?;
+                return Ok(None);
+            }
+        };
+
+        db_tx.prune_address_balance_history(below_height).await?;
+        db_tx.commit().await?;
+        logging::log::info!("Address balance history aggregated below height {below_height}");
+
+        Ok(Some(below_height))
+    }
 }
 
 #[async_trait::async_trait]
@@ -102,6 +149,13 @@ impl<S: ApiServerStorage + Send + Sync> LocalBlockchainState for BlockchainState
 
     async fn best_block(&self) -> Result<(BlockHeight, Id<GenBlock>), Self::Error> {
         let db_tx = self.storage.transaction_ro().await.expect("Unable to connect to database");
+
+        // The checkpoint is a single record, unlike the best block that has to be looked up
+        // among all the stored blocks
+        if let Some(checkpoint) = db_tx.get_scan_checkpoint().await? {
+            return Ok((checkpoint.block_height(), checkpoint.block_id()));
+        }
+
         let best_block = db_tx.get_best_block().await.expect("Unable to get best block");
         Ok((best_block.block_height(), best_block.block_id()))
     }
@@ -117,6 +171,11 @@ impl<S: ApiServerStorage + Send + Sync> LocalBlockchainState for BlockchainState
             .await
             .expect("Unable to disconnect tables");
 
+        let mut checkpoint = match db_tx.get_main_chain_block_id(common_block_height).await? {
+            Some(block_id) => ScanCheckpoint::new(block_id.into(), common_block_height),
+            None => ScanCheckpoint::new(self.chain_config.genesis_block_id(), BlockHeight::zero()),
+        };
+
         // Connect the new blocks in the new chain
         for (index, block) in blocks.into_iter().map(WithId::new).enumerate() {
             let block_height = BlockHeight::new(common_block_height.into_int() + index as u64 + 1);
@@ -140,6 +199,7 @@ impl<S: ApiServerStorage + Send + Sync> LocalBlockchainState for BlockchainState
                 calculate_fees(&self.chain_config, &mut db_tx, &block, block_height).await?;
 
             let block_id = block.get_id();
+            checkpoint = ScanCheckpoint::new(block_id.into(), block_height);
 
             let block_with_extras = BlockWithExtraData {
                 block: WithId::take(block),
@@ -165,6 +225,23 @@ impl<S: ApiServerStorage + Send + Sync> LocalBlockchainState for BlockchainState
 
             for (tx, additinal_info) in block.transactions().iter().zip(tx_additional_infos.iter())
             {
+                // The spent UTXOs are looked up before they are updated by the transaction
+                #[cfg(feature = "address-clustering")]
+                if self.address_clustering {
+                    let addresses = address_clustering::input_addresses(
+                        &self.chain_config,
+                        &db_tx,
+                        tx.transaction(),
+                    )
+                    .await?;
+                    address_clustering::merge_address_clusters(
+                        &mut db_tx,
+                        &addresses,
+                        block_height,
+                    )
+                    .await?;
+                }
+
                 update_tables_from_transaction(
                     Arc::clone(&self.chain_config),
                     &mut db_tx,
@@ -196,6 +273,10 @@ impl<S: ApiServerStorage + Send + Sync> LocalBlockchainState for BlockchainState
             .expect("Unable to update tables from block");
         }
 
+        // The checkpoint is committed together with the data of the blocks, so the scan
+        // resumes from the last fully processed block if it's interrupted
+        db_tx.set_scan_checkpoint(checkpoint).await?;
+
         db_tx.commit().await.expect("Unable to commit transaction");
         logging::log::info!("Database commit completed successfully");
 
@@ -266,7 +347,7 @@ async fn update_locked_amounts_for_current_block<T: ApiServerStorageWrite>(
         if let Some(destination) = get_tx_output_destination(&locked_utxo.output) {
             let address = Address::<Destination>::new(chain_config, destination.clone())
                 .expect("Unable to encode destination");
-            let utxo = Utxo::new_with_info(locked_utxo, false);
+            let utxo = Utxo::new_with_info(locked_utxo, None);
             db_tx.set_utxo_at_height(outpoint, utxo, address.as_str(), block_height).await?;
         }
     }
@@ -308,6 +389,11 @@ async fn disconnect_tables_above_height<T: ApiServerStorageWrite>(
         .await
         .expect("Unable to disconnect address transactions");
 
+    db_tx
+        .del_address_clusters_above_height(block_height)
+        .await
+        .expect("Unable to disconnect address clusters");
+
     db_tx
         .del_utxo_above_height(block_height)
         .await
@@ -323,6 +409,11 @@ async fn disconnect_tables_above_height<T: ApiServerStorageWrite>(
         .await
         .expect("Unable to disconnect address transactions");
 
+    db_tx
+        .del_delegation_rewards_above_height(block_height)
+        .await
+        .expect("Unable to disconnect delegation rewards");
+
     db_tx
         .del_pools_above_height(block_height)
         .await
@@ -406,7 +497,7 @@ async fn update_tables_from_block_reward<T: ApiServerStorageWrite>(
                     None,
                     db_tx,
                     block_height,
-                    false,
+                    None,
                     &chain_config,
                 )
                 .await;
@@ -424,7 +515,7 @@ async fn update_tables_from_block_reward<T: ApiServerStorageWrite>(
                     None,
                     db_tx,
                     block_height,
-                    false,
+                    None,
                     &chain_config,
                 )
                 .await;
@@ -520,7 +611,7 @@ async fn update_tables_from_block_reward<T: ApiServerStorageWrite>(
                     token_decimals,
                     db_tx,
                     block_height,
-                    false,
+                    None,
                     &chain_config,
                 )
                 .await;
@@ -867,7 +958,7 @@ async fn update_tables_from_consensus_data<T: ApiServerStorageWrite>(
                             None,
                             db_tx,
                             block_height,
-                            true,
+                            Some(UtxoSpender::Block(block.get_id())),
                             &chain_config,
                         )
                         .await;
@@ -929,6 +1020,15 @@ async fn update_tables_from_consensus_data<T: ApiServerStorageWrite>(
                 db_tx
                     .set_delegation_at_height(*delegation_id, &updated_delegation, block_height)
                     .await?;
+
+                let reward = DelegationReward {
+                    block_id: block.get_id(),
+                    pool_id,
+                    amount: *rewards,
+                };
+                db_tx
+                    .set_delegation_reward_at_height(*delegation_id, &reward, block_height)
+                    .await?;
             }
 
             let pool_data = adapter.get_pool_data(pool_id).expect("no error").expect("must exist");
@@ -983,12 +1083,12 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
 
     for input in inputs {
         match input {
-            TxInput::AccountCommand(_, cmd) => match cmd {
+            TxInput::AccountCommand(nonce, cmd) => match cmd {
                 AccountCommand::MintTokens(token_id, amount) => {
                     let issuance =
                         db_tx.get_fungible_token_issuance(*token_id).await?.expect("must exist");
 
-                    let issuance = issuance.mint_tokens(*amount);
+                    let issuance = issuance.mint_tokens(*amount, *nonce);
                     db_tx.set_fungible_token_issuance(*token_id, block_height, issuance).await?;
                     increase_statistic_amount(
                         db_tx,
@@ -1023,7 +1123,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                     let issuance =
                         db_tx.get_fungible_token_issuance(*token_id).await?.expect("must exist");
 
-                    let issuance = issuance.unmint_tokens(total_burned);
+                    let issuance = issuance.unmint_tokens(total_burned, *nonce);
                     db_tx.set_fungible_token_issuance(*token_id, block_height, issuance).await?;
                     let amount = chain_config.token_supply_change_fee(block_height);
                     increase_statistic_amount(
@@ -1047,7 +1147,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                     let issuance =
                         db_tx.get_fungible_token_issuance(*token_id).await?.expect("must exist");
 
-                    let issuance = issuance.freeze(*is_unfreezable);
+                    let issuance = issuance.freeze(*is_unfreezable, *nonce);
                     db_tx.set_fungible_token_issuance(*token_id, block_height, issuance).await?;
                     let amount = chain_config.token_freeze_fee(block_height);
                     increase_statistic_amount(
@@ -1071,7 +1171,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                     let issuance =
                         db_tx.get_fungible_token_issuance(*token_id).await?.expect("must exist");
 
-                    let issuance = issuance.unfreeze();
+                    let issuance = issuance.unfreeze(*nonce);
                     db_tx.set_fungible_token_issuance(*token_id, block_height, issuance).await?;
                     let amount = chain_config.token_freeze_fee(block_height);
                     increase_statistic_amount(
@@ -1095,7 +1195,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                     let issuance =
                         db_tx.get_fungible_token_issuance(*token_id).await?.expect("must exist");
 
-                    let issuance = issuance.lock();
+                    let issuance = issuance.lock(*nonce);
                     db_tx.set_fungible_token_issuance(*token_id, block_height, issuance).await?;
                     let amount = chain_config.token_supply_change_fee(block_height);
                     increase_statistic_amount(
@@ -1119,7 +1219,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                     let issuance =
                         db_tx.get_fungible_token_issuance(*token_id).await?.expect("must exist");
 
-                    let issuance = issuance.change_authority(destination.clone());
+                    let issuance = issuance.change_authority(destination.clone(), *nonce);
                     db_tx.set_fungible_token_issuance(*token_id, block_height, issuance).await?;
                     let amount = chain_config.token_change_authority_fee(block_height);
                     increase_statistic_amount(
@@ -1143,7 +1243,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                     let issuance =
                         db_tx.get_fungible_token_issuance(*token_id).await?.expect("must exist");
 
-                    let issuance = issuance.change_metadata_uri(metadata_uri.clone());
+                    let issuance = issuance.change_metadata_uri(metadata_uri.clone(), *nonce);
                     db_tx.set_fungible_token_issuance(*token_id, block_height, issuance).await?;
                     let amount = chain_config.token_change_metadata_uri_fee();
                     increase_statistic_amount(
@@ -1205,7 +1305,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         utxo.utxo_with_extra_info().token_decimals,
                         db_tx,
                         block_height,
-                        true,
+                        Some(UtxoSpender::Transaction(tx.get_id())),
                         &chain_config,
                     )
                     .await;
@@ -1263,7 +1363,7 @@ async fn update_tables_from_transaction_inputs<T: ApiServerStorageWrite>(
                         utxo.utxo_with_extra_info().token_decimals,
                         db_tx,
                         block_height,
-                        true,
+                        Some(UtxoSpender::Transaction(tx.get_id())),
                         &chain_config,
                     )
                     .await;
@@ -1447,6 +1547,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                         is_locked: false,
                         frozen: IsTokenFrozen::No(issuance.is_freezable),
                         authority: issuance.authority.clone(),
+                        next_nonce: AccountNonce::new(0),
                     },
                 };
                 db_tx.set_fungible_token_issuance(token_id, block_height, issuance).await?;
@@ -1514,7 +1615,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                     None,
                     db_tx,
                     block_height,
-                    false,
+                    None,
                     &chain_config,
                 )
                 .await;
@@ -1561,7 +1662,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                     None,
                     db_tx,
                     block_height,
-                    false,
+                    None,
                     &chain_config,
                 )
                 .await;
@@ -1642,7 +1743,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
 
                 let outpoint =
                     UtxoOutPoint::new(OutPointSourceId::Transaction(transaction_id), idx as u32);
-                let utxo = Utxo::new(output.clone(), token_decimals, false);
+                let utxo = Utxo::new(output.clone(), token_decimals, None);
                 db_tx
                     .set_utxo_at_height(outpoint, utxo, address.as_str(), block_height)
                     .await
@@ -1715,7 +1816,7 @@ async fn update_tables_from_transaction_outputs<T: ApiServerStorageWrite>(
                 };
 
                 if already_unlocked {
-                    let utxo = Utxo::new(output.clone(), token_decimals, false);
+                    let utxo = Utxo::new(output.clone(), token_decimals, None);
                     db_tx
                         .set_utxo_at_height(outpoint, utxo, address.as_str(), block_height)
                         .await
@@ -1906,10 +2007,10 @@ async fn set_utxo<T: ApiServerStorageWrite>(
     token_decimals: Option<u8>,
     db_tx: &mut T,
     block_height: BlockHeight,
-    spent: bool,
+    spent_by: Option<UtxoSpender>,
     chain_config: &ChainConfig,
 ) {
-    let utxo = Utxo::new(output.clone(), token_decimals, spent);
+    let utxo = Utxo::new(output.clone(), token_decimals, spent_by);
     if let Some(destination) = get_tx_output_destination(output) {
         let address = Address::<Destination>::new(chain_config, destination.clone())
             .expect("Unable to encode destination");
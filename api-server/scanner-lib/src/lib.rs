@@ -13,5 +13,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "address-clustering")]
+pub mod address_clustering;
 pub mod blockchain_state;
+pub mod retention;
 pub mod sync;
@@ -0,0 +1,124 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    chain::ChainConfig,
+    primitives::{BlockDistance, BlockHeight},
+};
+
+/// The history is aggregated in steps of at least this many blocks,
+/// so that the storage isn't rewritten after every new block
+const MIN_PRUNING_STEP: BlockDistance = BlockDistance::new(1000);
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum RetentionPolicyError {
+    #[error("The retention window of {0} blocks is shorter than the max reorg depth of {1}")]
+    WindowShorterThanMaxReorgDepth(u64, BlockDistance),
+}
+
+/// How much of the historical index data is kept in full detail.
+///
+/// The address balance history of the last `keep_blocks` blocks is kept as is, while the older
+/// history is aggregated into checkpoints, i.e. only the balances as of the start of the retention
+/// window are kept. The window can't be shorter than the max reorg depth, so a reorg never
+/// disconnects a block whose history is already aggregated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    keep_blocks: u64,
+}
+
+impl RetentionPolicy {
+    pub fn new(chain_config: &ChainConfig, keep_blocks: u64) -> Result<Self, RetentionPolicyError> {
+        let max_reorg_depth = chain_config.max_depth_for_reorg();
+        utils::ensure!(
+            i128::from(keep_blocks) >= i128::from(max_reorg_depth.to_int()),
+            RetentionPolicyError::WindowShorterThanMaxReorgDepth(keep_blocks, max_reorg_depth)
+        );
+
+        Ok(Self { keep_blocks })
+    }
+
+    pub fn keep_blocks(&self) -> u64 {
+        self.keep_blocks
+    }
+
+    /// The height below which the history should be aggregated with the given best block,
+    /// or None if it's not worth pruning yet because the history has been aggregated
+    /// up to the `pruned_height` already
+    pub fn prune_below_height(
+        &self,
+        best_block_height: BlockHeight,
+        pruned_height: Option<BlockHeight>,
+    ) -> Option<BlockHeight> {
+        let below_height =
+            BlockHeight::new(best_block_height.into_int().checked_sub(self.keep_blocks)?);
+        let pruned_height = pruned_height.unwrap_or(BlockHeight::zero());
+
+        (below_height >= (pruned_height + MIN_PRUNING_STEP)?).then_some(below_height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::chain::config::create_unit_test_config;
+
+    #[test]
+    fn prune_below_height() {
+        let chain_config = create_unit_test_config();
+        let max_reorg_depth = chain_config.max_depth_for_reorg().to_int() as u64;
+        assert_eq!(
+            RetentionPolicy::new(&chain_config, max_reorg_depth - 1),
+            Err(RetentionPolicyError::WindowShorterThanMaxReorgDepth(
+                max_reorg_depth - 1,
+                chain_config.max_depth_for_reorg()
+            ))
+        );
+
+        let keep_blocks = max_reorg_depth + 10;
+        let policy = RetentionPolicy::new(&chain_config, keep_blocks).unwrap();
+        let step = MIN_PRUNING_STEP.to_int() as u64;
+
+        // Nothing to prune within the window
+        assert_eq!(
+            policy.prune_below_height(BlockHeight::new(keep_blocks), None),
+            None
+        );
+        // Not enough new blocks since the last pruning
+        assert_eq!(
+            policy.prune_below_height(BlockHeight::new(keep_blocks + step - 1), None),
+            None
+        );
+        assert_eq!(
+            policy.prune_below_height(BlockHeight::new(keep_blocks + step), None),
+            Some(BlockHeight::new(step))
+        );
+        assert_eq!(
+            policy.prune_below_height(
+                BlockHeight::new(keep_blocks + step * 2 - 1),
+                Some(BlockHeight::new(step))
+            ),
+            None
+        );
+        assert_eq!(
+            policy.prune_below_height(
+                BlockHeight::new(keep_blocks + step * 3),
+                Some(BlockHeight::new(step))
+            ),
+            Some(BlockHeight::new(step * 3))
+        );
+    }
+}
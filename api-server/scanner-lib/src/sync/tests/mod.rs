@@ -1217,3 +1217,95 @@ async fn check_all_destinations_are_tracked(#[case] seed: Seed) {
         assert_eq!(utxos.len(), 2);
     }
 }
+
+// A batch of blocks that fails halfway doesn't move the scan checkpoint, and the scanning
+// resumes from the last fully processed block
+#[rstest]
+#[trace]
+#[case(test_utils::random::Seed::from_entropy())]
+fn resume_interrupted_scan(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+    let mut tf = TestFramework::builder(&mut rng).build();
+    let chain_config = Arc::clone(tf.chainstate.get_chain_config());
+    let genesis_id = chain_config.genesis_block_id();
+
+    let mut local_state = runtime.block_on(async {
+        let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+
+        let mut db_tx = storage.transaction_rw().await.unwrap();
+        db_tx.reinitialize_storage(&chain_config).await.unwrap();
+        db_tx.commit().await.unwrap();
+
+        let mut local_state = BlockchainState::new(chain_config.clone(), storage);
+        local_state.scan_genesis(chain_config.genesis_block().as_ref()).await.unwrap();
+        local_state
+    });
+
+    tf.create_chain(&genesis_id, 3, &mut rng).unwrap();
+    let blocks = (1..=3)
+        .map(|height| tf.block(tf.to_chain_block_id(&tf.block_id(height))))
+        .collect::<Vec<_>>();
+    let block_1_id: Id<GenBlock> = blocks[0].get_id().into();
+    let block_3_id: Id<GenBlock> = blocks[2].get_id().into();
+
+    // The last block of the batch spends a UTXO that doesn't exist, which makes the scanner
+    // fail after the previous block has been processed
+    let bad_tx = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(
+                OutPointSourceId::Transaction(Id::new(H256::random_using(&mut rng))),
+                0,
+            ),
+            InputWitness::NoSignature(None),
+        )
+        .add_output(TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(1)),
+            Destination::AnyoneCanSpend,
+        ))
+        .build();
+    let bad_block = tf
+        .make_block_builder()
+        .with_parent(blocks[1].get_id().into())
+        .add_transaction(bad_tx)
+        .build(&mut rng);
+
+    runtime
+        .block_on(local_state.scan_blocks(BlockHeight::zero(), vec![blocks[0].clone()]))
+        .unwrap();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        runtime.block_on(
+            local_state.scan_blocks(BlockHeight::new(1), vec![blocks[1].clone(), bad_block]),
+        )
+    }));
+    assert!(result.is_err());
+
+    runtime.block_on(async {
+        assert_eq!(
+            local_state.best_block().await.unwrap(),
+            (BlockHeight::new(1), block_1_id)
+        );
+
+        let (common_block_height, _) = local_state.best_block().await.unwrap();
+        local_state
+            .scan_blocks(common_block_height, blocks[1..].to_vec())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            local_state.best_block().await.unwrap(),
+            (BlockHeight::new(3), block_3_id)
+        );
+        let db_tx = local_state.storage().transaction_ro().await.unwrap();
+        for (height, block) in (1..).zip(blocks.iter()) {
+            assert_eq!(
+                db_tx.get_main_chain_block_id(BlockHeight::new(height)).await.unwrap(),
+                Some(block.get_id())
+            );
+        }
+        let best_block = db_tx.get_best_block().await.unwrap();
+        assert_eq!(best_block.block_id(), block_3_id);
+    });
+}
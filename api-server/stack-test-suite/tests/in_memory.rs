@@ -87,7 +87,10 @@ async fn bad_request() {
     let (task, response) = spawn_webserver("/non-existent-url").await;
 
     assert_eq!(response.status(), 400);
-    assert_eq!(response.text().await.unwrap(), r#"{"error":"Bad request"}"#);
+    assert_eq!(
+        response.text().await.unwrap(),
+        r#"{"code":"BAD_REQUEST","message":"Bad request","details":null,"retryable":false,"error":"Bad request"}"#
+    );
 
     task.abort();
 }
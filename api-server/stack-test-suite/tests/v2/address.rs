@@ -16,12 +16,36 @@
 use std::sync::RwLock;
 
 use api_web_server::{api::json_helpers::amount_to_json, CachedValues};
-use common::primitives::time::get_time;
+use common::{chain::ChainConfig, primitives::time::get_time};
 
 use crate::DummyRPC;
 
 use super::*;
 
+/// The expected json of a coin output at index 0 of a transaction, spent by another transaction
+fn spent_output_json(
+    source_tx_id: Id<Transaction>,
+    amount: Amount,
+    spender_tx_id: Id<Transaction>,
+    chain_config: &ChainConfig,
+) -> serde_json::Value {
+    json!({
+        "outpoint": {
+            "source_type": "Transaction",
+            "source_id": source_tx_id.to_hash().encode_hex::<String>(),
+            "index": 0,
+        },
+        "value": {
+            "type": "Coin",
+            "amount": amount_to_json(amount, chain_config.coin_decimals()),
+        },
+        "spent_by": {
+            "spender_type": "Transaction",
+            "spender_id": spender_tx_id.to_hash().encode_hex::<String>(),
+        },
+    })
+}
+
 #[tokio::test]
 async fn invalid_address() {
     let (task, response) = spawn_webserver("/api/v2/address/invalid-address").await;
@@ -226,7 +250,16 @@ async fn multiple_outputs_to_single_address(#[case] seed: Seed) {
                         json!({
                         "coin_balance": amount_to_json(alice_balance, chain_config.coin_decimals()),
                         "locked_coin_balance": amount_to_json(Amount::ZERO, chain_config.coin_decimals()),
+                        "first_seen_block_height": BlockHeight::new(1),
+                        "last_activity_block_height": BlockHeight::new(2),
                         "transaction_history": alice_transaction_history,
+                        // The initial output of Alice is spent by the second transaction
+                        "outgoing": [spent_output_json(
+                            previous_transaction_id,
+                            Amount::from_atoms(1_000_000),
+                            transaction.transaction().get_id(),
+                            &chain_config,
+                        )],
                                 }),
                     ),
                     (
@@ -234,7 +267,10 @@ async fn multiple_outputs_to_single_address(#[case] seed: Seed) {
                         json!({
                         "coin_balance": amount_to_json(bob_balance, chain_config.coin_decimals()),
                         "locked_coin_balance": amount_to_json(bob_locked_balance, chain_config.coin_decimals()),
+                        "first_seen_block_height": BlockHeight::new(2),
+                        "last_activity_block_height": BlockHeight::new(2),
                         "transaction_history": bob_transaction_history,
+                        "outgoing": [],
                                 }),
                     ),
                 ]);
@@ -475,7 +511,16 @@ async fn test_unlocking_for_locked_utxos(#[case] seed: Seed) {
                         json!({
                         "coin_balance": amount_to_json(alice_balance, chain_config.coin_decimals()),
                         "locked_coin_balance": amount_to_json(Amount::ZERO, chain_config.coin_decimals()),
+                        "first_seen_block_height": BlockHeight::new(1),
+                        "last_activity_block_height": BlockHeight::new(2),
                         "transaction_history": alice_transaction_history,
+                        // The initial output of Alice is spent by the second transaction
+                        "outgoing": [spent_output_json(
+                            previous_transaction_id,
+                            Amount::from_atoms(1_000_000),
+                            transaction.transaction().get_id(),
+                            &chain_config,
+                        )],
                                 }),
                     ),
                     (
@@ -483,7 +528,10 @@ async fn test_unlocking_for_locked_utxos(#[case] seed: Seed) {
                         json!({
                         "coin_balance": amount_to_json((bob_balance + bob_locked_balance).unwrap(), chain_config.coin_decimals()),
                         "locked_coin_balance": amount_to_json(Amount::ZERO, chain_config.coin_decimals()),
+                        "first_seen_block_height": BlockHeight::new(2),
+                        "last_activity_block_height": BlockHeight::new(2),
                         "transaction_history": bob_transaction_history,
+                        "outgoing": [],
                                 }),
                     ),
                 ]);
@@ -629,8 +677,12 @@ async fn ok(#[case] seed: Seed) {
                     .unwrap()
                     .block_id()];
 
+                // The spent outputs of Alice, in the order they are spent
+                let mut alice_outgoing = Vec::new();
+
                 for _ in 0..rng.gen_range(1..100) {
                     let random_coin_amount = rng.gen_range(1..10);
+                    let previous_alice_balance = alice_balance;
 
                     alice_balance =
                         (alice_balance - Amount::from_atoms(random_coin_amount)).unwrap();
@@ -661,6 +713,12 @@ async fn ok(#[case] seed: Seed) {
 
                     alice_transaction_history.push(transaction.transaction().get_id());
                     bob_transaction_history.push(transaction.transaction().get_id());
+                    alice_outgoing.push(spent_output_json(
+                        previous_transaction_id,
+                        previous_alice_balance,
+                        transaction.transaction().get_id(),
+                        &chain_config,
+                    ));
                     previous_transaction_id = transaction.transaction().get_id();
 
                     previous_witness = InputWitness::Standard(
@@ -694,13 +752,19 @@ async fn ok(#[case] seed: Seed) {
                     previous_tx_out = alice_tx_out;
                 }
 
+                let last_block_height = BlockHeight::new(chainstate_block_ids.len() as u64);
+
                 _ = tx.send([
                     (
                         alice_address.as_str().to_string(),
                         json!({
                         "coin_balance": amount_to_json(alice_balance, chain_config.coin_decimals()),
                         "locked_coin_balance": amount_to_json(Amount::ZERO, chain_config.coin_decimals()),
+                        "first_seen_block_height": BlockHeight::new(1),
+                        "last_activity_block_height": last_block_height,
                         "transaction_history": alice_transaction_history,
+                        // The first page of the most recently spent outputs
+                        "outgoing": alice_outgoing.into_iter().rev().take(10).collect::<Vec<_>>(),
                                 }),
                     ),
                     (
@@ -708,7 +772,10 @@ async fn ok(#[case] seed: Seed) {
                         json!({
                         "coin_balance": amount_to_json(bob_balance, chain_config.coin_decimals()),
                         "locked_coin_balance": amount_to_json(Amount::ZERO, chain_config.coin_decimals()),
+                        "first_seen_block_height": BlockHeight::new(2),
+                        "last_activity_block_height": last_block_height,
                         "transaction_history": bob_transaction_history,
+                        "outgoing": [],
                                 }),
                     ),
                 ]);
@@ -0,0 +1,215 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::RwLock;
+
+use api_server_common::storage::storage_api::ApiServerStorageRead;
+use api_web_server::{api::json_helpers::amount_to_json, CachedValues};
+use common::primitives::{time::get_time, CoinOrTokenId};
+
+use crate::DummyRPC;
+
+use super::*;
+
+#[tokio::test]
+async fn invalid_address() {
+    let (task, response) = spawn_webserver("/api/v2/address/invalid-address/balance/0").await;
+
+    assert_eq!(response.status(), 400);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["error"].as_str().unwrap(), "Invalid address");
+
+    task.abort();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn invalid_height(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = create_unit_test_config();
+
+    let (_, public_key) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+    let destination = Destination::PublicKeyHash(PublicKeyHash::from(&public_key));
+    let address = Address::<Destination>::new(&chain_config, destination).unwrap();
+
+    let (task, response) = spawn_webserver(&format!(
+        "/api/v2/address/{}/balance/invalid-height",
+        address.as_str()
+    ))
+    .await;
+
+    assert_eq!(response.status(), 400);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["error"].as_str().unwrap(), "Invalid block height");
+
+    task.abort();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn balance_with_pruned_history(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let n_blocks = rng.gen_range(20..50);
+    let pruned_height = BlockHeight::new(rng.gen_range(1..n_blocks as u64 - 10));
+    let height_in_window = rng.gen_range(pruned_height.into_int()..=n_blocks as u64);
+    let height_below_window = rng.gen_range(0..pruned_height.into_int());
+    let chain_config = create_unit_test_config();
+
+    let (_, public_key) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+    let destination = Destination::PublicKeyHash(PublicKeyHash::from(&public_key));
+    let address = Address::<Destination>::new(&chain_config, destination).unwrap();
+
+    // A balance change at every height up to the tip
+    let balances = (0..=n_blocks)
+        .map(|_| Amount::from_atoms(rng.gen_range(1..1_000_000)))
+        .collect::<Vec<_>>();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let task = tokio::spawn({
+        let address = address.to_string();
+        let balances = balances.clone();
+        let chain_config = chain_config.clone();
+
+        async move {
+            let web_server_state = {
+                let chainstate_blocks = {
+                    let mut tf = TestFramework::builder(&mut rng)
+                        .with_chain_config(chain_config.clone())
+                        .build();
+
+                    let chainstate_block_ids = tf
+                        .create_chain_return_ids(&tf.genesis().get_id().into(), n_blocks, &mut rng)
+                        .unwrap();
+
+                    chainstate_block_ids
+                        .iter()
+                        .map(|id| tf.block(tf.to_chain_block_id(id)))
+                        .collect::<Vec<_>>()
+                };
+
+                let storage = {
+                    let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+
+                    let mut db_tx = storage.transaction_rw().await.unwrap();
+                    db_tx.reinitialize_storage(&chain_config).await.unwrap();
+                    db_tx.commit().await.unwrap();
+
+                    storage
+                };
+
+                let chain_config = Arc::new(chain_config);
+                let mut local_node = BlockchainState::new(Arc::clone(&chain_config), storage);
+                local_node.scan_genesis(chain_config.genesis_block()).await.unwrap();
+                local_node.scan_blocks(BlockHeight::new(0), chainstate_blocks).await.unwrap();
+
+                let mut storage = local_node.storage().clone_storage().await;
+                let mut db_tx = storage.transaction_rw().await.unwrap();
+                for (height, balance) in balances.iter().enumerate() {
+                    db_tx
+                        .set_address_balance_at_height(
+                            &address,
+                            *balance,
+                            CoinOrTokenId::Coin,
+                            BlockHeight::new(height as u64),
+                        )
+                        .await
+                        .unwrap();
+                }
+                db_tx.prune_address_balance_history(pruned_height).await.unwrap();
+                assert_eq!(
+                    db_tx.get_pruned_height().await.unwrap(),
+                    Some(pruned_height)
+                );
+                db_tx.commit().await.unwrap();
+
+                ApiServerWebServerState {
+                    db: Arc::new(storage),
+                    chain_config: Arc::clone(&chain_config),
+                    rpc: Arc::new(DummyRPC {}),
+                    cached_values: Arc::new(CachedValues {
+                        feerate_points: RwLock::new((get_time(), vec![])),
+                    }),
+                    time_getter: Default::default(),
+                }
+            };
+
+            web_server(listener, web_server_state, true).await
+        }
+    });
+
+    let get_balance = |height: u64| {
+        let url = format!(
+            "http://{}:{}/api/v2/address/{}/balance/{height}",
+            addr.ip(),
+            addr.port(),
+            address.as_str()
+        );
+        async move {
+            let response = reqwest::get(url).await.unwrap();
+            let status = response.status();
+            let body = response.text().await.unwrap();
+            (
+                status,
+                serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+            )
+        }
+    };
+
+    // Within the retention window
+    for height in [pruned_height.into_int(), height_in_window] {
+        let (status, body) = get_balance(height).await;
+        assert_eq!(status, 200);
+        assert_eq!(body["block_height"], json!(height));
+        assert_eq!(
+            body["coin_balance"],
+            amount_to_json(balances[height as usize], chain_config.coin_decimals())
+        );
+    }
+
+    // Below the retention window
+    let (status, body) = get_balance(height_below_window).await;
+    assert_eq!(status, 400);
+    assert_eq!(
+        body["error"].as_str().unwrap(),
+        format!("The history before block height {pruned_height} has been pruned")
+    );
+    assert_eq!(body["code"].as_str().unwrap(), "HISTORY_PRUNED");
+    assert_eq!(
+        body["details"]["pruned_before_height"],
+        json!(pruned_height)
+    );
+
+    // Past the tip
+    let (status, body) = get_balance(n_blocks as u64 + 1).await;
+    assert_eq!(status, 404);
+    assert_eq!(
+        body["error"].as_str().unwrap(),
+        "No block found at supplied height"
+    );
+
+    task.abort();
+}
@@ -0,0 +1,145 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+async fn spawn_and_post(body: String) -> (tokio::task::JoinHandle<()>, reqwest::Response) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let url = "/api/v2/batch";
+
+    let task = tokio::spawn(async move {
+        let web_server_state = {
+            let chain_config = Arc::new(create_unit_test_config());
+            let storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+
+            ApiServerWebServerState {
+                db: Arc::new(storage),
+                chain_config: Arc::clone(&chain_config),
+                rpc: Arc::new(DummyRPC {}),
+                cached_values: Arc::new(CachedValues {
+                    feerate_points: RwLock::new((get_time(), vec![])),
+                }),
+                time_getter: Default::default(),
+            }
+        };
+
+        web_server(listener, web_server_state, false).await.unwrap();
+    });
+
+    // Given that the listener port is open, this will block until a
+    // response is made (by the web server, which takes the listener
+    // over)
+    let response = reqwest::Client::new()
+        .post(format!("http://{}:{}{url}", addr.ip(), addr.port()))
+        .body(body)
+        .send()
+        .await
+        .unwrap();
+
+    (task, response)
+}
+
+#[tokio::test]
+async fn invalid_batch() {
+    let (task, response) = spawn_and_post("not a batch".to_owned()).await;
+
+    assert_eq!(response.status(), 400);
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["error"].as_str().unwrap(), "Invalid batch request");
+
+    task.abort();
+
+    let body = json!([{ "path": "/chain/tip?x=1" }]);
+    let (task, response) = spawn_and_post(body.to_string()).await;
+
+    assert_eq!(response.status(), 400);
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["error"].as_str().unwrap(), "Invalid batch request");
+
+    task.abort();
+
+    let body = serde_json::Value::Array(vec![json!({ "path": "/chain/tip" }); 21]);
+    let (task, response) = spawn_and_post(body.to_string()).await;
+
+    assert_eq!(response.status(), 400);
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        body["error"].as_str().unwrap(),
+        "Too many queries in the batch"
+    );
+
+    task.abort();
+}
+
+#[tokio::test]
+async fn ok() {
+    let chain_config = create_unit_test_config();
+    let genesis_id = chain_config.genesis_block().get_id();
+
+    let body = json!([
+        { "path": "/chain/tip" },
+        { "path": "/block/invalid-block-id" },
+        { "path": "/transaction", "params": { "items": "a lot" } },
+        { "path": "/no-such-route" },
+        { "path": "/export/blocks" },
+        { "path": "/richlist" },
+        { "path": "/token" },
+        { "path": "/nft/invalid-nft-id" },
+    ]);
+    let (task, response) = spawn_and_post(body.to_string()).await;
+
+    assert_eq!(response.status(), 200);
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    let client_error = |code: &str, message: &str| {
+        json!({
+            "code": code,
+            "message": message,
+            "details": null,
+            "retryable": false,
+            "error": message,
+        })
+    };
+
+    assert_eq!(
+        body,
+        json!([
+            {
+                "status": 200,
+                "body": {
+                    "block_height": 0,
+                    "block_id": genesis_id.to_hash().encode_hex::<String>(),
+                },
+            },
+            { "status": 400, "body": client_error("INVALID_BLOCK_ID", "Invalid block Id") },
+            {
+                "status": 400,
+                "body": client_error("INVALID_NUM_ITEMS", "Invalid number of items"),
+            },
+            { "status": 404, "body": null },
+            { "status": 404, "body": null },
+            { "status": 404, "body": null },
+            { "status": 200, "body": [] },
+            { "status": 400, "body": client_error("INVALID_NFT_ID", "Invalid NFT Id") },
+        ])
+    );
+
+    task.abort();
+}
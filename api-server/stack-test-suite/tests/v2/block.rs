@@ -40,6 +40,10 @@ async fn invalid_block_id() {
     let body: serde_json::Value = serde_json::from_str(&body).unwrap();
 
     assert_eq!(body["error"].as_str().unwrap(), "Invalid block Id");
+    assert_eq!(body["message"].as_str().unwrap(), "Invalid block Id");
+    assert_eq!(body["code"].as_str().unwrap(), "INVALID_BLOCK_ID");
+    assert_eq!(body["details"], serde_json::Value::Null);
+    assert!(!body["retryable"].as_bool().unwrap());
 
     task.abort();
 }
@@ -57,6 +61,8 @@ async fn block_not_found() {
     let body: serde_json::Value = serde_json::from_str(&body).unwrap();
 
     assert_eq!(body["error"].as_str().unwrap(), "Block not found");
+    assert_eq!(body["code"].as_str().unwrap(), "BLOCK_NOT_FOUND");
+    assert!(!body["retryable"].as_bool().unwrap());
 
     task.abort();
 }
@@ -160,6 +166,9 @@ async fn ok(#[case] seed: Seed) {
                                         .map(|(tx, additinal_data)| tx_to_json(tx.transaction(), additinal_data, tf.chain_config()))
                                         .collect::<Vec<_>>(),
                 },
+                // The block is not in the main chain anymore
+                "confirmations": "",
+                "is_final": false,
             });
 
             // create a reorg
@@ -212,6 +221,8 @@ async fn ok(#[case] seed: Seed) {
                                         .map(|(tx, additinal_data)| tx_to_json(tx.transaction(), additinal_data, tf.chain_config()))
                                         .collect::<Vec<_>>(),
                 },
+                "confirmations": (count - block_height).to_string(),
+                "is_final": (count - block_height) as i64 >= tf.chain_config().max_depth_for_reorg().to_int(),
             });
 
             _ = tx.send((
@@ -155,10 +155,60 @@ async fn ok(#[case] seed: Seed) {
 
     assert_eq!(response.status(), 200);
 
+    let etag = response.headers().get("etag").unwrap().clone();
+    assert!(response
+        .headers()
+        .get("cache-control")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .contains("immutable"));
+
     let body = response.text().await.unwrap();
     let body: serde_json::Value = serde_json::from_str(&body).unwrap();
 
     assert_eq!(body, expected_header);
 
+    // The same ETag is returned for the same resource, and the body isn't re-sent if the client
+    // already has it
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}:{}{url}", addr.ip(), addr.port()))
+        .header("if-none-match", etag.clone())
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 304);
+    assert_eq!(response.headers().get("etag").unwrap(), &etag);
+    assert!(response.text().await.unwrap().is_empty());
+
+    // Other resources of the same block have a different ETag
+    let response = client
+        .get(format!(
+            "http://{}:{}/api/v2/block/{block_id}/reward",
+            addr.ip(),
+            addr.port()
+        ))
+        .header("if-none-match", etag.clone())
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_ne!(response.headers().get("etag").unwrap(), &etag);
+
+    // The response is compressed if the client accepts it
+    let response = client
+        .get(format!("http://{}:{}{url}", addr.ip(), addr.port()))
+        .header("accept-encoding", "gzip")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("content-encoding").unwrap(), "gzip");
+    assert_eq!(response.headers().get("etag").unwrap(), &etag);
+
     task.abort();
 }
@@ -0,0 +1,235 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+#[tokio::test]
+async fn invalid_block_cursor() {
+    let (task, response) = spawn_webserver("/api/v2/export/blocks?cursor=asd").await;
+
+    assert_eq!(response.status(), 400);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["error"].as_str().unwrap(), "Invalid cursor");
+
+    task.abort();
+}
+
+#[tokio::test]
+async fn invalid_transaction_cursor() {
+    let (task, response) = spawn_webserver("/api/v2/export/transactions?cursor=12").await;
+
+    assert_eq!(response.status(), 400);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["error"].as_str().unwrap(), "Invalid cursor");
+
+    task.abort();
+}
+
+#[tokio::test]
+async fn stale_cursor() {
+    // The block id doesn't match the genesis
+    let cursor = format!("0:{}", "ab".repeat(32));
+    let (task, response) = spawn_webserver(&format!("/api/v2/export/blocks?cursor={cursor}")).await;
+
+    assert_eq!(response.status(), 400);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["code"].as_str().unwrap(), "STALE_CURSOR");
+
+    task.abort();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn invalid_num_items_max(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let more_than_max = rng.gen_range(1001..10000);
+    let (task, response) =
+        spawn_webserver(&format!("/api/v2/export/blocks?items={more_than_max}")).await;
+
+    assert_eq!(response.status(), 400);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["error"].as_str().unwrap(), "Invalid number of items");
+
+    task.abort();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn ok(#[case] seed: Seed) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    let task = tokio::spawn(async move {
+        let web_server_state = {
+            let mut rng = make_seedable_rng(seed);
+            let n_blocks = rng.gen_range(1..50);
+
+            let chain_config = create_unit_test_config();
+
+            let chainstate_blocks = {
+                let mut tf = TestFramework::builder(&mut rng)
+                    .with_chain_config(chain_config.clone())
+                    .build();
+
+                let chainstate_block_ids = tf
+                    .create_chain_return_ids(&tf.genesis().get_id().into(), n_blocks, &mut rng)
+                    .unwrap();
+
+                let blocks = chainstate_block_ids
+                    .iter()
+                    .map(|id| tf.block(tf.to_chain_block_id(id)))
+                    .collect::<Vec<_>>();
+
+                _ = tx.send(blocks.clone());
+
+                blocks
+            };
+
+            let storage = {
+                let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+
+                let mut db_tx = storage.transaction_rw().await.unwrap();
+                db_tx.reinitialize_storage(&chain_config).await.unwrap();
+                db_tx.commit().await.unwrap();
+
+                storage
+            };
+
+            let chain_config = Arc::new(chain_config);
+            let mut local_node = BlockchainState::new(Arc::clone(&chain_config), storage);
+            local_node.scan_genesis(chain_config.genesis_block()).await.unwrap();
+            local_node.scan_blocks(BlockHeight::new(0), chainstate_blocks).await.unwrap();
+
+            ApiServerWebServerState {
+                db: Arc::new(local_node.storage().clone_storage().await),
+                chain_config: Arc::clone(&chain_config),
+                rpc: Arc::new(DummyRPC {}),
+                cached_values: Arc::new(CachedValues {
+                    feerate_points: RwLock::new((get_time(), vec![])),
+                }),
+                time_getter: Default::default(),
+            }
+        };
+
+        web_server(listener, web_server_state, true).await
+    });
+
+    let expected_blocks = rx.await.unwrap();
+
+    let export = |url: String| async move {
+        let response = reqwest::get(format!("http://{}:{}{url}", addr.ip(), addr.port()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+
+        let mut lines = response
+            .text()
+            .await
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        // The last line contains the cursor to continue from
+        let next_cursor = lines.pop().unwrap()["next_cursor"].as_str().unwrap().to_owned();
+        (lines, next_cursor)
+    };
+    let cursor_param = |cursor: &Option<String>| {
+        cursor.as_ref().map_or(String::new(), |cursor| format!("&cursor={cursor}"))
+    };
+
+    // Export all the blocks, two at a time
+    let mut exported_blocks = Vec::new();
+    let mut cursor = None;
+    loop {
+        let (lines, next_cursor) = export(format!(
+            "/api/v2/export/blocks?items=2{}",
+            cursor_param(&cursor)
+        ))
+        .await;
+        if lines.is_empty() {
+            assert_eq!(Some(next_cursor), cursor);
+            break;
+        }
+        exported_blocks.extend(lines);
+        cursor = Some(next_cursor);
+    }
+
+    assert_eq!(exported_blocks.len(), expected_blocks.len());
+    for (idx, (exported, expected)) in exported_blocks.iter().zip(&expected_blocks).enumerate() {
+        assert_eq!(exported["height"], json!(idx + 1));
+        assert_eq!(exported["block_id"], json!(expected.get_id()));
+    }
+
+    // The block export has stopped at the tip
+    let tip_cursor = cursor.unwrap();
+    let expected_tip = expected_blocks.last().unwrap();
+    assert_eq!(
+        tip_cursor,
+        format!(
+            "{}:{}",
+            expected_blocks.len(),
+            expected_tip.get_id().to_hash().encode_hex::<String>()
+        )
+    );
+
+    // Export all the transactions, one at a time
+    let mut exported_txs = Vec::new();
+    let mut cursor = None;
+    loop {
+        let (lines, next_cursor) = export(format!(
+            "/api/v2/export/transactions?items=1{}",
+            cursor_param(&cursor)
+        ))
+        .await;
+        if Some(&next_cursor) == cursor.as_ref() {
+            assert!(lines.is_empty());
+            break;
+        }
+        exported_txs.extend(lines);
+        cursor = Some(next_cursor);
+    }
+
+    let expected_txs = expected_blocks
+        .iter()
+        .flat_map(|block| block.transactions().iter().map(|tx| tx.transaction().get_id()))
+        .collect::<Vec<_>>();
+    assert_eq!(exported_txs.len(), expected_txs.len());
+    for (exported, expected) in exported_txs.iter().zip(&expected_txs) {
+        assert_eq!(
+            exported["id"].as_str().unwrap(),
+            expected.to_hash().encode_hex::<String>()
+        );
+    }
+
+    task.abort();
+}
@@ -15,20 +15,24 @@
 
 mod address;
 mod address_all_utxos;
+mod address_balance;
 mod address_delegations;
 mod address_spendable_utxos;
+mod batch;
 mod block;
 mod block_header;
 mod block_reward;
 mod block_transaction_ids;
 mod chain_at_height;
 mod chain_tip;
+mod export;
 mod feerate;
 mod helpers;
 mod nft;
 mod pool;
 mod pool_block_stats;
 mod pools;
+mod richlist;
 mod statistics;
 mod token;
 mod token_ids;
@@ -49,7 +53,7 @@ use api_server_common::storage::{
 use api_web_server::{
     api::{
         json_helpers::{txoutput_to_json, TokenDecimals},
-        web_server,
+        web_server, web_server_with_finality_depth,
     },
     ApiServerWebServerState, CachedValues,
 };
@@ -38,6 +38,52 @@ async fn invalid_pool_id() {
     task.abort();
 }
 
+#[tokio::test]
+async fn invalid_delegations_sort_order() {
+    let pool_id = PoolId::new(H256::zero());
+    let chain_config = create_unit_test_config();
+    let pool_id = Address::new(&chain_config, pool_id).unwrap();
+    let (task, response) =
+        spawn_webserver(&format!("/api/v2/pool/{pool_id}/delegations?sort=invalid")).await;
+
+    assert_eq!(response.status(), 400);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(
+        body["error"].as_str().unwrap(),
+        "Invalid delegations sort order"
+    );
+
+    task.abort();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn invalid_delegations_num_items_max(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let more_than_max = rng.gen_range(101..10000);
+    let pool_id = PoolId::new(H256::zero());
+    let chain_config = create_unit_test_config();
+    let pool_id = Address::new(&chain_config, pool_id).unwrap();
+    let (task, response) = spawn_webserver(&format!(
+        "/api/v2/pool/{pool_id}/delegations?items={more_than_max}"
+    ))
+    .await;
+
+    assert_eq!(response.status(), 400);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["error"].as_str().unwrap(), "Invalid number of items");
+
+    task.abort();
+}
+
 #[tokio::test]
 async fn pool_id_not_fund() {
     let pool_id = PoolId::new(H256::zero());
@@ -272,6 +318,98 @@ async fn ok(#[case] seed: Seed) {
             assert_eq!(resp.get("spend_destination").unwrap(), destination.as_str());
         }
 
+        let url = format!("/api/v2/pool/{pool_id}/delegations?sort=by_balance");
+        let response = reqwest::get(format!("http://{}:{}{url}", addr.ip(), addr.port()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+
+        let body = response.text().await.unwrap();
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let body = body.as_array().unwrap();
+
+        let mut expected_balances = delegations.iter().map(|d| d.1).collect::<Vec<_>>();
+        expected_balances.sort_by(|a, b| b.cmp(a));
+        let balances = body.iter().map(|d| d.get("balance").unwrap().clone()).collect::<Vec<_>>();
+        assert_eq!(
+            balances,
+            expected_balances
+                .iter()
+                .map(|b| serde_json::json!(amount_to_json(*b, chain_config.coin_decimals())))
+                .collect::<Vec<_>>()
+        );
+
+        let url = format!("/api/v2/pool/{pool_id}/delegations?sort=by_balance&offset=1&items=1");
+        let response = reqwest::get(format!("http://{}:{}{url}", addr.ip(), addr.port()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+
+        let body = response.text().await.unwrap();
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let body = body.as_array().unwrap();
+        assert_eq!(body.len(), delegations.len().saturating_sub(1).min(1));
+        if let Some(delegation) = body.first() {
+            assert_eq!(delegation.get("balance").unwrap(), &balances[1]);
+        }
+
+        let url = format!("/api/v2/pool/{pool_id}/summary");
+        let response = reqwest::get(format!("http://{}:{}{url}", addr.ip(), addr.port()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+
+        let body = response.text().await.unwrap();
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let body = body.as_object().unwrap();
+
+        assert_eq!(body.get("pool_id").unwrap(), pool_id.as_str());
+        assert_eq!(
+            body.get("staker_balance").unwrap(),
+            &serde_json::json!(amount_to_json(
+                pool_data.pledge(),
+                chain_config.coin_decimals()
+            ))
+        );
+
+        let total_delegated =
+            expected_balances.iter().try_fold(Amount::ZERO, |total, b| total + *b).unwrap();
+        let total_balance = (pool_data.pledge() + total_delegated).unwrap();
+        let effective_balance = consensus::calculate_effective_pool_balance(
+            pool_data.pledge(),
+            total_balance,
+            chain_config.final_supply().unwrap().to_amount_atoms(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            body.get("total_balance").unwrap(),
+            &serde_json::json!(amount_to_json(total_balance, chain_config.coin_decimals()))
+        );
+        assert_eq!(
+            body.get("effective_balance").unwrap(),
+            &serde_json::json!(amount_to_json(
+                effective_balance,
+                chain_config.coin_decimals()
+            ))
+        );
+
+        let stats = body.get("delegations").unwrap();
+        assert_eq!(
+            stats.get("delegation_count").unwrap(),
+            &serde_json::json!(expected_balances.len())
+        );
+        assert_eq!(
+            stats.get("total_delegated").unwrap(),
+            &serde_json::json!(amount_to_json(
+                total_delegated,
+                chain_config.coin_decimals()
+            ))
+        );
+
         for (delegation_id, balance, destination, _) in delegations {
             let delegation_id = Address::new(&chain_config, delegation_id).unwrap();
             let url = format!("/api/v2/delegation/{delegation_id}");
@@ -300,3 +438,156 @@ async fn ok(#[case] seed: Seed) {
 
     task.abort();
 }
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn many_delegations(#[case] seed: Seed) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    let task = tokio::spawn(async move {
+        let web_server_state = {
+            let mut rng = make_seedable_rng(seed);
+
+            let chain_config = create_unit_test_config();
+
+            let chainstate_blocks = {
+                let mut tf = TestFramework::builder(&mut rng)
+                    .with_chain_config(chain_config.clone())
+                    .build();
+
+                let stake_pool_outpoint = UtxoOutPoint::new(
+                    OutPointSourceId::BlockReward(tf.genesis().get_id().into()),
+                    0,
+                );
+                let mut available_amount = ((chain_config.min_stake_pool_pledge() * 10).unwrap()
+                    + Amount::from_atoms(10000))
+                .unwrap();
+
+                let (mut transfer_outpoint, _, pool_id, block) = prepare_stake_pool(
+                    stake_pool_outpoint,
+                    &mut rng,
+                    &mut available_amount,
+                    &mut tf,
+                );
+                let mut blocks = vec![block];
+
+                // More than any default page size, so that nothing is silently cut off
+                let mut delegation_ids = vec![];
+                for _ in 0..rng.gen_range(11..30) {
+                    let (delegation_id, _, outpoint, block) = prepare_delegation(
+                        transfer_outpoint,
+                        &mut rng,
+                        pool_id,
+                        available_amount,
+                        None,
+                        &mut tf,
+                    );
+                    let (amount, outpoint, block2) = stake_delegation(
+                        &mut rng,
+                        available_amount,
+                        outpoint,
+                        delegation_id,
+                        &mut tf,
+                    );
+                    available_amount = (available_amount - amount).unwrap();
+                    transfer_outpoint = outpoint;
+
+                    delegation_ids.push(delegation_id);
+                    blocks.extend([block, block2]);
+                }
+
+                _ = tx.send((pool_id, delegation_ids));
+
+                blocks
+            };
+
+            let storage = {
+                let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+
+                let mut db_tx = storage.transaction_rw().await.unwrap();
+                db_tx.reinitialize_storage(&chain_config).await.unwrap();
+                db_tx.commit().await.unwrap();
+
+                storage
+            };
+
+            let chain_config = Arc::new(chain_config);
+            let mut local_node = BlockchainState::new(Arc::clone(&chain_config), storage);
+            local_node.scan_genesis(chain_config.genesis_block()).await.unwrap();
+            local_node.scan_blocks(BlockHeight::new(0), chainstate_blocks).await.unwrap();
+
+            ApiServerWebServerState {
+                db: Arc::new(local_node.storage().clone_storage().await),
+                chain_config: Arc::clone(&chain_config),
+                rpc: Arc::new(DummyRPC {}),
+                cached_values: Arc::new(CachedValues {
+                    feerate_points: RwLock::new((get_time(), vec![])),
+                }),
+                time_getter: Default::default(),
+            }
+        };
+
+        web_server(listener, web_server_state, true).await
+    });
+
+    let chain_config = create_unit_test_config();
+    let (pool_id, mut delegation_ids) = rx.await.unwrap();
+    delegation_ids.sort();
+    let expected_ids = delegation_ids
+        .iter()
+        .map(|id| serde_json::json!(Address::new(&chain_config, *id).unwrap().as_str()))
+        .collect::<Vec<_>>();
+    let pool_id = Address::new(&chain_config, pool_id).unwrap();
+
+    let get_ids = |url: String| async move {
+        let response = reqwest::get(format!("http://{}:{}{url}", addr.ip(), addr.port()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+
+        let body = response.text().await.unwrap();
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        body.as_array()
+            .unwrap()
+            .iter()
+            .map(|d| d.get("delegation_id").unwrap().clone())
+            .collect::<Vec<_>>()
+    };
+
+    // Without the number of items all the delegations are returned
+    let ids = get_ids(format!("/api/v2/pool/{pool_id}/delegations")).await;
+    assert_eq!(ids, expected_ids);
+
+    // The pages cover all the delegations in order
+    let items = 4;
+    let mut paged_ids = vec![];
+    for offset in (0..expected_ids.len()).step_by(items) {
+        let ids = get_ids(format!(
+            "/api/v2/pool/{pool_id}/delegations?offset={offset}&items={items}"
+        ))
+        .await;
+        assert!(ids.len() <= items);
+        paged_ids.extend(ids);
+    }
+    assert_eq!(paged_ids, expected_ids);
+
+    let url = format!("/api/v2/pool/{pool_id}/summary");
+    let response = reqwest::get(format!("http://{}:{}{url}", addr.ip(), addr.port()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        body["delegations"]["delegation_count"],
+        serde_json::json!(expected_ids.len())
+    );
+
+    task.abort();
+}
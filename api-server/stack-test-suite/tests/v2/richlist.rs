@@ -0,0 +1,209 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api_web_server::api::json_helpers::amount_to_json;
+use common::{chain::tokens::TokenId, primitives::H256};
+
+use super::*;
+
+#[tokio::test]
+async fn invalid_top() {
+    let (task, response) = spawn_webserver("/api/v2/richlist?top=invalid").await;
+
+    assert_eq!(response.status(), 400);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["error"].as_str().unwrap(), "Invalid number of items");
+
+    task.abort();
+}
+
+#[tokio::test]
+async fn invalid_token_id() {
+    let (task, response) = spawn_webserver("/api/v2/token/invalid-token-id/holders").await;
+
+    assert_eq!(response.status(), 400);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["error"].as_str().unwrap(), "Invalid token Id");
+
+    task.abort();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn token_not_found(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = create_unit_test_config();
+
+    let token_id = TokenId::new(H256::random_using(&mut rng));
+    let token_id = Address::<TokenId>::new(&chain_config, token_id).unwrap();
+
+    let (task, response) =
+        spawn_webserver(&format!("/api/v2/token/{}/holders", token_id.as_str())).await;
+
+    assert_eq!(response.status(), 404);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["error"].as_str().unwrap(), "Token not found");
+
+    task.abort();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn ok(#[case] seed: Seed) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    let task = tokio::spawn(async move {
+        let web_server_state = {
+            let mut rng = make_seedable_rng(seed);
+            let chain_config = create_unit_test_config();
+
+            let chainstate_blocks = {
+                let mut tf = TestFramework::builder(&mut rng)
+                    .with_chain_config(chain_config.clone())
+                    .build();
+
+                let genesis_amount = match &chain_config.genesis_block().utxos()[0] {
+                    TxOutput::Transfer(OutputValue::Coin(amount), _) => *amount,
+                    _ => panic!("unexpected genesis output"),
+                };
+
+                // Split the genesis output between random addresses and keep the rest
+                let mut remaining = genesis_amount;
+                let mut holders = Vec::new();
+                let mut tx_builder = TransactionBuilder::new().add_input(
+                    TxInput::from_utxo(
+                        OutPointSourceId::BlockReward(tf.genesis().get_id().into()),
+                        0,
+                    ),
+                    InputWitness::NoSignature(None),
+                );
+                for _ in 0..rng.gen_range(1..10) {
+                    let (_, pk) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+                    let destination = Destination::PublicKeyHash(PublicKeyHash::from(&pk));
+                    let amount = Amount::from_atoms(rng.gen_range(1..1_000_000));
+                    remaining = (remaining - amount).unwrap();
+                    tx_builder = tx_builder.add_output(TxOutput::Transfer(
+                        OutputValue::Coin(amount),
+                        destination.clone(),
+                    ));
+                    holders.push((
+                        Address::new(&chain_config, destination).unwrap().into_string(),
+                        amount,
+                    ));
+                }
+                let transaction = tx_builder
+                    .add_output(TxOutput::Transfer(
+                        OutputValue::Coin(remaining),
+                        Destination::AnyoneCanSpend,
+                    ))
+                    .build();
+                holders.push((
+                    Address::new(&chain_config, Destination::AnyoneCanSpend).unwrap().into_string(),
+                    remaining,
+                ));
+                holders.sort_by(|(a1, b1), (a2, b2)| b2.cmp(b1).then(a1.cmp(a2)));
+
+                let chainstate_block_ids = [*tf
+                    .make_block_builder()
+                    .add_transaction(transaction)
+                    .build_and_process(&mut rng)
+                    .unwrap()
+                    .unwrap()
+                    .block_id()];
+
+                let top = rng.gen_range(1..=holders.len());
+                let expected = holders
+                    .into_iter()
+                    .take(top)
+                    .map(|(address, amount)| {
+                        json!({
+                            "address": address,
+                            "amount": amount_to_json(amount, chain_config.coin_decimals()),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                _ = tx.send((top, expected));
+
+                chainstate_block_ids
+                    .iter()
+                    .map(|id| tf.block(tf.to_chain_block_id(id.into())))
+                    .collect::<Vec<_>>()
+            };
+
+            let storage = {
+                let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+
+                let mut db_tx = storage.transaction_rw().await.unwrap();
+                db_tx.reinitialize_storage(&chain_config).await.unwrap();
+                db_tx.commit().await.unwrap();
+
+                storage
+            };
+
+            let chain_config = Arc::new(chain_config);
+
+            let mut local_node = BlockchainState::new(Arc::clone(&chain_config), storage);
+            local_node.scan_genesis(chain_config.genesis_block()).await.unwrap();
+            local_node.scan_blocks(BlockHeight::new(0), chainstate_blocks).await.unwrap();
+
+            ApiServerWebServerState {
+                db: Arc::new(local_node.storage().clone_storage().await),
+                chain_config: Arc::clone(&chain_config),
+                rpc: Arc::new(DummyRPC {}),
+                cached_values: Arc::new(CachedValues {
+                    feerate_points: RwLock::new((get_time(), vec![])),
+                }),
+                time_getter: Default::default(),
+            }
+        };
+
+        web_server(listener, web_server_state, false).await
+    });
+
+    let (top, expected) = rx.await.unwrap();
+    let url = format!("/api/v2/richlist?top={top}");
+
+    // Given that the listener port is open, this will block until a
+    // response is made (by the web server, which takes the listener
+    // over)
+    let response = reqwest::get(format!("http://{}:{}{url}", addr.ip(), addr.port()))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    let body = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body, serde_json::Value::Array(expected));
+
+    task.abort();
+}
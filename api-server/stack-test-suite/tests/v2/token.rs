@@ -13,12 +13,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use api_server_common::storage::storage_api::FungibleTokenData;
-use api_web_server::api::json_helpers::{amount_to_json, to_json_string};
+use std::time::Duration;
+
+use api_server_common::storage::storage_api::{
+    FungibleTokenData, TokenMetadataCacheEntry, TokenMetadataContent,
+};
+use api_web_server::{
+    api::json_helpers::{amount_to_json, to_json_string},
+    token_metadata::{
+        check_content_type, fetch_metadata, sanitize_json, TokenMetadataFetchError,
+        TokenMetadataFetcherConfig,
+    },
+};
 use common::{
-    chain::tokens::{
-        make_token_id, IsTokenFreezable, IsTokenFrozen, TokenId, TokenIssuance, TokenIssuanceV1,
-        TokenTotalSupply,
+    chain::{
+        block::timestamp::BlockTimestamp,
+        tokens::{
+            make_token_id, IsTokenFreezable, IsTokenFrozen, TokenId, TokenIssuance,
+            TokenIssuanceV1, TokenTotalSupply,
+        },
+        AccountNonce,
     },
     primitives::H256,
 };
@@ -131,6 +145,7 @@ async fn ok(#[case] seed: Seed) {
                     is_locked: false,
                     frozen: IsTokenFrozen::No(token_issuance.is_freezable),
                     authority: token_issuance.authority.clone(),
+                    next_nonce: AccountNonce::new(0),
                 };
 
                 _ = tx.send([(
@@ -209,7 +224,171 @@ async fn ok(#[case] seed: Seed) {
         let body: serde_json::Value = serde_json::from_str(&body).unwrap();
 
         assert_eq!(body, expected_values);
+
+        // No account commands have been issued for the token yet
+        let url = format!("/api/v2/token/{token_id}/next-nonce");
+        let response = reqwest::get(format!("http://{}:{}{url}", addr.ip(), addr.port()))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            response.status(),
+            200,
+            "Failed getting token nonce for {token_id}"
+        );
+
+        let body = response.text().await.unwrap();
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+        assert_eq!(body, json!({ "next_nonce": AccountNonce::new(0) }));
+    }
+
+    task.abort();
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn metadata(#[case] seed: Seed) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = create_unit_test_config();
+
+    let fetched_token_id = TokenId::new(H256::random_using(&mut rng));
+    let failed_token_id = TokenId::new(H256::random_using(&mut rng));
+    let missing_token_id = TokenId::new(H256::random_using(&mut rng));
+    let metadata = json!({ "name": "token", "image": "https://example.com/image.png" });
+
+    let task = {
+        let chain_config = chain_config.clone();
+        let metadata = metadata.clone();
+
+        tokio::spawn(async move {
+            let mut storage = TransactionalApiServerInMemoryStorage::new(&chain_config);
+
+            let mut db_tx = storage.transaction_rw().await.unwrap();
+            db_tx.reinitialize_storage(&chain_config).await.unwrap();
+            db_tx
+                .set_token_metadata_cache(
+                    fetched_token_id,
+                    &TokenMetadataCacheEntry {
+                        uri: b"https://example.com/metadata.json".to_vec(),
+                        content: TokenMetadataContent::Fetched {
+                            content_type: "application/json".to_owned(),
+                            content: serde_json::to_vec(&metadata).unwrap(),
+                        },
+                        fetched_at: BlockTimestamp::from_int_seconds(0),
+                    },
+                )
+                .await
+                .unwrap();
+            db_tx
+                .set_token_metadata_cache(
+                    failed_token_id,
+                    &TokenMetadataCacheEntry {
+                        uri: b"https://example.com/metadata.json".to_vec(),
+                        content: TokenMetadataContent::Failed("Timeout".to_owned()),
+                        fetched_at: BlockTimestamp::from_int_seconds(0),
+                    },
+                )
+                .await
+                .unwrap();
+            db_tx.commit().await.unwrap();
+
+            let web_server_state = ApiServerWebServerState {
+                db: Arc::new(storage),
+                chain_config: Arc::new(chain_config),
+                rpc: Arc::new(DummyRPC {}),
+                cached_values: Arc::new(CachedValues {
+                    feerate_points: RwLock::new((get_time(), vec![])),
+                }),
+                time_getter: Default::default(),
+            };
+
+            web_server(listener, web_server_state, false).await
+        })
+    };
+
+    let get_metadata = |token_id: TokenId| {
+        let token_id = Address::new(&chain_config, token_id).unwrap();
+        reqwest::get(format!(
+            "http://{}:{}/api/v2/token/{token_id}/metadata",
+            addr.ip(),
+            addr.port()
+        ))
+    };
+
+    let response = get_metadata(fetched_token_id).await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/json"
+    );
+    assert_eq!(
+        response.headers().get("x-content-type-options").unwrap(),
+        "nosniff"
+    );
+    let body: serde_json::Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(body, metadata);
+
+    for token_id in [failed_token_id, missing_token_id] {
+        let response = get_metadata(token_id).await.unwrap();
+        assert_eq!(response.status(), 404);
+
+        let body: serde_json::Value =
+            serde_json::from_str(&response.text().await.unwrap()).unwrap();
+        assert_eq!(body["error"].as_str().unwrap(), "Token metadata not found");
     }
 
     task.abort();
 }
+
+#[tokio::test]
+async fn metadata_validation() {
+    assert!(check_content_type("application/json").is_ok());
+    assert!(check_content_type("application/json; charset=utf-8").is_ok());
+    assert!(check_content_type("application/ld+json").is_ok());
+    assert!(check_content_type("text/html").is_err());
+    assert!(check_content_type("").is_err());
+
+    assert_eq!(
+        sanitize_json(b" { \"name\" : \"token\" } ").unwrap(),
+        br#"{"name":"token"}"#.to_vec()
+    );
+    assert!(matches!(
+        sanitize_json(b"<html></html>"),
+        Err(TokenMetadataFetchError::InvalidJson(_))
+    ));
+
+    // Only public http(s) URIs are fetched
+    let config = TokenMetadataFetcherConfig {
+        max_size: 1024,
+        timeout: Duration::from_secs(10),
+        refresh_interval: Duration::from_secs(60),
+    };
+    assert_eq!(
+        fetch_metadata(b"not a uri", &config).await,
+        Err(TokenMetadataFetchError::InvalidUri)
+    );
+    assert_eq!(
+        fetch_metadata(b"ftp://example.com/metadata.json", &config).await,
+        Err(TokenMetadataFetchError::UnsupportedScheme("ftp".to_owned()))
+    );
+    for uri in [
+        "http://localhost/metadata.json",
+        "http://127.0.0.1:3000/metadata.json",
+        "https://192.168.1.1/metadata.json",
+        "http://[::1]/metadata.json",
+    ] {
+        assert!(
+            matches!(
+                fetch_metadata(uri.as_bytes(), &config).await,
+                Err(TokenMetadataFetchError::ForbiddenHost(_))
+            ),
+            "{uri}"
+        );
+    }
+}
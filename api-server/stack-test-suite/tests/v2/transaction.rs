@@ -13,10 +13,38 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use api_web_server::api::json_helpers::tx_input_to_json;
+use api_server_common::storage::storage_api::UtxoSpender;
+use api_web_server::api::json_helpers::{amount_to_json, tx_input_to_json, utxo_spender_to_json};
+use common::chain::{ChainConfig, UtxoOutPoint};
 
 use super::*;
 
+/// The expected json of a transaction input, which spends a coin transfer output if any
+fn input_json(
+    inp: &TxInput,
+    utxo: Option<TxOutput>,
+    chain_config: &ChainConfig,
+) -> serde_json::Value {
+    let (address, value) = match &utxo {
+        Some(TxOutput::Transfer(OutputValue::Coin(amount), dest)) => (
+            Some(Address::new(chain_config, dest.clone()).unwrap().to_string()),
+            Some(json!({
+                "type": "Coin",
+                "amount": amount_to_json(*amount, chain_config.coin_decimals()),
+            })),
+        ),
+        Some(output) => panic!("Unexpected output {output:?}"),
+        None => (None, None),
+    };
+
+    json!({
+        "input": tx_input_to_json(inp, chain_config, &TokenDecimals::Single(None)),
+        "utxo": utxo.as_ref().map(|txo| txoutput_to_json(txo, chain_config, &TokenDecimals::Single(None))),
+        "address": address,
+        "value": value,
+    })
+}
+
 #[tokio::test]
 async fn invalid_transaction_id() {
     let (task, response) = spawn_webserver("/api/v2/transaction/invalid-transaction-id").await;
@@ -190,16 +218,20 @@ async fn multiple_tx_in_same_block(#[case] seed: Seed) {
                 "block_id": block_id.to_hash().encode_hex::<String>(),
                 "timestamp": block.timestamp().to_string(),
                 "confirmations": BlockHeight::new(0).to_string(),
+                "is_final": false,
                 "version_byte": transaction.version_byte(),
                 "is_replaceable": transaction.is_replaceable(),
                 "flags": transaction.flags(),
-                "inputs": transaction.inputs().iter().zip(utxos).map(|(inp, utxo)| json!({
-                    "input": tx_input_to_json(inp, &chain_config, &TokenDecimals::Single(None)),
-                    "utxo": utxo.as_ref().map(|txo| txoutput_to_json(txo, &chain_config, &TokenDecimals::Single(None))),
-                    })).collect::<Vec<_>>(),
+                "inputs": transaction.inputs().iter().zip(utxos).map(|(inp, utxo)| {
+                    input_json(inp, utxo, &chain_config)
+                    }).collect::<Vec<_>>(),
                 "outputs": transaction.outputs()
                             .iter()
-                            .map(|out| txoutput_to_json(out, &chain_config, &TokenDecimals::Single(None)))
+                            .map(|out| {
+                                let mut out = txoutput_to_json(out, &chain_config, &TokenDecimals::Single(None));
+                                out.as_object_mut().unwrap().insert("spent_by".into(), serde_json::Value::Null);
+                                out
+                            })
                             .collect::<Vec<_>>(),
                 });
 
@@ -281,6 +313,10 @@ async fn multiple_tx_in_same_block(#[case] seed: Seed) {
         body.get("confirmations").unwrap(),
         &expected_transaction["confirmations"]
     );
+    assert_eq!(
+        body.get("is_final").unwrap(),
+        &expected_transaction["is_final"]
+    );
 
     task.abort();
 }
@@ -296,10 +332,12 @@ async fn ok(#[case] seed: Seed) {
     let (tx, rx) = tokio::sync::oneshot::channel();
 
     let task = tokio::spawn(async move {
-        let web_server_state = {
+        let (web_server_state, finality_depth) = {
             let mut rng = make_seedable_rng(seed);
             let block_height = rng.gen_range(2..50);
             let n_blocks = rng.gen_range(block_height..100);
+            // Any depth up to the number of blocks on top of the transaction's block makes it final
+            let finality_depth = rng.gen_range(0..=(n_blocks - block_height) as u64);
 
             let chain_config = create_unit_test_config();
 
@@ -330,20 +368,47 @@ async fn ok(#[case] seed: Seed) {
                     TxInput::Account(_) | TxInput::AccountCommand(_, _) => None,
                 });
 
+                // The outputs can only be spent by the transactions in the following blocks
+                let spenders = (0..transaction.outputs().len())
+                    .map(|index| {
+                        let outpoint = UtxoOutPoint::new(
+                            OutPointSourceId::Transaction(transaction_id),
+                            index as u32,
+                        );
+                        chainstate_block_ids[block_height..].iter().find_map(|id| {
+                            tf.block(tf.to_chain_block_id(id)).transactions().iter().find_map(
+                                |tx| {
+                                    tx.inputs()
+                                        .iter()
+                                        .any(|inp| inp.utxo_outpoint() == Some(&outpoint))
+                                        .then(|| {
+                                            UtxoSpender::Transaction(tx.transaction().get_id())
+                                        })
+                                },
+                            )
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
                 let expected_transaction = json!({
                 "block_id": block_id.to_hash().encode_hex::<String>(),
                 "timestamp": block.timestamp().to_string(),
                 "confirmations": BlockHeight::new((n_blocks - block_height) as u64).to_string(),
+                "is_final": true,
                 "version_byte": transaction.version_byte(),
                 "is_replaceable": transaction.is_replaceable(),
                 "flags": transaction.flags(),
-                "inputs": transaction.inputs().iter().zip(utxos).map(|(inp, utxo)| json!({
-                    "input": tx_input_to_json(inp, &chain_config, &TokenDecimals::Single(None)),
-                    "utxo": utxo.as_ref().map(|txo| txoutput_to_json(txo, &chain_config, &TokenDecimals::Single(None))),
-                    })).collect::<Vec<_>>(),
+                "inputs": transaction.inputs().iter().zip(utxos).map(|(inp, utxo)| {
+                    input_json(inp, utxo, &chain_config)
+                    }).collect::<Vec<_>>(),
                 "outputs": transaction.outputs()
                             .iter()
-                            .map(|out| txoutput_to_json(out, &chain_config, &TokenDecimals::Single(None)))
+                            .zip(spenders)
+                            .map(|(out, spender)| {
+                                let mut out = txoutput_to_json(out, &chain_config, &TokenDecimals::Single(None));
+                                out.as_object_mut().unwrap().insert("spent_by".into(), utxo_spender_to_json(spender));
+                                out
+                            })
                             .collect::<Vec<_>>(),
                 });
 
@@ -374,7 +439,7 @@ async fn ok(#[case] seed: Seed) {
             local_node.scan_genesis(chain_config.genesis_block()).await.unwrap();
             local_node.scan_blocks(BlockHeight::new(0), chainstate_blocks).await.unwrap();
 
-            ApiServerWebServerState {
+            let web_server_state = ApiServerWebServerState {
                 db: Arc::new(local_node.storage().clone_storage().await),
                 chain_config: Arc::clone(&chain_config),
                 rpc: Arc::new(DummyRPC {}),
@@ -382,10 +447,12 @@ async fn ok(#[case] seed: Seed) {
                     feerate_points: RwLock::new((get_time(), vec![])),
                 }),
                 time_getter: Default::default(),
-            }
+            };
+
+            (web_server_state, finality_depth)
         };
 
-        web_server(listener, web_server_state, true).await
+        web_server_with_finality_depth(listener, web_server_state, true, Some(finality_depth)).await
     });
 
     let (block_id, transaction_id, expected_transaction) = rx.await.unwrap();
@@ -427,6 +494,10 @@ async fn ok(#[case] seed: Seed) {
         body.get("confirmations").unwrap(),
         &expected_transaction["confirmations"]
     );
+    assert_eq!(
+        body.get("is_final").unwrap(),
+        &expected_transaction["is_final"]
+    );
 
     task.abort();
 }
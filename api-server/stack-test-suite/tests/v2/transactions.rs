@@ -131,6 +131,7 @@ async fn ok(#[case] seed: Seed) {
                             },
                             &chain_config,
                             BlockHeight::new(n_blocks as u64),
+                            chain_config.max_depth_for_reorg().to_int() as u64,
                             BlockAuxData::new(
                                 block_id.into(),
                                 BlockHeight::new((n_blocks - idx) as u64),
@@ -222,6 +223,10 @@ async fn ok(#[case] seed: Seed) {
             body.get("confirmations").unwrap(),
             &expected_transaction["confirmations"]
         );
+        assert_eq!(
+            body.get("is_final").unwrap(),
+            &expected_transaction["is_final"]
+        );
     }
 
     task.abort();
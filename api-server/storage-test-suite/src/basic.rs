@@ -24,9 +24,12 @@ use api_server_common::storage::{
     impls::CURRENT_STORAGE_VERSION,
     storage_api::{
         block_aux_data::{BlockAuxData, BlockWithExtraData},
-        ApiServerStorage, ApiServerStorageRead, ApiServerStorageWrite, ApiServerTransactionRw,
-        BlockInfo, CoinOrTokenStatistic, Delegation, FungibleTokenData, LockedUtxo,
-        TransactionInfo, TxAdditionalInfo, Utxo, UtxoLock, UtxoWithExtraInfo,
+        scan_checkpoint::ScanCheckpoint,
+        AddressCluster, ApiServerStorage, ApiServerStorageError, ApiServerStorageRead,
+        ApiServerStorageWrite, ApiServerTransactionRw, BlockInfo, CoinOrTokenStatistic, Delegation,
+        DelegationReward, FungibleTokenData, LockedUtxo, TokenMetadataCacheEntry,
+        TokenMetadataContent, TransactionInfo, TxAdditionalInfo, Utxo, UtxoLock, UtxoSpender,
+        UtxoWithExtraInfo,
     },
 };
 use crypto::{
@@ -418,7 +421,7 @@ where
             bob_destination.clone(),
         );
 
-        let utxo = Utxo::new(output.clone(), None, false);
+        let utxo = Utxo::new(output.clone(), None, None);
         let block_height = BlockHeight::new(rng.gen_range(1..100));
 
         // set one and get it
@@ -552,7 +555,7 @@ where
                 .unwrap();
 
             // set it as unlocked at next block height
-            let utxo = Utxo::new(output.clone(), None, false);
+            let utxo = Utxo::new(output.clone(), None, None);
             db_tx
                 .set_utxo_at_height(
                     outpoint.clone(),
@@ -564,7 +567,8 @@ where
                 .unwrap();
 
             // and set it as spent on the next block height
-            let spent_utxo = Utxo::new(output.clone(), None, true);
+            let spender = UtxoSpender::Transaction(Id::new(H256::random_using(&mut rng)));
+            let spent_utxo = Utxo::new(output.clone(), None, Some(spender));
             db_tx
                 .set_utxo_at_height(
                     outpoint.clone(),
@@ -575,6 +579,21 @@ where
                 .await
                 .unwrap();
 
+            let utxo = db_tx.get_utxo(outpoint.clone()).await.unwrap().unwrap();
+            assert!(utxo.spent());
+            assert_eq!(utxo.spent_by(), Some(spender));
+
+            let spent_utxos =
+                db_tx.get_address_spent_utxos(bob_address.as_str(), 10, 0).await.unwrap();
+            assert_eq!(
+                spent_utxos,
+                vec![(
+                    outpoint.clone(),
+                    UtxoWithExtraInfo::new(output.clone(), None),
+                    spender
+                )]
+            );
+
             // set another locked utxo
             let random_tx_id: Id<Transaction> =
                 Id::<Transaction>::new(H256::random_using(&mut rng));
@@ -642,7 +661,7 @@ where
                 bob_destination,
             );
 
-            let utxo = Utxo::new(output2.clone(), None, false);
+            let utxo = Utxo::new(output2.clone(), None, None);
             let block_height = BlockHeight::new(rng.gen_range(1..100));
             db_tx
                 .set_utxo_at_height(
@@ -670,10 +689,11 @@ where
             }
 
             // set the new one to spent in the same block
-            let utxo = Utxo::new(output2.clone(), None, true);
+            let spender = UtxoSpender::Block(Id::new(H256::random_using(&mut rng)));
+            let utxo = Utxo::new(output2.clone(), None, Some(spender));
             expected_utxos.remove(&outpoint2);
             db_tx
-                .set_utxo_at_height(outpoint2, utxo, bob_address.as_str(), block_height)
+                .set_utxo_at_height(outpoint2.clone(), utxo, bob_address.as_str(), block_height)
                 .await
                 .unwrap();
 
@@ -684,6 +704,13 @@ where
                 let expected = expected_utxos.get(&outpoint).unwrap();
                 assert_eq!(&output, expected);
             }
+
+            let bob_spent_utxos =
+                db_tx.get_address_spent_utxos(bob_address.as_str(), 10, 0).await.unwrap();
+            assert_eq!(
+                bob_spent_utxos,
+                vec![(outpoint2, UtxoWithExtraInfo::new(output2, None), spender)]
+            );
         }
 
         db_tx.commit().await.unwrap();
@@ -1052,6 +1079,68 @@ where
             assert!(delegation.is_none());
         }
 
+        // test delegation rewards
+        {
+            let random_delegation_id = DelegationId::new(H256::random_using(&mut rng));
+            let random_pool_id = PoolId::new(H256::random_using(&mut rng));
+            let random_block_height = BlockHeight::new(rng.gen_range(1..1000) as u64);
+
+            let rewards = (0..3)
+                .map(|i| {
+                    let reward = DelegationReward {
+                        block_id: Id::<Block>::new(H256::random_using(&mut rng)),
+                        pool_id: random_pool_id,
+                        amount: Amount::from_atoms(rng.gen::<u128>()),
+                    };
+                    (random_block_height.checked_add(i).unwrap(), reward)
+                })
+                .collect::<Vec<_>>();
+
+            for (height, reward) in &rewards {
+                db_tx
+                    .set_delegation_reward_at_height(random_delegation_id, reward, *height)
+                    .await
+                    .unwrap();
+            }
+
+            let all_rewards = db_tx
+                .get_delegation_rewards(random_delegation_id, BlockHeight::zero(), 10, 0)
+                .await
+                .unwrap();
+            assert_eq!(all_rewards, rewards);
+
+            let from_next_height = db_tx
+                .get_delegation_rewards(
+                    random_delegation_id,
+                    random_block_height.next_height(),
+                    10,
+                    0,
+                )
+                .await
+                .unwrap();
+            assert_eq!(from_next_height, rewards[1..]);
+
+            let page = db_tx
+                .get_delegation_rewards(random_delegation_id, BlockHeight::zero(), 1, 1)
+                .await
+                .unwrap();
+            assert_eq!(page, rewards[1..2]);
+
+            let other_delegation_id = DelegationId::new(H256::random_using(&mut rng));
+            let other_rewards = db_tx
+                .get_delegation_rewards(other_delegation_id, BlockHeight::zero(), 10, 0)
+                .await
+                .unwrap();
+            assert!(other_rewards.is_empty());
+
+            db_tx.del_delegation_rewards_above_height(random_block_height).await.unwrap();
+            let all_rewards = db_tx
+                .get_delegation_rewards(random_delegation_id, BlockHeight::zero(), 10, 0)
+                .await
+                .unwrap();
+            assert_eq!(all_rewards, rewards[..1]);
+        }
+
         db_tx.commit().await.unwrap();
     }
 
@@ -1124,6 +1213,7 @@ where
             is_locked: false,
             frozen: IsTokenFrozen::No(IsTokenFreezable::Yes),
             authority: random_destination,
+            next_nonce: AccountNonce::new(0),
         };
 
         let mut db_tx = storage.transaction_rw().await.unwrap();
@@ -1141,8 +1231,11 @@ where
 
         let locked_token_data = token_data
             .clone()
-            .mint_tokens(Amount::from_atoms(rng.gen_range(1..1000)))
-            .lock();
+            .mint_tokens(
+                Amount::from_atoms(rng.gen_range(1..1000)),
+                AccountNonce::new(0),
+            )
+            .lock(AccountNonce::new(1));
 
         db_tx
             .set_fungible_token_issuance(
@@ -1192,6 +1285,7 @@ where
             is_locked: false,
             frozen: IsTokenFrozen::No(IsTokenFreezable::Yes),
             authority: random_destination,
+            next_nonce: AccountNonce::new(0),
         };
 
         let block_height = BlockHeight::new(rng.gen_range(1..100));
@@ -1288,6 +1382,82 @@ where
 
         let ids = db_tx.get_token_ids_by_ticker(0, 6, "NOT_FOUND".as_bytes()).await.unwrap();
         assert!(ids.is_empty());
+
+        // only the tokens changed above the height are returned
+        db_tx
+            .set_fungible_token_issuance(
+                random_token_id2,
+                block_height.next_height(),
+                token_data.clone(),
+            )
+            .await
+            .unwrap();
+        let ids = db_tx
+            .get_token_ids_changed_above_height(block_height.prev_height().unwrap())
+            .await
+            .unwrap();
+        assert!(ids.contains(&random_token_id1));
+        assert!(ids.contains(&random_token_id2));
+        assert!(ids.contains(&random_token_id3));
+        assert!(ids.contains(&random_token_id4));
+        assert!(ids.contains(&random_token_id5));
+        assert!(ids.contains(&random_token_id6));
+
+        let ids = db_tx.get_token_ids_changed_above_height(block_height).await.unwrap();
+        assert!(!ids.contains(&random_token_id1));
+        assert!(ids.contains(&random_token_id2));
+        assert!(!ids.contains(&random_token_id3));
+        assert!(!ids.contains(&random_token_id4));
+        assert!(!ids.contains(&random_token_id5));
+        assert!(!ids.contains(&random_token_id6));
+
+        let ids = db_tx
+            .get_token_ids_changed_above_height(block_height.next_height())
+            .await
+            .unwrap();
+        assert!(!ids.contains(&random_token_id2));
+    }
+
+    // test address activity heights
+    {
+        let mut db_tx = storage.transaction_rw().await.unwrap();
+
+        let (_, pk) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let address = Address::<Destination>::new(
+            &chain_config,
+            Destination::PublicKeyHash(PublicKeyHash::from(&pk)),
+        )
+        .unwrap();
+
+        let heights = db_tx.get_address_activity_heights(address.as_str()).await.unwrap();
+        assert_eq!(heights, None);
+
+        let first_height = BlockHeight::new(rng.gen_range(1..100));
+        let last_height = BlockHeight::new(first_height.into_int() + rng.gen_range(1..100));
+        for height in [last_height, first_height] {
+            let tx_id = Id::<Transaction>::new(H256::random_using(&mut rng));
+            db_tx
+                .set_address_transactions_at_height(address.as_str(), [tx_id].into(), height)
+                .await
+                .unwrap();
+        }
+
+        let heights = db_tx.get_address_activity_heights(address.as_str()).await.unwrap();
+        assert_eq!(heights, Some((first_height, last_height)));
+
+        // after reorg only the remaining transactions are taken into account
+        db_tx.del_address_transactions_above_height(first_height).await.unwrap();
+        let heights = db_tx.get_address_activity_heights(address.as_str()).await.unwrap();
+        assert_eq!(heights, Some((first_height, first_height)));
+
+        db_tx
+            .del_address_transactions_above_height(first_height.prev_height().unwrap())
+            .await
+            .unwrap();
+        let heights = db_tx.get_address_activity_heights(address.as_str()).await.unwrap();
+        assert_eq!(heights, None);
+
+        db_tx.commit().await.unwrap();
     }
 
     // test coin and token statistics
@@ -1363,6 +1533,427 @@ where
         db_tx.commit().await.unwrap();
     }
 
+    // test token metadata cache
+    {
+        let mut db_tx = storage.transaction_rw().await.unwrap();
+
+        let random_token_id = TokenId::new(H256::random_using(&mut rng));
+        assert!(db_tx.get_token_metadata_cache(random_token_id).await.unwrap().is_none());
+
+        let entry = TokenMetadataCacheEntry {
+            uri: b"https://example.com/metadata.json".to_vec(),
+            content: TokenMetadataContent::Fetched {
+                content_type: "application/json".to_owned(),
+                content: br#"{"name":"token"}"#.to_vec(),
+            },
+            fetched_at: BlockTimestamp::from_int_seconds(rng.gen()),
+        };
+        db_tx.set_token_metadata_cache(random_token_id, &entry).await.unwrap();
+        assert_eq!(
+            db_tx.get_token_metadata_cache(random_token_id).await.unwrap(),
+            Some(entry)
+        );
+
+        // overwrite the entry
+        let entry = TokenMetadataCacheEntry {
+            uri: b"https://example.com/other.json".to_vec(),
+            content: TokenMetadataContent::Failed("timeout".to_owned()),
+            fetched_at: BlockTimestamp::from_int_seconds(rng.gen()),
+        };
+        db_tx.set_token_metadata_cache(random_token_id, &entry).await.unwrap();
+        assert_eq!(
+            db_tx.get_token_metadata_cache(random_token_id).await.unwrap(),
+            Some(entry)
+        );
+
+        db_tx.commit().await.unwrap();
+    }
+
+    Ok(())
+}
+
+pub async fn address_balance_pruning<S, Fut, F>(
+    storage_maker: Arc<F>,
+    seed_maker: Box<dyn Fn() -> Seed + Send>,
+) -> Result<(), Failed>
+where
+    S: ApiServerStorage,
+    Fut: Future<Output = S> + Send + 'static,
+    F: Fn() -> Fut,
+{
+    let seed = seed_maker();
+    let mut rng = make_seedable_rng(seed);
+
+    let mut storage = storage_maker().await;
+    let mut db_tx = storage.transaction_rw().await.unwrap();
+    let chain_config = create_unit_test_config();
+    db_tx.reinitialize_storage(&chain_config).await.unwrap();
+
+    let address = "some_address";
+    let token_id = CoinOrTokenId::TokenId(TokenId::random_using(&mut rng));
+    let heights = (1..=10).map(|h| BlockHeight::new(h * 10)).collect::<Vec<_>>();
+    let balances = heights
+        .iter()
+        .map(|height| {
+            let coins = Amount::from_atoms(rng.gen::<u128>());
+            let tokens = Amount::from_atoms(rng.gen::<u128>());
+            (*height, coins, tokens)
+        })
+        .collect::<Vec<_>>();
+    for (height, coins, tokens) in &balances {
+        db_tx
+            .set_address_balance_at_height(address, *coins, CoinOrTokenId::Coin, *height)
+            .await
+            .unwrap();
+        db_tx
+            .set_address_balance_at_height(address, *tokens, token_id, *height)
+            .await
+            .unwrap();
+        db_tx
+            .set_address_locked_balance_at_height(address, *coins, CoinOrTokenId::Coin, *height)
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(db_tx.get_pruned_height().await.unwrap(), None);
+    assert_eq!(
+        db_tx
+            .get_address_balance_at_height(address, CoinOrTokenId::Coin, BlockHeight::new(9))
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        db_tx
+            .get_address_balance_at_height(address, CoinOrTokenId::Coin, BlockHeight::new(25))
+            .await
+            .unwrap(),
+        Some(balances[1].1)
+    );
+
+    // Prune in the middle between two entries
+    let pruned_height = BlockHeight::new(rng.gen_range(41..50));
+    db_tx.prune_address_balance_history(pruned_height).await.unwrap();
+    assert_eq!(
+        db_tx.get_pruned_height().await.unwrap(),
+        Some(pruned_height)
+    );
+
+    // The queries below the retention window fail
+    let below_window = BlockHeight::new(rng.gen_range(0..pruned_height.into_int()));
+    assert!(matches!(
+        db_tx
+            .get_address_balance_at_height(address, CoinOrTokenId::Coin, below_window)
+            .await,
+        Err(ApiServerStorageError::HistoryPruned(height)) if height == pruned_height
+    ));
+
+    // The balances at the start of the window are served from the checkpoint,
+    // and the ones above it are intact
+    for height in [pruned_height, BlockHeight::new(49), BlockHeight::new(50)]
+        .into_iter()
+        .chain(heights[4..].iter().copied())
+    {
+        let (_, coins, tokens) =
+            balances.iter().rev().find(|(h, _, _)| *h <= height).copied().unwrap();
+        assert_eq!(
+            db_tx
+                .get_address_balance_at_height(address, CoinOrTokenId::Coin, height)
+                .await
+                .unwrap(),
+            Some(coins)
+        );
+        assert_eq!(
+            db_tx.get_address_balance_at_height(address, token_id, height).await.unwrap(),
+            Some(tokens)
+        );
+    }
+
+    let (_, last_coins, last_tokens) = *balances.last().unwrap();
+    assert_eq!(
+        db_tx.get_address_balance(address, CoinOrTokenId::Coin).await.unwrap(),
+        Some(last_coins)
+    );
+    assert_eq!(
+        db_tx.get_address_balance(address, token_id).await.unwrap(),
+        Some(last_tokens)
+    );
+    assert_eq!(
+        db_tx.get_address_locked_balance(address, CoinOrTokenId::Coin).await.unwrap(),
+        Some(last_coins)
+    );
+
+    // A reorg within the retention window keeps the checkpoint
+    db_tx.del_address_balance_above_height(BlockHeight::new(50)).await.unwrap();
+    assert_eq!(
+        db_tx.get_address_balance(address, CoinOrTokenId::Coin).await.unwrap(),
+        Some(balances[4].1)
+    );
+    assert_eq!(
+        db_tx
+            .get_address_balance_at_height(address, CoinOrTokenId::Coin, pruned_height)
+            .await
+            .unwrap(),
+        Some(balances[3].1)
+    );
+
+    // Pruning below the already pruned height doesn't move the retention window back
+    db_tx.prune_address_balance_history(BlockHeight::new(20)).await.unwrap();
+    assert_eq!(
+        db_tx.get_pruned_height().await.unwrap(),
+        Some(pruned_height)
+    );
+
+    db_tx.commit().await.unwrap();
+
+    Ok(())
+}
+
+pub async fn coin_or_token_holders<S, Fut, F>(
+    storage_maker: Arc<F>,
+    seed_maker: Box<dyn Fn() -> Seed + Send>,
+) -> Result<(), Failed>
+where
+    S: ApiServerStorage,
+    Fut: Future<Output = S> + Send + 'static,
+    F: Fn() -> Fut,
+{
+    let seed = seed_maker();
+    let mut rng = make_seedable_rng(seed);
+
+    let mut storage = storage_maker().await;
+    let mut db_tx = storage.transaction_rw().await.unwrap();
+    let chain_config = create_unit_test_config();
+    db_tx.reinitialize_storage(&chain_config).await.unwrap();
+
+    let token_id = CoinOrTokenId::TokenId(TokenId::random_using(&mut rng));
+    let num_addresses = rng.gen_range(2..20);
+    let addresses = (0..num_addresses).map(|i| format!("address_{i:02}")).collect::<Vec<_>>();
+
+    // Every address has an older balance, which must be superseded by the newest one
+    let mut expected_coin_holders = Vec::new();
+    for address in &addresses {
+        let old_amount = Amount::from_atoms(rng.gen_range(1..1_000_000));
+        let amount = Amount::from_atoms(rng.gen_range(0..1_000_000));
+        db_tx
+            .set_address_balance_at_height(address, old_amount, CoinOrTokenId::Coin, 1.into())
+            .await
+            .unwrap();
+        db_tx
+            .set_address_balance_at_height(address, amount, CoinOrTokenId::Coin, 2.into())
+            .await
+            .unwrap();
+        if amount != Amount::ZERO {
+            expected_coin_holders.push((address.clone(), amount));
+        }
+    }
+    expected_coin_holders.sort_by(|(a1, b1), (a2, b2)| b2.cmp(b1).then(a1.cmp(a2)));
+
+    // A token balance of a single address
+    let token_amount = Amount::from_atoms(rng.gen_range(1..1_000_000));
+    db_tx
+        .set_address_balance_at_height(&addresses[0], token_amount, token_id, 2.into())
+        .await
+        .unwrap();
+
+    let holders = db_tx
+        .get_coin_or_token_holders(CoinOrTokenId::Coin, num_addresses, 0)
+        .await
+        .unwrap();
+    assert_eq!(holders, expected_coin_holders);
+
+    let offset = rng.gen_range(0..num_addresses);
+    let len = rng.gen_range(0..num_addresses);
+    let holders = db_tx.get_coin_or_token_holders(CoinOrTokenId::Coin, len, offset).await.unwrap();
+    let expected = expected_coin_holders
+        .iter()
+        .skip(offset as usize)
+        .take(len as usize)
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(holders, expected);
+
+    let holders = db_tx.get_coin_or_token_holders(token_id, 10, 0).await.unwrap();
+    assert_eq!(holders, vec![(addresses[0].clone(), token_amount)]);
+
+    // A reorg brings the old balances back
+    db_tx.del_address_balance_above_height(1.into()).await.unwrap();
+    let holders = db_tx.get_coin_or_token_holders(token_id, 10, 0).await.unwrap();
+    assert_eq!(holders, vec![]);
+    let holders = db_tx
+        .get_coin_or_token_holders(CoinOrTokenId::Coin, num_addresses, 0)
+        .await
+        .unwrap();
+    assert_eq!(holders.len(), addresses.len());
+
+    db_tx.commit().await.unwrap();
+
+    Ok(())
+}
+
+pub async fn address_clusters<S, Fut, F>(
+    storage_maker: Arc<F>,
+    seed_maker: Box<dyn Fn() -> Seed + Send>,
+) -> Result<(), Failed>
+where
+    S: ApiServerStorage,
+    Fut: Future<Output = S> + Send + 'static,
+    F: Fn() -> Fut,
+{
+    let seed = seed_maker();
+    let mut rng = make_seedable_rng(seed);
+
+    let mut storage = storage_maker().await;
+    let mut db_tx = storage.transaction_rw().await.unwrap();
+    let chain_config = create_unit_test_config();
+    db_tx.reinitialize_storage(&chain_config).await.unwrap();
+
+    let num_addresses = rng.gen_range(2..20);
+    let addresses = (0..num_addresses).map(|i| format!("address_{i:02}")).collect::<Vec<_>>();
+    let cluster_id = &addresses[0];
+    let other_cluster_id = &addresses[num_addresses - 1];
+
+    assert_eq!(db_tx.get_address_cluster(cluster_id).await.unwrap(), None);
+    assert_eq!(
+        db_tx.get_address_cluster_members(cluster_id).await.unwrap(),
+        Vec::<String>::new()
+    );
+
+    // All the addresses start in the same cluster, except for the last one; the parent of each
+    // address is a random one of the previous addresses, so the tree has several levels
+    let cluster_size = num_addresses as u64 - 1;
+    db_tx
+        .set_address_cluster_parent_at_height(cluster_id, cluster_id, cluster_size, 1.into())
+        .await
+        .unwrap();
+    for (i, address) in addresses.iter().enumerate().take(num_addresses - 1).skip(1) {
+        let parent = &addresses[rng.gen_range(0..i)];
+        db_tx
+            .set_address_cluster_parent_at_height(address, parent, 1, 1.into())
+            .await
+            .unwrap();
+    }
+    db_tx
+        .set_address_cluster_parent_at_height(other_cluster_id, other_cluster_id, 1, 1.into())
+        .await
+        .unwrap();
+
+    let expected_members = addresses[..num_addresses - 1].to_vec();
+    let mut members = db_tx.get_address_cluster_members(cluster_id).await.unwrap();
+    members.sort();
+    assert_eq!(members, expected_members);
+    assert_eq!(
+        db_tx.get_address_cluster_members(other_cluster_id).await.unwrap(),
+        vec![other_cluster_id.clone()]
+    );
+    for address in &expected_members {
+        assert_eq!(
+            db_tx.get_address_cluster(address).await.unwrap(),
+            Some(AddressCluster {
+                id: cluster_id.clone(),
+                size: cluster_size,
+            })
+        );
+    }
+
+    // Then the other cluster is merged into the first one, by only updating the two roots
+    db_tx
+        .set_address_cluster_parent_at_height(other_cluster_id, cluster_id, 1, 2.into())
+        .await
+        .unwrap();
+    db_tx
+        .set_address_cluster_parent_at_height(cluster_id, cluster_id, cluster_size + 1, 2.into())
+        .await
+        .unwrap();
+
+    let mut members = db_tx.get_address_cluster_members(cluster_id).await.unwrap();
+    members.sort();
+    assert_eq!(members, addresses);
+    assert_eq!(
+        db_tx.get_address_cluster_members(other_cluster_id).await.unwrap(),
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        db_tx.get_address_cluster(other_cluster_id).await.unwrap(),
+        Some(AddressCluster {
+            id: cluster_id.clone(),
+            size: cluster_size + 1,
+        })
+    );
+
+    // A reorg splits the clusters again
+    db_tx.del_address_clusters_above_height(1.into()).await.unwrap();
+    let mut members = db_tx.get_address_cluster_members(cluster_id).await.unwrap();
+    members.sort();
+    assert_eq!(members, expected_members);
+    assert_eq!(
+        db_tx.get_address_cluster_members(other_cluster_id).await.unwrap(),
+        vec![other_cluster_id.clone()]
+    );
+    assert_eq!(
+        db_tx.get_address_cluster(cluster_id).await.unwrap(),
+        Some(AddressCluster {
+            id: cluster_id.clone(),
+            size: cluster_size,
+        })
+    );
+
+    db_tx.del_address_clusters_above_height(0.into()).await.unwrap();
+    assert_eq!(db_tx.get_address_cluster(cluster_id).await.unwrap(), None);
+
+    db_tx.commit().await.unwrap();
+
+    Ok(())
+}
+
+pub async fn scan_checkpoint<S, Fut, F>(
+    storage_maker: Arc<F>,
+    seed_maker: Box<dyn Fn() -> Seed + Send>,
+) -> Result<(), Failed>
+where
+    S: ApiServerStorage,
+    Fut: Future<Output = S> + Send + 'static,
+    F: Fn() -> Fut,
+{
+    let seed = seed_maker();
+    let mut rng = make_seedable_rng(seed);
+
+    let mut storage = storage_maker().await;
+    let mut db_tx = storage.transaction_rw().await.unwrap();
+    let chain_config = create_unit_test_config();
+    db_tx.reinitialize_storage(&chain_config).await.unwrap();
+    assert_eq!(db_tx.get_scan_checkpoint().await.unwrap(), None);
+    db_tx.commit().await.unwrap();
+
+    let block_id: Id<Block> = Id::new(H256::random_using(&mut rng));
+    let block_height = BlockHeight::new(rng.gen_range(1..1000));
+    let checkpoint = ScanCheckpoint::new(block_id.into(), block_height);
+
+    let mut db_tx = storage.transaction_rw().await.unwrap();
+    db_tx.set_scan_checkpoint(checkpoint).await.unwrap();
+    db_tx.commit().await.unwrap();
+
+    // A rolled back update, e.g. of an interrupted scan, leaves the previous checkpoint in place
+    let mut db_tx = storage.transaction_rw().await.unwrap();
+    let next_block_id: Id<Block> = Id::new(H256::random_using(&mut rng));
+    let next_block_height = BlockHeight::new(block_height.into_int() + rng.gen_range(1..100));
+    db_tx
+        .set_scan_checkpoint(ScanCheckpoint::new(next_block_id.into(), next_block_height))
+        .await
+        .unwrap();
+    db_tx.rollback().await.unwrap();
+
+    let mut db_tx = storage.transaction_rw().await.unwrap();
+    let stored = db_tx.get_scan_checkpoint().await.unwrap().unwrap();
+    assert_eq!(stored, checkpoint);
+    assert_eq!(stored.block_id(), block_id.into());
+    assert_eq!(stored.block_height(), block_height);
+
+    // Reinitialization drops the checkpoint
+    db_tx.reinitialize_storage(&chain_config).await.unwrap();
+    assert_eq!(db_tx.get_scan_checkpoint().await.unwrap(), None);
+    db_tx.commit().await.unwrap();
+
     Ok(())
 }
 
@@ -1375,7 +1966,11 @@ where
 {
     vec![
         make_test!(initialization, storage_maker.clone()),
-        make_test!(set_get, storage_maker),
+        make_test!(set_get, storage_maker.clone()),
+        make_test!(address_balance_pruning, storage_maker.clone()),
+        make_test!(coin_or_token_holders, storage_maker.clone()),
+        make_test!(address_clusters, storage_maker.clone()),
+        make_test!(scan_checkpoint, storage_maker),
     ]
     .into_iter()
 }
@@ -13,10 +13,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{collections::BTreeMap, ops::Sub};
+use std::collections::BTreeMap;
 
 use api_server_common::storage::storage_api::{
-    block_aux_data::BlockAuxData, TransactionInfo, TxAdditionalInfo,
+    block_aux_data::BlockAuxData, TransactionInfo, TxAdditionalInfo, UtxoSpender,
 };
 use common::{
     address::Address,
@@ -254,6 +254,24 @@ pub fn utxo_outpoint_to_json(utxo: &UtxoOutPoint) -> serde_json::Value {
     }
 }
 
+pub fn utxo_spender_to_json(spender: Option<UtxoSpender>) -> serde_json::Value {
+    match spender {
+        Some(UtxoSpender::Transaction(tx_id)) => {
+            json!({
+                "spender_type": "Transaction",
+                "spender_id": tx_id.to_hash().encode_hex::<String>(),
+            })
+        }
+        Some(UtxoSpender::Block(block_id)) => {
+            json!({
+                "spender_type": "Block",
+                "spender_id": block_id.to_hash().encode_hex::<String>(),
+            })
+        }
+        None => serde_json::Value::Null,
+    }
+}
+
 pub fn tx_input_to_json(
     inp: &TxInput,
     chain_config: &ChainConfig,
@@ -373,6 +391,66 @@ pub fn tx_input_to_json(
     }
 }
 
+/// The destination that can spend the output, if any
+fn txoutput_destination(out: &TxOutput) -> Option<&Destination> {
+    match out {
+        TxOutput::Transfer(_, dest)
+        | TxOutput::LockThenTransfer(_, dest, _)
+        | TxOutput::CreateDelegationId(dest, _)
+        | TxOutput::IssueNft(_, _, dest)
+        | TxOutput::ProduceBlockFromStake(dest, _) => Some(dest),
+        TxOutput::CreateStakePool(_, data) => Some(data.decommission_key()),
+        // Can be spent by either the spend or the refund key, so there is no single destination
+        TxOutput::Htlc(_, _) => None,
+        TxOutput::Burn(_)
+        | TxOutput::DelegateStaking(_, _)
+        | TxOutput::IssueFungibleToken(_)
+        | TxOutput::DataDeposit(_)
+        | TxOutput::AnyoneCanTake(_) => None,
+    }
+}
+
+/// The value locked in the output, if any
+pub fn txoutput_value(out: &TxOutput) -> Option<OutputValue> {
+    match out {
+        TxOutput::Transfer(value, _)
+        | TxOutput::LockThenTransfer(value, _, _)
+        | TxOutput::Burn(value)
+        | TxOutput::Htlc(value, _) => Some(value.clone()),
+        TxOutput::CreateStakePool(_, data) => Some(OutputValue::Coin(data.pledge())),
+        TxOutput::DelegateStaking(amount, _) => Some(OutputValue::Coin(*amount)),
+        TxOutput::AnyoneCanTake(data) => Some(data.give().clone()),
+        TxOutput::CreateDelegationId(_, _)
+        | TxOutput::IssueNft(_, _, _)
+        | TxOutput::ProduceBlockFromStake(_, _)
+        | TxOutput::IssueFungibleToken(_)
+        | TxOutput::DataDeposit(_) => None,
+    }
+}
+
+/// Input json together with the output it spends, if known, and the address and the value
+/// of that output
+pub fn tx_input_with_utxo_to_json(
+    inp: &TxInput,
+    utxo: Option<&TxOutput>,
+    chain_config: &ChainConfig,
+    token_decimals: &TokenDecimals,
+) -> serde_json::Value {
+    let address = utxo
+        .and_then(txoutput_destination)
+        .map(|dest| Address::new(chain_config, dest.clone()).expect("no error").to_string());
+    let value = utxo
+        .and_then(txoutput_value)
+        .map(|value| outputvalue_to_json(&value, chain_config, token_decimals));
+
+    json!({
+        "input": tx_input_to_json(inp, chain_config, token_decimals),
+        "utxo": utxo.map(|txo| txoutput_to_json(txo, chain_config, token_decimals)),
+        "address": address,
+        "value": value,
+    })
+}
+
 pub fn tx_to_json(
     tx: &Transaction,
     additional_info: &TxAdditionalInfo,
@@ -384,10 +462,9 @@ pub fn tx_to_json(
     "is_replaceable": tx.is_replaceable(),
     "flags": tx.flags(),
     "fee": amount_to_json(additional_info.fee, chain_config.coin_decimals()),
-    "inputs": tx.inputs().iter().zip(additional_info.input_utxos.iter()).map(|(inp, utxo)| json!({
-        "input": tx_input_to_json(inp, chain_config, &(&additional_info.token_decimals).into()),
-        "utxo": utxo.as_ref().map(|txo| txoutput_to_json(txo, chain_config, &(&additional_info.token_decimals).into())),
-        })).collect::<Vec<_>>(),
+    "inputs": tx.inputs().iter().zip(additional_info.input_utxos.iter()).map(|(inp, utxo)| {
+        tx_input_with_utxo_to_json(inp, utxo.as_ref(), chain_config, &(&additional_info.token_decimals).into())
+        }).collect::<Vec<_>>(),
     "outputs": tx.outputs()
             .iter()
             .map(|out| txoutput_to_json(out, chain_config, &(&additional_info.token_decimals).into()))
@@ -395,16 +472,40 @@ pub fn tx_to_json(
     })
 }
 
+/// The number of confirmations of a block at the given height, i.e. the number of blocks
+/// on top of it (so the tip block has 0), or `None` if the block is above the tip.
+pub fn block_confirmations(tip_height: BlockHeight, block_height: BlockHeight) -> Option<u64> {
+    tip_height.into_int().checked_sub(block_height.into_int())
+}
+
+/// Insert the `confirmations` and `is_final` fields into a block or transaction json object.
+/// A block is final if it has at least `finality_depth` blocks on top of it.
+pub fn insert_confirmations(
+    obj: &mut serde_json::Map<String, serde_json::Value>,
+    confirmations: Option<u64>,
+    finality_depth: u64,
+) {
+    obj.insert(
+        "confirmations".into(),
+        confirmations.map_or("".to_string(), |c| c.to_string()).into(),
+    );
+    obj.insert(
+        "is_final".into(),
+        confirmations.is_some_and(|c| c >= finality_depth).into(),
+    );
+}
+
 pub fn to_tx_json_with_block_info(
     tx: &TransactionInfo,
     chain_config: &ChainConfig,
     tip_height: BlockHeight,
+    finality_depth: u64,
     block: BlockAuxData,
 ) -> serde_json::Value {
     let mut json = tx_to_json(tx.tx.transaction(), &tx.additinal_info, chain_config);
     let obj = json.as_object_mut().expect("object");
 
-    let confirmations = tip_height.sub(block.block_height());
+    let confirmations = block_confirmations(tip_height, block.block_height());
 
     obj.insert(
         "block_id".into(),
@@ -414,10 +515,7 @@ pub fn to_tx_json_with_block_info(
         "timestamp".into(),
         block.block_timestamp().to_string().into(),
     );
-    obj.insert(
-        "confirmations".into(),
-        confirmations.map_or("".to_string(), |c| c.to_string()).into(),
-    );
+    insert_confirmations(obj, confirmations, finality_depth);
     json
 }
 
@@ -23,11 +23,21 @@ use crate::{
 };
 
 use api_server_common::storage::storage_api::ApiServerStorage;
-use axum::{http::Method, response::IntoResponse, routing::get, Json, Router};
+use axum::{
+    extract::{connect_info::IntoMakeServiceWithConnectInfo, ConnectInfo},
+    http::Method,
+    middleware::AddExtension,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
 use serde_json::json;
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 use tokio::net::TcpListener;
-use tower_http_axum::cors::{AllowMethods, Any, CorsLayer};
+use tower_http_axum::{
+    compression::CompressionLayer,
+    cors::{AllowMethods, Any, CorsLayer},
+};
 
 #[allow(clippy::unused_async)]
 async fn bad_request() -> Result<(), ApiServerWebServerError> {
@@ -50,7 +60,31 @@ pub fn web_server<
     socket: TcpListener,
     state: ApiServerWebServerState<Arc<T>, Arc<R>>,
     enable_post_endpoints: bool,
-) -> axum::serve::Serve<Router, Router> {
+) -> axum::serve::Serve<
+    IntoMakeServiceWithConnectInfo<Router, SocketAddr>,
+    AddExtension<Router, ConnectInfo<SocketAddr>>,
+> {
+    web_server_with_finality_depth(socket, state, enable_post_endpoints, None)
+}
+
+/// Same as `web_server`, but blocks and transactions are reported as final after
+/// `finality_depth` confirmations instead of the maximum reorg depth of the chain
+pub fn web_server_with_finality_depth<
+    T: ApiServerStorage + Send + Sync + 'static,
+    R: TxSubmitClient + Send + Sync + 'static,
+>(
+    socket: TcpListener,
+    state: ApiServerWebServerState<Arc<T>, Arc<R>>,
+    enable_post_endpoints: bool,
+    finality_depth: Option<u64>,
+) -> axum::serve::Serve<
+    IntoMakeServiceWithConnectInfo<Router, SocketAddr>,
+    AddExtension<Router, ConnectInfo<SocketAddr>>,
+> {
+    let finality_depth = finality_depth.unwrap_or_else(|| {
+        state.chain_config.max_depth_for_reorg().to_int().try_into().unwrap_or(0)
+    });
+
     let cors_layer = CorsLayer::new()
         .allow_methods(AllowMethods::list([Method::GET, Method::POST]))
         .allow_headers(Any)
@@ -58,10 +92,22 @@ pub fn web_server<
 
     let routes = Router::new()
         .route("/", get(server_status))
-        .nest("/api/v2", api::v2::routes(enable_post_endpoints))
+        .nest(
+            "/api/v2",
+            api::v2::routes(
+                enable_post_endpoints,
+                api::v2::FinalityDepth(finality_depth),
+            ),
+        )
         .fallback(bad_request)
         .with_state(state)
-        .layer(cors_layer);
+        .layer(cors_layer)
+        // The encoding is negotiated with the client's Accept-Encoding header
+        .layer(CompressionLayer::new().gzip(true).br(true));
 
-    axum::serve(socket, routes)
+    // The client addresses are needed by the per-client rate limits
+    axum::serve(
+        socket,
+        routes.into_make_service_with_connect_info::<SocketAddr>(),
+    )
 }
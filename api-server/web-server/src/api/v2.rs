@@ -15,8 +15,9 @@
 
 use crate::{
     api::json_helpers::{
-        amount_to_json, block_header_to_json, to_tx_json_with_block_info, tx_to_json,
-        txoutput_to_json, utxo_outpoint_to_json, TokenDecimals,
+        amount_to_json, block_confirmations, block_header_to_json, insert_confirmations,
+        outputvalue_to_json, to_tx_json_with_block_info, tx_to_json, txoutput_to_json,
+        txoutput_value, utxo_outpoint_to_json, utxo_spender_to_json, TokenDecimals,
     },
     error::{
         ApiServerWebServerClientError, ApiServerWebServerError, ApiServerWebServerForbiddenError,
@@ -25,29 +26,45 @@ use crate::{
     TxSubmitClient,
 };
 use api_server_common::storage::storage_api::{
-    block_aux_data::BlockAuxData, ApiServerStorage, ApiServerStorageRead, BlockInfo,
-    CoinOrTokenStatistic, TransactionInfo,
+    block_aux_data::{BlockAuxData, BlockWithExtraData},
+    ApiServerStorage, ApiServerStorageError, ApiServerStorageRead, BlockInfo, CoinOrTokenStatistic,
+    Delegation, TokenMetadataContent, TransactionInfo, UtxoSpender,
 };
 use axum::{
-    extract::{DefaultBodyLimit, Path, Query, State},
-    response::IntoResponse,
+    body::Body,
+    extract::{ConnectInfo, DefaultBodyLimit, Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, Request, StatusCode, Uri},
+    response::{IntoResponse, Response},
     routing::{get, post},
-    Json, Router,
+    Extension, Json, Router,
 };
 use common::{
     address::Address,
     chain::{
         block::timestamp::BlockTimestamp,
         tokens::{IsTokenFreezable, IsTokenFrozen, IsTokenUnfreezable},
-        Block, Destination, SignedTransaction, Transaction,
+        Block, ChainConfig, Destination, GenBlock, OutPointSourceId, SignedTransaction,
+        Transaction, UtxoOutPoint,
+    },
+    primitives::{
+        id::hash_encoded, time::Time, Amount, BlockHeight, CoinOrTokenId, Id, Idable, H256,
     },
-    primitives::{Amount, BlockHeight, CoinOrTokenId, Id, Idable, H256},
 };
+use futures::{Stream, StreamExt};
 use hex::ToHex;
 use serde::Deserialize;
 use serde_json::json;
 use serialization::hex_encoded::HexEncoded;
-use std::{collections::BTreeMap, ops::Sub, str::FromStr, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::Infallible,
+    fmt::Write,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tower::ServiceExt;
 use utils::ensure;
 
 use crate::ApiServerWebServerState;
@@ -58,11 +75,20 @@ pub const API_VERSION: &str = "2.0.0";
 
 const TX_BODY_LIMIT: usize = 10240;
 
+/// How long the clients and the caches in between may keep the immutable resources, in seconds
+const IMMUTABLE_RESOURCE_MAX_AGE: u64 = 365 * 24 * 60 * 60;
+
+/// The number of blocks on top of a block after which the block and its transactions
+/// are reported as final
+#[derive(Debug, Clone, Copy)]
+pub struct FinalityDepth(pub u64);
+
 pub fn routes<
     T: ApiServerStorage + Send + Sync + 'static,
     R: TxSubmitClient + Send + Sync + 'static,
 >(
     enable_post_routes: bool,
+    finality_depth: FinalityDepth,
 ) -> Router<ApiServerWebServerState<Arc<T>, Arc<R>>> {
     let router = Router::new();
 
@@ -72,7 +98,7 @@ pub fn routes<
         .route("/chain/:height", get(chain_at_height));
 
     let router = router
-        .route("/block/:id", get(block))
+        .route("/block/:id", get(block).layer(Extension(finality_depth)))
         .route("/block/:id/header", get(block_header))
         .route("/block/:id/reward", get(block_reward))
         .route("/block/:id/transaction-ids", get(block_transaction_ids));
@@ -89,33 +115,76 @@ pub fn routes<
     let router = router.route("/feerate", get(feerate));
 
     let router = router
-        .route("/transaction", get(transactions))
-        .route("/transaction/:id", get(transaction))
+        .route(
+            "/transaction",
+            get(transactions).layer(Extension(finality_depth)),
+        )
+        .route(
+            "/transaction/:id",
+            get(transaction).layer(Extension(finality_depth)),
+        )
         .route("/transaction/:id/merkle-path", get(transaction_merkle_path));
 
     let router = router
         .route("/address/:address", get(address))
+        .route(
+            "/address/:address/balance/:height",
+            get(address_balance_at_height),
+        )
         .route("/address/:address/all-utxos", get(all_address_utxos))
         .route("/address/:address/spendable-utxos", get(address_utxos))
-        .route("/address/:address/delegations", get(address_delegations));
+        .route("/address/:address/delegations", get(address_delegations))
+        .route("/address/:address/cluster", get(address_cluster));
 
     let router = router
         .route("/pool", get(pools))
         .route("/pool/:id", get(pool))
         .route("/pool/:id/block-stats", get(pool_block_stats))
-        .route("/pool/:id/delegations", get(pool_delegations));
+        .route("/pool/:id/delegations", get(pool_delegations))
+        .route("/pool/:id/summary", get(pool_summary));
 
-    let router = router.route("/delegation/:id", get(delegation));
+    let router = router
+        .route("/delegation/:id", get(delegation))
+        .route("/delegation/:id/next-nonce", get(delegation_next_nonce))
+        .route("/delegation/:id/rewards", get(delegation_rewards));
 
     let router = router
         .route("/statistics/coin", get(coin_statistics))
         .route("/statistics/token/:id", get(token_statistics));
 
-    router
+    let router = router
         .route("/token", get(token_ids))
         .route("/token/:id", get(token))
+        .route("/token/:id/next-nonce", get(token_next_nonce))
+        .route("/token/:id/metadata", get(token_metadata))
         .route("/token/ticker/:ticker", get(token_ids_by_ticker))
-        .route("/nft/:id", get(nft))
+        .route("/nft/:id", get(nft));
+
+    // The batch queries can use all the routes above; the exports are excluded because they
+    // have their own rate limits and don't return JSON, and the holders rankings because they
+    // are too expensive to be fanned out, so they must be added after this
+    let batch_router = router.clone();
+    let router = router.route(
+        "/batch",
+        post(batch)
+            .layer(Extension(batch_router))
+            .layer(DefaultBodyLimit::max(BATCH_BODY_LIMIT)),
+    );
+
+    let router = router
+        .route("/richlist", get(richlist))
+        .route("/token/:id/holders", get(token_holders));
+
+    let export_rate_limiter = Arc::new(ExportRateLimiter::new());
+    router
+        .route(
+            "/export/blocks",
+            get(export_blocks).layer(Extension(Arc::clone(&export_rate_limiter))),
+        )
+        .route(
+            "/export/transactions",
+            get(export_transactions).layer(Extension(export_rate_limiter)),
+        )
 }
 
 async fn forbidden_request() -> Result<(), ApiServerWebServerError> {
@@ -159,67 +228,164 @@ async fn get_block(
 pub async fn block<T: ApiServerStorage>(
     Path(block_id): Path<String>,
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+    Extension(FinalityDepth(finality_depth)): Extension<FinalityDepth>,
 ) -> Result<impl IntoResponse, ApiServerWebServerError> {
     let BlockInfo { block, height } = get_block(&block_id, &state).await?;
 
-    Ok(Json(json!({
+    let confirmations = match height {
+        Some(height) => {
+            let tip_height = best_block(&state).await?.block_height();
+            block_confirmations(tip_height, height)
+        }
+        None => None,
+    };
+
+    let mut json = block_to_json(&block, height, &state.chain_config);
+    let obj = json.as_object_mut().expect("object");
+    insert_confirmations(obj, confirmations, finality_depth);
+
+    Ok(Json(json))
+}
+
+fn block_to_json(
+    block: &BlockWithExtraData,
+    height: Option<BlockHeight>,
+    chain_config: &ChainConfig,
+) -> serde_json::Value {
+    json!({
     "height": height,
     "header": block_header_to_json(&block.block),
     "body": {
         "reward": block.block.block_reward()
             .outputs()
             .iter()
-            .map(|out| txoutput_to_json(out, &state.chain_config, &TokenDecimals::Single(None)))
+            .map(|out| txoutput_to_json(out, chain_config, &TokenDecimals::Single(None)))
             .collect::<Vec<_>>(),
         "transactions": block.block.transactions()
                             .iter()
                             .zip(block.tx_additional_infos.iter())
-                            .map(|(tx, additinal_info)| tx_to_json(tx.transaction(), additinal_info, &state.chain_config))
+                            .map(|(tx, additinal_info)| tx_to_json(tx.transaction(), additinal_info, chain_config))
                             .collect::<Vec<_>>(),
     },
-    })))
+    })
+}
+
+/// Make the response for a resource that is fully determined by the id of a block, so it never
+/// changes. The ETag of the response is derived from the block id and the request path; if the
+/// client already has the resource, "304 Not Modified" is returned without the body.
+///
+/// The ETag is weak because the body may be sent with different content encodings.
+fn immutable_block_resource_response(
+    block_id: Id<Block>,
+    uri: &Uri,
+    request_headers: &HeaderMap,
+    make_body: impl FnOnce() -> serde_json::Value,
+) -> Response {
+    let etag = format!(
+        "W/\"{}\"",
+        hash_encoded(&(block_id, uri.path())).encode_hex::<String>()
+    );
+
+    let cache_headers = [
+        (
+            header::ETAG,
+            HeaderValue::from_str(&etag).expect("A hex string is a valid header value"),
+        ),
+        (
+            header::CACHE_CONTROL,
+            HeaderValue::from_str(&format!(
+                "public, max-age={IMMUTABLE_RESOURCE_MAX_AGE}, immutable"
+            ))
+            .expect("The cache control string is a valid header value"),
+        ),
+    ];
+
+    if etag_matches(request_headers, &etag) {
+        (StatusCode::NOT_MODIFIED, cache_headers).into_response()
+    } else {
+        (cache_headers, Json(make_body())).into_response()
+    }
+}
+
+/// Check whether any of the ETags in the If-None-Match headers of the request matches the given
+/// one; the comparison is weak, as required for If-None-Match.
+fn etag_matches(request_headers: &HeaderMap, etag: &str) -> bool {
+    let opaque_tag = |tag: &str| tag.trim().trim_start_matches("W/").to_owned();
+    let etag = opaque_tag(etag);
+
+    request_headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || opaque_tag(tag) == etag)
 }
 
 #[allow(clippy::unused_async)]
 pub async fn block_header<T: ApiServerStorage>(
     Path(block_id): Path<String>,
+    uri: Uri,
+    headers: HeaderMap,
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
 ) -> Result<impl IntoResponse, ApiServerWebServerError> {
     let block = get_block(&block_id, &state).await?.block;
 
-    Ok(Json(block_header_to_json(&block.block)))
+    Ok(immutable_block_resource_response(
+        block.block.get_id(),
+        &uri,
+        &headers,
+        || block_header_to_json(&block.block),
+    ))
 }
 
 #[allow(clippy::unused_async)]
 pub async fn block_reward<T: ApiServerStorage>(
     Path(block_id): Path<String>,
+    uri: Uri,
+    headers: HeaderMap,
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
 ) -> Result<impl IntoResponse, ApiServerWebServerError> {
     let block = get_block(&block_id, &state).await?.block;
 
-    Ok(Json(json!(block
-        .block
-        .block_reward()
-        .outputs()
-        .iter()
-        .map(|out| txoutput_to_json(out, &state.chain_config, &TokenDecimals::Single(None)))
-        .collect::<Vec<_>>())))
+    Ok(immutable_block_resource_response(
+        block.block.get_id(),
+        &uri,
+        &headers,
+        || {
+            json!(block
+                .block
+                .block_reward()
+                .outputs()
+                .iter()
+                .map(|out| txoutput_to_json(out, &state.chain_config, &TokenDecimals::Single(None)))
+                .collect::<Vec<_>>())
+        },
+    ))
 }
 
 pub async fn block_transaction_ids<T: ApiServerStorage>(
     Path(block_id): Path<String>,
+    uri: Uri,
+    headers: HeaderMap,
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
 ) -> Result<impl IntoResponse, ApiServerWebServerError> {
     let block = get_block(&block_id, &state).await?.block;
 
-    let transaction_ids = block
-        .block
-        .transactions()
-        .iter()
-        .map(|tx| tx.transaction().get_id())
-        .collect::<Vec<_>>();
-
-    Ok(Json(json!(transaction_ids)))
+    Ok(immutable_block_resource_response(
+        block.block.get_id(),
+        &uri,
+        &headers,
+        || {
+            let transaction_ids = block
+                .block
+                .transactions()
+                .iter()
+                .map(|tx| tx.transaction().get_id())
+                .collect::<Vec<_>>();
+
+            json!(transaction_ids)
+        },
+    ))
 }
 
 //
@@ -339,6 +505,29 @@ async fn get_transaction(
         ))
 }
 
+/// For each output of the transaction, get what has spent it (if anything)
+async fn get_outputs_spenders(
+    tx: &Transaction,
+    state: &ApiServerWebServerState<Arc<impl ApiServerStorage>, Arc<impl TxSubmitClient>>,
+) -> Result<Vec<Option<UtxoSpender>>, ApiServerWebServerError> {
+    let db_tx = state.db.transaction_ro().await.map_err(|e| {
+        logging::log::error!("internal error: {e}");
+        ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+    })?;
+
+    let mut spenders = Vec::with_capacity(tx.outputs().len());
+    for index in 0..tx.outputs().len() {
+        let outpoint = UtxoOutPoint::new(OutPointSourceId::Transaction(tx.get_id()), index as u32);
+        let utxo = db_tx.get_utxo(outpoint).await.map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?;
+        spenders.push(utxo.and_then(|utxo| utxo.spent_by()));
+    }
+
+    Ok(spenders)
+}
+
 pub async fn feerate<T: ApiServerStorage>(
     Query(params): Query<BTreeMap<String, String>>,
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
@@ -425,6 +614,7 @@ pub async fn submit_transaction<T: ApiServerStorage>(
 pub async fn transactions<T: ApiServerStorage>(
     Query(params): Query<BTreeMap<String, String>>,
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+    Extension(FinalityDepth(finality_depth)): Extension<FinalityDepth>,
 ) -> Result<impl IntoResponse, ApiServerWebServerError> {
     const OFFSET: &str = "offset";
     const ITEMS: &str = "items";
@@ -471,7 +661,9 @@ pub async fn transactions<T: ApiServerStorage>(
     let tip_height = best_block(&state).await?.block_height();
     let txs = txs
         .into_iter()
-        .map(|(block, tx)| to_tx_json_with_block_info(&tx, &state.chain_config, tip_height, block))
+        .map(|(block, tx)| {
+            to_tx_json_with_block_info(&tx, &state.chain_config, tip_height, finality_depth, block)
+        })
         .collect();
 
     Ok(Json(serde_json::Value::Array(txs)))
@@ -480,19 +672,30 @@ pub async fn transactions<T: ApiServerStorage>(
 pub async fn transaction<T: ApiServerStorage>(
     Path(transaction_id): Path<String>,
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+    Extension(FinalityDepth(finality_depth)): Extension<FinalityDepth>,
 ) -> Result<impl IntoResponse, ApiServerWebServerError> {
     let (block, TransactionInfo { tx, additinal_info }) =
         get_transaction(&transaction_id, &state).await?;
 
     let confirmations = if let Some(block) = &block {
         let tip_height = best_block(&state).await?.block_height();
-        tip_height.sub(block.block_height())
+        block_confirmations(tip_height, block.block_height())
     } else {
         None
     };
+    let spenders = get_outputs_spenders(tx.transaction(), &state).await?;
+
     let mut json = tx_to_json(tx.transaction(), &additinal_info, &state.chain_config);
     let obj = json.as_object_mut().expect("object");
 
+    let outputs = obj.get_mut("outputs").and_then(|o| o.as_array_mut()).expect("array");
+    for (output, spender) in outputs.iter_mut().zip(spenders) {
+        output
+            .as_object_mut()
+            .expect("object")
+            .insert("spent_by".into(), utxo_spender_to_json(spender));
+    }
+
     obj.insert(
         "block_id".into(),
         block
@@ -509,10 +712,7 @@ pub async fn transaction<T: ApiServerStorage>(
             .map_or("".to_string(), |b| b.block_timestamp().to_string())
             .into(),
     );
-    obj.insert(
-        "confirmations".into(),
-        confirmations.map_or("".to_string(), |c| c.to_string()).into(),
-    );
+    insert_confirmations(obj, confirmations, finality_depth);
 
     Ok(Json(json))
 }
@@ -588,8 +788,37 @@ pub async fn transaction_merkle_path<T: ApiServerStorage>(
 
 pub async fn address<T: ApiServerStorage>(
     Path(address): Path<String>,
+    Query(params): Query<BTreeMap<String, String>>,
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
 ) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    // The pagination of the outgoing amounts
+    const OFFSET: &str = "offset";
+    const ITEMS: &str = "items";
+    const DEFAULT_NUM_ITEMS: u32 = 10;
+    const MAX_NUM_ITEMS: u32 = 100;
+
+    let offset = params
+        .get(OFFSET)
+        .map(|offset| u32::from_str(offset))
+        .transpose()
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidOffset)
+        })?
+        .unwrap_or_default();
+
+    let items = params
+        .get(ITEMS)
+        .map(|items| u32::from_str(items))
+        .transpose()
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidNumItems)
+        })?
+        .unwrap_or(DEFAULT_NUM_ITEMS);
+    ensure!(
+        items <= MAX_NUM_ITEMS,
+        ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidNumItems)
+    );
+
     let address =
         Address::<Destination>::from_string(&state.chain_config, &address).map_err(|_| {
             ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidAddress)
@@ -629,14 +858,101 @@ pub async fn address<T: ApiServerStorage>(
         })?
         .unwrap_or(Amount::ZERO);
 
+    let (first_seen_height, last_activity_height) = tx
+        .get_address_activity_heights(&address.to_string())
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?
+        .ok_or(ApiServerWebServerError::NotFound(
+            ApiServerWebServerNotFoundError::AddressNotFound,
+        ))?;
+
+    // The amounts sent from the address, i.e. its spent outputs and what has spent them,
+    // starting from the most recent ones
+    let spent_utxos = tx
+        .get_address_spent_utxos(&address.to_string(), items, offset)
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?;
+
+    let outgoing = spent_utxos
+        .into_iter()
+        .map(|(outpoint, utxo, spender)| {
+            let token_decimals = TokenDecimals::Single(utxo.token_decimals);
+            json!({
+                "outpoint": utxo_outpoint_to_json(&outpoint),
+                "value": txoutput_value(&utxo.output)
+                    .map(|value| outputvalue_to_json(&value, &state.chain_config, &token_decimals)),
+                "spent_by": utxo_spender_to_json(Some(spender)),
+            })
+        })
+        .collect::<Vec<_>>();
+
     Ok(Json(json!({
     "coin_balance": amount_to_json(coin_balance, state.chain_config.coin_decimals()),
     "locked_coin_balance": amount_to_json(locked_coin_balance, state.chain_config.coin_decimals()),
-    "transaction_history": transaction_history
+    "first_seen_block_height": first_seen_height,
+    "last_activity_block_height": last_activity_height,
+    "transaction_history": transaction_history,
+    "outgoing": outgoing,
     //TODO "token_balances": destination_summary.token_balances(),
     })))
 }
 
+pub async fn address_balance_at_height<T: ApiServerStorage>(
+    Path((address, block_height)): Path<(String, String)>,
+    State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    let address =
+        Address::<Destination>::from_string(&state.chain_config, &address).map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidAddress)
+        })?;
+    let block_height = block_height.parse::<BlockHeight>().map_err(|_| {
+        ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidBlockHeight)
+    })?;
+
+    let tx = state.db.transaction_ro().await.map_err(|e| {
+        logging::log::error!("internal error: {e}");
+        ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+    })?;
+
+    let best_block = tx.get_best_block().await.map_err(|e| {
+        logging::log::error!("internal error: {e}");
+        ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+    })?;
+    ensure!(
+        block_height <= best_block.block_height(),
+        ApiServerWebServerError::NotFound(ApiServerWebServerNotFoundError::NoBlockAtHeight)
+    );
+
+    let coin_balance = tx
+        .get_address_balance_at_height(&address.to_string(), CoinOrTokenId::Coin, block_height)
+        .await
+        .map_err(|e| match e {
+            ApiServerStorageError::HistoryPruned(pruned_height) => {
+                ApiServerWebServerError::ClientError(ApiServerWebServerClientError::HistoryPruned(
+                    pruned_height,
+                ))
+            }
+            e => {
+                logging::log::error!("internal error: {e}");
+                ApiServerWebServerError::ServerError(
+                    ApiServerWebServerServerError::InternalServerError,
+                )
+            }
+        })?
+        .unwrap_or(Amount::ZERO);
+
+    Ok(Json(json!({
+        "block_height": block_height,
+        "coin_balance": amount_to_json(coin_balance, state.chain_config.coin_decimals()),
+    })))
+}
+
 pub async fn address_utxos<T: ApiServerStorage>(
     Path(address): Path<String>,
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
@@ -752,6 +1068,39 @@ pub async fn address_delegations<T: ApiServerStorage>(
     ))
 }
 
+/// Return the common-input-ownership cluster of the address, available only if the scanner
+/// runs with the address clustering enabled and the address has spent any of its outputs
+pub async fn address_cluster<T: ApiServerStorage>(
+    Path(address): Path<String>,
+    State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    let address =
+        Address::<Destination>::from_string(&state.chain_config, &address).map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidAddress)
+        })?;
+
+    let tx = state.db.transaction_ro().await.map_err(|e| {
+        logging::log::error!("internal error: {e}");
+        ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+    })?;
+
+    let cluster = tx
+        .get_address_cluster(address.as_str())
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?
+        .ok_or(ApiServerWebServerError::NotFound(
+            ApiServerWebServerNotFoundError::AddressClusterNotFound,
+        ))?;
+
+    Ok(Json(json!({
+        "cluster_id": cluster.id,
+        "member_count": cluster.size,
+    })))
+}
+
 //
 // pool/
 //
@@ -945,16 +1294,100 @@ pub async fn pool_block_stats<T: ApiServerStorage>(
     })))
 }
 
+enum DelegationSorting {
+    ByBalance,
+    ByHeight,
+}
+
+impl FromStr for DelegationSorting {
+    type Err = ApiServerWebServerClientError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "by_balance" => Ok(Self::ByBalance),
+            "by_height" => Ok(Self::ByHeight),
+            _ => Err(ApiServerWebServerClientError::InvalidDelegationsSortOrder),
+        }
+    }
+}
+
+/// Aggregated statistics over all the delegations of a pool, the same ones that are listed
+/// by the pool delegations endpoint
+struct DelegationStats {
+    delegation_count: usize,
+    delegator_count: usize,
+    total_delegated: Amount,
+    median_delegation: Amount,
+}
+
+fn delegation_stats<'a>(delegations: impl Iterator<Item = &'a Delegation>) -> DelegationStats {
+    let delegations = delegations.collect::<Vec<_>>();
+
+    let mut balances = delegations.iter().map(|d| *d.balance()).collect::<Vec<_>>();
+    balances.sort();
+
+    let total_delegated = balances
+        .iter()
+        .try_fold(Amount::ZERO, |total, b| total + *b)
+        .expect("no overflow");
+
+    let mid = balances.len() / 2;
+    let median_delegation = if balances.is_empty() {
+        Amount::ZERO
+    } else if balances.len() % 2 == 1 {
+        balances[mid]
+    } else {
+        let (lo, hi) = (balances[mid - 1], balances[mid]);
+        Amount::from_atoms(lo.into_atoms() + (hi.into_atoms() - lo.into_atoms()) / 2)
+    };
+
+    let delegator_count =
+        delegations.iter().map(|d| d.spend_destination()).collect::<BTreeSet<_>>().len();
+
+    DelegationStats {
+        delegation_count: delegations.len(),
+        delegator_count,
+        total_delegated,
+        median_delegation,
+    }
+}
+
 pub async fn pool_delegations<T: ApiServerStorage>(
     Path(pool_id): Path<String>,
+    Query(params): Query<BTreeMap<String, String>>,
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
 ) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    const OFFSET: &str = "offset";
+    const ITEMS: &str = "items";
+    const MAX_NUM_ITEMS: u32 = 100;
+    const SORT: &str = "sort";
+
     let pool_id = Address::from_string(&state.chain_config, &pool_id)
         .map_err(|_| {
             ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidPoolId)
         })?
         .into_object();
 
+    let offset = params
+        .get(OFFSET)
+        .map(|offset| u32::from_str(offset))
+        .transpose()
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidOffset)
+        })?
+        .unwrap_or_default();
+
+    // All delegations are returned unless the number of items is specified
+    let items = params.get(ITEMS).map(|items| u32::from_str(items)).transpose().map_err(|_| {
+        ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidNumItems)
+    })?;
+    ensure!(
+        !items.is_some_and(|items| items > MAX_NUM_ITEMS),
+        ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidNumItems)
+    );
+
+    let sort = params.get(SORT).map(|sort| DelegationSorting::from_str(sort)).transpose()?;
+
     let delegations = state
         .db
         .transaction_ro()
@@ -970,42 +1403,132 @@ pub async fn pool_delegations<T: ApiServerStorage>(
             ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
         })?;
 
+    // Without sorting the delegations are ordered by their ids; ties are broken the same way
+    let mut delegations = delegations.into_iter().collect::<Vec<_>>();
+    match sort {
+        None => {}
+        Some(DelegationSorting::ByBalance) => {
+            delegations.sort_by(|(_, d1), (_, d2)| d2.balance().cmp(d1.balance()))
+        }
+        Some(DelegationSorting::ByHeight) => delegations.sort_by(|(_, d1), (_, d2)| {
+            d2.creation_block_height().cmp(&d1.creation_block_height())
+        }),
+    }
+
     Ok(Json(
-        delegations.into_iter().map(|(delegation_id, delegation)|
-            json!({
-            "delegation_id": Address::new(&state.chain_config, delegation_id).expect(
-                "no error in encoding"
-            ).as_str(),
-            "next_nonce": delegation.next_nonce(),
-            "spend_destination": Address::new(&state.chain_config, delegation.spend_destination().clone()).expect(
-                "no error in encoding"
-            ).as_str(),
-            "balance": amount_to_json(*delegation.balance(), state.chain_config.coin_decimals()),
-            "creation_block_height": delegation.creation_block_height(),
-        })
-        ).collect::<Vec<_>>(),
+        delegations
+            .into_iter()
+            .skip(offset as usize)
+            .take(items.map_or(usize::MAX, |items| items as usize))
+            .map(|(delegation_id, delegation)| {
+                json!({
+                    "delegation_id": Address::new(&state.chain_config, delegation_id).expect(
+                        "no error in encoding"
+                    ).as_str(),
+                    "next_nonce": delegation.next_nonce(),
+                    "spend_destination": Address::new(&state.chain_config, delegation.spend_destination().clone()).expect(
+                        "no error in encoding"
+                    ).as_str(),
+                    "balance": amount_to_json(*delegation.balance(), state.chain_config.coin_decimals()),
+                    "creation_block_height": delegation.creation_block_height(),
+                })
+            })
+            .collect::<Vec<_>>(),
     ))
 }
 
-pub async fn delegation<T: ApiServerStorage>(
-    Path(delegation_id): Path<String>,
+pub async fn pool_summary<T: ApiServerStorage>(
+    Path(pool_id): Path<String>,
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
 ) -> Result<impl IntoResponse, ApiServerWebServerError> {
-    let delegation_id = Address::from_string(&state.chain_config, delegation_id)
+    let pool_id = Address::from_string(&state.chain_config, &pool_id)
         .map_err(|_| {
             ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidPoolId)
         })?
         .into_object();
 
-    let delegation = state
-        .db
-        .transaction_ro()
+    let db_tx = state.db.transaction_ro().await.map_err(|e| {
+        logging::log::error!("internal error: {e}");
+        ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+    })?;
+
+    let pool_data = db_tx
+        .get_pool_data(pool_id)
         .await
         .map_err(|e| {
             logging::log::error!("internal error: {e}");
             ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
         })?
-        .get_delegation(delegation_id)
+        .ok_or(ApiServerWebServerError::NotFound(
+            ApiServerWebServerNotFoundError::PoolNotFound,
+        ))?;
+
+    let delegations = db_tx.get_pool_delegations(pool_id).await.map_err(|e| {
+        logging::log::error!("internal error: {e}");
+        ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+    })?;
+    let stats = delegation_stats(delegations.values());
+
+    let staker_balance = pool_data.staker_balance().expect("no overflow");
+    let total_balance = (staker_balance + stats.total_delegated).expect("no overflow");
+
+    // Null if the chain has no final supply or the balances are out of range
+    let effective_balance = state.chain_config.final_supply().and_then(|final_supply| {
+        consensus::calculate_effective_pool_balance(
+            staker_balance,
+            total_balance,
+            final_supply.to_amount_atoms(),
+        )
+        .ok()
+    });
+
+    let decommission_destination = Address::new(
+        &state.chain_config,
+        pool_data.decommission_destination().clone(),
+    )
+    .expect("no error in encoding");
+    let vrf_key = Address::new(&state.chain_config, pool_data.vrf_public_key().clone())
+        .expect("no error in encoding");
+    let pool_id = Address::new(&state.chain_config, pool_id).expect("no error in encoding");
+    let coin_decimals = state.chain_config.coin_decimals();
+
+    Ok(Json(json!({
+        "pool_id": pool_id.as_str(),
+        "decommission_destination": decommission_destination.as_str(),
+        "staker_balance": amount_to_json(staker_balance, coin_decimals),
+        "margin_ratio_per_thousand": pool_data.margin_ratio_per_thousand(),
+        "cost_per_block": amount_to_json(pool_data.cost_per_block(), coin_decimals),
+        "vrf_public_key": vrf_key.as_str(),
+        "total_balance": amount_to_json(total_balance, coin_decimals),
+        "effective_balance": effective_balance.map(|balance| amount_to_json(balance, coin_decimals)),
+        "delegations": {
+            "delegation_count": stats.delegation_count,
+            "delegator_count": stats.delegator_count,
+            "total_delegated": amount_to_json(stats.total_delegated, coin_decimals),
+            "median_delegation": amount_to_json(stats.median_delegation, coin_decimals),
+        },
+    })))
+}
+
+pub async fn delegation<T: ApiServerStorage>(
+    Path(delegation_id): Path<String>,
+    State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    let delegation_id = Address::from_string(&state.chain_config, delegation_id)
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidPoolId)
+        })?
+        .into_object();
+
+    let delegation = state
+        .db
+        .transaction_ro()
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?
+        .get_delegation(delegation_id)
         .await
         .map_err(|e| {
             logging::log::error!("internal error: {e}");
@@ -1028,6 +1551,129 @@ pub async fn delegation<T: ApiServerStorage>(
     })))
 }
 
+pub async fn delegation_next_nonce<T: ApiServerStorage>(
+    Path(delegation_id): Path<String>,
+    State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    let delegation_id = Address::from_string(&state.chain_config, delegation_id)
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidPoolId)
+        })?
+        .into_object();
+
+    let delegation = state
+        .db
+        .transaction_ro()
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?
+        .get_delegation(delegation_id)
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?
+        .ok_or(ApiServerWebServerError::NotFound(
+            ApiServerWebServerNotFoundError::DelegationNotFound,
+        ))?;
+
+    Ok(Json(json!({
+        "next_nonce": delegation.next_nonce(),
+    })))
+}
+
+/// A page of the rewards credited to a delegation by the blocks produced by its pool,
+/// starting from the specified height
+pub async fn delegation_rewards<T: ApiServerStorage>(
+    Path(delegation_id): Path<String>,
+    Query(params): Query<BTreeMap<String, String>>,
+    State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    const FROM_HEIGHT: &str = "from_height";
+    const OFFSET: &str = "offset";
+    const ITEMS: &str = "items";
+    const DEFAULT_NUM_ITEMS: u32 = 10;
+    const MAX_NUM_ITEMS: u32 = 100;
+
+    let offset = params
+        .get(OFFSET)
+        .map(|offset| u32::from_str(offset))
+        .transpose()
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidOffset)
+        })?
+        .unwrap_or_default();
+
+    let items = params
+        .get(ITEMS)
+        .map(|items| u32::from_str(items))
+        .transpose()
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidNumItems)
+        })?
+        .unwrap_or(DEFAULT_NUM_ITEMS);
+    ensure!(
+        items <= MAX_NUM_ITEMS,
+        ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidNumItems)
+    );
+
+    let delegation_id = Address::from_string(&state.chain_config, delegation_id)
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidPoolId)
+        })?
+        .into_object();
+
+    let from_height = params
+        .get(FROM_HEIGHT)
+        .map(|height| BlockHeight::from_str(height))
+        .transpose()
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidBlockHeight)
+        })?
+        .unwrap_or(BlockHeight::zero());
+
+    let tx = state.db.transaction_ro().await.map_err(|e| {
+        logging::log::error!("internal error: {e}");
+        ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+    })?;
+
+    tx.get_delegation(delegation_id)
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?
+        .ok_or(ApiServerWebServerError::NotFound(
+            ApiServerWebServerNotFoundError::DelegationNotFound,
+        ))?;
+
+    let rewards = tx
+        .get_delegation_rewards(delegation_id, from_height, items, offset)
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?;
+
+    Ok(Json(
+        rewards
+            .into_iter()
+            .map(|(block_height, reward)| {
+                json!({
+                    "block_height": block_height,
+                    "block_id": reward.block_id,
+                    "pool_id": Address::new(&state.chain_config, reward.pool_id).expect(
+                        "no error in encoding"
+                    ).as_str(),
+                    "amount": amount_to_json(reward.amount, state.chain_config.coin_decimals()),
+                })
+            })
+            .collect::<Vec<_>>(),
+    ))
+}
+
 pub async fn token<T: ApiServerStorage>(
     Path(token_id): Path<String>,
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
@@ -1089,6 +1735,87 @@ pub async fn token<T: ApiServerStorage>(
     })))
 }
 
+pub async fn token_next_nonce<T: ApiServerStorage>(
+    Path(token_id): Path<String>,
+    State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    let token_id = Address::from_string(&state.chain_config, &token_id)
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidTokenId)
+        })?
+        .into_object();
+
+    let token = state
+        .db
+        .transaction_ro()
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?
+        .get_fungible_token_issuance(token_id)
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?
+        .ok_or(ApiServerWebServerError::NotFound(
+            ApiServerWebServerNotFoundError::TokenNotFound,
+        ))?;
+
+    Ok(Json(json!({
+        "next_nonce": token.next_nonce,
+    })))
+}
+
+/// Serve the cached copy of the contents of the token's metadata URI,
+/// fetched by the token metadata fetcher
+pub async fn token_metadata<T: ApiServerStorage>(
+    Path(token_id): Path<String>,
+    State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    let token_id = Address::from_string(&state.chain_config, &token_id)
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidTokenId)
+        })?
+        .into_object();
+
+    let entry = state
+        .db
+        .transaction_ro()
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?
+        .get_token_metadata_cache(token_id)
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?;
+
+    match entry.map(|entry| entry.content) {
+        Some(TokenMetadataContent::Fetched {
+            content_type,
+            content,
+        }) => Ok((
+            [
+                (header::CONTENT_TYPE, content_type),
+                (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_owned()),
+                (
+                    header::CONTENT_SECURITY_POLICY,
+                    "default-src 'none'".to_owned(),
+                ),
+            ],
+            content,
+        )),
+        Some(TokenMetadataContent::Failed(_)) | None => Err(ApiServerWebServerError::NotFound(
+            ApiServerWebServerNotFoundError::TokenMetadataNotFound,
+        )),
+    }
+}
+
 pub async fn nft<T: ApiServerStorage>(
     Path(nft_id): Path<String>,
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
@@ -1185,6 +1912,126 @@ pub async fn token_statistics<T: ApiServerStorage>(
     })))
 }
 
+pub async fn richlist<T: ApiServerStorage>(
+    Query(params): Query<BTreeMap<String, String>>,
+    State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    const TOP: &str = "top";
+    const DEFAULT_NUM_ITEMS: u32 = 10;
+    const MAX_NUM_ITEMS: u32 = 100;
+
+    let top = params
+        .get(TOP)
+        .map(|top| u32::from_str(top))
+        .transpose()
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidNumItems)
+        })?
+        .unwrap_or(DEFAULT_NUM_ITEMS);
+    ensure!(
+        top <= MAX_NUM_ITEMS,
+        ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidNumItems)
+    );
+
+    let holders = state
+        .db
+        .transaction_ro()
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?
+        .get_coin_or_token_holders(CoinOrTokenId::Coin, top, 0)
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?;
+
+    Ok(Json(holders_to_json(
+        holders,
+        state.chain_config.coin_decimals(),
+    )))
+}
+
+pub async fn token_holders<T: ApiServerStorage>(
+    Path(token_id): Path<String>,
+    Query(params): Query<BTreeMap<String, String>>,
+    State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    const OFFSET: &str = "offset";
+    const ITEMS: &str = "items";
+    const DEFAULT_NUM_ITEMS: u32 = 10;
+    const MAX_NUM_ITEMS: u32 = 100;
+
+    let token_id = Address::from_string(&state.chain_config, &token_id)
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidTokenId)
+        })?
+        .into_object();
+
+    let offset = params
+        .get(OFFSET)
+        .map(|offset| u32::from_str(offset))
+        .transpose()
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidOffset)
+        })?
+        .unwrap_or_default();
+
+    let items = params
+        .get(ITEMS)
+        .map(|items| u32::from_str(items))
+        .transpose()
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidNumItems)
+        })?
+        .unwrap_or(DEFAULT_NUM_ITEMS);
+    ensure!(
+        items <= MAX_NUM_ITEMS,
+        ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidNumItems)
+    );
+
+    let tx = state.db.transaction_ro().await.map_err(|e| {
+        logging::log::error!("internal error: {e}");
+        ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+    })?;
+
+    let token_decimals = tx
+        .get_token_num_decimals(token_id)
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?
+        .ok_or(ApiServerWebServerError::NotFound(
+            ApiServerWebServerNotFoundError::TokenNotFound,
+        ))?;
+
+    let holders = tx
+        .get_coin_or_token_holders(CoinOrTokenId::TokenId(token_id), items, offset)
+        .await
+        .map_err(|e| {
+            logging::log::error!("internal error: {e}");
+            ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+        })?;
+
+    Ok(Json(holders_to_json(holders, token_decimals)))
+}
+
+fn holders_to_json(holders: Vec<(String, Amount)>, decimals: u8) -> serde_json::Value {
+    holders
+        .into_iter()
+        .map(|(address, amount)| {
+            json!({
+                "address": address,
+                "amount": amount_to_json(amount, decimals),
+            })
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
 pub async fn token_ids<T: ApiServerStorage>(
     Query(params): Query<BTreeMap<String, String>>,
     State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
@@ -1295,3 +2142,532 @@ pub async fn token_ids_by_ticker<T: ApiServerStorage>(
 
     Ok(Json(serde_json::Value::Array(token_ids)))
 }
+
+//
+// export/
+//
+
+const EXPORT_CURSOR: &str = "cursor";
+const EXPORT_ITEMS: &str = "items";
+const EXPORT_DEFAULT_NUM_ITEMS: u32 = 100;
+const EXPORT_MAX_NUM_ITEMS: u32 = 1000;
+const EXPORT_RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+const EXPORT_MAX_REQUESTS_PER_WINDOW: u32 = 60;
+
+/// A per-client limit on the number of export requests, so that bulk exports of one client
+/// can't starve the others. The clients are identified by their IP address.
+pub struct ExportRateLimiter {
+    /// The start of the current window and the number of requests accepted in it, per client
+    windows: Mutex<BTreeMap<IpAddr, (Time, u32)>>,
+}
+
+impl ExportRateLimiter {
+    pub fn new() -> Self {
+        Self {
+            windows: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn accept(&self, client: IpAddr, now: Time) -> bool {
+        let mut windows = self.windows.lock().expect("should not fail normally");
+
+        // The clients whose window has expired are forgotten, so that the map stays small
+        windows.retain(|_, (window_start, _)| {
+            (now - *window_start).is_some_and(|elapsed| elapsed < EXPORT_RATE_LIMIT_WINDOW)
+        });
+
+        let (_, count) = windows.entry(client).or_insert((now, 0));
+        if *count < EXPORT_MAX_REQUESTS_PER_WINDOW {
+            *count += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Default for ExportRateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The position in the main chain at which an export has stopped.
+///
+/// The cursor contains the id of the last visited block, so that continuing an export after
+/// that block has been disconnected by a reorg fails instead of silently skipping or repeating
+/// data. It's formatted as `<block height>:<block id>` by the block export and as
+/// `<block height>:<block id>:<position in the block>` by the transaction export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ExportCursor {
+    height: BlockHeight,
+    block_id: Id<GenBlock>,
+    /// The number of transactions of the block that have already been exported
+    position: usize,
+}
+
+impl ExportCursor {
+    /// The cursor of an export that hasn't started yet
+    fn genesis(chain_config: &ChainConfig) -> Self {
+        Self {
+            height: BlockHeight::zero(),
+            block_id: chain_config.genesis_block_id(),
+            position: 0,
+        }
+    }
+
+    fn from_block_cursor(s: &str) -> Result<Self, ApiServerWebServerClientError> {
+        match s.split(':').collect::<Vec<_>>().as_slice() {
+            [height, block_id] => Self::parse(height, block_id, "0"),
+            _ => Err(ApiServerWebServerClientError::InvalidCursor),
+        }
+    }
+
+    fn from_tx_cursor(s: &str) -> Result<Self, ApiServerWebServerClientError> {
+        match s.split(':').collect::<Vec<_>>().as_slice() {
+            [height, block_id, position] => Self::parse(height, block_id, position),
+            _ => Err(ApiServerWebServerClientError::InvalidCursor),
+        }
+    }
+
+    fn parse(
+        height: &str,
+        block_id: &str,
+        position: &str,
+    ) -> Result<Self, ApiServerWebServerClientError> {
+        let height = BlockHeight::from_str(height)
+            .map_err(|_| ApiServerWebServerClientError::InvalidCursor)?;
+        let block_id = H256::from_str(block_id)
+            .map_err(|_| ApiServerWebServerClientError::InvalidCursor)?
+            .into();
+        let position =
+            usize::from_str(position).map_err(|_| ApiServerWebServerClientError::InvalidCursor)?;
+
+        Ok(Self {
+            height,
+            block_id,
+            position,
+        })
+    }
+
+    fn to_block_cursor(self) -> String {
+        format!("{}:{:x}", self.height, self.block_id)
+    }
+
+    fn to_tx_cursor(self) -> String {
+        format!("{}:{:x}:{}", self.height, self.block_id, self.position)
+    }
+}
+
+fn export_num_items(params: &BTreeMap<String, String>) -> Result<u32, ApiServerWebServerError> {
+    let items = params
+        .get(EXPORT_ITEMS)
+        .map(|items| u32::from_str(items))
+        .transpose()
+        .map_err(|_| {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidNumItems)
+        })?
+        .unwrap_or(EXPORT_DEFAULT_NUM_ITEMS);
+    ensure!(
+        items <= EXPORT_MAX_NUM_ITEMS,
+        ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidNumItems)
+    );
+
+    Ok(items)
+}
+
+fn export_storage_error(e: ApiServerStorageError) -> ApiServerWebServerError {
+    logging::log::error!("internal error: {e}");
+    ApiServerWebServerError::ServerError(ApiServerWebServerServerError::InternalServerError)
+}
+
+/// Check that the block of the cursor is still in the main chain and return it;
+/// `None` is returned for the genesis
+async fn load_export_cursor_block<T: ApiServerStorage>(
+    db: &T,
+    chain_config: &ChainConfig,
+    cursor: &ExportCursor,
+) -> Result<Option<BlockWithExtraData>, ApiServerWebServerError> {
+    if cursor.height == BlockHeight::zero() {
+        ensure!(
+            cursor.block_id == chain_config.genesis_block_id(),
+            ApiServerWebServerClientError::StaleCursor
+        );
+        return Ok(None);
+    }
+
+    let db_tx = db.transaction_ro().await.map_err(export_storage_error)?;
+
+    let block_id = db_tx
+        .get_main_chain_block_id(cursor.height)
+        .await
+        .map_err(export_storage_error)?
+        .filter(|block_id| Id::<GenBlock>::from(*block_id) == cursor.block_id)
+        .ok_or(ApiServerWebServerClientError::StaleCursor)?;
+
+    let BlockInfo { block, height: _ } =
+        db_tx.get_block(block_id).await.map_err(export_storage_error)?.ok_or(
+            ApiServerWebServerError::ServerError(
+                ApiServerWebServerServerError::InternalServerError,
+            ),
+        )?;
+
+    Ok(Some(block))
+}
+
+/// Read the main chain block following the cursor block.
+///
+/// `None` is returned at the tip, and also if the cursor block has been disconnected by a reorg
+/// in the meantime, in which case continuing from the cursor will be rejected.
+async fn next_export_block<T: ApiServerStorage>(
+    db: &T,
+    cursor: &ExportCursor,
+) -> Result<Option<(Id<Block>, BlockWithExtraData)>, ApiServerWebServerError> {
+    let db_tx = db.transaction_ro().await.map_err(export_storage_error)?;
+
+    let block_id = db_tx
+        .get_main_chain_block_id(cursor.height.next_height())
+        .await
+        .map_err(export_storage_error)?;
+    let Some(block_id) = block_id else {
+        return Ok(None);
+    };
+
+    let BlockInfo { block, height: _ } =
+        db_tx.get_block(block_id).await.map_err(export_storage_error)?.ok_or(
+            ApiServerWebServerError::ServerError(
+                ApiServerWebServerServerError::InternalServerError,
+            ),
+        )?;
+
+    if block.block.prev_block_id() != cursor.block_id {
+        return Ok(None);
+    }
+
+    Ok(Some((block_id, block)))
+}
+
+/// Export the transactions of the cursor block, starting from the cursor position and
+/// advancing it, until the block ends or `items_left` drops to zero
+fn export_block_txs(
+    block: &BlockWithExtraData,
+    cursor: &mut ExportCursor,
+    items_left: &mut u32,
+    chain_config: &ChainConfig,
+) -> String {
+    let mut lines = String::new();
+
+    let txs = block.block.transactions().iter().zip(block.tx_additional_infos.iter());
+    for (tx, additional_info) in txs.skip(cursor.position).take(*items_left as usize) {
+        let mut json = tx_to_json(tx.transaction(), additional_info, chain_config);
+        let obj = json.as_object_mut().expect("object");
+        obj.insert("block_id".into(), json!(cursor.block_id));
+        obj.insert("block_height".into(), json!(cursor.height));
+        obj.insert("position".into(), json!(cursor.position));
+        obj.insert("timestamp".into(), json!(block.block.timestamp()));
+
+        writeln!(lines, "{json}").expect("Writing to a memory buffer should not fail");
+        cursor.position += 1;
+        *items_left -= 1;
+    }
+
+    lines
+}
+
+/// The last line of an export, which contains the cursor to continue the export from
+fn export_next_cursor_line(next_cursor: String) -> String {
+    format!("{}\n", json!({ "next_cursor": next_cursor }))
+}
+
+/// The state of an export stream, which reads one block per step
+struct ExportStreamState<T> {
+    db: Arc<T>,
+    chain_config: Arc<ChainConfig>,
+    cursor: ExportCursor,
+    /// The number of items that may still be exported
+    items_left: u32,
+    /// The number of blocks that may still be scanned
+    blocks_left: u32,
+}
+
+/// Make a streamed newline-delimited JSON response
+fn export_response(
+    lines: impl Stream<Item = Result<String, ApiServerWebServerError>> + Send + 'static,
+) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(lines),
+    )
+}
+
+/// Export the main chain blocks as newline-delimited JSON, one block per line, in the order of
+/// their heights. The response is streamed, one block at a time.
+///
+/// The `cursor` parameter is the cursor returned by the previous request; if not specified,
+/// the export starts from the first block after the genesis. The last line of the response
+/// is an object with the `next_cursor` field, which should be passed to the next request;
+/// a response that doesn't end with it has been cut short and should be retried. If no blocks
+/// were exported, the tip has been reached and the same cursor should be retried later.
+/// A cursor whose block has been disconnected by a reorg is rejected.
+pub async fn export_blocks<T: ApiServerStorage + Send + Sync + 'static>(
+    Query(params): Query<BTreeMap<String, String>>,
+    ConnectInfo(client_addr): ConnectInfo<SocketAddr>,
+    Extension(rate_limiter): Extension<Arc<ExportRateLimiter>>,
+    State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    ensure!(
+        rate_limiter.accept(client_addr.ip(), state.time_getter.get_time()),
+        ApiServerWebServerError::TooManyRequests
+    );
+
+    let cursor = params
+        .get(EXPORT_CURSOR)
+        .map(|cursor| ExportCursor::from_block_cursor(cursor))
+        .transpose()?
+        .unwrap_or_else(|| ExportCursor::genesis(&state.chain_config));
+    let items = export_num_items(&params)?;
+
+    load_export_cursor_block(state.db.as_ref(), &state.chain_config, &cursor).await?;
+
+    let stream_state = ExportStreamState {
+        db: state.db,
+        chain_config: state.chain_config,
+        cursor,
+        items_left: items,
+        blocks_left: items,
+    };
+
+    let lines = futures::stream::unfold(Some(stream_state), |stream_state| async move {
+        let mut stream_state = stream_state?;
+
+        let next_block = if stream_state.items_left == 0 {
+            Ok(None)
+        } else {
+            next_export_block(stream_state.db.as_ref(), &stream_state.cursor).await
+        };
+
+        match next_block {
+            Ok(Some((block_id, block))) => {
+                let height = stream_state.cursor.height.next_height();
+                let mut json = block_to_json(&block, Some(height), &stream_state.chain_config);
+                let obj = json.as_object_mut().expect("object");
+                obj.insert("block_id".into(), json!(block_id));
+
+                stream_state.cursor = ExportCursor {
+                    height,
+                    block_id: block_id.into(),
+                    position: 0,
+                };
+                stream_state.items_left -= 1;
+
+                Some((Ok(format!("{json}\n")), Some(stream_state)))
+            }
+            Ok(None) => Some((
+                Ok(export_next_cursor_line(
+                    stream_state.cursor.to_block_cursor(),
+                )),
+                None,
+            )),
+            Err(e) => Some((Err(e), None)),
+        }
+    });
+
+    Ok(export_response(lines))
+}
+
+/// Export the transactions of the main chain blocks as newline-delimited JSON, one transaction
+/// per line, in the order of their positions in the chain. The response is streamed, one block
+/// at a time.
+///
+/// The cursor works the same way as for the block export; if not specified, the export starts
+/// from the first transaction after the genesis. At most `items` blocks are scanned by one
+/// request, so the export should be continued from the returned cursor even if fewer than
+/// `items` transactions were exported; the tip has been reached only if the returned cursor
+/// is the same as the passed one.
+pub async fn export_transactions<T: ApiServerStorage + Send + Sync + 'static>(
+    Query(params): Query<BTreeMap<String, String>>,
+    ConnectInfo(client_addr): ConnectInfo<SocketAddr>,
+    Extension(rate_limiter): Extension<Arc<ExportRateLimiter>>,
+    State(state): State<ApiServerWebServerState<Arc<T>, Arc<impl TxSubmitClient>>>,
+) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    ensure!(
+        rate_limiter.accept(client_addr.ip(), state.time_getter.get_time()),
+        ApiServerWebServerError::TooManyRequests
+    );
+
+    let mut cursor = params
+        .get(EXPORT_CURSOR)
+        .map(|cursor| ExportCursor::from_tx_cursor(cursor))
+        .transpose()?
+        .unwrap_or_else(|| ExportCursor::genesis(&state.chain_config));
+    let mut items_left = export_num_items(&params)?;
+
+    // The rest of the transactions of the cursor block go first
+    let cursor_block =
+        load_export_cursor_block(state.db.as_ref(), &state.chain_config, &cursor).await?;
+    let first_lines = cursor_block
+        .map(|block| export_block_txs(&block, &mut cursor, &mut items_left, &state.chain_config))
+        .unwrap_or_default();
+
+    let stream_state = ExportStreamState {
+        db: state.db,
+        chain_config: state.chain_config,
+        cursor,
+        items_left,
+        blocks_left: items_left,
+    };
+
+    let lines = futures::stream::unfold(Some(stream_state), |stream_state| async move {
+        let mut stream_state = stream_state?;
+
+        let next_block = if stream_state.items_left == 0 || stream_state.blocks_left == 0 {
+            Ok(None)
+        } else {
+            next_export_block(stream_state.db.as_ref(), &stream_state.cursor).await
+        };
+
+        match next_block {
+            Ok(Some((block_id, block))) => {
+                stream_state.cursor = ExportCursor {
+                    height: stream_state.cursor.height.next_height(),
+                    block_id: block_id.into(),
+                    position: 0,
+                };
+                stream_state.blocks_left -= 1;
+
+                let lines = export_block_txs(
+                    &block,
+                    &mut stream_state.cursor,
+                    &mut stream_state.items_left,
+                    &stream_state.chain_config,
+                );
+
+                Some((Ok(lines), Some(stream_state)))
+            }
+            Ok(None) => Some((
+                Ok(export_next_cursor_line(stream_state.cursor.to_tx_cursor())),
+                None,
+            )),
+            Err(e) => Some((Err(e), None)),
+        }
+    });
+
+    Ok(export_response(
+        futures::stream::once(std::future::ready(Ok(first_lines))).chain(lines),
+    ))
+}
+
+//
+// batch/
+//
+
+const BATCH_BODY_LIMIT: usize = 10240;
+const BATCH_MAX_QUERIES: usize = 20;
+const BATCH_RESPONSE_LIMIT: usize = 10 * 1024 * 1024;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchQuery {
+    path: String,
+    #[serde(default)]
+    params: BTreeMap<String, String>,
+}
+
+/// Execute several GET queries with one request, e.g. to load everything an explorer page needs.
+///
+/// The body is a JSON array of `{"path": "/block/<id>", "params": {"offset": "10"}}` objects,
+/// where the path is relative to the API root and `params` are the optional query parameters.
+/// The queries are executed concurrently and the response is an array of
+/// `{"status": <HTTP status code>, "body": <response>}` objects, in the same order as the queries.
+///
+/// Every query is executed like a standalone request with its own read transaction, so the
+/// results of different queries may be inconsistent if a new block is scanned or a reorg
+/// happens in the meantime. Clients that need a consistent view can include `/chain/tip` as
+/// the first and the last query and retry the batch if the results differ.
+pub async fn batch<
+    T: ApiServerStorage + Send + Sync + 'static,
+    R: TxSubmitClient + Send + Sync + 'static,
+>(
+    Extension(router): Extension<Router<ApiServerWebServerState<Arc<T>, Arc<R>>>>,
+    State(state): State<ApiServerWebServerState<Arc<T>, Arc<R>>>,
+    body: String,
+) -> Result<impl IntoResponse, ApiServerWebServerError> {
+    let queries: Vec<BatchQuery> = serde_json::from_str(&body).map_err(|_| {
+        ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidBatchRequest)
+    })?;
+    ensure!(
+        queries.len() <= BATCH_MAX_QUERIES,
+        ApiServerWebServerError::ClientError(ApiServerWebServerClientError::TooManyBatchQueries)
+    );
+
+    let requests = queries.iter().map(batch_query_to_request).collect::<Result<Vec<_>, _>>()?;
+
+    let router: Router = router.with_state(state);
+    let responses = futures::future::join_all(
+        requests.into_iter().map(|request| router.clone().oneshot(request)),
+    )
+    .await;
+
+    let mut results = Vec::with_capacity(responses.len());
+    for response in responses {
+        let response = response.unwrap_or_else(|e: Infallible| match e {});
+        let status = response.status().as_u16();
+        let body = axum::body::to_bytes(response.into_body(), BATCH_RESPONSE_LIMIT).await.map_err(
+            |e| {
+                logging::log::error!("internal error: {e}");
+                ApiServerWebServerError::ServerError(
+                    ApiServerWebServerServerError::InternalServerError,
+                )
+            },
+        )?;
+        // Routes that don't exist return an empty body
+        let body = if body.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&body).unwrap_or_else(|_| {
+                serde_json::Value::String(String::from_utf8_lossy(&body).into_owned())
+            })
+        };
+
+        results.push(json!({
+            "status": status,
+            "body": body,
+        }));
+    }
+
+    Ok(Json(results))
+}
+
+fn batch_query_to_request(query: &BatchQuery) -> Result<Request<Body>, ApiServerWebServerError> {
+    ensure!(
+        query.path.starts_with('/') && !query.path.contains(['?', '#']),
+        ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidBatchRequest)
+    );
+
+    let mut uri = query.path.clone();
+    for (i, (key, value)) in query.params.iter().enumerate() {
+        let separator = if i == 0 { '?' } else { '&' };
+        write!(
+            uri,
+            "{separator}{}={}",
+            percent_encode(key),
+            percent_encode(value)
+        )
+        .expect("Writing to a memory buffer should not fail");
+    }
+
+    Request::get(uri).body(Body::empty()).map_err(|_| {
+        ApiServerWebServerError::ClientError(ApiServerWebServerClientError::InvalidBatchRequest)
+    })
+}
+
+fn percent_encode(s: &str) -> String {
+    s.bytes().fold(String::with_capacity(s.len()), |mut result, byte| {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            result.push(byte as char);
+        } else {
+            write!(result, "%{byte:02X}").expect("Writing to a memory buffer should not fail");
+        }
+        result
+    })
+}
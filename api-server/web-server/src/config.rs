@@ -63,6 +63,30 @@ pub struct ApiServerWebServerConfig {
     /// RPC password (either provide a username and password, or use a cookie file. You cannot use both)
     #[clap(long)]
     pub node_rpc_password: Option<String>,
+
+    /// Fetch the contents of the token metadata URIs in the background and serve them
+    /// from the `/token/:id/metadata` endpoint
+    #[clap(long)]
+    pub enable_token_metadata_fetcher: bool,
+
+    /// The maximum size of the fetched token metadata in bytes
+    #[clap(long, default_value_t = 64 * 1024)]
+    pub token_metadata_max_size: usize,
+
+    /// The timeout for fetching the token metadata, in seconds
+    #[clap(long, default_value_t = 10)]
+    pub token_metadata_fetch_timeout: u64,
+
+    /// How often the fetched token metadata is refreshed, in seconds
+    #[clap(long, default_value_t = 24 * 60 * 60)]
+    pub token_metadata_refresh_interval: u64,
+
+    /// The number of confirmations after which blocks and transactions are reported as final
+    /// (the `is_final` field of the `/block` and `/transaction` responses)
+    ///
+    /// Default: the maximum reorg depth of the chain
+    #[clap(long)]
+    pub finality_depth: Option<u64>,
 }
 
 #[derive(Clone, Debug, Parser)]
@@ -19,6 +19,7 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use common::primitives::BlockHeight;
 use serde::Serialize;
 use serde_json::json;
 use thiserror::Error;
@@ -43,6 +44,8 @@ pub enum ApiServerWebServerError {
     Forbidden(#[from] ApiServerWebServerForbiddenError),
     #[error("Server error: {0}")]
     ServerError(#[from] ApiServerWebServerServerError),
+    #[error("Too many requests")]
+    TooManyRequests,
 }
 
 #[derive(Debug, Error, Serialize)]
@@ -65,6 +68,10 @@ pub enum ApiServerWebServerNotFoundError {
     TokenNotFound,
     #[error("NFT not found")]
     NftNotFound,
+    #[error("Token metadata not found")]
+    TokenMetadataNotFound,
+    #[error("Address cluster not found")]
+    AddressClusterNotFound,
 }
 
 #[derive(Debug, Error, Serialize)]
@@ -93,6 +100,8 @@ pub enum ApiServerWebServerClientError {
     InvalidNumItems,
     #[error("Invalid pools sort order")]
     InvalidPoolsSortOrder,
+    #[error("Invalid delegations sort order")]
+    InvalidDelegationsSortOrder,
     #[error("Invalid signed transaction")]
     InvalidSignedTransaction,
     #[error("Invalid token Id")]
@@ -101,6 +110,16 @@ pub enum ApiServerWebServerClientError {
     InvalidNftId,
     #[error("Invalid in top X MB query parameter")]
     InvalidInTopX,
+    #[error("Invalid cursor")]
+    InvalidCursor,
+    #[error("The block of the cursor is no longer in the main chain")]
+    StaleCursor,
+    #[error("Invalid batch request")]
+    InvalidBatchRequest,
+    #[error("Too many queries in the batch")]
+    TooManyBatchQueries,
+    #[error("The history before block height {0} has been pruned")]
+    HistoryPruned(BlockHeight),
 }
 
 #[allow(dead_code)]
@@ -120,19 +139,196 @@ pub enum ApiServerWebServerServerError {
     RpcError(String),
 }
 
-impl IntoResponse for ApiServerWebServerError {
-    fn into_response(self) -> Response {
-        let (status, message) = match self {
-            ApiServerWebServerError::ClientError(error) => {
-                (StatusCode::BAD_REQUEST, error.to_string())
+impl ApiServerWebServerError {
+    /// The stable, machine-readable code of the error, which clients can branch on
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiServerWebServerError::ClientError(error) => error.code(),
+            ApiServerWebServerError::NotFound(error) => error.code(),
+            ApiServerWebServerError::Forbidden(error) => error.code(),
+            ApiServerWebServerError::ServerError(error) => error.code(),
+            ApiServerWebServerError::TooManyRequests => "TOO_MANY_REQUESTS",
+        }
+    }
+
+    /// Whether the same request may succeed if it's repeated later
+    pub fn retryable(&self) -> bool {
+        match self {
+            ApiServerWebServerError::ClientError(_) | ApiServerWebServerError::Forbidden(_) => {
+                false
+            }
+            ApiServerWebServerError::NotFound(error) => error.retryable(),
+            ApiServerWebServerError::ServerError(error) => error.retryable(),
+            ApiServerWebServerError::TooManyRequests => true,
+        }
+    }
+
+    /// Additional data specific to the error code
+    pub fn details(&self) -> serde_json::Value {
+        match self {
+            ApiServerWebServerError::ClientError(ApiServerWebServerClientError::HistoryPruned(
+                height,
+            )) => json!({ "pruned_before_height": height }),
+            ApiServerWebServerError::ClientError(_)
+            | ApiServerWebServerError::NotFound(_)
+            | ApiServerWebServerError::Forbidden(_)
+            | ApiServerWebServerError::ServerError(_)
+            | ApiServerWebServerError::TooManyRequests => serde_json::Value::Null,
+        }
+    }
+
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiServerWebServerError::ClientError(_) => StatusCode::BAD_REQUEST,
+            ApiServerWebServerError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiServerWebServerError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiServerWebServerError::ServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiServerWebServerError::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+}
+
+impl ApiServerWebServerNotFoundError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiServerWebServerNotFoundError::AddressNotFound => "ADDRESS_NOT_FOUND",
+            ApiServerWebServerNotFoundError::BlockNotFound => "BLOCK_NOT_FOUND",
+            ApiServerWebServerNotFoundError::NoBlockAtHeight => "NO_BLOCK_AT_HEIGHT",
+            ApiServerWebServerNotFoundError::TransactionNotFound => "TRANSACTION_NOT_FOUND",
+            ApiServerWebServerNotFoundError::TransactionNotPartOfBlock => {
+                "TRANSACTION_NOT_PART_OF_BLOCK"
+            }
+            ApiServerWebServerNotFoundError::PoolNotFound => "POOL_NOT_FOUND",
+            ApiServerWebServerNotFoundError::DelegationNotFound => "DELEGATION_NOT_FOUND",
+            ApiServerWebServerNotFoundError::TokenNotFound => "TOKEN_NOT_FOUND",
+            ApiServerWebServerNotFoundError::NftNotFound => "NFT_NOT_FOUND",
+            ApiServerWebServerNotFoundError::TokenMetadataNotFound => "TOKEN_METADATA_NOT_FOUND",
+            ApiServerWebServerNotFoundError::AddressClusterNotFound => "ADDRESS_CLUSTER_NOT_FOUND",
+        }
+    }
+
+    pub fn retryable(&self) -> bool {
+        match self {
+            // The transaction may be included in a block later
+            ApiServerWebServerNotFoundError::TransactionNotPartOfBlock => true,
+            ApiServerWebServerNotFoundError::AddressNotFound
+            | ApiServerWebServerNotFoundError::BlockNotFound
+            | ApiServerWebServerNotFoundError::NoBlockAtHeight
+            | ApiServerWebServerNotFoundError::TransactionNotFound
+            | ApiServerWebServerNotFoundError::PoolNotFound
+            | ApiServerWebServerNotFoundError::DelegationNotFound
+            | ApiServerWebServerNotFoundError::TokenNotFound
+            | ApiServerWebServerNotFoundError::NftNotFound
+            | ApiServerWebServerNotFoundError::TokenMetadataNotFound
+            | ApiServerWebServerNotFoundError::AddressClusterNotFound => false,
+        }
+    }
+}
+
+impl ApiServerWebServerForbiddenError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiServerWebServerForbiddenError::Forbidden => "FORBIDDEN",
+        }
+    }
+}
+
+impl ApiServerWebServerClientError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiServerWebServerClientError::BadRequest => "BAD_REQUEST",
+            ApiServerWebServerClientError::InvalidBlockHeight => "INVALID_BLOCK_HEIGHT",
+            ApiServerWebServerClientError::InvalidBlockId => "INVALID_BLOCK_ID",
+            ApiServerWebServerClientError::InvalidAddress => "INVALID_ADDRESS",
+            ApiServerWebServerClientError::InvalidTransactionId => "INVALID_TRANSACTION_ID",
+            ApiServerWebServerClientError::InvalidPoolId => "INVALID_POOL_ID",
+            ApiServerWebServerClientError::InvalidOffset => "INVALID_OFFSET",
+            ApiServerWebServerClientError::InvalidNumItems => "INVALID_NUM_ITEMS",
+            ApiServerWebServerClientError::InvalidPoolsSortOrder => "INVALID_POOLS_SORT_ORDER",
+            ApiServerWebServerClientError::InvalidDelegationsSortOrder => {
+                "INVALID_DELEGATIONS_SORT_ORDER"
+            }
+            ApiServerWebServerClientError::InvalidSignedTransaction => "INVALID_SIGNED_TRANSACTION",
+            ApiServerWebServerClientError::InvalidTokenId => "INVALID_TOKEN_ID",
+            ApiServerWebServerClientError::InvalidNftId => "INVALID_NFT_ID",
+            ApiServerWebServerClientError::InvalidInTopX => "INVALID_IN_TOP_X",
+            ApiServerWebServerClientError::InvalidCursor => "INVALID_CURSOR",
+            ApiServerWebServerClientError::StaleCursor => "STALE_CURSOR",
+            ApiServerWebServerClientError::InvalidBatchRequest => "INVALID_BATCH_REQUEST",
+            ApiServerWebServerClientError::TooManyBatchQueries => "TOO_MANY_BATCH_QUERIES",
+            ApiServerWebServerClientError::HistoryPruned(_) => "HISTORY_PRUNED",
+        }
+    }
+}
+
+impl ApiServerWebServerServerError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiServerWebServerServerError::CannotFindTransactionInBlock => {
+                "CANNOT_FIND_TRANSACTION_IN_BLOCK"
+            }
+            ApiServerWebServerServerError::ErrorCalculatingMerklePath => {
+                "ERROR_CALCULATING_MERKLE_PATH"
             }
-            ApiServerWebServerError::NotFound(error) => (StatusCode::NOT_FOUND, error.to_string()),
-            ApiServerWebServerError::Forbidden(error) => (StatusCode::FORBIDDEN, error.to_string()),
-            ApiServerWebServerError::ServerError(error) => {
-                (StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+            ApiServerWebServerServerError::ErrorCalculatingMerkleTree => {
+                "ERROR_CALCULATING_MERKLE_TREE"
             }
+            ApiServerWebServerServerError::InternalServerError => "INTERNAL_SERVER_ERROR",
+            ApiServerWebServerServerError::TransactionIndexOverflow => "TRANSACTION_INDEX_OVERFLOW",
+            ApiServerWebServerServerError::RpcError(_) => "RPC_ERROR",
+        }
+    }
+
+    pub fn retryable(&self) -> bool {
+        match self {
+            // Storage and node connection problems are usually temporary
+            ApiServerWebServerServerError::InternalServerError
+            | ApiServerWebServerServerError::RpcError(_) => true,
+            ApiServerWebServerServerError::CannotFindTransactionInBlock
+            | ApiServerWebServerServerError::ErrorCalculatingMerklePath
+            | ApiServerWebServerServerError::ErrorCalculatingMerkleTree
+            | ApiServerWebServerServerError::TransactionIndexOverflow => false,
+        }
+    }
+}
+
+/// The body of every error response
+#[derive(Debug, Serialize)]
+pub struct ApiErrorBody {
+    /// The machine-readable error code, see [ApiServerWebServerError::code]
+    pub code: &'static str,
+    /// The human-readable description of the error
+    pub message: String,
+    /// Additional data specific to the error code, null if there is none
+    pub details: serde_json::Value,
+    /// Whether the same request may succeed if it's repeated later
+    pub retryable: bool,
+    /// Same as `message`, kept for the clients written before the error codes were introduced
+    pub error: String,
+}
+
+impl From<&ApiServerWebServerError> for ApiErrorBody {
+    fn from(error: &ApiServerWebServerError) -> Self {
+        let message = match error {
+            ApiServerWebServerError::ClientError(error) => error.to_string(),
+            ApiServerWebServerError::NotFound(error) => error.to_string(),
+            ApiServerWebServerError::Forbidden(error) => error.to_string(),
+            ApiServerWebServerError::ServerError(error) => error.to_string(),
+            ApiServerWebServerError::TooManyRequests => error.to_string(),
         };
 
-        (status, Json(json!({ "error": message }))).into_response()
+        Self {
+            code: error.code(),
+            message: message.clone(),
+            details: error.details(),
+            retryable: error.retryable(),
+            error: message,
+        }
+    }
+}
+
+impl IntoResponse for ApiServerWebServerError {
+    fn into_response(self) -> Response {
+        (self.status_code(), Json(ApiErrorBody::from(&self))).into_response()
     }
 }
@@ -16,6 +16,7 @@
 pub mod api;
 pub mod config;
 pub mod error;
+pub mod token_metadata;
 
 pub use error::ApiServerWebServerError;
 
@@ -19,8 +19,10 @@ mod error;
 
 use api_server_common::storage::impls::postgres::TransactionalApiServerPostgresStorage;
 use api_web_server::{
-    api::web_server, config::ApiServerWebServerConfig, ApiServerWebServerState, CachedValues,
-    TxSubmitClient,
+    api::web_server_with_finality_depth,
+    config::ApiServerWebServerConfig,
+    token_metadata::{run_token_metadata_fetcher, TokenMetadataFetcherConfig},
+    ApiServerWebServerState, CachedValues, TxSubmitClient,
 };
 use clap::Parser;
 use common::{
@@ -31,7 +33,10 @@ use logging::log;
 use node_comm::make_rpc_client;
 use node_lib::default_rpc_config;
 use rpc::RpcAuthData;
-use std::sync::{Arc, RwLock};
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 use utils::{cookie::COOKIE_FILENAME, default_data_dir::default_data_dir_for_chain};
 
 use crate::error::ApiServerWebServerInitError;
@@ -50,17 +55,38 @@ async fn main() -> Result<(), ApiServerWebServerInitError> {
     let chain_type: ChainType = args.network.into();
     let chain_config = Arc::new(Builder::new(chain_type).build());
 
-    let storage = TransactionalApiServerPostgresStorage::new(
-        &args.postgres_config.postgres_host,
-        args.postgres_config.postgres_port,
-        &args.postgres_config.postgres_user,
-        args.postgres_config.postgres_password.as_deref(),
-        args.postgres_config.postgres_database.as_deref(),
-        args.postgres_config.postgres_max_connections,
-        chain_config.clone(),
-    )
-    .await
-    .map_err(ApiServerWebServerInitError::PostgresConnectionError)?;
+    let make_storage = || {
+        TransactionalApiServerPostgresStorage::new(
+            &args.postgres_config.postgres_host,
+            args.postgres_config.postgres_port,
+            &args.postgres_config.postgres_user,
+            args.postgres_config.postgres_password.as_deref(),
+            args.postgres_config.postgres_database.as_deref(),
+            args.postgres_config.postgres_max_connections,
+            chain_config.clone(),
+        )
+    };
+
+    let storage = make_storage()
+        .await
+        .map_err(ApiServerWebServerInitError::PostgresConnectionError)?;
+
+    if args.enable_token_metadata_fetcher {
+        // The fetcher needs its own storage instance because it writes to it
+        let fetcher_storage = make_storage()
+            .await
+            .map_err(ApiServerWebServerInitError::PostgresConnectionError)?;
+        let fetcher_config = TokenMetadataFetcherConfig {
+            max_size: args.token_metadata_max_size,
+            timeout: Duration::from_secs(args.token_metadata_fetch_timeout),
+            refresh_interval: Duration::from_secs(args.token_metadata_refresh_interval),
+        };
+        tokio::spawn(run_token_metadata_fetcher(
+            fetcher_storage,
+            fetcher_config,
+            Default::default(),
+        ));
+    }
 
     let rpc_client = {
         let rpc_auth = match (
@@ -103,10 +129,11 @@ async fn main() -> Result<(), ApiServerWebServerInitError> {
         time_getter: Default::default(),
     };
 
-    web_server(
+    web_server_with_finality_depth(
         args.bind_address.unwrap_or_default().tcp_listener().await,
         state,
         args.enable_post_routes,
+        args.finality_depth,
     )
     .await
     .expect("API Server Web Server failed");
@@ -0,0 +1,455 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A background task that fetches the contents of the token and NFT metadata URIs and caches
+//! a sanitized copy of them in the storage, so that explorer front-ends can get them
+//! from the API server instead of making browsers hit arbitrary third-party URLs.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use api_server_common::storage::storage_api::{
+    ApiServerStorage, ApiServerStorageError, ApiServerStorageRead, ApiServerStorageWrite,
+    ApiServerTransactionRw, TokenMetadataCacheEntry, TokenMetadataContent,
+};
+use common::{
+    chain::{
+        block::timestamp::BlockTimestamp,
+        tokens::{NftIssuance, TokenId},
+        GenBlock,
+    },
+    primitives::{BlockHeight, Id},
+    time_getter::TimeGetter,
+};
+use logging::log;
+use p2p_types::IsGlobalIp;
+use reqwest::{header, redirect, Client, Url};
+use utils::ensure;
+
+/// How often the new and the due tokens are checked for missing or stale metadata
+const FETCH_ROUND_INTERVAL: Duration = Duration::from_secs(60);
+/// The number of token ids read from the storage at once
+const TOKEN_IDS_PAGE_SIZE: u32 = 100;
+/// The maximum number of metadata URIs fetched in one round
+const MAX_FETCHES_PER_ROUND: usize = 100;
+/// The maximum number of redirects followed when fetching a metadata URI
+const MAX_REDIRECTS: usize = 3;
+/// How long to wait before retrying a failed fetch
+const FAILED_FETCH_RETRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+const JSON_CONTENT_TYPE: &str = "application/json";
+
+#[derive(Debug, Clone)]
+pub struct TokenMetadataFetcherConfig {
+    /// The maximum size of the fetched contents in bytes
+    pub max_size: usize,
+    /// The timeout for fetching a single metadata URI, including redirects
+    pub timeout: Duration,
+    /// How often the successfully fetched contents are refreshed
+    pub refresh_interval: Duration,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum TokenMetadataFetchError {
+    #[error("Invalid URI")]
+    InvalidUri,
+    #[error("Unsupported URI scheme: {0}")]
+    UnsupportedScheme(String),
+    #[error("Forbidden host: {0}")]
+    ForbiddenHost(String),
+    #[error("Failed to resolve host {0}")]
+    HostResolutionFailed(String),
+    #[error("HTTP error: {0}")]
+    HttpError(String),
+    #[error("Unexpected HTTP status: {0}")]
+    BadStatus(u16),
+    #[error("Too many redirects")]
+    TooManyRedirects,
+    #[error("Timeout")]
+    Timeout,
+    #[error("Unsupported content type: {0}")]
+    UnsupportedContentType(String),
+    #[error("The contents exceed the size limit of {0} bytes")]
+    TooLarge(usize),
+    #[error("Invalid JSON: {0}")]
+    InvalidJson(String),
+}
+
+impl From<reqwest::Error> for TokenMetadataFetchError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::HttpError(e.to_string())
+    }
+}
+
+/// The tokens whose metadata has to be checked, by the time the check is due.
+/// Only the tokens issued or changed since the previous round are read from the storage,
+/// all the tokens are only read in the first round and after a reorg.
+struct FetchSchedule {
+    /// The best block up to which the changed tokens have been scheduled
+    scanned_tip: Option<(BlockHeight, Id<GenBlock>)>,
+    due: BTreeSet<(BlockTimestamp, TokenId)>,
+    due_times: BTreeMap<TokenId, BlockTimestamp>,
+}
+
+impl FetchSchedule {
+    fn new() -> Self {
+        Self {
+            scanned_tip: None,
+            due: BTreeSet::new(),
+            due_times: BTreeMap::new(),
+        }
+    }
+
+    fn schedule(&mut self, token_id: TokenId, due_time: BlockTimestamp) {
+        if let Some(old_due_time) = self.due_times.insert(token_id, due_time) {
+            self.due.remove(&(old_due_time, token_id));
+        }
+        self.due.insert((due_time, token_id));
+    }
+
+    fn pop_due(&mut self, now: BlockTimestamp) -> Option<TokenId> {
+        let (due_time, _) = self.due.first()?;
+        if *due_time > now {
+            return None;
+        }
+
+        let (_, token_id) = self.due.pop_first()?;
+        self.due_times.remove(&token_id);
+        Some(token_id)
+    }
+}
+
+/// Periodically fetch the metadata of the tokens that have none cached yet, whose metadata URI
+/// has changed or whose cached metadata is stale.
+pub async fn run_token_metadata_fetcher<S: ApiServerStorage>(
+    mut storage: S,
+    config: TokenMetadataFetcherConfig,
+    time_getter: TimeGetter,
+) {
+    let mut schedule = FetchSchedule::new();
+
+    loop {
+        if let Err(e) = fetch_round(&mut storage, &config, &time_getter, &mut schedule).await {
+            log::error!("Token metadata fetcher storage error: {e}");
+        }
+
+        tokio::time::sleep(FETCH_ROUND_INTERVAL).await;
+    }
+}
+
+async fn fetch_round<S: ApiServerStorage>(
+    storage: &mut S,
+    config: &TokenMetadataFetcherConfig,
+    time_getter: &TimeGetter,
+    schedule: &mut FetchSchedule,
+) -> Result<(), ApiServerStorageError> {
+    let now = BlockTimestamp::from_time(time_getter.get_time());
+    schedule_changed_tokens(storage, schedule, now).await?;
+    let to_fetch = collect_tokens_to_fetch(storage, config, schedule, now).await?;
+
+    for (token_id, uri) in to_fetch {
+        let content = match fetch_metadata(&uri, config).await {
+            Ok(content) => TokenMetadataContent::Fetched {
+                content_type: JSON_CONTENT_TYPE.to_owned(),
+                content,
+            },
+            Err(e) => {
+                log::debug!("Fetching metadata of token {token_id} failed: {e}");
+                TokenMetadataContent::Failed(e.to_string())
+            }
+        };
+
+        let entry = TokenMetadataCacheEntry {
+            uri,
+            content,
+            fetched_at: BlockTimestamp::from_time(time_getter.get_time()),
+        };
+
+        let mut db_tx = storage.transaction_rw().await?;
+        db_tx.set_token_metadata_cache(token_id, &entry).await?;
+        db_tx.commit().await?;
+
+        schedule.schedule(token_id, next_fetch_time(Some(&entry), &entry.uri, config));
+    }
+
+    Ok(())
+}
+
+/// Schedule an immediate check of the tokens issued or changed since the previous round
+async fn schedule_changed_tokens<S: ApiServerStorage>(
+    storage: &S,
+    schedule: &mut FetchSchedule,
+    now: BlockTimestamp,
+) -> Result<(), ApiServerStorageError> {
+    let db_tx = storage.transaction_ro().await?;
+
+    // The best block is read first, so that the tokens changed in the meantime are read again
+    // in the next round
+    let best_block = db_tx.get_best_block().await?;
+
+    let scanned_height = match schedule.scanned_tip {
+        Some((height, block_id)) => {
+            let main_chain_block_id = db_tx.get_main_chain_block_id(height).await?;
+            let on_main_chain = match main_chain_block_id {
+                Some(main_chain_block_id) => Id::<GenBlock>::from(main_chain_block_id) == block_id,
+                None => height == BlockHeight::zero(),
+            };
+            on_main_chain.then_some(height)
+        }
+        None => None,
+    };
+
+    let token_ids = match scanned_height {
+        Some(height) => db_tx.get_token_ids_changed_above_height(height).await?,
+        None => {
+            let mut token_ids = Vec::new();
+            loop {
+                let page = db_tx.get_token_ids(TOKEN_IDS_PAGE_SIZE, token_ids.len() as u32).await?;
+                if page.is_empty() {
+                    break;
+                }
+                token_ids.extend(page);
+            }
+            token_ids
+        }
+    };
+
+    for token_id in token_ids {
+        schedule.schedule(token_id, now);
+    }
+    schedule.scanned_tip = Some((best_block.block_height(), best_block.block_id()));
+
+    Ok(())
+}
+
+/// Take the due tokens from the schedule and return those whose metadata has to be fetched;
+/// the others are scheduled again for when their cached metadata becomes stale
+async fn collect_tokens_to_fetch<S: ApiServerStorage>(
+    storage: &S,
+    config: &TokenMetadataFetcherConfig,
+    schedule: &mut FetchSchedule,
+    now: BlockTimestamp,
+) -> Result<Vec<(TokenId, Vec<u8>)>, ApiServerStorageError> {
+    let db_tx = storage.transaction_ro().await?;
+
+    let mut result = Vec::new();
+    while result.len() < MAX_FETCHES_PER_ROUND {
+        let token_id = match schedule.pop_due(now) {
+            Some(token_id) => token_id,
+            None => break,
+        };
+
+        let uri = match db_tx.get_fungible_token_issuance(token_id).await? {
+            Some(data) => data.metadata_uri,
+            None => match db_tx.get_nft_token_issuance(token_id).await? {
+                Some(NftIssuance::V0(data)) => data
+                    .metadata
+                    .additional_metadata_uri
+                    .as_opt_slice()
+                    .map_or_else(Vec::new, |uri| uri.to_vec()),
+                None => continue,
+            },
+        };
+        // The token is scheduled again if its metadata URI gets changed
+        if uri.is_empty() {
+            continue;
+        }
+
+        let entry = db_tx.get_token_metadata_cache(token_id).await?;
+        let due_time = next_fetch_time(entry.as_ref(), &uri, config);
+        if due_time <= now {
+            result.push((token_id, uri));
+        } else {
+            schedule.schedule(token_id, due_time);
+        }
+    }
+
+    Ok(result)
+}
+
+/// The time at which the metadata with the given URI has to be fetched, given the cached one
+fn next_fetch_time(
+    entry: Option<&TokenMetadataCacheEntry>,
+    uri: &[u8],
+    config: &TokenMetadataFetcherConfig,
+) -> BlockTimestamp {
+    match entry {
+        Some(entry) if entry.uri == uri => {
+            let interval = match entry.content {
+                TokenMetadataContent::Fetched { .. } => config.refresh_interval,
+                TokenMetadataContent::Failed(_) => {
+                    FAILED_FETCH_RETRY_INTERVAL.min(config.refresh_interval)
+                }
+            };
+            BlockTimestamp::from_int_seconds(
+                entry.fetched_at.as_int_seconds().saturating_add(interval.as_secs()),
+            )
+        }
+        Some(_) | None => BlockTimestamp::from_int_seconds(0),
+    }
+}
+
+/// Fetch the contents of the URI and return them as sanitized JSON
+pub async fn fetch_metadata(
+    uri: &[u8],
+    config: &TokenMetadataFetcherConfig,
+) -> Result<Vec<u8>, TokenMetadataFetchError> {
+    let uri = std::str::from_utf8(uri).map_err(|_| TokenMetadataFetchError::InvalidUri)?;
+    let url = Url::parse(uri.trim()).map_err(|_| TokenMetadataFetchError::InvalidUri)?;
+
+    tokio::time::timeout(
+        config.timeout,
+        fetch_following_redirects(url, config.max_size),
+    )
+    .await
+    .map_err(|_| TokenMetadataFetchError::Timeout)?
+}
+
+async fn fetch_following_redirects(
+    mut url: Url,
+    max_size: usize,
+) -> Result<Vec<u8>, TokenMetadataFetchError> {
+    for _ in 0..=MAX_REDIRECTS {
+        let client = make_client(&url).await?;
+        let response = client.get(url.clone()).send().await?;
+
+        if response.status().is_redirection() {
+            let location = response
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .ok_or(TokenMetadataFetchError::BadStatus(
+                    response.status().as_u16(),
+                ))?;
+            url = url.join(location).map_err(|_| TokenMetadataFetchError::InvalidUri)?;
+            continue;
+        }
+
+        return read_json_response(response, max_size).await;
+    }
+
+    Err(TokenMetadataFetchError::TooManyRedirects)
+}
+
+/// Make a client that only connects to the checked addresses of the URL's host, so that
+/// the host can't be re-resolved to a local address between the check and the request.
+/// Redirects are not followed automatically, because their targets have to be checked too.
+async fn make_client(url: &Url) -> Result<Client, TokenMetadataFetchError> {
+    match url.scheme() {
+        "http" | "https" => {}
+        scheme => {
+            return Err(TokenMetadataFetchError::UnsupportedScheme(
+                scheme.to_owned(),
+            ))
+        }
+    }
+
+    let host = url.host_str().ok_or(TokenMetadataFetchError::InvalidUri)?;
+    let port = url.port_or_known_default().ok_or(TokenMetadataFetchError::InvalidUri)?;
+
+    let builder = Client::builder().redirect(redirect::Policy::none());
+
+    let builder =
+        if let Ok(ip) = host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+            check_ip(host, &ip)?;
+            builder
+        } else {
+            let lowercase_host = host.to_ascii_lowercase();
+            if lowercase_host == "localhost" || lowercase_host.ends_with(".localhost") {
+                return Err(TokenMetadataFetchError::ForbiddenHost(host.to_owned()));
+            }
+
+            let addresses = tokio::net::lookup_host((host, port))
+                .await
+                .map_err(|_| TokenMetadataFetchError::HostResolutionFailed(host.to_owned()))?
+                .collect::<Vec<SocketAddr>>();
+            if addresses.is_empty() {
+                return Err(TokenMetadataFetchError::HostResolutionFailed(
+                    host.to_owned(),
+                ));
+            }
+
+            addresses.iter().try_fold(builder, |builder, address| {
+                check_ip(host, &address.ip())?;
+                Ok(builder.resolve(host, *address))
+            })?
+        };
+
+    Ok(builder.build()?)
+}
+
+fn check_ip(host: &str, ip: &IpAddr) -> Result<(), TokenMetadataFetchError> {
+    ensure!(
+        ip.is_global_unicast_ip(),
+        TokenMetadataFetchError::ForbiddenHost(host.to_owned())
+    );
+    Ok(())
+}
+
+async fn read_json_response(
+    mut response: reqwest::Response,
+    max_size: usize,
+) -> Result<Vec<u8>, TokenMetadataFetchError> {
+    if !response.status().is_success() {
+        return Err(TokenMetadataFetchError::BadStatus(
+            response.status().as_u16(),
+        ));
+    }
+
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .unwrap_or_default();
+    check_content_type(content_type)?;
+
+    if response.content_length().is_some_and(|len| len > max_size as u64) {
+        return Err(TokenMetadataFetchError::TooLarge(max_size));
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        ensure!(
+            body.len() + chunk.len() <= max_size,
+            TokenMetadataFetchError::TooLarge(max_size)
+        );
+        body.extend_from_slice(&chunk);
+    }
+
+    sanitize_json(&body)
+}
+
+/// Only JSON metadata is accepted
+pub fn check_content_type(content_type: &str) -> Result<(), TokenMetadataFetchError> {
+    let mime_type = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+
+    ensure!(
+        mime_type == JSON_CONTENT_TYPE
+            || (mime_type.starts_with("application/") && mime_type.ends_with("+json")),
+        TokenMetadataFetchError::UnsupportedContentType(content_type.to_owned())
+    );
+    Ok(())
+}
+
+/// Parse the contents and serialize them again, so that only well-formed JSON is served
+pub fn sanitize_json(body: &[u8]) -> Result<Vec<u8>, TokenMetadataFetchError> {
+    let value: serde_json::Value = serde_json::from_slice(body)
+        .map_err(|e| TokenMetadataFetchError::InvalidJson(e.to_string()))?;
+
+    Ok(serde_json::to_vec(&value).expect("JSON value serialization can't fail"))
+}
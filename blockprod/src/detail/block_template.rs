@@ -0,0 +1,187 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{cmp, sync::Arc};
+
+use chainstate::ChainstateHandle;
+use common::{
+    chain::{
+        block::timestamp::BlockTimestamp, ChainConfig, GenBlock, RequiredConsensus,
+        SignedTransaction,
+    },
+    primitives::{amount::RpcAmountOut, BlockHeight, Id, H256},
+    time_getter::TimeGetter,
+};
+use consensus::{
+    calculate_target_required_from_block_index, calculate_work_required, ConsensusCreationError,
+};
+use mempool::{
+    tx_accumulator::{PackingStrategy, TxAccumulatorPolicy},
+    MempoolHandle,
+};
+use serialization::hex_encoded::HexEncoded;
+
+use crate::BlockProductionError;
+
+use super::utils::{
+    calculate_median_time_past, collect_transactions_with_fees, get_best_block_index,
+    get_sealed_epoch_randomness, make_ancestor_getter, timestamp_add_secs,
+};
+
+/// The consensus rules the header of the next block has to satisfy
+#[derive(
+    Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint,
+)]
+#[serde(tag = "type", content = "content")]
+pub enum BlockTemplateConsensus {
+    /// The block must have no consensus data
+    IgnoreConsensus,
+    /// The block must be mined with the given compact difficulty target
+    PoW { target_bits: u32 },
+    /// The block must be staked with the given compact target, using the randomness of the
+    /// last sealed epoch for the VRF
+    PoS {
+        target_bits: u32,
+        sealed_epoch_randomness: H256,
+    },
+}
+
+/// Everything an external block producer needs to build the next block on top of the current tip
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
+pub struct BlockTemplate {
+    /// The block the new block has to be built on
+    pub prev_block_id: Id<GenBlock>,
+    /// The height of the new block
+    pub height: BlockHeight,
+    /// The earliest timestamp the new block may have
+    pub min_timestamp: BlockTimestamp,
+    /// The latest timestamp the new block may have for it to be accepted by the node now
+    pub max_timestamp: BlockTimestamp,
+    /// The time lock constraints of the transactions are checked against this timestamp
+    pub median_time_past: BlockTimestamp,
+    pub consensus: BlockTemplateConsensus,
+    /// The amount of new coins the block reward may create
+    pub block_subsidy: RpcAmountOut,
+    /// The sum of the fees of the transactions, which may be added to the block reward
+    pub total_fees: RpcAmountOut,
+    /// The transactions to include in the block, in the given order
+    pub transactions: Vec<HexEncoded<SignedTransaction>>,
+}
+
+pub async fn make_block_template(
+    chain_config: &Arc<ChainConfig>,
+    chainstate_handle: &ChainstateHandle,
+    mempool_handle: &MempoolHandle,
+    time_getter: &TimeGetter,
+) -> Result<BlockTemplate, BlockProductionError> {
+    let current_time = BlockTimestamp::from_time(time_getter.get_time());
+
+    let (best_block_index, median_time_past, consensus) = chainstate_handle
+        .call({
+            let chain_config = Arc::clone(chain_config);
+
+            move |cs| -> Result<_, BlockProductionError> {
+                let best_block_index = get_best_block_index(cs)?;
+                let median_time_past =
+                    calculate_median_time_past(cs, &best_block_index.block_id())?;
+                let block_height = best_block_index.block_height().next_height();
+
+                let consensus = match chain_config
+                    .consensus_upgrades()
+                    .consensus_status(block_height)
+                {
+                    RequiredConsensus::IgnoreConsensus => BlockTemplateConsensus::IgnoreConsensus,
+                    RequiredConsensus::PoW(pow_status) => {
+                        let target = calculate_work_required(
+                            &chain_config,
+                            &best_block_index,
+                            current_time,
+                            &pow_status,
+                            make_ancestor_getter(cs),
+                        )
+                        .map_err(ConsensusCreationError::MiningError)?;
+
+                        BlockTemplateConsensus::PoW {
+                            target_bits: target.0,
+                        }
+                    }
+                    RequiredConsensus::PoS(pos_status) => {
+                        let target = calculate_target_required_from_block_index(
+                            &chain_config,
+                            &pos_status,
+                            &best_block_index,
+                            make_ancestor_getter(cs),
+                        )
+                        .map_err(ConsensusCreationError::StakingError)?;
+                        let sealed_epoch_randomness =
+                            get_sealed_epoch_randomness(&chain_config, cs, block_height)?;
+
+                        BlockTemplateConsensus::PoS {
+                            target_bits: target.0,
+                            sealed_epoch_randomness: sealed_epoch_randomness.value(),
+                        }
+                    }
+                };
+
+                Ok((best_block_index, median_time_past, consensus))
+            }
+        })
+        .await??;
+
+    let height = best_block_index.block_height().next_height();
+
+    // PoS additionally requires the timestamp to be greater than the timestamp of the parent
+    let min_timestamp = match consensus {
+        BlockTemplateConsensus::IgnoreConsensus | BlockTemplateConsensus::PoW { .. } => {
+            median_time_past
+        }
+        BlockTemplateConsensus::PoS { .. } => cmp::max(
+            median_time_past,
+            timestamp_add_secs(best_block_index.block_timestamp(), 1)?,
+        ),
+    };
+    let max_timestamp = timestamp_add_secs(
+        current_time,
+        chain_config.max_future_block_time_offset(height).as_secs(),
+    )?;
+
+    let (transactions, total_fees) = collect_transactions_with_fees(
+        mempool_handle,
+        chain_config,
+        best_block_index.block_id(),
+        median_time_past,
+        vec![],
+        vec![],
+        PackingStrategy::FillSpaceFromMempool,
+        TxAccumulatorPolicy::default(),
+    )
+    .await?
+    .ok_or(BlockProductionError::RecoverableMempoolError)?;
+
+    Ok(BlockTemplate {
+        prev_block_id: best_block_index.block_id(),
+        height,
+        min_timestamp,
+        max_timestamp,
+        median_time_past,
+        consensus,
+        block_subsidy: RpcAmountOut::from_amount(
+            chain_config.block_subsidy_at_height(&height),
+            chain_config.coin_decimals(),
+        ),
+        total_fees: RpcAmountOut::from_amount(total_fees.0, chain_config.coin_decimals()),
+        transactions: transactions.into_iter().map(HexEncoded::new).collect(),
+    })
+}
@@ -13,6 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod block_template;
 pub mod job_manager;
 pub mod timestamp_searcher;
 pub mod utils;
@@ -24,7 +25,7 @@ use std::{
 
 use tokio::sync::oneshot;
 
-use chainstate::{chainstate_interface::ChainstateInterface, ChainstateHandle};
+use chainstate::{chainstate_interface::ChainstateInterface, BlockSource, ChainstateHandle};
 use chainstate_types::{pos_randomness::PoSRandomness, GenBlockIndex};
 use common::{
     chain::{
@@ -44,13 +45,17 @@ use consensus::{
     PoSGenerateBlockInputData,
 };
 use crypto::ephemeral_e2e::{self, EndToEndPrivateKey};
-use mempool::{tx_accumulator::PackingStrategy, MempoolHandle};
+use mempool::{
+    tx_accumulator::{PackingStrategy, TxAccumulatorPolicy},
+    MempoolHandle,
+};
 use p2p::P2pHandle;
 use randomness::{make_true_rng, Rng};
 use serialization::{Decode, Encode};
 
 use ::utils::{
     atomics::{AcqRelAtomicU64, RelaxedAtomicBool},
+    ensure,
     once_destructor::OnceDestructor,
 };
 
@@ -64,6 +69,7 @@ use crate::{
 };
 
 use self::{
+    block_template::{make_block_template, BlockTemplate},
     timestamp_searcher::TimestampSearchData,
     utils::{
         calculate_median_time_past, get_best_block_index, get_pool_staker_balance,
@@ -361,12 +367,33 @@ impl BlockProduction {
         transactions: Vec<SignedTransaction>,
         transaction_ids: Vec<Id<Transaction>>,
         packing_strategy: PackingStrategy,
+    ) -> Result<(Block, oneshot::Receiver<usize>), BlockProductionError> {
+        self.produce_block_with_policy(
+            input_data,
+            transactions,
+            transaction_ids,
+            packing_strategy,
+            TxAccumulatorPolicy::default(),
+        )
+        .await
+    }
+
+    /// Same as `produce_block`, but the transactions taken from the mempool
+    /// are additionally constrained by the given policy.
+    pub async fn produce_block_with_policy(
+        &self,
+        input_data: GenerateBlockInputData,
+        transactions: Vec<SignedTransaction>,
+        transaction_ids: Vec<Id<Transaction>>,
+        packing_strategy: PackingStrategy,
+        tx_accumulator_policy: TxAccumulatorPolicy,
     ) -> Result<(Block, oneshot::Receiver<usize>), BlockProductionError> {
         self.produce_block_with_custom_id(
             input_data,
             transactions,
             transaction_ids,
             packing_strategy,
+            tx_accumulator_policy,
             None,
         )
         .await
@@ -404,6 +431,7 @@ impl BlockProduction {
         transactions: Vec<SignedTransaction>,
         transaction_ids: Vec<Id<Transaction>>,
         packing_strategy: PackingStrategy,
+        tx_accumulator_policy: TxAccumulatorPolicy,
         custom_id_maybe: Option<Vec<u8>>,
     ) -> Result<(Block, oneshot::Receiver<usize>), BlockProductionError> {
         self.ensure_can_produce_block().await?;
@@ -487,6 +515,7 @@ impl BlockProduction {
             transactions.clone(),
             transaction_ids.clone(),
             packing_strategy,
+            tx_accumulator_policy,
         )
         .await?
         .ok_or(BlockProductionError::RecoverableMempoolError)?;
@@ -610,6 +639,40 @@ impl BlockProduction {
         Ok(())
     }
 
+    /// Make a template of the next block on top of the current tip, for external block producers
+    pub async fn block_template(&self) -> Result<BlockTemplate, BlockProductionError> {
+        self.ensure_can_produce_block().await?;
+
+        make_block_template(
+            &self.chain_config,
+            &self.chainstate_handle,
+            &self.mempool_handle,
+            &self.time_getter,
+        )
+        .await
+    }
+
+    /// Process a block built by an external block producer from a block template.
+    ///
+    /// The block must be built on top of the current tip; if the tip has changed since
+    /// the template was made, the block is rejected and a new template has to be requested.
+    pub async fn submit_block(&self, block: Block) -> Result<(), BlockProductionError> {
+        let tip_id = self.pull_best_block_index().await?.block_id();
+        let prev_block_id = block.prev_block_id();
+
+        ensure!(
+            prev_block_id == tip_id,
+            BlockProductionError::StaleBlockTemplate(prev_block_id, tip_id)
+        );
+
+        self.chainstate_handle
+            .call_mut(move |cs| cs.process_block(block, BlockSource::Local))
+            .await?
+            .map_err(BlockProductionError::SubmittedBlockRejected)?;
+
+        Ok(())
+    }
+
     pub fn e2e_private_key(&self) -> &ephemeral_e2e::EndToEndPrivateKey {
         &self.e2e_encryption_key
     }
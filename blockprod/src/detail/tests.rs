@@ -33,8 +33,8 @@ use common::{
         stakelock::StakePoolData,
         timelock::OutputTimeLock,
         transaction::TxInput,
-        CoinUnit, ConsensusUpgrade, Destination, Genesis, NetUpgrades, OutPointSourceId, PoolId,
-        RequiredConsensus, TxOutput,
+        Block, CoinUnit, ConsensusUpgrade, Destination, Genesis, NetUpgrades, OutPointSourceId,
+        PoolId, RequiredConsensus, TxOutput,
     },
     primitives::{per_thousand::PerThousand, Amount, BlockHeight, Id, Idable, H256},
     time_getter::TimeGetter,
@@ -50,7 +50,7 @@ use crypto::{
 };
 use mempool::{
     error::{BlockConstructionError, TxValidationError},
-    tx_accumulator::{DefaultTxAccumulator, PackingStrategy},
+    tx_accumulator::{DefaultTxAccumulator, PackingStrategy, TxAccumulatorPolicy},
     tx_origin::LocalTxOrigin,
     TxOptions,
 };
@@ -65,6 +65,7 @@ use utils::once_destructor::OnceDestructor;
 
 use crate::{
     detail::{
+        block_template::BlockTemplateConsensus,
         collect_transactions,
         job_manager::{tests::MockJobManager, JobManagerError, JobManagerImpl},
         CustomId, GenerateBlockInputData,
@@ -111,6 +112,7 @@ mod collect_transactions {
                 vec![],
                 vec![],
                 PackingStrategy::FillSpaceFromMempool,
+                TxAccumulatorPolicy::default(),
             )
             .await;
 
@@ -158,6 +160,7 @@ mod collect_transactions {
                 vec![],
                 vec![],
                 PackingStrategy::LeaveEmptySpace,
+                TxAccumulatorPolicy::default(),
             )
             .await;
 
@@ -209,6 +212,7 @@ mod collect_transactions {
                     vec![],
                     vec![],
                     PackingStrategy::FillSpaceFromMempool,
+                    TxAccumulatorPolicy::default(),
                 )
                 .await;
 
@@ -1920,6 +1924,7 @@ mod process_block_with_custom_id {
                         vec![],
                         vec![],
                         PackingStrategy::LeaveEmptySpace,
+                        TxAccumulatorPolicy::default(),
                         Some(id),
                     )
                 });
@@ -1986,6 +1991,7 @@ mod process_block_with_custom_id {
                             vec![],
                             vec![],
                             PackingStrategy::LeaveEmptySpace,
+                            TxAccumulatorPolicy::default(),
                             Some(id.clone()),
                         )
                         .await;
@@ -2332,6 +2338,84 @@ mod stop_job {
     }
 }
 
+mod block_template {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn make_and_submit() {
+        let (manager, chain_config, chainstate, mempool, p2p) =
+            setup_blockprod_test(None, TimeGetter::default());
+
+        let join_handle = tokio::spawn({
+            let shutdown_trigger = manager.make_shutdown_trigger();
+            async move {
+                // Ensure a shutdown signal will be sent by the end of the scope
+                let _shutdown_signal = OnceDestructor::new(move || {
+                    shutdown_trigger.initiate();
+                });
+
+                let block_production = BlockProduction::new(
+                    Arc::clone(&chain_config),
+                    Arc::new(test_blockprod_config()),
+                    chainstate.clone(),
+                    mempool,
+                    p2p,
+                    Default::default(),
+                    prepare_thread_pool(1),
+                )
+                .expect("Error initializing blockprod");
+
+                let template = block_production.block_template().await.unwrap();
+
+                let genesis_id = chain_config.genesis_block_id();
+                assert_eq!(template.prev_block_id, genesis_id);
+                assert_eq!(template.height, BlockHeight::new(1));
+                assert_eq!(template.consensus, BlockTemplateConsensus::IgnoreConsensus);
+                assert!(template.min_timestamp <= template.max_timestamp);
+                assert_eq!(
+                    template.block_subsidy.amount(),
+                    chain_config.block_subsidy_at_height(&BlockHeight::new(1))
+                );
+                assert_eq!(template.total_fees.amount(), Amount::ZERO);
+                assert!(template.transactions.is_empty());
+
+                let (consensus_data, block_reward) =
+                    consensus::generate_consensus_data_and_reward_ignore_consensus(
+                        &chain_config,
+                        template.height,
+                    )
+                    .unwrap();
+                let block = Block::new(
+                    vec![],
+                    template.prev_block_id,
+                    template.min_timestamp,
+                    consensus_data,
+                    block_reward,
+                )
+                .unwrap();
+                let block_id = block.get_id();
+
+                block_production.submit_block(block.clone()).await.unwrap();
+
+                let best_block_id = chainstate.call(|cs| cs.get_best_block_id()).await.unwrap();
+                assert_eq!(best_block_id, Ok(block_id.into()));
+
+                // The same block is now built on a stale tip
+                assert_eq!(
+                    block_production.submit_block(block).await,
+                    Err(BlockProductionError::StaleBlockTemplate(
+                        genesis_id,
+                        block_id.into()
+                    ))
+                );
+            }
+        });
+
+        manager.main().await;
+        join_handle.await.unwrap();
+    }
+}
+
 async fn assert_job_count(block_production: &BlockProduction, expected_jobs_count: usize) {
     // try for a sufficient amount of time before giving up with an error
     for _ in 1..100 {
@@ -27,10 +27,12 @@ use common::{
         Block, ChainConfig, GenBlock, PoSStatus, PoolId, RequiredConsensus, SignedTransaction,
         Transaction,
     },
-    primitives::{Amount, BlockHeight, Id, Idable},
+    primitives::{Amount, BlockHeight, Fee, Id, Idable},
 };
 use mempool::{
-    tx_accumulator::{DefaultTxAccumulator, PackingStrategy, TransactionAccumulator},
+    tx_accumulator::{
+        DefaultTxAccumulator, PackingStrategy, TransactionAccumulator, TxAccumulatorPolicy,
+    },
     MempoolHandle,
 };
 
@@ -297,12 +299,43 @@ pub async fn collect_transactions(
     transactions: Vec<SignedTransaction>,
     transaction_ids: Vec<Id<Transaction>>,
     packing_strategy: PackingStrategy,
+    policy: TxAccumulatorPolicy,
 ) -> Result<Option<Vec<SignedTransaction>>, BlockProductionError> {
-    let mut accumulator = Box::new(DefaultTxAccumulator::new(
-        chain_config.max_block_size_from_std_scripts(),
+    let transactions = collect_transactions_with_fees(
+        mempool_handle,
+        chain_config,
         current_tip,
         current_tip_median_time_past,
-    ));
+        transactions,
+        transaction_ids,
+        packing_strategy,
+        policy,
+    )
+    .await?;
+
+    Ok(transactions.map(|(transactions, _total_fees)| transactions))
+}
+
+/// Same as `collect_transactions`, but also return the total fee of the collected transactions.
+/// The fees of the explicitly provided `transactions` are unknown and are not counted.
+pub async fn collect_transactions_with_fees(
+    mempool_handle: &MempoolHandle,
+    chain_config: &ChainConfig,
+    current_tip: Id<GenBlock>,
+    current_tip_median_time_past: BlockTimestamp,
+    transactions: Vec<SignedTransaction>,
+    transaction_ids: Vec<Id<Transaction>>,
+    packing_strategy: PackingStrategy,
+    policy: TxAccumulatorPolicy,
+) -> Result<Option<(Vec<SignedTransaction>, Fee)>, BlockProductionError> {
+    let mut accumulator = Box::new(
+        DefaultTxAccumulator::new(
+            chain_config.max_block_size_from_std_scripts(),
+            current_tip,
+            current_tip_median_time_past,
+        )
+        .with_policy(policy),
+    );
 
     for transaction in transactions.into_iter() {
         let transaction_id = transaction.transaction().get_id();
@@ -316,8 +349,12 @@ pub async fn collect_transactions(
         .call(move |mempool| mempool.collect_txs(accumulator, transaction_ids, packing_strategy))
         .await??;
 
-    let transactions = returned_accumulator
-        .map(|returned_accumulator| returned_accumulator.transactions().to_vec());
+    let transactions = returned_accumulator.map(|returned_accumulator| {
+        (
+            returned_accumulator.transactions().to_vec(),
+            returned_accumulator.total_fees(),
+        )
+    });
 
     Ok(transactions)
 }
@@ -13,14 +13,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{detail::job_manager::JobKey, BlockProductionError, TimestampSearchData};
+use crate::{
+    detail::job_manager::JobKey, BlockProductionError, BlockTemplate, TimestampSearchData,
+};
 use common::{
     chain::{Block, PoolId, SignedTransaction, Transaction},
     primitives::{BlockHeight, Id},
 };
 use consensus::GenerateBlockInputData;
 use crypto::ephemeral_e2e;
-use mempool::tx_accumulator::PackingStrategy;
+use mempool::tx_accumulator::{PackingStrategy, TxAccumulatorPolicy};
 
 #[async_trait::async_trait]
 pub trait BlockProductionInterface: Send + Sync {
@@ -41,12 +43,16 @@ pub trait BlockProductionInterface: Send + Sync {
     ///
     /// If `include_mempool` is true, the rest of the block will be
     /// filled with available transactions from the mempool.
+    ///
+    /// The transactions taken from the mempool are additionally constrained
+    /// by `tx_accumulator_policy`, e.g. to require or exclude specific ones.
     async fn generate_block(
         &mut self,
         input_data: GenerateBlockInputData,
         transactions: Vec<SignedTransaction>,
         transaction_ids: Vec<Id<Transaction>>,
         packing_strategy: PackingStrategy,
+        tx_accumulator_policy: TxAccumulatorPolicy,
     ) -> Result<Block, BlockProductionError>;
 
     async fn e2e_public_key(&self) -> ephemeral_e2e::EndToEndPublicKey;
@@ -59,6 +65,7 @@ pub trait BlockProductionInterface: Send + Sync {
         transactions: Vec<SignedTransaction>,
         transaction_ids: Vec<Id<Transaction>>,
         packing_strategy: PackingStrategy,
+        tx_accumulator_policy: TxAccumulatorPolicy,
     ) -> Result<Block, BlockProductionError>;
 
     /// Collect the search data needed by the `timestamp_searcher` module.
@@ -73,4 +80,11 @@ pub trait BlockProductionInterface: Send + Sync {
         seconds_to_check_for_height: u64,
         check_all_timestamps_between_blocks: bool,
     ) -> Result<TimestampSearchData, BlockProductionError>;
+
+    /// Make a template of the next block on top of the current tip, so that the block can be
+    /// built and solved by an external block producer
+    async fn block_template(&self) -> Result<BlockTemplate, BlockProductionError>;
+
+    /// Process a block that was built from a block template
+    async fn submit_block(&mut self, block: Block) -> Result<(), BlockProductionError>;
 }
@@ -15,7 +15,7 @@
 
 use crate::{
     detail::{job_manager::JobKey, BlockProduction},
-    BlockProductionError, TimestampSearchData,
+    BlockProductionError, BlockTemplate, TimestampSearchData,
 };
 use common::{
     chain::{Block, PoolId, SignedTransaction, Transaction},
@@ -23,7 +23,7 @@ use common::{
 };
 use consensus::GenerateBlockInputData;
 use crypto::ephemeral_e2e;
-use mempool::tx_accumulator::PackingStrategy;
+use mempool::tx_accumulator::{PackingStrategy, TxAccumulatorPolicy};
 
 use super::blockprod_interface::BlockProductionInterface;
 
@@ -43,9 +43,16 @@ impl BlockProductionInterface for BlockProduction {
         transactions: Vec<SignedTransaction>,
         transaction_ids: Vec<Id<Transaction>>,
         packing_strategy: PackingStrategy,
+        tx_accumulator_policy: TxAccumulatorPolicy,
     ) -> Result<Block, BlockProductionError> {
         let (block, end_receiver) = self
-            .produce_block(input_data, transactions, transaction_ids, packing_strategy)
+            .produce_block_with_policy(
+                input_data,
+                transactions,
+                transaction_ids,
+                packing_strategy,
+                tx_accumulator_policy,
+            )
             .await?;
 
         // The only error that can happen is if the channel is closed. We don't care about that here.
@@ -65,12 +72,19 @@ impl BlockProductionInterface for BlockProduction {
         transactions: Vec<SignedTransaction>,
         transaction_ids: Vec<Id<Transaction>>,
         packing_strategy: PackingStrategy,
+        tx_accumulator_policy: TxAccumulatorPolicy,
     ) -> Result<Block, BlockProductionError> {
         let shared_secret = self.e2e_private_key().shared_secret(&e2e_public_key);
         let input_data =
             shared_secret.decrypt_then_decode::<GenerateBlockInputData>(&encrypted_input_data)?;
-        self.generate_block(input_data, transactions, transaction_ids, packing_strategy)
-            .await
+        self.generate_block(
+            input_data,
+            transactions,
+            transaction_ids,
+            packing_strategy,
+            tx_accumulator_policy,
+        )
+        .await
     }
 
     async fn collect_timestamp_search_data(
@@ -90,6 +104,14 @@ impl BlockProductionInterface for BlockProduction {
         )
         .await
     }
+
+    async fn block_template(&self) -> Result<BlockTemplate, BlockProductionError> {
+        BlockProduction::block_template(self).await
+    }
+
+    async fn submit_block(&mut self, block: Block) -> Result<(), BlockProductionError> {
+        BlockProduction::submit_block(self, block).await
+    }
 }
 
 impl subsystem::Subsystem for Box<dyn BlockProductionInterface> {
@@ -41,7 +41,10 @@ use mempool::{tx_accumulator::TxAccumulatorError, MempoolHandle};
 use p2p::P2pHandle;
 use subsystem::error::CallError;
 
-pub use detail::timestamp_searcher::{find_timestamps_for_staking, TimestampSearchData};
+pub use detail::{
+    block_template::{BlockTemplate, BlockTemplateConsensus},
+    timestamp_searcher::{find_timestamps_for_staking, TimestampSearchData},
+};
 
 #[derive(thiserror::Error, Debug, PartialEq, Eq)]
 pub enum BlockProductionError {
@@ -101,6 +104,10 @@ pub enum BlockProductionError {
     RecoverableMempoolError,
     #[error("Task exited prematurely")]
     TaskExitedPrematurely,
+    #[error("The block is built on {0}, but the current tip is {1}")]
+    StaleBlockTemplate(Id<GenBlock>, Id<GenBlock>),
+    #[error("The submitted block was rejected: {0}")]
+    SubmittedBlockRejected(chainstate::ChainstateError),
 }
 
 pub type BlockProductionSubsystem = Box<dyn BlockProductionInterface>;
@@ -209,7 +216,8 @@ mod tests {
                                 tip_sx.send(()).unwrap();
                             }
                         }
-                        mempool::event::MempoolEvent::TransactionProcessed(_) => (),
+                        mempool::event::MempoolEvent::TransactionProcessed(_)
+                        | mempool::event::MempoolEvent::MinFeeRateChanged(_) => (),
                     }
                 }))
             })
@@ -270,10 +278,15 @@ mod tests {
             // by the heavy checks in chainstate. But since the checks are not very useful in blockprod
             // tests in general, we disable them globally.
             enable_heavy_checks: Some(false),
+            defer_txs_during_ibd: None,
+            enable_tx_index: None,
 
             max_db_commit_attempts: Default::default(),
             max_orphan_blocks: Default::default(),
             min_max_bootstrap_import_buffer_sizes: Default::default(),
+            recovery_replay_depth: Default::default(),
+            undo_data_retention_depth: None,
+            auto_storage_compaction_interval: None,
         };
 
         let mempool_config = MempoolConfig::new();
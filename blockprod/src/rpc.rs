@@ -21,11 +21,11 @@ use common::{
 };
 use consensus::GenerateBlockInputData;
 use crypto::ephemeral_e2e::{self, EndToEndPublicKey};
-use mempool::tx_accumulator::PackingStrategy;
+use mempool::tx_accumulator::{PackingStrategy, TxAccumulatorPolicy};
 use rpc::RpcResult;
 use serialization::hex_encoded::HexEncoded;
 
-use crate::{detail::job_manager::JobKey, TimestampSearchData};
+use crate::{detail::job_manager::JobKey, BlockTemplate, TimestampSearchData};
 
 #[rpc::describe]
 #[rpc::rpc(server, client, namespace = "blockprod")]
@@ -51,6 +51,8 @@ trait BlockProductionRpc {
     ///                   against the current state of the blockchain.
     /// - `transaction_ids`: The transaction IDs of the transactions to be included in the block from the mempool.
     /// - `packing_strategy`: Whether or not to include transactions from the mempool in the block, other than the ones specified in `transaction_ids`.
+    /// - `tx_accumulator_policy`: Optional constraints on the transactions taken from the mempool for this block only,
+    ///                            such as transactions that must be included or must never be included.
     #[method(name = "generate_block")]
     async fn generate_block(
         &self,
@@ -58,6 +60,7 @@ trait BlockProductionRpc {
         transactions: Vec<HexEncoded<SignedTransaction>>,
         transaction_ids: Vec<Id<Transaction>>,
         packing_strategy: PackingStrategy,
+        tx_accumulator_policy: Option<TxAccumulatorPolicy>,
     ) -> RpcResult<HexEncoded<Block>>;
 
     /// Get the public key to be used for end-to-end encryption.
@@ -77,6 +80,7 @@ trait BlockProductionRpc {
         transactions: Vec<HexEncoded<SignedTransaction>>,
         transaction_ids: Vec<Id<Transaction>>,
         packing_strategy: PackingStrategy,
+        tx_accumulator_policy: Option<TxAccumulatorPolicy>,
     ) -> RpcResult<HexEncoded<Block>>;
 
     /// Collect the search data needed by the `timestamp_searcher` module.
@@ -92,6 +96,22 @@ trait BlockProductionRpc {
         seconds_to_check_for_height: u64,
         all_timestamps_between_blocks: bool,
     ) -> RpcResult<HexEncoded<TimestampSearchData>>;
+
+    /// Get a template of the next block on top of the current tip.
+    ///
+    /// This allows external block producers to build and solve blocks without the wallet.
+    /// The template contains the transactions to include, their total fee, the block subsidy
+    /// and the consensus and timestamp rules the new block must satisfy.
+    /// The solved block should be passed to `submit_block`.
+    #[method(name = "block_template")]
+    async fn block_template(&self) -> RpcResult<BlockTemplate>;
+
+    /// Submit a block built from a template returned by `block_template`.
+    ///
+    /// The block must be built on top of the current tip; if the tip has changed since
+    /// the template was made, the block is rejected and a new template should be requested.
+    #[method(name = "submit_block")]
+    async fn submit_block(&self, block: HexEncoded<Block>) -> RpcResult<()>;
 }
 
 #[async_trait::async_trait]
@@ -115,6 +135,7 @@ impl BlockProductionRpcServer for super::BlockProductionHandle {
         transactions: Vec<HexEncoded<SignedTransaction>>,
         transaction_ids: Vec<Id<Transaction>>,
         packing_strategy: PackingStrategy,
+        tx_accumulator_policy: Option<TxAccumulatorPolicy>,
     ) -> rpc::RpcResult<HexEncoded<Block>> {
         let transactions = transactions.into_iter().map(HexEncoded::take).collect::<Vec<_>>();
 
@@ -125,6 +146,7 @@ impl BlockProductionRpcServer for super::BlockProductionHandle {
                     transactions,
                     transaction_ids,
                     packing_strategy,
+                    tx_accumulator_policy.unwrap_or_default(),
                 )
             })
             .await,
@@ -147,6 +169,7 @@ impl BlockProductionRpcServer for super::BlockProductionHandle {
         transactions: Vec<HexEncoded<SignedTransaction>>,
         transaction_ids: Vec<Id<Transaction>>,
         packing_strategy: PackingStrategy,
+        tx_accumulator_policy: Option<TxAccumulatorPolicy>,
     ) -> RpcResult<HexEncoded<Block>> {
         let transactions = transactions.into_iter().map(HexEncoded::take).collect::<Vec<_>>();
         let e2e_public_key = e2e_public_key.take();
@@ -159,6 +182,7 @@ impl BlockProductionRpcServer for super::BlockProductionHandle {
                     transactions,
                     transaction_ids,
                     packing_strategy,
+                    tx_accumulator_policy.unwrap_or_default(),
                 )
             })
             .await,
@@ -190,4 +214,12 @@ impl BlockProductionRpcServer for super::BlockProductionHandle {
 
         Ok(search_data.into())
     }
+
+    async fn block_template(&self) -> RpcResult<BlockTemplate> {
+        rpc::handle_result(self.call_async(move |this| this.block_template()).await)
+    }
+
+    async fn submit_block(&self, block: HexEncoded<Block>) -> RpcResult<()> {
+        rpc::handle_result(self.call_async_mut(move |this| this.submit_block(block.take())).await)
+    }
 }
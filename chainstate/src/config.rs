@@ -32,6 +32,7 @@ make_config_setting!(
     )
 );
 make_config_setting!(MaxTipAge, Duration, Duration::from_secs(60 * 60 * 24));
+make_config_setting!(RecoveryReplayDepth, u64, 10);
 
 /// The chainstate subsystem configuration.
 #[derive(Debug, Clone, Default)]
@@ -49,6 +50,23 @@ pub struct ChainstateConfig {
     /// If true, additional computationally-expensive consistency checks will be performed by
     /// the chainstate. The default value depends on the chain type.
     pub enable_heavy_checks: Option<bool>,
+    /// If true, the processing of transactions is deferred until the initial block download is
+    /// finished. This can only be disabled on regtest.
+    pub defer_txs_during_ibd: Option<bool>,
+    /// If true, the transactions of the mainchain blocks are indexed by their ids, so that
+    /// any confirmed transaction can be looked up. Disabled by default.
+    pub enable_tx_index: Option<bool>,
+    /// The number of the most recent mainchain blocks that are checked on startup and, if the
+    /// previous run was interrupted in an inconsistent state, rolled back and replayed.
+    pub recovery_replay_depth: RecoveryReplayDepth,
+    /// The number of the most recent mainchain blocks whose undo data is kept when the undo data
+    /// is compacted. It can't be less than the maximum reorg depth, which is also the default.
+    pub undo_data_retention_depth: Option<u64>,
+    /// If set, the chainstate checks this often, when it is idle, whether the database files
+    /// have grown much larger than the data stored in them, and schedules a compaction for
+    /// the next start if so; the node has to be restarted for the compaction to happen.
+    /// Disabled by default.
+    pub auto_storage_compaction_interval: Option<Duration>,
 }
 
 impl ChainstateConfig {
@@ -80,6 +98,36 @@ impl ChainstateConfig {
         self
     }
 
+    pub fn with_txs_deferred_during_ibd(mut self, defer: bool) -> Self {
+        self.defer_txs_during_ibd = Some(defer);
+        self
+    }
+
+    pub fn with_tx_index_enabled(mut self, enable: bool) -> Self {
+        self.enable_tx_index = Some(enable);
+        self
+    }
+
+    pub fn with_recovery_replay_depth(mut self, depth: u64) -> Self {
+        self.recovery_replay_depth = depth.into();
+        self
+    }
+
+    pub fn with_undo_data_retention_depth(mut self, depth: u64) -> Self {
+        self.undo_data_retention_depth = Some(depth);
+        self
+    }
+
+    pub fn with_auto_storage_compaction_interval(mut self, interval: Duration) -> Self {
+        self.auto_storage_compaction_interval = Some(interval);
+        self
+    }
+
+    /// Note: disabling the deferral outside of regtest is rejected when the chainstate is created.
+    pub fn txs_deferred_during_ibd(&self) -> bool {
+        self.defer_txs_during_ibd.unwrap_or(true)
+    }
+
     pub fn heavy_checks_enabled(&self, chain_config: &ChainConfig) -> bool {
         if let Some(enable_heavy_checks) = self.enable_heavy_checks {
             return enable_heavy_checks;
@@ -90,4 +138,14 @@ impl ChainstateConfig {
             ChainType::Regtest => true,
         }
     }
+
+    pub fn tx_index_enabled(&self) -> bool {
+        self.enable_tx_index.unwrap_or(false)
+    }
+
+    pub fn undo_data_retention_depth(&self, chain_config: &ChainConfig) -> u64 {
+        self.undo_data_retention_depth.unwrap_or_else(|| {
+            u64::try_from(chain_config.max_depth_for_reorg().to_int()).unwrap_or(0)
+        })
+    }
 }
@@ -31,7 +31,8 @@ use super::{
         error::{ConnectTransactionError, TokensError},
         storage::TransactionVerifierStorageError,
     },
-    BlockSizeError, CheckBlockError, CheckBlockTransactionsError, OrphanCheckError,
+    BlockProcessingErrorClass, BlockProcessingErrorClassification, BlockSizeError, CheckBlockError,
+    CheckBlockTransactionsError, OrphanCheckError,
 };
 use crate::{BlockError, ChainstateError};
 use chainstate_types::GetAncestorError;
@@ -39,8 +40,82 @@ use chainstate_types::GetAncestorError;
 // TODO: use a ban_score macro in a form similar to thiserror::Error in order to define the ban score
 //       value of an error on the error enum arms instead of separately like in this file
 
+/// The ban score of an error which proves that the peer has sent invalid data.
+pub const MAX_BAN_SCORE: u32 = 100;
+
+/// The maximum ban score of an error caused by data that is only bad at the moment,
+/// so that a single such error can't get the peer discouraged, only repeated ones can.
+pub const MAX_TEMPORARILY_BAD_BAN_SCORE: u32 = MAX_BAN_SCORE / 5;
+
+/// Describes how p2p should treat a peer that has sent the data which caused an error.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct PeerMisbehavior {
+    /// The value by which the peer's ban score should be increased.
+    pub score: u32,
+    /// Whether the peer should be disconnected right away.
+    pub should_disconnect: bool,
+    /// Whether the error alone is enough to discourage the peer.
+    pub should_discourage: bool,
+}
+
+impl PeerMisbehavior {
+    /// The error is not the peer's fault.
+    pub const NONE: Self = Self {
+        score: 0,
+        should_disconnect: false,
+        should_discourage: false,
+    };
+
+    pub fn from_ban_score(score: u32) -> Self {
+        let is_max = score >= MAX_BAN_SCORE;
+        Self {
+            score,
+            should_disconnect: is_max,
+            should_discourage: is_max,
+        }
+    }
+
+    /// Combine the ban score of an error with its block processing class:
+    /// - general errors (storage failures, invariant violations) are the local node's problem,
+    ///   so the peer is not punished regardless of the ban score;
+    /// - data that is definitely bad gets the peer disconnected;
+    /// - data that is only bad at the moment (e.g. a block from the future) only increases
+    ///   the ban score, by no more than `MAX_TEMPORARILY_BAD_BAN_SCORE`, because the peer
+    ///   may have a slightly different view of the world.
+    pub fn from_classified_error<E: BanScore + BlockProcessingErrorClassification>(
+        err: &E,
+    ) -> Self {
+        match err.classify() {
+            BlockProcessingErrorClass::General => Self::NONE,
+            BlockProcessingErrorClass::BadBlock => {
+                let score = err.ban_score();
+                Self {
+                    score,
+                    should_disconnect: score > 0,
+                    should_discourage: score >= MAX_BAN_SCORE,
+                }
+            }
+            BlockProcessingErrorClass::TemporarilyBadBlock => Self {
+                score: std::cmp::min(err.ban_score(), MAX_TEMPORARILY_BAD_BAN_SCORE),
+                should_disconnect: false,
+                should_discourage: false,
+            },
+        }
+    }
+
+    pub fn is_misbehavior(&self) -> bool {
+        self.score > 0 || self.should_disconnect || self.should_discourage
+    }
+}
+
 pub trait BanScore {
     fn ban_score(&self) -> u32;
+
+    /// By default, only the errors with the maximum ban score lead to disconnection
+    /// and discouragement.
+    fn peer_misbehavior(&self) -> PeerMisbehavior {
+        PeerMisbehavior::from_ban_score(self.ban_score())
+    }
 }
 
 impl BanScore for BlockError {
@@ -87,6 +162,10 @@ impl BanScore for BlockError {
             BlockError::OrdersAccountingError(err) => err.ban_score(),
         }
     }
+
+    fn peer_misbehavior(&self) -> PeerMisbehavior {
+        PeerMisbehavior::from_classified_error(self)
+    }
 }
 
 impl BanScore for OrphanCheckError {
@@ -145,6 +224,10 @@ impl BanScore for ConnectTransactionError {
             ConnectTransactionError::AttemptToCreateOrderFromAccounts => 100,
         }
     }
+
+    fn peer_misbehavior(&self) -> PeerMisbehavior {
+        PeerMisbehavior::from_classified_error(self)
+    }
 }
 
 impl BanScore for tx_verifier::error::InputCheckError {
@@ -366,8 +449,13 @@ impl BanScore for CheckTransactionError {
             CheckTransactionError::OrdersAreNotActivated(_) => 100,
             CheckTransactionError::OrdersCurrenciesMustBeDifferent(_) => 100,
             CheckTransactionError::ChangeTokenMetadataUriNotActivated => 100,
+            CheckTransactionError::InvalidWitnessFormat(_) => 100,
         }
     }
+
+    fn peer_misbehavior(&self) -> PeerMisbehavior {
+        PeerMisbehavior::from_classified_error(self)
+    }
 }
 
 impl BanScore for ConsensusVerificationError {
@@ -548,6 +636,20 @@ impl BanScore for ChainstateError {
             ChainstateError::FailedToReadProperty(_) => 0,
             ChainstateError::BootstrapError(_) => 0,
             ChainstateError::BlockInvalidatorError(_) => 0,
+            ChainstateError::UndoCompactionError(_) => 0,
+            ChainstateError::StorageMaintenanceError(_) => 0,
+        }
+    }
+
+    fn peer_misbehavior(&self) -> PeerMisbehavior {
+        match self {
+            ChainstateError::ProcessBlockError(e) => e.peer_misbehavior(),
+            ChainstateError::FailedToInitializeChainstate(_)
+            | ChainstateError::FailedToReadProperty(_)
+            | ChainstateError::BootstrapError(_)
+            | ChainstateError::BlockInvalidatorError(_)
+            | ChainstateError::UndoCompactionError(_)
+            | ChainstateError::StorageMaintenanceError(_) => PeerMisbehavior::NONE,
         }
     }
 }
@@ -688,3 +790,363 @@ impl BanScore for orders_accounting::Error {
 }
 
 // TODO: tests in which we simulate every possible case and test the score
+
+#[cfg(test)]
+mod tests {
+    use chainstate_types::PropertyQueryError;
+    use common::{
+        chain::{tokens::TokenId, Block, GenBlock, PoolId, Transaction, UtxoOutPoint},
+        primitives::{Amount, BlockHeight, Id, H256},
+    };
+    use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, Seed};
+
+    use crate::detail::{
+        block_invalidation::{BestChainCandidatesError, BlockInvalidatorError},
+        bootstrap::BootstrapError,
+        DbCommittingContext, InitializationError,
+    };
+
+    use super::*;
+
+    fn storage_error() -> chainstate_storage::Error {
+        chainstate_storage::Error::Storage(storage::error::Recoverable::TransactionFailed)
+    }
+
+    // This function doesn't do anything useful, its purpose is to make the compiler complain
+    // when a new BlockError variant is added, so that it is also added to the table below.
+    #[allow(dead_code)]
+    fn all_block_error_variants_are_in_the_table(err: &BlockError) {
+        match err {
+            BlockError::StorageError(_)
+            | BlockError::OrphanCheckFailed(_)
+            | BlockError::CheckBlockFailed(_)
+            | BlockError::StateUpdateFailed(_)
+            | BlockError::PrevBlockNotFoundForNewBlock(_)
+            | BlockError::BlockAlreadyExists(_)
+            | BlockError::BlockIndexAlreadyExists(_)
+            | BlockError::BlockAlreadyProcessed(_)
+            | BlockError::InvalidBlockAlreadyProcessed(_)
+            | BlockError::DbCommitError(_, _, _)
+            | BlockError::BlockProofCalculationError(_)
+            | BlockError::TransactionVerifierError(_)
+            | BlockError::PoSAccountingError(_)
+            | BlockError::EpochSealError(_)
+            | BlockError::BlockDataMissingForValidBlockIndex(_)
+            | BlockError::BestChainCandidatesAccessorError(_)
+            | BlockError::TokensAccountingError(_)
+            | BlockError::InMemoryReorgFailed(_)
+            | BlockError::OrdersAccountingError(_)
+            | BlockError::BestBlockIdQueryError(_)
+            | BlockError::BestBlockIndexQueryError(_)
+            | BlockError::BlockIndexQueryError(_, _)
+            | BlockError::IsBlockInMainChainQueryError(_, _)
+            | BlockError::MinHeightForReorgQueryError(_)
+            | BlockError::PropertyQueryError(_)
+            | BlockError::InvariantErrorFailedToFindNewChainPath(_, _, _)
+            | BlockError::InvariantErrorInvalidTip(_)
+            | BlockError::InvariantErrorAttemptToConnectInvalidBlock(_)
+            | BlockError::InvariantErrorDisconnectedHeaders
+            | BlockError::InvariantErrorTotalPoolBalanceLessThanStakers { .. }
+            | BlockError::InvariantErrorPoolBalancePresentDataMissing(_, _)
+            | BlockError::InvariantErrorPoolDataPresentBalanceMissing(_, _)
+            | BlockError::UnexpectedHeightRange(_, _) => {}
+        }
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn block_error_peer_misbehavior(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let block_id = Id::<Block>::new(H256::random_using(&mut rng));
+        let gen_block_id: Id<GenBlock> = block_id.into();
+        let tx_id = Id::<Transaction>::new(H256::random_using(&mut rng));
+        let pool_id = PoolId::new(H256::random_using(&mut rng));
+        let token_id = TokenId::new(H256::random_using(&mut rng));
+        let height = BlockHeight::new(123);
+
+        let none = PeerMisbehavior::NONE;
+        let bad = PeerMisbehavior {
+            score: MAX_BAN_SCORE,
+            should_disconnect: true,
+            should_discourage: true,
+        };
+        let temporarily_bad = PeerMisbehavior {
+            score: MAX_TEMPORARILY_BAD_BAN_SCORE,
+            should_disconnect: false,
+            should_discourage: false,
+        };
+
+        let table = [
+            (BlockError::StorageError(storage_error()), none),
+            (
+                BlockError::OrphanCheckFailed(OrphanCheckError::LocalOrphan),
+                none,
+            ),
+            (
+                BlockError::CheckBlockFailed(CheckBlockError::MerkleRootMismatch),
+                bad,
+            ),
+            (
+                BlockError::CheckBlockFailed(CheckBlockError::BlockFromTheFuture(block_id)),
+                temporarily_bad,
+            ),
+            // The ban score of this one is non-zero, but it's not the peer's fault
+            (
+                BlockError::CheckBlockFailed(CheckBlockError::PropertyQueryError(
+                    PropertyQueryError::BestBlockIndexNotFound,
+                )),
+                none,
+            ),
+            (
+                BlockError::StateUpdateFailed(ConnectTransactionError::MissingOutputOrSpent(
+                    UtxoOutPoint::new(tx_id.into(), 0),
+                )),
+                bad,
+            ),
+            (
+                BlockError::StateUpdateFailed(ConnectTransactionError::StorageError(
+                    storage_error(),
+                )),
+                none,
+            ),
+            (
+                BlockError::PrevBlockNotFoundForNewBlock(block_id),
+                temporarily_bad,
+            ),
+            (BlockError::BlockAlreadyExists(block_id), none),
+            (BlockError::BlockIndexAlreadyExists(block_id), none),
+            (BlockError::BlockAlreadyProcessed(block_id), none),
+            (BlockError::InvalidBlockAlreadyProcessed(block_id), bad),
+            (
+                BlockError::DbCommitError(1, storage_error(), DbCommittingContext::Block(block_id)),
+                none,
+            ),
+            (BlockError::BlockProofCalculationError(block_id), bad),
+            (
+                BlockError::TransactionVerifierError(
+                    TransactionVerifierStorageError::DuplicateBlockUndo(block_id),
+                ),
+                bad,
+            ),
+            (
+                BlockError::PoSAccountingError(
+                    pos_accounting::Error::InvariantErrorPoolBalanceAlreadyExists,
+                ),
+                bad,
+            ),
+            (
+                BlockError::EpochSealError(EpochSealError::PoolDataNotFound(pool_id)),
+                none,
+            ),
+            (
+                BlockError::BlockDataMissingForValidBlockIndex(block_id),
+                none,
+            ),
+            (
+                BlockError::BestChainCandidatesAccessorError(
+                    BestChainCandidatesError::PropertyQueryError(
+                        PropertyQueryError::BestBlockIndexNotFound,
+                    ),
+                ),
+                none,
+            ),
+            (
+                BlockError::TokensAccountingError(tokens_accounting::Error::TokenAlreadyExists(
+                    token_id,
+                )),
+                bad,
+            ),
+            (
+                BlockError::InMemoryReorgFailed(InMemoryReorgError::BlockNotFound(gen_block_id)),
+                none,
+            ),
+            (
+                BlockError::OrdersAccountingError(orders_accounting::Error::CurrencyMismatch),
+                bad,
+            ),
+            (
+                BlockError::BestBlockIdQueryError(PropertyQueryError::BestBlockIndexNotFound),
+                none,
+            ),
+            (
+                BlockError::BestBlockIndexQueryError(PropertyQueryError::BestBlockIndexNotFound),
+                none,
+            ),
+            (
+                BlockError::BlockIndexQueryError(
+                    gen_block_id,
+                    PropertyQueryError::BestBlockIndexNotFound,
+                ),
+                none,
+            ),
+            (
+                BlockError::IsBlockInMainChainQueryError(
+                    gen_block_id,
+                    PropertyQueryError::BestBlockIndexNotFound,
+                ),
+                none,
+            ),
+            (
+                BlockError::MinHeightForReorgQueryError(PropertyQueryError::BestBlockIndexNotFound),
+                none,
+            ),
+            (
+                BlockError::PropertyQueryError(PropertyQueryError::BestBlockIndexNotFound),
+                none,
+            ),
+            (
+                BlockError::InvariantErrorFailedToFindNewChainPath(
+                    gen_block_id,
+                    gen_block_id,
+                    PropertyQueryError::BestBlockIndexNotFound,
+                ),
+                none,
+            ),
+            (BlockError::InvariantErrorInvalidTip(gen_block_id), none),
+            (
+                BlockError::InvariantErrorAttemptToConnectInvalidBlock(gen_block_id),
+                none,
+            ),
+            (BlockError::InvariantErrorDisconnectedHeaders, none),
+            (
+                BlockError::InvariantErrorTotalPoolBalanceLessThanStakers {
+                    total_balance: Amount::from_atoms(1),
+                    staker_balance: Amount::from_atoms(2),
+                    pool_id,
+                    best_block_height: height,
+                },
+                none,
+            ),
+            (
+                BlockError::InvariantErrorPoolBalancePresentDataMissing(pool_id, height),
+                none,
+            ),
+            (
+                BlockError::InvariantErrorPoolDataPresentBalanceMissing(pool_id, height),
+                none,
+            ),
+            (BlockError::UnexpectedHeightRange(height, height), none),
+            // Nested errors
+            (
+                BlockError::CheckBlockFailed(CheckBlockError::CheckTransactionFailed(
+                    CheckBlockTransactionsError::CheckTransactionError(
+                        CheckTransactionError::DuplicateInputInTransaction(tx_id),
+                    ),
+                )),
+                bad,
+            ),
+            (
+                BlockError::CheckBlockFailed(CheckBlockError::CheckTransactionFailed(
+                    CheckBlockTransactionsError::CheckTransactionError(
+                        CheckTransactionError::PropertyQueryError(
+                            PropertyQueryError::BestBlockIndexNotFound,
+                        ),
+                    ),
+                )),
+                none,
+            ),
+            (
+                BlockError::CheckBlockFailed(CheckBlockError::BlockSizeError(
+                    BlockSizeError::SizeOfTxs(2, 1),
+                )),
+                bad,
+            ),
+            (
+                BlockError::CheckBlockFailed(CheckBlockError::ConsensusVerificationFailed(
+                    ConsensusVerificationError::PrevBlockNotFound(gen_block_id, block_id),
+                )),
+                bad,
+            ),
+            (
+                BlockError::InMemoryReorgFailed(InMemoryReorgError::StateUpdateFailed(
+                    ConnectTransactionError::MissingOutputOrSpent(UtxoOutPoint::new(
+                        tx_id.into(),
+                        0,
+                    )),
+                )),
+                bad,
+            ),
+            (
+                BlockError::InMemoryReorgFailed(InMemoryReorgError::StateUpdateFailed(
+                    ConnectTransactionError::MissingTxUndo(tx_id),
+                )),
+                none,
+            ),
+            (
+                BlockError::InMemoryReorgFailed(
+                    InMemoryReorgError::StepHandlerFailedWhenDisconnectingBlocks {
+                        error: "error".to_owned(),
+                        error_class: BlockProcessingErrorClass::TemporarilyBadBlock,
+                        ban_score: MAX_BAN_SCORE,
+                    },
+                ),
+                temporarily_bad,
+            ),
+            (
+                BlockError::InMemoryReorgFailed(
+                    InMemoryReorgError::StepHandlerFailedWhenDisconnectingBlocks {
+                        error: "error".to_owned(),
+                        error_class: BlockProcessingErrorClass::TemporarilyBadBlock,
+                        ban_score: 1,
+                    },
+                ),
+                PeerMisbehavior {
+                    score: 1,
+                    should_disconnect: false,
+                    should_discourage: false,
+                },
+            ),
+        ];
+
+        for (err, expected) in table {
+            assert_eq!(err.peer_misbehavior(), expected, "error: {err:?}");
+
+            let chainstate_err = ChainstateError::ProcessBlockError(err);
+            assert_eq!(chainstate_err.peer_misbehavior(), expected);
+        }
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn tx_error_peer_misbehavior(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let tx_id = Id::<Transaction>::new(H256::random_using(&mut rng));
+
+        assert_eq!(
+            CheckTransactionError::DuplicateInputInTransaction(tx_id).peer_misbehavior(),
+            PeerMisbehavior::from_ban_score(MAX_BAN_SCORE)
+        );
+        assert_eq!(
+            CheckTransactionError::PropertyQueryError(PropertyQueryError::BestBlockIndexNotFound)
+                .peer_misbehavior(),
+            PeerMisbehavior::NONE
+        );
+        assert_eq!(
+            ConnectTransactionError::MissingTxUndo(tx_id).peer_misbehavior(),
+            PeerMisbehavior::NONE
+        );
+        assert_eq!(
+            ConnectTransactionError::AttemptToSpendBurnedAmount.peer_misbehavior(),
+            PeerMisbehavior::from_ban_score(MAX_BAN_SCORE)
+        );
+    }
+
+    #[test]
+    fn non_block_chainstate_errors_are_not_misbehavior() {
+        let errors = [
+            ChainstateError::FailedToInitializeChainstate(InitializationError::Block1Missing),
+            ChainstateError::FailedToReadProperty(PropertyQueryError::BestBlockIndexNotFound),
+            ChainstateError::BootstrapError(BootstrapError::File("file".to_owned())),
+            ChainstateError::BlockInvalidatorError(BlockInvalidatorError::StorageError(
+                storage_error(),
+            )),
+            ChainstateError::StorageMaintenanceError(storage_error()),
+        ];
+        for err in errors {
+            assert_eq!(err.peer_misbehavior(), PeerMisbehavior::NONE);
+        }
+    }
+}
@@ -26,7 +26,9 @@ use crate::{
     detail::chainstateref::ReorgError, BlockError, BlockProcessingErrorClassification,
     TransactionVerificationStrategy,
 };
-use chainstate_storage::{BlockchainStorage, BlockchainStorageRead, BlockchainStorageWrite};
+use chainstate_storage::{
+    BlockchainStorage, BlockchainStorageRead, BlockchainStorageWrite, PendingOperation,
+};
 use chainstate_types::{BlockIndex, BlockStatus, GenBlockIndex, PropertyQueryError};
 use common::{
     chain::{Block, GenBlock},
@@ -154,8 +156,19 @@ impl<'a, S: BlockchainStorage, V: TransactionVerificationStrategy> BlockInvalida
             BlockInvalidatorError::BlockTooDeepToInvalidate(*block_id)
         );
 
+        // The invalidation is recorded in the same db transaction as the disconnection, so that
+        // it can be completed on the next start if the node is shut down in the middle of it.
+        let pending_operation = PendingOperation::BlockInvalidation {
+            block_id: *block_id,
+            tip_before: best_block_id.into(),
+            is_explicit: matches!(is_explicit_invalidation, IsExplicit::Yes),
+        };
+
         self.chainstate.with_rw_tx(
             |chainstate_ref| {
+                chainstate_ref.set_pending_operation(&pending_operation).map_err(|err| {
+                    BlockInvalidatorError::PendingOperationUpdateError(Box::new(err))
+                })?;
                 let disconnect_until_id = block_index.prev_block_id();
                 chainstate_ref.disconnect_until(&best_block_id, disconnect_until_id).map_err(
                     |err| BlockInvalidatorError::BlocksDisconnectionError { disconnect_until: *disconnect_until_id, error: Box::new(err) })
@@ -176,14 +189,38 @@ impl<'a, S: BlockchainStorage, V: TransactionVerificationStrategy> BlockInvalida
             log::warn!("No better chain was found after invalidating block {block_id}");
         }
 
+        self.clear_pending_operation()?;
+
         Ok(())
     }
 
+    /// Remove the record of a multi-transaction operation that has been completed.
+    #[log_error]
+    pub fn clear_pending_operation(&mut self) -> Result<(), BlockInvalidatorError> {
+        self.chainstate.with_rw_tx(
+            |chainstate_ref| {
+                chainstate_ref.del_pending_operation().map_err(|err| {
+                    BlockInvalidatorError::PendingOperationUpdateError(Box::new(err))
+                })
+            },
+            |attempt_number| {
+                log::info!("Clearing the pending operation record, attempt #{attempt_number}");
+            },
+            |attempts_count, db_err| {
+                BlockInvalidatorError::DbCommitError(
+                    attempts_count,
+                    db_err,
+                    DbCommittingContext::PendingOperationCleanup,
+                )
+            },
+        )
+    }
+
     /// Search among stale chains for ones with more trust than the current mainchain;
     /// activate the best valid chain among them.
     /// Return true if a reorg has occurred.
     #[log_error]
-    fn find_and_activate_best_chain(&mut self) -> Result<bool, BlockInvalidatorError> {
+    pub fn find_and_activate_best_chain(&mut self) -> Result<bool, BlockInvalidatorError> {
         let (min_chain_trust, best_chain_candidates) = {
             let chainstate_ref = self.chainstate.make_db_tx_ro()?;
             let cur_best_block_index = get_best_block_index(&chainstate_ref)?;
@@ -356,6 +393,8 @@ pub enum BlockInvalidatorError {
     BlockIndexQueryError(Id<GenBlock>, PropertyQueryError),
     #[error("Error deleting index for block {0}: {1}")]
     DelBlockIndexError(Id<Block>, BlockError),
+    #[error("Error updating the pending operation record: {0}")]
+    PendingOperationUpdateError(Box<BlockError>),
 }
 
 #[derive(Debug, Display, PartialEq, Eq, Clone)]
@@ -368,6 +407,8 @@ pub enum DbCommittingContext {
     ClearedBlockTreeStatuses(Id<Block>),
     #[display(fmt = "committing block tree disconnection (root block: {})", _0)]
     BlockTreeDisconnection(Id<Block>),
+    #[display(fmt = "clearing the pending operation record")]
+    PendingOperationCleanup,
 }
 
 /// The error type for reorgs that happen inside invalidate_block.
@@ -125,5 +125,7 @@ where
         let block = query_interface.get_existing_block(block_id)?;
         writer.write_all(&block.encode())?;
     }
+    // Flush explicitly, because the errors are ignored when the writer is dropped
+    writer.flush()?;
     Ok(())
 }
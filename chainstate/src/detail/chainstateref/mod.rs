@@ -19,6 +19,7 @@ mod epoch_seal;
 mod in_memory_reorg;
 mod tx_verifier_storage;
 
+use consensus::{ConsensusPoWError, ConsensusVerificationError};
 use itertools::Itertools;
 use serialization::{Decode, Encode};
 use std::{
@@ -27,10 +28,12 @@ use std::{
 };
 use thiserror::Error;
 
-use chainstate_storage::{BlockchainStorageRead, BlockchainStorageWrite, TransactionRw};
+use chainstate_storage::{
+    BlockchainStorageRead, BlockchainStorageWrite, PendingOperation, TransactionRw,
+};
 use chainstate_types::{
-    block_index_ancestor_getter, get_skip_height, BlockIndex, BlockIndexHandle, BlockStatus,
-    BlockValidationStage, EpochData, EpochDataCache, GenBlockIndex, GetAncestorError,
+    block_index_ancestor_getter, get_skip_height, BlockIndex, BlockIndexHandle, BlockStats,
+    BlockStatus, BlockValidationStage, EpochData, EpochDataCache, GenBlockIndex, GetAncestorError,
     PropertyQueryError, TipStorageTag,
 };
 use common::{
@@ -41,18 +44,19 @@ use common::{
         },
         config::EpochIndex,
         tokens::{TokenAuxiliaryData, TokenId},
-        AccountNonce, AccountType, Block, ChainConfig, GenBlock, GenBlockId, PoolId, Transaction,
-        TxOutput, UtxoOutPoint,
+        AccountNonce, AccountType, Block, ChainConfig, DelegationId, GenBlock, GenBlockId, PoolId,
+        RequiredConsensus, SignedTransaction, Transaction, TxOutput, UtxoOutPoint,
     },
     primitives::{
-        id::WithId, time::Time, Amount, BlockCount, BlockDistance, BlockHeight, Id, Idable,
+        id::WithId, time::Time, Amount, BlockCount, BlockDistance, BlockHeight, Fee, Id, Idable,
     },
     time_getter::TimeGetter,
     Uint256,
 };
 use logging::log;
 use pos_accounting::{
-    PoSAccountingDB, PoSAccountingDelta, PoSAccountingStorageRead, PoSAccountingView,
+    DefaultStorageTag, InMemoryPoSAccounting, PoSAccountingDB, PoSAccountingData,
+    PoSAccountingDelta, PoSAccountingStorageRead, PoSAccountingUndo, PoSAccountingView,
 };
 use tx_verifier::transaction_verifier::TransactionVerifier;
 use utils::{debug_assert_or_log, ensure, log_error, tap_log::TapLog};
@@ -74,9 +78,12 @@ use super::{
 pub use epoch_seal::EpochSealError;
 pub use in_memory_reorg::InMemoryReorgError;
 
+/// The number of the most recent mainchain blocks whose statistics are kept
+const BLOCK_STATS_WINDOW: u64 = 1000;
+
 pub struct ChainstateRef<'a, S, V> {
     chain_config: &'a ChainConfig,
-    _chainstate_config: &'a ChainstateConfig,
+    chainstate_config: &'a ChainstateConfig,
     tx_verification_strategy: &'a V,
     db_tx: S,
     time_getter: &'a TimeGetter,
@@ -141,7 +148,7 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
     ) -> Self {
         ChainstateRef {
             chain_config,
-            _chainstate_config: chainstate_config,
+            chainstate_config,
             db_tx,
             tx_verification_strategy,
             time_getter,
@@ -157,7 +164,7 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
     ) -> Self {
         ChainstateRef {
             chain_config,
-            _chainstate_config: chainstate_config,
+            chainstate_config,
             db_tx,
             tx_verification_strategy,
             time_getter,
@@ -377,6 +384,96 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         self.db_tx.get_token_id(tx_id).map_err(PropertyQueryError::from)
     }
 
+    /// Look up a mainchain transaction in the transaction index and return it together with
+    /// the id and the height of the block containing it
+    #[log_error]
+    pub fn get_transaction(
+        &self,
+        tx_id: &Id<Transaction>,
+    ) -> Result<Option<(SignedTransaction, Id<Block>, BlockHeight)>, PropertyQueryError> {
+        ensure!(
+            self.chainstate_config.tx_index_enabled(),
+            PropertyQueryError::TxIndexDisabled
+        );
+
+        let block_id = match self.db_tx.get_tx_block_id(tx_id)? {
+            Some(block_id) => block_id,
+            None => return Ok(None),
+        };
+        let block = self.get_block(block_id)?.ok_or(PropertyQueryError::BlockNotFound(block_id))?;
+        let block_height = self.get_existing_block_index(&block_id)?.block_height();
+
+        let tx = block
+            .transactions()
+            .iter()
+            .find(|tx| tx.transaction().get_id() == *tx_id)
+            .cloned();
+        Ok(tx.map(|tx| (tx, block_id, block_height)))
+    }
+
+    /// Return the statistics of up to `count` most recent mainchain blocks, starting from the tip.
+    /// Only the statistics of the last `BLOCK_STATS_WINDOW` connected blocks are available.
+    #[log_error]
+    pub fn get_recent_block_stats(
+        &self,
+        count: usize,
+    ) -> Result<Vec<(BlockIndex, BlockStats)>, PropertyQueryError> {
+        let mut result = Vec::new();
+        let mut block_id = self.get_best_block_id()?;
+
+        while result.len() < count {
+            let chain_block_id = match block_id.classify(self.chain_config).chain_block_id() {
+                Some(id) => id,
+                None => break,
+            };
+            let stats = match self.db_tx.get_block_stats(&chain_block_id)? {
+                Some(stats) => stats,
+                None => break,
+            };
+            let block_index = self.get_existing_block_index(&chain_block_id)?;
+            block_id = *block_index.prev_block_id();
+            result.push((block_index, stats));
+        }
+
+        Ok(result)
+    }
+
+    /// Return how the reward of a PoS block was credited to the pool that produced it
+    /// and to its delegations. `None` is returned if the block is not PoS or its undo data
+    /// isn't available.
+    #[log_error]
+    pub fn get_block_reward_distribution(
+        &self,
+        block_id: &Id<Block>,
+    ) -> Result<Option<BlockRewardDistribution>, PropertyQueryError> {
+        let reward_undos = match self.db_tx.get_pos_accounting_undo(*block_id)? {
+            Some(block_undo) => block_undo.consume().0,
+            None => return Ok(None),
+        };
+
+        let mut staker_reward = None;
+        let mut delegation_rewards = BTreeMap::new();
+        for undo in reward_undos.into_iter().flat_map(|undos| undos.into_inner()) {
+            match undo {
+                PoSAccountingUndo::IncreaseStakerRewards(undo) => {
+                    staker_reward = Some((undo.pool_id(), undo.amount_added()));
+                }
+                PoSAccountingUndo::DelegateStaking(undo) => {
+                    delegation_rewards.insert(undo.delegation_target(), undo.amount_to_delegate());
+                }
+                PoSAccountingUndo::CreatePool(_)
+                | PoSAccountingUndo::DecommissionPool(_)
+                | PoSAccountingUndo::CreateDelegationId(_)
+                | PoSAccountingUndo::DeleteDelegationId(_)
+                | PoSAccountingUndo::SpendFromShare(_) => {}
+            }
+        }
+
+        Ok(staker_reward.map(|(pool_id, staker_reward)| {
+            BlockRewardDistribution::new(pool_id, staker_reward, delegation_rewards)
+        }))
+    }
+
     #[log_error]
     pub fn get_header_from_height(
         &self,
@@ -407,6 +504,43 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         self.db_tx.get_epoch_data(epoch_index).map_err(PropertyQueryError::from)
     }
 
+    #[log_error]
+    pub fn get_pos_accounting_data_tip(&self) -> Result<PoSAccountingData, PropertyQueryError> {
+        self.db_tx.get_pos_accounting_data_tip().map_err(PropertyQueryError::from)
+    }
+
+    /// Return the PoS accounting state as it was right after the specified epoch was sealed.
+    ///
+    /// The state is obtained by undoing the deltas of the epochs sealed after the specified one,
+    /// so the epoch must not be later than the last sealed epoch.
+    #[log_error]
+    pub fn get_pos_accounting_data_at_epoch(
+        &self,
+        epoch_index: EpochIndex,
+    ) -> Result<PoSAccountingData, PropertyQueryError> {
+        let tip_height = self.get_best_block_index()?.block_height();
+        let sealed_epoch_index = self
+            .chain_config
+            .sealed_epoch_index(&tip_height)
+            .filter(|sealed_epoch_index| epoch_index <= *sealed_epoch_index)
+            .ok_or(PropertyQueryError::EpochNotSealed(epoch_index))?;
+
+        let mut data =
+            InMemoryPoSAccounting::from_data(self.db_tx.get_pos_accounting_data_sealed()?);
+
+        for epoch_index_to_undo in (epoch_index + 1..=sealed_epoch_index).rev() {
+            // epochs without accounting changes have no undo
+            if let Some(epoch_undo) =
+                self.db_tx.get_accounting_epoch_undo_delta(epoch_index_to_undo)?
+            {
+                PoSAccountingDB::<_, DefaultStorageTag>::new(&mut data)
+                    .undo_merge_with_delta(epoch_undo)?;
+            }
+        }
+
+        Ok(data.into_data())
+    }
+
     #[log_error]
     pub fn get_account_nonce_count(
         &self,
@@ -665,6 +799,95 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         Ok(())
     }
 
+    /// Check a header whose parent is not in the database yet, but is itself a header received
+    /// ahead of its block. Only the checks that don't need the parent's block index are performed;
+    /// the rest is done when the block itself is processed.
+    #[log_error]
+    pub fn check_pending_block_header(
+        &self,
+        header: &SignedBlockHeader,
+        header_height: BlockHeight,
+    ) -> Result<(), CheckBlockError> {
+        self.check_header_size(header)?;
+        self.enforce_exact_checkpoint_assuming_height(header, header_height)?;
+
+        let max_future_offset = self.chain_config.max_future_block_time_offset(header_height);
+        let current_time = self.current_time().as_duration_since_epoch();
+        ensure!(
+            header.timestamp().as_duration_since_epoch() <= current_time + max_future_offset,
+            CheckBlockError::BlockFromTheFuture(header.block_id()),
+        );
+        Ok(())
+    }
+
+    /// Check a header whose parent is a header stored ahead of its block. On top of the checks
+    /// that don't need the parent at all, the consensus type is checked against the chain config,
+    /// the proof of work is checked against the bits the header declares and, for PoS,
+    /// the timestamp is checked against the parent header's one.
+    /// This only weeds out obviously bad headers early; the consensus data is checked fully
+    /// with `check_block_header` once the parent block is processed.
+    #[log_error]
+    pub fn check_block_header_against_parent_header(
+        &self,
+        header: &SignedBlockHeader,
+        parent_header: &SignedBlockHeader,
+        header_height: BlockHeight,
+    ) -> Result<(), CheckBlockError> {
+        debug_assert_eq!(header.prev_block_id(), &parent_header.get_id().into());
+
+        self.check_pending_block_header(header, header_height)?;
+
+        let consensus_type_mismatch = |required: &str| {
+            CheckBlockError::ConsensusVerificationFailed(
+                ConsensusVerificationError::ConsensusTypeMismatch(format!(
+                    "Chain configuration says we are {required} but block consensus data is not {required}."
+                )),
+            )
+        };
+
+        match (
+            self.chain_config.consensus_upgrades().consensus_status(header_height),
+            header.consensus_data(),
+        ) {
+            (RequiredConsensus::IgnoreConsensus, ConsensusData::None) => {}
+            (RequiredConsensus::PoW(_), ConsensusData::PoW(pow_data)) => {
+                let is_valid =
+                    consensus::check_proof_of_work(header.block_id().to_hash(), pow_data.bits())
+                        .map_err(|err| CheckBlockError::ConsensusVerificationFailed(err.into()))?;
+                ensure!(
+                    is_valid,
+                    CheckBlockError::ConsensusVerificationFailed(
+                        ConsensusPoWError::InvalidPoW(header.get_id()).into()
+                    )
+                );
+            }
+            (RequiredConsensus::PoS(_), ConsensusData::PoS(_)) => {
+                ensure!(
+                    header.timestamp() > parent_header.timestamp(),
+                    CheckBlockError::BlockTimeOrderInvalid(
+                        header.timestamp(),
+                        parent_header.timestamp()
+                    ),
+                );
+            }
+            (RequiredConsensus::IgnoreConsensus, ConsensusData::PoW(_) | ConsensusData::PoS(_)) => {
+                return Err(CheckBlockError::ConsensusVerificationFailed(
+                    ConsensusVerificationError::ConsensusTypeMismatch(
+                        "Chain configuration says consensus should be empty but block consensus data is not `None`.".into(),
+                    ),
+                ));
+            }
+            (RequiredConsensus::PoW(_), ConsensusData::None | ConsensusData::PoS(_)) => {
+                return Err(consensus_type_mismatch("PoW"));
+            }
+            (RequiredConsensus::PoS(_), ConsensusData::None | ConsensusData::PoW(_)) => {
+                return Err(consensus_type_mismatch("PoS"));
+            }
+        }
+
+        Ok(())
+    }
+
     #[log_error]
     fn check_block_reward_maturity_settings(&self, block: &Block) -> Result<(), CheckBlockError> {
         block
@@ -856,6 +1079,30 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         Ok(())
     }
 
+    /// Calculate the chain trust of the block with the given header; its parent block must be known
+    #[log_error]
+    pub fn get_header_chain_trust(
+        &self,
+        header: &SignedBlockHeader,
+    ) -> Result<Uint256, BlockError> {
+        let parent_block_index = self
+            .get_gen_block_index(header.prev_block_id())
+            .map_err(BlockError::PropertyQueryError)?
+            .ok_or_else(|| {
+                BlockError::PropertyQueryError(PropertyQueryError::PrevBlockIndexNotFound {
+                    block_id: header.get_id(),
+                    prev_block_id: *header.prev_block_id(),
+                })
+            })?;
+        let block_proof = header
+            .consensus_data()
+            .get_block_proof(parent_block_index.block_timestamp(), header.timestamp())
+            .ok_or_else(|| BlockError::BlockProofCalculationError(header.get_id()))?;
+        let chain_trust = (parent_block_index.chain_trust() + block_proof)
+            .expect("Chain trust growth is locally controlled. This can't happen.");
+        Ok(chain_trust)
+    }
+
     #[log_error]
     fn get_block_proof(
         &self,
@@ -1173,16 +1420,17 @@ impl<'a, S: BlockchainStorageWrite, V: TransactionVerificationStrategy> Chainsta
         Ok(())
     }
 
+    /// Connect the transactions of the block and return the fee paid by each of them
     #[log_error]
     fn connect_transactions(
         &mut self,
         block_index: &BlockIndex,
         block: &WithId<Block>,
-    ) -> Result<(), BlockError> {
+    ) -> Result<Vec<Fee>, BlockError> {
         // The comparison for timelock is done with median_time_past based on BIP-113, i.e., the median time instead of the block timestamp
         let median_time_past = calculate_median_time_past(self, &block.prev_block_id());
 
-        let connected_txs = self
+        let (connected_txs, tx_fees) = self
             .tx_verification_strategy
             .connect_block(
                 TransactionVerifier::new,
@@ -1197,6 +1445,38 @@ impl<'a, S: BlockchainStorageWrite, V: TransactionVerificationStrategy> Chainsta
         let consumed = connected_txs.consume()?;
         flush_to_storage(self, consumed)?;
 
+        Ok(tx_fees)
+    }
+
+    /// Store the statistics of a newly connected block and remove the ones
+    /// of the block that is no longer within the window
+    #[log_error]
+    fn store_block_stats(
+        &mut self,
+        block_index: &BlockIndex,
+        block: &WithId<Block>,
+        tx_fees: Vec<Fee>,
+    ) -> Result<(), BlockError> {
+        let tx_sizes = block.transactions().iter().map(|tx| tx.encoded_size());
+        match BlockStats::from_tx_fees_and_sizes(tx_fees.into_iter().zip(tx_sizes)) {
+            Some(stats) => self.db_tx.set_block_stats(block_index.block_id(), &stats)?,
+            None => log::warn!(
+                "Total fee overflow when collecting the statistics of block {}",
+                block_index.block_id()
+            ),
+        }
+
+        let expired_height = block_index.block_height().into_int().checked_sub(BLOCK_STATS_WINDOW);
+        if let Some(expired_height) = expired_height {
+            let expired_block_id = self
+                .db_tx
+                .get_block_id_by_height(&BlockHeight::new(expired_height))?
+                .and_then(|id| id.classify(self.chain_config).chain_block_id());
+            if let Some(expired_block_id) = expired_block_id {
+                self.db_tx.del_block_stats(&expired_block_id)?;
+            }
+        }
+
         Ok(())
     }
 
@@ -1253,7 +1533,13 @@ impl<'a, S: BlockchainStorageWrite, V: TransactionVerificationStrategy> Chainsta
             BlockError::InvariantErrorInvalidTip(block.get_id().into()),
         );
 
-        self.connect_transactions(block_index, &block)?;
+        let tx_fees = self.connect_transactions(block_index, &block)?;
+
+        if self.chainstate_config.tx_index_enabled() {
+            for tx in block.transactions() {
+                self.db_tx.set_tx_block_id(&tx.transaction().get_id(), &block.get_id())?;
+            }
+        }
 
         self.db_tx.set_block_id_at_height(
             &block_index.block_height(),
@@ -1261,6 +1547,8 @@ impl<'a, S: BlockchainStorageWrite, V: TransactionVerificationStrategy> Chainsta
         )?;
         self.db_tx.set_best_block_id(&(*block_index.block_id()).into())?;
 
+        self.store_block_stats(block_index, &block, tx_fees)?;
+
         if block_index.status().last_valid_stage() != BlockValidationStage::FullyChecked {
             let mut block_status = block_status;
             block_status.advance_validation_stage_to(BlockValidationStage::FullyChecked);
@@ -1295,10 +1583,17 @@ impl<'a, S: BlockchainStorageWrite, V: TransactionVerificationStrategy> Chainsta
             .get_block_index(&best_block_id)
             .expect("Database error on retrieving current best block index")
             .expect("Best block index not present in the database");
-        let block = self.get_block_from_index(&block_index)?.expect("Inconsistent DB");
+        let block: WithId<Block> =
+            self.get_block_from_index(&block_index)?.expect("Inconsistent DB").into();
         // Disconnect transactions
-        self.disconnect_transactions(&block.into())?;
+        self.disconnect_transactions(&block)?;
+        if self.chainstate_config.tx_index_enabled() {
+            for tx in block.transactions() {
+                self.db_tx.del_tx_block_id(&tx.transaction().get_id())?;
+            }
+        }
         self.db_tx.set_best_block_id(block_index.prev_block_id())?;
+        self.db_tx.del_block_stats(&best_block_id)?;
         // Disconnect block
         self.db_tx.del_block_id_at_height(&block_index.block_height())?;
 
@@ -1403,6 +1698,19 @@ impl<'a, S: BlockchainStorageWrite, V: TransactionVerificationStrategy> Chainsta
         Ok(())
     }
 
+    #[log_error]
+    pub fn set_pending_operation(
+        &mut self,
+        operation: &PendingOperation,
+    ) -> Result<(), BlockError> {
+        self.db_tx.set_pending_operation(operation).map_err(BlockError::from)
+    }
+
+    #[log_error]
+    pub fn del_pending_operation(&mut self) -> Result<(), BlockError> {
+        self.db_tx.del_pending_operation().map_err(BlockError::from)
+    }
+
     #[log_error]
     fn post_connect_tip(&mut self, tip_index: &BlockIndex, tip: &Block) -> Result<(), BlockError> {
         let tip_height = tip_index.block_height();
@@ -1543,3 +1851,47 @@ impl NonZeroPoolBalances {
         self.staker_balance
     }
 }
+
+/// The reward of a PoS block, i.e. the block subsidy and the fees of its transactions,
+/// as it was credited to the pool that produced the block and to the pool's delegations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockRewardDistribution {
+    pool_id: PoolId,
+    staker_reward: Amount,
+    delegation_rewards: BTreeMap<DelegationId, Amount>,
+}
+
+impl BlockRewardDistribution {
+    pub fn new(
+        pool_id: PoolId,
+        staker_reward: Amount,
+        delegation_rewards: BTreeMap<DelegationId, Amount>,
+    ) -> Self {
+        Self {
+            pool_id,
+            staker_reward,
+            delegation_rewards,
+        }
+    }
+
+    pub fn pool_id(&self) -> PoolId {
+        self.pool_id
+    }
+
+    /// The part of the reward that went to the staker, including the cost per block
+    /// and the margin
+    pub fn staker_reward(&self) -> Amount {
+        self.staker_reward
+    }
+
+    /// The non-zero rewards of the pool's delegations
+    pub fn delegation_rewards(&self) -> &BTreeMap<DelegationId, Amount> {
+        &self.delegation_rewards
+    }
+
+    pub fn total_reward(&self) -> Option<Amount> {
+        std::iter::once(self.staker_reward)
+            .chain(self.delegation_rewards.values().copied())
+            .sum()
+    }
+}
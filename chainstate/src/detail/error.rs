@@ -17,9 +17,10 @@ use derive_more::Display;
 use thiserror::Error;
 
 use super::{
-    block_invalidation::BestChainCandidatesError,
+    block_invalidation::{BestChainCandidatesError, BlockInvalidatorError},
     chainstateref::{EpochSealError, InMemoryReorgError},
     orphan_blocks::OrphanAddError,
+    recovery::ChainInconsistency,
     transaction_verifier::{
         error::ConnectTransactionError, storage::TransactionVerifierStorageError,
     },
@@ -124,6 +125,8 @@ pub enum DbCommittingContext {
     Block(Id<Block>),
     #[display(fmt = "committing block status for block {}", _0)]
     BlockStatus(Id<Block>),
+    #[display(fmt = "rolling back the mainchain to block {}", _0)]
+    Rollback(Id<GenBlock>),
 }
 
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
@@ -218,6 +221,16 @@ pub enum InitializationError {
     StorageCompatibilityCheckError(#[from] StorageCompatibilityCheckError),
     #[error("Error initializing best chain candidates: {0}")]
     BestChainCandidatesError(#[from] BestChainCandidatesError),
+    #[error("Failed to roll back the mainchain during recovery: {0}")]
+    RecoveryRollbackFailed(BlockError),
+    #[error("Failed to replay the mainchain blocks during recovery: {0}")]
+    RecoveryReplayFailed(BlockInvalidatorError),
+    #[error("The chainstate is still inconsistent after recovery: {0}")]
+    InconsistencyAfterRecovery(ChainInconsistency),
+    #[error(
+        "Deferring transactions during the initial block download can only be disabled on regtest"
+    )]
+    TxsDeferralDisabledOutsideRegtest,
 }
 
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
@@ -426,9 +426,9 @@ impl BlockProcessingErrorClassification for PropertyQueryError {
             | PropertyQueryError::BlockForHeightNotFound(_)
             | PropertyQueryError::GenesisHeaderRequested
             | PropertyQueryError::InvalidStartingBlockHeightForMainchainBlocks(_)
-            | PropertyQueryError::InvalidBlockHeightRange { .. } => {
-                BlockProcessingErrorClass::General
-            }
+            | PropertyQueryError::InvalidBlockHeightRange { .. }
+            | PropertyQueryError::TxIndexDisabled
+            | PropertyQueryError::EpochNotSealed(_) => BlockProcessingErrorClass::General,
             // Note: these errors are strange - sometimes they don't look like General, judging
             // by the code that uses them. But other times some of them seem to just wrap storage
             // errors.
@@ -439,6 +439,7 @@ impl BlockProcessingErrorClassification for PropertyQueryError {
 
             PropertyQueryError::StorageError(err) => err.classify(),
             PropertyQueryError::GetAncestorError(err) => err.classify(),
+            PropertyQueryError::PoSAccountingError(err) => err.classify(),
         }
     }
 }
@@ -801,7 +802,8 @@ impl BlockProcessingErrorClassification for CheckTransactionError {
             | CheckTransactionError::HtlcsAreNotActivated
             | CheckTransactionError::OrdersAreNotActivated(_)
             | CheckTransactionError::ChangeTokenMetadataUriNotActivated
-            | CheckTransactionError::OrdersCurrenciesMustBeDifferent(_) => {
+            | CheckTransactionError::OrdersCurrenciesMustBeDifferent(_)
+            | CheckTransactionError::InvalidWitnessFormat(_) => {
                 BlockProcessingErrorClass::BadBlock
             }
 
@@ -0,0 +1,397 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use common::{
+    chain::{block::signed_block_header::SignedBlockHeader, Block, GenBlock},
+    primitives::{BlockHeight, Id, Idable, H256},
+    Uint256,
+};
+
+/// The maximum number of headers that can be kept ahead of their blocks.
+pub const MAX_PENDING_HEADERS: usize = 200_000;
+
+/// Block headers that have been validated and stored ahead of the block bodies.
+///
+/// Headers are removed from here once the corresponding blocks are processed. The chain is kept
+/// in memory only; after a restart the headers are simply received from peers again.
+///
+/// A header counts towards the best header only once its consensus data has been checked fully
+/// against its parent block, which is also when its chain trust becomes known. The headers whose
+/// parent block hasn't been processed yet are checked once it is; until then they don't count,
+/// otherwise a peer could make any chain of forged headers the best one.
+pub struct HeadersChain {
+    headers: BTreeMap<Id<Block>, (SignedBlockHeader, BlockHeight)>,
+    by_height: BTreeSet<(BlockHeight, Id<Block>)>,
+    chain_trusts: BTreeMap<Id<Block>, Uint256>,
+    validated_by_trust: BTreeSet<(Uint256, Id<Block>)>,
+    max_len: usize,
+}
+
+impl HeadersChain {
+    pub fn new() -> Self {
+        Self::with_max_len(MAX_PENDING_HEADERS)
+    }
+
+    fn with_max_len(max_len: usize) -> Self {
+        Self {
+            headers: BTreeMap::new(),
+            by_height: BTreeSet::new(),
+            chain_trusts: BTreeMap::new(),
+            validated_by_trust: BTreeSet::new(),
+            max_len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.headers.len()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len() >= self.max_len
+    }
+
+    pub fn get(&self, block_id: &Id<Block>) -> Option<&(SignedBlockHeader, BlockHeight)> {
+        self.headers.get(block_id)
+    }
+
+    pub fn contains(&self, block_id: &Id<Block>) -> bool {
+        self.headers.contains_key(block_id)
+    }
+
+    /// Return the id, the height and the chain trust of the fully checked header
+    /// with the most chain trust
+    pub fn best_header(&self) -> Option<(Id<GenBlock>, BlockHeight, Uint256)> {
+        self.validated_by_trust.last().map(|(chain_trust, block_id)| {
+            let (_, height) = self.headers.get(block_id).expect("must be present");
+            ((*block_id).into(), *height, *chain_trust)
+        })
+    }
+
+    /// Add a header; `chain_trust` is known only if it has been checked fully
+    pub fn insert(
+        &mut self,
+        header: SignedBlockHeader,
+        height: BlockHeight,
+        chain_trust: Option<Uint256>,
+    ) {
+        let block_id = header.get_id();
+        if self.headers.insert(block_id, (header, height)).is_none() {
+            self.by_height.insert((height, block_id));
+        }
+        if let Some(chain_trust) = chain_trust {
+            self.mark_validated(&block_id, chain_trust);
+        }
+    }
+
+    fn is_validated(&self, block_id: &Id<Block>) -> bool {
+        self.chain_trusts.contains_key(block_id)
+    }
+
+    /// Mark the header as checked fully, e.g. once its parent block has been processed
+    pub fn mark_validated(&mut self, block_id: &Id<Block>, chain_trust: Uint256) {
+        if self.headers.contains_key(block_id)
+            && self.chain_trusts.insert(*block_id, chain_trust).is_none()
+        {
+            self.validated_by_trust.insert((chain_trust, *block_id));
+        }
+    }
+
+    /// Return the headers building on top of the block at the given height that haven't been
+    /// checked fully yet
+    pub fn unvalidated_children_of(
+        &self,
+        parent_id: &Id<Block>,
+        parent_height: BlockHeight,
+    ) -> Vec<SignedBlockHeader> {
+        let height = parent_height.next_height();
+        let parent_id: Id<GenBlock> = (*parent_id).into();
+
+        self.by_height
+            .range((height, Id::new(H256::zero()))..)
+            .take_while(|(child_height, _)| *child_height == height)
+            .filter(|(_, id)| !self.is_validated(id))
+            .map(|(_, id)| &self.headers.get(id).expect("must be present").0)
+            .filter(|header| *header.prev_block_id() == parent_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Remove the header, e.g. because the block itself has been received
+    pub fn remove(&mut self, block_id: &Id<Block>) {
+        if let Some((_, height)) = self.headers.remove(block_id) {
+            self.by_height.remove(&(height, *block_id));
+            if let Some(chain_trust) = self.chain_trusts.remove(block_id) {
+                self.validated_by_trust.remove(&(chain_trust, *block_id));
+            }
+        }
+    }
+
+    /// Remove all the headers that build on top of the specified block, e.g. if it turned out
+    /// to be invalid
+    pub fn remove_descendants_of(&mut self, block_id: &Id<Block>) {
+        let mut removed_ids: BTreeSet<Id<GenBlock>> = BTreeSet::from([(*block_id).into()]);
+
+        // Parents always have lower heights than their children, so a single pass is enough
+        let descendants = self
+            .by_height
+            .iter()
+            .filter(|(_, id)| {
+                let (header, _) = self.headers.get(id).expect("must be present");
+                if removed_ids.contains(header.prev_block_id()) {
+                    removed_ids.insert((*id).into());
+                    true
+                } else {
+                    false
+                }
+            })
+            .map(|(_, id)| *id)
+            .collect::<Vec<_>>();
+
+        for id in descendants {
+            self.remove(&id);
+        }
+    }
+
+    /// Make room for new headers.
+    ///
+    /// First the stale headers are dropped, i.e. the ones that don't lead to a chain higher than
+    /// the best block, so their blocks are not going to be needed. If that's not enough, only
+    /// the fully checked headers and the ancestors of the highest header are kept.
+    pub fn evict(&mut self, best_block_height: BlockHeight) {
+        // The height of the highest header each header leads to; children have greater heights
+        // than their parents, so a single pass from the top is enough
+        let mut reached_heights = BTreeMap::<Id<Block>, BlockHeight>::new();
+        let mut stale = Vec::new();
+        for (height, id) in self.by_height.iter().rev() {
+            let reached_height = reached_heights.get(id).copied().unwrap_or(*height);
+            if reached_height <= best_block_height {
+                stale.push(*id);
+            }
+
+            let (header, _) = self.headers.get(id).expect("must be present");
+            let parent_id = Id::<Block>::new(header.prev_block_id().to_hash());
+            if self.contains(&parent_id) {
+                let parent_reached_height = reached_heights.entry(parent_id).or_insert(*height);
+                *parent_reached_height = std::cmp::max(*parent_reached_height, reached_height);
+            }
+        }
+        for id in stale {
+            self.remove(&id);
+        }
+
+        if !self.is_full() {
+            return;
+        }
+
+        let mut best_chain = BTreeSet::new();
+        let mut next = self.by_height.last().map(|(_, id)| *id);
+        while let Some(id) = next {
+            best_chain.insert(id);
+            // The genesis is never stored here, so the parent is a block if it's stored
+            next = self
+                .headers
+                .get(&id)
+                .map(|(header, _)| Id::<Block>::new(header.prev_block_id().to_hash()))
+                .filter(|parent_id| self.contains(parent_id));
+        }
+
+        let off_best_chain = self
+            .by_height
+            .iter()
+            .filter(|(_, id)| !best_chain.contains(id) && !self.is_validated(id))
+            .map(|(_, id)| *id)
+            .collect::<Vec<_>>();
+        for id in off_best_chain {
+            self.remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::{
+        chain::block::{timestamp::BlockTimestamp, BlockHeader, ConsensusData},
+        primitives::H256,
+    };
+
+    fn make_header(prev_block_id: Id<GenBlock>, timestamp: u64) -> SignedBlockHeader {
+        BlockHeader::new(
+            prev_block_id,
+            H256::zero(),
+            H256::zero(),
+            BlockTimestamp::from_int_seconds(timestamp),
+            ConsensusData::None,
+        )
+        .with_no_signature()
+    }
+
+    fn trust(value: u64) -> Option<Uint256> {
+        Some(Uint256::from_u64(value))
+    }
+
+    #[test]
+    fn best_header_and_removal() {
+        let root = Id::<GenBlock>::new(H256::zero());
+        let mut chain = HeadersChain::new();
+        assert_eq!(chain.best_header(), None);
+
+        // root <- a1 <- a2 <- a3
+        //      <- b1
+        let a1 = make_header(root, 1);
+        let a2 = make_header(a1.get_id().into(), 2);
+        let a3 = make_header(a2.get_id().into(), 3);
+        let b1 = make_header(root, 4);
+        for (header, height) in [(&a1, 1), (&a2, 2), (&a3, 3), (&b1, 1)] {
+            chain.insert(header.clone(), BlockHeight::new(height), trust(height));
+        }
+        assert_eq!(chain.len(), 4);
+        assert_eq!(
+            chain.best_header(),
+            Some((
+                a3.get_id().into(),
+                BlockHeight::new(3),
+                Uint256::from_u64(3)
+            ))
+        );
+
+        chain.remove(&a1.get_id());
+        assert!(!chain.contains(&a1.get_id()));
+        assert_eq!(chain.len(), 3);
+
+        chain.remove_descendants_of(&a1.get_id());
+        assert_eq!(chain.len(), 1);
+        assert_eq!(
+            chain.best_header(),
+            Some((
+                b1.get_id().into(),
+                BlockHeight::new(1),
+                Uint256::from_u64(1)
+            ))
+        );
+    }
+
+    #[test]
+    fn unvalidated_headers_are_not_best() {
+        let root = Id::<GenBlock>::new(H256::zero());
+        let mut chain = HeadersChain::new();
+
+        // root <- a1 <- a2 <- a3, only a1 is checked fully
+        let a1 = make_header(root, 1);
+        let a2 = make_header(a1.get_id().into(), 2);
+        let a3 = make_header(a2.get_id().into(), 3);
+        chain.insert(a1.clone(), BlockHeight::new(1), trust(1));
+        chain.insert(a2.clone(), BlockHeight::new(2), None);
+        chain.insert(a3.clone(), BlockHeight::new(3), None);
+        assert_eq!(
+            chain.best_header(),
+            Some((
+                a1.get_id().into(),
+                BlockHeight::new(1),
+                Uint256::from_u64(1)
+            ))
+        );
+
+        // The block a1 is processed, a2 can be checked now
+        chain.remove(&a1.get_id());
+        assert_eq!(chain.best_header(), None);
+        assert_eq!(
+            chain.unvalidated_children_of(&a1.get_id(), BlockHeight::new(1)),
+            vec![a2.clone()]
+        );
+        chain.mark_validated(&a2.get_id(), Uint256::from_u64(2));
+        assert!(chain.unvalidated_children_of(&a1.get_id(), BlockHeight::new(1)).is_empty());
+        assert_eq!(
+            chain.best_header(),
+            Some((
+                a2.get_id().into(),
+                BlockHeight::new(2),
+                Uint256::from_u64(2)
+            ))
+        );
+    }
+
+    #[test]
+    fn best_header_by_chain_trust() {
+        let root = Id::<GenBlock>::new(H256::zero());
+        let mut chain = HeadersChain::new();
+
+        // root <- a1 <- a2
+        //      <- b1, which has more chain trust than a2 despite the lower height
+        let a1 = make_header(root, 1);
+        let a2 = make_header(a1.get_id().into(), 2);
+        let b1 = make_header(root, 3);
+        chain.insert(a1.clone(), BlockHeight::new(1), trust(1));
+        chain.insert(a2.clone(), BlockHeight::new(2), trust(2));
+        chain.insert(b1.clone(), BlockHeight::new(1), trust(5));
+        assert_eq!(
+            chain.best_header(),
+            Some((
+                b1.get_id().into(),
+                BlockHeight::new(1),
+                Uint256::from_u64(5)
+            ))
+        );
+
+        chain.remove(&b1.get_id());
+        assert_eq!(
+            chain.best_header(),
+            Some((
+                a2.get_id().into(),
+                BlockHeight::new(2),
+                Uint256::from_u64(2)
+            ))
+        );
+    }
+
+    #[test]
+    fn eviction() {
+        let root = Id::<GenBlock>::new(H256::zero());
+        let mut chain = HeadersChain::with_max_len(5);
+
+        // root <- a1 <- a2 <- a3 <- a4
+        //      <- b1 <- b2
+        let a1 = make_header(root, 1);
+        let a2 = make_header(a1.get_id().into(), 2);
+        let a3 = make_header(a2.get_id().into(), 3);
+        let a4 = make_header(a3.get_id().into(), 4);
+        let b1 = make_header(root, 5);
+        let b2 = make_header(b1.get_id().into(), 6);
+        chain.insert(a1.clone(), BlockHeight::new(1), trust(1));
+        chain.insert(a2.clone(), BlockHeight::new(2), None);
+        chain.insert(b1.clone(), BlockHeight::new(1), trust(1));
+        chain.insert(b2.clone(), BlockHeight::new(2), None);
+        chain.insert(a3.clone(), BlockHeight::new(3), None);
+        assert!(chain.is_full());
+
+        // Nothing is stale, the unvalidated headers off the chain of the highest header go
+        chain.evict(BlockHeight::new(0));
+        assert_eq!(chain.len(), 4);
+        assert!(!chain.contains(&b2.get_id()));
+        assert!(chain.contains(&b1.get_id()));
+
+        // Only the chains that don't get above the best block are stale
+        chain.insert(a4.clone(), BlockHeight::new(4), None);
+        chain.evict(BlockHeight::new(3));
+        assert_eq!(chain.len(), 4);
+        assert!(!chain.contains(&b1.get_id()));
+
+        chain.evict(BlockHeight::new(4));
+        assert_eq!(chain.len(), 0);
+    }
+}
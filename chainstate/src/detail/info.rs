@@ -27,4 +27,8 @@ pub struct ChainInfo {
     pub best_block_timestamp: BlockTimestamp,
     pub median_time: BlockTimestamp,
     pub is_initial_block_download: bool,
+    /// The best known block header, which is ahead of the best block if the headers of
+    /// blocks that haven't been downloaded yet are known
+    pub best_header_id: Id<GenBlock>,
+    pub best_header_height: BlockHeight,
 }
@@ -16,9 +16,12 @@
 mod chainstateref;
 mod error;
 mod error_classification;
+mod headers_chain;
 mod info;
 mod median_time;
 mod orphan_blocks;
+mod recovery;
+mod undo_compaction;
 
 pub mod ban_score;
 pub mod block_checking;
@@ -27,7 +30,11 @@ pub mod bootstrap;
 pub mod query;
 pub mod tx_verification_strategy;
 
-use std::{collections::VecDeque, sync::Arc};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
 
 use itertools::Itertools;
 use thiserror::Error;
@@ -35,13 +42,15 @@ use utils_networking::broadcaster;
 
 use self::{
     block_invalidation::BlockInvalidator,
+    headers_chain::HeadersChain,
     orphan_blocks::{OrphanBlocksMut, OrphansProxy},
     query::ChainstateQuery,
     tx_verification_strategy::TransactionVerificationStrategy,
 };
 use crate::{BlockInvalidatorError, ChainstateConfig, ChainstateEvent};
 use chainstate_storage::{
-    BlockchainStorage, BlockchainStorageRead, BlockchainStorageWrite, TransactionRw, Transactional,
+    BlockchainStorage, BlockchainStorageRead, BlockchainStorageWrite, CompactionStatus,
+    StorageMaintenance, StorageSizeInfo, TransactionRw, Transactional,
 };
 use chainstate_types::{
     pos_randomness::PoSRandomness, BlockIndex, BlockStatus, BlockValidationStage, EpochData,
@@ -49,7 +58,11 @@ use chainstate_types::{
 };
 use chainstateref::{ChainstateRef, ReorgError};
 use common::{
-    chain::{block::timestamp::BlockTimestamp, config::ChainConfig, Block, GenBlock, TxOutput},
+    chain::{
+        block::{signed_block_header::SignedBlockHeader, timestamp::BlockTimestamp},
+        config::{ChainConfig, ChainType},
+        Block, GenBlock, TxOutput,
+    },
     primitives::{id::WithId, BlockHeight, Compact, Id, Idable},
     time_getter::TimeGetter,
     Uint256,
@@ -74,19 +87,21 @@ pub use self::{
     error::*, info::ChainInfo, median_time::calculate_median_time_past,
     median_time::calculate_median_time_past_from_blocktimestamps, median_time::MEDIAN_TIME_SPAN,
 };
-pub use chainstate_types::Locator;
-pub use chainstateref::NonZeroPoolBalances;
+pub use chainstate_types::{BlockDataAvailability, Locator};
+pub use chainstateref::{BlockRewardDistribution, NonZeroPoolBalances};
 pub use error::{
     BlockError, CheckBlockError, CheckBlockTransactionsError, DbCommittingContext,
     InitializationError, OrphanCheckError, StorageCompatibilityCheckError,
 };
 pub use error_classification::{BlockProcessingErrorClass, BlockProcessingErrorClassification};
 pub use orphan_blocks::OrphanBlocksRef;
+pub use recovery::{ChainInconsistency, RecoveryReport};
 pub use transaction_verifier::{
     error::{ConnectTransactionError, SpendStakeError, TokenIssuanceError, TokensError},
     storage::TransactionVerifierStorageError,
     IOPolicyError,
 };
+pub use undo_compaction::{UndoCompactionError, UndoCompactionReport};
 
 type TxRw<'a, S> = <S as Transactional<'a>>::TransactionRw;
 type TxRo<'a, S> = <S as Transactional<'a>>::TransactionRo;
@@ -101,13 +116,25 @@ pub struct Chainstate<S, V> {
     chainstate_storage: S,
     tx_verification_strategy: V,
     orphan_blocks: OrphansProxy,
+    headers_chain: HeadersChain,
     custom_orphan_error_hook: Option<Arc<OrphanErrorHandler>>,
     subsystem_events: EventsController<ChainstateEvent>,
     rpc_events: broadcaster::Broadcaster<ChainstateEvent>,
     time_getter: TimeGetter,
     is_initial_block_download_finished: SetFlag,
+    recovery_report: Option<RecoveryReport>,
+    /// When the storage has last been checked for the automatic compaction, since the epoch
+    last_storage_maintenance: Duration,
 }
 
+/// The compaction is scheduled automatically if the database files are this many times larger
+/// than the data stored in them
+const AUTO_COMPACTION_MIN_DISK_TO_DATA_RATIO: u64 = 2;
+
+/// The number of blocks whose transactions are indexed in a single db transaction
+/// when the transaction index is built
+const TX_INDEX_BUILD_BATCH_SIZE: u64 = 1000;
+
 #[derive(Copy, Clone, Eq, Debug, PartialEq)]
 pub enum BlockSource {
     Peer,
@@ -154,6 +181,59 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         self.make_db_tx_ro().map(ChainstateQuery::new).map_err(PropertyQueryError::from)
     }
 
+    #[log_error]
+    pub fn storage_size_info(&self) -> chainstate_storage::Result<StorageSizeInfo> {
+        self.chainstate_storage.size_info()
+    }
+
+    #[log_error]
+    pub fn compact_storage(&mut self) -> chainstate_storage::Result<CompactionStatus> {
+        self.chainstate_storage.compact()
+    }
+
+    /// Whether it's time to check if the storage should be compacted. The check is only done
+    /// after the initial block download, when the database doesn't change as quickly.
+    pub fn is_storage_maintenance_due(&self) -> bool {
+        let interval = match self.chainstate_config.auto_storage_compaction_interval {
+            Some(interval) => interval,
+            None => return false,
+        };
+
+        let now = self.time_getter.get_time().as_duration_since_epoch();
+        !self.is_initial_block_download()
+            && now.saturating_sub(self.last_storage_maintenance) >= interval
+    }
+
+    /// Schedule the compaction of the storage if the database files have grown much larger
+    /// than the data stored in them
+    #[log_error]
+    pub fn perform_storage_maintenance(&mut self) -> chainstate_storage::Result<()> {
+        self.last_storage_maintenance = self.time_getter.get_time().as_duration_since_epoch();
+
+        let info = self.chainstate_storage.size_info()?;
+        let disk_size = match info.disk_size {
+            Some(disk_size) if !info.compaction_scheduled => disk_size,
+            Some(_) | None => return Ok(()),
+        };
+        let data_size = info
+            .maps
+            .iter()
+            .map(|map| map.key_bytes.saturating_add(map.value_bytes))
+            .fold(0u64, u64::saturating_add);
+
+        if disk_size > data_size.saturating_mul(AUTO_COMPACTION_MIN_DISK_TO_DATA_RATIO) {
+            let status = self.chainstate_storage.compact()?;
+            log::info!(
+                "Storage compaction requested, {} bytes on disk for {} bytes of data: {:?}",
+                disk_size,
+                data_size,
+                status,
+            );
+        }
+
+        Ok(())
+    }
+
     pub fn subscribe_to_events(&mut self, handler: ChainstateEventHandler) {
         self.subsystem_events.subscribe_to_events(handler);
     }
@@ -173,6 +253,14 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
     ) -> Result<Self, crate::ChainstateError> {
         use crate::ChainstateError;
 
+        ensure!(
+            chainstate_config.txs_deferred_during_ibd()
+                || *chain_config.chain_type() == ChainType::Regtest,
+            ChainstateError::FailedToInitializeChainstate(
+                InitializationError::TxsDeferralDisabledOutsideRegtest
+            )
+        );
+
         let best_block_id = {
             let db_tx = chainstate_storage
                 .transaction_ro()
@@ -195,8 +283,12 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
             chainstate.process_genesis().map_err(ChainstateError::ProcessBlockError)?;
         } else {
             chainstate.check_genesis().map_err(crate::ChainstateError::from)?;
+            chainstate.recovery_report =
+                chainstate.recover_if_needed().map_err(crate::ChainstateError::from)?;
         }
 
+        chainstate.update_tx_index().map_err(crate::ChainstateError::from)?;
+
         chainstate.update_initial_block_download_flag()?;
 
         chainstate
@@ -217,17 +309,21 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         let orphan_blocks = OrphansProxy::new(*chainstate_config.max_orphan_blocks);
         let subsystem_events = EventsController::new();
         let rpc_events = broadcaster::Broadcaster::new();
+        let last_storage_maintenance = time_getter.get_time().as_duration_since_epoch();
         Self {
             chain_config,
             chainstate_config: chainstate_config.into(),
             chainstate_storage,
             tx_verification_strategy,
             orphan_blocks,
+            headers_chain: HeadersChain::new(),
             custom_orphan_error_hook,
             subsystem_events,
             rpc_events,
             time_getter,
             is_initial_block_download_finished: SetFlag::new(),
+            recovery_report: None,
+            last_storage_maintenance,
         }
     }
 
@@ -259,6 +355,64 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         Ok(())
     }
 
+    /// Build the transaction index if it has been enabled since the last start, or remove it
+    /// if it has been disabled.
+    #[log_error]
+    fn update_tx_index(&mut self) -> Result<(), InitializationError> {
+        let enable = self.chainstate_config.tx_index_enabled();
+
+        let mut db_tx = self.chainstate_storage.transaction_rw(None)?;
+        if db_tx.get_tx_index_enabled()?.unwrap_or(false) == enable {
+            db_tx.abort();
+            return Ok(());
+        }
+
+        if !enable {
+            log::info!("Removing the transaction index");
+            db_tx.clear_tx_index()?;
+            db_tx.set_tx_index_enabled(false)?;
+            db_tx.commit()?;
+            return Ok(());
+        }
+
+        log::info!("Building the transaction index, this may take a while");
+
+        // The index is only marked as enabled once it's complete, so an interrupted build
+        // starts over on the next start
+        db_tx.clear_tx_index()?;
+        db_tx.commit()?;
+
+        let mut height = BlockHeight::one();
+        loop {
+            let batch_end =
+                BlockHeight::new(height.into_int().saturating_add(TX_INDEX_BUILD_BATCH_SIZE));
+
+            let mut db_tx = self.chainstate_storage.transaction_rw(None)?;
+            while height < batch_end {
+                let block_id = match db_tx.get_block_id_by_height(&height)? {
+                    Some(block_id) => block_id,
+                    None => {
+                        db_tx.set_tx_index_enabled(true)?;
+                        db_tx.commit()?;
+                        return Ok(());
+                    }
+                };
+                let block_id = block_id
+                    .classify(&self.chain_config)
+                    .chain_block_id()
+                    .ok_or(PropertyQueryError::BlockForHeightNotFound(height))?;
+                let block = db_tx
+                    .get_block(block_id)?
+                    .ok_or(PropertyQueryError::BlockNotFound(block_id))?;
+                for tx in block.transactions() {
+                    db_tx.set_tx_block_id(&tx.transaction().get_id(), &block_id)?;
+                }
+                height = height.next_height();
+            }
+            db_tx.commit()?;
+        }
+    }
+
     fn broadcast_new_tip_event(&mut self, new_block_index: &Option<BlockIndex>) {
         match new_block_index {
             Some(ref new_block_index) => {
@@ -397,6 +551,9 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         let block = self.check_legitimate_orphan(block_source, block)?;
         let block_id = block.get_id();
 
+        // The block has arrived, so its header is no longer ahead of it.
+        self.headers_chain.remove(&block_id);
+
         // Ensure that the block being submitted is new to us. If not, bail out immediately,
         // otherwise create a new block index and continue.
         let block_index = {
@@ -431,10 +588,15 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
                 // If the above code has succeeded, then the block_index must be present in the DB.
                 // Note that we can't return the initially obtained block_index, because its
                 // block status is outdated.
-                let chainstate_ref = self.make_db_tx_ro().map_err(BlockError::from)?;
-                let saved_block_index = get_existing_block_index(&chainstate_ref, &block_id)?;
+                let saved_block_index = {
+                    let chainstate_ref = self.make_db_tx_ro().map_err(BlockError::from)?;
+                    get_existing_block_index(&chainstate_ref, &block_id)?
+                };
 
                 assert!(saved_block_index.status().is_ok());
+
+                self.validate_pending_headers_of(&block_id, saved_block_index.block_height());
+
                 return Ok(reorg_occurred.then_some(saved_block_index));
             }
             Err(BlockIntegrationError::BlockCommitError(block_id, attempts_count, db_err)) => {
@@ -506,6 +668,7 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
                     status.set_validation_failed();
                     // Ignore the result, because we already have an error to return.
                     let _result = self.set_new_block_index(&block_index.with_status(status));
+                    self.headers_chain.remove_descendants_of(&block_id);
                 } else {
                     log::warn!(
                         "Block {} integration failed, but it may not be a bad block",
@@ -653,6 +816,179 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         result
     }
 
+    /// Validate the headers of blocks that haven't been received yet and store them ahead of
+    /// the blocks themselves, so that the bodies can be downloaded later.
+    ///
+    /// The headers must be connected to each other. The first one must be connected either to
+    /// a known block or to a header stored earlier. Headers whose parent block is known are
+    /// checked fully and may become the best header; the others are only checked against their
+    /// parent header and don't count toward the best header until they are checked fully once
+    /// their parent block is processed. When the storage is full, the headers that can no longer
+    /// extend the best chain are evicted first.
+    #[log_error]
+    pub fn process_block_headers(
+        &mut self,
+        headers: &[SignedBlockHeader],
+    ) -> Result<(), BlockError> {
+        let first_header = match headers.first() {
+            Some(header) => header,
+            None => return Ok(()),
+        };
+
+        for (prev_header, header) in headers.iter().tuple_windows() {
+            ensure!(
+                header.prev_block_id() == &prev_header.get_id().into(),
+                BlockError::InvariantErrorDisconnectedHeaders
+            );
+        }
+
+        let (new_headers, best_block_height) = {
+            let chainstate_ref = self.make_db_tx_ro().map_err(BlockError::from)?;
+            let best_block_height = chainstate_ref
+                .get_best_block_index()
+                .map_err(BlockError::PropertyQueryError)?
+                .block_height();
+
+            let first_parent_id = first_header.prev_block_id();
+            let mut height = match chainstate_ref
+                .get_gen_block_index(first_parent_id)
+                .map_err(BlockError::PropertyQueryError)?
+            {
+                Some(parent_block_index) => parent_block_index.block_height(),
+                None => first_parent_id
+                    .classify(&self.chain_config)
+                    .chain_block_id()
+                    .and_then(|parent_id| self.headers_chain.get(&parent_id))
+                    .map(|(_, parent_height)| *parent_height)
+                    .ok_or(CheckBlockError::ParentBlockMissing {
+                        block_id: first_header.get_id(),
+                        parent_block_id: *first_parent_id,
+                    })?,
+            };
+
+            let mut new_headers = Vec::new();
+            let mut headers_in_batch = BTreeMap::new();
+            for header in headers {
+                height = height.next_height();
+                let block_id = header.get_id();
+
+                let is_known = self.headers_chain.contains(&block_id)
+                    || chainstate_ref
+                        .get_block_index(&block_id)
+                        .map_err(BlockError::PropertyQueryError)?
+                        .is_some();
+                if is_known {
+                    continue;
+                }
+
+                let is_parent_known = chainstate_ref
+                    .get_gen_block_index(header.prev_block_id())
+                    .map_err(BlockError::PropertyQueryError)?
+                    .is_some();
+                let parent_id =
+                    header.prev_block_id().classify(&self.chain_config).chain_block_id();
+                let parent_header = parent_id.and_then(|parent_id| {
+                    headers_in_batch
+                        .get(&parent_id)
+                        .copied()
+                        .or_else(|| self.headers_chain.get(&parent_id).map(|(header, _)| header))
+                });
+
+                let chain_trust = if is_parent_known {
+                    chainstate_ref.check_block_header(header)?;
+                    Some(chainstate_ref.get_header_chain_trust(header)?)
+                } else if let Some(parent_header) = parent_header {
+                    chainstate_ref.check_block_header_against_parent_header(
+                        header,
+                        parent_header,
+                        height,
+                    )?;
+                    None
+                } else {
+                    chainstate_ref.check_pending_block_header(header, height)?;
+                    None
+                };
+
+                headers_in_batch.insert(block_id, header);
+                new_headers.push((header.clone(), height, chain_trust));
+            }
+            (new_headers, best_block_height)
+        };
+
+        for (header, height, chain_trust) in new_headers {
+            if self.headers_chain.is_full() {
+                self.headers_chain.evict(best_block_height);
+            }
+            if self.headers_chain.is_full() {
+                log::warn!(
+                    "Too many headers are stored ahead of their blocks, ignoring header {}",
+                    header.get_id()
+                );
+                break;
+            }
+            self.headers_chain.insert(header, height, chain_trust);
+        }
+
+        Ok(())
+    }
+
+    /// Check fully the stored headers building on top of a block that has just been processed,
+    /// only the checks not requiring the parent block have been done for them so far.
+    /// The headers failing the checks are dropped together with their descendants.
+    fn validate_pending_headers_of(&mut self, block_id: &Id<Block>, block_height: BlockHeight) {
+        let children = self.headers_chain.unvalidated_children_of(block_id, block_height);
+        if children.is_empty() {
+            return;
+        }
+
+        let check_results = match self.make_db_tx_ro() {
+            Ok(chainstate_ref) => children
+                .iter()
+                .map(|header| {
+                    let check_result = chainstate_ref
+                        .check_block_header(header)
+                        .map_err(BlockError::from)
+                        .and_then(|()| chainstate_ref.get_header_chain_trust(header));
+                    (header.get_id(), check_result)
+                })
+                .collect::<Vec<_>>(),
+            Err(err) => {
+                log::error!("Failed to check the headers building on block {block_id}: {err}");
+                return;
+            }
+        };
+
+        for (header_id, check_result) in check_results {
+            match check_result {
+                Ok(chain_trust) => self.headers_chain.mark_validated(&header_id, chain_trust),
+                Err(err) => {
+                    log::warn!("Dropping the invalid stored header {header_id}: {err}");
+                    self.headers_chain.remove(&header_id);
+                    self.headers_chain.remove_descendants_of(&header_id);
+                }
+            }
+        }
+    }
+
+    /// Return the id and height of the best known header. It differs from the best block if
+    /// a fully checked header of a block that hasn't been processed yet has more chain trust.
+    #[log_error]
+    pub fn best_header_id_and_height(
+        &self,
+    ) -> Result<(Id<GenBlock>, BlockHeight), PropertyQueryError> {
+        let best_block_index = self.query()?.get_best_block_index()?;
+        let best_block = (best_block_index.block_id(), best_block_index.block_height());
+
+        Ok(match self.headers_chain.best_header() {
+            Some((block_id, height, chain_trust))
+                if chain_trust > best_block_index.chain_trust() =>
+            {
+                (block_id, height)
+            }
+            Some(_) | None => best_block,
+        })
+    }
+
     /// Initialize chainstate with genesis block
     #[log_error]
     pub fn process_genesis(&mut self) -> Result<(), BlockError> {
@@ -700,6 +1036,7 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
     pub fn invalidate_block(&mut self, block_id: &Id<Block>) -> Result<(), BlockInvalidatorError> {
         let result = BlockInvalidator::new(self)
             .invalidate_block(block_id, block_invalidation::IsExplicit::Yes);
+        self.headers_chain.remove_descendants_of(block_id);
         // Note: we don't ignore the result of check_consistency even though we may already have
         // an error to return (if the checks are enabled but couldn't be done for some reason,
         // we don't want to miss this).
@@ -744,6 +1081,12 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         &self.chainstate_config
     }
 
+    /// The report of the recovery performed on startup, if the chainstate hadn't been
+    /// shut down cleanly.
+    pub fn recovery_report(&self) -> Option<&RecoveryReport> {
+        self.recovery_report.as_ref()
+    }
+
     pub fn orphan_blocks_pool(&self) -> &OrphansProxy {
         &self.orphan_blocks
     }
@@ -756,6 +1099,10 @@ impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V>
         !self.is_initial_block_download_finished.test()
     }
 
+    pub fn is_tx_processing_deferred(&self) -> bool {
+        self.is_initial_block_download() && self.chainstate_config.txs_deferred_during_ibd()
+    }
+
     /// Returns true if the given block timestamp is newer than `ChainstateConfig::max_tip_age`.
     fn is_fresh_block(&self, time: &BlockTimestamp) -> bool {
         let now = self.time_getter.get_time().as_duration_since_epoch();
@@ -16,7 +16,9 @@
 use std::num::NonZeroUsize;
 
 use chainstate_storage::BlockchainStorageRead;
-use chainstate_types::{BlockIndex, GenBlockIndex, Locator, PropertyQueryError};
+use chainstate_types::{
+    BlockDataAvailability, BlockIndex, BlockStats, GenBlockIndex, Locator, PropertyQueryError,
+};
 use common::{
     chain::{
         block::{signed_block_header::SignedBlockHeader, BlockReward},
@@ -24,7 +26,7 @@ use common::{
             NftIssuance, RPCFungibleTokenInfo, RPCIsTokenFrozen, RPCNonFungibleTokenInfo,
             RPCTokenInfo, TokenAuxiliaryData, TokenId,
         },
-        Block, GenBlock, OrderData, OrderId, Transaction, TxOutput,
+        Block, GenBlock, OrderData, OrderId, SignedTransaction, Transaction, TxOutput,
     },
     primitives::{Amount, BlockDistance, BlockHeight, Id, Idable},
 };
@@ -32,7 +34,10 @@ use orders_accounting::OrdersAccountingStorageRead;
 use tokens_accounting::TokensAccountingStorageRead;
 use utils::ensure;
 
-use super::{chainstateref, tx_verification_strategy::TransactionVerificationStrategy};
+use super::{
+    chainstateref::{self, BlockRewardDistribution},
+    tx_verification_strategy::TransactionVerificationStrategy,
+};
 
 pub fn locator_tip_distances() -> impl Iterator<Item = BlockDistance> {
     itertools::iterate(0, |&i| std::cmp::max(1, i * 2)).map(BlockDistance::new)
@@ -159,6 +164,20 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         self.chainstate_ref.get_best_block_index()
     }
 
+    pub fn get_recent_block_stats(
+        &self,
+        count: usize,
+    ) -> Result<Vec<(BlockIndex, BlockStats)>, PropertyQueryError> {
+        self.chainstate_ref.get_recent_block_stats(count)
+    }
+
+    pub fn get_block_reward_distribution(
+        &self,
+        block_id: &Id<Block>,
+    ) -> Result<Option<BlockRewardDistribution>, PropertyQueryError> {
+        self.chainstate_ref.get_block_reward_distribution(block_id)
+    }
+
     pub fn get_best_block_header(&self) -> Result<SignedBlockHeader, PropertyQueryError> {
         let best_block_index = self.chainstate_ref.get_best_block_index()?;
         match best_block_index {
@@ -167,6 +186,16 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         }
     }
 
+    pub fn get_block_data_availability(&self) -> Result<BlockDataAvailability, PropertyQueryError> {
+        let best_block_height = self.chainstate_ref.get_best_block_index()?.block_height();
+        // Block data is never removed from the storage at this moment, so the data of all
+        // mainchain blocks is available.
+        Ok(BlockDataAvailability::new(
+            BlockHeight::one(),
+            best_block_height,
+        ))
+    }
+
     pub fn get_locator(&self) -> Result<Locator, PropertyQueryError> {
         let best_block_index = self.chainstate_ref.get_best_block_index()?;
         let height = best_block_index.block_height();
@@ -382,6 +411,13 @@ impl<'a, S: BlockchainStorageRead, V: TransactionVerificationStrategy> Chainstat
         self.chainstate_ref.get_token_id(tx_id)
     }
 
+    pub fn get_transaction(
+        &self,
+        tx_id: &Id<Transaction>,
+    ) -> Result<Option<(SignedTransaction, Id<Block>, BlockHeight)>, PropertyQueryError> {
+        self.chainstate_ref.get_transaction(tx_id)
+    }
+
     pub fn get_mainchain_blocks_list(&self) -> Result<Vec<Id<Block>>, PropertyQueryError> {
         self.chainstate_ref.get_mainchain_blocks_list()
     }
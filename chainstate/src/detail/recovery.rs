@@ -0,0 +1,329 @@
+// Copyright (c) 2022 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Recovery from a shutdown in the middle of a chainstate operation.
+//!
+//! Every db transaction is atomic, but some operations (such as block invalidation) consist of
+//! several of them. If the node is stopped between those, the chainstate may be left in a state
+//! that the rest of the code doesn't expect. On startup, the most recent mainchain blocks are
+//! checked and, if the previous run hasn't finished cleanly, they are rolled back and replayed.
+
+use thiserror::Error;
+
+use super::{
+    block_invalidation::{BlockInvalidator, IsExplicit},
+    tx_verification_strategy::TransactionVerificationStrategy,
+    BlockError, Chainstate, DbCommittingContext, InitializationError,
+};
+use chainstate_storage::{
+    BlockchainStorage, BlockchainStorageRead, BlockchainStorageWrite, PendingOperation,
+    TransactionRw, Transactional,
+};
+use chainstate_types::PropertyQueryError;
+use common::{
+    chain::{Block, ChainConfig, GenBlock},
+    primitives::{BlockDistance, BlockHeight, Id},
+};
+use logging::log;
+use utils::log_error;
+
+/// An inconsistency among the most recent mainchain blocks.
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum ChainInconsistency {
+    #[error("Block {1} is registered as the mainchain block at height {0}, above the tip")]
+    BlockAboveTip(BlockHeight, Id<GenBlock>),
+    #[error("Block {1} is not registered as the mainchain block at height {0}")]
+    BlockNotAtHeight(BlockHeight, Id<Block>),
+    #[error("Index of mainchain block {0} is missing")]
+    BlockIndexMissing(Id<Block>),
+    #[error("Mainchain block {0} has height {1} instead of {2}")]
+    BlockHeightMismatch(Id<Block>, BlockHeight, BlockHeight),
+    #[error("Mainchain block {0} is not persisted")]
+    BlockNotPersisted(Id<Block>),
+    #[error("Mainchain block {0} is not fully valid")]
+    BlockNotFullyValid(Id<Block>),
+}
+
+/// The outcome of the recovery performed on startup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryReport {
+    /// The multi-transaction operation that had been interrupted, if any.
+    pub interrupted_operation: Option<PendingOperation>,
+    /// The inconsistencies found among the most recent mainchain blocks.
+    pub inconsistencies: Vec<ChainInconsistency>,
+    pub old_tip_id: Id<GenBlock>,
+    pub old_tip_height: BlockHeight,
+    /// The last block that remained connected after the rollback.
+    pub rolled_back_to_id: Id<GenBlock>,
+    pub rolled_back_to_height: BlockHeight,
+    pub new_tip_id: Id<GenBlock>,
+    pub new_tip_height: BlockHeight,
+}
+
+impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V> {
+    /// If the previous run was interrupted in the middle of an operation or left inconsistencies
+    /// among the `recovery_replay_depth` most recent mainchain blocks, roll those blocks back,
+    /// complete the interrupted operation and replay the blocks.
+    ///
+    /// Return a report if the recovery has been performed.
+    #[log_error]
+    pub(super) fn recover_if_needed(
+        &mut self,
+    ) -> Result<Option<RecoveryReport>, InitializationError> {
+        let depth = *self.chainstate_config.recovery_replay_depth;
+
+        let (interrupted_operation, inconsistencies) = {
+            let db_tx = self.chainstate_storage.transaction_ro()?;
+            let interrupted_operation = db_tx.get_pending_operation()?;
+            let inconsistencies = find_inconsistencies(&db_tx, &self.chain_config, depth)?;
+            (interrupted_operation, inconsistencies)
+        };
+
+        if interrupted_operation.is_none() && inconsistencies.is_empty() {
+            return Ok(None);
+        }
+
+        log::warn!("The chainstate hasn't been shut down cleanly, starting recovery");
+        if let Some(operation) = &interrupted_operation {
+            log::warn!("Interrupted operation found: {operation:?}");
+        }
+        for inconsistency in &inconsistencies {
+            log::warn!("Inconsistency found: {inconsistency}");
+        }
+
+        let (old_tip_id, old_tip_height) = self.tip_id_and_height()?;
+
+        self.remove_block_ids_above_tip(&inconsistencies)?;
+        let (rolled_back_to_id, rolled_back_to_height) = self.roll_back(depth)?;
+
+        let mut block_invalidator = BlockInvalidator::new(self);
+        match interrupted_operation {
+            Some(PendingOperation::BlockInvalidation {
+                block_id,
+                tip_before: _,
+                is_explicit,
+            }) => {
+                let is_explicit = if is_explicit {
+                    IsExplicit::Yes
+                } else {
+                    IsExplicit::No
+                };
+                block_invalidator
+                    .invalidate_block(&block_id, is_explicit)
+                    .map_err(InitializationError::RecoveryReplayFailed)?;
+            }
+            None => {}
+        }
+        block_invalidator
+            .find_and_activate_best_chain()
+            .map_err(InitializationError::RecoveryReplayFailed)?;
+        block_invalidator
+            .clear_pending_operation()
+            .map_err(InitializationError::RecoveryReplayFailed)?;
+
+        {
+            let db_tx = self.chainstate_storage.transaction_ro()?;
+            if let Some(inconsistency) =
+                find_inconsistencies(&db_tx, &self.chain_config, depth)?.into_iter().next()
+            {
+                return Err(InitializationError::InconsistencyAfterRecovery(
+                    inconsistency,
+                ));
+            }
+        }
+
+        let (new_tip_id, new_tip_height) = self.tip_id_and_height()?;
+
+        log::info!(
+            "Recovery finished: the tip was {old_tip_id} at height {old_tip_height}, rolled back to {rolled_back_to_id} at height {rolled_back_to_height}, the new tip is {new_tip_id} at height {new_tip_height}"
+        );
+
+        Ok(Some(RecoveryReport {
+            interrupted_operation,
+            inconsistencies,
+            old_tip_id,
+            old_tip_height,
+            rolled_back_to_id,
+            rolled_back_to_height,
+            new_tip_id,
+            new_tip_height,
+        }))
+    }
+
+    fn tip_id_and_height(&self) -> Result<(Id<GenBlock>, BlockHeight), PropertyQueryError> {
+        let tip_index = self.make_db_tx_ro()?.get_best_block_index()?;
+        Ok((tip_index.block_id(), tip_index.block_height()))
+    }
+
+    /// Unregister the blocks that are registered as mainchain blocks above the tip; block
+    /// disconnection doesn't expect them.
+    #[log_error]
+    fn remove_block_ids_above_tip(
+        &mut self,
+        inconsistencies: &[ChainInconsistency],
+    ) -> Result<(), InitializationError> {
+        let heights_above_tip = inconsistencies
+            .iter()
+            .filter_map(|inconsistency| match inconsistency {
+                ChainInconsistency::BlockAboveTip(height, _) => Some(height),
+                ChainInconsistency::BlockNotAtHeight(_, _)
+                | ChainInconsistency::BlockIndexMissing(_)
+                | ChainInconsistency::BlockHeightMismatch(_, _, _)
+                | ChainInconsistency::BlockNotPersisted(_)
+                | ChainInconsistency::BlockNotFullyValid(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        if heights_above_tip.is_empty() {
+            return Ok(());
+        }
+
+        let mut db_tx = self.chainstate_storage.transaction_rw(None)?;
+        for height in heights_above_tip {
+            db_tx.del_block_id_at_height(height)?;
+        }
+        db_tx.commit()?;
+        Ok(())
+    }
+
+    /// Disconnect up to `depth` mainchain blocks, without going below the minimum height with
+    /// allowed reorgs. Return the id and height of the new tip.
+    #[log_error]
+    fn roll_back(
+        &mut self,
+        depth: u64,
+    ) -> Result<(Id<GenBlock>, BlockHeight), InitializationError> {
+        let (tip_id, tip_height) = self.tip_id_and_height()?;
+
+        let tip_block_id = match tip_id.classify(&self.chain_config).chain_block_id() {
+            Some(id) => id,
+            None => return Ok((tip_id, tip_height)),
+        };
+
+        let (target_id, target_height) = {
+            let chainstate_ref = self.make_db_tx_ro()?;
+            let min_height = chainstate_ref.get_min_height_with_allowed_reorg()?;
+            let depth = BlockDistance::new(depth.try_into().unwrap_or(i64::MAX));
+            let target_height = (tip_height - depth)
+                .unwrap_or(BlockHeight::zero())
+                .max(min_height)
+                .min(tip_height);
+            let target_id = chainstate_ref.get_existing_block_id_by_height(&target_height)?;
+            (target_id, target_height)
+        };
+
+        if target_id == tip_id {
+            return Ok((tip_id, tip_height));
+        }
+
+        log::info!("Rolling back the mainchain from {tip_id} at height {tip_height} to {target_id} at height {target_height}");
+
+        self.with_rw_tx(
+            |chainstate_ref| chainstate_ref.disconnect_until(&tip_block_id, &target_id),
+            |attempt_number| {
+                log::info!(
+                    "Rolling back the mainchain to block {target_id}, attempt #{attempt_number}"
+                );
+            },
+            |attempts_count, db_err| {
+                BlockError::DbCommitError(
+                    attempts_count,
+                    db_err,
+                    DbCommittingContext::Rollback(target_id),
+                )
+            },
+        )
+        .map_err(InitializationError::RecoveryRollbackFailed)?;
+
+        Ok((target_id, target_height))
+    }
+}
+
+/// Check the `depth` most recent mainchain blocks for the inconsistencies that an interrupted
+/// multi-transaction operation may leave behind.
+#[log_error]
+fn find_inconsistencies(
+    db_tx: &impl BlockchainStorageRead,
+    chain_config: &ChainConfig,
+    depth: u64,
+) -> Result<Vec<ChainInconsistency>, chainstate_storage::Error> {
+    let mut inconsistencies = Vec::new();
+
+    let best_block_id = match db_tx.get_best_block_id()? {
+        Some(id) => id,
+        None => return Ok(inconsistencies),
+    };
+
+    let tip_height = match best_block_id.classify(chain_config).chain_block_id() {
+        Some(tip_block_id) => match db_tx.get_block_index(&tip_block_id)? {
+            Some(tip_index) => tip_index.block_height(),
+            None => {
+                inconsistencies.push(ChainInconsistency::BlockIndexMissing(tip_block_id));
+                return Ok(inconsistencies);
+            }
+        },
+        None => BlockHeight::zero(),
+    };
+
+    let mut height = tip_height.next_height();
+    while let Some(id) = db_tx.get_block_id_by_height(&height)? {
+        inconsistencies.push(ChainInconsistency::BlockAboveTip(height, id));
+        height = height.next_height();
+    }
+
+    let mut height = tip_height;
+    let mut expected_id = best_block_id;
+    for _ in 0..depth {
+        let block_id = match expected_id.classify(chain_config).chain_block_id() {
+            Some(id) => id,
+            None => break,
+        };
+
+        if db_tx.get_block_id_by_height(&height)? != Some(expected_id) {
+            inconsistencies.push(ChainInconsistency::BlockNotAtHeight(height, block_id));
+        }
+
+        let block_index = match db_tx.get_block_index(&block_id)? {
+            Some(block_index) => block_index,
+            None => {
+                inconsistencies.push(ChainInconsistency::BlockIndexMissing(block_id));
+                break;
+            }
+        };
+
+        if block_index.block_height() != height {
+            inconsistencies.push(ChainInconsistency::BlockHeightMismatch(
+                block_id,
+                block_index.block_height(),
+                height,
+            ));
+            break;
+        }
+        if !block_index.is_persisted() || !db_tx.block_exists(block_id)? {
+            inconsistencies.push(ChainInconsistency::BlockNotPersisted(block_id));
+        }
+        if !block_index.status().is_fully_valid() {
+            inconsistencies.push(ChainInconsistency::BlockNotFullyValid(block_id));
+        }
+
+        expected_id = *block_index.prev_block_id();
+        height = match height.prev_height() {
+            Some(height) => height,
+            None => break,
+        };
+    }
+
+    Ok(inconsistencies)
+}
@@ -18,7 +18,7 @@ use crate::TransactionVerifierMakerFn;
 use chainstate_types::BlockIndex;
 use common::{
     chain::{block::timestamp::BlockTimestamp, Block, ChainConfig},
-    primitives::{id::WithId, Idable},
+    primitives::{id::WithId, Fee, Idable},
 };
 use constraints_value_accumulator::AccumulatedFee;
 use orders_accounting::OrdersAccountingView;
@@ -57,7 +57,7 @@ impl TransactionVerificationStrategy for DefaultTransactionVerificationStrategy
         block_index: &BlockIndex,
         block: &WithId<Block>,
         median_time_past: BlockTimestamp,
-    ) -> Result<TransactionVerifier<C, S, U, A, T, O>, ConnectTransactionError>
+    ) -> Result<(TransactionVerifier<C, S, U, A, T, O>, Vec<Fee>), ConnectTransactionError>
     where
         C: AsRef<ChainConfig> + ShallowClone,
         S: TransactionVerifierStorageRef,
@@ -70,32 +70,35 @@ impl TransactionVerificationStrategy for DefaultTransactionVerificationStrategy
     {
         let mut tx_verifier = tx_verifier_maker(storage_backend, chain_config.shallow_clone());
 
-        let total_fees = block
-            .transactions()
-            .iter()
-            .try_fold(AccumulatedFee::new(), |total, tx| {
-                let fee = tx_verifier
-                    .connect_transaction(
-                        &TransactionSourceForConnect::Chain {
-                            new_block_index: block_index,
-                        },
-                        tx,
-                        &median_time_past,
+        let map_into_block_fees = |fee: AccumulatedFee| {
+            fee.map_into_block_fees(chain_config.as_ref(), block_index.block_height())
+                .map_err(|err| {
+                    ConnectTransactionError::ConstrainedValueAccumulatorError(
+                        err,
+                        block.get_id().into(),
                     )
-                    .log_err()?;
-                total
-                    .combine(fee)
-                    .map_err(|_| ConnectTransactionError::FailedToAddAllFeesOfBlock(block.get_id()))
-            })
-            .log_err()?;
-        let total_fees = total_fees
-            .map_into_block_fees(chain_config.as_ref(), block_index.block_height())
-            .map_err(|err| {
-                ConnectTransactionError::ConstrainedValueAccumulatorError(
-                    err,
-                    block.get_id().into(),
+                })
+        };
+
+        let mut total_fees = AccumulatedFee::new();
+        let mut tx_fees = Vec::with_capacity(block.transactions().len());
+        for tx in block.transactions() {
+            let fee = tx_verifier
+                .connect_transaction(
+                    &TransactionSourceForConnect::Chain {
+                        new_block_index: block_index,
+                    },
+                    tx,
+                    &median_time_past,
                 )
-            })?;
+                .log_err()?;
+            tx_fees.push(map_into_block_fees(fee.clone())?);
+            total_fees = total_fees
+                .combine(fee)
+                .map_err(|_| ConnectTransactionError::FailedToAddAllFeesOfBlock(block.get_id()))
+                .log_err()?;
+        }
+        let total_fees = map_into_block_fees(total_fees)?;
 
         tx_verifier
             .check_block_reward(block, total_fees, block_index.block_height())
@@ -112,7 +115,7 @@ impl TransactionVerificationStrategy for DefaultTransactionVerificationStrategy
 
         tx_verifier.set_best_block(block.get_id().into());
 
-        Ok(tx_verifier)
+        Ok((tx_verifier, tx_fees))
     }
 
     fn disconnect_block<C, S, M, U, A, T, O>(
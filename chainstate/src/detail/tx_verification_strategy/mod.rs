@@ -19,7 +19,7 @@ pub use default_strategy::DefaultTransactionVerificationStrategy;
 use chainstate_types::BlockIndex;
 use common::{
     chain::{block::timestamp::BlockTimestamp, Block, ChainConfig},
-    primitives::id::WithId,
+    primitives::{id::WithId, Fee},
 };
 use orders_accounting::OrdersAccountingView;
 use pos_accounting::PoSAccountingView;
@@ -49,11 +49,12 @@ impl<C, S, U, A, T, O, F> TransactionVerifierMakerFn<C, S, U, A, T, O> for F whe
 pub trait TransactionVerificationStrategy: Sized + Send {
     /// Connect the transactions given by block and block_index,
     /// and return a TransactionVerifier with an internal state
-    /// that represents them being connected.
+    /// that represents them being connected, along with the fee
+    /// paid by each of the transactions.
     /// Notice that this doesn't modify the internal database/storage
     /// state. It just returns a TransactionVerifier that can be
     /// used to update the database/storage state.
-    #[allow(clippy::too_many_arguments)]
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
     fn connect_block<C, S, M, U, A, T, O>(
         &self,
         tx_verifier_maker: M,
@@ -62,7 +63,7 @@ pub trait TransactionVerificationStrategy: Sized + Send {
         block_index: &BlockIndex,
         block: &WithId<Block>,
         median_time_past: BlockTimestamp,
-    ) -> Result<TransactionVerifier<C, S, U, A, T, O>, ConnectTransactionError>
+    ) -> Result<(TransactionVerifier<C, S, U, A, T, O>, Vec<Fee>), ConnectTransactionError>
     where
         S: TransactionVerifierStorageRef<Error = TransactionVerifierStorageError>,
         U: UtxosView,
@@ -0,0 +1,137 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dropping the undo data of the blocks that can no longer be disconnected.
+//!
+//! The undo data of a block is only needed to disconnect it, which can't happen once the block
+//! is deeper than the maximum reorg depth. For a long-running node this data takes a significant
+//! part of the storage, so it can be dropped for all the blocks deeper than a configurable depth.
+//! The epoch undo deltas of the PoS accounting are kept, they are few and small.
+
+use thiserror::Error;
+
+use super::{tx_verification_strategy::TransactionVerificationStrategy, Chainstate};
+use chainstate_storage::{
+    BlockchainStorage, BlockchainStorageRead, BlockchainStorageWrite, TransactionRw, Transactional,
+};
+use chainstate_types::PropertyQueryError;
+use common::{
+    chain::Block,
+    primitives::{BlockDistance, BlockHeight, Id},
+};
+use logging::log;
+use utils::{ensure, log_error};
+
+/// The number of blocks whose undo data is dropped in a single db transaction
+const COMPACTION_BATCH_SIZE: u64 = 1000;
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum UndoCompactionError {
+    #[error("Blockchain storage error: {0}")]
+    StorageError(#[from] chainstate_storage::Error),
+    #[error("Property query error: {0}")]
+    PropertyQueryError(#[from] PropertyQueryError),
+    #[error("The undo data retention depth {0} is less than the maximum reorg depth {1}")]
+    RetentionDepthBelowMaxReorgDepth(u64, u64),
+    #[error("Mainchain block at height {0} not found")]
+    MainchainBlockNotFound(BlockHeight),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndoCompactionReport {
+    /// The undo data is kept for the mainchain blocks starting from this height.
+    pub retained_from_height: BlockHeight,
+    /// The number of blocks whose undo data has been dropped by this compaction.
+    pub compacted_blocks_count: u64,
+}
+
+impl<S: BlockchainStorage, V: TransactionVerificationStrategy> Chainstate<S, V> {
+    /// Drop the undo data of the mainchain blocks that are deeper than the configured retention
+    /// depth. The blocks that can still be disconnected by a reorg are never affected.
+    #[log_error]
+    pub fn compact_undo_data(&mut self) -> Result<UndoCompactionReport, UndoCompactionError> {
+        let retention_depth = self.chainstate_config.undo_data_retention_depth(&self.chain_config);
+        let max_reorg_depth =
+            u64::try_from(self.chain_config.max_depth_for_reorg().to_int()).unwrap_or(0);
+        ensure!(
+            retention_depth >= max_reorg_depth,
+            UndoCompactionError::RetentionDepthBelowMaxReorgDepth(retention_depth, max_reorg_depth)
+        );
+
+        let retained_from_height = {
+            let chainstate_ref = self.make_db_tx_ro()?;
+            let tip_height = chainstate_ref.get_best_block_index()?.block_height();
+            let min_height = chainstate_ref.get_min_height_with_allowed_reorg()?;
+            let depth = BlockDistance::new(retention_depth.try_into().unwrap_or(i64::MAX));
+            (tip_height - depth).unwrap_or(BlockHeight::zero()).min(min_height)
+        };
+
+        log::info!(
+            "Dropping the undo data of the mainchain blocks below height {retained_from_height}"
+        );
+
+        // The genesis has no undo data
+        let mut compacted_blocks_count = 0;
+        let mut height = BlockHeight::one();
+        while height < retained_from_height {
+            let batch_end =
+                BlockHeight::new(height.into_int().saturating_add(COMPACTION_BATCH_SIZE))
+                    .min(retained_from_height);
+
+            let mut db_tx = self.chainstate_storage.transaction_rw(None)?;
+            while height < batch_end {
+                let block_id = db_tx
+                    .get_block_id_by_height(&height)?
+                    .and_then(|id| id.classify(&self.chain_config).chain_block_id())
+                    .ok_or(UndoCompactionError::MainchainBlockNotFound(height))?;
+                if drop_block_undo_data(&mut db_tx, block_id)? {
+                    compacted_blocks_count += 1;
+                }
+                height = height.next_height();
+            }
+            db_tx.commit()?;
+        }
+
+        log::info!(
+            "Undo data compaction finished: the undo data of {compacted_blocks_count} blocks has been dropped"
+        );
+
+        Ok(UndoCompactionReport {
+            retained_from_height,
+            compacted_blocks_count,
+        })
+    }
+}
+
+/// Drop all the per-block undo data of the given block.
+/// Return false if there was nothing to drop, e.g. because of a previous compaction.
+fn drop_block_undo_data(
+    db_tx: &mut impl BlockchainStorageWrite,
+    block_id: Id<Block>,
+) -> chainstate_storage::Result<bool> {
+    let has_undo_data = db_tx.get_undo_data(block_id)?.is_some()
+        || db_tx.get_tokens_accounting_undo(block_id)?.is_some()
+        || db_tx.get_orders_accounting_undo(block_id)?.is_some()
+        || db_tx.get_pos_accounting_undo(block_id)?.is_some();
+
+    if has_undo_data {
+        db_tx.del_undo_data(block_id)?;
+        db_tx.del_tokens_accounting_undo_data(block_id)?;
+        db_tx.del_orders_accounting_undo_data(block_id)?;
+        db_tx.del_pos_accounting_undo_data(block_id)?;
+    }
+
+    Ok(has_undo_data)
+}
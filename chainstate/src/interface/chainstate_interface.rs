@@ -16,23 +16,26 @@
 use std::{collections::BTreeMap, num::NonZeroUsize, sync::Arc};
 
 use crate::{
-    detail::BlockSource, ChainInfo, ChainstateConfig, ChainstateError, ChainstateEvent,
-    NonZeroPoolBalances,
+    detail::BlockSource, BlockRewardDistribution, ChainInfo, ChainstateConfig, ChainstateError, ChainstateEvent,
+    CompactionStatus, NonZeroPoolBalances, RecoveryReport, StorageSizeInfo, UndoCompactionReport,
+};
+use chainstate_types::{
+    BlockDataAvailability, BlockIndex, BlockStats, EpochData, GenBlockIndex, Locator,
 };
-use chainstate_types::{BlockIndex, EpochData, GenBlockIndex, Locator};
 use common::{
     chain::{
         block::{
             signed_block_header::SignedBlockHeader, timestamp::BlockTimestamp, Block, BlockReward,
             GenBlock,
         },
+        config::EpochIndex,
         tokens::{RPCTokenInfo, TokenAuxiliaryData, TokenId},
         AccountNonce, AccountType, ChainConfig, DelegationId, OrderData, OrderId, PoolId,
-        Transaction, TxInput, UtxoOutPoint,
+        SignedTransaction, Transaction, TxInput, UtxoOutPoint,
     },
     primitives::{Amount, BlockHeight, Id},
 };
-use pos_accounting::{DelegationData, PoolData};
+use pos_accounting::{DelegationData, PoSAccountingData, PoolData};
 use utils::eventhandler::EventHandler;
 use utils_networking::broadcaster;
 use utxo::Utxo;
@@ -63,6 +66,16 @@ pub trait ChainstateInterface: Send + Sync {
         headers: &[SignedBlockHeader],
     ) -> Result<(), ChainstateError>;
 
+    /// Validate the headers of blocks that haven't been received yet and store them ahead of
+    /// the blocks, so that the bodies can be downloaded later.
+    /// The headers must be connected to each other; the first one must be connected either to
+    /// a known block or to a previously processed header.
+    /// The best of the stored headers is reported by `info`.
+    fn process_block_headers(
+        &mut self,
+        headers: Vec<SignedBlockHeader>,
+    ) -> Result<(), ChainstateError>;
+
     fn get_best_block_id(&self) -> Result<Id<GenBlock>, ChainstateError>;
     fn is_block_in_main_chain(&self, block_id: &Id<GenBlock>) -> Result<bool, ChainstateError>;
     fn get_min_height_with_allowed_reorg(&self) -> Result<BlockHeight, ChainstateError>;
@@ -94,6 +107,9 @@ pub trait ChainstateInterface: Send + Sync {
     /// to quickly find a common ancestor between different chains.
     fn get_locator(&self) -> Result<Locator, ChainstateError>;
 
+    /// Returns the range of the mainchain blocks whose data is stored locally.
+    fn get_block_data_availability(&self) -> Result<BlockDataAvailability, ChainstateError>;
+
     /// Returns a locator starting from the specified height.
     fn get_locator_from_height(&self, height: BlockHeight) -> Result<Locator, ChainstateError>;
 
@@ -164,6 +180,13 @@ pub trait ChainstateInterface: Send + Sync {
 
     fn get_best_block_index(&self) -> Result<GenBlockIndex, ChainstateError>;
 
+    /// Return the fee and fullness statistics of up to `count` most recent mainchain blocks,
+    /// starting from the tip. The statistics are only kept for a limited number of recent blocks.
+    fn get_recent_block_stats(
+        &self,
+        count: usize,
+    ) -> Result<Vec<(BlockIndex, BlockStats)>, ChainstateError>;
+
     fn get_chain_config(&self) -> &Arc<ChainConfig>;
     fn get_chainstate_config(&self) -> ChainstateConfig;
     fn wait_for_all_events(&self);
@@ -196,6 +219,14 @@ pub trait ChainstateInterface: Send + Sync {
         block_index: &BlockIndex,
     ) -> Result<Option<BlockReward>, ChainstateError>;
 
+    /// Returns how the reward of a PoS block was credited to the pool that produced it
+    /// and to its delegations.
+    /// Returns `None` if the block is not PoS or its undo data isn't available (anymore).
+    fn get_block_reward_distribution(
+        &self,
+        block_id: &Id<Block>,
+    ) -> Result<Option<BlockRewardDistribution>, ChainstateError>;
+
     /// Returns epoch data for the given epoch index.
     /// Returns `None` if no epoch data was found.
     fn get_epoch_data(&self, epoch_index: u64) -> Result<Option<EpochData>, ChainstateError>;
@@ -213,6 +244,12 @@ pub trait ChainstateInterface: Send + Sync {
         &self,
         tx_id: &Id<Transaction>,
     ) -> Result<Option<TokenId>, ChainstateError>;
+    /// Returns a mainchain transaction together with the id and the height of the block
+    /// containing it. Fails if the transaction index is disabled.
+    fn get_transaction(
+        &self,
+        tx_id: &Id<Transaction>,
+    ) -> Result<Option<(SignedTransaction, Id<Block>, BlockHeight)>, ChainstateError>;
     fn get_token_data(
         &self,
         id: &TokenId,
@@ -253,12 +290,32 @@ pub trait ChainstateInterface: Send + Sync {
         include_orphans: bool,
     ) -> Result<(), ChainstateError>;
 
+    /// Imports the blocks from a bootstrap file, reading it in chunks.
+    fn import_bootstrap_file(&mut self, file_path: &std::path::Path)
+        -> Result<(), ChainstateError>;
+
+    /// Exports the blocks of the blockchain into a bootstrap file, see `export_bootstrap_stream`.
+    /// The file is created, or truncated if it exists.
+    fn export_bootstrap_file(
+        &self,
+        file_path: &std::path::Path,
+        include_orphans: bool,
+    ) -> Result<(), ChainstateError>;
+
     /// Returns the UTXO for a specified OutPoint.
     fn utxo(&self, outpoint: &UtxoOutPoint) -> Result<Option<Utxo>, ChainstateError>;
 
     /// Returns true if the initial block download isn't finished yet.
     fn is_initial_block_download(&self) -> bool;
 
+    /// Returns true if transactions shouldn't be processed or relayed yet, because the initial
+    /// block download isn't finished and the deferral isn't disabled in the config.
+    fn is_tx_processing_deferred(&self) -> bool;
+
+    /// Returns the report of the recovery performed on startup, if the chainstate hadn't been
+    /// shut down cleanly.
+    fn get_recovery_report(&self) -> Option<RecoveryReport>;
+
     /// Check whether stake pool with given ID exists.
     fn stake_pool_exists(&self, pool_id: PoolId) -> Result<bool, ChainstateError>;
 
@@ -309,9 +366,42 @@ pub trait ChainstateInterface: Send + Sync {
         delegation_id: DelegationId,
     ) -> Result<Option<Amount>, ChainstateError>;
 
+    /// Get the entire PoS accounting data (all pools, delegations and their balances) at the tip.
+    fn get_pos_accounting_data_tip(&self) -> Result<PoSAccountingData, ChainstateError>;
+
+    /// Get the entire PoS accounting data (all pools, delegations and their balances) as it was
+    /// right after the specified epoch was sealed. The epoch must not be later than the last
+    /// sealed one, which lags behind the tip by a number of epochs defined by the chain config.
+    fn get_pos_accounting_data_at_epoch(
+        &self,
+        epoch_index: EpochIndex,
+    ) -> Result<PoSAccountingData, ChainstateError>;
+
     /// Returns information about the chain.
     fn info(&self) -> Result<ChainInfo, ChainstateError>;
 
+    /// Returns the size of the storage on disk and the amount of data in each of its maps.
+    fn storage_size_info(&self) -> Result<StorageSizeInfo, ChainstateError>;
+
+    /// Reclaim the disk space occupied by deleted data.
+    ///
+    /// Depending on the storage backend, the compaction happens immediately or on the next start.
+    fn compact_storage(&mut self) -> Result<CompactionStatus, ChainstateError>;
+
+    /// Whether the storage is due to be checked for the automatic compaction.
+    fn is_storage_maintenance_due(&self) -> bool;
+
+    /// Check the storage and schedule its compaction if too much space is wasted.
+    ///
+    /// Like `compact_storage`, this doesn't compact the LMDB database right away; the compaction
+    /// only happens when the node is restarted.
+    fn perform_storage_maintenance(&mut self) -> Result<(), ChainstateError>;
+
+    /// Drop the undo data of the mainchain blocks deeper than the undo data retention depth.
+    ///
+    /// The freed space can then be reclaimed by `compact_storage`.
+    fn compact_undo_data(&mut self) -> Result<UndoCompactionReport, ChainstateError>;
+
     /// Returns account nonce for the account
     fn get_account_nonce_count(
         &self,
@@ -20,27 +20,31 @@ use crate::{
         self,
         block_checking::BlockChecker,
         block_invalidation::BlockInvalidator,
-        bootstrap::{export_bootstrap_stream, import_bootstrap_stream},
+        bootstrap::{export_bootstrap_stream, import_bootstrap_stream, BootstrapError},
         calculate_median_time_past,
         tx_verification_strategy::TransactionVerificationStrategy,
         BlockSource, OrphanBlocksRef,
     },
-    ChainInfo, ChainstateConfig, ChainstateError, ChainstateEvent, ChainstateInterface, Locator,
-    NonZeroPoolBalances,
+    BlockRewardDistribution, ChainInfo, ChainstateConfig, ChainstateError, ChainstateEvent,
+    ChainstateInterface, CompactionStatus, Locator, NonZeroPoolBalances, RecoveryReport, StorageSizeInfo,
+    UndoCompactionReport,
 };
 use chainstate_storage::BlockchainStorage;
-use chainstate_types::{BlockIndex, EpochData, GenBlockIndex, PropertyQueryError};
+use chainstate_types::{
+    BlockDataAvailability, BlockIndex, BlockStats, EpochData, GenBlockIndex, PropertyQueryError,
+};
 use common::{
     chain::{
         block::{signed_block_header::SignedBlockHeader, Block, BlockReward, GenBlock},
-        config::ChainConfig,
+        config::{ChainConfig, EpochIndex},
         tokens::{RPCTokenInfo, TokenAuxiliaryData, TokenId},
-        AccountNonce, AccountType, DelegationId, OrderData, OrderId, PoolId, Transaction, TxInput,
-        TxOutput, UtxoOutPoint,
+        AccountNonce, AccountType, DelegationId, OrderData, OrderId, PoolId, SignedTransaction,
+        Transaction, TxInput, TxOutput, UtxoOutPoint,
     },
     primitives::{id::WithId, Amount, BlockHeight, Id, Idable},
 };
-use pos_accounting::{DelegationData, PoSAccountingStorageRead, PoolData};
+use logging::log;
+use pos_accounting::{DelegationData, PoSAccountingData, PoSAccountingStorageRead, PoolData};
 use utils::{displayable_option::DisplayableOption, eventhandler::EventHandler};
 use utils_networking::broadcaster;
 use utxo::{Utxo, UtxosView};
@@ -108,6 +112,16 @@ where
             .map_err(ChainstateError::ProcessBlockError)
     }
 
+    #[tracing::instrument(skip_all)]
+    fn process_block_headers(
+        &mut self,
+        headers: Vec<SignedBlockHeader>,
+    ) -> Result<(), ChainstateError> {
+        self.chainstate
+            .process_block_headers(&headers)
+            .map_err(ChainstateError::ProcessBlockError)
+    }
+
     #[tracing::instrument(skip_all, fields(block_id = %block.get_id()))]
     fn preliminary_block_check(&self, block: Block) -> Result<Block, ChainstateError> {
         let block = BlockChecker::new(&self.chainstate)
@@ -210,6 +224,15 @@ where
             .map_err(ChainstateError::FailedToReadProperty)
     }
 
+    #[tracing::instrument(skip_all)]
+    fn get_block_data_availability(&self) -> Result<BlockDataAvailability, ChainstateError> {
+        self.chainstate
+            .query()
+            .map_err(ChainstateError::from)?
+            .get_block_data_availability()
+            .map_err(ChainstateError::FailedToReadProperty)
+    }
+
     #[tracing::instrument(skip_all, fields(height = %height))]
     fn get_locator_from_height(&self, height: BlockHeight) -> Result<Locator, ChainstateError> {
         self.chainstate
@@ -310,6 +333,18 @@ where
             .map_err(ChainstateError::FailedToReadProperty)
     }
 
+    #[tracing::instrument(skip_all, fields(count = %count))]
+    fn get_recent_block_stats(
+        &self,
+        count: usize,
+    ) -> Result<Vec<(BlockIndex, BlockStats)>, ChainstateError> {
+        self.chainstate
+            .query()
+            .map_err(ChainstateError::from)?
+            .get_recent_block_stats(count)
+            .map_err(ChainstateError::FailedToReadProperty)
+    }
+
     #[tracing::instrument(skip_all, fields(block_id = %block_id))]
     fn get_block_index_for_persisted_block(
         &self,
@@ -472,6 +507,18 @@ where
             .map_err(ChainstateError::FailedToReadProperty)
     }
 
+    #[tracing::instrument(skip_all, fields(block_id = %block_id))]
+    fn get_block_reward_distribution(
+        &self,
+        block_id: &Id<Block>,
+    ) -> Result<Option<BlockRewardDistribution>, ChainstateError> {
+        self.chainstate
+            .query()
+            .map_err(ChainstateError::from)?
+            .get_block_reward_distribution(block_id)
+            .map_err(ChainstateError::FailedToReadProperty)
+    }
+
     #[tracing::instrument(skip(self))]
     fn get_epoch_data(&self, epoch_index: u64) -> Result<Option<EpochData>, ChainstateError> {
         self.chainstate
@@ -517,6 +564,18 @@ where
             .map_err(ChainstateError::FailedToReadProperty)
     }
 
+    #[tracing::instrument(skip_all, fields(tx_id = %tx_id))]
+    fn get_transaction(
+        &self,
+        tx_id: &Id<Transaction>,
+    ) -> Result<Option<(SignedTransaction, Id<Block>, BlockHeight)>, ChainstateError> {
+        self.chainstate
+            .query()
+            .map_err(ChainstateError::from)?
+            .get_transaction(tx_id)
+            .map_err(ChainstateError::FailedToReadProperty)
+    }
+
     #[tracing::instrument(skip_all)]
     fn get_inputs_outpoints_coin_amount(
         &self,
@@ -606,6 +665,31 @@ where
         Ok(())
     }
 
+    #[tracing::instrument(skip_all, fields(file_path = %file_path.display()))]
+    fn import_bootstrap_file(
+        &mut self,
+        file_path: &std::path::Path,
+    ) -> Result<(), ChainstateError> {
+        let file = std::fs::File::open(file_path).map_err(BootstrapError::from)?;
+        let reader: std::io::BufReader<Box<dyn std::io::Read + Send>> =
+            std::io::BufReader::new(Box::new(file));
+
+        self.import_bootstrap_stream(reader)
+    }
+
+    #[tracing::instrument(skip_all, fields(file_path = %file_path.display()))]
+    fn export_bootstrap_file(
+        &self,
+        file_path: &std::path::Path,
+        include_orphans: bool,
+    ) -> Result<(), ChainstateError> {
+        let file = std::fs::File::create(file_path).map_err(BootstrapError::from)?;
+        let writer: std::io::BufWriter<Box<dyn std::io::Write + Send>> =
+            std::io::BufWriter::new(Box::new(file));
+
+        self.export_bootstrap_stream(writer, include_orphans)
+    }
+
     #[tracing::instrument(skip_all)]
     fn utxo(&self, outpoint: &UtxoOutPoint) -> Result<Option<Utxo>, ChainstateError> {
         let chainstate_ref = self
@@ -622,6 +706,14 @@ where
         self.chainstate.is_initial_block_download()
     }
 
+    fn get_recovery_report(&self) -> Option<RecoveryReport> {
+        self.chainstate.recovery_report().cloned()
+    }
+
+    fn is_tx_processing_deferred(&self) -> bool {
+        self.chainstate.is_tx_processing_deferred()
+    }
+
     #[tracing::instrument(skip_all, fields(pool_id = %pool_id))]
     fn stake_pool_exists(&self, pool_id: PoolId) -> Result<bool, ChainstateError> {
         self.get_stake_pool_data(pool_id).map(|v| v.is_some())
@@ -708,6 +800,27 @@ where
             .map_err(|e| ChainstateError::ProcessBlockError(e.into()))
     }
 
+    #[tracing::instrument(skip_all)]
+    fn get_pos_accounting_data_tip(&self) -> Result<PoSAccountingData, ChainstateError> {
+        self.chainstate
+            .make_db_tx_ro()
+            .map_err(|e| ChainstateError::FailedToReadProperty(e.into()))?
+            .get_pos_accounting_data_tip()
+            .map_err(ChainstateError::FailedToReadProperty)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn get_pos_accounting_data_at_epoch(
+        &self,
+        epoch_index: EpochIndex,
+    ) -> Result<PoSAccountingData, ChainstateError> {
+        self.chainstate
+            .make_db_tx_ro()
+            .map_err(|e| ChainstateError::FailedToReadProperty(e.into()))?
+            .get_pos_accounting_data_at_epoch(epoch_index)
+            .map_err(ChainstateError::FailedToReadProperty)
+    }
+
     #[tracing::instrument(skip_all)]
     fn info(&self) -> Result<ChainInfo, ChainstateError> {
         let best_block_index = self.get_best_block_index()?;
@@ -719,16 +832,54 @@ where
 
         let is_initial_block_download = self.is_initial_block_download();
 
+        let (best_header_id, best_header_height) = self
+            .chainstate
+            .best_header_id_and_height()
+            .map_err(ChainstateError::FailedToReadProperty)?;
+
         Ok(ChainInfo {
             best_block_height,
             best_block_id,
             best_block_timestamp,
             median_time,
             is_initial_block_download,
+            best_header_id,
+            best_header_height,
         })
     }
 
     #[tracing::instrument(skip_all)]
+    fn storage_size_info(&self) -> Result<StorageSizeInfo, ChainstateError> {
+        self.chainstate
+            .storage_size_info()
+            .map_err(|e| ChainstateError::FailedToReadProperty(e.into()))
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn compact_storage(&mut self) -> Result<CompactionStatus, ChainstateError> {
+        self.chainstate
+            .compact_storage()
+            .map_err(ChainstateError::StorageMaintenanceError)
+    }
+
+    fn is_storage_maintenance_due(&self) -> bool {
+        self.chainstate.is_storage_maintenance_due()
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn perform_storage_maintenance(&mut self) -> Result<(), ChainstateError> {
+        self.chainstate
+            .perform_storage_maintenance()
+            .map_err(ChainstateError::StorageMaintenanceError)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn compact_undo_data(&mut self) -> Result<UndoCompactionReport, ChainstateError> {
+        self.chainstate
+            .compact_undo_data()
+            .map_err(ChainstateError::UndoCompactionError)
+    }
+
     fn get_account_nonce_count(
         &self,
         account: AccountType,
@@ -844,4 +995,14 @@ impl subsystem::Subsystem for Box<dyn ChainstateInterface> {
     fn interface_mut(&mut self) -> &mut Self::Interface {
         self.as_mut()
     }
+
+    fn perform_background_work_unit(&mut self) {
+        if let Err(err) = self.perform_storage_maintenance() {
+            log::error!("Storage maintenance failed: {err}");
+        }
+    }
+
+    fn has_background_work(&self) -> bool {
+        self.is_storage_maintenance_due()
+    }
 }
@@ -20,25 +20,28 @@ use std::{
     sync::Arc,
 };
 
-use chainstate_types::{BlockIndex, EpochData, GenBlockIndex, Locator};
+use chainstate_types::{
+    BlockDataAvailability, BlockIndex, BlockStats, EpochData, GenBlockIndex, Locator,
+};
 use common::{
     chain::{
         block::{signed_block_header::SignedBlockHeader, timestamp::BlockTimestamp, BlockReward},
-        config::ChainConfig,
+        config::{ChainConfig, EpochIndex},
         tokens::{RPCTokenInfo, TokenAuxiliaryData, TokenId},
         AccountNonce, AccountType, Block, DelegationId, GenBlock, OrderData, OrderId, PoolId,
-        Transaction, TxInput, UtxoOutPoint,
+        SignedTransaction, Transaction, TxInput, UtxoOutPoint,
     },
     primitives::{Amount, BlockHeight, Id},
 };
-use pos_accounting::{DelegationData, PoolData};
+use pos_accounting::{DelegationData, PoSAccountingData, PoolData};
 use utils::eventhandler::EventHandler;
 use utils_networking::broadcaster;
 use utxo::Utxo;
 
 use crate::{
-    chainstate_interface::ChainstateInterface, BlockSource, ChainInfo, ChainstateConfig,
-    ChainstateError, ChainstateEvent, NonZeroPoolBalances,
+    chainstate_interface::ChainstateInterface, BlockRewardDistribution, BlockSource, ChainInfo, ChainstateConfig,
+    ChainstateError, ChainstateEvent, CompactionStatus, NonZeroPoolBalances, RecoveryReport,
+    StorageSizeInfo, UndoCompactionReport,
 };
 
 impl<T: Deref + DerefMut + Send + Sync> ChainstateInterface for T
@@ -83,6 +86,13 @@ where
         self.deref().preliminary_headers_check(headers)
     }
 
+    fn process_block_headers(
+        &mut self,
+        headers: Vec<SignedBlockHeader>,
+    ) -> Result<(), ChainstateError> {
+        self.deref_mut().process_block_headers(headers)
+    }
+
     fn get_best_block_id(&self) -> Result<Id<GenBlock>, ChainstateError> {
         self.deref().get_best_block_id()
     }
@@ -133,6 +143,10 @@ where
         self.deref().get_locator()
     }
 
+    fn get_block_data_availability(&self) -> Result<BlockDataAvailability, ChainstateError> {
+        self.deref().get_block_data_availability()
+    }
+
     fn get_locator_from_height(&self, height: BlockHeight) -> Result<Locator, ChainstateError> {
         self.deref().get_locator_from_height(height)
     }
@@ -202,6 +216,13 @@ where
         self.deref().get_best_block_index()
     }
 
+    fn get_recent_block_stats(
+        &self,
+        count: usize,
+    ) -> Result<Vec<(BlockIndex, BlockStats)>, ChainstateError> {
+        self.deref().get_recent_block_stats(count)
+    }
+
     fn get_chain_config(&self) -> &Arc<ChainConfig> {
         self.deref().get_chain_config()
     }
@@ -264,6 +285,13 @@ where
         self.deref().get_block_reward(block_index)
     }
 
+    fn get_block_reward_distribution(
+        &self,
+        block_id: &Id<Block>,
+    ) -> Result<Option<BlockRewardDistribution>, ChainstateError> {
+        self.deref().get_block_reward_distribution(block_id)
+    }
+
     fn get_epoch_data(&self, epoch_index: u64) -> Result<Option<EpochData>, ChainstateError> {
         self.deref().get_epoch_data(epoch_index)
     }
@@ -289,6 +317,13 @@ where
         self.deref().get_token_id_from_issuance_tx(tx_id)
     }
 
+    fn get_transaction(
+        &self,
+        tx_id: &Id<Transaction>,
+    ) -> Result<Option<(SignedTransaction, Id<Block>, BlockHeight)>, ChainstateError> {
+        self.deref().get_transaction(tx_id)
+    }
+
     fn get_inputs_outpoints_coin_amount(
         &self,
         inputs: &[TxInput],
@@ -319,6 +354,21 @@ where
         self.deref().export_bootstrap_stream(writer, include_orphans)
     }
 
+    fn import_bootstrap_file(
+        &mut self,
+        file_path: &std::path::Path,
+    ) -> Result<(), ChainstateError> {
+        self.deref_mut().import_bootstrap_file(file_path)
+    }
+
+    fn export_bootstrap_file(
+        &self,
+        file_path: &std::path::Path,
+        include_orphans: bool,
+    ) -> Result<(), ChainstateError> {
+        self.deref().export_bootstrap_file(file_path, include_orphans)
+    }
+
     fn utxo(&self, outpoint: &UtxoOutPoint) -> Result<Option<Utxo>, ChainstateError> {
         self.deref().utxo(outpoint)
     }
@@ -327,6 +377,14 @@ where
         self.deref().is_initial_block_download()
     }
 
+    fn get_recovery_report(&self) -> Option<RecoveryReport> {
+        self.deref().get_recovery_report()
+    }
+
+    fn is_tx_processing_deferred(&self) -> bool {
+        self.deref().is_tx_processing_deferred()
+    }
+
     fn stake_pool_exists(&self, pool_id: PoolId) -> Result<bool, ChainstateError> {
         self.deref().stake_pool_exists(pool_id)
     }
@@ -378,10 +436,41 @@ where
         self.deref().get_stake_pool_delegation_share(pool_id, delegation_id)
     }
 
+    fn get_pos_accounting_data_tip(&self) -> Result<PoSAccountingData, ChainstateError> {
+        self.deref().get_pos_accounting_data_tip()
+    }
+
+    fn get_pos_accounting_data_at_epoch(
+        &self,
+        epoch_index: EpochIndex,
+    ) -> Result<PoSAccountingData, ChainstateError> {
+        self.deref().get_pos_accounting_data_at_epoch(epoch_index)
+    }
+
     fn info(&self) -> Result<ChainInfo, ChainstateError> {
         self.deref().info()
     }
 
+    fn storage_size_info(&self) -> Result<StorageSizeInfo, ChainstateError> {
+        self.deref().storage_size_info()
+    }
+
+    fn compact_storage(&mut self) -> Result<CompactionStatus, ChainstateError> {
+        self.deref_mut().compact_storage()
+    }
+
+    fn is_storage_maintenance_due(&self) -> bool {
+        self.deref().is_storage_maintenance_due()
+    }
+
+    fn perform_storage_maintenance(&mut self) -> Result<(), ChainstateError> {
+        self.deref_mut().perform_storage_maintenance()
+    }
+
+    fn compact_undo_data(&mut self) -> Result<UndoCompactionReport, ChainstateError> {
+        self.deref_mut().compact_undo_data()
+    }
+
     fn get_block_header(
         &self,
         block_id: Id<Block>,
@@ -472,6 +561,11 @@ mod tests {
                 min_max_bootstrap_import_buffer_sizes: Default::default(),
                 max_tip_age: Default::default(),
                 enable_heavy_checks: Some(true),
+                defer_txs_during_ibd: None,
+                enable_tx_index: None,
+                recovery_replay_depth: Default::default(),
+                undo_data_retention_depth: None,
+                auto_storage_compaction_interval: None,
             };
             let chainstate_storage = Store::new_empty().unwrap();
 
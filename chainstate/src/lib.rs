@@ -35,14 +35,20 @@ pub use crate::{
     config::{ChainstateConfig, MaxTipAge},
     detail::{
         ban_score, block_invalidation::BlockInvalidatorError, calculate_median_time_past,
-        calculate_median_time_past_from_blocktimestamps, BlockError, BlockProcessingErrorClass,
-        BlockProcessingErrorClassification, BlockSource, ChainInfo, CheckBlockError,
-        CheckBlockTransactionsError, ConnectTransactionError, IOPolicyError, InitializationError,
-        Locator, NonZeroPoolBalances, OrphanCheckError, SpendStakeError,
-        StorageCompatibilityCheckError, TokenIssuanceError, TokensError,
-        TransactionVerifierStorageError, MEDIAN_TIME_SPAN,
+        calculate_median_time_past_from_blocktimestamps, BlockDataAvailability, BlockError,
+        BlockRewardDistribution,
+        BlockProcessingErrorClass, BlockProcessingErrorClassification, BlockSource,
+        ChainInconsistency, ChainInfo, CheckBlockError, CheckBlockTransactionsError,
+        ConnectTransactionError, IOPolicyError, InitializationError, Locator, NonZeroPoolBalances,
+        OrphanCheckError, RecoveryReport, SpendStakeError, StorageCompatibilityCheckError,
+        TokenIssuanceError, TokensError, TransactionVerifierStorageError, UndoCompactionError,
+        UndoCompactionReport, MEDIAN_TIME_SPAN,
     },
 };
+pub use chainstate_storage::{
+    CompactionOutcome, CompactionReport, CompactionStatus, MapSizeInfo, PendingOperation,
+    StorageSizeInfo,
+};
 pub use chainstate_types::{BlockIndex, GenBlockIndex, PropertyQueryError};
 pub use constraints_value_accumulator;
 pub use detail::tx_verification_strategy::*;
@@ -81,6 +87,10 @@ pub enum ChainstateError {
     BootstrapError(#[from] BootstrapError),
     #[error("Error invoking block invalidator: {0}")]
     BlockInvalidatorError(#[from] BlockInvalidatorError),
+    #[error("Undo data compaction error: {0}")]
+    UndoCompactionError(#[from] UndoCompactionError),
+    #[error("Storage maintenance error: {0}")]
+    StorageMaintenanceError(chainstate_storage::Error),
 }
 
 pub type ChainstateSubsystem = Box<dyn ChainstateInterface>;
@@ -17,21 +17,28 @@
 
 mod types;
 
-use std::{
-    convert::Infallible,
-    io::{Read, Write},
-    num::NonZeroUsize,
-    sync::Arc,
+use std::{convert::Infallible, num::NonZeroUsize, sync::Arc};
+
+use self::types::{
+    block::RpcBlock,
+    block_reward_distribution::RpcBlockRewardDistribution,
+    block_stats::RpcBlockStats,
+    deployment::RpcDeploymentInfo,
+    event::RpcEvent,
+    pos_accounting::RpcPoSAccountingData,
+    recovery::RpcRecoveryReport,
+    storage::{RpcCompactionStatus, RpcStorageSizeInfo, RpcUndoCompactionReport},
+    token::RpcTokenState,
+    transaction::RpcIndexedTransaction,
 };
-
-use self::types::{block::RpcBlock, event::RpcEvent};
-use crate::{Block, BlockSource, ChainInfo, GenBlock};
+use crate::{Block, BlockSource, ChainInfo, ChainstateError, GenBlock};
 use chainstate_types::BlockIndex;
 use common::{
     address::{dehexify::to_dehexified_json, Address},
     chain::{
+        config::EpochIndex,
         tokens::{RPCTokenInfo, TokenId},
-        ChainConfig, DelegationId, PoolId, TxOutput,
+        AccountType, ChainConfig, DelegationId, PoolId, Transaction, TxOutput,
     },
     primitives::{Amount, BlockHeight, Id},
 };
@@ -85,11 +92,27 @@ trait ChainstateRpc {
         step: NonZeroUsize,
     ) -> RpcResult<Vec<(BlockHeight, Id<GenBlock>)>>;
 
+    /// Returns a hex-encoded serialized mainchain transaction with the given id, together with
+    /// the id and the height of the block containing it.
+    ///
+    /// Returns `None` (null) if the transaction is not found in the mainchain.
+    /// Requires the transaction index to be enabled in the node's config.
+    #[method(name = "get_transaction")]
+    async fn get_transaction(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> RpcResult<Option<RpcIndexedTransaction>>;
+
     /// Returns the TxOutput for a specified UtxoOutPoint.
     /// Returns `None` (null) if the UtxoOutPoint is not found or is already spent.
     #[method(name = "get_utxo")]
     async fn get_utxo(&self, outpoint: RpcUtxoOutpoint) -> RpcResult<Option<TxOutput>>;
 
+    /// Returns the TxOutputs for the specified UtxoOutPoints, in the same order.
+    /// An item is `None` (null) if its UtxoOutPoint is not found or is already spent.
+    #[method(name = "get_utxos")]
+    async fn get_utxos(&self, outpoints: Vec<RpcUtxoOutpoint>) -> RpcResult<Vec<Option<TxOutput>>>;
+
     /// Submit a block to be included in the blockchain.
     ///
     /// Note that the submission does not circumvent any validation process.
@@ -151,10 +174,53 @@ trait ChainstateRpc {
         delegation_address: String,
     ) -> RpcResult<Option<Amount>>;
 
+    /// Returns how the reward of a PoS block, i.e. the block subsidy and the transaction fees,
+    /// was credited to the pool that produced the block and to its delegations.
+    ///
+    /// Returns `None` (null) if the block is not a PoS block or its undo data has already been
+    /// dropped from the node's storage.
+    #[method(name = "block_reward_distribution")]
+    async fn block_reward_distribution(
+        &self,
+        id: Id<Block>,
+    ) -> RpcResult<Option<RpcBlockRewardDistribution>>;
+
+    /// Returns the complete PoS accounting state: all pools and delegations along with their balances.
+    ///
+    /// If `epoch` is specified, the state right after that epoch was sealed is returned; the epoch
+    /// must not be later than the last sealed one, which lags behind the tip by a number of epochs.
+    /// Otherwise, the state at the tip is returned.
+    #[method(name = "pos_accounting_data")]
+    async fn pos_accounting_data(
+        &self,
+        epoch: Option<EpochIndex>,
+    ) -> RpcResult<RpcPoSAccountingData>;
+
+    /// Exports the complete PoS accounting state to a CSV file, one record per pool or delegation.
+    ///
+    /// If `epoch` is specified, the state right after that sealed epoch is exported;
+    /// otherwise, the state at the tip is exported.
+    #[method(name = "export_pos_accounting_csv")]
+    async fn export_pos_accounting_csv(
+        &self,
+        file_path: &std::path::Path,
+        epoch: Option<EpochIndex>,
+    ) -> RpcResult<()>;
+
     /// Get token information, given a token id, in address form.
     #[method(name = "token_info")]
     async fn token_info(&self, token_id: String) -> RpcResult<Option<RPCTokenInfo>>;
 
+    /// Get the state of a fungible token as seen by the consensus rules at the tip of the chain,
+    /// given a token id in address form: the circulating supply, whether the supply is locked,
+    /// the frozen state, the current authority and the nonce for the next authority command.
+    ///
+    /// The data comes directly from the tokens accounting database, which allows verifying
+    /// the status of a token before sending it without relying on an indexer.
+    /// Returns `None` (null) if the token doesn't exist or is not a fungible token.
+    #[method(name = "token_state")]
+    async fn token_state(&self, token_id: String) -> RpcResult<Option<RpcTokenState>>;
+
     /// Exports a "bootstrap file", which contains all blocks
     #[method(name = "export_bootstrap_file")]
     async fn export_bootstrap_file(
@@ -171,6 +237,49 @@ trait ChainstateRpc {
     #[method(name = "info")]
     async fn info(&self) -> RpcResult<ChainInfo>;
 
+    /// List the consensus upgrades known to the node, with their activation heights and states.
+    ///
+    /// The upgrades are activated at the heights defined in the chain config. An upgrade is
+    /// "LockedIn" once its activation is at most one epoch away, which means that the nodes
+    /// that don't support it will stop following the chain soon. The states are informational only.
+    #[method(name = "get_deployment_info")]
+    async fn get_deployment_info(&self) -> RpcResult<Vec<RpcDeploymentInfo>>;
+
+    /// Return the size of the chainstate database on disk and the amount of data stored in each of its maps.
+    ///
+    /// The compaction is only performed when the node starts, so `last_compaction` describes
+    /// the compaction done at the last start (or the one interrupted by a stop), not a compaction
+    /// running in the background.
+    #[method(name = "storage_size_info")]
+    async fn storage_size_info(&self) -> RpcResult<RpcStorageSizeInfo>;
+
+    /// Return the fee and fullness statistics of up to `count` most recent mainchain blocks,
+    /// starting from the tip.
+    ///
+    /// The statistics are only kept for the last 1000 blocks, so fewer entries may be returned.
+    #[method(name = "get_recent_block_stats")]
+    async fn get_recent_block_stats(&self, count: usize) -> RpcResult<Vec<RpcBlockStats>>;
+
+    /// Reclaim the disk space occupied by deleted data in the chainstate database.
+    ///
+    /// The LMDB database cannot be compacted while it's in use, so the compaction happens the next time
+    /// the node starts; whether it's pending can be checked with `storage_size_info`.
+    /// A node that is never restarted is never compacted, even if the automatic compaction is enabled.
+    #[method(name = "compact_storage")]
+    async fn compact_storage(&self) -> RpcResult<RpcCompactionStatus>;
+
+    /// Drop the undo data of the mainchain blocks that are deeper than the configured retention depth.
+    ///
+    /// The undo data is only needed to disconnect blocks, which is impossible below the maximum reorg depth,
+    /// so the retention depth can't be less than that. Use `compact_storage` afterwards to reclaim the disk space.
+    #[method(name = "compact_undo_data")]
+    async fn compact_undo_data(&self) -> RpcResult<RpcUndoCompactionReport>;
+
+    /// Return the report of the recovery performed when the node started, if the previous run
+    /// had been interrupted in the middle of a chainstate operation; null otherwise.
+    #[method(name = "recovery_report")]
+    async fn recovery_report(&self) -> RpcResult<Option<RpcRecoveryReport>>;
+
     /// Subscribe to chainstate events, such as new tip.
     ///
     /// After a successful subscription, the node will message the subscriber with a message on every event.
@@ -252,6 +361,16 @@ impl ChainstateRpcServer for super::ChainstateHandle {
         )
     }
 
+    async fn get_transaction(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> RpcResult<Option<RpcIndexedTransaction>> {
+        let tx = rpc::handle_result(self.call(move |this| this.get_transaction(&tx_id)).await)?;
+        Ok(tx.map(|(tx, block_id, block_height)| {
+            RpcIndexedTransaction::new(tx, block_id, block_height)
+        }))
+    }
+
     async fn get_utxo(&self, outpoint: RpcUtxoOutpoint) -> RpcResult<Option<TxOutput>> {
         let outpoint = outpoint.into_outpoint();
         rpc::handle_result(
@@ -262,6 +381,22 @@ impl ChainstateRpcServer for super::ChainstateHandle {
         )
     }
 
+    async fn get_utxos(&self, outpoints: Vec<RpcUtxoOutpoint>) -> RpcResult<Vec<Option<TxOutput>>> {
+        let outpoints =
+            outpoints.into_iter().map(RpcUtxoOutpoint::into_outpoint).collect::<Vec<_>>();
+        rpc::handle_result(
+            self.call_mut(move |this| {
+                outpoints
+                    .iter()
+                    .map(|outpoint| {
+                        this.utxo(outpoint).map(|utxo| utxo.map(|utxo| utxo.take_output()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .await,
+        )
+    }
+
     async fn submit_block(&self, block: HexEncoded<Block>) -> RpcResult<()> {
         let res = self
             .call_mut(move |this| this.process_block(block.take(), BlockSource::Local))
@@ -361,6 +496,58 @@ impl ChainstateRpcServer for super::ChainstateHandle {
         )
     }
 
+    async fn block_reward_distribution(
+        &self,
+        id: Id<Block>,
+    ) -> RpcResult<Option<RpcBlockRewardDistribution>> {
+        rpc::handle_result(
+            self.call(move |this| {
+                let chain_config = this.get_chain_config();
+                dynamize_err(this.get_block_reward_distribution(&id)).and_then(|distribution| {
+                    distribution
+                        .map(|distribution| {
+                            dynamize_err(RpcBlockRewardDistribution::new(
+                                chain_config,
+                                &distribution,
+                            ))
+                        })
+                        .transpose()
+                })
+            })
+            .await,
+        )
+    }
+
+    async fn pos_accounting_data(
+        &self,
+        epoch: Option<EpochIndex>,
+    ) -> RpcResult<RpcPoSAccountingData> {
+        rpc::handle_result(
+            self.call(move |this| {
+                let chain_config = this.get_chain_config();
+                let data = match epoch {
+                    Some(epoch) => dynamize_err(this.get_pos_accounting_data_at_epoch(epoch)),
+                    None => dynamize_err(this.get_pos_accounting_data_tip()),
+                };
+                data.and_then(|data| dynamize_err(RpcPoSAccountingData::new(chain_config, &data)))
+            })
+            .await,
+        )
+    }
+
+    async fn export_pos_accounting_csv(
+        &self,
+        file_path: &std::path::Path,
+        epoch: Option<EpochIndex>,
+    ) -> RpcResult<()> {
+        let data = self.pos_accounting_data(epoch).await?;
+
+        let file_obj: std::fs::File = rpc::handle_result(std::fs::File::create(file_path))?;
+        let mut writer = std::io::BufWriter::new(file_obj);
+
+        rpc::handle_result(data.write_csv(&mut writer))
+    }
+
     async fn token_info(&self, token_id: String) -> RpcResult<Option<RPCTokenInfo>> {
         rpc::handle_result(
             self.call(move |this| {
@@ -376,35 +563,130 @@ impl ChainstateRpcServer for super::ChainstateHandle {
         )
     }
 
+    async fn token_state(&self, token_id: String) -> RpcResult<Option<RpcTokenState>> {
+        rpc::handle_result(
+            self.call(
+                move |this| -> Result<_, Box<dyn std::error::Error + Send + Sync>> {
+                    let chain_config = this.get_chain_config();
+                    let token_id =
+                        dynamize_err(Address::<TokenId>::from_string(chain_config, token_id))?
+                            .into_object();
+
+                    let token_data = match dynamize_err(this.get_token_data(&token_id))? {
+                        Some(tokens_accounting::TokenData::FungibleToken(data)) => data,
+                        None => return Ok(None),
+                    };
+                    let circulating_supply =
+                        dynamize_err(this.get_token_circulating_supply(&token_id))?
+                            .unwrap_or(Amount::ZERO);
+                    let last_nonce =
+                        dynamize_err(this.get_account_nonce_count(AccountType::Token(token_id)))?;
+
+                    dynamize_err(RpcTokenState::new(
+                        chain_config,
+                        token_id,
+                        &token_data,
+                        circulating_supply,
+                        last_nonce,
+                    ))
+                    .map(Some)
+                },
+            )
+            .await,
+        )
+    }
+
     async fn export_bootstrap_file(
         &self,
         file_path: &std::path::Path,
         include_orphans: bool,
     ) -> RpcResult<()> {
         // TODO: test this function in functional tests
-        let file_obj: std::fs::File = rpc::handle_result(std::fs::File::create(file_path))?;
-        let writer: std::io::BufWriter<Box<dyn Write + Send>> =
-            std::io::BufWriter::new(Box::new(file_obj));
-
+        let file_path = file_path.to_owned();
         rpc::handle_result(
-            self.call(move |this| this.export_bootstrap_stream(writer, include_orphans))
+            self.call(move |this| this.export_bootstrap_file(&file_path, include_orphans))
                 .await,
         )
     }
 
     async fn import_bootstrap_file(&self, file_path: &std::path::Path) -> RpcResult<()> {
         // TODO: test this function in functional tests
-        let file_obj: std::fs::File = rpc::handle_result(std::fs::File::create(file_path))?;
-        let reader: std::io::BufReader<Box<dyn Read + Send>> =
-            std::io::BufReader::new(Box::new(file_obj));
-
-        rpc::handle_result(self.call_mut(move |this| this.import_bootstrap_stream(reader)).await)
+        let file_path = file_path.to_owned();
+        rpc::handle_result(self.call_mut(move |this| this.import_bootstrap_file(&file_path)).await)
     }
 
     async fn info(&self) -> RpcResult<ChainInfo> {
         rpc::handle_result(self.call(move |this| this.info()).await)
     }
 
+    async fn get_deployment_info(&self) -> RpcResult<Vec<RpcDeploymentInfo>> {
+        rpc::handle_result(
+            self.call(move |this| {
+                let next_height = this.get_best_block_height()?.next_height();
+                let chain_config = this.get_chain_config();
+                let lock_in_period = chain_config.epoch_length().get();
+
+                let deployments = chain_config
+                    .deployments()
+                    .iter()
+                    .map(|deployment| {
+                        RpcDeploymentInfo::new(deployment, next_height, lock_in_period)
+                    })
+                    .collect::<Vec<_>>();
+                Ok::<_, ChainstateError>(deployments)
+            })
+            .await,
+        )
+    }
+
+    async fn storage_size_info(&self) -> RpcResult<RpcStorageSizeInfo> {
+        rpc::handle_result(
+            self.call(move |this| this.storage_size_info().map(RpcStorageSizeInfo::from))
+                .await,
+        )
+    }
+
+    async fn get_recent_block_stats(&self, count: usize) -> RpcResult<Vec<RpcBlockStats>> {
+        rpc::handle_result(
+            self.call(move |this| {
+                let max_txs_size = this.get_chain_config().max_block_size_from_std_scripts() as u64;
+                let stats = this
+                    .get_recent_block_stats(count)?
+                    .iter()
+                    .map(|(block_index, stats)| {
+                        RpcBlockStats::new(block_index, stats, max_txs_size)
+                    })
+                    .collect::<Vec<_>>();
+                Ok::<_, ChainstateError>(stats)
+            })
+            .await,
+        )
+    }
+
+    async fn compact_storage(&self) -> RpcResult<RpcCompactionStatus> {
+        rpc::handle_result(
+            self.call_mut(move |this| this.compact_storage().map(RpcCompactionStatus::from))
+                .await,
+        )
+    }
+
+    async fn compact_undo_data(&self) -> RpcResult<RpcUndoCompactionReport> {
+        rpc::handle_result(
+            self.call_mut(move |this| this.compact_undo_data().map(RpcUndoCompactionReport::from))
+                .await,
+        )
+    }
+
+    async fn recovery_report(&self) -> RpcResult<Option<RpcRecoveryReport>> {
+        rpc::handle_result(
+            self.call(move |this| {
+                let report = this.get_recovery_report().map(RpcRecoveryReport::from);
+                Ok::<_, Infallible>(report)
+            })
+            .await,
+        )
+    }
+
     async fn subscribe_events(&self, pending: subscription::Pending) -> subscription::Reply {
         let event_rx = self.call_mut(move |this| this.subscribe_to_rpc_events()).await?;
         rpc::subscription::connect_broadcast_map(event_rx, pending, RpcEvent::from_event).await
@@ -0,0 +1,61 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    address::{AddressError, RpcAddress},
+    chain::{ChainConfig, DelegationId, PoolId},
+    primitives::Amount,
+};
+
+use crate::BlockRewardDistribution;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
+pub struct RpcDelegationReward {
+    pub delegation_id: RpcAddress<DelegationId>,
+    pub reward: Amount,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
+pub struct RpcBlockRewardDistribution {
+    pub pool_id: RpcAddress<PoolId>,
+    /// The part of the reward that went to the staker, including the cost per block and the margin
+    pub staker_reward: Amount,
+    /// The non-zero rewards of the pool's delegations
+    pub delegation_rewards: Vec<RpcDelegationReward>,
+}
+
+impl RpcBlockRewardDistribution {
+    pub fn new(
+        chain_config: &ChainConfig,
+        distribution: &BlockRewardDistribution,
+    ) -> Result<Self, AddressError> {
+        let delegation_rewards = distribution
+            .delegation_rewards()
+            .iter()
+            .map(|(delegation_id, reward)| {
+                Ok(RpcDelegationReward {
+                    delegation_id: RpcAddress::new(chain_config, *delegation_id)?,
+                    reward: *reward,
+                })
+            })
+            .collect::<Result<_, AddressError>>()?;
+
+        Ok(Self {
+            pool_id: RpcAddress::new(chain_config, distribution.pool_id())?,
+            staker_reward: distribution.staker_reward(),
+            delegation_rewards,
+        })
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chainstate_types::{BlockIndex, BlockStats};
+use common::{
+    chain::{block::timestamp::BlockTimestamp, Block},
+    primitives::{Amount, BlockHeight, Id},
+};
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
+pub struct RpcBlockStats {
+    pub block_id: Id<Block>,
+    pub height: BlockHeight,
+    pub timestamp: BlockTimestamp,
+    /// The number of transactions, not counting the block reward
+    pub tx_count: u32,
+    pub total_fees: Amount,
+    /// The total size of the transactions in bytes
+    pub txs_size: u64,
+    /// The maximum total size of the transactions that fits into a block
+    pub max_txs_size: u64,
+    /// The median fee rate in atoms per 1000 bytes; null if the block has no transactions
+    pub median_fee_rate: Option<Amount>,
+}
+
+impl RpcBlockStats {
+    pub fn new(block_index: &BlockIndex, stats: &BlockStats, max_txs_size: u64) -> Self {
+        Self {
+            block_id: *block_index.block_id(),
+            height: block_index.block_height(),
+            timestamp: block_index.block_timestamp(),
+            tx_count: stats.tx_count(),
+            total_fees: stats.total_fees(),
+            txs_size: stats.txs_size(),
+            max_txs_size,
+            median_fee_rate: stats.median_fee_rate(),
+        }
+    }
+}
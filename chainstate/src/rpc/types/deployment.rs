@@ -0,0 +1,38 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    chain::{Deployment, DeploymentState},
+    primitives::BlockHeight,
+};
+
+#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+pub struct RpcDeploymentInfo {
+    pub name: String,
+    /// The state of the deployment for the next block
+    pub state: DeploymentState,
+    /// The height at which the upgrade is activated; null if it's not scheduled on this chain
+    pub activation_height: Option<BlockHeight>,
+}
+
+impl RpcDeploymentInfo {
+    pub fn new(deployment: &Deployment, next_height: BlockHeight, lock_in_period: u64) -> Self {
+        Self {
+            name: deployment.name().to_owned(),
+            state: deployment.state(next_height, lock_in_period),
+            activation_height: deployment.activation_height(),
+        }
+    }
+}
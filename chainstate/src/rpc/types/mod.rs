@@ -16,9 +16,16 @@
 pub mod account;
 pub mod block;
 pub mod block_reward;
+pub mod block_reward_distribution;
+pub mod block_stats;
 pub mod consensus_data;
+pub mod deployment;
 pub mod event;
 pub mod input;
 pub mod output;
+pub mod pos_accounting;
+pub mod recovery;
 pub mod signed_transaction;
+pub mod storage;
 pub mod token;
+pub mod transaction;
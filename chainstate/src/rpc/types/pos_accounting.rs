@@ -0,0 +1,289 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write;
+
+use common::{
+    address::{AddressError, RpcAddress},
+    chain::{ChainConfig, DelegationId, Destination, PoolId},
+    primitives::{amount::RpcAmountOut, per_thousand::PerThousand, Amount},
+};
+use crypto::vrf::VRFPublicKey;
+use pos_accounting::{DelegationData, PoSAccountingData, PoolData};
+
+const CSV_HEADER: &str = "record_type,pool_id,delegation_id,balance,pledge,staker_rewards,cost_per_block,margin_ratio_per_thousand,destination,vrf_public_key";
+
+#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+pub struct RpcPoolAccountingData {
+    pool_id: RpcAddress<PoolId>,
+    /// Staker balance plus the balances of all the delegations
+    balance: RpcAmountOut,
+    pledge: RpcAmountOut,
+    staker_rewards: RpcAmountOut,
+    vrf_public_key: RpcAddress<VRFPublicKey>,
+    decommission_destination: RpcAddress<Destination>,
+    margin_ratio_per_thousand: PerThousand,
+    cost_per_block: RpcAmountOut,
+}
+
+impl RpcPoolAccountingData {
+    fn new(
+        chain_config: &ChainConfig,
+        pool_id: PoolId,
+        balance: Amount,
+        data: &PoolData,
+    ) -> Result<Self, AddressError> {
+        let decimals = chain_config.coin_decimals();
+        let result = Self {
+            pool_id: RpcAddress::new(chain_config, pool_id)?,
+            balance: RpcAmountOut::from_amount(balance, decimals),
+            pledge: RpcAmountOut::from_amount(data.pledge_amount(), decimals),
+            staker_rewards: RpcAmountOut::from_amount(data.staker_rewards(), decimals),
+            vrf_public_key: RpcAddress::new(chain_config, data.vrf_public_key().clone())?,
+            decommission_destination: RpcAddress::new(
+                chain_config,
+                data.decommission_destination().clone(),
+            )?,
+            margin_ratio_per_thousand: data.margin_ratio_per_thousand(),
+            cost_per_block: RpcAmountOut::from_amount(data.cost_per_block(), decimals),
+        };
+        Ok(result)
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+pub struct RpcDelegationAccountingData {
+    delegation_id: RpcAddress<DelegationId>,
+    pool_id: RpcAddress<PoolId>,
+    spend_destination: RpcAddress<Destination>,
+    balance: RpcAmountOut,
+}
+
+impl RpcDelegationAccountingData {
+    fn new(
+        chain_config: &ChainConfig,
+        delegation_id: DelegationId,
+        balance: Amount,
+        data: &DelegationData,
+    ) -> Result<Self, AddressError> {
+        let result = Self {
+            delegation_id: RpcAddress::new(chain_config, delegation_id)?,
+            pool_id: RpcAddress::new(chain_config, *data.source_pool())?,
+            spend_destination: RpcAddress::new(chain_config, data.spend_destination().clone())?,
+            balance: RpcAmountOut::from_amount(balance, chain_config.coin_decimals()),
+        };
+        Ok(result)
+    }
+}
+
+/// The complete PoS accounting state, in a form suitable for external audit.
+///
+/// Pools and delegations are sorted by their ids, so the output is stable for the same state.
+#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+pub struct RpcPoSAccountingData {
+    pools: Vec<RpcPoolAccountingData>,
+    delegations: Vec<RpcDelegationAccountingData>,
+}
+
+impl RpcPoSAccountingData {
+    pub fn new(chain_config: &ChainConfig, data: &PoSAccountingData) -> Result<Self, AddressError> {
+        let pools = data
+            .pool_data
+            .iter()
+            .map(|(pool_id, pool_data)| {
+                let balance = data.pool_balances.get(pool_id).copied().unwrap_or(Amount::ZERO);
+                RpcPoolAccountingData::new(chain_config, *pool_id, balance, pool_data)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let delegations = data
+            .delegation_data
+            .iter()
+            .map(|(delegation_id, delegation_data)| {
+                let balance =
+                    data.delegation_balances.get(delegation_id).copied().unwrap_or(Amount::ZERO);
+                RpcDelegationAccountingData::new(
+                    chain_config,
+                    *delegation_id,
+                    balance,
+                    delegation_data,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { pools, delegations })
+    }
+
+    /// Write the data as CSV, one record per pool or delegation.
+    ///
+    /// Amounts are written in atoms and the margin ratio in thousandths;
+    /// the columns that don't apply to a record type are left empty.
+    pub fn write_csv(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        writeln!(writer, "{CSV_HEADER}")?;
+
+        for pool in &self.pools {
+            writeln!(
+                writer,
+                "pool,{},,{},{},{},{},{},{},{}",
+                pool.pool_id,
+                pool.balance.amount().into_atoms(),
+                pool.pledge.amount().into_atoms(),
+                pool.staker_rewards.amount().into_atoms(),
+                pool.cost_per_block.amount().into_atoms(),
+                pool.margin_ratio_per_thousand.value(),
+                pool.decommission_destination,
+                pool.vrf_public_key,
+            )?;
+        }
+
+        for delegation in &self.delegations {
+            writeln!(
+                writer,
+                "delegation,{},{},{},,,,,{},",
+                delegation.pool_id,
+                delegation.delegation_id,
+                delegation.balance.amount().into_atoms(),
+                delegation.spend_destination,
+            )?;
+        }
+
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use common::{
+        address::{pubkeyhash::PublicKeyHash, traits::Addressable},
+        chain::config::create_unit_test_config,
+        primitives::H256,
+    };
+    use crypto::vrf::{VRFKeyKind, VRFPrivateKey};
+    use randomness::{CryptoRng, Rng};
+    use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, Seed};
+
+    use super::*;
+
+    fn make_random_data(rng: &mut (impl Rng + CryptoRng)) -> PoSAccountingData {
+        let mut data = PoSAccountingData::new();
+
+        for _ in 0..rng.gen_range(1..5) {
+            let pool_id = PoolId::new(H256::random_using(rng));
+            let (_, vrf_pk) = VRFPrivateKey::new_from_rng(rng, VRFKeyKind::Schnorrkel);
+            let pool_data = PoolData::new(
+                Destination::PublicKeyHash(PublicKeyHash::random_using(rng)),
+                Amount::from_atoms(rng.gen_range(1..1_000_000)),
+                Amount::from_atoms(rng.gen_range(0..1_000_000)),
+                vrf_pk,
+                PerThousand::new_from_rng(rng),
+                Amount::from_atoms(rng.gen_range(0..1_000)),
+            );
+            data.pool_data.insert(pool_id, pool_data);
+            data.pool_balances
+                .insert(pool_id, Amount::from_atoms(rng.gen_range(1..1_000_000)));
+
+            for _ in 0..rng.gen_range(0..3) {
+                let delegation_id = DelegationId::new(H256::random_using(rng));
+                let delegation_data = DelegationData::new(
+                    pool_id,
+                    Destination::PublicKeyHash(PublicKeyHash::random_using(rng)),
+                );
+                data.delegation_data.insert(delegation_id, delegation_data);
+                data.delegation_balances.insert(
+                    delegation_id,
+                    Amount::from_atoms(rng.gen_range(1..1_000_000)),
+                );
+            }
+        }
+
+        data
+    }
+
+    fn decode<T: Addressable>(chain_config: &ChainConfig, s: &str) -> T {
+        RpcAddress::<T>::from_string(s.to_owned()).decode_object(chain_config).unwrap()
+    }
+
+    fn parse_amount(s: &str) -> Amount {
+        Amount::from_atoms(s.parse().unwrap())
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn write_csv_round_trip(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let chain_config = create_unit_test_config();
+        let data = make_random_data(&mut rng);
+
+        let mut output = Vec::new();
+        RpcPoSAccountingData::new(&chain_config, &data)
+            .unwrap()
+            .write_csv(&mut output)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let mut lines = output.lines();
+        assert_eq!(lines.next(), Some(CSV_HEADER));
+
+        let column_count = CSV_HEADER.split(',').count();
+        let mut pool_data = BTreeMap::new();
+        let mut pool_balances = BTreeMap::new();
+        let mut delegation_data = BTreeMap::new();
+        let mut delegation_balances = BTreeMap::new();
+
+        for line in lines {
+            let columns = line.split(',').collect::<Vec<_>>();
+            assert_eq!(columns.len(), column_count);
+
+            let pool_id = decode::<PoolId>(&chain_config, columns[1]);
+
+            match columns[0] {
+                "pool" => {
+                    assert_eq!(columns[2], "");
+                    let vrf_pk = decode::<VRFPublicKey>(&chain_config, columns[9]);
+                    let decommission_destination = decode::<Destination>(&chain_config, columns[8]);
+                    let pool = PoolData::new(
+                        decommission_destination,
+                        parse_amount(columns[4]),
+                        parse_amount(columns[5]),
+                        vrf_pk,
+                        PerThousand::new(columns[7].parse().unwrap()).unwrap(),
+                        parse_amount(columns[6]),
+                    );
+                    assert!(pool_data.insert(pool_id, pool).is_none());
+                    pool_balances.insert(pool_id, parse_amount(columns[3]));
+                }
+                "delegation" => {
+                    assert_eq!(&columns[4..8], &["", "", "", ""]);
+                    assert_eq!(columns[9], "");
+                    let delegation_id = decode::<DelegationId>(&chain_config, columns[2]);
+                    let spend_destination = decode::<Destination>(&chain_config, columns[8]);
+                    let delegation = DelegationData::new(pool_id, spend_destination);
+                    assert!(delegation_data.insert(delegation_id, delegation).is_none());
+                    delegation_balances.insert(delegation_id, parse_amount(columns[3]));
+                }
+                record_type => panic!("Unexpected record type {record_type}"),
+            }
+        }
+
+        assert_eq!(pool_data, data.pool_data);
+        assert_eq!(pool_balances, data.pool_balances);
+        assert_eq!(delegation_data, data.delegation_data);
+        assert_eq!(delegation_balances, data.delegation_balances);
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    chain::{Block, GenBlock},
+    primitives::{BlockHeight, Id},
+};
+
+use crate::{PendingOperation, RecoveryReport};
+
+#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+#[serde(tag = "type", content = "content")]
+pub enum RpcPendingOperation {
+    BlockInvalidation {
+        block_id: Id<Block>,
+        tip_before: Id<GenBlock>,
+        is_explicit: bool,
+    },
+}
+
+impl From<PendingOperation> for RpcPendingOperation {
+    fn from(operation: PendingOperation) -> Self {
+        match operation {
+            PendingOperation::BlockInvalidation {
+                block_id,
+                tip_before,
+                is_explicit,
+            } => Self::BlockInvalidation {
+                block_id,
+                tip_before,
+                is_explicit,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+pub struct RpcRecoveryReport {
+    /// The operation that was interrupted by the shutdown, if any
+    pub interrupted_operation: Option<RpcPendingOperation>,
+    /// Descriptions of the inconsistencies found among the most recent mainchain blocks
+    pub inconsistencies: Vec<String>,
+    pub old_tip_id: Id<GenBlock>,
+    pub old_tip_height: BlockHeight,
+    pub rolled_back_to_id: Id<GenBlock>,
+    pub rolled_back_to_height: BlockHeight,
+    pub new_tip_id: Id<GenBlock>,
+    pub new_tip_height: BlockHeight,
+}
+
+impl From<RecoveryReport> for RpcRecoveryReport {
+    fn from(report: RecoveryReport) -> Self {
+        let RecoveryReport {
+            interrupted_operation,
+            inconsistencies,
+            old_tip_id,
+            old_tip_height,
+            rolled_back_to_id,
+            rolled_back_to_height,
+            new_tip_id,
+            new_tip_height,
+        } = report;
+
+        Self {
+            interrupted_operation: interrupted_operation.map(Into::into),
+            inconsistencies: inconsistencies.iter().map(ToString::to_string).collect(),
+            old_tip_id,
+            old_tip_height,
+            rolled_back_to_id,
+            rolled_back_to_height,
+            new_tip_id,
+            new_tip_height,
+        }
+    }
+}
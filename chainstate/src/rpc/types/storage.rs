@@ -0,0 +1,167 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chainstate_storage::{
+    CompactionOutcome, CompactionReport, CompactionStatus, MapSizeInfo, StorageSizeInfo,
+};
+use common::primitives::BlockHeight;
+
+use crate::UndoCompactionReport;
+
+#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+pub struct RpcMapSizeInfo {
+    pub name: String,
+    pub entry_count: u64,
+    pub key_bytes: u64,
+    pub value_bytes: u64,
+}
+
+impl From<MapSizeInfo> for RpcMapSizeInfo {
+    fn from(info: MapSizeInfo) -> Self {
+        let MapSizeInfo {
+            name,
+            entry_count,
+            key_bytes,
+            value_bytes,
+        } = info;
+
+        Self {
+            name,
+            entry_count,
+            key_bytes,
+            value_bytes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+#[serde(tag = "type", content = "content")]
+pub enum RpcCompactionOutcome {
+    /// The compaction hasn't finished, either because it's in progress or because the node
+    /// was stopped while compacting
+    Interrupted,
+    Completed {
+        size_after: u64,
+    },
+    /// The compaction has failed and the database has been left uncompacted
+    Failed {
+        error: String,
+    },
+}
+
+impl From<CompactionOutcome> for RpcCompactionOutcome {
+    fn from(outcome: CompactionOutcome) -> Self {
+        match outcome {
+            CompactionOutcome::Interrupted => Self::Interrupted,
+            CompactionOutcome::Completed { size_after } => Self::Completed { size_after },
+            CompactionOutcome::Failed { error } => Self::Failed { error },
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+pub struct RpcCompactionReport {
+    /// Size of the database files in bytes before the compaction
+    pub size_before: u64,
+    /// The number of maps that have been copied so far
+    pub maps_compacted: usize,
+    pub maps_total: usize,
+    /// Seconds since the Unix epoch; null if the compaction hasn't finished
+    pub finished_at: Option<u64>,
+    pub outcome: RpcCompactionOutcome,
+}
+
+impl From<CompactionReport> for RpcCompactionReport {
+    fn from(report: CompactionReport) -> Self {
+        let CompactionReport {
+            size_before,
+            maps_compacted,
+            maps_total,
+            finished_at,
+            outcome,
+        } = report;
+
+        Self {
+            size_before,
+            maps_compacted,
+            maps_total,
+            finished_at,
+            outcome: outcome.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+pub struct RpcStorageSizeInfo {
+    /// Size of the database files in bytes; null if the storage is not kept on disk
+    pub disk_size: Option<u64>,
+    pub maps: Vec<RpcMapSizeInfo>,
+    /// Whether the compaction is going to happen on the next start of the node
+    pub compaction_scheduled: bool,
+    /// The progress and the outcome of the last compaction; null if the database has never
+    /// been compacted
+    pub last_compaction: Option<RpcCompactionReport>,
+}
+
+impl From<StorageSizeInfo> for RpcStorageSizeInfo {
+    fn from(info: StorageSizeInfo) -> Self {
+        Self {
+            disk_size: info.disk_size,
+            maps: info.maps.into_iter().map(Into::into).collect(),
+            compaction_scheduled: info.compaction_scheduled,
+            last_compaction: info.last_compaction.map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, serde::Serialize, rpc_description::HasValueHint)]
+#[serde(tag = "type", content = "content")]
+pub enum RpcCompactionStatus {
+    NotSupported,
+    Completed,
+    ScheduledOnRestart,
+}
+
+impl From<CompactionStatus> for RpcCompactionStatus {
+    fn from(status: CompactionStatus) -> Self {
+        match status {
+            CompactionStatus::NotSupported => Self::NotSupported,
+            CompactionStatus::Completed => Self::Completed,
+            CompactionStatus::ScheduledOnRestart => Self::ScheduledOnRestart,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, serde::Serialize, rpc_description::HasValueHint)]
+pub struct RpcUndoCompactionReport {
+    /// The undo data is kept for the mainchain blocks starting from this height
+    pub retained_from_height: BlockHeight,
+    /// The number of blocks whose undo data has been dropped
+    pub compacted_blocks_count: u64,
+}
+
+impl From<UndoCompactionReport> for RpcUndoCompactionReport {
+    fn from(report: UndoCompactionReport) -> Self {
+        let UndoCompactionReport {
+            retained_from_height,
+            compacted_blocks_count,
+        } = report;
+
+        Self {
+            retained_from_height,
+            compacted_blocks_count,
+        }
+    }
+}
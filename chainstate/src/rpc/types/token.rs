@@ -16,10 +16,13 @@
 use common::{
     address::{AddressError, RpcAddress},
     chain::{
-        tokens::{IsTokenFreezable, NftIssuance, TokenIssuance, TokenTotalSupply},
-        ChainConfig, Destination,
+        tokens::{
+            IsTokenFreezable, IsTokenFrozen, IsTokenUnfreezable, NftIssuance, TokenId,
+            TokenIssuance, TokenTotalSupply,
+        },
+        AccountNonce, ChainConfig, Destination,
     },
-    primitives::amount::RpcAmountOut,
+    primitives::{amount::RpcAmountOut, Amount},
 };
 use rpc::types::{RpcHexString, RpcString};
 
@@ -74,6 +77,75 @@ impl RpcTokenIssuance {
     }
 }
 
+#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+#[serde(tag = "type", content = "content")]
+pub enum RpcTokenFrozenState {
+    NotFrozen { freezable: bool },
+    Frozen { unfreezable: bool },
+}
+
+impl From<IsTokenFrozen> for RpcTokenFrozenState {
+    fn from(frozen: IsTokenFrozen) -> Self {
+        match frozen {
+            IsTokenFrozen::No(freezable) => RpcTokenFrozenState::NotFrozen {
+                freezable: match freezable {
+                    IsTokenFreezable::No => false,
+                    IsTokenFreezable::Yes => true,
+                },
+            },
+            IsTokenFrozen::Yes(unfreezable) => RpcTokenFrozenState::Frozen {
+                unfreezable: match unfreezable {
+                    IsTokenUnfreezable::No => false,
+                    IsTokenUnfreezable::Yes => true,
+                },
+            },
+        }
+    }
+}
+
+/// The state of a fungible token as seen by the consensus rules at the tip of the chain
+#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+pub struct RpcTokenState {
+    pub token_id: RpcAddress<TokenId>,
+    pub circulating_supply: RpcAmountOut,
+    pub total_supply: RpcTokenTotalSupply,
+    pub is_locked: bool,
+    pub frozen: RpcTokenFrozenState,
+    pub authority: RpcAddress<Destination>,
+    /// The nonce that the next token authority command has to use
+    pub next_nonce: u64,
+}
+
+impl RpcTokenState {
+    pub fn new(
+        chain_config: &ChainConfig,
+        token_id: TokenId,
+        token_data: &tokens_accounting::FungibleTokenData,
+        circulating_supply: Amount,
+        last_nonce: Option<AccountNonce>,
+    ) -> Result<Self, AddressError> {
+        let decimals = token_data.number_of_decimals();
+        let total_supply = match token_data.total_supply() {
+            TokenTotalSupply::Fixed(amount) => RpcTokenTotalSupply::Fixed {
+                amount: RpcAmountOut::from_amount(*amount, decimals),
+            },
+            TokenTotalSupply::Lockable => RpcTokenTotalSupply::Lockable,
+            TokenTotalSupply::Unlimited => RpcTokenTotalSupply::Unlimited,
+        };
+        let next_nonce = last_nonce.map_or(0, |nonce| nonce.value().saturating_add(1));
+
+        Ok(Self {
+            token_id: RpcAddress::new(chain_config, token_id)?,
+            circulating_supply: RpcAmountOut::from_amount(circulating_supply, decimals),
+            total_supply,
+            is_locked: token_data.is_locked(),
+            frozen: token_data.frozen_state().into(),
+            authority: RpcAddress::new(chain_config, token_data.authority().clone())?,
+            next_nonce,
+        })
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
 pub struct RpcNftIssuance {
     pub metadata: RpcNftMetadata,
@@ -0,0 +1,38 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    chain::{Block, SignedTransaction},
+    primitives::{BlockHeight, Id},
+};
+use serialization::hex_encoded::HexEncoded;
+
+/// A mainchain transaction found in the transaction index
+#[derive(Debug, Clone, serde::Serialize, rpc_description::HasValueHint)]
+pub struct RpcIndexedTransaction {
+    pub tx: HexEncoded<SignedTransaction>,
+    pub block_id: Id<Block>,
+    pub block_height: BlockHeight,
+}
+
+impl RpcIndexedTransaction {
+    pub fn new(tx: SignedTransaction, block_id: Id<Block>, block_height: BlockHeight) -> Self {
+        Self {
+            tx: HexEncoded::new(tx),
+            block_id,
+            block_height,
+        }
+    }
+}
@@ -41,43 +41,7 @@ impl<B: storage::Backend> StoreTxRo<'_, B> {
     /// Collect and return all tip accounting data from storage
     #[log_error]
     pub fn read_pos_accounting_data_tip(&self) -> crate::Result<pos_accounting::PoSAccountingData> {
-        let pool_data = self
-            .0
-            .get::<db::DBAccountingPoolDataTip, _>()
-            .prefix_iter_decoded(&())?
-            .collect::<BTreeMap<_, _>>();
-
-        let pool_balances = self
-            .0
-            .get::<db::DBAccountingPoolBalancesTip, _>()
-            .prefix_iter_decoded(&())?
-            .collect::<BTreeMap<_, _>>();
-
-        let delegation_data = self
-            .0
-            .get::<db::DBAccountingDelegationDataTip, _>()
-            .prefix_iter_decoded(&())?
-            .collect::<BTreeMap<_, _>>();
-
-        let delegation_balances = self
-            .0
-            .get::<db::DBAccountingDelegationBalancesTip, _>()
-            .prefix_iter_decoded(&())?
-            .collect::<BTreeMap<_, _>>();
-
-        let pool_delegation_shares = self
-            .0
-            .get::<db::DBAccountingPoolDelegationSharesTip, _>()
-            .prefix_iter_decoded(&())?
-            .collect::<BTreeMap<_, _>>();
-
-        Ok(pos_accounting::PoSAccountingData {
-            pool_data,
-            pool_balances,
-            pool_delegation_shares,
-            delegation_balances,
-            delegation_data,
-        })
+        self.get_pos_accounting_data_tip()
     }
 
     /// Collect and return all sealed accounting data from storage
@@ -85,43 +49,7 @@ impl<B: storage::Backend> StoreTxRo<'_, B> {
     pub fn read_pos_accounting_data_sealed(
         &self,
     ) -> crate::Result<pos_accounting::PoSAccountingData> {
-        let pool_data = self
-            .0
-            .get::<db::DBAccountingPoolDataSealed, _>()
-            .prefix_iter_decoded(&())?
-            .collect::<BTreeMap<_, _>>();
-
-        let pool_balances = self
-            .0
-            .get::<db::DBAccountingPoolBalancesSealed, _>()
-            .prefix_iter_decoded(&())?
-            .collect::<BTreeMap<_, _>>();
-
-        let delegation_data = self
-            .0
-            .get::<db::DBAccountingDelegationDataSealed, _>()
-            .prefix_iter_decoded(&())?
-            .collect::<BTreeMap<_, _>>();
-
-        let delegation_balances = self
-            .0
-            .get::<db::DBAccountingDelegationBalancesSealed, _>()
-            .prefix_iter_decoded(&())?
-            .collect::<BTreeMap<_, _>>();
-
-        let pool_delegation_shares = self
-            .0
-            .get::<db::DBAccountingPoolDelegationSharesSealed, _>()
-            .prefix_iter_decoded(&())?
-            .collect::<BTreeMap<_, _>>();
-
-        Ok(pos_accounting::PoSAccountingData {
-            pool_data,
-            pool_balances,
-            pool_delegation_shares,
-            delegation_balances,
-            delegation_data,
-        })
+        self.get_pos_accounting_data_sealed()
     }
 
     #[log_error]
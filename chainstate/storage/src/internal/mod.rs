@@ -32,12 +32,15 @@ use utils::log_error;
 
 use crate::{
     schema::Schema, BlockchainStorage, BlockchainStorageRead, BlockchainStorageWrite,
-    TransactionRw, Transactional,
+    CompactionStatus, MapSizeInfo, StorageMaintenance, StorageSizeInfo, TransactionRw,
+    Transactional,
 };
 
 pub use store_tx::{StoreTxRo, StoreTxRw};
 
+mod pending_operation;
 mod version;
+pub use pending_operation::PendingOperation;
 pub use version::ChainstateStorageVersion;
 
 /// Store for blockchain data, parametrized over the backend B
@@ -111,6 +114,34 @@ impl<'tx, B: storage::Backend + 'tx> Transactional<'tx> for Store<B> {
     }
 }
 
+impl<B: storage::Backend> StorageMaintenance for Store<B> {
+    #[log_error]
+    fn size_info(&self) -> crate::Result<StorageSizeInfo> {
+        let stats = self.0.transaction_ro()?.storage_stats()?;
+        let maps = stats
+            .into_iter()
+            .map(|(map_id, stats)| MapSizeInfo {
+                name: map_id.name(),
+                entry_count: stats.entry_count,
+                key_bytes: stats.key_bytes,
+                value_bytes: stats.value_bytes,
+            })
+            .collect();
+
+        Ok(StorageSizeInfo {
+            disk_size: self.0.disk_size()?,
+            maps,
+            compaction_scheduled: self.0.is_compaction_scheduled()?,
+            last_compaction: self.0.last_compaction()?,
+        })
+    }
+
+    #[log_error]
+    fn compact(&self) -> crate::Result<CompactionStatus> {
+        Ok(self.0.compact()?)
+    }
+}
+
 impl<B: storage::Backend + 'static> BlockchainStorage for Store<B> {}
 
 impl<B: storage::Backend> PoSAccountingStorageRead<TipStorageTag> for Store<B> {
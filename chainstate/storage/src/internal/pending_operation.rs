@@ -0,0 +1,37 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    chain::{Block, GenBlock},
+    primitives::Id,
+};
+use serialization::{Decode, Encode};
+
+/// A chainstate operation that spans multiple db transactions.
+///
+/// The operation is recorded in the db together with its first step and removed once the whole
+/// operation has been completed. If it is still present on startup, the node was shut down in
+/// the middle of it.
+#[derive(Debug, Encode, Decode, Clone, Copy, Eq, PartialEq)]
+pub enum PendingOperation {
+    /// Invalidation of a mainchain block: disconnecting the block and its descendants, marking
+    /// them as invalid and activating the best remaining chain.
+    #[codec(index = 0)]
+    BlockInvalidation {
+        block_id: Id<Block>,
+        tip_before: Id<GenBlock>,
+        is_explicit: bool,
+    },
+}
@@ -54,6 +54,8 @@ mod well_known {
     declare_entry!(MagicBytes: chain::config::MagicBytes);
     declare_entry!(ChainType: String);
     declare_entry!(MinHeightForReorg: BlockHeight);
+    declare_entry!(TxIndexEnabled: bool);
+    declare_entry!(PendingOperation: crate::PendingOperation);
 }
 
 /// Read-only chainstate storage transaction
@@ -197,6 +199,11 @@ impl<'st, B: storage::Backend> StoreTxRw<'st, B> {
         self.write::<db::DBValue, _, _, _>(E::KEY, val.encode())
     }
 
+    // Delete the value of a well-known entry
+    fn del_value<E: well_known::Entry>(&mut self) -> crate::Result<()> {
+        self.del::<db::DBValue, _, _>(E::KEY)
+    }
+
     // Delete a value from the database
     fn del<DbMap, I, K>(&mut self, key: K) -> crate::Result<()>
     where
@@ -16,7 +16,9 @@
 use std::collections::{BTreeMap, BTreeSet};
 
 use super::db;
-use chainstate_types::{BlockIndex, EpochData, EpochStorageRead, SealedStorageTag, TipStorageTag};
+use chainstate_types::{
+    BlockIndex, BlockStats, EpochData, EpochStorageRead, SealedStorageTag, TipStorageTag,
+};
 use common::{
     chain::{
         block::{signed_block_header::SignedBlockHeader, BlockReward},
@@ -29,8 +31,8 @@ use common::{
 };
 use orders_accounting::{OrdersAccountingStorageRead, OrdersAccountingUndo};
 use pos_accounting::{
-    DelegationData, DeltaMergeUndo, PoSAccountingDeltaData, PoSAccountingStorageRead,
-    PoSAccountingUndo, PoolData,
+    DelegationData, DeltaMergeUndo, PoSAccountingData, PoSAccountingDeltaData,
+    PoSAccountingStorageRead, PoSAccountingUndo, PoolData,
 };
 use serialization::{Decode, Encode};
 use storage::MakeMapRef;
@@ -38,7 +40,7 @@ use tokens_accounting::{TokenAccountingUndo, TokensAccountingStorageRead};
 use utils::log_error;
 use utxo::{Utxo, UtxosBlockUndo, UtxosStorageRead};
 
-use crate::{BlockchainStorageRead, ChainstateStorageVersion};
+use crate::{BlockchainStorageRead, ChainstateStorageVersion, PendingOperation};
 
 use super::well_known;
 
@@ -161,6 +163,26 @@ impl<'st, B: storage::Backend> BlockchainStorageRead for super::StoreTxRo<'st, B
         self.read::<db::DBIssuanceTxVsTokenId, _, _>(&issuance_tx_id)
     }
 
+    #[log_error]
+    fn get_tx_index_enabled(&self) -> crate::Result<Option<bool>> {
+        self.read_value::<well_known::TxIndexEnabled>()
+    }
+
+    #[log_error]
+    fn get_tx_block_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<Id<Block>>> {
+        self.read::<db::DBTxIndex, _, _>(tx_id)
+    }
+
+    #[log_error]
+    fn get_block_stats(&self, block_id: &Id<Block>) -> crate::Result<Option<BlockStats>> {
+        self.read::<db::DBBlockStats, _, _>(block_id)
+    }
+
+    #[log_error]
+    fn get_pending_operation(&self) -> crate::Result<Option<PendingOperation>> {
+        self.read_value::<well_known::PendingOperation>()
+    }
+
     #[log_error]
     fn get_tokens_accounting_undo(
         &self,
@@ -244,6 +266,68 @@ impl<'st, B: storage::Backend> BlockchainStorageRead for super::StoreTxRo<'st, B
         let items = map.prefix_iter_decoded(&())?;
         Ok(items.collect::<BTreeMap<_, _>>())
     }
+
+    #[log_error]
+    fn get_pos_accounting_data_tip(&self) -> crate::Result<PoSAccountingData> {
+        Ok(PoSAccountingData {
+            pool_data: self
+                .0
+                .get::<db::DBAccountingPoolDataTip, _>()
+                .prefix_iter_decoded(&())?
+                .collect(),
+            pool_balances: self
+                .0
+                .get::<db::DBAccountingPoolBalancesTip, _>()
+                .prefix_iter_decoded(&())?
+                .collect(),
+            pool_delegation_shares: self
+                .0
+                .get::<db::DBAccountingPoolDelegationSharesTip, _>()
+                .prefix_iter_decoded(&())?
+                .collect(),
+            delegation_balances: self
+                .0
+                .get::<db::DBAccountingDelegationBalancesTip, _>()
+                .prefix_iter_decoded(&())?
+                .collect(),
+            delegation_data: self
+                .0
+                .get::<db::DBAccountingDelegationDataTip, _>()
+                .prefix_iter_decoded(&())?
+                .collect(),
+        })
+    }
+
+    #[log_error]
+    fn get_pos_accounting_data_sealed(&self) -> crate::Result<PoSAccountingData> {
+        Ok(PoSAccountingData {
+            pool_data: self
+                .0
+                .get::<db::DBAccountingPoolDataSealed, _>()
+                .prefix_iter_decoded(&())?
+                .collect(),
+            pool_balances: self
+                .0
+                .get::<db::DBAccountingPoolBalancesSealed, _>()
+                .prefix_iter_decoded(&())?
+                .collect(),
+            pool_delegation_shares: self
+                .0
+                .get::<db::DBAccountingPoolDelegationSharesSealed, _>()
+                .prefix_iter_decoded(&())?
+                .collect(),
+            delegation_balances: self
+                .0
+                .get::<db::DBAccountingDelegationBalancesSealed, _>()
+                .prefix_iter_decoded(&())?
+                .collect(),
+            delegation_data: self
+                .0
+                .get::<db::DBAccountingDelegationDataSealed, _>()
+                .prefix_iter_decoded(&())?
+                .collect(),
+        })
+    }
 }
 
 impl<'st, B: storage::Backend> EpochStorageRead for super::StoreTxRo<'st, B> {
@@ -473,6 +557,26 @@ impl<'st, B: storage::Backend> BlockchainStorageRead for super::StoreTxRw<'st, B
         self.read::<db::DBIssuanceTxVsTokenId, _, _>(&issuance_tx_id)
     }
 
+    #[log_error]
+    fn get_tx_index_enabled(&self) -> crate::Result<Option<bool>> {
+        self.read_value::<well_known::TxIndexEnabled>()
+    }
+
+    #[log_error]
+    fn get_tx_block_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<Id<Block>>> {
+        self.read::<db::DBTxIndex, _, _>(tx_id)
+    }
+
+    #[log_error]
+    fn get_block_stats(&self, block_id: &Id<Block>) -> crate::Result<Option<BlockStats>> {
+        self.read::<db::DBBlockStats, _, _>(block_id)
+    }
+
+    #[log_error]
+    fn get_pending_operation(&self) -> crate::Result<Option<PendingOperation>> {
+        self.read_value::<well_known::PendingOperation>()
+    }
+
     #[log_error]
     fn get_tokens_accounting_undo(
         &self,
@@ -561,6 +665,58 @@ impl<'st, B: storage::Backend> BlockchainStorageRead for super::StoreTxRw<'st, B
         let items = map.prefix_iter_decoded(&())?;
         Ok(items.collect::<BTreeMap<_, _>>())
     }
+
+    #[log_error]
+    fn get_pos_accounting_data_tip(&self) -> crate::Result<PoSAccountingData> {
+        Ok(PoSAccountingData {
+            pool_data: self
+                .get_map::<db::DBAccountingPoolDataTip, _>()?
+                .prefix_iter_decoded(&())?
+                .collect(),
+            pool_balances: self
+                .get_map::<db::DBAccountingPoolBalancesTip, _>()?
+                .prefix_iter_decoded(&())?
+                .collect(),
+            pool_delegation_shares: self
+                .get_map::<db::DBAccountingPoolDelegationSharesTip, _>()?
+                .prefix_iter_decoded(&())?
+                .collect(),
+            delegation_balances: self
+                .get_map::<db::DBAccountingDelegationBalancesTip, _>()?
+                .prefix_iter_decoded(&())?
+                .collect(),
+            delegation_data: self
+                .get_map::<db::DBAccountingDelegationDataTip, _>()?
+                .prefix_iter_decoded(&())?
+                .collect(),
+        })
+    }
+
+    #[log_error]
+    fn get_pos_accounting_data_sealed(&self) -> crate::Result<PoSAccountingData> {
+        Ok(PoSAccountingData {
+            pool_data: self
+                .get_map::<db::DBAccountingPoolDataSealed, _>()?
+                .prefix_iter_decoded(&())?
+                .collect(),
+            pool_balances: self
+                .get_map::<db::DBAccountingPoolBalancesSealed, _>()?
+                .prefix_iter_decoded(&())?
+                .collect(),
+            pool_delegation_shares: self
+                .get_map::<db::DBAccountingPoolDelegationSharesSealed, _>()?
+                .prefix_iter_decoded(&())?
+                .collect(),
+            delegation_balances: self
+                .get_map::<db::DBAccountingDelegationBalancesSealed, _>()?
+                .prefix_iter_decoded(&())?
+                .collect(),
+            delegation_data: self
+                .get_map::<db::DBAccountingDelegationDataSealed, _>()?
+                .prefix_iter_decoded(&())?
+                .collect(),
+        })
+    }
 }
 
 impl<'st, B: storage::Backend> EpochStorageRead for super::StoreTxRw<'st, B> {
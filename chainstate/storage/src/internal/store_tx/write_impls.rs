@@ -14,8 +14,11 @@
 // limitations under the License.
 
 use super::{well_known, StoreTxRw};
-use crate::{BlockchainStorageWrite, ChainstateStorageVersion, SealedStorageTag, TipStorageTag};
-use chainstate_types::{BlockIndex, EpochData, EpochStorageWrite};
+use crate::{
+    BlockchainStorageWrite, ChainstateStorageVersion, PendingOperation, SealedStorageTag,
+    TipStorageTag,
+};
+use chainstate_types::{BlockIndex, BlockStats, EpochData, EpochStorageWrite};
 use common::{
     chain::{
         config::{EpochIndex, MagicBytes},
@@ -134,6 +137,54 @@ impl<'st, B: storage::Backend> BlockchainStorageWrite for StoreTxRw<'st, B> {
         self.del::<db::DBIssuanceTxVsTokenId, _, _>(issuance_tx_id)
     }
 
+    #[log_error]
+    fn set_tx_index_enabled(&mut self, enabled: bool) -> crate::Result<()> {
+        self.write_value::<well_known::TxIndexEnabled>(&enabled)
+    }
+
+    #[log_error]
+    fn set_tx_block_id(
+        &mut self,
+        tx_id: &Id<Transaction>,
+        block_id: &Id<Block>,
+    ) -> crate::Result<()> {
+        self.write::<db::DBTxIndex, _, _, _>(tx_id, block_id)
+    }
+
+    #[log_error]
+    fn del_tx_block_id(&mut self, tx_id: &Id<Transaction>) -> crate::Result<()> {
+        self.del::<db::DBTxIndex, _, _>(tx_id)
+    }
+
+    #[log_error]
+    fn clear_tx_index(&mut self) -> crate::Result<()> {
+        let tx_ids = self.get_map::<db::DBTxIndex, _>()?.prefix_iter_keys(&())?.collect::<Vec<_>>();
+        for tx_id in tx_ids {
+            self.del::<db::DBTxIndex, _, _>(tx_id)?;
+        }
+        Ok(())
+    }
+
+    #[log_error]
+    fn set_block_stats(&mut self, block_id: &Id<Block>, stats: &BlockStats) -> crate::Result<()> {
+        self.write::<db::DBBlockStats, _, _, _>(block_id, stats)
+    }
+
+    #[log_error]
+    fn del_block_stats(&mut self, block_id: &Id<Block>) -> crate::Result<()> {
+        self.del::<db::DBBlockStats, _, _>(block_id)
+    }
+
+    #[log_error]
+    fn set_pending_operation(&mut self, operation: &PendingOperation) -> crate::Result<()> {
+        self.write_value::<well_known::PendingOperation>(operation)
+    }
+
+    #[log_error]
+    fn del_pending_operation(&mut self) -> crate::Result<()> {
+        self.del_value::<well_known::PendingOperation>()
+    }
+
     #[log_error]
     fn set_tokens_accounting_undo_data(
         &mut self,
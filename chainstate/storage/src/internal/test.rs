@@ -16,7 +16,7 @@
 use super::*;
 use common::chain::output_value::OutputValue;
 use common::chain::transaction::signed_transaction::SignedTransaction;
-use common::chain::{Block, Destination, OutPointSourceId, TxOutput, UtxoOutPoint};
+use common::chain::{Block, Destination, OutPointSourceId, Transaction, TxOutput, UtxoOutPoint};
 use common::primitives::Id;
 use common::primitives::{Amount, BlockHeight, Idable, H256};
 use crypto::key::{KeyKind, PrivateKey};
@@ -40,6 +40,110 @@ fn test_storage_get_default_version_in_tx() {
     })
 }
 
+#[test]
+fn size_info() {
+    utils::concurrency::model(|| {
+        let store = TestStore::new_empty().unwrap();
+
+        let info = store.size_info().unwrap();
+        assert_eq!(info.disk_size, None);
+        assert!(!info.compaction_scheduled);
+        assert_eq!(info.last_compaction, None);
+        assert!(!info.maps.is_empty());
+        assert!(info.maps.iter().all(|map| map.entry_count == 0));
+
+        let mut db_tx = store.transaction_rw(None).unwrap();
+        db_tx.set_storage_version(ChainstateStorageVersion::CURRENT).unwrap();
+        db_tx.commit().unwrap();
+
+        let info = store.size_info().unwrap();
+        let values_map = info.maps.iter().find(|map| map.name == "DBValue").unwrap();
+        assert_eq!(values_map.entry_count, 1);
+        assert!(values_map.key_bytes > 0);
+        assert!(values_map.value_bytes > 0);
+
+        assert_eq!(store.compact().unwrap(), CompactionStatus::NotSupported);
+    })
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn tx_index(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let store = TestStore::new_empty().unwrap();
+
+        let tx_ids: Vec<Id<Transaction>> =
+            (0..3).map(|_| Id::new(H256::random_using(&mut rng))).collect();
+        let block_id = Id::<Block>::new(H256::random_using(&mut rng));
+
+        assert_eq!(
+            store.transaction_ro().unwrap().get_tx_index_enabled().unwrap(),
+            None
+        );
+
+        let mut db_tx = store.transaction_rw(None).unwrap();
+        db_tx.set_tx_index_enabled(true).unwrap();
+        for tx_id in &tx_ids {
+            db_tx.set_tx_block_id(tx_id, &block_id).unwrap();
+        }
+        db_tx.del_tx_block_id(&tx_ids[0]).unwrap();
+        db_tx.commit().unwrap();
+
+        let db_tx = store.transaction_ro().unwrap();
+        assert_eq!(db_tx.get_tx_index_enabled().unwrap(), Some(true));
+        assert_eq!(db_tx.get_tx_block_id(&tx_ids[0]).unwrap(), None);
+        assert_eq!(db_tx.get_tx_block_id(&tx_ids[1]).unwrap(), Some(block_id));
+        assert_eq!(db_tx.get_tx_block_id(&tx_ids[2]).unwrap(), Some(block_id));
+        drop(db_tx);
+
+        let mut db_tx = store.transaction_rw(None).unwrap();
+        db_tx.clear_tx_index().unwrap();
+        db_tx.commit().unwrap();
+
+        let db_tx = store.transaction_ro().unwrap();
+        assert!(tx_ids.iter().all(|tx_id| db_tx.get_tx_block_id(tx_id).unwrap().is_none()));
+    })
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn pending_operation(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let store = TestStore::new_empty().unwrap();
+
+        let operation = PendingOperation::BlockInvalidation {
+            block_id: Id::new(H256::random_using(&mut rng)),
+            tip_before: Id::new(H256::random_using(&mut rng)),
+            is_explicit: rng.gen(),
+        };
+
+        assert_eq!(
+            store.transaction_ro().unwrap().get_pending_operation().unwrap(),
+            None
+        );
+
+        let mut db_tx = store.transaction_rw(None).unwrap();
+        db_tx.set_pending_operation(&operation).unwrap();
+        db_tx.commit().unwrap();
+        assert_eq!(
+            store.transaction_ro().unwrap().get_pending_operation().unwrap(),
+            Some(operation)
+        );
+
+        let mut db_tx = store.transaction_rw(None).unwrap();
+        db_tx.del_pending_operation().unwrap();
+        db_tx.commit().unwrap();
+        assert_eq!(
+            store.transaction_ro().unwrap().get_pending_operation().unwrap(),
+            None
+        );
+    })
+}
+
 fn assert_block_exists<DbTx: BlockchainStorageRead>(db_tx: &DbTx, block: &Block) {
     assert_eq!(
         db_tx.get_block(block.get_id()).unwrap().as_ref(),
@@ -24,7 +24,7 @@ pub mod schema;
 use std::collections::{BTreeMap, BTreeSet};
 
 use chainstate_types::{
-    BlockIndex, EpochStorageRead, EpochStorageWrite, SealedStorageTag, TipStorageTag,
+    BlockIndex, BlockStats, EpochStorageRead, EpochStorageWrite, SealedStorageTag, TipStorageTag,
 };
 use common::{
     chain::{
@@ -40,15 +40,16 @@ use orders_accounting::{
     OrdersAccountingStorageRead, OrdersAccountingStorageWrite, OrdersAccountingUndo,
 };
 use pos_accounting::{
-    DeltaMergeUndo, PoSAccountingDeltaData, PoSAccountingStorageRead, PoSAccountingStorageWrite,
-    PoSAccountingUndo,
+    DeltaMergeUndo, PoSAccountingData, PoSAccountingDeltaData, PoSAccountingStorageRead,
+    PoSAccountingStorageWrite, PoSAccountingUndo,
 };
 use tokens_accounting::{
     TokenAccountingUndo, TokensAccountingStorageRead, TokensAccountingStorageWrite,
 };
 use utxo::{UtxosBlockUndo, UtxosStorageRead, UtxosStorageWrite};
 
-pub use internal::{ChainstateStorageVersion, Store};
+pub use internal::{ChainstateStorageVersion, PendingOperation, Store};
+pub use storage::{CompactionOutcome, CompactionReport, CompactionStatus};
 
 /// Possibly failing result of blockchain storage query
 pub type Result<T> = chainstate_types::storage_result::Result<T>;
@@ -111,6 +112,18 @@ pub trait BlockchainStorageRead:
     /// Get token id by id of the creation tx
     fn get_token_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<TokenId>>;
 
+    /// Whether the transaction index has been built and is being maintained
+    fn get_tx_index_enabled(&self) -> crate::Result<Option<bool>>;
+
+    /// Get the id of the mainchain block containing the transaction from the transaction index
+    fn get_tx_block_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<Id<Block>>>;
+
+    /// Get the fee and fullness statistics of a recent mainchain block
+    fn get_block_stats(&self, block_id: &Id<Block>) -> crate::Result<Option<BlockStats>>;
+
+    /// Get the multi-transaction operation that has been started but not completed yet
+    fn get_pending_operation(&self) -> crate::Result<Option<PendingOperation>>;
+
     /// Get block tree as height vs ids
     fn get_block_tree_by_height(
         &self,
@@ -159,6 +172,11 @@ pub trait BlockchainStorageRead:
     /// Get the entire mainchain-block-by-height map as BTreeMap. This is used in the chainstate's
     /// "heavy" consistency checks.
     fn get_block_by_height_map(&self) -> crate::Result<BTreeMap<BlockHeight, Id<GenBlock>>>;
+
+    /// Get the entire tip PoS accounting data. This is used to export the accounting state.
+    fn get_pos_accounting_data_tip(&self) -> crate::Result<PoSAccountingData>;
+    /// Get the entire sealed PoS accounting data. This is used to export the accounting state.
+    fn get_pos_accounting_data_sealed(&self) -> crate::Result<PoSAccountingData>;
 }
 
 /// Modifying operations on persistent blockchain data
@@ -223,6 +241,30 @@ pub trait BlockchainStorageWrite:
     /// Remove token id
     fn del_token_id(&mut self, issuance_tx_id: &Id<Transaction>) -> Result<()>;
 
+    /// Set whether the transaction index has been built and is being maintained
+    fn set_tx_index_enabled(&mut self, enabled: bool) -> Result<()>;
+
+    /// Add a transaction to the transaction index
+    fn set_tx_block_id(&mut self, tx_id: &Id<Transaction>, block_id: &Id<Block>) -> Result<()>;
+
+    /// Remove a transaction from the transaction index
+    fn del_tx_block_id(&mut self, tx_id: &Id<Transaction>) -> Result<()>;
+
+    /// Remove all the transactions from the transaction index
+    fn clear_tx_index(&mut self) -> Result<()>;
+
+    /// Set the fee and fullness statistics of a mainchain block
+    fn set_block_stats(&mut self, block_id: &Id<Block>, stats: &BlockStats) -> Result<()>;
+
+    /// Remove the statistics of a block
+    fn del_block_stats(&mut self, block_id: &Id<Block>) -> Result<()>;
+
+    /// Record a multi-transaction operation that is being started
+    fn set_pending_operation(&mut self, operation: &PendingOperation) -> Result<()>;
+
+    /// Remove the record of a multi-transaction operation once it has been completed
+    fn del_pending_operation(&mut self) -> Result<()>;
+
     /// Set tokens accounting undo data for specific block
     fn set_tokens_accounting_undo_data(
         &mut self,
@@ -310,4 +352,34 @@ pub trait Transactional<'t> {
     fn transaction_rw<'s: 't>(&'s self, size: Option<usize>) -> Result<Self::TransactionRw>;
 }
 
-pub trait BlockchainStorage: for<'tx> Transactional<'tx> + Send {}
+/// Amount of data stored in a single key-value map of the storage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapSizeInfo {
+    pub name: String,
+    pub entry_count: u64,
+    pub key_bytes: u64,
+    pub value_bytes: u64,
+}
+
+/// Size of the storage on disk and the amount of data in each of its maps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageSizeInfo {
+    /// Size of the database files, `None` if the storage is not kept on disk
+    pub disk_size: Option<u64>,
+    pub maps: Vec<MapSizeInfo>,
+    /// Whether the compaction is going to happen on the next start
+    pub compaction_scheduled: bool,
+    /// The progress and the outcome of the last compaction, if any
+    pub last_compaction: Option<CompactionReport>,
+}
+
+/// Maintenance of the underlying database
+pub trait StorageMaintenance {
+    /// Collect the size of the database files and the amount of data stored in each map
+    fn size_info(&self) -> Result<StorageSizeInfo>;
+
+    /// Reclaim the space occupied by deleted data
+    fn compact(&self) -> Result<CompactionStatus>;
+}
+
+pub trait BlockchainStorage: for<'tx> Transactional<'tx> + StorageMaintenance + Send {}
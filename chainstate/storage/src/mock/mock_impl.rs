@@ -17,7 +17,7 @@
 
 use std::collections::{BTreeMap, BTreeSet};
 
-use chainstate_types::{BlockIndex, EpochData, EpochStorageRead, EpochStorageWrite};
+use chainstate_types::{BlockIndex, BlockStats, EpochData, EpochStorageRead, EpochStorageWrite};
 use common::{
     chain::{
         block::{signed_block_header::SignedBlockHeader, BlockReward},
@@ -33,7 +33,8 @@ use orders_accounting::{
     OrdersAccountingStorageRead, OrdersAccountingStorageWrite, OrdersAccountingUndo,
 };
 use pos_accounting::{
-    DelegationData, DeltaMergeUndo, PoSAccountingDeltaData, PoSAccountingUndo, PoolData,
+    DelegationData, DeltaMergeUndo, PoSAccountingData, PoSAccountingDeltaData, PoSAccountingUndo,
+    PoolData,
 };
 use tokens_accounting::{
     TokenAccountingUndo, TokensAccountingStorageRead, TokensAccountingStorageWrite,
@@ -45,7 +46,7 @@ use super::mock_impl_accounting::{
     PoSAccountingStorageWriteTip,
 };
 
-use crate::ChainstateStorageVersion;
+use crate::{ChainstateStorageVersion, PendingOperation};
 
 mockall::mock! {
     /// A mock object for blockchain storage
@@ -74,6 +75,10 @@ mockall::mock! {
         fn get_token_aux_data(&self, token_id: &TokenId) -> crate::Result<Option<TokenAuxiliaryData>>;
 
         fn get_token_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<TokenId>>;
+        fn get_tx_index_enabled(&self) -> crate::Result<Option<bool>>;
+        fn get_tx_block_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<Id<Block>>>;
+        fn get_block_stats(&self, block_id: &Id<Block>) -> crate::Result<Option<BlockStats>>;
+        fn get_pending_operation(&self) -> crate::Result<Option<PendingOperation>>;
 
         fn get_tokens_accounting_undo(
             &self,
@@ -107,6 +112,9 @@ mockall::mock! {
         fn get_block_map_keys(&self) -> crate::Result<BTreeSet<Id<Block>>>;
         fn get_block_index_map(&self) -> crate::Result<BTreeMap<Id<Block>, BlockIndex>>;
         fn get_block_by_height_map(&self) -> crate::Result<BTreeMap<BlockHeight, Id<GenBlock>>>;
+
+        fn get_pos_accounting_data_tip(&self) -> crate::Result<PoSAccountingData>;
+        fn get_pos_accounting_data_sealed(&self) -> crate::Result<PoSAccountingData>;
     }
 
     impl EpochStorageRead for Store {
@@ -203,6 +211,14 @@ mockall::mock! {
         fn del_token_aux_data(&mut self, token_id: &TokenId) -> crate::Result<()>;
         fn set_token_id(&mut self, issuance_tx_id: &Id<Transaction>, token_id: &TokenId) -> crate::Result<()>;
         fn del_token_id(&mut self, issuance_tx_id: &Id<Transaction>) -> crate::Result<()>;
+        fn set_tx_index_enabled(&mut self, enabled: bool) -> crate::Result<()>;
+        fn set_tx_block_id(&mut self, tx_id: &Id<Transaction>, block_id: &Id<Block>) -> crate::Result<()>;
+        fn del_tx_block_id(&mut self, tx_id: &Id<Transaction>) -> crate::Result<()>;
+        fn clear_tx_index(&mut self) -> crate::Result<()>;
+        fn set_block_stats(&mut self, block_id: &Id<Block>, stats: &BlockStats) -> crate::Result<()>;
+        fn del_block_stats(&mut self, block_id: &Id<Block>) -> crate::Result<()>;
+        fn set_pending_operation(&mut self, operation: &PendingOperation) -> crate::Result<()>;
+        fn del_pending_operation(&mut self) -> crate::Result<()>;
 
         fn set_tokens_accounting_undo_data(
             &mut self,
@@ -349,6 +365,11 @@ mockall::mock! {
         fn transaction_rw<'st>(&'st self, size: Option<usize>) -> crate::Result<MockStoreTxRw> where 'st: 'tx;
     }
 
+    impl crate::StorageMaintenance for Store {
+        fn size_info(&self) -> crate::Result<crate::StorageSizeInfo>;
+        fn compact(&self) -> crate::Result<crate::CompactionStatus>;
+    }
+
     impl crate::BlockchainStorage for Store {}
 }
 
@@ -378,6 +399,10 @@ mockall::mock! {
 
         fn get_token_aux_data(&self, token_id: &TokenId) -> crate::Result<Option<TokenAuxiliaryData>>;
         fn get_token_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<TokenId>>;
+        fn get_tx_index_enabled(&self) -> crate::Result<Option<bool>>;
+        fn get_tx_block_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<Id<Block>>>;
+        fn get_block_stats(&self, block_id: &Id<Block>) -> crate::Result<Option<BlockStats>>;
+        fn get_pending_operation(&self) -> crate::Result<Option<PendingOperation>>;
         fn get_block_tree_by_height(
             &self,
             start_from: BlockHeight,
@@ -404,6 +429,9 @@ mockall::mock! {
         fn get_block_map_keys(&self) -> crate::Result<BTreeSet<Id<Block>>>;
         fn get_block_index_map(&self) -> crate::Result<BTreeMap<Id<Block>, BlockIndex>>;
         fn get_block_by_height_map(&self) -> crate::Result<BTreeMap<BlockHeight, Id<GenBlock>>>;
+
+        fn get_pos_accounting_data_tip(&self) -> crate::Result<PoSAccountingData>;
+        fn get_pos_accounting_data_sealed(&self) -> crate::Result<PoSAccountingData>;
     }
 
     impl EpochStorageRead for StoreTxRo {
@@ -505,6 +533,10 @@ mockall::mock! {
 
         fn get_token_aux_data(&self, token_id: &TokenId) -> crate::Result<Option<TokenAuxiliaryData>>;
         fn get_token_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<TokenId>>;
+        fn get_tx_index_enabled(&self) -> crate::Result<Option<bool>>;
+        fn get_tx_block_id(&self, tx_id: &Id<Transaction>) -> crate::Result<Option<Id<Block>>>;
+        fn get_block_stats(&self, block_id: &Id<Block>) -> crate::Result<Option<BlockStats>>;
+        fn get_pending_operation(&self) -> crate::Result<Option<PendingOperation>>;
         fn get_tokens_accounting_undo(&self, id: Id<Block>) -> crate::Result<Option<accounting::BlockUndo<TokenAccountingUndo>>>;
         fn get_block_tree_by_height(
             &self,
@@ -530,6 +562,9 @@ mockall::mock! {
         fn get_block_map_keys(&self) -> crate::Result<BTreeSet<Id<Block>>>;
         fn get_block_index_map(&self) -> crate::Result<BTreeMap<Id<Block>, BlockIndex>>;
         fn get_block_by_height_map(&self) -> crate::Result<BTreeMap<BlockHeight, Id<GenBlock>>>;
+
+        fn get_pos_accounting_data_tip(&self) -> crate::Result<PoSAccountingData>;
+        fn get_pos_accounting_data_sealed(&self) -> crate::Result<PoSAccountingData>;
     }
 
     impl EpochStorageRead for StoreTxRw {
@@ -626,6 +661,14 @@ mockall::mock! {
 
         fn set_token_id(&mut self, issuance_tx_id: &Id<Transaction>, token_id: &TokenId) -> crate::Result<()>;
         fn del_token_id(&mut self, issuance_tx_id: &Id<Transaction>) -> crate::Result<()>;
+        fn set_tx_index_enabled(&mut self, enabled: bool) -> crate::Result<()>;
+        fn set_tx_block_id(&mut self, tx_id: &Id<Transaction>, block_id: &Id<Block>) -> crate::Result<()>;
+        fn del_tx_block_id(&mut self, tx_id: &Id<Transaction>) -> crate::Result<()>;
+        fn clear_tx_index(&mut self) -> crate::Result<()>;
+        fn set_block_stats(&mut self, block_id: &Id<Block>, stats: &BlockStats) -> crate::Result<()>;
+        fn del_block_stats(&mut self, block_id: &Id<Block>) -> crate::Result<()>;
+        fn set_pending_operation(&mut self, operation: &PendingOperation) -> crate::Result<()>;
+        fn del_pending_operation(&mut self) -> crate::Result<()>;
 
         fn set_tokens_accounting_undo_data(
             &mut self,
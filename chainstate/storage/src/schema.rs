@@ -15,7 +15,7 @@
 
 //! Chainstate database schema
 
-use chainstate_types::{BlockIndex, EpochData};
+use chainstate_types::{BlockIndex, BlockStats, EpochData};
 use common::{
     chain::{
         config::EpochIndex,
@@ -53,6 +53,11 @@ storage::decl_schema! {
         pub DBTokensAuxData: Map<TokenId, TokenAuxiliaryData>,
         /// Store of issuance tx id vs token id
         pub DBIssuanceTxVsTokenId: Map<Id<Transaction>, TokenId>,
+        /// Store of the mainchain block containing the transaction; only populated
+        /// if the transaction index is enabled
+        pub DBTxIndex: Map<Id<Transaction>, Id<Block>>,
+        /// Store of the fee and fullness statistics of the most recent mainchain blocks
+        pub DBBlockStats: Map<Id<Block>, BlockStats>,
         /// Store the number of transactions per account
         pub DBAccountNonceCount: Map<AccountType, AccountNonce>,
 
@@ -0,0 +1,162 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::TestFramework;
+use chainstate::{BlockError, BlockSource, ChainstateError, OrphanCheckError};
+use common::{
+    chain::{Block, GenBlock},
+    primitives::{Id, Idable},
+};
+use randomness::{Rng, SliceRandom};
+
+/// The order in which the blocks are delivered to the chainstate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryOrder {
+    /// The blocks are delivered in the given order
+    InOrder,
+    /// The blocks are delivered in the reversed order, so for a chain every block except
+    /// the first one arrives before its parent
+    Reversed,
+    /// The blocks are delivered in a random order
+    Shuffled,
+}
+
+/// The builder that delivers a prepared set of blocks to the chainstate out of order,
+/// simulating the blocks arriving from the network.
+///
+/// The blocks from a local source are passed to the chainstate as they arrive, so the ones
+/// with an unknown parent end up in its orphan pool. The chainstate doesn't accept orphans
+/// from peers, so such blocks are buffered by the builder until their parent is processed,
+/// and each block goes through the preliminary checks that are done for the blocks received
+/// from peers.
+#[must_use]
+pub struct BlockDeliveryBuilder<'f> {
+    framework: &'f mut TestFramework,
+    blocks: Vec<Block>,
+    order: DeliveryOrder,
+    block_source: BlockSource,
+    expected_tip: Option<Id<GenBlock>>,
+}
+
+impl<'f> BlockDeliveryBuilder<'f> {
+    /// Creates a new builder instance that delivers the blocks in a random order
+    /// from a local source.
+    pub fn new(framework: &'f mut TestFramework, blocks: Vec<Block>) -> Self {
+        Self {
+            framework,
+            blocks,
+            order: DeliveryOrder::Shuffled,
+            block_source: BlockSource::Local,
+            expected_tip: None,
+        }
+    }
+
+    pub fn with_order(mut self, order: DeliveryOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn with_block_source(mut self, block_source: BlockSource) -> Self {
+        self.block_source = block_source;
+        self
+    }
+
+    /// The best block is checked to be the given one after all the blocks are delivered.
+    pub fn with_expected_tip(mut self, expected_tip: Id<GenBlock>) -> Self {
+        self.expected_tip = Some(expected_tip);
+        self
+    }
+
+    /// Delivers the blocks and returns the id of the resulting best block.
+    ///
+    /// Fails on the first block that is rejected for a reason other than being an orphan.
+    /// The blocks whose parent never arrives are left unprocessed.
+    pub fn deliver(self, rng: &mut impl Rng) -> Result<Id<GenBlock>, ChainstateError> {
+        let Self {
+            framework,
+            mut blocks,
+            order,
+            block_source,
+            expected_tip,
+        } = self;
+
+        match order {
+            DeliveryOrder::InOrder => {}
+            DeliveryOrder::Reversed => blocks.reverse(),
+            DeliveryOrder::Shuffled => blocks.shuffle(rng),
+        }
+
+        match block_source {
+            BlockSource::Local => deliver_from_local_source(framework, blocks)?,
+            BlockSource::Peer => deliver_from_peer(framework, blocks)?,
+        }
+
+        let best_block_id = framework.best_block_id();
+        if let Some(expected_tip) = expected_tip {
+            assert_eq!(best_block_id, expected_tip);
+        }
+
+        Ok(best_block_id)
+    }
+}
+
+fn deliver_from_local_source(
+    framework: &mut TestFramework,
+    blocks: Vec<Block>,
+) -> Result<(), ChainstateError> {
+    for block in blocks {
+        match framework.process_block(block, BlockSource::Local) {
+            Ok(_)
+            | Err(ChainstateError::ProcessBlockError(BlockError::OrphanCheckFailed(
+                OrphanCheckError::LocalOrphan,
+            ))) => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(())
+}
+
+fn deliver_from_peer(
+    framework: &mut TestFramework,
+    blocks: Vec<Block>,
+) -> Result<(), ChainstateError> {
+    // The blocks waiting for their parent, by the parent id
+    let mut orphans = BTreeMap::<Id<GenBlock>, Vec<Block>>::new();
+
+    for block in blocks {
+        if !framework.block_index_exists(&block.prev_block_id()) {
+            orphans.entry(block.prev_block_id()).or_default().push(block);
+            continue;
+        }
+
+        let mut process_queue = VecDeque::from([block]);
+        while let Some(block) = process_queue.pop_front() {
+            let block_id: Id<GenBlock> = block.get_id().into();
+
+            framework
+                .chainstate
+                .preliminary_headers_check(std::slice::from_ref(block.header()))?;
+            let block = framework.chainstate.preliminary_block_check(block)?;
+            framework.process_block(block, BlockSource::Peer)?;
+
+            process_queue.extend(orphans.remove(&block_id).unwrap_or_default());
+        }
+    }
+
+    Ok(())
+}
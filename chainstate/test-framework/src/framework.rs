@@ -30,7 +30,7 @@ use crate::{
         assert_gen_block_index_opt_identical_to, find_create_pool_tx_in_genesis,
         outputs_from_block, outputs_from_genesis,
     },
-    BlockBuilder, TestChainstate, TestFrameworkBuilder, TestStore,
+    BlockBuilder, BlockDeliveryBuilder, TestChainstate, TestFrameworkBuilder, TestStore,
 };
 use chainstate::{chainstate_interface::ChainstateInterface, BlockSource, ChainstateError};
 use chainstate_types::{BlockIndex, BlockStatus, GenBlockIndex};
@@ -88,6 +88,12 @@ impl TestFramework {
         BlockBuilder::new(self)
     }
 
+    /// Returns a builder that delivers the given prepared blocks out of order,
+    /// simulating the blocks arriving from the network.
+    pub fn make_block_delivery(&mut self, blocks: Vec<Block>) -> BlockDeliveryBuilder {
+        BlockDeliveryBuilder::new(self, blocks)
+    }
+
     pub fn make_pos_block_builder(&mut self) -> PoSBlockBuilder {
         PoSBlockBuilder::new(self)
     }
@@ -16,6 +16,7 @@
 #![allow(clippy::unwrap_used)]
 
 mod block_builder;
+mod block_delivery;
 mod framework;
 mod framework_builder;
 mod key_manager;
@@ -43,6 +44,7 @@ pub use {
         output_value_amount, pos_mine, produce_kernel_signature,
     },
     block_builder::BlockBuilder,
+    block_delivery::{BlockDeliveryBuilder, DeliveryOrder},
     framework::TestFramework,
     framework_builder::{OrphanErrorHandler, TestFrameworkBuilder, TxVerificationStrategy},
     pos_block_builder::PoSBlockBuilder,
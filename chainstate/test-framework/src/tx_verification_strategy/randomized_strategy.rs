@@ -19,7 +19,7 @@ use chainstate::{
 use chainstate_types::BlockIndex;
 use common::{
     chain::{block::timestamp::BlockTimestamp, Block, ChainConfig},
-    primitives::{id::WithId, Idable},
+    primitives::{id::WithId, Fee, Idable},
 };
 use constraints_value_accumulator::AccumulatedFee;
 use orders_accounting::OrdersAccountingView;
@@ -73,7 +73,7 @@ impl TransactionVerificationStrategy for RandomizedTransactionVerificationStrate
         block_index: &BlockIndex,
         block: &WithId<Block>,
         median_time_past: BlockTimestamp,
-    ) -> Result<TransactionVerifier<C, S, U, A, T, O>, ConnectTransactionError>
+    ) -> Result<(TransactionVerifier<C, S, U, A, T, O>, Vec<Fee>), ConnectTransactionError>
     where
         C: AsRef<ChainConfig> + ShallowClone,
         S: TransactionVerifierStorageRef<Error = TransactionVerifierStorageError>,
@@ -84,7 +84,7 @@ impl TransactionVerificationStrategy for RandomizedTransactionVerificationStrate
         M: TransactionVerifierMakerFn<C, S, U, A, T, O>,
         <S as utxo::UtxosStorageRead>::Error: From<U::Error>,
     {
-        let mut tx_verifier = self
+        let (mut tx_verifier, tx_fees) = self
             .connect_with_base(
                 tx_verifier_maker,
                 storage_backend,
@@ -97,7 +97,7 @@ impl TransactionVerificationStrategy for RandomizedTransactionVerificationStrate
 
         tx_verifier.set_best_block(block.get_id().into());
 
-        Ok(tx_verifier)
+        Ok((tx_verifier, tx_fees))
     }
 
     fn disconnect_block<C, S, M, U, A, T, O>(
@@ -136,7 +136,7 @@ impl RandomizedTransactionVerificationStrategy {
         block_index: &BlockIndex,
         block: &WithId<Block>,
         median_time_past: &BlockTimestamp,
-    ) -> Result<TransactionVerifier<C, S, U, A, T, O>, ConnectTransactionError>
+    ) -> Result<(TransactionVerifier<C, S, U, A, T, O>, Vec<Fee>), ConnectTransactionError>
     where
         C: AsRef<ChainConfig> + ShallowClone,
         S: TransactionVerifierStorageRef<Error = TransactionVerifierStorageError>,
@@ -149,12 +149,12 @@ impl RandomizedTransactionVerificationStrategy {
     {
         let mut tx_verifier = tx_verifier_maker(storage_backend, chain_config.shallow_clone());
 
-        let mut total_fees = AccumulatedFee::new();
+        let mut fees = Vec::with_capacity(block.transactions().len());
         let mut tx_num = 0usize;
         while tx_num < block.transactions().len() {
             if self.rng.lock().unwrap().gen::<bool>() {
                 // derive a new cache
-                let (consumed_cache, fee, new_tx_index) = self.connect_with_derived(
+                let (consumed_cache, derived_fees, new_tx_index) = self.connect_with_derived(
                     &tx_verifier,
                     block,
                     block_index,
@@ -162,9 +162,7 @@ impl RandomizedTransactionVerificationStrategy {
                     tx_num,
                 )?;
 
-                total_fees = total_fees.combine(fee).map_err(|_| {
-                    ConnectTransactionError::FailedToAddAllFeesOfBlock(block.get_id())
-                })?;
+                fees.extend(derived_fees);
 
                 flush_to_storage(&mut tx_verifier, consumed_cache)
                     .map_err(ConnectTransactionError::from)?;
@@ -180,22 +178,33 @@ impl RandomizedTransactionVerificationStrategy {
                     median_time_past,
                 )?;
 
-                total_fees = total_fees.combine(fee).map_err(|_| {
-                    ConnectTransactionError::FailedToAddAllFeesOfBlock(block.get_id())
-                })?;
+                fees.push(fee);
 
                 tx_num += 1;
             }
         }
 
-        let total_fees = total_fees
-            .map_into_block_fees(chain_config.as_ref(), block_index.block_height())
-            .map_err(|err| {
-                ConnectTransactionError::ConstrainedValueAccumulatorError(
-                    err,
-                    block.get_id().into(),
-                )
-            })?;
+        let map_into_block_fees = |fee: AccumulatedFee| {
+            fee.map_into_block_fees(chain_config.as_ref(), block_index.block_height())
+                .map_err(|err| {
+                    ConnectTransactionError::ConstrainedValueAccumulatorError(
+                        err,
+                        block.get_id().into(),
+                    )
+                })
+        };
+
+        let tx_fees = fees
+            .iter()
+            .map(|fee| map_into_block_fees(fee.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let total_fees = fees.into_iter().try_fold(AccumulatedFee::new(), |total, fee| {
+            total
+                .combine(fee)
+                .map_err(|_| ConnectTransactionError::FailedToAddAllFeesOfBlock(block.get_id()))
+        })?;
+        let total_fees = map_into_block_fees(total_fees)?;
 
         tx_verifier
             .check_block_reward(block, total_fees, block_index.block_height())
@@ -210,7 +219,7 @@ impl RandomizedTransactionVerificationStrategy {
             )
             .log_err()?;
 
-        Ok(tx_verifier)
+        Ok((tx_verifier, tx_fees))
     }
 
     fn connect_with_derived<C, S, U, A, T, O>(
@@ -220,7 +229,7 @@ impl RandomizedTransactionVerificationStrategy {
         block_index: &BlockIndex,
         median_time_past: &BlockTimestamp,
         mut tx_num: usize,
-    ) -> Result<(TransactionVerifierDelta, AccumulatedFee, usize), ConnectTransactionError>
+    ) -> Result<(TransactionVerifierDelta, Vec<AccumulatedFee>, usize), ConnectTransactionError>
     where
         C: AsRef<ChainConfig>,
         U: UtxosView,
@@ -231,7 +240,7 @@ impl RandomizedTransactionVerificationStrategy {
         <S as utxo::UtxosStorageRead>::Error: From<U::Error>,
     {
         let mut tx_verifier = base_tx_verifier.derive_child();
-        let mut total_fees = AccumulatedFee::new();
+        let mut fees = Vec::new();
         while tx_num < block.transactions().len() {
             if self.rng.lock().unwrap().gen::<bool>() {
                 // break the loop, which effectively would flush current state to the parent
@@ -246,14 +255,12 @@ impl RandomizedTransactionVerificationStrategy {
                     median_time_past,
                 )?;
 
-                total_fees = total_fees.combine(fee).map_err(|_| {
-                    ConnectTransactionError::FailedToAddAllFeesOfBlock(block.get_id())
-                })?;
+                fees.push(fee);
                 tx_num += 1;
             }
         }
         let cache = tx_verifier.consume()?;
-        Ok((cache, total_fees, tx_num))
+        Ok((cache, fees, tx_num))
     }
 
     fn disconnect_with_base<C, S, M, U, A, T, O>(
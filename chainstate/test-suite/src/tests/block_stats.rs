@@ -0,0 +1,89 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use chainstate_storage::{BlockchainStorageRead, Transactional};
+use chainstate_test_framework::{anyonecanspend_address, empty_witness, TransactionBuilder};
+use common::{
+    chain::{output_value::OutputValue, TxInput, TxOutput},
+    primitives::{Amount, Idable},
+};
+use serialization::Encode;
+
+// The statistics are collected for every connected block and are removed when the block
+// is reorged out.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn stats_and_reorg(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+        let genesis_id = tf.genesis().get_id();
+
+        let genesis_amount = chainstate_test_framework::get_output_value(&tf.genesis().utxos()[0])
+            .unwrap()
+            .coin_amount()
+            .unwrap();
+        let fee = Amount::from_atoms(rng.gen_range(1..100_000));
+        let tx = TransactionBuilder::new()
+            .add_input(
+                TxInput::from_utxo(genesis_id.into(), 0),
+                empty_witness(&mut rng),
+            )
+            .add_output(TxOutput::Transfer(
+                OutputValue::Coin((genesis_amount - fee).unwrap()),
+                anyonecanspend_address(),
+            ))
+            .build();
+        let tx_size = tx.encoded_size();
+
+        let block = tf.make_block_builder().add_transaction(tx).build(&mut rng);
+        let block_id = block.get_id();
+        tf.process_block(block, BlockSource::Local).unwrap();
+        let tip_id = tf.create_chain(&block_id.into(), 2, &mut rng).unwrap();
+
+        let stats = tf.chainstate.get_recent_block_stats(10).unwrap();
+        assert_eq!(stats.len(), 3);
+        assert_eq!(Id::<GenBlock>::from(*stats[0].0.block_id()), tip_id);
+        // The blocks made by `create_chain` contain a single test transaction
+        assert_eq!(stats[0].1.tx_count(), 1);
+
+        let (block_index, block_stats) = &stats[2];
+        assert_eq!(*block_index.block_id(), block_id);
+        assert_eq!(block_stats.tx_count(), 1);
+        assert_eq!(block_stats.total_fees(), fee);
+        assert_eq!(block_stats.txs_size(), tx_size as u64);
+        assert_eq!(
+            block_stats.median_fee_rate(),
+            Some(Amount::from_atoms(
+                fee.into_atoms() * 1000 / tx_size as u128
+            ))
+        );
+
+        assert_eq!(tf.chainstate.get_recent_block_stats(2).unwrap().len(), 2);
+        assert!(tf.chainstate.get_recent_block_stats(0).unwrap().is_empty());
+
+        let new_tip_id = tf.create_chain(&genesis_id.into(), 4, &mut rng).unwrap();
+        assert_eq!(tf.best_block_id(), new_tip_id);
+
+        let stats = tf.chainstate.get_recent_block_stats(10).unwrap();
+        assert_eq!(stats.len(), 4);
+        assert!(stats.iter().all(|(_, stats)| stats.tx_count() == 1));
+
+        let db_tx = tf.storage.transaction_ro().unwrap();
+        assert_eq!(db_tx.get_block_stats(&block_id).unwrap(), None);
+    });
+}
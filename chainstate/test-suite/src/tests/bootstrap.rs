@@ -262,5 +262,32 @@ fn bootstrap_tests(#[case] seed: Seed) {
                 tf1.chainstate.get_block_id_tree_as_list().unwrap(),
             );
         }
+
+        // test exporting to and importing from a file
+        {
+            let dir = tempfile::TempDir::new().unwrap();
+            let file_path = dir.path().join("bootstrap.bin");
+
+            tf1.chainstate.export_bootstrap_file(&file_path, true).unwrap();
+            assert_eq!(std::fs::read(&file_path).unwrap(), bootstrap_with_orphans);
+
+            let mut tf6 = TestFramework::builder(&mut rng)
+                .with_chainstate_config(
+                    ChainstateConfig::new()
+                        .with_max_orphan_blocks(0)
+                        .with_bootstrap_buffer_sizes((largest_block_size, 2 * largest_block_size)),
+                )
+                .build();
+
+            tf6.chainstate.import_bootstrap_file(&file_path).unwrap();
+
+            assert_eq!(
+                tf6.chainstate.get_block_id_tree_as_list().unwrap(),
+                tf1.chainstate.get_block_id_tree_as_list().unwrap(),
+            );
+
+            // a missing file is reported as an error
+            assert!(tf6.chainstate.import_bootstrap_file(&dir.path().join("missing.bin")).is_err());
+        }
     });
 }
@@ -1760,3 +1760,116 @@ fn delegate_same_pool_as_staking(#[case] seed: Seed) {
     )
     .unwrap();
 }
+
+// Generate blocks with a pool that has a delegation and check that the reported reward
+// distribution matches the block subsidy and the delegation balance.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn block_reward_distribution(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let (vrf_sk, vrf_pk) = VRFPrivateKey::new_from_rng(&mut rng, VRFKeyKind::Schnorrkel);
+    let (staker_sk, staker_pk) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+
+    let genesis_pool_id = PoolId::new(H256::random_using(&mut rng));
+    let stake_pool_pledge = create_unit_test_config().min_stake_pool_pledge();
+    let stake_pool_data = StakePoolData::new(
+        stake_pool_pledge,
+        Destination::PublicKey(staker_pk),
+        vrf_pk,
+        Destination::AnyoneCanSpend,
+        PerThousand::new(rng.gen_range(0..=500)).unwrap(),
+        Amount::ZERO,
+    );
+
+    let mint_amount = stake_pool_pledge;
+    let chain_config = chainstate_test_framework::create_chain_config_with_staking_pool(
+        &mut rng,
+        mint_amount,
+        genesis_pool_id,
+        stake_pool_data,
+    )
+    .build();
+
+    let target_block_time = chain_config.target_block_spacing();
+
+    let mut tf = TestFramework::builder(&mut rng)
+        .with_chain_config(chain_config.clone())
+        .with_initial_time_since_genesis(target_block_time.as_secs())
+        .build();
+
+    let genesis_mint_outpoint = UtxoOutPoint::new(tf.chain_config().genesis_block_id().into(), 0);
+    let create_delegation_tx = TransactionBuilder::new()
+        .add_input(
+            TxInput::Utxo(genesis_mint_outpoint.clone()),
+            empty_witness(&mut rng),
+        )
+        .add_output(TxOutput::CreateDelegationId(
+            Destination::AnyoneCanSpend,
+            genesis_pool_id,
+        ))
+        .add_output(TxOutput::Transfer(
+            OutputValue::Coin(mint_amount),
+            Destination::AnyoneCanSpend,
+        ))
+        .build();
+    let create_delegation_tx_id = create_delegation_tx.transaction().get_id();
+    let delegation_id = pos_accounting::make_delegation_id(&genesis_mint_outpoint);
+
+    let delegate_staking_tx = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(create_delegation_tx_id.into(), 1),
+            empty_witness(&mut rng),
+        )
+        .add_output(TxOutput::DelegateStaking(mint_amount, delegation_id))
+        .build();
+
+    tf.make_pos_block_builder()
+        .with_stake_pool_id(genesis_pool_id)
+        .with_stake_spending_key(staker_sk.clone())
+        .with_vrf_key(vrf_sk.clone())
+        .with_transactions(vec![create_delegation_tx, delegate_staking_tx])
+        .build_and_process(&mut rng)
+        .unwrap();
+
+    let delegation_balance_before =
+        tf.chainstate.get_stake_delegation_balance(delegation_id).unwrap().unwrap();
+
+    let block_index = tf
+        .make_pos_block_builder()
+        .with_stake_pool_id(genesis_pool_id)
+        .with_stake_spending_key(staker_sk.clone())
+        .with_vrf_key(vrf_sk.clone())
+        .build_and_process(&mut rng)
+        .unwrap()
+        .unwrap();
+
+    let delegation_balance_after =
+        tf.chainstate.get_stake_delegation_balance(delegation_id).unwrap().unwrap();
+    let delegation_reward = (delegation_balance_after - delegation_balance_before).unwrap();
+
+    let distribution = tf
+        .chainstate
+        .get_block_reward_distribution(block_index.block_id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(distribution.pool_id(), genesis_pool_id);
+    assert_eq!(
+        distribution.total_reward(),
+        Some(chain_config.block_subsidy_at_height(&block_index.block_height()))
+    );
+    assert_eq!(
+        distribution
+            .delegation_rewards()
+            .get(&delegation_id)
+            .copied()
+            .unwrap_or(Amount::ZERO),
+        delegation_reward
+    );
+    assert!(distribution.delegation_rewards().keys().all(|id| *id == delegation_id));
+    assert_eq!(
+        distribution.staker_reward(),
+        (distribution.total_reward().unwrap() - delegation_reward).unwrap()
+    );
+}
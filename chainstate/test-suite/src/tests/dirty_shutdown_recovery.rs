@@ -0,0 +1,144 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use rstest::rstest;
+
+use super::helpers::{block_creation_helpers::*, block_status_helpers::*};
+use chainstate::{ChainInconsistency, PendingOperation};
+use chainstate_storage::{
+    BlockchainStorageRead, BlockchainStorageWrite, TransactionRw, Transactional,
+};
+use chainstate_test_framework::TestFramework;
+use chainstate_types::BlockValidationStage;
+use common::primitives::{BlockHeight, Idable};
+use randomness::Rng;
+use test_utils::random::{make_seedable_rng, Seed};
+
+// A chainstate that has been shut down cleanly doesn't need recovery.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn no_recovery_after_clean_shutdown(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+        let genesis_id = tf.genesis().get_id();
+        let tip_id = tf.create_chain(&genesis_id.into(), rng.gen_range(1..20), &mut rng).unwrap();
+
+        let tf = tf.reload();
+
+        assert_eq!(tf.chainstate.get_recovery_report(), None);
+        assert_eq!(tf.best_block_id(), tip_id);
+    });
+}
+
+// The node was stopped in the middle of invalidating m1 in:
+// G----m0----m1----m2
+// After the restart, the invalidation should be completed.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn interrupted_block_invalidation(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+        let genesis_id = tf.genesis().get_id();
+
+        let (m0_id, result) = process_block(&mut tf, &genesis_id.into(), &mut rng);
+        assert!(result.is_ok());
+        let (m1_id, result) = process_block(&mut tf, &m0_id.into(), &mut rng);
+        assert!(result.is_ok());
+        let (m2_id, result) = process_block(&mut tf, &m1_id.into(), &mut rng);
+        assert!(result.is_ok());
+
+        let operation = PendingOperation::BlockInvalidation {
+            block_id: m1_id,
+            tip_before: m2_id.into(),
+            is_explicit: true,
+        };
+        {
+            let mut db_tx = tf.storage.transaction_rw(None).unwrap();
+            db_tx.set_pending_operation(&operation).unwrap();
+            db_tx.commit().unwrap();
+        }
+
+        let tf = tf.reload();
+
+        let report = tf.chainstate.get_recovery_report().unwrap();
+        assert_eq!(report.interrupted_operation, Some(operation));
+        assert!(report.inconsistencies.is_empty());
+        assert_eq!(report.old_tip_id, m2_id);
+        assert_eq!(report.old_tip_height, BlockHeight::new(3));
+        assert_eq!(report.new_tip_id, m0_id);
+        assert_eq!(report.new_tip_height, BlockHeight::new(1));
+
+        assert_eq!(tf.best_block_id(), m0_id);
+        assert_fully_valid_blocks(&tf, &[m0_id]);
+        assert_invalidated_blocks_at_stage(&tf, &[m1_id], BlockValidationStage::FullyChecked);
+        assert_blocks_with_bad_parent_at_stage(&tf, &[m2_id], BlockValidationStage::FullyChecked);
+
+        let db_tx = tf.storage.transaction_ro().unwrap();
+        assert_eq!(db_tx.get_pending_operation().unwrap(), None);
+    });
+}
+
+// A block is registered as a mainchain block above the tip, which can't happen after a clean
+// shutdown. It should be unregistered and the tip should remain the same.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn block_registered_above_tip(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+        let genesis_id = tf.genesis().get_id();
+
+        let (m0_id, result) = process_block(&mut tf, &genesis_id.into(), &mut rng);
+        assert!(result.is_ok());
+        let (m1_id, result) = process_block(&mut tf, &m0_id.into(), &mut rng);
+        assert!(result.is_ok());
+        // A stale block whose id will be registered above the tip.
+        let (a0_id, result) = process_block(&mut tf, &genesis_id.into(), &mut rng);
+        assert!(result.is_ok());
+        assert_eq!(tf.best_block_id(), m1_id);
+
+        let height_above_tip = BlockHeight::new(3);
+        {
+            let mut db_tx = tf.storage.transaction_rw(None).unwrap();
+            db_tx.set_block_id_at_height(&height_above_tip, &a0_id.into()).unwrap();
+            db_tx.commit().unwrap();
+        }
+
+        let tf = tf.reload();
+
+        let report = tf.chainstate.get_recovery_report().unwrap();
+        assert_eq!(report.interrupted_operation, None);
+        assert_eq!(
+            report.inconsistencies,
+            vec![ChainInconsistency::BlockAboveTip(height_above_tip, a0_id.into())]
+        );
+        assert_eq!(report.old_tip_id, m1_id);
+        assert_eq!(report.new_tip_id, m1_id);
+
+        assert_eq!(tf.best_block_id(), m1_id);
+        assert_fully_valid_blocks(&tf, &[m0_id, m1_id]);
+
+        let db_tx = tf.storage.transaction_ro().unwrap();
+        assert_eq!(
+            db_tx.get_block_id_by_height(&height_above_tip).unwrap(),
+            None
+        );
+    });
+}
@@ -14,7 +14,7 @@
 // limitations under the License.
 
 use super::*;
-use chainstate::ChainstateError;
+use chainstate::{ChainstateConfig, ChainstateError, InitializationError};
 use chainstate_test_framework::TestFramework;
 use common::{
     chain::{
@@ -144,3 +144,28 @@ fn genesis_check_err_nonempty_chain(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
     genesis_check_err(rng.gen_range(2..100), &mut rng);
 }
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn txs_deferral_disabled_outside_regtest(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chainstate_config = ChainstateConfig::new().with_txs_deferred_during_ibd(false);
+
+    let result = TestFramework::builder(&mut rng)
+        .with_chain_config(ChainConfigBuilder::test_chain().build())
+        .with_chainstate_config(chainstate_config.clone())
+        .try_build();
+    match result {
+        Err(ChainstateError::FailedToInitializeChainstate(
+            InitializationError::TxsDeferralDisabledOutsideRegtest,
+        )) => (),
+        Err(e) => panic!("unexpected error {e:?}"),
+        Ok(_) => panic!("expected chainstate initialization to fail"),
+    }
+
+    let tf = TestFramework::builder(&mut rng)
+        .with_chainstate_config(chainstate_config)
+        .build();
+    assert!(!tf.chainstate.is_tx_processing_deferred());
+}
@@ -28,12 +28,14 @@ use test_utils::random::{make_seedable_rng, Seed};
 
 mod basic_tests;
 mod block_invalidation;
+mod block_stats;
 mod block_status;
 mod bootstrap;
 mod chainstate_accounting_storage_tests;
 mod chainstate_storage_tests;
 mod data_deposit;
 mod delegation_tests;
+mod dirty_shutdown_recovery;
 mod double_spend_tests;
 mod events_tests;
 mod framework_tests;
@@ -61,9 +63,11 @@ mod signature_tests;
 mod stake_pool_tests;
 mod syncing_tests;
 mod tx_fee;
+mod tx_index;
 mod tx_verification_simulation;
 mod tx_verifier_among_threads;
 mod tx_verifier_disconnect;
+mod undo_compaction;
 
 mod helpers;
 
@@ -22,7 +22,7 @@ use chainstate::BlockSource;
 use chainstate_storage::{BlockchainStorageWrite, TransactionRw, Transactional};
 use chainstate_test_framework::{
     anyonecanspend_address, create_stake_pool_data_with_all_reward_to_staker, empty_witness,
-    TestFramework, TestStore, TransactionBuilder,
+    DeliveryOrder, TestFramework, TestStore, TransactionBuilder,
 };
 use common::{
     chain::{
@@ -962,6 +962,70 @@ fn pos_reorg_simple(#[case] seed: Seed) {
     assert_eq!(<Id<GenBlock>>::from(block_c_id), tf1.best_block_id());
 }
 
+// Same as `pos_reorg_simple`, but the longer branch is delivered to Chainstate1 out of order,
+// so the blocks that arrive before their parent have to be buffered as orphans.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn pos_reorg_out_of_order_delivery(
+    #[case] seed: Seed,
+    #[values(BlockSource::Local, BlockSource::Peer)] block_source: BlockSource,
+    #[values(DeliveryOrder::Reversed, DeliveryOrder::Shuffled)] order: DeliveryOrder,
+) {
+    let mut rng = make_seedable_rng(seed);
+
+    let (vrf_sk, vrf_pk) = VRFPrivateKey::new_from_rng(&mut rng, VRFKeyKind::Schnorrkel);
+    let (staker_sk, staker_pk) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+
+    let (chain_config_builder, genesis_pool_id) =
+        chainstate_test_framework::create_chain_config_with_default_staking_pool(
+            &mut rng, staker_pk, vrf_pk,
+        );
+    let chain_config = chain_config_builder.build();
+
+    let target_block_time = chain_config.target_block_spacing();
+
+    let mut tf1 = TestFramework::builder(&mut rng).with_chain_config(chain_config.clone()).build();
+    let mut tf2 = TestFramework::builder(&mut rng).with_chain_config(chain_config).build();
+
+    tf1.progress_time_seconds_since_epoch(target_block_time.as_secs());
+    tf2.progress_time_seconds_since_epoch(target_block_time.as_secs());
+
+    // Block A
+    tf1.make_pos_block_builder()
+        .with_stake_pool_id(genesis_pool_id)
+        .with_stake_spending_key(staker_sk.clone())
+        .with_vrf_key(vrf_sk.clone())
+        .build_and_process(&mut rng)
+        .unwrap();
+
+    // The longer branch
+    let branch_length = rng.gen_range(2..10);
+    let mut branch = Vec::with_capacity(branch_length);
+    for _ in 0..branch_length {
+        let block = tf2
+            .make_pos_block_builder()
+            .with_stake_pool_id(genesis_pool_id)
+            .with_stake_spending_key(staker_sk.clone())
+            .with_vrf_key(vrf_sk.clone())
+            .build(&mut rng);
+        tf2.process_block(block.clone(), BlockSource::Local).unwrap();
+        branch.push(block);
+
+        tf1.progress_time_seconds_since_epoch(target_block_time.as_secs());
+        tf2.progress_time_seconds_since_epoch(target_block_time.as_secs());
+    }
+    let branch_tip_id = tf2.best_block_id();
+
+    tf1.make_block_delivery(branch)
+        .with_order(order)
+        .with_block_source(block_source)
+        .with_expected_tip(branch_tip_id)
+        .deliver(&mut rng)
+        .unwrap();
+    assert_eq!(tf1.chainstate.orphans_count(), 0);
+}
+
 // Produce `genesis -> a -> b -> c` chain, where block `a` creates delegation with some coins and
 // block `b` and `c` spend it.
 // Then produce a parallel `genesis -> a -> d` that should trigger a in-memory reorg for blocks `b` and `c`.
@@ -640,6 +640,11 @@ fn initial_block_download(#[case] seed: Seed) {
                 min_max_bootstrap_import_buffer_sizes: Default::default(),
                 max_tip_age: Duration::from_secs(1).into(),
                 enable_heavy_checks: Some(true),
+                defer_txs_during_ibd: None,
+                enable_tx_index: None,
+                recovery_replay_depth: Default::default(),
+                undo_data_retention_depth: None,
+                auto_storage_compaction_interval: None,
             })
             .with_initial_time_since_genesis(2)
             .build();
@@ -942,3 +947,68 @@ fn get_block_ids_as_checkpoints(#[case] seed: Seed) {
         assert_eq!(result, []);
     });
 }
+
+// Check that headers can be processed ahead of their blocks and that only the fully checked ones,
+// i.e. the ones whose parent block is known, count toward the best header.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn process_headers_ahead_of_blocks(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+
+        let blocks = {
+            let mut tf = TestFramework::builder(&mut rng).build();
+            let ids =
+                tf.create_chain_return_ids(&tf.genesis().get_id().into(), 10, &mut rng).unwrap();
+            ids.iter().map(|id| tf.block(tf.to_chain_block_id(id))).collect::<Vec<_>>()
+        };
+        let headers = blocks.iter().map(|block| block.header().clone()).collect::<Vec<_>>();
+
+        let mut tf = TestFramework::builder(&mut rng).build();
+        let genesis_id = tf.genesis().get_id();
+
+        // A header whose parent is unknown is rejected.
+        let err = tf.chainstate.process_block_headers(headers[5..].to_vec()).unwrap_err();
+        assert_eq!(
+            err,
+            ChainstateError::ProcessBlockError(chainstate::BlockError::CheckBlockFailed(
+                CheckBlockError::ParentBlockMissing {
+                    block_id: headers[5].block_id(),
+                    parent_block_id: *headers[5].prev_block_id(),
+                },
+            ))
+        );
+
+        // The first batch is connected to genesis, only its first header can be checked fully.
+        tf.chainstate.process_block_headers(headers[..5].to_vec()).unwrap();
+        let info = tf.chainstate.info().unwrap();
+        assert_eq!(info.best_block_id, genesis_id);
+        assert_eq!(info.best_header_id, headers[0].block_id().into());
+        assert_eq!(info.best_header_height, BlockHeight::new(1));
+
+        // The second batch is connected to the previously processed headers.
+        tf.chainstate.process_block_headers(headers[5..].to_vec()).unwrap();
+        let info = tf.chainstate.info().unwrap();
+        assert_eq!(info.best_block_id, genesis_id);
+        assert_eq!(info.best_header_id, headers[0].block_id().into());
+        assert_eq!(info.best_header_height, BlockHeight::new(1));
+
+        // Once the blocks arrive, the headers building on them are checked fully.
+        for block in blocks.iter().take(7) {
+            tf.process_block(block.clone(), BlockSource::Peer).unwrap();
+        }
+        let info = tf.chainstate.info().unwrap();
+        assert_eq!(info.best_block_height, BlockHeight::new(7));
+        assert_eq!(info.best_header_id, headers[7].block_id().into());
+        assert_eq!(info.best_header_height, BlockHeight::new(8));
+
+        for block in blocks.iter().skip(7) {
+            tf.process_block(block.clone(), BlockSource::Peer).unwrap();
+        }
+        let info = tf.chainstate.info().unwrap();
+        assert_eq!(info.best_block_id, headers[9].block_id().into());
+        assert_eq!(info.best_header_id, info.best_block_id);
+        assert_eq!(info.best_header_height, BlockHeight::new(10));
+    });
+}
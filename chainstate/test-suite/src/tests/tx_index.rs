@@ -0,0 +1,111 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use chainstate::{ChainstateConfig, ChainstateError, PropertyQueryError};
+use chainstate_storage::{BlockchainStorageRead, Transactional};
+use chainstate_test_framework::{
+    anyonecanspend_address, empty_witness, TestFramework, TransactionBuilder,
+};
+use common::{
+    chain::{
+        output_value::OutputValue, signed_transaction::SignedTransaction, OutPointSourceId,
+        TxInput, TxOutput,
+    },
+    primitives::{Amount, Idable},
+};
+
+fn tx_from_genesis(genesis: &Genesis, rng: &mut impl Rng) -> SignedTransaction {
+    TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(OutPointSourceId::BlockReward(genesis.get_id().into()), 0),
+            empty_witness(rng),
+        )
+        .add_output(TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(rng.gen_range(100_000..200_000))),
+            anyonecanspend_address(),
+        ))
+        .build()
+}
+
+// A transaction is found while its block is in the mainchain and is no longer found after
+// the block has been reorged out.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn lookup_and_reorg(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chainstate_config(ChainstateConfig::new().with_tx_index_enabled(true))
+            .build();
+        let genesis_id = tf.genesis().get_id();
+
+        let tx = tx_from_genesis(&tf.genesis(), &mut rng);
+        let tx_id = tx.transaction().get_id();
+        let block = tf.make_block_builder().add_transaction(tx.clone()).build(&mut rng);
+        let block_id = block.get_id();
+        tf.process_block(block, BlockSource::Local).unwrap();
+
+        assert_eq!(
+            tf.chainstate.get_transaction(&tx_id).unwrap(),
+            Some((tx, block_id, BlockHeight::new(1)))
+        );
+
+        let new_tip = tf.create_chain(&genesis_id.into(), 2, &mut rng).unwrap();
+        assert_eq!(tf.best_block_id(), new_tip);
+        assert_eq!(tf.chainstate.get_transaction(&tx_id).unwrap(), None);
+    });
+}
+
+// The index is built on startup when it gets enabled for an existing chain and is removed
+// when it gets disabled.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn enable_and_disable(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = TestFramework::builder(&mut rng).build();
+
+        let tx = tx_from_genesis(&tf.genesis(), &mut rng);
+        let tx_id = tx.transaction().get_id();
+        let block = tf.make_block_builder().add_transaction(tx.clone()).build(&mut rng);
+        let block_id = block.get_id();
+        tf.process_block(block, BlockSource::Local).unwrap();
+        tf.create_chain(&block_id.into(), 2, &mut rng).unwrap();
+
+        assert_eq!(
+            tf.chainstate.get_transaction(&tx_id),
+            Err(ChainstateError::FailedToReadProperty(
+                PropertyQueryError::TxIndexDisabled
+            ))
+        );
+
+        let tf = TestFramework::builder(&mut rng)
+            .with_storage(tf.storage)
+            .with_chainstate_config(ChainstateConfig::new().with_tx_index_enabled(true))
+            .build();
+        assert_eq!(
+            tf.chainstate.get_transaction(&tx_id).unwrap(),
+            Some((tx, block_id, BlockHeight::new(1)))
+        );
+
+        let tf = TestFramework::builder(&mut rng).with_storage(tf.storage).build();
+        let db_tx = tf.storage.transaction_ro().unwrap();
+        assert_eq!(db_tx.get_tx_index_enabled().unwrap(), Some(false));
+        assert_eq!(db_tx.get_tx_block_id(&tx_id).unwrap(), None);
+    });
+}
@@ -0,0 +1,174 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use rstest::rstest;
+
+use super::helpers::block_creation_helpers::*;
+use chainstate::{ChainstateConfig, ChainstateError, UndoCompactionError};
+use chainstate_storage::{BlockchainStorageRead, Transactional};
+use chainstate_test_framework::TestFramework;
+use common::{
+    chain::{self, Block, GenBlock},
+    primitives::{BlockDistance, BlockHeight, Id},
+};
+use randomness::{CryptoRng, Rng};
+use test_utils::random::{make_seedable_rng, Seed};
+
+const MAX_REORG_DEPTH: u64 = 5;
+
+fn make_test_framework(
+    rng: &mut (impl Rng + CryptoRng),
+    chainstate_config: ChainstateConfig,
+) -> TestFramework {
+    TestFramework::builder(rng)
+        .with_chain_config(
+            chain::config::create_unit_test_config_builder()
+                .max_depth_for_reorg(BlockDistance::new(MAX_REORG_DEPTH as i64))
+                .build(),
+        )
+        .with_chainstate_config(chainstate_config)
+        .build()
+}
+
+// Create a chain of blocks that spend the outputs of their parents, so that all of them have
+// undo data. Return the ids of the blocks, starting from height 1.
+fn make_chain(
+    tf: &mut TestFramework,
+    length: usize,
+    rng: &mut (impl Rng + CryptoRng),
+) -> Vec<Id<Block>> {
+    let mut parent_id: Id<GenBlock> = tf.genesis().get_id().into();
+    (0..length)
+        .map(|_| {
+            let (block_id, result) = process_block(tf, &parent_id, rng);
+            assert!(result.is_ok());
+            parent_id = block_id.into();
+            block_id
+        })
+        .collect()
+}
+
+fn has_undo_data(tf: &TestFramework, block_id: Id<Block>) -> bool {
+    let db_tx = tf.storage.transaction_ro().unwrap();
+    db_tx.get_undo_data(block_id).unwrap().is_some()
+}
+
+// The undo data of the blocks deeper than the max reorg depth is dropped, the rest is kept.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn compact_with_default_depth(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut tf = make_test_framework(&mut rng, ChainstateConfig::new());
+        let chain_length = rng.gen_range(MAX_REORG_DEPTH as usize + 2..20);
+        let block_ids = make_chain(&mut tf, chain_length, &mut rng);
+        assert!(block_ids.iter().all(|id| has_undo_data(&tf, *id)));
+
+        let retained_from_height = BlockHeight::new(chain_length as u64 - MAX_REORG_DEPTH);
+
+        let report = tf.chainstate.compact_undo_data().unwrap();
+        assert_eq!(report.retained_from_height, retained_from_height);
+        assert_eq!(
+            report.compacted_blocks_count,
+            retained_from_height.into_int() - 1
+        );
+
+        for (height, block_id) in (1..).zip(block_ids.iter()) {
+            assert_eq!(
+                has_undo_data(&tf, *block_id),
+                BlockHeight::new(height) >= retained_from_height,
+                "height {height}"
+            );
+        }
+
+        // Nothing is left to compact
+        let report = tf.chainstate.compact_undo_data().unwrap();
+        assert_eq!(report.retained_from_height, retained_from_height);
+        assert_eq!(report.compacted_blocks_count, 0);
+
+        // A reorg of the max depth, which disconnects all the blocks above the retained height,
+        // is still possible
+        let fork_point = block_ids[retained_from_height.into_int() as usize - 1];
+        let mut parent_id: Id<GenBlock> = fork_point.into();
+        for _ in 0..=MAX_REORG_DEPTH {
+            let (block_id, result) = process_block(&mut tf, &parent_id, &mut rng);
+            assert!(result.is_ok());
+            parent_id = block_id.into();
+        }
+        assert_eq!(tf.best_block_id(), parent_id);
+    });
+}
+
+// A configured depth greater than the max reorg depth keeps more undo data.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn compact_with_configured_depth(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let retention_depth = rng.gen_range(MAX_REORG_DEPTH + 1..10);
+        let mut tf = make_test_framework(
+            &mut rng,
+            ChainstateConfig::new().with_undo_data_retention_depth(retention_depth),
+        );
+        let chain_length = rng.gen_range(retention_depth as usize + 2..25);
+        let block_ids = make_chain(&mut tf, chain_length, &mut rng);
+
+        let retained_from_height = BlockHeight::new(chain_length as u64 - retention_depth);
+
+        let report = tf.chainstate.compact_undo_data().unwrap();
+        assert_eq!(report.retained_from_height, retained_from_height);
+        assert_eq!(
+            report.compacted_blocks_count,
+            retained_from_height.into_int() - 1
+        );
+
+        for (height, block_id) in (1..).zip(block_ids.iter()) {
+            assert_eq!(
+                has_undo_data(&tf, *block_id),
+                BlockHeight::new(height) >= retained_from_height,
+                "height {height}"
+            );
+        }
+    });
+}
+
+// The undo data of the blocks that can still be disconnected must never be dropped.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn retention_depth_below_max_reorg_depth(#[case] seed: Seed) {
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let retention_depth = rng.gen_range(0..MAX_REORG_DEPTH);
+        let mut tf = make_test_framework(
+            &mut rng,
+            ChainstateConfig::new().with_undo_data_retention_depth(retention_depth),
+        );
+        let block_ids = make_chain(&mut tf, 10, &mut rng);
+
+        assert_eq!(
+            tf.chainstate.compact_undo_data().unwrap_err(),
+            ChainstateError::UndoCompactionError(
+                UndoCompactionError::RetentionDepthBelowMaxReorgDepth(
+                    retention_depth,
+                    MAX_REORG_DEPTH
+                )
+            )
+        );
+        assert!(block_ids.iter().all(|id| has_undo_data(&tf, *id)));
+    });
+}
@@ -16,7 +16,7 @@
 pub mod transaction_verifier;
 
 pub use transaction_verifier::{
-    check_transaction::{check_transaction, CheckTransactionError},
+    check_transaction::{check_transaction, check_transaction_stateless, CheckTransactionError},
     error,
     flush::flush_to_storage,
     input_check,
@@ -19,7 +19,13 @@ use chainstate_types::PropertyQueryError;
 use common::{
     chain::{
         output_value::OutputValue,
-        signature::inputsig::InputWitness,
+        signature::inputsig::{
+            authorize_hashed_timelock_contract_spend::AuthorizedHashedTimelockContractSpend,
+            authorize_pubkey_spend::AuthorizedPublicKeySpend,
+            authorize_pubkeyhash_spend::AuthorizedPublicKeyHashSpend,
+            classical_multisig::authorize_classical_multisig::AuthorizedClassicalMultisigSpend,
+            InputWitness,
+        },
         tokens::{get_tokens_issuance_count, NftIssuance},
         AccountCommand, ChainConfig, ChangeTokenMetadataUriActivated, HtlcActivated,
         SignedTransaction, TokenIssuanceVersion, Transaction, TransactionSize, TxInput, TxOutput,
@@ -64,6 +70,8 @@ pub enum CheckTransactionError {
     OrdersCurrenciesMustBeDifferent(Id<Transaction>),
     #[error("Change token metadata uri not activated yet")]
     ChangeTokenMetadataUriNotActivated,
+    #[error("Malformed signature in transaction {0}")]
+    InvalidWitnessFormat(Id<Transaction>),
 }
 
 pub fn check_transaction(
@@ -82,6 +90,21 @@ pub fn check_transaction(
     Ok(())
 }
 
+/// Checks that depend neither on the chain state nor on the height at which the transaction is
+/// going to be included. These are cheap, so they can be used to reject malformed transactions
+/// before doing the full validation.
+pub fn check_transaction_stateless(
+    chain_config: &ChainConfig,
+    tx: &SignedTransaction,
+) -> Result<(), CheckTransactionError> {
+    check_size(chain_config, tx)?;
+    check_duplicate_inputs(tx)?;
+    check_witness_count(tx)?;
+    check_no_signature_size(chain_config, tx)?;
+    check_witness_format(tx)?;
+    Ok(())
+}
+
 fn check_size(
     chain_config: &ChainConfig,
     tx: &SignedTransaction,
@@ -140,6 +163,29 @@ fn check_witness_count(tx: &SignedTransaction) -> Result<(), CheckTransactionErr
     Ok(())
 }
 
+fn check_witness_format(tx: &SignedTransaction) -> Result<(), CheckTransactionError> {
+    for signature in tx.signatures() {
+        match signature {
+            InputWitness::NoSignature(_) => (),
+            InputWitness::Standard(sig) => {
+                // The destination being spent is not known without the utxo set, so only check
+                // that the signature decodes as one of the supported authorization types
+                let data = sig.raw_signature();
+                let is_known_format = AuthorizedPublicKeyHashSpend::from_data(data).is_ok()
+                    || AuthorizedPublicKeySpend::from_data(data).is_ok()
+                    || AuthorizedClassicalMultisigSpend::from_data(data).is_ok()
+                    || AuthorizedHashedTimelockContractSpend::from_data(data).is_ok();
+                ensure!(
+                    is_known_format,
+                    CheckTransactionError::InvalidWitnessFormat(tx.transaction().get_id())
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn check_tokens_tx(
     chain_config: &ChainConfig,
     block_height: BlockHeight,
@@ -0,0 +1,52 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::primitives::BlockHeight;
+use serialization::{Decode, Encode};
+
+/// The range of mainchain heights whose block data (i.e. the block bodies and not just
+/// the headers) is stored locally and can be served to peers.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Encode, Decode)]
+pub struct BlockDataAvailability {
+    first_height: BlockHeight,
+    last_height: BlockHeight,
+}
+
+impl BlockDataAvailability {
+    /// Note that the range is empty if `first_height` is greater than `last_height`.
+    pub fn new(first_height: BlockHeight, last_height: BlockHeight) -> Self {
+        Self {
+            first_height,
+            last_height,
+        }
+    }
+
+    pub fn first_height(&self) -> BlockHeight {
+        self.first_height
+    }
+
+    pub fn last_height(&self) -> BlockHeight {
+        self.last_height
+    }
+
+    /// Return true if the data of all blocks after the genesis up to `last_height` is available.
+    pub fn is_complete(&self) -> bool {
+        self.first_height <= BlockHeight::one()
+    }
+
+    pub fn contains(&self, height: BlockHeight) -> bool {
+        self.first_height <= height && height <= self.last_height
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::primitives::{Amount, Fee};
+use serialization::{Decode, Encode};
+
+/// Fees and fullness of a mainchain block, collected when the block is connected
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct BlockStats {
+    tx_count: u32,
+    total_fees: Amount,
+    txs_size: u64,
+    median_fee_rate: Option<Amount>,
+}
+
+impl BlockStats {
+    /// Make the statistics from the fee paid by each transaction of the block and its encoded size.
+    /// Returns `None` if the total fee overflows.
+    pub fn from_tx_fees_and_sizes(txs: impl IntoIterator<Item = (Fee, usize)>) -> Option<Self> {
+        let txs = txs.into_iter().collect::<Vec<_>>();
+
+        let total_fees = txs.iter().map(|(fee, _)| fee.0).sum::<Option<Amount>>()?;
+        let txs_size = txs.iter().map(|(_, size)| *size as u64).sum();
+
+        let mut fee_rates =
+            txs.iter().map(|(fee, size)| fee_rate_per_kb(fee.0, *size)).collect::<Vec<_>>();
+        fee_rates.sort();
+        // For an even number of transactions the upper one of the middle two is taken
+        let median_fee_rate = fee_rates.get(fee_rates.len() / 2).copied();
+
+        Some(Self {
+            tx_count: txs.len() as u32,
+            total_fees,
+            txs_size,
+            median_fee_rate,
+        })
+    }
+
+    /// The number of transactions, not counting the block reward
+    pub fn tx_count(&self) -> u32 {
+        self.tx_count
+    }
+
+    /// The sum of the fees paid by all the transactions
+    pub fn total_fees(&self) -> Amount {
+        self.total_fees
+    }
+
+    /// The total size of the transactions in bytes
+    pub fn txs_size(&self) -> u64 {
+        self.txs_size
+    }
+
+    /// The median fee rate of the transactions, in atoms per 1000 bytes;
+    /// `None` if the block has no transactions
+    pub fn median_fee_rate(&self) -> Option<Amount> {
+        self.median_fee_rate
+    }
+}
+
+fn fee_rate_per_kb(fee: Amount, size: usize) -> Amount {
+    let size = std::cmp::max(size, 1) as u128;
+    Amount::from_atoms(fee.into_atoms().saturating_mul(1000) / size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee(atoms: u128) -> Fee {
+        Fee(Amount::from_atoms(atoms))
+    }
+
+    #[test]
+    fn stats_from_txs() {
+        let empty = BlockStats::from_tx_fees_and_sizes([]).unwrap();
+        assert_eq!(empty.tx_count(), 0);
+        assert_eq!(empty.total_fees(), Amount::ZERO);
+        assert_eq!(empty.txs_size(), 0);
+        assert_eq!(empty.median_fee_rate(), None);
+
+        let stats =
+            BlockStats::from_tx_fees_and_sizes([(fee(300), 100), (fee(50), 500), (fee(200), 400)])
+                .unwrap();
+        assert_eq!(stats.tx_count(), 3);
+        assert_eq!(stats.total_fees(), Amount::from_atoms(550));
+        assert_eq!(stats.txs_size(), 1000);
+        // The rates are 3000, 100 and 500 atoms per kb
+        assert_eq!(stats.median_fee_rate(), Some(Amount::from_atoms(500)));
+
+        let stats =
+            BlockStats::from_tx_fees_and_sizes([(fee(100), 1000), (fee(400), 1000)]).unwrap();
+        assert_eq!(stats.median_fee_rate(), Some(Amount::from_atoms(400)));
+
+        assert!(BlockStats::from_tx_fees_and_sizes([(fee(u128::MAX), 1), (fee(1), 1)]).is_none());
+    }
+}
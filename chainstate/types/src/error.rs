@@ -16,7 +16,7 @@
 use thiserror::Error;
 
 use common::{
-    chain::{Block, GenBlock, PoolId},
+    chain::{config::EpochIndex, Block, GenBlock, PoolId},
     primitives::{BlockHeight, Id},
 };
 
@@ -56,6 +56,12 @@ pub enum PropertyQueryError {
         start: BlockHeight,
         end: BlockHeight,
     },
+    #[error("Transaction index is disabled")]
+    TxIndexDisabled,
+    #[error("Epoch {0} hasn't been sealed yet")]
+    EpochNotSealed(EpochIndex),
+    #[error("PoS accounting error: {0}")]
+    PoSAccountingError(#[from] pos_accounting::Error),
 }
 
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
@@ -20,9 +20,11 @@ pub mod vrf_tools;
 pub use crate::{
     ancestor::block_index_ancestor_getter,
     ancestor::gen_block_index_getter,
+    block_data_availability::BlockDataAvailability,
     block_index::BlockIndex,
     block_index_handle::BlockIndexHandle,
     block_index_history_iter::BlockIndexHistoryIterator,
+    block_stats::BlockStats,
     block_status::{BlockStatus, BlockValidationStage},
     epoch_data::EpochData,
     epoch_data_cache::{
@@ -36,9 +38,11 @@ pub use crate::{
 };
 
 mod ancestor;
+mod block_data_availability;
 mod block_index;
 mod block_index_handle;
 mod block_index_history_iter;
+mod block_stats;
 mod block_status;
 mod epoch_data;
 mod epoch_data_cache;
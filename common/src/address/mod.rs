@@ -119,7 +119,10 @@ mod tests {
     use crate::chain::config::{create_mainnet, create_regtest};
     use crate::chain::{DelegationId, Destination, PoolId};
     use crypto::{
-        key::{KeyKind, PrivateKey},
+        key::{
+            extended::{ExtendedKeyKind, ExtendedPrivateKey, ExtendedPublicKey},
+            KeyKind, PrivateKey,
+        },
         vrf::VRFPublicKey,
     };
     use pubkeyhash::PublicKeyHash;
@@ -141,6 +144,34 @@ mod tests {
         assert_eq!(public_key_hash_restored_dest, public_key_hash_dest);
     }
 
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn keys(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+        let cfg = create_mainnet();
+
+        let (priv_key, _pub_key) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let address = Address::new(&cfg, priv_key.clone()).unwrap();
+        assert!(address.as_str().starts_with("mprv1"));
+        let restored = Address::<PrivateKey>::from_string(&cfg, address.as_str()).unwrap();
+        assert_eq!(restored.into_object(), priv_key);
+
+        let (_xpriv, xpub) =
+            ExtendedPrivateKey::new_from_rng(&mut rng, ExtendedKeyKind::Secp256k1Schnorr);
+        let address = Address::new(&cfg, xpub.clone()).unwrap();
+        assert!(address.as_str().starts_with("mxpub1"));
+        let restored = Address::<ExtendedPublicKey>::from_string(&cfg, address.as_str()).unwrap();
+        assert_eq!(restored.into_object(), xpub);
+
+        // The keys of another network are rejected
+        let regtest_address = Address::new(&create_regtest(), xpub).unwrap();
+        assert!(matches!(
+            Address::<ExtendedPublicKey>::from_string(&cfg, regtest_address.as_str()),
+            Err(AddressError::InvalidPrefix(_))
+        ));
+    }
+
     #[test]
     fn to_short_string() {
         let cfg = create_regtest();
@@ -13,7 +13,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crypto::vrf::VRFPublicKey;
+use crypto::{
+    key::{extended::ExtendedPublicKey, PrivateKey},
+    vrf::VRFPublicKey,
+};
 use serialization::{DecodeAll, Encode};
 
 use crate::chain::ChainConfig;
@@ -62,3 +65,51 @@ impl Addressable for VRFPublicKey {
         "HexifiedVRFPublicKey"
     }
 }
+
+impl Addressable for PrivateKey {
+    type Error = AddressError;
+
+    fn address_prefix(&self, chain_config: &ChainConfig) -> &str {
+        chain_config.private_key_address_prefix()
+    }
+
+    fn encode_to_bytes_for_address(&self) -> Vec<u8> {
+        self.encode()
+    }
+
+    fn decode_from_bytes_from_address<T: AsRef<[u8]>>(address_bytes: T) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        Self::decode_all(&mut address_bytes.as_ref())
+            .map_err(|e| AddressError::DecodingError(e.to_string()))
+    }
+
+    fn json_wrapper_prefix() -> &'static str {
+        "HexifiedPrivateKey"
+    }
+}
+
+impl Addressable for ExtendedPublicKey {
+    type Error = AddressError;
+
+    fn address_prefix(&self, chain_config: &ChainConfig) -> &str {
+        chain_config.extended_public_key_address_prefix()
+    }
+
+    fn encode_to_bytes_for_address(&self) -> Vec<u8> {
+        self.encode()
+    }
+
+    fn decode_from_bytes_from_address<T: AsRef<[u8]>>(address_bytes: T) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        Self::decode_all(&mut address_bytes.as_ref())
+            .map_err(|e| AddressError::DecodingError(e.to_string()))
+    }
+
+    fn json_wrapper_prefix() -> &'static str {
+        "HexifiedExtendedPublicKey"
+    }
+}
@@ -36,7 +36,7 @@ use serialization::{Decode, Encode};
 
 use crate::chain::block::timestamp::BlockTimestamp;
 use crate::chain::transaction::Destination;
-use crate::chain::upgrades::NetUpgrades;
+use crate::chain::upgrades::{Deployment, NetUpgrades};
 use crate::chain::PoWChainConfig;
 use crate::chain::TxOutput;
 use crate::chain::{GenBlock, Genesis};
@@ -352,6 +352,28 @@ impl ChainConfig {
         }
     }
 
+    /// Prefix of the bech32m encoding of private keys exported from wallets
+    #[must_use]
+    pub fn private_key_address_prefix(&self) -> &'static str {
+        match self.chain_type {
+            ChainType::Mainnet => "mprv",
+            ChainType::Testnet => "tprv",
+            ChainType::Regtest => "rprv",
+            ChainType::Signet => "sprv",
+        }
+    }
+
+    /// Prefix of the bech32m encoding of account extended public keys exported from wallets
+    #[must_use]
+    pub fn extended_public_key_address_prefix(&self) -> &'static str {
+        match self.chain_type {
+            ChainType::Mainnet => "mxpub",
+            ChainType::Testnet => "txpub",
+            ChainType::Regtest => "rxpub",
+            ChainType::Signet => "sxpub",
+        }
+    }
+
     /// The BIP44 coin type for this chain
     #[must_use]
     pub fn bip44_coin_type(&self) -> ChildNumber {
@@ -424,6 +446,12 @@ impl ChainConfig {
         &self.chainstate_upgrades
     }
 
+    /// The deployments of the known upgrades, with their activation heights on this chain
+    #[must_use]
+    pub fn deployments(&self) -> Vec<Deployment> {
+        crate::chain::upgrades::deployments(&self.chainstate_upgrades, &self.consensus_upgrades)
+    }
+
     /// Checkpoints enforced by the chain, as in, a block id vs height that must be satisfied
     #[must_use]
     pub fn height_checkpoints(&self) -> &Checkpoints {
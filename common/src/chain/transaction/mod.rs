@@ -108,6 +108,8 @@ pub enum TransactionCreationError {
     InvalidDestinationsCount,
     #[error("The number of htlc secrets does not match the number of inputs")]
     InvalidHtlcSecretsCount,
+    #[error("The number of sighash types does not match the number of inputs")]
+    InvalidSigHashTypesCount,
     #[error("Failed to convert partially signed tx to signed")]
     FailedToConvertPartiallySignedTx(PartiallySignedTransaction),
 }
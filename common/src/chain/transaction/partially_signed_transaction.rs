@@ -15,14 +15,49 @@
 
 use super::{
     htlc::HtlcSecret,
-    signature::{inputsig::InputWitness, Signable, Transactable},
-    Destination, Transaction, TxOutput,
+    signature::{
+        inputsig::InputWitness, sighash::sighashtype::SigHashType, Signable, Transactable,
+    },
+    Destination, Transaction, TransactionV1, TxOutput,
 };
-use crate::chain::{SignedTransaction, TransactionCreationError, TxInput};
-use serialization::{Decode, Encode};
+use crate::{
+    chain::{SignedTransaction, TransactionCreationError, TxInput},
+    primitives::VersionTag,
+};
+use serialization::{Decode, DirectDecode, DirectEncode, Encode, Tagged};
 use utils::ensure;
 
-#[derive(Debug, Eq, PartialEq, Clone, Encode, Decode)]
+/// The encoding of a partially signed transaction made before the sighash types were added.
+///
+/// It has no version byte of its own and starts with the transaction, which could only
+/// be a V1 transaction at that time.
+#[derive(Encode, Decode, Tagged)]
+struct PartiallySignedTransactionV1 {
+    tx: TransactionV1,
+    witnesses: Vec<Option<InputWitness>>,
+    input_utxos: Vec<Option<TxOutput>>,
+    destinations: Vec<Option<Destination>>,
+    htlc_secrets: Vec<Option<HtlcSecret>>,
+}
+
+#[derive(Encode, Decode, Tagged)]
+struct PartiallySignedTransactionV2 {
+    version: VersionTag<2>,
+    tx: Transaction,
+    witnesses: Vec<Option<InputWitness>>,
+    input_utxos: Vec<Option<TxOutput>>,
+    destinations: Vec<Option<Destination>>,
+    htlc_secrets: Vec<Option<HtlcSecret>>,
+    sighash_types: Vec<SigHashType>,
+}
+
+#[derive(DirectEncode, DirectDecode)]
+enum VersionedPartiallySignedTransaction {
+    V1(PartiallySignedTransactionV1),
+    V2(PartiallySignedTransactionV2),
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub struct PartiallySignedTransaction {
     tx: Transaction,
     witnesses: Vec<Option<InputWitness>>,
@@ -31,6 +66,9 @@ pub struct PartiallySignedTransaction {
     destinations: Vec<Option<Destination>>,
 
     htlc_secrets: Vec<Option<HtlcSecret>>,
+
+    /// The sighash type that should be used when signing each input
+    sighash_types: Vec<SigHashType>,
 }
 
 impl PartiallySignedTransaction {
@@ -40,6 +78,7 @@ impl PartiallySignedTransaction {
         input_utxos: Vec<Option<TxOutput>>,
         destinations: Vec<Option<Destination>>,
         htlc_secrets: Option<Vec<Option<HtlcSecret>>>,
+        sighash_types: Option<Vec<SigHashType>>,
     ) -> Result<Self, TransactionCreationError> {
         ensure!(
             tx.inputs().len() == witnesses.len(),
@@ -62,12 +101,20 @@ impl PartiallySignedTransaction {
             TransactionCreationError::InvalidHtlcSecretsCount
         );
 
+        let sighash_types =
+            sighash_types.unwrap_or_else(|| vec![SigHashType::default(); tx.inputs().len()]);
+        ensure!(
+            sighash_types.len() == tx.inputs().len(),
+            TransactionCreationError::InvalidSigHashTypesCount
+        );
+
         Ok(Self {
             tx,
             witnesses,
             input_utxos,
             destinations,
             htlc_secrets,
+            sighash_types,
         })
     }
 
@@ -100,6 +147,10 @@ impl PartiallySignedTransaction {
         self.htlc_secrets.as_ref()
     }
 
+    pub fn sighash_types(&self) -> &[SigHashType] {
+        self.sighash_types.as_ref()
+    }
+
     pub fn count_inputs(&self) -> usize {
         self.tx.inputs().len()
     }
@@ -130,6 +181,46 @@ impl PartiallySignedTransaction {
     }
 }
 
+// Always encoded as `PartiallySignedTransactionV2`, field by field to avoid cloning
+impl Encode for PartiallySignedTransaction {
+    fn encode_to<T: serialization::Output + ?Sized>(&self, dest: &mut T) {
+        VersionTag::<2>::default().encode_to(dest);
+        self.tx.encode_to(dest);
+        self.witnesses.encode_to(dest);
+        self.input_utxos.encode_to(dest);
+        self.destinations.encode_to(dest);
+        self.htlc_secrets.encode_to(dest);
+        self.sighash_types.encode_to(dest);
+    }
+}
+
+impl Decode for PartiallySignedTransaction {
+    fn decode<I: serialization::Input>(input: &mut I) -> Result<Self, serialization::Error> {
+        let result = match VersionedPartiallySignedTransaction::decode(input)? {
+            VersionedPartiallySignedTransaction::V1(ptx) => Self::new(
+                Transaction::V1(ptx.tx),
+                ptx.witnesses,
+                ptx.input_utxos,
+                ptx.destinations,
+                Some(ptx.htlc_secrets),
+                None,
+            ),
+            VersionedPartiallySignedTransaction::V2(ptx) => Self::new(
+                ptx.tx,
+                ptx.witnesses,
+                ptx.input_utxos,
+                ptx.destinations,
+                Some(ptx.htlc_secrets),
+                Some(ptx.sighash_types),
+            ),
+        };
+
+        result.map_err(|_| {
+            serialization::Error::from("Partially signed transaction fields don't match inputs")
+        })
+    }
+}
+
 impl Signable for PartiallySignedTransaction {
     fn inputs(&self) -> Option<&[TxInput]> {
         Some(self.tx.inputs())
@@ -153,3 +244,82 @@ impl Transactable for PartiallySignedTransaction {
         self.witnesses.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chain::OutPointSourceId,
+        primitives::{Id, H256},
+    };
+    use serialization::DecodeAll;
+
+    fn make_tx(inputs_count: u32) -> Transaction {
+        let inputs = (0..inputs_count)
+            .map(|i| {
+                let source_id: OutPointSourceId = Id::<Transaction>::new(H256::zero()).into();
+                TxInput::from_utxo(source_id, i)
+            })
+            .collect();
+        Transaction::new(0, inputs, vec![]).unwrap()
+    }
+
+    #[test]
+    fn encode_decode_roundtrip() {
+        let tx = make_tx(2);
+        let ptx = PartiallySignedTransaction::new(
+            tx,
+            vec![None; 2],
+            vec![None; 2],
+            vec![None; 2],
+            None,
+            Some(vec![
+                SigHashType::default(),
+                SigHashType::try_from(SigHashType::ALL | SigHashType::ANYONECANPAY).unwrap(),
+            ]),
+        )
+        .unwrap();
+
+        let encoded = ptx.encode();
+        assert_eq!(encoded[0], 2);
+        assert_eq!(
+            PartiallySignedTransaction::decode_all(&mut encoded.as_slice()).unwrap(),
+            ptx
+        );
+    }
+
+    #[test]
+    fn decode_legacy_encoding() {
+        let tx = make_tx(2);
+        let legacy = PartiallySignedTransactionV1 {
+            tx: match tx.clone() {
+                Transaction::V1(tx) => tx,
+            },
+            witnesses: vec![None; 2],
+            input_utxos: vec![None; 2],
+            destinations: vec![None; 2],
+            htlc_secrets: vec![None; 2],
+        };
+
+        let decoded =
+            PartiallySignedTransaction::decode_all(&mut legacy.encode().as_slice()).unwrap();
+        assert_eq!(decoded.tx(), &tx);
+        assert_eq!(decoded.sighash_types(), &[SigHashType::default(); 2]);
+    }
+
+    #[test]
+    fn decode_rejects_mismatched_sighash_types() {
+        let encoded = PartiallySignedTransactionV2 {
+            version: VersionTag::default(),
+            tx: make_tx(2),
+            witnesses: vec![None; 2],
+            input_utxos: vec![None; 2],
+            destinations: vec![None; 2],
+            htlc_secrets: vec![None; 2],
+            sighash_types: vec![SigHashType::default()],
+        }
+        .encode();
+
+        assert!(PartiallySignedTransaction::decode_all(&mut encoded.as_slice()).is_err());
+    }
+}
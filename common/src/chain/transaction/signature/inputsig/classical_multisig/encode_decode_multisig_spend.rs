@@ -32,6 +32,7 @@ use super::authorize_classical_multisig::AuthorizedClassicalMultisigSpend;
 pub fn encode_multisig_spend(
     sig_component: &AuthorizedClassicalMultisigSpend,
     utxo: Option<&TxOutput>,
+    sighash_type: SigHashType,
 ) -> StandardInputSignature {
     let raw_signature = match utxo {
         Some(utxo) => {
@@ -44,7 +45,6 @@ pub fn encode_multisig_spend(
         None => sig_component.encode(),
     };
 
-    let sighash_type = SigHashType::try_from(SigHashType::ALL).expect("Should not fail");
     StandardInputSignature::new(sighash_type, raw_signature)
 }
 
@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use serialization::Encode;
+use serialization::{Decode, Encode};
 
 use super::DestinationSigError;
 
@@ -65,6 +65,13 @@ impl TryFrom<u8> for SigHashType {
     }
 }
 
+impl Decode for SigHashType {
+    fn decode<I: serialization::Input>(input: &mut I) -> Result<Self, serialization::Error> {
+        let sighash_byte = input.read_byte()?;
+        Self::try_from(sighash_byte).map_err(|_| serialization::Error::from("Invalid sighash byte"))
+    }
+}
+
 /// How inputs should be hashed
 #[derive(PartialEq, Eq, Debug)]
 pub enum InputsMode {
@@ -125,6 +132,15 @@ mod test {
         assert_eq!(sighash_type.inputs_mode(), InputsMode::AnyoneCanPay);
         assert_eq!(sighash_type.outputs_mode(), OutputsMode::Single);
 
+        // Check decoding
+        let sighash_type =
+            SigHashType::try_from(SigHashType::SINGLE | SigHashType::ANYONECANPAY).unwrap();
+        assert_eq!(
+            SigHashType::decode(&mut sighash_type.encode().as_slice()).unwrap(),
+            sighash_type
+        );
+        assert!(SigHashType::decode(&mut [SigHashType::ANYONECANPAY].as_slice()).is_err());
+
         // Check try from
         assert_eq!(
             SigHashType::try_from(0),
@@ -65,13 +65,13 @@ pub enum ChangeTokenMetadataUriActivated {
 
 #[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd)]
 pub struct ChainstateUpgrade {
-    token_issuance_version: TokenIssuanceVersion,
-    reward_distribution_version: RewardDistributionVersion,
-    tokens_fee_version: TokensFeeVersion,
-    data_deposit_fee_version: DataDepositFeeVersion,
-    change_token_metadata_uri_activated: ChangeTokenMetadataUriActivated,
-    htlc_activated: HtlcActivated,
-    orders_activated: OrdersActivated,
+    pub(super) token_issuance_version: TokenIssuanceVersion,
+    pub(super) reward_distribution_version: RewardDistributionVersion,
+    pub(super) tokens_fee_version: TokensFeeVersion,
+    pub(super) data_deposit_fee_version: DataDepositFeeVersion,
+    pub(super) change_token_metadata_uri_activated: ChangeTokenMetadataUriActivated,
+    pub(super) htlc_activated: HtlcActivated,
+    pub(super) orders_activated: OrdersActivated,
 }
 
 impl ChainstateUpgrade {
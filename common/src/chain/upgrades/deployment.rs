@@ -0,0 +1,228 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{chain::PoSConsensusVersion, primitives::BlockHeight};
+
+use super::{
+    ChainstateUpgrade, ChangeTokenMetadataUriActivated, ConsensusUpgrade, DataDepositFeeVersion,
+    HtlcActivated, NetUpgrades, OrdersActivated, RewardDistributionVersion, TokenIssuanceVersion,
+    TokensFeeVersion,
+};
+
+/// The state of a deployment, as seen by the block following the current tip.
+///
+/// Upgrades are activated at the heights defined in the chain config and blocks don't signal
+/// support for them, so the state depends only on the height of the chain:
+/// a scheduled upgrade is `Started` while it's more than `lock_in_period` blocks away
+/// from the activation, `LockedIn` after that and `Active` starting from the activation height.
+/// The states are informational only and don't affect block validation.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+    rpc_description::HasValueHint,
+)]
+pub enum DeploymentState {
+    /// The upgrade is known, but its activation is not scheduled on this chain
+    Defined,
+    /// The activation height is scheduled
+    Started,
+    /// The activation height is at most `lock_in_period` blocks away, so nodes that don't support
+    /// the upgrade will stop following the chain soon. Unlike the BIP9 state of the same name,
+    /// nothing is locked: the activation height is still defined only by the chain config.
+    LockedIn,
+    /// The new rules are in effect
+    Active,
+}
+
+/// A consensus upgrade that is activated at a specific height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deployment {
+    name: &'static str,
+    activation_height: Option<BlockHeight>,
+}
+
+impl Deployment {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn activation_height(&self) -> Option<BlockHeight> {
+        self.activation_height
+    }
+
+    /// The state of the deployment for the block at the specified height
+    pub fn state(&self, height: BlockHeight, lock_in_period: u64) -> DeploymentState {
+        match self.activation_height {
+            None => DeploymentState::Defined,
+            Some(activation_height) if height >= activation_height => DeploymentState::Active,
+            Some(activation_height) => {
+                let remaining = activation_height.into_int() - height.into_int();
+                if remaining <= lock_in_period {
+                    DeploymentState::LockedIn
+                } else {
+                    DeploymentState::Started
+                }
+            }
+        }
+    }
+}
+
+fn first_activation<T>(
+    upgrades: &NetUpgrades<T>,
+    is_activated: impl Fn(&T) -> bool,
+) -> Option<BlockHeight> {
+    upgrades
+        .all_upgrades()
+        .iter()
+        .find(|(_, upgrade)| is_activated(upgrade))
+        .map(|(height, _)| *height)
+}
+
+/// The names of the deployments defined by a chainstate upgrade, along with whether the upgrade
+/// activates each of them
+fn chainstate_upgrade_activations(upgrade: &ChainstateUpgrade) -> [(&'static str, bool); 7] {
+    // The upgrade is destructured exhaustively, so that a new field can't be added to it
+    // without defining the corresponding deployment
+    let ChainstateUpgrade {
+        token_issuance_version,
+        reward_distribution_version,
+        tokens_fee_version,
+        data_deposit_fee_version,
+        change_token_metadata_uri_activated,
+        htlc_activated,
+        orders_activated,
+    } = upgrade;
+
+    [
+        (
+            "token_issuance_v1",
+            *token_issuance_version == TokenIssuanceVersion::V1,
+        ),
+        (
+            "reward_distribution_v1",
+            *reward_distribution_version == RewardDistributionVersion::V1,
+        ),
+        ("tokens_fee_v1", *tokens_fee_version == TokensFeeVersion::V1),
+        (
+            "data_deposit_fee_v1",
+            *data_deposit_fee_version == DataDepositFeeVersion::V1,
+        ),
+        (
+            "change_token_metadata_uri",
+            *change_token_metadata_uri_activated == ChangeTokenMetadataUriActivated::Yes,
+        ),
+        ("htlc", *htlc_activated == HtlcActivated::Yes),
+        ("orders", *orders_activated == OrdersActivated::Yes),
+    ]
+}
+
+/// Collect the deployments of all the known upgrades from the net upgrades of the chain
+pub fn deployments(
+    chainstate_upgrades: &NetUpgrades<ChainstateUpgrade>,
+    consensus_upgrades: &NetUpgrades<ConsensusUpgrade>,
+) -> Vec<Deployment> {
+    let pos_version = |upgrade: &ConsensusUpgrade| match upgrade {
+        ConsensusUpgrade::PoS { config, .. } => Some(config.consensus_version()),
+        ConsensusUpgrade::PoW { .. } | ConsensusUpgrade::IgnoreConsensus => None,
+    };
+
+    let mut result = vec![
+        Deployment {
+            name: "pos",
+            activation_height: first_activation(consensus_upgrades, |upgrade| {
+                pos_version(upgrade).is_some()
+            }),
+        },
+        Deployment {
+            name: "pos_v1",
+            activation_height: first_activation(consensus_upgrades, |upgrade| {
+                pos_version(upgrade).is_some_and(|version| version >= PoSConsensusVersion::V1)
+            }),
+        },
+    ];
+
+    // The names are the same for all the upgrades, so they are taken from the first one
+    if let Some((_, first_upgrade)) = chainstate_upgrades.all_upgrades().first() {
+        let chainstate_deployments = chainstate_upgrade_activations(first_upgrade)
+            .into_iter()
+            .enumerate()
+            .map(|(index, (name, _))| Deployment {
+                name,
+                activation_height: first_activation(chainstate_upgrades, |upgrade| {
+                    chainstate_upgrade_activations(upgrade)[index].1
+                }),
+            });
+        result.extend(chainstate_deployments);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deployment_state() {
+        let deployment = Deployment {
+            name: "test",
+            activation_height: Some(BlockHeight::new(100)),
+        };
+        let state = |height| deployment.state(BlockHeight::new(height), 10);
+        assert_eq!(state(0), DeploymentState::Started);
+        assert_eq!(state(89), DeploymentState::Started);
+        assert_eq!(state(90), DeploymentState::LockedIn);
+        assert_eq!(state(99), DeploymentState::LockedIn);
+        assert_eq!(state(100), DeploymentState::Active);
+        assert_eq!(state(1000), DeploymentState::Active);
+
+        let deployment = Deployment {
+            name: "test",
+            activation_height: None,
+        };
+        assert_eq!(
+            deployment.state(BlockHeight::new(100), 10),
+            DeploymentState::Defined
+        );
+    }
+
+    #[test]
+    fn config_deployments() {
+        let find = |deployments: &[Deployment], name| {
+            deployments.iter().find(|d| d.name() == name).unwrap().activation_height()
+        };
+
+        let mainnet = crate::chain::config::create_mainnet().deployments();
+        assert_eq!(find(&mainnet, "pos"), Some(BlockHeight::one()));
+        assert_eq!(find(&mainnet, "pos_v1"), Some(BlockHeight::one()));
+        assert_eq!(
+            find(&mainnet, "token_issuance_v1"),
+            Some(BlockHeight::zero())
+        );
+        assert!(mainnet.iter().all(|d| d.activation_height().is_some()));
+
+        // Regtest uses PoW by default
+        let regtest = crate::chain::config::create_regtest().deployments();
+        assert_eq!(find(&regtest, "pos"), None);
+        assert_eq!(find(&regtest, "htlc"), Some(BlockHeight::zero()));
+    }
+}
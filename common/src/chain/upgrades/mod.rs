@@ -15,6 +15,7 @@
 
 mod chainstate_upgrade;
 mod consensus_upgrade;
+mod deployment;
 mod netupgrade;
 
 pub use chainstate_upgrade::{
@@ -22,6 +23,7 @@ pub use chainstate_upgrade::{
     OrdersActivated, RewardDistributionVersion, TokenIssuanceVersion, TokensFeeVersion,
 };
 pub use consensus_upgrade::{ConsensusUpgrade, PoSStatus, PoWStatus, RequiredConsensus};
+pub use deployment::{deployments, Deployment, DeploymentState};
 pub use netupgrade::{Activate, NetUpgrades};
 
 pub enum NetUpgradeError {
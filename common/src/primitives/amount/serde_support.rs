@@ -116,6 +116,7 @@ impl HasValueHint for RpcAmountInSerde {
 rpc_description::impl_value_hint!({
     AtomsInner => VH::NUMBER_STRING;
     DecimalInner => VH::DECIMAL_STRING;
+    DecimalAmount => VH::DECIMAL_STRING;
     Amount => AmountSerde::HINT_SER;
     super::RpcAmountIn => RpcAmountInSerde::HINT_SER;
     super::RpcAmountOut => RpcAmountOutSerde::HINT_SER;
@@ -18,6 +18,7 @@ use std::time::Duration;
 use common::{
     chain::ChainConfig,
     primitives::{
+        per_thousand::PerThousand,
         semver::SemVer,
         time::Time,
         user_agent::{mintlayer_core_user_agent, UserAgent},
@@ -63,12 +64,77 @@ pub const PURGE_UNREACHABLE_TIME: Duration = Duration::from_secs(3600);
 /// Such a long time is useful if the server itself has prolonged connectivity problems.
 pub const PURGE_REACHABLE_FAIL_COUNT: u32 = 35;
 
+/// Statistics about the availability of a node, collected since it was first reachable.
+#[derive(Debug, Clone, Copy, Encode, Decode, Eq, PartialEq)]
+pub struct UptimeStats {
+    /// The time when the node was first reachable (as duration since unix epoch).
+    pub first_seen_time: Duration,
+    /// Total time the node was connected, not including the current connection.
+    pub total_connected_time: Duration,
+    /// The number of successful connection attempts.
+    pub successful_connections: u32,
+    /// The number of failed connection attempts.
+    pub failed_connections: u32,
+}
+
+impl UptimeStats {
+    pub fn new(first_seen_time: Time) -> Self {
+        Self {
+            first_seen_time: first_seen_time.as_duration_since_epoch(),
+            total_connected_time: Duration::ZERO,
+            successful_connections: 0,
+            failed_connections: 0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ConnectionInfo {
     /// Peer's software info
     pub peer_software_info: SoftwareInfo,
     /// Last time we've requested addresses from this peer.
     pub last_addr_list_request_time: Option<Time>,
+    /// Availability statistics of the peer.
+    pub uptime_stats: UptimeStats,
+    /// When the current connection was established; `None` if the peer is not connected.
+    pub connected_since: Option<Time>,
+}
+
+impl ConnectionInfo {
+    /// Total time the peer has been connected, including the current connection.
+    pub fn total_connected_time(&self, now: Time) -> Duration {
+        let current = self
+            .connected_since
+            .and_then(|connected_since| now - connected_since)
+            .unwrap_or(Duration::ZERO);
+        self.uptime_stats.total_connected_time + current
+    }
+
+    /// The share of time the peer has been connected since it was first reachable, in 0..=1 range.
+    #[allow(clippy::float_arithmetic)]
+    pub fn uptime_ratio(&self, now: Time) -> f64 {
+        let first_seen = Time::from_duration_since_epoch(self.uptime_stats.first_seen_time);
+        match now - first_seen {
+            Some(observed) if !observed.is_zero() => {
+                (self.total_connected_time(now).as_secs_f64() / observed.as_secs_f64()).min(1.0)
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Same as `uptime_ratio`, rounded to a `PerThousand`.
+    pub fn uptime(&self, now: Time) -> PerThousand {
+        #[allow(clippy::float_arithmetic)]
+        let value = (self.uptime_ratio(now) * 1000.0).round() as u16;
+        PerThousand::new(value).expect("The uptime ratio must be in 0..=1 range")
+    }
+
+    /// Close the current connection, if any, adding its duration to the statistics.
+    fn close_connection(mut self, now: Time) -> Self {
+        self.uptime_stats.total_connected_time = self.total_connected_time(now);
+        self.connected_since = None;
+        self
+    }
 }
 
 /// Connection state of a potential node address (outbound only)
@@ -347,10 +413,18 @@ impl AddressData {
                         .and_then(|conn_info| conn_info.last_addr_list_request_time)
                 };
 
+                let mut uptime_stats = self
+                    .state
+                    .connection_info()
+                    .map_or_else(|| UptimeStats::new(now), |info| info.uptime_stats);
+                uptime_stats.successful_connections += 1;
+
                 self.state = AddressState::Connected {
                     connection_info: ConnectionInfo {
                         peer_software_info,
                         last_addr_list_request_time,
+                        uptime_stats,
+                        connected_since: Some(now),
                     },
                 };
             }
@@ -363,7 +437,10 @@ impl AddressData {
 
                 self.state = AddressState::Disconnecting {
                     fail_count: self.state.fail_count(),
-                    last_connection_info: self.state.connection_info().cloned(),
+                    last_connection_info: self
+                        .state
+                        .connection_info()
+                        .map(|info| info.clone().close_connection(now)),
                 };
             }
 
@@ -374,23 +451,32 @@ impl AddressData {
                         || matches!(self.state, AddressState::Disconnecting { .. })
                 );
 
+                let connection_failed = matches!(self.state, AddressState::Connecting { .. });
+                let last_connection_info = self.state.connection_info().map(|info| {
+                    let mut info = info.clone().close_connection(now);
+                    if connection_failed {
+                        info.uptime_stats.failed_connections += 1;
+                    }
+                    info
+                });
+
                 self.state = match (*self.reserved, self.state.was_reachable()) {
                     (false, true) if self.state.fail_count() + 1 >= PURGE_REACHABLE_FAIL_COUNT => {
                         AddressState::Unreachable {
                             fail_count: self.state.fail_count() + 1,
-                            last_connection_info: self.state.connection_info().cloned(),
+                            last_connection_info,
                             erase_after: now,
                         }
                     }
                     (false, false) => AddressState::Unreachable {
                         fail_count: self.state.fail_count() + 1,
-                        last_connection_info: self.state.connection_info().cloned(),
+                        last_connection_info,
                         erase_after: (now + PURGE_UNREACHABLE_TIME)
                             .expect("All from local clocks; cannot fail"),
                     },
                     _ => AddressState::Disconnected {
                         fail_count: self.state.fail_count() + 1,
-                        last_connection_info: self.state.connection_info().cloned(),
+                        last_connection_info,
                         disconnected_at: now,
                     },
                 };
@@ -159,6 +159,8 @@ pub enum CrawlerCommand {
         address: SocketAddress,
         old_state: AddressState,
         new_state: AddressState,
+        /// The time of the update
+        now: Time,
     },
     MarkAsBanned {
         address: BannableAddress,
@@ -192,6 +194,8 @@ impl Crawler {
                                     last_addr_list_request_time: addr_info
                                         .last_addr_list_request_time
                                         .map(Time::from_duration_since_epoch),
+                                    uptime_stats: addr_info.uptime_stats,
+                                    connected_since: None,
                                 }),
                                 disconnected_at: now,
                             },
@@ -418,6 +422,7 @@ impl Crawler {
             address: *address,
             old_state,
             new_state: address_data.state.clone(),
+            now,
         });
     }
 
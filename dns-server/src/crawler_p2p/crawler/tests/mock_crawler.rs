@@ -165,6 +165,7 @@ impl MockCrawler {
                 address,
                 old_state,
                 new_state,
+                now: _,
             } => {
                 match (old_state.is_reachable(), new_state.is_reachable()) {
                     (false, true) => {
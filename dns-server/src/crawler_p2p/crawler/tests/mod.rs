@@ -45,7 +45,10 @@ use mock_crawler::test_crawler;
 
 use crate::crawler_p2p::{crawler::CrawlerEvent, crawler_manager::storage::AddressInfo};
 
-use super::{address_data::SoftwareInfo, CrawlerConfig};
+use super::{
+    address_data::{SoftwareInfo, UptimeStats},
+    CrawlerConfig,
+};
 
 #[rstest]
 #[trace]
@@ -103,6 +106,85 @@ fn basic(#[case] seed: Seed) {
     assert!(crawler.reachable.contains(&node2));
 }
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn uptime_stats(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let node1: SocketAddress = "1.2.3.4:3031".parse().unwrap();
+    let peer1 = PeerId::new();
+    let chain_config = common::chain::config::create_mainnet();
+    let mut crawler = test_crawler(
+        make_config(),
+        BTreeMap::new(),
+        BTreeMap::new(),
+        [node1].into_iter().collect(),
+        Time::from_duration_since_epoch(Duration::ZERO),
+    );
+
+    crawler.timer(Duration::from_secs(100), &mut rng);
+    assert!(crawler.pending_connects.contains(&node1));
+    let first_seen = crawler.now();
+
+    crawler.step(
+        CrawlerEvent::Connected {
+            address: node1,
+            peer_info: make_peer_info(peer1, &chain_config),
+        },
+        &mut rng,
+    );
+
+    crawler.timer(Duration::from_secs(1000), &mut rng);
+    crawler.step(CrawlerEvent::Disconnected { peer_id: peer1 }, &mut rng);
+
+    let connection_info = crawler
+        .address_updates
+        .last()
+        .unwrap()
+        .new_state
+        .connection_info()
+        .cloned()
+        .unwrap();
+    assert_eq!(connection_info.connected_since, None);
+    assert_eq!(
+        connection_info.uptime_stats,
+        UptimeStats {
+            first_seen_time: first_seen.as_duration_since_epoch(),
+            total_connected_time: Duration::from_secs(1000),
+            successful_connections: 1,
+            failed_connections: 0,
+        }
+    );
+    assert_eq!(connection_info.uptime_ratio(crawler.now()), 1.0);
+
+    // The reconnection attempt fails
+    crawler.timer(Duration::from_secs(100), &mut rng);
+    assert!(crawler.pending_connects.contains(&node1));
+    crawler.step(
+        CrawlerEvent::ConnectionError {
+            address: node1,
+            error: P2pError::DialError(DialError::ConnectionRefusedOrTimedOut),
+        },
+        &mut rng,
+    );
+
+    let connection_info = crawler
+        .address_updates
+        .last()
+        .unwrap()
+        .new_state
+        .connection_info()
+        .cloned()
+        .unwrap();
+    assert_eq!(connection_info.uptime_stats.successful_connections, 1);
+    assert_eq!(connection_info.uptime_stats.failed_connections, 1);
+    assert_eq!(
+        connection_info.total_connected_time(crawler.now()),
+        Duration::from_secs(1000)
+    );
+    assert_eq!(connection_info.uptime_ratio(crawler.now()), 1000.0 / 1100.0);
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -137,6 +219,7 @@ fn addr_list_requests(#[case] seed: Seed) {
                 AddressInfo {
                     software_info: SoftwareInfo::current(&chain_config),
                     last_addr_list_request_time: Some(Duration::ZERO),
+                    uptime_stats: UptimeStats::new(Time::from_duration_since_epoch(Duration::ZERO)),
                 },
             ),
             (
@@ -144,6 +227,7 @@ fn addr_list_requests(#[case] seed: Seed) {
                 AddressInfo {
                     software_info: SoftwareInfo::current(&chain_config),
                     last_addr_list_request_time: Some(addr_list_request_interval / 2),
+                    uptime_stats: UptimeStats::new(Time::from_duration_since_epoch(Duration::ZERO)),
                 },
             ),
         ]
@@ -278,6 +362,7 @@ fn randomized(#[case] seed: Seed) {
                 AddressInfo {
                     software_info: SoftwareInfo::current(&chain_config),
                     last_addr_list_request_time: None,
+                    uptime_stats: UptimeStats::new(Time::from_duration_since_epoch(Duration::ZERO)),
                 },
             )
         })
@@ -408,6 +493,7 @@ fn long_offline(#[case] seed: Seed) {
             AddressInfo {
                 software_info: SoftwareInfo::current(&chain_config),
                 last_addr_list_request_time: None,
+                uptime_stats: UptimeStats::new(Time::from_duration_since_epoch(Duration::ZERO)),
             },
         )]
         .into_iter()
@@ -30,8 +30,8 @@ use logging::log;
 use p2p::{
     error::{P2pError, ProtocolError},
     message::{
-        AddrListRequest, AddrListResponse, AnnounceAddrRequest, PeerManagerMessage, PingRequest,
-        PingResponse,
+        AddrListRequest, AddrListResponse, AddrListResponseV2, AnnounceAddrRequest,
+        AnnounceAddrV2Request, PeerManagerMessage, PingRequest, PingResponse,
     },
     net::{
         types::{ConnectivityEvent, SyncingEvent},
@@ -63,7 +63,10 @@ use super::crawler::{Crawler, CrawlerCommand, CrawlerConfig, CrawlerEvent};
 /// How often the server performs maintenance (tries to connect to new nodes)
 const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
 
-const CURRENT_STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+const CURRENT_STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+/// The previous storage version, without the uptime statistics; it's upgraded on load
+const STORAGE_VERSION_V2: StorageVersion = StorageVersion::new(2);
 
 #[derive(Clone)]
 pub struct CrawlerManagerConfig {
@@ -135,7 +138,7 @@ where
         let last_crawler_timer = time_getter.get_time();
 
         // Addresses that are stored in the DB as reachable
-        let loaded_storage = Self::load_storage(&storage)?;
+        let loaded_storage = Self::load_storage(&storage, last_crawler_timer)?;
 
         // Addresses listed as reachable from the command line
         let reserved_addresses: BTreeSet<SocketAddress> = config
@@ -174,7 +177,7 @@ where
         })
     }
 
-    fn load_storage(storage: &S) -> crate::Result<LoadedStorage> {
+    fn load_storage(storage: &S, now: Time) -> crate::Result<LoadedStorage> {
         let tx = storage.transaction_ro()?;
         let version = tx.get_version()?;
         tx.close();
@@ -182,6 +185,10 @@ where
         match version {
             None => Self::init_storage(storage),
             Some(CURRENT_STORAGE_VERSION) => Self::load_storage_impl(storage),
+            Some(STORAGE_VERSION_V2) => {
+                Self::upgrade_storage_from_v2(storage, now)?;
+                Self::load_storage_impl(storage)
+            }
             Some(version) => Err(DnsServerError::StorageVersionMismatch {
                 expected_version: CURRENT_STORAGE_VERSION,
                 actual_version: version,
@@ -196,6 +203,19 @@ where
         Ok(LoadedStorage::new())
     }
 
+    /// The uptime statistics of the stored addresses are started from scratch.
+    fn upgrade_storage_from_v2(storage: &S, now: Time) -> crate::Result<()> {
+        log::info!(
+            "Upgrading the storage from version {STORAGE_VERSION_V2} to {CURRENT_STORAGE_VERSION}"
+        );
+
+        let mut tx = storage.transaction_rw()?;
+        tx.upgrade_addresses_from_v2(now)?;
+        tx.set_version(CURRENT_STORAGE_VERSION)?;
+        tx.commit()?;
+        Ok(())
+    }
+
     fn load_storage_impl(storage: &S) -> crate::Result<LoadedStorage> {
         let tx = storage.transaction_ro()?;
         let known_addresses = tx.get_addresses()?.into_iter().collect::<BTreeMap<_, _>>();
@@ -219,18 +239,14 @@ where
                 Ok(())
             }
             PeerManagerMessage::AnnounceAddrRequest(AnnounceAddrRequest { address }) => {
-                log::debug!(
-                    "Got address announcement from peer {} ({})",
-                    peer_id,
-                    address.to_string()
-                );
-                if let Some(address) = address.as_discoverable_socket_address(false) {
-                    self.send_crawler_event(CrawlerEvent::AddressAnnouncement {
-                        address,
-                        sender: peer_id,
-                    });
+                self.handle_address_announcement(peer_id, address);
+                Ok(())
+            }
+            PeerManagerMessage::AnnounceAddrV2Request(AnnounceAddrV2Request { entry }) => {
+                // Onion addresses can't be crawled
+                if let Some(address) = entry.address.as_peer_address() {
+                    self.handle_address_announcement(peer_id, address);
                 }
-
                 Ok(())
             }
             PeerManagerMessage::PingRequest(PingRequest { nonce }) => {
@@ -244,28 +260,19 @@ where
                 Ok(())
             }
             PeerManagerMessage::AddrListResponse(AddrListResponse { addresses }) => {
-                log::debug!(
-                    "Got address list response from peer {}, address count = ({})",
-                    peer_id,
-                    addresses.len()
-                );
-
+                self.handle_address_list_response(peer_id, addresses)
+            }
+            PeerManagerMessage::AddrListResponseV2(AddrListResponseV2 { entries }) => {
                 ensure!(
-                    addresses.len() <= *p2p::protocol::MaxAddrListResponseAddressCount::default(),
+                    entries.len() <= *p2p::protocol::MaxAddrListResponseAddressCount::default(),
                     P2pError::ProtocolError(ProtocolError::AddressListLimitExceeded)
                 );
 
-                let addresses = addresses
-                    .iter()
-                    .filter_map(|addr| addr.as_discoverable_socket_address(false))
+                let addresses = entries
+                    .into_iter()
+                    .filter_map(|entry| entry.address.as_peer_address())
                     .collect::<Vec<_>>();
-
-                self.send_crawler_event(CrawlerEvent::AddressListResponse {
-                    addresses,
-                    sender: peer_id,
-                });
-
-                Ok(())
+                self.handle_address_list_response(peer_id, addresses)
             }
             PeerManagerMessage::PingResponse(_) => Ok(()),
 
@@ -281,6 +288,49 @@ where
         }
     }
 
+    fn handle_address_announcement(&mut self, peer_id: PeerId, address: PeerAddress) {
+        log::debug!(
+            "Got address announcement from peer {} ({})",
+            peer_id,
+            address.to_string()
+        );
+        if let Some(address) = address.as_discoverable_socket_address(false) {
+            self.send_crawler_event(CrawlerEvent::AddressAnnouncement {
+                address,
+                sender: peer_id,
+            });
+        }
+    }
+
+    fn handle_address_list_response(
+        &mut self,
+        peer_id: PeerId,
+        addresses: Vec<PeerAddress>,
+    ) -> p2p::Result<()> {
+        log::debug!(
+            "Got address list response from peer {}, address count = ({})",
+            peer_id,
+            addresses.len()
+        );
+
+        ensure!(
+            addresses.len() <= *p2p::protocol::MaxAddrListResponseAddressCount::default(),
+            P2pError::ProtocolError(ProtocolError::AddressListLimitExceeded)
+        );
+
+        let addresses = addresses
+            .iter()
+            .filter_map(|addr| addr.as_discoverable_socket_address(false))
+            .collect::<Vec<_>>();
+
+        self.send_crawler_event(CrawlerEvent::AddressListResponse {
+            addresses,
+            sender: peer_id,
+        });
+
+        Ok(())
+    }
+
     fn handle_conn_event(&mut self, event: ConnectivityEvent) {
         match event {
             ConnectivityEvent::Message { peer_id, message } => {
@@ -399,6 +449,7 @@ where
                 address,
                 old_state,
                 new_state,
+                now,
             } => {
                 let was_reachable = old_state.is_reachable();
                 let is_reachable = new_state.is_reachable();
@@ -422,6 +473,7 @@ where
                             .send(DnsServerCommand::AddAddress(
                                 ip,
                                 connection_info.peer_software_info.clone(),
+                                connection_info.uptime(now),
                             ))
                             .expect("sending must succeed (AddAddress)");
                     }
@@ -445,6 +497,7 @@ where
                                 last_addr_list_request_time: connection_info
                                     .last_addr_list_request_time
                                     .map(|time| time.as_duration_since_epoch()),
+                                uptime_stats: connection_info.uptime_stats,
                             },
                         )
                     })
@@ -501,7 +554,7 @@ where
 
     #[cfg(test)]
     pub fn load_storage_for_tests(&self) -> crate::Result<LoadedStorage> {
-        Self::load_storage(&self.storage)
+        Self::load_storage(&self.storage, self.time_getter.get_time())
     }
 }
 
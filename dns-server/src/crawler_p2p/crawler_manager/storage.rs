@@ -22,9 +22,12 @@ use p2p::{
 };
 use serialization::{Decode, Encode};
 
-use crate::{crawler_p2p::crawler::address_data::SoftwareInfo, error::DnsServerError};
+use crate::{
+    crawler_p2p::crawler::address_data::{SoftwareInfo, UptimeStats},
+    error::DnsServerError,
+};
 
-use super::{storage_impl::DnsServerStorageImpl, CURRENT_STORAGE_VERSION};
+use super::{storage_impl::DnsServerStorageImpl, CURRENT_STORAGE_VERSION, STORAGE_VERSION_V2};
 
 #[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
 pub struct AddressInfo {
@@ -32,6 +35,8 @@ pub struct AddressInfo {
     pub software_info: SoftwareInfo,
     /// Last time we've requested addresses from this peer (as duration since unix epoch).
     pub last_addr_list_request_time: Option<Duration>,
+    /// Availability statistics of the peer.
+    pub uptime_stats: UptimeStats,
 }
 
 pub trait DnsServerStorageRead {
@@ -52,6 +57,10 @@ pub trait DnsServerStorageWrite {
     fn add_banned_address(&mut self, address: &BannableAddress, time: Time) -> crate::Result<()>;
 
     fn del_banned_address(&mut self, address: &BannableAddress) -> crate::Result<()>;
+
+    /// Convert the stored addresses from the format of the storage version 2, which didn't
+    /// have the uptime statistics; the statistics are started at `now`.
+    fn upgrade_addresses_from_v2(&mut self, now: Time) -> crate::Result<()>;
 }
 
 // Note: here we want to say something like:
@@ -87,7 +96,8 @@ where
     let version = storage.transaction_ro()?.get_version()?;
 
     match version {
-        None | Some(CURRENT_STORAGE_VERSION) => Ok(storage),
+        // The addresses are upgraded from version 2 when the storage is loaded
+        None | Some(CURRENT_STORAGE_VERSION) | Some(STORAGE_VERSION_V2) => Ok(storage),
         Some(version) => Err(DnsServerError::StorageVersionMismatch {
             expected_version: CURRENT_STORAGE_VERSION,
             actual_version: version,
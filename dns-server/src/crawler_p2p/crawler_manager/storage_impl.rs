@@ -15,7 +15,10 @@
 
 use std::time::Duration;
 
-use crate::error::DnsServerError;
+use crate::{
+    crawler_p2p::crawler::address_data::{SoftwareInfo, UptimeStats},
+    error::DnsServerError,
+};
 
 use super::storage::{AddressInfo, DnsServerStorage, DnsServerStorageRead, DnsServerStorageWrite};
 use common::primitives::time::Time;
@@ -26,7 +29,7 @@ use p2p::{
     },
     types::{bannable_address::BannableAddress, socket_address::SocketAddress},
 };
-use serialization::{encoded::Encoded, DecodeAll, Encode};
+use serialization::{encoded::Encoded, Decode, DecodeAll, Encode};
 use storage::MakeMapRef;
 
 type ValueId = u32;
@@ -47,6 +50,17 @@ storage::decl_schema! {
 
 const VALUE_ID_VERSION: ValueId = 1;
 
+/// `AddressInfo` as it was stored by the storage version 2
+#[derive(Decode)]
+#[cfg_attr(test, derive(Encode))]
+pub(super) struct AddressInfoV2 {
+    pub(super) software_info: SoftwareInfo,
+    pub(super) last_addr_list_request_time: Option<Duration>,
+}
+
+#[cfg(test)]
+impl serialization::EncodeLike<AddressInfo> for AddressInfoV2 {}
+
 type DnsServerStoreTxRo<'st, B> = StorageTxRo<'st, B, Schema>;
 type DnsServerStoreTxRw<'st, B> = StorageTxRw<'st, B, Schema>;
 
@@ -77,6 +91,33 @@ impl<'st, B: storage::Backend> DnsServerStorageWrite for DnsServerStoreTxRw<'st,
     fn del_banned_address(&mut self, address: &BannableAddress) -> crate::Result<()> {
         Ok(self.storage().get_mut::<DBBannedAddresses, _>().del(address.to_string())?)
     }
+
+    fn upgrade_addresses_from_v2(&mut self, now: Time) -> crate::Result<()> {
+        let mut map = self.storage().get_mut::<DBAddresses, _>();
+
+        let old_entries = map
+            .prefix_iter(&())?
+            .map(|(addr_str, info)| {
+                let info = AddressInfoV2::decode_all(&mut info.bytes()).map_err(|err| {
+                    DnsServerError::InvalidStorageState(format!(
+                        "Error decoding version 2 address info for {addr_str:?}: {err}"
+                    ))
+                })?;
+                Ok((addr_str, info))
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        for (addr_str, old_info) in old_entries {
+            let info = AddressInfo {
+                software_info: old_info.software_info,
+                last_addr_list_request_time: old_info.last_addr_list_request_time,
+                uptime_stats: UptimeStats::new(now),
+            };
+            map.put(addr_str, info)?;
+        }
+
+        Ok(())
+    }
 }
 
 impl<'st, B: storage::Backend> DnsServerStorageRead for DnsServerStoreTxRo<'st, B> {
@@ -31,7 +31,7 @@ use tokio::{
 };
 
 use common::{chain::ChainConfig, primitives::time::Time, time_getter::TimeGetter};
-use networking::test_helpers::TestAddressMaker;
+use networking::{test_helpers::TestAddressMaker, types::TransportProtocol};
 use p2p::{
     config::{NodeType, P2pConfig},
     disconnection_reason::DisconnectionReason,
@@ -160,6 +160,8 @@ impl NetworkingService for MockNetworkingService {
     type MessagingHandle = ();
     type SyncingEventReceiver = MockSyncingEventReceiver;
 
+    const TRANSPORT_PROTOCOL: TransportProtocol = TransportProtocol::Tcp;
+
     async fn start(
         _networking_enabled: bool,
         _transport: Self::Transport,
@@ -15,12 +15,13 @@
 
 mod mock_manager;
 
-use std::time::Duration;
+use std::{collections::BTreeMap, net::IpAddr, time::Duration};
 
 use chainstate::ban_score::BanScore;
-use common::primitives::semver::SemVer;
+use common::primitives::{per_thousand::PerThousand, semver::SemVer, time::Time};
 use p2p::{
     error::{P2pError, ProtocolError},
+    peer_manager::peerdb_common::{TransactionRo, TransactionRw, Transactional},
     types::socket_address::SocketAddress,
 };
 use p2p_test_utils::{expect_no_recv, expect_recv};
@@ -29,15 +30,39 @@ use test_utils::random::{make_seedable_rng, Seed};
 
 use crate::{
     crawler_p2p::{
-        crawler::{address_data::SoftwareInfo, BanDuration, BanThreshold},
-        crawler_manager::tests::mock_manager::{
-            advance_time, assert_banned_addresses, assert_known_addresses, test_crawler,
-            ErraticNodeConnectError,
+        crawler::{
+            address_data::{SoftwareInfo, UptimeStats},
+            BanDuration, BanThreshold,
+        },
+        crawler_manager::{
+            storage::{AddressInfo, DnsServerStorageRead, DnsServerStorageWrite},
+            storage_impl::{AddressInfoV2, DBAddresses, DnsServerStorageImpl},
+            tests::mock_manager::{
+                advance_time, assert_banned_addresses, assert_known_addresses, test_crawler,
+                ErraticNodeConnectError, MockNetworkingService,
+            },
+            CrawlerManager, CURRENT_STORAGE_VERSION, STORAGE_VERSION_V2,
         },
     },
     dns_server::DnsServerCommand,
 };
 
+/// Check that the command adds the address to DNS and return the uptime passed with it.
+fn assert_address_added(
+    command: DnsServerCommand,
+    ip: IpAddr,
+    software_info: &SoftwareInfo,
+) -> PerThousand {
+    match command {
+        DnsServerCommand::AddAddress(command_ip, command_software_info, uptime) => {
+            assert_eq!(command_ip, ip);
+            assert_eq!(&command_software_info, software_info);
+            uptime
+        }
+        DnsServerCommand::DelAddress(_) => panic!("Unexpected command {command:?}"),
+    }
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -56,10 +81,12 @@ async fn basic(#[case] seed: Seed) {
     // Node goes online, DNS record added
     state.node_online(node_addr, node_soft_info.clone());
     advance_time(&mut crawler, &time_getter, Duration::from_secs(60), 60).await;
-    assert_eq!(
+    let uptime = assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node_addr.socket_addr().ip(), node_soft_info.clone())
+        node_addr.socket_addr().ip(),
+        &node_soft_info,
     );
+    assert_eq!(uptime, PerThousand::new(1000).unwrap());
 
     assert_known_addresses(&crawler, &[(node_addr, node_soft_info.clone())]);
 
@@ -73,13 +100,15 @@ async fn basic(#[case] seed: Seed) {
 
     assert_known_addresses(&crawler, &[(node_addr, node_soft_info.clone())]);
 
-    // Node goes online again, DNS record added
+    // Node goes online again, DNS record added; the time it was offline lowers its uptime
     state.node_online(node_addr, node_soft_info.clone());
     advance_time(&mut crawler, &time_getter, Duration::from_secs(60), 60).await;
-    assert_eq!(
+    let uptime = assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node_addr.socket_addr().ip(), node_soft_info.clone())
+        node_addr.socket_addr().ip(),
+        &node_soft_info,
     );
+    assert!(uptime < PerThousand::new(1000).unwrap());
 
     assert_known_addresses(&crawler, &[(node_addr, node_soft_info)]);
 }
@@ -108,9 +137,10 @@ async fn software_info_update(#[case] seed: Seed) {
     // Node goes online.
     state.node_online(node_addr, node_soft_info1.clone());
     advance_time(&mut crawler, &time_getter, Duration::from_secs(60), 60).await;
-    assert_eq!(
+    assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node_addr.socket_addr().ip(), node_soft_info1.clone())
+        node_addr.socket_addr().ip(),
+        &node_soft_info1,
     );
 
     assert_known_addresses(&crawler, &[(node_addr, node_soft_info1.clone())]);
@@ -128,9 +158,10 @@ async fn software_info_update(#[case] seed: Seed) {
     // Node goes online again with a different software info.
     state.node_online(node_addr, node_soft_info2.clone());
     advance_time(&mut crawler, &time_getter, Duration::from_secs(60), 60).await;
-    assert_eq!(
+    assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node_addr.socket_addr().ip(), node_soft_info2.clone())
+        node_addr.socket_addr().ip(),
+        &node_soft_info2,
     );
 
     assert_known_addresses(&crawler, &[(node_addr, node_soft_info2)]);
@@ -163,9 +194,10 @@ async fn long_offline(#[case] seed: Seed) {
     // Node goes online, DNS record is added in 24 hours
     state.node_online(node_addr, node_soft_info.clone());
     advance_time(&mut crawler, &time_getter, Duration::from_secs(60), 24 * 60).await;
-    assert_eq!(
+    assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node_addr.socket_addr().ip(), node_soft_info.clone())
+        node_addr.socket_addr().ip(),
+        &node_soft_info,
     );
 
     assert_known_addresses(&crawler, &[(node_addr, node_soft_info)]);
@@ -201,23 +233,26 @@ async fn announced_online(#[case] seed: Seed) {
     state.node_online(node3_addr, node3_soft_info.clone());
 
     advance_time(&mut crawler, &time_getter, Duration::from_secs(60), 60).await;
-    assert_eq!(
+    assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node1_addr.socket_addr().ip(), node1_soft_info.clone())
+        node1_addr.socket_addr().ip(),
+        &node1_soft_info,
     );
 
     state.announce_address(node1_addr, node2_addr);
     advance_time(&mut crawler, &time_getter, Duration::from_secs(60), 60).await;
-    assert_eq!(
+    assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node2_addr.socket_addr().ip(), node2_soft_info.clone())
+        node2_addr.socket_addr().ip(),
+        &node2_soft_info,
     );
 
     state.announce_address(node2_addr, node3_addr);
     advance_time(&mut crawler, &time_getter, Duration::from_secs(60), 60).await;
-    assert_eq!(
+    assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node3_addr.socket_addr().ip(), node3_soft_info.clone())
+        node3_addr.socket_addr().ip(),
+        &node3_soft_info,
     );
 
     assert_known_addresses(
@@ -253,9 +288,10 @@ async fn announced_offline(#[case] seed: Seed) {
     state.node_online(node1_addr, node1_soft_info.clone());
 
     advance_time(&mut crawler, &time_getter, Duration::from_secs(60), 60).await;
-    assert_eq!(
+    assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node1_addr.socket_addr().ip(), node1_soft_info.clone())
+        node1_addr.socket_addr().ip(),
+        &node1_soft_info,
     );
     assert_eq!(state.connection_attempts.lock().unwrap().len(), 1);
 
@@ -268,9 +304,10 @@ async fn announced_offline(#[case] seed: Seed) {
     state.node_online(node2_addr, node2_soft_info.clone());
     state.announce_address(node1_addr, node2_addr);
     advance_time(&mut crawler, &time_getter, Duration::from_secs(60), 24 * 60).await;
-    assert_eq!(
+    assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node2_addr.socket_addr().ip(), node2_soft_info.clone())
+        node2_addr.socket_addr().ip(),
+        &node2_soft_info,
     );
     assert_eq!(state.connection_attempts.lock().unwrap().len(), 3);
 
@@ -313,13 +350,15 @@ async fn private_ip(#[case] seed: Seed) {
     advance_time(&mut crawler, &time_getter, Duration::from_secs(60), 24 * 60).await;
 
     // Check that only nodes with public addresses and on the default port are added to DNS
-    assert_eq!(
+    assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node1_addr.socket_addr().ip(), node_soft_info.clone())
+        node1_addr.socket_addr().ip(),
+        &node_soft_info,
     );
-    assert_eq!(
+    assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node2_addr.socket_addr().ip(), node_soft_info.clone())
+        node2_addr.socket_addr().ip(),
+        &node_soft_info,
     );
     expect_no_recv!(command_rx);
 
@@ -390,13 +429,15 @@ async fn ban_unban(#[case] seed: Seed) {
     let node2_ban_end_time = (time_getter.get_time_getter().get_time() + ban_duration).unwrap();
 
     // Only normal nodes are added to DNS
-    assert_eq!(
+    assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node1_addr.socket_addr().ip(), node_soft_info.clone())
+        node1_addr.socket_addr().ip(),
+        &node_soft_info,
     );
-    assert_eq!(
+    assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node3_addr.socket_addr().ip(), node_soft_info.clone())
+        node3_addr.socket_addr().ip(),
+        &node_soft_info,
     );
     expect_no_recv!(command_rx);
 
@@ -450,9 +491,10 @@ async fn ban_unban(#[case] seed: Seed) {
 
     // node2 is no longer banned; its address has been added to DNS.
     assert_banned_addresses(&crawler, &[(node1_addr.as_bannable(), node1_ban_end_time)]);
-    assert_eq!(
+    assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node2_addr.socket_addr().ip(), node_soft_info.clone())
+        node2_addr.socket_addr().ip(),
+        &node_soft_info,
     );
 
     // Wait enough time for node1 to be unbanned.
@@ -462,9 +504,10 @@ async fn ban_unban(#[case] seed: Seed) {
 
     // node1 is no longer banned; its address has been added to DNS.
     assert_banned_addresses(&crawler, &[]);
-    assert_eq!(
+    assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node1_addr.socket_addr().ip(), node_soft_info.clone())
+        node1_addr.socket_addr().ip(),
+        &node_soft_info,
     );
 }
 
@@ -508,16 +551,64 @@ async fn no_ban_on_connection_error(#[case] seed: Seed) {
     advance_time(&mut crawler, &time_getter, time_step, 1).await;
 
     // Only normal nodes are added to DNS
-    assert_eq!(
+    assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node1_addr.socket_addr().ip(), node_soft_info.clone())
+        node1_addr.socket_addr().ip(),
+        &node_soft_info,
     );
-    assert_eq!(
+    assert_address_added(
         expect_recv!(command_rx),
-        DnsServerCommand::AddAddress(node3_addr.socket_addr().ip(), node_soft_info)
+        node3_addr.socket_addr().ip(),
+        &node_soft_info,
     );
     expect_no_recv!(command_rx);
 
     // But node2 is still not banned
     assert_banned_addresses(&crawler, &[]);
 }
+
+// The addresses stored by the storage version 2 are kept when the storage is upgraded,
+// with the uptime statistics started at the upgrade time.
+#[test]
+fn storage_upgrade_from_v2() {
+    let storage = DnsServerStorageImpl::new(storage_inmemory::InMemory::new()).unwrap();
+
+    let node_addr: SocketAddress = "1.2.3.4:3031".parse().unwrap();
+    let node_soft_info = SoftwareInfo {
+        user_agent: "foo".try_into().unwrap(),
+        version: SemVer::new(1, 2, 3),
+    };
+    let last_addr_list_request_time = Some(Duration::from_secs(1000));
+
+    let mut tx = storage.transaction_rw().unwrap();
+    tx.set_version(STORAGE_VERSION_V2).unwrap();
+    tx.storage()
+        .get_mut::<DBAddresses, _>()
+        .put(
+            node_addr.to_string(),
+            AddressInfoV2 {
+                software_info: node_soft_info.clone(),
+                last_addr_list_request_time,
+            },
+        )
+        .unwrap();
+    tx.commit().unwrap();
+
+    let now = Time::from_secs_since_epoch(2000);
+    let loaded_storage =
+        CrawlerManager::<MockNetworkingService, _>::load_storage(&storage, now).unwrap();
+    assert_eq!(
+        loaded_storage.known_addresses,
+        BTreeMap::from([(
+            node_addr,
+            AddressInfo {
+                software_info: node_soft_info,
+                last_addr_list_request_time,
+                uptime_stats: UptimeStats::new(now),
+            }
+        )])
+    );
+
+    let version = storage.transaction_ro().unwrap().get_version().unwrap();
+    assert_eq!(version, Some(CURRENT_STORAGE_VERSION));
+}
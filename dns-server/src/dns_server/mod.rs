@@ -52,7 +52,9 @@ use crate::{
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum DnsServerCommand {
-    AddAddress(IpAddr, SoftwareInfo),
+    /// Add the address of a reachable node, together with its software info and the share
+    /// of time it has been reachable since it was first seen.
+    AddAddress(IpAddr, SoftwareInfo, PerThousand),
 
     DelAddress(IpAddr),
 }
@@ -76,6 +78,11 @@ const TTL_IP: u32 = 3600;
 const TTL_NS: u32 = 21600;
 const TTL_SOA: u32 = 21600;
 
+/// The weight with which the addresses of the nodes with (almost) zero uptime are selected,
+/// relative to the ones that are always up; it's not zero so that such nodes are still
+/// returned sometimes and can improve their uptime.
+const MIN_UPTIME_SELECTION_WEIGHT: f64 = 0.05;
+
 make_config_setting!(MaxIpv4RecordsCount, usize, 24);
 make_config_setting!(MaxIpv6RecordsCount, usize, 14);
 make_config_setting!(
@@ -167,28 +174,39 @@ impl AuthorityImplConfig {
     }
 }
 
+/// What is known about a node whose address can be returned in the DNS responses
+#[derive(Debug, Clone)]
+struct NodeInfo {
+    software_info: SoftwareInfo,
+    uptime: PerThousand,
+}
+
 /// Wrapper for InMemoryAuthority that selects random addresses every second
 struct AuthorityImpl {
     chain_config: Arc<ChainConfig>,
     config: AuthorityImplConfig,
     serial: RelaxedAtomicU32,
     inner: InMemoryAuthority,
-    ipv4_addrs: Mutex<BTreeMap<Ipv4Addr, SoftwareInfo>>,
-    ipv6_addrs: Mutex<BTreeMap<Ipv6Addr, SoftwareInfo>>,
+    ipv4_addrs: Mutex<BTreeMap<Ipv4Addr, NodeInfo>>,
+    ipv6_addrs: Mutex<BTreeMap<Ipv6Addr, NodeInfo>>,
 }
 
 impl AuthorityImpl {
     fn addr_info_for_logging<Addr: Clone + Ord>(
         addrs_to_include: &[Addr],
-        all_addrs: &BTreeMap<Addr, SoftwareInfo>,
+        all_addrs: &BTreeMap<Addr, NodeInfo>,
     ) -> BTreeMap<Addr, String> {
         addrs_to_include
             .iter()
             .map(|addr| {
-                let software_info = all_addrs.get(addr).expect("Address must be known");
-                let software_info_str =
-                    format!("{}-{}", software_info.user_agent, software_info.version);
-                (addr.clone(), software_info_str)
+                let node_info = all_addrs.get(addr).expect("Address must be known");
+                let node_info_str = format!(
+                    "{}-{}, uptime {}",
+                    node_info.software_info.user_agent,
+                    node_info.software_info.version,
+                    node_info.uptime.to_percentage_str()
+                );
+                (addr.clone(), node_info_str)
             })
             .collect()
     }
@@ -287,30 +305,44 @@ impl AuthorityImpl {
         }
     }
 
+    /// Randomly choose up to `count` addresses, preferring the ones with a higher uptime.
+    fn choose_by_uptime<Addr: Clone>(
+        addrs: &[(Addr, PerThousand)],
+        count: usize,
+        rng: &mut impl Rng,
+    ) -> Vec<Addr> {
+        let mut result = addrs
+            .choose_multiple_weighted(rng, count, |(_, uptime)| {
+                uptime.as_f64().max(MIN_UPTIME_SELECTION_WEIGHT)
+            })
+            .expect("Selection weights must be valid")
+            .map(|(addr, _)| addr.clone())
+            .collect::<Vec<_>>();
+        result.shuffle(rng);
+        result
+    }
+
     fn select_addresses<Addr: Clone>(
         &self,
-        addrs: &BTreeMap<Addr, SoftwareInfo>,
+        addrs: &BTreeMap<Addr, NodeInfo>,
         count: usize,
         rng: &mut impl Rng,
     ) -> Vec<Addr> {
         let same_software_info = SoftwareInfo::current(&self.chain_config);
 
         let (same_version_addrs, other_version_addrs): (Vec<_>, Vec<_>) =
-            addrs.iter().partition_map(|(addr, software_info)| {
-                if *software_info == same_software_info {
-                    itertools::Either::Left(addr.clone())
+            addrs.iter().partition_map(|(addr, node_info)| {
+                if node_info.software_info == same_software_info {
+                    itertools::Either::Left((addr.clone(), node_info.uptime))
                 } else {
-                    itertools::Either::Right(addr.clone())
+                    itertools::Either::Right((addr.clone(), node_info.uptime))
                 }
             });
 
         let mut selected_same_version_addrs =
-            same_version_addrs.choose_multiple(rng, count).cloned().collect::<Vec<_>>();
-        selected_same_version_addrs.shuffle(rng);
-
+            Self::choose_by_uptime(&same_version_addrs, count, rng);
         let mut selected_other_version_addrs =
-            other_version_addrs.choose_multiple(rng, count).cloned().collect::<Vec<_>>();
-        selected_other_version_addrs.shuffle(rng);
+            Self::choose_by_uptime(&other_version_addrs, count, rng);
 
         #[allow(clippy::float_arithmetic)]
         let same_version_addrs_preferred_count = {
@@ -415,19 +447,25 @@ impl Authority for AuthorityImpl {
 
 fn handle_command(auth: &AuthorityImpl, command: DnsServerCommand) {
     match command {
-        DnsServerCommand::AddAddress(IpAddr::V4(ip), software_version) => {
+        DnsServerCommand::AddAddress(IpAddr::V4(ip), software_info, uptime) => {
             log::debug!("Adding address {ip}");
-            auth.ipv4_addrs
-                .lock()
-                .expect("mutex must be valid (add ipv4)")
-                .insert(ip, software_version);
+            auth.ipv4_addrs.lock().expect("mutex must be valid (add ipv4)").insert(
+                ip,
+                NodeInfo {
+                    software_info,
+                    uptime,
+                },
+            );
         }
-        DnsServerCommand::AddAddress(IpAddr::V6(ip), software_version) => {
+        DnsServerCommand::AddAddress(IpAddr::V6(ip), software_info, uptime) => {
             log::debug!("Adding address {ip}");
-            auth.ipv6_addrs
-                .lock()
-                .expect("mutex must be valid (add ipv6)")
-                .insert(ip, software_version);
+            auth.ipv6_addrs.lock().expect("mutex must be valid (add ipv6)").insert(
+                ip,
+                NodeInfo {
+                    software_info,
+                    uptime,
+                },
+            );
         }
         DnsServerCommand::DelAddress(IpAddr::V4(ip)) => {
             log::debug!("Deleting address {ip}");
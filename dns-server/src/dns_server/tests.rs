@@ -51,6 +51,10 @@ fn create_test_config() -> AuthorityImplConfig {
     }
 }
 
+fn full_uptime() -> PerThousand {
+    PerThousand::new(1000).unwrap()
+}
+
 #[tokio::test]
 async fn dns_server_basic() {
     let chain_config = Arc::new(chain::config::create_testnet());
@@ -76,9 +80,12 @@ async fn dns_server_basic() {
     let ip2: Ipv6Addr = "2a00::1".parse().unwrap();
     handle_command(
         &auth,
-        DnsServerCommand::AddAddress(ip1.into(), soft_info.clone()),
+        DnsServerCommand::AddAddress(ip1.into(), soft_info.clone(), full_uptime()),
+    );
+    handle_command(
+        &auth,
+        DnsServerCommand::AddAddress(ip2.into(), soft_info, full_uptime()),
     );
-    handle_command(&auth, DnsServerCommand::AddAddress(ip2.into(), soft_info));
     assert_eq!(auth.ipv4_addrs.lock().unwrap().len(), 1);
     assert_eq!(auth.ipv6_addrs.lock().unwrap().len(), 1);
 
@@ -110,6 +117,57 @@ async fn dns_server_basic() {
     assert_eq!(auth.ipv6_addrs.lock().unwrap().len(), 0);
 }
 
+// The addresses of the nodes with a higher uptime are selected more often.
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn high_uptime_preferred(#[case] seed: Seed) {
+    let mut rng = test_utils::random::make_seedable_rng(seed);
+
+    let chain_config = Arc::new(chain::config::create_testnet());
+    let config = create_test_config();
+    let soft_info = SoftwareInfo::current(&chain_config);
+
+    let inner = InMemoryAuthority::empty(config.host.clone(), ZoneType::Primary, false);
+    let auth = AuthorityImpl {
+        config,
+        chain_config,
+        serial: Default::default(),
+        inner,
+        ipv4_addrs: Default::default(),
+        ipv6_addrs: Default::default(),
+    };
+
+    let addrs = TestAddressMaker::new_distinct_random_ipv4_addrs(100, &mut rng);
+    let (high_uptime_addrs, low_uptime_addrs) = addrs.split_at(50);
+    for addr in high_uptime_addrs {
+        handle_command(
+            &auth,
+            DnsServerCommand::AddAddress((*addr).into(), soft_info.clone(), full_uptime()),
+        );
+    }
+    for addr in low_uptime_addrs {
+        handle_command(
+            &auth,
+            DnsServerCommand::AddAddress(
+                (*addr).into(),
+                soft_info.clone(),
+                PerThousand::new(0).unwrap(),
+            ),
+        );
+    }
+
+    let selected_addrs = auth.select_addresses(&auth.ipv4_addrs.lock().unwrap(), 10, &mut rng);
+    assert_eq!(selected_addrs.len(), 10);
+
+    // A low uptime address is picked with the probability of about 5%, so getting more than
+    // a half of them is practically impossible
+    let high_uptime_count =
+        selected_addrs.iter().filter(|addr| high_uptime_addrs.contains(addr)).count();
+    assert!(high_uptime_count >= 5);
+}
+
 mod same_software_version_addr_selection_test {
     use crate::dns_server::{MaxIpv4RecordsCount, MaxIpv6RecordsCount};
 
@@ -158,7 +216,11 @@ mod same_software_version_addr_selection_test {
         for addr in &addrs {
             handle_command(
                 &auth,
-                DnsServerCommand::AddAddress(*addr, addr_map.get(addr).unwrap().clone()),
+                DnsServerCommand::AddAddress(
+                    *addr,
+                    addr_map.get(addr).unwrap().clone(),
+                    full_uptime(),
+                ),
             );
         }
 
@@ -67,6 +67,7 @@ async fn run(options: DnsServerRunOptions) -> anyhow::Result<Never> {
         bind_addresses: Vec::new(),
         socks5_proxy: None,
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Vec::new(),
         reserved_nodes: Vec::new(),
         whitelisted_addresses: Default::default(),
@@ -84,6 +85,7 @@ async fn run(options: DnsServerRunOptions) -> anyhow::Result<Never> {
         sync_stalling_timeout: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
 
     let transport = p2p::make_p2p_transport();
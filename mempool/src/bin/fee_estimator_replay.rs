@@ -0,0 +1,60 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Replays a mempool activity recording through the fee estimators and prints how
+//! accurate each of them was.
+//!
+//! Usage: `fee-estimator-replay <RECORDING_FILE>`
+
+use std::num::NonZeroUsize;
+
+use mempool::{
+    fee_estimator_replay::{
+        replay, ConfirmationEstimator, FeeEstimator, MempoolTopEstimator, RecentBlocksEstimator,
+        Recording,
+    },
+    MempoolConfig,
+};
+
+/// The number of blocks the recent blocks estimator looks back at
+const RECENT_BLOCKS: NonZeroUsize = NonZeroUsize::MIN.saturating_add(5);
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::args_os()
+        .nth(1)
+        .ok_or("Usage: fee-estimator-replay <RECORDING_FILE>")?;
+
+    let recording = Recording::load(path)?;
+
+    let min_fee_rate = *MempoolConfig::new().min_tx_relay_fee_rate;
+    let mut estimators: Vec<Box<dyn FeeEstimator>> = vec![
+        Box::new(MempoolTopEstimator::new(1, min_fee_rate)),
+        Box::new(RecentBlocksEstimator::new(RECENT_BLOCKS, min_fee_rate)),
+        Box::new(ConfirmationEstimator::new(min_fee_rate)),
+    ];
+
+    for report in replay(&recording, &mut estimators)? {
+        println!(
+            "{}: blocks: {}, underestimates: {}, overestimates: {}, mean absolute error: {} atoms/kB",
+            report.estimator,
+            report.blocks,
+            report.underestimates,
+            report.overestimates,
+            report.mean_absolute_error_atoms_per_kb,
+        );
+    }
+
+    Ok(())
+}
@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{str::FromStr, time::Duration};
+use std::{path::PathBuf, str::FromStr, time::Duration};
 
 use common::primitives::{Amount, BlockDistance};
 use rpc::description::HasValueHint;
@@ -102,8 +102,6 @@ impl HasValueHint for MempoolMaxSize {
     );
 }
 
-pub const ENABLE_RBF: bool = false;
-
 // Number of times we try to add transaction if the tip moves during validation
 pub const MAX_TX_ADDITION_ATTEMPTS: usize = 3;
 
@@ -117,6 +115,14 @@ pub const DEFAULT_MEMPOOL_EXPIRY: Duration = Duration::new(336 * 60 * 60, 0);
 
 pub const ROLLING_FEE_DECAY_INTERVAL: Duration = Duration::new(10, 0);
 
+/// The minimum relative change (in percent) of the mempool minimum fee rate that is reported
+/// to the event subscribers; smaller steps of the rolling fee decay are not reported.
+pub const MIN_FEE_RATE_CHANGE_NOTIFICATION_PERCENT: u128 = 10;
+
+/// How often the mempool minimum fee rate is re-checked, so that its decay is reported
+/// even if no new transactions or blocks arrive.
+pub const MIN_FEE_RATE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
 pub const DEFAULT_ORPHAN_POOL_CAPACITY: usize = 100;
 
 pub const DEFAULT_ORPHAN_TX_EXPIRY_INTERVAL: Duration = Duration::from_secs(5 * 10);
@@ -141,9 +147,31 @@ make_config_setting!(
     FeeRate::from_amount_per_kb(Amount::from_atoms(100_000_000_000))
 );
 
+make_config_setting!(AcceptZeroFeeTxs, bool, false);
+
+make_config_setting!(EnableRbf, bool, false);
+
+make_config_setting!(MinRbfFeeRateIncrement, FeeRate, INCREMENTAL_RELAY_FEE_RATE);
+
 #[derive(Debug, Clone, Default)]
 pub struct MempoolConfig {
     pub min_tx_relay_fee_rate: MinTxRelayFeeRate,
+
+    /// If true, transactions are accepted regardless of the relay and rolling fees they pay.
+    /// Only allowed on regtest, to simplify functional tests.
+    pub accept_zero_fee_txs: AcceptZeroFeeTxs,
+
+    /// If true, a transaction conflicting with replaceable transactions in the mempool
+    /// replaces them if it pays sufficiently higher fees (replace-by-fee).
+    pub enable_rbf: EnableRbf,
+
+    /// The amount by which the fee rate of a replacement transaction has to exceed the fee rate
+    /// of each of the transactions it directly conflicts with.
+    pub min_rbf_fee_rate_increment: MinRbfFeeRateIncrement,
+
+    /// If set, the transactions accepted into the mempool and the connected blocks are appended
+    /// to this file, which can later be replayed through fee estimators for backtesting.
+    pub fee_estimator_recording_file: Option<PathBuf>,
 }
 
 impl MempoolConfig {
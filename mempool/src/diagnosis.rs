@@ -0,0 +1,57 @@
+// Copyright (c) 2022 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Diagnostics for the transactions that are not being included in blocks
+
+use common::{chain::Transaction, primitives::Id};
+use rpc::description::HasValueHint;
+
+use crate::FeeRate;
+
+/// Why a transaction may not be included in the next block
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, HasValueHint)]
+#[serde(tag = "type", content = "content")]
+pub enum TxDiagnosis {
+    InMempool(MempoolTxDiagnosis),
+    InOrphanPool(OrphanTxDiagnosis),
+}
+
+/// The standing of a transaction from the main pool in the block production order
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct MempoolTxDiagnosis {
+    /// The fee rate of the transaction together with its unconfirmed ancestors,
+    /// which determines the order in which transactions are taken into blocks
+    pub package_fee_rate: FeeRate,
+    /// The package fee rate of the last transaction that fits into the next block,
+    /// or `None` if all the transactions in the mempool fit into it
+    pub next_block_cutoff_fee_rate: Option<FeeRate>,
+    /// The number of transactions that are taken into a block before this one
+    pub txs_ahead: usize,
+    /// The total size of the transactions that are taken into a block before this one
+    pub size_ahead: usize,
+    /// Whether the transaction is expected to be included in the next block
+    pub fits_in_next_block: bool,
+    /// The unconfirmed transactions that have to be included before this one
+    pub unconfirmed_ancestors: Vec<Id<Transaction>>,
+}
+
+/// What prevents a transaction from the orphan pool from entering the main pool
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct OrphanTxDiagnosis {
+    /// The transactions whose outputs are spent but which are not in the main pool
+    pub missing_parents: Vec<Id<Transaction>>,
+    /// The transactions in the main pool that spend the same outputs or account nonces
+    pub conflicts: Vec<Id<Transaction>>,
+}
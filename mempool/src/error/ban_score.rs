@@ -42,6 +42,8 @@ impl MempoolBanScore for Error {
             Error::Orphan(_) => 0,
             // Tip moved during validation
             Error::TipMoved => 0,
+            // Not related to transactions
+            Error::ZeroFeeTxsNotAllowed => 0,
         }
     }
 }
@@ -63,6 +65,7 @@ impl MempoolBanScore for MempoolPolicyError {
             MempoolPolicyError::ConflictsFeeOverflow => 0,
             MempoolPolicyError::TransactionFeeLowerThanConflictsWithDescendants => 0,
             MempoolPolicyError::ReplacementFeeLowerThanOriginal { .. } => 0,
+            MempoolPolicyError::ReplacementFeeRateTooLow { .. } => 0,
             MempoolPolicyError::AdditionalFeesUnderflow => 0,
 
             // Sending transactions with a fee below the minimum should not be punished.
@@ -109,6 +112,8 @@ impl MempoolBanScore for ChainstateError {
             ChainstateError::FailedToReadProperty(_) => 0,
             ChainstateError::BootstrapError(_) => 0,
             ChainstateError::BlockInvalidatorError(_) => 0,
+            ChainstateError::UndoCompactionError(_) => 0,
+            ChainstateError::StorageMaintenanceError(_) => 0,
         }
     }
 }
@@ -490,6 +495,7 @@ impl MempoolBanScore for CheckTransactionError {
             CheckTransactionError::OrdersAreNotActivated(_) => 100,
             CheckTransactionError::OrdersCurrenciesMustBeDifferent(_) => 100,
             CheckTransactionError::ChangeTokenMetadataUriNotActivated => 100,
+            CheckTransactionError::InvalidWitnessFormat(_) => 100,
         }
     }
 }
@@ -25,7 +25,7 @@ use common::{
     primitives::{amount::DisplayAmount, Id, H256},
 };
 
-use crate::pool::fee::Fee;
+use crate::pool::{fee::Fee, FeeRate};
 
 /// Error related to the construction of transaction sequence for inclusion in a block
 #[derive(Debug, Clone, Error, PartialEq, Eq)]
@@ -38,6 +38,10 @@ pub enum BlockConstructionError {
     Call(#[from] subsystem::error::CallError),
     #[error("User-requested transaction {0} not found in mempool")]
     TxNotFound(Id<Transaction>),
+    #[error("Transaction {0} is both required and excluded by the accumulator policy")]
+    RequiredTxExcluded(Id<Transaction>),
+    #[error("Required transaction {0} could not be included in the block")]
+    RequiredTxNotIncluded(Id<Transaction>),
 }
 
 #[derive(Debug, Clone, Error, PartialEq, Eq)]
@@ -50,6 +54,8 @@ pub enum Error {
     Orphan(#[from] OrphanPoolError),
     #[error("Tip moved while trying to process transaction")]
     TipMoved,
+    #[error("Accepting zero-fee transactions is only allowed on regtest")]
+    ZeroFeeTxsNotAllowed,
 }
 
 #[derive(Debug, Clone, Error, PartialEq, Eq)]
@@ -84,6 +90,11 @@ pub enum MempoolPolicyError {
     },
     #[error("Replacement transaction does not pay enough for its bandwidth.")]
     InsufficientFeesToRelayRBF,
+    #[error("Replacement transaction fee rate {replacement_fee_rate:?} is lower than the required {min_fee_rate:?}")]
+    ReplacementFeeRateTooLow {
+        replacement_fee_rate: FeeRate,
+        min_fee_rate: FeeRate,
+    },
     #[error("Rolling fee threshold not met (fee is {tx_fee}, minimum {minimum_fee}).")]
     RollingFeeThresholdNotMet {
         minimum_fee: DisplayAmount,
@@ -22,6 +22,7 @@ use crate::{
     error::{Error, MempoolBanScore},
     tx_options::TxRelayPolicy,
     tx_origin::TxOrigin,
+    FeeRate,
 };
 
 /// Event triggered when a transaction has been fully validated
@@ -102,11 +103,29 @@ impl NewTip {
     }
 }
 
+/// Event triggered when the minimum fee rate required for a transaction to enter the mempool
+/// has changed, e.g. after the mempool got full and low-fee transactions were trimmed
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MinFeeRateChanged {
+    fee_rate: FeeRate,
+}
+
+impl MinFeeRateChanged {
+    pub fn new(fee_rate: FeeRate) -> Self {
+        Self { fee_rate }
+    }
+
+    pub fn fee_rate(&self) -> FeeRate {
+        self.fee_rate
+    }
+}
+
 /// Events emitted by mempool
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum MempoolEvent {
     NewTip(NewTip),
     TransactionProcessed(TransactionProcessed),
+    MinFeeRateChanged(MinFeeRateChanged),
 }
 
 impl From<TransactionProcessed> for MempoolEvent {
@@ -120,3 +139,9 @@ impl From<NewTip> for MempoolEvent {
         Self::NewTip(event)
     }
 }
+
+impl From<MinFeeRateChanged> for MempoolEvent {
+    fn from(event: MinFeeRateChanged) -> Self {
+        Self::MinFeeRateChanged(event)
+    }
+}
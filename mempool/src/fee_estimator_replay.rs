@@ -0,0 +1,587 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deterministic replay of recorded mempool activity through fee estimators.
+//!
+//! A recording is a sequence of transaction additions and block confirmations. Replaying the
+//! same recording through several estimators makes it possible to compare them on real data
+//! without a live network. Before each block, every estimator is asked for the fee rate needed
+//! to get into the next block; the answer is then compared to the lowest fee rate that
+//! actually made it into that block.
+//!
+//! A node records its mempool activity if `MempoolConfig::fee_estimator_recording_file`
+//! is set; the `fee-estimator-replay` binary replays such a file.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fs::File,
+    io::{BufWriter, Write},
+    num::NonZeroUsize,
+    path::Path,
+};
+
+use common::{
+    chain::Transaction,
+    primitives::{Amount, BlockHeight, Id},
+};
+use logging::log;
+use serialization::{Decode, Encode};
+
+use crate::{
+    pool::{fee::Fee, fee_estimator::ConfirmationFeeEstimator},
+    FeeRate,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ReplayError {
+    #[error("Recording file error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Zero sized transaction {0} in the recording")]
+    ZeroSizedTransaction(Id<Transaction>),
+    #[error("Fee rate overflow for transaction {0}")]
+    FeeRateOverflow(Id<Transaction>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum RecordedEvent {
+    /// A transaction has been accepted into the mempool
+    TxAdded {
+        tx_id: Id<Transaction>,
+        size: u32,
+        fee: Amount,
+    },
+    /// A block has been connected; the transactions it included leave the mempool
+    BlockConnected {
+        height: BlockHeight,
+        tx_ids: Vec<Id<Transaction>>,
+    },
+}
+
+/// A sequence of recorded mempool events, in the order in which they happened.
+///
+/// The recording file is the concatenation of the encoded events, so that new events
+/// can be appended to it. If the node is stopped while writing an event, the file may end
+/// with a partial event, which is ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recording {
+    events: Vec<RecordedEvent>,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    pub fn record_tx_added(&mut self, tx_id: Id<Transaction>, size: u32, fee: Amount) {
+        self.events.push(RecordedEvent::TxAdded { tx_id, size, fee });
+    }
+
+    pub fn record_block_connected(&mut self, height: BlockHeight, tx_ids: Vec<Id<Transaction>>) {
+        self.events.push(RecordedEvent::BlockConnected { height, tx_ids });
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        let data = std::fs::read(path)?;
+        let (events, _) = decode_events(&data);
+        Ok(Self { events })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ReplayError> {
+        let data: Vec<u8> = self.events.iter().flat_map(|event| event.encode()).collect();
+        Ok(std::fs::write(path, data)?)
+    }
+}
+
+/// Decode the recorded events, ignoring a partial event at the end of the data.
+/// Return the events and the length of the data that they occupy.
+fn decode_events(data: &[u8]) -> (Vec<RecordedEvent>, usize) {
+    let mut input = data;
+    let mut events = Vec::new();
+    let mut valid_len = 0;
+    while !input.is_empty() {
+        match RecordedEvent::decode(&mut input) {
+            Ok(event) => {
+                events.push(event);
+                valid_len = data.len() - input.len();
+            }
+            Err(err) => {
+                log::warn!(
+                    "Ignoring the last {} bytes of the fee estimator recording: {err}",
+                    data.len() - valid_len
+                );
+                break;
+            }
+        }
+    }
+    (events, valid_len)
+}
+
+/// Appends the mempool events to a recording file as they happen.
+///
+/// The events are buffered and written out when a block is connected, so that recording
+/// doesn't slow down the processing of transactions.
+pub struct RecordingWriter {
+    file: BufWriter<File>,
+}
+
+impl RecordingWriter {
+    /// Open the recording file, keeping the events already recorded in it; a partial event
+    /// at the end of the file is dropped, so that the new events can be decoded.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        let path = path.as_ref();
+        let file = File::options().create(true).append(true).open(path)?;
+        let data = std::fs::read(path)?;
+        let (_, valid_len) = decode_events(&data);
+        if valid_len < data.len() {
+            file.set_len(valid_len as u64)?;
+        }
+        Ok(Self {
+            file: BufWriter::new(file),
+        })
+    }
+
+    pub fn record_tx_added(
+        &mut self,
+        tx_id: Id<Transaction>,
+        size: u32,
+        fee: Amount,
+    ) -> Result<(), ReplayError> {
+        self.write(&RecordedEvent::TxAdded { tx_id, size, fee })
+    }
+
+    pub fn record_block_connected(
+        &mut self,
+        height: BlockHeight,
+        tx_ids: Vec<Id<Transaction>>,
+    ) -> Result<(), ReplayError> {
+        self.write(&RecordedEvent::BlockConnected { height, tx_ids })?;
+        Ok(self.file.flush()?)
+    }
+
+    fn write(&mut self, event: &RecordedEvent) -> Result<(), ReplayError> {
+        Ok(self.file.write_all(&event.encode())?)
+    }
+}
+
+/// A transaction waiting in the replayed mempool
+#[derive(Debug, Clone, Copy)]
+pub struct PendingTx {
+    pub size: usize,
+    pub fee_rate: FeeRate,
+}
+
+/// The state of the mempool at some point of the replay
+#[derive(Debug, Default)]
+pub struct ReplayState {
+    pending: BTreeMap<Id<Transaction>, PendingTx>,
+}
+
+impl ReplayState {
+    pub fn pending(&self) -> &BTreeMap<Id<Transaction>, PendingTx> {
+        &self.pending
+    }
+
+    /// Pending transactions, starting from the highest fee rate
+    pub fn pending_by_fee_rate(&self) -> Vec<PendingTx> {
+        let mut txs: Vec<_> = self.pending.values().copied().collect();
+        txs.sort_by(|a, b| b.fee_rate.cmp(&a.fee_rate));
+        txs
+    }
+}
+
+pub trait FeeEstimator {
+    /// The name under which the estimator is reported
+    fn name(&self) -> String;
+
+    /// Estimate the fee rate needed for a transaction to get into the next block
+    fn estimate(&self, state: &ReplayState) -> FeeRate;
+
+    /// Called when a transaction enters the mempool
+    fn on_tx_added(&mut self, _tx_id: Id<Transaction>, _fee_rate: FeeRate) {}
+
+    /// Called after each block with the ids and fee rates of the transactions it included
+    fn on_block_connected(
+        &mut self,
+        _height: BlockHeight,
+        _included: &[(Id<Transaction>, FeeRate)],
+    ) {
+    }
+}
+
+/// The fee rate at which the pending transactions reach the given total size,
+/// which mirrors how the mempool answers fee rate queries
+pub struct MempoolTopEstimator {
+    in_top_x_mb: usize,
+    min_fee_rate: FeeRate,
+}
+
+impl MempoolTopEstimator {
+    pub fn new(in_top_x_mb: usize, min_fee_rate: FeeRate) -> Self {
+        Self {
+            in_top_x_mb,
+            min_fee_rate,
+        }
+    }
+}
+
+impl FeeEstimator for MempoolTopEstimator {
+    fn name(&self) -> String {
+        format!("mempool-top-{}mb", self.in_top_x_mb)
+    }
+
+    fn estimate(&self, state: &ReplayState) -> FeeRate {
+        let mut total_size = 0;
+        state
+            .pending_by_fee_rate()
+            .into_iter()
+            .find(|tx| {
+                total_size += tx.size;
+                (total_size / 1_000_000) >= self.in_top_x_mb
+            })
+            .map_or(self.min_fee_rate, |tx| {
+                std::cmp::max(tx.fee_rate, self.min_fee_rate)
+            })
+    }
+}
+
+/// The median of the lowest fee rates included in the last few blocks
+pub struct RecentBlocksEstimator {
+    num_blocks: NonZeroUsize,
+    min_fee_rate: FeeRate,
+    history: VecDeque<FeeRate>,
+}
+
+impl RecentBlocksEstimator {
+    pub fn new(num_blocks: NonZeroUsize, min_fee_rate: FeeRate) -> Self {
+        Self {
+            num_blocks,
+            min_fee_rate,
+            history: VecDeque::with_capacity(num_blocks.get()),
+        }
+    }
+}
+
+impl FeeEstimator for RecentBlocksEstimator {
+    fn name(&self) -> String {
+        format!("recent-blocks-{}", self.num_blocks)
+    }
+
+    fn estimate(&self, _state: &ReplayState) -> FeeRate {
+        let mut rates: Vec<_> = self.history.iter().copied().collect();
+        rates.sort();
+        rates.get(rates.len() / 2).map_or(self.min_fee_rate, |rate| {
+            std::cmp::max(*rate, self.min_fee_rate)
+        })
+    }
+
+    fn on_block_connected(
+        &mut self,
+        _height: BlockHeight,
+        included: &[(Id<Transaction>, FeeRate)],
+    ) {
+        if let Some(lowest) = included.iter().map(|(_, fee_rate)| fee_rate).min() {
+            if self.history.len() == self.num_blocks.get() {
+                self.history.pop_front();
+            }
+            self.history.push_back(*lowest);
+        }
+    }
+}
+
+/// The confirmation-based estimator the mempool uses, asked for the fee rate needed
+/// to get confirmed within the next block
+pub struct ConfirmationEstimator {
+    estimator: ConfirmationFeeEstimator,
+    min_fee_rate: FeeRate,
+}
+
+impl ConfirmationEstimator {
+    pub fn new(min_fee_rate: FeeRate) -> Self {
+        Self {
+            estimator: ConfirmationFeeEstimator::new(min_fee_rate),
+            min_fee_rate,
+        }
+    }
+}
+
+impl FeeEstimator for ConfirmationEstimator {
+    fn name(&self) -> String {
+        "confirmation-next-block".to_owned()
+    }
+
+    fn estimate(&self, _state: &ReplayState) -> FeeRate {
+        self.estimator.estimate(1).map_or(self.min_fee_rate, |fee_rate| {
+            std::cmp::max(fee_rate, self.min_fee_rate)
+        })
+    }
+
+    fn on_tx_added(&mut self, tx_id: Id<Transaction>, fee_rate: FeeRate) {
+        self.estimator.tx_added(tx_id, fee_rate);
+    }
+
+    fn on_block_connected(&mut self, height: BlockHeight, included: &[(Id<Transaction>, FeeRate)]) {
+        self.estimator.block_connected(height, included.iter().map(|(tx_id, _)| tx_id));
+    }
+}
+
+/// How well an estimator predicted the fee rates over a replay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayReport {
+    pub estimator: String,
+    /// The number of blocks the estimator was evaluated on
+    pub blocks: usize,
+    /// The estimate was below the lowest included fee rate, so a transaction
+    /// paying it would likely have missed the block
+    pub underestimates: usize,
+    /// The estimate was above the lowest included fee rate, so a transaction
+    /// paying it would have overpaid
+    pub overestimates: usize,
+    pub mean_absolute_error_atoms_per_kb: u128,
+}
+
+/// Replay the recording through all the estimators and report their accuracy.
+///
+/// Blocks that don't include any transaction seen in the recording are skipped,
+/// as there is nothing to compare the estimates to.
+pub fn replay(
+    recording: &Recording,
+    estimators: &mut [Box<dyn FeeEstimator>],
+) -> Result<Vec<ReplayReport>, ReplayError> {
+    let mut state = ReplayState::default();
+    let mut reports: Vec<_> = estimators
+        .iter()
+        .map(|estimator| ReplayReport {
+            estimator: estimator.name(),
+            blocks: 0,
+            underestimates: 0,
+            overestimates: 0,
+            mean_absolute_error_atoms_per_kb: 0,
+        })
+        .collect();
+    let mut total_errors = vec![0u128; estimators.len()];
+
+    for event in recording.events() {
+        match event {
+            RecordedEvent::TxAdded { tx_id, size, fee } => {
+                let size = NonZeroUsize::new(*size as usize)
+                    .ok_or(ReplayError::ZeroSizedTransaction(*tx_id))?;
+                let fee_rate = FeeRate::from_total_tx_fee(Fee::from(*fee), size)
+                    .map_err(|_| ReplayError::FeeRateOverflow(*tx_id))?;
+                state.pending.insert(
+                    *tx_id,
+                    PendingTx {
+                        size: size.get(),
+                        fee_rate,
+                    },
+                );
+                for estimator in estimators.iter_mut() {
+                    estimator.on_tx_added(*tx_id, fee_rate);
+                }
+            }
+            RecordedEvent::BlockConnected { height, tx_ids } => {
+                let estimates: Vec<_> =
+                    estimators.iter().map(|estimator| estimator.estimate(&state)).collect();
+
+                let included: Vec<_> = tx_ids
+                    .iter()
+                    .filter_map(|tx_id| state.pending.remove(tx_id).map(|tx| (*tx_id, tx.fee_rate)))
+                    .collect();
+
+                if let Some(lowest_included) = included.iter().map(|(_, fee_rate)| fee_rate).min() {
+                    for ((report, total_error), estimate) in
+                        reports.iter_mut().zip(total_errors.iter_mut()).zip(estimates)
+                    {
+                        report.blocks += 1;
+                        if estimate < *lowest_included {
+                            report.underestimates += 1;
+                        } else if estimate > *lowest_included {
+                            report.overestimates += 1;
+                        }
+                        *total_error +=
+                            estimate.atoms_per_kb().abs_diff(lowest_included.atoms_per_kb());
+                    }
+                }
+
+                for estimator in estimators.iter_mut() {
+                    estimator.on_block_connected(*height, &included);
+                }
+            }
+        }
+    }
+
+    for (report, total_error) in reports.iter_mut().zip(total_errors) {
+        if report.blocks > 0 {
+            report.mean_absolute_error_atoms_per_kb = total_error / report.blocks as u128;
+        }
+    }
+
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::primitives::H256;
+    use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, Rng, Seed};
+
+    fn random_tx_id(rng: &mut impl Rng) -> Id<Transaction> {
+        Id::new(H256::random_using(rng))
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn recording_roundtrip(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        let mut recording = Recording::new();
+        let tx_id = random_tx_id(&mut rng);
+        recording.record_tx_added(tx_id, rng.gen_range(1..1000), Amount::from_atoms(rng.gen()));
+        recording.record_block_connected(BlockHeight::new(rng.gen()), vec![tx_id]);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("recording");
+        recording.save(&path).unwrap();
+        assert_eq!(Recording::load(&path).unwrap(), recording);
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn recording_writer_appends(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("recording");
+
+        let mut recording = Recording::new();
+        let tx_id1 = random_tx_id(&mut rng);
+        recording.record_tx_added(tx_id1, 100, Amount::from_atoms(1000));
+        recording.save(&path).unwrap();
+
+        // Reopening the file keeps the events recorded before
+        let tx_id2 = random_tx_id(&mut rng);
+        let height = BlockHeight::new(rng.gen());
+        let mut writer = RecordingWriter::open(&path).unwrap();
+        writer.record_tx_added(tx_id2, 200, Amount::from_atoms(2000)).unwrap();
+        writer.record_block_connected(height, vec![tx_id1, tx_id2]).unwrap();
+        drop(writer);
+
+        recording.record_tx_added(tx_id2, 200, Amount::from_atoms(2000));
+        recording.record_block_connected(height, vec![tx_id1, tx_id2]);
+        assert_eq!(Recording::load(&path).unwrap(), recording);
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn recording_with_partial_last_event(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("recording");
+
+        let mut recording = Recording::new();
+        let tx_id1 = random_tx_id(&mut rng);
+        recording.record_tx_added(tx_id1, 100, Amount::from_atoms(1000));
+        recording.save(&path).unwrap();
+
+        // Simulate a node that has been stopped in the middle of writing an event
+        let partial_event = RecordedEvent::BlockConnected {
+            height: BlockHeight::new(rng.gen()),
+            tx_ids: vec![tx_id1],
+        }
+        .encode();
+        let mut data = std::fs::read(&path).unwrap();
+        data.extend_from_slice(&partial_event[..rng.gen_range(1..partial_event.len())]);
+        std::fs::write(&path, data).unwrap();
+        assert_eq!(Recording::load(&path).unwrap(), recording);
+
+        // The partial event is dropped when the recording is resumed
+        let tx_id2 = random_tx_id(&mut rng);
+        let height = BlockHeight::new(rng.gen());
+        let mut writer = RecordingWriter::open(&path).unwrap();
+        writer.record_tx_added(tx_id2, 200, Amount::from_atoms(2000)).unwrap();
+        writer.record_block_connected(height, vec![tx_id2]).unwrap();
+        drop(writer);
+
+        recording.record_tx_added(tx_id2, 200, Amount::from_atoms(2000));
+        recording.record_block_connected(height, vec![tx_id2]);
+        assert_eq!(Recording::load(&path).unwrap(), recording);
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn replay_is_deterministic(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        // Each block includes the better paying half of the pending transactions
+        let mut recording = Recording::new();
+        let mut pending = Vec::new();
+        for height in 1..20 {
+            for _ in 0..rng.gen_range(1..10) {
+                let tx_id = random_tx_id(&mut rng);
+                let size = 1000;
+                let fee = rng.gen_range(1000..100_000);
+                recording.record_tx_added(tx_id, size, Amount::from_atoms(fee));
+                pending.push((fee, tx_id));
+            }
+            pending.sort();
+            let included = pending.split_off(pending.len() / 2);
+            recording.record_block_connected(
+                BlockHeight::new(height),
+                included.into_iter().map(|(_, tx_id)| tx_id).collect(),
+            );
+        }
+
+        let make_estimators = || -> Vec<Box<dyn FeeEstimator>> {
+            let min_fee_rate = FeeRate::from_atoms_per_kb(1000);
+            vec![
+                Box::new(MempoolTopEstimator::new(0, min_fee_rate)),
+                Box::new(RecentBlocksEstimator::new(
+                    NonZeroUsize::new(3).unwrap(),
+                    min_fee_rate,
+                )),
+                Box::new(ConfirmationEstimator::new(min_fee_rate)),
+            ]
+        };
+
+        let reports1 = replay(&recording, &mut make_estimators()).unwrap();
+        let reports2 = replay(&recording, &mut make_estimators()).unwrap();
+        assert_eq!(reports1, reports2);
+
+        for report in reports1 {
+            assert_eq!(report.blocks, 19);
+            assert!(report.underestimates + report.overestimates <= report.blocks);
+        }
+    }
+
+    #[test]
+    fn zero_sized_tx() {
+        let tx_id = Id::new(H256::zero());
+        let mut recording = Recording::new();
+        recording.record_tx_added(tx_id, 0, Amount::ZERO);
+
+        let result = replay(&recording, &mut []);
+        assert!(matches!(result, Err(ReplayError::ZeroSizedTransaction(id)) if id == tx_id));
+    }
+}
@@ -14,9 +14,12 @@
 // limitations under the License.
 
 use crate::{
+    diagnosis::TxDiagnosis,
     error::{BlockConstructionError, Error},
     event::MempoolEvent,
+    reorg_stats::ReorgStats,
     tx_accumulator::{PackingStrategy, TransactionAccumulator},
+    tx_info::{MempoolTxInfo, ProjectedBlock},
     tx_origin::{LocalTxOrigin, RemoteTxOrigin},
     FeeRate, MempoolMaxSize, TxOptions, TxStatus,
 };
@@ -58,9 +61,25 @@ pub trait MempoolInterface: Send + Sync {
     /// Check given transaction is contained in the main mempool (non-orphan)
     fn contains_orphan_transaction(&self, tx: &Id<Transaction>) -> bool;
 
+    /// Get a transaction from the main pool or the orphan pool together with its fee, size
+    /// and the time it was added
+    fn transaction_info(&self, id: &Id<Transaction>) -> Option<MempoolTxInfo>;
+
+    /// Get the in-mempool transactions the given transaction depends on, directly or indirectly.
+    /// Returns `None` if the transaction is not in the main mempool.
+    fn unconfirmed_ancestors(&self, id: &Id<Transaction>) -> Option<Vec<Id<Transaction>>>;
+
+    /// Get the in-mempool transactions that depend on the given transaction, directly or
+    /// indirectly. Returns `None` if the transaction is not in the main mempool.
+    fn unconfirmed_descendants(&self, id: &Id<Transaction>) -> Option<Vec<Id<Transaction>>>;
+
     /// Best block ID according to mempool. May be temporarily out of sync with chainstate.
     fn best_block_id(&self) -> Id<GenBlock>;
 
+    /// Explain why the transaction may not be included in the next block.
+    /// Returns `None` if the transaction is neither in the main pool nor in the orphan pool.
+    fn diagnose_transaction(&self, tx_id: &Id<Transaction>) -> Option<TxDiagnosis>;
+
     /// Collect transactions by putting them in given accumulator
     /// Returns the accumulator with the collected transactions
     /// Ok(None) is returned on recoverable errors, such as if
@@ -72,22 +91,48 @@ pub trait MempoolInterface: Send + Sync {
         packing_strategy: PackingStrategy,
     ) -> Result<Option<Box<dyn TransactionAccumulator>>, BlockConstructionError>;
 
+    /// Select the transactions the next block would contain if it was produced now
+    fn projected_next_block(&self) -> Result<ProjectedBlock, BlockConstructionError>;
+
     /// Subscribe to events emitted by mempool
     fn subscribe_to_events(&mut self, handler: Arc<dyn Fn(MempoolEvent) + Send + Sync>);
 
     /// Get current memory usage
     fn memory_usage(&self) -> usize;
 
+    /// Get the statistics of returning the transactions from disconnected blocks to mempool
+    fn reorg_stats(&self) -> ReorgStats;
+
     /// Get the maximum allowed mempool size, as in, the maximum total byte-size of all transactions in the mempool.
     fn get_size_limit(&self) -> MempoolMaxSize;
 
     /// Set the allowed size limit for the total of all transactions in the mempool.
     fn set_size_limit(&mut self, max_size: MempoolMaxSize) -> Result<(), Error>;
 
+    /// Whether transactions are accepted regardless of the fees they pay.
+    fn accepts_zero_fee_txs(&self) -> bool;
+
+    /// Enable or disable the acceptance of transactions regardless of the fees they pay.
+    /// Only allowed on regtest.
+    fn set_accept_zero_fee_txs(&mut self, accept: bool) -> Result<(), Error>;
+
     /// Get the fee rate such that it would put the new transaction in the top X MB of the mempool
     /// making it less likely to get rejected or trimmed in the case the mempool is full
     fn get_fee_rate(&self, in_top_x_mb: usize) -> FeeRate;
 
+    /// Get the minimum fee rate a transaction has to pay to be accepted into the mempool.
+    /// It is raised when the mempool gets full and decays back to the minimum relay fee rate
+    fn get_min_fee_rate(&self) -> FeeRate;
+
+    /// Re-check the minimum fee rate, which decays over time, and emit
+    /// `MempoolEvent::MinFeeRateChanged` if it has changed noticeably
+    fn check_min_fee_rate(&mut self);
+
+    /// Estimate the fee rate a transaction needs to pay to be confirmed within the given number
+    /// of blocks, based on how quickly the recent transactions have been confirmed.
+    /// Returns `None` if there is not enough data yet
+    fn estimate_fee_rate(&self, target_blocks: usize) -> Option<FeeRate>;
+
     /// Get the fee rate at multiple uniformly distributed points along the mempool's transactions
     fn get_fee_rate_points(&self, num_points: NonZeroUsize)
         -> Result<Vec<(usize, FeeRate)>, Error>;
@@ -14,11 +14,14 @@
 // limitations under the License.
 
 use crate::{
-    config::MempoolConfig,
+    config::{self, MempoolConfig},
+    diagnosis::TxDiagnosis,
     error::{BlockConstructionError, Error},
     event::MempoolEvent,
     pool::memory_usage_estimator::StoreMemoryUsageEstimator,
+    reorg_stats::ReorgStats,
     tx_accumulator::{PackingStrategy, TransactionAccumulator},
+    tx_info::{MempoolTxInfo, ProjectedBlock},
     tx_origin::{LocalTxOrigin, RemoteTxOrigin},
     FeeRate, MempoolInterface, MempoolMaxSize, TxOptions, TxStatus,
 };
@@ -72,6 +75,21 @@ impl MempoolInit {
             StoreMemoryUsageEstimator,
         );
 
+        // The minimum fee rate decays with time, so it has to be re-checked periodically
+        // to notify the subscribers even if nothing else happens.
+        let check_handle = this.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(config::MIN_FEE_RATE_CHECK_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if check_handle.submit_mut(|this| this.check_min_fee_rate()).is_err() {
+                    // The mempool has been shut down
+                    break;
+                }
+            }
+        });
+
         log::trace!("Subscribing to chainstate events");
         let subscribe_func = Arc::new(move |event: chainstate::ChainstateEvent| {
             let _ = this
@@ -136,10 +154,26 @@ impl MempoolInterface for Mempool {
         self.orphan_transaction(id).cloned()
     }
 
+    fn transaction_info(&self, id: &Id<Transaction>) -> Option<MempoolTxInfo> {
+        self.transaction_info(id)
+    }
+
+    fn unconfirmed_ancestors(&self, id: &Id<Transaction>) -> Option<Vec<Id<Transaction>>> {
+        self.unconfirmed_ancestors(id)
+    }
+
+    fn unconfirmed_descendants(&self, id: &Id<Transaction>) -> Option<Vec<Id<Transaction>>> {
+        self.unconfirmed_descendants(id)
+    }
+
     fn best_block_id(&self) -> Id<GenBlock> {
         self.best_block_id()
     }
 
+    fn diagnose_transaction(&self, tx_id: &Id<Transaction>) -> Option<TxDiagnosis> {
+        self.diagnose_transaction(tx_id)
+    }
+
     #[tracing::instrument(skip_all)]
     fn collect_txs(
         &self,
@@ -150,6 +184,10 @@ impl MempoolInterface for Mempool {
         self.collect_txs(tx_accumulator, transaction_ids, packing_strategy)
     }
 
+    fn projected_next_block(&self) -> Result<ProjectedBlock, BlockConstructionError> {
+        self.projected_next_block()
+    }
+
     fn subscribe_to_events(&mut self, handler: Arc<dyn Fn(MempoolEvent) + Send + Sync>) {
         self.subscribe_to_events(handler);
     }
@@ -158,6 +196,10 @@ impl MempoolInterface for Mempool {
         self.memory_usage()
     }
 
+    fn reorg_stats(&self) -> ReorgStats {
+        self.reorg_stats()
+    }
+
     fn get_size_limit(&self) -> MempoolMaxSize {
         self.max_size()
     }
@@ -166,10 +208,30 @@ impl MempoolInterface for Mempool {
         self.set_size_limit(max_size)
     }
 
+    fn accepts_zero_fee_txs(&self) -> bool {
+        self.accepts_zero_fee_txs()
+    }
+
+    fn set_accept_zero_fee_txs(&mut self, accept: bool) -> Result<(), Error> {
+        self.set_accept_zero_fee_txs(accept)
+    }
+
     fn get_fee_rate(&self, in_top_x_mb: usize) -> FeeRate {
         self.get_fee_rate(in_top_x_mb)
     }
 
+    fn get_min_fee_rate(&self) -> FeeRate {
+        self.get_min_fee_rate()
+    }
+
+    fn check_min_fee_rate(&mut self) {
+        self.check_min_fee_rate()
+    }
+
+    fn estimate_fee_rate(&self, target_blocks: usize) -> Option<FeeRate> {
+        self.estimate_fee_rate(target_blocks)
+    }
+
     fn get_fee_rate_points(
         &self,
         num_points: NonZeroUsize,
@@ -20,12 +20,16 @@ pub use interface::{make_mempool, MempoolInterface};
 pub use mempool_types::{tx_options, tx_origin, TxOptions, TxStatus};
 
 mod config;
+pub mod diagnosis;
 pub mod error;
 pub mod event;
+pub mod fee_estimator_replay;
 mod interface;
 mod pool;
+pub mod reorg_stats;
 pub mod rpc;
 pub mod tx_accumulator;
+pub mod tx_info;
 
 pub use {config::MempoolConfig, pool::feerate_points::find_interpolated_value, pool::FeeRate};
 
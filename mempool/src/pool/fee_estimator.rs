@@ -0,0 +1,317 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fee estimation based on how quickly the transactions of different fee rates
+//! have been confirmed recently.
+//!
+//! Every transaction accepted into the mempool is tracked together with the height at which
+//! it has been accepted. When it's included in a block, the number of blocks it has waited for
+//! is recorded in the bucket corresponding to its fee rate. The fee rate needed to get confirmed
+//! within N blocks is then the lowest fee rate for which most of the transactions paying at least
+//! that much have been confirmed within N blocks.
+
+use std::collections::BTreeMap;
+
+use common::{
+    chain::Transaction,
+    primitives::{BlockHeight, Id},
+};
+
+use super::FeeRate;
+
+/// The maximum number of blocks for which fee rates can be estimated
+pub const MAX_CONFIRMATION_TARGET: usize = 48;
+
+/// The ratio between the lower bounds of two consecutive fee rate buckets
+const BUCKET_SPACING: f64 = 1.1;
+
+/// The number of buckets; with the spacing above they cover the fee rates up to about
+/// 10000 times the minimum relay fee rate
+const BUCKET_COUNT: usize = 100;
+
+/// The factor by which the past data is multiplied on each block, so that the recent
+/// blocks have more weight; the half-life is about 350 blocks
+const DECAY: f64 = 0.998;
+
+/// The share of the transactions that must be confirmed within the target for a fee rate
+/// to be considered sufficient
+const SUCCESS_THRESHOLD: f64 = 0.85;
+
+/// The (decayed) number of transactions needed to make a decision about a range of buckets
+const SUFFICIENT_TX_COUNT: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy)]
+struct TrackedTx {
+    entry_height: BlockHeight,
+    bucket: usize,
+}
+
+/// The estimator used by the mempool to answer fee rate estimation queries
+pub struct ConfirmationFeeEstimator {
+    /// The lowest fee rate of each bucket, in ascending order
+    bucket_bounds: Vec<FeeRate>,
+
+    /// `confirmed[target - 1][bucket]` is the number of transactions of the bucket that have been
+    /// confirmed within `target` blocks
+    confirmed: Vec<Vec<f64>>,
+
+    /// The number of confirmed transactions in each bucket
+    total_confirmed: Vec<f64>,
+
+    /// Transactions in the mempool that are waiting for confirmation
+    tracked: BTreeMap<Id<Transaction>, TrackedTx>,
+
+    /// The height of the last connected block, `None` until the first block is seen,
+    /// as transactions cannot be tracked without it
+    best_height: Option<BlockHeight>,
+}
+
+impl ConfirmationFeeEstimator {
+    #[allow(clippy::float_arithmetic)]
+    pub fn new(min_fee_rate: FeeRate) -> Self {
+        let first_bound = std::cmp::max(min_fee_rate.atoms_per_kb(), 1) as f64;
+        let bucket_bounds = std::iter::once(FeeRate::from_atoms_per_kb(0))
+            .chain((0..BUCKET_COUNT - 1).map(|i| {
+                FeeRate::from_atoms_per_kb((first_bound * BUCKET_SPACING.powi(i as i32)) as u128)
+            }))
+            .collect();
+
+        Self {
+            bucket_bounds,
+            confirmed: vec![vec![0.0; BUCKET_COUNT]; MAX_CONFIRMATION_TARGET],
+            total_confirmed: vec![0.0; BUCKET_COUNT],
+            tracked: BTreeMap::new(),
+            best_height: None,
+        }
+    }
+
+    fn bucket_index(&self, fee_rate: FeeRate) -> usize {
+        self.bucket_bounds.partition_point(|bound| *bound <= fee_rate).saturating_sub(1)
+    }
+
+    pub fn tracked_tx_count(&self) -> usize {
+        self.tracked.len()
+    }
+
+    /// Start tracking a transaction that has just been accepted into the mempool
+    ///
+    /// A transaction that is already tracked keeps its original entry height.
+    pub fn tx_added(&mut self, tx_id: Id<Transaction>, fee_rate: FeeRate) {
+        if let Some(best_height) = self.best_height {
+            let tracked_tx = TrackedTx {
+                entry_height: best_height,
+                bucket: self.bucket_index(fee_rate),
+            };
+            self.tracked.entry(tx_id).or_insert(tracked_tx);
+        }
+    }
+
+    /// Record the confirmations of the tracked transactions included in a new block
+    ///
+    /// The transactions that are no longer in the mempool for other reasons, e.g. because they
+    /// have been evicted or replaced, are not counted and should be removed by the caller
+    /// via `retain_txs`.
+    #[allow(clippy::float_arithmetic)]
+    pub fn block_connected<'a>(
+        &mut self,
+        height: BlockHeight,
+        tx_ids: impl IntoIterator<Item = &'a Id<Transaction>>,
+    ) {
+        self.best_height = Some(height);
+
+        for count in self.confirmed.iter_mut().flatten().chain(self.total_confirmed.iter_mut()) {
+            *count *= DECAY;
+        }
+
+        for tx_id in tx_ids {
+            let Some(tracked_tx) = self.tracked.remove(tx_id) else {
+                continue;
+            };
+
+            let blocks = height.into_int().saturating_sub(tracked_tx.entry_height.into_int()).max(1)
+                as usize;
+
+            self.total_confirmed[tracked_tx.bucket] += 1.0;
+            for confirmed in self.confirmed.iter_mut().skip(blocks - 1) {
+                confirmed[tracked_tx.bucket] += 1.0;
+            }
+        }
+    }
+
+    /// Stop tracking the transactions that have left the mempool without being confirmed
+    pub fn retain_txs(&mut self, mut keep: impl FnMut(&Id<Transaction>) -> bool) {
+        self.tracked.retain(|tx_id, _| keep(tx_id));
+    }
+
+    /// Estimate the fee rate needed for a transaction to be confirmed within `target_blocks`.
+    ///
+    /// The target is clamped to `[1, MAX_CONFIRMATION_TARGET]`. Returns `None` if there is
+    /// not enough data.
+    #[allow(clippy::float_arithmetic)]
+    pub fn estimate(&self, target_blocks: usize) -> Option<FeeRate> {
+        let target = target_blocks.clamp(1, MAX_CONFIRMATION_TARGET);
+        let confirmed = &self.confirmed[target - 1];
+
+        // The transactions still waiting in the mempool for longer than the target
+        // are counted as failures
+        let mut waiting_too_long = vec![0.0; BUCKET_COUNT];
+        if let Some(best_height) = self.best_height {
+            for tracked_tx in self.tracked.values() {
+                let waited =
+                    best_height.into_int().saturating_sub(tracked_tx.entry_height.into_int());
+                if waited >= target as u64 {
+                    waiting_too_long[tracked_tx.bucket] += 1.0;
+                }
+            }
+        }
+
+        // Starting from the highest fee rates, group the buckets until there is enough data to
+        // make a decision; stop at the first group in which too few transactions were confirmed
+        // in time.
+        let mut result = None;
+        let mut group_confirmed = 0.0;
+        let mut group_total = 0.0;
+        for bucket in (0..BUCKET_COUNT).rev() {
+            group_confirmed += confirmed[bucket];
+            group_total += self.total_confirmed[bucket] + waiting_too_long[bucket];
+
+            if group_total >= SUFFICIENT_TX_COUNT {
+                if group_confirmed / group_total < SUCCESS_THRESHOLD {
+                    break;
+                }
+                result = Some(self.bucket_bounds[bucket]);
+                group_confirmed = 0.0;
+                group_total = 0.0;
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::primitives::H256;
+    use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, Rng, Seed};
+
+    const MIN_FEE_RATE: FeeRate =
+        FeeRate::from_amount_per_kb(common::primitives::Amount::from_atoms(1000));
+
+    fn random_tx_id(rng: &mut impl Rng) -> Id<Transaction> {
+        Id::new(H256::random_using(rng))
+    }
+
+    #[test]
+    fn no_data() {
+        let mut estimator = ConfirmationFeeEstimator::new(MIN_FEE_RATE);
+        assert_eq!(estimator.estimate(1), None);
+
+        // Transactions are not tracked until the height is known
+        estimator.tx_added(Id::new(H256::zero()), MIN_FEE_RATE);
+        assert_eq!(estimator.tracked_tx_count(), 0);
+
+        estimator.block_connected(BlockHeight::new(1), []);
+        assert_eq!(estimator.estimate(1), None);
+    }
+
+    // Adding a tracked transaction again doesn't reset the time it has been waiting for.
+    #[test]
+    fn tx_added_twice() {
+        let mut estimator = ConfirmationFeeEstimator::new(MIN_FEE_RATE);
+        let tx_id = Id::new(H256::zero());
+        let bucket = estimator.bucket_index(MIN_FEE_RATE);
+
+        estimator.block_connected(BlockHeight::new(1), []);
+        estimator.tx_added(tx_id, MIN_FEE_RATE);
+        estimator.block_connected(BlockHeight::new(2), []);
+        estimator.tx_added(tx_id, MIN_FEE_RATE);
+        assert_eq!(estimator.tracked_tx_count(), 1);
+
+        estimator.block_connected(BlockHeight::new(3), [&tx_id]);
+        assert_eq!(estimator.tracked_tx_count(), 0);
+        assert_eq!(estimator.confirmed[0][bucket], 0.0);
+        assert_eq!(estimator.confirmed[1][bucket], 1.0);
+    }
+
+    #[test]
+    fn buckets() {
+        let estimator = ConfirmationFeeEstimator::new(MIN_FEE_RATE);
+        assert_eq!(estimator.bucket_index(FeeRate::from_atoms_per_kb(0)), 0);
+        assert_eq!(estimator.bucket_index(FeeRate::from_atoms_per_kb(999)), 0);
+        assert_eq!(estimator.bucket_index(MIN_FEE_RATE), 1);
+        assert_eq!(estimator.bucket_index(FeeRate::from_atoms_per_kb(1099)), 1);
+        assert_eq!(estimator.bucket_index(FeeRate::from_atoms_per_kb(1100)), 2);
+        assert_eq!(
+            estimator.bucket_index(FeeRate::from_atoms_per_kb(u128::MAX)),
+            BUCKET_COUNT - 1
+        );
+    }
+
+    // High fee transactions are confirmed in the next block, low fee ones have to wait
+    // for several blocks.
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn fast_and_slow_transactions(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        let high_fee_rate = FeeRate::from_atoms_per_kb(100_000);
+        let low_fee_rate = FeeRate::from_atoms_per_kb(2000);
+        let slow_delay = 5;
+
+        let mut estimator = ConfirmationFeeEstimator::new(MIN_FEE_RATE);
+        estimator.block_connected(BlockHeight::new(1), []);
+
+        let mut slow_txs = BTreeMap::<u64, Vec<Id<Transaction>>>::new();
+        for height in 2..200 {
+            let fast_txs: Vec<_> =
+                (0..rng.gen_range(2..5)).map(|_| random_tx_id(&mut rng)).collect();
+            for tx_id in &fast_txs {
+                estimator.tx_added(*tx_id, high_fee_rate);
+            }
+            let new_slow_txs: Vec<_> =
+                (0..rng.gen_range(2..5)).map(|_| random_tx_id(&mut rng)).collect();
+            for tx_id in &new_slow_txs {
+                estimator.tx_added(*tx_id, low_fee_rate);
+            }
+            slow_txs.insert(height + slow_delay - 1, new_slow_txs);
+
+            let confirmed_slow_txs = slow_txs.remove(&height).unwrap_or_default();
+            estimator.block_connected(
+                BlockHeight::new(height),
+                fast_txs.iter().chain(confirmed_slow_txs.iter()),
+            );
+        }
+
+        let fast_estimate = estimator.estimate(1).unwrap();
+        assert!(fast_estimate > low_fee_rate && fast_estimate <= high_fee_rate);
+        assert_eq!(estimator.estimate(0), Some(fast_estimate));
+
+        let slow_estimate = estimator.estimate(slow_delay as usize).unwrap();
+        assert!(slow_estimate <= low_fee_rate);
+        assert_eq!(
+            estimator.estimate(MAX_CONFIRMATION_TARGET + 1),
+            Some(slow_estimate)
+        );
+
+        // Evicted transactions are no longer tracked
+        assert!(estimator.tracked_tx_count() > 0);
+        estimator.retain_txs(|_| false);
+        assert_eq!(estimator.tracked_tx_count(), 0);
+    }
+}
@@ -15,10 +15,10 @@
 
 use std::{num::NonZeroUsize, sync::Arc};
 
-use chainstate::ChainstateEvent;
+use chainstate::{chainstate_interface::ChainstateInterface, ChainstateEvent};
 use common::{
     chain::{Block, ChainConfig, GenBlock, SignedTransaction, Transaction},
-    primitives::{time::Time, BlockHeight, Id},
+    primitives::{time::Time, BlockHeight, Id, Idable},
     time_getter::TimeGetter,
 };
 use logging::log;
@@ -29,25 +29,31 @@ pub use self::{feerate::FeeRate, tx_pool::feerate_points};
 use self::{
     entry::{TxDependency, TxEntry},
     fee::Fee,
+    fee_estimator::{ConfirmationFeeEstimator, MAX_CONFIRMATION_TARGET},
     memory_usage_estimator::MemoryUsageEstimator,
     orphans::{OrphanType, TxOrphanPool},
     tx_pool::{TxAdditionOutcome, TxPool},
 };
 use crate::{
     config,
+    diagnosis::TxDiagnosis,
     error::{
         BlockConstructionError, ChainstateEventError, Error, MempoolPolicyError, OrphanPoolError,
         ReorgError,
     },
     event::{self, MempoolEvent},
+    fee_estimator_replay::{RecordingWriter, ReplayError},
+    reorg_stats::ReorgStats,
     tx_accumulator::{PackingStrategy, TransactionAccumulator},
-    tx_options::{TxOptions, TxTrustPolicy},
+    tx_info::{MempoolTxInfo, ProjectedBlock},
+    tx_options::TxOptions,
     tx_origin::{RemoteTxOrigin, TxOrigin},
     MempoolMaxSize, TxStatus,
 };
 
 mod entry;
 pub mod fee;
+pub mod fee_estimator;
 mod feerate;
 mod orphans;
 mod tx_pool;
@@ -68,6 +74,15 @@ pub struct Mempool<M> {
     work_queue: WorkQueue,
     events_controller: EventsController<MempoolEvent>,
     clock: TimeGetter,
+    /// The last minimum fee rate announced to the event subscribers
+    min_fee_rate: FeeRate,
+    /// The minimum relay fee rate, below which the minimum fee rate never decays
+    min_relay_fee_rate: FeeRate,
+    fee_estimator: ConfirmationFeeEstimator,
+    /// The tip for which the fee estimator has been updated last
+    fee_estimator_tip: Option<Id<GenBlock>>,
+    /// Records the events seen by the fee estimator, if enabled in the config
+    fee_estimator_recorder: Option<RecordingWriter>,
 }
 
 impl<M> Mempool<M> {
@@ -78,6 +93,23 @@ impl<M> Mempool<M> {
         clock: TimeGetter,
         memory_usage_estimator: M,
     ) -> Self {
+        let min_fee_rate = *mempool_config.min_tx_relay_fee_rate;
+        let fee_estimator_recorder =
+            mempool_config.fee_estimator_recording_file.as_ref().and_then(|path| {
+                match RecordingWriter::open(path) {
+                    Ok(recorder) => {
+                        log::info!("Recording the mempool activity to {}", path.display());
+                        Some(recorder)
+                    }
+                    Err(err) => {
+                        log::error!(
+                            "Failed to open the fee estimator recording file {}: {err}",
+                            path.display()
+                        );
+                        None
+                    }
+                }
+            });
         let tx_pool = TxPool::new(
             chain_config,
             mempool_config,
@@ -91,6 +123,11 @@ impl<M> Mempool<M> {
             work_queue: WorkQueue::new(),
             events_controller: EventsController::new(),
             clock,
+            min_fee_rate,
+            min_relay_fee_rate: min_fee_rate,
+            fee_estimator: ConfirmationFeeEstimator::new(min_fee_rate),
+            fee_estimator_tip: None,
+            fee_estimator_recorder,
         }
     }
 
@@ -127,6 +164,36 @@ impl<M> Mempool<M> {
         self.tx_pool.best_block_id()
     }
 
+    pub fn transaction_info(&self, tx_id: &Id<Transaction>) -> Option<MempoolTxInfo> {
+        self.tx_pool.transaction_info(tx_id).or_else(|| {
+            self.orphans.get(tx_id).map(|entry| MempoolTxInfo {
+                transaction: entry.transaction().clone(),
+                status: TxStatus::InOrphanPool,
+                fee: None,
+                size: entry.size().get(),
+                time_added: entry.creation_time(),
+            })
+        })
+    }
+
+    pub fn unconfirmed_ancestors(&self, tx_id: &Id<Transaction>) -> Option<Vec<Id<Transaction>>> {
+        self.tx_pool.unconfirmed_ancestors(tx_id)
+    }
+
+    pub fn unconfirmed_descendants(&self, tx_id: &Id<Transaction>) -> Option<Vec<Id<Transaction>>> {
+        self.tx_pool.unconfirmed_descendants(tx_id)
+    }
+
+    pub fn diagnose_transaction(&self, tx_id: &Id<Transaction>) -> Option<TxDiagnosis> {
+        if let Some(diagnosis) = self.tx_pool.diagnose_transaction(tx_id) {
+            return Some(TxDiagnosis::InMempool(diagnosis));
+        }
+
+        self.orphans
+            .get(tx_id)
+            .map(|entry| TxDiagnosis::InOrphanPool(self.tx_pool.diagnose_orphan(entry)))
+    }
+
     pub fn chainstate_handle(&self) -> &chainstate::ChainstateHandle {
         self.tx_pool.chainstate_handle()
     }
@@ -142,6 +209,11 @@ impl<M> Mempool<M> {
             work_queue,
             events_controller,
             clock,
+            min_fee_rate: _,
+            min_relay_fee_rate: _,
+            fee_estimator: _,
+            fee_estimator_tip: _,
+            fee_estimator_recorder: _,
         } = self;
 
         let finalizer = TxFinalizer::new(orphans, clock, events_controller, work_queue);
@@ -152,22 +224,40 @@ impl<M> Mempool<M> {
 // Mempool Interface and Event Reactions
 impl<M: MemoryUsageEstimator> Mempool<M> {
     /// Add transaction to transaction pool if valid or orphan pool if it's a possible orphan.
+    ///
+    /// This is also how the orphans are moved to the transaction pool by the work queue, so
+    /// every transaction that enters the transaction pool is tracked by the fee estimator,
+    /// except for the ones returned to it by a reorg.
     pub fn add_transaction(&mut self, transaction: TxEntry) -> Result<TxStatus, Error> {
-        match transaction.options().trust_policy() {
-            TxTrustPolicy::Trusted => {
-                log::warn!(concat!(
-                    "Trusted mempool processing policy not yet implemented, ",
-                    "transaction will go through all the standard checks",
-                ))
-            }
-            TxTrustPolicy::Untrusted => (),
-        }
-
+        let tx_id = *transaction.tx_id();
         let (tx_pool, mut finalizer) = self.as_tx_pool_and_finalizer();
 
-        tx_pool.add_transaction(transaction, |outcome, tx_pool| {
+        let result = tx_pool.add_transaction(transaction, |outcome, tx_pool| {
             finalizer.finalize_tx(tx_pool, outcome)
-        })?
+        })?;
+
+        match result {
+            Ok(TxStatus::InMempool | TxStatus::InMempoolReplacement) => {
+                if let Some((fee, size)) = self.tx_pool.tx_fee_and_size(&tx_id) {
+                    if let Ok(fee_rate) = FeeRate::from_total_tx_fee(fee, size) {
+                        self.fee_estimator.tx_added(tx_id, fee_rate);
+                    }
+                    let size = size.get().try_into().expect("transaction size must fit into u32");
+                    self.record_fee_estimator_event(|recorder| {
+                        recorder.record_tx_added(tx_id, size, fee.into())
+                    });
+                }
+            }
+            Ok(
+                TxStatus::InMempoolDuplicate
+                | TxStatus::InOrphanPool
+                | TxStatus::InOrphanPoolDuplicate,
+            )
+            | Err(_) => {}
+        }
+
+        self.notify_min_fee_rate_change();
+        result
     }
 
     /// Make transaction entry out of a signed transaction.
@@ -242,28 +332,131 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
             }
         })?;
 
+        self.update_fee_estimator(block_id, height);
+
         let new_tip = event::NewTip::new(block_id, height);
         self.events_controller.broadcast(new_tip.into());
+        self.notify_min_fee_rate_change();
 
         Ok(())
     }
 
+    /// Record which of the tracked transactions have been confirmed by the blocks connected
+    /// since the previous tip; if the tip has moved by several blocks at once, each of them
+    /// is taken into account, up to `MAX_CONFIRMATION_TARGET` blocks.
+    fn update_fee_estimator(&mut self, block_id: Id<Block>, height: BlockHeight) {
+        let old_tip = self.fee_estimator_tip.replace(block_id.into());
+
+        let blocks_tx_ids = if self.fee_estimator.tracked_tx_count() == 0
+            && self.fee_estimator_recorder.is_none()
+        {
+            Vec::new()
+        } else {
+            let blocks = self
+                .tx_pool
+                .blocking_chainstate_handle()
+                .call(move |c| connected_blocks_tx_ids(c, old_tip, block_id))
+                .map_err(|err| err.to_string())
+                .and_then(|res| res.map_err(|err| err.to_string()));
+            match blocks {
+                Ok(blocks) => blocks,
+                Err(err) => {
+                    log::warn!("Fee estimator failed to load the blocks up to {block_id}: {err}");
+                    Vec::new()
+                }
+            }
+        };
+
+        if blocks_tx_ids.is_empty() {
+            self.fee_estimator.block_connected(height, []);
+        }
+        // The blocks are ordered from the new tip backwards
+        for (depth, block_tx_ids) in blocks_tx_ids.into_iter().enumerate().rev() {
+            let block_height = BlockHeight::new(height.into_int().saturating_sub(depth as u64));
+            self.fee_estimator.block_connected(block_height, &block_tx_ids);
+            self.record_fee_estimator_event(|recorder| {
+                recorder.record_block_connected(block_height, block_tx_ids)
+            });
+        }
+
+        let tx_pool = &self.tx_pool;
+        self.fee_estimator.retain_txs(|tx_id| tx_pool.contains_transaction(tx_id));
+    }
+
+    /// Write an event to the fee estimator recording, if it's enabled;
+    /// the recording is stopped on the first error
+    fn record_fee_estimator_event(
+        &mut self,
+        record: impl FnOnce(&mut RecordingWriter) -> Result<(), ReplayError>,
+    ) {
+        if let Some(recorder) = &mut self.fee_estimator_recorder {
+            if let Err(err) = record(recorder) {
+                log::error!("Fee estimator recording failed, stopping it: {err}");
+                self.fee_estimator_recorder = None;
+            }
+        }
+    }
+
+    /// Notify the subscribers if the minimum fee rate required to enter the mempool has changed
+    /// noticeably since the last notification or has decayed back to the minimum relay fee rate
+    fn notify_min_fee_rate_change(&mut self) {
+        let min_fee_rate = self.tx_pool.min_fee_rate();
+        if is_noticeable_fee_rate_change(self.min_fee_rate, min_fee_rate)
+            || (min_fee_rate == self.min_relay_fee_rate && min_fee_rate != self.min_fee_rate)
+        {
+            log::debug!("Mempool minimum fee rate changed to {min_fee_rate:?}");
+            self.min_fee_rate = min_fee_rate;
+            let evt = event::MinFeeRateChanged::new(min_fee_rate);
+            self.events_controller.broadcast(evt.into());
+        }
+    }
+
+    /// Re-check the minimum fee rate, which decays over time, and notify the subscribers
+    /// if it has changed; meant to be called periodically.
+    pub fn check_min_fee_rate(&mut self) {
+        self.notify_min_fee_rate_change();
+    }
+
     pub fn max_size(&self) -> MempoolMaxSize {
         self.tx_pool.max_size()
     }
 
     pub fn set_size_limit(&mut self, max_size: MempoolMaxSize) -> Result<(), Error> {
-        self.tx_pool.set_max_size(max_size)
+        let result = self.tx_pool.set_max_size(max_size);
+        self.notify_min_fee_rate_change();
+        result
+    }
+
+    pub fn accepts_zero_fee_txs(&self) -> bool {
+        self.tx_pool.accepts_zero_fee_txs()
+    }
+
+    pub fn set_accept_zero_fee_txs(&mut self, accept: bool) -> Result<(), Error> {
+        self.tx_pool.set_accept_zero_fee_txs(accept)
     }
 
     pub fn memory_usage(&self) -> usize {
         self.tx_pool.memory_usage()
     }
 
+    pub fn reorg_stats(&self) -> ReorgStats {
+        self.tx_pool.reorg_stats()
+    }
+
     pub fn get_fee_rate(&self, in_top_x_mb: usize) -> FeeRate {
         self.tx_pool.get_fee_rate(in_top_x_mb)
     }
 
+    pub fn get_min_fee_rate(&self) -> FeeRate {
+        self.tx_pool.min_fee_rate()
+    }
+
+    pub fn estimate_fee_rate(&self, target_blocks: usize) -> Option<FeeRate> {
+        self.fee_estimator
+            .estimate(target_blocks)
+            .map(|fee_rate| std::cmp::max(fee_rate, self.tx_pool.min_fee_rate()))
+    }
+
     pub fn get_fee_rate_points(
         &self,
         num_points: NonZeroUsize,
@@ -279,6 +472,66 @@ impl<M: MemoryUsageEstimator> Mempool<M> {
     ) -> Result<Option<Box<dyn TransactionAccumulator>>, BlockConstructionError> {
         self.tx_pool.collect_txs(tx_accumulator, transaction_ids, packing_strategy)
     }
+
+    pub fn projected_next_block(&self) -> Result<ProjectedBlock, BlockConstructionError> {
+        self.tx_pool.projected_next_block()
+    }
+}
+
+/// Whether the fee rate has changed by at least `MIN_FEE_RATE_CHANGE_NOTIFICATION_PERCENT`
+fn is_noticeable_fee_rate_change(old: FeeRate, new: FeeRate) -> bool {
+    let old = old.atoms_per_kb();
+    let diff = old.abs_diff(new.atoms_per_kb());
+    diff > 0
+        && diff.saturating_mul(100)
+            >= old.saturating_mul(config::MIN_FEE_RATE_CHANGE_NOTIFICATION_PERCENT)
+}
+
+/// Collect the ids of the transactions of the blocks connected since `old_tip`, going from
+/// `new_tip` backwards, at most `MAX_CONFIRMATION_TARGET` blocks.
+///
+/// Only the new tip is taken if the old one is not known.
+fn connected_blocks_tx_ids<C: ChainstateInterface + ?Sized>(
+    chainstate: &C,
+    old_tip: Option<Id<GenBlock>>,
+    new_tip: Id<Block>,
+) -> Result<Vec<Vec<Id<Transaction>>>, chainstate::ChainstateError> {
+    let new_tip: Id<GenBlock> = new_tip.into();
+
+    let common_ancestor_id = match old_tip {
+        Some(old_tip) => {
+            let old_index = chainstate.get_gen_block_index_for_persisted_block(&old_tip)?;
+            let new_index = chainstate.get_gen_block_index_for_persisted_block(&new_tip)?;
+            match (old_index, new_index) {
+                (Some(old_index), Some(new_index)) => {
+                    Some(chainstate.last_common_ancestor(&old_index, &new_index)?.block_id())
+                }
+                (None, _) | (_, None) => None,
+            }
+        }
+        None => None,
+    };
+    let max_blocks = if common_ancestor_id.is_some() {
+        MAX_CONFIRMATION_TARGET
+    } else {
+        1
+    };
+
+    let mut result = Vec::new();
+    let mut curr_id = new_tip;
+    while Some(curr_id) != common_ancestor_id && result.len() < max_blocks {
+        let Some(block_id) = curr_id.classify(chainstate.get_chain_config()).chain_block_id()
+        else {
+            break;
+        };
+        let Some(block) = chainstate.get_block(block_id)? else {
+            break;
+        };
+        curr_id = block.prev_block_id();
+        result.push(block.transactions().iter().map(|tx| tx.transaction().get_id()).collect());
+    }
+
+    Ok(result)
 }
 
 /// [TxFinalizer] holds data needed to finalize the transaction processing after it's been processed
@@ -316,7 +569,10 @@ impl<'a> TxFinalizer<'a> {
         outcome: TxAdditionOutcome,
     ) -> crate::Result<TxStatus> {
         match outcome {
-            TxAdditionOutcome::Added { transaction } => {
+            TxAdditionOutcome::Added {
+                transaction,
+                replaced,
+            } => {
                 let tx_id = *transaction.tx_id();
                 let relay_policy = transaction.tx_entry().options().relay_policy();
                 let origin = transaction.tx_entry().origin();
@@ -325,7 +581,16 @@ impl<'a> TxFinalizer<'a> {
                 self.enqueue_children(transaction.tx_entry());
                 let evt = event::TransactionProcessed::accepted(tx_id, relay_policy, origin);
                 self.events_controller.broadcast(evt.into());
-                Ok(TxStatus::InMempool)
+
+                if replaced.is_empty() {
+                    Ok(TxStatus::InMempool)
+                } else {
+                    log::debug!(
+                        "Transaction {tx_id} replaced {} transactions",
+                        replaced.len()
+                    );
+                    Ok(TxStatus::InMempoolReplacement)
+                }
             }
             TxAdditionOutcome::Duplicate { transaction } => {
                 log::trace!("Duplicate transaction {}", transaction.tx_id());
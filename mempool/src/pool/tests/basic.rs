@@ -182,3 +182,70 @@ async fn spends_new_unconfirmed(#[case] seed: Seed) -> anyhow::Result<()> {
     mempool.tx_store().assert_valid();
     Ok(())
 }
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn min_fee_rate_change_broadcast(#[case] seed: Seed) -> anyhow::Result<()> {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis = tf.genesis();
+    let tx = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(OutPointSourceId::BlockReward(genesis.get_id().into()), 0),
+            empty_witness(&mut rng),
+        )
+        .add_output(TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(999_999_999_000)),
+            anyonecanspend_address(),
+        ))
+        .build();
+
+    let mut mempool = setup_with_chainstate(tf.chainstate());
+    let min_relay_fee_rate = *create_mempool_config().min_tx_relay_fee_rate;
+    assert_eq!(mempool.get_min_fee_rate(), min_relay_fee_rate);
+
+    let fee_rate_changes = Arc::new(std::sync::Mutex::new(Vec::new()));
+    mempool.subscribe_to_events(Arc::new({
+        let fee_rate_changes = Arc::clone(&fee_rate_changes);
+        move |evt| match evt {
+            MempoolEvent::MinFeeRateChanged(evt) => {
+                fee_rate_changes.lock().unwrap().push(evt.fee_rate())
+            }
+            MempoolEvent::NewTip(_) | MempoolEvent::TransactionProcessed(_) => (),
+        }
+    }));
+
+    mempool.add_transaction_test(tx)?.assert_in_mempool();
+    assert!(fee_rate_changes.lock().unwrap().is_empty());
+
+    // Shrinking the mempool evicts the transaction and raises the floor
+    let size_limit = mempool.max_size();
+    mempool.set_size_limit(MempoolMaxSize::from_bytes(0))?;
+    let raised_fee_rate = mempool.get_min_fee_rate();
+    assert!(raised_fee_rate > min_relay_fee_rate);
+    assert_eq!(*fee_rate_changes.lock().unwrap(), vec![raised_fee_rate]);
+
+    // Growing it back drops the rolling fee
+    mempool.set_size_limit(size_limit)?;
+    assert_eq!(mempool.get_min_fee_rate(), min_relay_fee_rate);
+    assert_eq!(
+        *fee_rate_changes.lock().unwrap(),
+        vec![raised_fee_rate, min_relay_fee_rate]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn min_fee_rate_small_changes_not_noticeable() {
+    let rate = FeeRate::from_atoms_per_kb;
+
+    assert!(!is_noticeable_fee_rate_change(rate(1000), rate(1000)));
+    assert!(!is_noticeable_fee_rate_change(rate(1000), rate(950)));
+    assert!(!is_noticeable_fee_rate_change(rate(1000), rate(1099)));
+    assert!(is_noticeable_fee_rate_change(rate(1000), rate(900)));
+    assert!(is_noticeable_fee_rate_change(rate(1000), rate(1100)));
+    assert!(is_noticeable_fee_rate_change(rate(0), rate(1)));
+}
@@ -84,6 +84,57 @@ async fn two_transactions_in_sequence(
     assert_eq!(mempool.contains_transaction(&tx1_id), expected_in_mempool);
 }
 
+// The transactions are tracked by the fee estimator once they are in the mempool, including
+// the orphans moved there by the work queue, but not while they are in the orphan pool.
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fee_estimator_tracks_released_orphans(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis_id = tf.genesis().get_id();
+
+    let tx0 = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(genesis_id.into(), 0),
+            empty_witness(&mut rng),
+        )
+        .add_output(TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(100_000_000)),
+            Destination::AnyoneCanSpend,
+        ))
+        .build();
+    let tx0_id = tx0.transaction().get_id();
+
+    let tx1 = TransactionBuilder::new()
+        .add_input(
+            TxInput::from_utxo(tx0_id.into(), 0),
+            empty_witness(&mut rng),
+        )
+        .add_output(TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(90_000_000)),
+            Destination::AnyoneCanSpend,
+        ))
+        .build();
+    let tx1_id = tx1.transaction().get_id();
+
+    let mut mempool = setup_with_chainstate(tf.chainstate());
+    mempool.fee_estimator.block_connected(BlockHeight::zero(), []);
+
+    mempool.add_transaction_test(tx1).unwrap().assert_in_orphan_pool();
+    assert_eq!(mempool.fee_estimator.tracked_tx_count(), 0);
+
+    let res = mempool.add_transaction_test(tx0.clone());
+    assert_eq!(res, Ok(TxStatus::InMempool));
+    assert!(mempool.contains_transaction(&tx1_id));
+    assert_eq!(mempool.fee_estimator.tracked_tx_count(), 2);
+
+    let res = mempool.add_transaction_test(tx0);
+    assert_eq!(res, Ok(TxStatus::InMempoolDuplicate));
+    assert_eq!(mempool.fee_estimator.tracked_tx_count(), 2);
+}
+
 // Below, each test case encodes a sequence of transaction insertions.
 //
 // Each element of the Vec contains:
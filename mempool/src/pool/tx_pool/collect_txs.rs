@@ -99,6 +99,12 @@ pub fn collect_txs<M>(
         .expect("best index to exist");
     let tx_source = TransactionSourceForConnect::for_mempool(&best_index);
 
+    let policy = tx_accumulator.policy().clone();
+
+    // Transaction IDs required by the policy, followed by the ones specified by the user
+    let transaction_ids: Vec<_> =
+        policy.required_tx_ids.iter().chain(transaction_ids.iter()).copied().collect();
+
     // Use transactions already in the Accumulator to check for uniqueness and to update the
     // verifier state to update UTXOs they consume / provide.
     let accum_ids = tx_accumulator
@@ -116,7 +122,7 @@ pub fn collect_txs<M>(
     // Set of already processed transactions, for de-duplication
     let mut processed = emitted.clone();
 
-    // Transaction IDs specified by the user
+    // Transaction IDs specified by the user or required by the policy
     let given_txids = {
         for tx_id in &transaction_ids {
             ensure!(
@@ -128,8 +134,26 @@ pub fn collect_txs<M>(
         graph_traversals::dag_depth_postorder_multiroot(&transaction_ids, |tx_id| {
             mempool.store.get_entry(tx_id).expect("already checked").parents()
         })
+        .collect::<Vec<_>>()
     };
 
+    // Required transactions and their ancestors are not subject to the low fee rate limit
+    let forced_txids = if policy.required_tx_ids.is_empty() {
+        BTreeSet::new()
+    } else {
+        graph_traversals::dag_depth_postorder_multiroot(&policy.required_tx_ids, |tx_id| {
+            mempool.store.get_entry(tx_id).expect("already checked").parents()
+        })
+        .collect::<BTreeSet<_>>()
+    };
+
+    for tx_id in &forced_txids {
+        ensure!(
+            !policy.is_excluded(tx_id),
+            BlockConstructionError::RequiredTxExcluded(**tx_id),
+        );
+    }
+
     // Transaction IDs taken from mempool to fill in the rest of the block
     let mempool_txids = {
         // Get transactions from mempool by score
@@ -143,10 +167,13 @@ pub fn collect_txs<M>(
 
     // Put all the transaction IDs together
     let mut tx_iter = given_txids
+        .into_iter()
         .chain(mempool_txids)
         .filter_map(|tx_id| {
             // If the transaction with this ID has already been processed, skip it
             ensure!(processed.insert(tx_id));
+            // Excluded transactions are never emitted, so their descendants stay pending
+            ensure!(!policy.is_excluded(tx_id));
             let tx = mempool.store.txs_by_id.get(tx_id).expect("already checked").deref();
 
             tx_verifier::input_check::verify_timelocks(
@@ -194,6 +221,12 @@ pub fn collect_txs<M>(
             (None, None) => break,
         };
 
+        if !forced_txids.contains(&next_tx.tx_id())
+            && !tx_accumulator.fits_low_fee_rate_space(next_tx.size().get(), next_tx.fee())
+        {
+            continue;
+        }
+
         let verification_result =
             tx_verifier.connect_transaction(&tx_source, next_tx.transaction(), &unlock_timestamp);
 
@@ -234,6 +267,13 @@ pub fn collect_txs<M>(
         }
     }
 
+    for tx_id in &policy.required_tx_ids {
+        ensure!(
+            emitted.contains(&tx_id),
+            BlockConstructionError::RequiredTxNotIncluded(*tx_id),
+        );
+    }
+
     let final_chainstate_tip =
         utxo::UtxosView::best_block_hash(&chainstate).expect("cannot fetch tip");
     ensure!(
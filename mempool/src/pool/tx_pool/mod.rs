@@ -33,6 +33,7 @@ use utxo::UtxosStorageRead;
 use chainstate::{
     chainstate_interface::ChainstateInterface,
     tx_verifier::{
+        check_transaction_stateless,
         transaction_verifier::{TransactionSourceForConnect, TransactionVerifierDelta},
         TransactionSource,
     },
@@ -40,13 +41,14 @@ use chainstate::{
 };
 use common::{
     chain::{
-        block::timestamp::BlockTimestamp, Block, ChainConfig, GenBlock, SignedTransaction,
-        Transaction, TxInput,
+        block::timestamp::BlockTimestamp, config::ChainType, Block, ChainConfig, GenBlock,
+        SignedTransaction, Transaction, TxInput,
     },
-    primitives::{amount::DisplayAmount, time::Time, Amount, BlockHeight, Id},
+    primitives::{amount::DisplayAmount, time::Time, Amount, BlockHeight, Id, Idable},
     time_getter::TimeGetter,
 };
 use logging::log;
+use serialization::Encode;
 use utils::{const_value::ConstValue, ensure, shallow_clone::ShallowClone};
 
 use self::{
@@ -56,17 +58,22 @@ use self::{
 };
 use crate::{
     config::{self, MempoolConfig, MempoolMaxSize},
+    diagnosis::{MempoolTxDiagnosis, OrphanTxDiagnosis},
     error::{
         BlockConstructionError, Error, MempoolConflictError, MempoolPolicyError, OrphanPoolError,
         ReorgError, TxValidationError,
     },
     pool::{
-        entry::{TxEntry, TxEntryWithFee},
+        entry::{TxDependency, TxEntry, TxEntryWithFee},
         fee::Fee,
         feerate::FeeRate,
     },
-    tx_accumulator::{PackingStrategy, TransactionAccumulator},
+    reorg_stats::ReorgStats,
+    tx_accumulator::{DefaultTxAccumulator, PackingStrategy, TransactionAccumulator},
+    tx_info::{MempoolTxInfo, ProjectedBlock},
+    tx_options::TxTrustPolicy,
     tx_origin::RemoteTxOrigin,
+    TxStatus,
 };
 
 pub struct TxPool<M> {
@@ -76,10 +83,12 @@ pub struct TxPool<M> {
     rolling_fee_rate: RwLock<RollingFeeRate>,
     max_size: config::MempoolMaxSize,
     max_tx_age: Duration,
+    accept_zero_fee_txs: bool,
     chainstate_handle: chainstate::ChainstateHandle,
     clock: TimeGetter,
     memory_usage_estimator: M,
     tx_verifier: tx_verifier::TransactionVerifier,
+    reorg_stats: ReorgStats,
 }
 
 impl<M> std::fmt::Debug for TxPool<M> {
@@ -102,6 +111,12 @@ impl<M> TxPool<M> {
             chainstate_handle.shallow_clone(),
         );
 
+        let is_regtest = *chain_config.chain_type() == ChainType::Regtest;
+        if *mempool_config.accept_zero_fee_txs && !is_regtest {
+            log::warn!("Accepting zero-fee transactions is only allowed on regtest, ignoring");
+        }
+        let accept_zero_fee_txs = *mempool_config.accept_zero_fee_txs && is_regtest;
+
         log::trace!("Creating mempool object");
         Self {
             chain_config,
@@ -110,10 +125,12 @@ impl<M> TxPool<M> {
             chainstate_handle,
             max_size: config::MempoolMaxSize::default(),
             max_tx_age: config::DEFAULT_MEMPOOL_EXPIRY,
+            accept_zero_fee_txs,
             rolling_fee_rate: RwLock::new(RollingFeeRate::new(clock.get_time())),
             clock,
             memory_usage_estimator,
             tx_verifier,
+            reorg_stats: ReorgStats::default(),
         }
     }
 
@@ -136,6 +153,14 @@ impl<M> TxPool<M> {
         self.max_size
     }
 
+    pub fn reorg_stats(&self) -> ReorgStats {
+        self.reorg_stats
+    }
+
+    pub fn accepts_zero_fee_txs(&self) -> bool {
+        self.accept_zero_fee_txs
+    }
+
     // Reset the mempool state, returning the list of transactions previously stored in mempool
     pub fn reset(&mut self) -> impl Iterator<Item = TxEntry> {
         // Discard the old tx verifier and replace it with a fresh one
@@ -147,9 +172,11 @@ impl<M> TxPool<M> {
         std::mem::replace(&mut self.store, MempoolStore::new()).into_transactions()
     }
 
-    pub fn is_ibd(&self) -> bool {
+    /// Transactions are not accepted while the node is in IBD, unless this is disabled in the
+    /// chainstate config.
+    pub fn is_tx_processing_deferred(&self) -> bool {
         self.blocking_chainstate_handle()
-            .call(|chainstate| chainstate.is_initial_block_download())
+            .call(|chainstate| chainstate.is_tx_processing_deferred())
             .expect("IBD state query failed")
     }
 
@@ -221,6 +248,18 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
         )
     }
 
+    /// The minimum fee rate a new transaction has to pay to be accepted into the mempool.
+    ///
+    /// This is the rolling minimum fee rate, which is raised when the mempool gets full and
+    /// transactions are trimmed, and decays over time afterwards, but never lower than
+    /// the minimum relay fee rate.
+    pub fn min_fee_rate(&self) -> FeeRate {
+        std::cmp::max(
+            self.get_update_min_fee_rate(),
+            *self.mempool_config.min_tx_relay_fee_rate,
+        )
+    }
+
     fn drop_rolling_fee(&self) {
         let mut rolling_fee_rate = self.rolling_fee_rate.write();
         (*rolling_fee_rate)
@@ -244,6 +283,34 @@ impl<M> TxPool<M> {
     pub fn transaction(&self, id: &Id<Transaction>) -> Option<&SignedTransaction> {
         self.store.get_entry(id).map(TxMempoolEntry::transaction)
     }
+
+    pub fn transaction_info(&self, id: &Id<Transaction>) -> Option<MempoolTxInfo> {
+        self.store.get_entry(id).map(|entry| MempoolTxInfo {
+            transaction: entry.transaction().clone(),
+            status: TxStatus::InMempool,
+            fee: Some(entry.fee().into()),
+            size: entry.size().get(),
+            time_added: entry.creation_time(),
+        })
+    }
+
+    pub fn unconfirmed_ancestors(&self, id: &Id<Transaction>) -> Option<Vec<Id<Transaction>>> {
+        self.store
+            .unconfirmed_ancestors_of(id)
+            .map(|ancestors| ancestors.iter().copied().collect())
+    }
+
+    pub fn unconfirmed_descendants(&self, id: &Id<Transaction>) -> Option<Vec<Id<Transaction>>> {
+        self.store
+            .unconfirmed_descendants_of(id)
+            .map(|descendants| descendants.iter().copied().collect())
+    }
+
+    /// The fee and the size of the transaction itself, not taking its ancestors or descendants
+    /// into account
+    pub fn tx_fee_and_size(&self, id: &Id<Transaction>) -> Option<(Fee, NonZeroUsize)> {
+        self.store.get_entry(id).map(|entry| (entry.fee(), entry.size()))
+    }
 }
 
 // Transaction Validation
@@ -265,15 +332,33 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
         Ok(())
     }
 
+    // Consensus checks that don't need the chainstate, done before the full validation to reject
+    // malformed transactions early
+    fn precheck_transaction(&self, entry: &TxEntry) -> Result<(), TxValidationError> {
+        check_transaction_stateless(&self.chain_config, entry.transaction())
+            .map_err(ConnectTransactionError::from)?;
+        Ok(())
+    }
+
     // Check the transaction against the mempool inclusion policy
     fn check_mempool_policy(
         &self,
         entry: &TxEntryWithFee,
     ) -> Result<Conflicts, MempoolPolicyError> {
-        self.pays_minimum_relay_fees(entry)?;
-        self.pays_minimum_mempool_fee(entry)?;
+        if !self.accept_zero_fee_txs {
+            match entry.tx_entry().options().trust_policy() {
+                TxTrustPolicy::Trusted => {
+                    log::debug!(
+                        "Skipping minimum relay fee check for trusted tx {}",
+                        entry.tx_entry().tx_id()
+                    );
+                }
+                TxTrustPolicy::Untrusted => self.pays_minimum_relay_fees(entry)?,
+            }
+            self.pays_minimum_mempool_fee(entry)?;
+        }
 
-        if config::ENABLE_RBF {
+        if *self.mempool_config.enable_rbf {
             self.rbf_checks(entry)
         } else {
             // Without RBF enabled, any conflicting transaction results in an error
@@ -334,6 +419,18 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
         entry.requires().filter_map(|dep| self.store.find_conflicting_tx(&dep))
     }
 
+    /// The transactions the given entry would replace, i.e. its conflicts together with their
+    /// descendants, ordered so that descendants come before their ancestors.
+    fn replacement_candidates(&self, entry: &TxEntry) -> Vec<Id<Transaction>> {
+        let mut seen = BTreeSet::new();
+        self.conflicting_tx_ids(entry)
+            .map(|id_conflict| self.store.get_entry(id_conflict).expect("entry for id"))
+            .flat_map(|conflict| conflict.depth_postorder_descendants(&self.store))
+            .map(|entry| *entry.tx_id())
+            .filter(|tx_id| seen.insert(*tx_id))
+            .collect()
+    }
+
     fn spends_unconfirmed(&self, input: &TxInput) -> bool {
         // TODO: if TxInput spends from an account there is no way to know tx_id
         match input {
@@ -344,6 +441,75 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
             TxInput::Account(..) | TxInput::AccountCommand(..) => false,
         }
     }
+
+    /// Estimate the standing of the transaction in the order in which transactions are taken
+    /// into blocks. The estimate doesn't take the timelocks and the ancestors with a lower
+    /// package fee rate into account, so the actual position can be further back.
+    pub fn diagnose_transaction(&self, tx_id: &Id<Transaction>) -> Option<MempoolTxDiagnosis> {
+        let entry = self.store.get_entry(tx_id)?;
+        let max_block_size = self.chain_config.max_block_size_from_std_scripts();
+
+        let mut position = None;
+        let mut last_fitting_score = None;
+        let mut next_block_cutoff_fee_rate = None;
+        let mut total_size = 0;
+        for (index, (score, id)) in self.store.txs_by_ancestor_score.iter().rev().enumerate() {
+            if id == tx_id {
+                position = Some((index, total_size));
+            }
+
+            let size: usize = self.store.txs_by_id.get(id).map_or(0, |tx| tx.size().into());
+            if total_size + size > max_block_size {
+                // The block is full, so the cutoff is set by the last transaction that fits into it
+                next_block_cutoff_fee_rate.get_or_insert(last_fitting_score.unwrap_or(**score));
+            } else {
+                last_fitting_score = Some(**score);
+            }
+            total_size += size;
+
+            if position.is_some() && next_block_cutoff_fee_rate.is_some() {
+                break;
+            }
+        }
+        let (txs_ahead, size_ahead) = position.expect("entry must be indexed by score");
+
+        Some(MempoolTxDiagnosis {
+            package_fee_rate: *entry.ancestor_score(),
+            next_block_cutoff_fee_rate,
+            txs_ahead,
+            size_ahead,
+            fits_in_next_block: size_ahead + usize::from(entry.size()) <= max_block_size,
+            unconfirmed_ancestors: entry
+                .unconfirmed_ancestors(&self.store)
+                .iter()
+                .copied()
+                .collect(),
+        })
+    }
+
+    /// Find what prevents the orphan transaction from entering the pool
+    pub fn diagnose_orphan<O: crate::tx_origin::IsOrigin>(
+        &self,
+        entry: &TxEntry<O>,
+    ) -> OrphanTxDiagnosis {
+        let missing_parents = entry
+            .requires()
+            .filter_map(|dep| match dep {
+                TxDependency::TxOutput(parent_id, _) => Some(parent_id),
+                TxDependency::DelegationAccount(_)
+                | TxDependency::TokenSupplyAccount(_)
+                | TxDependency::OrderAccount(_) => None,
+            })
+            .filter(|parent_id| !self.contains_transaction(parent_id))
+            .collect::<BTreeSet<_>>();
+
+        let conflicts = self.conflicting_tx_ids(entry).copied().collect::<BTreeSet<_>>();
+
+        OrphanTxDiagnosis {
+            missing_parents: missing_parents.into_iter().collect(),
+            conflicts: conflicts.into_iter().collect(),
+        }
+    }
 }
 
 // RBF checks
@@ -368,9 +534,7 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
             return Ok(());
         }
 
-        if config::ENABLE_RBF {
-            // Note: Since RBF is currently disabled, the following is effectively dead code and
-            // completely untested. Needs to be reviewed when RBF is re-enabled.
+        if *self.mempool_config.enable_rbf {
             let conflicts: Vec<_> = conflicts
                 .map(|id_conflict| self.store.get_entry(id_conflict).expect("entry for id"))
                 .collect();
@@ -397,6 +561,8 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
         // transactions that would need to be removed (direct conflicts and all descendants), check
         // that the replacement transaction pays more than its direct conflicts.
         self.pays_more_than_direct_conflicts(tx, conflicts)?;
+        // The replacement has to pay a sufficiently higher fee rate than its direct conflicts.
+        self.pays_higher_fee_rate_than_direct_conflicts(tx, conflicts)?;
         // Enforce BIP125 Rule #2.
         self.spends_no_new_unconfirmed_outputs(tx.tx_entry(), conflicts)?;
         // Enforce BIP125 Rule #5.
@@ -483,7 +649,7 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
             unconfirmed && new
         });
         ensure!(
-            spends_new_unconfirmed,
+            !spends_new_unconfirmed,
             MempoolConflictError::SpendsNewUnconfirmed,
         );
         Ok(())
@@ -508,6 +674,28 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
         )
     }
 
+    fn pays_higher_fee_rate_than_direct_conflicts(
+        &self,
+        tx: &TxEntryWithFee,
+        conflicts: &[&TxMempoolEntry],
+    ) -> Result<(), MempoolPolicyError> {
+        let replacement_fee_rate = FeeRate::from_total_tx_fee(tx.fee(), tx.tx_entry().size())?;
+        for conflict in conflicts {
+            let conflict_fee_rate = FeeRate::from_total_tx_fee(conflict.fee(), conflict.size())?;
+            let min_fee_rate = (conflict_fee_rate
+                + *self.mempool_config.min_rbf_fee_rate_increment)
+                .ok_or(MempoolPolicyError::FeeOverflow)?;
+            ensure!(
+                replacement_fee_rate >= min_fee_rate,
+                MempoolPolicyError::ReplacementFeeRateTooLow {
+                    replacement_fee_rate,
+                    min_fee_rate,
+                }
+            );
+        }
+        Ok(())
+    }
+
     fn potential_replacements_within_limit(
         &self,
         conflicts: &[&TxMempoolEntry],
@@ -561,6 +749,15 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
         self.limit_mempool_size()
     }
 
+    pub fn set_accept_zero_fee_txs(&mut self, accept: bool) -> Result<(), Error> {
+        ensure!(
+            *self.chain_config.chain_type() == ChainType::Regtest,
+            Error::ZeroFeeTxsNotAllowed
+        );
+        self.accept_zero_fee_txs = accept;
+        Ok(())
+    }
+
     fn limit_mempool_size(&mut self) -> Result<(), Error> {
         let removed_fees = self.trim()?;
         if !removed_fees.is_empty() {
@@ -663,8 +860,11 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
 #[must_use = "Check transaction addition outcome"]
 #[allow(clippy::large_enum_variant)]
 pub enum TxAdditionOutcome<'a> {
-    /// Transaction was added to mempool
-    Added { transaction: &'a TxMempoolEntry },
+    /// Transaction was added to mempool, replacing the listed conflicting transactions
+    Added {
+        transaction: &'a TxMempoolEntry,
+        replaced: Vec<Id<Transaction>>,
+    },
 
     /// Transaction already in mempool
     Duplicate { transaction: &'a TxMempoolEntry },
@@ -680,8 +880,8 @@ pub enum TxAdditionOutcome<'a> {
 #[must_use = "Check transaction addition outcome"]
 #[allow(clippy::large_enum_variant)]
 pub enum TxAdditionAttemptOutcome {
-    /// Transaction was added to mempool
-    Added,
+    /// Transaction was added to mempool, replacing the listed conflicting transactions
+    Added { replaced: Vec<Id<Transaction>> },
 
     /// Transaction was rejected from the mempool since it is not valid at the current tip
     Rejected {
@@ -720,7 +920,10 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
         mut transaction: TxEntry,
         finalizer: impl for<'b> FnOnce(TxAdditionOutcome, &'b Self) -> R,
     ) -> Result<R, Error> {
-        ensure!(!self.is_ibd(), TxValidationError::AddedDuringIBD);
+        ensure!(
+            !self.is_tx_processing_deferred(),
+            TxValidationError::AddedDuringIBD
+        );
 
         let tx_id = *transaction.tx_id();
         if let Some(transaction) = self.store.get_entry(&tx_id) {
@@ -729,13 +932,17 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
         }
 
         self.check_preliminary_mempool_policy(&transaction)?;
+        self.precheck_transaction(&transaction)?;
 
         for attempt_no in 1..=config::MAX_TX_ADDITION_ATTEMPTS {
             log::trace!("Adding {tx_id:?} attempt #{attempt_no}");
             transaction = match self.try_add_transaction(transaction)? {
-                TxAdditionAttemptOutcome::Added => {
+                TxAdditionAttemptOutcome::Added { replaced } => {
                     let transaction = self.store.get_entry(&tx_id).expect("just added");
-                    let outcome = TxAdditionOutcome::Added { transaction };
+                    let outcome = TxAdditionOutcome::Added {
+                        transaction,
+                        replaced,
+                    };
                     return Ok(finalizer(outcome, self));
                 }
                 TxAdditionAttemptOutcome::Rejected { transaction, error } => {
@@ -762,9 +969,17 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
         &mut self,
         transaction: TxEntry,
     ) -> Result<TxAdditionAttemptOutcome, Error> {
-        debug_assert!(!self.is_ibd());
+        debug_assert!(!self.is_tx_processing_deferred());
+
+        // With RBF, the transaction is validated as if the transactions it would replace were
+        // not in the mempool. Whether the replacement is allowed is checked afterwards.
+        let replaced = if *self.mempool_config.enable_rbf {
+            self.replacement_candidates(&transaction)
+        } else {
+            Vec::new()
+        };
 
-        let (fee, delta) = match self.validate_transaction(&transaction)? {
+        let (fee, delta) = match self.validate_transaction(&transaction, &replaced)? {
             TxValidationOutcome::Valid { fee, delta } => (fee, delta),
             TxValidationOutcome::Rejected { error } => {
                 return Ok(TxAdditionAttemptOutcome::Rejected { transaction, error })
@@ -784,19 +999,22 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
         let tx = TxEntryWithFee::new(transaction, fee);
         let conflicts = self.check_mempool_policy(&tx)?;
 
-        if config::ENABLE_RBF {
-            self.store.drop_conflicts(conflicts);
+        // The replaced transactions have already been disconnected in the verifier delta
+        let replaced = self.store.drop_conflicts(conflicts);
+        if !replaced.is_empty() {
+            log::debug!("Transaction {} replaced {replaced:?}", tx.tx_id());
         }
         tx_verifier::flush_to_storage(&mut self.tx_verifier, delta)?;
         self.finalize_tx(tx)?;
         self.store.assert_valid();
 
-        Ok(TxAdditionAttemptOutcome::Added)
+        Ok(TxAdditionAttemptOutcome::Added { replaced })
     }
 
     fn validate_transaction(
         &mut self,
         transaction: &TxEntry,
+        replaced: &[Id<Transaction>],
     ) -> Result<TxValidationOutcome, TxValidationError> {
         let tx_id = *transaction.tx_id();
         let chainstate_handle = self.blocking_chainstate_handle();
@@ -811,6 +1029,14 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
 
         let mut tx_verifier = self.tx_verifier.derive_child();
 
+        for replaced_id in replaced {
+            let replaced_entry = self.store.get_entry(replaced_id).expect("replaced tx in mempool");
+            tx_verifier.disconnect_transaction(
+                &TransactionSource::Mempool,
+                replaced_entry.transaction(),
+            )?;
+        }
+
         log::trace!(
             "Verifying {tx_id:?}, tip = {start_tip:?}, tx_verifier's best block for utxos = {:?}",
             tx_verifier.get_best_block_for_utxos()?
@@ -862,6 +1088,38 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
         collect_txs::collect_txs(self, tx_accumulator, transaction_ids, packing_strategy)
     }
 
+    /// Select the transactions that would be put into a block built on the current tip right
+    /// now. The default accumulator policy is used, so the actual block may differ if the block
+    /// producer restricts the space available to low fee rate transactions.
+    pub fn projected_next_block(&self) -> Result<ProjectedBlock, BlockConstructionError> {
+        let tip = self.best_block_id();
+        let median_time_past = self
+            .blocking_chainstate_handle()
+            .call(move |chainstate| chainstate.calculate_median_time_past(&tip))?
+            .map_err(TxValidationError::from)?;
+
+        let accumulator = DefaultTxAccumulator::new(
+            self.chain_config.max_block_size_from_std_scripts(),
+            tip,
+            median_time_past,
+        );
+        let accumulator = self
+            .collect_txs(
+                Box::new(accumulator),
+                vec![],
+                PackingStrategy::FillSpaceFromMempool,
+            )?
+            .expect("the accumulator expects the mempool tip");
+
+        let transactions = accumulator.transactions();
+        Ok(ProjectedBlock {
+            prev_block_id: tip,
+            tx_ids: transactions.iter().map(|tx| tx.transaction().get_id()).collect(),
+            total_fees: accumulator.total_fees().into(),
+            total_size: transactions.iter().map(Encode::encoded_size).sum(),
+        })
+    }
+
     pub fn reorg(
         &mut self,
         block_id: Id<Block>,
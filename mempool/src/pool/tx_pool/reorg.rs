@@ -111,7 +111,7 @@ impl ReorgData {
 fn fetch_disconnected_txs<M>(
     tx_pool: &TxPool<M>,
     new_tip: Id<Block>,
-) -> Result<impl Iterator<Item = TxEntry>, ReorgError> {
+) -> Result<(usize, impl Iterator<Item = TxEntry>), ReorgError> {
     let old_tip = tx_pool
         .tx_verifier
         .get_best_block_for_utxos()
@@ -124,7 +124,12 @@ fn fetch_disconnected_txs<M>(
     tx_pool
         .blocking_chainstate_handle()
         .call(move |c| ReorgData::from_chainstate(c, old_tip, new_tip.into()))?
-        .map(|data| data.into_disconnected_transactions(now))
+        .map(|data| {
+            (
+                data.disconnected.len(),
+                data.into_disconnected_transactions(now),
+            )
+        })
 }
 
 pub fn handle_new_tip<M: MemoryUsageEstimator>(
@@ -135,7 +140,7 @@ pub fn handle_new_tip<M: MemoryUsageEstimator>(
     tx_pool.rolling_fee_rate.get_mut().set_block_since_last_rolling_fee_bump(true);
 
     let (is_ibd, actual_tip) = tx_pool.blocking_chainstate_handle().call(|cs| {
-        let is_ibd = cs.is_initial_block_download();
+        let is_ibd = cs.is_tx_processing_deferred();
         let actual_tip = cs.get_best_block_id()?;
         Ok::<_, chainstate::ChainstateError>((is_ibd, actual_tip))
     })??;
@@ -171,7 +176,14 @@ pub fn handle_new_tip<M: MemoryUsageEstimator>(
     }
 
     match fetch_disconnected_txs(tx_pool, new_tip) {
-        Ok(to_insert) => reorg_mempool_transactions(tx_pool, to_insert, finalizer),
+        Ok((disconnected_blocks, to_insert)) => {
+            if disconnected_blocks > 0 {
+                let stats = &mut tx_pool.reorg_stats;
+                stats.reorgs += 1;
+                stats.disconnected_blocks += disconnected_blocks as u64;
+            }
+            reorg_mempool_transactions(tx_pool, to_insert, finalizer)
+        }
         Err(err) => {
             log::error!("Error fetching disconnected transactions after reorg: {err}");
             refresh_mempool(tx_pool, finalizer)
@@ -194,14 +206,36 @@ fn reorg_mempool_transactions<M: MemoryUsageEstimator>(
             .map_err(|_| ReorgError::BestBlockForUtxos)?
     );
 
+    let mut resurrected_txs = 0;
+    let mut rejected_txs = 0;
     for tx in txs_to_insert {
         let tx_id = *tx.tx_id();
         log::trace!("Adding {tx_id} after reorg");
-        if let Err(e) = tx_pool.add_transaction(tx, &mut finalizer) {
-            log::debug!("Disconnected transaction {tx_id:?} no longer validates: {e:?}")
+        let result = tx_pool.add_transaction(tx, |outcome, tx_pool| {
+            let accepted = !matches!(outcome, TxAdditionOutcome::Rejected { .. });
+            finalizer(outcome, tx_pool);
+            accepted
+        });
+        match result {
+            Ok(true) => resurrected_txs += 1,
+            Ok(false) => rejected_txs += 1,
+            Err(e) => {
+                rejected_txs += 1;
+                log::debug!("Disconnected transaction {tx_id:?} no longer validates: {e:?}")
+            }
         }
     }
 
+    if resurrected_txs + rejected_txs > 0 {
+        log::debug!(
+            "Returned {resurrected_txs} disconnected transactions to mempool, {rejected_txs} rejected"
+        );
+        let stats = &mut tx_pool.reorg_stats;
+        stats.disconnected_txs += resurrected_txs + rejected_txs;
+        stats.resurrected_txs += resurrected_txs;
+        stats.rejected_txs += rejected_txs;
+    }
+
     // Re-populate the verifier with transactions from mempool
     for tx in old_transactions {
         let tx_id = *tx.tx_id();
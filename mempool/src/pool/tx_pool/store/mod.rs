@@ -168,6 +168,18 @@ impl MempoolStore {
         self.txs_by_id.contains_key(id)
     }
 
+    /// All the in-mempool transactions the given one depends on, directly or indirectly.
+    /// Returns `None` if the transaction is not in the store.
+    pub fn unconfirmed_ancestors_of(&self, id: &Id<Transaction>) -> Option<Ancestors> {
+        self.get_entry(id).map(|entry| entry.unconfirmed_ancestors(self))
+    }
+
+    /// All the in-mempool transactions that depend on the given one, directly or indirectly.
+    /// Returns `None` if the transaction is not in the store.
+    pub fn unconfirmed_descendants_of(&self, id: &Id<Transaction>) -> Option<Descendants> {
+        self.get_entry(id).map(|entry| entry.unconfirmed_descendants(self))
+    }
+
     pub fn memory_usage(&self) -> usize {
         self.mem_tracker.get_usage()
     }
@@ -489,10 +501,16 @@ impl MempoolStore {
         assert_eq!(tx_id_seq, Some(*tx_id), "Inconsistent transaction seq nos");
     }
 
-    pub fn drop_conflicts(&mut self, conflicts: Conflicts) {
+    // Remove the conflicting transactions, descendants first. Return the IDs of the removed transactions
+    pub fn drop_conflicts(&mut self, conflicts: Conflicts) -> Vec<Id<Transaction>> {
+        let mut removed = Vec::new();
         for conflict in conflicts.0 {
-            self.remove_tx(&conflict, MempoolRemovalReason::Replaced);
+            removed.extend(
+                self.drop_tx_and_descendants(&conflict, MempoolRemovalReason::Replaced)
+                    .map(|entry| *entry.tx_id()),
+            );
         }
+        removed
     }
 
     // Remove given transaction and its descendants. Return the IDs of the removed transactions
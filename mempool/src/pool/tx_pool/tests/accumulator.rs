@@ -27,12 +27,17 @@ use common::{
         },
         timelock::OutputTimeLock,
     },
+    primitives::{per_thousand::PerThousand, H256},
 };
 use crypto::key::{KeyKind, PrivateKey};
 use serialization::Compact;
 
 use super::*;
-use crate::tx_accumulator::DefaultTxAccumulator;
+use crate::{
+    error::BlockConstructionError,
+    pool::FeeRate,
+    tx_accumulator::{DefaultTxAccumulator, LowFeeRateSpaceLimit, TxAccumulatorPolicy},
+};
 
 // Useful for testing cases where timestamp is irrelevant.
 const DUMMY_TIMESTAMP: BlockTimestamp = BlockTimestamp::from_int_seconds(0u64);
@@ -136,6 +141,94 @@ async fn transaction_order_respects_deps(#[case] seed: Seed) {
     assert_eq!(tx_ids, vec![tx0_id, tx1_id, tx2_id]);
 }
 
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn accumulator_policy(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis_id = tf.genesis().get_id();
+
+    let tx0 = make_tx(&mut rng, &[(genesis_id.into(), 0)], &[900_000_000_000]);
+    let tx0_id = tx0.transaction().get_id();
+
+    let tx1 = make_tx(&mut rng, &[(tx0_id.into(), 0)], &[800_000_000_000]);
+    let tx1_id = tx1.transaction().get_id();
+
+    let tx2 = make_tx(&mut rng, &[(tx1_id.into(), 0)], &[500_000_000_000]);
+    let tx2_id = tx2.transaction().get_id();
+
+    let mut mempool = setup_with_chainstate(tf.chainstate());
+    assert_eq!(mempool.add_transaction_test(tx0), Ok(TxStatus::InMempool));
+    assert_eq!(mempool.add_transaction_test(tx1), Ok(TxStatus::InMempool));
+    assert_eq!(mempool.add_transaction_test(tx2), Ok(TxStatus::InMempool));
+
+    let collect = |size_limit: usize, policy: TxAccumulatorPolicy| {
+        let accumulator = Box::new(
+            DefaultTxAccumulator::new(size_limit, genesis_id.into(), DUMMY_TIMESTAMP)
+                .with_policy(policy),
+        );
+        mempool
+            .collect_txs(accumulator, vec![], PackingStrategy::FillSpaceFromMempool)
+            .map(|accumulator| {
+                accumulator
+                    .unwrap()
+                    .transactions()
+                    .iter()
+                    .map(|tx| tx.transaction().get_id())
+                    .collect::<Vec<_>>()
+            })
+    };
+
+    // Excluding a transaction excludes its descendants as well
+    let policy = TxAccumulatorPolicy {
+        excluded_tx_ids: [tx1_id].into(),
+        ..Default::default()
+    };
+    assert_eq!(collect(1_000_000, policy), Ok(vec![tx0_id]));
+
+    // A required transaction cannot descend from an excluded one
+    let policy = TxAccumulatorPolicy {
+        required_tx_ids: vec![tx2_id],
+        excluded_tx_ids: [tx1_id].into(),
+        ..Default::default()
+    };
+    assert_eq!(
+        collect(1_000_000, policy),
+        Err(BlockConstructionError::RequiredTxExcluded(tx1_id))
+    );
+
+    // A required transaction that does not fit fails the block construction
+    let policy = TxAccumulatorPolicy {
+        required_tx_ids: vec![tx0_id],
+        ..Default::default()
+    };
+    assert_eq!(
+        collect(10, policy),
+        Err(BlockConstructionError::RequiredTxNotIncluded(tx0_id))
+    );
+
+    // No space for low fee rate transactions, all of them are considered low fee rate
+    let low_fee_rate_limit = Some(LowFeeRateSpaceLimit {
+        fee_rate_threshold: FeeRate::from_atoms_per_kb(u128::MAX),
+        max_block_space: PerThousand::new(0).unwrap(),
+    });
+    let policy = TxAccumulatorPolicy {
+        low_fee_rate_limit,
+        ..Default::default()
+    };
+    assert_eq!(collect(1_000_000, policy), Ok(vec![]));
+
+    // Required transactions and their ancestors are not subject to the limit
+    let policy = TxAccumulatorPolicy {
+        required_tx_ids: vec![tx1_id],
+        low_fee_rate_limit,
+        ..Default::default()
+    };
+    assert_eq!(collect(1_000_000, policy), Ok(vec![tx0_id, tx1_id]));
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -630,3 +723,234 @@ async fn timelocked_htlc_refund(
     assert_eq!(has_tx1, in_accumulator_at1);
     assert!(accumulator.transactions().len() <= 1);
 }
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn diagnose_transaction(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis_id = tf.genesis().get_id();
+
+    let tx0 = make_tx(&mut rng, &[(genesis_id.into(), 0)], &[900_000_000_000]);
+    let tx0_id = tx0.transaction().get_id();
+
+    let tx1 = make_tx(&mut rng, &[(tx0_id.into(), 0)], &[800_000_000_000]);
+    let tx1_id = tx1.transaction().get_id();
+
+    let tx2 = make_tx(&mut rng, &[(tx1_id.into(), 0)], &[500_000_000_000]);
+    let tx2_id = tx2.transaction().get_id();
+
+    let mut mempool = setup_with_chainstate(tf.chainstate());
+    assert_eq!(mempool.add_transaction_test(tx0), Ok(TxStatus::InMempool));
+    assert_eq!(mempool.add_transaction_test(tx1), Ok(TxStatus::InMempool));
+    assert_eq!(mempool.add_transaction_test(tx2), Ok(TxStatus::InMempool));
+
+    let unknown_tx_id = Id::<Transaction>::new(H256::random_using(&mut rng));
+    assert_eq!(mempool.diagnose_transaction(&unknown_tx_id), None);
+
+    // All the transactions fit into a block, so there is no cutoff
+    let diagnosis = mempool.diagnose_transaction(&tx2_id).unwrap();
+    let entry = mempool.store.get_entry(&tx2_id).unwrap();
+    assert_eq!(diagnosis.package_fee_rate, *entry.ancestor_score());
+    assert_eq!(diagnosis.next_block_cutoff_fee_rate, None);
+    assert!(diagnosis.txs_ahead < 3);
+    assert!(diagnosis.fits_in_next_block);
+    assert_eq!(
+        BTreeSet::from_iter(diagnosis.unconfirmed_ancestors),
+        BTreeSet::from([tx0_id, tx1_id])
+    );
+
+    let diagnosis = mempool.diagnose_transaction(&tx0_id).unwrap();
+    assert!(diagnosis.unconfirmed_ancestors.is_empty());
+
+    // An orphan that spends an output of an unknown transaction and an output already spent by tx1
+    let orphan = make_tx(
+        &mut rng,
+        &[(unknown_tx_id.into(), 0), (tx0_id.into(), 0)],
+        &[100_000_000_000],
+    );
+    let orphan = mempool.make_transaction_test(orphan);
+    assert_eq!(
+        mempool.diagnose_orphan(&orphan),
+        OrphanTxDiagnosis {
+            missing_parents: vec![unknown_tx_id],
+            conflicts: vec![tx1_id],
+        }
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn diagnose_transaction_block_boundary(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    // Independent transactions of the same size
+    let txs: Vec<_> = (1..=3)
+        .map(|flags| {
+            SignedTransaction::new(Transaction::new(flags, vec![], vec![]).unwrap(), vec![])
+                .unwrap()
+        })
+        .collect();
+    let tx_size = txs[0].encoded_size();
+
+    // Exactly two of the transactions fit into a block
+    let chain_config = Arc::new(
+        common::chain::config::Builder::test_chain()
+            .max_block_size_with_standard_txs(2 * tx_size)
+            .build(),
+    );
+    let chainstate_handle = start_chainstate_with_config(Arc::clone(&chain_config));
+    let mut mempool = TxPool::new(
+        chain_config,
+        create_mempool_config(),
+        chainstate_handle,
+        Default::default(),
+        StoreMemoryUsageEstimator,
+    );
+
+    // Decreasing fees, so the transactions are taken into a block in the given order
+    let base_fee = rng.gen_range(1_000..100_000);
+    let tx_ids: Vec<_> = txs
+        .into_iter()
+        .zip([3, 2, 1])
+        .map(|(tx, multiplier)| {
+            let fee = Fee::new(Amount::from_atoms(base_fee * multiplier));
+            let entry = TxMempoolEntry::new_from_data(
+                tx,
+                fee,
+                BTreeSet::new(),
+                BTreeSet::new(),
+                common::primitives::time::get_time(),
+            )
+            .unwrap();
+            let tx_id = *entry.tx_id();
+            mempool.store.add_tx_entry(entry).unwrap();
+            tx_id
+        })
+        .collect();
+
+    let diagnoses: Vec<_> = tx_ids
+        .iter()
+        .map(|tx_id| mempool.diagnose_transaction(tx_id).unwrap())
+        .collect();
+
+    // The second transaction fills the block exactly and still fits into it
+    assert!(diagnoses[0].fits_in_next_block);
+    assert!(diagnoses[1].fits_in_next_block);
+    assert_eq!(diagnoses[1].txs_ahead, 1);
+    assert_eq!(diagnoses[1].size_ahead, tx_size);
+    assert!(!diagnoses[2].fits_in_next_block);
+    assert_eq!(diagnoses[2].txs_ahead, 2);
+    assert_eq!(diagnoses[2].size_ahead, 2 * tx_size);
+
+    // The cutoff is the package fee rate of the last transaction that fits
+    for diagnosis in &diagnoses {
+        assert_eq!(
+            diagnosis.next_block_cutoff_fee_rate,
+            Some(diagnoses[1].package_fee_rate)
+        );
+    }
+    assert!(diagnoses[2].package_fee_rate < diagnoses[1].package_fee_rate);
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn transaction_ancestry(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis_id = tf.genesis().get_id();
+
+    let tx0 = make_tx(&mut rng, &[(genesis_id.into(), 0)], &[900_000_000_000]);
+    let tx0_id = tx0.transaction().get_id();
+
+    let tx1 = make_tx(&mut rng, &[(tx0_id.into(), 0)], &[800_000_000_000]);
+    let tx1_id = tx1.transaction().get_id();
+
+    let tx2 = make_tx(&mut rng, &[(tx1_id.into(), 0)], &[500_000_000_000]);
+    let tx2_id = tx2.transaction().get_id();
+
+    let mut mempool = setup_with_chainstate(tf.chainstate());
+    assert_eq!(mempool.add_transaction_test(tx0), Ok(TxStatus::InMempool));
+    assert_eq!(
+        mempool.add_transaction_test(tx1.clone()),
+        Ok(TxStatus::InMempool)
+    );
+    assert_eq!(mempool.add_transaction_test(tx2), Ok(TxStatus::InMempool));
+
+    let unknown_tx_id = Id::<Transaction>::new(H256::random_using(&mut rng));
+    assert_eq!(mempool.transaction_info(&unknown_tx_id), None);
+    assert_eq!(mempool.unconfirmed_ancestors(&unknown_tx_id), None);
+    assert_eq!(mempool.unconfirmed_descendants(&unknown_tx_id), None);
+
+    let info = mempool.transaction_info(&tx1_id).unwrap();
+    let entry = mempool.store.get_entry(&tx1_id).unwrap();
+    assert_eq!(info.transaction, tx1);
+    assert_eq!(info.status, TxStatus::InMempool);
+    assert_eq!(info.fee, Some(Amount::from_atoms(100_000_000_000)));
+    assert_eq!(info.size, entry.size().get());
+    assert_eq!(info.time_added, entry.creation_time());
+
+    assert_eq!(
+        BTreeSet::from_iter(mempool.unconfirmed_ancestors(&tx2_id).unwrap()),
+        BTreeSet::from([tx0_id, tx1_id])
+    );
+    assert_eq!(mempool.unconfirmed_ancestors(&tx1_id), Some(vec![tx0_id]));
+    assert_eq!(mempool.unconfirmed_ancestors(&tx0_id), Some(vec![]));
+
+    assert_eq!(
+        BTreeSet::from_iter(mempool.unconfirmed_descendants(&tx0_id).unwrap()),
+        BTreeSet::from([tx1_id, tx2_id])
+    );
+    assert_eq!(mempool.unconfirmed_descendants(&tx1_id), Some(vec![tx2_id]));
+    assert_eq!(mempool.unconfirmed_descendants(&tx2_id), Some(vec![]));
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn projected_next_block(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis_id = tf.genesis().get_id();
+
+    let mut mempool = setup_with_chainstate(tf.chainstate());
+
+    let projected = mempool.projected_next_block().unwrap();
+    assert_eq!(projected.prev_block_id, genesis_id.into());
+    assert!(projected.tx_ids.is_empty());
+    assert_eq!(projected.total_fees, Amount::ZERO);
+    assert_eq!(projected.total_size, 0);
+
+    let tx0 = make_tx(&mut rng, &[(genesis_id.into(), 0)], &[900_000_000_000]);
+    let tx0_id = tx0.transaction().get_id();
+
+    let tx1 = make_tx(&mut rng, &[(tx0_id.into(), 0)], &[800_000_000_000]);
+    let tx1_id = tx1.transaction().get_id();
+
+    let tx2 = make_tx(&mut rng, &[(tx1_id.into(), 0)], &[500_000_000_000]);
+    let tx2_id = tx2.transaction().get_id();
+
+    assert_eq!(mempool.add_transaction_test(tx0), Ok(TxStatus::InMempool));
+    assert_eq!(mempool.add_transaction_test(tx1), Ok(TxStatus::InMempool));
+    assert_eq!(mempool.add_transaction_test(tx2), Ok(TxStatus::InMempool));
+
+    // The parents have to come before their children
+    let projected = mempool.projected_next_block().unwrap();
+    assert_eq!(projected.prev_block_id, genesis_id.into());
+    assert_eq!(projected.tx_ids, vec![tx0_id, tx1_id, tx2_id]);
+
+    let entries = [tx0_id, tx1_id, tx2_id].map(|id| mempool.store.get_entry(&id).unwrap());
+    let total_fees = entries.iter().map(|entry| entry.fee()).sum::<Option<Fee>>().unwrap();
+    assert_eq!(projected.total_fees, total_fees.into());
+    assert_eq!(
+        projected.total_size,
+        entries.iter().map(|entry| entry.size().get()).sum::<usize>()
+    );
+}
@@ -126,6 +126,103 @@ async fn add_tx_with_fee_rate_below_minimum() {
     assert_eq!(tx_status, TxStatus::InMempool);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn add_zero_fee_tx_if_accepted() {
+    let min_relay_fee_rate = FeeRate::from_amount_per_kb(Amount::from_atoms(123));
+    let mut mempool = setup_with_min_tx_relay_fee_rate(min_relay_fee_rate);
+    assert!(!mempool.accepts_zero_fee_txs());
+
+    let outpoint_source_id = mempool.chain_config.genesis_block_id().into();
+    let input = TxInput::from_utxo(outpoint_source_id, 0);
+    let tx = tx_spend_input(
+        &mempool,
+        input,
+        InputWitness::NoSignature(Some(DUMMY_WITNESS_MSG.to_vec())),
+        Amount::ZERO.into(),
+        0,
+    )
+    .await
+    .unwrap();
+
+    let err = mempool.add_transaction_test(tx.clone()).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Policy(MempoolPolicyError::InsufficientFeesToRelay {
+            tx_fee: _,
+            min_relay_fee: _
+        })
+    ));
+
+    // The unit test chain is regtest, so the fee checks can be turned off
+    mempool.set_accept_zero_fee_txs(true).unwrap();
+    assert!(mempool.accepts_zero_fee_txs());
+
+    let tx_status = mempool.add_transaction_test(tx).unwrap();
+    assert_eq!(tx_status, TxStatus::InMempool);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn add_trusted_tx_below_min_relay_fee() {
+    let min_relay_fee_rate = FeeRate::from_amount_per_kb(Amount::from_atoms(123));
+    let mut mempool = setup_with_min_tx_relay_fee_rate(min_relay_fee_rate);
+
+    let outpoint_source_id = mempool.chain_config.genesis_block_id().into();
+    let input = TxInput::from_utxo(outpoint_source_id, 0);
+    let tx = tx_spend_input(
+        &mempool,
+        input,
+        InputWitness::NoSignature(Some(DUMMY_WITNESS_MSG.to_vec())),
+        Amount::ZERO.into(),
+        0,
+    )
+    .await
+    .unwrap();
+
+    let err = mempool.add_transaction_test(tx.clone()).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Policy(MempoolPolicyError::InsufficientFeesToRelay {
+            tx_fee: _,
+            min_relay_fee: _
+        })
+    ));
+
+    let origin = TxOrigin::Remote(RemoteTxOrigin::new(p2p_types::PeerId::from_u64(1)));
+    let options = TxOptions::default_for(origin).with_trust_policy(TxTrustPolicy::Trusted);
+    let entry = TxEntry::new(tx, mempool.clock.get_time(), origin, options);
+    let tx_status = mempool.add_transaction_bare(entry).unwrap();
+    assert_eq!(tx_status, TxStatus::InMempool);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn zero_fee_txs_only_on_regtest() {
+    logging::init_logging();
+    let chain_config = Arc::new(common::chain::config::Builder::test_chain().build());
+    let mempool_config = MempoolConfig {
+        min_tx_relay_fee_rate: TEST_MIN_TX_RELAY_FEE_RATE.into(),
+        accept_zero_fee_txs: true.into(),
+        enable_rbf: Default::default(),
+        min_rbf_fee_rate_increment: Default::default(),
+        fee_estimator_recording_file: None,
+    };
+    let chainstate_handle = start_chainstate_with_config(Arc::clone(&chain_config));
+    let mut mempool = TxPool::new(
+        chain_config,
+        mempool_config.into(),
+        chainstate_handle,
+        Default::default(),
+        StoreMemoryUsageEstimator,
+    );
+
+    // The config setting is ignored outside of regtest and can't be changed at runtime
+    assert!(!mempool.accepts_zero_fee_txs());
+    assert_eq!(
+        mempool.set_accept_zero_fee_txs(true),
+        Err(Error::ZeroFeeTxsNotAllowed)
+    );
+    assert!(!mempool.accepts_zero_fee_txs());
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -250,6 +347,52 @@ async fn tx_duplicate_inputs() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tx_malformed_signature() -> anyhow::Result<()> {
+    use chainstate::tx_verifier::CheckTransactionError;
+    use common::chain::signature::{
+        inputsig::standard_signature::StandardInputSignature, sighash::sighashtype::SigHashType,
+    };
+
+    let mut mempool = setup();
+
+    let outpoint_source_id = OutPointSourceId::from(mempool.chain_config.genesis_block_id());
+    let input = TxInput::from_utxo(outpoint_source_id, 0);
+    let flags = 0;
+    let outputs = tx_spend_input(
+        &mempool,
+        input.clone(),
+        InputWitness::NoSignature(Some(DUMMY_WITNESS_MSG.to_vec())),
+        None,
+        flags,
+    )
+    .await?
+    .transaction()
+    .outputs()
+    .to_owned();
+    let tx = Transaction::new(flags, vec![input], outputs)?;
+    let tx_id = tx.get_id();
+
+    // Rejected by the stateless precheck, the signature doesn't decode as any known format
+    let witness = InputWitness::Standard(StandardInputSignature::new(
+        SigHashType::try_from(SigHashType::ALL).unwrap(),
+        vec![0xff; 3],
+    ));
+    let tx = SignedTransaction::new(tx, vec![witness]).expect("invalid witness count");
+
+    assert_eq!(
+        mempool.add_transaction_test(tx),
+        Err(
+            TxValidationError::TxValidation(ConnectTransactionError::CheckTransactionError(
+                CheckTransactionError::InvalidWitnessFormat(tx_id)
+            ))
+            .into()
+        )
+    );
+    mempool.store.assert_valid();
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn tx_already_in_mempool() -> anyhow::Result<()> {
     let mut mempool = setup();
@@ -514,7 +657,7 @@ async fn test_bip125_max_replacements(
     }
 
     let tx = tx_builder.build();
-    let mut mempool = setup_with_chainstate(tf.chainstate());
+    let mut mempool = setup_with_chainstate_rbf(tf.chainstate());
     let input = tx.transaction().inputs().first().expect("one input").clone();
     let outputs = tx.transaction().outputs().to_owned();
     let tx_id = tx.transaction().get_id();
@@ -560,7 +703,7 @@ async fn test_bip125_max_replacements(
 #[trace]
 #[case(Seed::from_entropy())]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-#[ignore = "RBF not implemented"]
+#[ignore = "Fees used here are not adapted to the RBF fee rate increment"]
 async fn too_many_conflicts(#[case] seed: Seed) -> anyhow::Result<()> {
     let num_potential_replacements = MAX_BIP125_REPLACEMENT_CANDIDATES + 1;
     let err: Error = test_bip125_max_replacements(seed, num_potential_replacements)
@@ -579,7 +722,7 @@ async fn too_many_conflicts(#[case] seed: Seed) -> anyhow::Result<()> {
 #[trace]
 #[case(Seed::from_entropy())]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-#[ignore = "RBF not implemented"]
+#[ignore = "Fees used here are not adapted to the RBF fee rate increment"]
 async fn not_too_many_conflicts(#[case] seed: Seed) -> anyhow::Result<()> {
     let num_potential_replacements = MAX_BIP125_REPLACEMENT_CANDIDATES;
     test_bip125_max_replacements(seed, num_potential_replacements).await
@@ -683,6 +826,7 @@ async fn rolling_fee(#[case] seed: Seed) -> anyhow::Result<()> {
     assert!(tx_pool.contains_transaction(&child_1_id));
     assert!(!tx_pool.contains_transaction(&child_0_id));
     let rolling_fee = tx_pool.get_minimum_rolling_fee();
+    assert_eq!(tx_pool.min_fee_rate(), rolling_fee);
     let child_0_fee = try_get_fee(&tx_pool, &child_0).await;
     log::debug!("FeeRate of child_0 {:?}", child_0_fee);
     assert_eq!(
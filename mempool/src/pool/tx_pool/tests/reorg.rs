@@ -92,6 +92,7 @@ async fn basic_reorg(#[case] seed: Seed) {
     tx_pool.on_new_tip(block2_id, BlockHeight::new(2)).unwrap();
     assert!(!tx_pool.contains_transaction(&tx1_id));
     assert!(!tx_pool.contains_transaction(&tx2_id));
+    assert_eq!(tx_pool.reorg_stats(), ReorgStats::default());
 
     // Submit two blocks on top of block1 and reorg out block2, causing tx2 to reappear in mempool
     let block3 = make_test_block(Vec::new(), block1_id, DUMMY_TIME);
@@ -107,6 +108,16 @@ async fn basic_reorg(#[case] seed: Seed) {
     tx_pool.on_new_tip(block4_id, BlockHeight::new(3)).unwrap();
     assert!(!tx_pool.contains_transaction(&tx1_id));
     assert!(tx_pool.contains_transaction(&tx2_id));
+    assert_eq!(
+        tx_pool.reorg_stats(),
+        ReorgStats {
+            reorgs: 1,
+            disconnected_blocks: 1,
+            disconnected_txs: 1,
+            resurrected_txs: 1,
+            rejected_txs: 0,
+        }
+    );
 }
 
 #[rstest]
@@ -240,3 +251,50 @@ async fn reject_txs_during_ibd(#[case] seed: Seed) {
     assert_eq!(res, Ok(TxStatus::InMempool));
     assert!(tx_pool.contains_transaction(&tx1_id));
 }
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn accept_txs_during_ibd_if_not_deferred(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    // Set up chainstate, mempool, and mock time; the deferral is disabled, which is only
+    // possible on regtest
+    let tf = TestFramework::builder(&mut rng)
+        .with_chainstate_config(ChainstateConfig::new().with_txs_deferred_during_ibd(false))
+        .with_max_tip_age(Duration::from_secs(10).into())
+        .with_initial_time_since_genesis(200)
+        .build();
+    let genesis_id = tf.genesis().get_id();
+    let mock_time = tf.time_value.unwrap().shallow_clone();
+    let mock_clock = tf.time_getter.clone();
+    let mut tx_pool = setup_with_chainstate(tf.chainstate);
+    tx_pool.clock = mock_clock;
+    let chainstate = tx_pool.chainstate_handle().shallow_clone();
+
+    let is_ibd = chainstate.call(|c| c.is_initial_block_download()).await.unwrap();
+    assert!(is_ibd);
+
+    // The transaction is accepted even though the node is in IBD
+    let tx1 = make_tx(&mut rng, &[(genesis_id.into(), 0)], &[1_000_000_000]);
+    let tx1_id = tx1.transaction().get_id();
+    let res = tx_pool.add_transaction_test(tx1);
+    assert_eq!(res, Ok(TxStatus::InMempool));
+    assert!(tx_pool.contains_transaction(&tx1_id));
+
+    // An "old" block doesn't end IBD, but the mempool is not reset
+    let block1_time = BlockTimestamp::from_int_seconds(mock_time.fetch_add(15));
+    let block1 = make_test_block(vec![], genesis_id, block1_time);
+    let block1_id = block1.get_id();
+    chainstate
+        .call_mut(move |c| c.process_block(block1, BlockSource::Local))
+        .await
+        .unwrap()
+        .expect("block1");
+    tx_pool.on_new_tip(block1_id, BlockHeight::new(1)).unwrap();
+
+    let is_ibd = chainstate.call(|c| c.is_initial_block_download()).await.unwrap();
+    assert!(is_ibd);
+    assert!(tx_pool.contains_transaction(&tx1_id));
+}
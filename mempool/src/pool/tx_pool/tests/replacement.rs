@@ -36,7 +36,7 @@ async fn test_replace_tx(
     let input = TxInput::from_utxo(outpoint_source_id, 0);
     let flags = 1;
 
-    let mut mempool = setup_with_chainstate(tf.chainstate());
+    let mut mempool = setup_with_chainstate_rbf(tf.chainstate());
     let original = tx_spend_input(
         &mempool,
         input.clone(),
@@ -78,7 +78,6 @@ async fn test_replace_tx(
 #[trace]
 #[case(Seed::from_entropy())]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-#[ignore = "RBF not implemented"]
 async fn try_replace_irreplaceable(#[case] seed: Seed) -> anyhow::Result<()> {
     let mut rng = make_seedable_rng(seed);
     let tf = TestFramework::builder(&mut rng).build();
@@ -88,7 +87,7 @@ async fn try_replace_irreplaceable(#[case] seed: Seed) -> anyhow::Result<()> {
     let input = TxInput::from_utxo(outpoint_source_id, 0);
     let flags = 0;
     let original_fee: Fee = get_relay_fee_from_tx_size(TX_SPEND_INPUT_SIZE).into();
-    let mut mempool = setup_with_chainstate(tf.chainstate());
+    let mut mempool = setup_with_chainstate_rbf(tf.chainstate());
     let original = tx_spend_input(
         &mempool,
         input.clone(),
@@ -128,7 +127,115 @@ async fn try_replace_irreplaceable(#[case] seed: Seed) -> anyhow::Result<()> {
 #[trace]
 #[case(Seed::from_entropy())]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-#[ignore = "RBF not implemented"]
+async fn replacement_fee_rate_increment(#[case] seed: Seed) -> anyhow::Result<()> {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis = tf.genesis();
+    let outpoint_source_id = OutPointSourceId::BlockReward(genesis.get_id().into());
+
+    let input = TxInput::from_utxo(outpoint_source_id, 0);
+    let rbf = 1;
+    let original_fee: Fee = get_relay_fee_from_tx_size(TX_SPEND_INPUT_SIZE).into();
+    let mut mempool = setup_with_chainstate_rbf(tf.chainstate());
+    let original = tx_spend_input(
+        &mempool,
+        input.clone(),
+        InputWitness::NoSignature(Some(DUMMY_WITNESS_MSG.to_vec())),
+        original_fee,
+        rbf,
+    )
+    .await?;
+    let original_id = original.transaction().get_id();
+    mempool.add_transaction_test(original)?.assert_in_mempool();
+
+    // Paying a slightly higher fee is not enough, the fee rate has to go up by at least the
+    // configured increment
+    let low_replacement_fee = (original_fee + Fee::new(Amount::from_atoms(1))).unwrap();
+    let low_replacement = tx_spend_input(
+        &mempool,
+        input.clone(),
+        InputWitness::NoSignature(Some(DUMMY_WITNESS_MSG.to_vec())),
+        low_replacement_fee,
+        rbf,
+    )
+    .await?;
+    assert!(matches!(
+        mempool.add_transaction_test(low_replacement),
+        Err(Error::Policy(
+            MempoolPolicyError::ReplacementFeeRateTooLow { .. }
+        ))
+    ));
+    assert!(mempool.contains_transaction(&original_id));
+
+    let replacement_fee: Fee = (*original_fee * 4).unwrap().into();
+    let replacement = tx_spend_input(
+        &mempool,
+        input,
+        InputWitness::NoSignature(Some(DUMMY_WITNESS_MSG.to_vec())),
+        replacement_fee,
+        rbf,
+    )
+    .await?;
+    let replacement_id = replacement.transaction().get_id();
+    assert_eq!(
+        mempool.add_transaction_test(replacement),
+        Ok(TxStatus::InMempoolReplacement)
+    );
+    assert!(!mempool.contains_transaction(&original_id));
+    assert!(mempool.contains_transaction(&replacement_id));
+    mempool.store.assert_valid();
+
+    Ok(())
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn replacement_rejected_without_rbf(#[case] seed: Seed) -> anyhow::Result<()> {
+    let mut rng = make_seedable_rng(seed);
+    let tf = TestFramework::builder(&mut rng).build();
+    let genesis = tf.genesis();
+    let outpoint_source_id = OutPointSourceId::BlockReward(genesis.get_id().into());
+
+    let input = TxInput::from_utxo(outpoint_source_id, 0);
+    let rbf = 1;
+    let original_fee: Fee = get_relay_fee_from_tx_size(TX_SPEND_INPUT_SIZE).into();
+    let mut mempool = setup_with_chainstate(tf.chainstate());
+    let original = tx_spend_input(
+        &mempool,
+        input.clone(),
+        InputWitness::NoSignature(Some(DUMMY_WITNESS_MSG.to_vec())),
+        original_fee,
+        rbf,
+    )
+    .await?;
+    let original_id = original.transaction().get_id();
+    mempool.add_transaction_test(original)?.assert_in_mempool();
+
+    let replacement_fee: Fee = (*original_fee * 4).unwrap().into();
+    let replacement = tx_spend_input(
+        &mempool,
+        input,
+        InputWitness::NoSignature(Some(DUMMY_WITNESS_MSG.to_vec())),
+        replacement_fee,
+        rbf,
+    )
+    .await?;
+    let replacement_id = replacement.transaction().get_id();
+    assert!(mempool.add_transaction_test(replacement).is_err());
+    assert!(mempool.contains_transaction(&original_id));
+    assert!(!mempool.contains_transaction(&replacement_id));
+    mempool.store.assert_valid();
+
+    Ok(())
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore = "Fees used here are below the minimum relay fee"]
 async fn tx_replace(#[case] seed: Seed) -> anyhow::Result<()> {
     let mut rng = make_seedable_rng(seed);
     let relay_fee = get_relay_fee_from_tx_size(TX_SPEND_INPUT_SIZE);
@@ -172,7 +279,7 @@ async fn tx_replace(#[case] seed: Seed) -> anyhow::Result<()> {
 #[trace]
 #[case(Seed::from_entropy())]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-#[ignore = "RBF not implemented"]
+#[ignore = "Fees used here are below the minimum relay fee"]
 async fn tx_replace_child(#[case] seed: Seed) -> anyhow::Result<()> {
     let mut rng = make_seedable_rng(seed);
     let tf = TestFramework::builder(&mut rng).build();
@@ -188,7 +295,7 @@ async fn tx_replace_child(#[case] seed: Seed) -> anyhow::Result<()> {
         ))
         .with_flags(1)
         .build();
-    let mut mempool = setup_with_chainstate(tf.chainstate());
+    let mut mempool = setup_with_chainstate_rbf(tf.chainstate());
     mempool.add_transaction_test(tx.clone())?.assert_in_mempool();
 
     let outpoint_source_id = OutPointSourceId::Transaction(tx.transaction().get_id());
@@ -225,7 +332,7 @@ async fn tx_replace_child(#[case] seed: Seed) -> anyhow::Result<()> {
 #[trace]
 #[case(Seed::from_entropy())]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-#[ignore = "RBF not implemented"]
+#[ignore = "Fees used here are below the minimum relay fee"]
 async fn pays_more_than_conflicts_with_descendants(#[case] seed: Seed) -> anyhow::Result<()> {
     let mut rng = make_seedable_rng(seed);
     let tf = TestFramework::builder(&mut rng).build();
@@ -241,7 +348,7 @@ async fn pays_more_than_conflicts_with_descendants(#[case] seed: Seed) -> anyhow
         ))
         .with_flags(1)
         .build();
-    let mut mempool = setup_with_chainstate(tf.chainstate());
+    let mut mempool = setup_with_chainstate_rbf(tf.chainstate());
     let tx_id = tx.transaction().get_id();
     mempool.add_transaction_test(tx)?.assert_in_mempool();
 
@@ -44,6 +44,17 @@ pub const TEST_MIN_TX_RELAY_FEE_RATE: FeeRate =
 pub fn create_mempool_config() -> ConstValue<MempoolConfig> {
     ConstValue::new(MempoolConfig {
         min_tx_relay_fee_rate: TEST_MIN_TX_RELAY_FEE_RATE.into(),
+        accept_zero_fee_txs: Default::default(),
+        enable_rbf: Default::default(),
+        min_rbf_fee_rate_increment: Default::default(),
+        fee_estimator_recording_file: None,
+    })
+}
+
+pub fn create_mempool_config_with_rbf() -> ConstValue<MempoolConfig> {
+    ConstValue::new(MempoolConfig {
+        enable_rbf: true.into(),
+        ..create_mempool_config().take()
     })
 }
 
@@ -78,7 +89,11 @@ impl<M: MemoryUsageEstimator> TxPool<M> {
 
     pub fn add_transaction_bare(&mut self, tx: TxEntry) -> Result<TxStatus, Error> {
         self.add_transaction(tx, |outcome, _| match outcome {
-            TxAdditionOutcome::Added { .. } => Ok(TxStatus::InMempool),
+            TxAdditionOutcome::Added { replaced, .. } => Ok(if replaced.is_empty() {
+                TxStatus::InMempool
+            } else {
+                TxStatus::InMempoolReplacement
+            }),
             TxAdditionOutcome::Duplicate { .. } => Ok(TxStatus::InMempoolDuplicate),
             TxAdditionOutcome::Rejected { error, .. } => Err(error.into()),
         })?
@@ -342,6 +357,10 @@ pub fn setup_with_min_tx_relay_fee_rate(fee_rate: FeeRate) -> TxPool<StoreMemory
     let chain_config = Arc::new(common::chain::config::create_unit_test_config());
     let mempool_config = MempoolConfig {
         min_tx_relay_fee_rate: fee_rate.into(),
+        accept_zero_fee_txs: Default::default(),
+        enable_rbf: Default::default(),
+        min_rbf_fee_rate_increment: Default::default(),
+        fee_estimator_recording_file: None,
     };
     let chainstate_interface = start_chainstate_with_config(Arc::clone(&chain_config));
     TxPool::new(
@@ -368,6 +387,21 @@ pub fn setup_with_chainstate(
     )
 }
 
+pub fn setup_with_chainstate_rbf(
+    chainstate: Box<dyn ChainstateInterface>,
+) -> TxPool<StoreMemoryUsageEstimator> {
+    logging::init_logging();
+    let chain_config = Arc::clone(chainstate.get_chain_config());
+    let chainstate_handle = start_chainstate(chainstate);
+    TxPool::new(
+        chain_config,
+        create_mempool_config_with_rbf(),
+        chainstate_handle,
+        Default::default(),
+        StoreMemoryUsageEstimator,
+    )
+}
+
 pub fn start_chainstate(chainstate: Box<dyn ChainstateInterface>) -> chainstate::ChainstateHandle {
     let mut man = subsystem::Manager::new("TODO");
     let handle = man.add_subsystem("chainstate", chainstate);
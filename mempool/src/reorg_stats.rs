@@ -0,0 +1,36 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Statistics of returning the transactions from the blocks disconnected by reorgs to the mempool
+
+use rpc::description::HasValueHint;
+
+/// The totals since the node has been started
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, HasValueHint,
+)]
+pub struct ReorgStats {
+    /// The number of reorgs that have disconnected at least one block
+    pub reorgs: u64,
+    /// The number of disconnected blocks
+    pub disconnected_blocks: u64,
+    /// The number of transactions from the disconnected blocks that are not in the new chain
+    pub disconnected_txs: u64,
+    /// The number of disconnected transactions that have been returned to the mempool
+    pub resurrected_txs: u64,
+    /// The number of disconnected transactions that are no longer valid, e.g. because they spend
+    /// outputs that have been spent by other transactions in the new chain
+    pub rejected_txs: u64,
+}
@@ -19,13 +19,18 @@ use std::num::NonZeroUsize;
 
 use common::{
     chain::{GenBlock, SignedTransaction, Transaction},
-    primitives::Id,
+    primitives::{Amount, Id},
 };
 use mempool_types::{tx_options::TxOptionsOverrides, tx_origin::LocalTxOrigin, TxOptions};
 use serialization::hex_encoded::HexEncoded;
 use utils::tap_log::TapLog;
 
-use crate::{FeeRate, MempoolMaxSize, TxStatus};
+use crate::{
+    diagnosis::TxDiagnosis,
+    reorg_stats::ReorgStats,
+    tx_info::{MempoolTxInfo, ProjectedBlock},
+    FeeRate, MempoolMaxSize, TxStatus,
+};
 
 use rpc::RpcResult;
 
@@ -34,6 +39,33 @@ pub struct GetTxResponse {
     id: Id<Transaction>,
     status: TxStatus,
     transaction: HexEncoded<SignedTransaction>,
+    /// The fee paid by the transaction, null for orphans
+    fee: Option<Amount>,
+    /// Encoded size of the transaction in bytes
+    size: usize,
+    /// When the transaction was added to the mempool, in seconds since the Unix epoch
+    time_added: u64,
+}
+
+impl GetTxResponse {
+    fn new(id: Id<Transaction>, info: MempoolTxInfo) -> Self {
+        let MempoolTxInfo {
+            transaction,
+            status,
+            fee,
+            size,
+            time_added,
+        } = info;
+
+        Self {
+            id,
+            status,
+            transaction: HexEncoded::new(transaction),
+            fee,
+            size,
+            time_added: time_added.as_secs_since_epoch(),
+        }
+    }
 }
 
 #[rpc::describe]
@@ -51,11 +83,32 @@ trait MempoolRpc {
 
     /// Returns the transaction defined by the provided id, given that it is in the pool.
     ///
-    /// The returned transaction is returned in an object that contains more information about the transaction.
+    /// The returned transaction is returned in an object that contains more information about the transaction,
+    /// such as its fee, its size and the time it was added to the mempool.
     /// Returns `None` (null) if the transaction is not found.
     #[method(name = "get_transaction")]
     async fn get_transaction(&self, tx_id: Id<Transaction>) -> RpcResult<Option<GetTxResponse>>;
 
+    /// Get the ids of the mempool transactions the given transaction depends on, directly or indirectly.
+    ///
+    /// These are the unconfirmed transactions that have to be included in a block before this one.
+    /// Returns `None` (null) if the transaction is not in the mempool (orphans are not considered).
+    #[method(name = "get_ancestors")]
+    async fn get_ancestors(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> RpcResult<Option<Vec<Id<Transaction>>>>;
+
+    /// Get the ids of the mempool transactions that depend on the given transaction, directly or indirectly.
+    ///
+    /// These transactions would be evicted from the mempool together with the given one.
+    /// Returns `None` (null) if the transaction is not in the mempool (orphans are not considered).
+    #[method(name = "get_descendants")]
+    async fn get_descendants(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> RpcResult<Option<Vec<Id<Transaction>>>>;
+
     /// Get all mempool transactions in a Vec/List, with hex-encoding.
     ///
     /// Notice that this call may be expensive. Use it with caution.
@@ -84,6 +137,11 @@ trait MempoolRpc {
     #[method(name = "memory_usage")]
     async fn memory_usage(&self) -> RpcResult<usize>;
 
+    /// Statistics of returning the transactions from the blocks disconnected by reorgs
+    /// to the mempool, since the node has been started.
+    #[method(name = "reorg_stats")]
+    async fn reorg_stats(&self) -> RpcResult<ReorgStats>;
+
     /// Get the maximum allowed size of all transactions in the mempool.
     #[method(name = "get_size_limit")]
     async fn get_size_limit(&self) -> RpcResult<usize>;
@@ -94,14 +152,59 @@ trait MempoolRpc {
     #[method(name = "set_size_limit")]
     async fn set_size_limit(&self, max_size: MempoolMaxSize) -> RpcResult<()>;
 
+    /// Check whether transactions are accepted regardless of the fees they pay.
+    #[method(name = "get_accept_zero_fee_txs")]
+    async fn get_accept_zero_fee_txs(&self) -> RpcResult<bool>;
+
+    /// Enable or disable the acceptance of transactions regardless of the fees they pay.
+    ///
+    /// This is only allowed on regtest, where it's meant to simplify testing.
+    #[method(name = "set_accept_zero_fee_txs")]
+    async fn set_accept_zero_fee_txs(&self, accept: bool) -> RpcResult<()>;
+
     /// Get the current fee rate of the mempool, that puts the transaction in the top X MBs of the mempool.
     /// X, in this description, is provided as a parameter.
     #[method(name = "get_fee_rate")]
     async fn get_fee_rate(&self, in_top_x_mb: usize) -> RpcResult<FeeRate>;
 
+    /// Get the minimum fee rate a transaction has to pay to be accepted into the mempool.
+    ///
+    /// The rate rises above the minimum relay fee rate when the mempool gets full and
+    /// low-fee transactions are evicted, and then gradually decays back.
+    #[method(name = "get_min_fee_rate")]
+    async fn get_min_fee_rate(&self) -> RpcResult<FeeRate>;
+
+    /// Estimate the fee rate needed for a transaction to be confirmed within `target_blocks` blocks.
+    ///
+    /// The estimate is based on how quickly the transactions with different fee rates have been
+    /// confirmed recently. The target is clamped to the range from 1 to 48 blocks.
+    /// The estimate is never below the current minimum fee rate of the mempool.
+    /// Returns null if there is not enough data yet, e.g. shortly after the node has started.
+    #[method(name = "estimate_fee_rate")]
+    async fn estimate_fee_rate(&self, target_blocks: usize) -> RpcResult<Option<FeeRate>>;
+
     /// Get the curve data points that represent the fee rate as a function of transaction size.
     #[method(name = "get_fee_rate_points")]
     async fn get_fee_rate_points(&self) -> RpcResult<Vec<(usize, FeeRate)>>;
+
+    /// Get the transactions the mempool would currently select for the next block.
+    ///
+    /// The transaction ids are returned in the order they would appear in the block,
+    /// along with their total fees and size. The actual block may differ, e.g. if the block producer
+    /// limits the space available to transactions paying a low fee rate.
+    #[method(name = "get_projected_next_block")]
+    async fn get_projected_next_block(&self) -> RpcResult<ProjectedBlock>;
+
+    /// Explain why a transaction is not being included in blocks.
+    ///
+    /// For a transaction in the mempool, its package fee rate (the fee rate together with
+    /// its unconfirmed ancestors) is reported along with the package fee rate needed to get
+    /// into the next block and an estimate of its position in the block production order.
+    /// For a transaction in the orphan pool, the missing parents and the conflicting mempool
+    /// transactions are reported.
+    /// Returns null if the transaction is not found.
+    #[method(name = "diagnose_transaction")]
+    async fn diagnose_transaction(&self, tx_id: Id<Transaction>) -> RpcResult<Option<TxDiagnosis>>;
 }
 
 #[async_trait::async_trait]
@@ -127,20 +230,24 @@ impl MempoolRpcServer for super::MempoolHandle {
         &self,
         tx_id: Id<Transaction>,
     ) -> rpc::RpcResult<Option<GetTxResponse>> {
-        let res: Option<_> = rpc::handle_result(
-            self.call(move |this| {
-                this.transaction(&tx_id).map(|tx| (tx, TxStatus::InMempool)).or_else(|| {
-                    this.orphan_transaction(&tx_id).map(|tx| (tx, TxStatus::InOrphanPool))
-                })
-            })
-            .await,
-        )?;
+        let res: Option<_> =
+            rpc::handle_result(self.call(move |this| this.transaction_info(&tx_id)).await)?;
 
-        Ok(res.map(|(transaction, status)| GetTxResponse {
-            id: tx_id,
-            status,
-            transaction: HexEncoded::new(transaction),
-        }))
+        Ok(res.map(|info| GetTxResponse::new(tx_id, info)))
+    }
+
+    async fn get_ancestors(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> rpc::RpcResult<Option<Vec<Id<Transaction>>>> {
+        rpc::handle_result(self.call(move |this| this.unconfirmed_ancestors(&tx_id)).await)
+    }
+
+    async fn get_descendants(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> rpc::RpcResult<Option<Vec<Id<Transaction>>>> {
+        rpc::handle_result(self.call(move |this| this.unconfirmed_descendants(&tx_id)).await)
     }
 
     async fn submit_transaction(
@@ -165,6 +272,10 @@ impl MempoolRpcServer for super::MempoolHandle {
         rpc::handle_result(self.call(|this| this.memory_usage()).await)
     }
 
+    async fn reorg_stats(&self) -> rpc::RpcResult<ReorgStats> {
+        rpc::handle_result(self.call(|this| this.reorg_stats()).await)
+    }
+
     async fn get_size_limit(&self) -> rpc::RpcResult<usize> {
         rpc::handle_result(self.call(|this| this.get_size_limit().as_bytes()).await)
     }
@@ -173,13 +284,40 @@ impl MempoolRpcServer for super::MempoolHandle {
         rpc::handle_result(self.call_mut(move |this| this.set_size_limit(max_size)).await)
     }
 
+    async fn get_accept_zero_fee_txs(&self) -> rpc::RpcResult<bool> {
+        rpc::handle_result(self.call(|this| this.accepts_zero_fee_txs()).await)
+    }
+
+    async fn set_accept_zero_fee_txs(&self, accept: bool) -> rpc::RpcResult<()> {
+        rpc::handle_result(self.call_mut(move |this| this.set_accept_zero_fee_txs(accept)).await)
+    }
+
     async fn get_fee_rate(&self, in_top_x_mb: usize) -> rpc::RpcResult<FeeRate> {
         rpc::handle_result(self.call(move |this| this.get_fee_rate(in_top_x_mb)).await)
     }
 
+    async fn get_min_fee_rate(&self) -> rpc::RpcResult<FeeRate> {
+        rpc::handle_result(self.call(move |this| this.get_min_fee_rate()).await)
+    }
+
+    async fn estimate_fee_rate(&self, target_blocks: usize) -> rpc::RpcResult<Option<FeeRate>> {
+        rpc::handle_result(self.call(move |this| this.estimate_fee_rate(target_blocks)).await)
+    }
+
     async fn get_fee_rate_points(&self) -> RpcResult<Vec<(usize, FeeRate)>> {
         // MIN(1) + 9 = 10, to keep it as const
         const NUM_POINTS: NonZeroUsize = NonZeroUsize::MIN.saturating_add(9);
         rpc::handle_result(self.call(move |this| this.get_fee_rate_points(NUM_POINTS)).await)
     }
+
+    async fn get_projected_next_block(&self) -> rpc::RpcResult<ProjectedBlock> {
+        rpc::handle_result(self.call(move |this| this.projected_next_block()).await)
+    }
+
+    async fn diagnose_transaction(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> rpc::RpcResult<Option<TxDiagnosis>> {
+        rpc::handle_result(self.call(move |this| this.diagnose_transaction(&tx_id)).await)
+    }
 }
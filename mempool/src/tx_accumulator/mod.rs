@@ -13,14 +13,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::{collections::BTreeSet, num::NonZeroUsize};
+
 use common::{
-    chain::{block::timestamp::BlockTimestamp, GenBlock, SignedTransaction},
-    primitives::{Amount, Id},
+    chain::{block::timestamp::BlockTimestamp, GenBlock, SignedTransaction, Transaction},
+    primitives::{per_thousand::PerThousand, Amount, Id},
 };
 use rpc::description::HasValueHint;
 use serialization::{Compact, Encode};
 
-use crate::pool::fee::Fee;
+use crate::pool::{fee::Fee, FeeRate};
 
 #[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
 pub enum TxAccumulatorError {
@@ -34,6 +36,47 @@ pub enum PackingStrategy {
     LeaveEmptySpace,
 }
 
+/// Limits the block space that can be taken by transactions paying a low fee rate
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize, HasValueHint)]
+pub struct LowFeeRateSpaceLimit {
+    /// Transactions paying less than this fee rate are considered low fee rate transactions
+    pub fee_rate_threshold: FeeRate,
+    /// The maximum fraction of the block space that low fee rate transactions may take
+    pub max_block_space: PerThousand,
+}
+
+/// Constraints on the transactions that the mempool puts into an accumulator
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize, HasValueHint,
+)]
+#[serde(default)]
+pub struct TxAccumulatorPolicy {
+    /// Transactions that must be included, together with their ancestors.
+    /// Block construction fails if any of them cannot be included.
+    pub required_tx_ids: Vec<Id<Transaction>>,
+    /// Transactions that must never be included. Their descendants are not included either.
+    pub excluded_tx_ids: BTreeSet<Id<Transaction>>,
+    /// Limit on the space taken by low fee rate transactions. Required transactions are
+    /// always included but still count towards the limit.
+    pub low_fee_rate_limit: Option<LowFeeRateSpaceLimit>,
+}
+
+impl TxAccumulatorPolicy {
+    pub fn is_excluded(&self, tx_id: &Id<Transaction>) -> bool {
+        self.excluded_tx_ids.contains(tx_id)
+    }
+
+    fn is_low_fee_rate(&self, tx_size: usize, tx_fee: Fee) -> bool {
+        let limit = match &self.low_fee_rate_limit {
+            Some(limit) => limit,
+            None => return false,
+        };
+        NonZeroUsize::new(tx_size)
+            .and_then(|tx_size| FeeRate::from_total_tx_fee(tx_fee, tx_size).ok())
+            .is_some_and(|fee_rate| fee_rate < limit.fee_rate_threshold)
+    }
+}
+
 pub trait TransactionAccumulator: Send {
     /// Add a transaction to the accumulator and its fee
     /// This method should not mutate self unless it's successful
@@ -54,6 +97,13 @@ pub trait TransactionAccumulator: Send {
     /// must be the so-called "median time past" calculated from "expected_tip".
     /// If a later timestamp is specified, the produced block may not be correct.
     fn unlock_timestamp(&self) -> BlockTimestamp;
+
+    /// The constraints the mempool has to respect when filling the accumulator.
+    fn policy(&self) -> &TxAccumulatorPolicy;
+
+    /// Whether a transaction of the given size and fee can be added without exceeding the
+    /// space reserved for low fee rate transactions by the policy.
+    fn fits_low_fee_rate_space(&self, tx_size: usize, tx_fee: Fee) -> bool;
 }
 
 pub struct DefaultTxAccumulator {
@@ -64,6 +114,8 @@ pub struct DefaultTxAccumulator {
     total_fees: Fee,
     expected_tip: Id<GenBlock>,
     unlock_timestamp: BlockTimestamp,
+    policy: TxAccumulatorPolicy,
+    low_fee_rate_txs_size: usize,
 }
 
 impl DefaultTxAccumulator {
@@ -80,9 +132,16 @@ impl DefaultTxAccumulator {
             total_fees: Amount::ZERO.into(),
             expected_tip,
             unlock_timestamp,
+            policy: TxAccumulatorPolicy::default(),
+            low_fee_rate_txs_size: 0,
         }
     }
 
+    pub fn with_policy(mut self, policy: TxAccumulatorPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
     pub fn total_size(&self) -> usize {
         Compact(self.transactions().len() as u64).encoded_size() + self.txs_size
     }
@@ -103,6 +162,9 @@ impl TransactionAccumulator for DefaultTxAccumulator {
             self.total_fees = (self.total_fees + tx_fee).ok_or(
                 TxAccumulatorError::FeeAccumulationError(self.total_fees, tx_fee),
             )?;
+            if self.policy.is_low_fee_rate(tx_size, tx_fee) {
+                self.low_fee_rate_txs_size += tx_size;
+            }
             self.txs.push(tx);
 
             // Sanity check that total_size_with() and total_size() agree
@@ -137,4 +199,21 @@ impl TransactionAccumulator for DefaultTxAccumulator {
     fn unlock_timestamp(&self) -> BlockTimestamp {
         self.unlock_timestamp
     }
+
+    fn policy(&self) -> &TxAccumulatorPolicy {
+        &self.policy
+    }
+
+    fn fits_low_fee_rate_space(&self, tx_size: usize, tx_fee: Fee) -> bool {
+        let limit = match &self.policy.low_fee_rate_limit {
+            Some(limit) => limit,
+            None => return true,
+        };
+        if !self.policy.is_low_fee_rate(tx_size, tx_fee) {
+            return true;
+        }
+        let max_size = self.target_size * usize::from(limit.max_block_space.value())
+            / usize::from(limit.max_block_space.denominator());
+        self.low_fee_rate_txs_size + tx_size <= max_size
+    }
 }
@@ -0,0 +1,51 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Information about the transactions held by the mempool
+
+use common::{
+    chain::{GenBlock, SignedTransaction, Transaction},
+    primitives::{time::Time, Amount, Id},
+};
+use rpc::description::HasValueHint;
+
+use crate::TxStatus;
+
+/// A transaction from the main pool or the orphan pool together with its mempool metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MempoolTxInfo {
+    pub transaction: SignedTransaction,
+    /// Either `TxStatus::InMempool` or `TxStatus::InOrphanPool`
+    pub status: TxStatus,
+    /// The fee paid by the transaction, `None` for orphans since their inputs are not known
+    pub fee: Option<Amount>,
+    /// Encoded size of the transaction
+    pub size: usize,
+    /// When the transaction was added to the mempool
+    pub time_added: Time,
+}
+
+/// The transactions the mempool would currently select for the next block
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct ProjectedBlock {
+    /// The block the projected block would be built on
+    pub prev_block_id: Id<GenBlock>,
+    /// The ids of the selected transactions, in the order they would appear in the block
+    pub tx_ids: Vec<Id<Transaction>>,
+    /// The sum of the fees paid by the selected transactions
+    pub total_fees: Amount,
+    /// The total encoded size of the selected transactions
+    pub total_size: usize,
+}
@@ -18,7 +18,7 @@ use rpc_description::ValueHint as VH;
 
 #[derive(Clone, Copy, Eq, PartialEq, Debug, serde::Serialize, serde::Deserialize, Default)]
 pub enum TxTrustPolicy {
-    /// Mempool policy checks are bypassed for this transaction
+    /// Transaction is exempt from the minimum relay fee requirement.
     Trusted,
 
     /// Transaction is subject to all the usual mempool policy checks.
@@ -75,6 +75,11 @@ impl TxOptions {
         self
     }
 
+    pub const fn with_trust_policy(mut self, trust_policy: TxTrustPolicy) -> Self {
+        self.trust_policy = trust_policy;
+        self
+    }
+
     pub fn trust_policy(&self) -> TxTrustPolicy {
         self.trust_policy
     }
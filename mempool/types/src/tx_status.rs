@@ -34,6 +34,9 @@ pub enum TxStatus {
     /// Transaction has already been in the mempool, duplicate insertion
     InMempoolDuplicate,
 
+    /// Transaction is in mempool, having replaced one or more conflicting transactions
+    InMempoolReplacement,
+
     /// Transaction is in orphan pool
     InOrphanPool,
 
@@ -47,6 +50,7 @@ impl TxStatus {
         match self {
             TxStatus::InMempool => true,
             TxStatus::InMempoolDuplicate => true,
+            TxStatus::InMempoolReplacement => true,
             TxStatus::InOrphanPool => false,
             TxStatus::InOrphanPoolDuplicate => false,
         }
@@ -57,6 +61,7 @@ impl TxStatus {
         match self {
             TxStatus::InMempool => false,
             TxStatus::InMempoolDuplicate => false,
+            TxStatus::InMempoolReplacement => false,
             TxStatus::InOrphanPool => true,
             TxStatus::InOrphanPoolDuplicate => true,
         }
@@ -67,6 +72,7 @@ impl TxStatus {
         match self {
             TxStatus::InMempool => false,
             TxStatus::InMempoolDuplicate => true,
+            TxStatus::InMempoolReplacement => false,
             TxStatus::InOrphanPool => false,
             TxStatus::InOrphanPoolDuplicate => true,
         }
@@ -78,6 +84,7 @@ impl std::fmt::Display for TxStatus {
         let status_str = match self {
             TxStatus::InMempool => "in mempool",
             TxStatus::InMempoolDuplicate => "in mempool (duplicate)",
+            TxStatus::InMempoolReplacement => "in mempool (replaced conflicting transactions)",
             TxStatus::InOrphanPool => "in orphan pool",
             TxStatus::InOrphanPoolDuplicate => "in orphan pool (duplicate)",
         };
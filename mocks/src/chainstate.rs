@@ -16,22 +16,24 @@
 use std::{collections::BTreeMap, num::NonZeroUsize, sync::Arc};
 
 use chainstate::{
-    BlockSource, ChainInfo, ChainstateConfig, ChainstateError, ChainstateEvent, Locator,
+    BlockSource, ChainInfo, ChainstateConfig, ChainstateError, ChainstateEvent, CompactionStatus,
+    Locator, RecoveryReport, StorageSizeInfo, UndoCompactionReport,
 };
-use chainstate_types::{BlockIndex, EpochData, GenBlockIndex};
+use chainstate_types::{BlockDataAvailability, BlockIndex, BlockStats, EpochData, GenBlockIndex};
 use common::{
     chain::{
         block::{
             signed_block_header::SignedBlockHeader, timestamp::BlockTimestamp, Block, BlockReward,
             GenBlock,
         },
+        config::EpochIndex,
         tokens::{RPCTokenInfo, TokenAuxiliaryData, TokenId},
-        AccountNonce, AccountType, ChainConfig, DelegationId, OrderData, OrderId, PoolId, TxInput,
-        UtxoOutPoint,
+        AccountNonce, AccountType, ChainConfig, DelegationId, OrderData, OrderId, PoolId,
+        SignedTransaction, TxInput, UtxoOutPoint,
     },
     primitives::{Amount, BlockHeight, Id},
 };
-use pos_accounting::PoolData;
+use pos_accounting::{PoSAccountingData, PoolData};
 use utils::eventhandler::EventHandler;
 use utxo::Utxo;
 
@@ -51,6 +53,7 @@ mockall::mock! {
             &self,
             headers: &[SignedBlockHeader],
         )-> Result<(), ChainstateError>;
+        fn process_block_headers(&mut self, headers: Vec<SignedBlockHeader>) -> Result<(), ChainstateError>;
         fn get_best_block_id(&self) -> Result<Id<GenBlock>, ChainstateError>;
         fn get_best_block_height(&self) -> Result<BlockHeight, ChainstateError>;
         fn get_best_block_header(&self) -> Result<SignedBlockHeader, ChainstateError>;
@@ -72,6 +75,7 @@ mockall::mock! {
         ) -> Result<Vec<Block>, ChainstateError>;
         fn get_block_header(&self, block_id: Id<Block>) -> Result<Option<SignedBlockHeader>, ChainstateError>;
         fn get_locator(&self) -> Result<Locator, ChainstateError>;
+        fn get_block_data_availability(&self) -> Result<BlockDataAvailability, ChainstateError>;
         fn get_locator_from_height(&self, height: BlockHeight) -> Result<Locator, ChainstateError>;
         fn get_block_ids_as_checkpoints(
             &self,
@@ -111,6 +115,10 @@ mockall::mock! {
         ) -> Result<Option<GenBlockIndex>, ChainstateError>;
         fn get_chain_config(&self) -> &Arc<ChainConfig>;
         fn get_best_block_index(&self) -> Result<chainstate_types::GenBlockIndex, ChainstateError>;
+        fn get_recent_block_stats(
+            &self,
+            count: usize,
+        ) -> Result<Vec<(BlockIndex, BlockStats)>, ChainstateError>;
         fn get_chainstate_config(&self) -> ChainstateConfig;
         fn wait_for_all_events(&self);
         fn subscribers(&self) -> &[EventHandler<ChainstateEvent>];
@@ -136,6 +144,10 @@ mockall::mock! {
             &self,
             block_index: &BlockIndex,
         ) -> Result<Option<BlockReward>, ChainstateError>;
+        fn get_block_reward_distribution(
+            &self,
+            block_id: &Id<Block>,
+        ) -> Result<Option<chainstate::BlockRewardDistribution>, ChainstateError>;
         fn get_epoch_data(&self, epoch_index: u64) -> Result<Option<EpochData>, ChainstateError>;
         fn get_token_info_for_rpc(&self, token_id: TokenId) -> Result<Option<RPCTokenInfo>, ChainstateError>;
         fn get_token_aux_data(
@@ -146,6 +158,10 @@ mockall::mock! {
             &self,
             tx_id: &Id<common::chain::Transaction>,
         ) -> Result<Option<TokenId>, ChainstateError>;
+        fn get_transaction(
+            &self,
+            tx_id: &Id<common::chain::Transaction>,
+        ) -> Result<Option<(SignedTransaction, Id<Block>, BlockHeight)>, ChainstateError>;
         fn get_token_data(
             &self,
             id: &TokenId,
@@ -166,8 +182,16 @@ mockall::mock! {
             writer: std::io::BufWriter<Box<dyn std::io::Write + Send + 'a>>,
             include_orphans: bool,
         ) -> Result<(), ChainstateError>;
+        fn import_bootstrap_file(&mut self, file_path: &std::path::Path) -> Result<(), ChainstateError>;
+        fn export_bootstrap_file(
+            &self,
+            file_path: &std::path::Path,
+            include_orphans: bool,
+        ) -> Result<(), ChainstateError>;
         fn utxo(&self, outpoint: &UtxoOutPoint) -> Result<Option<Utxo>, ChainstateError>;
         fn is_initial_block_download(&self) -> bool;
+        fn is_tx_processing_deferred(&self) -> bool;
+        fn get_recovery_report(&self) -> Option<RecoveryReport>;
         fn stake_pool_exists(&self, pool_id: PoolId) -> Result<bool, ChainstateError>;
         fn get_stake_pool_balance(&self, pool_id: PoolId) -> Result<Option<Amount>, ChainstateError>;
         fn get_stake_pool_balances_at_heights(
@@ -194,7 +218,17 @@ mockall::mock! {
             pool_id: PoolId,
             delegation_id: DelegationId,
         ) -> Result<Option<Amount>, ChainstateError>;
+        fn get_pos_accounting_data_tip(&self) -> Result<PoSAccountingData, ChainstateError>;
+        fn get_pos_accounting_data_at_epoch(
+            &self,
+            epoch_index: EpochIndex,
+        ) -> Result<PoSAccountingData, ChainstateError>;
         fn info(&self) -> Result<ChainInfo, ChainstateError>;
+        fn storage_size_info(&self) -> Result<StorageSizeInfo, ChainstateError>;
+        fn compact_storage(&mut self) -> Result<CompactionStatus, ChainstateError>;
+        fn is_storage_maintenance_due(&self) -> bool;
+        fn perform_storage_maintenance(&mut self) -> Result<(), ChainstateError>;
+        fn compact_undo_data(&mut self) -> Result<UndoCompactionReport, ChainstateError>;
         fn get_account_nonce_count(
             &self,
             account: AccountType,
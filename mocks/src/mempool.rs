@@ -22,9 +22,12 @@ use common::{
     primitives::Id,
 };
 use mempool::{
+    diagnosis::TxDiagnosis,
     error::{BlockConstructionError, Error},
     event::MempoolEvent,
+    reorg_stats::ReorgStats,
     tx_accumulator::{PackingStrategy, TransactionAccumulator},
+    tx_info::{MempoolTxInfo, ProjectedBlock},
     tx_origin::{LocalTxOrigin, RemoteTxOrigin},
     FeeRate, MempoolInterface, MempoolMaxSize, TxOptions, TxStatus,
 };
@@ -52,7 +55,11 @@ mockall::mock! {
         fn orphan_transaction(&self, id: &Id<Transaction>) -> Option<SignedTransaction>;
         fn contains_transaction(&self, tx: &Id<Transaction>) -> bool;
         fn contains_orphan_transaction(&self, tx: &Id<Transaction>) -> bool;
+        fn transaction_info(&self, id: &Id<Transaction>) -> Option<MempoolTxInfo>;
+        fn unconfirmed_ancestors(&self, id: &Id<Transaction>) -> Option<Vec<Id<Transaction>>>;
+        fn unconfirmed_descendants(&self, id: &Id<Transaction>) -> Option<Vec<Id<Transaction>>>;
         fn best_block_id(&self) -> Id<GenBlock>;
+        fn diagnose_transaction(&self, tx_id: &Id<Transaction>) -> Option<TxDiagnosis>;
 
         fn collect_txs(
             &self,
@@ -60,12 +67,19 @@ mockall::mock! {
             transaction_ids: Vec<Id<Transaction>>,
             packing_strategy: PackingStrategy,
         ) -> Result<Option<Box<dyn TransactionAccumulator>>, BlockConstructionError>;
+        fn projected_next_block(&self) -> Result<ProjectedBlock, BlockConstructionError>;
 
         fn subscribe_to_events(&mut self, handler: Arc<dyn Fn(MempoolEvent) + Send + Sync>);
         fn memory_usage(&self) -> usize;
+        fn reorg_stats(&self) -> ReorgStats;
         fn get_size_limit(&self) -> MempoolMaxSize;
         fn set_size_limit(&mut self, max_size: MempoolMaxSize) -> Result<(), Error>;
+        fn accepts_zero_fee_txs(&self) -> bool;
+        fn set_accept_zero_fee_txs(&mut self, accept: bool) -> Result<(), Error>;
         fn get_fee_rate(&self, in_top_x_mb: usize) -> FeeRate;
+        fn get_min_fee_rate(&self) -> FeeRate;
+        fn check_min_fee_rate(&mut self);
+        fn estimate_fee_rate(&self, target_blocks: usize) -> Option<FeeRate>;
         fn get_fee_rate_points(&self, num_points: NonZeroUsize) -> Result<Vec<(usize, FeeRate)>, Error>;
 
         fn notify_peer_disconnected(&mut self, peer_id: p2p_types::PeerId);
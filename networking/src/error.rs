@@ -22,6 +22,8 @@ pub enum NetworkingError {
     IoError(std::io::ErrorKind),
     #[error("Message codec error: {0}")]
     MessageCodecError(#[from] MessageCodecError),
+    #[error("Multiplexed frame error: {0}")]
+    MultiplexedFrameError(#[from] MultiplexedFrameError),
     #[error("Noise protocol handshake error")]
     NoiseHandshakeError(String),
     #[error("Proxy error: {0}")]
@@ -39,6 +41,16 @@ pub enum MessageCodecError {
     InvalidEncodedData(serialization::Error),
 }
 
+#[derive(thiserror::Error, Debug, PartialEq, Eq, Clone)]
+pub enum MultiplexedFrameError {
+    #[error("Unknown lane {lane}, the number of lanes is {lane_count}")]
+    UnknownLane { lane: u8, lane_count: u8 },
+    #[error("Invalid chunk size {0}")]
+    InvalidChunkSize(usize),
+    #[error("The data waiting to be sent on lane {lane} exceeds its budget of {budget} bytes")]
+    LaneBudgetExceeded { lane: u8, budget: usize },
+}
+
 impl From<std::io::Error> for NetworkingError {
     fn from(value: std::io::Error) -> Self {
         NetworkingError::IoError(value.kind())
@@ -45,6 +45,12 @@ impl<S, Msg> BufferedTranscoder<S, Msg> {
     pub fn inner_stream(&self) -> &S {
         &self.stream
     }
+
+    /// Return the stream together with the data that has been read from it but not decoded yet,
+    /// e.g. to continue with a different framing.
+    pub fn into_parts(self) -> (S, BytesMut) {
+        (self.stream, self.buffer)
+    }
 }
 
 impl<S, Msg> BufferedTranscoder<S, Msg>
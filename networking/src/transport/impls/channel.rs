@@ -38,6 +38,7 @@ use utils::sync::atomic::AtomicU16;
 use crate::{
     error::NetworkingError,
     transport::{ConnectedSocketInfo, PeerStream, TransportListener, TransportSocket},
+    types::TransportProtocol,
     Result,
 };
 
@@ -100,6 +101,9 @@ impl TransportSocket for MpscChannelTransport {
     type Listener = ChannelListener;
     type Stream = ChannelStream;
 
+    // The channels emulate TCP connections in tests
+    const PROTOCOL: TransportProtocol = TransportProtocol::Tcp;
+
     async fn bind(&self, mut addresses: Vec<SocketAddr>) -> Result<Self::Listener> {
         let mut connections = CONNECTIONS.lock().expect("Connections mutex is poisoned");
 
@@ -23,6 +23,7 @@ use tokio_socks::tcp::Socks5Stream;
 use crate::{
     error::NetworkingError,
     transport::{ConnectedSocketInfo, PeerStream, TransportListener, TransportSocket},
+    types::TransportProtocol,
     Result,
 };
 
@@ -47,6 +48,8 @@ impl TransportSocket for Socks5TransportSocket {
     type Listener = Socks5TransportListener;
     type Stream = Socks5TransportStream;
 
+    const PROTOCOL: TransportProtocol = TransportProtocol::Tcp;
+
     async fn bind(&self, addresses: Vec<SocketAddr>) -> Result<Self::Listener> {
         Socks5TransportListener::new(addresses)
     }
@@ -39,6 +39,7 @@ use crate::{
         NoiseEncryptionAdapter, NoiseEncryptionAdapterMaker, PeerStream, TcpTransportSocket,
         TransportListener, TransportSocket,
     },
+    types::TransportProtocol,
 };
 
 use super::wrapped_socket::WrappedTransportSocket;
@@ -171,6 +172,8 @@ impl TransportSocket for TestTransport {
     type Listener = TestListener;
     type Stream = <MpscChannelTransport as TransportSocket>::Stream;
 
+    const PROTOCOL: TransportProtocol = MpscChannelTransport::PROTOCOL;
+
     async fn bind(&self, addresses: Vec<SocketAddr>) -> crate::Result<Self::Listener> {
         let listener = self.transport.bind(addresses).await.unwrap();
         *self.port_open.lock().unwrap() = true;
@@ -22,7 +22,7 @@ use super::wrapped_listener::AdaptedListener;
 
 use crate::{
     transport::{impls::stream_adapter::traits::StreamAdapter, TransportSocket},
-    types::ConnectionDirection,
+    types::{ConnectionDirection, TransportProtocol},
     Result,
 };
 
@@ -52,6 +52,8 @@ impl<M: Fn() -> S + Sync + Send + 'static, S: StreamAdapter<T::Stream>, T: Trans
     type Listener = AdaptedListener<S, T>;
     type Stream = S::Stream;
 
+    const PROTOCOL: TransportProtocol = T::PROTOCOL;
+
     async fn bind(&self, addresses: Vec<SocketAddr>) -> Result<Self::Listener> {
         let stream_adapter = (self.stream_adapter_maker)();
         let listener = self.base_transport.bind(addresses).await?;
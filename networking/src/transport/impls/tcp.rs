@@ -22,6 +22,7 @@ use utils::tap_log::TapLog;
 
 use crate::{
     transport::{ConnectedSocketInfo, PeerStream, TransportListener, TransportSocket},
+    types::TransportProtocol,
     Result,
 };
 
@@ -39,6 +40,8 @@ impl TransportSocket for TcpTransportSocket {
     type Listener = TcpTransportListener;
     type Stream = TcpTransportStream;
 
+    const PROTOCOL: TransportProtocol = TransportProtocol::Tcp;
+
     async fn bind(&self, addresses: Vec<SocketAddr>) -> Result<Self::Listener> {
         TcpTransportListener::new(addresses)
     }
@@ -16,6 +16,7 @@
 mod buffered_transcoder;
 mod impls;
 mod message_codec;
+mod multiplexed_transcoder;
 mod traits;
 
 use impls::{channel, socks5, stream_adapter, tcp};
@@ -23,6 +24,7 @@ use impls::{channel, socks5, stream_adapter, tcp};
 pub use self::{
     buffered_transcoder::BufferedTranscoder,
     channel::{ChannelListener, ChannelStream, MpscChannelTransport, MpscChannelTransportError},
+    multiplexed_transcoder::MultiplexedTranscoder,
     socks5::Socks5TransportSocket,
     stream_adapter::{
         identity::IdentityStreamAdapter,
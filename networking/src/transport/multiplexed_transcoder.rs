@@ -0,0 +1,557 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A transcoder that multiplexes several logical streams ("lanes") over one connection.
+//!
+//! Each message is sent over one of the lanes and is split into chunks; the chunks of
+//! the lanes that have something to send are written in turn, so a large message on one lane
+//! doesn't hold back the messages on the others. Inside a lane the messages are delivered
+//! in order; there is no ordering between the lanes.
+//!
+//! Every chunk is preceded by a header consisting of the lane number (one byte) and the chunk
+//! length (two bytes, little endian). The data of a lane is the same as what `MessageCodec`
+//! produces, i.e. each message is prefixed by its length.
+//!
+//! The amount of data that may wait to be written is limited per lane; if the peer doesn't
+//! read fast enough and the limit is reached, sending fails, so that the peer is disconnected.
+
+use std::{
+    collections::VecDeque,
+    io,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use bytes::{Buf, BufMut, BytesMut};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadHalf},
+    sync::mpsc,
+};
+use tokio_util::codec::{Decoder, Encoder};
+
+use logging::log;
+use serialization::{Decode, Encode};
+
+use crate::error::MultiplexedFrameError;
+
+use super::{message_codec::MessageCodec, ConnectedSocketInfo};
+
+/// The size of the chunk header: the lane number and the chunk length.
+const CHUNK_HEADER_SIZE: usize = 3;
+
+/// The maximum number of bytes of a message that are written before switching to another lane.
+const MAX_CHUNK_SIZE: usize = 16 * 1024;
+
+/// How long the data that is still queued when the transcoder is dropped may take to be sent.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The number of messages that may be passed to the writer task before `send` has to wait.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// How many messages of the maximum size may wait to be written on a lane.
+const LANE_BUDGET_IN_MESSAGES: usize = 4;
+
+/// The number of bytes that may wait to be written on a lane if the message size isn't limited.
+const DEFAULT_LANE_BUDGET: usize = 64 * 1024 * 1024;
+
+pub struct MultiplexedTranscoder<S, Msg> {
+    read_half: ReadHalf<S>,
+    buffer: BytesMut,
+    lane_buffers: Vec<BytesMut>,
+    /// The lane that has received new data since it was last decoded.
+    lane_to_decode: Option<u8>,
+    chunk_sender: mpsc::Sender<(u8, BytesMut)>,
+    /// The number of bytes per lane that have been sent but not written yet.
+    queued_bytes: Arc<[AtomicUsize]>,
+    lane_budget: usize,
+    message_codec: MessageCodec<Msg>,
+    local_address: crate::Result<SocketAddr>,
+    remote_address: crate::Result<SocketAddr>,
+}
+
+impl<S, Msg> MultiplexedTranscoder<S, Msg>
+where
+    S: AsyncWrite + AsyncRead + ConnectedSocketInfo + Send + 'static,
+{
+    /// Start multiplexing the stream over the specified number of lanes.
+    ///
+    /// The writing is done by a separate task, which keeps running after the transcoder
+    /// is dropped until the queued data is sent (or `DRAIN_TIMEOUT` expires).
+    pub fn new(stream: S, lane_count: u8, max_message_size: Option<usize>) -> Self {
+        Self::with_received_data(stream, lane_count, max_message_size, BytesMut::new())
+    }
+
+    /// Same as `new`, for a stream some data of which has already been read, e.g. when switching
+    /// to multiplexing after a handshake done with a different framing.
+    pub fn with_received_data(
+        stream: S,
+        lane_count: u8,
+        max_message_size: Option<usize>,
+        received_data: BytesMut,
+    ) -> Self {
+        assert!(lane_count > 0, "At least one lane is required");
+
+        let local_address = stream.local_address();
+        let remote_address = stream.remote_address();
+
+        let (read_half, write_half) = tokio::io::split(stream);
+        let (chunk_sender, chunk_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+        let queued_bytes: Arc<[AtomicUsize]> =
+            (0..lane_count).map(|_| AtomicUsize::new(0)).collect();
+        let lane_budget = max_message_size.map_or(DEFAULT_LANE_BUDGET, |size| {
+            size.saturating_mul(LANE_BUDGET_IN_MESSAGES)
+        });
+
+        let writer_queued_bytes = Arc::clone(&queued_bytes);
+        logging::spawn_in_current_span(async move {
+            if let Err(e) =
+                run_writer(write_half, chunk_receiver, lane_count, writer_queued_bytes).await
+            {
+                log::debug!("Multiplexed stream writer failed: {e}");
+            }
+        });
+
+        MultiplexedTranscoder {
+            read_half,
+            buffer: received_data,
+            lane_buffers: (0..lane_count).map(|_| BytesMut::new()).collect(),
+            lane_to_decode: None,
+            chunk_sender,
+            queued_bytes,
+            lane_budget,
+            message_codec: MessageCodec::new(max_message_size),
+            local_address,
+            remote_address,
+        }
+    }
+}
+
+impl<S, Msg> MultiplexedTranscoder<S, Msg>
+where
+    Msg: Encode + Decode,
+    S: AsyncRead,
+{
+    fn lane_count(&self) -> u8 {
+        self.lane_buffers.len() as u8
+    }
+
+    /// Queue the message for sending over the specified lane.
+    ///
+    /// This doesn't wait for the message to be written, only for the writer task to accept it.
+    /// An error is returned if the connection has already failed or if the data waiting
+    /// to be written on the lane would exceed its budget, i.e. the peer doesn't keep up.
+    pub async fn send(&mut self, lane: u8, msg: Msg) -> crate::Result<()> {
+        assert!(lane < self.lane_count(), "Invalid lane {lane}");
+
+        let mut buf = BytesMut::new();
+        self.message_codec.encode(msg, &mut buf)?;
+
+        let queued_bytes = &self.queued_bytes[lane as usize];
+        let total_queued = queued_bytes.fetch_add(buf.len(), Ordering::Relaxed) + buf.len();
+        if total_queued > self.lane_budget {
+            queued_bytes.fetch_sub(buf.len(), Ordering::Relaxed);
+            return Err(MultiplexedFrameError::LaneBudgetExceeded {
+                lane,
+                budget: self.lane_budget,
+            }
+            .into());
+        }
+
+        self.chunk_sender
+            .send((lane, buf))
+            .await
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+        Ok(())
+    }
+
+    /// Read a message from any of the lanes.
+    ///
+    /// This is cancel-safe, i.e. no data is lost if the returned future is dropped before
+    /// it completes.
+    pub async fn recv(&mut self) -> crate::Result<Msg> {
+        loop {
+            if let Some(lane) = self.lane_to_decode {
+                match self.message_codec.decode(&mut self.lane_buffers[lane as usize])? {
+                    // Keep the lane, there may be more messages in its buffer
+                    Some(msg) => return Ok(msg),
+                    None => self.lane_to_decode = None,
+                }
+            }
+
+            if let Some(lane) = self.take_chunk()? {
+                self.lane_to_decode = Some(lane);
+                continue;
+            }
+
+            if self.read_half.read_buf(&mut self.buffer).await? == 0 {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+            }
+        }
+    }
+
+    /// Move the next chunk from the receive buffer to the buffer of its lane, if it has been
+    /// received completely.
+    fn take_chunk(&mut self) -> crate::Result<Option<u8>> {
+        if self.buffer.len() < CHUNK_HEADER_SIZE {
+            return Ok(None);
+        }
+
+        let lane = self.buffer[0];
+        let chunk_size = u16::from_le_bytes([self.buffer[1], self.buffer[2]]) as usize;
+
+        if lane >= self.lane_count() {
+            return Err(MultiplexedFrameError::UnknownLane {
+                lane,
+                lane_count: self.lane_count(),
+            }
+            .into());
+        }
+        if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+            return Err(MultiplexedFrameError::InvalidChunkSize(chunk_size).into());
+        }
+
+        if self.buffer.len() < CHUNK_HEADER_SIZE + chunk_size {
+            self.buffer.reserve(CHUNK_HEADER_SIZE + chunk_size - self.buffer.len());
+            return Ok(None);
+        }
+
+        self.buffer.advance(CHUNK_HEADER_SIZE);
+        let chunk = self.buffer.split_to(chunk_size);
+        self.lane_buffers[lane as usize].unsplit(chunk);
+
+        Ok(Some(lane))
+    }
+}
+
+impl<S, Msg> ConnectedSocketInfo for MultiplexedTranscoder<S, Msg> {
+    fn local_address(&self) -> crate::Result<SocketAddr> {
+        self.local_address.clone()
+    }
+
+    fn remote_address(&self) -> crate::Result<SocketAddr> {
+        self.remote_address.clone()
+    }
+}
+
+/// The messages waiting to be written, per lane.
+struct LaneQueues {
+    queues: Vec<VecDeque<BytesMut>>,
+    next_lane: usize,
+}
+
+impl LaneQueues {
+    fn new(lane_count: u8) -> Self {
+        Self {
+            queues: (0..lane_count).map(|_| VecDeque::new()).collect(),
+            next_lane: 0,
+        }
+    }
+
+    fn push(&mut self, lane: u8, data: BytesMut) {
+        self.queues[lane as usize].push_back(data);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.queues.iter().all(VecDeque::is_empty)
+    }
+
+    /// Take the next chunk, going through the non-empty lanes in turn.
+    fn next_chunk(&mut self) -> Option<(u8, BytesMut)> {
+        let lane_count = self.queues.len();
+        let lane = (0..lane_count)
+            .map(|i| (self.next_lane + i) % lane_count)
+            .find(|lane| !self.queues[*lane].is_empty())?;
+        self.next_lane = (lane + 1) % lane_count;
+
+        let queue = &mut self.queues[lane];
+        let message = queue.front_mut().expect("the lane must not be empty");
+        let chunk = message.split_to(std::cmp::min(message.len(), MAX_CHUNK_SIZE));
+        if message.is_empty() {
+            queue.pop_front();
+        }
+
+        Some((lane as u8, chunk))
+    }
+}
+
+async fn write_next_chunk<W: AsyncWrite + Unpin>(
+    stream: &mut W,
+    queues: &mut LaneQueues,
+    queued_bytes: &[AtomicUsize],
+) -> crate::Result<()> {
+    if let Some((lane, chunk)) = queues.next_chunk() {
+        let chunk_size = chunk.len();
+        let mut buf = BytesMut::with_capacity(CHUNK_HEADER_SIZE + chunk_size);
+        buf.put_u8(lane);
+        buf.put_u16_le(chunk_size as u16);
+        buf.unsplit(chunk);
+        stream.write_all(&buf).await?;
+        queued_bytes[lane as usize].fetch_sub(chunk_size, Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+async fn run_writer<W: AsyncWrite + Unpin>(
+    mut stream: W,
+    mut chunk_receiver: mpsc::Receiver<(u8, BytesMut)>,
+    lane_count: u8,
+    queued_bytes: Arc<[AtomicUsize]>,
+) -> crate::Result<()> {
+    let mut queues = LaneQueues::new(lane_count);
+
+    'running: loop {
+        if queues.is_empty() {
+            match chunk_receiver.recv().await {
+                Some((lane, data)) => queues.push(lane, data),
+                None => break 'running,
+            }
+        }
+
+        // Pick up the messages that have been queued in the meantime, so that they get
+        // their turn before the rest of the current ones
+        loop {
+            match chunk_receiver.try_recv() {
+                Ok((lane, data)) => queues.push(lane, data),
+                Err(mpsc::error::TryRecvError::Empty) => break,
+                Err(mpsc::error::TryRecvError::Disconnected) => break 'running,
+            }
+        }
+
+        write_next_chunk(&mut stream, &mut queues, &queued_bytes).await?;
+
+        if queues.is_empty() {
+            stream.flush().await?;
+        }
+    }
+
+    // The transcoder is gone; send what's left, but don't wait for a stalled peer forever
+    tokio::time::timeout(DRAIN_TIMEOUT, async {
+        while !queues.is_empty() {
+            write_next_chunk(&mut stream, &mut queues, &queued_bytes).await?;
+        }
+        stream.shutdown().await?;
+        Ok(())
+    })
+    .await
+    .map_err(|_| io::Error::from(io::ErrorKind::TimedOut))?
+}
+
+#[cfg(test)]
+mod tests {
+    use randomness::Rng;
+    use test_utils::random::{gen_random_bytes, make_seedable_rng, Seed};
+    use tokio::io::DuplexStream;
+
+    use crate::transport::BufferedTranscoder;
+
+    use super::*;
+
+    impl ConnectedSocketInfo for DuplexStream {
+        fn local_address(&self) -> crate::Result<SocketAddr> {
+            Ok("127.0.0.1:1".parse().unwrap())
+        }
+
+        fn remote_address(&self) -> crate::Result<SocketAddr> {
+            Ok("127.0.0.1:2".parse().unwrap())
+        }
+    }
+
+    fn make_transcoders(
+        buf_size: usize,
+        lane_count: u8,
+    ) -> (
+        MultiplexedTranscoder<DuplexStream, Vec<u8>>,
+        MultiplexedTranscoder<DuplexStream, Vec<u8>>,
+    ) {
+        let (stream1, stream2) = tokio::io::duplex(buf_size);
+        (
+            MultiplexedTranscoder::new(stream1, lane_count, None),
+            MultiplexedTranscoder::new(stream2, lane_count, None),
+        )
+    }
+
+    // Send messages of various lengths over random lanes and check that the messages of each
+    // lane arrive unchanged and in order.
+    #[tracing::instrument(skip(seed))]
+    #[rstest::rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    #[tokio::test]
+    async fn message_roundtrip(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        let lane_count = rng.gen_range(1..=4);
+        let message_count = rng.gen_range(1..=20u8);
+        let max_msg_size = 3 * MAX_CHUNK_SIZE;
+        // The first byte of a message is its index, so that it can be told apart from the others
+        let messages = (0..message_count)
+            .map(|i| {
+                let mut message = vec![i];
+                message.extend(gen_random_bytes(&mut rng, 1, max_msg_size));
+                (rng.gen_range(0..lane_count), message)
+            })
+            .collect::<Vec<_>>();
+
+        let (mut sender, mut receiver) =
+            make_transcoders(rng.gen_range(10..max_msg_size + 10), lane_count);
+
+        for (lane, message) in &messages {
+            sender.send(*lane, message.clone()).await.unwrap();
+        }
+
+        let mut received = vec![Vec::new(); lane_count as usize];
+        for _ in 0..message_count {
+            let message = receiver.recv().await.unwrap();
+            let lane = messages[message[0] as usize].0;
+            received[lane as usize].push(message);
+        }
+
+        for lane in 0..lane_count {
+            let expected = messages
+                .iter()
+                .filter(|(msg_lane, _)| *msg_lane == lane)
+                .map(|(_, msg)| msg.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(received[lane as usize], expected);
+        }
+
+        assert!(receiver.buffer.is_empty());
+        assert!(receiver.lane_buffers.iter().all(BytesMut::is_empty));
+    }
+
+    // A small message isn't held back by a large one sent earlier over another lane.
+    #[tokio::test]
+    async fn no_head_of_line_blocking() {
+        let (mut sender, mut receiver) = make_transcoders(1024, 2);
+
+        let large_message = vec![1; 10 * MAX_CHUNK_SIZE];
+        let small_message = vec![2; 10];
+        sender.send(0, large_message.clone()).await.unwrap();
+        sender.send(1, small_message.clone()).await.unwrap();
+
+        assert_eq!(receiver.recv().await.unwrap(), small_message);
+        assert_eq!(receiver.recv().await.unwrap(), large_message);
+    }
+
+    // The queued messages are still delivered after the sender is dropped.
+    #[tokio::test]
+    async fn drain_on_drop() {
+        let (mut sender, mut receiver) = make_transcoders(1024, 2);
+
+        let message = vec![3; 5 * MAX_CHUNK_SIZE];
+        sender.send(1, message.clone()).await.unwrap();
+        drop(sender);
+
+        assert_eq!(receiver.recv().await.unwrap(), message);
+        assert_eq!(
+            receiver.recv().await,
+            Err(io::Error::from(io::ErrorKind::UnexpectedEof).into())
+        );
+    }
+
+    // Sending fails once the data waiting on a lane exceeds its budget, but the data that has
+    // been written doesn't count toward it.
+    #[tokio::test]
+    async fn lane_budget() {
+        let max_message_size = 2 * MAX_CHUNK_SIZE;
+        let (stream1, stream2) = tokio::io::duplex(1024);
+        let mut sender =
+            MultiplexedTranscoder::<_, Vec<u8>>::new(stream1, 2, Some(max_message_size));
+        let mut receiver =
+            MultiplexedTranscoder::<_, Vec<u8>>::new(stream2, 2, Some(max_message_size));
+
+        let message = vec![4; MAX_CHUNK_SIZE];
+        let mut sent_count = 0;
+        let err = loop {
+            match sender.send(0, message.clone()).await {
+                Ok(()) => sent_count += 1,
+                Err(err) => break err,
+            }
+        };
+        assert_eq!(
+            err,
+            MultiplexedFrameError::LaneBudgetExceeded {
+                lane: 0,
+                budget: max_message_size * LANE_BUDGET_IN_MESSAGES
+            }
+            .into()
+        );
+        assert!(sent_count >= 2 * LANE_BUDGET_IN_MESSAGES - 1);
+
+        // Other lanes have their own budget
+        sender.send(1, message.clone()).await.unwrap();
+
+        for _ in 0..=sent_count {
+            assert_eq!(receiver.recv().await.unwrap(), message);
+        }
+        sender.send(0, message.clone()).await.unwrap();
+        assert_eq!(receiver.recv().await.unwrap(), message);
+    }
+
+    // The data read ahead by the buffered transcoder isn't lost when switching to multiplexing.
+    #[tokio::test]
+    async fn switch_from_buffered() {
+        let (stream1, stream2) = tokio::io::duplex(1024);
+        let mut sender = BufferedTranscoder::<_, Vec<u8>>::new(stream1, None);
+        let mut receiver = BufferedTranscoder::<_, Vec<u8>>::new(stream2, None);
+
+        let handshake = vec![5; 10];
+        let message = vec![6; 2 * MAX_CHUNK_SIZE];
+        sender.send(handshake.clone()).await.unwrap();
+        let (stream1, received_data) = sender.into_parts();
+        assert!(received_data.is_empty());
+        let mut sender = MultiplexedTranscoder::new(stream1, 2, None);
+        sender.send(1, message.clone()).await.unwrap();
+
+        assert_eq!(receiver.recv().await.unwrap(), handshake);
+        let (stream2, received_data) = receiver.into_parts();
+        let mut receiver = MultiplexedTranscoder::<_, Vec<u8>>::with_received_data(
+            stream2,
+            2,
+            None,
+            received_data,
+        );
+        assert_eq!(receiver.recv().await.unwrap(), message);
+    }
+
+    #[tokio::test]
+    async fn invalid_frames() {
+        let (mut stream1, stream2) = tokio::io::duplex(1024);
+        let mut receiver = MultiplexedTranscoder::<_, Vec<u8>>::new(stream2, 2, None);
+
+        stream1.write_all(&[2, 1, 0, 0]).await.unwrap();
+        assert_eq!(
+            receiver.recv().await,
+            Err(MultiplexedFrameError::UnknownLane {
+                lane: 2,
+                lane_count: 2
+            }
+            .into())
+        );
+
+        let (mut stream1, stream2) = tokio::io::duplex(1024);
+        let mut receiver = MultiplexedTranscoder::<_, Vec<u8>>::new(stream2, 2, None);
+
+        stream1.write_all(&[1, 0, 0]).await.unwrap();
+        assert_eq!(
+            receiver.recv().await,
+            Err(MultiplexedFrameError::InvalidChunkSize(0).into())
+        );
+    }
+}
@@ -18,7 +18,7 @@ use std::net::SocketAddr;
 use async_trait::async_trait;
 use futures::future::BoxFuture;
 
-use crate::Result;
+use crate::{types::TransportProtocol, Result};
 
 use super::{listener::TransportListener, stream::PeerStream};
 
@@ -34,6 +34,9 @@ pub trait TransportSocket: Send + Sync + 'static {
     /// A messages stream.
     type Stream: PeerStream + ConnectedSocketInfo;
 
+    /// The protocol used by the transport, e.g. to report it for the listening addresses.
+    const PROTOCOL: TransportProtocol;
+
     /// Creates a new listener bound to the specified address.
     async fn bind(&self, address: Vec<SocketAddr>) -> Result<Self::Listener>;
 
@@ -18,3 +18,19 @@ pub enum ConnectionDirection {
     Inbound,
     Outbound,
 }
+
+/// The protocol that a transport uses to carry the peer connections.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+pub enum TransportProtocol {
+    Tcp,
+}
+
+impl std::fmt::Display for TransportProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransportProtocol::Tcp => write!(f, "tcp"),
+        }
+    }
+}
@@ -384,6 +384,7 @@ impl Backend {
             ControllerConfig {
                 in_top_x_mb: IN_TOP_X_MB,
                 broadcast_to_mempool: true,
+                fee_override: None,
             },
             WalletRpcHandlesClient::new(wallet_rpc.clone(), None),
         )
@@ -542,6 +543,7 @@ impl Backend {
             ControllerConfig {
                 in_top_x_mb: IN_TOP_X_MB,
                 broadcast_to_mempool: true,
+                fee_override: None,
             },
             WalletRpcHandlesClient::new(wallet_rpc.clone(), None),
         )
@@ -705,7 +707,7 @@ impl Backend {
             .ok_or(BackendError::InvalidAmount(amount))?;
 
         // TODO: add support for utxo selection in the GUI
-        let tx = self
+        let (tx, _fee) = self
             .hot_wallet(wallet_id)?
             .send_coins(
                 account_id.account_index(),
@@ -716,6 +718,7 @@ impl Backend {
                     in_top_x_mb: IN_TOP_X_MB,
                     // don't broadcast_to_mempool before confirmation dialog
                     broadcast_to_mempool: false,
+                    fee_override: None,
                 },
             )
             .await
@@ -764,6 +767,7 @@ impl Backend {
                     in_top_x_mb: IN_TOP_X_MB,
                     // don't broadcast_to_mempool before confirmation dialog
                     broadcast_to_mempool: false,
+                    fee_override: None,
                 },
             )
             .await
@@ -793,6 +797,7 @@ impl Backend {
                     in_top_x_mb: IN_TOP_X_MB,
                     // don't broadcast_to_mempool before confirmation dialog
                     broadcast_to_mempool: false,
+                    fee_override: None,
                 },
             )
             .await
@@ -822,6 +827,7 @@ impl Backend {
                     in_top_x_mb: IN_TOP_X_MB,
                     // don't broadcast_to_mempool before confirmation dialog
                     broadcast_to_mempool: false,
+                    fee_override: None,
                 },
             )
             .await
@@ -858,6 +864,7 @@ impl Backend {
                     in_top_x_mb: IN_TOP_X_MB,
                     // don't broadcast_to_mempool before confirmation dialog
                     broadcast_to_mempool: false,
+                    fee_override: None,
                 },
             )
             .await
@@ -898,6 +905,7 @@ impl Backend {
                     in_top_x_mb: IN_TOP_X_MB,
                     // don't broadcast_to_mempool before confirmation dialog
                     broadcast_to_mempool: false,
+                    fee_override: None,
                 },
             )
             .await
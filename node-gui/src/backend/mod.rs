@@ -164,6 +164,8 @@ pub async fn node_initialize(
                 median_time: chain_config.genesis_block().timestamp(),
                 best_block_timestamp: chain_config.genesis_block().timestamp(),
                 is_initial_block_download: false,
+                best_header_id: chain_config.genesis_block_id(),
+                best_header_height: BlockHeight::zero(),
             };
 
             let manager_join_handle = tokio::spawn(async move {});
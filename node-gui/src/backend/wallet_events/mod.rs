@@ -51,4 +51,8 @@ impl WalletEvents for GuiWalletEvents {
     fn del_transaction(&self, _id: U31, _source: OutPointSourceId) {
         self.notify();
     }
+
+    fn node_switched(&self, _address: &str) {
+        self.notify();
+    }
 }
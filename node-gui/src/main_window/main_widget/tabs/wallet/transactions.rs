@@ -42,7 +42,8 @@ pub fn view_transactions(
             .push(field("Timestamp (UTC)".into()))
             .push(field("Type".into()))
             .push(field("Amount".into()))
-            .push(field("State".into())),
+            .push(field("State".into()))
+            .push(field("Labels".into())),
     );
     for (index, tx) in current_transaction_list.txs.iter().enumerate() {
         let amount_str = tx
@@ -83,7 +84,8 @@ pub fn view_transactions(
                 .push(field(timestamp))
                 .push(field(tx.tx_type.type_name().to_owned()))
                 .push(field(amount_str))
-                .push(field(tx.state.short_name().to_owned())),
+                .push(field(tx.state.short_name().to_owned()))
+                .push(field(tx.labels.join(", "))),
         );
     }
 
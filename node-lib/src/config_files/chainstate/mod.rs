@@ -36,6 +36,21 @@ pub struct ChainstateConfigFile {
     pub max_tip_age: Option<u64>,
     /// If true, additional computationally-expensive consistency checks will be performed by the chainstate.
     pub enable_heavy_checks: Option<bool>,
+    /// If true, the processing of transactions is deferred until the initial block download is
+    /// finished. This can only be disabled on regtest.
+    pub defer_txs_during_ibd: Option<bool>,
+    /// If true, the transactions of the mainchain blocks are indexed by their ids.
+    pub enable_tx_index: Option<bool>,
+    /// The number of the most recent mainchain blocks that are checked on startup and replayed
+    /// if the previous run was interrupted in an inconsistent state.
+    pub recovery_replay_depth: Option<u64>,
+    /// The number of the most recent mainchain blocks whose undo data is kept when the undo data
+    /// is compacted; it can't be less than the maximum reorg depth.
+    pub undo_data_retention_depth: Option<u64>,
+    /// How often, in seconds, the chainstate checks whether the database should be compacted
+    /// on the next start (it's not compacted until the node is restarted); the check is disabled
+    /// if not set.
+    pub auto_storage_compaction_interval: Option<u64>,
 }
 
 impl From<ChainstateConfigFile> for ChainstateConfig {
@@ -46,6 +61,11 @@ impl From<ChainstateConfigFile> for ChainstateConfig {
             min_max_bootstrap_import_buffer_sizes,
             max_tip_age,
             enable_heavy_checks,
+            defer_txs_during_ibd,
+            enable_tx_index,
+            recovery_replay_depth,
+            undo_data_retention_depth,
+            auto_storage_compaction_interval,
         } = config_file;
 
         ChainstateConfig {
@@ -54,6 +74,12 @@ impl From<ChainstateConfigFile> for ChainstateConfig {
             min_max_bootstrap_import_buffer_sizes: min_max_bootstrap_import_buffer_sizes.into(),
             max_tip_age: max_tip_age.map(Duration::from_secs).into(),
             enable_heavy_checks,
+            defer_txs_during_ibd,
+            enable_tx_index,
+            recovery_replay_depth: recovery_replay_depth.into(),
+            undo_data_retention_depth,
+            auto_storage_compaction_interval: auto_storage_compaction_interval
+                .map(Duration::from_secs),
         }
     }
 }
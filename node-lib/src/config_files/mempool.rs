@@ -13,6 +13,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
 use common::primitives::Amount;
@@ -27,6 +29,20 @@ use crate::RunOptions;
 pub struct MempoolConfigFile {
     /// Minimum transaction relay fee rate (in atoms per 1000 bytes).
     pub min_tx_relay_fee_rate: Option<u64>,
+
+    /// If true, transactions are accepted regardless of the fees they pay (regtest only).
+    pub accept_zero_fee_txs: Option<bool>,
+
+    /// If true, transactions conflicting with mempool transactions may replace them
+    /// by paying higher fees.
+    pub enable_rbf: Option<bool>,
+
+    /// Minimum fee rate increment a replacement transaction has to pay over the transactions
+    /// it replaces (in atoms per 1000 bytes).
+    pub min_rbf_fee_rate_increment: Option<u64>,
+
+    /// File to which the mempool activity is appended for fee estimator backtesting.
+    pub fee_estimator_recording_file: Option<PathBuf>,
 }
 
 impl MempoolConfigFile {
@@ -37,12 +53,28 @@ impl MempoolConfigFile {
     pub fn with_run_options(config: MempoolConfigFile, options: &RunOptions) -> MempoolConfigFile {
         let MempoolConfigFile {
             min_tx_relay_fee_rate,
+            accept_zero_fee_txs,
+            enable_rbf,
+            min_rbf_fee_rate_increment,
+            fee_estimator_recording_file,
         } = config;
 
         let min_tx_relay_fee_rate = min_tx_relay_fee_rate.or(options.min_tx_relay_fee_rate);
+        let accept_zero_fee_txs = options.accept_zero_fee_txs.or(accept_zero_fee_txs);
+        let enable_rbf = options.enable_rbf.or(enable_rbf);
+        let min_rbf_fee_rate_increment =
+            options.min_rbf_fee_rate_increment.or(min_rbf_fee_rate_increment);
+        let fee_estimator_recording_file = options
+            .mempool_fee_estimator_recording_file
+            .clone()
+            .or(fee_estimator_recording_file);
 
         MempoolConfigFile {
             min_tx_relay_fee_rate,
+            accept_zero_fee_txs,
+            enable_rbf,
+            min_rbf_fee_rate_increment,
+            fee_estimator_recording_file,
         }
     }
 }
@@ -51,12 +83,22 @@ impl From<MempoolConfigFile> for MempoolConfig {
     fn from(config_file: MempoolConfigFile) -> Self {
         let MempoolConfigFile {
             min_tx_relay_fee_rate,
+            accept_zero_fee_txs,
+            enable_rbf,
+            min_rbf_fee_rate_increment,
+            fee_estimator_recording_file,
         } = config_file;
 
         Self {
             min_tx_relay_fee_rate: min_tx_relay_fee_rate
                 .map(|val| FeeRate::from_amount_per_kb(Amount::from_atoms(val.into())))
                 .into(),
+            accept_zero_fee_txs: accept_zero_fee_txs.into(),
+            enable_rbf: enable_rbf.into(),
+            min_rbf_fee_rate_increment: min_rbf_fee_rate_increment
+                .map(|val| FeeRate::from_amount_per_kb(Amount::from_atoms(val.into())))
+                .into(),
+            fee_estimator_recording_file,
         }
     }
 }
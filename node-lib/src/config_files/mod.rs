@@ -152,6 +152,11 @@ fn chainstate_config(
         min_max_bootstrap_import_buffer_sizes,
         max_tip_age,
         enable_heavy_checks,
+        defer_txs_during_ibd,
+        enable_tx_index,
+        recovery_replay_depth,
+        undo_data_retention_depth,
+        auto_storage_compaction_interval,
     } = chainstate_config;
 
     let storage_backend = options.storage_backend.clone().unwrap_or(storage_backend);
@@ -159,6 +164,8 @@ fn chainstate_config(
     let max_orphan_blocks = options.max_orphan_blocks.or(max_orphan_blocks);
     let max_tip_age = options.max_tip_age.or(max_tip_age);
     let enable_heavy_checks = options.enable_chainstate_heavy_checks.or(enable_heavy_checks);
+    let defer_txs_during_ibd = options.defer_txs_during_ibd.or(defer_txs_during_ibd);
+    let enable_tx_index = options.enable_tx_index.or(enable_tx_index);
 
     let chainstate_config = ChainstateConfigFile {
         max_db_commit_attempts,
@@ -166,6 +173,11 @@ fn chainstate_config(
         min_max_bootstrap_import_buffer_sizes,
         max_tip_age,
         enable_heavy_checks,
+        defer_txs_during_ibd,
+        enable_tx_index,
+        recovery_replay_depth,
+        undo_data_retention_depth,
+        auto_storage_compaction_interval,
     };
     ChainstateLauncherConfigFile {
         storage_backend,
@@ -179,10 +191,13 @@ fn p2p_config(config: P2pConfigFile, options: &RunOptions) -> P2pConfigFile {
         bind_addresses,
         socks5_proxy,
         disable_noise,
+        multiplexed_transport,
         boot_nodes,
         reserved_nodes,
         whitelisted_addresses,
         max_inbound_connections,
+        max_inbound_connections_per_address_group,
+        max_outbound_connections_per_network,
         discouragement_threshold,
         discouragement_duration,
         max_clock_diff,
@@ -192,16 +207,25 @@ fn p2p_config(config: P2pConfigFile, options: &RunOptions) -> P2pConfigFile {
         sync_stalling_timeout,
         node_type,
         force_dns_query_if_no_global_addresses_known,
+        local_tx_broadcast_privacy,
+        local_tx_broadcast_resubmit,
     } = config;
 
     let networking_enabled = options.p2p_networking_enabled.or(networking_enabled);
     let bind_addresses = options.p2p_bind_addresses.clone().or(bind_addresses);
     let socks5_proxy = options.p2p_socks5_proxy.clone().or(socks5_proxy);
     let disable_noise = options.p2p_disable_noise.or(disable_noise);
+    let multiplexed_transport = options.p2p_multiplexed_transport.or(multiplexed_transport);
     let boot_nodes = options.p2p_boot_nodes.clone().or(boot_nodes);
     let reserved_nodes = options.p2p_reserved_nodes.clone().or(reserved_nodes);
     let whitelisted_addresses = options.p2p_whitelist_addr.clone().or(whitelisted_addresses);
     let max_inbound_connections = options.p2p_max_inbound_connections.or(max_inbound_connections);
+    let max_inbound_connections_per_address_group = options
+        .p2p_max_inbound_connections_per_address_group
+        .or(max_inbound_connections_per_address_group);
+    let max_outbound_connections_per_network = options
+        .p2p_max_outbound_connections_per_network
+        .or(max_outbound_connections_per_network);
     let discouragement_threshold =
         options.p2p_discouragement_threshold.or(discouragement_threshold);
     let discouragement_duration = options.p2p_discouragement_duration.or(discouragement_duration);
@@ -215,16 +239,23 @@ fn p2p_config(config: P2pConfigFile, options: &RunOptions) -> P2pConfigFile {
     let force_dns_query_if_no_global_addresses_known = options
         .p2p_force_dns_query_if_no_global_addresses_known
         .or(force_dns_query_if_no_global_addresses_known);
+    let local_tx_broadcast_privacy =
+        options.p2p_local_tx_broadcast_privacy.or(local_tx_broadcast_privacy);
+    let local_tx_broadcast_resubmit =
+        options.p2p_local_tx_broadcast_resubmit.or(local_tx_broadcast_resubmit);
 
     P2pConfigFile {
         networking_enabled,
         bind_addresses,
         socks5_proxy,
         disable_noise,
+        multiplexed_transport,
         boot_nodes,
         reserved_nodes,
         whitelisted_addresses,
         max_inbound_connections,
+        max_inbound_connections_per_address_group,
+        max_outbound_connections_per_network,
         discouragement_threshold,
         discouragement_duration,
         max_clock_diff,
@@ -234,6 +265,8 @@ fn p2p_config(config: P2pConfigFile, options: &RunOptions) -> P2pConfigFile {
         sync_stalling_timeout,
         node_type,
         force_dns_query_if_no_global_addresses_known,
+        local_tx_broadcast_privacy,
+        local_tx_broadcast_resubmit,
     }
 }
 
@@ -13,12 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{
-    net::{IpAddr, SocketAddr},
-    num::NonZeroU64,
-    str::FromStr,
-    time::Duration,
-};
+use std::{net::SocketAddr, num::NonZeroU64, str::FromStr, time::Duration};
 
 use common::primitives::user_agent::mintlayer_core_user_agent;
 use serde::{Deserialize, Serialize};
@@ -27,8 +22,9 @@ use p2p::{
     ban_config::BanConfig,
     config::{NodeType, P2pConfig},
     peer_manager::config::PeerManagerConfig,
+    sync::local_tx_broadcast::LocalTxBroadcastConfig,
 };
-use utils_networking::IpOrSocketAddress;
+use utils_networking::{IpOrSocketAddress, IpSubnet};
 
 /// A node type.
 #[derive(Debug, Serialize, Deserialize, Copy, Clone, Eq, PartialEq)]
@@ -72,14 +68,20 @@ pub struct P2pConfigFile {
     pub socks5_proxy: Option<String>,
     /// Disable p2p encryption (for tests only).
     pub disable_noise: Option<bool>,
+    /// Use the experimental multiplexed transport with the peers that support it.
+    pub multiplexed_transport: Option<bool>,
     /// Optional list of boot node addresses to connect.
     pub boot_nodes: Option<Vec<IpOrSocketAddress>>,
     /// Optional list of reserved node addresses to connect.
     pub reserved_nodes: Option<Vec<IpOrSocketAddress>>,
-    /// Optional list of whitelisted addresses.
-    pub whitelisted_addresses: Option<Vec<IpAddr>>,
+    /// Optional list of whitelisted addresses or subnets.
+    pub whitelisted_addresses: Option<Vec<IpSubnet>>,
     /// Maximum allowed number of inbound connections.
     pub max_inbound_connections: Option<usize>,
+    /// Maximum allowed number of inbound connections from peers in the same address group.
+    pub max_inbound_connections_per_address_group: Option<usize>,
+    /// Maximum allowed number of automatic outbound connections to peers in the same network.
+    pub max_outbound_connections_per_network: Option<usize>,
     /// The score threshold after which a peer becomes discouraged.
     pub discouragement_threshold: Option<u32>,
     /// Duration of discouragement in seconds.
@@ -100,6 +102,12 @@ pub struct P2pConfigFile {
     /// If true, the node will perform an early dns query if the peer db doesn't contain
     /// any global addresses at startup.
     pub force_dns_query_if_no_global_addresses_known: Option<bool>,
+    /// If true, transactions originating from this node are announced to a few random peers
+    /// after a random delay first, and to the rest of the peers later.
+    pub local_tx_broadcast_privacy: Option<bool>,
+    /// If true, a delayed local transaction is also re-submitted through one more peer,
+    /// which didn't get the initial announcement, before being announced to everyone.
+    pub local_tx_broadcast_resubmit: Option<bool>,
 }
 
 impl From<P2pConfigFile> for P2pConfig {
@@ -109,10 +117,13 @@ impl From<P2pConfigFile> for P2pConfig {
             bind_addresses,
             socks5_proxy,
             disable_noise,
+            multiplexed_transport,
             boot_nodes,
             reserved_nodes,
             whitelisted_addresses,
             max_inbound_connections,
+            max_inbound_connections_per_address_group,
+            max_outbound_connections_per_network,
             discouragement_threshold,
             discouragement_duration,
             max_clock_diff,
@@ -122,12 +133,15 @@ impl From<P2pConfigFile> for P2pConfig {
             sync_stalling_timeout,
             node_type,
             force_dns_query_if_no_global_addresses_known,
+            local_tx_broadcast_privacy,
+            local_tx_broadcast_resubmit,
         } = config_file;
 
         P2pConfig {
             bind_addresses: bind_addresses.unwrap_or_default(),
             socks5_proxy,
             disable_noise,
+            multiplexed_transport,
             boot_nodes: boot_nodes.unwrap_or_default(),
             reserved_nodes: reserved_nodes.unwrap_or_default(),
             whitelisted_addresses: whitelisted_addresses.unwrap_or_default(),
@@ -150,16 +164,20 @@ impl From<P2pConfigFile> for P2pConfig {
                 .into(),
             peer_manager_config: PeerManagerConfig {
                 max_inbound_connections: max_inbound_connections.into(),
+                max_inbound_connections_per_address_group:
+                    max_inbound_connections_per_address_group.into(),
 
                 preserved_inbound_count_address_group: Default::default(),
                 preserved_inbound_count_ping: Default::default(),
                 preserved_inbound_count_new_blocks: Default::default(),
                 preserved_inbound_count_new_transactions: Default::default(),
+                preserved_inbound_count_age: Default::default(),
 
                 outbound_full_relay_count: Default::default(),
                 outbound_full_relay_extra_count: Default::default(),
                 outbound_block_relay_count: Default::default(),
                 outbound_block_relay_extra_count: Default::default(),
+                max_outbound_connections_per_network: max_outbound_connections_per_network.into(),
 
                 outbound_block_relay_connection_min_age: Default::default(),
                 outbound_full_relay_connection_min_age: Default::default(),
@@ -179,6 +197,14 @@ impl From<P2pConfigFile> for P2pConfig {
             },
             protocol_config: Default::default(),
             peer_handshake_timeout: Default::default(),
+            local_tx_broadcast_config: LocalTxBroadcastConfig {
+                privacy_enabled: local_tx_broadcast_privacy.into(),
+                initial_announcement_max_delay: Default::default(),
+                initial_announcement_peer_count: Default::default(),
+                full_announcement_delay: Default::default(),
+                resubmit_enabled: local_tx_broadcast_resubmit.into(),
+                resubmit_delay: Default::default(),
+            },
         }
     }
 }
@@ -15,19 +15,14 @@
 
 //! The node command line options.
 
-use std::{
-    ffi::OsString,
-    net::{IpAddr, SocketAddr},
-    num::NonZeroU64,
-    path::PathBuf,
-};
+use std::{ffi::OsString, net::SocketAddr, num::NonZeroU64, path::PathBuf};
 
 use clap::{Args, Parser, Subcommand};
 use common::chain::config::{regtest_options::ChainConfigOptions, ChainType};
 use utils::{
     clap_utils, default_data_dir::default_data_dir_common, root_user::ForceRunAsRootOptions,
 };
-use utils_networking::IpOrSocketAddress;
+use utils_networking::{IpOrSocketAddress, IpSubnet};
 
 use crate::config_files::{NodeTypeConfigFile, StorageBackendConfigFile};
 
@@ -144,6 +139,13 @@ pub struct RunOptions {
     #[arg(hide = true)]
     pub p2p_disable_noise: Option<bool>,
 
+    /// Use the experimental multiplexed p2p transport, where the block sync messages and
+    /// the transactions are sent over separate lanes of the connection, so that e.g. a large
+    /// block doesn't delay the transaction announcements. It's only used with the peers that
+    /// support it, the others are talked to as usual.
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub p2p_multiplexed_transport: Option<bool>,
+
     /// Optional list of boot node addresses to connect.
     /// Can be specified multiple times and/or be a comma-separated list.
     #[clap(long, value_name = "ADDR", value_delimiter(','))]
@@ -154,15 +156,28 @@ pub struct RunOptions {
     #[clap(long, value_name = "ADDR", value_delimiter(','))]
     pub p2p_reserved_nodes: Option<Vec<IpOrSocketAddress>>,
 
-    /// Optional list of whitelisted addresses.
+    /// Optional list of whitelisted addresses or subnets (e.g. "10.0.0.0/8").
+    /// Whitelisted peers are never banned automatically, are not rate limited and their
+    /// transactions are relayed even if they pay less than the minimum relay fee.
     /// Can be specified multiple times and/or be a comma-separated list.
     #[clap(long, value_name = "ADDR", value_delimiter(','))]
-    pub p2p_whitelist_addr: Option<Vec<IpAddr>>,
+    pub p2p_whitelist_addr: Option<Vec<IpSubnet>>,
 
     /// Maximum allowed number of inbound connections.
     #[clap(long, value_name = "COUNT")]
     pub p2p_max_inbound_connections: Option<usize>,
 
+    /// Maximum allowed number of inbound connections from peers in the same address group
+    /// (an IPv4 /16 or IPv6 /32 subnet); connections from a group that has reached the limit
+    /// are rejected.
+    #[clap(long, value_name = "COUNT")]
+    pub p2p_max_inbound_connections_per_address_group: Option<usize>,
+
+    /// Maximum allowed number of automatic outbound connections to peers in the same network
+    /// (IPv4 or IPv6).
+    #[clap(long, value_name = "COUNT")]
+    pub p2p_max_outbound_connections_per_network: Option<usize>,
+
     /// The p2p score threshold after which a peer is discouraged.
     #[clap(long, value_name = "THRESHOLD")]
     pub p2p_discouragement_threshold: Option<u32>,
@@ -201,6 +216,18 @@ pub struct RunOptions {
     #[arg(hide = true)]
     pub p2p_force_dns_query_if_no_global_addresses_known: Option<bool>,
 
+    /// If true, transactions originating from this node are announced to a few random peers
+    /// after a random delay first, and to the rest of the peers later.
+    /// This makes it harder to find out which node a transaction came from.
+    #[clap(long, value_name = "VAL")]
+    pub p2p_local_tx_broadcast_privacy: Option<bool>,
+
+    /// If true, a local transaction whose broadcast is delayed is also re-submitted
+    /// through one more peer before being announced to everyone.
+    /// Has no effect unless p2p_local_tx_broadcast_privacy is enabled.
+    #[clap(long, value_name = "VAL")]
+    pub p2p_local_tx_broadcast_resubmit: Option<bool>,
+
     /// A maximum tip age in seconds.
     ///
     /// The initial block download is finished if the difference between the current time and the
@@ -235,6 +262,26 @@ pub struct RunOptions {
     #[clap(long, value_name = "VAL")]
     pub min_tx_relay_fee_rate: Option<u64>,
 
+    /// If true, the mempool accepts transactions regardless of the fees they pay.
+    /// Only has effect on regtest.
+    #[clap(long, value_name = "VAL")]
+    pub accept_zero_fee_txs: Option<bool>,
+
+    /// If true, the mempool allows transactions to replace conflicting mempool transactions
+    /// by paying higher fees (replace-by-fee).
+    #[clap(long, value_name = "VAL")]
+    pub enable_rbf: Option<bool>,
+
+    /// Minimum fee rate increment a replacement transaction has to pay over each transaction
+    /// it replaces (in atoms per 1000 bytes).
+    #[clap(long, value_name = "VAL")]
+    pub min_rbf_fee_rate_increment: Option<u64>,
+
+    /// If set, the mempool activity is appended to this file, so that it can be replayed
+    /// through the fee estimators with the `fee-estimator-replay` tool.
+    #[clap(long, value_name = "PATH")]
+    pub mempool_fee_estimator_recording_file: Option<PathBuf>,
+
     #[clap(flatten)]
     pub force_allow_run_as_root_outer: ForceRunAsRootOptions,
 
@@ -242,6 +289,18 @@ pub struct RunOptions {
     /// Defaults to true for regtest and false in other cases.
     #[clap(long, value_name = "VAL")]
     pub enable_chainstate_heavy_checks: Option<bool>,
+
+    /// If true, transactions are neither accepted into the mempool nor relayed until
+    /// the initial block download is finished.
+    /// Defaults to true; can only be disabled on regtest.
+    #[clap(long, value_name = "VAL")]
+    pub defer_txs_during_ibd: Option<bool>,
+
+    /// If true, the transactions of the mainchain blocks are indexed by their ids, so that
+    /// any confirmed transaction can be looked up via RPC.
+    /// Enabling or disabling the index on an existing node rebuilds or removes it on startup.
+    #[clap(long, value_name = "VAL")]
+    pub enable_tx_index: Option<bool>,
 }
 
 impl Options {
@@ -13,7 +13,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{net::SocketAddr, num::NonZeroU64, path::Path, str::FromStr};
+use std::{
+    net::SocketAddr,
+    num::NonZeroU64,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use common::chain::config::create_testnet;
 use tempfile::TempDir;
@@ -103,9 +108,12 @@ fn read_config_override_values() {
     let p2p_bind_addr = "127.0.0.1:44444".parse::<SocketAddr>().unwrap();
     let p2p_socks5_proxy = "socks5_proxy";
     let p2p_disable_noise = false;
+    let p2p_multiplexed_transport = true;
     let p2p_boot_node: IpOrSocketAddress = "127.0.0.1".parse().unwrap();
     let p2p_reserved_node: IpOrSocketAddress = "127.0.0.1".parse().unwrap();
     let p2p_max_inbound_connections = 123;
+    let p2p_max_inbound_connections_per_address_group = 5;
+    let p2p_max_outbound_connections_per_network = 6;
     let p2p_discouragement_threshold = 3;
     let p2p_discouragement_duration = 234;
     let p2p_timeout = NonZeroU64::new(10000).unwrap();
@@ -114,6 +122,8 @@ fn read_config_override_values() {
     let p2p_sync_stalling_timeout = NonZeroU64::new(37).unwrap();
     let p2p_max_clock_diff = 15;
     let p2p_force_dns_query_if_no_global_addresses_known = true;
+    let p2p_local_tx_broadcast_privacy = true;
+    let p2p_local_tx_broadcast_resubmit = true;
     let rpc_bind_address = "127.0.0.1:5432".parse().unwrap();
     let backend_type = StorageBackendConfigFile::InMemory;
     let node_type = NodeTypeConfigFile::FullNode;
@@ -123,6 +133,12 @@ fn read_config_override_values() {
     let rpc_cookie_file = "cookie_file";
     let min_tx_relay_fee_rate = 321;
     let enable_chainstate_heavy_checks = true;
+    let defer_txs_during_ibd = false;
+    let enable_tx_index = true;
+    let accept_zero_fee_txs = true;
+    let enable_rbf = true;
+    let min_rbf_fee_rate_increment = 123;
+    let mempool_fee_estimator_recording_file = PathBuf::from("fee_estimator_recording");
 
     let options = RunOptions {
         blockprod_min_peers_to_produce_blocks: Some(blockprod_min_peers_to_produce_blocks),
@@ -137,9 +153,14 @@ fn read_config_override_values() {
         p2p_bind_addresses: Some(vec![p2p_bind_addr]),
         p2p_socks5_proxy: Some(p2p_socks5_proxy.to_owned()),
         p2p_disable_noise: Some(p2p_disable_noise),
+        p2p_multiplexed_transport: Some(p2p_multiplexed_transport),
         p2p_boot_nodes: Some(vec![p2p_boot_node.clone()]),
         p2p_reserved_nodes: Some(vec![p2p_reserved_node.clone()]),
         p2p_max_inbound_connections: Some(p2p_max_inbound_connections),
+        p2p_max_inbound_connections_per_address_group: Some(
+            p2p_max_inbound_connections_per_address_group,
+        ),
+        p2p_max_outbound_connections_per_network: Some(p2p_max_outbound_connections_per_network),
         p2p_discouragement_threshold: Some(p2p_discouragement_threshold),
         p2p_discouragement_duration: Some(p2p_discouragement_duration),
         p2p_outbound_connection_timeout: Some(p2p_timeout),
@@ -151,6 +172,8 @@ fn read_config_override_values() {
         p2p_force_dns_query_if_no_global_addresses_known: Some(
             p2p_force_dns_query_if_no_global_addresses_known,
         ),
+        p2p_local_tx_broadcast_privacy: Some(p2p_local_tx_broadcast_privacy),
+        p2p_local_tx_broadcast_resubmit: Some(p2p_local_tx_broadcast_resubmit),
         max_tip_age: Some(max_tip_age),
         rpc_bind_address: Some(rpc_bind_address),
         rpc_enabled: Some(true),
@@ -159,8 +182,14 @@ fn read_config_override_values() {
         rpc_cookie_file: Some(rpc_cookie_file.to_owned()),
         clean_data: Some(false),
         min_tx_relay_fee_rate: Some(min_tx_relay_fee_rate),
+        accept_zero_fee_txs: Some(accept_zero_fee_txs),
+        enable_rbf: Some(enable_rbf),
+        min_rbf_fee_rate_increment: Some(min_rbf_fee_rate_increment),
+        mempool_fee_estimator_recording_file: Some(mempool_fee_estimator_recording_file.clone()),
         force_allow_run_as_root_outer: Default::default(),
         enable_chainstate_heavy_checks: Some(enable_chainstate_heavy_checks),
+        defer_txs_during_ibd: Some(defer_txs_during_ibd),
+        enable_tx_index: Some(enable_tx_index),
     };
     let config = NodeConfigFile::read(&chain_config, &config_path, &options).unwrap();
 
@@ -193,14 +222,35 @@ fn read_config_override_values() {
     );
 
     assert_eq!(
-        config.mempool.unwrap().min_tx_relay_fee_rate,
+        config.mempool.clone().unwrap().min_tx_relay_fee_rate,
         Some(min_tx_relay_fee_rate)
     );
+    assert_eq!(
+        config.mempool.clone().unwrap().accept_zero_fee_txs,
+        Some(accept_zero_fee_txs)
+    );
+    assert_eq!(config.mempool.clone().unwrap().enable_rbf, Some(enable_rbf));
+    assert_eq!(
+        config.mempool.clone().unwrap().min_rbf_fee_rate_increment,
+        Some(min_rbf_fee_rate_increment)
+    );
+    assert_eq!(
+        config.mempool.unwrap().fee_estimator_recording_file,
+        Some(mempool_fee_estimator_recording_file)
+    );
 
     assert_eq!(
         config.chainstate.clone().unwrap().chainstate_config.enable_heavy_checks,
         Some(enable_chainstate_heavy_checks)
     );
+    assert_eq!(
+        config.chainstate.clone().unwrap().chainstate_config.defer_txs_during_ibd,
+        Some(defer_txs_during_ibd)
+    );
+    assert_eq!(
+        config.chainstate.clone().unwrap().chainstate_config.enable_tx_index,
+        Some(enable_tx_index)
+    );
 
     assert_eq!(
         config.p2p.clone().unwrap().networking_enabled,
@@ -218,6 +268,10 @@ fn read_config_override_values() {
         config.p2p.clone().unwrap().disable_noise,
         Some(p2p_disable_noise)
     );
+    assert_eq!(
+        config.p2p.clone().unwrap().multiplexed_transport,
+        Some(p2p_multiplexed_transport)
+    );
     assert_eq!(
         config.p2p.clone().unwrap().boot_nodes,
         Some(vec!(p2p_boot_node))
@@ -230,6 +284,14 @@ fn read_config_override_values() {
         config.p2p.clone().unwrap().max_inbound_connections,
         Some(p2p_max_inbound_connections)
     );
+    assert_eq!(
+        config.p2p.clone().unwrap().max_inbound_connections_per_address_group,
+        Some(p2p_max_inbound_connections_per_address_group)
+    );
+    assert_eq!(
+        config.p2p.clone().unwrap().max_outbound_connections_per_network,
+        Some(p2p_max_outbound_connections_per_network)
+    );
     assert_eq!(
         config.p2p.clone().unwrap().discouragement_threshold,
         Some(p2p_discouragement_threshold)
@@ -263,6 +325,14 @@ fn read_config_override_values() {
         config.p2p.clone().unwrap().force_dns_query_if_no_global_addresses_known,
         Some(p2p_force_dns_query_if_no_global_addresses_known)
     );
+    assert_eq!(
+        config.p2p.clone().unwrap().local_tx_broadcast_privacy,
+        Some(p2p_local_tx_broadcast_privacy)
+    );
+    assert_eq!(
+        config.p2p.clone().unwrap().local_tx_broadcast_resubmit,
+        Some(p2p_local_tx_broadcast_resubmit)
+    );
 
     assert_eq!(
         config.rpc.clone().unwrap().bind_address,
@@ -104,6 +104,7 @@ where
             peer_id: _,
             common_services: _,
             protocol_version: _,
+            is_whitelisted: _,
             block_sync_msg_receiver,
             transaction_sync_msg_receiver: _,
         } => block_sync_msg_receiver,
@@ -141,6 +142,7 @@ where
             peer_id: _,
             common_services: _,
             protocol_version: _,
+            is_whitelisted: _,
             block_sync_msg_receiver,
             transaction_sync_msg_receiver: _,
         } => block_sync_msg_receiver,
@@ -179,6 +181,7 @@ where
         bind_addresses: Vec::new(),
         socks5_proxy: None,
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Vec::new(),
         reserved_nodes: Vec::new(),
         whitelisted_addresses: Default::default(),
@@ -193,6 +196,7 @@ where
         sync_stalling_timeout: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
     let shutdown = Arc::new(SeqCstAtomicBool::new(false));
     let (shutdown_sender_1, shutdown_receiver) = oneshot::channel();
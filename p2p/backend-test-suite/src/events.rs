@@ -102,8 +102,9 @@ where
             software_version: _,
         }) => {
             assert_eq!(id, info.peer_id);
+            // The optional services depend on the networking service, e.g. on its framing
             assert_eq!(
-                services,
+                services.required(),
                 [Service::Transactions, Service::Blocks, Service::PeerAddresses].as_ref().into()
             );
         }
@@ -18,13 +18,14 @@ use std::time::Duration;
 
 use common::primitives::user_agent::UserAgent;
 use utils::make_config_setting;
-use utils_networking::IpOrSocketAddress;
+use utils_networking::{IpOrSocketAddress, IpSubnet};
 
 use crate::{
     ban_config::BanConfig,
     net::types::services::{Service, Services},
     peer_manager::config::PeerManagerConfig,
     protocol::ProtocolConfig,
+    sync::local_tx_broadcast::LocalTxBroadcastConfig,
 };
 
 make_config_setting!(OutboundConnectionTimeout, Duration, Duration::from_secs(10));
@@ -54,10 +55,19 @@ pub enum NodeType {
 impl From<NodeType> for Services {
     fn from(t: NodeType) -> Self {
         match t {
-            NodeType::Full => [Service::Blocks, Service::Transactions, Service::PeerAddresses]
-                .as_slice()
-                .into(),
-            NodeType::BlocksOnly => [Service::Blocks, Service::PeerAddresses].as_slice().into(),
+            NodeType::Full => [
+                Service::Blocks,
+                Service::Transactions,
+                Service::PeerAddresses,
+                Service::BlockDataAvailability,
+            ]
+            .as_slice()
+            .into(),
+            NodeType::BlocksOnly => {
+                [Service::Blocks, Service::PeerAddresses, Service::BlockDataAvailability]
+                    .as_slice()
+                    .into()
+            }
             NodeType::DnsServer => [Service::PeerAddresses].as_slice().into(),
             NodeType::Inactive => [].as_slice().into(),
         }
@@ -73,6 +83,10 @@ pub struct P2pConfig {
     pub socks5_proxy: Option<String>,
     /// Disable p2p encryption (for tests only).
     pub disable_noise: Option<bool>,
+    /// Use the experimental multiplexed transport, where the block sync messages and
+    /// the transactions are sent over separate lanes of the connection. It's only used with
+    /// the peers that support it as well.
+    pub multiplexed_transport: Option<bool>,
     /// Optional list of initial node addresses.
     /// Boot node addresses are added to PeerDb as regular discovered addresses.
     pub boot_nodes: Vec<IpOrSocketAddress>,
@@ -80,8 +94,10 @@ pub struct P2pConfig {
     /// PeerManager will try to maintain persistent connections to the reserved nodes.
     /// Ban scores are not adjusted for the reserved nodes.
     pub reserved_nodes: Vec<IpOrSocketAddress>,
-    /// Optional list of whitelisted addresses. Such addresses cannot be automatically banned.
-    pub whitelisted_addresses: Vec<IpAddr>,
+    /// Optional list of whitelisted addresses or subnets. Peers from such addresses cannot be
+    /// automatically banned, are not rate limited, get transactions relayed without a delay
+    /// and their transactions are accepted even if they don't pay the minimum relay fee.
+    pub whitelisted_addresses: Vec<IpSubnet>,
     /// Settings related to banning and discouragement.
     pub ban_config: BanConfig,
     /// The outbound connection timeout value in seconds.
@@ -107,6 +123,8 @@ pub struct P2pConfig {
     pub peer_manager_config: PeerManagerConfig,
     /// Various limits related to the protocol; these should only be overridden in tests.
     pub protocol_config: ProtocolConfig,
+    /// Settings related to the broadcasting of transactions that originate from this node.
+    pub local_tx_broadcast_config: LocalTxBroadcastConfig,
 }
 
 impl P2pConfig {
@@ -117,4 +135,9 @@ impl P2pConfig {
     pub fn effective_max_clock_diff(&self) -> Duration {
         *self.max_clock_diff + *self.peer_handshake_timeout
     }
+
+    /// Whether the address belongs to one of the whitelisted subnets.
+    pub fn is_whitelisted_address(&self, address: &IpAddr) -> bool {
+        self.whitelisted_addresses.iter().any(|subnet| subnet.contains(address))
+    }
 }
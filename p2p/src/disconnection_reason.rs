@@ -46,10 +46,14 @@ pub enum DisconnectionReason {
     PingIgnored,
     #[error("You ignore our sync requests")]
     SyncRequestsIgnored,
+    #[error("You sent us invalid data")]
+    InvalidDataReceived,
     #[error("Too many inbound connections and your address is discouraged")]
     TooManyInboundPeersAndThisOneIsDiscouraged,
     #[error("Too many inbound connections, which can't be evicted")]
     TooManyInboundPeersAndCannotEvictAnyone,
+    #[error("Too many inbound connections from your address group")]
+    TooManyInboundPeersInAddressGroup,
     #[error("Unsupported protocol version, our min version is {}", *MIN_SUPPORTED_PROTOCOL_VERSION as u32)]
     UnsupportedProtocol,
     #[error("Your time {remote_time:?} is out of the acceptable range {accepted_peer_time:?}")]
@@ -114,6 +118,9 @@ impl DisconnectionReason {
                 ConnectionValidationError::TooManyInboundPeersAndCannotEvictAnyone => {
                     Some(Self::TooManyInboundPeersAndCannotEvictAnyone)
                 }
+                ConnectionValidationError::TooManyInboundPeersInAddressGroup => {
+                    Some(Self::TooManyInboundPeersInAddressGroup)
+                }
                 ConnectionValidationError::AddressBanned { address: _ } => {
                     Some(Self::AddressBanned)
                 }
@@ -15,7 +15,10 @@
 
 use thiserror::Error;
 
-use chainstate::{ban_score::BanScore, ChainstateError};
+use chainstate::{
+    ban_score::{BanScore, PeerMisbehavior},
+    ChainstateError,
+};
 use common::{
     chain::{config::MagicBytes, Block, Transaction},
     primitives::{time::Time, Id},
@@ -49,6 +52,8 @@ pub enum ProtocolError {
     UnexpectedMessage(String),
     #[error("Peer sent a block ({0}) that wasn't requested")]
     UnsolicitedBlockReceived(Id<Block>),
+    #[error("Peer reported that it doesn't have a block ({0}) that wasn't requested")]
+    UnsolicitedBlockNotAvailableReceived(Id<Block>),
     #[error("Peer sent block {expected_block_id} while it was expected to send {actual_block_id}")]
     BlocksReceivedInWrongOrder {
         expected_block_id: Id<Block>,
@@ -124,6 +129,8 @@ pub enum ConnectionValidationError {
     TooManyInboundPeersAndThisOneIsDiscouraged,
     #[error("Too many peers")]
     TooManyInboundPeersAndCannotEvictAnyone,
+    #[error("Too many peers from the same address group")]
+    TooManyInboundPeersInAddressGroup,
     #[error("Address {address} is banned")]
     AddressBanned { address: String },
     #[error("Address {address} is discouraged")]
@@ -240,6 +247,29 @@ impl BanScore for P2pError {
             P2pError::SyncError(err) => err.ban_score(),
         }
     }
+
+    fn peer_misbehavior(&self) -> PeerMisbehavior {
+        match self {
+            P2pError::ChainstateError(err) => err.peer_misbehavior(),
+            P2pError::NetworkingError(_)
+            | P2pError::ProtocolError(_)
+            | P2pError::DialError(_)
+            | P2pError::ChannelClosed
+            | P2pError::PeerError(_)
+            | P2pError::SubsystemFailure
+            | P2pError::StorageFailure(_)
+            | P2pError::NoiseHandshakeError(_)
+            | P2pError::InvalidConfigurationValue(_)
+            | P2pError::InvalidStorageState(_)
+            | P2pError::PeerDbStorageVersionMismatch {
+                expected_version: _,
+                actual_version: _,
+            }
+            | P2pError::MempoolError(_)
+            | P2pError::ConnectionValidationFailed(_)
+            | P2pError::SyncError(_) => PeerMisbehavior::from_ban_score(self.ban_score()),
+        }
+    }
 }
 
 impl BanScore for ProtocolError {
@@ -254,6 +284,7 @@ impl BanScore for ProtocolError {
             ProtocolError::DisconnectedHeaders => 20,
             ProtocolError::UnexpectedMessage(_) => 20,
             ProtocolError::UnsolicitedBlockReceived(_) => 20,
+            ProtocolError::UnsolicitedBlockNotAvailableReceived(_) => 20,
             ProtocolError::BlocksReceivedInWrongOrder {
                 expected_block_id: _,
                 actual_block_id: _,
@@ -15,14 +15,23 @@
 
 use std::{sync::Arc, time::Duration};
 
-use common::{chain::SignedTransaction, primitives::time::Time};
+use common::{
+    chain::{SignedTransaction, Transaction},
+    primitives::{time::Time, Id},
+};
 use mempool::tx_options::TxOptionsOverrides;
 use p2p_types::{
     bannable_address::BannableAddress, p2p_event::P2pEvent, socket_address::SocketAddress,
 };
 use utils_networking::IpOrSocketAddress;
 
-use crate::{interface::types::ConnectedPeer, types::peer_id::PeerId};
+use crate::{
+    interface::types::{
+        ConnectedPeer, ListenEndpointInfo, NetworkConnectionsInfo, SyncProgress,
+        TransactionRejection,
+    },
+    types::peer_id::PeerId,
+};
 
 #[async_trait::async_trait]
 pub trait P2pInterface: Send + Sync {
@@ -39,7 +48,14 @@ pub trait P2pInterface: Send + Sync {
 
     async fn get_peer_count(&self) -> crate::Result<usize>;
     async fn get_bind_addresses(&self) -> crate::Result<Vec<SocketAddress>>;
+    async fn get_listen_endpoints(&self) -> crate::Result<Vec<ListenEndpointInfo>>;
+    async fn get_network_connections(&self) -> crate::Result<Vec<NetworkConnectionsInfo>>;
     async fn get_connected_peers(&self) -> crate::Result<Vec<ConnectedPeer>>;
+    async fn get_sync_progress(&self) -> crate::Result<SyncProgress>;
+    async fn get_transaction_rejections(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> crate::Result<Vec<TransactionRejection>>;
 
     async fn get_reserved_nodes(&self) -> crate::Result<Vec<SocketAddress>>;
     async fn add_reserved_node(&mut self, addr: IpOrSocketAddress) -> crate::Result<()>;
@@ -15,7 +15,10 @@
 
 use std::{sync::Arc, time::Duration};
 
-use common::{chain::SignedTransaction, primitives::time::Time};
+use common::{
+    chain::{SignedTransaction, Transaction},
+    primitives::{time::Time, Id},
+};
 use mempool::{
     tx_options::{TxOptions, TxOptionsOverrides},
     tx_origin::LocalTxOrigin,
@@ -26,7 +29,13 @@ use utils_networking::IpOrSocketAddress;
 use crate::{
     disconnection_reason::DisconnectionReason,
     error::P2pError,
-    interface::{p2p_interface::P2pInterface, types::ConnectedPeer},
+    interface::{
+        p2p_interface::P2pInterface,
+        types::{
+            ConnectedPeer, ListenEndpointInfo, NetworkConnectionsInfo, SyncProgress, SyncStage,
+            TransactionRejection,
+        },
+    },
     net::NetworkingService,
     peer_manager_event::PeerDisconnectionDbAction,
     types::peer_id::PeerId,
@@ -120,6 +129,20 @@ where
         Ok(response_receiver.await?)
     }
 
+    async fn get_listen_endpoints(&self) -> crate::Result<Vec<ListenEndpointInfo>> {
+        let (response_sender, response_receiver) = oneshot_nofail::channel();
+        self.peer_mgr_event_sender
+            .send(PeerManagerEvent::GetListenEndpoints(response_sender))?;
+        Ok(response_receiver.await?)
+    }
+
+    async fn get_network_connections(&self) -> crate::Result<Vec<NetworkConnectionsInfo>> {
+        let (response_sender, response_receiver) = oneshot_nofail::channel();
+        self.peer_mgr_event_sender
+            .send(PeerManagerEvent::GetNetworkConnections(response_sender))?;
+        Ok(response_receiver.await?)
+    }
+
     async fn get_connected_peers(&self) -> crate::Result<Vec<ConnectedPeer>> {
         let (response_sender, response_receiver) = oneshot_nofail::channel();
         self.peer_mgr_event_sender
@@ -127,6 +150,53 @@ where
         Ok(response_receiver.await?)
     }
 
+    async fn get_sync_progress(&self) -> crate::Result<SyncProgress> {
+        let chain_info = self.chainstate_handle.call(|c| c.info()).await??;
+        let best_block_height = chain_info.best_block_height;
+        let is_initial_block_download = chain_info.is_initial_block_download;
+        // Only the headers that have been validated by the chainstate are taken into account
+        let best_known_header_height = (chain_info.best_header_height > best_block_height)
+            .then_some(chain_info.best_header_height);
+
+        let stage = match best_known_header_height {
+            Some(header_height) if header_height > best_block_height => {
+                SyncStage::DownloadingBlocks
+            }
+            _ if !is_initial_block_download => SyncStage::Synced,
+            _ if self.get_peer_count().await? == 0 => SyncStage::WaitingForPeers,
+            _ => SyncStage::DownloadingHeaders,
+        };
+
+        let estimated_remaining_time_secs = match (stage, best_known_header_height) {
+            (SyncStage::DownloadingBlocks, Some(header_height)) => {
+                let (start_time, start_height) = self.sync_start;
+                let elapsed = (self.time_getter.get_time() - start_time).unwrap_or_default();
+                SyncProgress::estimate_remaining_time_secs(
+                    start_height,
+                    best_block_height,
+                    header_height,
+                    elapsed.as_secs(),
+                )
+            }
+            _ => None,
+        };
+
+        Ok(SyncProgress {
+            stage,
+            best_block_height,
+            best_known_header_height,
+            downloaded_block_bytes: self.sync_progress.downloaded_block_bytes(),
+            estimated_remaining_time_secs,
+        })
+    }
+
+    async fn get_transaction_rejections(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> crate::Result<Vec<TransactionRejection>> {
+        Ok(self.tx_rejections.get(&tx_id))
+    }
+
     async fn get_reserved_nodes(&self) -> crate::Result<Vec<SocketAddress>> {
         let (response_sender, response_receiver) = oneshot_nofail::channel();
         self.peer_mgr_event_sender
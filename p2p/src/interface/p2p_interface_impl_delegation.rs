@@ -19,14 +19,23 @@ use std::{
     time::Duration,
 };
 
-use common::{chain::SignedTransaction, primitives::time::Time};
+use common::{
+    chain::{SignedTransaction, Transaction},
+    primitives::{time::Time, Id},
+};
 use mempool::tx_options::TxOptionsOverrides;
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress};
 use utils_networking::IpOrSocketAddress;
 
 use crate::{types::peer_id::PeerId, P2pEvent};
 
-use super::{p2p_interface::P2pInterface, types::ConnectedPeer};
+use super::{
+    p2p_interface::P2pInterface,
+    types::{
+        ConnectedPeer, ListenEndpointInfo, NetworkConnectionsInfo, SyncProgress,
+        TransactionRejection,
+    },
+};
 
 #[async_trait::async_trait]
 impl<T: Deref<Target = dyn P2pInterface> + DerefMut<Target = dyn P2pInterface> + Send + Sync>
@@ -68,10 +77,29 @@ impl<T: Deref<Target = dyn P2pInterface> + DerefMut<Target = dyn P2pInterface> +
         self.deref().get_bind_addresses().await
     }
 
+    async fn get_listen_endpoints(&self) -> crate::Result<Vec<ListenEndpointInfo>> {
+        self.deref().get_listen_endpoints().await
+    }
+
+    async fn get_network_connections(&self) -> crate::Result<Vec<NetworkConnectionsInfo>> {
+        self.deref().get_network_connections().await
+    }
+
     async fn get_connected_peers(&self) -> crate::Result<Vec<ConnectedPeer>> {
         self.deref().get_connected_peers().await
     }
 
+    async fn get_sync_progress(&self) -> crate::Result<SyncProgress> {
+        self.deref().get_sync_progress().await
+    }
+
+    async fn get_transaction_rejections(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> crate::Result<Vec<TransactionRejection>> {
+        self.deref().get_transaction_rejections(tx_id).await
+    }
+
     async fn get_reserved_nodes(&self) -> crate::Result<Vec<SocketAddress>> {
         self.deref().get_reserved_nodes().await
     }
@@ -13,10 +13,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use p2p_types::socket_address::SocketAddress;
+use common::primitives::{time::Time, BlockHeight};
+use p2p_types::{peer_address::NetworkType, socket_address::SocketAddress};
 use serde::{Deserialize, Serialize};
 
-use crate::{net::types::PeerRole, types::peer_id::PeerId};
+use crate::{message::TransactionRejectCode, net::types::PeerRole, types::peer_id::PeerId};
 
 /// Helper type used to return information about a connected peer from RPC.
 ///
@@ -43,4 +44,156 @@ pub struct ConnectedPeer {
 
     /// Min time for a ping roundtrip, in milliseconds
     pub ping_min: Option<u64>,
+
+    /// The number of transaction announcements to the peer dropped because its send queue was full
+    pub dropped_tx_announcements: u64,
+
+    /// The number of header announcements to the peer dropped because its send queue was full
+    pub dropped_header_announcements: u64,
+}
+
+/// Helper type used to return information about an address the node listens on from RPC.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, rpc_description::HasValueHint)]
+pub struct ListenEndpointInfo {
+    pub address: SocketAddress,
+
+    /// The transport protocol, e.g. "tcp"
+    pub protocol: String,
+
+    /// The number of inbound connections accepted on this address since the node was started
+    pub inbound_accepted: u64,
+
+    /// The number of inbound connections rejected on this address since the node was started
+    pub inbound_rejected: u64,
+
+    /// The number of currently connected inbound peers
+    pub inbound_active: u64,
+}
+
+/// Helper type used to return the number of connections to a network from RPC.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, rpc_description::HasValueHint)]
+pub struct NetworkConnectionsInfo {
+    pub network: NetworkType,
+
+    /// The number of automatic outbound (full relay and block relay) connections
+    pub outbound_automatic: u64,
+
+    /// The number of reserved and manual outbound connections
+    pub outbound_manual: u64,
+
+    /// The number of inbound connections
+    pub inbound: u64,
+}
+
+/// The current stage of the initial block download.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, rpc_description::HasValueHint,
+)]
+pub enum SyncStage {
+    /// The node is in initial block download, but it's not connected to any peers.
+    WaitingForPeers,
+    /// The node is exchanging headers with its peers to find out which blocks are missing.
+    DownloadingHeaders,
+    /// The node is downloading and verifying the blocks.
+    DownloadingBlocks,
+    /// The node has caught up with the network.
+    Synced,
+}
+
+/// Information about the progress of the initial block download, suitable for showing a progress bar.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, rpc_description::HasValueHint)]
+pub struct SyncProgress {
+    pub stage: SyncStage,
+
+    /// The height of the current tip of the chainstate
+    pub best_block_height: BlockHeight,
+
+    /// The height of the best header validated by the chainstate, if it is ahead of the best block
+    pub best_known_header_height: Option<BlockHeight>,
+
+    /// The total size of the blocks downloaded since the node was started, in bytes
+    pub downloaded_block_bytes: u64,
+
+    /// The estimated time until the best known header is reached, in seconds,
+    /// based on the average block processing speed since the node was started
+    pub estimated_remaining_time_secs: Option<u64>,
+}
+
+impl SyncProgress {
+    /// Estimate the remaining syncing time, assuming that the remaining blocks will be processed
+    /// at the same speed as the ones processed since `start_height`.
+    pub fn estimate_remaining_time_secs(
+        start_height: BlockHeight,
+        best_block_height: BlockHeight,
+        best_known_header_height: BlockHeight,
+        elapsed_secs: u64,
+    ) -> Option<u64> {
+        let processed = best_block_height.into_int().checked_sub(start_height.into_int())?;
+        let remaining =
+            best_known_header_height.into_int().checked_sub(best_block_height.into_int())?;
+        if processed == 0 {
+            return None;
+        }
+        let estimate = (remaining as u128 * elapsed_secs as u128) / processed as u128;
+        Some(estimate.try_into().unwrap_or(u64::MAX))
+    }
+}
+
+/// A rejection of a transaction relayed by this node, as reported by a peer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, rpc_description::HasValueHint)]
+pub struct TransactionRejection {
+    pub peer_id: PeerId,
+
+    pub code: TransactionRejectCode,
+
+    /// The human-readable description of the error, as sent by the peer
+    pub reason: String,
+
+    /// The time when the rejection was received
+    pub time: Time,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(start: u64, best: u64, best_known: u64, elapsed_secs: u64) -> Option<u64> {
+        SyncProgress::estimate_remaining_time_secs(
+            BlockHeight::new(start),
+            BlockHeight::new(best),
+            BlockHeight::new(best_known),
+            elapsed_secs,
+        )
+    }
+
+    #[test]
+    fn estimate_remaining_time_no_progress() {
+        // Nothing has been processed yet, so the speed is unknown
+        assert_eq!(estimate(0, 0, 1000, 0), None);
+        assert_eq!(estimate(100, 100, 1000, 60), None);
+        // The best block is below the starting height, e.g. after a reorg
+        assert_eq!(estimate(100, 50, 1000, 60), None);
+    }
+
+    #[test]
+    fn estimate_remaining_time_finished() {
+        assert_eq!(estimate(0, 1000, 1000, 60), Some(0));
+        // The best known header can lag behind the best block
+        assert_eq!(estimate(0, 1000, 900, 60), None);
+    }
+
+    #[test]
+    fn estimate_remaining_time_typical() {
+        assert_eq!(estimate(0, 100, 1000, 10), Some(90));
+        assert_eq!(estimate(500, 600, 1100, 30), Some(150));
+        // The result is rounded down
+        assert_eq!(estimate(0, 3, 4, 10), Some(3));
+        // Too little time has elapsed to measure the speed
+        assert_eq!(estimate(0, 100, 1000, 0), Some(0));
+    }
+
+    #[test]
+    fn estimate_remaining_time_saturates() {
+        assert_eq!(estimate(0, 1, u64::MAX, u64::MAX), Some(u64::MAX));
+    }
 }
@@ -47,7 +47,7 @@ use ::utils::atomics::SeqCstAtomicBool;
 use ::utils::ensure;
 use common::{
     chain::{config::ChainType, ChainConfig},
-    primitives::BlockHeight,
+    primitives::{time::Time, BlockHeight},
     time_getter::TimeGetter,
 };
 use interface::p2p_interface::P2pInterface;
@@ -64,9 +64,10 @@ use crate::{
     config::P2pConfig,
     error::P2pError,
     net::{
-        default_backend::DefaultNetworkingService, ConnectivityService, MessagingService,
-        NetworkingService, SyncingEventReceiver,
+        default_backend::{DefaultNetworkingService, MultiplexedNetworkingService},
+        ConnectivityService, MessagingService, NetworkingService, SyncingEventReceiver,
     },
+    sync::{sync_progress::SyncProgressCounters, tx_rejections::TxRejections},
 };
 
 pub use p2p_types as types;
@@ -83,6 +84,16 @@ struct P2p<T: NetworkingService> {
     /// A sender for the peer manager events.
     peer_mgr_event_sender: mpsc::UnboundedSender<PeerManagerEvent>,
     mempool_handle: MempoolHandle,
+    chainstate_handle: chainstate::ChainstateHandle,
+
+    /// Block syncing progress, shared with the sync manager.
+    sync_progress: Arc<SyncProgressCounters>,
+    /// Rejections of the relayed transactions reported by peers, shared with the sync manager.
+    tx_rejections: Arc<TxRejections>,
+    /// The time when p2p was started and the height of the tip at that moment;
+    /// used to estimate the remaining syncing time.
+    sync_start: (Time, BlockHeight),
+    time_getter: TimeGetter,
 
     backend_shutdown_sender: oneshot::Sender<()>,
 
@@ -172,16 +183,21 @@ where
             }
         });
 
+        let start_height = chainstate_handle.call(|c| c.get_best_block_height()).await??;
+        let sync_start = (time_getter.get_time(), start_height);
+
         let sync_manager = sync::SyncManager::<T>::new(
             chain_config,
             p2p_config,
             messaging_handle,
             syncing_event_receiver,
-            chainstate_handle,
+            chainstate_handle.clone(),
             mempool_handle.clone(),
             peer_mgr_event_sender.clone(),
-            time_getter,
+            time_getter.clone(),
         );
+        let sync_progress = sync_manager.sync_progress();
+        let tx_rejections = sync_manager.tx_rejections();
         let shutdown_ = Arc::clone(&shutdown);
         let sync_manager_task = logging::spawn_in_current_span(async move {
             match sync_manager.run().await {
@@ -200,6 +216,11 @@ where
         Ok(Self {
             peer_mgr_event_sender,
             mempool_handle,
+            chainstate_handle,
+            sync_progress,
+            tx_rejections,
+            sync_start,
+            time_getter,
             shutdown,
             backend_shutdown_sender,
             backend_task,
@@ -216,6 +237,7 @@ pub type P2pHandle = subsystem::Handle<dyn P2pInterface>;
 pub type P2pNetworkingService = DefaultNetworkingService<NoiseTcpTransport>;
 pub type P2pNetworkingServiceSocks5Proxy = DefaultNetworkingService<NoiseSocks5Transport>;
 pub type P2pNetworkingServiceUnencrypted = DefaultNetworkingService<TcpTransportSocket>;
+pub type P2pNetworkingServiceMultiplexed = MultiplexedNetworkingService<NoiseTcpTransport>;
 
 pub fn make_p2p_transport() -> NoiseTcpTransport {
     let base_transport = TcpTransportSocket::new();
@@ -298,6 +320,10 @@ impl<S: PeerDbStorage + 'static> P2pInit<S> {
             type NetService = P2pNetworkingServiceSocks5Proxy;
             let transport = make_p2p_transport_socks5_proxy(socks5_proxy);
             manager.add_custom_subsystem(name, move |_| self.init::<NetService>(transport))
+        } else if let Some(true) = self.p2p_config.multiplexed_transport {
+            type NetService = P2pNetworkingServiceMultiplexed;
+            let transport = make_p2p_transport();
+            manager.add_custom_subsystem(name, move |_| self.init::<NetService>(transport))
         } else {
             type NetService = P2pNetworkingService;
             let transport = make_p2p_transport();
@@ -381,6 +407,28 @@ pub fn make_p2p<S: PeerDbStorage + 'static>(
         );
     }
 
+    if let Some(true) = p2p_config.multiplexed_transport {
+        ensure!(
+            p2p_config.disable_noise != Some(true) && p2p_config.socks5_proxy.is_none(),
+            P2pError::InvalidConfigurationValue(
+                "The multiplexed transport can't be used without encryption or with a SOCKS5 proxy"
+                    .to_owned()
+            )
+        );
+    }
+
+    let local_tx_broadcast_config = &p2p_config.local_tx_broadcast_config;
+    if *local_tx_broadcast_config.resubmit_enabled {
+        ensure!(
+            *local_tx_broadcast_config.resubmit_delay
+                < *local_tx_broadcast_config.full_announcement_delay,
+            P2pError::InvalidConfigurationValue(
+                "The local tx resubmit delay must be shorter than the full announcement delay"
+                    .to_owned()
+            )
+        );
+    }
+
     Ok(P2pInit {
         networking_enabled,
         chain_config,
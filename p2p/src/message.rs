@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use chainstate::Locator;
+use chainstate::{BlockDataAvailability, Locator};
 use common::{
     chain::{
         block::{signed_block_header::SignedBlockHeader, Block},
@@ -21,9 +21,13 @@ use common::{
     },
     primitives::Id,
 };
+use p2p_types::{
+    peer_address::{PeerAddressIp4, PeerAddressIp6},
+    services::Services,
+};
 use serialization::{Decode, Encode};
 
-use crate::types::peer_address::PeerAddress;
+use crate::{net::default_backend::types::P2pTimestamp, types::peer_address::PeerAddress};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BlockSyncMessage {
@@ -31,6 +35,8 @@ pub enum BlockSyncMessage {
     BlockListRequest(BlockListRequest),
     HeaderList(HeaderList),
     BlockResponse(BlockResponse),
+    BlockDataAvailability(BlockDataAvailability),
+    BlocksNotAvailable(BlocksNotAvailable),
 
     // A "sentinel" message for testing purposes that allows to ensure that all block sync messages
     // that were sent into a channel have been processed by the receiver.
@@ -45,6 +51,7 @@ pub enum TransactionSyncMessage {
     NewTransaction(Id<Transaction>),
     TransactionRequest(Id<Transaction>),
     TransactionResponse(TransactionResponse),
+    TransactionReject(TransactionRejectMessage),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -55,6 +62,8 @@ pub enum PeerManagerMessage {
     AddrListResponse(AddrListResponse),
     PingResponse(PingResponse),
     WillDisconnect(WillDisconnectMessage),
+    AnnounceAddrV2Request(AnnounceAddrV2Request),
+    AddrListResponseV2(AddrListResponseV2),
 }
 
 #[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
@@ -152,6 +161,27 @@ impl BlockResponse {
     }
 }
 
+/// A response to `BlockListRequest`, which lists the requested blocks that the node can't send
+/// because it doesn't have their data.
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct BlocksNotAvailable {
+    block_ids: Vec<Id<Block>>,
+}
+
+impl BlocksNotAvailable {
+    pub fn new(block_ids: Vec<Id<Block>>) -> Self {
+        Self { block_ids }
+    }
+
+    pub fn block_ids(&self) -> &[Id<Block>] {
+        &self.block_ids
+    }
+
+    pub fn into_block_ids(self) -> Vec<Id<Block>> {
+        self.block_ids
+    }
+}
+
 #[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
 pub enum TransactionResponse {
     #[codec(index = 0)]
@@ -160,11 +190,120 @@ pub enum TransactionResponse {
     Found(SignedTransaction),
 }
 
+/// The reason why a relayed transaction has been rejected by the receiving node.
+#[derive(
+    Debug,
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    rpc_description::HasValueHint,
+)]
+pub enum TransactionRejectCode {
+    /// The transaction is not valid, e.g. it has a bad signature or spends a missing output.
+    #[codec(index = 0)]
+    Invalid,
+    /// The transaction doesn't pay enough fees.
+    #[codec(index = 1)]
+    InsufficientFee,
+    /// The transaction conflicts with another one, which cannot be replaced.
+    #[codec(index = 2)]
+    Conflict,
+    /// The mempool (or the orphan pool) is full.
+    #[codec(index = 3)]
+    MempoolFull,
+    /// The transaction is not accepted for some other reason, which is only described
+    /// by the message text.
+    #[codec(index = 4)]
+    Other,
+    /// The transaction cannot be accepted at the moment, e.g. because the node is still in
+    /// the initial block download; it may be accepted if re-sent later.
+    #[codec(index = 5)]
+    TryLater,
+}
+
+/// Tells the peer that the transaction it has sent us has not been accepted into our mempool.
+///
+/// Note: the code is intended for programmatic handling, the reason is a human-readable
+/// description of the error, which is only used for logging and debugging.
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct TransactionRejectMessage {
+    pub tx_id: Id<Transaction>,
+    pub code: TransactionRejectCode,
+    pub reason: String,
+}
+
 #[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
 pub struct AddrListResponse {
     pub addresses: Vec<PeerAddress>,
 }
 
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct AnnounceAddrV2Request {
+    pub entry: AddrGossipEntry,
+}
+
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct AddrListResponseV2 {
+    pub entries: Vec<AddrGossipEntry>,
+}
+
+/// A Tor v3 onion service address.
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct OnionV3Address {
+    /// The ed25519 public key of the service, which the ".onion" hostname is derived from
+    pub public_key: [u8; 32],
+    pub port: u16,
+}
+
+/// A peer address in the v2 address gossip messages.
+///
+/// Unlike `PeerAddress`, this also includes the networks that the node can't connect to
+/// directly, so that such addresses can still be passed around.
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub enum GossipAddress {
+    #[codec(index = 0)]
+    Ip4(PeerAddressIp4),
+    #[codec(index = 1)]
+    Ip6(PeerAddressIp6),
+    #[codec(index = 2)]
+    OnionV3(OnionV3Address),
+}
+
+impl GossipAddress {
+    /// Return the address as a `PeerAddress`, if it's in one of the IP networks
+    pub fn as_peer_address(&self) -> Option<PeerAddress> {
+        match self {
+            GossipAddress::Ip4(address) => Some(PeerAddress::Ip4(address.clone())),
+            GossipAddress::Ip6(address) => Some(PeerAddress::Ip6(address.clone())),
+            GossipAddress::OnionV3(_) => None,
+        }
+    }
+}
+
+impl From<PeerAddress> for GossipAddress {
+    fn from(address: PeerAddress) -> Self {
+        match address {
+            PeerAddress::Ip4(address) => GossipAddress::Ip4(address),
+            PeerAddress::Ip6(address) => GossipAddress::Ip6(address),
+        }
+    }
+}
+
+/// An address along with what is known about the node behind it.
+#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct AddrGossipEntry {
+    pub address: GossipAddress,
+    /// The last time the node was known to be online
+    pub last_seen: P2pTimestamp,
+    /// The services that the node provides, empty if unknown
+    pub services: Services,
+}
+
 #[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
 pub struct PingResponse {
     pub nonce: u64,
@@ -59,6 +59,7 @@ use crate::{
 
 use super::{
     peer::ConnectionInfo,
+    peer_socket::PeerFraming,
     types::{peer_event, HandshakeNonce, Message},
 };
 
@@ -168,6 +169,9 @@ pub struct Backend<T: TransportSocket> {
     /// equal to default_networking_service::PREFERRED_PROTOCOL_VERSION, but it can be
     /// overridden for testing purposes.
     node_protocol_version: ProtocolVersion,
+
+    /// How the messages are framed on the peer connections.
+    peer_framing: PeerFraming,
 }
 
 impl<T> Backend<T>
@@ -189,6 +193,7 @@ where
         shutdown_receiver: oneshot::Receiver<()>,
         subscribers_receiver: mpsc::UnboundedReceiver<P2pEventHandler>,
         node_protocol_version: ProtocolVersion,
+        peer_framing: PeerFraming,
     ) -> Self {
         Self {
             networking_enabled,
@@ -209,6 +214,7 @@ where
             events_controller: EventsController::new(),
             subscribers_receiver,
             node_protocol_version,
+            peer_framing,
         }
     }
 
@@ -272,6 +278,9 @@ where
                 peer_id,
                 common_services: peer.common_services,
                 protocol_version: peer.protocol_version,
+                is_whitelisted: self
+                    .p2p_config
+                    .is_whitelisted_address(&peer.peer_address.ip_addr()),
                 block_sync_msg_receiver,
                 transaction_sync_msg_receiver,
             },
@@ -402,6 +411,7 @@ where
             backend_event_receiver,
             self.node_protocol_version,
             self.time_getter.shallow_clone(),
+            self.peer_framing,
         );
         let shutdown = Arc::clone(&self.shutdown);
         let handle = logging::spawn_in_current_span(async move {
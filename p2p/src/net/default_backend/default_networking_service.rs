@@ -23,7 +23,10 @@ use tokio::{
 
 use common::time_getter::TimeGetter;
 use logging::log;
-use networking::transport::{TransportListener, TransportSocket};
+use networking::{
+    transport::{TransportListener, TransportSocket},
+    types::TransportProtocol,
+};
 use p2p_types::socket_address::SocketAddress;
 use utils::atomics::SeqCstAtomicBool;
 
@@ -34,12 +37,15 @@ use crate::{
     P2pConfig, P2pEventHandler,
 };
 
-use super::{backend::Backend, ConnectivityHandle, MessagingHandle, SyncingEventReceiver};
+use super::{
+    backend::Backend, peer_socket::PeerFraming, ConnectivityHandle, MessagingHandle,
+    SyncingEventReceiver,
+};
 
 // The preferred protocol version.
 // Note that we intentionally keep this constant private, because most of the code should
 // not depend on its value.
-const PREFERRED_PROTOCOL_VERSION: SupportedProtocolVersion = SupportedProtocolVersion::V3;
+const PREFERRED_PROTOCOL_VERSION: SupportedProtocolVersion = SupportedProtocolVersion::V5;
 
 // Some tests do need this value though in order to check the correct version selection.
 // So we make it available for them via a function with a test-specific name and under cfg(test).
@@ -48,6 +54,78 @@ pub const fn get_preferred_protocol_version_for_tests() -> SupportedProtocolVers
     PREFERRED_PROTOCOL_VERSION
 }
 
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn start_backend<S: NetworkingService, T: TransportSocket>(
+    peer_framing: PeerFraming,
+    networking_enabled: bool,
+    transport: T,
+    socket: <T as TransportSocket>::Listener,
+    chain_config: Arc<common::chain::ChainConfig>,
+    p2p_config: Arc<P2pConfig>,
+    time_getter: TimeGetter,
+    shutdown: Arc<SeqCstAtomicBool>,
+    shutdown_receiver: oneshot::Receiver<()>,
+    subscribers_receiver: mpsc::UnboundedReceiver<P2pEventHandler>,
+    protocol_version: ProtocolVersion,
+    tracing_span: tracing::Span,
+) -> crate::Result<(
+    ConnectivityHandle<S>,
+    MessagingHandle,
+    SyncingEventReceiver,
+    JoinHandle<()>,
+)> {
+    let (cmd_sender, cmd_receiver) = mpsc::unbounded_channel();
+    let (conn_event_sender, conn_event_receiver) = mpsc::unbounded_channel();
+    let (syncing_event_sender, syncing_event_receiver) = mpsc::unbounded_channel();
+    let local_addresses = socket
+        .local_addresses()
+        .expect("to have bind address available")
+        .into_iter()
+        .map(SocketAddress::new)
+        .collect();
+
+    let backend = Backend::<T>::new(
+        networking_enabled,
+        transport,
+        socket,
+        chain_config,
+        Arc::clone(&p2p_config),
+        time_getter.clone(),
+        cmd_receiver,
+        conn_event_sender,
+        syncing_event_sender,
+        Arc::clone(&shutdown),
+        shutdown_receiver,
+        subscribers_receiver,
+        protocol_version,
+        peer_framing,
+    );
+    let backend_task = logging::spawn_in_span(
+        async move {
+            match backend.run().await {
+                Ok(never) => match never {},
+                Err(P2pError::ChannelClosed) if shutdown.load() => {
+                    log::info!("Backend is shut down");
+                }
+                Err(e) => {
+                    shutdown.store(true);
+                    log::error!("Failed to run backend: {e}");
+                }
+            }
+        },
+        tracing_span,
+    );
+
+    Ok((
+        ConnectivityHandle::new(local_addresses, cmd_sender.clone(), conn_event_receiver),
+        MessagingHandle::new(cmd_sender),
+        SyncingEventReceiver {
+            syncing_event_receiver,
+        },
+        backend_task,
+    ))
+}
+
 #[derive(Debug)]
 pub struct DefaultNetworkingService<T: TransportSocket>(PhantomData<T>);
 
@@ -71,55 +149,20 @@ impl<T: TransportSocket> DefaultNetworkingService<T> {
         <Self as NetworkingService>::SyncingEventReceiver,
         JoinHandle<()>,
     )> {
-        let (cmd_sender, cmd_receiver) = mpsc::unbounded_channel();
-        let (conn_event_sender, conn_event_receiver) = mpsc::unbounded_channel();
-        let (syncing_event_sender, syncing_event_receiver) = mpsc::unbounded_channel();
-        let local_addresses = socket
-            .local_addresses()
-            .expect("to have bind address available")
-            .into_iter()
-            .map(SocketAddress::new)
-            .collect();
-
-        let backend = Backend::<T>::new(
+        start_backend(
+            PeerFraming::Buffered,
             networking_enabled,
             transport,
             socket,
             chain_config,
-            Arc::clone(&p2p_config),
-            time_getter.clone(),
-            cmd_receiver,
-            conn_event_sender,
-            syncing_event_sender,
-            Arc::clone(&shutdown),
+            p2p_config,
+            time_getter,
+            shutdown,
             shutdown_receiver,
             subscribers_receiver,
             protocol_version,
-        );
-        let backend_task = logging::spawn_in_span(
-            async move {
-                match backend.run().await {
-                    Ok(never) => match never {},
-                    Err(P2pError::ChannelClosed) if shutdown.load() => {
-                        log::info!("Backend is shut down");
-                    }
-                    Err(e) => {
-                        shutdown.store(true);
-                        log::error!("Failed to run backend: {e}");
-                    }
-                }
-            },
             tracing_span,
-        );
-
-        Ok((
-            ConnectivityHandle::new(local_addresses, cmd_sender.clone(), conn_event_receiver),
-            MessagingHandle::new(cmd_sender),
-            SyncingEventReceiver {
-                syncing_event_receiver,
-            },
-            backend_task,
-        ))
+        )
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -165,6 +208,8 @@ impl<T: TransportSocket> NetworkingService for DefaultNetworkingService<T> {
     type MessagingHandle = MessagingHandle;
     type SyncingEventReceiver = SyncingEventReceiver;
 
+    const TRANSPORT_PROTOCOL: TransportProtocol = T::PROTOCOL;
+
     async fn start(
         networking_enabled: bool,
         transport: Self::Transport,
@@ -196,3 +241,57 @@ impl<T: TransportSocket> NetworkingService for DefaultNetworkingService<T> {
         .await
     }
 }
+
+/// An experimental alternative to `DefaultNetworkingService`, which multiplexes the messages
+/// of each peer over several lanes (peer management, headers, blocks and transactions), so that
+/// e.g. a large block being sent doesn't delay the transaction and header announcements.
+///
+/// The handshake is done with the same framing as `DefaultNetworkingService` uses, and
+/// the multiplexing is only enabled for the peers that advertise `Service::MultiplexedFraming`,
+/// so the other peers are still talked to as usual.
+#[derive(Debug)]
+pub struct MultiplexedNetworkingService<T: TransportSocket>(PhantomData<T>);
+
+#[async_trait]
+impl<T: TransportSocket> NetworkingService for MultiplexedNetworkingService<T> {
+    type Transport = T;
+    type ConnectivityHandle = ConnectivityHandle<Self>;
+    type MessagingHandle = MessagingHandle;
+    type SyncingEventReceiver = SyncingEventReceiver;
+
+    const TRANSPORT_PROTOCOL: TransportProtocol = T::PROTOCOL;
+
+    async fn start(
+        networking_enabled: bool,
+        transport: Self::Transport,
+        bind_addresses: Vec<SocketAddress>,
+        chain_config: Arc<common::chain::ChainConfig>,
+        p2p_config: Arc<P2pConfig>,
+        time_getter: TimeGetter,
+        shutdown: Arc<SeqCstAtomicBool>,
+        shutdown_receiver: oneshot::Receiver<()>,
+        subscribers_receiver: mpsc::UnboundedReceiver<P2pEventHandler>,
+    ) -> crate::Result<(
+        Self::ConnectivityHandle,
+        Self::MessagingHandle,
+        Self::SyncingEventReceiver,
+        JoinHandle<()>,
+    )> {
+        let bind_addresses = bind_addresses.iter().map(SocketAddress::socket_addr).collect();
+        let socket = transport.bind(bind_addresses).await?;
+        start_backend(
+            PeerFraming::Multiplexed,
+            networking_enabled,
+            transport,
+            socket,
+            chain_config,
+            p2p_config,
+            time_getter,
+            shutdown,
+            shutdown_receiver,
+            subscribers_receiver,
+            PREFERRED_PROTOCOL_VERSION.into(),
+            tracing::Span::current(),
+        )
+    }
+}
@@ -16,6 +16,7 @@
 pub mod backend;
 mod default_networking_service;
 mod peer;
+mod peer_socket;
 pub mod types;
 
 use std::marker::PhantomData;
@@ -38,7 +39,7 @@ use crate::{
     types::peer_id::PeerId,
 };
 
-pub use default_networking_service::DefaultNetworkingService;
+pub use default_networking_service::{DefaultNetworkingService, MultiplexedNetworkingService};
 
 #[derive(Debug)]
 pub struct ConnectivityHandle<S: NetworkingService> {
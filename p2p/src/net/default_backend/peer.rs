@@ -23,8 +23,11 @@ use tokio::{
 use chainstate::ban_score::BanScore;
 use common::{chain::ChainConfig, primitives::time::Time, time_getter::TimeGetter};
 use logging::log;
-use networking::transport::{BufferedTranscoder, ConnectedSocketInfo, TransportSocket};
-use p2p_types::{services::Services, socket_addr_ext::SocketAddrExt};
+use networking::transport::TransportSocket;
+use p2p_types::{
+    services::{Service, Services},
+    socket_addr_ext::SocketAddrExt,
+};
 
 use crate::{
     config::P2pConfig,
@@ -36,9 +39,12 @@ use crate::{
     types::peer_id::PeerId,
 };
 
-use super::types::{
-    can_send_will_disconnect, peer_event, CategorizedMessage, HandshakeMessage, HandshakeNonce,
-    Message, P2pTimestamp,
+use super::{
+    peer_socket::{PeerFraming, PeerSocket},
+    types::{
+        can_send_will_disconnect, peer_event, CategorizedMessage, HandshakeMessage, HandshakeNonce,
+        Message, P2pTimestamp,
+    },
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -62,7 +68,7 @@ pub struct Peer<T: TransportSocket> {
     connection_info: ConnectionInfo,
 
     /// Peer socket
-    socket: BufferedTranscoder<T::Stream, Message>,
+    socket: PeerSocket<T::Stream>,
 
     /// Channel sender for sending events to Backend
     peer_event_sender: mpsc::Sender<PeerEvent>,
@@ -80,6 +86,9 @@ pub struct Peer<T: TransportSocket> {
 
     /// Time getter
     time_getter: TimeGetter,
+
+    /// The framing to use after the handshake if the peer supports it
+    peer_framing: PeerFraming,
 }
 
 impl<T> Peer<T>
@@ -97,9 +106,9 @@ where
         backend_event_receiver: mpsc::UnboundedReceiver<BackendEvent>,
         node_protocol_version: ProtocolVersion,
         time_getter: TimeGetter,
+        peer_framing: PeerFraming,
     ) -> Self {
-        let socket =
-            BufferedTranscoder::new(socket, Some(*p2p_config.protocol_config.max_message_size));
+        let socket = PeerSocket::new(socket, *p2p_config.protocol_config.max_message_size);
 
         Self {
             peer_id,
@@ -112,6 +121,27 @@ where
             node_protocol_version,
             time_getter,
             common_protocol_version: None,
+            peer_framing,
+        }
+    }
+
+    /// The services that depend on the framing rather than on the node type.
+    fn framing_services(&self) -> Services {
+        match self.peer_framing {
+            PeerFraming::Buffered => Services::from_u64(0),
+            PeerFraming::Multiplexed => [Service::MultiplexedFraming].as_slice().into(),
+        }
+    }
+
+    /// Switch to the multiplexed framing if both sides support it; must be called once
+    /// the last handshake message has been sent or received.
+    fn maybe_switch_framing(&mut self, common_services: Services) {
+        if common_services.has_service(Service::MultiplexedFraming) {
+            log::debug!(
+                "Switching to the multiplexed framing for peer {}",
+                self.peer_id
+            );
+            self.socket.switch_to_multiplexed();
         }
     }
 
@@ -221,7 +251,7 @@ where
 
     async fn handshake(&mut self) -> crate::Result<()> {
         let init_time = self.time_getter.get_time();
-        let peer_address = self.socket.inner_stream().remote_address()?;
+        let peer_address = self.socket.remote_address()?;
 
         // Sending the remote socket address makes no sense and can leak private information when using a proxy
         let peer_address_to_send = if self.p2p_config.socks5_proxy.is_some() {
@@ -251,7 +281,8 @@ where
                     .common_protocol_version
                     .expect("common_protocol_version must be set by validate_handshake");
 
-                let local_services: Services = (*self.p2p_config.node_type).into();
+                let local_services: Services =
+                    Services::from(*self.p2p_config.node_type) | self.framing_services();
                 let common_services = local_services & remote_services;
 
                 // Note: we send `PeerInfoReceived` to `Backend` before sending `HelloAck`
@@ -293,18 +324,21 @@ where
                         network: *self.chain_config.magic_bytes(),
                         user_agent: self.p2p_config.user_agent.clone(),
                         software_version: *self.chain_config.software_version(),
-                        services: (*self.p2p_config.node_type).into(),
+                        services: local_services,
                         receiver_address: peer_address_to_send,
                         current_time: P2pTimestamp::from_time(self.time_getter.get_time()),
                     }))
                     .await?;
+
+                self.maybe_switch_framing(common_services);
             }
             ConnectionInfo::Outbound {
                 handshake_nonce,
                 local_services_override,
             } => {
-                let local_services =
-                    local_services_override.unwrap_or_else(|| (*self.p2p_config.node_type).into());
+                let local_services = local_services_override
+                    .unwrap_or_else(|| (*self.p2p_config.node_type).into())
+                    | self.framing_services();
 
                 self.socket
                     .send(Message::Handshake(HandshakeMessage::Hello {
@@ -361,6 +395,8 @@ where
                         handshake_nonce,
                     }))
                     .await?;
+
+                self.maybe_switch_framing(common_services);
             }
         }
 
@@ -520,7 +556,10 @@ mod tests {
         get_two_connected_sockets, TestTransportChannel, TestTransportMaker, TestTransportNoise,
         TestTransportTcp,
     };
-    use networking::transport::{MpscChannelTransport, NoiseTcpTransport, TcpTransportSocket};
+    use networking::transport::{
+        BufferedTranscoder, MpscChannelTransport, MultiplexedTranscoder, NoiseTcpTransport,
+        TcpTransportSocket,
+    };
     use test_utils::{
         assert_matches,
         mock_time_getter::{mocked_time_getter_milliseconds, mocked_time_getter_seconds},
@@ -529,7 +568,8 @@ mod tests {
 
     use super::*;
     use crate::{
-        message::HeaderListRequest,
+        message::{HeaderListRequest, PingRequest},
+        net::default_backend::peer_socket::MessageLane,
         net::types::services::Service,
         test_helpers::{test_p2p_config, TEST_PROTOCOL_VERSION},
     };
@@ -597,6 +637,7 @@ mod tests {
             backend_event_receiver,
             TEST_PROTOCOL_VERSION.into(),
             time_getter,
+            PeerFraming::Buffered,
         );
 
         let handle = logging::spawn_in_current_span(async move {
@@ -656,6 +697,89 @@ mod tests {
         handshake_inbound::<TestTransportNoise, NoiseTcpTransport>().await;
     }
 
+    // A peer using the multiplexed framing only switches to it if the other side supports it too;
+    // the handshake itself is done with the buffered framing in both cases.
+    #[rstest::rstest]
+    #[tokio::test]
+    async fn handshake_inbound_multiplexed(#[values(false, true)] remote_multiplexes: bool) {
+        let (socket1, socket2) =
+            get_two_connected_sockets::<TestTransportTcp, TcpTransportSocket>().await;
+        let chain_config = Arc::new(common::chain::config::create_unit_test_config());
+        let p2p_config = Arc::new(test_p2p_config());
+        let (peer_event_sender, mut peer_event_receiver) = mpsc::channel(TEST_CHAN_BUF_SIZE);
+        let (_backend_event_sender, backend_event_receiver) = mpsc::unbounded_channel();
+        let time_getter = mocked_time_getter_seconds(Arc::new(SeqCstAtomicU64::new(123456)));
+
+        let mut peer = Peer::<TcpTransportSocket>::new(
+            PeerId::new(),
+            ConnectionInfo::Inbound,
+            Arc::clone(&chain_config),
+            Arc::clone(&p2p_config),
+            socket1,
+            peer_event_sender,
+            backend_event_receiver,
+            TEST_PROTOCOL_VERSION.into(),
+            time_getter,
+            PeerFraming::Multiplexed,
+        );
+
+        let handle = logging::spawn_in_current_span(async move {
+            peer.handshake().await.unwrap();
+            peer.socket.send(Message::PingRequest(PingRequest { nonce: 1 })).await.unwrap();
+            peer
+        });
+
+        let max_message_size = *p2p_config.protocol_config.max_message_size;
+        let mut socket2 = BufferedTranscoder::new(socket2, Some(max_message_size));
+        let remote_services: Services = if remote_multiplexes {
+            [Service::Blocks, Service::MultiplexedFraming].as_slice().into()
+        } else {
+            [Service::Blocks].as_slice().into()
+        };
+        socket2
+            .send(Message::Handshake(HandshakeMessage::Hello {
+                protocol_version: TEST_PROTOCOL_VERSION.into(),
+                software_version: *chain_config.software_version(),
+                network: *chain_config.magic_bytes(),
+                user_agent: p2p_config.user_agent.clone(),
+                services: remote_services,
+                receiver_address: None,
+                current_time: P2pTimestamp::from_int_seconds(123456),
+                handshake_nonce: 123,
+            }))
+            .await
+            .unwrap();
+
+        let peer_event = peer_event_receiver.recv().await.unwrap();
+        let PeerEvent::PeerInfoReceived(info) = peer_event else {
+            panic!("Unexpected peer event: {peer_event:?}")
+        };
+        assert_eq!(info.common_services, remote_services);
+        expect_sync_event(&mut peer_event_receiver).await;
+
+        let hello_ack = socket2.recv().await.unwrap();
+        let Message::Handshake(HandshakeMessage::HelloAck { services, .. }) = hello_ack else {
+            panic!("Unexpected message: {hello_ack:?}")
+        };
+        assert!(services.has_service(Service::MultiplexedFraming));
+
+        let ping = if remote_multiplexes {
+            let (socket2, received_data) = socket2.into_parts();
+            let mut socket2 = MultiplexedTranscoder::<_, Message>::with_received_data(
+                socket2,
+                MessageLane::COUNT,
+                Some(max_message_size),
+                received_data,
+            );
+            socket2.recv().await.unwrap()
+        } else {
+            socket2.recv().await.unwrap()
+        };
+        assert_eq!(ping, Message::PingRequest(PingRequest { nonce: 1 }));
+
+        let _peer = handle.await.unwrap();
+    }
+
     async fn handshake_outbound<A, T>()
     where
         A: TestTransportMaker<Transport = T>,
@@ -683,6 +807,7 @@ mod tests {
             backend_event_receiver,
             TEST_PROTOCOL_VERSION.into(),
             time_getter,
+            PeerFraming::Buffered,
         );
 
         let handle = logging::spawn_in_current_span(async move {
@@ -764,6 +889,7 @@ mod tests {
             backend_event_receiver,
             TEST_PROTOCOL_VERSION.into(),
             time_getter,
+            PeerFraming::Buffered,
         );
 
         let handle = logging::spawn_in_current_span(async move { peer.handshake().await });
@@ -832,6 +958,7 @@ mod tests {
             backend_event_receiver,
             TEST_PROTOCOL_VERSION.into(),
             time_getter,
+            PeerFraming::Buffered,
         );
 
         let handle = logging::spawn_in_current_span(async move { peer.handshake().await });
@@ -953,6 +1080,7 @@ mod tests {
             rx2,
             TEST_PROTOCOL_VERSION.into(),
             peer_time_getter,
+            PeerFraming::Buffered,
         );
 
         let handle = logging::spawn_in_current_span(async move { peer.run_handshake().await });
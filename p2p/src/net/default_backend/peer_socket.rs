@@ -0,0 +1,166 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+
+use networking::transport::{
+    BufferedTranscoder, ConnectedSocketInfo, MultiplexedTranscoder, PeerStream,
+};
+
+use super::types::Message;
+
+/// How the messages are framed on a peer connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerFraming {
+    /// The messages are sent one after another, in the order they were sent.
+    Buffered,
+    /// The messages are sent over separate lanes, see `MessageLane`.
+    ///
+    /// The handshake is always done with the buffered framing; the connection switches to
+    /// multiplexing afterwards if both sides have `Service::MultiplexedFraming`.
+    Multiplexed,
+}
+
+/// The lanes of a multiplexed connection. The messages of different lanes may arrive
+/// in a different order than they were sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum MessageLane {
+    /// Handshake and peer manager messages
+    Control,
+    /// Header and block requests, announcements and responses.
+    ///
+    /// These share a lane because their order matters: e.g. a header list announcing a new tip
+    /// must not overtake the block responses sent before it, otherwise the headers wouldn't
+    /// connect to what the peer has received.
+    BlockSync,
+    /// Transaction announcements and responses
+    Transactions,
+}
+
+impl MessageLane {
+    pub(super) const COUNT: u8 = 3;
+
+    fn of(message: &Message) -> Self {
+        match message {
+            Message::Handshake(_)
+            | Message::PingRequest(_)
+            | Message::PingResponse(_)
+            | Message::AnnounceAddrRequest(_)
+            | Message::AddrListRequest(_)
+            | Message::AddrListResponse(_)
+            | Message::WillDisconnect(_)
+            | Message::AnnounceAddrV2Request(_)
+            | Message::AddrListResponseV2(_) => Self::Control,
+
+            Message::HeaderListRequest(_)
+            | Message::HeaderList(_)
+            | Message::BlockListRequest(_)
+            | Message::BlockResponse(_)
+            | Message::BlockDataAvailability(_)
+            | Message::BlocksNotAvailable(_) => Self::BlockSync,
+            #[cfg(test)]
+            Message::TestBlockSyncMsgSentinel(_) => Self::BlockSync,
+
+            Message::NewTransaction(_)
+            | Message::TransactionRequest(_)
+            | Message::TransactionResponse(_)
+            | Message::TransactionReject(_) => Self::Transactions,
+        }
+    }
+
+    fn index(self) -> u8 {
+        match self {
+            Self::Control => 0,
+            Self::BlockSync => 1,
+            Self::Transactions => 2,
+        }
+    }
+}
+
+enum Transcoder<S> {
+    Buffered(BufferedTranscoder<S, Message>),
+    Multiplexed(MultiplexedTranscoder<S, Message>),
+}
+
+pub struct PeerSocket<S> {
+    /// Only `None` while switching the framing, which can't be interrupted.
+    transcoder: Option<Transcoder<S>>,
+    max_message_size: usize,
+}
+
+impl<S> PeerSocket<S>
+where
+    S: PeerStream + ConnectedSocketInfo + 'static,
+{
+    /// Create a socket using the buffered framing, see `switch_to_multiplexed`.
+    pub fn new(stream: S, max_message_size: usize) -> Self {
+        Self {
+            transcoder: Some(Transcoder::Buffered(BufferedTranscoder::new(
+                stream,
+                Some(max_message_size),
+            ))),
+            max_message_size,
+        }
+    }
+
+    fn transcoder(&mut self) -> &mut Transcoder<S> {
+        self.transcoder.as_mut().expect("the framing switch must be complete")
+    }
+
+    /// Continue with the multiplexed framing, e.g. once the handshake is done and both sides
+    /// have agreed on it. The data already received is kept.
+    pub fn switch_to_multiplexed(&mut self) {
+        let transcoder = match self.transcoder.take().expect("the framing switch must be complete")
+        {
+            Transcoder::Buffered(transcoder) => {
+                let (stream, received_data) = transcoder.into_parts();
+                MultiplexedTranscoder::with_received_data(
+                    stream,
+                    MessageLane::COUNT,
+                    Some(self.max_message_size),
+                    received_data,
+                )
+            }
+            Transcoder::Multiplexed(transcoder) => transcoder,
+        };
+        self.transcoder = Some(Transcoder::Multiplexed(transcoder));
+    }
+
+    pub async fn send(&mut self, message: Message) -> crate::Result<()> {
+        match self.transcoder() {
+            Transcoder::Buffered(transcoder) => transcoder.send(message).await?,
+            Transcoder::Multiplexed(transcoder) => {
+                transcoder.send(MessageLane::of(&message).index(), message).await?
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn recv(&mut self) -> crate::Result<Message> {
+        let message = match self.transcoder() {
+            Transcoder::Buffered(transcoder) => transcoder.recv().await?,
+            Transcoder::Multiplexed(transcoder) => transcoder.recv().await?,
+        };
+        Ok(message)
+    }
+
+    pub fn remote_address(&self) -> crate::Result<SocketAddr> {
+        let address = match self.transcoder.as_ref().expect("the framing switch must be complete") {
+            Transcoder::Buffered(transcoder) => transcoder.inner_stream().remote_address()?,
+            Transcoder::Multiplexed(transcoder) => transcoder.remote_address()?,
+        };
+        Ok(address)
+    }
+}
@@ -17,6 +17,7 @@ use std::time::Duration;
 
 use tokio::sync::{mpsc::Sender, oneshot};
 
+use chainstate::BlockDataAvailability;
 use common::{
     chain::{config::MagicBytes, Transaction},
     primitives::{semver::SemVer, time::Time, user_agent::UserAgent, Id},
@@ -28,12 +29,14 @@ use crate::{
     disconnection_reason::DisconnectionReason,
     error::P2pError,
     message::{
-        AddrListRequest, AddrListResponse, AnnounceAddrRequest, BlockListRequest, BlockResponse,
-        BlockSyncMessage, HeaderList, HeaderListRequest, PeerManagerMessage, PingRequest,
-        PingResponse, TransactionResponse, TransactionSyncMessage, WillDisconnectMessage,
+        AddrListRequest, AddrListResponse, AddrListResponseV2, AnnounceAddrRequest,
+        AnnounceAddrV2Request, BlockListRequest, BlockResponse, BlockSyncMessage,
+        BlocksNotAvailable, HeaderList, HeaderListRequest, PeerManagerMessage, PingRequest,
+        PingResponse, TransactionRejectMessage, TransactionResponse, TransactionSyncMessage,
+        WillDisconnectMessage,
     },
     net::types::services::Services,
-    protocol::{ProtocolVersion, SupportedProtocolVersion},
+    protocol::{ProtocolFeature, ProtocolVersion, SupportedProtocolVersion},
     types::{peer_address::PeerAddress, peer_id::PeerId},
 };
 
@@ -83,6 +86,10 @@ impl P2pTimestamp {
     pub fn from_time(time: Time) -> Self {
         Self::from_duration_since_epoch(time.as_duration_since_epoch())
     }
+
+    pub fn as_time(&self) -> Time {
+        Time::from_duration_since_epoch(self.as_duration_since_epoch())
+    }
 }
 
 pub mod peer_event {
@@ -218,6 +225,23 @@ pub enum Message {
     #[codec(index = 13)]
     WillDisconnect(WillDisconnectMessage),
 
+    /// Indicates that a transaction sent by the peer has been rejected. Available since protocol V4.
+    #[codec(index = 14)]
+    TransactionReject(TransactionRejectMessage),
+
+    /// Address gossip with the last-seen times and services. Available since protocol V5.
+    #[codec(index = 15)]
+    AnnounceAddrV2Request(AnnounceAddrV2Request),
+    #[codec(index = 16)]
+    AddrListResponseV2(AddrListResponseV2),
+
+    /// The range of blocks that the peer can serve and the response for the requested blocks
+    /// that it doesn't have. Only sent if both sides have `Service::BlockDataAvailability`.
+    #[codec(index = 17)]
+    BlockDataAvailability(BlockDataAvailability),
+    #[codec(index = 18)]
+    BlocksNotAvailable(BlocksNotAvailable),
+
     // A message that corresponds to BlockSyncMessage::TestSentinel.
     #[cfg(test)]
     #[codec(index = 255)]
@@ -233,6 +257,8 @@ impl From<PeerManagerMessage> for Message {
             PeerManagerMessage::AddrListResponse(r) => Message::AddrListResponse(r),
             PeerManagerMessage::PingResponse(r) => Message::PingResponse(r),
             PeerManagerMessage::WillDisconnect(r) => Message::WillDisconnect(r),
+            PeerManagerMessage::AnnounceAddrV2Request(r) => Message::AnnounceAddrV2Request(r),
+            PeerManagerMessage::AddrListResponseV2(r) => Message::AddrListResponseV2(r),
         }
     }
 }
@@ -244,6 +270,8 @@ impl From<BlockSyncMessage> for Message {
             BlockSyncMessage::BlockListRequest(r) => Message::BlockListRequest(r),
             BlockSyncMessage::HeaderList(r) => Message::HeaderList(r),
             BlockSyncMessage::BlockResponse(r) => Message::BlockResponse(r),
+            BlockSyncMessage::BlockDataAvailability(r) => Message::BlockDataAvailability(r),
+            BlockSyncMessage::BlocksNotAvailable(r) => Message::BlocksNotAvailable(r),
             #[cfg(test)]
             BlockSyncMessage::TestSentinel(id) => Message::TestBlockSyncMsgSentinel(id),
         }
@@ -256,6 +284,7 @@ impl From<TransactionSyncMessage> for Message {
             TransactionSyncMessage::NewTransaction(id) => Message::NewTransaction(id),
             TransactionSyncMessage::TransactionRequest(id) => Message::TransactionRequest(id),
             TransactionSyncMessage::TransactionResponse(tx) => Message::TransactionResponse(tx),
+            TransactionSyncMessage::TransactionReject(r) => Message::TransactionReject(r),
         }
     }
 }
@@ -293,6 +322,12 @@ impl Message {
             Message::WillDisconnect(msg) => {
                 CategorizedMessage::PeerManagerMessage(PeerManagerMessage::WillDisconnect(msg))
             }
+            Message::AnnounceAddrV2Request(msg) => CategorizedMessage::PeerManagerMessage(
+                PeerManagerMessage::AnnounceAddrV2Request(msg),
+            ),
+            Message::AddrListResponseV2(msg) => {
+                CategorizedMessage::PeerManagerMessage(PeerManagerMessage::AddrListResponseV2(msg))
+            }
 
             Message::HeaderListRequest(msg) => {
                 CategorizedMessage::BlockSyncMessage(BlockSyncMessage::HeaderListRequest(msg))
@@ -306,6 +341,12 @@ impl Message {
             Message::BlockResponse(msg) => {
                 CategorizedMessage::BlockSyncMessage(BlockSyncMessage::BlockResponse(msg))
             }
+            Message::BlockDataAvailability(msg) => {
+                CategorizedMessage::BlockSyncMessage(BlockSyncMessage::BlockDataAvailability(msg))
+            }
+            Message::BlocksNotAvailable(msg) => {
+                CategorizedMessage::BlockSyncMessage(BlockSyncMessage::BlocksNotAvailable(msg))
+            }
             #[cfg(test)]
             Message::TestBlockSyncMsgSentinel(id) => {
                 CategorizedMessage::BlockSyncMessage(BlockSyncMessage::TestSentinel(id))
@@ -320,6 +361,9 @@ impl Message {
             Message::TransactionResponse(msg) => CategorizedMessage::TransactionSyncMessage(
                 TransactionSyncMessage::TransactionResponse(msg),
             ),
+            Message::TransactionReject(msg) => CategorizedMessage::TransactionSyncMessage(
+                TransactionSyncMessage::TransactionReject(msg),
+            ),
         }
     }
 }
@@ -327,7 +371,7 @@ impl Message {
 /// Return true if the WillDisconnect message can be sent to a peer with the specified
 /// protocol version.
 pub fn can_send_will_disconnect(peer_protocol_version: ProtocolVersion) -> bool {
-    peer_protocol_version >= SupportedProtocolVersion::V3.into()
+    peer_protocol_version.supports(ProtocolFeature::WillDisconnectMessage)
 }
 
 #[cfg(test)]
@@ -338,7 +382,7 @@ mod tests {
     use chainstate_test_framework::TestFramework;
     use common::{
         chain::config::MagicBytes,
-        primitives::{semver::SemVer, Id},
+        primitives::{semver::SemVer, BlockHeight, Id},
     };
     use networking::test_helpers::{get_two_connected_sockets, TestTransportChannel};
     use networking::transport::{BufferedTranscoder, MpscChannelTransport};
@@ -348,9 +392,10 @@ mod tests {
 
     use crate::{
         message::{
-            AddrListRequest, AddrListResponse, AnnounceAddrRequest, BlockListRequest,
-            BlockResponse, HeaderList, HeaderListRequest, PingRequest, PingResponse,
-            TransactionResponse,
+            AddrGossipEntry, AddrListRequest, AddrListResponse, AddrListResponseV2,
+            AnnounceAddrRequest, AnnounceAddrV2Request, BlockListRequest, BlockResponse,
+            GossipAddress, HeaderList, HeaderListRequest, OnionV3Address, PingRequest,
+            PingResponse, TransactionRejectCode, TransactionResponse,
         },
         net::default_backend::types::{HandshakeMessage, P2pTimestamp},
         protocol::ProtocolVersion,
@@ -428,11 +473,24 @@ mod tests {
                 Id::new(rng.gen()),
             ])),
             Message::BlockResponse(BlockResponse::new(block.clone())),
+            Message::BlockDataAvailability(BlockDataAvailability::new(
+                BlockHeight::new(rng.gen()),
+                BlockHeight::new(rng.gen()),
+            )),
+            Message::BlocksNotAvailable(BlocksNotAvailable::new(vec![
+                Id::new(rng.gen()),
+                Id::new(rng.gen()),
+            ])),
             Message::TransactionRequest(Id::new(rng.gen())),
             Message::TransactionResponse(TransactionResponse::NotFound(Id::new(rng.gen()))),
             Message::TransactionResponse(TransactionResponse::Found(
                 block.transactions()[0].clone(),
             )),
+            Message::TransactionReject(TransactionRejectMessage {
+                tx_id: Id::new(rng.gen()),
+                code: TransactionRejectCode::InsufficientFee,
+                reason: "fee too low".to_owned(),
+            }),
             Message::AnnounceAddrRequest(AnnounceAddrRequest {
                 address: SocketAddr::new(
                     IpAddr::V4(Ipv4Addr::new(rng.gen(), rng.gen(), rng.gen(), rng.gen())),
@@ -440,7 +498,28 @@ mod tests {
                 )
                 .into(),
             }),
+            Message::AnnounceAddrV2Request(AnnounceAddrV2Request {
+                entry: AddrGossipEntry {
+                    address: GossipAddress::OnionV3(OnionV3Address {
+                        public_key: rng.gen(),
+                        port: rng.gen(),
+                    }),
+                    last_seen: P2pTimestamp::from_int_seconds(rng.gen()),
+                    services: [Service::Blocks].as_slice().into(),
+                },
+            }),
             Message::AddrListRequest(AddrListRequest {}),
+            Message::AddrListResponseV2(AddrListResponseV2 {
+                entries: vec![AddrGossipEntry {
+                    address: PeerAddress::from(SocketAddr::new(
+                        IpAddr::V6(rng.gen::<[u8; 16]>().into()),
+                        rng.gen(),
+                    ))
+                    .into(),
+                    last_seen: P2pTimestamp::from_int_seconds(rng.gen()),
+                    services: Services::from_u64(0),
+                }],
+            }),
             Message::AddrListResponse(AddrListResponse {
                 addresses: vec![SocketAddr::new(
                     IpAddr::V4(Ipv4Addr::new(rng.gen(), rng.gen(), rng.gen(), rng.gen())),
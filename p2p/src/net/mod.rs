@@ -23,6 +23,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use common::time_getter::TimeGetter;
+use networking::types::TransportProtocol;
 use p2p_types::{services::Services, socket_address::SocketAddress};
 use tokio::{
     sync::{mpsc, oneshot},
@@ -57,6 +58,9 @@ pub trait NetworkingService {
     /// A receiver for syncing events.
     type SyncingEventReceiver: Send;
 
+    /// The protocol that the peers use to connect to the local addresses
+    const TRANSPORT_PROTOCOL: TransportProtocol;
+
     /// Initializes the network service provider.
     #[allow(clippy::too_many_arguments)]
     async fn start(
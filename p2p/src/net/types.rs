@@ -232,6 +232,8 @@ pub enum SyncingEvent {
         peer_id: PeerId,
         common_services: Services,
         protocol_version: SupportedProtocolVersion,
+        /// Whether the peer's address is whitelisted in the config
+        is_whitelisted: bool,
         block_sync_msg_receiver: Receiver<BlockSyncMessage>,
         transaction_sync_msg_receiver: Receiver<TransactionSyncMessage>,
     },
@@ -21,16 +21,19 @@ use super::{
     peerdb::config::PeerDbConfig,
     peers_eviction::{
         OutboundBlockRelayConnectionMinAge, OutboundFullRelayConnectionMinAge,
-        PreservedInboundCountAddressGroup, PreservedInboundCountNewBlocks,
-        PreservedInboundCountNewTransactions, PreservedInboundCountPing,
+        PreservedInboundCountAddressGroup, PreservedInboundCountAge,
+        PreservedInboundCountNewBlocks, PreservedInboundCountNewTransactions,
+        PreservedInboundCountPing,
     },
 };
 
 make_config_setting!(MaxInboundConnections, usize, 128);
+make_config_setting!(MaxInboundConnectionsPerAddressGroup, usize, usize::MAX);
 make_config_setting!(OutboundFullRelayCount, usize, 8);
 make_config_setting!(OutboundFullRelayExtraCount, usize, 1);
 make_config_setting!(OutboundBlockRelayCount, usize, 2);
 make_config_setting!(OutboundBlockRelayExtraCount, usize, 1);
+make_config_setting!(MaxOutboundConnectionsPerNetwork, usize, usize::MAX);
 make_config_setting!(StaleTipTimeDiff, Duration, Duration::from_secs(30 * 60));
 make_config_setting!(MainLoopTickInterval, Duration, Duration::from_secs(1));
 make_config_setting!(
@@ -53,6 +56,10 @@ make_config_setting!(AllowSameIpConnections, bool, false);
 pub struct PeerManagerConfig {
     /// Maximum allowed number of inbound connections.
     pub max_inbound_connections: MaxInboundConnections,
+    /// The maximum number of inbound connections from peers in the same address group
+    /// (e.g. an IPv4 /16 subnet). Not limited by default.
+    /// New inbound connections from a group that has reached the limit are rejected.
+    pub max_inbound_connections_per_address_group: MaxInboundConnectionsPerAddressGroup,
 
     /// The number of inbound peers to preserve based on the address group.
     pub preserved_inbound_count_address_group: PreservedInboundCountAddressGroup,
@@ -62,6 +69,8 @@ pub struct PeerManagerConfig {
     pub preserved_inbound_count_new_blocks: PreservedInboundCountNewBlocks,
     /// The number of inbound peers to preserve based on the last time they sent us new transactions.
     pub preserved_inbound_count_new_transactions: PreservedInboundCountNewTransactions,
+    /// The number of inbound peers to preserve based on how long they have been connected.
+    pub preserved_inbound_count_age: PreservedInboundCountAge,
 
     /// The desired maximum number of full relay outbound connections.
     /// Note that this limit may be exceeded temporarily by up to outbound_full_relay_extra_count
@@ -78,6 +87,13 @@ pub struct PeerManagerConfig {
     /// The number of extra block relay connections that we will establish and evict regularly.
     pub outbound_block_relay_extra_count: OutboundBlockRelayExtraCount,
 
+    /// The maximum number of automatic (full relay and block relay) outbound connections
+    /// to peers in the same network, e.g. IPv4 or IPv6. Not limited by default.
+    /// Note that the node also tries to keep at least one full relay connection to each network
+    /// in which it knows some addresses; such a connection may be established in addition
+    /// to outbound_full_relay_count, in which case another full relay peer will be evicted.
+    pub max_outbound_connections_per_network: MaxOutboundConnectionsPerNetwork,
+
     /// Outbound block relay connections younger than this age will not be taken into account
     /// during eviction.
     /// Note that extra block relay connections are established and evicted on a regular basis
@@ -133,6 +149,7 @@ impl PeerManagerConfig {
             + *self.preserved_inbound_count_ping
             + *self.preserved_inbound_count_new_blocks
             + *self.preserved_inbound_count_new_transactions
+            + *self.preserved_inbound_count_age
     }
 
     /// The desired maximum number of automatic outbound connections.
@@ -0,0 +1,169 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use networking::types::TransportProtocol;
+use p2p_types::socket_address::SocketAddress;
+
+use crate::interface::types::ListenEndpointInfo;
+
+#[derive(Debug, Default, Clone, Copy)]
+struct InboundStats {
+    accepted: u64,
+    rejected: u64,
+}
+
+/// Inbound connection statistics of the addresses the node listens on.
+pub struct ListenEndpoints {
+    protocol: TransportProtocol,
+    stats: BTreeMap<SocketAddress, InboundStats>,
+}
+
+impl ListenEndpoints {
+    pub fn new(protocol: TransportProtocol, addresses: &[SocketAddress]) -> Self {
+        Self {
+            protocol,
+            stats: addresses.iter().map(|addr| (*addr, InboundStats::default())).collect(),
+        }
+    }
+
+    /// Find the listen address that accepted a connection with the specified local address.
+    ///
+    /// If the node listens on an unspecified address (e.g. `0.0.0.0`), the local address
+    /// of the connection is the actual address of the interface that was connected to.
+    pub fn find_endpoint(&self, bind_address: &SocketAddress) -> Option<SocketAddress> {
+        if self.stats.contains_key(bind_address) {
+            return Some(*bind_address);
+        }
+
+        let bind_address = bind_address.socket_addr();
+        self.stats
+            .keys()
+            .find(|listen_address| {
+                let listen_address = listen_address.socket_addr();
+                listen_address.port() == bind_address.port()
+                    && listen_address.ip().is_unspecified()
+                    && listen_address.is_ipv4() == bind_address.is_ipv4()
+            })
+            .copied()
+    }
+
+    pub fn record_inbound(&mut self, bind_address: &SocketAddress, accepted: bool) {
+        let stats = self
+            .find_endpoint(bind_address)
+            .and_then(|endpoint| self.stats.get_mut(&endpoint));
+        if let Some(stats) = stats {
+            if accepted {
+                stats.accepted += 1;
+            } else {
+                stats.rejected += 1;
+            }
+        }
+    }
+
+    /// Return the statistics of all endpoints, `inbound_bind_addresses` are the local addresses
+    /// of the currently connected inbound peers
+    pub fn info(
+        &self,
+        inbound_bind_addresses: impl Iterator<Item = SocketAddress>,
+    ) -> Vec<ListenEndpointInfo> {
+        let mut active = BTreeMap::<SocketAddress, u64>::new();
+        for bind_address in inbound_bind_addresses {
+            if let Some(endpoint) = self.find_endpoint(&bind_address) {
+                *active.entry(endpoint).or_default() += 1;
+            }
+        }
+
+        self.stats
+            .iter()
+            .map(|(address, stats)| ListenEndpointInfo {
+                address: *address,
+                protocol: self.protocol.to_string(),
+                inbound_accepted: stats.accepted,
+                inbound_rejected: stats.rejected,
+                inbound_active: active.get(address).copied().unwrap_or(0),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> SocketAddress {
+        SocketAddress::new(s.parse().unwrap())
+    }
+
+    #[test]
+    fn endpoint_matching() {
+        let endpoints = ListenEndpoints::new(
+            TransportProtocol::Tcp,
+            &[addr("0.0.0.0:3031"), addr("[::]:3031"), addr("127.0.0.1:4000")],
+        );
+
+        assert_eq!(
+            endpoints.find_endpoint(&addr("192.168.1.2:3031")),
+            Some(addr("0.0.0.0:3031"))
+        );
+        assert_eq!(
+            endpoints.find_endpoint(&addr("[2001:db8::1]:3031")),
+            Some(addr("[::]:3031"))
+        );
+        assert_eq!(
+            endpoints.find_endpoint(&addr("127.0.0.1:4000")),
+            Some(addr("127.0.0.1:4000"))
+        );
+        assert_eq!(endpoints.find_endpoint(&addr("127.0.0.2:4000")), None);
+        assert_eq!(endpoints.find_endpoint(&addr("127.0.0.1:4001")), None);
+    }
+
+    #[test]
+    fn inbound_stats() {
+        let mut endpoints = ListenEndpoints::new(
+            TransportProtocol::Tcp,
+            &[addr("0.0.0.0:3031"), addr("[::]:3031")],
+        );
+
+        endpoints.record_inbound(&addr("10.0.0.1:3031"), true);
+        endpoints.record_inbound(&addr("10.0.0.1:3031"), true);
+        endpoints.record_inbound(&addr("10.0.0.1:3031"), false);
+        endpoints.record_inbound(&addr("[::1]:3031"), false);
+        // Unknown endpoints are ignored
+        endpoints.record_inbound(&addr("10.0.0.1:1234"), true);
+
+        let info = endpoints.info([addr("10.0.0.1:3031"), addr("10.0.0.2:1234")].into_iter());
+        assert_eq!(
+            info,
+            vec![
+                ListenEndpointInfo {
+                    address: addr("0.0.0.0:3031"),
+                    protocol: "tcp".to_owned(),
+                    inbound_accepted: 2,
+                    inbound_rejected: 1,
+                    inbound_active: 1,
+                },
+                ListenEndpointInfo {
+                    address: addr("[::]:3031"),
+                    protocol: "tcp".to_owned(),
+                    inbound_accepted: 0,
+                    inbound_rejected: 1,
+                    inbound_active: 0,
+                },
+            ]
+        );
+    }
+}
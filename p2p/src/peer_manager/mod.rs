@@ -19,6 +19,8 @@ mod addr_list_response_cache;
 pub mod address_groups;
 pub mod config;
 pub mod dns_seed;
+mod listen_endpoints;
+mod onion_addresses;
 pub mod peer_context;
 pub mod peerdb;
 pub mod peerdb_common;
@@ -51,22 +53,25 @@ use crate::{
     config::P2pConfig,
     disconnection_reason::DisconnectionReason,
     error::{ConnectionValidationError, P2pError, PeerError, ProtocolError},
-    interface::types::ConnectedPeer,
+    interface::types::{ConnectedPeer, NetworkConnectionsInfo},
     message::{
-        AddrListRequest, AddrListResponse, AnnounceAddrRequest, PeerManagerMessage, PingRequest,
-        PingResponse, WillDisconnectMessage,
+        AddrGossipEntry, AddrListRequest, AddrListResponse, AddrListResponseV2,
+        AnnounceAddrRequest, AnnounceAddrV2Request, GossipAddress, OnionV3Address,
+        PeerManagerMessage, PingRequest, PingResponse, WillDisconnectMessage,
     },
     net::{
+        default_backend::types::P2pTimestamp,
         types::{
             services::{Service, Services},
             ConnectivityEvent, PeerInfo, PeerRole,
         },
         ConnectivityService, NetworkingService,
     },
-    peer_manager_event::PeerDisconnectionDbAction,
+    peer_manager_event::{AnnouncementKind, PeerDisconnectionDbAction},
+    protocol::ProtocolFeature,
     sync::sync_status::PeerBlockSyncStatus,
     types::{
-        peer_address::{PeerAddress, PeerAddressIp4, PeerAddressIp6},
+        peer_address::{NetworkType, PeerAddress, PeerAddressIp4, PeerAddressIp6},
         peer_id::PeerId,
     },
     utils::{oneshot_nofail, rate_limiter::RateLimiter},
@@ -77,8 +82,10 @@ use self::{
     addr_list_response_cache::AddrListResponseCache,
     address_groups::AddressGroup,
     dns_seed::{DefaultDnsSeed, DnsSeed},
+    listen_endpoints::ListenEndpoints,
+    onion_addresses::OnionAddresses,
     peer_context::{PeerContext, SentPing},
-    peerdb::storage::PeerDbStorage,
+    peerdb::{address_data::UNKNOWN_LAST_SEEN_AGE, storage::PeerDbStorage},
 };
 
 /// Lower bound for how often [`PeerManager::heartbeat()`] is called
@@ -103,6 +110,30 @@ pub const ADDR_RATE_BUCKET_SIZE: u32 = 10;
 /// To how many peers resend received address
 const PEER_ADDRESS_RESEND_COUNT: usize = 2;
 
+/// What is known about the node behind a gossiped address
+#[derive(Debug, Clone, Copy)]
+struct GossipInfo {
+    last_seen: Time,
+    services: Services,
+}
+
+impl GossipInfo {
+    fn from_entry(entry: &AddrGossipEntry) -> Self {
+        Self {
+            last_seen: entry.last_seen.as_time(),
+            services: entry.services,
+        }
+    }
+
+    fn make_entry(&self, address: impl Into<GossipAddress>) -> AddrGossipEntry {
+        AddrGossipEntry {
+            address: address.into(),
+            last_seen: P2pTimestamp::from_time(self.last_seen),
+            services: self.services,
+        }
+    }
+}
+
 // Use the same parameters as Bitcoin Core (last 5000 addresses)
 const PEER_ADDRESSES_ROLLING_BLOOM_FILTER_SIZE: usize = 5000;
 const PEER_ADDRESSES_ROLLING_BLOOM_FPP: f64 = 0.001;
@@ -185,6 +216,9 @@ where
     /// Map of all connected peers
     peers: BTreeMap<PeerId, PeerContext>,
 
+    /// Inbound connection statistics of the local listen addresses
+    listen_endpoints: ListenEndpoints,
+
     /// Peer database
     peerdb: peerdb::PeerDb<S>,
 
@@ -196,6 +230,9 @@ where
     /// Cached address list responses.
     addr_list_response_cache: AddrListResponseCache,
 
+    /// The gossiped onion addresses, which are only relayed.
+    onion_addresses: OnionAddresses,
+
     /// PeerManager's observer for use by tests.
     observer: Option<Box<dyn Observer + Send>>,
 
@@ -278,6 +315,8 @@ where
             Self::choose_next_feeler_connection_time(&p2p_config, now);
         assert!(!p2p_config.outbound_connection_timeout.is_zero());
         assert!(!p2p_config.ping_timeout.is_zero());
+        let listen_endpoints =
+            ListenEndpoints::new(T::TRANSPORT_PROTOCOL, handle.local_addresses());
 
         Ok(PeerManager {
             networking_enabled,
@@ -289,10 +328,12 @@ where
             pending_outbound_connects: HashMap::new(),
             pending_disconnects: HashMap::new(),
             peers: BTreeMap::new(),
+            listen_endpoints,
             peerdb,
             subscribed_to_peer_addresses: BTreeSet::new(),
             peer_eviction_random_state: peers_eviction::RandomState::new(&mut rng),
             addr_list_response_cache: AddrListResponseCache::new(salt),
+            onion_addresses: OnionAddresses::new(),
             observer,
             dns_seed,
             init_time: now,
@@ -385,45 +426,66 @@ where
         chosen_discovered_address
     }
 
+    /// Make an address announcement in the format that the peer understands
+    fn make_announce_addr_message(
+        peer: &PeerContext,
+        address: PeerAddress,
+        info: GossipInfo,
+    ) -> PeerManagerMessage {
+        if peer.info.protocol_version.supports(ProtocolFeature::AddrGossipV2) {
+            PeerManagerMessage::AnnounceAddrV2Request(AnnounceAddrV2Request {
+                entry: info.make_entry(address),
+            })
+        } else {
+            PeerManagerMessage::AnnounceAddrRequest(AnnounceAddrRequest { address })
+        }
+    }
+
     /// Send address announcement to the selected peer (if the address is new)
     /// `peer_id` must be from the connected peer.
-    fn announce_address(&mut self, peer_id: PeerId, address: SocketAddress) {
+    fn announce_address(&mut self, peer_id: PeerId, address: SocketAddress, info: GossipInfo) {
         let peer = self.peers.get_mut(&peer_id).expect("peer must be known");
         if !peer.announced_addresses.contains(&address) {
             Self::send_peer_message(
                 &mut self.peer_connectivity_handle,
                 peer_id,
-                PeerManagerMessage::AnnounceAddrRequest(AnnounceAddrRequest {
-                    address: address.as_peer_address(),
-                }),
+                Self::make_announce_addr_message(peer, address.as_peer_address(), info),
             );
             peer.announced_addresses.insert(&address, &mut make_pseudo_rng());
         }
     }
 
+    /// The information about this node that is announced along with its own address
+    fn own_gossip_info(&self) -> GossipInfo {
+        GossipInfo {
+            last_seen: self.time_getter.get_time(),
+            services: (*self.p2p_config.node_type).into(),
+        }
+    }
+
     fn send_own_address_to_peer(
         peer_connectivity_handle: &mut T::ConnectivityHandle,
         peer: &PeerContext,
+        own_info: GossipInfo,
     ) {
         if let Some(discovered_addr) = peer.discovered_own_address.as_ref() {
             Self::send_peer_message(
                 peer_connectivity_handle,
                 peer.info.peer_id,
-                PeerManagerMessage::AnnounceAddrRequest(AnnounceAddrRequest {
-                    address: discovered_addr.as_peer_address(),
-                }),
+                Self::make_announce_addr_message(peer, discovered_addr.as_peer_address(), own_info),
             );
         }
     }
 
     fn resend_own_address_randomly(&mut self) {
+        let own_info = self.own_gossip_info();
         if let Some(peer) = self
             .peers
             .values_mut()
             .filter(|peer| peer.discovered_own_address.is_some())
             .choose(&mut make_pseudo_rng())
         {
-            Self::send_own_address_to_peer(&mut self.peer_connectivity_handle, peer);
+            Self::send_own_address_to_peer(&mut self.peer_connectivity_handle, peer, own_info);
         }
     }
 
@@ -432,9 +494,7 @@ where
             PeerRole::Inbound
             | PeerRole::OutboundFullRelay
             | PeerRole::OutboundBlockRelay
-            | PeerRole::Feeler => {
-                self.p2p_config.whitelisted_addresses.contains(&address.ip_addr())
-            }
+            | PeerRole::Feeler => self.p2p_config.is_whitelisted_address(&address.ip_addr()),
             PeerRole::OutboundReserved | PeerRole::OutboundManual => true,
         }
     }
@@ -784,14 +844,37 @@ where
                         ));
                     }
                 }
+
+                // Unlike the total limit above, nobody is evicted to make room here, so that
+                // peers from a crowded group can't push each other out by reconnecting.
+                let max_per_group =
+                    *self.p2p_config.peer_manager_config.max_inbound_connections_per_address_group;
+                let addr_group = AddressGroup::from_peer_address(&address.as_peer_address());
+                let inbound_count_in_group = self
+                    .peers
+                    .values()
+                    .filter(|peer| {
+                        peer.peer_role == PeerRole::Inbound
+                            && AddressGroup::from_peer_address(&peer.peer_address.as_peer_address())
+                                == addr_group
+                    })
+                    .count();
+                if inbound_count_in_group >= max_per_group {
+                    log::info!(
+                        "Rejecting inbound connection - too many peers from its address group"
+                    );
+                    return Err(P2pError::ConnectionValidationFailed(
+                        ConnectionValidationError::TooManyInboundPeersInAddressGroup,
+                    ));
+                }
             }
 
             PeerRole::OutboundReserved | PeerRole::OutboundManual | PeerRole::Feeler => {}
 
             PeerRole::OutboundFullRelay => {
-                let needed_services: Services = (*self.p2p_config.node_type).into();
+                let needed_services = Services::from(*self.p2p_config.node_type).required();
                 utils::ensure!(
-                    info.common_services == needed_services,
+                    info.common_services.required() == needed_services,
                     P2pError::ConnectionValidationFailed(
                         ConnectionValidationError::InsufficientServices {
                             needed_services,
@@ -804,7 +887,7 @@ where
             PeerRole::OutboundBlockRelay => {
                 let needed_services: Services = [Service::Blocks].as_slice().into();
                 utils::ensure!(
-                    info.common_services == needed_services,
+                    info.common_services.required() == needed_services,
                     P2pError::ConnectionValidationFailed(
                         ConnectionValidationError::InsufficientServices {
                             needed_services,
@@ -818,8 +901,60 @@ where
         Ok(())
     }
 
+    /// Count outbound full relay and manual connections per network.
+    fn outbound_full_relay_or_manual_counts_per_network(&self) -> BTreeMap<NetworkType, usize> {
+        let mut counts = BTreeMap::new();
+        for peer in self.peers.values() {
+            match peer.peer_role {
+                PeerRole::OutboundFullRelay
+                | PeerRole::OutboundReserved
+                | PeerRole::OutboundManual => {
+                    *counts
+                        .entry(peer.peer_address.as_peer_address().network_type())
+                        .or_insert(0) += 1;
+                }
+                PeerRole::Inbound | PeerRole::OutboundBlockRelay | PeerRole::Feeler => {}
+            }
+        }
+        counts
+    }
+
+    fn get_network_connections(&self) -> Vec<NetworkConnectionsInfo> {
+        NetworkType::ALL
+            .into_iter()
+            .map(|network| {
+                let mut info = NetworkConnectionsInfo {
+                    network,
+                    outbound_automatic: 0,
+                    outbound_manual: 0,
+                    inbound: 0,
+                };
+
+                for peer in self.peers.values() {
+                    if peer.peer_address.as_peer_address().network_type() != network {
+                        continue;
+                    }
+
+                    match peer.peer_role {
+                        PeerRole::Inbound => info.inbound += 1,
+                        PeerRole::OutboundFullRelay | PeerRole::OutboundBlockRelay => {
+                            info.outbound_automatic += 1
+                        }
+                        PeerRole::OutboundReserved | PeerRole::OutboundManual => {
+                            info.outbound_manual += 1
+                        }
+                        PeerRole::Feeler => {}
+                    }
+                }
+
+                info
+            })
+            .collect()
+    }
+
     fn eviction_candidates(&self, peer_role: PeerRole) -> Vec<peers_eviction::EvictionCandidate> {
         let now = self.time_getter.get_time();
+        let counts_per_network = self.outbound_full_relay_or_manual_counts_per_network();
         self.peers
             .values()
             .filter(|peer| {
@@ -828,11 +963,15 @@ where
             })
             .map(|peer| {
                 let addr = peer.peer_address.as_bannable();
+                let network = peer.peer_address.as_peer_address().network_type();
+                let is_only_outbound_to_network = peer.peer_role == PeerRole::OutboundFullRelay
+                    && counts_per_network.get(&network).copied().unwrap_or(0) == 1;
                 peers_eviction::EvictionCandidate::new(
                     peer,
                     &self.peer_eviction_random_state,
                     now,
                     self.peerdb.is_address_banned_or_discouraged(&addr),
+                    is_only_outbound_to_network,
                 )
             })
             .collect()
@@ -860,6 +999,26 @@ where
         }
     }
 
+    /// If some address group has more inbound peers than allowed, find and disconnect
+    /// the "worst" one from the most represented such group.
+    fn evict_inbound_peer_over_address_group_limit(&mut self) {
+        if let Some(peer_id) = peers_eviction::select_for_eviction_inbound_address_group(
+            self.eviction_candidates(PeerRole::Inbound),
+            &self.p2p_config.peer_manager_config,
+            &mut make_pseudo_rng(),
+        ) {
+            log::info!(
+                "inbound peer {peer_id} is selected for eviction due to the address group limit"
+            );
+            self.disconnect(
+                peer_id,
+                PeerDisconnectionDbAction::RemoveIfOutbound,
+                Some(DisconnectionReason::PeerEvicted),
+                None,
+            );
+        }
+    }
+
     /// If there are too many outbound block relay peers, find and disconnect the "worst" one.
     fn evict_block_relay_peer(&mut self) {
         if let Some(peer_id) = peers_eviction::select_for_eviction_block_relay(
@@ -995,9 +1154,12 @@ where
             last_tip_block_time: None,
             last_tx_time: None,
             block_sync_status: PeerBlockSyncStatus::new(),
+            dropped_tx_announcements: 0,
+            dropped_header_announcements: 0,
         };
 
-        Self::send_own_address_to_peer(&mut self.peer_connectivity_handle, &peer);
+        let own_info = self.own_gossip_info();
+        Self::send_own_address_to_peer(&mut self.peer_connectivity_handle, &peer, own_info);
 
         let old_value = self.peers.insert(peer_id, peer);
         assert!(old_value.is_none());
@@ -1071,6 +1233,10 @@ where
             node_address_as_seen_by_peer,
         );
 
+        if conn_dir == ConnectionDirection::Inbound {
+            self.listen_endpoints.record_inbound(&bind_address, accept_res.is_ok());
+        }
+
         if let Err(accept_err) = &accept_res {
             log::debug!("connection rejected for peer {peer_id}: {accept_err}");
 
@@ -1235,6 +1401,7 @@ where
         if self.networking_enabled {
             self.establish_new_connections();
 
+            self.evict_inbound_peer_over_address_group_limit();
             self.evict_block_relay_peer();
             self.evict_full_relay_peer();
         }
@@ -1252,22 +1419,32 @@ where
         let mut cur_feeler_conn_count = 0;
         let mut cur_outbound_conn_addr_groups = BTreeSet::new();
         let mut cur_conn_ip_port_to_role_map = BTreeMap::new();
+        // Automatic outbound connection counts per network, used to enforce the per-network limit.
+        let mut cur_automatic_conn_count_per_network = BTreeMap::<NetworkType, usize>::new();
+        // Networks that already have an outbound full relay, reserved or manual connection.
+        let mut cur_full_relay_or_manual_conn_networks = BTreeSet::new();
 
         for (addr, role) in self.peer_addresses_iter() {
-            let addr_group = AddressGroup::from_peer_address(&addr.as_peer_address());
+            let peer_addr = addr.as_peer_address();
+            let addr_group = AddressGroup::from_peer_address(&peer_addr);
+            let network = peer_addr.network_type();
 
             match role {
                 PeerRole::Inbound => {}
                 PeerRole::OutboundReserved | PeerRole::OutboundManual => {
                     cur_outbound_conn_addr_groups.insert(addr_group);
+                    cur_full_relay_or_manual_conn_networks.insert(network);
                 }
                 PeerRole::OutboundFullRelay => {
                     cur_outbound_full_relay_conn_count += 1;
                     cur_outbound_conn_addr_groups.insert(addr_group);
+                    cur_full_relay_or_manual_conn_networks.insert(network);
+                    *cur_automatic_conn_count_per_network.entry(network).or_insert(0) += 1;
                 }
                 PeerRole::OutboundBlockRelay => {
                     cur_outbound_block_relay_conn_count += 1;
                     cur_outbound_conn_addr_groups.insert(addr_group);
+                    *cur_automatic_conn_count_per_network.entry(network).or_insert(0) += 1;
                 }
                 PeerRole::Feeler => {
                     cur_feeler_conn_count += 1;
@@ -1289,33 +1466,99 @@ where
                 .saturating_sub(cur_outbound_full_relay_conn_count)
         };
 
-        let new_full_relay_conn_addresses = self.peerdb.select_non_reserved_outbound_addresses(
-            &cur_outbound_conn_addr_groups,
-            &|addr| {
-                self.allow_new_outbound_connection(
-                    &cur_conn_ip_port_to_role_map,
-                    addr,
-                    PeerRole::OutboundFullRelay,
-                )
-            },
-            needed_outbound_full_relay_conn_count,
-        );
+        let max_conn_count_per_network =
+            *self.p2p_config.peer_manager_config.max_outbound_connections_per_network;
+        let network_has_free_slots = |counts: &BTreeMap<NetworkType, usize>,
+                                      addr: &SocketAddress| {
+            let network = addr.as_peer_address().network_type();
+            counts.get(&network).copied().unwrap_or(0) < max_conn_count_per_network
+        };
+
+        let mut new_full_relay_conn_addresses = Vec::new();
+
+        let full_relay_conns_enabled =
+            *self.p2p_config.peer_manager_config.outbound_full_relay_count > 0;
+        let reachable_networks = self.peerdb.reachable_networks();
+
+        for network in NetworkType::ALL {
+            if !full_relay_conns_enabled
+                || !reachable_networks.contains(&network)
+                || cur_full_relay_or_manual_conn_networks.contains(&network)
+            {
+                continue;
+            }
+
+            // Similarly to bitcoin's CConnman::MaybePickPreferredNetwork, try to have at least
+            // one outbound full relay connection to each reachable network, so that the node
+            // doesn't get isolated if one of the networks is partitioned. If all full relay slots
+            // are taken, this becomes an extra connection; the eviction logic will take care of
+            // it later. Networks the node has never managed to connect to are skipped, so that
+            // the extra connection attempts aren't wasted on them.
+            let selected = self.peerdb.select_non_reserved_outbound_addresses(
+                &cur_outbound_conn_addr_groups,
+                &|addr| {
+                    addr.as_peer_address().network_type() == network
+                        && network_has_free_slots(&cur_automatic_conn_count_per_network, addr)
+                        && self.allow_new_outbound_connection(
+                            &cur_conn_ip_port_to_role_map,
+                            addr,
+                            PeerRole::OutboundFullRelay,
+                        )
+                },
+                1,
+            );
+
+            if let Some(address) = selected.into_iter().next() {
+                log::debug!(
+                    "Selected address {address} to establish a connection to network {network:?}"
+                );
+
+                let addr_group = AddressGroup::from_peer_address(&address.as_peer_address());
+                cur_outbound_conn_addr_groups.insert(addr_group);
+                *cur_automatic_conn_count_per_network.entry(network).or_insert(0) += 1;
+                new_full_relay_conn_addresses.push(address);
+            }
+        }
+
+        let needed_outbound_full_relay_conn_count = needed_outbound_full_relay_conn_count
+            .saturating_sub(new_full_relay_conn_addresses.len());
+
+        let selected_full_relay_conn_addresses =
+            self.peerdb.select_non_reserved_outbound_addresses(
+                &cur_outbound_conn_addr_groups,
+                &|addr| {
+                    network_has_free_slots(&cur_automatic_conn_count_per_network, addr)
+                        && self.allow_new_outbound_connection(
+                            &cur_conn_ip_port_to_role_map,
+                            addr,
+                            PeerRole::OutboundFullRelay,
+                        )
+                },
+                needed_outbound_full_relay_conn_count,
+            );
 
         log::debug!(
             "Need to establish {} full relay connection(s); selected addresses: {:?}",
             needed_outbound_full_relay_conn_count,
-            new_full_relay_conn_addresses
+            selected_full_relay_conn_addresses
         );
 
-        // TODO: in bitcoin they also try to create an extra outbound full relay connection
-        // to an address in a reachable network in which there are no outbound full relay or
-        // manual connections (see CConnman::MaybePickPreferredNetwork for reference).
-        // See https://github.com/mintlayer/mintlayer-core/issues/1433
+        for address in selected_full_relay_conn_addresses {
+            // Several selected addresses may belong to the same network, so the limit
+            // has to be re-checked here.
+            if !network_has_free_slots(&cur_automatic_conn_count_per_network, &address) {
+                continue;
+            }
 
-        for address in &new_full_relay_conn_addresses {
-            let addr_group = AddressGroup::from_peer_address(&address.as_peer_address());
-            cur_outbound_conn_addr_groups.insert(addr_group);
+            let peer_addr = address.as_peer_address();
+            cur_outbound_conn_addr_groups.insert(AddressGroup::from_peer_address(&peer_addr));
+            *cur_automatic_conn_count_per_network
+                .entry(peer_addr.network_type())
+                .or_insert(0) += 1;
+            new_full_relay_conn_addresses.push(address);
+        }
 
+        for address in &new_full_relay_conn_addresses {
             self.connect(
                 *address,
                 OutboundConnectType::Automatic {
@@ -1332,11 +1575,12 @@ where
         let new_block_relay_conn_addresses = self.peerdb.select_non_reserved_outbound_addresses(
             &cur_outbound_conn_addr_groups,
             &|addr| {
-                self.allow_new_outbound_connection(
-                    &cur_conn_ip_port_to_role_map,
-                    addr,
-                    PeerRole::OutboundBlockRelay,
-                )
+                network_has_free_slots(&cur_automatic_conn_count_per_network, addr)
+                    && self.allow_new_outbound_connection(
+                        &cur_conn_ip_port_to_role_map,
+                        addr,
+                        PeerRole::OutboundBlockRelay,
+                    )
             },
             needed_outbound_block_relay_conn_count,
         );
@@ -1348,6 +1592,14 @@ where
         );
 
         for address in &new_block_relay_conn_addresses {
+            if !network_has_free_slots(&cur_automatic_conn_count_per_network, address) {
+                continue;
+            }
+
+            *cur_automatic_conn_count_per_network
+                .entry(address.as_peer_address().network_type())
+                .or_insert(0) += 1;
+
             self.connect(
                 *address,
                 OutboundConnectType::Automatic {
@@ -1382,9 +1634,15 @@ where
             && cur_feeler_conn_count == 0
             && now >= self.next_feeler_connection_time
         {
-            if let Some(address) =
-                self.peerdb.select_non_reserved_outbound_address_from_new_addr_table()
-            {
+            // Checking the older addresses of the "tried" table collisions takes priority,
+            // because the newer addresses can't be moved to "tried" until that's done.
+            let address = self
+                .peerdb
+                .select_tried_collision_address_for_feeler()
+                .filter(|addr| !self.pending_outbound_connects.contains_key(addr))
+                .or_else(|| self.peerdb.select_non_reserved_outbound_address_from_new_addr_table());
+
+            if let Some(address) = address {
                 self.connect(address, OutboundConnectType::Feeler);
                 self.next_feeler_connection_time =
                     Self::choose_next_feeler_connection_time(&self.p2p_config, now);
@@ -1398,10 +1656,16 @@ where
             PeerManagerMessage::AnnounceAddrRequest(r) => {
                 self.handle_announce_addr_request(peer, r.address)
             }
+            PeerManagerMessage::AnnounceAddrV2Request(r) => {
+                self.handle_announce_addr_v2_request(peer, r.entry)
+            }
             PeerManagerMessage::PingRequest(r) => self.handle_ping_request(peer, r.nonce),
             PeerManagerMessage::AddrListResponse(r) => {
                 self.handle_addr_list_response(peer, r.addresses)
             }
+            PeerManagerMessage::AddrListResponseV2(r) => {
+                self.handle_addr_list_response_v2(peer, r.entries)
+            }
             PeerManagerMessage::PingResponse(r) => self.handle_ping_response(peer, r.nonce),
             PeerManagerMessage::WillDisconnect(msg) => {
                 self.handle_will_disconnect_messgae(peer, msg)
@@ -1410,6 +1674,87 @@ where
     }
 
     fn handle_announce_addr_request(&mut self, peer_id: PeerId, address: PeerAddress) {
+        // Announcements are sent about the nodes that are online
+        let info = GossipInfo {
+            last_seen: self.time_getter.get_time(),
+            services: Services::from_u64(0),
+        };
+        self.handle_announced_address(peer_id, address, info);
+    }
+
+    fn handle_announce_addr_v2_request(&mut self, peer_id: PeerId, entry: AddrGossipEntry) {
+        let info = GossipInfo::from_entry(&entry);
+        match entry.address {
+            GossipAddress::Ip4(_) | GossipAddress::Ip6(_) => {
+                if let Some(address) = entry.address.as_peer_address() {
+                    self.handle_announced_address(peer_id, address, info)
+                }
+            }
+            GossipAddress::OnionV3(address) => {
+                self.handle_announced_onion_address(peer_id, address, info)
+            }
+        }
+    }
+
+    /// Onion addresses can't be stored in PeerDb or connected to, so they are only kept
+    /// in memory and relayed to the peers that support the v2 address gossip.
+    fn handle_announced_onion_address(
+        &mut self,
+        peer_id: PeerId,
+        address: OnionV3Address,
+        info: GossipInfo,
+    ) {
+        let now = self.time_getter.get_time();
+        let peer = self
+            .peers
+            .get_mut(&peer_id)
+            .expect("peer sending AnnounceAddrV2Request must be known");
+        let is_whitelisted = self.p2p_config.is_whitelisted_address(&peer.peer_address.ip_addr());
+        if !is_whitelisted && !peer.address_rate_limiter.accept(now) {
+            log::debug!("onion address announcement is rate limited from peer {peer_id}");
+            return;
+        }
+
+        // Don't let peers make the address look fresher than possible
+        let info = GossipInfo {
+            last_seen: std::cmp::min(info.last_seen, now),
+            services: info.services,
+        };
+
+        // Only the new information is relayed, which also stops the announcement from
+        // circulating in the network forever
+        if !self.onion_addresses.add(address.clone(), info) {
+            return;
+        }
+
+        let peer_ids = self
+            .subscribed_to_peer_addresses
+            .iter()
+            .cloned()
+            .filter(|new_peer_id| {
+                *new_peer_id != peer_id
+                    && self.peers.get(new_peer_id).is_some_and(|peer| {
+                        peer.info.protocol_version.supports(ProtocolFeature::AddrGossipV2)
+                    })
+            })
+            .choose_multiple(&mut make_pseudo_rng(), PEER_ADDRESS_RESEND_COUNT);
+        for new_peer_id in peer_ids {
+            Self::send_peer_message(
+                &mut self.peer_connectivity_handle,
+                new_peer_id,
+                PeerManagerMessage::AnnounceAddrV2Request(AnnounceAddrV2Request {
+                    entry: info.make_entry(GossipAddress::OnionV3(address.clone())),
+                }),
+            );
+        }
+    }
+
+    fn handle_announced_address(
+        &mut self,
+        peer_id: PeerId,
+        address: PeerAddress,
+        info: GossipInfo,
+    ) {
         if let Some(address) =
             address.as_discoverable_socket_address(*self.p2p_config.allow_discover_private_ips)
         {
@@ -1417,14 +1762,16 @@ where
                 .peers
                 .get_mut(&peer_id)
                 .expect("peer sending AnnounceAddrRequest must be known");
-            if !peer.address_rate_limiter.accept(self.time_getter.get_time()) {
+            let is_whitelisted =
+                self.p2p_config.is_whitelisted_address(&peer.peer_address.ip_addr());
+            if !is_whitelisted && !peer.address_rate_limiter.accept(self.time_getter.get_time()) {
                 log::debug!("address announcement is rate limited from peer {peer_id}");
                 return;
             }
 
             peer.announced_addresses.insert(&address, &mut make_pseudo_rng());
 
-            self.peerdb.peer_discovered(address);
+            self.peerdb.peer_address_gossiped(address, info.last_seen, info.services);
 
             if !self.peerdb.is_address_banned_or_discouraged(&address.as_bannable()) {
                 let peer_ids = self
@@ -1433,7 +1780,7 @@ where
                     .cloned()
                     .choose_multiple(&mut make_pseudo_rng(), PEER_ADDRESS_RESEND_COUNT);
                 for new_peer_id in peer_ids {
-                    self.announce_address(new_peer_id, address);
+                    self.announce_address(new_peer_id, address, info);
                 }
             }
         }
@@ -1478,17 +1825,44 @@ where
 
         assert!(addresses.len() <= max_addr_count);
 
-        Self::send_peer_message(
-            &mut self.peer_connectivity_handle,
-            peer_id,
-            PeerManagerMessage::AddrListResponse(AddrListResponse { addresses }),
-        );
+        let message = if peer.info.protocol_version.supports(ProtocolFeature::AddrGossipV2) {
+            // The onion addresses are only sent to the peers that can parse them
+            let onion_entries = self
+                .onion_addresses
+                .newest(max_addr_count - addresses.len())
+                .into_iter()
+                .map(|(address, info)| info.make_entry(GossipAddress::OnionV3(address)));
+            let entries = addresses
+                .into_iter()
+                .map(|address| self.make_addr_gossip_entry(address, now))
+                .chain(onion_entries)
+                .collect();
+            PeerManagerMessage::AddrListResponseV2(AddrListResponseV2 { entries })
+        } else {
+            PeerManagerMessage::AddrListResponse(AddrListResponse { addresses })
+        };
+
+        Self::send_peer_message(&mut self.peer_connectivity_handle, peer_id, message);
+    }
+
+    /// Make a v2 address list entry from what PeerDb knows about the address
+    fn make_addr_gossip_entry(&self, address: PeerAddress, now: Time) -> AddrGossipEntry {
+        let address_data = self.peerdb.address_data(&SocketAddress::new((&address).into()));
+        let info = GossipInfo {
+            last_seen: address_data
+                .and_then(|data| data.last_seen())
+                .unwrap_or_else(|| now.saturating_duration_sub(UNKNOWN_LAST_SEEN_AGE)),
+            services: address_data.map_or(Services::from_u64(0), |address_data| {
+                address_data.services()
+            }),
+        };
+        info.make_entry(address)
     }
 
     fn try_handle_addr_list_response(
         &mut self,
         peer_id: PeerId,
-        addresses: Vec<PeerAddress>,
+        addresses: Vec<(GossipAddress, Option<GossipInfo>)>,
     ) -> crate::Result<()> {
         log::debug!(
             "[peer id = {peer_id}] Handling addr list response, address count = {}",
@@ -1512,11 +1886,23 @@ where
             ))
         );
 
-        for address in &addresses {
-            if let Some(address) =
+        let now = self.time_getter.get_time();
+        for (address, info) in &addresses {
+            if let (GossipAddress::OnionV3(address), Some(info)) = (address, info) {
+                let info = GossipInfo {
+                    last_seen: std::cmp::min(info.last_seen, now),
+                    services: info.services,
+                };
+                self.onion_addresses.add(address.clone(), info);
+            } else if let Some(address) = address.as_peer_address().and_then(|address| {
                 address.as_discoverable_socket_address(*self.p2p_config.allow_discover_private_ips)
-            {
-                self.peerdb.peer_discovered(address);
+            }) {
+                match info {
+                    Some(info) => {
+                        self.peerdb.peer_address_gossiped(address, info.last_seen, info.services)
+                    }
+                    None => self.peerdb.peer_discovered(address),
+                }
             }
         }
 
@@ -1524,6 +1910,28 @@ where
     }
 
     fn handle_addr_list_response(&mut self, peer_id: PeerId, addresses: Vec<PeerAddress>) {
+        let addresses = addresses.into_iter().map(|address| (address.into(), None)).collect();
+        self.handle_addr_list_response_impl(peer_id, addresses);
+    }
+
+    fn handle_addr_list_response_v2(&mut self, peer_id: PeerId, entries: Vec<AddrGossipEntry>) {
+        let addresses = entries
+            .into_iter()
+            .map(|entry| {
+                let info = GossipInfo::from_entry(&entry);
+                (entry.address, Some(info))
+            })
+            .collect();
+        self.handle_addr_list_response_impl(peer_id, addresses);
+    }
+
+    /// Handle the address list, where the information about the nodes is only available
+    /// for the v2 responses
+    fn handle_addr_list_response_impl(
+        &mut self,
+        peer_id: PeerId,
+        addresses: Vec<(GossipAddress, Option<GossipInfo>)>,
+    ) {
         let res = self.try_handle_addr_list_response(peer_id, addresses);
         if let Err(err) = res {
             log::debug!("try_handle_addr_list_response failed: {err}");
@@ -1590,7 +1998,18 @@ where
                 self.connect(address, OutboundConnectType::Manual { response_sender });
             }
             PeerManagerEvent::Disconnect(peer_id, peerdb_action, reason, response_sender) => {
-                self.disconnect(peer_id, peerdb_action, reason, Some(response_sender));
+                // Same as with the peer score, whitelisted peers are not punished for misbehavior
+                let is_whitelisted_peer = self.peers.get(&peer_id).is_some_and(|peer| {
+                    self.is_whitelisted_node(peer.peer_role, &peer.peer_address)
+                });
+                if reason == Some(DisconnectionReason::InvalidDataReceived) && is_whitelisted_peer {
+                    log::info!(
+                        "Not disconnecting the whitelisted peer {peer_id} for sending invalid data"
+                    );
+                    response_sender.send(Ok(()));
+                } else {
+                    self.disconnect(peer_id, peerdb_action, reason, Some(response_sender));
+                }
             }
             PeerManagerEvent::AdjustPeerScore(peer_id, score, response_sender) => {
                 log::debug!("adjust peer {peer_id} score: {score}");
@@ -1622,6 +2041,18 @@ where
                     peer.block_sync_status = status;
                 }
             }
+            PeerManagerEvent::PeerAnnouncementsDropped {
+                peer_id,
+                kind,
+                count,
+            } => {
+                if let Some(peer) = self.peers.get_mut(&peer_id) {
+                    match kind {
+                        AnnouncementKind::Transaction => peer.dropped_tx_announcements += count,
+                        AnnouncementKind::Header => peer.dropped_header_announcements += count,
+                    }
+                }
+            }
             PeerManagerEvent::GetPeerCount(response_sender) => {
                 response_sender.send(self.active_peer_count());
             }
@@ -1629,6 +2060,17 @@ where
                 let addr = self.peer_connectivity_handle.local_addresses().to_vec();
                 response_sender.send(addr);
             }
+            PeerManagerEvent::GetListenEndpoints(response_sender) => {
+                let inbound_bind_addresses = self
+                    .peers
+                    .values()
+                    .filter(|peer| peer.peer_role == PeerRole::Inbound)
+                    .map(|peer| peer.bind_address);
+                response_sender.send(self.listen_endpoints.info(inbound_bind_addresses));
+            }
+            PeerManagerEvent::GetNetworkConnections(response_sender) => {
+                response_sender.send(self.get_network_connections());
+            }
             PeerManagerEvent::GetConnectedPeers(response_sender) => {
                 let peers = self.get_connected_peers();
                 response_sender.send(peers);
@@ -1763,6 +2205,8 @@ where
                 ping_min: context.ping_min.map(|time| {
                     duration_to_int(&time).expect("valid timestamp expected (ping_min)")
                 }),
+                dropped_tx_announcements: context.dropped_tx_announcements,
+                dropped_header_announcements: context.dropped_header_announcements,
             })
             .collect()
     }
@@ -0,0 +1,81 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use crate::message::OnionV3Address;
+
+use super::GossipInfo;
+
+/// The maximum number of onion addresses to keep
+const MAX_ONION_ADDRESSES: usize = 1000;
+
+/// The onion addresses learned from the v2 address gossip.
+///
+/// The node can't connect to them and PeerDb can't store them, so they are only kept in memory
+/// in order to be relayed to other peers and returned in the address list responses.
+/// When the limit is reached, the address with the oldest last-seen time is evicted.
+pub struct OnionAddresses {
+    addresses: BTreeMap<OnionV3Address, GossipInfo>,
+}
+
+impl OnionAddresses {
+    pub fn new() -> Self {
+        Self {
+            addresses: BTreeMap::new(),
+        }
+    }
+
+    /// Add or update the address; return true if it was unknown or its last-seen time
+    /// has become newer, i.e. if the address is worth relaying.
+    pub fn add(&mut self, address: OnionV3Address, info: GossipInfo) -> bool {
+        if let Some(existing) = self.addresses.get_mut(&address) {
+            if existing.last_seen >= info.last_seen {
+                return false;
+            }
+            *existing = info;
+            return true;
+        }
+
+        if self.addresses.len() >= MAX_ONION_ADDRESSES {
+            let oldest = self
+                .addresses
+                .iter()
+                .min_by_key(|(_, info)| info.last_seen)
+                .map(|(address, info)| (address.clone(), info.last_seen));
+            match oldest {
+                Some((oldest_address, oldest_last_seen)) if oldest_last_seen < info.last_seen => {
+                    self.addresses.remove(&oldest_address);
+                }
+                _ => return false,
+            }
+        }
+
+        self.addresses.insert(address, info);
+        true
+    }
+
+    /// Return up to `count` addresses with the newest last-seen times
+    pub fn newest(&self, count: usize) -> Vec<(OnionV3Address, GossipInfo)> {
+        let mut addresses = self
+            .addresses
+            .iter()
+            .map(|(address, info)| (address.clone(), *info))
+            .collect::<Vec<_>>();
+        addresses.sort_by_key(|(_, info)| std::cmp::Reverse(info.last_seen));
+        addresses.truncate(count);
+        addresses
+    }
+}
@@ -79,4 +79,10 @@ pub struct PeerContext {
 
     /// Certain information from the block sync manager that the peer manager may be interested in.
     pub block_sync_status: PeerBlockSyncStatus,
+
+    /// The number of transaction announcements to this peer dropped because its send queue was full.
+    pub dropped_tx_announcements: u64,
+
+    /// The number of header announcements to this peer dropped because its send queue was full.
+    pub dropped_header_announcements: u64,
 }
@@ -16,6 +16,7 @@
 use std::time::Duration;
 
 use common::primitives::time::Time;
+use p2p_types::services::Services;
 use randomness::Rng;
 
 /// Maximum delay between reconnection attempts to reserved nodes
@@ -43,6 +44,19 @@ pub const PURGE_REACHABLE_FAIL_COUNT: u32 =
 /// -ln(0.0000000000000035527136788) which is about 33.
 const MAX_DELAY_FACTOR: u32 = 30;
 
+/// The age assumed for addresses whose last-seen time is unknown, e.g. the ones loaded from the db
+/// or received from peers that don't support the v2 address gossip.
+pub const UNKNOWN_LAST_SEEN_AGE: Duration = Duration::from_secs(5 * 24 * 3600);
+
+/// The minimum age that a last-seen time received from a peer is treated as having when the
+/// address is selected, so that peers can't bias the selection towards the addresses they
+/// want by claiming that the nodes have just been seen.
+pub const MIN_GOSSIPED_LAST_SEEN_AGE: Duration = Duration::from_secs(24 * 3600);
+
+/// The factor by which the selection weight of an address is reduced if the node is known
+/// to lack some of the needed services.
+const MISSING_SERVICES_WEIGHT_FACTOR: f64 = 0.1;
+
 #[derive(Debug)]
 pub enum AddressState {
     Connected {},
@@ -90,6 +104,22 @@ pub struct AddressData {
     state: AddressState,
 
     reserved: bool,
+
+    /// The last time the node was known to be online, either because we were connected to it
+    /// or because a peer has told us so.
+    last_seen: Option<Time>,
+
+    /// The last time we were connected to the node ourselves; unlike `last_seen`,
+    /// it can't be faked by the peers. Stored in the DB for known addresses, so that
+    /// the selection weight survives restarts.
+    last_connected: Option<Time>,
+
+    /// The services that the node was last known to provide, empty if unknown. Not stored in the DB.
+    services: Services,
+
+    /// The time of the last outbound connection attempt, successful or not.
+    /// Stored in the DB along with the fail count, so that the backoff survives restarts.
+    last_attempt: Option<Time>,
 }
 
 impl AddressData {
@@ -101,13 +131,124 @@ impl AddressData {
                 next_connect_after: now,
             },
             reserved,
+            last_seen: None,
+            last_connected: None,
+            services: Services::from_u64(0),
+            last_attempt: None,
+        }
+    }
+
+    /// Restore the reconnection schedule of an address loaded from the DB.
+    ///
+    /// The next attempt is scheduled as if the last failure has just happened at `last_attempt`;
+    /// reachable addresses that have already failed too many times are marked for removal.
+    pub fn restore_connection_stats(
+        &mut self,
+        fail_count: u32,
+        last_attempt: Time,
+        rng: &mut impl Rng,
+    ) {
+        self.last_attempt = Some(last_attempt);
+
+        if let AddressState::Disconnected {
+            was_reachable,
+            fail_count: _,
+            next_connect_after: _,
+        } = self.state
+        {
+            self.state = if !self.reserved && fail_count >= PURGE_REACHABLE_FAIL_COUNT {
+                AddressState::Unreachable {
+                    erase_after: last_attempt,
+                }
+            } else {
+                AddressState::Disconnected {
+                    was_reachable,
+                    fail_count,
+                    next_connect_after: Self::next_connect_time(
+                        last_attempt,
+                        fail_count,
+                        self.reserved,
+                        rng,
+                    ),
+                }
+            };
         }
     }
 
+    /// Restore the last connection time of an address loaded from the DB.
+    pub fn restore_last_connected(&mut self, last_connected: Time) {
+        self.last_seen = Some(last_connected);
+        self.last_connected = Some(last_connected);
+    }
+
     pub fn reserved(&self) -> bool {
         self.reserved
     }
 
+    pub fn last_seen(&self) -> Option<Time> {
+        self.last_seen
+    }
+
+    pub fn last_connected(&self) -> Option<Time> {
+        self.last_connected
+    }
+
+    pub fn services(&self) -> Services {
+        self.services
+    }
+
+    pub fn last_attempt(&self) -> Option<Time> {
+        self.last_attempt
+    }
+
+    /// The number of consecutive failed connection attempts, zero if the address is
+    /// currently connected or unreachable.
+    pub fn fail_count(&self) -> u32 {
+        match self.state {
+            AddressState::Disconnected {
+                was_reachable: _,
+                fail_count,
+                next_connect_after: _,
+            } => fail_count,
+            AddressState::Connected {} | AddressState::Unreachable { erase_after: _ } => 0,
+        }
+    }
+
+    /// Update the information about the node that a peer has sent us.
+    ///
+    /// Only newer information is taken into account; `last_seen` must not be in the future.
+    pub fn update_from_gossip(&mut self, last_seen: Time, services: Services) {
+        if self.last_seen.map_or(true, |cur_last_seen| cur_last_seen < last_seen) {
+            self.last_seen = Some(last_seen);
+            if !services.is_empty() {
+                self.services = services;
+            }
+        }
+    }
+
+    /// The relative chance of the address to be selected for an outbound connection.
+    ///
+    /// Recently seen nodes are preferred, and the nodes that are known to lack some
+    /// of the needed services are penalized. The last-seen times received from the peers
+    /// are not trusted to be more recent than `MIN_GOSSIPED_LAST_SEEN_AGE`, only the times
+    /// of our own connections are.
+    #[allow(clippy::float_arithmetic)]
+    pub fn selection_weight(&self, now: Time, needed_services: Services) -> f64 {
+        let gossiped_age = self.last_seen.map_or(UNKNOWN_LAST_SEEN_AGE, |last_seen| {
+            std::cmp::max(now.saturating_sub(last_seen), MIN_GOSSIPED_LAST_SEEN_AGE)
+        });
+        let age = self.last_connected.map_or(gossiped_age, |last_connected| {
+            std::cmp::min(now.saturating_sub(last_connected), gossiped_age)
+        });
+        let age_days = age.as_secs_f64() / (24.0 * 3600.0);
+
+        let mut weight = 1.0 / (1.0 + age_days);
+        if !self.services.is_empty() && (self.services & needed_services) != needed_services {
+            weight *= MISSING_SERVICES_WEIGHT_FACTOR;
+        }
+        weight
+    }
+
     /// Returns true when it is time to attempt a new outbound connection
     pub fn connect_now(&self, now: Time) -> bool {
         match self.state {
@@ -147,6 +288,15 @@ impl AddressData {
         matches!(self.state, AddressState::Unreachable { .. })
     }
 
+    /// Whether the node has been connected to the address at least once
+    pub fn was_reachable(&self) -> bool {
+        match self.state {
+            AddressState::Connected {} => true,
+            AddressState::Disconnected { was_reachable, .. } => was_reachable,
+            AddressState::Unreachable { .. } => false,
+        }
+    }
+
     fn next_connect_delay(fail_count: u32, reserved: bool) -> Duration {
         let max_delay = if reserved {
             MAX_DELAY_RESERVED
@@ -173,6 +323,25 @@ impl AddressData {
         now: Time,
         rng: &mut impl Rng,
     ) {
+        match transition {
+            AddressStateTransitionTo::Connected | AddressStateTransitionTo::Disconnected => {
+                self.last_seen = Some(now);
+                self.last_connected = Some(now);
+            }
+            AddressStateTransitionTo::ConnectionFailed
+            | AddressStateTransitionTo::SetReserved
+            | AddressStateTransitionTo::UnsetReserved => {}
+        }
+
+        match transition {
+            AddressStateTransitionTo::Connected | AddressStateTransitionTo::ConnectionFailed => {
+                self.last_attempt = Some(now);
+            }
+            AddressStateTransitionTo::Disconnected
+            | AddressStateTransitionTo::SetReserved
+            | AddressStateTransitionTo::UnsetReserved => {}
+        }
+
         self.state = match transition {
             AddressStateTransitionTo::Connected => match self.state {
                 AddressState::Connected {} => unreachable!(),
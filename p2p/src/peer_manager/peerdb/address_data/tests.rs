@@ -13,6 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use p2p_types::services::Service;
 use randomness::{
     distributions::{Distribution, WeightedIndex},
     rngs::StepRng,
@@ -120,3 +121,87 @@ fn next_connect_time() {
     let mut always_max_rng = StepRng::new(u64::MAX, 0);
     next_connect_time_test_impl(&mut always_max_rng);
 }
+
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn restore_connection_stats(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let now = Time::from_secs_since_epoch(1600000000);
+    let last_attempt = (now - Duration::from_secs(100)).unwrap();
+
+    let mut address_data = AddressData::new(true, false, now);
+    assert_eq!(address_data.fail_count(), 0);
+    assert_eq!(address_data.last_attempt(), None);
+
+    let fail_count = rng.gen_range(1..PURGE_REACHABLE_FAIL_COUNT);
+    address_data.restore_connection_stats(fail_count, last_attempt, &mut rng);
+    assert_eq!(address_data.fail_count(), fail_count);
+    assert_eq!(address_data.last_attempt(), Some(last_attempt));
+    assert!(!address_data.is_unreachable());
+
+    // The backoff continues from where it was
+    let max_next_connect_time = (last_attempt + MAX_DELAY_REACHABLE * MAX_DELAY_FACTOR).unwrap();
+    assert!(address_data.connect_now(max_next_connect_time));
+
+    address_data.transition_to(AddressStateTransitionTo::ConnectionFailed, now, &mut rng);
+    assert_eq!(address_data.fail_count(), fail_count + 1);
+    assert_eq!(address_data.last_attempt(), Some(now));
+
+    address_data.transition_to(AddressStateTransitionTo::Connected, now, &mut rng);
+    assert_eq!(address_data.fail_count(), 0);
+
+    // Addresses that have failed too many times are forgotten
+    let mut address_data = AddressData::new(true, false, now);
+    address_data.restore_connection_stats(PURGE_REACHABLE_FAIL_COUNT, last_attempt, &mut rng);
+    assert!(address_data.is_unreachable());
+    assert!(!address_data.retain(now));
+
+    // But reserved ones are not
+    let mut address_data = AddressData::new(true, true, now);
+    address_data.restore_connection_stats(PURGE_REACHABLE_FAIL_COUNT, last_attempt, &mut rng);
+    assert!(!address_data.is_unreachable());
+    assert_eq!(address_data.fail_count(), PURGE_REACHABLE_FAIL_COUNT);
+}
+
+#[test]
+fn selection_weight() {
+    let now = Time::from_duration_since_epoch(Duration::from_secs(100 * 24 * 3600));
+    let day = Duration::from_secs(24 * 3600);
+    let all_services = Services::from(Service::ALL.as_slice());
+    let no_addresses = Services::from([Service::Blocks, Service::Transactions].as_slice());
+
+    let mut address_data = AddressData::new(false, false, now);
+    assert_eq!(address_data.last_seen(), None);
+    let unknown_weight = address_data.selection_weight(now, all_services);
+    assert_eq!(unknown_weight, 1.0 / 6.0);
+
+    let seen_day_ago = (now - day).unwrap();
+    address_data.update_from_gossip(seen_day_ago, all_services);
+    assert_eq!(address_data.last_seen(), Some(seen_day_ago));
+    assert_eq!(address_data.selection_weight(now, all_services), 0.5);
+
+    // Older information is ignored
+    address_data.update_from_gossip((now - day * 2).unwrap(), no_addresses);
+    assert_eq!(address_data.last_seen(), Some(seen_day_ago));
+    assert_eq!(address_data.services(), all_services);
+
+    // The node is penalized if it's known to lack some of the needed services;
+    // the gossiped last-seen time is not trusted to be more recent than a day
+    address_data.update_from_gossip(now, no_addresses);
+    assert_eq!(address_data.last_seen(), Some(now));
+    assert_eq!(address_data.selection_weight(now, all_services), 0.05);
+    assert_eq!(address_data.selection_weight(now, no_addresses), 0.5);
+
+    // Connecting to the node updates the last-seen time, which is trusted
+    let later = (now + day).unwrap();
+    address_data.transition_to(
+        AddressStateTransitionTo::Connected,
+        later,
+        &mut make_seedable_rng(Seed(1)),
+    );
+    assert_eq!(address_data.last_seen(), Some(later));
+    assert_eq!(address_data.last_connected(), Some(later));
+    assert_eq!(address_data.selection_weight(later, no_addresses), 1.0);
+}
@@ -76,11 +76,9 @@ impl AddressTables {
 
     /// Remove the specified address from "new" and put it into "tried".
     /// If an older "tried" address exists at the destination, move it into "new".
-    //
-    // TODO: in bitcoin they don't replace existing "tried" addresses right away; instead,
-    // they check if the old address is still reachable and if yes, keep it (the passed address
-    // stays in "new" in this case).
-    // Should we do the same?
+    ///
+    /// Note that the caller may want to check `tried_collision` first, to avoid replacing
+    /// an older address that is still reachable.
     #[must_use]
     pub fn move_to_tried(&mut self, addr: &SocketAddress) -> Option<MoveToTriedSideEffects> {
         self.new_addr_table.remove(addr);
@@ -131,6 +129,15 @@ impl AddressTables {
         self.tried_addr_table.replace(addr)
     }
 
+    /// Return the address that occupies the slot in the "tried" table where the specified
+    /// address would be put, if it's a different address.
+    pub fn tried_collision(&self, addr: &SocketAddress) -> Option<SocketAddress> {
+        self.tried_addr_table
+            .entry(addr)
+            .filter(|existing_addr| *existing_addr != addr)
+            .copied()
+    }
+
     pub fn is_in_new(&self, addr: &SocketAddress) -> bool {
         self.new_addr_table.entry(addr) == Some(addr)
     }
@@ -40,7 +40,10 @@ use std::{
 
 use common::{chain::ChainConfig, primitives::time::Time, time_getter::TimeGetter};
 use logging::log;
-use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress};
+use p2p_types::{
+    bannable_address::BannableAddress, peer_address::NetworkType, services::Services,
+    socket_address::SocketAddress,
+};
 use randomness::{make_pseudo_rng, seq::IteratorRandom, Rng, SliceRandom};
 
 use crate::config::P2pConfig;
@@ -49,7 +52,7 @@ use self::{
     address_data::{AddressData, AddressStateTransitionTo},
     address_tables::AddressTables,
     salt::Salt,
-    storage::{KnownAddressState, PeerDbStorage, PeerDbStorageWrite},
+    storage::{AddressConnectionStats, KnownAddressState, PeerDbStorage, PeerDbStorageWrite},
     storage_load::LoadedStorage,
 };
 
@@ -60,6 +63,13 @@ use super::{
 
 pub use storage_load::{open_storage, CURRENT_STORAGE_VERSION};
 
+/// The maximum number of "tried" table collisions waiting to be resolved, see `tried_collisions`.
+const MAX_TRIED_COLLISIONS: usize = 10;
+
+/// If a "tried" table collision hasn't been resolved by a feeler connection within this time,
+/// the older address is replaced anyway.
+pub const TRIED_COLLISION_RESOLUTION_TIMEOUT: Duration = Duration::from_secs(40 * 60);
+
 pub struct PeerDb<S> {
     /// P2P configuration
     p2p_config: Arc<P2pConfig>,
@@ -88,6 +98,19 @@ pub struct PeerDb<S> {
     /// Anchor addresses
     anchor_addresses: BTreeSet<SocketAddress>,
 
+    /// "New" addresses that we've successfully connected to, but whose slot in the "tried" table
+    /// is occupied by another address, along with the time of the connection.
+    ///
+    /// Instead of replacing the older address right away, a feeler connection is made to it
+    /// first and it's only replaced if it's no longer reachable (this is "test-before-evict"
+    /// from Bitcoin Core). This way an attacker can't flush the "tried" table by making us
+    /// connect to lots of its own addresses.
+    tried_collisions: BTreeMap<SocketAddress, Time>,
+
+    /// Addresses whose last connection time has changed since the last heartbeat;
+    /// the times are written to the DB in one transaction during the heartbeat.
+    unpersisted_last_connected: BTreeSet<SocketAddress>,
+
     time_getter: TimeGetter,
 
     storage: S,
@@ -108,6 +131,8 @@ impl<S: PeerDbStorage> PeerDb<S> {
             banned_addresses,
             discouraged_addresses,
             anchor_addresses,
+            address_connection_stats,
+            address_last_seen,
             salt,
         } = LoadedStorage::load_storage(&storage, &p2p_config.peer_manager_config.peerdb_config)?;
 
@@ -149,11 +174,21 @@ impl<S: PeerDbStorage> PeerDb<S> {
                 }
             }
 
-            let addr_data = AddressData::new(
+            let mut addr_data = AddressData::new(
                 *state == KnownAddressState::Tried,
                 reserved_nodes.contains(addr),
                 now,
             );
+            if let Some(stats) = address_connection_stats.get(addr) {
+                addr_data.restore_connection_stats(
+                    stats.fail_count,
+                    Time::from_duration_since_epoch(stats.last_attempt),
+                    &mut make_pseudo_rng(),
+                );
+            }
+            if let Some(last_connected) = address_last_seen.get(addr) {
+                addr_data.restore_last_connected(*last_connected);
+            }
             addresses.insert(*addr, addr_data);
         }
 
@@ -183,6 +218,8 @@ impl<S: PeerDbStorage> PeerDb<S> {
             banned_addresses,
             discouraged_addresses,
             anchor_addresses,
+            tried_collisions: BTreeMap::new(),
+            unpersisted_last_connected: BTreeSet::new(),
             p2p_config,
             time_getter,
             storage,
@@ -208,6 +245,16 @@ impl<S: PeerDbStorage> PeerDb<S> {
             .filter_map(|(addr, addr_data)| (!addr_data.is_unreachable()).then_some(addr))
     }
 
+    /// The networks that the node is known to be able to reach, i.e. those in which it has been
+    /// connected to some address at least once.
+    pub fn reachable_networks(&self) -> BTreeSet<NetworkType> {
+        self.addresses
+            .iter()
+            .filter(|(_addr, addr_data)| addr_data.was_reachable())
+            .map(|(addr, _addr_data)| addr.as_peer_address().network_type())
+            .collect()
+    }
+
     /// Selects peer addresses for outbound connections, excluding reserved ones.
     /// Only one outbound connection is allowed per address group.
     pub fn select_non_reserved_outbound_addresses(
@@ -253,14 +300,35 @@ impl<S: PeerDbStorage> PeerDb<S> {
             }
         };
 
+        // Within each table, the addresses are chosen based on their selection weights,
+        // i.e. the recently seen nodes with the needed services are preferred.
+        let needed_services = Services::from(*self.p2p_config.node_type).required();
+        let choose_weighted = |candidates: Vec<&SocketAddress>, rng: &mut _| {
+            candidates
+                .choose_multiple_weighted(rng, count, |addr| {
+                    self.addresses
+                        .get(*addr)
+                        .map_or(0.0, |data| data.selection_weight(now, needed_services))
+                })
+                .map(|selected| selected.copied().collect::<Vec<_>>())
+                .unwrap_or_else(|err| {
+                    debug_assert!(false, "Unexpected weighted selection error: {err}");
+                    Vec::new()
+                })
+        };
+
         // Note: here we want to select "new" and "tried" addresses with roughly equal probability.
         // To do so, we first select "count" addresses of each kind, shuffle the results and then
         // iteratively choose addresses from one of the vectors based on a randomly generated value.
-        let mut selected_new =
-            self.address_tables.new_addresses().filter(filter).choose_multiple(rng, count);
+        let mut selected_new = choose_weighted(
+            self.address_tables.new_addresses().filter(filter).collect(),
+            rng,
+        );
         selected_new.shuffle(rng);
-        let mut selected_tried =
-            self.address_tables.tried_addresses().filter(filter).choose_multiple(rng, count);
+        let mut selected_tried = choose_weighted(
+            self.address_tables.tried_addresses().filter(filter).collect(),
+            rng,
+        );
         selected_tried.shuffle(rng);
 
         let mut selected_new_iter = selected_new.into_iter().peekable();
@@ -317,6 +385,23 @@ impl<S: PeerDbStorage> PeerDb<S> {
             .choose(&mut make_pseudo_rng())
     }
 
+    /// Select the older address of a "tried" table collision, to check whether it's still
+    /// reachable via a feeler connection.
+    pub fn select_tried_collision_address_for_feeler(&self) -> Option<SocketAddress> {
+        self.tried_collisions
+            .keys()
+            .filter_map(|addr| self.address_tables.tried_collision(addr))
+            .filter(|tried_addr| match self.addresses.get(tried_addr) {
+                Some(addr_data) => {
+                    !addr_data.is_connected()
+                        && !addr_data.reserved()
+                        && !self.is_address_banned_or_discouraged(&tried_addr.as_bannable())
+                }
+                None => false,
+            })
+            .choose(&mut make_pseudo_rng())
+    }
+
     /// Selects reserved peer addresses for outbound connections
     pub fn select_reserved_outbound_addresses(
         &self,
@@ -339,18 +424,26 @@ impl<S: PeerDbStorage> PeerDb<S> {
     pub fn heartbeat(&mut self) {
         let now = self.time_getter.get_time();
 
+        self.resolve_tried_collisions(now);
+
         self.addresses.retain(|addr, address_data| {
             let retain = address_data.retain(now);
 
             if !retain {
                 self.address_tables.remove(addr);
-                update_db(&self.storage, |tx| tx.del_known_address(addr))
-                    .expect("DB failure when deleting known address {addr}");
+                update_db(&self.storage, |tx| {
+                    tx.del_known_address(addr)?;
+                    tx.del_address_connection_stats(addr)?;
+                    tx.del_address_last_seen(addr)
+                })
+                .expect("DB failure when deleting known address {addr}");
             }
 
             retain
         });
 
+        self.persist_last_connected();
+
         self.banned_addresses.retain(|addr, banned_till| {
             let banned = now < *banned_till;
 
@@ -394,6 +487,24 @@ impl<S: PeerDbStorage> PeerDb<S> {
         }
     }
 
+    /// Add a peer address received in the v2 address gossip, along with the information
+    /// about the node behind it.
+    pub fn peer_address_gossiped(
+        &mut self,
+        address: SocketAddress,
+        last_seen: Time,
+        services: Services,
+    ) {
+        self.peer_discovered(address);
+
+        // Don't trust timestamps from the future
+        let last_seen = std::cmp::min(last_seen, self.time_getter.get_time());
+        // Note: the gossiped time is not persisted, because it's not trusted.
+        if let Some(address_data) = self.addresses.get_mut(&address) {
+            address_data.update_from_gossip(last_seen, services);
+        }
+    }
+
     /// Report outbound connection failure
     ///
     /// When [`crate::peer_manager::PeerManager::heartbeat()`] has initiated an outbound connection
@@ -413,9 +524,79 @@ impl<S: PeerDbStorage> PeerDb<S> {
     /// it informs the `PeerDb` about it.
     pub fn outbound_peer_connected(&mut self, address: SocketAddress) {
         self.change_address_state(address, AddressStateTransitionTo::Connected);
+
+        if self.address_tables.is_in_new(&address) {
+            let tried_addr = self.address_tables.tried_collision(&address);
+            if let Some(tried_addr) = tried_addr.filter(|addr| !self.can_replace_tried_addr(addr)) {
+                if self.tried_collisions.len() < MAX_TRIED_COLLISIONS {
+                    log::debug!("Address {address} collides with the tried address {tried_addr}");
+                    self.tried_collisions.entry(address).or_insert(self.time_getter.get_time());
+                }
+                return;
+            }
+        }
+
+        self.tried_collisions.remove(&address);
         self.move_addr_to_tried(&address);
     }
 
+    fn can_replace_tried_addr(&self, tried_addr: &SocketAddress) -> bool {
+        self.addresses
+            .get(tried_addr)
+            .map_or(true, |addr_data| addr_data.is_unreachable())
+    }
+
+    /// Move the addresses of the "tried" table collisions into "tried" if the older addresses
+    /// turned out to be unreachable or weren't checked in time. If an older address
+    /// is reachable, it's kept and the new one stays in "new".
+    fn resolve_tried_collisions(&mut self, now: Time) {
+        let collisions = std::mem::take(&mut self.tried_collisions);
+
+        for (address, collision_time) in collisions {
+            // The address may have been removed or already moved since then.
+            if !self.address_tables.is_in_new(&address) {
+                continue;
+            }
+
+            let replace_tried_addr = match self.address_tables.tried_collision(&address) {
+                None => Some(true),
+                Some(tried_addr) => match self.addresses.get(&tried_addr) {
+                    None => Some(true),
+                    Some(addr_data) => {
+                        if addr_data.is_connected() {
+                            Some(false)
+                        } else if addr_data.is_unreachable() {
+                            Some(true)
+                        } else if addr_data.last_attempt().is_some_and(|t| t > collision_time) {
+                            // The older address has been checked since the collision.
+                            Some(addr_data.fail_count() > 0)
+                        } else if now
+                            >= collision_time
+                                .saturating_duration_add(TRIED_COLLISION_RESOLUTION_TIMEOUT)
+                        {
+                            Some(true)
+                        } else {
+                            None
+                        }
+                    }
+                },
+            };
+
+            match replace_tried_addr {
+                Some(true) => {
+                    log::debug!("Tried table collision resolved, moving {address} to tried");
+                    self.move_addr_to_tried(&address);
+                }
+                Some(false) => {
+                    log::debug!("Tried table collision resolved, {address} stays in new");
+                }
+                None => {
+                    self.tried_collisions.insert(address, collision_time);
+                }
+            }
+        }
+    }
+
     /// Handle peer disconnect event with unspecified reason
     pub fn outbound_peer_disconnected(&mut self, address: SocketAddress) {
         self.change_address_state(address, AddressStateTransitionTo::Disconnected);
@@ -427,8 +608,12 @@ impl<S: PeerDbStorage> PeerDb<S> {
         }
         self.address_tables.remove(address);
 
-        update_db(&self.storage, |tx| tx.del_known_address(address))
-            .expect("DB failure when removing known address {address}");
+        update_db(&self.storage, |tx| {
+            tx.del_known_address(address)?;
+            tx.del_address_connection_stats(address)?;
+            tx.del_address_last_seen(address)
+        })
+        .expect("DB failure when removing known address {address}");
     }
 
     fn move_addr_to_tried(&mut self, address: &SocketAddress) {
@@ -505,8 +690,12 @@ impl<S: PeerDbStorage> PeerDb<S> {
             if !self.reserved_nodes.contains(&address) {
                 self.addresses.remove(&address);
 
-                update_db(&self.storage, |tx| tx.del_known_address(&address))
-                    .expect("DB failure when deleting known address {address}");
+                update_db(&self.storage, |tx| {
+                    tx.del_known_address(&address)?;
+                    tx.del_address_connection_stats(&address)?;
+                    tx.del_address_last_seen(&address)
+                })
+                .expect("DB failure when deleting known address {address}");
             }
         }
     }
@@ -532,6 +721,68 @@ impl<S: PeerDbStorage> PeerDb<S> {
         );
 
         address_data.transition_to(transition, now, &mut make_pseudo_rng());
+
+        match transition {
+            AddressStateTransitionTo::Connected | AddressStateTransitionTo::ConnectionFailed => {
+                // Only the failures of known addresses are persisted; a successful connection
+                // resets the history.
+                let fail_count = address_data.fail_count();
+                let stats = address_data
+                    .last_attempt()
+                    .filter(|_| fail_count > 0 && self.address_tables.have_addr(&address))
+                    .map(|last_attempt| AddressConnectionStats {
+                        fail_count,
+                        last_attempt: last_attempt.as_duration_since_epoch(),
+                    });
+
+                update_db(&self.storage, |tx| match stats {
+                    Some(stats) => tx.set_address_connection_stats(&address, stats),
+                    None => tx.del_address_connection_stats(&address),
+                })
+                .expect("DB failure when updating address connection stats");
+            }
+            AddressStateTransitionTo::Disconnected
+            | AddressStateTransitionTo::SetReserved
+            | AddressStateTransitionTo::UnsetReserved => {}
+        }
+
+        match transition {
+            AddressStateTransitionTo::Connected | AddressStateTransitionTo::Disconnected => {
+                self.unpersisted_last_connected.insert(address);
+            }
+            AddressStateTransitionTo::ConnectionFailed
+            | AddressStateTransitionTo::SetReserved
+            | AddressStateTransitionTo::UnsetReserved => {}
+        }
+    }
+
+    /// Store the changed last connection times in the db; like the connection stats,
+    /// they are only stored for known addresses.
+    fn persist_last_connected(&mut self) {
+        let updates = std::mem::take(&mut self.unpersisted_last_connected)
+            .into_iter()
+            .filter(|address| self.address_tables.have_addr(address))
+            .filter_map(|address| {
+                self.addresses
+                    .get(&address)
+                    .and_then(|address_data| address_data.last_connected())
+                    .map(|last_connected| (address, last_connected))
+            })
+            .collect::<Vec<_>>();
+
+        if !updates.is_empty() {
+            update_db(&self.storage, |tx| {
+                for (address, last_connected) in &updates {
+                    tx.set_address_last_seen(address, *last_connected)?;
+                }
+                crate::Result::Ok(())
+            })
+            .expect("DB failure when updating address last connection times");
+        }
+    }
+
+    pub fn address_data(&self, address: &SocketAddress) -> Option<&AddressData> {
+        self.addresses.get(address)
     }
 
     pub fn is_reserved_node(&self, address: &SocketAddress) -> bool {
@@ -13,6 +13,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Duration;
+
 use common::primitives::time::Time;
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress};
 use serialization::{Decode, Encode};
@@ -31,6 +33,16 @@ pub enum KnownAddressState {
     Tried,
 }
 
+/// The outbound connection history of a known address, used to restore its reconnection
+/// schedule after the node is restarted.
+#[derive(Debug, Clone, Copy, Encode, Decode, Eq, PartialEq)]
+pub struct AddressConnectionStats {
+    /// The number of consecutive failed connection attempts
+    pub fail_count: u32,
+    /// The time of the last connection attempt (a timestamp since UNIX Epoch)
+    pub last_attempt: Duration,
+}
+
 pub trait PeerDbStorageRead {
     fn get_version(&self) -> crate::Result<Option<StorageVersion>>;
 
@@ -43,6 +55,12 @@ pub trait PeerDbStorageRead {
     fn get_discouraged_addresses(&self) -> crate::Result<Vec<(BannableAddress, Time)>>;
 
     fn get_anchor_addresses(&self) -> crate::Result<Vec<SocketAddress>>;
+
+    fn get_address_connection_stats(
+        &self,
+    ) -> crate::Result<Vec<(SocketAddress, AddressConnectionStats)>>;
+
+    fn get_address_last_seen(&self) -> crate::Result<Vec<(SocketAddress, Time)>>;
 }
 
 pub trait PeerDbStorageWrite {
@@ -71,6 +89,16 @@ pub trait PeerDbStorageWrite {
 
     fn add_anchor_address(&mut self, address: &SocketAddress) -> crate::Result<()>;
     fn del_anchor_address(&mut self, address: &SocketAddress) -> crate::Result<()>;
+
+    fn set_address_connection_stats(
+        &mut self,
+        address: &SocketAddress,
+        stats: AddressConnectionStats,
+    ) -> crate::Result<()>;
+    fn del_address_connection_stats(&mut self, address: &SocketAddress) -> crate::Result<()>;
+
+    fn set_address_last_seen(&mut self, address: &SocketAddress, time: Time) -> crate::Result<()>;
+    fn del_address_last_seen(&mut self, address: &SocketAddress) -> crate::Result<()>;
 }
 
 // Note: here we want to say something like:
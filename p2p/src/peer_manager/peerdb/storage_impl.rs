@@ -30,7 +30,10 @@ use crate::{
 
 use super::{
     salt::Salt,
-    storage::{KnownAddressState, PeerDbStorage, PeerDbStorageRead, PeerDbStorageWrite},
+    storage::{
+        AddressConnectionStats, KnownAddressState, PeerDbStorage, PeerDbStorageRead,
+        PeerDbStorageWrite,
+    },
 };
 
 type ValueId = u32;
@@ -54,6 +57,14 @@ storage::decl_schema! {
 
         /// Table for anchor peers addresses
         pub DBAnchorAddresses: Map<String, ()>,
+
+        /// Table for the outbound connection history of known addresses
+        /// (only addresses with failed connection attempts are stored)
+        pub DBAddressConnectionStats: Map<String, AddressConnectionStats>,
+
+        /// Table for the last time known addresses were seen online
+        /// (Duration is a timestamp since UNIX Epoch)
+        pub DBAddressLastSeen: Map<String, Duration>,
     }
 }
 
@@ -124,6 +135,35 @@ impl<'st, B: storage::Backend> PeerDbStorageWrite for PeerDbStoreTxRw<'st, B> {
     fn del_anchor_address(&mut self, address: &SocketAddress) -> crate::Result<()> {
         Ok(self.storage().get_mut::<DBAnchorAddresses, _>().del(address.to_string())?)
     }
+
+    fn set_address_connection_stats(
+        &mut self,
+        address: &SocketAddress,
+        stats: AddressConnectionStats,
+    ) -> crate::Result<()> {
+        Ok(self
+            .storage()
+            .get_mut::<DBAddressConnectionStats, _>()
+            .put(address.to_string(), stats)?)
+    }
+
+    fn del_address_connection_stats(&mut self, address: &SocketAddress) -> crate::Result<()> {
+        Ok(self
+            .storage()
+            .get_mut::<DBAddressConnectionStats, _>()
+            .del(address.to_string())?)
+    }
+
+    fn set_address_last_seen(&mut self, address: &SocketAddress, time: Time) -> crate::Result<()> {
+        Ok(self
+            .storage()
+            .get_mut::<DBAddressLastSeen, _>()
+            .put(address.to_string(), time.as_duration_since_epoch())?)
+    }
+
+    fn del_address_last_seen(&mut self, address: &SocketAddress) -> crate::Result<()> {
+        Ok(self.storage().get_mut::<DBAddressLastSeen, _>().del(address.to_string())?)
+    }
 }
 
 impl<'st, B: storage::Backend> PeerDbStorageRead for PeerDbStoreTxRo<'st, B> {
@@ -205,4 +245,32 @@ impl<'st, B: storage::Backend> PeerDbStorageRead for PeerDbStoreTxRo<'st, B> {
         });
         itertools::process_results(iter, |iter| iter.collect::<Vec<_>>())
     }
+
+    fn get_address_connection_stats(
+        &self,
+    ) -> crate::Result<Vec<(SocketAddress, AddressConnectionStats)>> {
+        let map = self.storage().get::<DBAddressConnectionStats, _>();
+        let iter = map.prefix_iter_decoded(&())?.map(|(addr_str, stats)| {
+            let addr = addr_str.parse::<SocketAddress>().map_err(|err| {
+                P2pError::InvalidStorageState(format!(
+                    "Error parsing address from {addr_str:?}: {err}"
+                ))
+            })?;
+            crate::Result::Ok((addr, stats))
+        });
+        itertools::process_results(iter, |iter| iter.collect::<Vec<_>>())
+    }
+
+    fn get_address_last_seen(&self) -> crate::Result<Vec<(SocketAddress, Time)>> {
+        let map = self.storage().get::<DBAddressLastSeen, _>();
+        let iter = map.prefix_iter_decoded(&())?.map(|(addr_str, dur)| {
+            let addr = addr_str.parse::<SocketAddress>().map_err(|err| {
+                P2pError::InvalidStorageState(format!(
+                    "Error parsing address from {addr_str:?}: {err}"
+                ))
+            })?;
+            Ok((addr, Time::from_duration_since_epoch(dur)))
+        });
+        itertools::process_results(iter, |iter| iter.collect::<Vec<_>>())
+    }
 }
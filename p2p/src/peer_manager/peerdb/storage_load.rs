@@ -26,7 +26,10 @@ use crate::{
 use super::{
     config::PeerDbConfig,
     salt::Salt,
-    storage::{KnownAddressState, PeerDbStorage, PeerDbStorageRead, PeerDbStorageWrite},
+    storage::{
+        AddressConnectionStats, KnownAddressState, PeerDbStorage, PeerDbStorageRead,
+        PeerDbStorageWrite,
+    },
     storage_impl::PeerDbStorageImpl,
 };
 
@@ -37,6 +40,8 @@ pub struct LoadedStorage {
     pub banned_addresses: BTreeMap<BannableAddress, Time>,
     pub discouraged_addresses: BTreeMap<BannableAddress, Time>,
     pub anchor_addresses: BTreeSet<SocketAddress>,
+    pub address_connection_stats: BTreeMap<SocketAddress, AddressConnectionStats>,
+    pub address_last_seen: BTreeMap<SocketAddress, Time>,
     pub salt: Salt,
 }
 
@@ -75,6 +80,8 @@ impl LoadedStorage {
             banned_addresses: BTreeMap::new(),
             discouraged_addresses: BTreeMap::new(),
             anchor_addresses: BTreeSet::new(),
+            address_connection_stats: BTreeMap::new(),
+            address_last_seen: BTreeMap::new(),
             salt,
         })
     }
@@ -91,6 +98,13 @@ impl LoadedStorage {
 
         let anchor_addresses = tx.get_anchor_addresses()?.into_iter().collect::<BTreeSet<_>>();
 
+        // Note: this table didn't exist in the older v3 databases, in which case it's just empty.
+        let address_connection_stats =
+            tx.get_address_connection_stats()?.into_iter().collect::<BTreeMap<_, _>>();
+
+        // Same for this one.
+        let address_last_seen = tx.get_address_last_seen()?.into_iter().collect::<BTreeMap<_, _>>();
+
         let salt = tx
             .get_salt()?
             .ok_or_else(|| P2pError::InvalidStorageState("Missing salt".to_owned()))?;
@@ -100,6 +114,8 @@ impl LoadedStorage {
             banned_addresses,
             discouraged_addresses,
             anchor_addresses,
+            address_connection_stats,
+            address_last_seen,
             salt,
         })
     }
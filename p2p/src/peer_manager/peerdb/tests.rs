@@ -16,6 +16,7 @@
 use std::{
     collections::{BTreeMap, BTreeSet},
     fmt::Debug,
+    net::{IpAddr, SocketAddr},
     sync::Arc,
     time::Duration,
 };
@@ -29,7 +30,7 @@ use ::test_utils::{
 };
 use common::{chain::config::create_unit_test_config, primitives::time::Time};
 use networking::test_helpers::TestAddressMaker;
-use p2p_types::socket_addr_ext::SocketAddrExt;
+use p2p_types::{peer_address::NetworkType, services::Services, socket_addr_ext::SocketAddrExt};
 use randomness::Rng;
 
 use crate::{
@@ -51,7 +52,7 @@ use crate::{
 use super::{
     address_tables::{
         table::Table,
-        test_utils::{make_non_colliding_addresses, make_random_address},
+        test_utils::{make_colliding_address, make_non_colliding_addresses, make_random_address},
     },
     config::PeerDbConfig,
     storage::PeerDbStorage,
@@ -441,6 +442,143 @@ fn connected_unknown(#[case] seed: Seed) {
     assert_addr_consistency(&peerdb);
 }
 
+// A network is only considered reachable once the node has connected to some address in it.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn reachable_networks(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let db_store = peerdb_inmemory_store();
+    let time_getter = BasicTestTimeGetter::new();
+    let chain_config = create_unit_test_config();
+    let p2p_config = Arc::new(test_p2p_config());
+    let mut peerdb = PeerDb::new(
+        &chain_config,
+        p2p_config,
+        time_getter.get_time_getter(),
+        db_store,
+    )
+    .unwrap();
+
+    let ipv4_address = SocketAddr::new(
+        IpAddr::V4(TestAddressMaker::new_random_ipv4_addr(&mut rng)),
+        rng.gen(),
+    )
+    .into();
+    let ipv6_address = TestAddressMaker::new_random_address(&mut rng).into();
+    peerdb.peer_discovered(ipv4_address);
+    peerdb.peer_discovered(ipv6_address);
+    assert_eq!(peerdb.reachable_networks(), BTreeSet::new());
+
+    peerdb.report_outbound_failure(ipv4_address);
+    peerdb.outbound_peer_connected(ipv6_address);
+    assert_eq!(
+        peerdb.reachable_networks(),
+        BTreeSet::from([NetworkType::Ipv6])
+    );
+
+    // The network stays reachable after the disconnection
+    peerdb.outbound_peer_disconnected(ipv6_address);
+    assert_eq!(
+        peerdb.reachable_networks(),
+        BTreeSet::from([NetworkType::Ipv6])
+    );
+
+    assert_addr_consistency(&peerdb);
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TriedCollisionFeelerResult {
+    Succeeded,
+    Failed,
+    NotMade,
+}
+
+// Make a "new" address collide with a "tried" one, check that the "tried" address is only
+// replaced if a feeler connection to it fails or isn't made in time.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn tried_collision(
+    #[case] seed: Seed,
+    #[values(
+        TriedCollisionFeelerResult::Succeeded,
+        TriedCollisionFeelerResult::Failed,
+        TriedCollisionFeelerResult::NotMade
+    )]
+    feeler_result: TriedCollisionFeelerResult,
+) {
+    let mut rng = make_seedable_rng(seed);
+
+    let db_store = peerdb_inmemory_store();
+    let time_getter = BasicTestTimeGetter::new();
+    let p2p_config = Arc::new(test_p2p_config());
+    let chain_config = create_unit_test_config();
+    let mut peerdb = PeerDb::new(
+        &chain_config,
+        p2p_config,
+        time_getter.get_time_getter(),
+        db_store,
+    )
+    .unwrap();
+
+    let tried_addr = make_random_address(&mut rng);
+    peerdb.outbound_peer_connected(tried_addr);
+    peerdb.outbound_peer_disconnected(tried_addr);
+    assert!(peerdb.address_tables.is_in_tried(&tried_addr));
+
+    let new_addr = make_colliding_address(tried_addr_table(&peerdb), &tried_addr);
+    peerdb.peer_discovered(new_addr);
+    assert!(peerdb.address_tables.is_in_new(&new_addr));
+
+    // The connection succeeds, but the address stays in "new" until the collision is resolved.
+    time_getter.advance_time(Duration::from_secs(1));
+    peerdb.outbound_peer_connected(new_addr);
+    assert!(peerdb.address_tables.is_in_new(&new_addr));
+    assert!(peerdb.address_tables.is_in_tried(&tried_addr));
+    assert!(peerdb.tried_collisions.contains_key(&new_addr));
+    assert_eq!(
+        peerdb.select_tried_collision_address_for_feeler(),
+        Some(tried_addr)
+    );
+    assert_addr_consistency(&peerdb);
+
+    peerdb.heartbeat();
+    assert!(peerdb.tried_collisions.contains_key(&new_addr));
+
+    time_getter.advance_time(Duration::from_secs(1));
+    match feeler_result {
+        TriedCollisionFeelerResult::Succeeded => {
+            peerdb.outbound_peer_connected(tried_addr);
+            peerdb.outbound_peer_disconnected(tried_addr);
+        }
+        TriedCollisionFeelerResult::Failed => {
+            peerdb.report_outbound_failure(tried_addr);
+        }
+        TriedCollisionFeelerResult::NotMade => {
+            time_getter.advance_time(super::TRIED_COLLISION_RESOLUTION_TIMEOUT);
+        }
+    }
+    peerdb.heartbeat();
+
+    assert!(peerdb.tried_collisions.is_empty());
+    assert_eq!(peerdb.select_tried_collision_address_for_feeler(), None);
+    match feeler_result {
+        TriedCollisionFeelerResult::Succeeded => {
+            assert!(peerdb.address_tables.is_in_new(&new_addr));
+            assert!(peerdb.address_tables.is_in_tried(&tried_addr));
+        }
+        TriedCollisionFeelerResult::Failed | TriedCollisionFeelerResult::NotMade => {
+            assert!(peerdb.address_tables.is_in_tried(&new_addr));
+            assert!(peerdb.address_tables.is_in_new(&tried_addr));
+        }
+    }
+    assert_addr_consistency(&peerdb);
+}
+
 #[tracing::instrument(skip(seed))]
 #[rstest]
 #[trace]
@@ -500,6 +638,140 @@ fn anchor_peers(#[case] seed: Seed) {
     assert_addr_consistency(&peerdb);
 }
 
+// Fail to connect to an address several times, restart the peerdb and check that the failures
+// are remembered; then connect to it successfully and check that they are forgotten.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn connection_stats_persisted(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let db_store = peerdb_inmemory_store();
+    let time_getter = BasicTestTimeGetter::new();
+    let chain_config = create_unit_test_config();
+    let p2p_config = Arc::new(test_p2p_config());
+
+    let mut peerdb = PeerDb::new(
+        &chain_config,
+        Arc::clone(&p2p_config),
+        time_getter.get_time_getter(),
+        db_store,
+    )
+    .unwrap();
+
+    let address = TestAddressMaker::new_random_address(&mut rng).into();
+    peerdb.peer_discovered(address);
+    peerdb.outbound_peer_connected(address);
+    peerdb.outbound_peer_disconnected(address);
+
+    let fail_count = rng.gen_range(1..10);
+    for _ in 0..fail_count {
+        time_getter.advance_time(Duration::from_secs(rng.gen_range(1..100)));
+        peerdb.report_outbound_failure(address);
+    }
+    let last_attempt = time_getter.get_time_getter().get_time();
+    assert_eq!(
+        peerdb.address_data(&address).unwrap().fail_count(),
+        fail_count
+    );
+    assert_addr_consistency(&peerdb);
+
+    time_getter.advance_time(Duration::from_secs(rng.gen_range(1..100)));
+    let mut peerdb = PeerDb::new(
+        &chain_config,
+        Arc::clone(&p2p_config),
+        time_getter.get_time_getter(),
+        peerdb.storage,
+    )
+    .unwrap();
+    let address_data = peerdb.address_data(&address).unwrap();
+    assert_eq!(address_data.fail_count(), fail_count);
+    assert_eq!(address_data.last_attempt(), Some(last_attempt));
+    assert_addr_consistency(&peerdb);
+
+    peerdb.outbound_peer_connected(address);
+    let peerdb = PeerDb::new(
+        &chain_config,
+        Arc::clone(&p2p_config),
+        time_getter.get_time_getter(),
+        peerdb.storage,
+    )
+    .unwrap();
+    let address_data = peerdb.address_data(&address).unwrap();
+    assert_eq!(address_data.fail_count(), 0);
+    assert_eq!(address_data.last_attempt(), None);
+    assert_addr_consistency(&peerdb);
+}
+
+// Receive the address via gossip, restart the peerdb and check that the untrusted last-seen time
+// is not remembered; then connect to it and check that the time of the connection is remembered
+// after the heartbeat.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn last_seen_persisted(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let db_store = peerdb_inmemory_store();
+    let time_getter = BasicTestTimeGetter::new();
+    let chain_config = create_unit_test_config();
+    let p2p_config = Arc::new(test_p2p_config());
+
+    let mut peerdb = PeerDb::new(
+        &chain_config,
+        Arc::clone(&p2p_config),
+        time_getter.get_time_getter(),
+        db_store,
+    )
+    .unwrap();
+
+    time_getter.advance_time(Duration::from_secs(rng.gen_range(1000..100000)));
+    let address = TestAddressMaker::new_random_address(&mut rng).into();
+    let last_seen = (time_getter.get_time_getter().get_time()
+        - Duration::from_secs(rng.gen_range(1..1000)))
+    .unwrap();
+    peerdb.peer_address_gossiped(address, last_seen, Services::from_u64(0));
+    assert_addr_consistency(&peerdb);
+
+    time_getter.advance_time(Duration::from_secs(rng.gen_range(1..100)));
+    let mut peerdb = PeerDb::new(
+        &chain_config,
+        Arc::clone(&p2p_config),
+        time_getter.get_time_getter(),
+        peerdb.storage,
+    )
+    .unwrap();
+    assert_eq!(peerdb.address_data(&address).unwrap().last_seen(), None);
+
+    time_getter.advance_time(Duration::from_secs(rng.gen_range(1..100)));
+    peerdb.outbound_peer_connected(address);
+    let connected_at = time_getter.get_time_getter().get_time();
+    assert_addr_consistency(&peerdb);
+    {
+        let tx = peerdb.storage.transaction_ro().unwrap();
+        assert!(tx.get_address_last_seen().unwrap().is_empty());
+    }
+    peerdb.heartbeat();
+
+    let mut peerdb = PeerDb::new(
+        &chain_config,
+        Arc::clone(&p2p_config),
+        time_getter.get_time_getter(),
+        peerdb.storage,
+    )
+    .unwrap();
+    let address_data = peerdb.address_data(&address).unwrap();
+    assert_eq!(address_data.last_seen(), Some(connected_at));
+    assert_eq!(address_data.last_connected(), Some(connected_at));
+
+    peerdb.remove_address(&address);
+    assert_addr_consistency(&peerdb);
+    let tx = peerdb.storage.transaction_ro().unwrap();
+    assert!(tx.get_address_last_seen().unwrap().is_empty());
+}
+
 // Call 'remove_address' on new and tried addresses, check that the db is
 // in consistent state.
 #[tracing::instrument(skip(seed))]
@@ -933,6 +1205,24 @@ fn assert_addr_consistency<S: PeerDbStorage>(peerdb: &PeerDb<S>) {
         tx.get_known_addresses().unwrap().iter().copied().collect::<BTreeMap<_, _>>()
     };
 
+    // Connection stats are only stored for known addresses.
+    let db_connection_stats = {
+        let tx = peerdb.storage.transaction_ro().unwrap();
+        tx.get_address_connection_stats().unwrap()
+    };
+    for (addr, _) in &db_connection_stats {
+        assert!(db_addrs.contains_key(addr));
+    }
+
+    // Same for the last-seen times.
+    let db_last_seen = {
+        let tx = peerdb.storage.transaction_ro().unwrap();
+        tx.get_address_last_seen().unwrap()
+    };
+    for (addr, _) in &db_last_seen {
+        assert!(db_addrs.contains_key(addr));
+    }
+
     // Addresses in the db and in peerdb.addresses are the same, if not taking "reserved"
     // ones into account.
     assert_eq_sets_if_not_in(
@@ -30,6 +30,7 @@ make_config_setting!(PreservedInboundCountAddressGroup, usize, 4);
 make_config_setting!(PreservedInboundCountPing, usize, 8);
 make_config_setting!(PreservedInboundCountNewBlocks, usize, 8);
 make_config_setting!(PreservedInboundCountNewTransactions, usize, 4);
+make_config_setting!(PreservedInboundCountAge, usize, 8);
 
 make_config_setting!(
     OutboundBlockRelayConnectionMinAge,
@@ -70,6 +71,10 @@ pub struct EvictionCandidate {
 
     /// Whether the address is banned or discouraged.
     is_banned_or_discouraged: bool,
+
+    /// Whether this is the only outbound full relay or manual connection to the peer's network
+    /// (in which case the peer is protected from eviction).
+    is_only_outbound_to_network: bool,
 }
 
 pub struct RandomState(u64, u64);
@@ -92,6 +97,7 @@ impl EvictionCandidate {
         random_state: &RandomState,
         now: Time,
         is_banned_or_discouraged: bool,
+        is_only_outbound_to_network: bool,
     ) -> Self {
         EvictionCandidate {
             age: now.saturating_sub(peer.created_at),
@@ -107,6 +113,7 @@ impl EvictionCandidate {
             expecting_blocks_since: peer.block_sync_status.expecting_blocks_since,
 
             is_banned_or_discouraged,
+            is_only_outbound_to_network,
         }
     }
 }
@@ -161,6 +168,32 @@ fn filter_by_last_transaction_time(
     candidates
 }
 
+// Preserve the longest-connected nodes.
+// An attacker would have to keep its connections open for a long time before the eviction
+// to take over these slots.
+fn filter_by_age(mut candidates: Vec<EvictionCandidate>, count: usize) -> Vec<EvictionCandidate> {
+    candidates.sort_unstable_by_key(|peer| peer.age);
+    candidates.truncate(candidates.len().saturating_sub(count));
+    candidates
+}
+
+// Filter out the inbound peers that are preserved according to the config.
+fn filter_preserved_inbound(
+    candidates: Vec<EvictionCandidate>,
+    config: &PeerManagerConfig,
+) -> Vec<EvictionCandidate> {
+    let candidates =
+        filter_address_group(candidates, *config.preserved_inbound_count_address_group);
+    let candidates = filter_fast_ping(candidates, *config.preserved_inbound_count_ping);
+    let candidates =
+        filter_by_last_tip_block_time(candidates, *config.preserved_inbound_count_new_blocks);
+    let candidates = filter_by_last_transaction_time(
+        candidates,
+        *config.preserved_inbound_count_new_transactions,
+    );
+    filter_by_age(candidates, *config.preserved_inbound_count_age)
+}
+
 fn find_group_most_connections(candidates: Vec<EvictionCandidate>) -> Option<PeerId> {
     if candidates.is_empty() {
         return None;
@@ -210,19 +243,61 @@ pub fn select_for_eviction_inbound(
         return Some(candidate.peer_id);
     }
 
-    let candidates =
-        filter_address_group(candidates, *config.preserved_inbound_count_address_group);
-    let candidates = filter_fast_ping(candidates, *config.preserved_inbound_count_ping);
-    let candidates =
-        filter_by_last_tip_block_time(candidates, *config.preserved_inbound_count_new_blocks);
-    let candidates = filter_by_last_transaction_time(
-        candidates,
-        *config.preserved_inbound_count_new_transactions,
-    );
+    let candidates = filter_preserved_inbound(candidates, config);
 
     find_group_most_connections(candidates)
 }
 
+/// Select an inbound peer to evict if some address group has more inbound connections than
+/// `max_inbound_connections_per_address_group`.
+///
+/// The peer is selected from the most represented of such groups, the preserved peers
+/// are never selected (same as in `select_for_eviction_inbound`).
+#[must_use]
+pub fn select_for_eviction_inbound_address_group(
+    candidates: Vec<EvictionCandidate>,
+    config: &PeerManagerConfig,
+    rng: &mut impl Rng,
+) -> Option<PeerId> {
+    debug_assert!(candidates.iter().all(|c| c.peer_role == PeerRole::Inbound));
+
+    let max_per_group = *config.max_inbound_connections_per_address_group;
+    let counts = candidates.iter().fold(BTreeMap::<NetGroupKeyed, usize>::new(), |mut acc, c| {
+        *acc.entry(c.net_group_keyed).or_insert(0) += 1;
+        acc
+    });
+    let mut groups_over_limit = counts
+        .into_iter()
+        .filter(|(_group_id, count)| *count > max_per_group)
+        .collect::<Vec<_>>();
+    if groups_over_limit.is_empty() {
+        return None;
+    }
+
+    if let Some(candidate) = candidates
+        .iter()
+        .filter(|ec| {
+            ec.is_banned_or_discouraged
+                && groups_over_limit.iter().any(|(group_id, _)| *group_id == ec.net_group_keyed)
+        })
+        .choose(rng)
+    {
+        return Some(candidate.peer_id);
+    }
+
+    let candidates = filter_preserved_inbound(candidates, config);
+
+    // Evict the youngest non-preserved peer from the most represented group
+    groups_over_limit.sort_by_key(|(_group_id, count)| std::cmp::Reverse(*count));
+    groups_over_limit.iter().find_map(|(group_id, _)| {
+        candidates
+            .iter()
+            .filter(|c| c.net_group_keyed == *group_id)
+            .min_by_key(|peer| peer.age)
+            .map(|peer| peer.peer_id)
+    })
+}
+
 #[must_use]
 pub fn select_for_eviction_block_relay(
     candidates: Vec<EvictionCandidate>,
@@ -247,10 +322,9 @@ pub fn select_for_eviction_full_relay(
     now: Time,
     rng: &mut impl Rng,
 ) -> Option<PeerId> {
-    // TODO: in bitcoin they protect full relay peers from eviction if there are no other
-    // connection to their network (counting outbound-full-relay and manual peers). We should
-    // probably do the same.
-    // See https://github.com/mintlayer/mintlayer-core/issues/1432
+    // Note: like in bitcoin, full relay peers are protected from eviction if there are no other
+    // connections to their network (counting outbound-full-relay and manual peers), see
+    // `is_only_outbound_to_network`.
     select_for_eviction_outbound(
         candidates,
         PeerRole::OutboundFullRelay,
@@ -321,8 +395,11 @@ fn select_for_eviction_outbound(
         return None;
     }
 
+    // Peers that are the only outbound connections to their networks still count towards
+    // the limit, but are never evicted.
     let mut candidates: Vec<_> = candidates
         .into_iter()
+        .filter(|ec| !ec.is_only_outbound_to_network)
         .map(|ec| EvictionCandidateExtOutbound::new(ec, now))
         .collect();
 
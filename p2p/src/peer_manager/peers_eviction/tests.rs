@@ -38,6 +38,7 @@ fn random_candidate(peer_role: PeerRole, rng: &mut impl Rng) -> EvictionCandidat
         last_tx_time: Some(Time::from_secs_since_epoch(rng.gen_range(0..10000))),
         expecting_blocks_since: Some(Time::from_secs_since_epoch(rng.gen_range(0..10000))),
         is_banned_or_discouraged: false,
+        is_only_outbound_to_network: false,
     }
 }
 
@@ -66,6 +67,7 @@ mod inbound {
                 last_tx_time: None,
                 expecting_blocks_since: None,
                 is_banned_or_discouraged: false,
+                is_only_outbound_to_network: false,
             }
         }
 
@@ -126,6 +128,7 @@ mod inbound {
                 last_tx_time: None,
                 expecting_blocks_since: None,
                 is_banned_or_discouraged: false,
+                is_only_outbound_to_network: false,
             }
         }
 
@@ -186,6 +189,7 @@ mod inbound {
                 last_tx_time: None,
                 expecting_blocks_since: None,
                 is_banned_or_discouraged: false,
+                is_only_outbound_to_network: false,
             }
         }
 
@@ -243,6 +247,7 @@ mod inbound {
                 last_tx_time: last_tx_time_secs.map(Time::from_secs_since_epoch),
                 expecting_blocks_since: None,
                 is_banned_or_discouraged: false,
+                is_only_outbound_to_network: false,
             }
         }
 
@@ -278,6 +283,61 @@ mod inbound {
         );
     }
 
+    #[tracing::instrument(skip(seed))]
+    #[rstest::rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn test_filter_by_age(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let peer1 = PeerId::new();
+        let peer2 = PeerId::new();
+        let peer3 = PeerId::new();
+
+        fn make_candidate(peer_id: PeerId, age_secs: u64) -> EvictionCandidate {
+            EvictionCandidate {
+                age: Duration::from_secs(age_secs),
+                peer_id,
+                net_group_keyed: NetGroupKeyed(1),
+                ping_min: 123,
+                peer_role: PeerRole::Inbound,
+                last_tip_block_time: None,
+                last_tx_time: None,
+                expecting_blocks_since: None,
+                is_banned_or_discouraged: false,
+                is_only_outbound_to_network: false,
+            }
+        }
+
+        assert_eq!(filter_by_age(vec![make_candidate(peer1, 100)], 1), vec![]);
+
+        assert_eq!(
+            filter_by_age(
+                shuffle_vec(
+                    vec![make_candidate(peer1, 100), make_candidate(peer2, 10)],
+                    &mut rng
+                ),
+                1
+            ),
+            vec![make_candidate(peer2, 10)]
+        );
+
+        assert_eq!(
+            filter_by_age(
+                shuffle_vec(
+                    vec![
+                        make_candidate(peer1, 100),
+                        make_candidate(peer2, 10),
+                        make_candidate(peer3, 1000)
+                    ],
+                    &mut rng
+                ),
+                2
+            ),
+            vec![make_candidate(peer2, 10)]
+        );
+    }
+
     #[tracing::instrument(skip(seed))]
     #[rstest::rstest]
     #[trace]
@@ -300,6 +360,7 @@ mod inbound {
                 last_tx_time: None,
                 expecting_blocks_since: None,
                 is_banned_or_discouraged: false,
+                is_only_outbound_to_network: false,
             }
         }
 
@@ -375,6 +436,16 @@ mod inbound {
         index < *config.preserved_inbound_count_new_transactions
     }
 
+    fn test_preserved_by_age(
+        index: usize,
+        candidate: &mut EvictionCandidate,
+        config: &PeerManagerConfig,
+    ) -> bool {
+        // Check that `preserved_inbound_count_age` longest-connected peers are preserved
+        candidate.age = Duration::MAX - Duration::from_secs(index as u64);
+        index < *config.preserved_inbound_count_age
+    }
+
     #[tracing::instrument(skip(seed))]
     #[rstest]
     #[trace]
@@ -388,6 +459,7 @@ mod inbound {
             test_preserved_by_address_group,
             test_preserved_by_last_block_time,
             test_preserved_by_last_tx_time,
+            test_preserved_by_age,
         ];
 
         for _ in 0..10 {
@@ -421,6 +493,119 @@ mod inbound {
     }
 }
 
+mod inbound_address_group {
+    use super::*;
+
+    fn make_candidate(net_group_keyed: u64, age_secs: u64) -> EvictionCandidate {
+        EvictionCandidate {
+            age: Duration::from_secs(age_secs),
+            peer_id: PeerId::new(),
+            net_group_keyed: NetGroupKeyed(net_group_keyed),
+            ping_min: 123,
+            peer_role: PeerRole::Inbound,
+            last_tip_block_time: None,
+            last_tx_time: None,
+            expecting_blocks_since: None,
+            is_banned_or_discouraged: false,
+            is_only_outbound_to_network: false,
+        }
+    }
+
+    // Only the preservation by age is enabled, for the result to be predictable.
+    fn config_with_group_limit(max_per_group: usize, preserved_by_age: usize) -> PeerManagerConfig {
+        PeerManagerConfig {
+            max_inbound_connections_per_address_group: max_per_group.into(),
+            preserved_inbound_count_address_group: 0.into(),
+            preserved_inbound_count_ping: 0.into(),
+            preserved_inbound_count_new_blocks: 0.into(),
+            preserved_inbound_count_new_transactions: 0.into(),
+            preserved_inbound_count_age: preserved_by_age.into(),
+            ..Default::default()
+        }
+    }
+
+    #[tracing::instrument(skip(seed))]
+    #[rstest::rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn test(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        // Sorted by age: group3, group2_young, group2_old, group1_youngest, group1_young, group1_old.
+        // The younger peers are created first, so that their peer ids are smaller.
+        let group3 = make_candidate(3, 1);
+        let group2_young = make_candidate(2, 2);
+        let group1_youngest = make_candidate(1, 800);
+        let group2_old = make_candidate(2, 500);
+        let group1_young = make_candidate(1, 900);
+        let group1_old = make_candidate(1, 1000);
+
+        let candidates = vec![
+            group1_old.clone(),
+            group1_young.clone(),
+            group1_youngest.clone(),
+            group2_old.clone(),
+            group2_young.clone(),
+            group3.clone(),
+        ];
+
+        let mut select = |config: &PeerManagerConfig| {
+            select_for_eviction_inbound_address_group(
+                shuffle_vec(candidates.clone(), &mut rng),
+                config,
+                &mut rng,
+            )
+        };
+
+        // No group is over the limit
+        assert_eq!(select(&config_with_group_limit(3, 0)), None);
+        assert_eq!(select(&Default::default()), None);
+
+        // The youngest non-preserved peer of the most represented group is evicted
+        assert_eq!(
+            select(&config_with_group_limit(1, 0)),
+            Some(group1_youngest.peer_id)
+        );
+        assert_eq!(
+            select(&config_with_group_limit(1, 2)),
+            Some(group1_youngest.peer_id)
+        );
+
+        // All the peers of the most represented group are preserved
+        assert_eq!(
+            select(&config_with_group_limit(1, 3)),
+            Some(group2_young.peer_id)
+        );
+
+        // Only the peer from the group that is within the limit remains
+        assert_eq!(select(&config_with_group_limit(1, 5)), None);
+
+        // A discouraged peer from a group that is over the limit is evicted first
+        let mut discouraged_group2_old = group2_old.clone();
+        discouraged_group2_old.is_banned_or_discouraged = true;
+        let mut discouraged_group3 = group3.clone();
+        discouraged_group3.is_banned_or_discouraged = true;
+        assert_eq!(
+            select_for_eviction_inbound_address_group(
+                shuffle_vec(
+                    vec![
+                        group1_old,
+                        group1_young,
+                        group1_youngest,
+                        discouraged_group2_old.clone(),
+                        group2_young,
+                        discouraged_group3,
+                    ],
+                    &mut rng
+                ),
+                &config_with_group_limit(1, 6),
+                &mut rng
+            ),
+            Some(discouraged_group2_old.peer_id)
+        );
+    }
+}
+
 mod outbound {
     use super::*;
 
@@ -495,6 +680,7 @@ mod outbound {
                 last_tx_time: None,
                 expecting_blocks_since: None,
                 is_banned_or_discouraged: false,
+                is_only_outbound_to_network: false,
             }
         };
 
@@ -575,6 +761,7 @@ mod outbound {
                 expecting_blocks_since: expecting_blocks_since_secs
                     .map(Time::from_secs_since_epoch),
                 is_banned_or_discouraged: false,
+                is_only_outbound_to_network: false,
             }
         };
 
@@ -693,6 +880,7 @@ mod outbound {
                 expecting_blocks_since: expecting_blocks_since_secs
                     .map(Time::from_secs_since_epoch),
                 is_banned_or_discouraged: false,
+                is_only_outbound_to_network: false,
             }
         };
 
@@ -776,6 +964,77 @@ mod outbound {
         );
     }
 
+    #[tracing::instrument(skip(seed))]
+    #[rstest::rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn test_only_outbound_to_network(#[case] seed: Seed) {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let peer1 = PeerId::new();
+        let peer2 = PeerId::new();
+        let peer3 = PeerId::new();
+
+        let now = Time::from_secs_since_epoch(100000);
+        let min_age = Duration::from_secs(5000);
+        let config = config_with_conn_limits(OutboundConnType::FullRelay, 2, min_age);
+
+        let make_candidate = |peer_id: PeerId,
+                              last_tip_block_time_secs: u64,
+                              is_only_outbound_to_network: bool|
+         -> EvictionCandidate {
+            EvictionCandidate {
+                age: Duration::from_secs(10000),
+                peer_id,
+                net_group_keyed: NetGroupKeyed(1),
+                ping_min: 123,
+                peer_role: PeerRole::OutboundFullRelay,
+                last_tip_block_time: Some(Time::from_secs_since_epoch(last_tip_block_time_secs)),
+                last_tx_time: None,
+                expecting_blocks_since: None,
+                is_banned_or_discouraged: false,
+                is_only_outbound_to_network,
+            }
+        };
+
+        // The peer that sent blocks a long time ago would normally be evicted, but it's
+        // the only connection to its network, so the next worst peer is evicted instead.
+        let candidates = vec![
+            make_candidate(peer1, 10000, true),
+            make_candidate(peer2, 20000, false),
+            make_candidate(peer3, 30000, false),
+        ];
+        let candidates = shuffle_vec(candidates, &mut rng);
+        assert_eq!(
+            select_for_eviction(
+                OutboundConnType::FullRelay,
+                candidates,
+                &config,
+                now,
+                &mut rng
+            ),
+            Some(peer2)
+        );
+
+        // If all peers are protected, nothing is evicted.
+        let candidates = vec![
+            make_candidate(peer1, 10000, true),
+            make_candidate(peer2, 20000, true),
+            make_candidate(peer3, 30000, true),
+        ];
+        let candidates = shuffle_vec(candidates, &mut rng);
+        assert_eq!(
+            select_for_eviction(
+                OutboundConnType::FullRelay,
+                candidates,
+                &config,
+                now,
+                &mut rng
+            ),
+            None
+        );
+    }
+
     fn config_with_block_relay_conn_limits(
         max_connections: usize,
         min_age: Duration,
@@ -786,13 +1045,16 @@ mod outbound {
 
             // Connection count limits that should not influence tests' behavior are set to MAX.
             max_inbound_connections: usize::MAX.into(),
+            max_inbound_connections_per_address_group: Default::default(),
             preserved_inbound_count_address_group: usize::MAX.into(),
             preserved_inbound_count_ping: usize::MAX.into(),
             preserved_inbound_count_new_blocks: usize::MAX.into(),
             preserved_inbound_count_new_transactions: usize::MAX.into(),
+            preserved_inbound_count_age: usize::MAX.into(),
             outbound_full_relay_count: usize::MAX.into(),
             outbound_full_relay_extra_count: usize::MAX.into(),
             outbound_block_relay_extra_count: usize::MAX.into(),
+            max_outbound_connections_per_network: Default::default(),
             outbound_full_relay_connection_min_age: Duration::MAX.into(),
 
             // Other values are irrelevant
@@ -816,13 +1078,16 @@ mod outbound {
 
             // Connection count limits that should not influence tests' behavior are set to MAX.
             max_inbound_connections: usize::MAX.into(),
+            max_inbound_connections_per_address_group: Default::default(),
             preserved_inbound_count_address_group: usize::MAX.into(),
             preserved_inbound_count_ping: usize::MAX.into(),
             preserved_inbound_count_new_blocks: usize::MAX.into(),
             preserved_inbound_count_new_transactions: usize::MAX.into(),
+            preserved_inbound_count_age: usize::MAX.into(),
             outbound_full_relay_extra_count: usize::MAX.into(),
             outbound_block_relay_count: usize::MAX.into(),
             outbound_block_relay_extra_count: usize::MAX.into(),
+            max_outbound_connections_per_network: Default::default(),
             outbound_block_relay_connection_min_age: Duration::MAX.into(),
 
             // Other values are irrelevant
@@ -844,12 +1109,15 @@ mod outbound {
 
             // Connection count limits that should not influence tests' behavior are set to 0.
             max_inbound_connections: 0.into(),
+            max_inbound_connections_per_address_group: Default::default(),
             preserved_inbound_count_address_group: 0.into(),
             preserved_inbound_count_ping: 0.into(),
             preserved_inbound_count_new_blocks: 0.into(),
             preserved_inbound_count_new_transactions: 0.into(),
+            preserved_inbound_count_age: 0.into(),
             outbound_full_relay_extra_count: 0.into(),
             outbound_block_relay_extra_count: 0.into(),
+            max_outbound_connections_per_network: Default::default(),
             outbound_block_relay_connection_min_age: Duration::ZERO.into(),
 
             // Other values are irrelevant
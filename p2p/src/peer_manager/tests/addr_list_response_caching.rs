@@ -244,11 +244,15 @@ fn make_p2p_config() -> P2pConfig {
             msg_max_locator_count: Default::default(),
             max_message_size: Default::default(),
             max_peer_tx_announcements: Default::default(),
+            max_peer_orphan_parent_requests: Default::default(),
+            max_peer_pending_tx_announcements: Default::default(),
+            max_peer_pending_header_announcements: Default::default(),
         },
 
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -263,6 +267,7 @@ fn make_p2p_config() -> P2pConfig {
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
         peer_manager_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     }
 }
 
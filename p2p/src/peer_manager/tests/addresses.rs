@@ -48,13 +48,19 @@ use test_utils::{
 use crate::{
     config::{NodeType, P2pConfig},
     error::{DialError, P2pError},
-    message::{AddrListRequest, AnnounceAddrRequest, PeerManagerMessage},
+    message::{
+        AddrGossipEntry, AddrListRequest, AddrListResponseV2, AnnounceAddrRequest, GossipAddress,
+        OnionV3Address, PeerManagerMessage,
+    },
     net::{
         default_backend::{
-            types::{CategorizedMessage, Command, Message},
+            types::{CategorizedMessage, Command, Message, P2pTimestamp},
             ConnectivityHandle, DefaultNetworkingService,
         },
-        types::{ConnectivityEvent, PeerInfo},
+        types::{
+            services::{Service, Services},
+            ConnectivityEvent, PeerInfo,
+        },
         ConnectivityService, NetworkingService,
     },
     peer_manager::{
@@ -65,6 +71,7 @@ use crate::{
         },
         OutboundConnectType, PeerManager, DNS_SEED_QUERY_INTERVAL,
     },
+    protocol::SupportedProtocolVersion,
     test_helpers::{peerdb_inmemory_store, test_p2p_config, TEST_PROTOCOL_VERSION},
     tests::helpers::TestDnsSeed,
     types::peer_id::PeerId,
@@ -355,6 +362,110 @@ fn test_addr_list_handling_outbound(#[case] seed: Seed) {
     assert_ne!(pm.peers.get(&peer_id_1).unwrap().score, 0);
 }
 
+// Check the v2 address gossip with a peer that supports it
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn test_addr_gossip_v2(#[case] seed: Seed) {
+    type TestNetworkingService = DefaultNetworkingService<TcpTransportSocket>;
+
+    let mut rng = make_seedable_rng(seed);
+
+    let bind_address = TestTransportTcp::make_address().into();
+    let chain_config = Arc::new(config::create_unit_test_config());
+    let p2p_config = Arc::new(test_p2p_config());
+    let (cmd_sender, mut cmd_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let (_conn_event_sender, conn_event_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let (_peer_mgr_event_sender, peer_mgr_event_receiver) =
+        tokio::sync::mpsc::unbounded_channel::<PeerManagerEvent>();
+    let time_getter = BasicTestTimeGetter::new();
+    let connectivity_handle =
+        ConnectivityHandle::<TestNetworkingService>::new(vec![], cmd_sender, conn_event_receiver);
+
+    let mut pm = PeerManager::<TestNetworkingService, _>::new(
+        true,
+        Arc::clone(&chain_config),
+        Arc::clone(&p2p_config),
+        connectivity_handle,
+        peer_mgr_event_receiver,
+        time_getter.get_time_getter(),
+        peerdb_inmemory_store(),
+    )
+    .unwrap();
+
+    let peer_id_1 = PeerId::new();
+    let peer_info = PeerInfo {
+        peer_id: peer_id_1,
+        protocol_version: SupportedProtocolVersion::V5,
+        network: *chain_config.magic_bytes(),
+        software_version: *chain_config.software_version(),
+        user_agent: mintlayer_core_user_agent(),
+        common_services: NodeType::Full.into(),
+    };
+    pm.accept_connection(
+        TestAddressMaker::new_random_address(&mut rng).into(),
+        bind_address,
+        ConnectionDirection::Inbound,
+        peer_info,
+        None,
+    );
+    match cmd_receiver.try_recv() {
+        Ok(Command::Accept { peer_id }) if peer_id == peer_id_1 => {}
+        v => panic!("unexpected result: {v:?}"),
+    }
+
+    // Onion addresses are not stored in PeerDb, but are still kept for relaying
+    let onion_entry = AddrGossipEntry {
+        address: GossipAddress::OnionV3(OnionV3Address {
+            public_key: rng.gen(),
+            port: rng.gen(),
+        }),
+        last_seen: P2pTimestamp::from_time(time_getter.get_time_getter().get_time()),
+        services: NodeType::Full.into(),
+    };
+    pm.handle_announce_addr_v2_request(peer_id_1, onion_entry.clone());
+    assert_eq!(pm.peerdb.known_addresses().count(), 0);
+
+    // The last-seen time and the services are stored in PeerDb
+    let address = get_new_discoverable_address(&mut rng);
+    let last_seen = P2pTimestamp::from_time(
+        (time_getter.get_time_getter().get_time() - Duration::from_secs(3600)).unwrap(),
+    );
+    let services: Services = [Service::Blocks].as_slice().into();
+    pm.handle_announce_addr_v2_request(
+        peer_id_1,
+        AddrGossipEntry {
+            address: address.clone().into(),
+            last_seen,
+            services,
+        },
+    );
+    let socket_address = address.as_discoverable_socket_address(false).unwrap();
+    let address_data = pm.peerdb.address_data(&socket_address).unwrap();
+    assert_eq!(address_data.last_seen(), Some(last_seen.as_time()));
+    assert_eq!(address_data.services(), services);
+
+    // Both addresses are sent back in the v2 format
+    pm.handle_addr_list_request(peer_id_1);
+    let cmd = cmd_receiver.try_recv().unwrap();
+    let (peer_id, peer_msg) = cmd_to_peer_man_msg(cmd);
+    assert_eq!(peer_id, peer_id_1);
+    assert_eq!(
+        peer_msg,
+        PeerManagerMessage::AddrListResponseV2(AddrListResponseV2 {
+            entries: vec![
+                AddrGossipEntry {
+                    address: address.into(),
+                    last_seen,
+                    services,
+                },
+                onion_entry
+            ]
+        })
+    );
+}
+
 // Verify that the node periodically resends its own address
 #[tracing::instrument(skip(seed))]
 #[rstest]
@@ -707,6 +818,7 @@ async fn dont_use_dns_seed_if_connections_exist(#[case] seed: Seed) {
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -721,6 +833,7 @@ async fn dont_use_dns_seed_if_connections_exist(#[case] seed: Seed) {
         sync_stalling_timeout: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
     let (cmd_sender, mut cmd_receiver) = tokio::sync::mpsc::unbounded_channel();
     let (conn_event_sender, conn_event_receiver) = tokio::sync::mpsc::unbounded_channel();
@@ -302,14 +302,17 @@ async fn no_outgoing_connection_to_banned_peer(#[case] seed: Seed) {
     let p2p_config = Arc::new(test_p2p_config_with_peer_mgr_config(PeerManagerConfig {
         outbound_block_relay_count: 2.into(),
         outbound_block_relay_extra_count: 0.into(),
+        max_outbound_connections_per_network: Default::default(),
         outbound_full_relay_count: 0.into(),
         outbound_full_relay_extra_count: 0.into(),
 
         max_inbound_connections: Default::default(),
+        max_inbound_connections_per_address_group: Default::default(),
         preserved_inbound_count_address_group: Default::default(),
         preserved_inbound_count_ping: Default::default(),
         preserved_inbound_count_new_blocks: Default::default(),
         preserved_inbound_count_new_transactions: Default::default(),
+        preserved_inbound_count_age: Default::default(),
         outbound_block_relay_connection_min_age: Default::default(),
         outbound_full_relay_connection_min_age: Default::default(),
         stale_tip_time_diff: Default::default(),
@@ -388,6 +391,7 @@ async fn banned_address_is_not_announced(#[case] seed: Seed) {
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -402,6 +406,7 @@ async fn banned_address_is_not_announced(#[case] seed: Seed) {
         sync_stalling_timeout: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
 
     let time_getter = BasicTestTimeGetter::new();
@@ -509,6 +514,7 @@ async fn banned_address_not_in_addr_response(#[case] seed: Seed) {
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -522,6 +528,7 @@ async fn banned_address_not_in_addr_response(#[case] seed: Seed) {
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
 
     let time_getter = BasicTestTimeGetter::new();
@@ -842,6 +842,7 @@ async fn connection_timeout_rpc_notified<T>(
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -856,6 +857,7 @@ async fn connection_timeout_rpc_notified<T>(
         sync_stalling_timeout: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
     let shutdown = Arc::new(SeqCstAtomicBool::new(false));
     let time_getter = TimeGetter::default();
@@ -956,6 +958,7 @@ where
         bind_addresses: Default::default(),
         socks5_proxy: None,
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -971,6 +974,7 @@ where
         sync_stalling_timeout: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
     let (peer_mgr_event_sender, _shutdown_sender, _subscribers_sender) = run_peer_manager::<T>(
         A::make_transport(),
@@ -1001,6 +1005,7 @@ where
         bind_addresses: Default::default(),
         socks5_proxy: None,
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
@@ -1015,6 +1020,7 @@ where
         sync_stalling_timeout: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
     let (peer_mgr_event_sender, _shutdown_sender, _subscribers_sender) = run_peer_manager::<T>(
         A::make_transport(),
@@ -1085,14 +1091,17 @@ where
         allow_same_ip_connections: true.into(),
 
         max_inbound_connections: Default::default(),
+        max_inbound_connections_per_address_group: Default::default(),
         preserved_inbound_count_address_group: Default::default(),
         preserved_inbound_count_ping: Default::default(),
         preserved_inbound_count_new_blocks: Default::default(),
         preserved_inbound_count_new_transactions: Default::default(),
+        preserved_inbound_count_age: Default::default(),
         outbound_full_relay_count: Default::default(),
         outbound_full_relay_extra_count: Default::default(),
         outbound_block_relay_count: Default::default(),
         outbound_block_relay_extra_count: Default::default(),
+        max_outbound_connections_per_network: Default::default(),
         outbound_block_relay_connection_min_age: Default::default(),
         outbound_full_relay_connection_min_age: Default::default(),
         stale_tip_time_diff: Default::default(),
@@ -1111,6 +1120,7 @@ where
         bind_addresses: Default::default(),
         socks5_proxy: None,
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -1124,6 +1134,7 @@ where
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
     let (peer_mgr_event_sender1, _shutdown_sender, _subscribers_sender) = run_peer_manager::<T>(
         A::make_transport(),
@@ -1156,6 +1167,7 @@ where
         bind_addresses: Default::default(),
         socks5_proxy: None,
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
@@ -1168,6 +1180,7 @@ where
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
     let (peer_mgr_event_sender2, _shutdown_sender, _subscribers_sender) = run_peer_manager::<T>(
         A::make_transport(),
@@ -1187,6 +1200,7 @@ where
         bind_addresses: Default::default(),
         socks5_proxy: None,
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
@@ -1199,6 +1213,7 @@ where
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
     let (peer_mgr_event_sender3, _shutdown_sender, _subscribers_sender) = run_peer_manager::<T>(
         A::make_transport(),
@@ -1289,14 +1304,17 @@ async fn discovered_node_2_groups() {
         allow_same_ip_connections: true.into(),
 
         max_inbound_connections: Default::default(),
+        max_inbound_connections_per_address_group: Default::default(),
         preserved_inbound_count_address_group: Default::default(),
         preserved_inbound_count_ping: Default::default(),
         preserved_inbound_count_new_blocks: Default::default(),
         preserved_inbound_count_new_transactions: Default::default(),
+        preserved_inbound_count_age: Default::default(),
         outbound_full_relay_count: Default::default(),
         outbound_full_relay_extra_count: Default::default(),
         outbound_block_relay_count: Default::default(),
         outbound_block_relay_extra_count: Default::default(),
+        max_outbound_connections_per_network: Default::default(),
         outbound_block_relay_connection_min_age: Default::default(),
         outbound_full_relay_connection_min_age: Default::default(),
         stale_tip_time_diff: Default::default(),
@@ -1315,6 +1333,7 @@ async fn discovered_node_2_groups() {
         bind_addresses: Default::default(),
         socks5_proxy: None,
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -1328,6 +1347,7 @@ async fn discovered_node_2_groups() {
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
     let (peer_mgr_event_sender1, _shutdown_sender, _subscribers_sender) =
         run_peer_manager::<DefaultNetworkingService<MpscChannelTransport>>(
@@ -1361,6 +1381,7 @@ async fn discovered_node_2_groups() {
         bind_addresses: Default::default(),
         socks5_proxy: None,
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
@@ -1373,6 +1394,7 @@ async fn discovered_node_2_groups() {
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
     let (peer_mgr_event_sender2, _shutdown_sender, _subscribers_sender) =
         run_peer_manager::<DefaultNetworkingService<MpscChannelTransport>>(
@@ -1393,6 +1415,7 @@ async fn discovered_node_2_groups() {
         bind_addresses: Default::default(),
         socks5_proxy: None,
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
@@ -1405,6 +1428,7 @@ async fn discovered_node_2_groups() {
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
     let (peer_mgr_event_sender3, _shutdown_sender, _subscribers_sender) =
         run_peer_manager::<DefaultNetworkingService<MpscChannelTransport>>(
@@ -1456,14 +1480,17 @@ async fn discovered_node_separate_groups() {
         allow_same_ip_connections: true.into(),
 
         max_inbound_connections: Default::default(),
+        max_inbound_connections_per_address_group: Default::default(),
         preserved_inbound_count_address_group: Default::default(),
         preserved_inbound_count_ping: Default::default(),
         preserved_inbound_count_new_blocks: Default::default(),
         preserved_inbound_count_new_transactions: Default::default(),
+        preserved_inbound_count_age: Default::default(),
         outbound_full_relay_count: Default::default(),
         outbound_full_relay_extra_count: Default::default(),
         outbound_block_relay_count: Default::default(),
         outbound_block_relay_extra_count: Default::default(),
+        max_outbound_connections_per_network: Default::default(),
         outbound_block_relay_connection_min_age: Default::default(),
         outbound_full_relay_connection_min_age: Default::default(),
         stale_tip_time_diff: Default::default(),
@@ -1482,6 +1509,7 @@ async fn discovered_node_separate_groups() {
         bind_addresses: Default::default(),
         socks5_proxy: None,
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -1495,6 +1523,7 @@ async fn discovered_node_separate_groups() {
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
     let (peer_mgr_event_sender1, _shutdown_sender, _subscribers_sender) =
         run_peer_manager::<DefaultNetworkingService<MpscChannelTransport>>(
@@ -1528,6 +1557,7 @@ async fn discovered_node_separate_groups() {
         bind_addresses: Default::default(),
         socks5_proxy: None,
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
@@ -1540,6 +1570,7 @@ async fn discovered_node_separate_groups() {
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
     let (peer_mgr_event_sender2, _shutdown_sender, _subscribers_sender) =
         run_peer_manager::<DefaultNetworkingService<MpscChannelTransport>>(
@@ -1560,6 +1591,7 @@ async fn discovered_node_separate_groups() {
         bind_addresses: Default::default(),
         socks5_proxy: None,
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
         ban_config: Default::default(),
@@ -1572,6 +1604,7 @@ async fn discovered_node_separate_groups() {
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
     let (peer_mgr_event_sender3, _shutdown_sender, _subscribers_sender) =
         run_peer_manager::<DefaultNetworkingService<MpscChannelTransport>>(
@@ -1842,6 +1875,7 @@ mod feeler_connections_test_utils {
 
                 outbound_full_relay_extra_count: 0.into(),
                 outbound_block_relay_extra_count: 0.into(),
+                max_outbound_connections_per_network: Default::default(),
 
                 feeler_connections_interval: feeler_connections_interval.into(),
 
@@ -1857,8 +1891,10 @@ mod feeler_connections_test_utils {
                 preserved_inbound_count_ping: Default::default(),
                 preserved_inbound_count_new_blocks: Default::default(),
                 preserved_inbound_count_new_transactions: Default::default(),
+                preserved_inbound_count_age: Default::default(),
 
                 max_inbound_connections: Default::default(),
+                max_inbound_connections_per_address_group: Default::default(),
                 outbound_block_relay_connection_min_age: Default::default(),
                 outbound_full_relay_connection_min_age: Default::default(),
                 stale_tip_time_diff: Default::default(),
@@ -1873,6 +1909,7 @@ mod feeler_connections_test_utils {
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
             disable_noise: Default::default(),
+            multiplexed_transport: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
@@ -1886,6 +1923,7 @@ mod feeler_connections_test_utils {
             user_agent: mintlayer_core_user_agent(),
             sync_stalling_timeout: Default::default(),
             protocol_config: Default::default(),
+            local_tx_broadcast_config: Default::default(),
         }
     }
 
@@ -1934,15 +1972,18 @@ async fn reject_connection_to_existing_ip(#[case] seed: Seed) {
             outbound_full_relay_extra_count: 0.into(),
             outbound_block_relay_count: 0.into(),
             outbound_block_relay_extra_count: 0.into(),
+            max_outbound_connections_per_network: Default::default(),
 
             // Disable feeler connections because they'll mess up the test.
             enable_feeler_connections: false.into(),
 
             max_inbound_connections: Default::default(),
+            max_inbound_connections_per_address_group: Default::default(),
             preserved_inbound_count_address_group: Default::default(),
             preserved_inbound_count_ping: Default::default(),
             preserved_inbound_count_new_blocks: Default::default(),
             preserved_inbound_count_new_transactions: Default::default(),
+            preserved_inbound_count_age: Default::default(),
             outbound_block_relay_connection_min_age: Default::default(),
             outbound_full_relay_connection_min_age: Default::default(),
             stale_tip_time_diff: Default::default(),
@@ -1959,6 +2000,7 @@ async fn reject_connection_to_existing_ip(#[case] seed: Seed) {
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -1972,6 +2014,7 @@ async fn reject_connection_to_existing_ip(#[case] seed: Seed) {
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     });
 
     let time_getter = BasicTestTimeGetter::new();
@@ -2104,12 +2147,15 @@ async fn feeler_connection_to_ip_address_of_inbound_peer(#[case] seed: Seed) {
         outbound_full_relay_extra_count: 0.into(),
         outbound_block_relay_count: 0.into(),
         outbound_block_relay_extra_count: 0.into(),
+        max_outbound_connections_per_network: Default::default(),
 
         max_inbound_connections: Default::default(),
+        max_inbound_connections_per_address_group: Default::default(),
         preserved_inbound_count_address_group: Default::default(),
         preserved_inbound_count_ping: Default::default(),
         preserved_inbound_count_new_blocks: Default::default(),
         preserved_inbound_count_new_transactions: Default::default(),
+        preserved_inbound_count_age: Default::default(),
         outbound_block_relay_connection_min_age: Default::default(),
         outbound_full_relay_connection_min_age: Default::default(),
         stale_tip_time_diff: Default::default(),
@@ -2206,3 +2252,118 @@ async fn feeler_connection_to_ip_address_of_inbound_peer(#[case] seed: Seed) {
 
     let _peer_mgr = peer_mgr_join_handle.await.unwrap();
 }
+
+// Check that an inbound connection is rejected if there are already
+// max_inbound_connections_per_address_group inbound peers from its address group,
+// and that the existing peers are not evicted to make room for it.
+#[tracing::instrument(skip(seed))]
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test]
+async fn reject_inbound_connection_over_address_group_limit(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let chain_config = Arc::new(config::create_unit_test_config());
+    let p2p_config = Arc::new(test_p2p_config_with_peer_mgr_config(PeerManagerConfig {
+        max_inbound_connections_per_address_group: 1.into(),
+
+        // Allow evicting any inbound peer.
+        preserved_inbound_count_address_group: 0.into(),
+        preserved_inbound_count_ping: 0.into(),
+        preserved_inbound_count_new_blocks: 0.into(),
+        preserved_inbound_count_new_transactions: 0.into(),
+        preserved_inbound_count_age: 0.into(),
+
+        ..Default::default()
+    }));
+
+    let time_getter = BasicTestTimeGetter::new();
+    let bind_addr = TestTransportTcp::make_address().into();
+
+    let (peer_mgr, conn_event_sender, peer_mgr_event_sender, mut cmd_receiver, _) =
+        make_standalone_peer_manager(
+            Arc::clone(&chain_config),
+            Arc::clone(&p2p_config),
+            vec![bind_addr],
+            time_getter.get_time_getter(),
+        );
+
+    // The first two addresses are in the same /16 subnet.
+    fn make_addr(a: u8, b: u8, rng: &mut impl Rng) -> SocketAddress {
+        let ip = std::net::Ipv4Addr::new(a, b, rng.gen(), rng.gen());
+        SocketAddress::new(std::net::SocketAddr::new(
+            ip.into(),
+            rng.gen_range(1024..u16::MAX),
+        ))
+    }
+    let group1_addr1 = make_addr(80, 1, &mut rng);
+    let group1_addr2 = make_addr(80, 1, &mut rng);
+    let group2_addr = make_addr(90, 1, &mut rng);
+
+    let peer_mgr_join_handle = logging::spawn_in_current_span(async move {
+        let mut peer_mgr = peer_mgr;
+        let _ = peer_mgr.run_internal(None).await;
+        peer_mgr
+    });
+
+    // The first peer from the group is accepted.
+    let group1_peer1_id = inbound_block_relay_peer_accepted_by_backend(
+        &conn_event_sender,
+        group1_addr1,
+        bind_addr,
+        &chain_config,
+    );
+    let cmd = expect_recv!(cmd_receiver);
+    assert_eq!(
+        cmd,
+        Command::Accept {
+            peer_id: group1_peer1_id
+        }
+    );
+
+    // The second one is rejected and the first one stays connected.
+    let group1_peer2_id = inbound_block_relay_peer_accepted_by_backend(
+        &conn_event_sender,
+        group1_addr2,
+        bind_addr,
+        &chain_config,
+    );
+    let cmd = expect_recv!(cmd_receiver);
+    assert_eq!(
+        cmd,
+        Command::Disconnect {
+            peer_id: group1_peer2_id,
+            reason: Some(DisconnectionReason::TooManyInboundPeersInAddressGroup)
+        }
+    );
+
+    // A peer from another group is accepted.
+    let group2_peer_id = inbound_block_relay_peer_accepted_by_backend(
+        &conn_event_sender,
+        group2_addr,
+        bind_addr,
+        &chain_config,
+    );
+    let cmd = expect_recv!(cmd_receiver);
+    assert_eq!(
+        cmd,
+        Command::Accept {
+            peer_id: group2_peer_id
+        }
+    );
+
+    let connected_peers = query_peer_manager(&peer_mgr_event_sender, |peer_mgr| {
+        peer_mgr.peers().keys().copied().collect::<BTreeSet<_>>()
+    })
+    .await;
+    assert_eq!(
+        connected_peers,
+        BTreeSet::from([group1_peer1_id, group2_peer_id])
+    );
+
+    drop(conn_event_sender);
+    drop(peer_mgr_event_sender);
+
+    let _peer_mgr = peer_mgr_join_handle.await.unwrap();
+}
@@ -92,6 +92,7 @@ mod dont_evict_if_blocks_in_flight {
             peer_manager_config: PeerManagerConfig {
                 outbound_block_relay_count: 2.into(),
                 outbound_block_relay_extra_count: 1.into(),
+                max_outbound_connections_per_network: Default::default(),
                 outbound_block_relay_connection_min_age: min_connection_age.into(),
 
                 outbound_full_relay_count: 0.into(),
@@ -111,8 +112,10 @@ mod dont_evict_if_blocks_in_flight {
                 preserved_inbound_count_ping: Default::default(),
                 preserved_inbound_count_new_blocks: Default::default(),
                 preserved_inbound_count_new_transactions: Default::default(),
+                preserved_inbound_count_age: Default::default(),
 
                 max_inbound_connections: Default::default(),
+                max_inbound_connections_per_address_group: Default::default(),
 
                 outbound_full_relay_connection_min_age: Default::default(),
                 stale_tip_time_diff: Default::default(),
@@ -127,6 +130,7 @@ mod dont_evict_if_blocks_in_flight {
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
             disable_noise: Default::default(),
+            multiplexed_transport: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
@@ -140,6 +144,7 @@ mod dont_evict_if_blocks_in_flight {
             user_agent: mintlayer_core_user_agent(),
             sync_stalling_timeout: Default::default(),
             protocol_config: Default::default(),
+            local_tx_broadcast_config: Default::default(),
         });
 
         let bind_address = TestTransportTcp::make_address().into();
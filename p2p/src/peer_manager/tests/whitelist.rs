@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{net::IpAddr, sync::Arc, time::Duration};
+use std::{sync::Arc, time::Duration};
 
 use p2p_types::socket_address::SocketAddress;
 use rstest::rstest;
@@ -33,6 +33,7 @@ use test_utils::{
     BasicTestTimeGetter,
 };
 use utils::atomics::SeqCstAtomicBool;
+use utils_networking::IpSubnet;
 
 use crate::{
     config::{NodeType, P2pConfig},
@@ -48,17 +49,19 @@ use crate::{
         tests::{make_peer_manager, make_peer_manager_custom},
         PeerManager,
     },
+    peer_manager_event::PeerDisconnectionDbAction,
     test_helpers::{connect_services, peerdb_inmemory_store, TEST_PROTOCOL_VERSION},
     types::peer_id::PeerId,
     utils::oneshot_nofail,
     PeerManagerEvent,
 };
 
-fn p2p_config_with_whitelisted(whitelisted_addresses: Vec<IpAddr>) -> P2pConfig {
+fn p2p_config_with_whitelisted(whitelisted_addresses: Vec<IpSubnet>) -> P2pConfig {
     P2pConfig {
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses,
@@ -74,6 +77,7 @@ fn p2p_config_with_whitelisted(whitelisted_addresses: Vec<IpAddr>) -> P2pConfig
         sync_stalling_timeout: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     }
 }
 
@@ -87,7 +91,7 @@ where
     let addr2 = A::make_address().into();
 
     let chain_config = Arc::new(config::create_unit_test_config());
-    let p2p_config = Arc::new(p2p_config_with_whitelisted(vec![addr1.ip_addr()]));
+    let p2p_config = Arc::new(p2p_config_with_whitelisted(vec![addr1.ip_addr().into()]));
 
     let (mut pm1, _, _shutdown_sender, _subscribers_sender) = make_peer_manager_custom::<T>(
         A::make_transport(),
@@ -158,6 +162,62 @@ async fn no_automatic_ban_for_whitelisted_noise() {
     .await;
 }
 
+// A whitelisted peer is not disconnected for sending invalid data with a minor ban score
+#[tracing::instrument]
+#[tokio::test]
+async fn no_invalid_data_disconnect_for_whitelisted() {
+    type T = DefaultNetworkingService<MpscChannelTransport>;
+
+    let addr1: SocketAddress = TestTransportChannel::make_address().into();
+    let addr2 = TestTransportChannel::make_address().into();
+
+    let chain_config = Arc::new(config::create_unit_test_config());
+    let p2p_config = Arc::new(p2p_config_with_whitelisted(vec![addr1.ip_addr().into()]));
+
+    let (mut pm1, _, _shutdown_sender, _subscribers_sender) = make_peer_manager_custom::<T>(
+        TestTransportChannel::make_transport(),
+        addr1,
+        Arc::clone(&chain_config),
+        Arc::clone(&p2p_config),
+        Default::default(),
+    )
+    .await;
+
+    let (mut pm2, _, _shutdown_sender, _subscribers_sender) = make_peer_manager_custom::<T>(
+        TestTransportChannel::make_transport(),
+        addr2,
+        Arc::clone(&chain_config),
+        Arc::clone(&p2p_config),
+        Default::default(),
+    )
+    .await;
+
+    let (address, peer_info, _) = connect_services::<T>(
+        &mut pm1.peer_connectivity_handle,
+        &mut pm2.peer_connectivity_handle,
+    )
+    .await;
+    let peer_id = peer_info.peer_id;
+    pm2.accept_connection(
+        address,
+        pm2.peer_connectivity_handle.local_addresses()[0],
+        ConnectionDirection::Inbound,
+        peer_info,
+        None,
+    );
+
+    let (sender, receiver) = oneshot_nofail::channel();
+    pm2.handle_control_event(PeerManagerEvent::Disconnect(
+        peer_id,
+        PeerDisconnectionDbAction::Keep,
+        Some(DisconnectionReason::InvalidDataReceived),
+        sender,
+    ));
+    receiver.await.unwrap().unwrap();
+    assert!(pm2.peers.contains_key(&peer_id));
+    assert!(!pm2.pending_disconnects.contains_key(&peer_id));
+}
+
 // if an address was banned it won't be unbanned automatically if whitelisted
 async fn no_automatic_unban_for_whitelisted<A, T>()
 where
@@ -174,8 +234,8 @@ where
         make_peer_manager::<T>(A::make_transport(), addr1, Arc::clone(&chain_config)).await;
 
     let p2p_config = Arc::new(p2p_config_with_whitelisted(vec![
-        addr1.ip_addr(),
-        addr2.ip_addr(),
+        addr1.ip_addr().into(),
+        addr2.ip_addr().into(),
     ]));
     let (_peer_sender, peer_receiver) = tokio::sync::mpsc::unbounded_channel::<PeerManagerEvent>();
     let time_getter = BasicTestTimeGetter::new();
@@ -282,7 +342,9 @@ fn manual_ban_overrides_whitelisting(#[case] seed: Seed) {
     let address_2 = TestAddressMaker::new_random_address(&mut rng).into();
 
     let chain_config = Arc::new(config::create_unit_test_config());
-    let p2p_config = Arc::new(p2p_config_with_whitelisted(vec![address_1.ip_addr()]));
+    let p2p_config = Arc::new(p2p_config_with_whitelisted(vec![address_1
+        .ip_addr()
+        .into()]));
     let (cmd_sender, mut cmd_receiver) = tokio::sync::mpsc::unbounded_channel();
     let (_conn_sender, conn_receiver) = tokio::sync::mpsc::unbounded_channel();
     let (_peer_sender, peer_receiver) = tokio::sync::mpsc::unbounded_channel::<PeerManagerEvent>();
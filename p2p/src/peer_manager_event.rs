@@ -23,9 +23,12 @@ use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress
 use utils_networking::IpOrSocketAddress;
 
 use crate::{
-    disconnection_reason::DisconnectionReason, interface::types::ConnectedPeer,
-    peer_manager::PeerManagerInterface, sync::sync_status::PeerBlockSyncStatus,
-    types::peer_id::PeerId, utils::oneshot_nofail,
+    disconnection_reason::DisconnectionReason,
+    interface::types::{ConnectedPeer, ListenEndpointInfo, NetworkConnectionsInfo},
+    peer_manager::PeerManagerInterface,
+    sync::sync_status::PeerBlockSyncStatus,
+    types::peer_id::PeerId,
+    utils::oneshot_nofail,
 };
 
 #[derive(Debug)]
@@ -38,6 +41,13 @@ pub enum PeerDisconnectionDbAction {
     RemoveIfOutbound,
 }
 
+/// The kind of an announcement sent to a peer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AnnouncementKind {
+    Transaction,
+    Header,
+}
+
 #[derive(Debug)]
 pub enum PeerManagerEvent {
     /// Try to establish connection with a remote peer
@@ -57,6 +67,12 @@ pub enum PeerManagerEvent {
     /// Get the bind address of the local node
     GetBindAddresses(oneshot_nofail::Sender<Vec<SocketAddress>>),
 
+    /// Get the inbound connection statistics of the local listen addresses
+    GetListenEndpoints(oneshot_nofail::Sender<Vec<ListenEndpointInfo>>),
+
+    /// Get the number of connections to each network
+    GetNetworkConnections(oneshot_nofail::Sender<Vec<NetworkConnectionsInfo>>),
+
     /// Get peer IDs and addresses of connected peers
     GetConnectedPeers(oneshot_nofail::Sender<Vec<ConnectedPeer>>),
 
@@ -96,6 +112,16 @@ pub enum PeerManagerEvent {
         new_status: PeerBlockSyncStatus,
     },
 
+    /// Announcements to the specified peer were dropped because its send queue was full.
+    ///
+    /// The drops are aggregated by the peer task and reported periodically rather than
+    /// one at a time.
+    PeerAnnouncementsDropped {
+        peer_id: PeerId,
+        kind: AnnouncementKind,
+        count: u64,
+    },
+
     GetReserved(oneshot_nofail::Sender<Vec<SocketAddress>>),
     AddReserved(IpOrSocketAddress, oneshot_nofail::Sender<crate::Result<()>>),
     RemoveReserved(IpOrSocketAddress, oneshot_nofail::Sender<crate::Result<()>>),
@@ -13,6 +13,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::num::NonZeroUsize;
+
 use enum_iterator::Sequence;
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
@@ -36,6 +38,11 @@ impl ProtocolVersion {
     pub const fn inner(&self) -> u32 {
         self.0
     }
+
+    /// Return true if a peer that has announced this version understands the feature.
+    pub fn supports(&self, feature: ProtocolFeature) -> bool {
+        *self >= feature.min_version().into_raw_version()
+    }
 }
 
 /// The validated network protocol version.
@@ -43,6 +50,8 @@ impl ProtocolVersion {
 pub enum SupportedProtocolVersion {
     V2 = 2,
     V3 = 3,
+    V4 = 4,
+    V5 = 5,
 }
 
 lazy_static::lazy_static! {
@@ -57,6 +66,10 @@ impl SupportedProtocolVersion {
     pub const fn into_raw_version(&self) -> ProtocolVersion {
         ProtocolVersion::new(*self as u32)
     }
+
+    pub fn supports(&self, feature: ProtocolFeature) -> bool {
+        *self >= feature.min_version()
+    }
 }
 
 impl From<SupportedProtocolVersion> for ProtocolVersion {
@@ -71,14 +84,48 @@ impl From<ProtocolVersion> for Option<SupportedProtocolVersion> {
     }
 }
 
+/// Optional protocol features, each introduced in a particular protocol version.
+///
+/// Peers that have negotiated an older version are still served; the messages related to
+/// the features they don't support are just never sent to them. This allows network upgrades
+/// to roll out gradually, without partitioning the old and the new nodes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Sequence)]
+pub enum ProtocolFeature {
+    /// The WillDisconnect message, which tells the peer why it's being disconnected.
+    WillDisconnectMessage,
+    /// The TransactionReject message, which tells the peer why its transaction wasn't accepted.
+    TransactionRejectMessage,
+    /// The second version of the address gossip messages, where each address comes with
+    /// its last-seen time and service flags, and which can also carry onion addresses.
+    AddrGossipV2,
+}
+
+impl ProtocolFeature {
+    /// The first protocol version that supports the feature.
+    pub const fn min_version(&self) -> SupportedProtocolVersion {
+        match self {
+            ProtocolFeature::WillDisconnectMessage => SupportedProtocolVersion::V3,
+            ProtocolFeature::TransactionRejectMessage => SupportedProtocolVersion::V4,
+            ProtocolFeature::AddrGossipV2 => SupportedProtocolVersion::V5,
+        }
+    }
+}
+
 /// Given this node's and peer's protocol versions (in any order), choose the best version
 /// that is supported by both.
+///
+/// A node understands all the supported versions up to the one it announces, so the result is
+/// the highest supported version not greater than either of them. This way, a peer announcing
+/// a version unknown to us (e.g. a newer one, or one that has been withdrawn) is still accepted
+/// as long as there is a supported version below it.
 pub fn choose_common_protocol_version(
     version1: ProtocolVersion,
     version2: ProtocolVersion,
 ) -> Option<SupportedProtocolVersion> {
-    let min_version = std::cmp::min(version1, version2);
-    min_version.into()
+    let max_common_version = std::cmp::min(version1, version2);
+    enum_iterator::all::<SupportedProtocolVersion>()
+        .filter(|version| version.into_raw_version() <= max_common_version)
+        .max()
 }
 
 make_config_setting!(HeaderLimit, usize, 2000);
@@ -86,6 +133,17 @@ make_config_setting!(MaxLocatorSize, usize, 101);
 make_config_setting!(RequestedBlocksLimit, usize, 500);
 make_config_setting!(MaxMessageSize, usize, 10 * 1024 * 1024);
 make_config_setting!(MaxPeerTxAnnouncements, usize, 5000);
+make_config_setting!(
+    MaxPeerPendingTxAnnouncements,
+    NonZeroUsize,
+    NonZeroUsize::new(5000).expect("must be non-zero")
+);
+make_config_setting!(
+    MaxPeerPendingHeaderAnnouncements,
+    NonZeroUsize,
+    NonZeroUsize::new(10).expect("must be non-zero")
+);
+make_config_setting!(MaxPeerOrphanParentRequests, usize, 100);
 make_config_setting!(MaxUnconnectedHeaders, usize, 10);
 make_config_setting!(MaxAddrListResponseAddressCount, usize, 1000);
 
@@ -115,4 +173,57 @@ pub struct ProtocolConfig {
     pub max_message_size: MaxMessageSize,
     /// The maximum number of announcements (hashes) for which we haven't receive transactions.
     pub max_peer_tx_announcements: MaxPeerTxAnnouncements,
+    /// The maximum number of missing parents of orphan transactions that can be requested
+    /// from a single peer at once.
+    pub max_peer_orphan_parent_requests: MaxPeerOrphanParentRequests,
+    /// The maximum number of transaction announcements waiting to be sent to a single peer;
+    /// the oldest ones are dropped when the limit is reached.
+    pub max_peer_pending_tx_announcements: MaxPeerPendingTxAnnouncements,
+    /// The maximum number of new tip header announcements waiting to be sent to a single peer;
+    /// the oldest ones are dropped when the limit is reached.
+    pub max_peer_pending_header_announcements: MaxPeerPendingHeaderAnnouncements,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_protocol_version() {
+        let v2 = SupportedProtocolVersion::V2.into_raw_version();
+        let v3 = SupportedProtocolVersion::V3.into_raw_version();
+        let v5 = SupportedProtocolVersion::V5.into_raw_version();
+        let next = ProtocolVersion::new(v5.inner() + 1);
+        let too_old = ProtocolVersion::new(v2.inner() - 1);
+
+        assert_eq!(
+            choose_common_protocol_version(v3, v2),
+            Some(SupportedProtocolVersion::V2)
+        );
+        assert_eq!(
+            choose_common_protocol_version(next, v3),
+            Some(SupportedProtocolVersion::V3)
+        );
+        assert_eq!(
+            choose_common_protocol_version(next, next),
+            Some(SupportedProtocolVersion::V5)
+        );
+        assert_eq!(choose_common_protocol_version(too_old, v3), None);
+    }
+
+    #[test]
+    fn feature_support() {
+        for feature in enum_iterator::all::<ProtocolFeature>() {
+            let min_version = feature.min_version();
+            assert!(min_version.supports(feature));
+            assert!(min_version.into_raw_version().supports(feature));
+            assert!(
+                !ProtocolVersion::new(min_version.into_raw_version().inner() - 1).supports(feature)
+            );
+        }
+
+        assert!(!SupportedProtocolVersion::V2.supports(ProtocolFeature::WillDisconnectMessage));
+        assert!(!SupportedProtocolVersion::V3.supports(ProtocolFeature::TransactionRejectMessage));
+        assert!(!SupportedProtocolVersion::V4.supports(ProtocolFeature::AddrGossipV2));
+    }
 }
@@ -15,13 +15,22 @@
 
 use std::time::Duration;
 
-use common::{chain::SignedTransaction, primitives::time::Time};
+use common::{
+    chain::{SignedTransaction, Transaction},
+    primitives::{time::Time, Id},
+};
 use mempool::tx_options::TxOptionsOverrides;
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress};
 use serialization::hex_encoded::HexEncoded;
 use utils_networking::IpOrSocketAddress;
 
-use crate::{interface::types::ConnectedPeer, types::peer_id::PeerId};
+use crate::{
+    interface::types::{
+        ConnectedPeer, ListenEndpointInfo, NetworkConnectionsInfo, SyncProgress,
+        TransactionRejection,
+    },
+    types::peer_id::PeerId,
+};
 use rpc::RpcResult;
 
 #[rpc::describe]
@@ -68,10 +77,35 @@ trait P2pRpc {
     #[method(name = "get_bind_addresses")]
     async fn get_bind_addresses(&self) -> RpcResult<Vec<SocketAddress>>;
 
+    /// Get the addresses this node listens on, with the statistics of the inbound connections.
+    #[method(name = "get_listen_endpoints")]
+    async fn get_listen_endpoints(&self) -> RpcResult<Vec<ListenEndpointInfo>>;
+
+    /// Get the number of inbound and outbound connections to each network (IPv4, IPv6, onion).
+    #[method(name = "get_network_connections")]
+    async fn get_network_connections(&self) -> RpcResult<Vec<NetworkConnectionsInfo>>;
+
     /// Get details of connected peers.
     #[method(name = "get_connected_peers")]
     async fn get_connected_peers(&self) -> RpcResult<Vec<ConnectedPeer>>;
 
+    /// Get the progress of the initial block download.
+    ///
+    /// The header height is the best one announced by the peers, so it's only known after
+    /// the headers have been exchanged with at least one peer.
+    #[method(name = "get_sync_progress")]
+    async fn get_sync_progress(&self) -> RpcResult<SyncProgress>;
+
+    /// Get the rejections of a relayed transaction reported by the peers.
+    ///
+    /// Only the peers that support the corresponding protocol message report the rejections,
+    /// and only a limited number of the most recent ones is kept.
+    #[method(name = "get_transaction_rejections")]
+    async fn get_transaction_rejections(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> RpcResult<Vec<TransactionRejection>>;
+
     /// Get addresses of reserved nodes.
     #[method(name = "get_reserved_nodes")]
     async fn get_reserved_nodes(&self) -> RpcResult<Vec<SocketAddress>>;
@@ -145,11 +179,34 @@ impl P2pRpcServer for super::P2pHandle {
         rpc::handle_result(res)
     }
 
+    async fn get_listen_endpoints(&self) -> RpcResult<Vec<ListenEndpointInfo>> {
+        let res = self.call_async(|this| this.get_listen_endpoints()).await;
+        rpc::handle_result(res)
+    }
+
+    async fn get_network_connections(&self) -> RpcResult<Vec<NetworkConnectionsInfo>> {
+        let res = self.call_async(|this| this.get_network_connections()).await;
+        rpc::handle_result(res)
+    }
+
     async fn get_connected_peers(&self) -> RpcResult<Vec<ConnectedPeer>> {
         let res = self.call_async(|this| this.get_connected_peers()).await;
         rpc::handle_result(res)
     }
 
+    async fn get_sync_progress(&self) -> RpcResult<SyncProgress> {
+        let res = self.call_async(|this| this.get_sync_progress()).await;
+        rpc::handle_result(res)
+    }
+
+    async fn get_transaction_rejections(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> RpcResult<Vec<TransactionRejection>> {
+        let res = self.call_async(move |this| this.get_transaction_rejections(tx_id)).await;
+        rpc::handle_result(res)
+    }
+
     async fn get_reserved_nodes(&self) -> RpcResult<Vec<SocketAddress>> {
         let res = self.call_async(|this| this.get_reserved_nodes()).await;
         rpc::handle_result(res)
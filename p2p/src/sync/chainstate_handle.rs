@@ -25,6 +25,9 @@ pub struct ChainstateHandle {
 
     /// A cached result of the `ChainstateInterface::is_initial_block_download` call.
     is_initial_block_download: Arc<AcqRelAtomicBool>,
+
+    /// A cached result of the `ChainstateInterface::is_tx_processing_deferred` call.
+    is_tx_processing_deferred: Arc<AcqRelAtomicBool>,
 }
 
 impl ChainstateHandle {
@@ -32,6 +35,7 @@ impl ChainstateHandle {
         ChainstateHandle {
             handle,
             is_initial_block_download: Arc::new(true.into()),
+            is_tx_processing_deferred: Arc::new(true.into()),
         }
     }
 
@@ -59,4 +63,15 @@ impl ChainstateHandle {
         self.is_initial_block_download.store(new_val);
         Ok(new_val)
     }
+
+    pub async fn is_tx_processing_deferred(&self) -> crate::Result<bool> {
+        // Note: same as is_initial_block_download, this can only go from true to false.
+        if !self.is_tx_processing_deferred.load() {
+            return Ok(false);
+        }
+
+        let new_val = self.handle.call(|cs| cs.is_tx_processing_deferred()).await?;
+        self.is_tx_processing_deferred.store(new_val);
+        Ok(new_val)
+    }
 }
@@ -0,0 +1,251 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Delayed broadcasting of the transactions that originate from this node.
+//!
+//! If a transaction is announced to all peers as soon as it has been submitted, an observer
+//! connected to many nodes can deduce its origin by checking who announced it first.
+//! To make this harder, the initial announcement is delayed by a random interval and is only
+//! made to a small random subset of peers; the rest of the peers get it after another delay,
+//! in case the initial ones didn't propagate it. Optionally, the transaction is also announced
+//! through a second connection in between, in case the initial peers dropped it.
+
+use std::{cmp::Reverse, collections::BinaryHeap, time::Duration};
+
+use randomness::Rng;
+use tokio::time::Instant;
+
+use common::{chain::Transaction, primitives::Id};
+use utils::make_config_setting;
+
+make_config_setting!(LocalTxBroadcastPrivacyEnabled, bool, false);
+make_config_setting!(
+    LocalTxInitialAnnouncementMaxDelay,
+    Duration,
+    Duration::from_secs(10)
+);
+make_config_setting!(LocalTxInitialAnnouncementPeerCount, usize, 2);
+make_config_setting!(
+    LocalTxFullAnnouncementDelay,
+    Duration,
+    Duration::from_secs(30)
+);
+make_config_setting!(LocalTxResubmitEnabled, bool, false);
+make_config_setting!(LocalTxResubmitDelay, Duration, Duration::from_secs(15));
+
+/// Settings related to the broadcasting of transactions that originate from this node.
+#[derive(Default, Debug, Clone)]
+pub struct LocalTxBroadcastConfig {
+    /// If true, local transactions are not announced to all peers at once; instead, they are
+    /// announced to a few random peers after a random delay and to the rest of the peers later.
+    pub privacy_enabled: LocalTxBroadcastPrivacyEnabled,
+    /// The upper bound of the random delay before the initial announcement.
+    pub initial_announcement_max_delay: LocalTxInitialAnnouncementMaxDelay,
+    /// The number of random peers that receive the initial announcement.
+    pub initial_announcement_peer_count: LocalTxInitialAnnouncementPeerCount,
+    /// The delay between the initial announcement and the announcement to the rest of the peers.
+    pub full_announcement_delay: LocalTxFullAnnouncementDelay,
+    /// If true, the transaction is announced again to one more random peer, which didn't get
+    /// the initial announcement, before it's announced to the rest of the peers.
+    pub resubmit_enabled: LocalTxResubmitEnabled,
+    /// The delay between the initial announcement and the one through the second connection;
+    /// must be shorter than `full_announcement_delay`.
+    pub resubmit_delay: LocalTxResubmitDelay,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AnnouncementStage {
+    /// The transaction should be announced to a random subset of peers.
+    Initial,
+    /// The transaction should be announced to one more random peer through a second connection.
+    Resubmit,
+    /// The transaction should be announced to all peers that haven't seen it yet.
+    Full,
+}
+
+pub struct LocalTxBroadcastScheduler {
+    txs: BinaryHeap<Reverse<(Instant, Id<Transaction>, AnnouncementStage)>>,
+}
+
+impl LocalTxBroadcastScheduler {
+    pub fn new() -> Self {
+        Self {
+            txs: Default::default(),
+        }
+    }
+
+    /// Schedule the initial announcement of a new local transaction.
+    pub fn schedule(
+        &mut self,
+        tx: Id<Transaction>,
+        config: &LocalTxBroadcastConfig,
+        rng: &mut impl Rng,
+    ) {
+        let delay = config.initial_announcement_max_delay.mul_f64(rng.gen_range(0.0..=1.0));
+        self.txs.push(Reverse((
+            Instant::now() + delay,
+            tx,
+            AnnouncementStage::Initial,
+        )));
+    }
+
+    /// Take the earliest scheduled announcement.
+    ///
+    /// If it's the initial one, the full announcement of the same transaction is scheduled,
+    /// as well as the re-submission if it's enabled.
+    pub fn pop(
+        &mut self,
+        config: &LocalTxBroadcastConfig,
+    ) -> Option<(Id<Transaction>, AnnouncementStage)> {
+        let Reverse((due, tx, stage)) = self.txs.pop()?;
+
+        match stage {
+            AnnouncementStage::Initial => {
+                if *config.resubmit_enabled {
+                    self.txs.push(Reverse((
+                        due + *config.resubmit_delay,
+                        tx,
+                        AnnouncementStage::Resubmit,
+                    )));
+                }
+                self.txs.push(Reverse((
+                    due + *config.full_announcement_delay,
+                    tx,
+                    AnnouncementStage::Full,
+                )));
+            }
+            AnnouncementStage::Resubmit | AnnouncementStage::Full => {}
+        }
+
+        Some((tx, stage))
+    }
+
+    pub async fn due(&self) {
+        match self.txs.peek() {
+            Some(item) => {
+                let (due, _, _) = item.0;
+                tokio::time::sleep_until(due).await;
+            }
+            None => std::future::pending().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::primitives::H256;
+    use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, Seed};
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    #[tokio::test]
+    async fn announcement_stages(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        tokio::time::pause();
+
+        let config = LocalTxBroadcastConfig {
+            privacy_enabled: true.into(),
+            initial_announcement_max_delay: Duration::from_secs(10).into(),
+            initial_announcement_peer_count: 1.into(),
+            full_announcement_delay: Duration::from_secs(100).into(),
+            resubmit_enabled: false.into(),
+            resubmit_delay: Duration::from_secs(50).into(),
+        };
+
+        let tx1 = Id::<Transaction>::new(H256::random_using(&mut rng));
+        let tx2 = Id::<Transaction>::new(H256::random_using(&mut rng));
+
+        let mut scheduler = LocalTxBroadcastScheduler::new();
+        assert_eq!(None, scheduler.pop(&config));
+
+        let start = Instant::now();
+        scheduler.schedule(tx1, &config, &mut rng);
+        scheduler.schedule(tx2, &config, &mut rng);
+
+        // Both initial announcements come before any of the full ones.
+        let mut initial = Vec::new();
+        for _ in 0..2 {
+            scheduler.due().await;
+            assert!(Instant::now().duration_since(start) <= Duration::from_secs(10));
+            let (tx, stage) = scheduler.pop(&config).unwrap();
+            assert_eq!(stage, AnnouncementStage::Initial);
+            initial.push(tx);
+        }
+        initial.sort();
+        let mut expected = vec![tx1, tx2];
+        expected.sort();
+        assert_eq!(initial, expected);
+
+        for _ in 0..2 {
+            scheduler.due().await;
+            assert!(Instant::now().duration_since(start) >= Duration::from_secs(100));
+            let (_, stage) = scheduler.pop(&config).unwrap();
+            assert_eq!(stage, AnnouncementStage::Full);
+        }
+
+        assert_eq!(None, scheduler.pop(&config));
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    #[tokio::test]
+    async fn resubmit_stage(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        tokio::time::pause();
+
+        let config = LocalTxBroadcastConfig {
+            privacy_enabled: true.into(),
+            initial_announcement_max_delay: Duration::from_secs(10).into(),
+            initial_announcement_peer_count: 1.into(),
+            full_announcement_delay: Duration::from_secs(100).into(),
+            resubmit_enabled: true.into(),
+            resubmit_delay: Duration::from_secs(50).into(),
+        };
+
+        let tx = Id::<Transaction>::new(H256::random_using(&mut rng));
+
+        let mut scheduler = LocalTxBroadcastScheduler::new();
+        scheduler.schedule(tx, &config, &mut rng);
+
+        scheduler.due().await;
+        let initial_time = Instant::now();
+        assert_eq!(
+            scheduler.pop(&config),
+            Some((tx, AnnouncementStage::Initial))
+        );
+
+        scheduler.due().await;
+        let elapsed = Instant::now().duration_since(initial_time);
+        assert!(elapsed > Duration::from_secs(49) && elapsed < Duration::from_secs(51));
+        assert_eq!(
+            scheduler.pop(&config),
+            Some((tx, AnnouncementStage::Resubmit))
+        );
+
+        scheduler.due().await;
+        let elapsed = Instant::now().duration_since(initial_time);
+        assert!(elapsed > Duration::from_secs(99) && elapsed < Duration::from_secs(101));
+        assert_eq!(scheduler.pop(&config), Some((tx, AnnouncementStage::Full)));
+
+        assert_eq!(None, scheduler.pop(&config));
+    }
+}
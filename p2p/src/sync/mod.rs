@@ -17,10 +17,13 @@
 //! to block announcement from peers and the announcement of blocks produced by this node).
 
 mod chainstate_handle;
+pub mod local_tx_broadcast;
 mod peer;
 mod peer_activity;
 mod peer_common;
+pub mod sync_progress;
 pub mod sync_status;
+pub mod tx_rejections;
 
 use std::collections::HashMap;
 
@@ -38,6 +41,7 @@ use common::{
 };
 use logging::log;
 use mempool::{event::TransactionProcessed, tx_origin::TxOrigin, MempoolHandle};
+use randomness::{make_pseudo_rng, seq::IteratorRandom};
 use tracing::Instrument;
 use utils::{sync::Arc, tap_log::TapLog};
 
@@ -46,7 +50,10 @@ use crate::{
     error::P2pError,
     message::{BlockSyncMessage, TransactionSyncMessage},
     net::{
-        types::{services::Services, SyncingEvent},
+        types::{
+            services::{Service, Services},
+            SyncingEvent,
+        },
         MessagingService, NetworkingService, SyncingEventReceiver,
     },
     protocol::SupportedProtocolVersion,
@@ -54,7 +61,12 @@ use crate::{
     PeerManagerEvent, Result,
 };
 
-use self::chainstate_handle::ChainstateHandle;
+use self::{
+    chainstate_handle::ChainstateHandle,
+    local_tx_broadcast::{AnnouncementStage, LocalTxBroadcastScheduler},
+    sync_progress::SyncProgressCounters,
+    tx_rejections::TxRejections,
+};
 
 #[derive(Debug, Clone)]
 pub enum LocalEvent {
@@ -63,6 +75,7 @@ pub enum LocalEvent {
 }
 
 pub struct PeerContext {
+    common_services: Services,
     tasks: JoinSet<()>,
     local_event_senders: Vec<UnboundedSender<LocalEvent>>,
 }
@@ -88,6 +101,19 @@ pub struct SyncManager<T: NetworkingService> {
     /// The list of connected peers
     peers: HashMap<PeerId, PeerContext>,
 
+    /// Local transactions whose announcement is delayed to make tracing their origin harder.
+    local_tx_broadcast_scheduler: LocalTxBroadcastScheduler,
+
+    /// The peers that got the initial announcement of a delayed local transaction,
+    /// kept until its full announcement, so that the re-submission goes through another peer.
+    local_tx_initial_peers: HashMap<Id<Transaction>, Vec<PeerId>>,
+
+    /// Block syncing progress, updated by the peer tasks.
+    sync_progress: Arc<SyncProgressCounters>,
+
+    /// Rejections of the relayed transactions reported by peers, updated by the peer tasks.
+    tx_rejections: Arc<TxRejections>,
+
     time_getter: TimeGetter,
 
     /// SyncManager's observer for use by tests.
@@ -147,11 +173,23 @@ where
             chainstate_handle: ChainstateHandle::new(chainstate_handle),
             mempool_handle,
             peers: Default::default(),
+            local_tx_broadcast_scheduler: LocalTxBroadcastScheduler::new(),
+            local_tx_initial_peers: HashMap::new(),
+            sync_progress: Arc::new(SyncProgressCounters::new()),
+            tx_rejections: Arc::new(TxRejections::new()),
             time_getter,
             observer,
         }
     }
 
+    pub fn sync_progress(&self) -> Arc<SyncProgressCounters> {
+        Arc::clone(&self.sync_progress)
+    }
+
+    pub fn tx_rejections(&self) -> Arc<TxRejections> {
+        Arc::clone(&self.tx_rejections)
+    }
+
     /// Runs the sync manager event loop.
     pub async fn run(mut self) -> Result<Never> {
         log::info!("Starting SyncManager");
@@ -175,6 +213,15 @@ where
                 event = self.syncing_event_receiver.poll_next() => {
                     self.handle_peer_event(event?).await;
                 },
+
+                _ = self.local_tx_broadcast_scheduler.due() => {
+                    if let Some((tx_id, stage)) = self
+                        .local_tx_broadcast_scheduler
+                        .pop(&self.p2p_config.local_tx_broadcast_config)
+                    {
+                        self.announce_local_tx(tx_id, stage);
+                    }
+                },
             }
         }
     }
@@ -184,7 +231,8 @@ where
         &mut self,
         peer_id: PeerId,
         common_services: Services,
-        _protocol_version: SupportedProtocolVersion,
+        protocol_version: SupportedProtocolVersion,
+        is_whitelisted: bool,
         block_sync_msg_receiver: Receiver<BlockSyncMessage>,
         transaction_sync_msg_receiver: Receiver<TransactionSyncMessage>,
     ) {
@@ -204,6 +252,7 @@ where
             block_sync_msg_receiver,
             self.messaging_handle.clone(),
             local_event_receiver,
+            Arc::clone(&self.sync_progress),
             self.time_getter.clone(),
         );
 
@@ -220,6 +269,8 @@ where
         let mut mgr = peer::transaction_manager::PeerTransactionSyncManager::<T>::new(
             peer_id,
             common_services,
+            protocol_version,
+            is_whitelisted,
             Arc::clone(&self.p2p_config),
             self.chainstate_handle.clone(),
             self.mempool_handle.clone(),
@@ -227,6 +278,7 @@ where
             transaction_sync_msg_receiver,
             self.messaging_handle.clone(),
             local_event_receiver,
+            Arc::clone(&self.tx_rejections),
             self.time_getter.clone(),
             self.observer.clone(),
         );
@@ -241,6 +293,7 @@ where
         peer_local_event_senders.push(local_event_sender);
 
         let peer_context = PeerContext {
+            common_services,
             tasks: peer_tasks,
             local_event_senders: peer_local_event_senders,
         };
@@ -268,6 +321,74 @@ where
         }
     }
 
+    /// Announces a local transaction, whose broadcast was delayed, to the peers corresponding
+    /// to the given announcement stage.
+    fn announce_local_tx(&mut self, tx_id: Id<Transaction>, stage: AnnouncementStage) {
+        let event = LocalEvent::MempoolNewTx(tx_id);
+
+        match stage {
+            AnnouncementStage::Initial => {
+                let peer_count =
+                    *self.p2p_config.local_tx_broadcast_config.initial_announcement_peer_count;
+                let peers = self
+                    .peers
+                    .iter()
+                    .filter(|(_, peer_ctx)| {
+                        peer_ctx.common_services.has_service(Service::Transactions)
+                    })
+                    .choose_multiple(&mut make_pseudo_rng(), peer_count);
+
+                log::debug!(
+                    "Announcing local transaction {tx_id} to {} random peers",
+                    peers.len()
+                );
+                for (_, peer_ctx) in &peers {
+                    for sender in &peer_ctx.local_event_senders {
+                        let _ = sender.send(event.clone());
+                    }
+                }
+
+                self.local_tx_initial_peers.insert(
+                    tx_id,
+                    peers.into_iter().map(|(peer_id, _)| *peer_id).collect(),
+                );
+            }
+            AnnouncementStage::Resubmit => {
+                let initial_peers =
+                    self.local_tx_initial_peers.get(&tx_id).map_or(&[][..], |peers| peers);
+                let peer = self
+                    .peers
+                    .iter()
+                    .filter(|(peer_id, peer_ctx)| {
+                        peer_ctx.common_services.has_service(Service::Transactions)
+                            && !initial_peers.contains(*peer_id)
+                    })
+                    .choose(&mut make_pseudo_rng());
+
+                match peer {
+                    Some((peer_id, peer_ctx)) => {
+                        log::debug!(
+                            "Re-submitting local transaction {tx_id} through peer {peer_id}"
+                        );
+                        for sender in &peer_ctx.local_event_senders {
+                            let _ = sender.send(event.clone());
+                        }
+                    }
+                    None => {
+                        log::debug!("No other peer to re-submit local transaction {tx_id} through");
+                    }
+                }
+            }
+            AnnouncementStage::Full => {
+                self.local_tx_initial_peers.remove(&tx_id);
+
+                // The peers that already know about the transaction will ignore the event.
+                log::debug!("Announcing local transaction {tx_id} to all peers");
+                self.send_local_event(&event);
+            }
+        }
+    }
+
     /// Announces the header of a new block to peers.
     async fn handle_new_tip(&mut self, block_id: Id<Block>) -> Result<()> {
         self.peer_mgr_event_sender
@@ -292,10 +413,24 @@ where
             Ok(()) => {
                 use mempool::tx_options::TxRelayPolicy;
                 match tx_proc_event.relay_policy() {
-                    TxRelayPolicy::DoRelay => {
-                        log::info!("Broadcasting transaction {tx_id} originating in {origin}");
-                        self.send_local_event(&LocalEvent::MempoolNewTx(tx_id));
-                    }
+                    TxRelayPolicy::DoRelay => match origin {
+                        TxOrigin::Local(_)
+                            if *self.p2p_config.local_tx_broadcast_config.privacy_enabled =>
+                        {
+                            log::info!(
+                                "Scheduling delayed broadcast of transaction {tx_id} originating in {origin}"
+                            );
+                            self.local_tx_broadcast_scheduler.schedule(
+                                tx_id,
+                                &self.p2p_config.local_tx_broadcast_config,
+                                &mut make_pseudo_rng(),
+                            );
+                        }
+                        TxOrigin::Local(_) | TxOrigin::Remote(_) => {
+                            log::info!("Broadcasting transaction {tx_id} originating in {origin}");
+                            self.send_local_event(&LocalEvent::MempoolNewTx(tx_id));
+                        }
+                    },
                     TxRelayPolicy::DontRelay => {
                         log::trace!("Not propagating transaction {tx_id} originating in {origin}");
                     }
@@ -330,12 +465,14 @@ where
                 peer_id,
                 common_services,
                 protocol_version,
+                is_whitelisted,
                 block_sync_msg_receiver,
                 transaction_sync_msg_receiver,
             } => self.register_peer(
                 peer_id,
                 common_services,
                 protocol_version,
+                is_whitelisted,
                 block_sync_msg_receiver,
                 transaction_sync_msg_receiver,
             ),
@@ -395,7 +532,8 @@ pub async fn subscribe_to_tx_processed(
         mempool::event::MempoolEvent::TransactionProcessed(tpe) => {
             let _ = sender.send(tpe).log_err_pfx("The tx processed receiver closed");
         }
-        mempool::event::MempoolEvent::NewTip(_) => (),
+        mempool::event::MempoolEvent::NewTip(_)
+        | mempool::event::MempoolEvent::MinFeeRateChanged(_) => (),
     };
     let subscribe_func = Arc::new(subscribe_func);
 
@@ -16,12 +16,19 @@
 use std::{
     collections::{BTreeSet, VecDeque},
     mem,
+    time::Duration,
 };
 
 use itertools::Itertools;
-use tokio::sync::mpsc::{Receiver, UnboundedReceiver, UnboundedSender};
+use tokio::{
+    sync::mpsc::{Receiver, UnboundedReceiver, UnboundedSender},
+    time::{Instant, MissedTickBehavior},
+};
 
-use chainstate::{chainstate_interface::ChainstateInterface, BlockIndex, BlockSource, Locator};
+use chainstate::{
+    chainstate_interface::ChainstateInterface, BlockDataAvailability, BlockIndex, BlockSource,
+    Locator,
+};
 use common::{
     chain::{
         block::{signed_block_header::SignedBlockHeader, timestamp::BlockTimestamp},
@@ -31,6 +38,7 @@ use common::{
     time_getter::TimeGetter,
 };
 use logging::log;
+use serialization::Encode;
 use utils::const_value::ConstValue;
 use utils::sync::Arc;
 
@@ -38,16 +46,20 @@ use crate::{
     config::P2pConfig,
     disconnection_reason::DisconnectionReason,
     error::{P2pError, PeerError, ProtocolError, SyncError},
-    message::{BlockListRequest, BlockResponse, BlockSyncMessage, HeaderList, HeaderListRequest},
+    message::{
+        BlockListRequest, BlockResponse, BlockSyncMessage, BlocksNotAvailable, HeaderList,
+        HeaderListRequest,
+    },
     net::{
         types::services::{Service, Services},
         NetworkingService,
     },
-    peer_manager_event::PeerDisconnectionDbAction,
+    peer_manager_event::{AnnouncementKind, PeerDisconnectionDbAction},
     sync::{
         chainstate_handle::ChainstateHandle,
         peer_activity::PeerActivity,
         peer_common::{choose_peers_best_block, handle_message_processing_result},
+        sync_progress::SyncProgressCounters,
         sync_status::PeerBlockSyncStatus,
         LocalEvent,
     },
@@ -56,6 +68,11 @@ use crate::{
     MessagingService, PeerManagerEvent, Result,
 };
 
+use super::pending_tip_announcements::PendingTipAnnouncements;
+
+/// How often the dropped header announcements are reported to the peer manager.
+const DROPPED_ANNOUNCEMENTS_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
 // TODO: Take into account the chain work when syncing.
 /// Block syncing manager.
 ///
@@ -70,6 +87,7 @@ pub struct PeerBlockSyncManager<T: NetworkingService> {
     messaging_handle: T::MessagingHandle,
     sync_msg_receiver: Receiver<BlockSyncMessage>,
     local_event_receiver: UnboundedReceiver<LocalEvent>,
+    sync_progress: Arc<SyncProgressCounters>,
     time_getter: TimeGetter,
     /// Incoming data state.
     incoming: IncomingDataState,
@@ -93,6 +111,10 @@ struct IncomingDataState {
     /// This includes headers received by any means, e.g. via HeaderList messages, as part
     /// of a locator during peer's header requests, via block responses.
     peers_best_block_that_we_have: Option<Id<GenBlock>>,
+    /// The height of the first block whose data the peer has, if the peer has announced it
+    /// or has reported some of the requested blocks as not available. Blocks below this height
+    /// are not requested from the peer.
+    peers_first_block_with_data: Option<BlockHeight>,
 }
 
 struct OutgoingDataState {
@@ -103,6 +125,10 @@ struct OutgoingDataState {
     /// The id of the best block header that we've sent to the peer.
     // Note: at this moment this field is only informational, i.e. we only print it to the log.
     best_sent_block_header: Option<Id<GenBlock>>,
+    /// The first height of the block data availability range that we've announced to the peer.
+    announced_first_block_with_data: Option<BlockHeight>,
+    /// The new tips whose headers are waiting to be announced to the peer.
+    pending_tip_announcements: PendingTipAnnouncements,
 }
 
 impl<T> PeerBlockSyncManager<T>
@@ -121,6 +147,7 @@ where
         sync_msg_receiver: Receiver<BlockSyncMessage>,
         messaging_handle: T::MessagingHandle,
         local_event_receiver: UnboundedReceiver<LocalEvent>,
+        sync_progress: Arc<SyncProgressCounters>,
         time_getter: TimeGetter,
     ) -> Self {
         Self {
@@ -133,16 +160,22 @@ where
             messaging_handle,
             sync_msg_receiver,
             local_event_receiver,
+            sync_progress,
             time_getter,
             incoming: IncomingDataState {
                 pending_headers: Vec::new(),
                 requested_blocks: VecDeque::new(),
                 peers_best_block_that_we_have: None,
+                peers_first_block_with_data: None,
             },
             outgoing: OutgoingDataState {
                 blocks_queue: VecDeque::new(),
                 best_sent_block: None,
                 best_sent_block_header: None,
+                announced_first_block_with_data: None,
+                pending_tip_announcements: PendingTipAnnouncements::new(
+                    *p2p_config.protocol_config.max_peer_pending_header_announcements,
+                ),
             },
             peer_activity: PeerActivity::new(),
             have_sent_all_headers: false,
@@ -171,8 +204,16 @@ where
             self.request_headers().await?;
         }
 
+        self.announce_block_data_availability().await?;
+
         self.handle_sync_status_change(&last_sync_status)?;
 
+        let mut dropped_announcements_report_interval = tokio::time::interval_at(
+            Instant::now() + DROPPED_ANNOUNCEMENTS_REPORT_INTERVAL,
+            DROPPED_ANNOUNCEMENTS_REPORT_INTERVAL,
+        );
+        dropped_announcements_report_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
         loop {
             let last_sync_status = self.get_sync_status();
 
@@ -188,6 +229,13 @@ where
                     self.send_block(block_to_send_to_peer).await?;
                 }
 
+                new_tip_id = async {
+                    self.outgoing.pending_tip_announcements.pop().expect("The tip announcement queue is empty")
+                }, if !self.outgoing.pending_tip_announcements.is_empty() => {
+                    self.announce_block_data_availability().await?;
+                    self.handle_new_tip(&new_tip_id).await?;
+                }
+
                 event = self.local_event_receiver.recv() => {
                     let event = event.ok_or(P2pError::ChannelClosed)?;
                     self.handle_local_event(event).await?;
@@ -195,6 +243,10 @@ where
 
                 _ = tokio::time::sleep(stalling_timeout),
                     if self.peer_activity.earliest_expected_activity_time().is_some() => {}
+
+                _ = dropped_announcements_report_interval.tick() => {
+                    self.report_dropped_announcements()?;
+                }
             }
 
             self.handle_sync_status_change(&last_sync_status)?;
@@ -332,11 +384,64 @@ where
         );
 
         match event {
-            LocalEvent::ChainstateNewTip(new_tip_id) => self.handle_new_tip(&new_tip_id).await,
+            LocalEvent::ChainstateNewTip(new_tip_id) => self.queue_tip_announcement(new_tip_id),
             LocalEvent::MempoolNewTx(_) => Ok(()),
         }
     }
 
+    /// Queue the announcement of the new tip, dropping the oldest queued one if the queue is full.
+    fn queue_tip_announcement(&mut self, new_tip_id: Id<Block>) -> Result<()> {
+        if let Some(dropped_tip_id) = self.outgoing.pending_tip_announcements.push(new_tip_id) {
+            log::debug!(
+                "[peer id = {}] Header announcement queue is full, dropping announcement of {dropped_tip_id}",
+                self.id()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Report the announcements dropped since the previous report to the peer manager.
+    fn report_dropped_announcements(&mut self) -> Result<()> {
+        let count = self.outgoing.pending_tip_announcements.take_unreported_dropped_count();
+        if count > 0 {
+            self.peer_mgr_event_sender.send(PeerManagerEvent::PeerAnnouncementsDropped {
+                peer_id: self.id(),
+                kind: AnnouncementKind::Header,
+                count,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Sends the range of blocks whose data we can serve to the peer.
+    ///
+    /// This is only done if we don't have the data of all the mainchain blocks and the range
+    /// has changed since the last announcement; a peer that hasn't received the range assumes
+    /// that all blocks are available.
+    async fn announce_block_data_availability(&mut self) -> Result<()> {
+        if !self.common_services.has_service(Service::BlockDataAvailability) {
+            return Ok(());
+        }
+
+        let availability =
+            self.chainstate_handle.call(|c| Ok(c.get_block_data_availability()?)).await?;
+        if availability.is_complete()
+            || self.outgoing.announced_first_block_with_data == Some(availability.first_height())
+        {
+            return Ok(());
+        }
+
+        log::debug!(
+            "[peer id = {}] Announcing block data availability: {}-{}",
+            self.id(),
+            availability.first_height(),
+            availability.last_height()
+        );
+        self.outgoing.announced_first_block_with_data = Some(availability.first_height());
+        self.send_message(BlockSyncMessage::BlockDataAvailability(availability))
+    }
+
     async fn request_headers(&mut self) -> Result<()> {
         let locator = self.chainstate_handle.call(|this| Ok(this.get_locator()?)).await?;
         if locator.len() > *self.p2p_config.protocol_config.msg_max_locator_count {
@@ -374,6 +479,10 @@ where
             }
             BlockSyncMessage::HeaderList(l) => self.handle_header_list(l.into_headers()).await,
             BlockSyncMessage::BlockResponse(r) => self.handle_block_response(r.into_block()).await,
+            BlockSyncMessage::BlockDataAvailability(a) => self.handle_block_data_availability(a),
+            BlockSyncMessage::BlocksNotAvailable(r) => {
+                self.handle_blocks_not_available(r.into_block_ids()).await
+            }
 
             #[cfg(test)]
             BlockSyncMessage::TestSentinel(id) => {
@@ -494,8 +603,12 @@ where
         // Then check the chainstate
         let ids = block_ids.clone();
         let best_sent_block = self.outgoing.best_sent_block.clone();
-        self.chainstate_handle
+        let unavailable_block_ids = self
+            .chainstate_handle
             .call(move |c| {
+                let first_block_with_data = c.get_block_data_availability()?.first_height();
+                let mut unavailable_block_ids = Vec::new();
+
                 for id in ids {
                     // Note: in the future, when/if we implement block purging, it may be possible for a previously
                     // existing block (and therefore its BlockIndex) not to exist anymore; if this happens, the
@@ -507,6 +620,10 @@ where
                         P2pError::ProtocolError(ProtocolError::UnknownBlockRequested(id)),
                     )?;
 
+                    if index.block_height() < first_block_with_data {
+                        unavailable_block_ids.push(id);
+                    }
+
                     if let Some(ref best_sent_block) = best_sent_block {
                         if index.block_height() <= best_sent_block.block_height() {
                             // This can be normal in case of reorg; ensure that the mainchain block
@@ -526,10 +643,27 @@ where
                     }
                 }
 
-                Ok(())
+                Ok(unavailable_block_ids)
             })
             .await?;
 
+        let block_ids = if !unavailable_block_ids.is_empty()
+            && self.common_services.has_service(Service::BlockDataAvailability)
+        {
+            log::debug!(
+                "[peer id = {}] The data of {} requested blocks is not available",
+                self.id(),
+                unavailable_block_ids.len()
+            );
+            let unavailable: BTreeSet<_> = unavailable_block_ids.iter().copied().collect();
+            self.send_message(BlockSyncMessage::BlocksNotAvailable(
+                BlocksNotAvailable::new(unavailable_block_ids),
+            ))?;
+            block_ids.into_iter().filter(|id| !unavailable.contains(id)).collect()
+        } else {
+            block_ids
+        };
+
         // Note: we've already checked that the total number of elements in the queue
         // won't exceed max_request_blocks_count.
         // TODO: we might want to overwrite the queue here instead of extending it, see
@@ -642,6 +776,7 @@ where
             .ok_or(P2pError::ProtocolError(ProtocolError::DisconnectedHeaders))?
             .block_height();
 
+        let headers_count = headers.len();
         let last_header = headers.last().expect("Headers shouldn't be empty");
         let last_header_height = first_header_prev_block_height
             .checked_add(headers.len() as u64)
@@ -687,18 +822,114 @@ where
             return Ok(());
         }
 
-        // Now use preliminary_headers_check; this can be done because the first header
-        // is known to be connected to the chainstate.
+        // Now validate the headers and store them in the chainstate ahead of the blocks;
+        // this can be done because the first header is known to be connected to the chainstate.
         {
             let new_block_headers = new_block_headers.clone();
             self.chainstate_handle
-                .call(move |c| Ok(c.preliminary_headers_check(&new_block_headers)?))
+                .call_mut(move |c| Ok(c.process_block_headers(new_block_headers)?))
                 .await?;
         }
 
+        let first_new_header_height = first_header_prev_block_height
+            .checked_add((headers_count - new_block_headers.len()) as u64 + 1)
+            .expect("cannot overflow");
+        if !self.peer_may_have_block_data(first_new_header_height) {
+            // The blocks will be downloaded from other peers.
+            log::debug!(
+                "[peer id = {}] Not requesting blocks, because the peer doesn't have the data of the block at height {}",
+                self.id(),
+                first_new_header_height
+            );
+            return Ok(());
+        }
+
         self.request_blocks(new_block_headers)
     }
 
+    fn peer_may_have_block_data(&self, height: BlockHeight) -> bool {
+        self.incoming.peers_first_block_with_data.map_or(true, |first_block_with_data| {
+            height >= first_block_with_data
+        })
+    }
+
+    fn handle_block_data_availability(
+        &mut self,
+        availability: BlockDataAvailability,
+    ) -> Result<()> {
+        log::debug!(
+            "[peer id = {}] Handling block data availability: {}-{}",
+            self.id(),
+            availability.first_height(),
+            availability.last_height()
+        );
+
+        utils::ensure!(
+            self.common_services.has_service(Service::BlockDataAvailability),
+            P2pError::ProtocolError(ProtocolError::UnexpectedMessage(
+                "BlockDataAvailability".to_owned()
+            ))
+        );
+
+        self.incoming.peers_first_block_with_data = Some(availability.first_height());
+        Ok(())
+    }
+
+    async fn handle_blocks_not_available(&mut self, block_ids: Vec<Id<Block>>) -> Result<()> {
+        log::debug!(
+            "[peer id = {}] Handling blocks not available response ({})",
+            self.id(),
+            block_ids.len()
+        );
+
+        utils::ensure!(
+            self.common_services.has_service(Service::BlockDataAvailability),
+            P2pError::ProtocolError(ProtocolError::UnexpectedMessage(
+                "BlocksNotAvailable".to_owned()
+            ))
+        );
+
+        for id in &block_ids {
+            let idx = self
+                .incoming
+                .requested_blocks
+                .iter()
+                .position(|requested_id| requested_id == id)
+                .ok_or(P2pError::ProtocolError(
+                    ProtocolError::UnsolicitedBlockNotAvailableReceived(*id),
+                ))?;
+            self.incoming.requested_blocks.remove(idx);
+        }
+
+        // The peer won't have the data of the blocks below the reported ones either, so don't
+        // ask it for them again; the pending blocks will be downloaded from other peers.
+        let max_unavailable_height = self
+            .chainstate_handle
+            .call(move |c| {
+                let mut max_height = None;
+                for id in block_ids {
+                    if let Some(index) = c.get_block_index_for_any_block(&id)? {
+                        max_height = std::cmp::max(max_height, Some(index.block_height()));
+                    }
+                }
+                Ok(max_height)
+            })
+            .await?;
+        if let Some(height) = max_unavailable_height {
+            self.incoming.peers_first_block_with_data = std::cmp::max(
+                self.incoming.peers_first_block_with_data,
+                Some(height.next_height()),
+            );
+        }
+        self.incoming.pending_headers.clear();
+
+        if self.incoming.requested_blocks.is_empty() {
+            self.peer_activity.set_expecting_blocks_since(None);
+        }
+
+        Ok(())
+    }
+
     async fn handle_block_response(&mut self, block: Block) -> Result<()> {
         let block_id = block.get_id();
         log::debug!(
@@ -730,6 +961,7 @@ where
         }
 
         self.incoming.requested_blocks.pop_front();
+        self.sync_progress.on_block_received(block.encoded_size());
 
         if self.incoming.requested_blocks.is_empty() {
             self.peer_activity.set_expecting_blocks_since(None);
@@ -853,7 +1085,15 @@ where
         // block to become missing by this point. This should be a rare and low impact situation,
         // but at least we should fail gracefully here and not panic.
         // Also see a similar note in handle_block_request.
-        let block = block?.ok_or(SyncError::BlockDataMissingInSendBlock(id))?;
+        let block = match block? {
+            Some(block) => block,
+            None if self.common_services.has_service(Service::BlockDataAvailability) => {
+                return self.send_message(BlockSyncMessage::BlocksNotAvailable(
+                    BlocksNotAvailable::new(vec![id]),
+                ));
+            }
+            None => return Err(SyncError::BlockDataMissingInSendBlock(id).into()),
+        };
         let block_index = block_index?.ok_or(SyncError::BlockIndexMissingInSendBlock(id))?;
 
         let old_best_sent_block_id = self.outgoing.best_sent_block.as_ref().map(|idx| {
@@ -14,7 +14,9 @@
 // limitations under the License.
 
 pub mod block_manager;
+pub mod orphan_parent_requests;
 pub mod requested_transactions;
 pub mod transaction_manager;
 
+mod pending_tip_announcements;
 mod pending_transactions;
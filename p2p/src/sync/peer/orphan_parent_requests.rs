@@ -0,0 +1,117 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use common::{
+    chain::Transaction,
+    primitives::{time::Time, Id},
+    time_getter::TimeGetter,
+};
+
+/// A request for a parent of an orphan transaction will expire after this duration.
+///
+/// This is much shorter than `REQUESTED_TX_EXPIRY_PERIOD`, because the orphan is only kept
+/// in the orphan pool for a limited time, so there is no point in waiting for its parent longer.
+pub const ORPHAN_PARENT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// This struct tracks the missing parents of orphan transactions that have been requested
+/// from a peer, but for which no response has been received yet.
+///
+/// Note that the requests are also tracked by `RequestedTransactions`, so that the responses
+/// are handled as solicited ones.
+pub struct OrphanParentRequests {
+    requests: BTreeMap<Id<Transaction>, Time>,
+    time_getter: TimeGetter,
+}
+
+impl OrphanParentRequests {
+    pub fn new(time_getter: TimeGetter) -> Self {
+        Self {
+            requests: BTreeMap::new(),
+            time_getter,
+        }
+    }
+
+    pub fn add(&mut self, id: &Id<Transaction>) {
+        let cur_time = self.time_getter.get_time();
+        let old_val = self.requests.insert(*id, cur_time);
+        assert!(old_val.is_none());
+    }
+
+    pub fn remove(&mut self, id: &Id<Transaction>) -> Option<Time> {
+        self.requests.remove(id)
+    }
+
+    pub fn count(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Remove the expired requests and return their ids.
+    pub fn take_expired(&mut self) -> Vec<Id<Transaction>> {
+        let cur_time = self.time_getter.get_time();
+        let min_time = (cur_time - ORPHAN_PARENT_REQUEST_TIMEOUT).expect("Bad time or time offset");
+
+        // Note: the number of requests is limited by `max_peer_orphan_parent_requests`,
+        // so linear complexity is ok.
+        let expired = self
+            .requests
+            .iter()
+            .filter_map(|(id, time)| (*time < min_time).then_some(*id))
+            .collect::<Vec<_>>();
+        for id in &expired {
+            self.requests.remove(id);
+        }
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::primitives::H256;
+    use rstest::rstest;
+    use test_utils::{
+        random::{make_seedable_rng, Seed},
+        BasicTestTimeGetter,
+    };
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn expiration(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let time_getter = BasicTestTimeGetter::new();
+
+        let tx1 = Id::<Transaction>::new(H256::random_using(&mut rng));
+        let tx2 = Id::<Transaction>::new(H256::random_using(&mut rng));
+
+        let mut requests = OrphanParentRequests::new(time_getter.get_time_getter());
+        requests.add(&tx1);
+        time_getter.advance_time(ORPHAN_PARENT_REQUEST_TIMEOUT / 2);
+        requests.add(&tx2);
+        assert_eq!(requests.count(), 2);
+        assert!(requests.take_expired().is_empty());
+
+        time_getter.advance_time(ORPHAN_PARENT_REQUEST_TIMEOUT / 2 + Duration::from_secs(1));
+        assert_eq!(requests.take_expired(), vec![tx1]);
+        assert_eq!(requests.count(), 1);
+
+        assert!(requests.remove(&tx2).is_some());
+        assert!(requests.remove(&tx2).is_none());
+        assert_eq!(requests.count(), 0);
+    }
+}
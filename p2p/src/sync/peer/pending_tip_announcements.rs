@@ -0,0 +1,119 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::VecDeque, num::NonZeroUsize};
+
+use common::{chain::Block, primitives::Id};
+
+/// New tips whose headers are waiting to be announced to a peer.
+///
+/// The queue is bounded; when it's full, the oldest tip is dropped, since the headers are
+/// sent up to the current tip anyway.
+pub struct PendingTipAnnouncements {
+    tips: VecDeque<Id<Block>>,
+    max_size: NonZeroUsize,
+    dropped_count: u64,
+    reported_dropped_count: u64,
+}
+
+impl PendingTipAnnouncements {
+    pub fn new(max_size: NonZeroUsize) -> Self {
+        Self {
+            tips: VecDeque::new(),
+            max_size,
+            dropped_count: 0,
+            reported_dropped_count: 0,
+        }
+    }
+
+    /// Queue the announcement; returns the announcement that had to be dropped,
+    /// if the queue was full.
+    pub fn push(&mut self, tip: Id<Block>) -> Option<Id<Block>> {
+        let dropped = if self.tips.len() >= self.max_size.get() {
+            self.dropped_count += 1;
+            self.tips.pop_front()
+        } else {
+            None
+        };
+
+        self.tips.push_back(tip);
+        dropped
+    }
+
+    pub fn pop(&mut self) -> Option<Id<Block>> {
+        self.tips.pop_front()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tips.is_empty()
+    }
+
+    /// The number of announcements dropped since the previous call.
+    pub fn take_unreported_dropped_count(&mut self) -> u64 {
+        let count = self.dropped_count - self.reported_dropped_count;
+        self.reported_dropped_count = self.dropped_count;
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::primitives::H256;
+    use rstest::rstest;
+    use test_utils::random::{make_seedable_rng, Rng, Seed};
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn drop_oldest_when_full(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        let max_size = rng.gen_range(1..10);
+        let extra_count = rng.gen_range(1..10);
+
+        let mut tips = PendingTipAnnouncements::new(NonZeroUsize::new(max_size).unwrap());
+        assert_eq!(tips.pop(), None);
+
+        let queued = (0..max_size + extra_count)
+            .map(|_| Id::<Block>::new(H256::random_using(&mut rng)))
+            .collect::<Vec<_>>();
+
+        for (i, tip) in queued.iter().enumerate() {
+            let dropped = tips.push(*tip);
+            if i < max_size {
+                assert_eq!(dropped, None);
+            } else {
+                assert_eq!(dropped, Some(queued[i - max_size]));
+            }
+        }
+        assert_eq!(tips.take_unreported_dropped_count(), extra_count as u64);
+        assert_eq!(tips.take_unreported_dropped_count(), 0);
+
+        // The most recently queued ones remain, in the order they were queued
+        for tip in &queued[extra_count..] {
+            assert_eq!(tips.pop(), Some(*tip));
+        }
+        assert!(tips.is_empty());
+        assert_eq!(tips.pop(), None);
+
+        // Only the drops since the previous call are reported
+        for tip in &queued[..max_size + 1] {
+            tips.push(*tip);
+        }
+        assert_eq!(tips.take_unreported_dropped_count(), 1);
+    }
+}
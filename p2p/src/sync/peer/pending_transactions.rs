@@ -13,42 +13,103 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{cmp::Reverse, collections::BinaryHeap};
+use std::{collections::BTreeMap, num::NonZeroUsize};
+
 use tokio::time::Instant;
 
 use common::{chain::Transaction, primitives::Id};
 
+/// Transaction announcements waiting to be sent to a peer.
+///
+/// The queue is bounded; when it's full, the announcement that was queued first is dropped
+/// to make room for the new one, so that a slow peer can't make the node accumulate
+/// announcements indefinitely (e.g. when lots of transactions return to the mempool after
+/// a reorg).
 pub struct PendingTransactions {
-    txs: BinaryHeap<Reverse<(Instant, Id<Transaction>)>>,
+    /// The announcements ordered by their due time; the sequence number makes the keys unique.
+    txs: BTreeMap<(Instant, u64), Id<Transaction>>,
+    /// The due times of the announcements in the order they were queued.
+    queue_order: BTreeMap<u64, Instant>,
+    next_seq: u64,
+    max_size: NonZeroUsize,
+    dropped_count: u64,
+    reported_dropped_count: u64,
 }
 
 impl PendingTransactions {
-    pub fn new() -> Self {
+    pub fn new(max_size: NonZeroUsize) -> Self {
         Self {
-            txs: Default::default(),
+            txs: BTreeMap::new(),
+            queue_order: BTreeMap::new(),
+            next_seq: 0,
+            max_size,
+            dropped_count: 0,
+            reported_dropped_count: 0,
         }
     }
 
-    pub fn push(&mut self, tx: Id<Transaction>, due_time: Instant) {
-        self.txs.push(Reverse((due_time, tx)));
+    /// Queue the announcement; returns the announcement that had to be dropped,
+    /// if the queue was full.
+    pub fn push(&mut self, tx: Id<Transaction>, due_time: Instant) -> Option<Id<Transaction>> {
+        let dropped = if self.txs.len() >= self.max_size.get() {
+            self.drop_oldest()
+        } else {
+            None
+        };
+
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.txs.insert((due_time, seq), tx);
+        self.queue_order.insert(seq, due_time);
+        dropped
     }
 
     pub fn pop(&mut self) -> Option<Id<Transaction>> {
-        self.txs.pop().map(|item| {
-            let (_, tx) = item.0;
+        self.txs.pop_first().map(|((_, seq), tx)| {
+            self.queue_order.remove(&seq);
             tx
         })
     }
 
+    /// Pop up to `limit` announcements that are due at `now`.
+    pub fn pop_due(&mut self, now: Instant, limit: usize) -> Vec<Id<Transaction>> {
+        let mut result = Vec::new();
+        while result.len() < limit
+            && self.txs.first_key_value().is_some_and(|((due_time, _), _)| *due_time <= now)
+        {
+            result.extend(self.pop());
+        }
+        result
+    }
+
+    /// The total number of announcements dropped because the queue was full.
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped_count
+    }
+
+    /// The number of announcements dropped since the previous call.
+    pub fn take_unreported_dropped_count(&mut self) -> u64 {
+        let count = self.dropped_count - self.reported_dropped_count;
+        self.reported_dropped_count = self.dropped_count;
+        count
+    }
+
     pub async fn due(&self) {
-        match self.txs.peek() {
-            Some(item) => {
-                let (due, _) = item.0;
-                tokio::time::sleep_until(due).await;
+        match self.txs.first_key_value() {
+            Some(((due, _), _)) => {
+                tokio::time::sleep_until(*due).await;
             }
             None => std::future::pending().await,
         }
     }
+
+    fn drop_oldest(&mut self) -> Option<Id<Transaction>> {
+        let (seq, due_time) = self.queue_order.pop_first()?;
+        let tx = self.txs.remove(&(due_time, seq));
+        debug_assert!(tx.is_some());
+        self.dropped_count += 1;
+        tx
+    }
 }
 
 #[cfg(test)]
@@ -75,7 +136,7 @@ mod tests {
         let instant2 = Instant::now() + Duration::from_secs(2);
         let instant3 = Instant::now() + Duration::from_secs(3);
 
-        let mut txs = PendingTransactions::new();
+        let mut txs = PendingTransactions::new(NonZeroUsize::MAX);
         assert_eq!(None, txs.pop());
 
         txs.push(tx3, instant3);
@@ -88,6 +149,107 @@ mod tests {
         assert_eq!(None, txs.pop());
     }
 
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn drop_oldest_when_full(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        let max_size = rng.gen_range(1..10);
+        let extra_count = rng.gen_range(1..10);
+        let now = Instant::now();
+
+        let mut txs = PendingTransactions::new(NonZeroUsize::new(max_size).unwrap());
+        let queued = (0..max_size + extra_count)
+            .map(|_| {
+                let tx = Id::<Transaction>::new(H256::random_using(&mut rng));
+                let due_time = now + Duration::from_secs(rng.gen_range(0..100));
+                (tx, due_time)
+            })
+            .collect::<Vec<_>>();
+
+        for (i, (tx, due_time)) in queued.iter().enumerate() {
+            let dropped = txs.push(*tx, *due_time);
+            if i < max_size {
+                assert_eq!(dropped, None);
+            } else {
+                assert_eq!(dropped, Some(queued[i - max_size].0));
+            }
+        }
+        assert_eq!(txs.dropped_count(), extra_count as u64);
+
+        // The most recently queued ones remain, in the order of their due times
+        let mut expected = queued[extra_count..].to_vec();
+        expected.sort_by_key(|(_, due_time)| *due_time);
+        let popped = txs.pop_due(now + Duration::from_secs(100), usize::MAX);
+        assert_eq!(
+            popped,
+            expected.iter().map(|(tx, _)| *tx).collect::<Vec<_>>()
+        );
+        assert_eq!(txs.pop(), None);
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn unreported_dropped_count(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        let now = Instant::now();
+        let mut txs = PendingTransactions::new(NonZeroUsize::new(1).unwrap());
+        assert_eq!(txs.take_unreported_dropped_count(), 0);
+
+        let mut push_count = |txs: &mut PendingTransactions, count| {
+            for _ in 0..count {
+                let tx = Id::<Transaction>::new(H256::random_using(&mut rng));
+                txs.push(tx, now);
+            }
+        };
+
+        // The first announcement fits into the queue
+        let count1 = rng.gen_range(1..10);
+        push_count(&mut txs, count1);
+        assert_eq!(txs.take_unreported_dropped_count(), count1 - 1);
+        assert_eq!(txs.take_unreported_dropped_count(), 0);
+
+        // Only the drops since the previous call are reported
+        let count2 = rng.gen_range(1..10);
+        push_count(&mut txs, count2);
+        assert_eq!(txs.take_unreported_dropped_count(), count2);
+        assert_eq!(txs.take_unreported_dropped_count(), 0);
+        assert_eq!(txs.dropped_count(), count1 + count2 - 1);
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn pop_due_test(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        let now = Instant::now() + Duration::from_secs(1000);
+        let due_count = rng.gen_range(1..20);
+        let not_due_count = rng.gen_range(0..20);
+
+        let mut txs = PendingTransactions::new(NonZeroUsize::MAX);
+        for _ in 0..due_count {
+            let tx = Id::<Transaction>::new(H256::random_using(&mut rng));
+            txs.push(tx, now - Duration::from_secs(rng.gen_range(0..100)));
+        }
+        for _ in 0..not_due_count {
+            let tx = Id::<Transaction>::new(H256::random_using(&mut rng));
+            txs.push(tx, now + Duration::from_secs(rng.gen_range(1..100)));
+        }
+
+        let limit = rng.gen_range(1..=due_count);
+        assert_eq!(txs.pop_due(now, limit).len(), limit);
+        assert_eq!(txs.pop_due(now, usize::MAX).len(), due_count - limit);
+        assert!(txs.pop_due(now, usize::MAX).is_empty());
+        assert_eq!(
+            txs.pop_due(now + Duration::from_secs(100), usize::MAX).len(),
+            not_due_count
+        );
+    }
+
     #[tokio::test]
     async fn due_test() {
         let before = Instant::now();
@@ -95,7 +257,7 @@ mod tests {
         let tx = Id::<Transaction>::new(H256::zero());
         let due_instant = Instant::now() + Duration::from_secs(1);
 
-        let mut txs = PendingTransactions::new();
+        let mut txs = PendingTransactions::new(NonZeroUsize::MAX);
         txs.push(tx, due_instant);
 
         tokio::time::pause();
@@ -115,7 +277,7 @@ mod tests {
         let tx = Id::<Transaction>::new(H256::zero());
         let due_instant = Instant::now();
 
-        let mut txs = PendingTransactions::new();
+        let mut txs = PendingTransactions::new(NonZeroUsize::MAX);
         txs.push(tx, due_instant);
 
         tokio::time::pause();
@@ -142,7 +304,7 @@ mod tests {
         // Spawn a task with a timeout
         let timeout_duration = Duration::from_secs(rng.gen_range(1..120));
         let test_task = tokio::spawn(async move {
-            let txs = PendingTransactions::new();
+            let txs = PendingTransactions::new(NonZeroUsize::MAX);
             tokio::time::timeout(timeout_duration, txs.due()).await
         });
 
@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::time::Duration;
+use std::{collections::BTreeSet, time::Duration};
 
 use randomness::make_pseudo_rng;
 use tokio::{
@@ -27,21 +27,32 @@ use common::{
     time_getter::TimeGetter,
 };
 use logging::log;
-use mempool::{MempoolHandle, TxOptions};
+use mempool::{
+    error::{Error as MempoolError, MempoolPolicyError, OrphanPoolError, TxValidationError},
+    tx_options::TxTrustPolicy,
+    MempoolHandle, TxOptions,
+};
 use utils::const_value::ConstValue;
 use utils::sync::Arc;
 
 use crate::{
     config::P2pConfig,
     error::{P2pError, ProtocolError},
-    message::{TransactionResponse, TransactionSyncMessage},
+    interface::types::TransactionRejection,
+    message::{
+        TransactionRejectCode, TransactionRejectMessage, TransactionResponse,
+        TransactionSyncMessage,
+    },
     net::{
         types::services::{Service, Services},
         NetworkingService,
     },
+    peer_manager_event::AnnouncementKind,
+    protocol::{ProtocolFeature, SupportedProtocolVersion},
     sync::{
         chainstate_handle::ChainstateHandle,
         peer_common::{handle_message_processing_result, KnownTransactions},
+        tx_rejections::TxRejections,
         BoxedObserver, LocalEvent,
     },
     types::peer_id::PeerId,
@@ -49,12 +60,16 @@ use crate::{
 };
 
 use super::{
-    pending_transactions::PendingTransactions, requested_transactions::RequestedTransactions,
+    orphan_parent_requests::OrphanParentRequests, pending_transactions::PendingTransactions,
+    requested_transactions::RequestedTransactions,
 };
 
 // TODO: add smaller interval for outbound connections
 pub const TX_RELAY_DELAY_INTERVAL: Duration = Duration::from_secs(5);
 
+/// The maximum number of due transaction announcements sent to the peer at once.
+const MAX_TX_ANNOUNCEMENTS_PER_BATCH: usize = 100;
+
 // TODO: Take into account the chain work when syncing.
 /// Transaction sync manager.
 ///
@@ -63,6 +78,10 @@ pub struct PeerTransactionSyncManager<T: NetworkingService> {
     id: ConstValue<PeerId>,
     p2p_config: Arc<P2pConfig>,
     common_services: Services,
+    protocol_version: SupportedProtocolVersion,
+    /// Whitelisted peers get transactions without a delay and their transactions are exempt
+    /// from the minimum relay fee
+    is_whitelisted: bool,
     chainstate_handle: ChainstateHandle,
     mempool_handle: MempoolHandle,
     peer_mgr_event_sender: UnboundedSender<PeerManagerEvent>,
@@ -74,9 +93,15 @@ pub struct PeerTransactionSyncManager<T: NetworkingService> {
     /// This tracks transactions that we've requested from this peer but for which we haven't
     /// received a response yet.
     requested_transactions: RequestedTransactions,
+    /// The missing parents of orphan transactions received from this peer that have been
+    /// requested from it.
+    orphan_parent_requests: OrphanParentRequests,
     /// Txs aren't relayed immediately but rather put into a collection to be propagated later
     /// with random delay to make tracing transactions' origin harder
     pending_transactions: PendingTransactions,
+    /// Rejections of our transactions reported by peers, shared with the other peer tasks.
+    tx_rejections: Arc<TxRejections>,
+    time_getter: TimeGetter,
     /// SyncManager's observer for use by tests.
     observer: Option<BoxedObserver>,
 }
@@ -90,6 +115,8 @@ where
     pub fn new(
         id: PeerId,
         common_services: Services,
+        protocol_version: SupportedProtocolVersion,
+        is_whitelisted: bool,
         p2p_config: Arc<P2pConfig>,
         chainstate_handle: ChainstateHandle,
         mempool_handle: MempoolHandle,
@@ -97,15 +124,20 @@ where
         sync_msg_receiver: Receiver<TransactionSyncMessage>,
         messaging_handle: T::MessagingHandle,
         local_event_receiver: UnboundedReceiver<LocalEvent>,
+        tx_rejections: Arc<TxRejections>,
         time_getter: TimeGetter,
         observer: Option<BoxedObserver>,
     ) -> Self {
         let known_transactions = KnownTransactions::new();
+        let pending_transactions =
+            PendingTransactions::new(*p2p_config.protocol_config.max_peer_pending_tx_announcements);
 
         Self {
             id: id.into(),
             p2p_config,
             common_services,
+            protocol_version,
+            is_whitelisted,
             chainstate_handle,
             mempool_handle,
             peer_mgr_event_sender,
@@ -113,8 +145,11 @@ where
             sync_msg_receiver,
             local_event_receiver,
             known_transactions,
-            requested_transactions: RequestedTransactions::new(time_getter),
-            pending_transactions: PendingTransactions::new(),
+            requested_transactions: RequestedTransactions::new(time_getter.clone()),
+            orphan_parent_requests: OrphanParentRequests::new(time_getter.clone()),
+            pending_transactions,
+            tx_rejections,
+            time_getter,
             observer,
         }
     }
@@ -125,7 +160,17 @@ where
     }
 
     pub async fn run(&mut self) {
-        match self.main_loop().await {
+        let result = self.main_loop().await;
+
+        let dropped_count = self.pending_transactions.dropped_count();
+        if dropped_count > 0 {
+            log::info!(
+                "[peer id = {}] {dropped_count} transaction announcements were dropped because the send queue was full",
+                self.id()
+            );
+        }
+
+        match result {
             // The unexpected "channel closed" error will be handled by the sync manager.
             Ok(()) | Err(P2pError::ChannelClosed) => {}
             Err(e) => panic!("{} peer task failed: {e:?}", self.id()),
@@ -158,18 +203,37 @@ where
                 }
 
                 _ = self.pending_transactions.due() => {
-                    if let Some(new_tx) = self.pending_transactions.pop(){
+                    let due_txs = self
+                        .pending_transactions
+                        .pop_due(Instant::now(), MAX_TX_ANNOUNCEMENTS_PER_BATCH);
+                    for new_tx in due_txs {
                         self.send_message(TransactionSyncMessage::NewTransaction(new_tx))?;
                     }
                 }
 
-                _ = maintenance_interval.tick() => {}
+                _ = maintenance_interval.tick() => {
+                    self.report_dropped_announcements()?;
+                }
             }
 
             self.requested_transactions.purge_if_needed();
+            self.purge_expired_orphan_parent_requests();
         }
     }
 
+    /// Report the announcements dropped since the previous report to the peer manager.
+    fn report_dropped_announcements(&mut self) -> Result<()> {
+        let count = self.pending_transactions.take_unreported_dropped_count();
+        if count > 0 {
+            self.peer_mgr_event_sender.send(PeerManagerEvent::PeerAnnouncementsDropped {
+                peer_id: self.id(),
+                kind: AnnouncementKind::Transaction,
+                count,
+            })?;
+        }
+        Ok(())
+    }
+
     fn send_message(&mut self, message: TransactionSyncMessage) -> Result<()> {
         self.messaging_handle.send_transaction_sync_message(self.id(), message)
     }
@@ -188,11 +252,19 @@ where
                 {
                     self.add_known_transaction(txid);
 
-                    // TODO: whitelisted peers should get txs without delay
+                    if self.is_whitelisted {
+                        return self.send_message(TransactionSyncMessage::NewTransaction(txid));
+                    }
+
                     let now = Instant::now();
                     let delay = TX_RELAY_DELAY_INTERVAL
                         .mul_f64(utils::exp_rand::exponential_rand(&mut make_pseudo_rng()));
-                    self.pending_transactions.push(txid, now + delay);
+                    if let Some(dropped_txid) = self.pending_transactions.push(txid, now + delay) {
+                        log::debug!(
+                            "[peer id = {}] Transaction announcement queue is full, dropping announcement of {dropped_txid}",
+                            self.id()
+                        );
+                    }
                 }
                 Ok(())
             }
@@ -215,6 +287,7 @@ where
             TransactionSyncMessage::TransactionResponse(tx) => {
                 self.handle_transaction_response(tx).await
             }
+            TransactionSyncMessage::TransactionReject(msg) => self.handle_transaction_reject(msg),
         };
         handle_message_processing_result(&self.peer_mgr_event_sender, self.id(), res).await
     }
@@ -250,6 +323,8 @@ where
             TransactionResponse::Found(tx) => (tx.transaction().get_id(), Some(tx)),
         };
 
+        self.orphan_parent_requests.remove(&id);
+
         if self.requested_transactions.remove(&id).is_none() {
             // Don't punish peers for unsolicited tx responses.
             //
@@ -278,14 +353,25 @@ where
 
         if let Some(transaction) = tx {
             let origin = mempool::tx_origin::RemoteTxOrigin::new(self.id());
-            let options = TxOptions::default_for(origin.into());
+            let options = if self.is_whitelisted {
+                TxOptions::default_for(origin.into()).with_trust_policy(TxTrustPolicy::Trusted)
+            } else {
+                TxOptions::default_for(origin.into())
+            };
             let txid = transaction.transaction().get_id();
-            let tx_status = self
+            let tx_status = match self
                 .mempool_handle
                 .call_mut(move |m| m.add_transaction_remote(transaction, origin, options))
-                .await??;
+                .await?
+            {
+                Ok(tx_status) => tx_status,
+                Err(err) => {
+                    self.send_transaction_reject(txid, &err)?;
+                    return Err(err.into());
+                }
+            };
             match tx_status {
-                mempool::TxStatus::InMempool => {
+                mempool::TxStatus::InMempool | mempool::TxStatus::InMempoolReplacement => {
                     self.peer_mgr_event_sender.send(
                         PeerManagerEvent::NewValidTransactionReceived {
                             peer_id: self.id(),
@@ -293,8 +379,10 @@ where
                         },
                     )?;
                 }
+                mempool::TxStatus::InOrphanPool => {
+                    self.request_orphan_parents(txid).await?;
+                }
                 mempool::TxStatus::InMempoolDuplicate
-                | mempool::TxStatus::InOrphanPool
                 | mempool::TxStatus::InOrphanPoolDuplicate => {}
             }
         }
@@ -302,6 +390,134 @@ where
         Ok(())
     }
 
+    /// Request the parents of an orphan transaction received from the peer that are neither
+    /// in the mempool nor in the orphan pool.
+    ///
+    /// The peer has just sent us the orphan, so it's supposed to have its parents as well.
+    /// Once a parent arrives, the mempool re-evaluates its orphan children.
+    async fn request_orphan_parents(&mut self, orphan_id: Id<Transaction>) -> Result<()> {
+        let missing_parents = self
+            .mempool_handle
+            .call(move |m| {
+                m.orphan_transaction(&orphan_id)
+                    .map(|orphan| {
+                        orphan
+                            .transaction()
+                            .inputs()
+                            .iter()
+                            .filter_map(|input| input.utxo_outpoint())
+                            .filter_map(|outpoint| outpoint.source_id().get_tx_id().copied())
+                            .filter(|parent_id| {
+                                !m.contains_transaction(parent_id)
+                                    && !m.contains_orphan_transaction(parent_id)
+                            })
+                            .collect::<BTreeSet<_>>()
+                    })
+                    .unwrap_or_default()
+            })
+            .await?;
+
+        for parent_id in missing_parents {
+            if self.requested_transactions.contains(&parent_id) {
+                continue;
+            }
+
+            // Note: the parent requests are limited separately from the announcements, so that
+            // a peer can't make us request an unbounded number of transactions by sending orphans.
+            // Exceeding the limit is not the peer's fault though, e.g. it may have relayed a long
+            // chain of dependent transactions, so the remaining parents are just not requested.
+            // The orphan will be resolved when the parents are announced normally.
+            if self.orphan_parent_requests.count()
+                >= *self.p2p_config.protocol_config.max_peer_orphan_parent_requests
+                || self.requested_transactions.count()
+                    >= *self.p2p_config.protocol_config.max_peer_tx_announcements
+            {
+                log::debug!(
+                    "[peer id = {}] Not requesting parent {parent_id} of orphan tx {orphan_id} because too many requests are in flight",
+                    self.id(),
+                );
+                break;
+            }
+
+            log::debug!(
+                "[peer id = {}] Requesting parent {parent_id} of orphan tx {orphan_id}",
+                self.id(),
+            );
+            self.add_known_transaction(parent_id);
+            self.send_message(TransactionSyncMessage::TransactionRequest(parent_id))?;
+            self.requested_transactions.add(&parent_id);
+            self.orphan_parent_requests.add(&parent_id);
+        }
+
+        Ok(())
+    }
+
+    /// Forget the orphan parent requests to which the peer hasn't responded in time,
+    /// so that the parents can be requested again.
+    fn purge_expired_orphan_parent_requests(&mut self) {
+        for parent_id in self.orphan_parent_requests.take_expired() {
+            log::debug!(
+                "[peer id = {}] Request for orphan parent {parent_id} has expired",
+                self.id(),
+            );
+            self.requested_transactions.remove(&parent_id);
+        }
+    }
+
+    /// Tell the peer that its transaction has been rejected, if the peer understands it
+    /// and the error is the transaction's fault.
+    fn send_transaction_reject(
+        &mut self,
+        tx_id: Id<Transaction>,
+        err: &MempoolError,
+    ) -> Result<()> {
+        if !self.protocol_version.supports(ProtocolFeature::TransactionRejectMessage) {
+            return Ok(());
+        }
+
+        match tx_reject_code(err) {
+            Some(code) => self.send_message(TransactionSyncMessage::TransactionReject(
+                TransactionRejectMessage {
+                    tx_id,
+                    code,
+                    reason: err.to_string(),
+                },
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn handle_transaction_reject(&mut self, msg: TransactionRejectMessage) -> Result<()> {
+        let TransactionRejectMessage {
+            tx_id,
+            code,
+            reason,
+        } = msg;
+
+        log::info!(
+            "[peer id = {}] Transaction {tx_id} has been rejected by the peer, code: {code:?}, reason: {reason}",
+            self.id(),
+        );
+
+        // Whatever the reason is, the peer already knows about the transaction, so there is
+        // no point in announcing it again.
+        // Note that unsolicited rejections aren't punished, for the same reason as unsolicited
+        // transaction responses; also, the peer's claim is not verified in any way, so it's
+        // only recorded for reporting purposes.
+        self.add_known_transaction(tx_id);
+        self.tx_rejections.add(
+            tx_id,
+            TransactionRejection {
+                peer_id: self.id(),
+                code,
+                reason,
+                time: self.time_getter.get_time(),
+            },
+        );
+
+        Ok(())
+    }
+
     fn add_known_transaction(&mut self, txid: Id<Transaction>) {
         self.known_transactions.insert(&txid);
     }
@@ -314,7 +530,7 @@ where
 
         self.add_known_transaction(tx);
 
-        if self.chainstate_handle.is_initial_block_download().await? {
+        if self.chainstate_handle.is_tx_processing_deferred().await? {
             log::debug!(
                 "[peer id = {}] Ignoring transaction announcement because the node is in initial block download", self.id()
             );
@@ -379,3 +595,49 @@ where
         Ok(())
     }
 }
+
+/// Return the code that should be reported to the peer whose transaction has been rejected
+/// with the specified error, or None if the error is not caused by the transaction itself.
+fn tx_reject_code(err: &MempoolError) -> Option<TransactionRejectCode> {
+    match err {
+        MempoolError::Validity(err) => match err {
+            TxValidationError::TxValidation(_) => Some(TransactionRejectCode::Invalid),
+            TxValidationError::AddedDuringIBD => Some(TransactionRejectCode::TryLater),
+            TxValidationError::ChainstateError(_) | TxValidationError::CallError(_) => None,
+        },
+        MempoolError::Policy(err) => Some(match err {
+            MempoolPolicyError::Conflict(_) => TransactionRejectCode::Conflict,
+            MempoolPolicyError::MempoolFull => TransactionRejectCode::MempoolFull,
+            MempoolPolicyError::NoInputs
+            | MempoolPolicyError::NoOutputs
+            | MempoolPolicyError::ExceedsMaxBlockSize => TransactionRejectCode::Invalid,
+            MempoolPolicyError::ReplacementFeeLowerThanOriginal { .. }
+            | MempoolPolicyError::TransactionFeeLowerThanConflictsWithDescendants
+            | MempoolPolicyError::InsufficientFeesToRelay { .. }
+            | MempoolPolicyError::InsufficientFeesToRelayRBF
+            | MempoolPolicyError::ReplacementFeeRateTooLow { .. }
+            | MempoolPolicyError::RollingFeeThresholdNotMet { .. } => {
+                TransactionRejectCode::InsufficientFee
+            }
+            MempoolPolicyError::ConflictsFeeOverflow
+            | MempoolPolicyError::AdditionalFeesUnderflow
+            | MempoolPolicyError::AncestorFeeOverflow
+            | MempoolPolicyError::AncestorFeeUpdateOverflow
+            | MempoolPolicyError::FeeOverflow
+            | MempoolPolicyError::GetParentError
+            | MempoolPolicyError::DescendantOfExpiredTransaction
+            | MempoolPolicyError::RelayFeeOverflow => TransactionRejectCode::Other,
+        }),
+        MempoolError::Orphan(err) => Some(match err {
+            OrphanPoolError::Conflict(_) | OrphanPoolError::MempoolConflict => {
+                TransactionRejectCode::Conflict
+            }
+            OrphanPoolError::Full => TransactionRejectCode::MempoolFull,
+            OrphanPoolError::TooLarge(_, _)
+            | OrphanPoolError::NonceGapTooLarge(_)
+            | OrphanPoolError::NotSupportedForLocalOrigin(_) => TransactionRejectCode::Other,
+        }),
+        MempoolError::ZeroFeeTxsNotAllowed => Some(TransactionRejectCode::InsufficientFee),
+        MempoolError::TipMoved => None,
+    }
+}
@@ -23,7 +23,9 @@ use p2p_types::PeerId;
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
+    disconnection_reason::DisconnectionReason,
     error::{P2pError, PeerError},
+    peer_manager_event::PeerDisconnectionDbAction,
     utils::oneshot_nofail,
     PeerManagerEvent, Result,
 };
@@ -35,7 +37,8 @@ pub use known_transactions::KnownTransactions;
 /// There are three possible types of errors:
 /// - Fatal errors will be propagated by this function effectively stopping the peer event loop.
 /// - Non-fatal errors aren't propagated, but the peer score will be increased by the
-///   "ban score" value of the given error.
+///   "ban score" value of the given error, and the peer may be disconnected, depending on
+///   the error's classification (see `PeerMisbehavior`).
 /// - Ignored errors aren't propagated and don't affect the peer score.
 pub async fn handle_message_processing_result(
     peer_mgr_event_sender: &UnboundedSender<PeerManagerEvent>,
@@ -55,37 +58,59 @@ pub async fn handle_message_processing_result(
         // The special handling of these mempool errors is not really necessary, because their ban score is 0
         P2pError::MempoolError(MempoolError::Policy(MempoolPolicyError::MempoolFull)) => Ok(()),
 
-        // A protocol error - increase the ban score of a peer if needed.
+        // A protocol error - increase the ban score of a peer and disconnect it if needed.
         e @ (P2pError::NetworkingError(_)
         | P2pError::ProtocolError(_)
         | P2pError::MempoolError(_)
         | P2pError::ChainstateError(_)
         | P2pError::SyncError(_)) => {
-            let ban_score = e.ban_score();
-            if ban_score > 0 {
+            let misbehavior = e.peer_misbehavior();
+            if !misbehavior.is_misbehavior() {
+                log::debug!(
+                    "[peer id = {}] Ignoring an error that is not a misbehavior: {:?}",
+                    peer_id,
+                    e,
+                );
+                return Ok(());
+            }
+
+            if misbehavior.score > 0 {
                 log::info!(
                     "[peer id = {}] Adjusting peer's score by {}: {:?}",
                     peer_id,
-                    ban_score,
+                    misbehavior.score,
                     e,
                 );
 
                 let (sender, receiver) = oneshot_nofail::channel();
                 peer_mgr_event_sender.send(PeerManagerEvent::AdjustPeerScore(
-                    peer_id, ban_score, sender,
+                    peer_id,
+                    misbehavior.score,
+                    sender,
                 ))?;
-                receiver.await?.or_else(|e| match e {
-                    P2pError::PeerError(PeerError::PeerDoesntExist) => Ok(()),
-                    e => Err(e),
-                })
-            } else {
-                log::debug!(
-                    "[peer id = {}] Ignoring an error with the ban score of 0: {:?}",
+                ignore_missing_peer(receiver.await?)?;
+            }
+
+            // Note: a discouraged peer is disconnected by the peer manager. Whitelisted peers are
+            // exempted from both by the peer manager.
+            if misbehavior.should_disconnect && !misbehavior.should_discourage {
+                log::info!(
+                    "[peer id = {}] Disconnecting the peer for sending invalid data: {:?}",
                     peer_id,
                     e,
                 );
-                Ok(())
+
+                let (sender, receiver) = oneshot_nofail::channel();
+                peer_mgr_event_sender.send(PeerManagerEvent::Disconnect(
+                    peer_id,
+                    PeerDisconnectionDbAction::Keep,
+                    Some(DisconnectionReason::InvalidDataReceived),
+                    sender,
+                ))?;
+                ignore_missing_peer(receiver.await?)?;
             }
+
+            Ok(())
         }
 
         // Some of these errors aren't technically fatal,
@@ -116,6 +141,13 @@ pub async fn handle_message_processing_result(
     }
 }
 
+fn ignore_missing_peer(result: Result<()>) -> Result<()> {
+    result.or_else(|e| match e {
+        P2pError::PeerError(PeerError::PeerDoesntExist) => Ok(()),
+        e => Err(e),
+    })
+}
+
 /// This function is used to update peers_best_block_that_we_have.
 /// The "better" block is the one that is on the main chain and has a higher chain-trust.
 /// In the case of a tie, new_block_id is preferred.
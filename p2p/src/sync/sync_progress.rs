@@ -0,0 +1,56 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use utils::atomics::RelaxedAtomicU64;
+
+/// Counters describing the progress of block syncing, shared between all the peer tasks.
+///
+/// They are only used for reporting, so relaxed orderings are enough.
+#[derive(Debug)]
+pub struct SyncProgressCounters {
+    /// The total size of the blocks downloaded from peers since the node was started.
+    downloaded_block_bytes: RelaxedAtomicU64,
+}
+
+impl SyncProgressCounters {
+    pub fn new() -> Self {
+        Self {
+            downloaded_block_bytes: RelaxedAtomicU64::new(0),
+        }
+    }
+
+    pub fn on_block_received(&self, block_size: usize) {
+        self.downloaded_block_bytes.fetch_add(block_size as u64);
+    }
+
+    pub fn downloaded_block_bytes(&self) -> u64 {
+        self.downloaded_block_bytes.load()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters() {
+        let counters = SyncProgressCounters::new();
+        assert_eq!(counters.downloaded_block_bytes(), 0);
+
+        counters.on_block_received(100);
+        counters.on_block_received(200);
+        assert_eq!(counters.downloaded_block_bytes(), 300);
+    }
+}
@@ -15,7 +15,10 @@
 
 use tokio::sync::mpsc::unbounded_channel;
 
-use chainstate::{ban_score::BanScore, BlockError, BlockSource, ChainstateError, CheckBlockError};
+use chainstate::{
+    ban_score::{BanScore, PeerMisbehavior},
+    BlockError, BlockSource, ChainstateError, CheckBlockError, PropertyQueryError,
+};
 use chainstate_test_framework::{empty_witness, TestFramework, TransactionBuilder};
 use common::{
     chain::{
@@ -52,6 +55,28 @@ fn ban_scores() {
         .ban_score(),
         100
     );
+
+    // Errors that are the local node's problem are not considered a misbehavior,
+    // even if their ban score is non-zero.
+    let local_err = P2pError::ChainstateError(ChainstateError::ProcessBlockError(
+        BlockError::CheckBlockFailed(CheckBlockError::PropertyQueryError(
+            PropertyQueryError::BestBlockIndexNotFound,
+        )),
+    ));
+    assert_eq!(local_err.ban_score(), 100);
+    assert_eq!(local_err.peer_misbehavior(), PeerMisbehavior::NONE);
+
+    assert_eq!(
+        P2pError::ChainstateError(ChainstateError::ProcessBlockError(
+            BlockError::CheckBlockFailed(CheckBlockError::MerkleRootMismatch),
+        ))
+        .peer_misbehavior(),
+        PeerMisbehavior {
+            score: 100,
+            should_disconnect: true,
+            should_discourage: true,
+        }
+    );
 }
 
 #[tracing::instrument]
@@ -523,11 +523,15 @@ async fn send_headers_connected_to_previously_sent_headers(#[case] seed: Seed) {
                 msg_max_locator_count: Default::default(),
                 max_message_size: Default::default(),
                 max_peer_tx_announcements: Default::default(),
+                max_peer_orphan_parent_requests: Default::default(),
+                max_peer_pending_tx_announcements: Default::default(),
+                max_peer_pending_header_announcements: Default::default(),
             },
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
             disable_noise: Default::default(),
+            multiplexed_transport: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
@@ -542,6 +546,7 @@ async fn send_headers_connected_to_previously_sent_headers(#[case] seed: Seed) {
             user_agent: mintlayer_core_user_agent(),
             sync_stalling_timeout: Default::default(),
             peer_manager_config: Default::default(),
+            local_tx_broadcast_config: Default::default(),
         });
 
         let initial_blocks = make_new_blocks(
@@ -626,11 +631,15 @@ async fn send_headers_connected_to_block_which_is_being_downloaded(#[case] seed:
                 msg_max_locator_count: Default::default(),
                 max_message_size: Default::default(),
                 max_peer_tx_announcements: Default::default(),
+                max_peer_orphan_parent_requests: Default::default(),
+                max_peer_pending_tx_announcements: Default::default(),
+                max_peer_pending_header_announcements: Default::default(),
             },
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
             disable_noise: Default::default(),
+            multiplexed_transport: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
@@ -645,6 +654,7 @@ async fn send_headers_connected_to_block_which_is_being_downloaded(#[case] seed:
             user_agent: mintlayer_core_user_agent(),
             sync_stalling_timeout: Default::default(),
             peer_manager_config: Default::default(),
+            local_tx_broadcast_config: Default::default(),
         });
 
         let initial_blocks = make_new_blocks(
@@ -726,11 +736,15 @@ async fn correct_pending_headers_update(#[case] seed: Seed) {
                 msg_max_locator_count: Default::default(),
                 max_message_size: Default::default(),
                 max_peer_tx_announcements: Default::default(),
+                max_peer_orphan_parent_requests: Default::default(),
+                max_peer_pending_tx_announcements: Default::default(),
+                max_peer_pending_header_announcements: Default::default(),
             },
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
             disable_noise: Default::default(),
+            multiplexed_transport: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
@@ -745,6 +759,7 @@ async fn correct_pending_headers_update(#[case] seed: Seed) {
             user_agent: mintlayer_core_user_agent(),
             sync_stalling_timeout: Default::default(),
             peer_manager_config: Default::default(),
+            local_tx_broadcast_config: Default::default(),
         });
 
         let initial_blocks = make_new_blocks(
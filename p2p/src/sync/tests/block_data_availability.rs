@@ -0,0 +1,180 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use chainstate::{ban_score::BanScore, BlockDataAvailability};
+use chainstate_test_framework::TestFramework;
+use common::{
+    chain::config::create_unit_test_config,
+    primitives::{BlockHeight, Idable},
+};
+use p2p_test_utils::create_n_blocks;
+use randomness::Rng;
+use test_utils::random::Seed;
+
+use crate::{
+    error::ProtocolError,
+    message::{BlockListRequest, BlockSyncMessage, BlocksNotAvailable, HeaderList},
+    sync::tests::helpers::TestNode,
+    test_helpers::for_each_protocol_version,
+    types::peer_id::PeerId,
+    P2pError,
+};
+
+// Blocks below the announced availability range are not requested from the peer.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn blocks_below_announced_range_not_requested(#[case] seed: Seed) {
+    for_each_protocol_version(|protocol_version| async move {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let chain_config = Arc::new(create_unit_test_config());
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chain_config(chain_config.as_ref().clone())
+            .build();
+        let num_blocks = rng.gen_range(2..10);
+        let blocks = create_n_blocks(&mut rng, &mut tf, num_blocks);
+
+        let mut node = TestNode::builder(protocol_version)
+            .with_chain_config(chain_config)
+            .with_chainstate(tf.into_chainstate())
+            .build()
+            .await;
+
+        let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+        let first_height = BlockHeight::new(num_blocks as u64 + 1);
+        peer.send_block_sync_message(BlockSyncMessage::BlockDataAvailability(
+            BlockDataAvailability::new(first_height, first_height.next_height()),
+        ))
+        .await;
+
+        let headers = blocks.iter().map(|b| b.header().clone()).collect();
+        peer.send_block_sync_message(BlockSyncMessage::HeaderList(HeaderList::new(headers)))
+            .await;
+
+        node.assert_no_sync_message().await;
+        node.assert_no_error().await;
+
+        node.join_subsystem_manager().await;
+    })
+    .await;
+}
+
+// A peer reporting that it doesn't have the requested blocks isn't punished and isn't asked
+// for them again.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn requested_blocks_not_available(#[case] seed: Seed) {
+    for_each_protocol_version(|protocol_version| async move {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let chain_config = Arc::new(create_unit_test_config());
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chain_config(chain_config.as_ref().clone())
+            .build();
+        let num_blocks = rng.gen_range(2..10);
+        let blocks = create_n_blocks(&mut rng, &mut tf, num_blocks);
+
+        let mut node = TestNode::builder(protocol_version)
+            .with_chain_config(chain_config)
+            .with_chainstate(tf.into_chainstate())
+            .build()
+            .await;
+
+        let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+        let headers: Vec<_> = blocks.iter().map(|b| b.header().clone()).collect();
+        peer.send_block_sync_message(BlockSyncMessage::HeaderList(HeaderList::new(
+            headers.clone(),
+        )))
+        .await;
+
+        let (sent_to, message) = node.get_sent_block_sync_message().await;
+        assert_eq!(peer.get_id(), sent_to);
+        let ids: Vec<_> = blocks.iter().map(|b| b.get_id()).collect();
+        assert_eq!(
+            message,
+            BlockSyncMessage::BlockListRequest(BlockListRequest::new(ids.clone()))
+        );
+
+        peer.send_block_sync_message(BlockSyncMessage::BlocksNotAvailable(
+            BlocksNotAvailable::new(ids),
+        ))
+        .await;
+
+        // The same headers are sent again, but the blocks are not requested anymore.
+        peer.send_block_sync_message(BlockSyncMessage::HeaderList(HeaderList::new(headers)))
+            .await;
+
+        node.assert_no_sync_message().await;
+        node.assert_no_error().await;
+
+        node.join_subsystem_manager().await;
+    })
+    .await;
+}
+
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn unsolicited_blocks_not_available(#[case] seed: Seed) {
+    for_each_protocol_version(|protocol_version| async move {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let chain_config = Arc::new(create_unit_test_config());
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chain_config(chain_config.as_ref().clone())
+            .build();
+        let block = tf.make_block_builder().build(&mut rng);
+        let block_id = block.get_id();
+
+        let mut node = TestNode::builder(protocol_version)
+            .with_chain_config(chain_config)
+            .with_chainstate(tf.into_chainstate())
+            .build()
+            .await;
+
+        let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+        peer.send_block_sync_message(BlockSyncMessage::BlocksNotAvailable(
+            BlocksNotAvailable::new(vec![block_id]),
+        ))
+        .await;
+
+        let (adjusted_peer, score) = node.receive_adjust_peer_score_event().await;
+        assert_eq!(peer.get_id(), adjusted_peer);
+        assert_eq!(
+            score,
+            P2pError::ProtocolError(ProtocolError::UnsolicitedBlockNotAvailableReceived(
+                block_id
+            ))
+            .ban_score()
+        );
+        node.assert_no_sync_message().await;
+
+        node.join_subsystem_manager().await;
+    })
+    .await;
+}
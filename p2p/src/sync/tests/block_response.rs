@@ -162,6 +162,7 @@ async fn block_responses_in_wrong_order(#[case] seed: Seed) {
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
             disable_noise: Default::default(),
+            multiplexed_transport: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
@@ -176,6 +177,7 @@ async fn block_responses_in_wrong_order(#[case] seed: Seed) {
             sync_stalling_timeout: Default::default(),
             peer_manager_config: Default::default(),
             protocol_config: Default::default(),
+            local_tx_broadcast_config: Default::default(),
         });
 
         let mut tf = TestFramework::builder(&mut rng)
@@ -290,6 +292,7 @@ async fn disconnect(#[case] seed: Seed) {
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
             disable_noise: Default::default(),
+            multiplexed_transport: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
@@ -304,6 +307,7 @@ async fn disconnect(#[case] seed: Seed) {
             user_agent: "test".try_into().unwrap(),
             peer_manager_config: Default::default(),
             protocol_config: Default::default(),
+            local_tx_broadcast_config: Default::default(),
         });
         let mut node = TestNode::builder(protocol_version)
             .with_chain_config(chain_config)
@@ -357,6 +361,7 @@ async fn slow_response(#[case] seed: Seed) {
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
             disable_noise: Default::default(),
+            multiplexed_transport: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
@@ -371,6 +376,7 @@ async fn slow_response(#[case] seed: Seed) {
             user_agent: mintlayer_core_user_agent(),
             peer_manager_config: Default::default(),
             protocol_config: Default::default(),
+            local_tx_broadcast_config: Default::default(),
         });
 
         let mut tf = TestFramework::builder(&mut rng)
@@ -138,6 +138,7 @@ async fn respond_with_empty_header_list_when_in_ibd() {
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
             disable_noise: Default::default(),
+            multiplexed_transport: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
@@ -152,6 +153,7 @@ async fn respond_with_empty_header_list_when_in_ibd() {
             user_agent: mintlayer_core_user_agent(),
             peer_manager_config: Default::default(),
             protocol_config: Default::default(),
+            local_tx_broadcast_config: Default::default(),
         });
 
         let mut node = TestNode::builder(protocol_version)
@@ -46,7 +46,7 @@ use common::{
 };
 use logging::log;
 use mempool::{MempoolConfig, MempoolHandle};
-use networking::transport::TcpTransportSocket;
+use networking::{transport::TcpTransportSocket, types::TransportProtocol};
 use p2p_test_utils::{expect_future_val, expect_no_recv, expect_recv, SHORT_TIMEOUT};
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress};
 use randomness::Rng;
@@ -58,8 +58,9 @@ use utils_networking::IpOrSocketAddress;
 use crate::{
     message::{BlockSyncMessage, HeaderList, TransactionSyncMessage},
     net::types::SyncingEvent,
+    peer_manager_event::AnnouncementKind,
     protocol::{choose_common_protocol_version, ProtocolVersion},
-    sync::{subscribe_to_new_tip, Observer, SyncManager},
+    sync::{subscribe_to_new_tip, tx_rejections::TxRejections, Observer, SyncManager},
     test_helpers::test_p2p_config,
     types::peer_id::PeerId,
     MessagingService, NetworkingService, P2pConfig, P2pError, P2pEventHandler, PeerManagerEvent,
@@ -87,6 +88,7 @@ pub struct TestNode {
     mempool_handle: MempoolHandle,
     _new_tip_receiver: UnboundedReceiver<Id<Block>>,
     sync_mgr_notification_receiver: UnboundedReceiver<SyncManagerNotification>,
+    tx_rejections: Arc<TxRejections>,
     protocol_version: ProtocolVersion,
 }
 
@@ -142,6 +144,7 @@ impl TestNode {
         );
 
         let sync_manager_chainstate_handle = sync_manager.chainstate().clone();
+        let tx_rejections = sync_manager.tx_rejections();
 
         let (error_sender, error_receiver) = mpsc::unbounded_channel();
         let sync_manager_handle = logging::spawn_in_current_span(async move {
@@ -166,6 +169,7 @@ impl TestNode {
             mempool_handle,
             _new_tip_receiver: new_tip_receiver,
             sync_mgr_notification_receiver,
+            tx_rejections,
             protocol_version,
         }
     }
@@ -186,6 +190,10 @@ impl TestNode {
         &self.mempool_handle
     }
 
+    pub fn tx_rejections(&self) -> &TxRejections {
+        &self.tx_rejections
+    }
+
     /// Sends the `SyncControlEvent::Connected` event without checking outgoing messages.
     #[must_use]
     pub fn try_connect_peer(
@@ -202,6 +210,7 @@ impl TestNode {
                 peer_id,
                 common_services: (*self.p2p_config.node_type).into(),
                 protocol_version: common_protocol_version,
+                is_whitelisted: false,
                 block_sync_msg_receiver,
                 transaction_sync_msg_receiver,
             })
@@ -349,6 +358,8 @@ impl TestNode {
                     | PeerManagerEvent::Disconnect(_, _, _, _)
                     | PeerManagerEvent::GetPeerCount(_)
                     | PeerManagerEvent::GetBindAddresses(_)
+                    | PeerManagerEvent::GetListenEndpoints(_)
+                    | PeerManagerEvent::GetNetworkConnections(_)
                     | PeerManagerEvent::GetConnectedPeers(_)
                     | PeerManagerEvent::AdjustPeerScore(_, _, _)
                     | PeerManagerEvent::GetReserved(_)
@@ -366,7 +377,8 @@ impl TestNode {
                     PeerManagerEvent::NewTipReceived { .. }
                     | PeerManagerEvent::NewChainstateTip(_)
                     | PeerManagerEvent::NewValidTransactionReceived { .. }
-                    | PeerManagerEvent::PeerBlockSyncStatusUpdate { .. } => {
+                    | PeerManagerEvent::PeerBlockSyncStatusUpdate { .. }
+                    | PeerManagerEvent::PeerAnnouncementsDropped { .. } => {
                         // Ignored
                     }
                 }
@@ -614,6 +626,8 @@ pub enum PeerManagerEventDesc {
     Disconnect(PeerId),
     GetPeerCount,
     GetBindAddresses,
+    GetListenEndpoints,
+    GetNetworkConnections,
     GetConnectedPeers,
     AdjustPeerScore {
         peer_id: PeerId,
@@ -635,6 +649,11 @@ pub enum PeerManagerEventDesc {
         // values must be easy to predict. Currently, PeerBlockSyncStatus only contains a Time
         // value, which may be hard to predict, depending on the test.
     },
+    PeerAnnouncementsDropped {
+        peer_id: PeerId,
+        kind: AnnouncementKind,
+        count: u64,
+    },
     GetReserved,
     AddReserved(IpOrSocketAddress),
     RemoveReserved(IpOrSocketAddress),
@@ -658,6 +677,10 @@ impl From<&PeerManagerEvent> for PeerManagerEventDesc {
             }
             PeerManagerEvent::GetPeerCount(_) => PeerManagerEventDesc::GetPeerCount,
             PeerManagerEvent::GetBindAddresses(_) => PeerManagerEventDesc::GetBindAddresses,
+            PeerManagerEvent::GetListenEndpoints(_) => PeerManagerEventDesc::GetListenEndpoints,
+            PeerManagerEvent::GetNetworkConnections(_) => {
+                PeerManagerEventDesc::GetNetworkConnections
+            }
             PeerManagerEvent::GetConnectedPeers(_) => PeerManagerEventDesc::GetConnectedPeers,
             PeerManagerEvent::AdjustPeerScore(peer_id, score, _) => {
                 PeerManagerEventDesc::AdjustPeerScore {
@@ -684,6 +707,15 @@ impl From<&PeerManagerEvent> for PeerManagerEventDesc {
                 peer_id,
                 new_status: _,
             } => PeerManagerEventDesc::PeerBlockSyncStatusUpdate { peer_id: *peer_id },
+            PeerManagerEvent::PeerAnnouncementsDropped {
+                peer_id,
+                kind,
+                count,
+            } => PeerManagerEventDesc::PeerAnnouncementsDropped {
+                peer_id: *peer_id,
+                kind: *kind,
+                count: *count,
+            },
             PeerManagerEvent::GetReserved(_) => PeerManagerEventDesc::GetReserved,
             PeerManagerEvent::AddReserved(addr, _) => {
                 PeerManagerEventDesc::AddReserved(addr.clone())
@@ -719,6 +751,8 @@ impl NetworkingService for NetworkingServiceStub {
     type MessagingHandle = MessagingHandleMock;
     type SyncingEventReceiver = SyncingEventReceiverMock;
 
+    const TRANSPORT_PROTOCOL: TransportProtocol = TransportProtocol::Tcp;
+
     async fn start(
         _: bool,
         _: Self::Transport,
@@ -315,6 +315,8 @@ impl TestNodeGroup {
                         | PeerManagerEvent::Disconnect(_, _, _, _)
                         | PeerManagerEvent::GetPeerCount(_)
                         | PeerManagerEvent::GetBindAddresses(_)
+                        | PeerManagerEvent::GetListenEndpoints(_)
+                        | PeerManagerEvent::GetNetworkConnections(_)
                         | PeerManagerEvent::GetConnectedPeers(_)
                         | PeerManagerEvent::AdjustPeerScore(_, _, _)
                         | PeerManagerEvent::GetReserved(_)
@@ -332,7 +334,8 @@ impl TestNodeGroup {
                         PeerManagerEvent::NewTipReceived { .. }
                         | PeerManagerEvent::NewChainstateTip(_)
                         | PeerManagerEvent::NewValidTransactionReceived { .. }
-                        | PeerManagerEvent::PeerBlockSyncStatusUpdate { .. } => {
+                        | PeerManagerEvent::PeerBlockSyncStatusUpdate { .. }
+                        | PeerManagerEvent::PeerAnnouncementsDropped { .. } => {
                             // Ignored
                         }
                     }
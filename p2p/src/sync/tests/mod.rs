@@ -15,6 +15,7 @@
 
 mod ban_scores;
 mod block_announcement;
+mod block_data_availability;
 mod block_list_request;
 mod block_response;
 mod header_list_request;
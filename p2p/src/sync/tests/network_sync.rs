@@ -61,11 +61,15 @@ async fn basic(#[case] seed: Seed) {
                 msg_max_locator_count: Default::default(),
                 max_message_size: Default::default(),
                 max_peer_tx_announcements: Default::default(),
+                max_peer_orphan_parent_requests: Default::default(),
+                max_peer_pending_tx_announcements: Default::default(),
+                max_peer_pending_header_announcements: Default::default(),
             },
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
             disable_noise: Default::default(),
+            multiplexed_transport: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
@@ -80,6 +84,7 @@ async fn basic(#[case] seed: Seed) {
             user_agent: mintlayer_core_user_agent(),
             sync_stalling_timeout: Default::default(),
             peer_manager_config: Default::default(),
+            local_tx_broadcast_config: Default::default(),
         });
 
         let blocks = make_new_blocks(
@@ -303,11 +308,15 @@ async fn block_announcement_disconnected_headers(#[case] seed: Seed) {
                 msg_max_locator_count: Default::default(),
                 max_message_size: Default::default(),
                 max_peer_tx_announcements: Default::default(),
+                max_peer_orphan_parent_requests: Default::default(),
+                max_peer_pending_tx_announcements: Default::default(),
+                max_peer_pending_header_announcements: Default::default(),
             },
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
             disable_noise: Default::default(),
+            multiplexed_transport: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
@@ -322,6 +331,7 @@ async fn block_announcement_disconnected_headers(#[case] seed: Seed) {
             user_agent: mintlayer_core_user_agent(),
             sync_stalling_timeout: Default::default(),
             peer_manager_config: Default::default(),
+            local_tx_broadcast_config: Default::default(),
         });
 
         let initial_block_count = rng.gen_range(1..=MAX_REQUEST_BLOCKS_COUNT);
@@ -427,6 +437,7 @@ async fn send_block_from_the_future_again(#[case] seed: Seed) {
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
             disable_noise: Default::default(),
+            multiplexed_transport: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
@@ -440,6 +451,7 @@ async fn send_block_from_the_future_again(#[case] seed: Seed) {
             sync_stalling_timeout: Default::default(),
             peer_manager_config: Default::default(),
             protocol_config: Default::default(),
+            local_tx_broadcast_config: Default::default(),
         });
 
         let mut rng = make_seedable_rng(seed);
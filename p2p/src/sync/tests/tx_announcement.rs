@@ -31,15 +31,21 @@ use mempool::{
     FeeRate, MempoolConfig,
 };
 use serialization::Encode;
-use test_utils::{random::Seed, BasicTestTimeGetter};
+use test_utils::{assert_matches, random::Seed, BasicTestTimeGetter};
 
 use crate::{
     config::NodeType,
     error::ProtocolError,
-    message::{TransactionResponse, TransactionSyncMessage},
-    protocol::ProtocolConfig,
+    message::{
+        TransactionRejectCode, TransactionRejectMessage, TransactionResponse,
+        TransactionSyncMessage,
+    },
+    protocol::{ProtocolConfig, ProtocolFeature},
     sync::{
-        peer::requested_transactions::REQUESTED_TX_EXPIRY_PERIOD,
+        peer::{
+            orphan_parent_requests::ORPHAN_PARENT_REQUEST_TIMEOUT,
+            requested_transactions::REQUESTED_TX_EXPIRY_PERIOD,
+        },
         tests::helpers::{PeerManagerEventDesc, SyncManagerNotification, TestNode},
     },
     test_helpers::{for_each_protocol_version, test_p2p_config},
@@ -87,11 +93,25 @@ async fn invalid_transaction(#[case] seed: Seed) {
             TransactionSyncMessage::TransactionRequest(tx.transaction().get_id())
         );
 
+        let tx_id = tx.transaction().get_id();
         peer.send_transaction_sync_message(TransactionSyncMessage::TransactionResponse(
             TransactionResponse::Found(tx),
         ))
         .await;
 
+        if protocol_version.supports(ProtocolFeature::TransactionRejectMessage) {
+            let (sent_to, message) = node.get_sent_transaction_sync_message().await;
+            assert_eq!(peer.get_id(), sent_to);
+            assert_eq!(
+                message,
+                TransactionSyncMessage::TransactionReject(TransactionRejectMessage {
+                    tx_id,
+                    code: TransactionRejectCode::Invalid,
+                    reason: MempoolPolicyError::NoInputs.to_string(),
+                })
+            );
+        }
+
         let (adjusted_peer, score) = node.receive_adjust_peer_score_event().await;
         assert_eq!(peer.get_id(), adjusted_peer);
         assert_eq!(
@@ -156,6 +176,7 @@ async fn no_transaction_service(#[case] seed: Seed) {
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
             disable_noise: Default::default(),
+            multiplexed_transport: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
@@ -170,6 +191,7 @@ async fn no_transaction_service(#[case] seed: Seed) {
             sync_stalling_timeout: Default::default(),
             peer_manager_config: Default::default(),
             protocol_config: Default::default(),
+            local_tx_broadcast_config: Default::default(),
         });
         let mut node = TestNode::builder(protocol_version)
             .with_chain_config(Arc::clone(&chain_config))
@@ -226,11 +248,15 @@ async fn too_many_announcements(#[case] seed: Seed) {
                 max_addr_list_response_address_count: Default::default(),
                 msg_max_locator_count: Default::default(),
                 max_message_size: Default::default(),
+                max_peer_orphan_parent_requests: Default::default(),
+                max_peer_pending_tx_announcements: Default::default(),
+                max_peer_pending_header_announcements: Default::default(),
             },
 
             bind_addresses: Default::default(),
             socks5_proxy: Default::default(),
             disable_noise: Default::default(),
+            multiplexed_transport: Default::default(),
             boot_nodes: Default::default(),
             reserved_nodes: Default::default(),
             whitelisted_addresses: Default::default(),
@@ -245,6 +271,7 @@ async fn too_many_announcements(#[case] seed: Seed) {
             user_agent: "test".try_into().unwrap(),
             sync_stalling_timeout: Default::default(),
             peer_manager_config: Default::default(),
+            local_tx_broadcast_config: Default::default(),
         });
         let mut node = TestNode::builder(protocol_version)
             .with_chain_config(Arc::clone(&chain_config))
@@ -444,6 +471,10 @@ async fn valid_transaction_with_fee_below_minimum(#[case] seed: Seed) {
         let p2p_config = Arc::new(test_p2p_config());
         let mempool_config = MempoolConfig {
             min_tx_relay_fee_rate: min_fee_rate.into(),
+            accept_zero_fee_txs: Default::default(),
+            enable_rbf: Default::default(),
+            min_rbf_fee_rate_increment: Default::default(),
+            fee_estimator_recording_file: None,
         };
         let mut node = TestNode::builder(protocol_version)
             .with_p2p_config(Arc::clone(&p2p_config))
@@ -511,6 +542,19 @@ async fn valid_transaction_with_fee_below_minimum(#[case] seed: Seed) {
         assert!(!tx1_in_mempool);
         assert!(tx2_in_mempool);
 
+        if protocol_version.supports(ProtocolFeature::TransactionRejectMessage) {
+            let (sent_to, message) = node.get_sent_transaction_sync_message().await;
+            assert_eq!(peer.get_id(), sent_to);
+            assert_matches!(
+                message,
+                TransactionSyncMessage::TransactionReject(TransactionRejectMessage {
+                    tx_id,
+                    code: TransactionRejectCode::InsufficientFee,
+                    reason: _,
+                }) if tx_id == tx1_id
+            );
+        }
+
         node.assert_no_sync_message().await;
         // Expect no other peer manager events, such as the propagation of tx1 or peer banning.
         node.assert_no_peer_manager_event().await;
@@ -542,6 +586,10 @@ async fn transaction_sequence_via_orphan_pool(#[case] seed: Seed) {
             .with_mempool_config(MempoolConfig {
                 min_tx_relay_fee_rate: FeeRate::from_amount_per_kb(Amount::from_atoms(100_000_000))
                     .into(),
+                accept_zero_fee_txs: Default::default(),
+                enable_rbf: Default::default(),
+                min_rbf_fee_rate_increment: Default::default(),
+                fee_estimator_recording_file: None,
             })
             .with_p2p_config(Arc::clone(&p2p_config))
             .with_chainstate(tf.into_chainstate())
@@ -622,7 +670,284 @@ async fn transaction_sequence_via_orphan_pool(#[case] seed: Seed) {
     .await;
 }
 
+// When an orphan is received from a peer, its missing parent is requested from the same peer,
+// after which both transactions get into the mempool and are relayed to other peers.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn orphan_parent_requested(#[case] seed: Seed) {
+    for_each_protocol_version(|protocol_version| async move {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let chain_config = Arc::new(create_unit_test_config());
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chain_config(chain_config.as_ref().clone())
+            .build();
+        // Process a block to finish the initial block download.
+        tf.make_block_builder().build_and_process(&mut rng).unwrap().unwrap();
+
+        let p2p_config = Arc::new(test_p2p_config());
+        let mut node = TestNode::builder(protocol_version)
+            .with_chain_config(Arc::clone(&chain_config))
+            .with_p2p_config(Arc::clone(&p2p_config))
+            .with_chainstate(tf.into_chainstate())
+            .build()
+            .await;
+
+        let peer1 = node.connect_peer(PeerId::new(), protocol_version).await;
+        let peer2 = node.connect_peer(PeerId::new(), protocol_version).await;
+
+        let tx0 = Transaction::new(
+            0x00,
+            vec![TxInput::from_utxo(chain_config.genesis_block_id().into(), 0)],
+            vec![TxOutput::Transfer(
+                OutputValue::Coin(Amount::from_atoms(100_000_000)),
+                common::chain::Destination::AnyoneCanSpend,
+            )],
+        )
+        .unwrap();
+        let tx0 = SignedTransaction::new(tx0, vec![InputWitness::NoSignature(None)]).unwrap();
+        let tx0_id = tx0.transaction().get_id();
+        let tx1 = transaction_spending(&[tx0_id], 90_000_000);
+        let tx1_id = tx1.transaction().get_id();
+
+        peer1
+            .send_transaction_sync_message(TransactionSyncMessage::NewTransaction(tx1_id))
+            .await;
+        let (sent_to, message) = node.get_sent_transaction_sync_message().await;
+        assert_eq!(sent_to, peer1.get_id());
+        assert_eq!(message, TransactionSyncMessage::TransactionRequest(tx1_id));
+
+        peer1
+            .send_transaction_sync_message(TransactionSyncMessage::TransactionResponse(
+                TransactionResponse::Found(tx1),
+            ))
+            .await;
+
+        // The orphan's parent is requested from the peer that has sent the orphan.
+        let (sent_to, message) = node.get_sent_transaction_sync_message().await;
+        assert_eq!(sent_to, peer1.get_id());
+        assert_eq!(message, TransactionSyncMessage::TransactionRequest(tx0_id));
+
+        peer1
+            .send_transaction_sync_message(TransactionSyncMessage::TransactionResponse(
+                TransactionResponse::Found(tx0),
+            ))
+            .await;
+
+        // Both transactions are relayed to the other peer only, because the first one knows them.
+        let mut announced = BTreeSet::new();
+        for _ in 0..2 {
+            let (sent_to, message) = node.get_sent_transaction_sync_message().await;
+            assert_eq!(sent_to, peer2.get_id());
+            match message {
+                TransactionSyncMessage::NewTransaction(tx_id) => announced.insert(tx_id),
+                msg => panic!("Unexpected message {msg:?}"),
+            };
+        }
+        assert_eq!(announced, BTreeSet::from([tx0_id, tx1_id]));
+
+        node.assert_no_peer_manager_event().await;
+
+        node.join_subsystem_manager().await;
+    })
+    .await;
+}
+
+// The number of orphan parent requests is limited per peer, and the requests expire
+// if the peer doesn't respond to them.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn orphan_parent_requests_limit(#[case] seed: Seed) {
+    for_each_protocol_version(|protocol_version| async move {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let chain_config = Arc::new(create_unit_test_config());
+        let time_getter = BasicTestTimeGetter::new();
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chain_config(chain_config.as_ref().clone())
+            .with_time_getter(time_getter.get_time_getter())
+            .build();
+        // Process a block to finish the initial block download.
+        tf.make_block_builder().build_and_process(&mut rng).unwrap().unwrap();
+
+        let p2p_config = Arc::new(P2pConfig {
+            protocol_config: ProtocolConfig {
+                max_peer_orphan_parent_requests: 1.into(),
+                max_peer_pending_tx_announcements: Default::default(),
+                max_peer_pending_header_announcements: Default::default(),
+
+                msg_header_count_limit: Default::default(),
+                max_request_blocks_count: Default::default(),
+                max_addr_list_response_address_count: Default::default(),
+                msg_max_locator_count: Default::default(),
+                max_message_size: Default::default(),
+                max_peer_tx_announcements: Default::default(),
+            },
+
+            bind_addresses: Default::default(),
+            socks5_proxy: Default::default(),
+            disable_noise: Default::default(),
+            multiplexed_transport: Default::default(),
+            boot_nodes: Default::default(),
+            reserved_nodes: Default::default(),
+            whitelisted_addresses: Default::default(),
+            ban_config: Default::default(),
+            outbound_connection_timeout: Default::default(),
+            ping_check_period: Default::default(),
+            ping_timeout: Default::default(),
+            peer_handshake_timeout: Default::default(),
+            max_clock_diff: Default::default(),
+            node_type: Default::default(),
+            allow_discover_private_ips: Default::default(),
+            user_agent: "test".try_into().unwrap(),
+            sync_stalling_timeout: Default::default(),
+            peer_manager_config: Default::default(),
+            local_tx_broadcast_config: Default::default(),
+        });
+        let mut node = TestNode::builder(protocol_version)
+            .with_chain_config(Arc::clone(&chain_config))
+            .with_p2p_config(Arc::clone(&p2p_config))
+            .with_chainstate(tf.into_chainstate())
+            .with_time_getter(time_getter.get_time_getter())
+            .build()
+            .await;
+
+        let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+        // Respond to node's initial header request (made inside connect_peer)
+        peer.send_headers(vec![]).await;
+
+        let parent1_id = transaction_with_amount(chain_config.genesis_block_id(), 1)
+            .transaction()
+            .get_id();
+        let parent2_id = transaction_with_amount(chain_config.genesis_block_id(), 2)
+            .transaction()
+            .get_id();
+        let orphan = transaction_spending(&[parent1_id, parent2_id], 1);
+        let orphan_id = orphan.transaction().get_id();
+
+        peer.send_transaction_sync_message(TransactionSyncMessage::NewTransaction(orphan_id))
+            .await;
+        let (_, message) = node.get_sent_transaction_sync_message().await;
+        assert_eq!(
+            message,
+            TransactionSyncMessage::TransactionRequest(orphan_id)
+        );
+        peer.send_transaction_sync_message(TransactionSyncMessage::TransactionResponse(
+            TransactionResponse::Found(orphan),
+        ))
+        .await;
+
+        // Only one parent is requested because of the limit.
+        let first_parent_id = std::cmp::min(parent1_id, parent2_id);
+        let (sent_to, message) = node.get_sent_transaction_sync_message().await;
+        assert_eq!(sent_to, peer.get_id());
+        assert_eq!(
+            message,
+            TransactionSyncMessage::TransactionRequest(first_parent_id)
+        );
+        node.assert_no_sync_message().await;
+
+        // While the request is in flight, the announcement of the parent is ignored.
+        peer.send_transaction_sync_message(TransactionSyncMessage::NewTransaction(first_parent_id))
+            .await;
+        node.assert_no_sync_message().await;
+
+        // Advance time to make the parent request expire.
+        time_getter.advance_time(ORPHAN_PARENT_REQUEST_TIMEOUT + Duration::from_secs(1));
+        node.clear_notifications().await;
+        node.wait_for_notification(SyncManagerNotification::NewTxSyncManagerMainLoopIteration {
+            peer_id: peer.get_id(),
+        })
+        .await;
+
+        // Now the parent can be requested again.
+        peer.send_transaction_sync_message(TransactionSyncMessage::NewTransaction(first_parent_id))
+            .await;
+        let (sent_to, message) = node.get_sent_transaction_sync_message().await;
+        assert_eq!(sent_to, peer.get_id());
+        assert_eq!(
+            message,
+            TransactionSyncMessage::TransactionRequest(first_parent_id)
+        );
+
+        // The peer is never punished.
+        node.assert_no_peer_manager_event().await;
+
+        node.join_subsystem_manager().await;
+    })
+    .await;
+}
+
 /// Creates a simple transaction.
+// A rejection of our transaction is recorded, and the transaction is no longer announced
+// to the peer that has rejected it.
+#[tracing::instrument(skip(seed))]
+#[rstest::rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn transaction_rejected_by_peer(#[case] seed: Seed) {
+    for_each_protocol_version(|protocol_version| async move {
+        let mut rng = test_utils::random::make_seedable_rng(seed);
+
+        let chain_config = Arc::new(create_unit_test_config());
+        let mut tf = TestFramework::builder(&mut rng)
+            .with_chain_config(chain_config.as_ref().clone())
+            .build();
+        // Process a block to finish the initial block download.
+        tf.make_block_builder().build_and_process(&mut rng).unwrap().unwrap();
+
+        let mut node = TestNode::builder(protocol_version)
+            .with_chain_config(Arc::clone(&chain_config))
+            .with_chainstate(tf.into_chainstate())
+            .build()
+            .await;
+
+        let peer = node.connect_peer(PeerId::new(), protocol_version).await;
+
+        let tx_id = transaction(chain_config.genesis_block_id()).transaction().get_id();
+        peer.send_transaction_sync_message(TransactionSyncMessage::TransactionReject(
+            TransactionRejectMessage {
+                tx_id,
+                code: TransactionRejectCode::MempoolFull,
+                reason: "Mempool is full".to_owned(),
+            },
+        ))
+        .await;
+
+        // The messages are handled in order, so the rejection has been handled once the response
+        // to the next message is sent.
+        peer.send_transaction_sync_message(TransactionSyncMessage::TransactionRequest(tx_id))
+            .await;
+        let (sent_to, message) = node.get_sent_transaction_sync_message().await;
+        assert_eq!(peer.get_id(), sent_to);
+        assert_eq!(
+            message,
+            TransactionSyncMessage::TransactionResponse(TransactionResponse::NotFound(tx_id))
+        );
+
+        let rejections = node.tx_rejections().get(&tx_id);
+        assert_eq!(rejections.len(), 1);
+        assert_eq!(rejections[0].peer_id, peer.get_id());
+        assert_eq!(rejections[0].code, TransactionRejectCode::MempoolFull);
+        assert_eq!(rejections[0].reason, "Mempool is full");
+
+        // Unsolicited rejections are not punished.
+        node.assert_no_peer_manager_event().await;
+        node.assert_no_error().await;
+
+        node.join_subsystem_manager().await;
+    })
+    .await;
+}
+
 fn transaction_with_amount(out_point: Id<GenBlock>, amount_atoms: u128) -> SignedTransaction {
     let tx = Transaction::new(
         0x00,
@@ -636,3 +961,18 @@ fn transaction_with_amount(out_point: Id<GenBlock>, amount_atoms: u128) -> Signe
 fn transaction(out_point: Id<GenBlock>) -> SignedTransaction {
     transaction_with_amount(out_point, 1)
 }
+
+/// Creates a transaction spending the first outputs of the specified transactions.
+fn transaction_spending(parents: &[Id<Transaction>], amount_atoms: u128) -> SignedTransaction {
+    let tx = Transaction::new(
+        0x00,
+        parents
+            .iter()
+            .map(|parent_id| TxInput::from_utxo((*parent_id).into(), 0))
+            .collect(),
+        vec![TxOutput::Burn(OutputValue::Coin(Amount::from_atoms(amount_atoms)))],
+    )
+    .unwrap();
+    let witnesses = parents.iter().map(|_| InputWitness::NoSignature(None)).collect();
+    SignedTransaction::new(tx, witnesses).unwrap()
+}
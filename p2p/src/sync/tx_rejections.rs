@@ -0,0 +1,102 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+
+use common::{chain::Transaction, primitives::Id};
+use utils::sync::Mutex;
+
+use crate::interface::types::TransactionRejection;
+
+/// The maximum number of rejections that are remembered; the oldest ones are forgotten first.
+const MAX_TX_REJECTIONS: usize = 1000;
+
+/// Rejections of our transactions reported by peers, shared between all the peer tasks.
+///
+/// They are only used for reporting, the peers' claims are not verified in any way.
+#[derive(Debug)]
+pub struct TxRejections {
+    rejections: Mutex<VecDeque<(Id<Transaction>, TransactionRejection)>>,
+}
+
+impl TxRejections {
+    pub fn new() -> Self {
+        Self {
+            rejections: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn add(&self, tx_id: Id<Transaction>, rejection: TransactionRejection) {
+        let mut rejections = self.rejections.lock().expect("poisoned mutex");
+        if rejections.len() >= MAX_TX_REJECTIONS {
+            rejections.pop_front();
+        }
+        rejections.push_back((tx_id, rejection));
+    }
+
+    /// Return the rejections of the transaction, the oldest first.
+    pub fn get(&self, tx_id: &Id<Transaction>) -> Vec<TransactionRejection> {
+        self.rejections
+            .lock()
+            .expect("poisoned mutex")
+            .iter()
+            .filter(|(id, _)| id == tx_id)
+            .map(|(_, rejection)| rejection.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::primitives::{time::Time, H256};
+
+    use crate::{message::TransactionRejectCode, types::peer_id::PeerId};
+
+    use super::*;
+
+    fn make_rejection(peer_id: PeerId) -> TransactionRejection {
+        TransactionRejection {
+            peer_id,
+            code: TransactionRejectCode::MempoolFull,
+            reason: "Mempool is full".to_owned(),
+            time: Time::from_secs_since_epoch(1),
+        }
+    }
+
+    #[test]
+    fn old_rejections_are_forgotten() {
+        let tx1 = Id::<Transaction>::new(H256::from_low_u64_be(1));
+        let tx2 = Id::<Transaction>::new(H256::from_low_u64_be(2));
+        let peer1 = PeerId::new();
+        let peer2 = PeerId::new();
+
+        let rejections = TxRejections::new();
+        rejections.add(tx1, make_rejection(peer1));
+        rejections.add(tx1, make_rejection(peer2));
+        rejections.add(tx2, make_rejection(peer1));
+
+        assert_eq!(
+            rejections.get(&tx1),
+            vec![make_rejection(peer1), make_rejection(peer2)]
+        );
+        assert_eq!(rejections.get(&tx2), vec![make_rejection(peer1)]);
+
+        for _ in 0..MAX_TX_REJECTIONS - 2 {
+            rejections.add(tx2, make_rejection(peer2));
+        }
+        assert_eq!(rejections.get(&tx1), vec![make_rejection(peer2)]);
+        assert_eq!(rejections.get(&tx2).len(), MAX_TX_REJECTIONS - 1);
+    }
+}
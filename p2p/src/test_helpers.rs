@@ -182,6 +182,7 @@ pub fn test_p2p_config() -> P2pConfig {
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -197,6 +198,7 @@ pub fn test_p2p_config() -> P2pConfig {
         sync_stalling_timeout: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     }
 }
 
@@ -207,6 +209,7 @@ pub fn test_p2p_config_with_peer_mgr_config(peer_manager_config: PeerManagerConf
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -221,6 +224,7 @@ pub fn test_p2p_config_with_peer_mgr_config(peer_manager_config: PeerManagerConf
         user_agent: mintlayer_core_user_agent(),
         sync_stalling_timeout: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     }
 }
 
@@ -229,14 +233,17 @@ pub fn test_p2p_config_with_peer_db_config(peerdb_config: PeerDbConfig) -> P2pCo
         peerdb_config,
 
         max_inbound_connections: Default::default(),
+        max_inbound_connections_per_address_group: Default::default(),
         preserved_inbound_count_address_group: Default::default(),
         preserved_inbound_count_ping: Default::default(),
         preserved_inbound_count_new_blocks: Default::default(),
         preserved_inbound_count_new_transactions: Default::default(),
+        preserved_inbound_count_age: Default::default(),
         outbound_full_relay_count: Default::default(),
         outbound_full_relay_extra_count: Default::default(),
         outbound_block_relay_count: Default::default(),
         outbound_block_relay_extra_count: Default::default(),
+        max_outbound_connections_per_network: Default::default(),
         outbound_block_relay_connection_min_age: Default::default(),
         outbound_full_relay_connection_min_age: Default::default(),
         stale_tip_time_diff: Default::default(),
@@ -255,6 +262,7 @@ pub fn test_p2p_config_with_ban_config(ban_config: BanConfig) -> P2pConfig {
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -269,6 +277,7 @@ pub fn test_p2p_config_with_ban_config(ban_config: BanConfig) -> P2pConfig {
         sync_stalling_timeout: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     }
 }
 
@@ -276,6 +285,7 @@ pub fn test_peer_mgr_config_with_no_auto_outbound_connections() -> PeerManagerCo
     PeerManagerConfig {
         outbound_block_relay_count: 0.into(),
         outbound_block_relay_extra_count: 0.into(),
+        max_outbound_connections_per_network: Default::default(),
         outbound_full_relay_count: 0.into(),
         outbound_full_relay_extra_count: 0.into(),
         enable_feeler_connections: false.into(),
@@ -286,7 +296,9 @@ pub fn test_peer_mgr_config_with_no_auto_outbound_connections() -> PeerManagerCo
         preserved_inbound_count_ping: Default::default(),
         preserved_inbound_count_new_blocks: Default::default(),
         preserved_inbound_count_new_transactions: Default::default(),
+        preserved_inbound_count_age: Default::default(),
         max_inbound_connections: Default::default(),
+        max_inbound_connections_per_address_group: Default::default(),
         outbound_full_relay_connection_min_age: Default::default(),
         stale_tip_time_diff: Default::default(),
         main_loop_tick_interval: Default::default(),
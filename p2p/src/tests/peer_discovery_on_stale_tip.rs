@@ -104,6 +104,7 @@ async fn peer_discovery_on_stale_tip_impl(
 
         outbound_full_relay_extra_count: outbound_full_relay_extra_conn_count.into(),
         outbound_block_relay_extra_count: outbound_block_relay_extra_conn_count.into(),
+        max_outbound_connections_per_network: Default::default(),
 
         // These values will only matter if max_inbound_connections is low enough.
         // Also, we don't really want to make inbound peer eviction more aggressive,
@@ -112,8 +113,10 @@ async fn peer_discovery_on_stale_tip_impl(
         preserved_inbound_count_ping: Default::default(),
         preserved_inbound_count_new_blocks: Default::default(),
         preserved_inbound_count_new_transactions: Default::default(),
+        preserved_inbound_count_age: Default::default(),
 
         max_inbound_connections: Default::default(),
+        max_inbound_connections_per_address_group: Default::default(),
         outbound_block_relay_connection_min_age: Default::default(),
         outbound_full_relay_connection_min_age: Default::default(),
         stale_tip_time_diff: Default::default(),
@@ -296,6 +299,7 @@ async fn new_full_relay_connections_on_stale_tip_impl(seed: Seed) {
 
         outbound_block_relay_count: 0.into(),
         outbound_block_relay_extra_count: 0.into(),
+        max_outbound_connections_per_network: Default::default(),
 
         stale_tip_time_diff: stale_tip_time_diff.into(),
         outbound_full_relay_connection_min_age: outbound_conn_min_age.into(),
@@ -307,7 +311,9 @@ async fn new_full_relay_connections_on_stale_tip_impl(seed: Seed) {
         preserved_inbound_count_ping: Default::default(),
         preserved_inbound_count_new_blocks: Default::default(),
         preserved_inbound_count_new_transactions: Default::default(),
+        preserved_inbound_count_age: Default::default(),
         max_inbound_connections: Default::default(),
+        max_inbound_connections_per_address_group: Default::default(),
         outbound_block_relay_connection_min_age: Default::default(),
         feeler_connections_interval: Default::default(),
         force_dns_query_if_no_global_addresses_known: Default::default(),
@@ -323,6 +329,7 @@ async fn new_full_relay_connections_on_stale_tip_impl(seed: Seed) {
 
         outbound_block_relay_count: 0.into(),
         outbound_block_relay_extra_count: 0.into(),
+        max_outbound_connections_per_network: Default::default(),
 
         stale_tip_time_diff: stale_tip_time_diff.into(),
         main_loop_tick_interval: peer_mgr_main_loop_tick_interval.into(),
@@ -333,7 +340,9 @@ async fn new_full_relay_connections_on_stale_tip_impl(seed: Seed) {
         preserved_inbound_count_ping: Default::default(),
         preserved_inbound_count_new_blocks: Default::default(),
         preserved_inbound_count_new_transactions: Default::default(),
+        preserved_inbound_count_age: Default::default(),
         max_inbound_connections: Default::default(),
+        max_inbound_connections_per_address_group: Default::default(),
         outbound_block_relay_connection_min_age: Default::default(),
         outbound_full_relay_connection_min_age: Default::default(),
         feeler_connections_interval: Default::default(),
@@ -491,6 +500,7 @@ pub fn make_p2p_config(peer_manager_config: PeerManagerConfig) -> P2pConfig {
         bind_addresses: Default::default(),
         socks5_proxy: Default::default(),
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -504,6 +514,7 @@ pub fn make_p2p_config(peer_manager_config: PeerManagerConfig) -> P2pConfig {
         allow_discover_private_ips: Default::default(),
         user_agent: mintlayer_core_user_agent(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     }
 }
 
@@ -0,0 +1,27 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use networking::test_helpers::TestTransportNoise;
+use networking::transport::NoiseTcpTransport;
+use p2p::net::default_backend::MultiplexedNetworkingService;
+
+fn main() {
+    logging::init_logging();
+
+    p2p_backend_test_suite::run::<
+        TestTransportNoise,
+        MultiplexedNetworkingService<NoiseTcpTransport>,
+    >();
+}
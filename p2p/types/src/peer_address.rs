@@ -50,7 +50,43 @@ pub enum PeerAddress {
     Ip6(PeerAddressIp6),
 }
 
+/// The network that a peer address belongs to.
+///
+/// The node tries to keep outbound connections to all the reachable networks it knows
+/// addresses in, so that it stays connected if the networks get partitioned.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    rpc_description::HasValueHint,
+)]
+pub enum NetworkType {
+    Ipv4,
+    Ipv6,
+    /// Tor onion services. Onion addresses are only relayed for now; they can't be stored
+    /// in PeerDb or connected to, so there are never any connections to this network.
+    Onion,
+}
+
+impl NetworkType {
+    pub const ALL: [NetworkType; 3] = [NetworkType::Ipv4, NetworkType::Ipv6, NetworkType::Onion];
+}
+
 impl PeerAddress {
+    pub fn network_type(&self) -> NetworkType {
+        match self {
+            PeerAddress::Ip4(_) => NetworkType::Ipv4,
+            PeerAddress::Ip6(_) => NetworkType::Ipv6,
+        }
+    }
+
     pub fn is_loopback(&self) -> bool {
         std::net::SocketAddr::from(self).ip().is_loopback()
     }
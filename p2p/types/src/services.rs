@@ -21,10 +21,27 @@ pub enum Service {
     Transactions = 1 << 0,
     Blocks = 1 << 1,
     PeerAddresses = 1 << 2,
+    /// The node announces the range of blocks it can serve if it doesn't have all of them and
+    /// reports the requested blocks that it doesn't have instead of ignoring the request.
+    BlockDataAvailability = 1 << 3,
+    /// The node can multiplex the messages over several lanes of the connection once
+    /// the handshake is done.
+    MultiplexedFraming = 1 << 4,
 }
 
 impl Service {
-    pub const ALL: [Service; 3] = [Service::Transactions, Service::Blocks, Service::PeerAddresses];
+    pub const ALL: [Service; 5] = [
+        Service::Transactions,
+        Service::Blocks,
+        Service::PeerAddresses,
+        Service::BlockDataAvailability,
+        Service::MultiplexedFraming,
+    ];
+
+    /// Services that are used when both sides support them, but which are not required
+    /// from the peers when choosing outbound connections.
+    pub const OPTIONAL: [Service; 2] =
+        [Service::BlockDataAvailability, Service::MultiplexedFraming];
 }
 
 #[derive(Eq, PartialEq, Clone, Copy, Debug, Encode, Decode)]
@@ -42,6 +59,12 @@ impl Services {
     pub fn is_empty(&self) -> bool {
         self.0 == 0
     }
+
+    /// Return the services without the optional ones.
+    pub fn required(&self) -> Services {
+        let optional: Services = Service::OPTIONAL.as_slice().into();
+        Services(self.0 & !optional.0)
+    }
 }
 
 impl From<&[Service]> for Services {
@@ -79,4 +102,16 @@ pub mod test {
             assert!(services.has_service(flag));
         }
     }
+
+    #[test]
+    fn test_required_services() {
+        let all_services: Services = Service::ALL.as_slice().into();
+        let required = all_services.required();
+        for flag in Service::ALL {
+            assert_eq!(
+                required.has_service(flag),
+                !Service::OPTIONAL.contains(&flag)
+            );
+        }
+    }
 }
@@ -55,6 +55,16 @@ pub struct DelegateStakingUndo {
     pub(crate) amount_to_delegate: Amount,
 }
 
+impl DelegateStakingUndo {
+    pub fn delegation_target(&self) -> DelegationId {
+        self.delegation_target
+    }
+
+    pub fn amount_to_delegate(&self) -> Amount {
+        self.amount_to_delegate
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
 pub struct SpendFromShareUndo {
     pub(crate) delegation_id: DelegationId,
@@ -68,6 +78,16 @@ pub struct IncreaseStakerRewardsUndo {
     pub(crate) data_undo: DataDeltaUndo<PoolData>,
 }
 
+impl IncreaseStakerRewardsUndo {
+    pub fn pool_id(&self) -> PoolId {
+        self.pool_id
+    }
+
+    pub fn amount_added(&self) -> Amount {
+        self.amount_added
+    }
+}
+
 #[must_use]
 #[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, VariantCount)]
 pub enum PoSAccountingUndo {
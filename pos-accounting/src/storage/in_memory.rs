@@ -57,6 +57,16 @@ impl InMemoryPoSAccounting {
         }
     }
 
+    pub fn into_data(self) -> PoSAccountingData {
+        PoSAccountingData {
+            pool_data: self.pool_data,
+            pool_balances: self.pool_balances,
+            pool_delegation_shares: self.pool_delegation_shares,
+            delegation_balances: self.delegation_balances,
+            delegation_data: self.delegation_data,
+        }
+    }
+
     pub fn from_values(
         pool_data: BTreeMap<PoolId, PoolData>,
         pool_balances: BTreeMap<PoolId, Amount>,
@@ -105,6 +105,11 @@ impl<T: HasValueHint> HasValueHint for Vec<T> {
     const HINT_DE: VH = VH::Array(&T::HINT_DE);
 }
 
+impl<T: HasValueHint> HasValueHint for std::collections::BTreeSet<T> {
+    const HINT_SER: VH = VH::Array(&T::HINT_SER);
+    const HINT_DE: VH = VH::Array(&T::HINT_DE);
+}
+
 impl<K: HasValueHint, V: HasValueHint> HasValueHint for std::collections::BTreeMap<K, V> {
     const HINT_SER: VH = VH::Map(&K::HINT_SER, &V::HINT_SER);
     const HINT_DE: VH = VH::Map(&K::HINT_DE, &V::HINT_DE);
@@ -18,7 +18,10 @@
 use std::fmt::Display;
 
 /// RPC error
-use jsonrpsee::types::{error::CALL_EXECUTION_FAILED_CODE, ErrorObject, ErrorObjectOwned};
+use jsonrpsee::types::{
+    error::{CALL_EXECUTION_FAILED_CODE, METHOD_NOT_FOUND_CODE},
+    ErrorObject, ErrorObjectOwned,
+};
 
 /// The Result type with RPC-specific error.
 pub type RpcResult<T> = jsonrpsee::core::RpcResult<T>;
@@ -27,6 +30,12 @@ pub type RpcClientResult<T> = std::result::Result<T, jsonrpsee::core::ClientErro
 pub type Error = ErrorObjectOwned;
 pub type ClientError = jsonrpsee::core::ClientError;
 
+/// Whether the call failed because the server doesn't know the method,
+/// e.g. because it runs an older version
+pub fn is_method_not_found(err: &ClientError) -> bool {
+    matches!(err, ClientError::Call(err) if err.code() == METHOD_NOT_FOUND_CODE)
+}
+
 /// Handle RPC result
 ///
 /// This is a generic way of converting the likes of:
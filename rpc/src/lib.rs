@@ -37,7 +37,10 @@ use jsonrpsee::{
 
 use logging::log;
 
-pub use error::{handle_result, ClientError, Error, RpcCallResult, RpcClientResult, RpcResult};
+pub use error::{
+    handle_result, is_method_not_found, ClientError, Error, RpcCallResult, RpcClientResult,
+    RpcResult,
+};
 
 pub use jsonrpsee::{core::server::Methods, proc_macros::rpc};
 use rpc_auth::RpcAuth;
@@ -19,7 +19,7 @@ use std::borrow::Cow;
 
 use utils::shallow_clone::ShallowClone;
 
-pub use crate::{Data, DbDesc, DbMapId};
+pub use crate::{CompactionReport, CompactionStatus, Data, DbDesc, DbMapId};
 
 /// Read-only database operations
 pub trait ReadOps {
@@ -77,6 +77,30 @@ pub trait BackendImpl: Send + Sync + ShallowClone + 'static {
 
     /// Start a read-write transaction
     fn transaction_rw(&self, size: Option<usize>) -> crate::Result<Self::TxRw<'_>>;
+
+    /// Size of the database files in bytes, or `None` if the database is not stored on disk
+    fn disk_size(&self) -> crate::Result<Option<u64>> {
+        Ok(None)
+    }
+
+    /// Reclaim the space occupied by deleted entries
+    ///
+    /// Backends that cannot do this while the database is open may schedule the compaction to
+    /// happen the next time the database is opened.
+    fn compact(&self) -> crate::Result<CompactionStatus> {
+        Ok(CompactionStatus::NotSupported)
+    }
+
+    /// Whether a compaction has been scheduled to happen the next time the database is opened
+    fn is_compaction_scheduled(&self) -> crate::Result<bool> {
+        Ok(false)
+    }
+
+    /// Progress and outcome of the last compaction, or `None` if the database has never been
+    /// compacted or the backend does not keep track of it
+    fn last_compaction(&self) -> crate::Result<Option<CompactionReport>> {
+        Ok(None)
+    }
 }
 
 /// Storage backend type. Used to set up storage.
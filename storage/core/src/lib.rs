@@ -93,7 +93,10 @@ pub mod util;
 // Re-export some commonly used items
 pub use backend::Backend;
 pub use error::Error;
-pub use types::{DbDesc, DbMapCount, DbMapDesc, DbMapId, DbMapsData};
+pub use types::{
+    CompactionOutcome, CompactionReport, CompactionStatus, DbDesc, DbMapCount, DbMapDesc, DbMapId,
+    DbMapsData,
+};
 
 /// Raw byte sequences, used to represent store keys and values
 pub type Data = Vec<u8>;
@@ -161,6 +161,50 @@ impl DbDesc {
     }
 }
 
+/// Outcome of a request to compact the database
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum CompactionStatus {
+    /// The backend does not support compaction
+    NotSupported,
+
+    /// The database has been compacted
+    Completed,
+
+    /// The database is going to be compacted the next time it is opened
+    ScheduledOnRestart,
+}
+
+/// Result of the last compaction of the database
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum CompactionOutcome {
+    /// The compaction did not finish, e.g. because the process was stopped while it was running
+    Interrupted,
+
+    /// The database has been compacted to the given size of its files
+    Completed { size_after: u64 },
+
+    /// The compaction has failed and the original database has been kept
+    Failed { error: String },
+}
+
+/// Progress and outcome of the last compaction of the database
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct CompactionReport {
+    /// Size of the database files before the compaction
+    pub size_before: u64,
+
+    /// Number of the key-value maps that have been copied into the compacted database
+    pub maps_compacted: usize,
+
+    /// Total number of the key-value maps
+    pub maps_total: usize,
+
+    /// When the compaction has finished, in seconds since the Unix epoch
+    pub finished_at: Option<u64>,
+
+    pub outcome: CompactionOutcome,
+}
+
 /// Internal constructors.
 ///
 /// Only to be used by storage framework and testing. Not for use by backend implementations.
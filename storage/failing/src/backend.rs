@@ -129,6 +129,22 @@ impl<T: backend::BackendImpl> backend::BackendImpl for FailingImpl<T> {
         let inner = self.inner.transaction_rw(size)?;
         Ok(TxRw { inner, state })
     }
+
+    fn disk_size(&self) -> storage_core::Result<Option<u64>> {
+        self.inner.disk_size()
+    }
+
+    fn compact(&self) -> storage_core::Result<storage_core::CompactionStatus> {
+        self.inner.compact()
+    }
+
+    fn is_compaction_scheduled(&self) -> storage_core::Result<bool> {
+        self.inner.is_compaction_scheduled()
+    }
+
+    fn last_compaction(&self) -> storage_core::Result<Option<storage_core::CompactionReport>> {
+        self.inner.last_compaction()
+    }
 }
 
 struct RwTxState<'a> {
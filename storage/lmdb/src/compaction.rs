@@ -0,0 +1,351 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Offline database compaction.
+//!
+//! LMDB never shrinks its data file; pages freed by deleted entries are only reused for new
+//! writes. To give the space back to the file system, the contents are copied into a fresh
+//! environment, which is then swapped in place of the original one.
+//!
+//! Copying must not race with writes to the original database, so the compaction is only
+//! scheduled while the database is in use (by creating a marker file in the database directory)
+//! and is performed the next time the database is opened.
+//!
+//! The progress and the outcome of the last compaction are recorded in a report file in the
+//! database directory, so they can be inspected once the database is open.
+
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use lmdb::{Cursor, Transaction};
+use logging::log;
+use storage_core::{CompactionOutcome, CompactionReport, DbDesc, DbMapDesc};
+
+use crate::error;
+
+/// Name of the file signalling that the database has to be compacted when opened
+const MARKER_FILE_NAME: &str = "compact_on_open";
+
+/// Name of the file the progress and the outcome of the last compaction are recorded in
+const REPORT_FILE_NAME: &str = "last_compaction";
+
+/// Name of the LMDB data file inside the database directory
+const DATA_FILE_NAME: &str = "data.mdb";
+
+/// Number of entries copied in a single write transaction
+const ENTRIES_PER_TX: usize = 10_000;
+
+fn marker_file_path(db_path: &Path) -> PathBuf {
+    db_path.join(MARKER_FILE_NAME)
+}
+
+fn sibling_path(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut name = db_path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    db_path.with_file_name(name)
+}
+
+/// Directory the compacted database is written into
+fn compacted_path(db_path: &Path) -> PathBuf {
+    sibling_path(db_path, ".compacting")
+}
+
+/// Directory the original database is moved to before the compacted one takes its place
+fn original_path(db_path: &Path) -> PathBuf {
+    sibling_path(db_path, ".original")
+}
+
+/// Size of the data file of the database stored in given directory
+pub fn data_file_size(db_path: &Path) -> storage_core::Result<u64> {
+    let metadata =
+        std::fs::metadata(db_path.join(DATA_FILE_NAME)).map_err(error::process_io_error)?;
+    Ok(metadata.len())
+}
+
+pub fn schedule(db_path: &Path) -> storage_core::Result<()> {
+    std::fs::write(marker_file_path(db_path), []).map_err(error::process_io_error)
+}
+
+pub fn is_scheduled(db_path: &Path) -> bool {
+    marker_file_path(db_path).exists()
+}
+
+fn unschedule(db_path: &Path) -> storage_core::Result<()> {
+    match std::fs::remove_file(marker_file_path(db_path)) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(error::process_io_error(err)),
+    }
+}
+
+fn seconds_since_epoch() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+fn write_report(db_path: &Path, report: &CompactionReport) -> storage_core::Result<()> {
+    let CompactionReport {
+        size_before,
+        maps_compacted,
+        maps_total,
+        finished_at,
+        outcome,
+    } = report;
+
+    let outcome = match outcome {
+        CompactionOutcome::Interrupted => "interrupted".to_owned(),
+        CompactionOutcome::Completed { size_after } => format!("completed:{size_after}"),
+        // Keep the report line-based
+        CompactionOutcome::Failed { error } => format!("failed:{}", error.replace('\n', " ")),
+    };
+    let mut contents = format!(
+        "size_before={size_before}\nmaps_compacted={maps_compacted}\nmaps_total={maps_total}\n"
+    );
+    if let Some(finished_at) = finished_at {
+        contents.push_str(&format!("finished_at={finished_at}\n"));
+    }
+    contents.push_str(&format!("outcome={outcome}\n"));
+
+    std::fs::write(db_path.join(REPORT_FILE_NAME), contents).map_err(error::process_io_error)
+}
+
+fn parse_report(contents: &str) -> Option<CompactionReport> {
+    let fields = contents
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .collect::<std::collections::BTreeMap<_, _>>();
+
+    let outcome = fields.get("outcome")?;
+    let outcome = if *outcome == "interrupted" {
+        CompactionOutcome::Interrupted
+    } else if let Some(size_after) = outcome.strip_prefix("completed:") {
+        CompactionOutcome::Completed {
+            size_after: size_after.parse().ok()?,
+        }
+    } else {
+        CompactionOutcome::Failed {
+            error: outcome.strip_prefix("failed:")?.to_owned(),
+        }
+    };
+
+    Some(CompactionReport {
+        size_before: fields.get("size_before")?.parse().ok()?,
+        maps_compacted: fields.get("maps_compacted")?.parse().ok()?,
+        maps_total: fields.get("maps_total")?.parse().ok()?,
+        finished_at: fields.get("finished_at").map(|v| v.parse()).transpose().ok()?,
+        outcome,
+    })
+}
+
+/// The progress and the outcome of the last compaction of the database in given directory
+pub fn read_report(db_path: &Path) -> storage_core::Result<Option<CompactionReport>> {
+    let contents = match std::fs::read_to_string(db_path.join(REPORT_FILE_NAME)) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(error::process_io_error(err)),
+    };
+
+    let report = parse_report(&contents);
+    if report.is_none() {
+        log::warn!("Malformed compaction report in {}", db_path.display());
+    }
+    Ok(report)
+}
+
+/// Bring the database directory into a consistent state if a previous compaction was interrupted
+pub fn recover(db_path: &Path) -> storage_core::Result<()> {
+    let compacted = compacted_path(db_path);
+    let original = original_path(db_path);
+
+    if original.exists() {
+        if !db_path.exists() {
+            // The original database has been moved away but the compacted one has not been
+            // moved in yet. The compacted database is complete at this point.
+            log::info!("Finishing interrupted compaction of {}", db_path.display());
+            std::fs::rename(&compacted, db_path).map_err(error::process_io_error)?;
+        }
+        std::fs::remove_dir_all(&original).map_err(error::process_io_error)?;
+    } else if compacted.exists() {
+        // The copying did not finish, the original database is untouched. The marker file is
+        // still in place, so the compaction is going to be attempted again.
+        log::info!(
+            "Removing incomplete compacted database {}",
+            compacted.display()
+        );
+        std::fs::remove_dir_all(&compacted).map_err(error::process_io_error)?;
+    }
+
+    Ok(())
+}
+
+fn open_env(
+    path: &Path,
+    desc: &DbDesc,
+    map_size: usize,
+) -> storage_core::Result<lmdb::Environment> {
+    lmdb::Environment::new()
+        .set_map_size(map_size)
+        .set_max_dbs(desc.db_map_count().as_usize() as u32)
+        .open(path)
+        .or_else(error::process_with_err)
+}
+
+fn open_db(env: &lmdb::Environment, desc: &DbMapDesc) -> storage_core::Result<lmdb::Database> {
+    env.create_db(Some(desc.name()), lmdb::DatabaseFlags::default())
+        .or_else(error::process_with_err)
+}
+
+fn copy_map(
+    src_env: &lmdb::Environment,
+    dest_env: &lmdb::Environment,
+    desc: &DbMapDesc,
+) -> storage_core::Result<usize> {
+    let src_db = open_db(src_env, desc)?;
+    let dest_db = open_db(dest_env, desc)?;
+
+    let src_tx = src_env.begin_ro_txn().or_else(error::process_with_err)?;
+    let cursor = src_tx.open_ro_cursor(src_db).or_else(error::process_with_err)?;
+
+    let mut dest_tx = dest_env.begin_rw_txn(None).or_else(error::process_with_err)?;
+    let mut copied = 0;
+
+    for entry in cursor.into_iter_start() {
+        let (key, val) = entry.or_else(error::process_with_err)?;
+
+        // The entries come out sorted, so appending packs them densely into the pages.
+        dest_tx
+            .put(dest_db, &key, &val, lmdb::WriteFlags::APPEND)
+            .or_else(error::process_with_err)?;
+        copied += 1;
+
+        if copied % ENTRIES_PER_TX == 0 {
+            dest_tx.commit().or_else(error::process_with_err)?;
+            dest_tx = dest_env.begin_rw_txn(None).or_else(error::process_with_err)?;
+            log::debug!("Compacting map {}: {} entries copied", desc.name(), copied);
+        }
+    }
+
+    dest_tx.commit().or_else(error::process_with_err)?;
+
+    Ok(copied)
+}
+
+/// Compact the database stored in given directory, replacing it with the compacted copy
+pub fn compact(db_path: &Path, desc: &DbDesc) -> storage_core::Result<()> {
+    let compacted = compacted_path(db_path);
+    let original = original_path(db_path);
+
+    let size_before = data_file_size(db_path)?;
+    log::info!(
+        "Compacting database {} ({} bytes)",
+        db_path.display(),
+        size_before
+    );
+
+    let mut report = CompactionReport {
+        size_before,
+        maps_compacted: 0,
+        maps_total: desc.db_map_count().as_usize(),
+        finished_at: None,
+        outcome: CompactionOutcome::Interrupted,
+    };
+    write_report(db_path, &report)?;
+
+    // The compacted data is not larger than the original one. Leave some headroom for the pages
+    // that are in use by the intermediate transactions; the memory map is only reserved, the file
+    // grows as the data is written.
+    let map_size = (size_before as usize).saturating_mul(2).max(1 << 20);
+
+    {
+        std::fs::create_dir_all(&compacted).map_err(error::process_io_error)?;
+        let src_env = open_env(db_path, desc, map_size)?;
+        let dest_env = open_env(&compacted, desc, map_size)?;
+
+        for idx in desc.db_map_count().indices() {
+            let map_desc = &desc.db_maps()[idx];
+            let copied = copy_map(&src_env, &dest_env, map_desc)?;
+            log::info!("Compacted map {}: {} entries", map_desc.name(), copied);
+
+            report.maps_compacted += 1;
+            write_report(db_path, &report)?;
+        }
+
+        dest_env.sync(true).or_else(error::process_with_err)?;
+    }
+
+    std::fs::rename(db_path, &original).map_err(error::process_io_error)?;
+    std::fs::rename(&compacted, db_path).map_err(error::process_io_error)?;
+
+    // The compacted database is in place at this point, the leftovers are removed by `recover`
+    // the next time if this fails
+    if let Err(err) = std::fs::remove_dir_all(&original) {
+        log::warn!("Failed to remove {}: {}", original.display(), err);
+    }
+
+    let size_after = data_file_size(db_path)?;
+    log::info!(
+        "Database {} compacted from {} to {} bytes",
+        db_path.display(),
+        size_before,
+        size_after
+    );
+
+    report.finished_at = Some(seconds_since_epoch());
+    report.outcome = CompactionOutcome::Completed { size_after };
+    write_report(db_path, &report)?;
+
+    Ok(())
+}
+
+/// Give up on a compaction that has failed, e.g. because there is not enough disk space
+/// for the copy, and keep the original database.
+///
+/// The compaction is not scheduled anymore, so the failure doesn't repeat on every start.
+pub fn abandon(
+    db_path: &Path,
+    desc: &DbDesc,
+    err: &storage_core::Error,
+) -> storage_core::Result<()> {
+    recover(db_path)?;
+    unschedule(db_path)?;
+
+    let report = match read_report(db_path)? {
+        Some(report) => report,
+        None => CompactionReport {
+            size_before: data_file_size(db_path).unwrap_or(0),
+            maps_compacted: 0,
+            maps_total: desc.db_map_count().as_usize(),
+            finished_at: None,
+            outcome: CompactionOutcome::Interrupted,
+        },
+    };
+    let report = CompactionReport {
+        finished_at: Some(seconds_since_epoch()),
+        outcome: CompactionOutcome::Failed {
+            error: err.to_string(),
+        },
+        ..report
+    };
+    // The report is informational only, not being able to write it (e.g. if the disk is still
+    // full) must not prevent the database from being opened
+    if let Err(err) = write_report(db_path, &report) {
+        log::warn!("Failed to record the compaction failure: {}", err);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BTreeMap, path::Path};
+
+use rstest::rstest;
+
+use storage_core::{
+    backend::{Backend, BackendImpl, CompactionStatus, ReadOps, TxRw, WriteOps},
+    CompactionOutcome, DbDesc,
+};
+use test_utils::random::{make_seedable_rng, Rng, Seed};
+
+use super::*;
+
+fn make_desc() -> DbDesc {
+    storage_core::types::construct::db_desc(
+        [DbMapDesc::new("Db0"), DbMapDesc::new("Db1")].into_iter(),
+    )
+}
+
+fn open(path: &Path) -> LmdbImpl {
+    Lmdb::new(
+        path.to_owned(),
+        Default::default(),
+        Default::default(),
+        Default::default(),
+    )
+    .open(make_desc())
+    .unwrap()
+}
+
+fn dump(lmdb_impl: &LmdbImpl) -> Vec<BTreeMap<Data, Data>> {
+    let ro_tx = lmdb_impl.transaction_ro().unwrap();
+    (0..2)
+        .map(|idx| ro_tx.greater_equal_iter(DbMapId::new(idx), Vec::new()).unwrap().collect())
+        .collect()
+}
+
+#[rstest]
+#[trace]
+#[case(test_utils::random::Seed::from_entropy())]
+fn compact_on_reopen(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let data_dir = tempfile::Builder::new().prefix("lmdb_compaction").tempdir().unwrap();
+    let db_path = data_dir.path().join("db");
+
+    let lmdb_impl = open(&db_path);
+    assert!(!lmdb_impl.is_compaction_scheduled().unwrap());
+
+    let entries: Vec<(usize, Data, Data)> = (0..2000)
+        .map(|i| {
+            let key = (i as u32).to_be_bytes().to_vec();
+            let val = (0..rng.gen_range(500..2000)).map(|_| rng.gen::<u8>()).collect();
+            (rng.gen_range(0..2), key, val)
+        })
+        .collect();
+
+    let mut rw_tx = lmdb_impl.transaction_rw(None).unwrap();
+    for (map_idx, key, val) in &entries {
+        rw_tx.put(DbMapId::new(*map_idx), key.clone(), val.clone()).unwrap();
+    }
+    rw_tx.commit().unwrap();
+
+    // Delete most of the data, the file does not shrink
+    let size_before_delete = lmdb_impl.disk_size().unwrap().unwrap();
+    let mut rw_tx = lmdb_impl.transaction_rw(None).unwrap();
+    for (map_idx, key, _) in entries.iter().filter(|_| rng.gen_bool(0.9)) {
+        rw_tx.del(DbMapId::new(*map_idx), key).unwrap();
+    }
+    rw_tx.commit().unwrap();
+    let size_before = lmdb_impl.disk_size().unwrap().unwrap();
+    assert!(size_before >= size_before_delete);
+
+    let expected = dump(&lmdb_impl);
+
+    assert_eq!(
+        lmdb_impl.compact().unwrap(),
+        CompactionStatus::ScheduledOnRestart
+    );
+    assert!(lmdb_impl.is_compaction_scheduled().unwrap());
+    drop(lmdb_impl);
+
+    let lmdb_impl = open(&db_path);
+    assert!(!lmdb_impl.is_compaction_scheduled().unwrap());
+    assert_eq!(dump(&lmdb_impl), expected);
+
+    let size_after = lmdb_impl.disk_size().unwrap().unwrap();
+    assert!(size_after < size_before);
+
+    let report = lmdb_impl.last_compaction().unwrap().unwrap();
+    assert_eq!(report.size_before, size_before);
+    assert_eq!(report.maps_compacted, 2);
+    assert_eq!(report.maps_total, 2);
+    assert!(report.finished_at.is_some());
+    assert_eq!(report.outcome, CompactionOutcome::Completed { size_after });
+}
+
+// The compaction fails because the directory for the compacted copy can't be created
+#[cfg(unix)]
+#[test]
+fn failed_compaction_keeps_original() {
+    let data_dir = tempfile::Builder::new().prefix("lmdb_compaction").tempdir().unwrap();
+    let db_path = data_dir.path().join("db");
+    let compacted_path = data_dir.path().join("db.compacting");
+
+    let lmdb_impl = open(&db_path);
+    assert_eq!(lmdb_impl.last_compaction().unwrap(), None);
+    let mut rw_tx = lmdb_impl.transaction_rw(None).unwrap();
+    rw_tx.put(DbMapId::new(0), vec![1], vec![2]).unwrap();
+    rw_tx.commit().unwrap();
+    let expected = dump(&lmdb_impl);
+    lmdb_impl.compact().unwrap();
+    drop(lmdb_impl);
+
+    std::os::unix::fs::symlink(data_dir.path().join("nonexistent"), &compacted_path).unwrap();
+
+    let lmdb_impl = open(&db_path);
+    assert_eq!(dump(&lmdb_impl), expected);
+    assert!(!lmdb_impl.is_compaction_scheduled().unwrap());
+
+    let report = lmdb_impl.last_compaction().unwrap().unwrap();
+    assert_eq!(report.maps_compacted, 0);
+    assert!(report.finished_at.is_some());
+    assert!(matches!(report.outcome, CompactionOutcome::Failed { .. }));
+    drop(lmdb_impl);
+
+    // The failure doesn't repeat on the next start
+    let lmdb_impl = open(&db_path);
+    assert_eq!(dump(&lmdb_impl), expected);
+}
+
+#[test]
+fn recover_interrupted_compaction() {
+    let data_dir = tempfile::Builder::new().prefix("lmdb_compaction").tempdir().unwrap();
+    let db_path = data_dir.path().join("db");
+    let compacted_path = data_dir.path().join("db.compacting");
+    let original_path = data_dir.path().join("db.original");
+
+    let lmdb_impl = open(&db_path);
+    let mut rw_tx = lmdb_impl.transaction_rw(None).unwrap();
+    rw_tx.put(DbMapId::new(0), vec![1], vec![2]).unwrap();
+    rw_tx.commit().unwrap();
+    let expected = dump(&lmdb_impl);
+    drop(lmdb_impl);
+
+    // Copying interrupted, the incomplete copy is discarded
+    std::fs::create_dir_all(&compacted_path).unwrap();
+    let lmdb_impl = open(&db_path);
+    assert_eq!(dump(&lmdb_impl), expected);
+    assert!(!compacted_path.exists());
+    drop(lmdb_impl);
+
+    // Interrupted after the original database has been moved away
+    std::fs::rename(&db_path, &compacted_path).unwrap();
+    std::fs::create_dir_all(&original_path).unwrap();
+    let lmdb_impl = open(&db_path);
+    assert_eq!(dump(&lmdb_impl), expected);
+    assert!(!compacted_path.exists());
+    assert!(!original_path.exists());
+    drop(lmdb_impl);
+
+    // Interrupted before the original database has been removed
+    std::fs::create_dir_all(&original_path).unwrap();
+    let lmdb_impl = open(&db_path);
+    assert_eq!(dump(&lmdb_impl), expected);
+    assert!(!original_path.exists());
+}
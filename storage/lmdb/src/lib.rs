@@ -16,6 +16,7 @@
 #[cfg(not(target_pointer_width = "64"))]
 compile_error!("LMDB only compiles for 64-bit targets due to the way memory mapping works");
 
+mod compaction;
 mod error;
 pub mod initial_map_size;
 pub mod memsize;
@@ -29,6 +30,7 @@ use std::{borrow::Cow, path::PathBuf};
 
 use initial_map_size::InitialMapSize;
 use lmdb::Cursor;
+use logging::log;
 use resize_callback::MapResizeCallback;
 use storage_core::{backend, Data, DbDesc, DbMapDesc, DbMapId, DbMapsData};
 use utils::const_value::ConstValue;
@@ -156,6 +158,9 @@ pub struct LmdbImpl {
 
     /// Schedule a database resize of the database map
     map_resize_scheduled: Arc<AtomicBool>,
+
+    /// Path to the database directory
+    path: Arc<PathBuf>,
 }
 
 impl LmdbImpl {
@@ -217,6 +222,7 @@ impl utils::shallow_clone::ShallowClone for LmdbImpl {
             env: self.env.shallow_clone(),
             dbs: self.dbs.shallow_clone(),
             map_resize_scheduled: self.map_resize_scheduled.shallow_clone(),
+            path: self.path.shallow_clone(),
         }
     }
 }
@@ -233,6 +239,23 @@ impl backend::BackendImpl for LmdbImpl {
         self.resize_if_resize_scheduled();
         self.start_transaction(|env| lmdb::Environment::begin_rw_txn(env, size))
     }
+
+    fn disk_size(&self) -> storage_core::Result<Option<u64>> {
+        compaction::data_file_size(&self.path).map(Some)
+    }
+
+    fn compact(&self) -> storage_core::Result<backend::CompactionStatus> {
+        compaction::schedule(&self.path)?;
+        Ok(backend::CompactionStatus::ScheduledOnRestart)
+    }
+
+    fn is_compaction_scheduled(&self) -> storage_core::Result<bool> {
+        Ok(compaction::is_scheduled(&self.path))
+    }
+
+    fn last_compaction(&self) -> storage_core::Result<Option<backend::CompactionReport>> {
+        compaction::read_report(&self.path)
+    }
 }
 
 pub struct Lmdb {
@@ -280,9 +303,24 @@ impl backend::Backend for Lmdb {
     type Impl = LmdbImpl;
 
     fn open(self, desc: DbDesc) -> storage_core::Result<Self::Impl> {
+        // Finish or roll back a compaction that has been interrupted
+        compaction::recover(&self.path)?;
+
         // Attempt to create the storage directory
         std::fs::create_dir_all(&self.path).map_err(error::process_io_error)?;
 
+        if compaction::is_scheduled(&self.path) {
+            if let Err(err) = compaction::compact(&self.path, &desc) {
+                // Keep using the uncompacted database rather than failing to start
+                log::error!(
+                    "Compaction of database {} failed, using it uncompacted: {}",
+                    self.path.display(),
+                    err
+                );
+                compaction::abandon(&self.path, &desc, &err)?;
+            }
+        }
+
         let initial_map_size = self
             .initial_map_size
             .into_memsize()
@@ -311,9 +349,12 @@ impl backend::Backend for Lmdb {
             env: Arc::new(environment),
             dbs,
             map_resize_scheduled: Arc::new(AtomicBool::new(false)),
+            path: Arc::new(self.path),
         })
     }
 }
 
+#[cfg(test)]
+mod compaction_tests;
 #[cfg(test)]
 mod resize_tests;
@@ -180,6 +180,9 @@ pub struct SqliteConnection {
 
     /// List of sql queries
     queries: SqliteQueries,
+
+    /// Whether the database is stored in a file, as opposed to being kept in memory
+    is_file: bool,
 }
 
 #[derive(Clone)]
@@ -190,6 +193,13 @@ impl SqliteImpl {
     fn start_transaction(&self) -> storage_core::Result<DbTx<'_>> {
         DbTx::start_transaction(self)
     }
+
+    fn lock_connection(&self) -> storage_core::Result<MutexGuard<'_, Connection>> {
+        self.0
+            .connection
+            .lock()
+            .map_err(|e| storage_core::error::Fatal::InternalError(e.to_string()).into())
+    }
 }
 
 impl ShallowClone for SqliteImpl {
@@ -210,6 +220,28 @@ impl backend::BackendImpl for SqliteImpl {
     fn transaction_rw(&self, _size: Option<usize>) -> storage_core::Result<Self::TxRw<'_>> {
         self.start_transaction()
     }
+
+    fn disk_size(&self) -> storage_core::Result<Option<u64>> {
+        if !self.0.is_file {
+            return Ok(None);
+        }
+
+        let connection = self.lock_connection()?;
+        let page_count = connection
+            .pragma_query_value(None, "page_count", |row| row.get::<usize, u64>(0))
+            .map_err(process_sqlite_error)?;
+        let page_size = connection
+            .pragma_query_value(None, "page_size", |row| row.get::<usize, u64>(0))
+            .map_err(process_sqlite_error)?;
+
+        Ok(Some(page_count * page_size))
+    }
+
+    fn compact(&self) -> storage_core::Result<backend::CompactionStatus> {
+        let connection = self.lock_connection()?;
+        connection.execute("VACUUM", ()).map_err(process_sqlite_error)?;
+        Ok(backend::CompactionStatus::Completed)
+    }
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
@@ -337,12 +369,14 @@ impl backend::Backend for Sqlite {
         }
 
         let queries = desc.db_maps().transform(queries::SqliteQuery::from_desc);
+        let is_file = matches!(self.backend, SqliteStorageMode::File(_));
 
         let connection = self.open_db(desc).map_err(process_sqlite_error)?;
 
         Ok(SqliteImpl(Arc::new(SqliteConnection {
             connection: Mutex::new(connection),
             queries,
+            is_file,
         })))
     }
 }
@@ -27,7 +27,7 @@ use crate::schema::{self, Schema};
 use serialization::{encoded::Encoded, Encode, EncodeLike};
 use storage_core::{
     backend::{self, TxRw, WriteOps},
-    Backend, DbMapId,
+    Backend, CompactionReport, CompactionStatus, DbMapId,
 };
 
 /// The main storage type
@@ -97,6 +97,26 @@ impl<B: Backend, Sch: Schema> Storage<B, Sch> {
         let _schema = std::marker::PhantomData;
         Ok(TransactionRw { dbtx, _schema })
     }
+
+    /// Size of the database files in bytes, or `None` if the database is not stored on disk
+    pub fn disk_size(&self) -> crate::Result<Option<u64>> {
+        backend::BackendImpl::disk_size(&self.backend)
+    }
+
+    /// Reclaim the space occupied by deleted entries, if supported by the backend
+    pub fn compact(&self) -> crate::Result<CompactionStatus> {
+        backend::BackendImpl::compact(&self.backend)
+    }
+
+    /// Whether the compaction is going to happen the next time the database is opened
+    pub fn is_compaction_scheduled(&self) -> crate::Result<bool> {
+        backend::BackendImpl::is_compaction_scheduled(&self.backend)
+    }
+
+    /// Progress and outcome of the last compaction, if known
+    pub fn last_compaction(&self) -> crate::Result<Option<CompactionReport>> {
+        backend::BackendImpl::last_compaction(&self.backend)
+    }
 }
 
 pub trait MakeMapRef<'tx, B: Backend, Sch: Schema>: TxImpl + Sized {
@@ -140,6 +160,11 @@ impl<'tx, B: Backend, Sch: Schema> TransactionRo<'tx, B, Sch> {
     pub fn dump_raw(&self) -> crate::Result<raw::StorageContents<Sch>> {
         raw::dump_storage(self)
     }
+
+    /// Collect the amount of data stored in each key-value map
+    pub fn storage_stats(&self) -> crate::Result<raw::StorageStats<Sch>> {
+        raw::storage_stats(self)
+    }
 }
 
 /// A read-write transaction
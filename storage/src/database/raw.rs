@@ -141,6 +141,41 @@ pub fn dump_storage<B: Backend, Sch: Schema>(
         .collect::<crate::Result<StorageContents<Sch>>>()
 }
 
+/// Amount of data stored in a single key-value store
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub struct MapStats {
+    /// Number of entries
+    pub entry_count: u64,
+    /// Total size of the keys in bytes
+    pub key_bytes: u64,
+    /// Total size of the values in bytes
+    pub value_bytes: u64,
+}
+
+/// Amount of data stored in each key-value store
+pub type StorageStats<Sch> = BTreeMap<DbMapId<Sch>, MapStats>;
+
+/// Collect the amount of data stored in each key-value store
+pub fn storage_stats<B: Backend, Sch: Schema>(
+    dbtx: &TransactionRo<'_, B, Sch>,
+) -> crate::Result<StorageStats<Sch>> {
+    Sch::desc_iter()
+        .enumerate()
+        .map(|(idx, _dbinfo)| {
+            let idx = storage_core::DbMapId::new(idx);
+            let stats = dbtx.dbtx.prefix_iter(idx, Vec::new())?.fold(
+                MapStats::default(),
+                |stats, (key, val)| MapStats {
+                    entry_count: stats.entry_count + 1,
+                    key_bytes: stats.key_bytes + key.len() as u64,
+                    value_bytes: stats.value_bytes + val.len() as u64,
+                },
+            );
+            Ok((DbMapId::from_idx_unchecked(idx), stats))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use crate::Storage;
@@ -216,4 +251,41 @@ mod test {
             }
         })
     }
+
+    #[test]
+    fn basic_stats() {
+        utils::concurrency::model(|| {
+            let storage = Storage::<_, TestSchema>::new(InMemory::new()).unwrap();
+            let db1 = DbMapId::new::<Db0, _>();
+            let db2 = DbMapId::new::<Db1, _>();
+
+            let stats = storage.transaction_ro().unwrap().storage_stats().unwrap();
+            assert_eq!(stats.len(), 2);
+            assert!(stats.values().all(|s| *s == MapStats::default()));
+
+            let mut dbtx = storage.transaction_rw(None).unwrap();
+            dbtx.get_mut::<Db0, _>().put(42, 1337).unwrap();
+            dbtx.get_mut::<Db1, _>().put(21, vec![1, 2, 3, 4]).unwrap();
+            dbtx.get_mut::<Db1, _>().put(22, vec![1, 2]).unwrap();
+            dbtx.commit().unwrap();
+
+            let stats = storage.transaction_ro().unwrap().storage_stats().unwrap();
+            assert_eq!(
+                stats[&db1],
+                MapStats {
+                    entry_count: 1,
+                    key_bytes: 4,
+                    value_bytes: 4,
+                }
+            );
+            assert_eq!(
+                stats[&db2],
+                MapStats {
+                    entry_count: 2,
+                    key_bytes: 4,
+                    value_bytes: 8,
+                }
+            );
+        })
+    }
 }
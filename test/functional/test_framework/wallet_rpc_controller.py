@@ -422,15 +422,22 @@ class WalletRpcController:
         result = result['result']
 
         coins = result['coins']['decimal']
-        tokens = {}
 
-        if 'tokens' in result:
-            for (hexified_token_id, balance) in result['tokens'].items():
+        def dehexify_tokens(key):
+            tokens = {}
+            for (hexified_token_id, balance) in result.get(key, {}).items():
                 token_id_as_addr = self.node.test_functions_dehexify_all_addresses(hexified_token_id)
                 tokens[token_id_as_addr] = balance['decimal']
+            return tokens
+
+        tokens = dehexify_tokens('tokens')
+        unverified_tokens = dehexify_tokens('unverified_tokens')
 
         # Mimic the output of wallet_cli_controller's 'get_balance'
-        return "\n".join([f"Coins amount: {coins}"] + [f"Token: {token} amount: {amount}" for token, amount in tokens.items()])
+        lines = [f"Coins amount: {coins}"] + [f"Token: {token} amount: {amount}" for token, amount in tokens.items()]
+        if unverified_tokens:
+            lines += ["Unverified tokens:"] + [f"Token: {token} amount: {amount}" for token, amount in unverified_tokens.items()]
+        return "\n".join(lines)
 
     async def list_pending_transactions(self) -> List[str]:
         output = self._write_command("transaction_list_pending", [self.account])['result']
@@ -541,11 +548,12 @@ class WalletRpcController:
                                   outputs: List[TransferTxOutput],
                                   selected_utxos: List[UtxoOutpoint],
                                   htlc_secrets: Optional[List[Optional[str]]] = None,
-                                  only_transaction: bool = False) -> str:
+                                  only_transaction: bool = False,
+                                  sighash_types: Optional[List[str]] = None) -> str:
         utxos = [utxo.to_json() for utxo in selected_utxos]
         outputs = [output.to_json() for output in outputs]
         print(outputs)
-        result = self._write_command('transaction_compose', [utxos, outputs, htlc_secrets, only_transaction])
+        result = self._write_command('transaction_compose', [utxos, outputs, htlc_secrets, sighash_types, only_transaction])
         return result
 
     async def create_htlc_transaction(self,
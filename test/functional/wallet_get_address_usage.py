@@ -114,32 +114,32 @@ class WalletGetAddressUsage(BitcoinTestFramework):
             output = await wallet.sync()
             assert_in("Success", output)
 
-            expected_output = """+-------+----------------------------------------------+--------------------------------+
-| Index | Address                                      | Is used in transaction history |
-+=======+==============================================+================================+
-| 0     | rmt1qx5p4r2en7c99mpmg2tz9hucxfarf4k6dypq388a | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 1     | rmt1q9jvqp9p8rzp2prmpa8y9vde7yrvlxgz3s54n787 | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 2     | rmt1qx7dwah3rtkh2mv7lyd4qserqx59mqjknc6qdn77 | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 3     | rmt1qxrkx54pykusw7am7zr282t6tzsl3wzkysrh0k2a | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 4     | rmt1qyyra5j3qduhyd43wa50lpn2ddpg9ql0u50ceu68 | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 5     | rmt1q8upmt2mjxel84msaqjj2rkquguvswwzquy6w8sn | No                             |
-+-------+----------------------------------------------+--------------------------------+
-| 6     | rmt1q8lrw5tzgmwjnsc26v8qfu8k2jmddpmhwqz6kwt7 | No                             |
-+-------+----------------------------------------------+--------------------------------+"""
+            expected_output = """+-------+----------------------------------------------+--------------------------------+-------+
+| Index | Address                                      | Is used in transaction history | Label |
++=======+==============================================+================================+=======+
+| 0     | rmt1qx5p4r2en7c99mpmg2tz9hucxfarf4k6dypq388a | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 1     | rmt1q9jvqp9p8rzp2prmpa8y9vde7yrvlxgz3s54n787 | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 2     | rmt1qx7dwah3rtkh2mv7lyd4qserqx59mqjknc6qdn77 | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 3     | rmt1qxrkx54pykusw7am7zr282t6tzsl3wzkysrh0k2a | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 4     | rmt1qyyra5j3qduhyd43wa50lpn2ddpg9ql0u50ceu68 | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 5     | rmt1q8upmt2mjxel84msaqjj2rkquguvswwzquy6w8sn | No                             |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 6     | rmt1q8lrw5tzgmwjnsc26v8qfu8k2jmddpmhwqz6kwt7 | No                             |       |
++-------+----------------------------------------------+--------------------------------+-------+"""
             output = await wallet.get_addresses_usage()
             for (line, expected_line) in zip(output.split(), expected_output.split()):
                 assert_equal(line, expected_line)
 
             decommission_address = await wallet.new_address()
-            expected_vrf_output = """+-------+---------+--------------------------------+
-| Index | Address | Is used in transaction history |
-+=======+=========+================================+
-+-------+---------+--------------------------------+"""
+            expected_vrf_output = """+-------+---------+--------------------------------+---------------+
+| Index | Address | Is used in transaction history | Used by pools |
++=======+=========+================================+===============+
++-------+---------+--------------------------------+---------------+"""
             output = await wallet.get_vrf_addresses_usage()
             self.log.info(output)
             for (line, expected_line) in zip(output.split(), expected_vrf_output.split()):
@@ -155,35 +155,38 @@ class WalletGetAddressUsage(BitcoinTestFramework):
             assert_in("The transaction was submitted successfully", await wallet.create_stake_pool(stake_pool_amount, 0, 0.5, decommission_address))
             self.generate_block()
 
-            expected_output = """+-------+----------------------------------------------+--------------------------------+
-| Index | Address                                      | Is used in transaction history |
-+=======+==============================================+================================+
-| 0     | rmt1qx5p4r2en7c99mpmg2tz9hucxfarf4k6dypq388a | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 1     | rmt1q9jvqp9p8rzp2prmpa8y9vde7yrvlxgz3s54n787 | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 2     | rmt1qx7dwah3rtkh2mv7lyd4qserqx59mqjknc6qdn77 | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 3     | rmt1qxrkx54pykusw7am7zr282t6tzsl3wzkysrh0k2a | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 4     | rmt1qyyra5j3qduhyd43wa50lpn2ddpg9ql0u50ceu68 | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 5     | rmt1q8upmt2mjxel84msaqjj2rkquguvswwzquy6w8sn | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 6     | rmt1q8lrw5tzgmwjnsc26v8qfu8k2jmddpmhwqz6kwt7 | Yes                            |
-+-------+----------------------------------------------+--------------------------------+
-| 7     | rmt1q824xhhlcdazxj38yuqr6llqz3wm7whhgvmyvyjz | Yes                            |
-+-------+----------------------------------------------+--------------------------------+"""
+            expected_output = """+-------+----------------------------------------------+--------------------------------+-------+
+| Index | Address                                      | Is used in transaction history | Label |
++=======+==============================================+================================+=======+
+| 0     | rmt1qx5p4r2en7c99mpmg2tz9hucxfarf4k6dypq388a | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 1     | rmt1q9jvqp9p8rzp2prmpa8y9vde7yrvlxgz3s54n787 | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 2     | rmt1qx7dwah3rtkh2mv7lyd4qserqx59mqjknc6qdn77 | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 3     | rmt1qxrkx54pykusw7am7zr282t6tzsl3wzkysrh0k2a | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 4     | rmt1qyyra5j3qduhyd43wa50lpn2ddpg9ql0u50ceu68 | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 5     | rmt1q8upmt2mjxel84msaqjj2rkquguvswwzquy6w8sn | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 6     | rmt1q8lrw5tzgmwjnsc26v8qfu8k2jmddpmhwqz6kwt7 | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+
+| 7     | rmt1q824xhhlcdazxj38yuqr6llqz3wm7whhgvmyvyjz | Yes                            |       |
++-------+----------------------------------------------+--------------------------------+-------+"""
             output = await wallet.get_addresses_usage()
             for (line, expected_line) in zip(output.split(), expected_output.split()):
                 assert_equal(line, expected_line)
 
             vrf_public_key = "rvrfpk1qregu4v895mchautf84u46nsf9xel2507a37ksaf3stmuw44y3m4vc2kzme"
-            expected_vrf_output = f"""+-------+--------------------------------------------------------------------+--------------------------------+
-            | Index | Address                                                            | Is used in transaction history |
-            +=======+====================================================================+================================+
-            | 0     | {vrf_public_key} | Yes                             |
-            +-------+--------------------------------------------------------------------+--------------------------------+"""
+            pool_id = (await wallet.list_pool_ids())[0].pool_id
+            pool_id_dashes = "-" * (len(pool_id) + 2)
+            pool_id_equals = "=" * (len(pool_id) + 2)
+            expected_vrf_output = f"""+-------+--------------------------------------------------------------------+--------------------------------+{pool_id_dashes}+
+            | Index | Address                                                            | Is used in transaction history | Used by pools |
+            +=======+====================================================================+================================+{pool_id_equals}+
+            | 0     | {vrf_public_key} | Yes                             | {pool_id} |
+            +-------+--------------------------------------------------------------------+--------------------------------+{pool_id_dashes}+"""
             output = await wallet.get_vrf_addresses_usage()
             for (line, expected_line) in zip(output.split(), expected_vrf_output.split()):
                 assert_equal(line, expected_line)
@@ -0,0 +1,216 @@
+// Copyright (c) 2021-2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    fmt::Display,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum IpSubnetParseError {
+    #[error("Invalid IP address: {0}")]
+    InvalidAddress(String),
+    #[error("Invalid prefix length: {0}")]
+    InvalidPrefixLength(String),
+}
+
+/// A range of IP addresses sharing the same prefix, e.g. "192.168.0.0/16" or "2a00::/16".
+///
+/// A plain IP address is treated as a subnet containing only that address.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, DeserializeFromStr, SerializeDisplay,
+)]
+pub struct IpSubnet {
+    address: IpAddr,
+    prefix_len: u8,
+}
+
+fn max_prefix_len(address: &IpAddr) -> u8 {
+    match address {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+fn mask_v4(address: Ipv4Addr, prefix_len: u8) -> Ipv4Addr {
+    let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+    Ipv4Addr::from(u32::from(address) & mask)
+}
+
+fn mask_v6(address: Ipv6Addr, prefix_len: u8) -> Ipv6Addr {
+    let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+    Ipv6Addr::from(u128::from(address) & mask)
+}
+
+impl IpSubnet {
+    /// Create a subnet; the host bits of the address are cleared.
+    /// Returns None if the prefix length is too big for the address family.
+    pub fn new(address: IpAddr, prefix_len: u8) -> Option<Self> {
+        if prefix_len > max_prefix_len(&address) {
+            return None;
+        }
+
+        let address = match address {
+            IpAddr::V4(address) => IpAddr::V4(mask_v4(address, prefix_len)),
+            IpAddr::V6(address) => IpAddr::V6(mask_v6(address, prefix_len)),
+        };
+
+        Some(Self {
+            address,
+            prefix_len,
+        })
+    }
+
+    pub fn address(&self) -> IpAddr {
+        self.address
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Check whether the address belongs to the subnet.
+    ///
+    /// IPv4-mapped IPv6 addresses are considered equal to the corresponding IPv4 addresses.
+    pub fn contains(&self, address: &IpAddr) -> bool {
+        let address = match address {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(*address, IpAddr::V4),
+            IpAddr::V4(_) => *address,
+        };
+
+        match (self.address, address) {
+            (IpAddr::V4(subnet), IpAddr::V4(address)) => {
+                mask_v4(address, self.prefix_len) == subnet
+            }
+            (IpAddr::V6(subnet), IpAddr::V6(address)) => {
+                mask_v6(address, self.prefix_len) == subnet
+            }
+            (IpAddr::V4(_), IpAddr::V6(_)) | (IpAddr::V6(_), IpAddr::V4(_)) => false,
+        }
+    }
+}
+
+impl From<IpAddr> for IpSubnet {
+    fn from(address: IpAddr) -> Self {
+        Self {
+            address,
+            prefix_len: max_prefix_len(&address),
+        }
+    }
+}
+
+impl FromStr for IpSubnet {
+    type Err = IpSubnetParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address_str, prefix_len_str) = match s.split_once('/') {
+            Some((address, prefix_len)) => (address, Some(prefix_len)),
+            None => (s, None),
+        };
+
+        let address = address_str
+            .parse::<IpAddr>()
+            .map_err(|_| IpSubnetParseError::InvalidAddress(address_str.to_owned()))?;
+
+        match prefix_len_str {
+            Some(prefix_len_str) => prefix_len_str
+                .parse::<u8>()
+                .ok()
+                .and_then(|prefix_len| Self::new(address, prefix_len))
+                .ok_or_else(|| IpSubnetParseError::InvalidPrefixLength(prefix_len_str.to_owned())),
+            None => Ok(address.into()),
+        }
+    }
+}
+
+impl Display for IpSubnet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.prefix_len == max_prefix_len(&self.address) {
+            self.address.fmt(f)
+        } else {
+            write!(f, "{}/{}", self.address, self.prefix_len)
+        }
+    }
+}
+
+impl rpc_description::HasValueHint for IpSubnet {
+    const HINT_SER: rpc_description::ValueHint = rpc_description::ValueHint::STRING;
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_test::{assert_tokens, Token};
+
+    use super::*;
+
+    #[test]
+    fn serialize_and_deserialize() {
+        for original in ["1.1.1.1", "10.0.0.0/8", "2a00::1", "2a00::/16", "0.0.0.0/0"] {
+            let parsed: IpSubnet = original.parse().unwrap();
+            assert_tokens(&parsed, &[Token::Str(original)]);
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "1.1.1".parse::<IpSubnet>(),
+            Err(IpSubnetParseError::InvalidAddress("1.1.1".to_owned()))
+        );
+        assert_eq!(
+            "1.1.1.0/33".parse::<IpSubnet>(),
+            Err(IpSubnetParseError::InvalidPrefixLength("33".to_owned()))
+        );
+        assert_eq!(
+            "2a00::/129".parse::<IpSubnet>(),
+            Err(IpSubnetParseError::InvalidPrefixLength("129".to_owned()))
+        );
+        assert_eq!(
+            "1.1.1.0/x".parse::<IpSubnet>(),
+            Err(IpSubnetParseError::InvalidPrefixLength("x".to_owned()))
+        );
+    }
+
+    #[test]
+    fn host_bits_are_cleared() {
+        let subnet: IpSubnet = "192.168.1.1/16".parse().unwrap();
+        assert_eq!(subnet.to_string(), "192.168.0.0/16");
+    }
+
+    #[test]
+    fn contains() {
+        let subnet: IpSubnet = "192.168.0.0/16".parse().unwrap();
+        assert!(subnet.contains(&"192.168.0.1".parse().unwrap()));
+        assert!(subnet.contains(&"192.168.255.255".parse().unwrap()));
+        assert!(subnet.contains(&"::ffff:192.168.1.1".parse().unwrap()));
+        assert!(!subnet.contains(&"192.169.0.1".parse().unwrap()));
+        assert!(!subnet.contains(&"2a00::1".parse().unwrap()));
+
+        let single: IpSubnet = "10.0.0.1".parse().unwrap();
+        assert!(single.contains(&"10.0.0.1".parse().unwrap()));
+        assert!(!single.contains(&"10.0.0.2".parse().unwrap()));
+
+        let subnet: IpSubnet = "2a00:1234::/32".parse().unwrap();
+        assert!(subnet.contains(&"2a00:1234:5678::1".parse().unwrap()));
+        assert!(!subnet.contains(&"2a00:1235::1".parse().unwrap()));
+        assert!(!subnet.contains(&"10.0.0.1".parse().unwrap()));
+
+        let any: IpSubnet = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains(&"8.8.8.8".parse().unwrap()));
+    }
+}
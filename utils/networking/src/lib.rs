@@ -17,9 +17,11 @@
 
 pub mod broadcaster;
 mod ip_or_socket_address;
+mod ip_subnet;
 mod network_address;
 mod resolvable_name;
 
 pub use ip_or_socket_address::*;
+pub use ip_subnet::*;
 pub use network_address::*;
 pub use resolvable_name::*;
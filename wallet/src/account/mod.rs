@@ -20,6 +20,7 @@ mod utxo_selector;
 
 use common::address::pubkeyhash::PublicKeyHash;
 use common::chain::block::timestamp::BlockTimestamp;
+use common::chain::block::ConsensusData;
 use common::chain::classic_multisig::ClassicMultisigChallenge;
 use common::chain::htlc::HashedTimelockContract;
 use common::chain::partially_signed_transaction::PartiallySignedTransaction;
@@ -31,13 +32,20 @@ use common::size_estimation::{
     DestinationInfoProvider,
 };
 use common::Uint256;
+use crypto::key::extended::ExtendedPublicKey;
 use crypto::key::hdkd::child_number::ChildNumber;
+use crypto::key::hdkd::derivable::Derivable;
 use mempool::FeeRate;
 use serialization::hex_encoded::HexEncoded;
 use utils::ensure;
 pub use utxo_selector::UtxoSelectorError;
-use wallet_types::account_id::AccountPrefixedId;
-use wallet_types::account_info::{StandaloneAddressDetails, StandaloneAddresses};
+use wallet_types::account_id::{AccountPoolId, AccountPrefixedId, AccountScheduledPaymentId};
+use wallet_types::account_info::{
+    ChangePolicy, DustProtection, ScheduledPayment, StandaloneAddressDetails, StandaloneAddresses,
+    WatchedPool,
+};
+use wallet_types::internal_transfer::InternalTransfer;
+use wallet_types::multisig_account::{MultisigAccountDescriptor, MultisigDerivationTemplate};
 use wallet_types::with_locked::WithLocked;
 
 use crate::account::utxo_selector::{select_coins, OutputGroup};
@@ -48,7 +56,7 @@ use crate::send_request::{
     make_decommission_stake_pool_output, make_mint_token_outputs, make_stake_output,
     make_unmint_token_outputs, IssueNftArguments, SelectedInputs, StakePoolDataArguments,
 };
-use crate::wallet::WalletPoolsFilter;
+use crate::wallet::{WalletPoolsFilter, MAX_EXTENDED_PUBLIC_KEY_IMPORT_ADDRESS_COUNT};
 use crate::wallet_events::{WalletEvents, WalletEventsNoOp};
 use crate::{SendRequest, WalletError, WalletResult};
 use common::address::{Address, RpcAddress};
@@ -69,6 +77,7 @@ use itertools::{izip, Itertools};
 use std::cmp::Reverse;
 use std::collections::btree_map::Entry;
 use std::collections::{BTreeMap, BTreeSet};
+use std::num::NonZeroU8;
 use std::ops::{Add, Sub};
 use std::sync::Arc;
 use wallet_storage::{
@@ -78,16 +87,19 @@ use wallet_storage::{
 use wallet_types::utxo_types::{get_utxo_type, UtxoState, UtxoStates, UtxoType, UtxoTypes};
 use wallet_types::wallet_tx::{BlockData, TxData, TxState};
 use wallet_types::{
-    AccountId, AccountInfo, AccountWalletCreatedTxId, AccountWalletTxId, BlockInfo, KeyPurpose,
-    KeychainUsageState, WalletTx,
+    AccountId, AccountInfo, AccountWalletTxId, BlockInfo, KeyPurpose, KeychainUsageState, WalletTx,
 };
 
 use self::currency_grouper::Currency;
 pub use self::output_cache::{
-    DelegationData, FungibleTokenInfo, PoolData, TxInfo, UnconfirmedTokenInfo, UtxoWithTxOutput,
+    DelegationData, FungibleTokenInfo, PoolData, StakedBlock, TrackedUtxo, TxChainPosition, TxInfo,
+    UnconfirmedTokenInfo, UtxoWithTxOutput,
 };
 use self::output_cache::{OutputCache, TokenIssuanceData};
-use self::transaction_list::{get_transaction_list, TransactionList};
+use self::transaction_list::{
+    describe_transaction, get_transaction_history, get_transaction_labels, get_transaction_list,
+    TransactionInfo, TransactionList, TxDescriptionItem,
+};
 use self::utxo_selector::PayFee;
 
 pub use self::utxo_selector::CoinSelectionAlgo;
@@ -97,6 +109,16 @@ pub struct CurrentFeeRate {
     pub consolidate_fee_rate: FeeRate,
 }
 
+/// The way the fee of an unconfirmed transaction is increased
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeBumpKind {
+    /// The transaction is replaced by one spending the same inputs with a higher fee
+    ReplaceByFee,
+    /// A new transaction spends the outputs of the original one and pays the missing fee
+    /// for both of them
+    ChildPaysForParent,
+}
+
 pub enum TransactionToSign {
     Tx(Transaction),
     Partial(PartiallySignedTransaction),
@@ -116,6 +138,12 @@ pub struct Account {
     key_chain: AccountKeyChainImpl,
     output_cache: OutputCache,
     account_info: AccountInfo,
+    watched_pools: BTreeMap<PoolId, WatchedPool>,
+    scheduled_payments: BTreeMap<u64, ScheduledPayment>,
+    dust_protection: DustProtection,
+    frozen_utxos: BTreeSet<UtxoOutPoint>,
+    change_policy: ChangePolicy,
+    address_labels: BTreeMap<Destination, String>,
 }
 
 impl Account {
@@ -138,11 +166,33 @@ impl Account {
         let txs = db_tx.get_transactions(&key_chain.get_account_id())?;
         let output_cache = OutputCache::new(txs)?;
 
+        let watched_pools = db_tx.get_account_watched_pools(&key_chain.get_account_id())?;
+        let scheduled_payments =
+            db_tx.get_account_scheduled_payments(&key_chain.get_account_id())?;
+
+        let dust_protection = db_tx
+            .get_account_dust_protection(&key_chain.get_account_id())?
+            .unwrap_or_default();
+
+        let frozen_utxos = db_tx.get_account_frozen_utxos(&key_chain.get_account_id())?;
+
+        let change_policy = db_tx
+            .get_account_change_policy(&key_chain.get_account_id())?
+            .unwrap_or_default();
+
+        let address_labels = db_tx.get_account_address_labels(&key_chain.get_account_id())?;
+
         Ok(Account {
             chain_config,
             key_chain,
             output_cache,
             account_info,
+            watched_pools,
+            scheduled_payments,
+            dust_protection,
+            frozen_utxos,
+            change_policy,
+            address_labels,
         })
     }
 
@@ -173,6 +223,12 @@ impl Account {
             key_chain,
             output_cache,
             account_info,
+            watched_pools: BTreeMap::new(),
+            scheduled_payments: BTreeMap::new(),
+            dust_protection: DustProtection::default(),
+            frozen_utxos: BTreeSet::new(),
+            change_policy: ChangePolicy::default(),
+            address_labels: BTreeMap::new(),
         };
 
         account.scan_genesis(db_tx, &WalletEventsNoOp)?;
@@ -223,11 +279,30 @@ impl Account {
             self.account_info.best_block_height(),
         )?;
 
-        let network_fee: Amount = fee_rates
-            .current_fee_rate
-            .compute_fee(tx_size_with_outputs(request.outputs()))
-            .map_err(|_| UtxoSelectorError::AmountArithmeticError)?
-            .into();
+        // With an absolute fee nothing is derived from the size of the transaction,
+        // so the whole fee is accounted for as the network fee and the rates are zeroed.
+        let (network_fee, fee_rates) = match request.absolute_fee() {
+            Some(absolute_fee) => {
+                let zero_rate = FeeRate::from_amount_per_kb(Amount::ZERO);
+                (
+                    absolute_fee,
+                    CurrentFeeRate {
+                        current_fee_rate: zero_rate,
+                        consolidate_fee_rate: zero_rate,
+                    },
+                )
+            }
+            None => {
+                let network_fee: Amount = fee_rates
+                    .current_fee_rate
+                    .compute_fee(tx_size_with_outputs(request.outputs()))
+                    .map_err(|_| UtxoSelectorError::AmountArithmeticError)?
+                    .into();
+                (network_fee, fee_rates)
+            }
+        };
+
+        self.ensure_not_frozen(request.inputs().iter().filter_map(TxInput::utxo_outpoint))?;
 
         let mut preselected_inputs = group_preselected_inputs(
             &request,
@@ -238,19 +313,31 @@ impl Account {
         )?;
 
         let (utxos, selection_algo) = if input_utxos.is_empty() {
+            let dust_utxos: BTreeSet<UtxoOutPoint> = self
+                .get_dust_utxos(median_time)
+                .into_iter()
+                .map(|(outpoint, _)| outpoint)
+                .collect();
             (
                 self.get_utxos(
                     UtxoType::Transfer | UtxoType::LockThenTransfer | UtxoType::IssueNft,
                     median_time,
                     UtxoState::Confirmed | UtxoState::InMempool | UtxoState::Inactive,
                     WithLocked::Unlocked,
-                ),
+                )
+                .into_iter()
+                .filter(|(outpoint, _)| {
+                    !dust_utxos.contains(outpoint) && !self.frozen_utxos.contains(outpoint)
+                })
+                .collect(),
                 selection_algo.unwrap_or(CoinSelectionAlgo::Randomize),
             )
         } else {
             let selection_algo = selection_algo.unwrap_or(CoinSelectionAlgo::UsePreselected);
             match input_utxos {
                 SelectedInputs::Utxos(input_utxos) => {
+                    self.ensure_not_frozen(input_utxos.iter())?;
+
                     let current_block_info = BlockInfo {
                         height: self.account_info.best_block_height(),
                         timestamp: median_time,
@@ -260,16 +347,36 @@ impl Account {
                         selection_algo,
                     )
                 }
-                SelectedInputs::Inputs(ref inputs) => (
-                    inputs
-                        .iter()
-                        .map(|(outpoint, utxo)| (outpoint.clone(), (utxo, None)))
-                        .collect(),
-                    selection_algo,
-                ),
+                SelectedInputs::Inputs(ref inputs) => {
+                    let inputs = match selection_algo {
+                        CoinSelectionAlgo::UsePreselected => {
+                            self.ensure_not_frozen(inputs.iter().map(|(outpoint, _)| outpoint))?;
+                            inputs.iter().collect::<Vec<_>>()
+                        }
+                        // The inputs are only the candidates to select from
+                        CoinSelectionAlgo::Randomize => inputs
+                            .iter()
+                            .filter(|(outpoint, _)| !self.frozen_utxos.contains(outpoint))
+                            .collect(),
+                    };
+
+                    (
+                        inputs
+                            .into_iter()
+                            .map(|(outpoint, utxo)| (outpoint.clone(), (utxo, None)))
+                            .collect(),
+                        selection_algo,
+                    )
+                }
             }
         };
 
+        // The size of the change outputs depends on their destination
+        let external_change_destination = match &self.change_policy {
+            ChangePolicy::External(destination) => Some(destination.clone()),
+            ChangePolicy::NewAddress | ChangePolicy::ReuseInputAddress => None,
+        };
+
         let current_fee_rate = fee_rates.current_fee_rate;
         let mut utxos_by_currency =
             self.utxo_output_groups_by_currency(fee_rates, &pay_fee_with_currency, utxos)?;
@@ -288,7 +395,10 @@ impl Account {
 
                 let (coin_change_fee, token_change_fee) = coin_and_token_output_change_fees(
                     current_fee_rate,
-                    change_addresses.get(currency),
+                    change_addresses
+                        .get(currency)
+                        .map(Address::as_object)
+                        .or(external_change_destination.as_ref()),
                 )?;
 
                 let cost_of_change = match currency {
@@ -341,7 +451,10 @@ impl Account {
 
         let (coin_change_fee, token_change_fee) = coin_and_token_output_change_fees(
             current_fee_rate,
-            change_addresses.get(&pay_fee_with_currency),
+            change_addresses
+                .get(&pay_fee_with_currency)
+                .map(Address::as_object)
+                .or(external_change_destination.as_ref()),
         )?;
         let cost_of_change = match pay_fee_with_currency {
             currency_grouper::Currency::Coin => coin_change_fee,
@@ -393,6 +506,13 @@ impl Account {
         db_tx: &mut impl WalletStorageWriteLocked,
         mut request: SendRequest,
     ) -> Result<SendRequest, WalletError> {
+        // Find the input before any change is added, as the policy may need it
+        let reused_input_destination = match &self.change_policy {
+            ChangePolicy::ReuseInputAddress => self
+                .fee_paying_input_destination(selected_inputs.get(pay_fee_with_currency), &request),
+            ChangePolicy::NewAddress | ChangePolicy::External(_) => None,
+        };
+
         for currency in output_currency_amounts.keys() {
             let currency_result = selected_inputs.get(currency);
             let change_amount = currency_result.map_or(Amount::ZERO, |result| result.get_change());
@@ -407,7 +527,7 @@ impl Account {
                 {
                     change_address
                 } else {
-                    self.key_chain.next_unused_address(db_tx, KeyPurpose::Change)?.1
+                    self.policy_change_address(db_tx, reused_input_destination.as_ref())?
                 };
 
                 let change_output = match currency {
@@ -428,6 +548,49 @@ impl Account {
         request.with_inputs(selected_inputs, &pool_data_getter)
     }
 
+    /// Return the address for the change according to the change policy of the account.
+    /// Falls back to a new change address if there is no input address to reuse.
+    fn policy_change_address(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        reused_input_destination: Option<&Destination>,
+    ) -> WalletResult<Address<Destination>> {
+        let destination = match &self.change_policy {
+            ChangePolicy::NewAddress => None,
+            ChangePolicy::ReuseInputAddress => reused_input_destination.cloned(),
+            ChangePolicy::External(destination) => Some(destination.clone()),
+        };
+
+        match destination {
+            Some(destination) => Ok(Address::new(&self.chain_config, destination)?),
+            None => Ok(self.key_chain.next_unused_address(db_tx, KeyPurpose::Change)?.1),
+        }
+    }
+
+    /// Return the destination of the first coin input of the transaction that belongs to this
+    /// account, the preselected inputs come first
+    fn fee_paying_input_destination(
+        &self,
+        selection_result: Option<&utxo_selector::SelectionResult>,
+        request: &SendRequest,
+    ) -> Option<Destination> {
+        let preselected_utxos = request.utxos().iter().flatten();
+        let selected_utxos = selection_result
+            .into_iter()
+            .flat_map(|result| result.output_pairs())
+            .map(|(_, txo)| txo);
+
+        preselected_utxos.chain(selected_utxos).find_map(|txo| match txo {
+            TxOutput::Transfer(OutputValue::Coin(_), destination)
+            | TxOutput::LockThenTransfer(OutputValue::Coin(_), destination, _)
+                if self.is_destination_mine(destination) =>
+            {
+                Some(destination.clone())
+            }
+            _ => None,
+        })
+    }
+
     fn utxo_output_groups_by_currency(
         &self,
         fee_rates: CurrentFeeRate,
@@ -493,8 +656,22 @@ impl Account {
         request: SendRequest,
         current_fee_rate: FeeRate,
     ) -> WalletResult<SendRequest> {
+        let outputs = self.make_sweep_outputs(destination, &request, current_fee_rate)?;
+        Ok(request.with_outputs(outputs))
+    }
+
+    /// Make the outputs that transfer everything spent by the request's inputs to the destination.
+    /// The coin output is the last one, and the fee is deducted from it.
+    pub fn make_sweep_outputs(
+        &self,
+        destination: Destination,
+        request: &SendRequest,
+        current_fee_rate: FeeRate,
+    ) -> WalletResult<Vec<TxOutput>> {
+        self.ensure_not_frozen(request.inputs().iter().filter_map(TxInput::utxo_outpoint))?;
+
         let mut grouped_inputs = group_preselected_inputs(
-            &request,
+            request,
             current_fee_rate,
             &self.chain_config,
             self.account_info.best_block_height(),
@@ -547,7 +724,7 @@ impl Account {
         );
         outputs.push(coin_output);
 
-        Ok(request.with_outputs(outputs))
+        Ok(outputs)
     }
 
     pub fn sweep_delegation(
@@ -1338,10 +1515,21 @@ impl Account {
             input_utxos,
             destinations,
             None,
+            None,
         )?;
         Ok(ptx)
     }
 
+    /// Find an output of a transaction known to the account, whether it's spent or not
+    pub fn find_txo(&self, outpoint: &UtxoOutPoint) -> Option<&TxOutput> {
+        self.output_cache.get_txo(outpoint)
+    }
+
+    /// The confirmed outputs of the account that the node's UTXO set is expected to agree on
+    pub fn get_confirmed_utxos(&self) -> Vec<TrackedUtxo> {
+        self.output_cache.confirmed_utxos(|txo| self.is_mine(txo))
+    }
+
     pub fn find_unspent_utxo_with_destination(
         &self,
         outpoint: &UtxoOutPoint,
@@ -1397,6 +1585,15 @@ impl Account {
         Ok(self.key_chain.add_standalone_watch_only_address(db_tx, address, label)?)
     }
 
+    /// Stop watching a standalone address previously added with `add_standalone_address`
+    pub fn remove_standalone_address(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        address: PublicKeyHash,
+    ) -> WalletResult<()> {
+        Ok(self.key_chain.remove_standalone_watch_only_address(db_tx, address)?)
+    }
+
     /// Add a standalone private key not derived from this account's key chain to be watched
     pub fn add_standalone_private_key(
         &mut self,
@@ -1417,6 +1614,558 @@ impl Account {
         Ok(self.key_chain.add_standalone_multisig(db_tx, challenge, label)?)
     }
 
+    /// Create the descriptor of a multisig account shared by this account and the other
+    /// cosigners, to be imported into the wallets of all the cosigners
+    pub fn create_multisig_account_descriptor(
+        &self,
+        min_required_signatures: NonZeroU8,
+        mut cosigner_keys: Vec<ExtendedPublicKey>,
+    ) -> WalletResult<MultisigAccountDescriptor> {
+        let own_key = self.key_chain.account_public_key();
+        if !cosigner_keys.contains(own_key) {
+            cosigner_keys.push(own_key.clone());
+        }
+
+        Ok(MultisigAccountDescriptor::new(
+            &self.chain_config,
+            min_required_signatures,
+            cosigner_keys,
+            MultisigDerivationTemplate::KeyPurposeAndIndex,
+        )?)
+    }
+
+    /// Start watching the first `address_count` receiving addresses of a multisig account
+    /// that this account is a cosigner of
+    pub fn import_multisig_account(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        descriptor: &MultisigAccountDescriptor,
+        address_count: U31,
+        label: Option<String>,
+    ) -> WalletResult<Vec<PublicKeyHash>> {
+        descriptor.is_valid(&self.chain_config)?;
+        ensure!(
+            descriptor.has_cosigner_key(self.key_chain.account_public_key()),
+            WalletError::NotMultisigAccountCosigner
+        );
+
+        (0..address_count.into_u32())
+            .map(|index| {
+                let index = U31::from_u32(index).expect("less than address count");
+                let challenge =
+                    descriptor.challenge(&self.chain_config, KeyPurpose::ReceiveFunds, index)?;
+                self.add_standalone_multisig(db_tx, challenge, label.clone())
+            })
+            .collect()
+    }
+
+    /// Import an extended public key of an account of another wallet, usually exported with
+    /// the matching export function of that wallet.
+    /// The addresses of the first `address_count` receiving keys and the first `address_count`
+    /// change keys derived from it are added as standalone watch-only addresses and returned,
+    /// the receiving addresses first.
+    pub fn import_extended_public_key(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        extended_public_key: &ExtendedPublicKey,
+        address_count: U31,
+        label: Option<String>,
+    ) -> WalletResult<Vec<PublicKeyHash>> {
+        ensure!(
+            extended_public_key != self.key_chain.account_public_key(),
+            WalletError::CannotImportOwnExtendedPublicKey
+        );
+        ensure!(
+            address_count.into_u32() <= MAX_EXTENDED_PUBLIC_KEY_IMPORT_ADDRESS_COUNT,
+            WalletError::ExtendedPublicKeyImportAddressCountTooLarge(
+                address_count.into_u32(),
+                MAX_EXTENDED_PUBLIC_KEY_IMPORT_ADDRESS_COUNT
+            )
+        );
+
+        let mut public_key_hashes = Vec::new();
+        for purpose in KeyPurpose::ALL {
+            let purpose_key = extended_public_key
+                .clone()
+                .derive_child(purpose.get_deterministic_index())
+                .map_err(KeyChainError::Derivation)?;
+
+            for index in 0..address_count.into_u32() {
+                let public_key = purpose_key
+                    .clone()
+                    .derive_child(ChildNumber::from_normal(
+                        U31::from_u32(index).expect("less than address count"),
+                    ))
+                    .map_err(KeyChainError::Derivation)?
+                    .into_public_key();
+                let public_key_hash = PublicKeyHash::from(&public_key);
+                self.add_standalone_address(db_tx, public_key_hash, label.clone())?;
+                public_key_hashes.push(public_key_hash);
+            }
+        }
+
+        Ok(public_key_hashes)
+    }
+
+    /// Start watching a stake pool that is not controlled by this account
+    pub fn add_watched_pool(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        pool_id: PoolId,
+        label: Option<String>,
+        decommission_key: Option<Destination>,
+    ) -> WalletResult<()> {
+        ensure!(
+            !self.pool_exists(pool_id),
+            WalletError::CannotWatchOwnedPool(pool_id)
+        );
+        ensure!(
+            !self.watched_pools.contains_key(&pool_id),
+            WalletError::PoolAlreadyWatched(pool_id)
+        );
+
+        let watched_pool = WatchedPool::new(label, decommission_key);
+        db_tx.set_watched_pool(
+            &AccountPoolId::new(self.get_account_id(), pool_id),
+            &watched_pool,
+        )?;
+        self.watched_pools.insert(pool_id, watched_pool);
+        Ok(())
+    }
+
+    /// Stop watching a stake pool
+    pub fn remove_watched_pool(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        pool_id: PoolId,
+    ) -> WalletResult<()> {
+        ensure!(
+            self.watched_pools.remove(&pool_id).is_some(),
+            WalletError::UnknownWatchedPoolId(pool_id)
+        );
+        db_tx.del_watched_pool(&AccountPoolId::new(self.get_account_id(), pool_id))?;
+        Ok(())
+    }
+
+    pub fn get_watched_pools(&self) -> &BTreeMap<PoolId, WatchedPool> {
+        &self.watched_pools
+    }
+
+    /// Add a new recurring payment and return its id
+    pub fn add_scheduled_payment(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        payment: ScheduledPayment,
+    ) -> WalletResult<u64> {
+        ensure!(
+            payment.interval_secs > 0,
+            WalletError::InvalidScheduledPaymentInterval
+        );
+        ensure!(
+            payment.amount != Amount::ZERO,
+            WalletError::InvalidScheduledPaymentAmount
+        );
+
+        let account_id = self.get_account_id();
+        // The counter is not stored for the accounts whose payments were added before it existed
+        let payment_id = match db_tx.get_account_next_scheduled_payment_id(&account_id)? {
+            Some(payment_id) => payment_id,
+            None => self
+                .scheduled_payments
+                .last_key_value()
+                .map_or(0, |(payment_id, _)| payment_id + 1),
+        };
+        db_tx.set_scheduled_payment(
+            &AccountScheduledPaymentId::new(account_id.clone(), payment_id),
+            &payment,
+        )?;
+        db_tx.set_account_next_scheduled_payment_id(&account_id, payment_id + 1)?;
+        self.scheduled_payments.insert(payment_id, payment);
+        Ok(payment_id)
+    }
+
+    /// Store the new state of a recurring payment after an attempt to execute it
+    pub fn update_scheduled_payment(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        payment_id: u64,
+        payment: ScheduledPayment,
+    ) -> WalletResult<()> {
+        let existing = self
+            .scheduled_payments
+            .get_mut(&payment_id)
+            .ok_or(WalletError::UnknownScheduledPaymentId(payment_id))?;
+        db_tx.set_scheduled_payment(
+            &AccountScheduledPaymentId::new(self.key_chain.get_account_id(), payment_id),
+            &payment,
+        )?;
+        *existing = payment;
+        Ok(())
+    }
+
+    /// Cancel a recurring payment
+    pub fn cancel_scheduled_payment(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        payment_id: u64,
+    ) -> WalletResult<()> {
+        ensure!(
+            self.scheduled_payments.remove(&payment_id).is_some(),
+            WalletError::UnknownScheduledPaymentId(payment_id)
+        );
+        db_tx.del_scheduled_payment(&AccountScheduledPaymentId::new(
+            self.get_account_id(),
+            payment_id,
+        ))?;
+        Ok(())
+    }
+
+    pub fn get_scheduled_payments(&self) -> &BTreeMap<u64, ScheduledPayment> {
+        &self.scheduled_payments
+    }
+
+    pub fn get_dust_protection(&self) -> &DustProtection {
+        &self.dust_protection
+    }
+
+    /// Set the dust protection policy, the UTXOs that were released before stay released
+    pub fn set_dust_protection_policy(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        min_coin_amount: Option<Amount>,
+        flag_unknown_tokens: bool,
+    ) -> WalletResult<()> {
+        let mut dust_protection = self.dust_protection.clone();
+        dust_protection.min_coin_amount = min_coin_amount;
+        dust_protection.flag_unknown_tokens = flag_unknown_tokens;
+        db_tx.set_account_dust_protection(&self.get_account_id(), &dust_protection)?;
+        self.dust_protection = dust_protection;
+        Ok(())
+    }
+
+    /// Allow the flagged UTXOs to be selected automatically again
+    pub fn release_dust_utxos(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        utxos: Vec<UtxoOutPoint>,
+        median_time: BlockTimestamp,
+    ) -> WalletResult<()> {
+        let dust_utxos: BTreeSet<UtxoOutPoint> = self
+            .get_dust_utxos(median_time)
+            .into_iter()
+            .map(|(outpoint, _)| outpoint)
+            .collect();
+        for utxo in &utxos {
+            ensure!(
+                dust_utxos.contains(utxo),
+                WalletError::UtxoNotFlaggedAsDust(utxo.clone())
+            );
+        }
+
+        let mut dust_protection = self.dust_protection.clone();
+        dust_protection.released_utxos.extend(utxos);
+        db_tx.set_account_dust_protection(&self.get_account_id(), &dust_protection)?;
+        self.dust_protection = dust_protection;
+        Ok(())
+    }
+
+    pub fn get_change_policy(&self) -> &ChangePolicy {
+        &self.change_policy
+    }
+
+    pub fn set_change_policy(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        change_policy: ChangePolicy,
+    ) -> WalletResult<()> {
+        db_tx.set_account_change_policy(&self.get_account_id(), &change_policy)?;
+        self.change_policy = change_policy;
+        Ok(())
+    }
+
+    /// Return the unspent UTXOs that are frozen by the user
+    pub fn get_frozen_utxos(
+        &self,
+        median_time: BlockTimestamp,
+    ) -> Vec<(UtxoOutPoint, (&TxOutput, Option<TokenId>))> {
+        if self.frozen_utxos.is_empty() {
+            return Vec::new();
+        }
+
+        self.get_utxos(
+            UtxoType::Transfer | UtxoType::LockThenTransfer | UtxoType::IssueNft,
+            median_time,
+            UtxoState::Confirmed | UtxoState::InMempool | UtxoState::Inactive,
+            WithLocked::Any,
+        )
+        .into_iter()
+        .filter(|(outpoint, _)| self.frozen_utxos.contains(outpoint))
+        .collect()
+    }
+
+    /// Mark the UTXOs as "do not spend", so they are neither selected automatically
+    /// nor accepted as explicitly selected inputs until they are unfrozen
+    pub fn freeze_utxos(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        utxos: Vec<UtxoOutPoint>,
+        median_time: BlockTimestamp,
+    ) -> WalletResult<()> {
+        let own_utxos: BTreeSet<UtxoOutPoint> = self
+            .get_utxos(
+                UtxoType::Transfer | UtxoType::LockThenTransfer | UtxoType::IssueNft,
+                median_time,
+                UtxoState::Confirmed | UtxoState::InMempool | UtxoState::Inactive,
+                WithLocked::Any,
+            )
+            .into_iter()
+            .map(|(outpoint, _)| outpoint)
+            .collect();
+        for utxo in &utxos {
+            ensure!(
+                own_utxos.contains(utxo),
+                WalletError::CannotFindUtxo(utxo.clone())
+            );
+        }
+
+        let mut frozen_utxos = self.frozen_utxos.clone();
+        frozen_utxos.extend(utxos);
+        db_tx.set_account_frozen_utxos(&self.get_account_id(), &frozen_utxos)?;
+        self.frozen_utxos = frozen_utxos;
+        Ok(())
+    }
+
+    /// Fail if any of the UTXOs is frozen; used wherever inputs are selected explicitly
+    fn ensure_not_frozen<'a>(
+        &self,
+        mut utxos: impl Iterator<Item = &'a UtxoOutPoint>,
+    ) -> WalletResult<()> {
+        match utxos.find(|utxo| self.frozen_utxos.contains(utxo)) {
+            Some(frozen_utxo) => Err(WalletError::FrozenUtxo(frozen_utxo.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Drop the frozen UTXOs from the candidates of a transaction that spends everything
+    /// it is given, e.g. a sweep
+    pub fn retain_unfrozen_utxos(
+        &self,
+        mut utxos: Vec<(UtxoOutPoint, TxOutput, Option<TokenId>)>,
+    ) -> Vec<(UtxoOutPoint, TxOutput, Option<TokenId>)> {
+        utxos.retain(|(outpoint, _, _)| !self.frozen_utxos.contains(outpoint));
+        utxos
+    }
+
+    /// Forget the frozen UTXOs spent by the transactions of the block, e.g. by another
+    /// instance of the wallet, so the persisted set doesn't grow forever
+    fn prune_spent_frozen_utxos(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        block: &Block,
+    ) -> WalletResult<()> {
+        if self.frozen_utxos.is_empty() {
+            return Ok(());
+        }
+
+        let mut frozen_utxos = self.frozen_utxos.clone();
+        let mut pruned = false;
+        for outpoint in block
+            .transactions()
+            .iter()
+            .flat_map(|tx| tx.transaction().inputs())
+            .filter_map(TxInput::utxo_outpoint)
+        {
+            pruned |= frozen_utxos.remove(outpoint);
+        }
+
+        if pruned {
+            db_tx.set_account_frozen_utxos(&self.get_account_id(), &frozen_utxos)?;
+            self.frozen_utxos = frozen_utxos;
+        }
+        Ok(())
+    }
+
+    /// Allow the frozen UTXOs to be spent again
+    pub fn unfreeze_utxos(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        utxos: Vec<UtxoOutPoint>,
+    ) -> WalletResult<()> {
+        let mut frozen_utxos = self.frozen_utxos.clone();
+        for utxo in &utxos {
+            ensure!(
+                frozen_utxos.remove(utxo),
+                WalletError::UtxoNotFrozen(utxo.clone())
+            );
+        }
+
+        db_tx.set_account_frozen_utxos(&self.get_account_id(), &frozen_utxos)?;
+        self.frozen_utxos = frozen_utxos;
+        Ok(())
+    }
+
+    /// Return the unspent UTXOs that are excluded from the automatic input selection
+    /// by the dust protection policy
+    pub fn get_dust_utxos(
+        &self,
+        median_time: BlockTimestamp,
+    ) -> Vec<(UtxoOutPoint, (&TxOutput, Option<TokenId>))> {
+        if !self.dust_protection.is_enabled() {
+            return Vec::new();
+        }
+
+        let known_tokens = if self.dust_protection.flag_unknown_tokens {
+            self.sent_token_ids()
+        } else {
+            BTreeSet::new()
+        };
+
+        self.get_utxos(
+            UtxoType::Transfer | UtxoType::LockThenTransfer,
+            median_time,
+            UtxoState::Confirmed | UtxoState::InMempool | UtxoState::Inactive,
+            WithLocked::Any,
+        )
+        .into_iter()
+        .filter(|(outpoint, (txo, _))| self.is_dust_utxo(outpoint, txo, &known_tokens))
+        .collect()
+    }
+
+    /// Check if the UTXO was received from someone else and matches the dust protection policy
+    fn is_dust_utxo(
+        &self,
+        outpoint: &UtxoOutPoint,
+        txo: &TxOutput,
+        known_tokens: &BTreeSet<TokenId>,
+    ) -> bool {
+        if self.dust_protection.released_utxos.contains(outpoint) {
+            return false;
+        }
+
+        let is_incoming = match self.output_cache.txs_with_unconfirmed().get(&outpoint.source_id())
+        {
+            Some(WalletTx::Tx(tx_data)) => !self.has_own_inputs(tx_data.get_transaction()),
+            Some(WalletTx::Block(_)) | None => false,
+        };
+        if !is_incoming {
+            return false;
+        }
+
+        let value = match txo {
+            TxOutput::Transfer(value, _)
+            | TxOutput::LockThenTransfer(value, _, _)
+            | TxOutput::Htlc(value, _) => value,
+            TxOutput::Burn(_)
+            | TxOutput::CreateStakePool(_, _)
+            | TxOutput::ProduceBlockFromStake(_, _)
+            | TxOutput::CreateDelegationId(_, _)
+            | TxOutput::DelegateStaking(_, _)
+            | TxOutput::IssueFungibleToken(_)
+            | TxOutput::IssueNft(_, _, _)
+            | TxOutput::DataDeposit(_)
+            | TxOutput::AnyoneCanTake(_) => return false,
+        };
+
+        match value {
+            OutputValue::Coin(amount) => self
+                .dust_protection
+                .min_coin_amount
+                .is_some_and(|min_coin_amount| *amount < min_coin_amount),
+            OutputValue::TokenV1(token_id, _) => {
+                self.dust_protection.flag_unknown_tokens && !known_tokens.contains(token_id)
+            }
+            OutputValue::TokenV0(_) => false,
+        }
+    }
+
+    /// Return true if any of the transaction inputs is controlled by this account
+    fn has_own_inputs(&self, tx: &Transaction) -> bool {
+        tx.inputs().iter().any(|input| match input {
+            TxInput::Utxo(outpoint) => {
+                self.output_cache.get_txo(outpoint).is_some_and(|txo| self.is_mine(txo))
+            }
+            TxInput::Account(outpoint) => match outpoint.account() {
+                AccountSpending::DelegationBalance(delegation_id, _) => {
+                    self.find_delegation(delegation_id).is_ok()
+                }
+            },
+            TxInput::AccountCommand(_, command) => match command {
+                AccountCommand::MintTokens(token_id, _)
+                | AccountCommand::UnmintTokens(token_id)
+                | AccountCommand::LockTokenSupply(token_id)
+                | AccountCommand::FreezeToken(token_id, _)
+                | AccountCommand::UnfreezeToken(token_id)
+                | AccountCommand::ChangeTokenAuthority(token_id, _)
+                | AccountCommand::ChangeTokenMetadataUri(token_id, _) => {
+                    self.find_token(token_id).is_ok()
+                }
+                // TODO(orders)
+                AccountCommand::ConcludeOrder(_) | AccountCommand::FillOrder(_, _, _) => false,
+            },
+        })
+    }
+
+    /// The tokens that this account has issued or sent in its own transactions
+    pub fn known_token_ids(&self) -> BTreeSet<TokenId> {
+        let mut token_ids = self.sent_token_ids();
+        token_ids.extend(self.output_cache.token_ids().copied());
+        token_ids
+    }
+
+    /// The tokens that this account has sent in its own transactions
+    fn sent_token_ids(&self) -> BTreeSet<TokenId> {
+        self.output_cache
+            .txs_with_unconfirmed()
+            .values()
+            .filter_map(|tx| match tx {
+                WalletTx::Tx(tx_data) => Some(tx_data.get_transaction()),
+                WalletTx::Block(_) => None,
+            })
+            .filter(|tx| self.has_own_inputs(tx))
+            .flat_map(|tx| tx.outputs())
+            .filter_map(|txo| match txo {
+                TxOutput::Transfer(value, _)
+                | TxOutput::LockThenTransfer(value, _, _)
+                | TxOutput::Htlc(value, _) => match value {
+                    OutputValue::TokenV1(token_id, _) => Some(*token_id),
+                    OutputValue::Coin(_) | OutputValue::TokenV0(_) => None,
+                },
+                TxOutput::Burn(_)
+                | TxOutput::CreateStakePool(_, _)
+                | TxOutput::ProduceBlockFromStake(_, _)
+                | TxOutput::CreateDelegationId(_, _)
+                | TxOutput::DelegateStaking(_, _)
+                | TxOutput::IssueFungibleToken(_)
+                | TxOutput::IssueNft(_, _, _)
+                | TxOutput::DataDeposit(_)
+                | TxOutput::AnyoneCanTake(_) => None,
+            })
+            .collect()
+    }
+
+    /// Record the block as created by a watched pool, if it was staked by one
+    fn update_watched_pools(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        block: &Block,
+        block_height: BlockHeight,
+    ) -> WalletResult<()> {
+        let pool_id = match block.consensus_data() {
+            ConsensusData::PoS(pos_data) => *pos_data.stake_pool_id(),
+            ConsensusData::None | ConsensusData::PoW(_) => return Ok(()),
+        };
+
+        if let Some(watched_pool) = self.watched_pools.get_mut(&pool_id) {
+            watched_pool.add_created_block(block_height, block.get_id().into());
+            db_tx.set_watched_pool(
+                &AccountPoolId::new(self.key_chain.get_account_id(), pool_id),
+                watched_pool,
+            )?;
+        }
+
+        Ok(())
+    }
+
     /// Get a new address that hasn't been used before
     pub fn get_new_address(
         &mut self,
@@ -1456,6 +2205,46 @@ impl Account {
         self.key_chain.get_all_issued_addresses()
     }
 
+    /// Set the label of an address issued by this account's key chain,
+    /// or remove the existing one if the label is None
+    pub fn set_address_label(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        address: Destination,
+        label: Option<String>,
+    ) -> WalletResult<()> {
+        let is_issued = self
+            .key_chain
+            .get_all_issued_addresses()
+            .values()
+            .any(|issued_address| *issued_address.as_object() == address);
+        ensure!(
+            is_issued,
+            WalletError::AddressNotIssued(
+                RpcAddress::new(&self.chain_config, address).expect("addressable")
+            )
+        );
+
+        let id = AccountPrefixedId::new(self.get_account_id(), address.clone());
+        match label {
+            Some(label) => {
+                ensure!(!label.is_empty(), WalletError::EmptyAddressLabel);
+                db_tx.set_address_label(&id, &label)?;
+                self.address_labels.insert(address, label);
+            }
+            None => {
+                db_tx.del_address_label(&id)?;
+                self.address_labels.remove(&address);
+            }
+        }
+        Ok(())
+    }
+
+    /// The labels of the addresses issued by this account's key chain
+    pub fn get_address_labels(&self) -> &BTreeMap<Destination, String> {
+        &self.address_labels
+    }
+
     pub fn get_all_standalone_addresses(&self) -> StandaloneAddresses {
         self.key_chain.get_all_standalone_addresses()
     }
@@ -1501,6 +2290,38 @@ impl Account {
         Ok((address, amounts_by_currency, standalone_key))
     }
 
+    /// Return the utxos of a standalone address, which are not included in the account's own utxos
+    pub fn get_standalone_address_utxos(
+        &self,
+        address: Destination,
+        utxo_types: UtxoTypes,
+        median_time: BlockTimestamp,
+        utxo_states: UtxoStates,
+        with_locked: WithLocked,
+    ) -> WalletResult<Vec<(UtxoOutPoint, (&TxOutput, Option<TokenId>))>> {
+        let (address, _) = self
+            .key_chain
+            .get_all_standalone_address_details(address.clone())
+            .ok_or_else(|| {
+                let addr = RpcAddress::new(&self.chain_config, address).expect("addressable");
+                WalletError::StandaloneAddressNotFound(addr)
+            })?;
+
+        let current_block_info = BlockInfo {
+            height: self.account_info.best_block_height(),
+            timestamp: median_time,
+        };
+        Ok(self.output_cache.utxos_with_token_ids(
+            current_block_info,
+            utxo_states,
+            with_locked,
+            |txo| {
+                self.is_watched_by(txo, &address)
+                    && get_utxo_type(txo).is_some_and(|v| utxo_types.contains(v))
+            },
+        ))
+    }
+
     pub fn get_all_issued_vrf_public_keys(
         &self,
     ) -> BTreeMap<ChildNumber, (Address<VRFPublicKey>, bool)> {
@@ -1576,7 +2397,7 @@ impl Account {
     }
 
     /// Return true if this destination can be spent by this account
-    fn is_destination_mine(&self, destination: &Destination) -> bool {
+    pub fn is_destination_mine(&self, destination: &Destination) -> bool {
         match destination {
             Destination::PublicKeyHash(pkh) => self.key_chain.is_public_key_hash_mine(pkh),
             Destination::PublicKey(pk) => self.key_chain.is_public_key_mine(pk),
@@ -1736,14 +2557,50 @@ impl Account {
         )
     }
 
-    pub fn get_transaction_list(&self, skip: usize, count: usize) -> WalletResult<TransactionList> {
-        get_transaction_list(&self.key_chain, &self.output_cache, skip, count)
+    pub fn get_transaction_list(
+        &self,
+        skip: usize,
+        count: usize,
+        internal_transfers: &BTreeMap<Id<Transaction>, InternalTransfer>,
+    ) -> WalletResult<TransactionList> {
+        get_transaction_list(
+            &self.key_chain,
+            &self.output_cache,
+            &self.address_labels,
+            internal_transfers,
+            skip,
+            count,
+        )
+    }
+
+    pub fn get_transaction_history(
+        &self,
+        internal_transfers: &BTreeMap<Id<Transaction>, InternalTransfer>,
+    ) -> WalletResult<Vec<TransactionInfo>> {
+        get_transaction_history(
+            &self.key_chain,
+            &self.output_cache,
+            &self.address_labels,
+            internal_transfers,
+        )
     }
 
     pub fn get_transaction(&self, transaction_id: Id<Transaction>) -> WalletResult<&TxData> {
         self.output_cache.get_transaction(transaction_id)
     }
 
+    pub fn describe_transaction(
+        &self,
+        transaction_id: Id<Transaction>,
+    ) -> WalletResult<Vec<TxDescriptionItem>> {
+        let tx_data = self.output_cache.get_transaction(transaction_id)?;
+        Ok(describe_transaction(
+            &self.key_chain,
+            &self.output_cache,
+            tx_data.get_transaction(),
+        ))
+    }
+
     pub fn reset_to_height<B: storage::Backend>(
         &mut self,
         db_tx: &mut StoreTxRw<B>,
@@ -1782,6 +2639,15 @@ impl Account {
             wallet_events.del_transaction(self.account_index(), source);
         }
 
+        let account_id = self.get_account_id();
+        for (pool_id, watched_pool) in self.watched_pools.iter_mut() {
+            watched_pool.remove_created_blocks_above(common_block_height);
+            db_tx.set_watched_pool(
+                &AccountPoolId::new(account_id.clone(), *pool_id),
+                watched_pool,
+            )?;
+        }
+
         Ok(())
     }
 
@@ -1877,6 +2743,9 @@ impl Account {
                 new_tx_was_added |=
                     self.add_wallet_tx_if_relevant(db_tx, wallet_events, wallet_tx)?;
 
+                self.update_watched_pools(db_tx, block, block_height)?;
+                self.prune_spent_frozen_utxos(db_tx, block)?;
+
                 block.transactions().iter().enumerate().try_fold(
                     new_tx_was_added,
                     |mut new_tx_was_added, (idx, signed_tx)| {
@@ -1885,13 +2754,8 @@ impl Account {
                         let wallet_tx = WalletTx::Tx(TxData::new(signed_tx.clone(), tx_state));
                         self.update_conflicting_txs(&wallet_tx, block, db_tx)?;
 
-                        new_tx_was_added |= self
-                            .add_wallet_tx_if_relevant_and_remove_from_user_txs(
-                                db_tx,
-                                wallet_events,
-                                wallet_tx,
-                                signed_tx.transaction().get_id(),
-                            )?;
+                        new_tx_was_added |=
+                            self.add_wallet_tx_if_relevant(db_tx, wallet_events, wallet_tx)?;
                         Ok(new_tx_was_added)
                     },
                 )
@@ -1925,26 +2789,6 @@ impl Account {
         Ok(())
     }
 
-    /// Add a new wallet tx if relevant for this account and remove it from the user transactions
-    /// to not be rebroadcast again
-    fn add_wallet_tx_if_relevant_and_remove_from_user_txs(
-        &mut self,
-        db_tx: &mut impl WalletStorageWriteLocked,
-        wallet_events: &impl WalletEvents,
-        wallet_tx: WalletTx,
-        tx_id: Id<Transaction>,
-    ) -> Result<bool, WalletError> {
-        Ok(
-            if self.add_wallet_tx_if_relevant(db_tx, wallet_events, wallet_tx)? {
-                let id = AccountWalletCreatedTxId::new(self.get_account_id(), tx_id);
-                db_tx.del_user_transaction(&id)?;
-                true
-            } else {
-                false
-            },
-        )
-    }
-
     pub fn update_best_block(
         &mut self,
         db_tx: &mut impl WalletStorageWriteLocked,
@@ -2004,10 +2848,6 @@ impl Account {
 
             if !self.add_wallet_tx_if_relevant(db_tx, wallet_events, wallet_tx)? {
                 not_added.push((signed_tx, tx_state));
-            } else {
-                let id =
-                    AccountPrefixedId::new(account_id.clone(), signed_tx.transaction().get_id());
-                db_tx.set_user_transaction(&id, signed_tx)?;
             }
         }
 
@@ -2021,12 +2861,6 @@ impl Account {
 
                 if !self.add_wallet_tx_if_relevant(db_tx, wallet_events, wallet_tx)? {
                     not_added_next.push((signed_tx, tx_state));
-                } else {
-                    let id = AccountPrefixedId::new(
-                        account_id.clone(),
-                        signed_tx.transaction().get_id(),
-                    );
-                    db_tx.set_user_transaction(&id, signed_tx)?;
                 }
             }
 
@@ -2068,7 +2902,36 @@ impl Account {
         destination: Option<Destination>,
         limit: usize,
     ) -> Vec<TxInfo> {
-        self.output_cache.mainchain_transactions(destination, limit)
+        let txs = self.output_cache.mainchain_transactions(destination, limit);
+        self.with_labels(txs)
+    }
+
+    pub fn mainchain_transactions_page(
+        &self,
+        destination: Option<Destination>,
+        after: Option<TxChainPosition>,
+        newest_first: bool,
+        limit: usize,
+    ) -> Vec<TxInfo> {
+        let txs =
+            self.output_cache
+                .mainchain_transactions_page(destination, after, newest_first, limit);
+        self.with_labels(txs)
+    }
+
+    /// Fill in the labels of the account's addresses the transactions spend from or pay to
+    fn with_labels(&self, mut txs: Vec<TxInfo>) -> Vec<TxInfo> {
+        for tx in &mut txs {
+            if let Ok(tx_data) = self.output_cache.get_transaction(tx.id) {
+                tx.labels = get_transaction_labels(
+                    &self.key_chain,
+                    &self.output_cache,
+                    &self.address_labels,
+                    tx_data.get_transaction(),
+                );
+            }
+        }
+        txs
     }
 
     pub fn abandon_transaction(
@@ -2080,13 +2943,224 @@ impl Account {
         let acc_id = self.get_account_id();
 
         for tx_id in abandoned_txs {
-            let id = AccountWalletCreatedTxId::new(acc_id.clone(), tx_id);
-            db_tx.del_user_transaction(&id)?;
+            let wallet_tx = WalletTx::Tx(self.output_cache.get_transaction(tx_id)?.clone());
+            db_tx.set_transaction(&AccountWalletTxId::new(acc_id.clone(), tx_id), &wallet_tx)?;
         }
 
         Ok(())
     }
 
+    /// Mark a transaction that has been replaced by a fee bump and its descendants as conflicted
+    pub fn mark_transaction_replaced(
+        &mut self,
+        tx_id: Id<Transaction>,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        wallet_events: &impl WalletEvents,
+    ) -> WalletResult<()> {
+        let replaced_txs = self
+            .output_cache
+            .mark_transaction_replaced(tx_id, self.account_info.best_block_id())?;
+        let acc_id = self.get_account_id();
+
+        for tx_id in replaced_txs {
+            let wallet_tx = self.output_cache.get_transaction(tx_id)?;
+            let wallet_tx = WalletTx::Tx(wallet_tx.clone());
+            db_tx.set_transaction(&AccountWalletTxId::new(acc_id.clone(), tx_id), &wallet_tx)?;
+            wallet_events.set_transaction(self.account_index(), &wallet_tx);
+        }
+
+        Ok(())
+    }
+
+    /// Create a transaction that makes an unconfirmed transaction pay at least the new fee rate.
+    ///
+    /// If replacement is allowed, the transaction signals replaceability and all its inputs
+    /// belong to the account, it's replaced by a transaction spending the same inputs, with
+    /// the additional fee taken from its coin change. Otherwise, a child transaction spends
+    /// the outputs of the original one that belong to the account and pays the fee missing
+    /// for both of them.
+    ///
+    /// A replacement pays at least the new fee rate, and at least the fees of the original
+    /// transaction and its descendants plus the relay fee for its own size, as required by
+    /// the mempool replacement rules.
+    pub fn create_fee_bump_tx(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        tx_id: Id<Transaction>,
+        new_fee_rate: FeeRate,
+        min_relay_fee_rate: FeeRate,
+        allow_replacement: bool,
+        median_time: BlockTimestamp,
+    ) -> WalletResult<(SendRequest, FeeBumpKind)> {
+        let tx_data = self.output_cache.get_transaction(tx_id)?;
+        match tx_data.state() {
+            TxState::Inactive(_) | TxState::InMempool(_) => {}
+            TxState::Confirmed(_, _, _) | TxState::Conflicted(_) | TxState::Abandoned => {
+                return Err(WalletError::CannotBumpTransactionFee(*tx_data.state()));
+            }
+        }
+
+        let tx = tx_data.get_transaction().clone();
+        let tx_size = serialization::Encode::encoded_size(tx_data.get_signed_transaction());
+        let tx_fee = self.find_tx_fee(&tx);
+        let required_fee: Amount = new_fee_rate
+            .compute_fee(tx_size)
+            .map_err(|_| UtxoSelectorError::AmountArithmeticError)?
+            .into();
+        if let Some(tx_fee) = tx_fee {
+            ensure!(
+                required_fee > tx_fee,
+                WalletError::FeeBumpNotHigher(tx_fee, required_fee)
+            );
+        }
+
+        let replaced_input_utxos = (allow_replacement && tx.is_replaceable())
+            .then(|| self.find_own_input_utxos(&tx))
+            .flatten()
+            .zip(tx_fee)
+            .zip(self.find_descendants_fee(tx_id));
+
+        if let Some(((input_utxos, tx_fee), descendants_fee)) = replaced_input_utxos {
+            let min_relay_fee: Amount = min_relay_fee_rate
+                .compute_fee(tx_size)
+                .map_err(|_| UtxoSelectorError::AmountArithmeticError)?
+                .into();
+            let replaced_fees = (tx_fee + descendants_fee)
+                .and_then(|fee| fee + min_relay_fee)
+                .ok_or(UtxoSelectorError::AmountArithmeticError)?;
+            let replacement_fee = std::cmp::max(required_fee, replaced_fees);
+            let extra_fee = (replacement_fee - tx_fee).expect("checked above");
+
+            // The additional fee is taken from the last coin output that belongs to the account
+            let mut outputs = tx.outputs().to_vec();
+            let (change_index, change_amount, change_destination) = outputs
+                .iter()
+                .enumerate()
+                .rev()
+                .find_map(|(index, output)| match output {
+                    TxOutput::Transfer(OutputValue::Coin(amount), destination)
+                        if self.is_mine(output) =>
+                    {
+                        Some((index, *amount, destination.clone()))
+                    }
+                    _ => None,
+                })
+                .ok_or(WalletError::NoOutputsToBumpFee(tx_id))?;
+            outputs[change_index] = TxOutput::Transfer(
+                OutputValue::Coin(
+                    (change_amount - extra_fee)
+                        .ok_or(WalletError::NotEnoughUtxo(change_amount, extra_fee))?,
+                ),
+                change_destination,
+            );
+
+            let replacement = Transaction::new(tx.flags(), tx.inputs().to_vec(), outputs)?;
+            let pool_data_getter = |pool_id: &PoolId| self.output_cache.pool_data(*pool_id).ok();
+            let request =
+                SendRequest::from_transaction(replacement, input_utxos, &pool_data_getter)?;
+
+            return Ok((request, FeeBumpKind::ReplaceByFee));
+        }
+
+        let current_block_info = BlockInfo {
+            height: self.account_info.best_block_height(),
+            timestamp: median_time,
+        };
+        let own_outputs = tx
+            .outputs()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, output)| {
+                let outpoint = UtxoOutPoint::new(tx_id.into(), index as u32);
+                let spendable = matches!(output, TxOutput::Transfer(_, _))
+                    && self.is_mine(output)
+                    && self
+                        .output_cache
+                        .find_unspent_unlocked_utxo(&outpoint, current_block_info)
+                        .is_ok();
+                spendable.then(|| (TxInput::Utxo(outpoint), output.clone()))
+            })
+            .collect_vec();
+        ensure!(
+            !own_outputs.is_empty(),
+            WalletError::NoOutputsToBumpFee(tx_id)
+        );
+
+        let pool_data_getter = |pool_id: &PoolId| self.output_cache.pool_data(*pool_id).ok();
+        let request = SendRequest::new().with_inputs(own_outputs, &pool_data_getter)?;
+        let destination =
+            self.policy_change_address(db_tx, request.destinations().first())?.into_object();
+
+        // The child pays for its own size with the new fee rate, and additionally for whatever
+        // the parent lacks; if the fee of the parent is unknown, it pays for the whole parent.
+        let missing_parent_fee =
+            (required_fee - tx_fee.unwrap_or(Amount::ZERO)).unwrap_or(Amount::ZERO);
+        let mut outputs = self.make_sweep_outputs(destination, &request, new_fee_rate)?;
+        if let Some(TxOutput::Transfer(OutputValue::Coin(amount), _)) = outputs.last_mut() {
+            *amount = (*amount - missing_parent_fee)
+                .ok_or(WalletError::NotEnoughUtxo(*amount, missing_parent_fee))?;
+        }
+
+        Ok((
+            request.with_outputs(outputs),
+            FeeBumpKind::ChildPaysForParent,
+        ))
+    }
+
+    /// The coin fee paid by the transaction, if all its inputs are known to the account
+    /// and all its inputs and outputs only transfer coins or tokens
+    fn find_tx_fee(&self, tx: &Transaction) -> Option<Amount> {
+        let inputs_amount = tx
+            .inputs()
+            .iter()
+            .map(|input| match input {
+                TxInput::Utxo(outpoint) => {
+                    self.output_cache.get_txo(outpoint).and_then(transferred_coins)
+                }
+                TxInput::Account(_) | TxInput::AccountCommand(_, _) => None,
+            })
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .sum::<Option<Amount>>()?;
+        let outputs_amount = tx
+            .outputs()
+            .iter()
+            .map(transferred_coins)
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .sum::<Option<Amount>>()?;
+
+        inputs_amount - outputs_amount
+    }
+
+    /// The total coin fee paid by the unconfirmed descendants of the transaction,
+    /// if the fees of all of them are known
+    fn find_descendants_fee(&self, tx_id: Id<Transaction>) -> Option<Amount> {
+        self.output_cache
+            .unconfirmed_descendants(tx_id)
+            .into_iter()
+            .map(|descendant_id| {
+                let descendant = self.output_cache.get_transaction(descendant_id).ok()?;
+                self.find_tx_fee(descendant.get_transaction())
+            })
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .sum::<Option<Amount>>()
+    }
+
+    /// The outputs spent by the transaction, if all its inputs are UTXOs of the account
+    fn find_own_input_utxos(&self, tx: &Transaction) -> Option<Vec<TxOutput>> {
+        tx.inputs()
+            .iter()
+            .map(|input| match input {
+                TxInput::Utxo(outpoint) => {
+                    self.output_cache.get_txo(outpoint).filter(|txo| self.is_mine(txo)).cloned()
+                }
+                TxInput::Account(_) | TxInput::AccountCommand(_, _) => None,
+            })
+            .collect()
+    }
+
     pub fn set_name(
         &mut self,
         name: Option<String>,
@@ -2102,6 +3176,14 @@ impl Account {
             .get_created_blocks(|destination| self.is_destination_mine(destination))
     }
 
+    pub fn get_staked_blocks(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Vec<StakedBlock> {
+        self.output_cache.staked_blocks(from_height, to_height)
+    }
+
     pub fn top_up_addresses(
         &mut self,
         db_tx: &mut impl WalletStorageWriteLocked,
@@ -2256,14 +3338,33 @@ fn group_preselected_inputs(
     Ok(preselected_inputs)
 }
 
+/// The coins transferred by the output, None for the kinds of outputs that do more than
+/// transferring coins or tokens
+fn transferred_coins(output: &TxOutput) -> Option<Amount> {
+    match output {
+        TxOutput::Transfer(value, _)
+        | TxOutput::LockThenTransfer(value, _, _)
+        | TxOutput::Burn(value)
+        | TxOutput::Htlc(value, _) => Some(value.coin_amount().unwrap_or(Amount::ZERO)),
+        TxOutput::CreateStakePool(_, _)
+        | TxOutput::ProduceBlockFromStake(_, _)
+        | TxOutput::CreateDelegationId(_, _)
+        | TxOutput::DelegateStaking(_, _)
+        | TxOutput::IssueFungibleToken(_)
+        | TxOutput::IssueNft(_, _, _)
+        | TxOutput::DataDeposit(_)
+        | TxOutput::AnyoneCanTake(_) => None,
+    }
+}
+
 /// Calculate the amount of fee that needs to be paid to add a change output
 /// Returns the Amounts for Coin output and Token output
 fn coin_and_token_output_change_fees(
     feerate: mempool::FeeRate,
-    destination: Option<&Address<Destination>>,
+    destination: Option<&Destination>,
 ) -> WalletResult<(Amount, Amount)> {
-    let destination = if let Some(addr) = destination {
-        addr.as_object().clone()
+    let destination = if let Some(destination) = destination {
+        destination.clone()
     } else {
         let pub_key_hash = PublicKeyHash::from_low_u64_ne(0);
         Destination::PublicKeyHash(pub_key_hash)
@@ -29,8 +29,8 @@ use common::{
             RPCFungibleTokenInfo, RPCIsTokenFrozen, RPCTokenTotalSupply, TokenId, TokenIssuance,
             TokenTotalSupply,
         },
-        AccountCommand, AccountNonce, AccountSpending, DelegationId, Destination, GenBlock,
-        OutPointSourceId, PoolId, Transaction, TxInput, TxOutput, UtxoOutPoint,
+        AccountCommand, AccountNonce, AccountSpending, ChainConfig, DelegationId, Destination,
+        GenBlock, OutPointSourceId, PoolId, Transaction, TxInput, TxOutput, UtxoOutPoint,
     },
     primitives::{id::WithId, per_thousand::PerThousand, Amount, BlockHeight, Id, Idable},
 };
@@ -41,7 +41,7 @@ use rpc_description::HasValueHint;
 use tx_verifier::transaction_verifier::calculate_tokens_burned_in_outputs;
 use utils::ensure;
 use wallet_types::{
-    utxo_types::{get_utxo_state, UtxoState, UtxoStates},
+    utxo_types::{get_utxo_state, get_utxo_type, UtxoState, UtxoStates},
     wallet_tx::{TxData, TxState},
     with_locked::WithLocked,
     AccountWalletTxId, BlockInfo, WalletTx,
@@ -56,6 +56,33 @@ pub struct TxInfo {
     pub id: Id<Transaction>,
     pub height: BlockHeight,
     pub timestamp: BlockTimestamp,
+    /// The user's labels of the account's addresses the transaction spends from or pays to
+    pub labels: Vec<String>,
+}
+
+/// The position of a confirmed transaction in the chain, by which the transactions
+/// are listed page by page
+pub type TxChainPosition = (BlockHeight, Id<Transaction>);
+
+/// A confirmed output that is expected to be in the node's UTXO set,
+/// unless it has been spent by a confirmed transaction
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct TrackedUtxo {
+    pub outpoint: UtxoOutPoint,
+    pub output: TxOutput,
+    /// The height of the block that created the output
+    pub created_at: BlockHeight,
+    /// The height of the block that spent the output, if any
+    pub spent_at: Option<BlockHeight>,
+}
+
+/// A block that was produced by one of the pools of the account
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct StakedBlock {
+    pub pool_id: PoolId,
+    pub block_id: Id<GenBlock>,
+    pub block_height: BlockHeight,
+    pub block_timestamp: BlockTimestamp,
 }
 
 impl TxInfo {
@@ -64,6 +91,7 @@ impl TxInfo {
             id,
             height,
             timestamp,
+            labels: Vec::new(),
         }
     }
 }
@@ -491,6 +519,7 @@ pub struct OutputCache {
     pools: BTreeMap<PoolId, PoolData>,
     delegations: BTreeMap<DelegationId, DelegationData>,
     token_issuance: BTreeMap<TokenId, TokenIssuanceData>,
+    staked_blocks: BTreeMap<BlockHeight, StakedBlock>,
 }
 
 impl OutputCache {
@@ -502,6 +531,7 @@ impl OutputCache {
             pools: BTreeMap::new(),
             delegations: BTreeMap::new(),
             token_issuance: BTreeMap::new(),
+            staked_blocks: BTreeMap::new(),
         }
     }
 
@@ -535,6 +565,47 @@ impl OutputCache {
             .and_then(|tx| tx.outputs().get(outpoint.output_index() as usize))
     }
 
+    /// Collect the outputs of the confirmed transactions that are supposed to be in the UTXO set,
+    /// along with the heights they were created and spent at.
+    /// Spending by unconfirmed transactions is ignored, the node still has those outputs.
+    pub fn confirmed_utxos<F: Fn(&TxOutput) -> bool>(&self, output_filter: F) -> Vec<TrackedUtxo> {
+        self.txs
+            .values()
+            .filter_map(|tx| match tx.state() {
+                TxState::Confirmed(height, _, _) => Some((tx, height)),
+                TxState::Inactive(_)
+                | TxState::InMempool(_)
+                | TxState::Conflicted(_)
+                | TxState::Abandoned => None,
+            })
+            .flat_map(|(tx, created_at)| {
+                tx.outputs()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, output)| get_utxo_type(output).is_some() && output_filter(output))
+                    .map(move |(idx, output)| {
+                        let outpoint = UtxoOutPoint::new(tx.id(), idx as u32);
+                        let spent_at = match self.consumed.get(&outpoint) {
+                            Some(TxState::Confirmed(height, _, _)) => Some(*height),
+                            Some(
+                                TxState::Inactive(_)
+                                | TxState::InMempool(_)
+                                | TxState::Conflicted(_)
+                                | TxState::Abandoned,
+                            )
+                            | None => None,
+                        };
+                        TrackedUtxo {
+                            outpoint,
+                            output: output.clone(),
+                            created_at,
+                            spent_at,
+                        }
+                    })
+            })
+            .collect()
+    }
+
     pub fn pool_ids(&self) -> Vec<(PoolId, PoolData)> {
         self.pools
             .iter()
@@ -578,6 +649,23 @@ impl OutputCache {
             })
     }
 
+    /// The blocks produced by the account's pools in the specified height range (inclusive),
+    /// sorted by the block height
+    pub fn staked_blocks(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Vec<StakedBlock> {
+        if from_height > to_height {
+            return Vec::new();
+        }
+
+        self.staked_blocks
+            .range(from_height..=to_height)
+            .map(|(_, block)| block.clone())
+            .collect()
+    }
+
     pub fn pool_data(&self, pool_id: PoolId) -> WalletResult<&PoolData> {
         self.pools.get(&pool_id).ok_or(WalletError::UnknownPoolId(pool_id))
     }
@@ -590,6 +678,10 @@ impl OutputCache {
         self.delegations.get(delegation_id)
     }
 
+    pub fn token_ids(&self) -> impl Iterator<Item = &TokenId> {
+        self.token_issuance.keys()
+    }
+
     pub fn token_data(&self, token_id: &TokenId) -> Option<&TokenIssuanceData> {
         self.token_issuance.get(token_id)
     }
@@ -600,15 +692,7 @@ impl OutputCache {
         is_mine: F,
     ) -> WalletResult<UnconfirmedTokenInfo> {
         let token_data = match self.token_issuance.get(&token_info.token_id) {
-            Some(token_data) => {
-                if !is_mine(&token_data.authority) {
-                    return Ok(UnconfirmedTokenInfo::FungibleToken(
-                        token_info.token_id,
-                        token_info.frozen.into(),
-                    ));
-                }
-                token_data
-            }
+            Some(token_data) => token_data,
             // If it is not ours just return what is in the token_info
             None => {
                 return Ok(UnconfirmedTokenInfo::FungibleToken(
@@ -625,6 +709,19 @@ impl OutputCache {
             .sorted_by(|x, y| wallet_tx_order(x, y))
             .collect_vec();
 
+        // The authority is derived from the confirmed one and the pending changes, so that
+        // subsequent operations use the right key and an abandoned change is not taken into account
+        let authority =
+            unconfirmed_txs.iter().fold(token_info.authority.clone(), |authority, tx| {
+                apply_authority_mutations_from_tx(authority, tx, &token_info.token_id)
+            });
+        if !is_mine(&authority) {
+            return Ok(UnconfirmedTokenInfo::FungibleToken(
+                token_info.token_id,
+                token_info.frozen.into(),
+            ));
+        }
+
         let mut frozen_state = token_info.frozen.into();
         let mut total_supply: TokenCurrentSupplyState = token_info.total_supply.into();
         total_supply = total_supply.mint(token_info.circulating_supply)?;
@@ -644,7 +741,7 @@ impl OutputCache {
                 frozen: frozen_state,
                 last_nonce: token_data.last_nonce,
                 total_supply,
-                authority: token_data.authority.clone(),
+                authority,
             },
         ))
     }
@@ -778,6 +875,18 @@ impl OutputCache {
                     } else {
                         return Err(WalletError::InconsistentProduceBlockFromStake(*pool_id));
                     }
+
+                    if let (WalletTx::Block(block), Some(block_info)) = (tx, block_info) {
+                        self.staked_blocks.insert(
+                            block_info.height,
+                            StakedBlock {
+                                pool_id: *pool_id,
+                                block_id: *block.block_id(),
+                                block_height: block_info.height,
+                                block_timestamp: block_info.timestamp,
+                            },
+                        );
+                    }
                 }
                 TxOutput::CreateStakePool(pool_id, data) => {
                     if let Some(block_info) = block_info {
@@ -1046,6 +1155,9 @@ impl OutputCache {
                         self.pools.remove(pool_id);
                     }
                     TxOutput::ProduceBlockFromStake(_, pool_id) => {
+                        if let WalletTx::Block(block) = &tx {
+                            self.staked_blocks.remove(&block.height());
+                        }
                         if self.pools.contains_key(pool_id) {
                             let latest_utxo = self.find_latest_utxo_for_pool(*pool_id);
                             if let Some(pool_data) = self.pools.get_mut(pool_id) {
@@ -1246,6 +1358,81 @@ impl OutputCache {
             .collect()
     }
 
+    /// Returns up to `limit` confirmed transactions that come after the `after` position
+    /// in the chain, the oldest ones first, or the newest ones first if `newest_first` is set.
+    ///
+    /// Only the transactions of the requested page are kept while scanning the cache,
+    /// so the cost of a page doesn't depend on how far into the list it is.
+    pub fn mainchain_transactions_page(
+        &self,
+        destination: Option<Destination>,
+        after: Option<TxChainPosition>,
+        newest_first: bool,
+        limit: usize,
+    ) -> Vec<TxInfo> {
+        let comes_after = |position: &TxChainPosition, other: &TxChainPosition| {
+            if newest_first {
+                position < other
+            } else {
+                position > other
+            }
+        };
+
+        let mut page = BTreeMap::new();
+        for tx in self.txs.values() {
+            let tx = match tx {
+                WalletTx::Block(_) => continue,
+                WalletTx::Tx(tx) => tx,
+            };
+            let (block_height, timestamp) = match tx.state() {
+                TxState::Confirmed(block_height, timestamp, _) => (*block_height, *timestamp),
+                TxState::Inactive(_)
+                | TxState::Conflicted(_)
+                | TxState::InMempool(_)
+                | TxState::Abandoned => continue,
+            };
+
+            let tx_with_id = tx.get_transaction_with_id();
+            let position = (block_height, tx_with_id.get_id());
+            if after.as_ref().is_some_and(|after| !comes_after(&position, after)) {
+                continue;
+            }
+            if page.len() >= limit {
+                let page_end = if newest_first {
+                    page.keys().next()
+                } else {
+                    page.keys().next_back()
+                };
+                if page_end.map_or(true, |page_end| comes_after(&position, page_end)) {
+                    continue;
+                }
+            }
+
+            if let Some(dest) = &destination {
+                if !self.destination_in_tx_outputs(&tx_with_id, dest)
+                    && !self.destination_in_tx_inputs(&tx_with_id, dest)
+                {
+                    continue;
+                }
+            }
+
+            page.insert(position, TxInfo::new(position.1, block_height, timestamp));
+            if page.len() > limit {
+                if newest_first {
+                    page.pop_first();
+                } else {
+                    page.pop_last();
+                }
+            }
+        }
+
+        if newest_first {
+            page.into_values().rev().collect()
+        } else {
+            page.into_values().collect()
+        }
+    }
+
     /// Returns true if the destination is found in the transaction's inputs
     fn destination_in_tx_inputs(&self, tx: &WithId<&Transaction>, dest: &Destination) -> bool {
         tx.inputs().iter().any(|inp| match inp {
@@ -1275,77 +1462,127 @@ impl OutputCache {
         &mut self,
         tx_id: Id<Transaction>,
     ) -> WalletResult<Vec<Id<Transaction>>> {
-        let mut all_abandoned = Vec::new();
-        let mut to_abandon = BTreeSet::from_iter([OutPointSourceId::from(tx_id)]);
+        self.drop_unconfirmed_transaction(tx_id, TxState::Abandoned, |state| match state {
+            TxState::Inactive(_) | TxState::Conflicted(_) => Ok(()),
+            TxState::Confirmed(_, _, _) | TxState::InMempool(_) | TxState::Abandoned => {
+                Err(WalletError::CannotAbandonTransaction(*state))
+            }
+        })
+    }
 
-        while let Some(outpoint_source_id) = to_abandon.pop_first() {
-            all_abandoned.push(*outpoint_source_id.get_tx_id().expect("must be a transaction"));
+    /// Mark a transaction that has been replaced by another one spending the same inputs
+    /// and its descendants as conflicted
+    /// Returns a Vec of the transaction Ids that have been marked
+    pub fn mark_transaction_replaced(
+        &mut self,
+        tx_id: Id<Transaction>,
+        best_block_id: Id<GenBlock>,
+    ) -> WalletResult<Vec<Id<Transaction>>> {
+        self.drop_unconfirmed_transaction(tx_id, TxState::Conflicted(best_block_id), |state| {
+            match state {
+                TxState::Inactive(_) | TxState::InMempool(_) => Ok(()),
+                TxState::Confirmed(_, _, _) | TxState::Conflicted(_) | TxState::Abandoned => {
+                    Err(WalletError::CannotReplaceTransaction(*state))
+                }
+            }
+        })
+    }
+
+    /// The unconfirmed transactions spending the outputs of the transaction, directly or
+    /// through other unconfirmed transactions
+    pub fn unconfirmed_descendants(&self, tx_id: Id<Transaction>) -> Vec<Id<Transaction>> {
+        let mut all_descendants = Vec::new();
+        let mut to_visit = self
+            .unconfirmed_descendants
+            .get(&OutPointSourceId::from(tx_id))
+            .cloned()
+            .unwrap_or_default();
+
+        while let Some(outpoint_source_id) = to_visit.pop_first() {
+            all_descendants.push(*outpoint_source_id.get_tx_id().expect("must be a transaction"));
+
+            if let Some(descendants) = self.unconfirmed_descendants.get(&outpoint_source_id) {
+                to_visit.extend(descendants.iter().cloned())
+            }
+        }
+
+        all_descendants
+    }
+
+    /// Set the new state to an unconfirmed transaction and its descendants, releasing
+    /// the outputs and nonces they have consumed
+    fn drop_unconfirmed_transaction(
+        &mut self,
+        tx_id: Id<Transaction>,
+        new_state: TxState,
+        check_state: impl Fn(&TxState) -> WalletResult<()>,
+    ) -> WalletResult<Vec<Id<Transaction>>> {
+        let mut all_dropped = Vec::new();
+        let mut to_drop = BTreeSet::from_iter([OutPointSourceId::from(tx_id)]);
+
+        while let Some(outpoint_source_id) = to_drop.pop_first() {
+            all_dropped.push(*outpoint_source_id.get_tx_id().expect("must be a transaction"));
 
             if let Some(descendants) = self.unconfirmed_descendants.remove(&outpoint_source_id) {
-                to_abandon.extend(descendants.into_iter())
+                to_drop.extend(descendants.into_iter())
             }
         }
 
-        for tx_id in all_abandoned.iter().rev().copied() {
+        for tx_id in all_dropped.iter().rev().copied() {
             match self.txs.entry(tx_id.into()) {
                 Entry::Occupied(mut entry) => match entry.get_mut() {
                     WalletTx::Block(_) => Err(WalletError::CannotFindTransactionWithId(tx_id)),
-                    WalletTx::Tx(tx) => match tx.state() {
-                        TxState::Inactive(_) | TxState::Conflicted(_) => {
-                            tx.set_state(TxState::Abandoned);
-                            for input in tx.get_transaction().inputs() {
-                                match input {
-                                    TxInput::Utxo(outpoint) => {
-                                        self.consumed.insert(outpoint.clone(), *tx.state());
-                                    }
-                                    TxInput::Account(outpoint) => match outpoint.account() {
-                                        AccountSpending::DelegationBalance(delegation_id, _) => {
-                                            if let Some(data) =
-                                                self.delegations.get_mut(delegation_id)
-                                            {
-                                                data.last_nonce = outpoint.nonce().decrement();
-                                                data.last_parent = find_parent(
-                                                    &self.unconfirmed_descendants,
-                                                    tx_id.into(),
-                                                );
-                                            }
+                    WalletTx::Tx(tx) => {
+                        check_state(tx.state())?;
+                        tx.set_state(new_state);
+                        for input in tx.get_transaction().inputs() {
+                            match input {
+                                TxInput::Utxo(outpoint) => {
+                                    self.consumed.insert(outpoint.clone(), *tx.state());
+                                }
+                                TxInput::Account(outpoint) => match outpoint.account() {
+                                    AccountSpending::DelegationBalance(delegation_id, _) => {
+                                        if let Some(data) = self.delegations.get_mut(delegation_id)
+                                        {
+                                            data.last_nonce = outpoint.nonce().decrement();
+                                            data.last_parent = find_parent(
+                                                &self.unconfirmed_descendants,
+                                                tx_id.into(),
+                                            );
                                         }
-                                    },
-                                    TxInput::AccountCommand(nonce, op) => match op {
-                                        AccountCommand::MintTokens(token_id, _)
-                                        | AccountCommand::UnmintTokens(token_id)
-                                        | AccountCommand::LockTokenSupply(token_id)
-                                        | AccountCommand::FreezeToken(token_id, _)
-                                        | AccountCommand::UnfreezeToken(token_id)
-                                        | AccountCommand::ChangeTokenMetadataUri(token_id, _)
-                                        | AccountCommand::ChangeTokenAuthority(token_id, _) => {
-                                            if let Some(data) =
-                                                self.token_issuance.get_mut(token_id)
-                                            {
-                                                data.last_nonce = nonce.decrement();
-                                                data.last_parent = find_parent(
-                                                    &self.unconfirmed_descendants,
-                                                    tx_id.into(),
-                                                );
-                                                data.unconfirmed_txs.remove(&tx_id.into());
-                                            }
+                                    }
+                                },
+                                TxInput::AccountCommand(nonce, op) => match op {
+                                    AccountCommand::MintTokens(token_id, _)
+                                    | AccountCommand::UnmintTokens(token_id)
+                                    | AccountCommand::LockTokenSupply(token_id)
+                                    | AccountCommand::FreezeToken(token_id, _)
+                                    | AccountCommand::UnfreezeToken(token_id)
+                                    | AccountCommand::ChangeTokenMetadataUri(token_id, _)
+                                    | AccountCommand::ChangeTokenAuthority(token_id, _) => {
+                                        if let Some(data) = self.token_issuance.get_mut(token_id) {
+                                            data.last_nonce = nonce.decrement();
+                                            data.last_parent = find_parent(
+                                                &self.unconfirmed_descendants,
+                                                tx_id.into(),
+                                            );
+                                            data.unconfirmed_txs.remove(&tx_id.into());
                                         }
-                                        // TODO(orders)
-                                        AccountCommand::ConcludeOrder(_) => unimplemented!(),
-                                        AccountCommand::FillOrder(_, _, _) => unimplemented!(),
-                                    },
-                                }
+                                    }
+                                    // TODO(orders)
+                                    AccountCommand::ConcludeOrder(_) => unimplemented!(),
+                                    AccountCommand::FillOrder(_, _, _) => unimplemented!(),
+                                },
                             }
-                            Ok(())
                         }
-                        state => Err(WalletError::CannotAbandonTransaction(*state)),
-                    },
+                        Ok(())
+                    }
                 },
                 Entry::Vacant(_) => Err(WalletError::CannotFindTransactionWithId(tx_id)),
             }?;
         }
 
-        Ok(all_abandoned)
+        Ok(all_dropped)
     }
 
     pub fn get_transaction(&self, transaction_id: Id<Transaction>) -> WalletResult<&TxData> {
@@ -1550,6 +1787,35 @@ fn apply_freeze_mutations_from_tx(
     Ok(frozen_state)
 }
 
+fn apply_authority_mutations_from_tx(
+    mut authority: Destination,
+    tx: &WalletTx,
+    own_token_id: &TokenId,
+) -> Destination {
+    for inp in tx.inputs() {
+        match inp {
+            TxInput::Utxo(_) | TxInput::Account(_) => {}
+            TxInput::AccountCommand(_, op) => match op {
+                AccountCommand::ChangeTokenAuthority(token_id, new_authority) => {
+                    if token_id == own_token_id {
+                        authority = new_authority.clone();
+                    }
+                }
+                AccountCommand::MintTokens(_, _)
+                | AccountCommand::UnmintTokens(_)
+                | AccountCommand::LockTokenSupply(_)
+                | AccountCommand::FreezeToken(_, _)
+                | AccountCommand::UnfreezeToken(_)
+                | AccountCommand::ChangeTokenMetadataUri(_, _)
+                | AccountCommand::ConcludeOrder(_)
+                | AccountCommand::FillOrder(_, _, _) => {}
+            },
+        }
+    }
+
+    authority
+}
+
 fn apply_total_supply_mutations_from_tx(
     mut total_supply: TokenCurrentSupplyState,
     tx: &WalletTx,
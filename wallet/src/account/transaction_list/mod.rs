@@ -13,20 +13,35 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{cmp::Ordering, ops::Add};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    ops::Add,
+};
 
 use common::{
-    chain::{block::timestamp::BlockTimestamp, Transaction, TxInput, TxOutput},
+    chain::{
+        block::timestamp::BlockTimestamp,
+        output_value::OutputValue,
+        tokens::{TokenId, TokenIssuance},
+        AccountCommand, AccountSpending, DelegationId, Destination, OrderId, PoolId, Transaction,
+        TxInput, TxOutput,
+    },
     primitives::{Amount, BlockHeight, Id, Idable},
 };
+use crypto::key::hdkd::u31::U31;
 use wallet_types::{
+    internal_transfer::InternalTransfer,
     wallet_tx::{TxData, TxState},
     KeyPurpose, WalletTx,
 };
 
 use crate::{key_chain::AccountKeyChainImpl, WalletError, WalletResult};
 
-use super::{currency_grouper::group_outputs, output_cache::OutputCache};
+use super::{
+    currency_grouper::{group_outputs, Currency},
+    output_cache::OutputCache,
+};
 
 // TODO: Show send/recv addresses and amounts
 // TODO: Show token amounts
@@ -48,6 +63,25 @@ pub enum TxType {
         amount: Amount,
     },
 
+    /// Sent to another account of the same wallet.
+    ///
+    /// Internal transfers are not reported as `Sent` and `Received`, so that they are not counted
+    /// twice when adding up the payments of all the accounts of the wallet.
+    TransferredOut {
+        /// The receiving account
+        to_account: U31,
+        /// Total transferred amount
+        amount: Amount,
+    },
+
+    /// Received from another account of the same wallet
+    TransferredIn {
+        /// The sending account
+        from_account: U31,
+        /// Total transferred amount
+        amount: Amount,
+    },
+
     /// Unknown transaction type
     Other {},
 }
@@ -55,7 +89,10 @@ pub enum TxType {
 impl TxType {
     pub fn amount(&self) -> Option<Amount> {
         match self {
-            TxType::Received { amount } | TxType::Sent { amount } => Some(*amount),
+            TxType::Received { amount }
+            | TxType::Sent { amount }
+            | TxType::TransferredOut { amount, .. }
+            | TxType::TransferredIn { amount, .. } => Some(*amount),
             TxType::Redeposit {} | TxType::Other {} => None,
         }
     }
@@ -65,17 +102,130 @@ impl TxType {
             TxType::Redeposit {} => "Redeposit",
             TxType::Received { .. } => "Received",
             TxType::Sent { .. } => "Sent",
+            TxType::TransferredOut { .. } => "Transferred out",
+            TxType::TransferredIn { .. } => "Transferred in",
             TxType::Other {} => "Other",
         }
     }
 }
 
+/// A single effect of a transaction, derived from its structure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxDescriptionItem {
+    /// Coins or tokens sent to a destination that doesn't belong to the account
+    Sent {
+        currency: Currency,
+        amount: Amount,
+        destination: Destination,
+    },
+
+    /// Coins or tokens received from someone else
+    Received {
+        currency: Currency,
+        amount: Amount,
+        destination: Destination,
+    },
+
+    Burned {
+        currency: Currency,
+        amount: Amount,
+    },
+
+    CreatedStakePool {
+        pool_id: PoolId,
+        pledge: Amount,
+    },
+
+    DecommissionedStakePool {
+        pool_id: PoolId,
+    },
+
+    CreatedDelegation {
+        pool_id: PoolId,
+    },
+
+    /// The pool is only known if the delegation belongs to the account
+    Delegated {
+        delegation_id: DelegationId,
+        pool_id: Option<PoolId>,
+        amount: Amount,
+    },
+
+    WithdrewFromDelegation {
+        delegation_id: DelegationId,
+        pool_id: Option<PoolId>,
+        amount: Amount,
+    },
+
+    IssuedToken {
+        ticker: Vec<u8>,
+    },
+
+    IssuedNft {
+        token_id: TokenId,
+    },
+
+    MintedTokens {
+        token_id: TokenId,
+        amount: Amount,
+    },
+
+    UnmintedTokens {
+        token_id: TokenId,
+    },
+
+    LockedTokenSupply {
+        token_id: TokenId,
+    },
+
+    FrozeToken {
+        token_id: TokenId,
+    },
+
+    UnfrozeToken {
+        token_id: TokenId,
+    },
+
+    ChangedTokenAuthority {
+        token_id: TokenId,
+        authority: Destination,
+    },
+
+    ChangedTokenMetadataUri {
+        token_id: TokenId,
+    },
+
+    CreatedOrder {
+        ask_currency: Currency,
+        ask_amount: Amount,
+        give_currency: Currency,
+        give_amount: Amount,
+    },
+
+    FilledOrder {
+        order_id: OrderId,
+        currency: Currency,
+        amount: Amount,
+    },
+
+    ConcludedOrder {
+        order_id: OrderId,
+    },
+
+    DataDeposit {},
+}
+
 #[derive(Debug, Clone)]
 pub struct TransactionInfo {
     pub txid: Id<Transaction>,
     pub tx_type: TxType,
+    pub description: Vec<TxDescriptionItem>,
     pub timestamp: Option<BlockTimestamp>,
     pub state: TxState,
+    /// The network fee in coins, if it's been paid by the account and all the spent inputs are known
+    pub fee: Option<Amount>,
+    /// The user's labels of the account's addresses the transaction spends from or pays to
+    pub labels: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -143,9 +293,309 @@ fn own_input<'a>(
     }
 }
 
+/// The label of the account's address the output is sent to, if there is one
+fn output_address_label<'a>(
+    address_labels: &'a BTreeMap<Destination, String>,
+    output: &TxOutput,
+) -> Option<&'a String> {
+    match output {
+        TxOutput::Transfer(_, dest) | TxOutput::LockThenTransfer(_, dest, _) => {
+            address_labels.get(dest)
+        }
+        TxOutput::Htlc(_, _)
+        | TxOutput::Burn(_)
+        | TxOutput::CreateStakePool(_, _)
+        | TxOutput::ProduceBlockFromStake(_, _)
+        | TxOutput::CreateDelegationId(_, _)
+        | TxOutput::DelegateStaking(_, _)
+        | TxOutput::IssueFungibleToken(_)
+        | TxOutput::IssueNft(_, _, _)
+        | TxOutput::DataDeposit(_)
+        | TxOutput::AnyoneCanTake(_) => None,
+    }
+}
+
+/// The distinct labels of the account's addresses the outputs are sent to
+fn address_labels_of<'a>(
+    address_labels: &BTreeMap<Destination, String>,
+    outputs: impl Iterator<Item = &'a TxOutput>,
+) -> Vec<String> {
+    outputs
+        .filter_map(|output| output_address_label(address_labels, output))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .cloned()
+        .collect()
+}
+
+/// The user's labels of the account's addresses the transaction spends from or pays to
+pub fn get_transaction_labels(
+    key_chain: &AccountKeyChainImpl,
+    output_cache: &OutputCache,
+    address_labels: &BTreeMap<Destination, String>,
+    tx: &Transaction,
+) -> Vec<String> {
+    let own_inputs =
+        tx.inputs().iter().filter_map(|input| own_input(key_chain, output_cache, input));
+    let own_outputs = tx.outputs().iter().filter(|output| own_output(key_chain, output));
+    address_labels_of(address_labels, own_inputs.chain(own_outputs))
+}
+
+fn output_value_amount(value: &OutputValue) -> Option<(Currency, Amount)> {
+    match value {
+        OutputValue::Coin(amount) => Some((Currency::Coin, *amount)),
+        OutputValue::TokenV0(_) => None,
+        OutputValue::TokenV1(token_id, amount) => Some((Currency::Token(*token_id), *amount)),
+    }
+}
+
+fn delegation_pool_id(output_cache: &OutputCache, delegation_id: &DelegationId) -> Option<PoolId> {
+    output_cache.delegation_data(delegation_id).map(|data| data.pool_id)
+}
+
+fn describe_input(output_cache: &OutputCache, input: &TxInput) -> Option<TxDescriptionItem> {
+    match input {
+        TxInput::Utxo(utxo) => {
+            let spent_output = output_cache
+                .txs_with_unconfirmed()
+                .get(&utxo.source_id())?
+                .outputs()
+                .get(utxo.output_index() as usize)?;
+
+            match spent_output {
+                TxOutput::CreateStakePool(pool_id, _)
+                | TxOutput::ProduceBlockFromStake(_, pool_id) => {
+                    Some(TxDescriptionItem::DecommissionedStakePool { pool_id: *pool_id })
+                }
+                TxOutput::Transfer(_, _)
+                | TxOutput::LockThenTransfer(_, _, _)
+                | TxOutput::Burn(_)
+                | TxOutput::CreateDelegationId(_, _)
+                | TxOutput::DelegateStaking(_, _)
+                | TxOutput::IssueFungibleToken(_)
+                | TxOutput::IssueNft(_, _, _)
+                | TxOutput::DataDeposit(_)
+                | TxOutput::Htlc(_, _)
+                | TxOutput::AnyoneCanTake(_) => None,
+            }
+        }
+        TxInput::Account(outpoint) => match outpoint.account() {
+            AccountSpending::DelegationBalance(delegation_id, amount) => {
+                Some(TxDescriptionItem::WithdrewFromDelegation {
+                    delegation_id: *delegation_id,
+                    pool_id: delegation_pool_id(output_cache, delegation_id),
+                    amount: *amount,
+                })
+            }
+        },
+        TxInput::AccountCommand(_, command) => {
+            let item = match command {
+                AccountCommand::MintTokens(token_id, amount) => TxDescriptionItem::MintedTokens {
+                    token_id: *token_id,
+                    amount: *amount,
+                },
+                AccountCommand::UnmintTokens(token_id) => TxDescriptionItem::UnmintedTokens {
+                    token_id: *token_id,
+                },
+                AccountCommand::LockTokenSupply(token_id) => TxDescriptionItem::LockedTokenSupply {
+                    token_id: *token_id,
+                },
+                AccountCommand::FreezeToken(token_id, _) => TxDescriptionItem::FrozeToken {
+                    token_id: *token_id,
+                },
+                AccountCommand::UnfreezeToken(token_id) => TxDescriptionItem::UnfrozeToken {
+                    token_id: *token_id,
+                },
+                AccountCommand::ChangeTokenAuthority(token_id, authority) => {
+                    TxDescriptionItem::ChangedTokenAuthority {
+                        token_id: *token_id,
+                        authority: authority.clone(),
+                    }
+                }
+                AccountCommand::ChangeTokenMetadataUri(token_id, _) => {
+                    TxDescriptionItem::ChangedTokenMetadataUri {
+                        token_id: *token_id,
+                    }
+                }
+                AccountCommand::ConcludeOrder(order_id) => TxDescriptionItem::ConcludedOrder {
+                    order_id: *order_id,
+                },
+                AccountCommand::FillOrder(order_id, fill, _) => {
+                    let (currency, amount) = output_value_amount(fill)?;
+                    TxDescriptionItem::FilledOrder {
+                        order_id: *order_id,
+                        currency,
+                        amount,
+                    }
+                }
+            };
+            Some(item)
+        }
+    }
+}
+
+fn describe_output(
+    key_chain: &AccountKeyChainImpl,
+    output_cache: &OutputCache,
+    has_own_inputs: bool,
+    output: &TxOutput,
+) -> Option<TxDescriptionItem> {
+    match output {
+        TxOutput::Transfer(value, destination)
+        | TxOutput::LockThenTransfer(value, destination, _) => {
+            let (currency, amount) = output_value_amount(value)?;
+            let destination = destination.clone();
+            match (has_own_inputs, own_output(key_chain, output)) {
+                (true, false) => Some(TxDescriptionItem::Sent {
+                    currency,
+                    amount,
+                    destination,
+                }),
+                (false, true) => Some(TxDescriptionItem::Received {
+                    currency,
+                    amount,
+                    destination,
+                }),
+                // Change or someone else's output
+                (true, true) | (false, false) => None,
+            }
+        }
+        TxOutput::Burn(value) => {
+            let (currency, amount) = output_value_amount(value)?;
+            Some(TxDescriptionItem::Burned { currency, amount })
+        }
+        TxOutput::CreateStakePool(pool_id, data) => Some(TxDescriptionItem::CreatedStakePool {
+            pool_id: *pool_id,
+            pledge: data.pledge(),
+        }),
+        TxOutput::CreateDelegationId(_, pool_id) => {
+            Some(TxDescriptionItem::CreatedDelegation { pool_id: *pool_id })
+        }
+        TxOutput::DelegateStaking(amount, delegation_id) => Some(TxDescriptionItem::Delegated {
+            delegation_id: *delegation_id,
+            pool_id: delegation_pool_id(output_cache, delegation_id),
+            amount: *amount,
+        }),
+        TxOutput::IssueFungibleToken(issuance) => match issuance.as_ref() {
+            TokenIssuance::V1(issuance) => Some(TxDescriptionItem::IssuedToken {
+                ticker: issuance.token_ticker.clone(),
+            }),
+        },
+        TxOutput::IssueNft(token_id, _, _) => Some(TxDescriptionItem::IssuedNft {
+            token_id: *token_id,
+        }),
+        TxOutput::AnyoneCanTake(order) => {
+            let (ask_currency, ask_amount) = output_value_amount(order.ask())?;
+            let (give_currency, give_amount) = output_value_amount(order.give())?;
+            Some(TxDescriptionItem::CreatedOrder {
+                ask_currency,
+                ask_amount,
+                give_currency,
+                give_amount,
+            })
+        }
+        TxOutput::DataDeposit(_) => Some(TxDescriptionItem::DataDeposit {}),
+        TxOutput::Htlc(_, _) => None, // TODO(HTLC)
+        TxOutput::ProduceBlockFromStake(_, _) => None,
+    }
+}
+
+/// Describe the effects of a transaction on the account, e.g. what has been sent where
+pub fn describe_transaction(
+    key_chain: &AccountKeyChainImpl,
+    output_cache: &OutputCache,
+    tx: &Transaction,
+) -> Vec<TxDescriptionItem> {
+    let has_own_inputs = tx
+        .inputs()
+        .iter()
+        .any(|input| own_input(key_chain, output_cache, input).is_some());
+
+    tx.inputs()
+        .iter()
+        .filter_map(|input| describe_input(output_cache, input))
+        .chain(
+            tx.outputs().iter().filter_map(|output| {
+                describe_output(key_chain, output_cache, has_own_inputs, output)
+            }),
+        )
+        .collect()
+}
+
+/// The coins consumed by a transaction, or None if some of them are unknown
+fn coin_input_amount(output_cache: &OutputCache, tx: &Transaction) -> Option<Amount> {
+    tx.inputs().iter().try_fold(Amount::ZERO, |total, input| {
+        let amount = match input {
+            TxInput::Utxo(utxo) => {
+                let spent_output = output_cache
+                    .txs_with_unconfirmed()
+                    .get(&utxo.source_id())?
+                    .outputs()
+                    .get(utxo.output_index() as usize)?;
+
+                match spent_output {
+                    TxOutput::Transfer(value, _)
+                    | TxOutput::LockThenTransfer(value, _, _)
+                    | TxOutput::Htlc(value, _) => value.coin_amount().unwrap_or(Amount::ZERO),
+                    // The pool balance includes the rewards, which are not known here
+                    TxOutput::CreateStakePool(_, _)
+                    | TxOutput::ProduceBlockFromStake(_, _)
+                    | TxOutput::Burn(_)
+                    | TxOutput::CreateDelegationId(_, _)
+                    | TxOutput::DelegateStaking(_, _)
+                    | TxOutput::IssueFungibleToken(_)
+                    | TxOutput::IssueNft(_, _, _)
+                    | TxOutput::DataDeposit(_)
+                    | TxOutput::AnyoneCanTake(_) => return None,
+                }
+            }
+            TxInput::Account(outpoint) => match outpoint.account() {
+                AccountSpending::DelegationBalance(_, amount) => *amount,
+            },
+            TxInput::AccountCommand(_, command) => match command {
+                AccountCommand::MintTokens(_, _)
+                | AccountCommand::UnmintTokens(_)
+                | AccountCommand::LockTokenSupply(_)
+                | AccountCommand::FreezeToken(_, _)
+                | AccountCommand::UnfreezeToken(_)
+                | AccountCommand::ChangeTokenAuthority(_, _)
+                | AccountCommand::ChangeTokenMetadataUri(_, _) => Amount::ZERO,
+                // The amounts taken from orders depend on the state of the order
+                AccountCommand::FillOrder(_, _, _) | AccountCommand::ConcludeOrder(_) => {
+                    return None
+                }
+            },
+        };
+        total + amount
+    })
+}
+
+/// The coins produced by a transaction, not counting the issuance and deposit fees
+fn coin_output_amount(tx: &Transaction) -> Option<Amount> {
+    tx.outputs().iter().try_fold(Amount::ZERO, |total, output| {
+        let amount = match output {
+            TxOutput::Transfer(value, _)
+            | TxOutput::LockThenTransfer(value, _, _)
+            | TxOutput::Burn(value)
+            | TxOutput::Htlc(value, _) => value.coin_amount().unwrap_or(Amount::ZERO),
+            TxOutput::CreateStakePool(_, data) => data.pledge(),
+            TxOutput::DelegateStaking(amount, _) => *amount,
+            TxOutput::AnyoneCanTake(order) => order.give().coin_amount().unwrap_or(Amount::ZERO),
+            TxOutput::CreateDelegationId(_, _)
+            | TxOutput::IssueFungibleToken(_)
+            | TxOutput::IssueNft(_, _, _)
+            | TxOutput::DataDeposit(_)
+            | TxOutput::ProduceBlockFromStake(_, _) => Amount::ZERO,
+        };
+        total + amount
+    })
+}
+
 fn get_transaction(
     key_chain: &AccountKeyChainImpl,
     output_cache: &OutputCache,
+    address_labels: &BTreeMap<Destination, String>,
+    internal_transfer: Option<&InternalTransfer>,
     tx_data: &TxData,
 ) -> WalletResult<TransactionInfo> {
     let timestamp = tx_data.state().timestamp();
@@ -191,8 +641,17 @@ fn get_transaction(
         .get(&super::currency_grouper::Currency::Coin)
         .unwrap_or(&Amount::ZERO);
 
-    let tx_type = if own_inputs.len() == all_inputs.len() && own_outputs.len() == all_outputs.len()
-    {
+    let tx_type = if let Some(transfer) = internal_transfer.filter(|_| !own_inputs.is_empty()) {
+        TxType::TransferredOut {
+            to_account: transfer.to_account,
+            amount: non_own_recv_amount,
+        }
+    } else if let Some(transfer) = internal_transfer.filter(|_| !own_outputs.is_empty()) {
+        TxType::TransferredIn {
+            from_account: transfer.from_account,
+            amount: recv_amount,
+        }
+    } else if own_inputs.len() == all_inputs.len() && own_outputs.len() == all_outputs.len() {
         TxType::Redeposit {}
     } else if own_inputs.len() == all_inputs.len() {
         TxType::Sent {
@@ -206,20 +665,30 @@ fn get_transaction(
         TxType::Other {}
     };
 
+    let description = describe_transaction(key_chain, output_cache, tx_data.get_transaction());
+
+    let fee = if own_inputs.is_empty() {
+        None
+    } else {
+        coin_input_amount(output_cache, tx_data.get_transaction())
+            .zip(coin_output_amount(tx_data.get_transaction()))
+            .and_then(|(inputs, outputs)| inputs - outputs)
+    };
+
+    let labels = address_labels_of(address_labels, own_inputs.into_iter().chain(own_outputs));
+
     Ok(TransactionInfo {
         txid: tx_data.get_transaction().get_id(),
         tx_type,
+        description,
         timestamp,
         state: *tx_data.state(),
+        fee,
+        labels,
     })
 }
 
-pub fn get_transaction_list(
-    key_chain: &AccountKeyChainImpl,
-    output_cache: &OutputCache,
-    skip: usize,
-    count: usize,
-) -> WalletResult<TransactionList> {
+fn collect_tx_refs(output_cache: &OutputCache) -> Vec<TxRef> {
     let mut tx_refs: Vec<TxRef> = output_cache
         .txs_with_unconfirmed()
         .values()
@@ -233,12 +702,34 @@ pub fn get_transaction_list(
         .collect();
 
     tx_refs.sort_by(compare_tx_ref);
+    tx_refs
+}
+
+pub fn get_transaction_list(
+    key_chain: &AccountKeyChainImpl,
+    output_cache: &OutputCache,
+    address_labels: &BTreeMap<Destination, String>,
+    internal_transfers: &BTreeMap<Id<Transaction>, InternalTransfer>,
+    skip: usize,
+    count: usize,
+) -> WalletResult<TransactionList> {
+    let tx_refs = collect_tx_refs(output_cache);
 
     let begin = skip.min(tx_refs.len());
     let end = (skip + count).min(tx_refs.len());
     let txs = tx_refs.as_slice()[begin..end]
         .iter()
-        .map(|tx_ref| get_transaction(key_chain, output_cache, tx_ref.tx_data))
+        .map(|tx_ref| {
+            let internal_transfer =
+                internal_transfers.get(&tx_ref.tx_data.get_transaction().get_id());
+            get_transaction(
+                key_chain,
+                output_cache,
+                address_labels,
+                internal_transfer,
+                tx_ref.tx_data,
+            )
+        })
         .collect::<Result<Vec<_>, _>>()?;
 
     Ok(TransactionList {
@@ -248,3 +739,27 @@ pub fn get_transaction_list(
         txs,
     })
 }
+
+/// All the transactions of the account, the oldest first; unconfirmed transactions go last
+pub fn get_transaction_history(
+    key_chain: &AccountKeyChainImpl,
+    output_cache: &OutputCache,
+    address_labels: &BTreeMap<Destination, String>,
+    internal_transfers: &BTreeMap<Id<Transaction>, InternalTransfer>,
+) -> WalletResult<Vec<TransactionInfo>> {
+    collect_tx_refs(output_cache)
+        .iter()
+        .rev()
+        .map(|tx_ref| {
+            let internal_transfer =
+                internal_transfers.get(&tx_ref.tx_data.get_transaction().get_id());
+            get_transaction(
+                key_chain,
+                output_cache,
+                address_labels,
+                internal_transfer,
+                tx_ref.tx_data,
+            )
+        })
+        .collect()
+}
@@ -64,6 +64,10 @@ impl SelectionResult {
         Ok(self)
     }
 
+    pub fn output_pairs(&self) -> &[(TxInput, TxOutput)] {
+        &self.outputs
+    }
+
     pub fn into_output_pairs(self) -> Vec<(TxInput, TxOutput)> {
         self.outputs
     }
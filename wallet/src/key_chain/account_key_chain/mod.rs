@@ -525,6 +525,25 @@ impl AccountKeyChainImpl {
         Ok(())
     }
 
+    /// Stops watching a public key hash previously added with `add_standalone_watch_only_address`
+    pub fn remove_standalone_watch_only_address(
+        &mut self,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        address: PublicKeyHash,
+    ) -> KeyChainResult<()> {
+        let destination = Destination::PublicKeyHash(address);
+        if !self.standalone_watch_only_keys.contains_key(&destination) {
+            let addr = RpcAddress::new(&self.chain_config, destination)?;
+            return Err(KeyChainError::NoStandaloneAddressFound(addr));
+        }
+
+        let id = AccountPrefixedId::new(self.get_account_id(), destination);
+        db_tx.del_standalone_watch_only_key(&id)?;
+        self.standalone_watch_only_keys.remove(id.item_id());
+
+        Ok(())
+    }
+
     ///  Adds a new private key to be watched, standalone from the keys derived from this account
     pub fn add_standalone_private_key(
         &mut self,
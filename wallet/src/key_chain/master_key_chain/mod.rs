@@ -125,7 +125,10 @@ impl MasterKeyChain {
         chain_config: Arc<ChainConfig>,
         db_tx: &impl WalletStorageReadLocked,
     ) -> KeyChainResult<Self> {
-        db_tx.check_root_keys_sanity()?;
+        // A watch-only replica has no root keys at all
+        if !db_tx.is_watch_only()? {
+            db_tx.check_root_keys_sanity()?;
+        }
         Ok(MasterKeyChain { chain_config })
     }
 
@@ -19,6 +19,7 @@ use std::mem::take;
 use common::address::Address;
 use common::chain::output_value::OutputValue;
 use common::chain::partially_signed_transaction::PartiallySignedTransaction;
+use common::chain::signature::sighash::sighashtype::SigHashType;
 use common::chain::stakelock::StakePoolData;
 use common::chain::timelock::OutputTimeLock::ForBlockCount;
 use common::chain::tokens::{Metadata, TokenId, TokenIssuance};
@@ -33,6 +34,7 @@ use utils::ensure;
 use crate::account::currency_grouper::Currency;
 use crate::account::PoolData;
 use crate::destination_getters::{get_tx_output_destination, HtlcSpendingCondition};
+use crate::signer::check_sighash_type;
 use crate::{WalletError, WalletResult};
 
 /// The `SendRequest` struct provides the necessary information to the wallet
@@ -49,9 +51,15 @@ pub struct SendRequest {
 
     inputs: Vec<TxInput>,
 
+    /// the sighash type to sign each input with
+    sighash_types: Vec<SigHashType>,
+
     outputs: Vec<TxOutput>,
 
     fees: BTreeMap<Currency, Amount>,
+
+    /// If set, the exact coin fee the transaction pays instead of a fee derived from the fee rate
+    absolute_fee: Option<Amount>,
 }
 
 pub fn make_address_output(address: Address<Destination>, amount: Amount) -> TxOutput {
@@ -185,8 +193,10 @@ impl SendRequest {
             utxos: Vec::new(),
             destinations: Vec::new(),
             inputs: Vec::new(),
+            sighash_types: Vec::new(),
             outputs: Vec::new(),
             fees: BTreeMap::new(),
+            absolute_fee: None,
         }
     }
 
@@ -219,8 +229,10 @@ impl SendRequest {
             utxos: utxos.into_iter().map(Some).collect(),
             destinations,
             inputs: transaction.inputs().to_vec(),
+            sighash_types: vec![SigHashType::default(); transaction.inputs().len()],
             outputs: transaction.outputs().to_vec(),
             fees: BTreeMap::new(),
+            absolute_fee: None,
         })
     }
 
@@ -232,6 +244,10 @@ impl SendRequest {
         &self.destinations
     }
 
+    pub fn sighash_types(&self) -> &[SigHashType] {
+        &self.sighash_types
+    }
+
     pub fn utxos(&self) -> &[Option<TxOutput>] {
         &self.utxos
     }
@@ -248,6 +264,7 @@ impl SendRequest {
             self.inputs.push(outpoint);
             self.destinations.push(destination);
             self.utxos.push(None);
+            self.sighash_types.push(SigHashType::default());
         }
 
         self
@@ -270,16 +287,42 @@ impl SendRequest {
                     })?,
             );
             self.utxos.push(Some(txo));
+            self.sighash_types.push(SigHashType::default());
         }
 
         Ok(self)
     }
 
+    /// Sign the input at the specified index with a sighash type other than the default ALL,
+    /// e.g. ALL|ANYONECANPAY so that other parties can add their own inputs to the transaction
+    pub fn with_sighash_type(
+        mut self,
+        input_index: usize,
+        sighash_type: SigHashType,
+    ) -> WalletResult<Self> {
+        let current = self
+            .sighash_types
+            .get_mut(input_index)
+            .ok_or(WalletError::InvalidInputIndex(input_index))?;
+        *current = sighash_type;
+        Ok(self)
+    }
+
     pub fn with_outputs(mut self, outputs: impl IntoIterator<Item = TxOutput>) -> Self {
         self.outputs.extend(outputs);
         self
     }
 
+    /// Pay exactly `fee` in coins, ignoring the fee rates used for input selection
+    pub fn with_absolute_fee(mut self, fee: Amount) -> Self {
+        self.absolute_fee = Some(fee);
+        self
+    }
+
+    pub fn absolute_fee(&self) -> Option<Amount> {
+        self.absolute_fee
+    }
+
     pub fn get_outputs_mut(&mut self) -> &mut Vec<TxOutput> {
         &mut self.outputs
     }
@@ -293,12 +336,17 @@ impl SendRequest {
         let tx = Transaction::new(self.flags, self.inputs, self.outputs)?;
         let destinations = self.destinations.into_iter().map(Some).collect();
 
+        for (input_index, sighash_type) in self.sighash_types.iter().enumerate() {
+            check_sighash_type(&tx, input_index, *sighash_type)?;
+        }
+
         let ptx = PartiallySignedTransaction::new(
             tx,
             vec![None; num_inputs],
             self.utxos,
             destinations,
             None,
+            Some(self.sighash_types),
         )?;
         Ok(ptx)
     }
@@ -17,9 +17,10 @@ use common::chain::{
     partially_signed_transaction::PartiallySignedTransaction,
     signature::{
         inputsig::arbitrary_message::{ArbitraryMessageSignature, SignArbitraryMessageError},
+        sighash::sighashtype::{OutputsMode, SigHashType},
         DestinationSigError,
     },
-    Destination,
+    Destination, Transaction,
 };
 use crypto::key::hdkd::derivable::DerivationError;
 use wallet_types::signature_status::SignatureStatus;
@@ -45,10 +46,39 @@ pub enum SignerError {
     DestinationNotFromThisWallet,
     #[error("{0}")]
     SignArbitraryMessageError(#[from] SignArbitraryMessageError),
+    #[error("Refusing to sign input {0} with a sighash type that doesn't commit to any outputs")]
+    SigHashNoneNotAllowed(usize),
+    #[error("Input {0} is signed with sighash SINGLE but there is no output with the same index")]
+    SigHashSingleWithoutOutput(usize),
+    #[error("The number of sighash types {0} does not match the number of inputs {1}")]
+    SigHashTypesCountMismatch(usize, usize),
 }
 
 type SignerResult<T> = Result<T, SignerError>;
 
+/// Check that signing the input with the specified sighash type is safe.
+///
+/// Signatures that don't commit to any outputs would allow anyone to redirect the funds,
+/// so they are never produced by the wallet. SINGLE requires the output with the same index
+/// as the input to be present.
+pub fn check_sighash_type(
+    tx: &Transaction,
+    input_index: usize,
+    sighash_type: SigHashType,
+) -> Result<(), SignerError> {
+    match sighash_type.outputs_mode() {
+        OutputsMode::All => Ok(()),
+        OutputsMode::None => Err(SignerError::SigHashNoneNotAllowed(input_index)),
+        OutputsMode::Single => {
+            if input_index < tx.outputs().len() {
+                Ok(())
+            } else {
+                Err(SignerError::SigHashSingleWithoutOutput(input_index))
+            }
+        }
+    }
+}
+
 /// Signer trait responsible for signing transactions or challenges using a software or hardware
 /// wallet
 pub trait Signer {
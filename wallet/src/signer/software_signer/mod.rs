@@ -44,12 +44,13 @@ use crypto::key::{
 };
 use itertools::Itertools;
 use randomness::make_true_rng;
+use utils::ensure;
 use wallet_storage::WalletStorageReadUnlocked;
 use wallet_types::signature_status::SignatureStatus;
 
 use crate::key_chain::{make_account_path, AccountKeyChains, FoundPubKey, MasterKeyChain};
 
-use super::{Signer, SignerError, SignerResult};
+use super::{check_sighash_type, Signer, SignerError, SignerResult};
 
 pub struct SoftwareSigner<'a, T> {
     db_tx: &'a T,
@@ -93,6 +94,7 @@ impl<'a, T: WalletStorageReadUnlocked> SoftwareSigner<'a, T> {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn sign_input(
         &self,
         tx: &Transaction,
@@ -101,6 +103,7 @@ impl<'a, T: WalletStorageReadUnlocked> SoftwareSigner<'a, T> {
         inputs_utxo_refs: &[Option<&TxOutput>],
         key_chain: &impl AccountKeyChains,
         htlc_secret: &Option<HtlcSecret>,
+        sighash_type: SigHashType,
     ) -> SignerResult<(Option<InputWitness>, SignatureStatus)> {
         match destination {
             Destination::AnyoneCanSpend => Ok((
@@ -111,8 +114,8 @@ impl<'a, T: WalletStorageReadUnlocked> SoftwareSigner<'a, T> {
                 let sig = self
                     .get_private_key_for_destination(destination, key_chain)?
                     .map(|private_key| {
-                        let sighash_type =
-                            SigHashType::try_from(SigHashType::ALL).expect("Should not fail");
+                        check_sighash_type(tx, input_index, sighash_type)?;
+
                         match htlc_secret {
                             Some(htlc_secret) => produce_uniparty_signature_for_htlc_input(
                                 &private_key,
@@ -149,6 +152,8 @@ impl<'a, T: WalletStorageReadUnlocked> SoftwareSigner<'a, T> {
             }
             Destination::ClassicMultisig(_) => {
                 if let Some(challenge) = key_chain.find_multisig_challenge(destination) {
+                    check_sighash_type(tx, input_index, sighash_type)?;
+
                     let current_signatures =
                         AuthorizedClassicalMultisigSpend::new_empty(challenge.clone());
 
@@ -158,9 +163,11 @@ impl<'a, T: WalletStorageReadUnlocked> SoftwareSigner<'a, T> {
                         inputs_utxo_refs,
                         current_signatures,
                         key_chain,
+                        sighash_type,
                     )?;
 
-                    let signature = encode_multisig_spend(&sig, inputs_utxo_refs[input_index]);
+                    let signature =
+                        encode_multisig_spend(&sig, inputs_utxo_refs[input_index], sighash_type);
 
                     return Ok((Some(InputWitness::Standard(signature)), status));
                 }
@@ -178,13 +185,12 @@ impl<'a, T: WalletStorageReadUnlocked> SoftwareSigner<'a, T> {
         input_utxos: &[Option<&TxOutput>],
         mut current_signatures: AuthorizedClassicalMultisigSpend,
         key_chain: &impl AccountKeyChains,
+        sighash_type: SigHashType,
     ) -> SignerResult<(
         AuthorizedClassicalMultisigSpend,
         SignatureStatus,
         SignatureStatus,
     )> {
-        let sighash_type = SigHashType::try_from(SigHashType::ALL).expect("Should not fail");
-
         let challenge = current_signatures.challenge().clone();
         let sighash = signature_hash(sighash_type, tx, input_utxos, input_index)?;
         let required_signatures = challenge.min_required_signatures();
@@ -247,6 +253,11 @@ impl<'a, T: WalletStorageReadUnlocked> Signer for SoftwareSigner<'a, T> {
         Vec<SignatureStatus>,
         Vec<SignatureStatus>,
     )> {
+        ensure!(
+            ptx.sighash_types().len() == ptx.count_inputs(),
+            SignerError::SigHashTypesCountMismatch(ptx.sighash_types().len(), ptx.count_inputs())
+        );
+
         let inputs_utxo_refs: Vec<_> = ptx.input_utxos().iter().map(|u| u.as_ref()).collect();
 
         let (witnesses, prev_statuses, new_statuses) = ptx
@@ -255,16 +266,18 @@ impl<'a, T: WalletStorageReadUnlocked> Signer for SoftwareSigner<'a, T> {
             .enumerate()
             .zip(ptx.destinations())
             .zip(ptx.htlc_secrets())
-            .map(|(((i, witness), destination), htlc_secret)| match witness {
-                Some(w) => match w {
-                    InputWitness::NoSignature(_) => Ok((
-                        Some(w.clone()),
-                        SignatureStatus::FullySigned,
-                        SignatureStatus::FullySigned,
-                    )),
-                    InputWitness::Standard(sig) => match destination {
-                        Some(destination) => {
-                            let sig_verified =
+            .zip(ptx.sighash_types())
+            .map(
+                |((((i, witness), destination), htlc_secret), sighash_type)| match witness {
+                    Some(w) => match w {
+                        InputWitness::NoSignature(_) => Ok((
+                            Some(w.clone()),
+                            SignatureStatus::FullySigned,
+                            SignatureStatus::FullySigned,
+                        )),
+                        InputWitness::Standard(sig) => match destination {
+                            Some(destination) => {
+                                let sig_verified =
                                 tx_verifier::input_check::signature_only_check::verify_tx_signature(
                                     &self.chain_config,
                                     destination,
@@ -274,64 +287,75 @@ impl<'a, T: WalletStorageReadUnlocked> Signer for SoftwareSigner<'a, T> {
                                 )
                                 .is_ok();
 
-                            if sig_verified {
-                                Ok((
-                                    Some(w.clone()),
-                                    SignatureStatus::FullySigned,
-                                    SignatureStatus::FullySigned,
-                                ))
-                            } else if let Destination::ClassicMultisig(_) = destination {
-                                let sig_components =
-                                    decode_multisig_spend(sig, inputs_utxo_refs[i])
-                                        .map_err(SignerError::SigningError)?;
-
-                                let (sig_component, previous_status, final_status) = self
-                                    .sign_multisig_input(
-                                        ptx.tx(),
-                                        i,
-                                        &inputs_utxo_refs,
-                                        sig_components,
-                                        key_chain,
-                                    )?;
-
-                                let signature =
-                                    encode_multisig_spend(&sig_component, inputs_utxo_refs[i]);
-
-                                Ok((
-                                    Some(InputWitness::Standard(signature)),
-                                    previous_status,
-                                    final_status,
-                                ))
-                            } else {
-                                Ok((
-                                    None,
-                                    SignatureStatus::InvalidSignature,
-                                    SignatureStatus::NotSigned,
-                                ))
+                                if sig_verified {
+                                    Ok((
+                                        Some(w.clone()),
+                                        SignatureStatus::FullySigned,
+                                        SignatureStatus::FullySigned,
+                                    ))
+                                } else if let Destination::ClassicMultisig(_) = destination {
+                                    let sig_components =
+                                        decode_multisig_spend(sig, inputs_utxo_refs[i])
+                                            .map_err(SignerError::SigningError)?;
+
+                                    // The other signers have committed to the sighash type
+                                    // of the existing signature, so it must be kept
+                                    let sighash_type = sig.sighash_type();
+                                    check_sighash_type(ptx.tx(), i, sighash_type)?;
+
+                                    let (sig_component, previous_status, final_status) = self
+                                        .sign_multisig_input(
+                                            ptx.tx(),
+                                            i,
+                                            &inputs_utxo_refs,
+                                            sig_components,
+                                            key_chain,
+                                            sighash_type,
+                                        )?;
+
+                                    let signature = encode_multisig_spend(
+                                        &sig_component,
+                                        inputs_utxo_refs[i],
+                                        sighash_type,
+                                    );
+
+                                    Ok((
+                                        Some(InputWitness::Standard(signature)),
+                                        previous_status,
+                                        final_status,
+                                    ))
+                                } else {
+                                    Ok((
+                                        None,
+                                        SignatureStatus::InvalidSignature,
+                                        SignatureStatus::NotSigned,
+                                    ))
+                                }
                             }
+                            None => Ok((
+                                Some(w.clone()),
+                                SignatureStatus::UnknownSignature,
+                                SignatureStatus::UnknownSignature,
+                            )),
+                        },
+                    },
+                    None => match destination {
+                        Some(destination) => {
+                            let (sig, status) = self.sign_input(
+                                ptx.tx(),
+                                destination,
+                                i,
+                                &inputs_utxo_refs,
+                                key_chain,
+                                htlc_secret,
+                                *sighash_type,
+                            )?;
+                            Ok((sig, SignatureStatus::NotSigned, status))
                         }
-                        None => Ok((
-                            Some(w.clone()),
-                            SignatureStatus::UnknownSignature,
-                            SignatureStatus::UnknownSignature,
-                        )),
+                        None => Ok((None, SignatureStatus::NotSigned, SignatureStatus::NotSigned)),
                     },
                 },
-                None => match destination {
-                    Some(destination) => {
-                        let (sig, status) = self.sign_input(
-                            ptx.tx(),
-                            destination,
-                            i,
-                            &inputs_utxo_refs,
-                            key_chain,
-                            htlc_secret,
-                        )?;
-                        Ok((sig, SignatureStatus::NotSigned, status))
-                    }
-                    None => Ok((None, SignatureStatus::NotSigned, SignatureStatus::NotSigned)),
-                },
-            })
+            )
             .collect::<Result<Vec<_>, SignerError>>()?
             .into_iter()
             .multiunzip();
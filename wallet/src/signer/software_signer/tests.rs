@@ -18,11 +18,11 @@ use std::ops::{Add, Div, Mul, Sub};
 use super::*;
 use crate::destination_getters::{get_tx_output_destination, HtlcSpendingCondition};
 use crate::key_chain::{MasterKeyChain, LOOKAHEAD_SIZE};
-use crate::{Account, SendRequest};
+use crate::{Account, SendRequest, WalletError};
 use common::chain::config::create_regtest;
 use common::chain::output_value::OutputValue;
 use common::chain::timelock::OutputTimeLock;
-use common::chain::{GenBlock, TxInput};
+use common::chain::{GenBlock, SignedTransaction, TxInput};
 use common::primitives::amount::UnsignedIntType;
 use common::primitives::{Amount, Id, H256};
 use crypto::key::KeyKind;
@@ -155,3 +155,112 @@ fn sign_transaction(#[case] seed: Seed) {
         .unwrap();
     }
 }
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn sign_transaction_anyone_can_pay(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+
+    let config = Arc::new(create_regtest());
+    let db = Arc::new(Store::new(DefaultBackend::new_in_memory()).unwrap());
+    let mut db_tx = db.transaction_rw_unlocked(None).unwrap();
+
+    let master_key_chain = MasterKeyChain::new_from_mnemonic(
+        config.clone(),
+        &mut db_tx,
+        MNEMONIC,
+        None,
+        StoreSeedPhrase::DoNotStore,
+    )
+    .unwrap();
+
+    let key_chain = master_key_chain
+        .create_account_key_chain(&mut db_tx, DEFAULT_ACCOUNT_INDEX, LOOKAHEAD_SIZE)
+        .unwrap();
+    let mut account = Account::new(config.clone(), &mut db_tx, key_chain, None).unwrap();
+
+    let our_utxo = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(rng.gen_range(1000..10000))),
+        account.get_new_address(&mut db_tx, ReceiveFunds).unwrap().1.into_object(),
+    );
+    let our_input = TxInput::from_utxo(
+        Id::<Transaction>::new(H256::random_using(&mut rng)).into(),
+        rng.next_u32(),
+    );
+
+    let (_dest_prv, dest_pub) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+    let output = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(rng.gen_range(10000..20000))),
+        Destination::PublicKey(dest_pub),
+    );
+
+    let all_anyone_can_pay =
+        SigHashType::try_from(SigHashType::ALL | SigHashType::ANYONECANPAY).unwrap();
+    let req = SendRequest::new()
+        .with_inputs([(our_input.clone(), our_utxo.clone())], &|_| None)
+        .unwrap()
+        .with_outputs([output.clone()])
+        .with_sighash_type(0, all_anyone_can_pay)
+        .unwrap();
+    let ptx = req.into_partially_signed_tx().unwrap();
+
+    let signer = SoftwareSigner::new(&db_tx, config.clone(), DEFAULT_ACCOUNT_INDEX);
+    let (ptx, _, _) = signer.sign_tx(ptx, account.key_chain()).unwrap();
+    assert!(ptx.all_signatures_available());
+    let our_witness = ptx.witnesses()[0].clone().unwrap();
+
+    // Another party adds an input of their own, the signature must still be valid
+    let other_utxo = TxOutput::Transfer(
+        OutputValue::Coin(Amount::from_atoms(rng.gen_range(10000..20000))),
+        Destination::AnyoneCanSpend,
+    );
+    let other_input = TxInput::from_utxo(
+        Id::<Transaction>::new(H256::random_using(&mut rng)).into(),
+        rng.next_u32(),
+    );
+    let extended_tx = SignedTransaction::new(
+        Transaction::new(0, vec![our_input.clone(), other_input], vec![output]).unwrap(),
+        vec![our_witness, InputWitness::NoSignature(None)],
+    )
+    .unwrap();
+
+    let destination =
+        get_tx_output_destination(&our_utxo, &|_| None, HtlcSpendingCondition::Skip).unwrap();
+    tx_verifier::input_check::signature_only_check::verify_tx_signature(
+        &config,
+        &destination,
+        &extended_tx,
+        &[Some(&our_utxo), Some(&other_utxo)],
+        0,
+    )
+    .unwrap();
+
+    // Sighash types that don't commit to the outputs are refused
+    let none = SigHashType::try_from(SigHashType::NONE).unwrap();
+    let err = SendRequest::new()
+        .with_inputs([(our_input.clone(), our_utxo.clone())], &|_| None)
+        .unwrap()
+        .with_sighash_type(0, none)
+        .unwrap()
+        .into_partially_signed_tx()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        WalletError::SignerError(SignerError::SigHashNoneNotAllowed(0))
+    );
+
+    // SINGLE requires an output with the same index
+    let single = SigHashType::try_from(SigHashType::SINGLE).unwrap();
+    let err = SendRequest::new()
+        .with_inputs([(our_input, our_utxo)], &|_| None)
+        .unwrap()
+        .with_sighash_type(0, single)
+        .unwrap()
+        .into_partially_signed_tx()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        WalletError::SignerError(SignerError::SigHashSingleWithoutOutput(0))
+    );
+}
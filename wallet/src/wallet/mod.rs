@@ -14,21 +14,25 @@
 // limitations under the License.
 
 use std::collections::{BTreeMap, BTreeSet};
+use std::num::NonZeroU8;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use crate::account::transaction_list::TransactionList;
+use crate::account::transaction_list::{TransactionInfo, TransactionList, TxDescriptionItem};
 use crate::account::{
     currency_grouper::Currency, CurrentFeeRate, DelegationData, PoolData, TransactionToSign,
     UnconfirmedTokenInfo, UtxoSelectorError,
 };
-use crate::account::{CoinSelectionAlgo, TxInfo};
+use crate::account::{
+    CoinSelectionAlgo, FeeBumpKind, StakedBlock, TrackedUtxo, TxChainPosition, TxInfo,
+};
 use crate::key_chain::{
     make_account_path, make_path_to_vrf_key, KeyChainError, MasterKeyChain, LOOKAHEAD_SIZE,
     VRF_INDEX,
 };
 use crate::send_request::{
-    make_issue_token_outputs, IssueNftArguments, SelectedInputs, StakePoolDataArguments,
+    make_address_output, make_address_output_token, make_issue_token_outputs, IssueNftArguments,
+    SelectedInputs, StakePoolDataArguments,
 };
 use crate::signer::software_signer::SoftwareSigner;
 use crate::signer::{Signer, SignerError};
@@ -54,9 +58,11 @@ use common::chain::{
     SignedTransaction, Transaction, TransactionCreationError, TxInput, TxOutput, UtxoOutPoint,
 };
 use common::primitives::id::{hash_encoded, WithId};
+use common::primitives::time::get_time;
 use common::primitives::{Amount, BlockHeight, Id, H256};
 use common::size_estimation::SizeEstimationError;
 use consensus::PoSGenerateBlockInputData;
+use crypto::key::extended::ExtendedPublicKey;
 use crypto::key::hdkd::child_number::ChildNumber;
 use crypto::key::hdkd::derivable::Derivable;
 use crypto::key::hdkd::u31::U31;
@@ -64,24 +70,42 @@ use crypto::key::{PrivateKey, PublicKey};
 use crypto::vrf::VRFPublicKey;
 use mempool::FeeRate;
 use pos_accounting::make_delegation_id;
+use serialization::{DecodeAll, Encode};
 use tx_verifier::error::TokenIssuanceError;
 use tx_verifier::{check_transaction, CheckTransactionError};
 use utils::ensure;
+use utils::maybe_encrypted::{MaybeEncrypted, MaybeEncryptedError};
 pub use wallet_storage::Error;
 use wallet_storage::{
     DefaultBackend, Store, StoreTxRw, StoreTxRwUnlocked, TransactionRoLocked, TransactionRwLocked,
     TransactionRwUnlocked, Transactional, WalletStorageReadLocked, WalletStorageReadUnlocked,
     WalletStorageWriteLocked, WalletStorageWriteUnlocked,
 };
-use wallet_types::account_info::{StandaloneAddressDetails, StandaloneAddresses};
+use wallet_types::account_id::{AccountAddress, AccountPoolId, AccountScheduledPaymentId};
+use wallet_types::account_info::{
+    ChangePolicy, DustProtection, ScheduledPayment, StandaloneAddressDetails, StandaloneAddresses,
+    StandaloneWatchOnlyKey, WatchedPool,
+};
+use wallet_types::address_book::Payee;
+use wallet_types::broadcast_queue::{BroadcastPolicy, BroadcastQueueEntry};
 use wallet_types::chain_info::ChainInfo;
+use wallet_types::internal_transfer::InternalTransfer;
+use wallet_types::multisig_account::{MultisigAccountDescriptor, MultisigAccountDescriptorError};
 use wallet_types::seed_phrase::{SerializableSeedPhrase, StoreSeedPhrase};
 use wallet_types::signature_status::SignatureStatus;
+use wallet_types::signing_log::{SigningLogEntry, SigningRequester};
+use wallet_types::token_trust::TokenTrust;
 use wallet_types::utxo_types::{UtxoStates, UtxoTypes};
 use wallet_types::wallet_tx::{TxData, TxState};
 use wallet_types::wallet_type::WalletType;
+use wallet_types::watch_only_export::{
+    EncryptedWatchOnlyExport, WatchOnlyAccount, WatchOnlyExport,
+};
 use wallet_types::with_locked::WithLocked;
-use wallet_types::{AccountId, AccountKeyPurposeId, BlockInfo, KeyPurpose, KeychainUsageState};
+use wallet_types::{
+    AccountId, AccountKeyPurposeId, AccountWalletTxId, BlockInfo, KeyPurpose, KeychainUsageState,
+    WalletTx,
+};
 
 pub const WALLET_VERSION_UNINITIALIZED: u32 = 0;
 pub const WALLET_VERSION_V1: u32 = 1;
@@ -91,7 +115,12 @@ pub const WALLET_VERSION_V4: u32 = 4;
 pub const WALLET_VERSION_V5: u32 = 5;
 pub const WALLET_VERSION_V6: u32 = 6;
 pub const WALLET_VERSION_V7: u32 = 7;
-pub const CURRENT_WALLET_VERSION: u32 = WALLET_VERSION_V7;
+pub const WALLET_VERSION_V8: u32 = 8;
+pub const CURRENT_WALLET_VERSION: u32 = WALLET_VERSION_V8;
+
+/// The maximum number of addresses per key purpose that can be imported from an extended
+/// public key at once
+pub const MAX_EXTENDED_PUBLIC_KEY_IMPORT_ADDRESS_COUNT: u32 = 1000;
 
 /// Wallet errors
 #[derive(thiserror::Error, Debug, Eq, PartialEq)]
@@ -166,12 +195,40 @@ pub enum WalletError {
     CoinSelectionError(#[from] UtxoSelectorError),
     #[error("Cannot abandon a transaction in {0} state")]
     CannotAbandonTransaction(TxState),
+    #[error("Cannot replace a transaction in {0} state")]
+    CannotReplaceTransaction(TxState),
+    #[error("Cannot bump the fee of a transaction in {0} state")]
+    CannotBumpTransactionFee(TxState),
+    #[error("The transaction already pays the fee {0:?}, which is not less than the fee {1:?} for the new fee rate")]
+    FeeBumpNotHigher(Amount, Amount),
+    #[error("The transaction {0} has no outputs of this account to pay the higher fee with")]
+    NoOutputsToBumpFee(Id<Transaction>),
     #[error("Transaction with Id {0} not found")]
     CannotFindTransactionWithId(Id<Transaction>),
     #[error("Address error: {0}")]
     AddressError(#[from] AddressError),
     #[error("Unknown pool id {0}")]
     UnknownPoolId(PoolId),
+    #[error("Pool id {0} is not being watched")]
+    UnknownWatchedPoolId(PoolId),
+    #[error("Pool id {0} is already being watched")]
+    PoolAlreadyWatched(PoolId),
+    #[error("Pool id {0} is controlled by this account and cannot be watched")]
+    CannotWatchOwnedPool(PoolId),
+    #[error("Unknown scheduled payment id {0}")]
+    UnknownScheduledPaymentId(u64),
+    #[error("The interval of a scheduled payment must be greater than zero")]
+    InvalidScheduledPaymentInterval,
+    #[error("The amount of a scheduled payment must be greater than zero")]
+    InvalidScheduledPaymentAmount,
+    #[error("Multisig account descriptor error: {0}")]
+    MultisigAccountDescriptorError(#[from] MultisigAccountDescriptorError),
+    #[error("The account is not a cosigner of the multisig account")]
+    NotMultisigAccountCosigner,
+    #[error("Cannot import the extended public key of the account itself")]
+    CannotImportOwnExtendedPublicKey,
+    #[error("Cannot import {0} addresses from an extended public key, the maximum is {1}")]
+    ExtendedPublicKeyImportAddressCountTooLarge(u32, u32),
     #[error("Cannot find UTXO {0:?}")]
     CannotFindUtxo(UtxoOutPoint),
     #[error("Selected UTXO {0:?} is already consumed")]
@@ -180,6 +237,12 @@ pub enum WalletError {
     LockedUtxo(UtxoOutPoint),
     #[error("Selected UTXO {0:?} is a token v0 and cannot be used")]
     TokenV0Utxo(UtxoOutPoint),
+    #[error("UTXO {0:?} is not flagged by the dust protection")]
+    UtxoNotFlaggedAsDust(UtxoOutPoint),
+    #[error("Selected UTXO {0:?} is frozen")]
+    FrozenUtxo(UtxoOutPoint),
+    #[error("UTXO {0:?} is not frozen")]
+    UtxoNotFrozen(UtxoOutPoint),
     #[error("Cannot change a Locked Token supply")]
     CannotChangeLockedTokenSupply,
     #[error("Cannot lock Token supply in state: {0}")]
@@ -234,11 +297,71 @@ pub enum WalletError {
     StandaloneAddressNotFound(RpcAddress<Destination>),
     #[error("Signer error: {0}")]
     SignerError(#[from] SignerError),
+    #[error("Invalid input index {0}")]
+    InvalidInputIndex(usize),
+    #[error("Invalid watch-only export data")]
+    InvalidWatchOnlyExport,
+    #[error("Failed to decrypt the watch-only export, the passphrase may be wrong")]
+    WatchOnlyExportDecryptionFailed,
+    #[error("The watch-only export belongs to a different wallet")]
+    WatchOnlyExportWalletMismatch,
+    #[error("Cannot create a wallet from an incremental watch-only export")]
+    IncrementalWatchOnlyExport,
+    #[error("The watch-only update starts at height {0}, but the wallet is only synced up to height {1}")]
+    WatchOnlyUpdateGap(BlockHeight, BlockHeight),
+    #[error("The wallet is not a watch-only replica")]
+    NotWatchOnlyWallet,
+    #[error("This operation is not supported by a watch-only wallet")]
+    WatchOnlyWallet,
+    #[error("The payee name cannot be empty")]
+    EmptyPayeeName,
+    #[error("The payee {0} is not found in the address book")]
+    PayeeNotFound(String),
+    #[error("Cannot transfer funds from an account to itself")]
+    TransferToSameAccount,
+    #[error("The address {0} has not been issued by the account")]
+    AddressNotIssued(RpcAddress<Destination>),
+    #[error("The address label cannot be empty")]
+    EmptyAddressLabel,
+}
+
+impl WalletError {
+    /// Whether the error means that the wallet DB is damaged, in which case some of its data may
+    /// still be recovered with [Wallet::salvage_wallet]
+    pub fn is_db_corruption(&self) -> bool {
+        fn is_storage_corruption(err: &wallet_storage::Error) -> bool {
+            match err {
+                wallet_storage::Error::StorageError(storage::error::Error::Fatal(
+                    storage::error::Fatal::DatabaseCorrupted,
+                ))
+                | wallet_storage::Error::WalletSanityErrorInvalidRootKeyCount(_)
+                | wallet_storage::Error::CannotDecodeAddress(_) => true,
+                _ => false,
+            }
+        }
+
+        match self {
+            WalletError::DatabaseError(err)
+            | WalletError::KeyChainError(KeyChainError::DatabaseError(err)) => {
+                is_storage_corruption(err)
+            }
+            _ => false,
+        }
+    }
 }
 
 /// Result type used for the wallet
 pub type WalletResult<T> = Result<T, WalletError>;
 
+/// The outcome of salvaging a damaged wallet DB with [Wallet::salvage_wallet]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SalvageReport {
+    /// The accounts that have been recreated in the new wallet
+    pub recovered_accounts: Vec<U31>,
+    /// Data that could not be recovered; should be shown to the user
+    pub warnings: Vec<String>,
+}
+
 /// Wallet tracks all pools that can be decommissioned or used for staking by the wallet.
 /// Filter allows to query for a specific set of pools.
 pub enum WalletPoolsFilter {
@@ -509,6 +632,45 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(())
     }
 
+    /// Migrate the wallet DB from version 7 to version 8
+    /// * put the unconfirmed transactions of the accounts into the broadcast queue,
+    ///   as they are not rebroadcast otherwise
+    fn migration_v8(db: &Store<B>) -> WalletResult<()> {
+        let mut db_tx = db.transaction_rw(None)?;
+        let now = BlockTimestamp::from_time(get_time());
+
+        for account_id in db_tx.get_accounts_info()?.into_keys() {
+            for (_, wallet_tx) in db_tx.get_transactions(&account_id)? {
+                let tx = match wallet_tx {
+                    WalletTx::Tx(tx) => tx,
+                    WalletTx::Block(_) => continue,
+                };
+                match tx.state() {
+                    TxState::InMempool(_) | TxState::Inactive(_) => {}
+                    TxState::Confirmed(_, _, _) | TxState::Conflicted(_) | TxState::Abandoned => {
+                        continue
+                    }
+                }
+
+                let entry = BroadcastQueueEntry::new(
+                    tx.get_signed_transaction().clone(),
+                    BroadcastPolicy::default(),
+                    now,
+                );
+                db_tx.set_broadcast_queue_entry(&tx.get_transaction().get_id(), &entry)?;
+            }
+        }
+
+        db_tx.set_storage_version(WALLET_VERSION_V8)?;
+        db_tx.commit()?;
+
+        logging::log::info!(
+            "Successfully migrated wallet database to latest version {}",
+            WALLET_VERSION_V8
+        );
+        Ok(())
+    }
+
     /// Check the wallet DB version and perform any migrations needed
     fn check_and_migrate_db<F: Fn(u32) -> Result<(), WalletError>>(
         db: &Store<B>,
@@ -544,6 +706,10 @@ impl<B: storage::Backend> Wallet<B> {
                 pre_migration(WALLET_VERSION_V6)?;
                 Self::migration_v7(db, chain_config.clone(), wallet_type)?;
             }
+            WALLET_VERSION_V7 => {
+                pre_migration(WALLET_VERSION_V7)?;
+                Self::migration_v8(db)?;
+            }
             CURRENT_WALLET_VERSION => return Ok(()),
             unsupported_version => {
                 return Err(WalletError::UnsupportedWalletVersion(unsupported_version))
@@ -649,6 +815,13 @@ impl<B: storage::Backend> Wallet<B> {
             )?;
             db_tx
                 .set_vrf_keychain_usage_state(&id.clone(), &KeychainUsageState::new(None, None))?;
+
+            // the blocks created by the watched pools will be found again during the rescan
+            for (pool_id, watched_pool) in db_tx.get_account_watched_pools(&id)? {
+                let watched_pool =
+                    WatchedPool::new(watched_pool.label, watched_pool.decommission_key);
+                db_tx.set_watched_pool(&AccountPoolId::new(id.clone(), pool_id), &watched_pool)?;
+            }
         }
 
         Ok(())
@@ -755,10 +928,633 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    /// Salvage the data of a wallet whose DB has failed the integrity checks when loading.
+    ///
+    /// The key chain material and the account metadata that can still be read from `db` are
+    /// written into `new_db`, from which the new wallet is then loaded. Transactions are not
+    /// copied and all accounts start from the genesis block, so the blockchain has to be rescanned.
+    /// Anything that can't be read is skipped and reported in the returned warnings.
+    pub fn salvage_wallet(
+        chain_config: Arc<ChainConfig>,
+        mut db: Store<B>,
+        mut new_db: Store<B>,
+        password: Option<String>,
+        wallet_type: WalletType,
+    ) -> WalletResult<(Self, SalvageReport)> {
+        if let Some(password) = &password {
+            db.unlock_private_keys(password)?;
+        }
+
+        let mut report = SalvageReport::default();
+        let old_tx = db.transaction_ro_unlocked()?;
+
+        // Without the root keys no account can be derived, so there is nothing to salvage
+        let root_keys = old_tx.get_root_key()?.ok_or(WalletError::WalletNotInitialized)?;
+
+        let seed_phrase = old_tx.get_seed_phrase().unwrap_or_else(|err| {
+            report.warnings.push(format!("The seed phrase could not be recovered: {err}"));
+            None
+        });
+
+        match old_tx.get_chain_info() {
+            Ok(chain_info) => ensure!(
+                chain_info.is_same(chain_config.as_ref()),
+                WalletError::DifferentChainType
+            ),
+            Err(err) => report.warnings.push(format!(
+                "The chain info could not be read, assuming the wallet belongs to the current chain: {err}"
+            )),
+        }
+
+        match old_tx.get_wallet_type() {
+            Ok(this_wallet_type) => ensure!(
+                this_wallet_type == wallet_type,
+                WalletError::DifferentWalletType(wallet_type, this_wallet_type)
+            ),
+            Err(err) => report.warnings.push(format!(
+                "The wallet type could not be read, assuming a {wallet_type} wallet: {err}"
+            )),
+        }
+
+        let lookahead_size = old_tx.get_lookahead_size().unwrap_or_else(|err| {
+            report.warnings.push(format!(
+                "The lookahead size could not be read, using the default of {LOOKAHEAD_SIZE}: {err}"
+            ));
+            LOOKAHEAD_SIZE
+        });
+
+        let accounts_info = old_tx.get_accounts_info().unwrap_or_else(|err| {
+            report.warnings.push(format!(
+                "The account metadata could not be read, only the default account is recreated: {err}"
+            ));
+            BTreeMap::new()
+        });
+        let account_names: BTreeMap<U31, Option<String>> = accounts_info
+            .values()
+            .map(|info| (info.account_index(), info.name().clone()))
+            .collect();
+
+        if password.is_some() {
+            // Set up the encryption before anything is written, so the private keys are written
+            // encrypted and never end up in the new DB file in plain text.
+            // Leaves the new DB unlocked with the same password.
+            new_db.encrypt_private_keys(&password)?;
+        }
+
+        let mut new_tx = new_db.transaction_rw_unlocked(None)?;
+        new_tx.set_storage_version(CURRENT_WALLET_VERSION)?;
+        new_tx.set_chain_info(&ChainInfo::new(chain_config.as_ref()))?;
+        new_tx.set_lookahead_size(lookahead_size)?;
+        new_tx.set_wallet_type(wallet_type)?;
+        new_tx.set_root_key(&root_keys)?;
+        if let Some(seed_phrase) = seed_phrase {
+            new_tx.set_seed_phrase(seed_phrase)?;
+        }
+
+        let key_chain = MasterKeyChain::new_from_existing_database(chain_config.clone(), &new_tx)?;
+
+        // Account indexes have no gaps and the last account is always the unused one,
+        // so recreate all of them up to the last known index, but at least the default
+        // and the unused accounts.
+        let last_account_index = std::cmp::max(
+            account_names.keys().last().copied().unwrap_or(U31::ONE),
+            U31::ONE,
+        );
+        for index in 0..=last_account_index.into_u32() {
+            let index = U31::from_u32(index).expect("not greater than the last index");
+            let name = account_names.get(&index).cloned().unwrap_or_else(|| {
+                if !accounts_info.is_empty() {
+                    report.warnings.push(format!(
+                        "The metadata of account {index} could not be found, recreating it without a name"
+                    ));
+                }
+                None
+            });
+            Self::create_next_unused_account(
+                index,
+                chain_config.clone(),
+                &key_chain,
+                &mut new_tx,
+                name,
+            )?;
+            report.recovered_accounts.push(index);
+        }
+        // The last one is the unused account
+        report.recovered_accounts.pop();
+
+        // The account ids are derived from the root key, so they are the same in the new wallet
+        for (account_id, info) in &accounts_info {
+            let index = info.account_index();
+            let mut warn = |what: &str, err: wallet_storage::Error| {
+                report.warnings.push(format!(
+                    "The {what} of account {index} could not be recovered: {err}"
+                ));
+            };
+
+            match old_tx.get_account_standalone_watch_only_keys(account_id) {
+                Ok(keys) => {
+                    for (destination, key) in keys {
+                        new_tx.set_standalone_watch_only_key(
+                            &AccountAddress::new(account_id.clone(), destination),
+                            &key,
+                        )?;
+                    }
+                }
+                Err(err) => warn("standalone watch-only addresses", err),
+            }
+
+            match old_tx.get_account_standalone_multisig_keys(account_id) {
+                Ok(keys) => {
+                    for (destination, key) in keys {
+                        new_tx.set_standalone_multisig_key(
+                            &AccountAddress::new(account_id.clone(), destination),
+                            &key,
+                        )?;
+                    }
+                }
+                Err(err) => warn("standalone multisig addresses", err),
+            }
+
+            match old_tx.get_account_standalone_private_keys(account_id) {
+                Ok(keys) => {
+                    for (id, label) in keys {
+                        match old_tx.get_account_standalone_private_key(&id) {
+                            Ok(Some(private_key)) => {
+                                new_tx.set_standalone_private_key(&id, &private_key, label)?;
+                            }
+                            Ok(None) => warn(
+                                "standalone private key",
+                                wallet_storage::Error::WalletDbInconsistentState,
+                            ),
+                            Err(err) => warn("standalone private key", err),
+                        }
+                    }
+                }
+                Err(err) => warn("standalone private keys", err),
+            }
+
+            match old_tx.get_account_watched_pools(account_id) {
+                Ok(pools) => {
+                    for (pool_id, pool) in pools {
+                        new_tx.set_watched_pool(
+                            &AccountPoolId::new(account_id.clone(), pool_id),
+                            &pool,
+                        )?;
+                    }
+                }
+                Err(err) => warn("watched pools", err),
+            }
+
+            match old_tx.get_account_scheduled_payments(account_id) {
+                Ok(payments) => {
+                    for (payment_id, payment) in payments {
+                        new_tx.set_scheduled_payment(
+                            &AccountScheduledPaymentId::new(account_id.clone(), payment_id),
+                            &payment,
+                        )?;
+                    }
+                }
+                Err(err) => warn("scheduled payments", err),
+            }
+
+            match old_tx.get_account_next_scheduled_payment_id(account_id) {
+                Ok(Some(payment_id)) => {
+                    new_tx.set_account_next_scheduled_payment_id(account_id, payment_id)?;
+                }
+                Ok(None) => {}
+                Err(err) => warn("scheduled payment counter", err),
+            }
+
+            match old_tx.get_account_dust_protection(account_id) {
+                Ok(Some(dust_protection)) => {
+                    new_tx.set_account_dust_protection(account_id, &dust_protection)?;
+                }
+                Ok(None) => {}
+                Err(err) => warn("dust protection", err),
+            }
+
+            match old_tx.get_account_frozen_utxos(account_id) {
+                Ok(frozen_utxos) => {
+                    new_tx.set_account_frozen_utxos(account_id, &frozen_utxos)?;
+                }
+                Err(err) => warn("frozen UTXOs", err),
+            }
+
+            match old_tx.get_account_change_policy(account_id) {
+                Ok(Some(change_policy)) => {
+                    new_tx.set_account_change_policy(account_id, &change_policy)?;
+                }
+                Ok(None) => {}
+                Err(err) => warn("change policy", err),
+            }
+
+            match old_tx.get_account_address_labels(account_id) {
+                Ok(labels) => {
+                    for (address, label) in labels {
+                        new_tx.set_address_label(
+                            &AccountAddress::new(account_id.clone(), address),
+                            &label,
+                        )?;
+                    }
+                }
+                Err(err) => warn("address labels", err),
+            }
+        }
+
+        match old_tx.get_signing_log() {
+            Ok(entries) => {
+                for entry in entries {
+                    new_tx.append_signing_log_entry(&entry)?;
+                }
+            }
+            Err(err) => {
+                report.warnings.push(format!("The signing log could not be recovered: {err}"))
+            }
+        }
+
+        match old_tx.get_token_trust_list() {
+            Ok(trust_list) => {
+                for (token_id, trust) in trust_list {
+                    new_tx.set_token_trust(&token_id, trust)?;
+                }
+            }
+            Err(err) => report.warnings.push(format!(
+                "The token trust list could not be recovered: {err}"
+            )),
+        }
+
+        match old_tx.get_payees() {
+            Ok(payees) => {
+                for (name, payee) in payees {
+                    new_tx.set_payee(&name, &payee)?;
+                }
+            }
+            Err(err) => {
+                report.warnings.push(format!("The address book could not be recovered: {err}"))
+            }
+        }
+
+        match old_tx.get_broadcast_queue() {
+            Ok(queue) => {
+                for (tx_id, entry) in queue {
+                    new_tx.set_broadcast_queue_entry(&tx_id, &entry)?;
+                }
+            }
+            Err(err) => report
+                .warnings
+                .push(format!("The broadcast queue could not be recovered: {err}")),
+        }
+
+        match old_tx.get_internal_transfers() {
+            Ok(transfers) => {
+                for (tx_id, transfer) in transfers {
+                    new_tx.set_internal_transfer(&tx_id, &transfer)?;
+                }
+            }
+            Err(err) => report.warnings.push(format!(
+                "The internal transfers could not be recovered: {err}"
+            )),
+        }
+
+        new_tx.commit()?;
+        old_tx.close();
+
+        for warning in &report.warnings {
+            logging::log::warn!("Wallet salvage: {warning}");
+        }
+
+        let wallet = Self::load_wallet(chain_config, new_db, None, |_| Ok(()), wallet_type, false)?;
+
+        Ok((wallet, report))
+    }
+
+    /// Export the public data of the wallet, encrypted with the passphrase.
+    ///
+    /// The export contains the accounts' public keys, labels and transactions, but none of the
+    /// private keys, so it can be imported with [Wallet::create_from_watch_only_export] to
+    /// quickly create a view-only replica of the wallet on another device.
+    /// If `since_height` is set, only the transactions confirmed above that height are exported
+    /// and the result can be applied to an existing replica with [Wallet::apply_watch_only_update].
+    pub fn export_watch_only(
+        &self,
+        passphrase: &String,
+        since_height: Option<BlockHeight>,
+    ) -> WalletResult<Vec<u8>> {
+        let db_tx = self.db.transaction_ro()?;
+
+        let usage_state =
+            |id: &AccountKeyPurposeId, what: &'static str| -> WalletResult<KeychainUsageState> {
+                Ok(db_tx
+                    .get_keychain_usage_state(id)?
+                    .ok_or(KeyChainError::MissingDatabaseProperty(what))?)
+            };
+
+        let accounts = db_tx
+            .get_accounts_info()?
+            .into_iter()
+            .map(|(account_id, info)| -> WalletResult<_> {
+                let vrf_keys = db_tx
+                    .get_account_vrf_public_keys(&account_id)?
+                    .ok_or(KeyChainError::MissingDatabaseProperty("VRF public keys"))?;
+                let receive_usage = usage_state(
+                    &AccountKeyPurposeId::new(account_id.clone(), KeyPurpose::ReceiveFunds),
+                    "ReceiveFunds usage state",
+                )?;
+                let change_usage = usage_state(
+                    &AccountKeyPurposeId::new(account_id.clone(), KeyPurpose::Change),
+                    "Change usage state",
+                )?;
+                let vrf_usage = db_tx
+                    .get_vrf_keychain_usage_state(&account_id)?
+                    .ok_or(KeyChainError::MissingDatabaseProperty("VRF usage state"))?;
+                let unconfirmed_tx_counter =
+                    db_tx.get_account_unconfirmed_tx_counter(&account_id)?.unwrap_or(0);
+
+                // The standalone private keys can only be watched by the replica
+                let standalone_watch_only_keys = db_tx
+                    .get_account_standalone_watch_only_keys(&account_id)?
+                    .into_iter()
+                    .chain(
+                        db_tx
+                            .get_account_standalone_private_keys(&account_id)?
+                            .into_iter()
+                            .flat_map(|(id, label)| {
+                                let public_key = id.into_item_id();
+                                let public_key_hash = (&public_key).into();
+                                [
+                                    (
+                                        Destination::PublicKey(public_key),
+                                        StandaloneWatchOnlyKey {
+                                            label: label.clone(),
+                                        },
+                                    ),
+                                    (
+                                        Destination::PublicKeyHash(public_key_hash),
+                                        StandaloneWatchOnlyKey { label },
+                                    ),
+                                ]
+                            }),
+                    )
+                    .collect();
+                let standalone_multisig_keys =
+                    db_tx.get_account_standalone_multisig_keys(&account_id)?.into_iter().collect();
+                let watched_pools =
+                    db_tx.get_account_watched_pools(&account_id)?.into_iter().collect();
+
+                let transactions = db_tx
+                    .get_transactions(&account_id)?
+                    .into_iter()
+                    .map(|(_, tx)| tx)
+                    .filter(|tx| match (since_height, tx.state().block_height()) {
+                        (Some(since_height), Some(height)) => height > since_height,
+                        (None, _) | (_, None) => true,
+                    })
+                    .collect();
+
+                Ok(WatchOnlyAccount {
+                    info,
+                    vrf_keys,
+                    receive_usage,
+                    change_usage,
+                    vrf_usage,
+                    unconfirmed_tx_counter,
+                    standalone_watch_only_keys,
+                    standalone_multisig_keys,
+                    watched_pools,
+                    transactions,
+                })
+            })
+            .collect::<WalletResult<Vec<_>>>()?;
+
+        let export = WatchOnlyExport {
+            wallet_id: self.wallet_info().0,
+            chain_info: db_tx.get_chain_info()?,
+            lookahead_size: db_tx.get_lookahead_size()?,
+            since_height,
+            accounts,
+            token_trust: db_tx.get_token_trust_list()?.into_iter().collect(),
+        };
+
+        let (sym_key, kdf_challenge) = wallet_storage::password_to_sym_key(passphrase)?;
+        let encrypted = EncryptedWatchOnlyExport {
+            kdf_challenge,
+            data: MaybeEncrypted::new(&export, &Some(sym_key)),
+        };
+
+        Ok(encrypted.encode())
+    }
+
+    fn decrypt_watch_only_export(
+        chain_config: &ChainConfig,
+        data: &[u8],
+        passphrase: &String,
+    ) -> WalletResult<WatchOnlyExport> {
+        let EncryptedWatchOnlyExport {
+            kdf_challenge,
+            data,
+        } = EncryptedWatchOnlyExport::decode_all(&mut &data[..])
+            .map_err(|_| WalletError::InvalidWatchOnlyExport)?;
+
+        let sym_key = wallet_storage::challenge_to_sym_key(passphrase, kdf_challenge)?;
+        let export = data.try_take(&Some(sym_key)).map_err(|err| match err {
+            MaybeEncryptedError::DecryptionError(_) => WalletError::WatchOnlyExportDecryptionFailed,
+            MaybeEncryptedError::DecodingError(_) => WalletError::InvalidWatchOnlyExport,
+        })?;
+
+        ensure!(
+            export.chain_info.is_same(chain_config),
+            WalletError::DifferentChainType
+        );
+        ensure!(
+            !export.accounts.is_empty(),
+            WalletError::InvalidWatchOnlyExport
+        );
+
+        Ok(export)
+    }
+
+    /// Write the data of a watch-only export into the DB, merging it with the existing data
+    /// of a replica, and load the accounts from it
+    fn write_watch_only_export_and_load(
+        chain_config: Arc<ChainConfig>,
+        db_tx: &mut impl WalletStorageWriteLocked,
+        export: WatchOnlyExport,
+    ) -> WalletResult<BTreeMap<U31, Account>> {
+        let existing_accounts = db_tx.get_accounts_info()?;
+
+        let merge_usage = |existing: Option<KeychainUsageState>, new: KeychainUsageState| {
+            let mut usage = existing.unwrap_or_default();
+            if let Some(last_issued) = new.last_issued() {
+                usage.increment_up_to_last_issued(last_issued);
+            }
+            if let Some(last_used) = new.last_used() {
+                usage.increment_up_to_last_used(last_used);
+            }
+            usage
+        };
+
+        db_tx.set_lookahead_size(export.lookahead_size)?;
+
+        for account in export.accounts {
+            let account_id = AccountId::new_from_xpub(account.info.account_key());
+            let mut info = account.info;
+
+            // Don't go back if the replica has already synced further than the exported wallet
+            if let Some(existing) = existing_accounts.get(&account_id) {
+                if existing.best_block_height() > info.best_block_height() {
+                    info.update_best_block(existing.best_block_height(), existing.best_block_id());
+                }
+            }
+            db_tx.set_account(&account_id, &info)?;
+            db_tx.set_account_vrf_public_keys(&account_id, &account.vrf_keys)?;
+
+            for (purpose, usage) in [
+                (KeyPurpose::ReceiveFunds, account.receive_usage),
+                (KeyPurpose::Change, account.change_usage),
+            ] {
+                let id = AccountKeyPurposeId::new(account_id.clone(), purpose);
+                let usage = merge_usage(db_tx.get_keychain_usage_state(&id)?, usage);
+                db_tx.set_keychain_usage_state(&id, &usage)?;
+            }
+            let vrf_usage = merge_usage(
+                db_tx.get_vrf_keychain_usage_state(&account_id)?,
+                account.vrf_usage,
+            );
+            db_tx.set_vrf_keychain_usage_state(&account_id, &vrf_usage)?;
+
+            let unconfirmed_tx_counter = std::cmp::max(
+                db_tx.get_account_unconfirmed_tx_counter(&account_id)?.unwrap_or(0),
+                account.unconfirmed_tx_counter,
+            );
+            db_tx.set_account_unconfirmed_tx_counter(&account_id, unconfirmed_tx_counter)?;
+
+            for (destination, key) in account.standalone_watch_only_keys {
+                db_tx.set_standalone_watch_only_key(
+                    &AccountAddress::new(account_id.clone(), destination),
+                    &key,
+                )?;
+            }
+            for (destination, key) in account.standalone_multisig_keys {
+                db_tx.set_standalone_multisig_key(
+                    &AccountAddress::new(account_id.clone(), destination),
+                    &key,
+                )?;
+            }
+            for (pool_id, pool) in account.watched_pools {
+                db_tx.set_watched_pool(&AccountPoolId::new(account_id.clone(), pool_id), &pool)?;
+            }
+            for tx in account.transactions {
+                db_tx.set_transaction(&AccountWalletTxId::new(account_id.clone(), tx.id()), &tx)?;
+            }
+        }
+
+        for (token_id, trust) in export.token_trust {
+            db_tx.set_token_trust(&token_id, trust)?;
+        }
+
+        // The derived keys are not exported, so derive them again
+        db_tx
+            .get_accounts_info()?
+            .into_keys()
+            .map(|id| {
+                let mut account = Account::load_from_database(chain_config.clone(), db_tx, &id)?;
+                account.top_up_addresses(db_tx)?;
+
+                Ok((account.account_index(), account))
+            })
+            .collect()
+    }
+
+    /// Create a watch-only replica of a wallet from the data produced by [Wallet::export_watch_only].
+    ///
+    /// The replica has no private keys, so it can track the balances and transactions,
+    /// but it cannot sign anything or create new accounts.
+    pub fn create_from_watch_only_export(
+        chain_config: Arc<ChainConfig>,
+        db: Store<B>,
+        data: &[u8],
+        passphrase: &String,
+        wallet_type: WalletType,
+    ) -> WalletResult<Self> {
+        let export = Self::decrypt_watch_only_export(chain_config.as_ref(), data, passphrase)?;
+        ensure!(
+            !export.is_incremental(),
+            WalletError::IncrementalWatchOnlyExport
+        );
+
+        let mut db_tx = db.transaction_rw(None)?;
+        db_tx.set_storage_version(CURRENT_WALLET_VERSION)?;
+        db_tx.set_chain_info(&ChainInfo::new(chain_config.as_ref()))?;
+        db_tx.set_wallet_type(wallet_type)?;
+        db_tx.set_watch_only(true)?;
+        Self::write_watch_only_export_and_load(chain_config.clone(), &mut db_tx, export)?;
+        db_tx.commit()?;
+
+        Self::load_wallet(chain_config, db, None, |_| Ok(()), wallet_type, false)
+    }
+
+    /// Apply a full or incremental export of the original wallet to this watch-only replica
+    pub fn apply_watch_only_update(
+        &mut self,
+        data: &[u8],
+        passphrase: &String,
+    ) -> WalletResult<()> {
+        ensure!(self.is_watch_only()?, WalletError::NotWatchOnlyWallet);
+
+        let export = Self::decrypt_watch_only_export(self.chain_config.as_ref(), data, passphrase)?;
+        ensure!(
+            export.wallet_id == self.wallet_info().0,
+            WalletError::WatchOnlyExportWalletMismatch
+        );
+
+        if let Some(since_height) = export.since_height {
+            let synced_height = self
+                .accounts
+                .values()
+                .chain(std::iter::once(&self.next_unused_account.1))
+                .map(|account| account.best_block().1)
+                .min()
+                .expect("not empty");
+            ensure!(
+                since_height <= synced_height,
+                WalletError::WatchOnlyUpdateGap(since_height, synced_height)
+            );
+        }
+
+        let mut db_tx = self.db.transaction_rw(None)?;
+        let mut accounts =
+            Self::write_watch_only_export_and_load(self.chain_config.clone(), &mut db_tx, export)?;
+        db_tx.commit()?;
+
+        self.next_unused_account = accounts.pop_last().expect("not empty accounts");
+        self.accounts = accounts;
+
+        Ok(())
+    }
+
+    /// Whether this wallet is a watch-only replica created from [Wallet::export_watch_only]
+    pub fn is_watch_only(&self) -> WalletResult<bool> {
+        Ok(self.db.transaction_ro()?.is_watch_only()?)
+    }
+
     pub fn seed_phrase(&self) -> WalletResult<Option<SerializableSeedPhrase>> {
         self.db.transaction_ro_unlocked()?.get_seed_phrase().map_err(WalletError::from)
     }
 
+    /// Get the entries of the signing log with the timestamps in the specified range
+    pub fn get_signing_log(
+        &self,
+        from: Option<BlockTimestamp>,
+        to: Option<BlockTimestamp>,
+    ) -> WalletResult<Vec<SigningLogEntry>> {
+        let entries = self.db.transaction_ro_unlocked()?.get_signing_log()?;
+        Ok(entries
+            .into_iter()
+            .filter(|entry| from.map_or(true, |from| entry.timestamp >= from))
+            .filter(|entry| to.map_or(true, |to| entry.timestamp <= to))
+            .collect())
+    }
+
     pub fn delete_seed_phrase(&self) -> WalletResult<Option<SerializableSeedPhrase>> {
         let mut tx = self.db.transaction_rw_unlocked(None)?;
         let seed_phrase = tx.del_seed_phrase().map_err(WalletError::from)?;
@@ -856,6 +1652,7 @@ impl<B: storage::Backend> Wallet<B> {
         &mut self,
         name: Option<String>,
     ) -> WalletResult<(U31, Option<String>)> {
+        ensure!(!self.is_watch_only()?, WalletError::WatchOnlyWallet);
         ensure!(
             self.accounts
                 .values()
@@ -1002,6 +1799,14 @@ impl<B: storage::Backend> Wallet<B> {
                 .map_err(|e| error_mapper(WalletError::TransactionCreation(e)))?;
 
             check_transaction(chain_config, block_height.next_height(), &tx)?;
+
+            Self::append_signing_log_entry(
+                db_tx,
+                tx.transaction(),
+                account_index,
+                SigningRequester::Wallet,
+                true,
+            )?;
             Ok(tx)
         })
     }
@@ -1014,6 +1819,24 @@ impl<B: storage::Backend> Wallet<B> {
         self.for_account_rw_unlocked_and_check_tx_custom_error(account_index, f, |err| err)
     }
 
+    fn append_signing_log_entry(
+        db_tx: &mut impl WalletStorageWriteUnlocked,
+        tx: &Transaction,
+        account_index: U31,
+        requester: SigningRequester,
+        fully_signed: bool,
+    ) -> WalletResult<()> {
+        let entry = SigningLogEntry::new(
+            tx,
+            BlockTimestamp::from_time(get_time()),
+            account_index,
+            requester,
+            fully_signed,
+        );
+        db_tx.append_signing_log_entry(&entry)?;
+        Ok(())
+    }
+
     fn get_account(&self, account_index: U31) -> WalletResult<&Account> {
         self.accounts
             .get(&account_index)
@@ -1084,13 +1907,80 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(utxos)
     }
 
-    pub fn find_unspent_utxo_with_destination(
+    pub fn get_standalone_address_utxos(
         &self,
-        outpoint: &UtxoOutPoint,
-    ) -> Option<(TxOutput, Destination)> {
-        self.accounts.values().find_map(|acc: &Account| {
-            let current_block_info = BlockInfo {
-                height: acc.best_block().1,
+        account_index: U31,
+        address: Destination,
+        utxo_types: UtxoTypes,
+        utxo_states: UtxoStates,
+        with_locked: WithLocked,
+    ) -> WalletResult<Vec<(UtxoOutPoint, TxOutput, Option<TokenId>)>> {
+        let account = self.get_account(account_index)?;
+        let utxos = account.get_standalone_address_utxos(
+            address,
+            utxo_types,
+            self.latest_median_time,
+            utxo_states,
+            with_locked,
+        )?;
+        let utxos = utxos
+            .into_iter()
+            .map(|(outpoint, (txo, token_id))| (outpoint, txo.clone(), token_id))
+            .collect();
+        Ok(utxos)
+    }
+
+    /// Find an output of a transaction known to any of the accounts, whether it's spent or not
+    pub fn find_txo(&self, outpoint: &UtxoOutPoint) -> Option<TxOutput> {
+        self.accounts.values().find_map(|acc: &Account| acc.find_txo(outpoint).cloned())
+    }
+
+    /// The confirmed outputs of all the accounts that the node's UTXO set is expected to agree on
+    pub fn get_confirmed_utxos(&self) -> Vec<(U31, TrackedUtxo)> {
+        self.accounts
+            .iter()
+            .flat_map(|(account_index, acc)| {
+                acc.get_confirmed_utxos().into_iter().map(|utxo| (*account_index, utxo))
+            })
+            .collect()
+    }
+
+    /// Forget the blocks above the specified height in all the accounts,
+    /// so they are scanned again with the next sync.
+    /// The accounts that haven't reached the height yet are not changed.
+    pub fn rewind_to_height(
+        &mut self,
+        common_block_height: BlockHeight,
+        common_block_id: Id<GenBlock>,
+        wallet_events: &impl WalletEvents,
+    ) -> WalletResult<()> {
+        let mut db_tx = self.db.transaction_rw(None)?;
+
+        for account in self
+            .accounts
+            .values_mut()
+            .chain(std::iter::once(&mut self.next_unused_account.1))
+        {
+            if account.best_block().1 > common_block_height {
+                account.reset_to_height(&mut db_tx, wallet_events, common_block_height)?;
+                account.update_best_block(&mut db_tx, common_block_height, common_block_id)?;
+            }
+        }
+
+        // Abort the process if the DB transaction fails. See `for_account_rw` for more information.
+        db_tx.commit().expect("RW transaction commit failed unexpectedly");
+
+        wallet_events.new_block();
+        Ok(())
+    }
+
+    pub fn find_unspent_utxo_with_destination(
+        &self,
+        outpoint: &UtxoOutPoint,
+    ) -> Option<(TxOutput, Destination)> {
+        self.accounts.values().find_map(|acc: &Account| {
+            let current_block_info = BlockInfo {
+                height: acc.best_block().1,
                 timestamp: self.latest_median_time,
             };
             acc.find_unspent_utxo_with_destination(outpoint, current_block_info).ok()
@@ -1117,6 +2007,22 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(transactions)
     }
 
+    /// Get up to `limit` confirmed transactions of the account that come after the `after`
+    /// position in the chain, the oldest ones first unless `newest_first` is set
+    pub fn mainchain_transactions_page(
+        &self,
+        account_index: U31,
+        destination: Option<Destination>,
+        after: Option<TxChainPosition>,
+        newest_first: bool,
+        limit: usize,
+    ) -> WalletResult<Vec<TxInfo>> {
+        let account = self.get_account(account_index)?;
+        let transactions =
+            account.mainchain_transactions_page(destination, after, newest_first, limit);
+        Ok(transactions)
+    }
+
     pub fn abandon_transaction(
         &mut self,
         account_index: U31,
@@ -1127,6 +2033,51 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    /// Create a transaction that makes an unconfirmed transaction of the account pay at least
+    /// the new fee rate, either a replacement or a child transaction.
+    /// A child transaction is always created if replacement is not allowed.
+    /// If it's a replacement, the original transaction should be marked with
+    /// `mark_transaction_replaced` once the replacement has been accepted by the mempool.
+    pub fn create_fee_bump_transaction(
+        &mut self,
+        account_index: U31,
+        tx_id: Id<Transaction>,
+        new_fee_rate: FeeRate,
+        min_relay_fee_rate: FeeRate,
+        allow_replacement: bool,
+    ) -> WalletResult<(SignedTransaction, FeeBumpKind)> {
+        let latest_median_time = self.latest_median_time;
+        let mut fee_bump_kind = None;
+        let tx = self.for_account_rw_unlocked_and_check_tx(account_index, |account, db_tx| {
+            let (request, kind) = account.create_fee_bump_tx(
+                db_tx,
+                tx_id,
+                new_fee_rate,
+                min_relay_fee_rate,
+                allow_replacement,
+                latest_median_time,
+            )?;
+            fee_bump_kind = Some(kind);
+            Ok(request)
+        })?;
+        Ok((
+            tx,
+            fee_bump_kind.expect("set when the transaction is created"),
+        ))
+    }
+
+    /// Mark a transaction replaced by a fee bump and its descendants as conflicted
+    pub fn mark_transaction_replaced(
+        &mut self,
+        account_index: U31,
+        tx_id: Id<Transaction>,
+        wallet_events: &impl WalletEvents,
+    ) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.mark_transaction_replaced(tx_id, db_tx, wallet_events)
+        })
+    }
+
     pub fn get_pool_ids(
         &self,
         account_index: U31,
@@ -1161,6 +2112,279 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(block_ids)
     }
 
+    pub fn get_staked_blocks(
+        &self,
+        account_index: U31,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> WalletResult<Vec<StakedBlock>> {
+        let blocks = self.get_account(account_index)?.get_staked_blocks(from_height, to_height);
+        Ok(blocks)
+    }
+
+    pub fn add_watched_pool(
+        &mut self,
+        account_index: U31,
+        pool_id: PoolId,
+        label: Option<String>,
+        decommission_key: Option<Destination>,
+    ) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.add_watched_pool(db_tx, pool_id, label, decommission_key)
+        })
+    }
+
+    pub fn remove_watched_pool(&mut self, account_index: U31, pool_id: PoolId) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.remove_watched_pool(db_tx, pool_id)
+        })
+    }
+
+    pub fn get_watched_pools(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<Vec<(PoolId, WatchedPool)>> {
+        let watched_pools = self
+            .get_account(account_index)?
+            .get_watched_pools()
+            .iter()
+            .map(|(pool_id, watched_pool)| (*pool_id, watched_pool.clone()))
+            .collect();
+        Ok(watched_pools)
+    }
+
+    pub fn add_scheduled_payment(
+        &mut self,
+        account_index: U31,
+        payment: ScheduledPayment,
+    ) -> WalletResult<u64> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.add_scheduled_payment(db_tx, payment)
+        })
+    }
+
+    pub fn update_scheduled_payment(
+        &mut self,
+        account_index: U31,
+        payment_id: u64,
+        payment: ScheduledPayment,
+    ) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.update_scheduled_payment(db_tx, payment_id, payment)
+        })
+    }
+
+    /// Save the transaction made for a scheduled payment, queued for broadcasting, together with
+    /// the advanced schedule of the payment, so the payment is not made again if the wallet
+    /// stops before the transaction is submitted
+    pub fn add_scheduled_payment_tx(
+        &mut self,
+        account_index: U31,
+        payment_id: u64,
+        payment: ScheduledPayment,
+        broadcast_entry: &BroadcastQueueEntry,
+        wallet_events: &impl WalletEvents,
+    ) -> WalletResult<()> {
+        let transaction = broadcast_entry.tx.clone();
+        let tx_id = transaction.transaction().get_id();
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.scan_new_inactive_transactions(&[transaction], db_tx, wallet_events)?;
+            db_tx.set_broadcast_queue_entry(&tx_id, broadcast_entry)?;
+            account.update_scheduled_payment(db_tx, payment_id, payment)
+        })
+    }
+
+    pub fn cancel_scheduled_payment(
+        &mut self,
+        account_index: U31,
+        payment_id: u64,
+    ) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.cancel_scheduled_payment(db_tx, payment_id)
+        })
+    }
+
+    pub fn get_scheduled_payments(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<Vec<(u64, ScheduledPayment)>> {
+        let payments = self
+            .get_account(account_index)?
+            .get_scheduled_payments()
+            .iter()
+            .map(|(payment_id, payment)| (*payment_id, payment.clone()))
+            .collect();
+        Ok(payments)
+    }
+
+    pub fn set_dust_protection_policy(
+        &mut self,
+        account_index: U31,
+        min_coin_amount: Option<Amount>,
+        flag_unknown_tokens: bool,
+    ) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.set_dust_protection_policy(db_tx, min_coin_amount, flag_unknown_tokens)
+        })
+    }
+
+    pub fn get_dust_protection(&self, account_index: U31) -> WalletResult<DustProtection> {
+        Ok(self.get_account(account_index)?.get_dust_protection().clone())
+    }
+
+    /// Set where the change of the transactions composed by the account is sent to
+    pub fn set_change_policy(
+        &mut self,
+        account_index: U31,
+        change_policy: ChangePolicy,
+    ) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.set_change_policy(db_tx, change_policy)
+        })
+    }
+
+    pub fn get_change_policy(&self, account_index: U31) -> WalletResult<ChangePolicy> {
+        Ok(self.get_account(account_index)?.get_change_policy().clone())
+    }
+
+    /// Mark the token as trusted or hidden, or remove the mark if `trust` is `None`
+    pub fn set_token_trust(
+        &mut self,
+        token_id: TokenId,
+        trust: Option<TokenTrust>,
+    ) -> WalletResult<()> {
+        let mut db_tx = self.db.transaction_rw(None)?;
+        match trust {
+            Some(trust) => db_tx.set_token_trust(&token_id, trust)?,
+            None => db_tx.del_token_trust(&token_id)?,
+        }
+        db_tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_token_trust_list(&self) -> WalletResult<BTreeMap<TokenId, TokenTrust>> {
+        Ok(self.db.transaction_ro()?.get_token_trust_list()?)
+    }
+
+    /// Add the payees to the address book, replacing the existing ones with the same names
+    pub fn set_payees(&mut self, payees: BTreeMap<String, Payee>) -> WalletResult<()> {
+        ensure!(
+            payees.keys().all(|name| !name.trim().is_empty()),
+            WalletError::EmptyPayeeName
+        );
+
+        let mut db_tx = self.db.transaction_rw(None)?;
+        for (name, payee) in payees {
+            db_tx.set_payee(&name, &payee)?;
+        }
+        db_tx.commit()?;
+        Ok(())
+    }
+
+    pub fn remove_payee(&mut self, name: &str) -> WalletResult<()> {
+        let mut db_tx = self.db.transaction_rw(None)?;
+        ensure!(
+            db_tx.get_payee(name)?.is_some(),
+            WalletError::PayeeNotFound(name.to_owned())
+        );
+        db_tx.del_payee(name)?;
+        db_tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_payee(&self, name: &str) -> WalletResult<Payee> {
+        self.db
+            .transaction_ro()?
+            .get_payee(name)?
+            .ok_or_else(|| WalletError::PayeeNotFound(name.to_owned()))
+    }
+
+    pub fn get_payees(&self) -> WalletResult<BTreeMap<String, Payee>> {
+        Ok(self.db.transaction_ro()?.get_payees()?)
+    }
+
+    /// Get the tokens that the account has issued or sent itself
+    pub fn get_known_token_ids(&self, account_index: U31) -> WalletResult<BTreeSet<TokenId>> {
+        Ok(self.get_account(account_index)?.known_token_ids())
+    }
+
+    /// Get the UTXOs that are not selected automatically because of the dust protection policy
+    pub fn get_dust_utxos(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<Vec<(UtxoOutPoint, TxOutput, Option<TokenId>)>> {
+        let utxos = self
+            .get_account(account_index)?
+            .get_dust_utxos(self.latest_median_time)
+            .into_iter()
+            .map(|(outpoint, (txo, token_id))| (outpoint, txo.clone(), token_id))
+            .collect();
+        Ok(utxos)
+    }
+
+    pub fn release_dust_utxos(
+        &mut self,
+        account_index: U31,
+        utxos: Vec<UtxoOutPoint>,
+    ) -> WalletResult<()> {
+        let latest_median_time = self.latest_median_time;
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.release_dust_utxos(db_tx, utxos, latest_median_time)
+        })
+    }
+
+    /// Get the unspent UTXOs that are frozen and must not be spent
+    pub fn get_frozen_utxos(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<Vec<(UtxoOutPoint, TxOutput)>> {
+        let utxos = self
+            .get_account(account_index)?
+            .get_frozen_utxos(self.latest_median_time)
+            .into_iter()
+            .map(|(outpoint, (txo, _))| (outpoint, txo.clone()))
+            .collect();
+        Ok(utxos)
+    }
+
+    pub fn freeze_utxos(
+        &mut self,
+        account_index: U31,
+        utxos: Vec<UtxoOutPoint>,
+    ) -> WalletResult<()> {
+        let latest_median_time = self.latest_median_time;
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.freeze_utxos(db_tx, utxos, latest_median_time)
+        })
+    }
+
+    pub fn unfreeze_utxos(
+        &mut self,
+        account_index: U31,
+        utxos: Vec<UtxoOutPoint>,
+    ) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.unfreeze_utxos(db_tx, utxos)
+        })
+    }
+
+    /// Get the scheduled payments of all accounts that should be executed at the specified time
+    pub fn get_due_scheduled_payments(
+        &self,
+        now: BlockTimestamp,
+    ) -> Vec<(U31, u64, ScheduledPayment)> {
+        self.accounts
+            .iter()
+            .flat_map(|(account_index, account)| {
+                account
+                    .get_scheduled_payments()
+                    .iter()
+                    .filter(|(_, payment)| payment.is_due(now))
+                    .map(|(payment_id, payment)| (*account_index, *payment_id, payment.clone()))
+            })
+            .collect()
+    }
+
     pub fn standalone_address_label_rename(
         &mut self,
         account_index: U31,
@@ -1183,6 +2407,16 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    pub fn remove_standalone_address(
+        &mut self,
+        account_index: U31,
+        public_key_hash: PublicKeyHash,
+    ) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.remove_standalone_address(db_tx, public_key_hash)
+        })
+    }
+
     pub fn add_standalone_private_key(
         &mut self,
         account_index: U31,
@@ -1194,6 +2428,20 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    /// Get the private key of the destination owned by the account, derived from its key chain
+    /// or added as a standalone private key. The private keys must be unlocked.
+    pub fn export_private_key(
+        &self,
+        account_index: U31,
+        destination: &Destination,
+    ) -> WalletResult<PrivateKey> {
+        let db_tx = self.db.transaction_ro_unlocked()?;
+        self.get_account(account_index)?
+            .key_chain()
+            .get_private_key_for_destination(destination, &db_tx)?
+            .ok_or(WalletError::DestinationNotFromThisWallet)
+    }
+
     pub fn add_standalone_multisig(
         &mut self,
         account_index: U31,
@@ -1205,6 +2453,47 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    pub fn account_extended_public_key(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<&ExtendedPublicKey> {
+        Ok(self.get_account(account_index)?.key_chain().account_public_key())
+    }
+
+    pub fn import_extended_public_key(
+        &mut self,
+        account_index: U31,
+        extended_public_key: &ExtendedPublicKey,
+        address_count: U31,
+        label: Option<String>,
+    ) -> WalletResult<Vec<PublicKeyHash>> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.import_extended_public_key(db_tx, extended_public_key, address_count, label)
+        })
+    }
+
+    pub fn create_multisig_account_descriptor(
+        &self,
+        account_index: U31,
+        min_required_signatures: NonZeroU8,
+        cosigner_keys: Vec<ExtendedPublicKey>,
+    ) -> WalletResult<MultisigAccountDescriptor> {
+        self.get_account(account_index)?
+            .create_multisig_account_descriptor(min_required_signatures, cosigner_keys)
+    }
+
+    pub fn import_multisig_account(
+        &mut self,
+        account_index: U31,
+        descriptor: &MultisigAccountDescriptor,
+        address_count: U31,
+        label: Option<String>,
+    ) -> WalletResult<Vec<PublicKeyHash>> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.import_multisig_account(db_tx, descriptor, address_count, label)
+        })
+    }
+
     pub fn get_new_address(
         &mut self,
         account_index: U31,
@@ -1245,7 +2534,17 @@ impl<B: storage::Backend> Wallet<B> {
         count: usize,
     ) -> WalletResult<TransactionList> {
         let account = self.get_account(account_index)?;
-        account.get_transaction_list(skip, count)
+        let internal_transfers = self.db.transaction_ro()?.get_internal_transfers()?;
+        account.get_transaction_list(skip, count, &internal_transfers)
+    }
+
+    pub fn get_transaction_history(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<Vec<TransactionInfo>> {
+        let account = self.get_account(account_index)?;
+        let internal_transfers = self.db.transaction_ro()?.get_internal_transfers()?;
+        account.get_transaction_history(&internal_transfers)
     }
 
     pub fn get_transaction(
@@ -1257,11 +2556,77 @@ impl<B: storage::Backend> Wallet<B> {
         account.get_transaction(transaction_id)
     }
 
-    pub fn get_transactions_to_be_broadcast(&self) -> WalletResult<Vec<SignedTransaction>> {
-        self.db
-            .transaction_ro()?
-            .get_user_transactions()
-            .map_err(WalletError::DatabaseError)
+    pub fn describe_transaction(
+        &self,
+        account_index: U31,
+        transaction_id: Id<Transaction>,
+    ) -> WalletResult<Vec<TxDescriptionItem>> {
+        let account = self.get_account(account_index)?;
+        account.describe_transaction(transaction_id)
+    }
+
+    /// Find the state of the transaction in the accounts of the wallet
+    pub fn find_transaction_state(&self, transaction_id: Id<Transaction>) -> Option<TxState> {
+        self.accounts
+            .values()
+            .find_map(|account| account.get_transaction(transaction_id).ok())
+            .map(|tx| *tx.state())
+    }
+
+    /// Abandon a transaction the wallet has given up broadcasting, so its inputs can be spent
+    /// again. Nothing is done if the transaction is not inactive, e.g. if it's in the mempool,
+    /// as it can still be included in a block.
+    pub fn abandon_unbroadcast_transaction(
+        &mut self,
+        transaction_id: Id<Transaction>,
+    ) -> WalletResult<()> {
+        let account_index = self.accounts.iter().find_map(|(account_index, account)| {
+            account
+                .get_transaction(transaction_id)
+                .ok()
+                .filter(|tx| matches!(tx.state(), TxState::Inactive(_)))
+                .map(|_| *account_index)
+        });
+
+        match account_index {
+            Some(account_index) => self.abandon_transaction(account_index, transaction_id),
+            None => Ok(()),
+        }
+    }
+
+    /// Add or replace the entry of the transaction in the broadcast queue
+    pub fn update_broadcast_queue_entry(
+        &mut self,
+        transaction_id: Id<Transaction>,
+        entry: &BroadcastQueueEntry,
+    ) -> WalletResult<()> {
+        let mut db_tx = self.db.transaction_rw(None)?;
+        db_tx.set_broadcast_queue_entry(&transaction_id, entry)?;
+        db_tx.commit()?;
+        Ok(())
+    }
+
+    pub fn remove_broadcast_queue_entry(
+        &mut self,
+        transaction_id: Id<Transaction>,
+    ) -> WalletResult<()> {
+        let mut db_tx = self.db.transaction_rw(None)?;
+        db_tx.del_broadcast_queue_entry(&transaction_id)?;
+        db_tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_broadcast_queue(
+        &self,
+    ) -> WalletResult<BTreeMap<Id<Transaction>, BroadcastQueueEntry>> {
+        Ok(self.db.transaction_ro()?.get_broadcast_queue()?)
+    }
+
+    pub fn get_broadcast_queue_entry(
+        &self,
+        transaction_id: Id<Transaction>,
+    ) -> WalletResult<Option<BroadcastQueueEntry>> {
+        Ok(self.db.transaction_ro()?.get_broadcast_queue_entry(&transaction_id)?)
     }
 
     pub fn get_all_issued_addresses(
@@ -1272,6 +2637,25 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(account.get_all_issued_addresses())
     }
 
+    pub fn set_address_label(
+        &mut self,
+        account_index: U31,
+        address: Destination,
+        label: Option<String>,
+    ) -> WalletResult<()> {
+        self.for_account_rw(account_index, |account, db_tx| {
+            account.set_address_label(db_tx, address, label)
+        })
+    }
+
+    pub fn get_address_labels(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<&BTreeMap<Destination, String>> {
+        let account = self.get_account(account_index)?;
+        Ok(account.get_address_labels())
+    }
+
     pub fn get_all_standalone_addresses(
         &self,
         account_index: U31,
@@ -1309,6 +2693,21 @@ impl<B: storage::Backend> Wallet<B> {
         Ok(account.get_legacy_vrf_public_key())
     }
 
+    /// Get the ids of the account's pools grouped by the VRF public key they use
+    pub fn get_pool_ids_by_vrf_public_key(
+        &self,
+        account_index: U31,
+    ) -> WalletResult<BTreeMap<VRFPublicKey, Vec<PoolId>>> {
+        let pools = self.get_pool_ids(account_index, WalletPoolsFilter::All)?;
+
+        let mut pool_ids_by_key = BTreeMap::<_, Vec<_>>::new();
+        for (pool_id, pool_data) in pools {
+            pool_ids_by_key.entry(pool_data.vrf_public_key).or_default().push(pool_id);
+        }
+
+        Ok(pool_ids_by_key)
+    }
+
     pub fn get_addresses_usage(&self, account_index: U31) -> WalletResult<&KeychainUsageState> {
         let account = self.get_account(account_index)?;
         Ok(account.get_addresses_usage())
@@ -1360,6 +2759,34 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    /// Same as `create_transaction_to_addresses` but the transaction pays exactly `fee` in coins
+    /// instead of a fee derived from a fee rate
+    pub fn create_transaction_to_addresses_with_absolute_fee(
+        &mut self,
+        account_index: U31,
+        outputs: impl IntoIterator<Item = TxOutput>,
+        inputs: SelectedInputs,
+        change_addresses: BTreeMap<Currency, Address<Destination>>,
+        fee: Amount,
+    ) -> WalletResult<SignedTransaction> {
+        let request = SendRequest::new().with_outputs(outputs).with_absolute_fee(fee);
+        let latest_median_time = self.latest_median_time;
+        let zero_fee_rate = FeeRate::from_amount_per_kb(Amount::ZERO);
+        self.for_account_rw_unlocked_and_check_tx(account_index, |account, db_tx| {
+            account.process_send_request_and_sign(
+                db_tx,
+                request,
+                inputs,
+                change_addresses,
+                latest_median_time,
+                CurrentFeeRate {
+                    current_fee_rate: zero_fee_rate,
+                    consolidate_fee_rate: zero_fee_rate,
+                },
+            )
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn create_unsigned_transaction_to_addresses(
         &mut self,
@@ -1389,6 +2816,8 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    /// Create a transaction that transfers everything held by the given inputs, except the frozen
+    /// ones, to the destination
     pub fn create_sweep_transaction(
         &mut self,
         account_index: U31,
@@ -1396,6 +2825,7 @@ impl<B: storage::Backend> Wallet<B> {
         inputs: Vec<(UtxoOutPoint, TxOutput, Option<TokenId>)>,
         current_fee_rate: FeeRate,
     ) -> WalletResult<SignedTransaction> {
+        let inputs = self.get_account(account_index)?.retain_unfrozen_utxos(inputs);
         let request = SendRequest::new().with_inputs(
             inputs
                 .into_iter()
@@ -1408,6 +2838,85 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    /// Compute the largest amount of the currency that can be sent to the destination in a single
+    /// output by spending all the given inputs, except the frozen ones.
+    /// For coins the fee is deducted from the amount, for tokens the fee has to be paid by other
+    /// coin inputs.
+    pub fn get_max_sendable_amount(
+        &self,
+        account_index: U31,
+        destination: Destination,
+        currency: &Currency,
+        inputs: Vec<(UtxoOutPoint, TxOutput, Option<TokenId>)>,
+        current_fee_rate: FeeRate,
+    ) -> WalletResult<Amount> {
+        let inputs = self.get_account(account_index)?.retain_unfrozen_utxos(inputs);
+        match currency {
+            Currency::Coin => {
+                let request = SendRequest::new().with_inputs(
+                    inputs
+                        .into_iter()
+                        .map(|(outpoint, output, _)| (TxInput::Utxo(outpoint), output)),
+                    &|_| None,
+                )?;
+                let outputs = self.get_account(account_index)?.make_sweep_outputs(
+                    destination,
+                    &request,
+                    current_fee_rate,
+                )?;
+                match outputs.last() {
+                    Some(TxOutput::Transfer(OutputValue::Coin(amount), _)) => Ok(*amount),
+                    _ => Err(WalletError::NoUtxos),
+                }
+            }
+            Currency::Token(token_id) => total_token_amount(token_id, &inputs),
+        }
+    }
+
+    /// Create a transaction that transfers all the tokens held by the given inputs to the
+    /// destination in a single output. The transaction spends exactly these inputs, except
+    /// the frozen ones, so the sent amount always matches them; the fee is paid with coin UTXOs
+    /// selected from the account.
+    pub fn create_token_sweep_transaction(
+        &mut self,
+        account_index: U31,
+        destination: Destination,
+        token_id: TokenId,
+        inputs: Vec<(UtxoOutPoint, TxOutput, Option<TokenId>)>,
+        current_fee_rate: FeeRate,
+        consolidate_fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        let inputs = self.get_account(account_index)?.retain_unfrozen_utxos(inputs);
+        let amount = total_token_amount(&token_id, &inputs)?;
+        ensure!(amount > Amount::ZERO, WalletError::NoUtxos);
+
+        let request = SendRequest::new()
+            .with_inputs(
+                inputs
+                    .into_iter()
+                    .map(|(outpoint, output, _)| (TxInput::Utxo(outpoint), output)),
+                &|_| None,
+            )?
+            .with_outputs([TxOutput::Transfer(
+                OutputValue::TokenV1(token_id, amount),
+                destination,
+            )]);
+        let latest_median_time = self.latest_median_time;
+        self.for_account_rw_unlocked_and_check_tx(account_index, |account, db_tx| {
+            account.process_send_request_and_sign(
+                db_tx,
+                request,
+                SelectedInputs::Utxos(vec![]),
+                BTreeMap::new(),
+                latest_median_time,
+                CurrentFeeRate {
+                    current_fee_rate,
+                    consolidate_fee_rate,
+                },
+            )
+        })
+    }
+
     pub fn create_sweep_from_delegation_transaction(
         &mut self,
         account_index: U31,
@@ -1421,6 +2930,47 @@ impl<B: storage::Backend> Wallet<B> {
         })
     }
 
+    /// Create a transaction that moves the amount of the currency from one account of the wallet
+    /// to a new receiving address of another one, and record it as an internal transfer
+    pub fn create_internal_transfer_tx(
+        &mut self,
+        from_account: U31,
+        to_account: U31,
+        currency: &Currency,
+        amount: Amount,
+        current_fee_rate: FeeRate,
+        consolidate_fee_rate: FeeRate,
+    ) -> WalletResult<SignedTransaction> {
+        ensure!(
+            from_account != to_account,
+            WalletError::TransferToSameAccount
+        );
+
+        let (_, address) = self.get_new_address(to_account)?;
+        let output = match currency {
+            Currency::Coin => make_address_output(address, amount),
+            Currency::Token(token_id) => make_address_output_token(address, amount, *token_id),
+        };
+        let tx = self.create_transaction_to_addresses(
+            from_account,
+            [output],
+            SelectedInputs::Utxos(vec![]),
+            BTreeMap::new(),
+            current_fee_rate,
+            consolidate_fee_rate,
+        )?;
+
+        let transfer = InternalTransfer {
+            from_account,
+            to_account,
+        };
+        let mut db_tx = self.db.transaction_rw(None)?;
+        db_tx.set_internal_transfer(&tx.transaction().get_id(), &transfer)?;
+        db_tx.commit()?;
+
+        Ok(tx)
+    }
+
     pub fn create_transaction_to_addresses_from_delegation(
         &mut self,
         account_index: U31,
@@ -1617,6 +3167,14 @@ impl<B: storage::Backend> Wallet<B> {
         self.get_account(account_index)?.get_token_unconfirmed_info(token_info)
     }
 
+    /// Returns the index of the account that can spend from the destination, if any
+    pub fn find_account_by_destination(&self, destination: &Destination) -> Option<U31> {
+        self.accounts
+            .iter()
+            .find(|(_, account)| account.is_destination_mine(destination))
+            .map(|(account_index, _)| *account_index)
+    }
+
     pub fn create_delegation(
         &mut self,
         account_index: U31,
@@ -1766,6 +3324,14 @@ impl<B: storage::Backend> Wallet<B> {
             if ptx.all_signatures_available() {
                 return Err(WalletError::FullySignedTransactionInDecommissionReq);
             }
+
+            Self::append_signing_log_entry(
+                db_tx,
+                ptx.tx(),
+                account_index,
+                SigningRequester::Wallet,
+                false,
+            )?;
             Ok(ptx)
         })
     }
@@ -1812,8 +3378,21 @@ impl<B: storage::Backend> Wallet<B> {
             };
             let signer = SoftwareSigner::new(db_tx, Arc::new(chain_config.clone()), account_index);
 
-            let res = signer.sign_tx(ptx, account.key_chain())?;
-            Ok(res)
+            let (ptx, prev_statuses, new_statuses) = signer.sign_tx(ptx, account.key_chain())?;
+
+            // Only log the transaction if the wallet has actually added some signatures
+            if prev_statuses != new_statuses {
+                let fully_signed =
+                    new_statuses.iter().all(|status| *status == SignatureStatus::FullySigned);
+                Self::append_signing_log_entry(
+                    db_tx,
+                    ptx.tx(),
+                    account_index,
+                    SigningRequester::External,
+                    fully_signed,
+                )?;
+            }
+            Ok((ptx, prev_statuses, new_statuses))
         })
     }
 
@@ -1854,6 +3433,11 @@ impl<B: storage::Backend> Wallet<B> {
         Err(WalletError::UnknownPoolId(pool_id))
     }
 
+    /// The median time of the node's tip at the moment of the last sync
+    pub fn latest_median_time(&self) -> BlockTimestamp {
+        self.latest_median_time
+    }
+
     /// Returns the last scanned block hash and height for all accounts.
     /// Returns genesis block when the wallet is just created.
     pub fn get_best_block(&self) -> BTreeMap<U31, (Id<GenBlock>, BlockHeight)> {
@@ -1927,6 +3511,11 @@ impl<B: storage::Backend> Wallet<B> {
             db_tx.commit()?;
 
             if added_new_tx_in_unused_acc {
+                if self.is_watch_only()? {
+                    // The new account can only be created by the original wallet
+                    // and will be added with the next watch-only update
+                    break;
+                }
                 self.create_next_account(None)?;
             } else {
                 break;
@@ -2021,5 +3610,25 @@ impl<B: storage::Backend> Wallet<B> {
     }
 }
 
+/// The total amount of the token held by the outputs
+fn total_token_amount(
+    token_id: &TokenId,
+    outputs: &[(UtxoOutPoint, TxOutput, Option<TokenId>)],
+) -> WalletResult<Amount> {
+    outputs
+        .iter()
+        .filter_map(|(_, output, _)| match output {
+            TxOutput::Transfer(OutputValue::TokenV1(id, amount), _)
+            | TxOutput::LockThenTransfer(OutputValue::TokenV1(id, amount), _, _)
+                if id == token_id =>
+            {
+                Some(*amount)
+            }
+            _ => None,
+        })
+        .sum::<Option<Amount>>()
+        .ok_or(WalletError::OutputAmountOverflow)
+}
+
 #[cfg(test)]
 mod tests;
@@ -14,7 +14,7 @@
 // limitations under the License.
 
 use crate::{
-    account::currency_grouper::Currency,
+    account::{currency_grouper::Currency, transaction_list::TxType},
     key_chain::{make_account_path, LOOKAHEAD_SIZE},
     send_request::{make_address_output, make_create_delegation_output},
     wallet_events::WalletEventsNoOp,
@@ -52,8 +52,11 @@ use storage::raw::DbMapId;
 use test_utils::random::{make_seedable_rng, Seed};
 use wallet_storage::{schema, WalletStorageEncryptionRead};
 use wallet_types::{
-    account_info::DEFAULT_ACCOUNT_INDEX,
+    account_info::{ChangePolicy, DEFAULT_ACCOUNT_INDEX},
+    address_book::Payee,
+    broadcast_queue::BroadcastStatus,
     seed_phrase::PassPhrase,
+    signing_log::{SigningLogOutput, SigningRequester},
     utxo_types::{UtxoState, UtxoType},
 };
 use wallet_types::{seed_phrase::SeedPhraseLanguage, AccountWalletTxId};
@@ -345,6 +348,246 @@ fn wallet_creation_in_memory() {
     .unwrap();
 }
 
+#[test]
+fn salvage_wallet() {
+    let chain_config = Arc::new(create_regtest());
+
+    // salvaging an empty wallet fails as there are no keys to recover
+    let result = Wallet::salvage_wallet(
+        Arc::clone(&chain_config),
+        create_wallet_in_memory().unwrap(),
+        create_wallet_in_memory().unwrap(),
+        None,
+        WalletType::Hot,
+    );
+    assert_eq!(result.err(), Some(WalletError::WalletNotInitialized));
+
+    let mut wallet = create_wallet(chain_config.clone());
+    let account_name = Some("name".to_owned());
+    wallet.set_account_name(DEFAULT_ACCOUNT_INDEX, account_name.clone()).unwrap();
+    let standalone_address = PublicKeyHash::from_low_u64_be(1);
+    wallet
+        .add_standalone_address(
+            DEFAULT_ACCOUNT_INDEX,
+            standalone_address,
+            Some("label".to_owned()),
+        )
+        .unwrap();
+    let (wallet_id, _) = wallet.wallet_info();
+    let (_, address) = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    let (mut salvaged_wallet, report) = Wallet::salvage_wallet(
+        chain_config,
+        wallet.db,
+        create_wallet_in_memory().unwrap(),
+        None,
+        WalletType::Hot,
+    )
+    .unwrap();
+
+    assert_eq!(report.recovered_accounts, vec![DEFAULT_ACCOUNT_INDEX]);
+    assert!(report.warnings.is_empty());
+
+    // the keys and the account metadata are the same
+    assert_eq!(
+        salvaged_wallet.wallet_info(),
+        (wallet_id, vec![account_name])
+    );
+    let (_, salvaged_address) = salvaged_wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(salvaged_address, address);
+    let standalone_addresses =
+        salvaged_wallet.get_all_standalone_addresses(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(
+        standalone_addresses.watch_only_addresses.first().map(|(dest, _)| dest.clone()),
+        Some(Destination::PublicKeyHash(standalone_address))
+    );
+
+    // the blockchain has to be rescanned
+    assert_eq!(
+        get_best_block(&salvaged_wallet),
+        (
+            salvaged_wallet.chain_config.genesis_block_id(),
+            BlockHeight::new(0)
+        )
+    );
+}
+
+#[test]
+fn salvage_encrypted_wallet() {
+    let chain_config = Arc::new(create_regtest());
+    let password = "password".to_owned();
+
+    let mut wallet = create_wallet(chain_config.clone());
+    let (_, address) = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    wallet.encrypt_wallet(&Some(password.clone())).unwrap();
+    wallet.lock_wallet().unwrap();
+
+    let (mut salvaged_wallet, report) = Wallet::salvage_wallet(
+        chain_config,
+        wallet.db,
+        create_wallet_in_memory().unwrap(),
+        Some(password.clone()),
+        WalletType::Hot,
+    )
+    .unwrap();
+    assert!(report.warnings.is_empty());
+
+    // the salvaged keys are encrypted with the same password
+    salvaged_wallet.lock_wallet().unwrap();
+    assert!(salvaged_wallet.is_locked());
+    assert!(salvaged_wallet.unlock_wallet(&"wrong password".to_owned()).is_err());
+    salvaged_wallet.unlock_wallet(&password).unwrap();
+
+    let (_, salvaged_address) = salvaged_wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(salvaged_address, address);
+}
+
+#[test]
+fn db_corruption_errors() {
+    let corrupted = wallet_storage::Error::StorageError(storage::error::Error::Fatal(
+        storage::error::Fatal::DatabaseCorrupted,
+    ));
+    assert!(WalletError::DatabaseError(corrupted.clone()).is_db_corruption());
+    assert!(WalletError::KeyChainError(KeyChainError::DatabaseError(corrupted)).is_db_corruption());
+    assert!(WalletError::DatabaseError(
+        wallet_storage::Error::WalletSanityErrorInvalidRootKeyCount(2)
+    )
+    .is_db_corruption());
+
+    // generic storage errors don't mean that the DB is damaged
+    for storage_err in [
+        storage::error::Error::Fatal(storage::error::Fatal::SchemaMismatch),
+        storage::error::Error::Fatal(storage::error::Fatal::InternalError("error".to_owned())),
+        storage::error::Error::Recoverable(storage::error::Recoverable::TransactionFailed),
+    ] {
+        let err = wallet_storage::Error::StorageError(storage_err);
+        assert!(!WalletError::DatabaseError(err).is_db_corruption());
+    }
+    assert!(
+        !WalletError::DatabaseError(wallet_storage::Error::WalletDbInconsistentState)
+            .is_db_corruption()
+    );
+    assert!(!WalletError::DatabaseError(wallet_storage::Error::WalletLocked).is_db_corruption());
+    assert!(
+        !WalletError::KeyChainError(KeyChainError::MissingDatabaseProperty("root key"))
+            .is_db_corruption()
+    );
+    assert!(!WalletError::WalletNotInitialized.is_db_corruption());
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn watch_only_export(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+    let passphrase = gen_random_password(&mut rng);
+
+    let mut wallet = create_wallet(chain_config.clone());
+    let account_name = Some("name".to_owned());
+    wallet.set_account_name(DEFAULT_ACCOUNT_INDEX, account_name.clone()).unwrap();
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 1..NETWORK_FEE + 10000));
+    create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let export = wallet.export_watch_only(&passphrase, None).unwrap();
+
+    // the export can't be decrypted with a different passphrase
+    let result = Wallet::create_from_watch_only_export(
+        chain_config.clone(),
+        create_wallet_in_memory().unwrap(),
+        &export,
+        &format!("{passphrase}x"),
+        WalletType::Hot,
+    );
+    assert_eq!(
+        result.err(),
+        Some(WalletError::WatchOnlyExportDecryptionFailed)
+    );
+
+    let mut replica = Wallet::create_from_watch_only_export(
+        chain_config.clone(),
+        create_wallet_in_memory().unwrap(),
+        &export,
+        &passphrase,
+        WalletType::Hot,
+    )
+    .unwrap();
+
+    // the replica sees the same accounts and transactions, but can't create new accounts
+    assert!(replica.is_watch_only().unwrap());
+    assert!(!wallet.is_watch_only().unwrap());
+    assert_eq!(replica.wallet_info(), wallet.wallet_info());
+    assert_eq!(get_best_block(&replica), get_best_block(&wallet));
+    assert_eq!(get_coin_balance(&replica), block1_amount);
+    assert_eq!(
+        replica.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1,
+        wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1
+    );
+    assert_eq!(
+        replica.create_next_account(None).err(),
+        Some(WalletError::WatchOnlyWallet)
+    );
+
+    let since_height = get_best_block(&wallet).1;
+    let block2_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 1..NETWORK_FEE + 10000));
+    create_block(&chain_config, &mut wallet, vec![], block2_amount, 1);
+
+    let update = wallet.export_watch_only(&passphrase, Some(since_height)).unwrap();
+
+    // an incremental update can only be applied to an existing replica
+    let result = Wallet::create_from_watch_only_export(
+        chain_config.clone(),
+        create_wallet_in_memory().unwrap(),
+        &update,
+        &passphrase,
+        WalletType::Hot,
+    );
+    assert_eq!(result.err(), Some(WalletError::IncrementalWatchOnlyExport));
+    assert_eq!(
+        wallet.apply_watch_only_update(&update, &passphrase),
+        Err(WalletError::NotWatchOnlyWallet)
+    );
+
+    // the replica is not synced up to the start of the update
+    let gap_update = wallet.export_watch_only(&passphrase, Some(BlockHeight::new(5))).unwrap();
+    assert_eq!(
+        replica.apply_watch_only_update(&gap_update, &passphrase),
+        Err(WalletError::WatchOnlyUpdateGap(
+            BlockHeight::new(5),
+            since_height
+        ))
+    );
+
+    let other_wallet = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC2);
+    let other_export = other_wallet.export_watch_only(&passphrase, None).unwrap();
+    assert_eq!(
+        replica.apply_watch_only_update(&other_export, &passphrase),
+        Err(WalletError::WatchOnlyExportWalletMismatch)
+    );
+
+    replica.apply_watch_only_update(&update, &passphrase).unwrap();
+    let total_amount = (block1_amount + block2_amount).unwrap();
+    assert_eq!(get_best_block(&replica), get_best_block(&wallet));
+    assert_eq!(get_coin_balance(&replica), total_amount);
+
+    // the replica can be loaded again
+    let replica = Wallet::load_wallet(
+        chain_config,
+        replica.db,
+        None,
+        |_| Ok(()),
+        WalletType::Hot,
+        false,
+    )
+    .unwrap();
+    assert!(replica.is_watch_only().unwrap());
+    assert_eq!(
+        replica.wallet_info(),
+        (wallet.wallet_info().0, vec![account_name])
+    );
+    assert_eq!(get_coin_balance(&replica), total_amount);
+}
+
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
@@ -1192,444 +1435,435 @@ fn wallet_get_transaction(#[case] seed: Seed) {
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn wallet_list_mainchain_transactions(#[case] seed: Seed) {
+fn wallet_describe_transaction(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
-    let chain_config = Arc::new(create_regtest());
+    let chain_config = Arc::new(create_mainnet());
 
     let mut wallet = create_wallet(chain_config.clone());
-    // Generate a new block which sends reward to the wallet
     let block1_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
-    let (addr, _) = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
-    let dest = addr.into_object();
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
 
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, block1_amount);
+    let (acc1_index, _) = wallet.create_next_account(None).unwrap();
+    let acc1_address = wallet.get_new_address(acc1_index).unwrap().1;
+    let acc1_destination = acc1_address.into_object();
 
-    // send some coins to the address
+    let send_amount = Amount::from_atoms(rng.gen_range(1..block1_amount.into_atoms()));
     let tx = wallet
         .create_transaction_to_addresses(
             DEFAULT_ACCOUNT_INDEX,
-            [TxOutput::Transfer(OutputValue::Coin(block1_amount), dest.clone())],
+            [TxOutput::Transfer(OutputValue::Coin(send_amount), acc1_destination.clone())],
             SelectedInputs::Utxos(vec![]),
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
         )
         .unwrap();
+    let tx_id = tx.transaction().get_id();
 
-    let send_tx_id = tx.transaction().get_id();
+    let _ = create_block(&chain_config, &mut wallet, vec![tx], Amount::ZERO, 1);
 
-    // put the tx in a block and scan it as confirmed
-    let _ = create_block(
-        &chain_config,
-        &mut wallet,
-        vec![tx.clone()],
-        Amount::ZERO,
-        1,
+    // The change output is not mentioned
+    assert_eq!(
+        wallet.describe_transaction(DEFAULT_ACCOUNT_INDEX, tx_id).unwrap(),
+        vec![TxDescriptionItem::Sent {
+            currency: Currency::Coin,
+            amount: send_amount,
+            destination: acc1_destination.clone(),
+        }]
     );
-
-    let tx = wallet
-        .create_transaction_to_addresses(
-            DEFAULT_ACCOUNT_INDEX,
-            [gen_random_transfer(&mut rng, block1_amount)],
-            SelectedInputs::Utxos(vec![]),
-            BTreeMap::new(),
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-        )
-        .unwrap();
-    let spend_from_tx_id = tx.transaction().get_id();
-
-    let _ = create_block(
-        &chain_config,
-        &mut wallet,
-        vec![tx.clone()],
-        Amount::ZERO,
-        2,
+    assert_eq!(
+        wallet.describe_transaction(acc1_index, tx_id).unwrap(),
+        vec![TxDescriptionItem::Received {
+            currency: Currency::Coin,
+            amount: send_amount,
+            destination: acc1_destination,
+        }]
     );
-
-    let txs = wallet.mainchain_transactions(DEFAULT_ACCOUNT_INDEX, Some(dest), 100).unwrap();
-    // should have 2 txs the send to and the spent from
-    assert_eq!(txs.len(), 2);
-    assert!(txs.iter().any(|info| info.id == send_tx_id));
-    assert!(txs.iter().any(|info| info.id == spend_from_tx_id));
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn wallet_transaction_with_fees(#[case] seed: Seed) {
+fn wallet_transaction_history(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
     let chain_config = Arc::new(create_mainnet());
 
     let mut wallet = create_wallet(chain_config.clone());
-
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, Amount::ZERO);
-
-    // Generate a new block which sends reward to the wallet
     let block1_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
     let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
 
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, block1_amount);
-
-    // Check that if the feerate is too high UTXOs will be filtered out
-    // if their value is < the fee needed to spend that input
-    {
-        // feerate such that the fee for the input is larger than the input amount
-        // 103 is the size of the signature required for the input
-        let very_big_feerate =
-            FeeRate::from_total_tx_fee(block1_amount.into(), NonZeroUsize::new(103).unwrap())
-                .unwrap();
-        let err = wallet
-            .create_transaction_to_addresses(
-                DEFAULT_ACCOUNT_INDEX,
-                [gen_random_transfer(&mut rng, Amount::from_atoms(1))],
-                SelectedInputs::Utxos(vec![]),
-                BTreeMap::new(),
-                very_big_feerate,
-                very_big_feerate,
-            )
-            .unwrap_err();
-
-        match err {
-            WalletError::CoinSelectionError(UtxoSelectorError::NoUtxos) => {}
-            _ => panic!("wrong error"),
-        }
-    }
-
-    let num_outputs = rng.gen_range(1..10);
-    let amount_to_transfer = Amount::from_atoms(
-        rng.gen_range(1..=(block1_amount.into_atoms() / num_outputs - NETWORK_FEE)),
-    );
-
-    let outputs: Vec<TxOutput> = (0..num_outputs)
-        .map(|_| gen_random_transfer(&mut rng, amount_to_transfer))
-        .collect();
+    let (acc1_index, _) = wallet.create_next_account(None).unwrap();
+    let acc1_destination = wallet.get_new_address(acc1_index).unwrap().1.into_object();
 
     let feerate = FeeRate::from_amount_per_kb(Amount::from_atoms(1000));
-    let transaction = wallet
+    let send_amount = Amount::from_atoms(rng.gen_range(1..block1_amount.into_atoms() / 2));
+    let tx = wallet
         .create_transaction_to_addresses(
             DEFAULT_ACCOUNT_INDEX,
-            outputs,
+            [TxOutput::Transfer(OutputValue::Coin(send_amount), acc1_destination.clone())],
             SelectedInputs::Utxos(vec![]),
             BTreeMap::new(),
             feerate,
             feerate,
         )
         .unwrap();
+    let tx1_id = tx.transaction().get_id();
+    let outputs_amount = tx
+        .transaction()
+        .outputs()
+        .iter()
+        .map(|output| match output {
+            TxOutput::Transfer(OutputValue::Coin(amount), _) => *amount,
+            _ => panic!("unexpected output {output:?}"),
+        })
+        .sum::<Option<Amount>>()
+        .unwrap();
+    let tx1_fee = (block1_amount - outputs_amount).unwrap();
+    assert!(tx1_fee > Amount::ZERO);
 
-    let tx_size = serialization::Encode::encoded_size(&transaction);
-    let fee = feerate.compute_fee(tx_size).unwrap();
+    let (_, block2) = create_block(&chain_config, &mut wallet, vec![tx], Amount::ZERO, 1);
 
-    // register the successful transaction and check the balance
-    wallet
-        .add_account_unconfirmed_tx(
+    // An unconfirmed transaction goes after the confirmed ones
+    let tx = wallet
+        .create_transaction_to_addresses(
             DEFAULT_ACCOUNT_INDEX,
-            transaction.clone(),
-            &WalletEventsNoOp,
+            [TxOutput::Transfer(OutputValue::Coin(send_amount), acc1_destination.clone())],
+            SelectedInputs::Utxos(vec![]),
+            BTreeMap::new(),
+            feerate,
+            feerate,
         )
         .unwrap();
-    let coin_balance = get_coin_balance_with_inactive(&wallet);
-    assert!(coin_balance <= ((block1_amount - amount_to_transfer).unwrap() - fee.into()).unwrap());
-}
-
-#[test]
-fn lock_wallet_fail_empty_password() {
-    let chain_config = Arc::new(create_mainnet());
+    let tx2_id = tx.transaction().get_id();
+    wallet
+        .add_account_unconfirmed_tx(DEFAULT_ACCOUNT_INDEX, tx, &WalletEventsNoOp)
+        .unwrap();
 
-    let mut wallet = create_wallet(chain_config);
-    let empty_password = Some(String::new());
+    let history = wallet.get_transaction_history(DEFAULT_ACCOUNT_INDEX).unwrap();
     assert_eq!(
-        wallet.encrypt_wallet(&empty_password),
-        Err(WalletError::DatabaseError(
-            wallet_storage::Error::WalletEmptyPassword
-        ))
-    );
+        history.iter().map(|tx| tx.txid).collect::<Vec<_>>(),
+        vec![tx1_id, tx2_id]
+    );
+    assert_eq!(history[0].fee, Some(tx1_fee));
+    assert_eq!(history[0].timestamp, Some(block2.timestamp()));
+    assert_eq!(history[0].state.block_height(), Some(BlockHeight::new(2)));
+    assert!(history[1].fee.is_some());
+    assert_eq!(history[1].timestamp, None);
+
+    // The fee has been paid by the sender
+    let history = wallet.get_transaction_history(acc1_index).unwrap();
+    let received_tx = history.iter().find(|tx| tx.txid == tx1_id).unwrap();
+    assert_eq!(received_tx.fee, None);
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn spend_from_user_specified_utxos(#[case] seed: Seed) {
+fn send_with_absolute_fee(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
     let chain_config = Arc::new(create_mainnet());
 
     let mut wallet = create_wallet(chain_config.clone());
+    let block1_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
 
-    // Generate a new block which sends reward to the wallet
-    let utxo_amount = Amount::from_atoms(rng.gen_range(100..10000));
-    let reward_outputs = (0..10)
-        .map(|idx| {
-            let address = get_address(
-                &chain_config,
-                MNEMONIC,
-                DEFAULT_ACCOUNT_INDEX,
-                KeyPurpose::ReceiveFunds,
-                idx.try_into().unwrap(),
-            );
-            make_address_output(address, utxo_amount)
-        })
-        .collect_vec();
-    let block1 = Block::new(
-        vec![],
-        chain_config.genesis_block_id(),
-        chain_config.genesis_block().timestamp(),
-        ConsensusData::None,
-        BlockReward::new(reward_outputs),
-    )
-    .unwrap();
-    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1]);
+    let destination = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1.into_object();
+    let fee = Amount::from_atoms(rng.gen_range(1..block1_amount.into_atoms() / 4));
+    let send_amount = Amount::from_atoms(rng.gen_range(1..block1_amount.into_atoms() / 2));
 
-    let utxos = wallet
-        .get_utxos(
+    let tx = wallet
+        .create_transaction_to_addresses_with_absolute_fee(
             DEFAULT_ACCOUNT_INDEX,
-            UtxoType::Transfer.into(),
-            UtxoState::Confirmed.into(),
-            WithLocked::Unlocked,
+            [TxOutput::Transfer(OutputValue::Coin(send_amount), destination)],
+            SelectedInputs::Utxos(vec![]),
+            BTreeMap::new(),
+            fee,
         )
         .unwrap();
-    let burn_amount = Amount::from_atoms(rng.gen_range(1..utxo_amount.into_atoms()));
 
-    {
-        let missing_utxo =
-            UtxoOutPoint::new(OutPointSourceId::Transaction(Id::new(H256::zero())), 123);
-        let err = wallet
-            .create_transaction_to_addresses(
-                DEFAULT_ACCOUNT_INDEX,
-                [TxOutput::Burn(OutputValue::Coin(burn_amount))],
-                SelectedInputs::Utxos(vec![missing_utxo.clone()]),
-                BTreeMap::new(),
-                FeeRate::from_amount_per_kb(Amount::ZERO),
-                FeeRate::from_amount_per_kb(Amount::ZERO),
-            )
-            .unwrap_err();
-        assert_eq!(err, WalletError::CannotFindUtxo(missing_utxo.clone()));
+    let outputs_amount = tx
+        .transaction()
+        .outputs()
+        .iter()
+        .map(|output| match output {
+            TxOutput::Transfer(OutputValue::Coin(amount), _) => *amount,
+            _ => panic!("unexpected output {output:?}"),
+        })
+        .sum::<Option<Amount>>()
+        .unwrap();
+    assert_eq!((block1_amount - outputs_amount).unwrap(), fee);
 
-        let err = wallet
-            .find_used_tokens(DEFAULT_ACCOUNT_INDEX, &[missing_utxo.clone()])
-            .unwrap_err();
+    // The spent inputs are still known to the wallet, so the fee can be calculated afterwards
+    wallet.add_unconfirmed_tx(tx.clone(), &WalletEventsNoOp).unwrap();
+    let inputs_amount = tx
+        .transaction()
+        .inputs()
+        .iter()
+        .map(|input| match input {
+            TxInput::Utxo(outpoint) => match wallet.find_txo(outpoint).unwrap() {
+                TxOutput::Transfer(OutputValue::Coin(amount), _) => amount,
+                output => panic!("unexpected input utxo {output:?}"),
+            },
+            _ => panic!("unexpected input {input:?}"),
+        })
+        .sum::<Option<Amount>>()
+        .unwrap();
+    assert_eq!(inputs_amount, block1_amount);
 
-        assert_eq!(err, WalletError::CannotFindUtxo(missing_utxo));
-    }
+    // The fee can't be paid if it exceeds the balance
+    let err = wallet
+        .create_transaction_to_addresses_with_absolute_fee(
+            DEFAULT_ACCOUNT_INDEX,
+            [TxOutput::Transfer(OutputValue::Coin(send_amount), Destination::AnyoneCanSpend)],
+            SelectedInputs::Utxos(vec![]),
+            BTreeMap::new(),
+            (block1_amount + Amount::from_atoms(1)).unwrap(),
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        WalletError::CoinSelectionError(UtxoSelectorError::NotEnoughFunds(_, _))
+    ));
+}
 
-    let selected_utxos = utxos
-        .iter()
-        .map(|(outpoint, _, _)| outpoint)
-        .take(rng.gen_range(1..utxos.len()))
-        .cloned()
-        .collect_vec();
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn confirmed_utxos_and_rewind(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let mut wallet = create_wallet(chain_config.clone());
+    let block1_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+    let (_, block1) = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+    let block1_reward = UtxoOutPoint::new(block1.get_id().into(), 0);
 
+    let utxos = wallet.get_confirmed_utxos();
+    assert_eq!(utxos.len(), 1);
+    let (account, utxo) = &utxos[0];
+    assert_eq!(*account, DEFAULT_ACCOUNT_INDEX);
+    assert_eq!(utxo.outpoint, block1_reward);
+    assert_eq!(utxo.created_at, BlockHeight::new(1));
+    assert_eq!(utxo.spent_at, None);
+
+    let destination = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1.into_object();
+    let send_amount = Amount::from_atoms(rng.gen_range(1..block1_amount.into_atoms()));
     let tx = wallet
         .create_transaction_to_addresses(
             DEFAULT_ACCOUNT_INDEX,
-            [TxOutput::Burn(OutputValue::Coin(burn_amount))],
-            SelectedInputs::Utxos(selected_utxos.clone()),
+            [TxOutput::Transfer(OutputValue::Coin(send_amount), destination)],
+            SelectedInputs::Utxos(vec![]),
             BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
         )
         .unwrap();
+    let tx_id = tx.transaction().get_id();
 
-    // check that we only have the selected_utxo as inputs
-    assert_eq!(tx.inputs().len(), selected_utxos.len());
-    for tx_input in tx.inputs() {
-        assert!(selected_utxos.contains(tx_input.utxo_outpoint().unwrap()));
-    }
+    // Spending by an unconfirmed transaction doesn't change the node's UTXO set
+    wallet.add_unconfirmed_tx(tx.clone(), &WalletEventsNoOp).unwrap();
+    let utxos = wallet.get_confirmed_utxos();
+    assert_eq!(utxos.len(), 1);
+    assert_eq!(utxos[0].1.spent_at, None);
 
-    // check that there is a change output
-    assert_eq!(tx.outputs().len(), 2);
-    let change_amount =
-        ((utxo_amount * selected_utxos.len() as u128).unwrap() - burn_amount).unwrap();
-    for out in tx.outputs() {
-        match out {
-            TxOutput::Transfer(value, _) => {
-                assert_eq!(value.coin_amount().unwrap(), change_amount)
-            }
-            TxOutput::Burn(value) => assert_eq!(value.coin_amount().unwrap(), burn_amount),
-            _ => panic!("unexpected output"),
-        }
-    }
+    let block2_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+    let _ = create_block(&chain_config, &mut wallet, vec![tx], block2_amount, 1);
 
-    {
-        wallet
-            .add_account_unconfirmed_tx(DEFAULT_ACCOUNT_INDEX, tx, &WalletEventsNoOp)
-            .unwrap();
-        // Try to select the same UTXOs now they should be already consumed
+    let utxos = wallet.get_confirmed_utxos();
+    let spent = utxos.iter().find(|(_, utxo)| utxo.outpoint == block1_reward).unwrap();
+    assert_eq!(spent.1.spent_at, Some(BlockHeight::new(2)));
+    let tx_outputs = utxos
+        .iter()
+        .filter(|(_, utxo)| utxo.outpoint.source_id() == OutPointSourceId::Transaction(tx_id))
+        .collect::<Vec<_>>();
+    assert!(!tx_outputs.is_empty());
+    assert!(tx_outputs
+        .iter()
+        .all(|(_, utxo)| utxo.created_at == BlockHeight::new(2) && utxo.spent_at.is_none()));
 
-        let err = wallet
-            .create_transaction_to_addresses(
-                DEFAULT_ACCOUNT_INDEX,
-                [TxOutput::Burn(OutputValue::Coin(burn_amount))],
-                SelectedInputs::Utxos(selected_utxos.clone()),
-                BTreeMap::new(),
-                FeeRate::from_amount_per_kb(Amount::ZERO),
-                FeeRate::from_amount_per_kb(Amount::ZERO),
-            )
-            .unwrap_err();
+    // After rewinding, the second block is forgotten and its outputs are no longer tracked
+    wallet
+        .rewind_to_height(
+            BlockHeight::new(1),
+            block1.get_id().into(),
+            &WalletEventsNoOp,
+        )
+        .unwrap();
+    assert_eq!(
+        get_best_block(&wallet),
+        (block1.get_id().into(), BlockHeight::new(1))
+    );
 
-        assert_eq!(
-            err,
-            WalletError::ConsumedUtxo(selected_utxos.first().unwrap().clone())
-        );
-    }
+    let utxos = wallet.get_confirmed_utxos();
+    assert_eq!(utxos.len(), 1);
+    assert_eq!(utxos[0].1.outpoint, block1_reward);
+    assert_eq!(utxos[0].1.spent_at, None);
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn create_stake_pool_and_list_pool_ids(#[case] seed: Seed) {
-    use crypto::vrf::transcript::no_rng::VRFTranscript;
-
+fn wallet_signing_log(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
-    let chain_config = Arc::new(create_regtest());
+    let chain_config = Arc::new(create_mainnet());
 
     let mut wallet = create_wallet(chain_config.clone());
+    let block1_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
 
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, Amount::ZERO);
+    assert_eq!(wallet.get_signing_log(None, None).unwrap(), vec![]);
 
-    // Generate a new block which sends reward to the wallet
-    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
-    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+    let destination = Destination::PublicKeyHash(PublicKeyHash::from_low_u64_be(1));
+    let send_amount = Amount::from_atoms(rng.gen_range(1..block1_amount.into_atoms()));
+    let tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            [TxOutput::Transfer(OutputValue::Coin(send_amount), destination.clone())],
+            SelectedInputs::Utxos(vec![]),
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
 
-    let pool_ids = wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
-    assert!(pool_ids.is_empty());
+    let log = wallet.get_signing_log(None, None).unwrap();
+    assert_eq!(log.len(), 1);
+    let entry = &log[0];
+    assert_eq!(entry.tx_id, tx.transaction().get_id());
+    assert_eq!(entry.account_index, DEFAULT_ACCOUNT_INDEX);
+    assert_eq!(entry.requester, SigningRequester::Wallet);
+    assert!(entry.fully_signed);
+    assert!(entry.outputs.contains(&SigningLogOutput {
+        destination: Some(destination),
+        token_id: None,
+        amount: send_amount,
+    }));
+
+    // Filtering by time
+    let after = BlockTimestamp::from_int_seconds(entry.timestamp.as_int_seconds() + 1);
+    assert_eq!(wallet.get_signing_log(Some(after), None).unwrap(), vec![]);
+    assert_eq!(
+        wallet.get_signing_log(Some(entry.timestamp), Some(entry.timestamp)).unwrap(),
+        log
+    );
 
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, block1_amount);
+    // The log can only be read while the wallet is unlocked
+    let password = Some("password".into());
+    wallet.encrypt_wallet(&password).unwrap();
+    wallet.lock_wallet().unwrap();
+    let err = wallet.get_signing_log(None, None).unwrap_err();
+    assert_eq!(
+        err,
+        WalletError::DatabaseError(wallet_storage::Error::WalletLocked)
+    );
+    wallet.unlock_wallet(&password.unwrap()).unwrap();
+    assert_eq!(wallet.get_signing_log(None, None).unwrap(), log);
+}
 
-    let pool_amount = block1_amount;
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_list_mainchain_transactions(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
 
-    let decommission_key = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+    let mut wallet = create_wallet(chain_config.clone());
+    // Generate a new block which sends reward to the wallet
+    let block1_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+    let (addr, _) = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+    let dest = addr.into_object();
 
-    let stake_pool_transaction = wallet
-        .create_stake_pool_tx(
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, block1_amount);
+
+    // send some coins to the address
+    let tx = wallet
+        .create_transaction_to_addresses(
             DEFAULT_ACCOUNT_INDEX,
+            [TxOutput::Transfer(OutputValue::Coin(block1_amount), dest.clone())],
+            SelectedInputs::Utxos(vec![]),
+            BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
-            StakePoolDataArguments {
-                amount: pool_amount,
-                margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
-                cost_per_block: Amount::ZERO,
-                decommission_key: decommission_key.as_object().clone(),
-            },
         )
         .unwrap();
-    let stake_pool_transaction_id = stake_pool_transaction.transaction().get_id();
-    let (addr, block2) = create_block(
+
+    let send_tx_id = tx.transaction().get_id();
+
+    // put the tx in a block and scan it as confirmed
+    let _ = create_block(
         &chain_config,
         &mut wallet,
-        vec![stake_pool_transaction],
+        vec![tx.clone()],
         Amount::ZERO,
         1,
     );
 
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, Amount::ZERO);
-
-    let pool_ids = wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
-    assert_eq!(pool_ids.len(), 1);
+    let tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            [gen_random_transfer(&mut rng, block1_amount)],
+            SelectedInputs::Utxos(vec![]),
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+    let spend_from_tx_id = tx.transaction().get_id();
 
-    let (pool_id, pool_data) = pool_ids.first().unwrap();
-    assert_eq!(&pool_data.decommission_key, decommission_key.as_object());
-    assert_eq!(
-        &pool_data.utxo_outpoint,
-        &UtxoOutPoint::new(OutPointSourceId::Transaction(stake_pool_transaction_id), 0)
+    let _ = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![tx.clone()],
+        Amount::ZERO,
+        2,
     );
 
-    let mut create_stake_pool_utxos = wallet
-        .get_utxos(
-            DEFAULT_ACCOUNT_INDEX,
-            UtxoType::CreateStakePool.into(),
-            UtxoState::Confirmed.into(),
-            WithLocked::Unlocked,
-        )
+    let txs = wallet
+        .mainchain_transactions(DEFAULT_ACCOUNT_INDEX, Some(dest.clone()), 100)
         .unwrap();
-    assert_eq!(create_stake_pool_utxos.len(), 1);
-    let (_, output, _) = create_stake_pool_utxos.pop().unwrap();
-    match output {
-        TxOutput::CreateStakePool(id, data) => {
-            assert_eq!(id, *pool_id);
-            assert_eq!(data.pledge(), pool_amount);
-        }
-        _ => panic!("wrong TxOutput type"),
+    // should have 2 txs the send to and the spent from
+    assert_eq!(txs.len(), 2);
+    assert!(txs.iter().any(|info| info.id == send_tx_id));
+    assert!(txs.iter().any(|info| info.id == spend_from_tx_id));
+
+    // list them page by page in both orders
+    let page = |after, newest_first| {
+        wallet
+            .mainchain_transactions_page(
+                DEFAULT_ACCOUNT_INDEX,
+                Some(dest.clone()),
+                after,
+                newest_first,
+                1,
+            )
+            .unwrap()
+            .into_iter()
+            .map(|info| (info.height, info.id))
+            .collect::<Vec<_>>()
     };
+    let send_tx_position = (BlockHeight::new(1), send_tx_id);
+    let spend_from_tx_position = (BlockHeight::new(2), spend_from_tx_id);
+    assert_eq!(page(None, false), vec![send_tx_position]);
+    assert_eq!(
+        page(Some(send_tx_position), false),
+        vec![spend_from_tx_position]
+    );
+    assert_eq!(page(Some(spend_from_tx_position), false), vec![]);
+    assert_eq!(page(None, true), vec![spend_from_tx_position]);
+    assert_eq!(
+        page(Some(spend_from_tx_position), true),
+        vec![send_tx_position]
+    );
+    assert_eq!(page(Some(send_tx_position), true), vec![]);
+}
 
-    let pos_data = wallet.get_pos_gen_block_data(DEFAULT_ACCOUNT_INDEX, *pool_id).unwrap();
-
-    let block3 = Block::new(
-        vec![],
-        chain_config.genesis_block_id(),
-        chain_config.genesis_block().timestamp(),
-        ConsensusData::PoS(Box::new(PoSData::new(
-            vec![TxInput::Utxo(UtxoOutPoint::new(
-                OutPointSourceId::Transaction(stake_pool_transaction_id),
-                0,
-            ))],
-            vec![],
-            *pool_id,
-            pos_data.vrf_private_key().produce_vrf_data(VRFTranscript::new(&[])),
-            common::primitives::Compact(0),
-        ))),
-        BlockReward::new(vec![TxOutput::ProduceBlockFromStake(
-            addr.into_object(),
-            *pool_id,
-        )]),
-    )
-    .unwrap();
-
-    scan_wallet(&mut wallet, BlockHeight::new(2), vec![block3.clone()]);
-
-    let pool_ids = wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
-    assert_eq!(pool_ids.len(), 1);
-    let (_pool_id, pool_data) = pool_ids.first().unwrap();
-    assert_eq!(
-        &pool_data.utxo_outpoint,
-        &UtxoOutPoint::new(OutPointSourceId::BlockReward(block3.get_id().into()), 0)
-    );
-
-    // do a reorg back to block 2
-    scan_wallet(&mut wallet, BlockHeight::new(1), vec![block2.clone()]);
-    let pool_ids = wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
-    assert_eq!(pool_ids.len(), 1);
-    let (pool_id, pool_data) = pool_ids.first().unwrap();
-    assert_eq!(
-        &pool_data.utxo_outpoint,
-        &UtxoOutPoint::new(OutPointSourceId::Transaction(stake_pool_transaction_id), 0)
-    );
-
-    let decommission_tx = wallet
-        .decommission_stake_pool(
-            DEFAULT_ACCOUNT_INDEX,
-            *pool_id,
-            pool_amount,
-            None,
-            FeeRate::from_amount_per_kb(Amount::from_atoms(0)),
-        )
-        .unwrap();
-
-    let _ = create_block(
-        &chain_config,
-        &mut wallet,
-        vec![decommission_tx],
-        Amount::ZERO,
-        2,
-    );
-    let pool_ids = wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
-    assert!(pool_ids.is_empty());
-
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, pool_amount,);
-}
-
-#[rstest]
-#[trace]
-#[case(Seed::from_entropy())]
-fn reset_keys_after_failed_transaction(#[case] seed: Seed) {
-    let mut rng = make_seedable_rng(seed);
-    let chain_config = Arc::new(create_mainnet());
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_transaction_with_fees(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
 
     let mut wallet = create_wallet(chain_config.clone());
 
@@ -1637,349 +1871,523 @@ fn reset_keys_after_failed_transaction(#[case] seed: Seed) {
     assert_eq!(coin_balance, Amount::ZERO);
 
     // Generate a new block which sends reward to the wallet
-    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let block1_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
     let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
 
     let coin_balance = get_coin_balance(&wallet);
     assert_eq!(coin_balance, block1_amount);
 
-    let not_enough = (block1_amount + Amount::from_atoms(1)).unwrap();
+    // Check that if the feerate is too high UTXOs will be filtered out
+    // if their value is < the fee needed to spend that input
+    {
+        // feerate such that the fee for the input is larger than the input amount
+        // 103 is the size of the signature required for the input
+        let very_big_feerate =
+            FeeRate::from_total_tx_fee(block1_amount.into(), NonZeroUsize::new(103).unwrap())
+                .unwrap();
+        let err = wallet
+            .create_transaction_to_addresses(
+                DEFAULT_ACCOUNT_INDEX,
+                [gen_random_transfer(&mut rng, Amount::from_atoms(1))],
+                SelectedInputs::Utxos(vec![]),
+                BTreeMap::new(),
+                very_big_feerate,
+                very_big_feerate,
+            )
+            .unwrap_err();
 
-    let last_issued_address =
-        wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap().last_issued();
+        match err {
+            WalletError::CoinSelectionError(UtxoSelectorError::NoUtxos) => {}
+            _ => panic!("wrong error"),
+        }
+    }
 
-    let result = wallet.create_stake_pool_tx(
-        DEFAULT_ACCOUNT_INDEX,
-        FeeRate::from_amount_per_kb(Amount::ZERO),
-        FeeRate::from_amount_per_kb(Amount::ZERO),
-        StakePoolDataArguments {
-            amount: not_enough,
-            margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
-            cost_per_block: Amount::ZERO,
-            decommission_key: Destination::AnyoneCanSpend,
-        },
-    );
-    // check that result is an error and we last issued address is still the same
-    assert!(result.is_err());
-    assert_eq!(
-        last_issued_address,
-        wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap().last_issued(),
+    let num_outputs = rng.gen_range(1..10);
+    let amount_to_transfer = Amount::from_atoms(
+        rng.gen_range(1..=(block1_amount.into_atoms() / num_outputs - NETWORK_FEE)),
     );
-}
-
-#[rstest]
-#[trace]
-#[case(Seed::from_entropy())]
-fn send_to_unknown_delegation(#[case] seed: Seed) {
-    let mut rng = make_seedable_rng(seed);
-    let chain_config = Arc::new(create_mainnet());
-
-    let mut wallet = create_wallet(chain_config.clone());
-
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, Amount::ZERO);
-
-    // Generate a new block which sends reward to the wallet
-    let delegation_amount = Amount::from_atoms(rng.gen_range(2..100));
-    let block1_amount = delegation_amount;
-    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
 
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, delegation_amount);
+    let outputs: Vec<TxOutput> = (0..num_outputs)
+        .map(|_| gen_random_transfer(&mut rng, amount_to_transfer))
+        .collect();
 
-    let unknown_delegation_id = DelegationId::new(H256::zero());
-    let delegation_stake_tx = wallet
+    let feerate = FeeRate::from_amount_per_kb(Amount::from_atoms(1000));
+    let transaction = wallet
         .create_transaction_to_addresses(
             DEFAULT_ACCOUNT_INDEX,
-            [TxOutput::DelegateStaking(delegation_amount, unknown_delegation_id)],
+            outputs,
             SelectedInputs::Utxos(vec![]),
             BTreeMap::new(),
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-            FeeRate::from_amount_per_kb(Amount::ZERO),
+            feerate,
+            feerate,
         )
         .unwrap();
 
-    let _ = create_block(
-        &chain_config,
-        &mut wallet,
-        vec![delegation_stake_tx],
-        block1_amount,
-        1,
-    );
-
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, block1_amount);
-
-    let (other_acc_idx, _) = wallet.create_next_account(None).unwrap();
-    let address = wallet.get_new_address(other_acc_idx).unwrap().1;
+    let tx_size = serialization::Encode::encoded_size(&transaction);
+    let fee = feerate.compute_fee(tx_size).unwrap();
 
-    let unknown_pool_id = PoolId::new(H256::zero());
-    let (delegation_id, delegation_tx) = wallet
-        .create_delegation(
+    // register the successful transaction and check the balance
+    wallet
+        .add_account_unconfirmed_tx(
             DEFAULT_ACCOUNT_INDEX,
-            vec![make_create_delegation_output(address.clone(), unknown_pool_id)],
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-            FeeRate::from_amount_per_kb(Amount::ZERO),
+            transaction.clone(),
+            &WalletEventsNoOp,
         )
         .unwrap();
+    let coin_balance = get_coin_balance_with_inactive(&wallet);
+    assert!(coin_balance <= ((block1_amount - amount_to_transfer).unwrap() - fee.into()).unwrap());
+}
 
-    let _ = create_block(
-        &chain_config,
-        &mut wallet,
-        vec![delegation_tx],
-        Amount::ZERO,
-        2,
-    );
+#[test]
+fn lock_wallet_fail_empty_password() {
+    let chain_config = Arc::new(create_mainnet());
 
-    // the new delegation even though created from DEFAULT_ACCOUNT_INDEX is not theirs
+    let mut wallet = create_wallet(chain_config);
+    let empty_password = Some(String::new());
     assert_eq!(
-        wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().count(),
-        0
+        wallet.encrypt_wallet(&empty_password),
+        Err(WalletError::DatabaseError(
+            wallet_storage::Error::WalletEmptyPassword
+        ))
     );
-    // the new delegation should be registered to the other account
-    let delegations = wallet.get_delegations(other_acc_idx).unwrap().collect_vec();
-    assert_eq!(delegations.len(), 1);
-    assert_eq!(*delegations.first().unwrap().0, delegation_id);
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn create_spend_from_delegations(#[case] seed: Seed) {
+fn spend_from_user_specified_utxos(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
     let chain_config = Arc::new(create_mainnet());
 
     let mut wallet = create_wallet(chain_config.clone());
 
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, Amount::ZERO);
-
     // Generate a new block which sends reward to the wallet
-    let delegation_amount = Amount::from_atoms(rng.gen_range(2..100));
-    let block1_amount = (chain_config.min_stake_pool_pledge() + delegation_amount).unwrap();
-    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
-
-    let pool_ids = wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
-    assert!(pool_ids.is_empty());
-
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, block1_amount);
-
-    let pool_amount = chain_config.min_stake_pool_pledge();
+    let utxo_amount = Amount::from_atoms(rng.gen_range(100..10000));
+    let reward_outputs = (0..10)
+        .map(|idx| {
+            let address = get_address(
+                &chain_config,
+                MNEMONIC,
+                DEFAULT_ACCOUNT_INDEX,
+                KeyPurpose::ReceiveFunds,
+                idx.try_into().unwrap(),
+            );
+            make_address_output(address, utxo_amount)
+        })
+        .collect_vec();
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(reward_outputs),
+    )
+    .unwrap();
+    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1]);
 
-    let stake_pool_transaction = wallet
-        .create_stake_pool_tx(
+    let utxos = wallet
+        .get_utxos(
             DEFAULT_ACCOUNT_INDEX,
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-            StakePoolDataArguments {
-                amount: pool_amount,
-                margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
-                cost_per_block: Amount::ZERO,
-                decommission_key: Destination::AnyoneCanSpend,
-            },
+            UtxoType::Transfer.into(),
+            UtxoState::Confirmed.into(),
+            WithLocked::Unlocked,
         )
         .unwrap();
+    let burn_amount = Amount::from_atoms(rng.gen_range(1..utxo_amount.into_atoms()));
 
-    let (address, _) = create_block(
-        &chain_config,
-        &mut wallet,
-        vec![stake_pool_transaction],
-        Amount::ZERO,
-        1,
-    );
+    {
+        let missing_utxo =
+            UtxoOutPoint::new(OutPointSourceId::Transaction(Id::new(H256::zero())), 123);
+        let err = wallet
+            .create_transaction_to_addresses(
+                DEFAULT_ACCOUNT_INDEX,
+                [TxOutput::Burn(OutputValue::Coin(burn_amount))],
+                SelectedInputs::Utxos(vec![missing_utxo.clone()]),
+                BTreeMap::new(),
+                FeeRate::from_amount_per_kb(Amount::ZERO),
+                FeeRate::from_amount_per_kb(Amount::ZERO),
+            )
+            .unwrap_err();
+        assert_eq!(err, WalletError::CannotFindUtxo(missing_utxo.clone()));
 
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, (block1_amount - pool_amount).unwrap(),);
+        let err = wallet
+            .find_used_tokens(DEFAULT_ACCOUNT_INDEX, &[missing_utxo.clone()])
+            .unwrap_err();
 
-    let pool_ids = wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
-    assert_eq!(pool_ids.len(), 1);
+        assert_eq!(err, WalletError::CannotFindUtxo(missing_utxo));
+    }
 
-    let pool_id = pool_ids.first().unwrap().0;
-    let (delegation_id, delegation_tx) = wallet
-        .create_delegation(
+    let selected_utxos = utxos
+        .iter()
+        .map(|(outpoint, _, _)| outpoint)
+        .take(rng.gen_range(1..utxos.len()))
+        .cloned()
+        .collect_vec();
+
+    let tx = wallet
+        .create_transaction_to_addresses(
             DEFAULT_ACCOUNT_INDEX,
-            vec![make_create_delegation_output(address.clone(), pool_id)],
+            [TxOutput::Burn(OutputValue::Coin(burn_amount))],
+            SelectedInputs::Utxos(selected_utxos.clone()),
+            BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
         )
         .unwrap();
 
-    let _ = create_block(
-        &chain_config,
-        &mut wallet,
-        vec![delegation_tx],
-        Amount::ZERO,
-        2,
-    );
+    // check that we only have the selected_utxo as inputs
+    assert_eq!(tx.inputs().len(), selected_utxos.len());
+    for tx_input in tx.inputs() {
+        assert!(selected_utxos.contains(tx_input.utxo_outpoint().unwrap()));
+    }
 
-    let mut delegations = wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().collect_vec();
-    assert_eq!(delegations.len(), 1);
-    let (deleg_id, deleg_data) = delegations.pop().unwrap();
-    assert_eq!(*deleg_id, delegation_id);
-    assert!(deleg_data.not_staked_yet);
-    assert_eq!(deleg_data.last_nonce, None);
-    assert_eq!(deleg_data.pool_id, pool_id);
-    assert_eq!(&deleg_data.destination, address.as_object());
+    // check that there is a change output
+    assert_eq!(tx.outputs().len(), 2);
+    let change_amount =
+        ((utxo_amount * selected_utxos.len() as u128).unwrap() - burn_amount).unwrap();
+    for out in tx.outputs() {
+        match out {
+            TxOutput::Transfer(value, _) => {
+                assert_eq!(value.coin_amount().unwrap(), change_amount)
+            }
+            TxOutput::Burn(value) => assert_eq!(value.coin_amount().unwrap(), burn_amount),
+            _ => panic!("unexpected output"),
+        }
+    }
 
-    let delegation_stake_tx = wallet
-        .create_transaction_to_addresses(
+    {
+        wallet
+            .add_account_unconfirmed_tx(DEFAULT_ACCOUNT_INDEX, tx, &WalletEventsNoOp)
+            .unwrap();
+        // Try to select the same UTXOs now they should be already consumed
+
+        let err = wallet
+            .create_transaction_to_addresses(
+                DEFAULT_ACCOUNT_INDEX,
+                [TxOutput::Burn(OutputValue::Coin(burn_amount))],
+                SelectedInputs::Utxos(selected_utxos.clone()),
+                BTreeMap::new(),
+                FeeRate::from_amount_per_kb(Amount::ZERO),
+                FeeRate::from_amount_per_kb(Amount::ZERO),
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            WalletError::ConsumedUtxo(selected_utxos.first().unwrap().clone())
+        );
+    }
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn create_stake_pool_and_list_pool_ids(#[case] seed: Seed) {
+    use crypto::vrf::transcript::no_rng::VRFTranscript;
+
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, Amount::ZERO);
+
+    // Generate a new block which sends reward to the wallet
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let pool_ids = wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
+    assert!(pool_ids.is_empty());
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, block1_amount);
+
+    let pool_amount = block1_amount;
+
+    let decommission_key = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+
+    let stake_pool_transaction = wallet
+        .create_stake_pool_tx(
             DEFAULT_ACCOUNT_INDEX,
-            [TxOutput::DelegateStaking(delegation_amount, delegation_id)],
-            SelectedInputs::Utxos(vec![]),
-            BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
+            StakePoolDataArguments {
+                amount: pool_amount,
+                margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
+                cost_per_block: Amount::ZERO,
+                decommission_key: decommission_key.as_object().clone(),
+            },
         )
         .unwrap();
-
-    let _ = create_block(
+    let stake_pool_transaction_id = stake_pool_transaction.transaction().get_id();
+    let (addr, block2) = create_block(
         &chain_config,
         &mut wallet,
-        vec![delegation_stake_tx],
+        vec![stake_pool_transaction],
         Amount::ZERO,
-        3,
+        1,
     );
 
-    let delegation_tx1 = wallet
-        .create_transaction_to_addresses_from_delegation(
-            DEFAULT_ACCOUNT_INDEX,
-            address.clone(),
-            Amount::from_atoms(1),
-            delegation_id,
-            Amount::from_atoms(2),
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-        )
-        .unwrap();
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, Amount::ZERO);
 
-    wallet
-        .add_account_unconfirmed_tx(
+    let pool_ids = wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
+    assert_eq!(pool_ids.len(), 1);
+
+    let (pool_id, pool_data) = pool_ids.first().unwrap();
+    assert_eq!(&pool_data.decommission_key, decommission_key.as_object());
+    assert_eq!(
+        &pool_data.utxo_outpoint,
+        &UtxoOutPoint::new(OutPointSourceId::Transaction(stake_pool_transaction_id), 0)
+    );
+
+    // The pool uses one of the issued VRF keys
+    let pool_ids_by_vrf_key = wallet.get_pool_ids_by_vrf_public_key(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(
+        pool_ids_by_vrf_key,
+        BTreeMap::from([(pool_data.vrf_public_key.clone(), vec![*pool_id])])
+    );
+    let issued_vrf_keys = wallet.get_all_issued_vrf_public_keys(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert!(issued_vrf_keys
+        .values()
+        .any(|(address, _used)| address.as_object() == &pool_data.vrf_public_key));
+
+    let mut create_stake_pool_utxos = wallet
+        .get_utxos(
             DEFAULT_ACCOUNT_INDEX,
-            delegation_tx1.clone(),
-            &WalletEventsNoOp,
+            UtxoType::CreateStakePool.into(),
+            UtxoState::Confirmed.into(),
+            WithLocked::Unlocked,
         )
         .unwrap();
-    let delegation_tx1 = vec![delegation_tx1];
+    assert_eq!(create_stake_pool_utxos.len(), 1);
+    let (_, output, _) = create_stake_pool_utxos.pop().unwrap();
+    match output {
+        TxOutput::CreateStakePool(id, data) => {
+            assert_eq!(id, *pool_id);
+            assert_eq!(data.pledge(), pool_amount);
+        }
+        _ => panic!("wrong TxOutput type"),
+    };
 
-    // Check delegation balance after unconfirmed tx status
-    let mut delegations = wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().collect_vec();
-    assert_eq!(delegations.len(), 1);
-    let (deleg_id, deleg_data) = delegations.pop().unwrap();
-    assert_eq!(*deleg_id, delegation_id);
-    assert_eq!(deleg_data.last_nonce, Some(AccountNonce::new(0)));
+    let pos_data = wallet.get_pos_gen_block_data(DEFAULT_ACCOUNT_INDEX, *pool_id).unwrap();
 
-    // Send delegation to account 1
-    // test that account 1 will receive the money but not register the delegation id as theirs
-    let (other_acc_idx, _) = wallet.create_next_account(None).unwrap();
-    let address = wallet.get_new_address(other_acc_idx).unwrap().1;
+    let block3 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::PoS(Box::new(PoSData::new(
+            vec![TxInput::Utxo(UtxoOutPoint::new(
+                OutPointSourceId::Transaction(stake_pool_transaction_id),
+                0,
+            ))],
+            vec![],
+            *pool_id,
+            pos_data.vrf_private_key().produce_vrf_data(VRFTranscript::new(&[])),
+            common::primitives::Compact(0),
+        ))),
+        BlockReward::new(vec![TxOutput::ProduceBlockFromStake(
+            addr.into_object(),
+            *pool_id,
+        )]),
+    )
+    .unwrap();
 
-    let delegation_tx2 = wallet
-        .create_transaction_to_addresses_from_delegation(
+    scan_wallet(&mut wallet, BlockHeight::new(2), vec![block3.clone()]);
+
+    let pool_ids = wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
+    assert_eq!(pool_ids.len(), 1);
+    let (_pool_id, pool_data) = pool_ids.first().unwrap();
+    assert_eq!(
+        &pool_data.utxo_outpoint,
+        &UtxoOutPoint::new(OutPointSourceId::BlockReward(block3.get_id().into()), 0)
+    );
+
+    let staked_blocks = wallet
+        .get_staked_blocks(
             DEFAULT_ACCOUNT_INDEX,
-            address.clone(),
-            Amount::from_atoms(1),
-            delegation_id,
-            Amount::from_atoms(1),
-            FeeRate::from_amount_per_kb(Amount::ZERO),
+            BlockHeight::zero(),
+            BlockHeight::max(),
         )
         .unwrap();
-    wallet
-        .add_account_unconfirmed_tx(
+    assert_eq!(staked_blocks.len(), 1);
+    assert_eq!(staked_blocks[0].pool_id, *pool_id);
+    assert_eq!(staked_blocks[0].block_id, block3.get_id().into());
+    assert_eq!(staked_blocks[0].block_height, BlockHeight::new(3));
+    assert!(wallet
+        .get_staked_blocks(
             DEFAULT_ACCOUNT_INDEX,
-            delegation_tx2.clone(),
-            &WalletEventsNoOp,
+            BlockHeight::zero(),
+            BlockHeight::new(2)
         )
-        .unwrap();
-
-    // Check delegation balance after unconfirmed tx status
-    let mut delegations = wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().collect_vec();
-    assert_eq!(delegations.len(), 1);
-    let (deleg_id, deleg_data) = delegations.pop().unwrap();
-    assert_eq!(*deleg_id, delegation_id);
-    assert_eq!(deleg_data.last_nonce, Some(AccountNonce::new(1)));
-
-    let (_, block5) = create_block(&chain_config, &mut wallet, delegation_tx1, Amount::ZERO, 4);
+        .unwrap()
+        .is_empty());
+
+    // do a reorg back to block 2
+    scan_wallet(&mut wallet, BlockHeight::new(1), vec![block2.clone()]);
+    let pool_ids = wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
+    assert_eq!(pool_ids.len(), 1);
+    assert!(wallet
+        .get_staked_blocks(
+            DEFAULT_ACCOUNT_INDEX,
+            BlockHeight::zero(),
+            BlockHeight::max()
+        )
+        .unwrap()
+        .is_empty());
+    let (pool_id, pool_data) = pool_ids.first().unwrap();
+    assert_eq!(
+        &pool_data.utxo_outpoint,
+        &UtxoOutPoint::new(OutPointSourceId::Transaction(stake_pool_transaction_id), 0)
+    );
+
+    let decommission_tx = wallet
+        .decommission_stake_pool(
+            DEFAULT_ACCOUNT_INDEX,
+            *pool_id,
+            pool_amount,
+            None,
+            FeeRate::from_amount_per_kb(Amount::from_atoms(0)),
+        )
+        .unwrap();
 
     let _ = create_block(
         &chain_config,
         &mut wallet,
-        vec![delegation_tx2.clone()],
+        vec![decommission_tx],
         Amount::ZERO,
-        5,
+        2,
     );
+    let pool_ids = wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
+    assert!(pool_ids.is_empty());
 
-    // Check delegation balance after confirmed tx status
-    let mut delegations = wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().collect_vec();
-    assert_eq!(delegations.len(), 1);
-    let (deleg_id, _deleg_data) = delegations.pop().unwrap();
-    assert_eq!(*deleg_id, delegation_id);
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, pool_amount,);
+}
 
-    // test that account 1 will receive the money but not register the delegation id as theirs
-    let coin_balance = wallet
-        .get_balance(other_acc_idx, UtxoState::Confirmed.into(), WithLocked::Any)
-        .unwrap()
-        .get(&Currency::Coin)
-        .copied()
-        .unwrap_or(Amount::ZERO);
-    assert_eq!(coin_balance, Amount::from_atoms(1));
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn reset_keys_after_failed_transaction(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
 
-    let delegations = wallet.get_delegations(other_acc_idx).unwrap().collect_vec();
-    assert!(delegations.is_empty());
+    let mut wallet = create_wallet(chain_config.clone());
 
-    // roll back the delegation tx to test removal code
-    scan_wallet(&mut wallet, BlockHeight::new(4), vec![block5]);
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, Amount::ZERO);
 
-    let coin_balance = wallet
-        .get_balance(other_acc_idx, UtxoState::Confirmed.into(), WithLocked::Any)
-        .unwrap()
-        .get(&Currency::Coin)
-        .copied()
-        .unwrap_or(Amount::ZERO);
+    // Generate a new block which sends reward to the wallet
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, block1_amount);
+
+    let not_enough = (block1_amount + Amount::from_atoms(1)).unwrap();
+
+    let last_issued_address =
+        wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap().last_issued();
+
+    let result = wallet.create_stake_pool_tx(
+        DEFAULT_ACCOUNT_INDEX,
+        FeeRate::from_amount_per_kb(Amount::ZERO),
+        FeeRate::from_amount_per_kb(Amount::ZERO),
+        StakePoolDataArguments {
+            amount: not_enough,
+            margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
+            cost_per_block: Amount::ZERO,
+            decommission_key: Destination::AnyoneCanSpend,
+        },
+    );
+    // check that result is an error and we last issued address is still the same
+    assert!(result.is_err());
+    assert_eq!(
+        last_issued_address,
+        wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap().last_issued(),
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn send_to_unknown_delegation(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let coin_balance = get_coin_balance(&wallet);
     assert_eq!(coin_balance, Amount::ZERO);
 
-    wallet
-        .add_account_unconfirmed_tx(
+    // Generate a new block which sends reward to the wallet
+    let delegation_amount = Amount::from_atoms(rng.gen_range(2..100));
+    let block1_amount = delegation_amount;
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, delegation_amount);
+
+    let unknown_delegation_id = DelegationId::new(H256::zero());
+    let delegation_stake_tx = wallet
+        .create_transaction_to_addresses(
             DEFAULT_ACCOUNT_INDEX,
-            delegation_tx2.clone(),
-            &WalletEventsNoOp,
+            [TxOutput::DelegateStaking(delegation_amount, unknown_delegation_id)],
+            SelectedInputs::Utxos(vec![]),
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
         )
         .unwrap();
-    let delegation_tx3 = wallet
-        .create_transaction_to_addresses_from_delegation(
+
+    let _ = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![delegation_stake_tx],
+        block1_amount,
+        1,
+    );
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, block1_amount);
+
+    let (other_acc_idx, _) = wallet.create_next_account(None).unwrap();
+    let address = wallet.get_new_address(other_acc_idx).unwrap().1;
+
+    let unknown_pool_id = PoolId::new(H256::zero());
+    let (delegation_id, delegation_tx) = wallet
+        .create_delegation(
             DEFAULT_ACCOUNT_INDEX,
-            address,
-            Amount::from_atoms(1),
-            delegation_id,
-            Amount::from_atoms(1),
+            vec![make_create_delegation_output(address.clone(), unknown_pool_id)],
+            FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
         )
         .unwrap();
-    wallet
-        .add_account_unconfirmed_tx(DEFAULT_ACCOUNT_INDEX, delegation_tx3, &WalletEventsNoOp)
-        .unwrap();
-
-    let mut delegations = wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().collect_vec();
-    assert_eq!(delegations.len(), 1);
-    let (deleg_id, deleg_data) = delegations.pop().unwrap();
-    assert_eq!(*deleg_id, delegation_id);
-    assert_eq!(deleg_data.last_nonce, Some(AccountNonce::new(2)));
 
-    // abandon tx2 should also abandon tx3 and roll back account nonce to 0
-    wallet
-        .abandon_transaction(DEFAULT_ACCOUNT_INDEX, delegation_tx2.transaction().get_id())
-        .unwrap();
+    let _ = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![delegation_tx],
+        Amount::ZERO,
+        2,
+    );
 
-    let mut delegations = wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().collect_vec();
+    // the new delegation even though created from DEFAULT_ACCOUNT_INDEX is not theirs
+    assert_eq!(
+        wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().count(),
+        0
+    );
+    // the new delegation should be registered to the other account
+    let delegations = wallet.get_delegations(other_acc_idx).unwrap().collect_vec();
     assert_eq!(delegations.len(), 1);
-    let (deleg_id, deleg_data) = delegations.pop().unwrap();
-    assert_eq!(*deleg_id, delegation_id);
-    assert_eq!(deleg_data.last_nonce, Some(AccountNonce::new(0)));
+    assert_eq!(*delegations.first().unwrap().0, delegation_id);
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn issue_and_transfer_tokens(#[case] seed: Seed) {
+fn create_spend_from_delegations(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
     let chain_config = Arc::new(create_mainnet());
 
@@ -1988,60 +2396,294 @@ fn issue_and_transfer_tokens(#[case] seed: Seed) {
     let coin_balance = get_coin_balance(&wallet);
     assert_eq!(coin_balance, Amount::ZERO);
 
-    // True for fungible, false for NFT
-    let issue_fungible_token = rng.gen::<bool>();
-
-    let issuance_fee = if issue_fungible_token {
-        chain_config.fungible_token_issuance_fee()
-    } else {
-        chain_config.nft_issuance_fee(BlockHeight::zero())
-    };
-
     // Generate a new block which sends reward to the wallet
-    let mut block1_amount =
-        (Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000)) + issuance_fee)
-            .unwrap();
-
-    if issue_fungible_token {
-        block1_amount =
-            (block1_amount + chain_config.token_supply_change_fee(BlockHeight::zero())).unwrap();
-    }
-
+    let delegation_amount = Amount::from_atoms(rng.gen_range(2..100));
+    let block1_amount = (chain_config.min_stake_pool_pledge() + delegation_amount).unwrap();
     let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
 
+    let pool_ids = wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
+    assert!(pool_ids.is_empty());
+
     let coin_balance = get_coin_balance(&wallet);
     assert_eq!(coin_balance, block1_amount);
 
-    let address2 = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+    let pool_amount = chain_config.min_stake_pool_pledge();
 
-    let amount_fraction = (block1_amount.into_atoms() - NETWORK_FEE) / 10;
-    let mut token_amount_to_issue = Amount::from_atoms(rng.gen_range(1..amount_fraction));
+    let stake_pool_transaction = wallet
+        .create_stake_pool_tx(
+            DEFAULT_ACCOUNT_INDEX,
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            StakePoolDataArguments {
+                amount: pool_amount,
+                margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
+                cost_per_block: Amount::ZERO,
+                decommission_key: Destination::AnyoneCanSpend,
+            },
+        )
+        .unwrap();
 
-    let (issued_token_id, token_issuance_transaction) = if issue_fungible_token {
-        let token_issuance = TokenIssuanceV1 {
-            token_ticker: "XXXX".as_bytes().to_vec(),
-            number_of_decimals: rng.gen_range(1..18),
-            metadata_uri: "http://uri".as_bytes().to_vec(),
-            total_supply: common::chain::tokens::TokenTotalSupply::Unlimited,
-            authority: address2.as_object().clone(),
-            is_freezable: common::chain::tokens::IsTokenFreezable::No,
-        };
-        let (issued_token_id, token_issuance_transaction) = wallet
-            .issue_new_token(
-                DEFAULT_ACCOUNT_INDEX,
-                TokenIssuance::V1(token_issuance.clone()),
-                FeeRate::from_amount_per_kb(Amount::ZERO),
-                FeeRate::from_amount_per_kb(Amount::ZERO),
-            )
-            .unwrap();
+    let (address, _) = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![stake_pool_transaction],
+        Amount::ZERO,
+        1,
+    );
 
-        let freezable = token_issuance.is_freezable.as_bool();
-        let token_info = RPCFungibleTokenInfo::new(
-            issued_token_id,
-            token_issuance.token_ticker,
-            token_issuance.number_of_decimals,
-            token_issuance.metadata_uri,
-            Amount::ZERO,
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, (block1_amount - pool_amount).unwrap(),);
+
+    let pool_ids = wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
+    assert_eq!(pool_ids.len(), 1);
+
+    let pool_id = pool_ids.first().unwrap().0;
+    let (delegation_id, delegation_tx) = wallet
+        .create_delegation(
+            DEFAULT_ACCOUNT_INDEX,
+            vec![make_create_delegation_output(address.clone(), pool_id)],
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+
+    let _ = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![delegation_tx],
+        Amount::ZERO,
+        2,
+    );
+
+    let mut delegations = wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().collect_vec();
+    assert_eq!(delegations.len(), 1);
+    let (deleg_id, deleg_data) = delegations.pop().unwrap();
+    assert_eq!(*deleg_id, delegation_id);
+    assert!(deleg_data.not_staked_yet);
+    assert_eq!(deleg_data.last_nonce, None);
+    assert_eq!(deleg_data.pool_id, pool_id);
+    assert_eq!(&deleg_data.destination, address.as_object());
+
+    let delegation_stake_tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            [TxOutput::DelegateStaking(delegation_amount, delegation_id)],
+            SelectedInputs::Utxos(vec![]),
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+
+    let _ = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![delegation_stake_tx],
+        Amount::ZERO,
+        3,
+    );
+
+    let delegation_tx1 = wallet
+        .create_transaction_to_addresses_from_delegation(
+            DEFAULT_ACCOUNT_INDEX,
+            address.clone(),
+            Amount::from_atoms(1),
+            delegation_id,
+            Amount::from_atoms(2),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+
+    wallet
+        .add_account_unconfirmed_tx(
+            DEFAULT_ACCOUNT_INDEX,
+            delegation_tx1.clone(),
+            &WalletEventsNoOp,
+        )
+        .unwrap();
+    let delegation_tx1 = vec![delegation_tx1];
+
+    // Check delegation balance after unconfirmed tx status
+    let mut delegations = wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().collect_vec();
+    assert_eq!(delegations.len(), 1);
+    let (deleg_id, deleg_data) = delegations.pop().unwrap();
+    assert_eq!(*deleg_id, delegation_id);
+    assert_eq!(deleg_data.last_nonce, Some(AccountNonce::new(0)));
+
+    // Send delegation to account 1
+    // test that account 1 will receive the money but not register the delegation id as theirs
+    let (other_acc_idx, _) = wallet.create_next_account(None).unwrap();
+    let address = wallet.get_new_address(other_acc_idx).unwrap().1;
+
+    let delegation_tx2 = wallet
+        .create_transaction_to_addresses_from_delegation(
+            DEFAULT_ACCOUNT_INDEX,
+            address.clone(),
+            Amount::from_atoms(1),
+            delegation_id,
+            Amount::from_atoms(1),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+    wallet
+        .add_account_unconfirmed_tx(
+            DEFAULT_ACCOUNT_INDEX,
+            delegation_tx2.clone(),
+            &WalletEventsNoOp,
+        )
+        .unwrap();
+
+    // Check delegation balance after unconfirmed tx status
+    let mut delegations = wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().collect_vec();
+    assert_eq!(delegations.len(), 1);
+    let (deleg_id, deleg_data) = delegations.pop().unwrap();
+    assert_eq!(*deleg_id, delegation_id);
+    assert_eq!(deleg_data.last_nonce, Some(AccountNonce::new(1)));
+
+    let (_, block5) = create_block(&chain_config, &mut wallet, delegation_tx1, Amount::ZERO, 4);
+
+    let _ = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![delegation_tx2.clone()],
+        Amount::ZERO,
+        5,
+    );
+
+    // Check delegation balance after confirmed tx status
+    let mut delegations = wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().collect_vec();
+    assert_eq!(delegations.len(), 1);
+    let (deleg_id, _deleg_data) = delegations.pop().unwrap();
+    assert_eq!(*deleg_id, delegation_id);
+
+    // test that account 1 will receive the money but not register the delegation id as theirs
+    let coin_balance = wallet
+        .get_balance(other_acc_idx, UtxoState::Confirmed.into(), WithLocked::Any)
+        .unwrap()
+        .get(&Currency::Coin)
+        .copied()
+        .unwrap_or(Amount::ZERO);
+    assert_eq!(coin_balance, Amount::from_atoms(1));
+
+    let delegations = wallet.get_delegations(other_acc_idx).unwrap().collect_vec();
+    assert!(delegations.is_empty());
+
+    // roll back the delegation tx to test removal code
+    scan_wallet(&mut wallet, BlockHeight::new(4), vec![block5]);
+
+    let coin_balance = wallet
+        .get_balance(other_acc_idx, UtxoState::Confirmed.into(), WithLocked::Any)
+        .unwrap()
+        .get(&Currency::Coin)
+        .copied()
+        .unwrap_or(Amount::ZERO);
+    assert_eq!(coin_balance, Amount::ZERO);
+
+    wallet
+        .add_account_unconfirmed_tx(
+            DEFAULT_ACCOUNT_INDEX,
+            delegation_tx2.clone(),
+            &WalletEventsNoOp,
+        )
+        .unwrap();
+    let delegation_tx3 = wallet
+        .create_transaction_to_addresses_from_delegation(
+            DEFAULT_ACCOUNT_INDEX,
+            address,
+            Amount::from_atoms(1),
+            delegation_id,
+            Amount::from_atoms(1),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+    wallet
+        .add_account_unconfirmed_tx(DEFAULT_ACCOUNT_INDEX, delegation_tx3, &WalletEventsNoOp)
+        .unwrap();
+
+    let mut delegations = wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().collect_vec();
+    assert_eq!(delegations.len(), 1);
+    let (deleg_id, deleg_data) = delegations.pop().unwrap();
+    assert_eq!(*deleg_id, delegation_id);
+    assert_eq!(deleg_data.last_nonce, Some(AccountNonce::new(2)));
+
+    // abandon tx2 should also abandon tx3 and roll back account nonce to 0
+    wallet
+        .abandon_transaction(DEFAULT_ACCOUNT_INDEX, delegation_tx2.transaction().get_id())
+        .unwrap();
+
+    let mut delegations = wallet.get_delegations(DEFAULT_ACCOUNT_INDEX).unwrap().collect_vec();
+    assert_eq!(delegations.len(), 1);
+    let (deleg_id, deleg_data) = delegations.pop().unwrap();
+    assert_eq!(*deleg_id, delegation_id);
+    assert_eq!(deleg_data.last_nonce, Some(AccountNonce::new(0)));
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn issue_and_transfer_tokens(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, Amount::ZERO);
+
+    // True for fungible, false for NFT
+    let issue_fungible_token = rng.gen::<bool>();
+
+    let issuance_fee = if issue_fungible_token {
+        chain_config.fungible_token_issuance_fee()
+    } else {
+        chain_config.nft_issuance_fee(BlockHeight::zero())
+    };
+
+    // Generate a new block which sends reward to the wallet
+    let mut block1_amount =
+        (Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000)) + issuance_fee)
+            .unwrap();
+
+    if issue_fungible_token {
+        block1_amount =
+            (block1_amount + chain_config.token_supply_change_fee(BlockHeight::zero())).unwrap();
+    }
+
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, block1_amount);
+
+    let address2 = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+
+    let amount_fraction = (block1_amount.into_atoms() - NETWORK_FEE) / 10;
+    let mut token_amount_to_issue = Amount::from_atoms(rng.gen_range(1..amount_fraction));
+
+    let (issued_token_id, token_issuance_transaction) = if issue_fungible_token {
+        let token_issuance = TokenIssuanceV1 {
+            token_ticker: "XXXX".as_bytes().to_vec(),
+            number_of_decimals: rng.gen_range(1..18),
+            metadata_uri: "http://uri".as_bytes().to_vec(),
+            total_supply: common::chain::tokens::TokenTotalSupply::Unlimited,
+            authority: address2.as_object().clone(),
+            is_freezable: common::chain::tokens::IsTokenFreezable::No,
+        };
+        let (issued_token_id, token_issuance_transaction) = wallet
+            .issue_new_token(
+                DEFAULT_ACCOUNT_INDEX,
+                TokenIssuance::V1(token_issuance.clone()),
+                FeeRate::from_amount_per_kb(Amount::ZERO),
+                FeeRate::from_amount_per_kb(Amount::ZERO),
+            )
+            .unwrap();
+
+        let freezable = token_issuance.is_freezable.as_bool();
+        let token_info = RPCFungibleTokenInfo::new(
+            issued_token_id,
+            token_issuance.token_ticker,
+            token_issuance.number_of_decimals,
+            token_issuance.metadata_uri,
+            Amount::ZERO,
             token_issuance.total_supply.into(),
             false,
             RPCIsTokenFrozen::NotFrozen { freezable },
@@ -2552,9 +3194,149 @@ fn freeze_and_unfreeze_tokens(#[case] seed: Seed) {
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn change_token_supply_fixed(#[case] seed: Seed) {
+fn change_token_authority_unconfirmed(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
-    let chain_config = Arc::new(create_mainnet());
+    let chain_config = Arc::new(create_regtest());
+
+    let mut wallet = create_wallet(chain_config.clone());
+
+    // Generate a new block which sends reward to the wallet
+    let block1_amount = (Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000))
+        + chain_config.fungible_token_issuance_fee())
+    .unwrap();
+
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let address2 = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+    let token_issuance = TokenIssuanceV1 {
+        token_ticker: "XXXX".as_bytes().to_vec(),
+        number_of_decimals: rng.gen_range(1..18),
+        metadata_uri: "http://uri".as_bytes().to_vec(),
+        total_supply: common::chain::tokens::TokenTotalSupply::Unlimited,
+        authority: address2.as_object().clone(),
+        is_freezable: common::chain::tokens::IsTokenFreezable::No,
+    };
+
+    let (issued_token_id, token_issuance_transaction) = wallet
+        .issue_new_token(
+            DEFAULT_ACCOUNT_INDEX,
+            TokenIssuance::V1(token_issuance.clone()),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+
+    let block2_amount = chain_config.token_change_authority_fee(BlockHeight::zero());
+    let _ = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![token_issuance_transaction],
+        block2_amount,
+        1,
+    );
+
+    let token_info = RPCFungibleTokenInfo::new(
+        issued_token_id,
+        token_issuance.token_ticker,
+        token_issuance.number_of_decimals,
+        token_issuance.metadata_uri,
+        Amount::ZERO,
+        token_issuance.total_supply.into(),
+        false,
+        RPCIsTokenFrozen::NotFrozen { freezable: false },
+        token_issuance.authority,
+    );
+
+    let unconfirmed_token_info =
+        wallet.get_token_unconfirmed_info(DEFAULT_ACCOUNT_INDEX, &token_info).unwrap();
+    assert_eq!(
+        unconfirmed_token_info.authority().unwrap(),
+        address2.as_object()
+    );
+
+    // change the authority to another address of the wallet
+    let address3 = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+    assert_eq!(
+        wallet.find_account_by_destination(address3.as_object()),
+        Some(DEFAULT_ACCOUNT_INDEX)
+    );
+
+    let change_tx = wallet
+        .change_token_authority(
+            DEFAULT_ACCOUNT_INDEX,
+            &unconfirmed_token_info,
+            address3.clone(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+    wallet.add_unconfirmed_tx(change_tx.clone(), &WalletEventsNoOp).unwrap();
+
+    // the pending change is taken into account before it is confirmed
+    let unconfirmed_token_info =
+        wallet.get_token_unconfirmed_info(DEFAULT_ACCOUNT_INDEX, &token_info).unwrap();
+    assert_eq!(
+        unconfirmed_token_info.authority().unwrap(),
+        address3.as_object()
+    );
+
+    // after abandoning the change the original authority is used again
+    wallet
+        .abandon_transaction(DEFAULT_ACCOUNT_INDEX, change_tx.transaction().get_id())
+        .unwrap();
+
+    let unconfirmed_token_info =
+        wallet.get_token_unconfirmed_info(DEFAULT_ACCOUNT_INDEX, &token_info).unwrap();
+    assert_eq!(
+        unconfirmed_token_info.authority().unwrap(),
+        address2.as_object()
+    );
+
+    // change the authority to a destination not controlled by the wallet
+    let foreign_destination = Destination::PublicKey(
+        crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr).1,
+    );
+    assert_eq!(
+        wallet.find_account_by_destination(&foreign_destination),
+        None
+    );
+
+    let change_tx = wallet
+        .change_token_authority(
+            DEFAULT_ACCOUNT_INDEX,
+            &unconfirmed_token_info,
+            Address::new(&chain_config, foreign_destination).unwrap(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+    wallet.add_unconfirmed_tx(change_tx.clone(), &WalletEventsNoOp).unwrap();
+
+    let unconfirmed_token_info =
+        wallet.get_token_unconfirmed_info(DEFAULT_ACCOUNT_INDEX, &token_info).unwrap();
+    assert_eq!(
+        unconfirmed_token_info.authority().unwrap_err(),
+        WalletError::CannotChangeNotOwnedToken(issued_token_id)
+    );
+
+    wallet
+        .abandon_transaction(DEFAULT_ACCOUNT_INDEX, change_tx.transaction().get_id())
+        .unwrap();
+
+    let unconfirmed_token_info =
+        wallet.get_token_unconfirmed_info(DEFAULT_ACCOUNT_INDEX, &token_info).unwrap();
+    assert_eq!(
+        unconfirmed_token_info.authority().unwrap(),
+        address2.as_object()
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn change_token_supply_fixed(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
 
     let mut wallet = create_wallet(chain_config.clone());
 
@@ -3594,1347 +4376,2849 @@ fn wallet_scan_multiple_transactions_from_mempool(#[case] seed: Seed) {
         ))
     );
 
-    let new_output = TxOutput::Transfer(
-        OutputValue::Coin(amount_to_keep),
-        Destination::PublicKey(
-            crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr)
-                .1,
-        ),
-    );
+    let new_output = TxOutput::Transfer(
+        OutputValue::Coin(amount_to_keep),
+        Destination::PublicKey(
+            crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr)
+                .1,
+        ),
+    );
+
+    let transaction = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            [new_output],
+            SelectedInputs::Utxos(vec![]),
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+    wallet.add_unconfirmed_tx(transaction.clone(), &WalletEventsNoOp).unwrap();
+
+    let transaction_id = transaction.transaction().get_id();
+
+    let coin_balance = get_coin_balance_with_inactive(&wallet);
+    assert_eq!(coin_balance, Amount::ZERO);
+
+    wallet.abandon_transaction(DEFAULT_ACCOUNT_INDEX, transaction_id).unwrap();
+
+    let coin_balance = get_coin_balance_with_inactive(&wallet);
+    assert_eq!(coin_balance, amount_to_keep);
+
+    // if we add it back from the mempool it should return even if abandoned
+    wallet.scan_mempool(&[transaction], &WalletEventsNoOp).unwrap();
+    let coin_balance = get_coin_balance_with_inactive(&wallet);
+    assert_eq!(coin_balance, Amount::ZERO);
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_abandone_transactions(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, Amount::ZERO);
+
+    let total_num_transactions: u32 = rng.gen_range(1..5);
+
+    // Generate a new block which sends reward to the wallet
+    // with enough coins for the total transactions
+    let block1_amount = Amount::from_atoms(rng.gen_range(
+        (NETWORK_FEE + 1) * (total_num_transactions as u128)
+            ..=(NETWORK_FEE + 1) * (total_num_transactions as u128) + 10000,
+    ));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, block1_amount);
+
+    // create multiple transactions that depend on each other
+    let mut used_inputs = BTreeSet::new();
+    let mut transactions = vec![];
+    let mut total_amount = block1_amount;
+
+    for idx in 0..total_num_transactions {
+        let amount_to_transfer = Amount::from_atoms(
+            rng.gen_range(1..=(total_amount.into_atoms() - (total_num_transactions - idx) as u128)),
+        );
+        let change = (total_amount - amount_to_transfer).unwrap();
+
+        let address = get_address(
+            &chain_config,
+            MNEMONIC,
+            DEFAULT_ACCOUNT_INDEX,
+            KeyPurpose::ReceiveFunds,
+            (idx + 1).try_into().unwrap(),
+        );
+        let change_output = make_address_output(address, change);
+
+        let new_output = TxOutput::Transfer(
+            OutputValue::Coin(amount_to_transfer),
+            Destination::PublicKey(
+                crypto::key::PrivateKey::new_from_rng(
+                    &mut rng,
+                    crypto::key::KeyKind::Secp256k1Schnorr,
+                )
+                .1,
+            ),
+        );
+
+        let transaction = wallet
+            .create_transaction_to_addresses(
+                DEFAULT_ACCOUNT_INDEX,
+                [new_output, change_output],
+                SelectedInputs::Utxos(vec![]),
+                BTreeMap::new(),
+                FeeRate::from_amount_per_kb(Amount::ZERO),
+                FeeRate::from_amount_per_kb(Amount::ZERO),
+            )
+            .unwrap();
+        wallet
+            .add_account_unconfirmed_tx(
+                DEFAULT_ACCOUNT_INDEX,
+                transaction.clone(),
+                &WalletEventsNoOp,
+            )
+            .unwrap();
+
+        for utxo in transaction.inputs().iter().map(|inp| inp.utxo_outpoint().unwrap()) {
+            // assert the utxos used in this transaction have not been used before
+            assert!(used_inputs.insert(utxo.clone()));
+        }
+
+        transactions.push((transaction, total_amount));
+        total_amount = change;
+    }
+
+    let coin_balance = get_coin_balance_with_inactive(&wallet);
+    assert_eq!(coin_balance, total_amount);
+
+    let abandonable_transactions = wallet.pending_transactions(DEFAULT_ACCOUNT_INDEX).unwrap();
+
+    assert_eq!(abandonable_transactions.len(), transactions.len());
+
+    let txs_to_abandone = rng.gen_range(0..total_num_transactions) as usize;
+    let (txs_to_keep, txs_to_abandone) = transactions.split_at(txs_to_abandone);
+
+    assert!(!txs_to_abandone.is_empty());
+
+    let transaction_id = txs_to_abandone.first().unwrap().0.transaction().get_id();
+    wallet.abandon_transaction(DEFAULT_ACCOUNT_INDEX, transaction_id).unwrap();
+
+    let coins_after_abandon = txs_to_abandone.first().unwrap().1;
+
+    let coin_balance = get_coin_balance_with_inactive(&wallet);
+    assert_eq!(coin_balance, coins_after_abandon);
+
+    let txs_to_keep: Vec<_> = txs_to_keep.iter().map(|(tx, _)| tx.clone()).collect();
+    wallet.scan_mempool(txs_to_keep.as_slice(), &WalletEventsNoOp).unwrap();
+    let coin_balance = get_coin_balance_with_inactive(&wallet);
+    assert_eq!(coin_balance, coins_after_abandon);
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_bump_fee(#[case] seed: Seed, #[values(true, false)] replaceable: bool) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let block1_amount = Amount::from_atoms(rng.gen_range(1_000_000..10_000_000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    // Send some coins to someone else without any fee, with the rest returned as change
+    let amount_to_transfer = Amount::from_atoms(rng.gen_range(1..=100_000));
+    let change = (block1_amount - amount_to_transfer).unwrap();
+    let change_address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::ReceiveFunds,
+        1.try_into().unwrap(),
+    );
+    let new_output = TxOutput::Transfer(
+        OutputValue::Coin(amount_to_transfer),
+        Destination::PublicKey(
+            crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr)
+                .1,
+        ),
+    );
+    let tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            [new_output, make_address_output(change_address, change)],
+            SelectedInputs::Utxos(vec![]),
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+    let tx = if replaceable {
+        let tx = Transaction::new(1, tx.inputs().to_vec(), tx.outputs().to_vec()).unwrap();
+        wallet
+            .sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, TransactionToSign::Tx(tx))
+            .unwrap()
+            .0
+            .into_signed_tx()
+            .unwrap()
+    } else {
+        tx
+    };
+    let tx_id = tx.transaction().get_id();
+    wallet
+        .add_account_unconfirmed_tx(DEFAULT_ACCOUNT_INDEX, tx.clone(), &WalletEventsNoOp)
+        .unwrap();
+    assert_eq!(get_coin_balance_with_inactive(&wallet), change);
+
+    let new_fee_rate =
+        FeeRate::from_amount_per_kb(Amount::from_atoms(rng.gen_range(1_000..100_000)));
+    let min_relay_fee_rate =
+        FeeRate::from_amount_per_kb(Amount::from_atoms(rng.gen_range(1_000..100_000)));
+    let parent_fee: Amount = new_fee_rate.compute_fee(tx.encoded_size()).unwrap().into();
+    // The original transaction pays no fee, so the replacement pays at least the relay fee
+    let replacement_fee = std::cmp::max(
+        parent_fee,
+        min_relay_fee_rate.compute_fee(tx.encoded_size()).unwrap().into(),
+    );
+
+    let (fee_bump_tx, fee_bump_kind) = wallet
+        .create_fee_bump_transaction(
+            DEFAULT_ACCOUNT_INDEX,
+            tx_id,
+            new_fee_rate,
+            min_relay_fee_rate,
+            true,
+        )
+        .unwrap();
+
+    if replaceable {
+        // The replacement spends the same inputs, the fee is taken from the change
+        assert_eq!(fee_bump_kind, FeeBumpKind::ReplaceByFee);
+        assert_eq!(fee_bump_tx.inputs(), tx.inputs());
+        assert_eq!(fee_bump_tx.outputs()[0], tx.outputs()[0]);
+
+        wallet
+            .mark_transaction_replaced(DEFAULT_ACCOUNT_INDEX, tx_id, &WalletEventsNoOp)
+            .unwrap();
+        assert!(matches!(
+            wallet.find_transaction_state(tx_id),
+            Some(TxState::Conflicted(_))
+        ));
+        wallet
+            .add_account_unconfirmed_tx(DEFAULT_ACCOUNT_INDEX, fee_bump_tx, &WalletEventsNoOp)
+            .unwrap();
+        assert_eq!(
+            get_coin_balance_with_inactive(&wallet),
+            (change - replacement_fee).unwrap()
+        );
+
+        // The replaced transaction cannot be bumped again
+        assert!(matches!(
+            wallet.create_fee_bump_transaction(
+                DEFAULT_ACCOUNT_INDEX,
+                tx_id,
+                new_fee_rate,
+                min_relay_fee_rate,
+                true,
+            ),
+            Err(WalletError::CannotBumpTransactionFee(TxState::Conflicted(
+                _
+            )))
+        ));
+    } else {
+        // The child spends the change and pays for itself and the parent
+        assert_eq!(fee_bump_kind, FeeBumpKind::ChildPaysForParent);
+        assert_eq!(fee_bump_tx.inputs(), [TxInput::from_utxo(tx_id.into(), 1)]);
+
+        wallet
+            .add_account_unconfirmed_tx(DEFAULT_ACCOUNT_INDEX, fee_bump_tx, &WalletEventsNoOp)
+            .unwrap();
+        assert!(get_coin_balance_with_inactive(&wallet) < (change - parent_fee).unwrap());
+    }
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_address_usage(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(usage.last_used(), None);
+    assert_eq!(usage.last_issued(), None);
+
+    // issue some new address
+    let addresses_to_issue = rng.gen_range(1..10);
+    for _ in 0..=addresses_to_issue {
+        let _ = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    }
+
+    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(usage.last_used(), None);
+    assert_eq!(
+        usage.last_issued(),
+        Some(addresses_to_issue.try_into().unwrap())
+    );
+
+    let block1_amount = Amount::from_atoms(10000);
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let last_used = addresses_to_issue + 1;
+    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(usage.last_used(), Some(last_used.try_into().unwrap()));
+    assert_eq!(usage.last_issued(), Some(last_used.try_into().unwrap()));
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn wallet_set_lookahead_size(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(usage.last_used(), None);
+    assert_eq!(usage.last_issued(), None);
+
+    // issue some new address
+    let addresses_to_issue = rng.gen_range(1..10);
+    for _ in 0..=addresses_to_issue {
+        let _ = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    }
+
+    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(usage.last_used(), None);
+    assert_eq!(
+        usage.last_issued(),
+        Some(addresses_to_issue.try_into().unwrap())
+    );
+
+    let block1_amount = Amount::from_atoms(10000);
+    let (_, block1) = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let last_used = addresses_to_issue + 1;
+    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(usage.last_used(), Some(last_used.try_into().unwrap()));
+    assert_eq!(usage.last_issued(), Some(last_used.try_into().unwrap()));
+
+    let coins = get_coin_balance_for_acc(&wallet, DEFAULT_ACCOUNT_INDEX);
+    assert_eq!(coins, block1_amount);
+
+    let less_than_last_used = rng.gen_range(1..=last_used);
+    let err = wallet.set_lookahead_size(less_than_last_used, false).unwrap_err();
+    assert_eq!(
+        err,
+        WalletError::ReducedLookaheadSize(less_than_last_used, last_used)
+    );
+
+    wallet.set_lookahead_size(less_than_last_used, true).unwrap();
+
+    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1.clone()]);
+    let coins = get_coin_balance_for_acc(&wallet, DEFAULT_ACCOUNT_INDEX);
+    assert_eq!(coins, Amount::ZERO);
+    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(usage.last_used(), None);
+    assert_eq!(usage.last_issued(), None);
+
+    let more_than_last_used = rng.gen_range(last_used + 1..100);
+    wallet.set_lookahead_size(more_than_last_used, false).unwrap();
+
+    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1.clone()]);
+    let coins = get_coin_balance_for_acc(&wallet, DEFAULT_ACCOUNT_INDEX);
+    assert_eq!(coins, block1_amount);
+    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(usage.last_used(), Some(last_used.try_into().unwrap()));
+    assert_eq!(usage.last_issued(), Some(last_used.try_into().unwrap()));
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn decommission_pool_wrong_account(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+
+    let acc_0_index = DEFAULT_ACCOUNT_INDEX;
+    let acc_1_index = U31::ONE;
+
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, Amount::ZERO);
+
+    // Generate a new block which sends reward to the wallet
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let pool_ids = wallet.get_pool_ids(acc_0_index, WalletPoolsFilter::All).unwrap();
+    assert!(pool_ids.is_empty());
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, block1_amount);
+
+    let pool_amount = block1_amount;
+
+    let res = wallet.create_next_account(Some("name".into())).unwrap();
+    assert_eq!(res, (U31::from_u32(1).unwrap(), Some("name".into())));
+
+    let decommission_key = wallet.get_new_address(acc_1_index).unwrap().1;
+
+    let stake_pool_transaction = wallet
+        .create_stake_pool_tx(
+            acc_0_index,
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            StakePoolDataArguments {
+                amount: pool_amount,
+                margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
+                cost_per_block: Amount::ZERO,
+                decommission_key: decommission_key.into_object(),
+            },
+        )
+        .unwrap();
+    let _ = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![stake_pool_transaction],
+        Amount::ZERO,
+        1,
+    );
+
+    let pool_ids = wallet.get_pool_ids(acc_0_index, WalletPoolsFilter::All).unwrap();
+    assert_eq!(pool_ids.len(), 1);
+
+    // Try to decommission the pool with default account
+    let pool_id = pool_ids.first().unwrap().0;
+    let decommission_cmd_res = wallet.decommission_stake_pool(
+        acc_0_index,
+        pool_id,
+        pool_amount,
+        None,
+        FeeRate::from_amount_per_kb(Amount::from_atoms(0)),
+    );
+    assert_eq!(
+        decommission_cmd_res.unwrap_err(),
+        WalletError::PartiallySignedTransactionInDecommissionCommand
+    );
+
+    // Decommission from the account that holds the key
+    let decommission_tx = wallet
+        .decommission_stake_pool(
+            acc_1_index,
+            pool_id,
+            pool_amount,
+            None,
+            FeeRate::from_amount_per_kb(Amount::from_atoms(0)),
+        )
+        .unwrap();
+
+    let _ = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![decommission_tx],
+        Amount::ZERO,
+        2,
+    );
+
+    let coin_balance = get_coin_balance_for_acc(&wallet, acc_1_index);
+    assert_eq!(coin_balance, pool_amount);
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn decommission_pool_request_wrong_account(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
+
+    let acc_0_index = DEFAULT_ACCOUNT_INDEX;
+    let acc_1_index = U31::ONE;
+
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, Amount::ZERO);
+
+    // Generate a new block which sends reward to the wallet
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let pool_ids = wallet.get_pool_ids(acc_0_index, WalletPoolsFilter::All).unwrap();
+    assert!(pool_ids.is_empty());
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, block1_amount);
+
+    let pool_amount = block1_amount;
+
+    let res = wallet.create_next_account(Some("name".into())).unwrap();
+    assert_eq!(res, (U31::from_u32(1).unwrap(), Some("name".into())));
+
+    let decommission_key = wallet.get_new_address(acc_1_index).unwrap().1;
+
+    let stake_pool_transaction = wallet
+        .create_stake_pool_tx(
+            acc_0_index,
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            StakePoolDataArguments {
+                amount: pool_amount,
+                margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
+                cost_per_block: Amount::ZERO,
+                decommission_key: decommission_key.into_object(),
+            },
+        )
+        .unwrap();
+    let _ = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![stake_pool_transaction],
+        Amount::ZERO,
+        1,
+    );
+
+    let pool_ids = wallet.get_pool_ids(acc_0_index, WalletPoolsFilter::All).unwrap();
+    assert_eq!(pool_ids.len(), 1);
+
+    // Try to create decommission request from account that holds the key
+    let pool_id = pool_ids.first().unwrap().0;
+    let decommission_req_res = wallet.decommission_stake_pool_request(
+        acc_1_index,
+        pool_id,
+        pool_amount,
+        None,
+        FeeRate::from_amount_per_kb(Amount::from_atoms(0)),
+    );
+    assert_eq!(
+        decommission_req_res.unwrap_err(),
+        WalletError::FullySignedTransactionInDecommissionReq
+    );
+
+    let decommission_partial_tx = wallet
+        .decommission_stake_pool_request(
+            acc_0_index,
+            pool_id,
+            pool_amount,
+            None,
+            FeeRate::from_amount_per_kb(Amount::from_atoms(0)),
+        )
+        .unwrap();
+    assert!(!decommission_partial_tx.all_signatures_available());
+    matches!(
+        decommission_partial_tx.into_signed_tx().unwrap_err(),
+        TransactionCreationError::FailedToConvertPartiallySignedTx(_)
+    );
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn sign_decommission_pool_request_between_accounts(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+
+    let acc_0_index = DEFAULT_ACCOUNT_INDEX;
+    let acc_1_index = U31::ONE;
+
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, Amount::ZERO);
+
+    // Generate a new block which sends reward to the wallet
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let pool_ids = wallet.get_pool_ids(acc_0_index, WalletPoolsFilter::All).unwrap();
+    assert!(pool_ids.is_empty());
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, block1_amount);
+
+    let pool_amount = block1_amount;
+
+    let res = wallet.create_next_account(Some("name".into())).unwrap();
+    assert_eq!(res, (U31::from_u32(1).unwrap(), Some("name".into())));
+
+    let decommission_key = wallet.get_new_address(acc_1_index).unwrap().1;
+
+    let stake_pool_transaction = wallet
+        .create_stake_pool_tx(
+            acc_0_index,
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            StakePoolDataArguments {
+                amount: pool_amount,
+                margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
+                cost_per_block: Amount::ZERO,
+                decommission_key: decommission_key.into_object(),
+            },
+        )
+        .unwrap();
+
+    // remove the signatures and try to sign it again
+    let tx = stake_pool_transaction.transaction().clone();
+    let stake_pool_transaction = wallet
+        .sign_raw_transaction(acc_0_index, TransactionToSign::Tx(tx))
+        .unwrap()
+        .0
+        .into_signed_tx()
+        .unwrap();
+
+    let _ = create_block(
+        &chain_config,
+        &mut wallet,
+        vec![stake_pool_transaction],
+        Amount::ZERO,
+        1,
+    );
+
+    assert_eq!(get_coin_balance(&wallet), Amount::ZERO);
+
+    let pool_ids = wallet.get_pool_ids(acc_0_index, WalletPoolsFilter::All).unwrap();
+    assert_eq!(pool_ids.len(), 1);
+
+    let pool_id = pool_ids.first().unwrap().0;
+    let decommission_partial_tx = wallet
+        .decommission_stake_pool_request(
+            acc_0_index,
+            pool_id,
+            pool_amount,
+            None,
+            FeeRate::from_amount_per_kb(Amount::from_atoms(0)),
+        )
+        .unwrap();
+
+    // Try to sign decommission request with wrong account
+    let sign_from_acc0_res = wallet
+        .sign_raw_transaction(
+            acc_0_index,
+            TransactionToSign::Partial(decommission_partial_tx.clone()),
+        )
+        .unwrap()
+        .0;
+    // the tx is still not fully signed
+    assert!(!sign_from_acc0_res.all_signatures_available());
+
+    let signed_tx = wallet
+        .sign_raw_transaction(
+            acc_1_index,
+            TransactionToSign::Partial(decommission_partial_tx),
+        )
+        .unwrap()
+        .0
+        .into_signed_tx()
+        .unwrap();
+
+    let _ = create_block(&chain_config, &mut wallet, vec![signed_tx], Amount::ZERO, 2);
+
+    // the pool amount is back after decommission
+    assert_eq!(get_coin_balance(&wallet), pool_amount);
+    assert_eq!(get_coin_balance_for_acc(&wallet, acc_1_index), Amount::ZERO);
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn sign_decommission_pool_request_cold_wallet(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+
+    let mut hot_wallet = create_wallet(chain_config.clone());
+
+    // create cold wallet that is not synced and only contains decommission key
+    let another_mnemonic =
+        "legal winner thank year wave sausage worth useful legal winner thank yellow";
+    let mut cold_wallet = create_wallet_with_mnemonic(chain_config.clone(), another_mnemonic);
+    let decommission_key = cold_wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+
+    let coin_balance = get_coin_balance(&hot_wallet);
+    assert_eq!(coin_balance, Amount::ZERO);
+
+    // Generate a new block which sends reward to the wallet
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let _ = create_block(&chain_config, &mut hot_wallet, vec![], block1_amount, 0);
+
+    let pool_ids = hot_wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
+    assert!(pool_ids.is_empty());
+
+    let coin_balance = get_coin_balance(&hot_wallet);
+    assert_eq!(coin_balance, block1_amount);
+
+    let pool_amount = block1_amount;
+
+    let res = hot_wallet.create_next_account(Some("name".into())).unwrap();
+    assert_eq!(res, (U31::from_u32(1).unwrap(), Some("name".into())));
+
+    let stake_pool_transaction = hot_wallet
+        .create_stake_pool_tx(
+            DEFAULT_ACCOUNT_INDEX,
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            StakePoolDataArguments {
+                amount: pool_amount,
+                margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
+                cost_per_block: Amount::ZERO,
+                decommission_key: decommission_key.into_object(),
+            },
+        )
+        .unwrap();
+    let _ = create_block(
+        &chain_config,
+        &mut hot_wallet,
+        vec![stake_pool_transaction],
+        Amount::ZERO,
+        1,
+    );
+
+    let pool_ids = hot_wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
+    assert_eq!(pool_ids.len(), 1);
+
+    let pool_id = pool_ids.first().unwrap().0;
+    let decommission_partial_tx = hot_wallet
+        .decommission_stake_pool_request(
+            DEFAULT_ACCOUNT_INDEX,
+            pool_id,
+            pool_amount,
+            None,
+            FeeRate::from_amount_per_kb(Amount::from_atoms(0)),
+        )
+        .unwrap();
+
+    // sign the tx with cold wallet
+    let partially_signed_transaction = cold_wallet
+        .sign_raw_transaction(
+            DEFAULT_ACCOUNT_INDEX,
+            TransactionToSign::Partial(decommission_partial_tx),
+        )
+        .unwrap()
+        .0;
+    assert!(partially_signed_transaction.all_signatures_available());
+
+    // sign it with the hot wallet should leave the signatures in place even if it can't find the
+    // destinations for the inputs
+    let partially_signed_transaction = hot_wallet
+        .sign_raw_transaction(
+            DEFAULT_ACCOUNT_INDEX,
+            TransactionToSign::Partial(partially_signed_transaction),
+        )
+        .unwrap()
+        .0;
+    assert!(partially_signed_transaction.all_signatures_available());
+
+    let signed_tx = partially_signed_transaction.into_signed_tx().unwrap();
+
+    let _ = create_block(
+        &chain_config,
+        &mut hot_wallet,
+        vec![signed_tx],
+        Amount::ZERO,
+        2,
+    );
+
+    let coin_balance = get_coin_balance(&hot_wallet);
+    assert_eq!(coin_balance, pool_amount,);
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn filter_pools(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+
+    let mut wallet1 = create_wallet(chain_config.clone());
+
+    // create another wallet to store decommission key
+    let another_mnemonic =
+        "legal winner thank year wave sausage worth useful legal winner thank yellow";
+    let mut wallet2 = create_wallet_with_mnemonic(chain_config.clone(), another_mnemonic);
+    let decommission_key = wallet2.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+
+    let coin_balance = get_coin_balance(&wallet1);
+    assert_eq!(coin_balance, Amount::ZERO);
+
+    // Generate a new block which sends reward to the wallet
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let _ = create_block(&chain_config, &mut wallet1, vec![], block1_amount, 0);
+    let _ = create_block(&chain_config, &mut wallet2, vec![], block1_amount, 0);
+
+    let pool_ids = wallet1.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
+    assert!(pool_ids.is_empty());
 
-    let transaction = wallet
-        .create_transaction_to_addresses(
+    let pool_ids = wallet2.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
+    assert!(pool_ids.is_empty());
+
+    let pool_amount = block1_amount;
+
+    let stake_pool_transaction = wallet1
+        .create_stake_pool_tx(
             DEFAULT_ACCOUNT_INDEX,
-            [new_output],
-            SelectedInputs::Utxos(vec![]),
-            BTreeMap::new(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
+            StakePoolDataArguments {
+                amount: pool_amount,
+                margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
+                cost_per_block: Amount::ZERO,
+                decommission_key: decommission_key.into_object(),
+            },
         )
         .unwrap();
-    wallet.add_unconfirmed_tx(transaction.clone(), &WalletEventsNoOp).unwrap();
+    // sync for wallet1
+    let _ = create_block(
+        &chain_config,
+        &mut wallet1,
+        vec![stake_pool_transaction.clone()],
+        Amount::ZERO,
+        1,
+    );
+    // sync for wallet2
+    let _ = create_block(
+        &chain_config,
+        &mut wallet2,
+        vec![stake_pool_transaction],
+        Amount::ZERO,
+        1,
+    );
 
-    let transaction_id = transaction.transaction().get_id();
+    // check wallet1 filter
+    let pool_ids = wallet1.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
+    assert_eq!(pool_ids.len(), 1);
 
-    let coin_balance = get_coin_balance_with_inactive(&wallet);
-    assert_eq!(coin_balance, Amount::ZERO);
+    let pool_ids = wallet1
+        .get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::Decommission)
+        .unwrap();
+    assert_eq!(pool_ids.len(), 0);
 
-    wallet.abandon_transaction(DEFAULT_ACCOUNT_INDEX, transaction_id).unwrap();
+    let pool_ids = wallet1.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::Stake).unwrap();
+    assert_eq!(pool_ids.len(), 1);
 
-    let coin_balance = get_coin_balance_with_inactive(&wallet);
-    assert_eq!(coin_balance, amount_to_keep);
+    // check wallet2 filter
+    let pool_ids = wallet2.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
+    assert_eq!(pool_ids.len(), 1);
 
-    // if we add it back from the mempool it should return even if abandoned
-    wallet.scan_mempool(&[transaction], &WalletEventsNoOp).unwrap();
-    let coin_balance = get_coin_balance_with_inactive(&wallet);
-    assert_eq!(coin_balance, Amount::ZERO);
+    let pool_ids = wallet2
+        .get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::Decommission)
+        .unwrap();
+    assert_eq!(pool_ids.len(), 1);
+
+    let pool_ids = wallet2.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::Stake).unwrap();
+    assert_eq!(pool_ids.len(), 0);
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn wallet_abandone_transactions(#[case] seed: Seed) {
+fn sign_send_request_cold_wallet(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
-    let chain_config = Arc::new(create_mainnet());
+    let chain_config = Arc::new(create_regtest());
 
-    let mut wallet = create_wallet(chain_config.clone());
+    let mut hot_wallet = create_wallet(chain_config.clone());
 
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, Amount::ZERO);
+    // create cold wallet that is not synced
+    let another_mnemonic =
+        "legal winner thank year wave sausage worth useful legal winner thank yellow";
+    let mut cold_wallet = create_wallet_with_mnemonic(chain_config.clone(), another_mnemonic);
+    let cold_wallet_address = cold_wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
 
-    let total_num_transactions: u32 = rng.gen_range(1..5);
+    let coin_balance = get_coin_balance(&hot_wallet);
+    assert_eq!(coin_balance, Amount::ZERO);
 
-    // Generate a new block which sends reward to the wallet
-    // with enough coins for the total transactions
-    let block1_amount = Amount::from_atoms(rng.gen_range(
-        (NETWORK_FEE + 1) * (total_num_transactions as u128)
-            ..=(NETWORK_FEE + 1) * (total_num_transactions as u128) + 10000,
-    ));
-    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+    // Generate a new block which sends reward to the cold wallet address
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let reward_output = make_address_output(cold_wallet_address.clone(), block1_amount);
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![reward_output.clone()]),
+    )
+    .unwrap();
 
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, block1_amount);
+    scan_wallet(&mut hot_wallet, BlockHeight::new(0), vec![block1.clone()]);
 
-    // create multiple transactions that depend on each other
-    let mut used_inputs = BTreeSet::new();
-    let mut transactions = vec![];
-    let mut total_amount = block1_amount;
+    // hot wallet has 0 balance
+    let coin_balance = get_coin_balance(&hot_wallet);
+    assert_eq!(coin_balance, Amount::ZERO);
+    let hot_wallet_address = hot_wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
 
-    for idx in 0..total_num_transactions {
-        let amount_to_transfer = Amount::from_atoms(
-            rng.gen_range(1..=(total_amount.into_atoms() - (total_num_transactions - idx) as u128)),
-        );
-        let change = (total_amount - amount_to_transfer).unwrap();
+    let to_send = Amount::from_atoms(1);
+    let (send_req, _) = hot_wallet
+        .create_unsigned_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            [TxOutput::Transfer(OutputValue::Coin(to_send), hot_wallet_address.into_object())],
+            SelectedInputs::Inputs(vec![(
+                UtxoOutPoint::new(OutPointSourceId::BlockReward(block1.get_id().into()), 0),
+                reward_output,
+            )]),
+            None,
+            [(Currency::Coin, cold_wallet_address.clone())].into(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
 
-        let address = get_address(
-            &chain_config,
-            MNEMONIC,
+    // Try to sign request with the hot wallet
+    let tx = hot_wallet
+        .sign_raw_transaction(
             DEFAULT_ACCOUNT_INDEX,
-            KeyPurpose::ReceiveFunds,
-            (idx + 1).try_into().unwrap(),
-        );
-        let change_output = make_address_output(address, change);
+            TransactionToSign::Partial(send_req.clone()),
+        )
+        .unwrap()
+        .0;
+    // the tx is not fully signed
+    assert!(!tx.all_signatures_available());
 
-        let new_output = TxOutput::Transfer(
-            OutputValue::Coin(amount_to_transfer),
-            Destination::PublicKey(
-                crypto::key::PrivateKey::new_from_rng(
-                    &mut rng,
-                    crypto::key::KeyKind::Secp256k1Schnorr,
-                )
-                .1,
-            ),
-        );
+    // sign the tx with cold wallet
+    let signed_tx = cold_wallet
+        .sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, TransactionToSign::Partial(send_req))
+        .unwrap()
+        .0
+        .into_signed_tx()
+        .unwrap();
 
-        let transaction = wallet
-            .create_transaction_to_addresses(
-                DEFAULT_ACCOUNT_INDEX,
-                [new_output, change_output],
-                SelectedInputs::Utxos(vec![]),
-                BTreeMap::new(),
-                FeeRate::from_amount_per_kb(Amount::ZERO),
-                FeeRate::from_amount_per_kb(Amount::ZERO),
-            )
-            .unwrap();
-        wallet
-            .add_account_unconfirmed_tx(
-                DEFAULT_ACCOUNT_INDEX,
-                transaction.clone(),
-                &WalletEventsNoOp,
-            )
-            .unwrap();
+    let (_, block2) = create_block(
+        &chain_config,
+        &mut hot_wallet,
+        vec![signed_tx],
+        Amount::ZERO,
+        1,
+    );
 
-        for utxo in transaction.inputs().iter().map(|inp| inp.utxo_outpoint().unwrap()) {
-            // assert the utxos used in this transaction have not been used before
-            assert!(used_inputs.insert(utxo.clone()));
-        }
+    let coin_balance = get_coin_balance(&hot_wallet);
+    assert_eq!(coin_balance, to_send,);
 
-        transactions.push((transaction, total_amount));
-        total_amount = change;
-    }
+    // update cold wallet just to check the balance and utxos
+    cold_wallet
+        .scan_new_blocks(
+            DEFAULT_ACCOUNT_INDEX,
+            BlockHeight::new(0),
+            vec![block1, block2],
+            &WalletEventsNoOp,
+        )
+        .unwrap();
 
-    let coin_balance = get_coin_balance_with_inactive(&wallet);
-    assert_eq!(coin_balance, total_amount);
+    let balance = (block1_amount - to_send).unwrap();
+    assert_eq!(get_coin_balance(&cold_wallet), balance);
 
-    let abandonable_transactions = wallet.pending_transactions(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let mut utxos = cold_wallet
+        .get_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoType::Transfer.into(),
+            UtxoState::Confirmed.into(),
+            WithLocked::Unlocked,
+        )
+        .unwrap();
 
-    assert_eq!(abandonable_transactions.len(), transactions.len());
+    assert_eq!(utxos.len(), 1);
+    let (_, output, _) = utxos.pop().unwrap();
 
-    let txs_to_abandone = rng.gen_range(0..total_num_transactions) as usize;
-    let (txs_to_keep, txs_to_abandone) = transactions.split_at(txs_to_abandone);
+    matches!(output, TxOutput::Transfer(OutputValue::Coin(value), dest)
+            if value == balance && dest == cold_wallet_address.into_object());
+}
 
-    assert!(!txs_to_abandone.is_empty());
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn test_not_exhaustion_of_keys(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
 
-    let transaction_id = txs_to_abandone.first().unwrap().0.transaction().get_id();
-    wallet.abandon_transaction(DEFAULT_ACCOUNT_INDEX, transaction_id).unwrap();
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, Amount::ZERO);
+    let address = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+
+    // Generate a new block which sends reward to the cold wallet address
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let reward_output = make_address_output(address.clone(), block1_amount);
+    let block1 = Block::new(
+        vec![],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![reward_output.clone()]),
+    )
+    .unwrap();
 
-    let coins_after_abandon = txs_to_abandone.first().unwrap().1;
+    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1.clone()]);
 
-    let coin_balance = get_coin_balance_with_inactive(&wallet);
-    assert_eq!(coin_balance, coins_after_abandon);
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, block1_amount);
 
-    let txs_to_keep: Vec<_> = txs_to_keep.iter().map(|(tx, _)| tx.clone()).collect();
-    wallet.scan_mempool(txs_to_keep.as_slice(), &WalletEventsNoOp).unwrap();
-    let coin_balance = get_coin_balance_with_inactive(&wallet);
-    assert_eq!(coin_balance, coins_after_abandon);
+    // can create 100 txs but will not run out of change addresses
+    for _ in 0..100 {
+        wallet
+            .create_transaction_to_addresses(
+                DEFAULT_ACCOUNT_INDEX,
+                [TxOutput::Transfer(
+                    OutputValue::Coin(Amount::from_atoms(1)),
+                    address.as_object().clone(),
+                )],
+                SelectedInputs::Inputs(vec![]),
+                [].into(),
+                FeeRate::from_amount_per_kb(Amount::ZERO),
+                FeeRate::from_amount_per_kb(Amount::ZERO),
+            )
+            .unwrap();
+    }
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn wallet_address_usage(#[case] seed: Seed) {
+fn test_add_standalone_private_key(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
     let chain_config = Arc::new(create_regtest());
+
     let mut wallet = create_wallet(chain_config.clone());
 
-    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
-    assert_eq!(usage.last_used(), None);
-    assert_eq!(usage.last_issued(), None);
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, Amount::ZERO);
+    // generate a random private key unrelated to the wallet and add it
+    let (private_key, pub_key) =
+        crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr);
 
-    // issue some new address
-    let addresses_to_issue = rng.gen_range(1..10);
-    for _ in 0..=addresses_to_issue {
-        let _ = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
-    }
+    wallet
+        .add_standalone_private_key(DEFAULT_ACCOUNT_INDEX, private_key, None)
+        .unwrap();
 
-    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
-    assert_eq!(usage.last_used(), None);
-    assert_eq!(
-        usage.last_issued(),
-        Some(addresses_to_issue.try_into().unwrap())
-    );
+    // get the destination address from the new private key and send some coins to it
+    let address =
+        Address::new(&chain_config, Destination::PublicKeyHash((&pub_key).into())).unwrap();
 
-    let block1_amount = Amount::from_atoms(10000);
-    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+    // Generate a new block which sends reward to the new address
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let output = make_address_output(address.clone(), block1_amount);
 
-    let last_used = addresses_to_issue + 1;
-    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
-    assert_eq!(usage.last_used(), Some(last_used.try_into().unwrap()));
-    assert_eq!(usage.last_issued(), Some(last_used.try_into().unwrap()));
+    let tx =
+        SignedTransaction::new(Transaction::new(0, vec![], vec![output]).unwrap(), vec![]).unwrap();
+
+    let block1 = Block::new(
+        vec![tx.clone()],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![]),
+    )
+    .unwrap();
+
+    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1.clone()]);
+
+    // Check amount is still zero
+    let coin_balance = get_coin_balance(&wallet);
+    assert_eq!(coin_balance, Amount::ZERO);
+
+    // but the transaction has been added to the wallet
+    let tx_data = wallet
+        .get_transaction(DEFAULT_ACCOUNT_INDEX, tx.transaction().get_id())
+        .unwrap();
+
+    assert_eq!(tx_data.get_transaction(), tx.transaction());
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn wallet_set_lookahead_size(#[case] seed: Seed) {
+fn watch_standalone_address(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
     let chain_config = Arc::new(create_regtest());
-    let mut wallet = create_wallet(chain_config.clone());
-
-    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
-    assert_eq!(usage.last_used(), None);
-    assert_eq!(usage.last_issued(), None);
 
-    // issue some new address
-    let addresses_to_issue = rng.gen_range(1..10);
-    for _ in 0..=addresses_to_issue {
-        let _ = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
-    }
+    let mut wallet = create_wallet(chain_config.clone());
 
-    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
-    assert_eq!(usage.last_used(), None);
-    assert_eq!(
-        usage.last_issued(),
-        Some(addresses_to_issue.try_into().unwrap())
-    );
+    let (_, watched_pk) =
+        crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr);
+    let watched_pkh = PublicKeyHash::from(&watched_pk);
+    let watched_dest = Destination::PublicKeyHash(watched_pkh);
+    wallet.add_standalone_address(DEFAULT_ACCOUNT_INDEX, watched_pkh, None).unwrap();
+
+    // Adding the same address twice is an error
+    assert!(matches!(
+        wallet.add_standalone_address(DEFAULT_ACCOUNT_INDEX, watched_pkh, None),
+        Err(WalletError::KeyChainError(
+            KeyChainError::StandaloneAddressAlreadyExists(_)
+        ))
+    ));
 
-    let block1_amount = Amount::from_atoms(10000);
-    let (_, block1) = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+    // Generate a new block which sends coins to the watched address
+    let amount = Amount::from_atoms(rng.gen_range(1..10000));
+    let watched_address = Address::new(&chain_config, watched_dest.clone()).unwrap();
+    let tx = SignedTransaction::new(
+        Transaction::new(
+            0,
+            vec![],
+            vec![make_address_output(watched_address, amount)],
+        )
+        .unwrap(),
+        vec![],
+    )
+    .unwrap();
+    let block1 = Block::new(
+        vec![tx.clone()],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![]),
+    )
+    .unwrap();
+    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1]);
 
-    let last_used = addresses_to_issue + 1;
-    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
-    assert_eq!(usage.last_used(), Some(last_used.try_into().unwrap()));
-    assert_eq!(usage.last_issued(), Some(last_used.try_into().unwrap()));
+    // The coins are not counted as the account's own
+    assert_eq!(get_coin_balance(&wallet), Amount::ZERO);
+    assert!(wallet
+        .get_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoTypes::ALL,
+            UtxoState::Confirmed.into(),
+            WithLocked::Unlocked
+        )
+        .unwrap()
+        .is_empty());
 
-    let coins = get_coin_balance_for_acc(&wallet, DEFAULT_ACCOUNT_INDEX);
-    assert_eq!(coins, block1_amount);
+    // but they are reported for the watched address
+    let (_, balances, _) = wallet
+        .get_all_standalone_address_details(DEFAULT_ACCOUNT_INDEX, watched_dest.clone())
+        .unwrap();
+    assert_eq!(balances.get(&Currency::Coin), Some(&amount));
 
-    let less_than_last_used = rng.gen_range(1..=last_used);
-    let err = wallet.set_lookahead_size(less_than_last_used, false).unwrap_err();
+    let utxos = wallet
+        .get_standalone_address_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            watched_dest.clone(),
+            UtxoTypes::ALL,
+            UtxoState::Confirmed.into(),
+            WithLocked::Unlocked,
+        )
+        .unwrap();
     assert_eq!(
-        err,
-        WalletError::ReducedLookaheadSize(less_than_last_used, last_used)
+        utxos,
+        vec![(
+            UtxoOutPoint::new(OutPointSourceId::Transaction(tx.transaction().get_id()), 0),
+            tx.transaction().outputs()[0].clone(),
+            None
+        )]
     );
 
-    wallet.set_lookahead_size(less_than_last_used, true).unwrap();
-
-    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1.clone()]);
-    let coins = get_coin_balance_for_acc(&wallet, DEFAULT_ACCOUNT_INDEX);
-    assert_eq!(coins, Amount::ZERO);
-    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
-    assert_eq!(usage.last_used(), None);
-    assert_eq!(usage.last_issued(), None);
-
-    let more_than_last_used = rng.gen_range(last_used + 1..100);
-    wallet.set_lookahead_size(more_than_last_used, false).unwrap();
-
-    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1.clone()]);
-    let coins = get_coin_balance_for_acc(&wallet, DEFAULT_ACCOUNT_INDEX);
-    assert_eq!(coins, block1_amount);
-    let usage = wallet.get_addresses_usage(DEFAULT_ACCOUNT_INDEX).unwrap();
-    assert_eq!(usage.last_used(), Some(last_used.try_into().unwrap()));
-    assert_eq!(usage.last_issued(), Some(last_used.try_into().unwrap()));
+    // Stop watching the address
+    wallet.remove_standalone_address(DEFAULT_ACCOUNT_INDEX, watched_pkh).unwrap();
+    assert!(wallet
+        .get_all_standalone_addresses(DEFAULT_ACCOUNT_INDEX)
+        .unwrap()
+        .watch_only_addresses
+        .is_empty());
+    assert!(matches!(
+        wallet.get_standalone_address_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            watched_dest,
+            UtxoTypes::ALL,
+            UtxoState::Confirmed.into(),
+            WithLocked::Unlocked,
+        ),
+        Err(WalletError::StandaloneAddressNotFound(_))
+    ));
+    assert!(matches!(
+        wallet.remove_standalone_address(DEFAULT_ACCOUNT_INDEX, watched_pkh),
+        Err(WalletError::KeyChainError(
+            KeyChainError::NoStandaloneAddressFound(_)
+        ))
+    ));
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn decommission_pool_wrong_account(#[case] seed: Seed) {
+fn test_add_standalone_multisig(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
     let chain_config = Arc::new(create_regtest());
 
-    let acc_0_index = DEFAULT_ACCOUNT_INDEX;
-    let acc_1_index = U31::ONE;
-
-    let mut wallet = create_wallet(chain_config.clone());
+    let mut wallet1 = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC);
+    let mut wallet2 = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC2);
 
-    let coin_balance = get_coin_balance(&wallet);
+    let coin_balance = get_coin_balance(&wallet1);
+    assert_eq!(coin_balance, Amount::ZERO);
+    let coin_balance = get_coin_balance(&wallet2);
     assert_eq!(coin_balance, Amount::ZERO);
 
-    // Generate a new block which sends reward to the wallet
-    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
-    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+    let (_, address1) = wallet1.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let pub_key1 = wallet1.find_public_key(DEFAULT_ACCOUNT_INDEX, address1.into_object()).unwrap();
 
-    let pool_ids = wallet.get_pool_ids(acc_0_index, WalletPoolsFilter::All).unwrap();
-    assert!(pool_ids.is_empty());
+    let (_, address2) = wallet2.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let pub_key2 = wallet2.find_public_key(DEFAULT_ACCOUNT_INDEX, address2.into_object()).unwrap();
+    let (_, address3) = wallet2.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let pub_key3 = wallet2.find_public_key(DEFAULT_ACCOUNT_INDEX, address3.into_object()).unwrap();
 
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, block1_amount);
+    let min_required_signatures = 2;
+    let challenge = ClassicMultisigChallenge::new(
+        &chain_config,
+        NonZeroU8::new(min_required_signatures).unwrap(),
+        vec![pub_key3, pub_key2, pub_key1],
+    )
+    .unwrap();
+    let multisig_hash = wallet1
+        .add_standalone_multisig(DEFAULT_ACCOUNT_INDEX, challenge.clone(), None)
+        .unwrap();
 
-    let pool_amount = block1_amount;
+    let multisig_address =
+        Address::new(&chain_config, Destination::ClassicMultisig(multisig_hash)).unwrap();
 
-    let res = wallet.create_next_account(Some("name".into())).unwrap();
-    assert_eq!(res, (U31::from_u32(1).unwrap(), Some("name".into())));
+    // Generate a new block which sends reward to the new multisig address
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let output = make_address_output(multisig_address.clone(), block1_amount);
 
-    let decommission_key = wallet.get_new_address(acc_1_index).unwrap().1;
+    let tx =
+        SignedTransaction::new(Transaction::new(0, vec![], vec![output]).unwrap(), vec![]).unwrap();
 
-    let stake_pool_transaction = wallet
-        .create_stake_pool_tx(
-            acc_0_index,
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-            StakePoolDataArguments {
-                amount: pool_amount,
-                margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
-                cost_per_block: Amount::ZERO,
-                decommission_key: decommission_key.into_object(),
-            },
-        )
+    let block1 = Block::new(
+        vec![tx.clone()],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![]),
+    )
+    .unwrap();
+
+    scan_wallet(&mut wallet1, BlockHeight::new(0), vec![block1.clone()]);
+
+    // Check amount is still zero
+    let coin_balance = get_coin_balance(&wallet1);
+    assert_eq!(coin_balance, Amount::ZERO);
+
+    // but the transaction has been added to the wallet
+    let tx_data = wallet1
+        .get_transaction(DEFAULT_ACCOUNT_INDEX, tx.transaction().get_id())
         .unwrap();
-    let _ = create_block(
-        &chain_config,
-        &mut wallet,
-        vec![stake_pool_transaction],
-        Amount::ZERO,
-        1,
-    );
 
-    let pool_ids = wallet.get_pool_ids(acc_0_index, WalletPoolsFilter::All).unwrap();
-    assert_eq!(pool_ids.len(), 1);
+    assert_eq!(tx_data.get_transaction(), tx.transaction());
 
-    // Try to decommission the pool with default account
-    let pool_id = pool_ids.first().unwrap().0;
-    let decommission_cmd_res = wallet.decommission_stake_pool(
-        acc_0_index,
-        pool_id,
-        pool_amount,
-        None,
-        FeeRate::from_amount_per_kb(Amount::from_atoms(0)),
-    );
-    assert_eq!(
-        decommission_cmd_res.unwrap_err(),
-        WalletError::PartiallySignedTransactionInDecommissionCommand
-    );
+    let spend_multisig_tx = Transaction::new(
+        0,
+        vec![TxInput::from_utxo(OutPointSourceId::Transaction(tx.transaction().get_id()), 0)],
+        vec![TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(1)),
+            Destination::AnyoneCanSpend,
+        )],
+    )
+    .unwrap();
 
-    // Decommission from the account that holds the key
-    let decommission_tx = wallet
-        .decommission_stake_pool(
-            acc_1_index,
-            pool_id,
-            pool_amount,
-            None,
-            FeeRate::from_amount_per_kb(Amount::from_atoms(0)),
+    // sign it with wallet1
+    let (ptx, _, statuses) = wallet1
+        .sign_raw_transaction(
+            DEFAULT_ACCOUNT_INDEX,
+            TransactionToSign::Tx(spend_multisig_tx),
         )
         .unwrap();
 
-    let _ = create_block(
-        &chain_config,
-        &mut wallet,
-        vec![decommission_tx],
-        Amount::ZERO,
-        2,
-    );
-
-    let coin_balance = get_coin_balance_for_acc(&wallet, acc_1_index);
-    assert_eq!(coin_balance, pool_amount);
-}
-
-#[rstest]
-#[trace]
-#[case(Seed::from_entropy())]
-fn decommission_pool_request_wrong_account(#[case] seed: Seed) {
-    let mut rng = make_seedable_rng(seed);
-    let chain_config = Arc::new(create_mainnet());
+    // check it is still not fully signed
+    assert!(ptx.all_signatures_available());
+    assert!(!statuses.iter().all(|s| *s == SignatureStatus::FullySigned));
 
-    let acc_0_index = DEFAULT_ACCOUNT_INDEX;
-    let acc_1_index = U31::ONE;
+    // try to sign it with wallet1 again
+    let (ptx, _, statuses) = wallet1
+        .sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, TransactionToSign::Partial(ptx))
+        .unwrap();
 
-    let mut wallet = create_wallet(chain_config.clone());
+    // check it is still not fully signed
+    assert!(ptx.all_signatures_available());
+    assert!(!statuses.iter().all(|s| *s == SignatureStatus::FullySigned));
 
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, Amount::ZERO);
+    // try to sign it with wallet2 but wallet2 does not have the multisig added as standalone
+    let ptx = wallet2
+        .sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, TransactionToSign::Partial(ptx))
+        .unwrap()
+        .0;
 
-    // Generate a new block which sends reward to the wallet
-    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
-    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+    // add it to wallet2 as well
+    wallet2.add_standalone_multisig(DEFAULT_ACCOUNT_INDEX, challenge, None).unwrap();
 
-    let pool_ids = wallet.get_pool_ids(acc_0_index, WalletPoolsFilter::All).unwrap();
-    assert!(pool_ids.is_empty());
+    // now we can sign it
+    let (ptx, _, statuses) = wallet2
+        .sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, TransactionToSign::Partial(ptx))
+        .unwrap();
 
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, block1_amount);
+    // now it is fully signed
+    assert!(ptx.all_signatures_available());
+    assert!(statuses.iter().all(|s| *s == SignatureStatus::FullySigned));
+}
 
-    let pool_amount = block1_amount;
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn multisig_account_descriptor(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
 
-    let res = wallet.create_next_account(Some("name".into())).unwrap();
-    assert_eq!(res, (U31::from_u32(1).unwrap(), Some("name".into())));
+    let mut wallet1 = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC);
+    let mut wallet2 = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC2);
+    let mut wallet3 = create_wallet_with_mnemonic(
+        chain_config.clone(),
+        "legal winner thank year wave sausage worth useful legal winner thank yellow",
+    );
 
-    let decommission_key = wallet.get_new_address(acc_1_index).unwrap().1;
+    let key1 = wallet1.account_extended_public_key(DEFAULT_ACCOUNT_INDEX).unwrap().clone();
+    let key2 = wallet2.account_extended_public_key(DEFAULT_ACCOUNT_INDEX).unwrap().clone();
+    let min_required_signatures = NonZeroU8::new(2).unwrap();
 
-    let stake_pool_transaction = wallet
-        .create_stake_pool_tx(
-            acc_0_index,
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-            StakePoolDataArguments {
-                amount: pool_amount,
-                margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
-                cost_per_block: Amount::ZERO,
-                decommission_key: decommission_key.into_object(),
-            },
+    // Both cosigners get the same descriptor
+    let descriptor = wallet1
+        .create_multisig_account_descriptor(
+            DEFAULT_ACCOUNT_INDEX,
+            min_required_signatures,
+            vec![key2],
         )
         .unwrap();
-    let _ = create_block(
-        &chain_config,
-        &mut wallet,
-        vec![stake_pool_transaction],
-        Amount::ZERO,
-        1,
+    assert_eq!(
+        wallet2
+            .create_multisig_account_descriptor(
+                DEFAULT_ACCOUNT_INDEX,
+                min_required_signatures,
+                vec![key1],
+            )
+            .unwrap(),
+        descriptor
     );
 
-    let pool_ids = wallet.get_pool_ids(acc_0_index, WalletPoolsFilter::All).unwrap();
-    assert_eq!(pool_ids.len(), 1);
-
-    // Try to create decommission request from account that holds the key
-    let pool_id = pool_ids.first().unwrap().0;
-    let decommission_req_res = wallet.decommission_stake_pool_request(
-        acc_1_index,
-        pool_id,
-        pool_amount,
-        None,
-        FeeRate::from_amount_per_kb(Amount::from_atoms(0)),
+    let address_count = U31::from_u32(rng.gen_range(1..10)).unwrap();
+    let multisig_hashes = wallet1
+        .import_multisig_account(DEFAULT_ACCOUNT_INDEX, &descriptor, address_count, None)
+        .unwrap();
+    assert_eq!(multisig_hashes.len(), address_count.into_u32() as usize);
+    assert_eq!(
+        wallet2
+            .import_multisig_account(DEFAULT_ACCOUNT_INDEX, &descriptor, address_count, None)
+            .unwrap(),
+        multisig_hashes
     );
+
+    // Only the cosigners can import the descriptor
     assert_eq!(
-        decommission_req_res.unwrap_err(),
-        WalletError::FullySignedTransactionInDecommissionReq
+        wallet3.import_multisig_account(DEFAULT_ACCOUNT_INDEX, &descriptor, address_count, None),
+        Err(WalletError::NotMultisigAccountCosigner)
     );
 
-    let decommission_partial_tx = wallet
-        .decommission_stake_pool_request(
-            acc_0_index,
-            pool_id,
-            pool_amount,
-            None,
-            FeeRate::from_amount_per_kb(Amount::from_atoms(0)),
+    // Send coins to one of the multisig account addresses and spend them with both cosigners
+    let multisig_hash = multisig_hashes[rng.gen_range(0..multisig_hashes.len())];
+    let multisig_address =
+        Address::new(&chain_config, Destination::ClassicMultisig(multisig_hash)).unwrap();
+    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
+    let output = make_address_output(multisig_address, block1_amount);
+    let tx =
+        SignedTransaction::new(Transaction::new(0, vec![], vec![output]).unwrap(), vec![]).unwrap();
+    let block1 = Block::new(
+        vec![tx.clone()],
+        chain_config.genesis_block_id(),
+        chain_config.genesis_block().timestamp(),
+        ConsensusData::None,
+        BlockReward::new(vec![]),
+    )
+    .unwrap();
+    scan_wallet(&mut wallet1, BlockHeight::new(0), vec![block1]);
+
+    let spend_multisig_tx = Transaction::new(
+        0,
+        vec![TxInput::from_utxo(OutPointSourceId::Transaction(tx.transaction().get_id()), 0)],
+        vec![TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(1)),
+            Destination::AnyoneCanSpend,
+        )],
+    )
+    .unwrap();
+
+    let (ptx, _, statuses) = wallet1
+        .sign_raw_transaction(
+            DEFAULT_ACCOUNT_INDEX,
+            TransactionToSign::Tx(spend_multisig_tx),
         )
         .unwrap();
-    assert!(!decommission_partial_tx.all_signatures_available());
-    matches!(
-        decommission_partial_tx.into_signed_tx().unwrap_err(),
-        TransactionCreationError::FailedToConvertPartiallySignedTx(_)
-    );
+    assert!(!statuses.iter().all(|s| *s == SignatureStatus::FullySigned));
+
+    let (ptx, _, statuses) = wallet2
+        .sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, TransactionToSign::Partial(ptx))
+        .unwrap();
+    assert!(ptx.all_signatures_available());
+    assert!(statuses.iter().all(|s| *s == SignatureStatus::FullySigned));
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn sign_decommission_pool_request_between_accounts(#[case] seed: Seed) {
+fn create_htlc_and_spend(#[case] seed: Seed) {
+    use common::chain::htlc::HtlcSecret;
+
     let mut rng = make_seedable_rng(seed);
     let chain_config = Arc::new(create_regtest());
 
-    let acc_0_index = DEFAULT_ACCOUNT_INDEX;
-    let acc_1_index = U31::ONE;
-
-    let mut wallet = create_wallet(chain_config.clone());
+    let mut wallet1 = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC);
+    let mut wallet2 = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC2);
 
-    let coin_balance = get_coin_balance(&wallet);
+    let coin_balance = get_coin_balance(&wallet1);
     assert_eq!(coin_balance, Amount::ZERO);
 
     // Generate a new block which sends reward to the wallet
     let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
-    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
-
-    let pool_ids = wallet.get_pool_ids(acc_0_index, WalletPoolsFilter::All).unwrap();
-    assert!(pool_ids.is_empty());
+    let _ = create_block(&chain_config, &mut wallet1, vec![], block1_amount, 0);
 
-    let coin_balance = get_coin_balance(&wallet);
+    let coin_balance = get_coin_balance(&wallet1);
     assert_eq!(coin_balance, block1_amount);
 
-    let pool_amount = block1_amount;
+    let (_, address1) = wallet1.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let pub_key1 = wallet1.find_public_key(DEFAULT_ACCOUNT_INDEX, address1.into_object()).unwrap();
 
-    let res = wallet.create_next_account(Some("name".into())).unwrap();
-    assert_eq!(res, (U31::from_u32(1).unwrap(), Some("name".into())));
+    let (_, address2) = wallet2.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let pub_key2 = wallet2
+        .find_public_key(DEFAULT_ACCOUNT_INDEX, address2.clone().into_object())
+        .unwrap();
 
-    let decommission_key = wallet.get_new_address(acc_1_index).unwrap().1;
+    let min_required_signatures = 2;
+    let challenge = ClassicMultisigChallenge::new(
+        &chain_config,
+        NonZeroU8::new(min_required_signatures).unwrap(),
+        vec![pub_key1, pub_key2],
+    )
+    .unwrap();
+    let multisig_hash = wallet1
+        .add_standalone_multisig(DEFAULT_ACCOUNT_INDEX, challenge.clone(), None)
+        .unwrap();
+    wallet2
+        .add_standalone_multisig(DEFAULT_ACCOUNT_INDEX, challenge.clone(), None)
+        .unwrap();
 
-    let stake_pool_transaction = wallet
-        .create_stake_pool_tx(
-            acc_0_index,
+    let secret = HtlcSecret::new_from_rng(&mut rng);
+    let spend_key = wallet2.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+    let htlc = HashedTimelockContract {
+        secret_hash: secret.hash(),
+        spend_key: spend_key.clone().into_object(),
+        refund_timelock: OutputTimeLock::ForBlockCount(1),
+        refund_key: Destination::ClassicMultisig(multisig_hash),
+    };
+    let output_value = OutputValue::Coin(coin_balance);
+
+    let create_htlc_tx = wallet1
+        .create_htlc_tx(
+            DEFAULT_ACCOUNT_INDEX,
+            output_value.clone(),
+            htlc.clone(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
-            StakePoolDataArguments {
-                amount: pool_amount,
-                margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
-                cost_per_block: Amount::ZERO,
-                decommission_key: decommission_key.into_object(),
-            },
         )
         .unwrap();
-
-    // remove the signatures and try to sign it again
-    let tx = stake_pool_transaction.transaction().clone();
-    let stake_pool_transaction = wallet
-        .sign_raw_transaction(acc_0_index, TransactionToSign::Tx(tx))
-        .unwrap()
-        .0
-        .into_signed_tx()
-        .unwrap();
-
-    let _ = create_block(
+    let create_htlc_tx_id = create_htlc_tx.transaction().get_id();
+    let (_, block2) = create_block(
         &chain_config,
-        &mut wallet,
-        vec![stake_pool_transaction],
+        &mut wallet1,
+        vec![create_htlc_tx.clone()],
         Amount::ZERO,
         1,
     );
+    scan_wallet(&mut wallet2, BlockHeight::new(0), vec![block2]);
 
-    assert_eq!(get_coin_balance(&wallet), Amount::ZERO);
-
-    let pool_ids = wallet.get_pool_ids(acc_0_index, WalletPoolsFilter::All).unwrap();
-    assert_eq!(pool_ids.len(), 1);
+    // Htlc is not accounted in balance
+    assert_eq!(get_coin_balance(&wallet1), Amount::ZERO);
+    assert_eq!(get_coin_balance(&wallet2), Amount::ZERO);
 
-    let pool_id = pool_ids.first().unwrap().0;
-    let decommission_partial_tx = wallet
-        .decommission_stake_pool_request(
-            acc_0_index,
-            pool_id,
-            pool_amount,
-            None,
-            FeeRate::from_amount_per_kb(Amount::from_atoms(0)),
+    // Htlc is available as utxo to spend
+    let wallet1_utxos = wallet1
+        .get_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoType::Htlc.into(),
+            UtxoState::Confirmed.into(),
+            WithLocked::Any,
         )
         .unwrap();
-
-    // Try to sign decommission request with wrong account
-    let sign_from_acc0_res = wallet
-        .sign_raw_transaction(
-            acc_0_index,
-            TransactionToSign::Partial(decommission_partial_tx.clone()),
+    assert!(wallet1_utxos.is_empty());
+    let mut wallet2_utxos = wallet2
+        .get_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoType::Htlc.into(),
+            UtxoState::Confirmed.into(),
+            WithLocked::Any,
         )
-        .unwrap()
-        .0;
-    // the tx is still not fully signed
-    assert!(!sign_from_acc0_res.all_signatures_available());
+        .unwrap();
+    assert_eq!(wallet2_utxos.len(), 1);
+    let (_, output, _) = wallet2_utxos.pop().unwrap();
+    match output {
+        TxOutput::Htlc(actual_output_value, actual_htlc) => {
+            assert_eq!(actual_output_value, output_value);
+            assert_eq!(htlc, *actual_htlc);
+        }
+        _ => panic!("wrong TxOutput type"),
+    };
 
-    let signed_tx = wallet
-        .sign_raw_transaction(
-            acc_1_index,
-            TransactionToSign::Partial(decommission_partial_tx),
-        )
-        .unwrap()
-        .0
-        .into_signed_tx()
+    // spend with a secret
+    let spend_tx = Transaction::new(
+        0,
+        vec![TxInput::from_utxo(create_htlc_tx_id.into(), 0)],
+        vec![TxOutput::Transfer(output_value, address2.into_object())],
+    )
+    .unwrap();
+    let spend_utxos = vec![create_htlc_tx.transaction().outputs().first().cloned()];
+    let spend_ptx = PartiallySignedTransaction::new(
+        spend_tx,
+        vec![None],
+        spend_utxos,
+        vec![Some(spend_key.into_object())],
+        Some(vec![Some(secret)]),
+        None,
+    )
+    .unwrap();
+
+    let (spend_ptx, _, new_statuses) = wallet2
+        .sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, TransactionToSign::Partial(spend_ptx))
         .unwrap();
+    assert_eq!(vec![SignatureStatus::FullySigned], new_statuses);
 
-    let _ = create_block(&chain_config, &mut wallet, vec![signed_tx], Amount::ZERO, 2);
+    let spend_tx = spend_ptx.into_signed_tx().unwrap();
 
-    // the pool amount is back after decommission
-    assert_eq!(get_coin_balance(&wallet), pool_amount);
-    assert_eq!(get_coin_balance_for_acc(&wallet, acc_1_index), Amount::ZERO);
+    let (_, block2) = create_block(&chain_config, &mut wallet2, vec![spend_tx], Amount::ZERO, 1);
+    scan_wallet(&mut wallet2, BlockHeight::new(0), vec![block2]);
+
+    // Coins from htlc successfully transferred
+    assert_eq!(get_coin_balance(&wallet1), Amount::ZERO);
+    assert_eq!(get_coin_balance(&wallet2), coin_balance);
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn sign_decommission_pool_request_cold_wallet(#[case] seed: Seed) {
+fn create_htlc_and_refund(#[case] seed: Seed) {
+    use common::chain::htlc::HtlcSecret;
+
     let mut rng = make_seedable_rng(seed);
     let chain_config = Arc::new(create_regtest());
 
-    let mut hot_wallet = create_wallet(chain_config.clone());
-
-    // create cold wallet that is not synced and only contains decommission key
-    let another_mnemonic =
-        "legal winner thank year wave sausage worth useful legal winner thank yellow";
-    let mut cold_wallet = create_wallet_with_mnemonic(chain_config.clone(), another_mnemonic);
-    let decommission_key = cold_wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+    let mut wallet1 = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC);
+    let mut wallet2 = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC2);
 
-    let coin_balance = get_coin_balance(&hot_wallet);
+    let coin_balance = get_coin_balance(&wallet1);
     assert_eq!(coin_balance, Amount::ZERO);
 
     // Generate a new block which sends reward to the wallet
     let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
-    let _ = create_block(&chain_config, &mut hot_wallet, vec![], block1_amount, 0);
-
-    let pool_ids = hot_wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
-    assert!(pool_ids.is_empty());
+    let _ = create_block(&chain_config, &mut wallet1, vec![], block1_amount, 0);
 
-    let coin_balance = get_coin_balance(&hot_wallet);
+    let coin_balance = get_coin_balance(&wallet1);
     assert_eq!(coin_balance, block1_amount);
+    assert_eq!(get_coin_balance(&wallet2), Amount::ZERO);
 
-    let pool_amount = block1_amount;
+    let (_, address1) = wallet1.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let pub_key1 = wallet1
+        .find_public_key(DEFAULT_ACCOUNT_INDEX, address1.clone().into_object())
+        .unwrap();
 
-    let res = hot_wallet.create_next_account(Some("name".into())).unwrap();
-    assert_eq!(res, (U31::from_u32(1).unwrap(), Some("name".into())));
+    let (_, address2) = wallet2.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let pub_key2 = wallet2.find_public_key(DEFAULT_ACCOUNT_INDEX, address2.into_object()).unwrap();
 
-    let stake_pool_transaction = hot_wallet
-        .create_stake_pool_tx(
+    let min_required_signatures = 2;
+    let challenge = ClassicMultisigChallenge::new(
+        &chain_config,
+        NonZeroU8::new(min_required_signatures).unwrap(),
+        vec![pub_key1, pub_key2],
+    )
+    .unwrap();
+    let multisig_hash = wallet1
+        .add_standalone_multisig(DEFAULT_ACCOUNT_INDEX, challenge.clone(), None)
+        .unwrap();
+    wallet2
+        .add_standalone_multisig(DEFAULT_ACCOUNT_INDEX, challenge.clone(), None)
+        .unwrap();
+
+    let secret = HtlcSecret::new_from_rng(&mut rng);
+    let spend_key = wallet2.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+    let refund_key = Destination::ClassicMultisig(multisig_hash);
+    let htlc = HashedTimelockContract {
+        secret_hash: secret.hash(),
+        spend_key: spend_key.into_object(),
+        refund_timelock: OutputTimeLock::ForBlockCount(1),
+        refund_key: refund_key.clone(),
+    };
+    let output_value = OutputValue::Coin(coin_balance);
+
+    let create_htlc_tx = wallet1
+        .create_htlc_tx(
             DEFAULT_ACCOUNT_INDEX,
+            output_value.clone(),
+            htlc.clone(),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
-            StakePoolDataArguments {
-                amount: pool_amount,
-                margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
-                cost_per_block: Amount::ZERO,
-                decommission_key: decommission_key.into_object(),
-            },
         )
         .unwrap();
-    let _ = create_block(
+    let create_htlc_tx_id = create_htlc_tx.transaction().get_id();
+
+    let refund_tx = Transaction::new(
+        0,
+        vec![TxInput::from_utxo(create_htlc_tx_id.into(), 0)],
+        vec![TxOutput::Transfer(output_value, address1.into_object())],
+    )
+    .unwrap();
+    let refund_utxos = vec![create_htlc_tx.transaction().outputs().first().cloned()];
+    let refund_ptx = PartiallySignedTransaction::new(
+        refund_tx,
+        vec![None],
+        refund_utxos,
+        vec![Some(refund_key)],
+        None,
+        None,
+    )
+    .unwrap();
+
+    let (_, block2) = create_block(
         &chain_config,
-        &mut hot_wallet,
-        vec![stake_pool_transaction],
+        &mut wallet1,
+        vec![create_htlc_tx],
         Amount::ZERO,
         1,
     );
+    scan_wallet(&mut wallet2, BlockHeight::new(0), vec![block2]);
 
-    let pool_ids = hot_wallet.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
-    assert_eq!(pool_ids.len(), 1);
+    // Htlc is not accounted in balance
+    assert_eq!(get_coin_balance(&wallet1), Amount::ZERO);
+    assert_eq!(get_coin_balance(&wallet2), Amount::ZERO);
 
-    let pool_id = pool_ids.first().unwrap().0;
-    let decommission_partial_tx = hot_wallet
-        .decommission_stake_pool_request(
+    let wallet1_multisig_utxos = wallet1
+        .get_multisig_utxos(
             DEFAULT_ACCOUNT_INDEX,
-            pool_id,
-            pool_amount,
-            None,
-            FeeRate::from_amount_per_kb(Amount::from_atoms(0)),
+            UtxoTypes::ALL,
+            UtxoStates::ALL,
+            WithLocked::Any,
+        )
+        .unwrap();
+    assert_eq!(wallet1_multisig_utxos.len(), 1);
+    let wallet2_multisig_utxos = wallet2
+        .get_multisig_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoTypes::ALL,
+            UtxoStates::ALL,
+            WithLocked::Any,
         )
         .unwrap();
+    assert_eq!(wallet2_multisig_utxos.len(), 1);
 
-    // sign the tx with cold wallet
-    let partially_signed_transaction = cold_wallet
+    let (refund_ptx, prev_statuses, new_statuses) = wallet2
         .sign_raw_transaction(
             DEFAULT_ACCOUNT_INDEX,
-            TransactionToSign::Partial(decommission_partial_tx),
+            TransactionToSign::Partial(refund_ptx),
         )
-        .unwrap()
-        .0;
-    assert!(partially_signed_transaction.all_signatures_available());
+        .unwrap();
 
-    // sign it with the hot wallet should leave the signatures in place even if it can't find the
-    // destinations for the inputs
-    let partially_signed_transaction = hot_wallet
+    assert_eq!(vec![SignatureStatus::NotSigned], prev_statuses);
+    assert_eq!(
+        vec![SignatureStatus::PartialMultisig {
+            required_signatures: 2,
+            num_signatures: 1
+        }],
+        new_statuses
+    );
+
+    let (refund_ptx, prev_statuses, new_statuses) = wallet1
         .sign_raw_transaction(
             DEFAULT_ACCOUNT_INDEX,
-            TransactionToSign::Partial(partially_signed_transaction),
+            TransactionToSign::Partial(refund_ptx),
         )
-        .unwrap()
-        .0;
-    assert!(partially_signed_transaction.all_signatures_available());
+        .unwrap();
+    assert_eq!(
+        vec![SignatureStatus::PartialMultisig {
+            required_signatures: 2,
+            num_signatures: 1
+        }],
+        prev_statuses
+    );
+    assert_eq!(vec![SignatureStatus::FullySigned], new_statuses);
 
-    let signed_tx = partially_signed_transaction.into_signed_tx().unwrap();
+    let refund_tx = refund_ptx.into_signed_tx().unwrap();
 
-    let _ = create_block(
+    let (_, block3) = create_block(
         &chain_config,
-        &mut hot_wallet,
-        vec![signed_tx],
+        &mut wallet1,
+        vec![refund_tx],
         Amount::ZERO,
         2,
     );
+    scan_wallet(&mut wallet2, BlockHeight::new(0), vec![block3]);
 
-    let coin_balance = get_coin_balance(&hot_wallet);
-    assert_eq!(coin_balance, pool_amount,);
+    // Refund can be seen in the wallet balance
+    assert_eq!(get_coin_balance(&wallet1), coin_balance);
+    assert_eq!(get_coin_balance(&wallet2), Amount::ZERO);
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn filter_pools(#[case] seed: Seed) {
+fn watch_pool_and_track_created_blocks(#[case] seed: Seed) {
+    use crypto::vrf::{transcript::no_rng::VRFTranscript, VRFKeyKind, VRFPrivateKey};
+
     let mut rng = make_seedable_rng(seed);
     let chain_config = Arc::new(create_regtest());
 
-    let mut wallet1 = create_wallet(chain_config.clone());
+    let mut wallet = create_wallet(chain_config.clone());
 
-    // create another wallet to store decommission key
-    let another_mnemonic =
-        "legal winner thank year wave sausage worth useful legal winner thank yellow";
-    let mut wallet2 = create_wallet_with_mnemonic(chain_config.clone(), another_mnemonic);
-    let decommission_key = wallet2.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+    let pool_id = PoolId::new(H256::random_using(&mut rng));
+    let (vrf_sk, _) = VRFPrivateKey::new_from_rng(&mut rng, VRFKeyKind::Schnorrkel);
+    let staker = Destination::PublicKey(
+        crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr).1,
+    );
 
-    let coin_balance = get_coin_balance(&wallet1);
-    assert_eq!(coin_balance, Amount::ZERO);
+    let decommission_key = Destination::PublicKey(
+        crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr).1,
+    );
 
-    // Generate a new block which sends reward to the wallet
-    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
-    let _ = create_block(&chain_config, &mut wallet1, vec![], block1_amount, 0);
-    let _ = create_block(&chain_config, &mut wallet2, vec![], block1_amount, 0);
+    wallet
+        .add_watched_pool(
+            DEFAULT_ACCOUNT_INDEX,
+            pool_id,
+            Some("pool".into()),
+            Some(decommission_key.clone()),
+        )
+        .unwrap();
 
-    let pool_ids = wallet1.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
-    assert!(pool_ids.is_empty());
+    let err = wallet.add_watched_pool(DEFAULT_ACCOUNT_INDEX, pool_id, None, None).unwrap_err();
+    assert_eq!(err, WalletError::PoolAlreadyWatched(pool_id));
 
-    let pool_ids = wallet2.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
-    assert!(pool_ids.is_empty());
+    let make_pos_block = |rng: &mut _| {
+        Block::new(
+            vec![],
+            chain_config.genesis_block_id(),
+            chain_config.genesis_block().timestamp(),
+            ConsensusData::PoS(Box::new(PoSData::new(
+                vec![TxInput::Utxo(UtxoOutPoint::new(
+                    OutPointSourceId::Transaction(Id::new(H256::random_using(rng))),
+                    0,
+                ))],
+                vec![],
+                pool_id,
+                vrf_sk.produce_vrf_data(VRFTranscript::new(&[])),
+                common::primitives::Compact(0),
+            ))),
+            BlockReward::new(vec![TxOutput::ProduceBlockFromStake(
+                staker.clone(),
+                pool_id,
+            )]),
+        )
+        .unwrap()
+    };
 
-    let pool_amount = block1_amount;
+    let block1 = make_pos_block(&mut rng);
+    let block2 = make_pos_block(&mut rng);
+    scan_wallet(
+        &mut wallet,
+        BlockHeight::new(0),
+        vec![block1.clone(), block2],
+    );
 
-    let stake_pool_transaction = wallet1
-        .create_stake_pool_tx(
-            DEFAULT_ACCOUNT_INDEX,
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-            StakePoolDataArguments {
-                amount: pool_amount,
-                margin_ratio_per_thousand: PerThousand::new_from_rng(&mut rng),
-                cost_per_block: Amount::ZERO,
-                decommission_key: decommission_key.into_object(),
-            },
-        )
-        .unwrap();
-    // sync for wallet1
-    let _ = create_block(
-        &chain_config,
-        &mut wallet1,
-        vec![stake_pool_transaction.clone()],
-        Amount::ZERO,
-        1,
+    let watched_pools = wallet.get_watched_pools(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(watched_pools.len(), 1);
+    let (watched_pool_id, watched_pool) = &watched_pools[0];
+    assert_eq!(*watched_pool_id, pool_id);
+    assert_eq!(watched_pool.label, Some("pool".into()));
+    assert_eq!(
+        watched_pool.decommission_key,
+        Some(decommission_key.clone())
     );
-    // sync for wallet2
-    let _ = create_block(
-        &chain_config,
-        &mut wallet2,
-        vec![stake_pool_transaction],
-        Amount::ZERO,
-        1,
+    assert_eq!(watched_pool.created_blocks.len(), 2);
+
+    // A reorg removes the blocks that are no longer on the mainchain
+    let _ = create_block(&chain_config, &mut wallet, vec![], Amount::ZERO, 1);
+
+    let watched_pools = wallet.get_watched_pools(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(
+        watched_pools[0].1.created_blocks,
+        vec![(BlockHeight::new(1), block1.get_id().into())]
     );
 
-    // check wallet1 filter
-    let pool_ids = wallet1.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
-    assert_eq!(pool_ids.len(), 1);
+    wallet.remove_watched_pool(DEFAULT_ACCOUNT_INDEX, pool_id).unwrap();
+    assert!(wallet.get_watched_pools(DEFAULT_ACCOUNT_INDEX).unwrap().is_empty());
 
-    let pool_ids = wallet1
-        .get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::Decommission)
+    let err = wallet.remove_watched_pool(DEFAULT_ACCOUNT_INDEX, pool_id).unwrap_err();
+    assert_eq!(err, WalletError::UnknownWatchedPoolId(pool_id));
+}
+
+#[test]
+fn scheduled_payments() {
+    let chain_config = Arc::new(create_regtest());
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let first_run = BlockTimestamp::from_int_seconds(1000);
+    let new_payment = |amount, interval_secs| {
+        ScheduledPayment::new(
+            Some("payroll".into()),
+            Destination::AnyoneCanSpend,
+            None,
+            amount,
+            interval_secs,
+            first_run,
+        )
+    };
+
+    let err = wallet
+        .add_scheduled_payment(DEFAULT_ACCOUNT_INDEX, new_payment(Amount::from_atoms(1), 0))
+        .unwrap_err();
+    assert_eq!(err, WalletError::InvalidScheduledPaymentInterval);
+    let err = wallet
+        .add_scheduled_payment(DEFAULT_ACCOUNT_INDEX, new_payment(Amount::ZERO, 100))
+        .unwrap_err();
+    assert_eq!(err, WalletError::InvalidScheduledPaymentAmount);
+
+    let payment_id1 = wallet
+        .add_scheduled_payment(
+            DEFAULT_ACCOUNT_INDEX,
+            new_payment(Amount::from_atoms(1), 100),
+        )
         .unwrap();
-    assert_eq!(pool_ids.len(), 0);
+    let payment_id2 = wallet
+        .add_scheduled_payment(
+            DEFAULT_ACCOUNT_INDEX,
+            new_payment(Amount::from_atoms(2), 100),
+        )
+        .unwrap();
+    assert_ne!(payment_id1, payment_id2);
+
+    assert!(wallet
+        .get_due_scheduled_payments(BlockTimestamp::from_int_seconds(999))
+        .is_empty());
+    let due = wallet.get_due_scheduled_payments(first_run);
+    assert_eq!(due.len(), 2);
+
+    let (account_index, payment_id, mut payment) = due.into_iter().next().unwrap();
+    assert_eq!(account_index, DEFAULT_ACCOUNT_INDEX);
+    payment.on_failure(first_run, "error".into());
+    wallet.update_scheduled_payment(account_index, payment_id, payment).unwrap();
+    assert_eq!(wallet.get_due_scheduled_payments(first_run).len(), 1);
+
+    // The payments are persisted in the DB
+    let mut wallet = Wallet::load_wallet(
+        Arc::clone(&chain_config),
+        wallet.db,
+        None,
+        |_| Ok(()),
+        WalletType::Hot,
+        false,
+    )
+    .unwrap();
+    let payments = wallet.get_scheduled_payments(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(payments.len(), 2);
+    assert_eq!(payments[0].1.failed_attempts, 1);
+    assert_eq!(payments[0].1.last_error, Some("error".into()));
+
+    wallet.cancel_scheduled_payment(DEFAULT_ACCOUNT_INDEX, payment_id1).unwrap();
+    let payments = wallet.get_scheduled_payments(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].0, payment_id2);
+
+    let err = wallet.cancel_scheduled_payment(DEFAULT_ACCOUNT_INDEX, payment_id1).unwrap_err();
+    assert_eq!(err, WalletError::UnknownScheduledPaymentId(payment_id1));
+
+    // The ids of the cancelled payments are not reused, even for the last one
+    wallet.cancel_scheduled_payment(DEFAULT_ACCOUNT_INDEX, payment_id2).unwrap();
+    let payment_id3 = wallet
+        .add_scheduled_payment(
+            DEFAULT_ACCOUNT_INDEX,
+            new_payment(Amount::from_atoms(3), 100),
+        )
+        .unwrap();
+    assert_ne!(payment_id3, payment_id1);
+    assert_ne!(payment_id3, payment_id2);
+}
 
-    let pool_ids = wallet1.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::Stake).unwrap();
-    assert_eq!(pool_ids.len(), 1);
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn max_sendable_coins(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
 
-    // check wallet2 filter
-    let pool_ids = wallet2.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::All).unwrap();
-    assert_eq!(pool_ids.len(), 1);
+    let mut wallet = create_wallet(chain_config.clone());
 
-    let pool_ids = wallet2
-        .get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::Decommission)
+    // Generate a few blocks which send rewards to the wallet
+    let num_blocks = rng.gen_range(1..5);
+    let mut total_amount = Amount::ZERO;
+    for block_height in 0..num_blocks {
+        let amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+        let _ = create_block(&chain_config, &mut wallet, vec![], amount, block_height);
+        total_amount = (total_amount + amount).unwrap();
+    }
+    assert_eq!(get_coin_balance(&wallet), total_amount);
+
+    let utxos = wallet
+        .get_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoType::Transfer | UtxoType::LockThenTransfer,
+            UtxoState::Confirmed.into(),
+            WithLocked::Unlocked,
+        )
         .unwrap();
-    assert_eq!(pool_ids.len(), 1);
+    let destination = Destination::PublicKey(
+        crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr).1,
+    );
+    let feerate = FeeRate::from_amount_per_kb(Amount::from_atoms(1000));
 
-    let pool_ids = wallet2.get_pool_ids(DEFAULT_ACCOUNT_INDEX, WalletPoolsFilter::Stake).unwrap();
-    assert_eq!(pool_ids.len(), 0);
+    let max_amount = wallet
+        .get_max_sendable_amount(
+            DEFAULT_ACCOUNT_INDEX,
+            destination.clone(),
+            &Currency::Coin,
+            utxos.clone(),
+            feerate,
+        )
+        .unwrap();
+    assert!(max_amount < total_amount);
+
+    // Sending the max amount spends everything, with no change
+    let transaction = wallet
+        .create_sweep_transaction(DEFAULT_ACCOUNT_INDEX, destination.clone(), utxos, feerate)
+        .unwrap();
+    assert_eq!(
+        transaction.transaction().outputs(),
+        &[TxOutput::Transfer(OutputValue::Coin(max_amount), destination)]
+    );
+
+    let tx_size = serialization::Encode::encoded_size(&transaction);
+    let fee = (total_amount - max_amount).unwrap();
+    assert!(fee >= feerate.compute_fee(tx_size).unwrap().into());
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn sign_send_request_cold_wallet(#[case] seed: Seed) {
+fn dust_protection(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
     let chain_config = Arc::new(create_regtest());
 
-    let mut hot_wallet = create_wallet(chain_config.clone());
-
-    // create cold wallet that is not synced
-    let another_mnemonic =
-        "legal winner thank year wave sausage worth useful legal winner thank yellow";
-    let mut cold_wallet = create_wallet_with_mnemonic(chain_config.clone(), another_mnemonic);
-    let cold_wallet_address = cold_wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+    let mut wallet = create_wallet(chain_config.clone());
 
-    let coin_balance = get_coin_balance(&hot_wallet);
-    assert_eq!(coin_balance, Amount::ZERO);
+    let reward_amount = Amount::from_atoms(rng.gen_range(10000..100000));
+    let (_, block1) = create_block(&chain_config, &mut wallet, vec![], reward_amount, 0);
 
-    // Generate a new block which sends reward to the cold wallet address
-    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
-    let reward_output = make_address_output(cold_wallet_address.clone(), block1_amount);
-    let block1 = Block::new(
+    // Someone else sends a tiny amount to the wallet
+    let (_, address) = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let dust_amount = Amount::from_atoms(rng.gen_range(1..1000));
+    let dust_tx = SignedTransaction::new(
+        Transaction::new(0, vec![], vec![make_address_output(address, dust_amount)]).unwrap(),
         vec![],
-        chain_config.genesis_block_id(),
+    )
+    .unwrap();
+    let dust_outpoint = UtxoOutPoint::new(
+        OutPointSourceId::Transaction(dust_tx.transaction().get_id()),
+        0,
+    );
+    let block2 = Block::new(
+        vec![dust_tx],
+        block1.get_id().into(),
         chain_config.genesis_block().timestamp(),
         ConsensusData::None,
-        BlockReward::new(vec![reward_output.clone()]),
+        BlockReward::new(vec![]),
     )
     .unwrap();
+    scan_wallet(&mut wallet, BlockHeight::new(1), vec![block2]);
+    assert_eq!(
+        get_coin_balance(&wallet),
+        (reward_amount + dust_amount).unwrap()
+    );
 
-    scan_wallet(&mut hot_wallet, BlockHeight::new(0), vec![block1.clone()]);
-
-    // hot wallet has 0 balance
-    let coin_balance = get_coin_balance(&hot_wallet);
-    assert_eq!(coin_balance, Amount::ZERO);
-    let hot_wallet_address = hot_wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
-
-    let to_send = Amount::from_atoms(1);
-    let (send_req, _) = hot_wallet
-        .create_unsigned_transaction_to_addresses(
-            DEFAULT_ACCOUNT_INDEX,
-            [TxOutput::Transfer(OutputValue::Coin(to_send), hot_wallet_address.into_object())],
-            SelectedInputs::Inputs(vec![(
-                UtxoOutPoint::new(OutPointSourceId::BlockReward(block1.get_id().into()), 0),
-                reward_output,
-            )]),
-            None,
-            [(Currency::Coin, cold_wallet_address.clone())].into(),
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-        )
-        .unwrap();
-
-    // Try to sign request with the hot wallet
-    let tx = hot_wallet
-        .sign_raw_transaction(
-            DEFAULT_ACCOUNT_INDEX,
-            TransactionToSign::Partial(send_req.clone()),
-        )
-        .unwrap()
-        .0;
-    // the tx is not fully signed
-    assert!(!tx.all_signatures_available());
+    // Nothing is flagged without a policy
+    assert!(wallet.get_dust_utxos(DEFAULT_ACCOUNT_INDEX).unwrap().is_empty());
 
-    // sign the tx with cold wallet
-    let signed_tx = cold_wallet
-        .sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, TransactionToSign::Partial(send_req))
-        .unwrap()
-        .0
-        .into_signed_tx()
+    wallet
+        .set_dust_protection_policy(DEFAULT_ACCOUNT_INDEX, Some(Amount::from_atoms(1000)), true)
         .unwrap();
+    let dust_utxos = wallet.get_dust_utxos(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(dust_utxos.len(), 1);
+    assert_eq!(dust_utxos[0].0, dust_outpoint);
 
-    let (_, block2) = create_block(
-        &chain_config,
-        &mut hot_wallet,
-        vec![signed_tx],
-        Amount::ZERO,
-        1,
-    );
-
-    let coin_balance = get_coin_balance(&hot_wallet);
-    assert_eq!(coin_balance, to_send,);
+    // The block reward cannot be released as it's not flagged
+    let reward_outpoint =
+        UtxoOutPoint::new(OutPointSourceId::BlockReward(block1.get_id().into()), 0);
+    let err = wallet
+        .release_dust_utxos(DEFAULT_ACCOUNT_INDEX, vec![reward_outpoint.clone()])
+        .unwrap_err();
+    assert_eq!(err, WalletError::UtxoNotFlaggedAsDust(reward_outpoint));
 
-    // update cold wallet just to check the balance and utxos
-    cold_wallet
-        .scan_new_blocks(
+    // The flagged UTXO is not selected automatically, but the balance still includes it
+    let send_amount = (reward_amount + Amount::from_atoms(1)).unwrap();
+    let new_output =
+        || TxOutput::Transfer(OutputValue::Coin(send_amount), Destination::AnyoneCanSpend);
+    let err = wallet
+        .create_transaction_to_addresses(
             DEFAULT_ACCOUNT_INDEX,
-            BlockHeight::new(0),
-            vec![block1, block2],
-            &WalletEventsNoOp,
+            [new_output()],
+            SelectedInputs::Utxos(vec![]),
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
         )
-        .unwrap();
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        WalletError::CoinSelectionError(UtxoSelectorError::NotEnoughFunds(_, _))
+    ));
+    assert_eq!(
+        get_coin_balance(&wallet),
+        (reward_amount + dust_amount).unwrap()
+    );
 
-    let balance = (block1_amount - to_send).unwrap();
-    assert_eq!(get_coin_balance(&cold_wallet), balance);
+    // After releasing it, the UTXO is selected again
+    wallet
+        .release_dust_utxos(DEFAULT_ACCOUNT_INDEX, vec![dust_outpoint.clone()])
+        .unwrap();
+    assert!(wallet.get_dust_utxos(DEFAULT_ACCOUNT_INDEX).unwrap().is_empty());
+    assert_eq!(
+        wallet.get_dust_protection(DEFAULT_ACCOUNT_INDEX).unwrap().released_utxos,
+        BTreeSet::from([dust_outpoint.clone()])
+    );
 
-    let mut utxos = cold_wallet
-        .get_utxos(
+    let tx = wallet
+        .create_transaction_to_addresses(
             DEFAULT_ACCOUNT_INDEX,
-            UtxoType::Transfer.into(),
-            UtxoState::Confirmed.into(),
-            WithLocked::Unlocked,
+            [new_output()],
+            SelectedInputs::Utxos(vec![]),
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
         )
         .unwrap();
-
-    assert_eq!(utxos.len(), 1);
-    let (_, output, _) = utxos.pop().unwrap();
-
-    matches!(output, TxOutput::Transfer(OutputValue::Coin(value), dest)
-            if value == balance && dest == cold_wallet_address.into_object());
+    assert!(tx.transaction().inputs().contains(&TxInput::Utxo(dust_outpoint)));
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn test_not_exhaustion_of_keys(#[case] seed: Seed) {
+fn change_policy(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
     let chain_config = Arc::new(create_regtest());
 
     let mut wallet = create_wallet(chain_config.clone());
 
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, Amount::ZERO);
-    let address = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
-
-    // Generate a new block which sends reward to the cold wallet address
-    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
-    let reward_output = make_address_output(address.clone(), block1_amount);
-    let block1 = Block::new(
-        vec![],
-        chain_config.genesis_block_id(),
-        chain_config.genesis_block().timestamp(),
-        ConsensusData::None,
-        BlockReward::new(vec![reward_output.clone()]),
-    )
-    .unwrap();
-
-    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1.clone()]);
-
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, block1_amount);
+    let reward_amount = Amount::from_atoms(rng.gen_range(10000..100000));
+    let (reward_address, _) = create_block(&chain_config, &mut wallet, vec![], reward_amount, 0);
 
-    // can create 100 txs but will not run out of change addresses
-    for _ in 0..100 {
-        wallet
+    let send_amount = Amount::from_atoms(rng.gen_range(1..reward_amount.into_atoms()));
+    let send_destination = Destination::PublicKeyHash(PublicKeyHash::from_low_u64_be(1));
+    let change_destination = |wallet: &mut DefaultWallet| {
+        let tx = wallet
             .create_transaction_to_addresses(
                 DEFAULT_ACCOUNT_INDEX,
-                [TxOutput::Transfer(
-                    OutputValue::Coin(Amount::from_atoms(1)),
-                    address.as_object().clone(),
-                )],
-                SelectedInputs::Inputs(vec![]),
-                [].into(),
+                [TxOutput::Transfer(OutputValue::Coin(send_amount), send_destination.clone())],
+                SelectedInputs::Utxos(vec![]),
+                BTreeMap::new(),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
                 FeeRate::from_amount_per_kb(Amount::ZERO),
             )
             .unwrap();
-    }
+        let change_destinations = tx
+            .transaction()
+            .outputs()
+            .iter()
+            .filter_map(|output| match output {
+                TxOutput::Transfer(OutputValue::Coin(amount), destination)
+                    if *destination != send_destination =>
+                {
+                    assert_eq!(*amount, (reward_amount - send_amount).unwrap());
+                    Some(destination.clone())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(change_destinations.len(), 1);
+        change_destinations[0].clone()
+    };
+
+    // By default the change goes to a new address of the account
+    assert_eq!(
+        wallet.get_change_policy(DEFAULT_ACCOUNT_INDEX).unwrap(),
+        ChangePolicy::NewAddress
+    );
+    let destination = change_destination(&mut wallet);
+    assert_ne!(destination, *reward_address.as_object());
+    assert!(wallet.find_public_key(DEFAULT_ACCOUNT_INDEX, destination).is_ok());
+
+    // The change goes back to the address of the spent input
+    wallet
+        .set_change_policy(DEFAULT_ACCOUNT_INDEX, ChangePolicy::ReuseInputAddress)
+        .unwrap();
+    assert_eq!(change_destination(&mut wallet), *reward_address.as_object());
+
+    // The change goes to the external address
+    let cold_destination = Destination::PublicKeyHash(PublicKeyHash::from_low_u64_be(2));
+    wallet
+        .set_change_policy(
+            DEFAULT_ACCOUNT_INDEX,
+            ChangePolicy::External(cold_destination.clone()),
+        )
+        .unwrap();
+    assert_eq!(change_destination(&mut wallet), cold_destination);
+    assert_eq!(
+        wallet.get_change_policy(DEFAULT_ACCOUNT_INDEX).unwrap(),
+        ChangePolicy::External(cold_destination)
+    );
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn test_add_standalone_private_key(#[case] seed: Seed) {
+fn frozen_utxos(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
     let chain_config = Arc::new(create_regtest());
 
     let mut wallet = create_wallet(chain_config.clone());
 
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, Amount::ZERO);
-    // generate a random private key unrelated to the wallet and add it
-    let (private_key, pub_key) =
-        crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr);
+    let reward_amount1 = Amount::from_atoms(rng.gen_range(10000..100000));
+    let (_, block1) = create_block(&chain_config, &mut wallet, vec![], reward_amount1, 0);
+    let reward_amount2 = Amount::from_atoms(rng.gen_range(10000..100000));
+    let (_, block2) = create_block(&chain_config, &mut wallet, vec![], reward_amount2, 1);
 
-    wallet
-        .add_standalone_private_key(DEFAULT_ACCOUNT_INDEX, private_key, None)
+    let outpoint1 = UtxoOutPoint::new(OutPointSourceId::BlockReward(block1.get_id().into()), 0);
+    let outpoint2 = UtxoOutPoint::new(OutPointSourceId::BlockReward(block2.get_id().into()), 0);
+
+    // Unknown UTXOs cannot be frozen
+    let unknown_outpoint = UtxoOutPoint::new(
+        OutPointSourceId::Transaction(Id::new(H256::random_using(&mut rng))),
+        0,
+    );
+    let err = wallet
+        .freeze_utxos(DEFAULT_ACCOUNT_INDEX, vec![unknown_outpoint.clone()])
+        .unwrap_err();
+    assert_eq!(err, WalletError::CannotFindUtxo(unknown_outpoint));
+
+    wallet.freeze_utxos(DEFAULT_ACCOUNT_INDEX, vec![outpoint1.clone()]).unwrap();
+    let frozen_utxos = wallet.get_frozen_utxos(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(frozen_utxos.len(), 1);
+    assert_eq!(frozen_utxos[0].0, outpoint1);
+
+    // The frozen UTXO is not selected automatically, but the balance still includes it
+    let send_amount = (reward_amount2 + Amount::from_atoms(1)).unwrap();
+    let new_output =
+        || TxOutput::Transfer(OutputValue::Coin(send_amount), Destination::AnyoneCanSpend);
+    let err = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            [new_output()],
+            SelectedInputs::Utxos(vec![]),
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        WalletError::CoinSelectionError(UtxoSelectorError::NotEnoughFunds(_, _))
+    ));
+    assert_eq!(
+        get_coin_balance(&wallet),
+        (reward_amount1 + reward_amount2).unwrap()
+    );
+
+    // It cannot be selected explicitly either
+    let err = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            [new_output()],
+            SelectedInputs::Utxos(vec![outpoint1.clone(), outpoint2.clone()]),
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap_err();
+    assert_eq!(err, WalletError::FrozenUtxo(outpoint1.clone()));
+
+    let frozen_output = wallet
+        .get_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoType::Transfer.into(),
+            UtxoState::Confirmed.into(),
+            WithLocked::Unlocked,
+        )
+        .unwrap()
+        .into_iter()
+        .find(|(outpoint, _, _)| *outpoint == outpoint1)
+        .unwrap()
+        .1;
+    let err = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            [new_output()],
+            SelectedInputs::Inputs(vec![(outpoint1.clone(), frozen_output)]),
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap_err();
+    assert_eq!(err, WalletError::FrozenUtxo(outpoint1.clone()));
+
+    // A sweep leaves the frozen UTXO out
+    let all_utxos = wallet
+        .get_utxos(
+            DEFAULT_ACCOUNT_INDEX,
+            UtxoType::Transfer | UtxoType::LockThenTransfer,
+            UtxoState::Confirmed.into(),
+            WithLocked::Unlocked,
+        )
         .unwrap();
+    let tx = wallet
+        .create_sweep_transaction(
+            DEFAULT_ACCOUNT_INDEX,
+            Destination::AnyoneCanSpend,
+            all_utxos,
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+    assert!(!tx.transaction().inputs().contains(&TxInput::Utxo(outpoint1.clone())));
+    assert!(tx.transaction().inputs().contains(&TxInput::Utxo(outpoint2.clone())));
 
-    // get the destination address from the new private key and send some coins to it
-    let address =
-        Address::new(&chain_config, Destination::PublicKeyHash((&pub_key).into())).unwrap();
+    // Only frozen UTXOs can be unfrozen
+    let err = wallet
+        .unfreeze_utxos(DEFAULT_ACCOUNT_INDEX, vec![outpoint2.clone()])
+        .unwrap_err();
+    assert_eq!(err, WalletError::UtxoNotFrozen(outpoint2.clone()));
 
-    // Generate a new block which sends reward to the new address
-    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
-    let output = make_address_output(address.clone(), block1_amount);
+    wallet.unfreeze_utxos(DEFAULT_ACCOUNT_INDEX, vec![outpoint1.clone()]).unwrap();
+    assert!(wallet.get_frozen_utxos(DEFAULT_ACCOUNT_INDEX).unwrap().is_empty());
 
-    let tx =
-        SignedTransaction::new(Transaction::new(0, vec![], vec![output]).unwrap(), vec![]).unwrap();
+    let tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            [new_output()],
+            SelectedInputs::Utxos(vec![outpoint1.clone(), outpoint2.clone()]),
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+    assert!(tx.transaction().inputs().contains(&TxInput::Utxo(outpoint1.clone())));
+    assert!(tx.transaction().inputs().contains(&TxInput::Utxo(outpoint2)));
+
+    // Once the UTXO is spent, e.g. by another instance of the wallet, it's no longer kept frozen
+    wallet.freeze_utxos(DEFAULT_ACCOUNT_INDEX, vec![outpoint1.clone()]).unwrap();
+    let _ = create_block(&chain_config, &mut wallet, vec![tx], Amount::ZERO, 2);
+    let account_id = wallet.accounts.get(&DEFAULT_ACCOUNT_INDEX).unwrap().get_account_id();
+    assert!(wallet
+        .db
+        .transaction_ro()
+        .unwrap()
+        .get_account_frozen_utxos(&account_id)
+        .unwrap()
+        .is_empty());
+}
 
-    let block1 = Block::new(
-        vec![tx.clone()],
-        chain_config.genesis_block_id(),
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn token_trust_list(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+
+    let mut wallet = create_wallet(chain_config.clone());
+    let reward_amount = Amount::from_atoms(rng.gen_range(10000..100000));
+    let (_, block1) = create_block(&chain_config, &mut wallet, vec![], reward_amount, 0);
+
+    // Someone else sends some tokens to the wallet, so they are not known to it
+    let token_id = TokenId::new(H256::random_using(&mut rng));
+    let (_, address) = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let token_amount = Amount::from_atoms(rng.gen_range(1..1000));
+    let token_tx = SignedTransaction::new(
+        Transaction::new(
+            0,
+            vec![],
+            vec![TxOutput::Transfer(
+                OutputValue::TokenV1(token_id, token_amount),
+                address.into_object(),
+            )],
+        )
+        .unwrap(),
+        vec![],
+    )
+    .unwrap();
+    let block2 = Block::new(
+        vec![token_tx],
+        block1.get_id().into(),
         chain_config.genesis_block().timestamp(),
         ConsensusData::None,
         BlockReward::new(vec![]),
     )
     .unwrap();
+    scan_wallet(&mut wallet, BlockHeight::new(1), vec![block2]);
+    assert!(!wallet.get_known_token_ids(DEFAULT_ACCOUNT_INDEX).unwrap().contains(&token_id));
 
-    scan_wallet(&mut wallet, BlockHeight::new(0), vec![block1.clone()]);
-
-    // Check amount is still zero
-    let coin_balance = get_coin_balance(&wallet);
-    assert_eq!(coin_balance, Amount::ZERO);
+    assert!(wallet.get_token_trust_list().unwrap().is_empty());
+    wallet.set_token_trust(token_id, Some(TokenTrust::Hidden)).unwrap();
+    wallet.set_token_trust(token_id, Some(TokenTrust::Trusted)).unwrap();
+    let other_token_id = TokenId::new(H256::random_using(&mut rng));
+    wallet.set_token_trust(other_token_id, Some(TokenTrust::Hidden)).unwrap();
 
-    // but the transaction has been added to the wallet
-    let tx_data = wallet
-        .get_transaction(DEFAULT_ACCOUNT_INDEX, tx.transaction().get_id())
-        .unwrap();
+    // The list is persisted in the DB
+    let mut wallet = Wallet::load_wallet(
+        Arc::clone(&chain_config),
+        wallet.db,
+        None,
+        |_| Ok(()),
+        WalletType::Hot,
+        false,
+    )
+    .unwrap();
+    assert_eq!(
+        wallet.get_token_trust_list().unwrap(),
+        BTreeMap::from([(token_id, TokenTrust::Trusted), (other_token_id, TokenTrust::Hidden)])
+    );
 
-    assert_eq!(tx_data.get_transaction(), tx.transaction());
+    wallet.set_token_trust(other_token_id, None).unwrap();
+    assert_eq!(
+        wallet.get_token_trust_list().unwrap(),
+        BTreeMap::from([(token_id, TokenTrust::Trusted)])
+    );
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn test_add_standalone_multisig(#[case] seed: Seed) {
+fn address_book(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
     let chain_config = Arc::new(create_regtest());
 
-    let mut wallet1 = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC);
-    let mut wallet2 = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC2);
-
-    let coin_balance = get_coin_balance(&wallet1);
-    assert_eq!(coin_balance, Amount::ZERO);
-    let coin_balance = get_coin_balance(&wallet2);
-    assert_eq!(coin_balance, Amount::ZERO);
-
-    let (_, address1) = wallet1.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
-    let pub_key1 = wallet1.find_public_key(DEFAULT_ACCOUNT_INDEX, address1.into_object()).unwrap();
-
-    let (_, address2) = wallet2.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
-    let pub_key2 = wallet2.find_public_key(DEFAULT_ACCOUNT_INDEX, address2.into_object()).unwrap();
-    let (_, address3) = wallet2.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
-    let pub_key3 = wallet2.find_public_key(DEFAULT_ACCOUNT_INDEX, address3.into_object()).unwrap();
+    let mut wallet = create_wallet(chain_config.clone());
+    assert!(wallet.get_payees().unwrap().is_empty());
 
-    let min_required_signatures = 2;
-    let challenge = ClassicMultisigChallenge::new(
-        &chain_config,
-        NonZeroU8::new(min_required_signatures).unwrap(),
-        vec![pub_key3, pub_key2, pub_key1],
-    )
-    .unwrap();
-    let multisig_hash = wallet1
-        .add_standalone_multisig(DEFAULT_ACCOUNT_INDEX, challenge.clone(), None)
+    let (_, address) = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let alice = Payee {
+        address: address.into_object(),
+        default_token: None,
+        notes: Some("notes".to_owned()),
+    };
+    let bob = Payee {
+        address: Destination::AnyoneCanSpend,
+        default_token: Some(TokenId::new(H256::random_using(&mut rng))),
+        notes: None,
+    };
+    wallet
+        .set_payees(BTreeMap::from([
+            ("alice".to_owned(), alice.clone()),
+            ("bob".to_owned(), alice.clone()),
+        ]))
         .unwrap();
+    // An existing payee is replaced
+    wallet.set_payees(BTreeMap::from([("bob".to_owned(), bob.clone())])).unwrap();
 
-    let multisig_address =
-        Address::new(&chain_config, Destination::ClassicMultisig(multisig_hash)).unwrap();
-
-    // Generate a new block which sends reward to the new multisig address
-    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
-    let output = make_address_output(multisig_address.clone(), block1_amount);
-
-    let tx =
-        SignedTransaction::new(Transaction::new(0, vec![], vec![output]).unwrap(), vec![]).unwrap();
+    assert!(matches!(
+        wallet.set_payees(BTreeMap::from([(" ".to_owned(), bob.clone())])),
+        Err(WalletError::EmptyPayeeName)
+    ));
 
-    let block1 = Block::new(
-        vec![tx.clone()],
-        chain_config.genesis_block_id(),
-        chain_config.genesis_block().timestamp(),
-        ConsensusData::None,
-        BlockReward::new(vec![]),
+    // The address book is persisted in the DB
+    let mut wallet = Wallet::load_wallet(
+        Arc::clone(&chain_config),
+        wallet.db,
+        None,
+        |_| Ok(()),
+        WalletType::Hot,
+        false,
     )
     .unwrap();
+    assert_eq!(
+        wallet.get_payees().unwrap(),
+        BTreeMap::from([("alice".to_owned(), alice), ("bob".to_owned(), bob.clone())])
+    );
+    assert_eq!(wallet.get_payee("bob").unwrap(), bob);
 
-    scan_wallet(&mut wallet1, BlockHeight::new(0), vec![block1.clone()]);
+    wallet.remove_payee("alice").unwrap();
+    assert!(matches!(
+        wallet.get_payee("alice"),
+        Err(WalletError::PayeeNotFound(name)) if name == "alice"
+    ));
+    assert!(matches!(
+        wallet.remove_payee("alice"),
+        Err(WalletError::PayeeNotFound(_))
+    ));
+    assert_eq!(
+        wallet.get_payees().unwrap(),
+        BTreeMap::from([("bob".to_owned(), bob)])
+    );
+}
 
-    // Check amount is still zero
-    let coin_balance = get_coin_balance(&wallet1);
-    assert_eq!(coin_balance, Amount::ZERO);
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn broadcast_queue_migration(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
 
-    // but the transaction has been added to the wallet
-    let tx_data = wallet1
-        .get_transaction(DEFAULT_ACCOUNT_INDEX, tx.transaction().get_id())
+    let mut wallet = create_wallet(chain_config.clone());
+
+    let (_, address) = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let tx = SignedTransaction::new(
+        Transaction::new(
+            0,
+            vec![],
+            vec![make_address_output(address, Amount::from_atoms(rng.gen_range(1..1000)))],
+        )
+        .unwrap(),
+        vec![],
+    )
+    .unwrap();
+    let tx_id = tx.transaction().get_id();
+    wallet
+        .add_account_unconfirmed_tx(DEFAULT_ACCOUNT_INDEX, tx, &WalletEventsNoOp)
         .unwrap();
+    assert!(matches!(
+        wallet.find_transaction_state(tx_id),
+        Some(TxState::Inactive(_))
+    ));
+    assert!(wallet.get_broadcast_queue().unwrap().is_empty());
 
-    assert_eq!(tx_data.get_transaction(), tx.transaction());
+    // set version back to v7
+    let db = wallet.db;
+    let mut db_tx = db.transaction_rw(None).unwrap();
+    db_tx.set_storage_version(WALLET_VERSION_V7).unwrap();
+    db_tx.commit().unwrap();
 
-    let spend_multisig_tx = Transaction::new(
-        0,
-        vec![TxInput::from_utxo(OutPointSourceId::Transaction(tx.transaction().get_id()), 0)],
-        vec![TxOutput::Transfer(
-            OutputValue::Coin(Amount::from_atoms(1)),
-            Destination::AnyoneCanSpend,
-        )],
+    let wallet = Wallet::load_wallet(
+        Arc::clone(&chain_config),
+        db,
+        None,
+        |_| Ok(()),
+        WalletType::Hot,
+        false,
     )
     .unwrap();
 
-    // sign it with wallet1
-    let (ptx, _, statuses) = wallet1
-        .sign_raw_transaction(
-            DEFAULT_ACCOUNT_INDEX,
-            TransactionToSign::Tx(spend_multisig_tx),
-        )
-        .unwrap();
+    // The unconfirmed transaction has been queued for broadcasting
+    let queue = wallet.get_broadcast_queue().unwrap();
+    assert_eq!(queue.keys().copied().collect::<Vec<_>>(), vec![tx_id]);
+    let entry = wallet.get_broadcast_queue_entry(tx_id).unwrap().unwrap();
+    assert_eq!(entry.status, BroadcastStatus::Pending);
+    assert_eq!(entry.policy, BroadcastPolicy::default());
+}
 
-    // check it is still not fully signed
-    assert!(ptx.all_signatures_available());
-    assert!(!statuses.iter().all(|s| *s == SignatureStatus::FullySigned));
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn abandon_unbroadcast_transaction(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_mainnet());
 
-    // try to sign it with wallet1 again
-    let (ptx, _, statuses) = wallet1
-        .sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, TransactionToSign::Partial(ptx))
-        .unwrap();
+    let mut wallet = create_wallet(chain_config.clone());
 
-    // check it is still not fully signed
-    assert!(ptx.all_signatures_available());
-    assert!(!statuses.iter().all(|s| *s == SignatureStatus::FullySigned));
+    let block1_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
 
-    // try to sign it with wallet2 but wallet2 does not have the multisig added as standalone
-    let ptx = wallet2
-        .sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, TransactionToSign::Partial(ptx))
-        .unwrap()
-        .0;
+    let new_output = TxOutput::Transfer(
+        OutputValue::Coin(block1_amount),
+        Destination::PublicKey(
+            crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr)
+                .1,
+        ),
+    );
+    let transaction = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            [new_output],
+            SelectedInputs::Utxos(vec![]),
+            BTreeMap::new(),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+        )
+        .unwrap();
+    let transaction_id = transaction.transaction().get_id();
 
-    // add it to wallet2 as well
-    wallet2.add_standalone_multisig(DEFAULT_ACCOUNT_INDEX, challenge, None).unwrap();
+    // An inactive transaction is abandoned and its inputs are released
+    wallet.add_unconfirmed_tx(transaction.clone(), &WalletEventsNoOp).unwrap();
+    assert_eq!(get_coin_balance_with_inactive(&wallet), Amount::ZERO);
 
-    // now we can sign it
-    let (ptx, _, statuses) = wallet2
-        .sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, TransactionToSign::Partial(ptx))
-        .unwrap();
+    wallet.abandon_unbroadcast_transaction(transaction_id).unwrap();
+    assert!(matches!(
+        wallet.find_transaction_state(transaction_id),
+        Some(TxState::Abandoned)
+    ));
+    assert_eq!(get_coin_balance_with_inactive(&wallet), block1_amount);
 
-    // now it is fully signed
-    assert!(ptx.all_signatures_available());
-    assert!(statuses.iter().all(|s| *s == SignatureStatus::FullySigned));
+    // A transaction in the mempool is kept as it can still be included in a block
+    wallet.scan_mempool(&[transaction], &WalletEventsNoOp).unwrap();
+    wallet.abandon_unbroadcast_transaction(transaction_id).unwrap();
+    assert!(matches!(
+        wallet.find_transaction_state(transaction_id),
+        Some(TxState::InMempool(_))
+    ));
+    assert_eq!(get_coin_balance_with_inactive(&wallet), Amount::ZERO);
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn create_htlc_and_spend(#[case] seed: Seed) {
-    use common::chain::htlc::HtlcSecret;
-
+fn internal_transfer(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
-    let chain_config = Arc::new(create_regtest());
-
-    let mut wallet1 = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC);
-    let mut wallet2 = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC2);
+    let chain_config = Arc::new(create_mainnet());
 
-    let coin_balance = get_coin_balance(&wallet1);
-    assert_eq!(coin_balance, Amount::ZERO);
+    let mut wallet = create_wallet(chain_config.clone());
 
-    // Generate a new block which sends reward to the wallet
-    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
-    let _ = create_block(&chain_config, &mut wallet1, vec![], block1_amount, 0);
+    let block1_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
 
-    let coin_balance = get_coin_balance(&wallet1);
-    assert_eq!(coin_balance, block1_amount);
+    let (acc1_index, _) = wallet.create_next_account(None).unwrap();
+    let feerate = FeeRate::from_amount_per_kb(Amount::ZERO);
+    let amount = Amount::from_atoms(rng.gen_range(1..block1_amount.into_atoms()));
 
-    let (_, address1) = wallet1.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
-    let pub_key1 = wallet1.find_public_key(DEFAULT_ACCOUNT_INDEX, address1.into_object()).unwrap();
+    assert_eq!(
+        wallet
+            .create_internal_transfer_tx(
+                acc1_index,
+                acc1_index,
+                &Currency::Coin,
+                amount,
+                feerate,
+                feerate
+            )
+            .unwrap_err(),
+        WalletError::TransferToSameAccount
+    );
 
-    let (_, address2) = wallet2.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
-    let pub_key2 = wallet2
-        .find_public_key(DEFAULT_ACCOUNT_INDEX, address2.clone().into_object())
+    let tx = wallet
+        .create_internal_transfer_tx(
+            DEFAULT_ACCOUNT_INDEX,
+            acc1_index,
+            &Currency::Coin,
+            amount,
+            feerate,
+            feerate,
+        )
         .unwrap();
+    let tx_id = tx.transaction().get_id();
+    let _ = create_block(&chain_config, &mut wallet, vec![tx], Amount::ZERO, 1);
 
-    let min_required_signatures = 2;
-    let challenge = ClassicMultisigChallenge::new(
-        &chain_config,
-        NonZeroU8::new(min_required_signatures).unwrap(),
-        vec![pub_key1, pub_key2],
-    )
-    .unwrap();
-    let multisig_hash = wallet1
-        .add_standalone_multisig(DEFAULT_ACCOUNT_INDEX, challenge.clone(), None)
-        .unwrap();
-    wallet2
-        .add_standalone_multisig(DEFAULT_ACCOUNT_INDEX, challenge.clone(), None)
-        .unwrap();
+    assert_eq!(get_coin_balance_for_acc(&wallet, acc1_index), amount);
 
-    let secret = HtlcSecret::new_from_rng(&mut rng);
-    let spend_key = wallet2.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
-    let htlc = HashedTimelockContract {
-        secret_hash: secret.hash(),
-        spend_key: spend_key.clone().into_object(),
-        refund_timelock: OutputTimeLock::ForBlockCount(1),
-        refund_key: Destination::ClassicMultisig(multisig_hash),
+    // Both sides of the transfer are reported as such instead of as sent and received
+    let tx_type = |account_index| {
+        wallet
+            .get_transaction_list(account_index, 0, 10)
+            .unwrap()
+            .txs
+            .into_iter()
+            .find(|tx| tx.txid == tx_id)
+            .unwrap()
+            .tx_type
     };
-    let output_value = OutputValue::Coin(coin_balance);
+    assert!(matches!(
+        tx_type(DEFAULT_ACCOUNT_INDEX),
+        TxType::TransferredOut { to_account, amount: transferred }
+            if to_account == acc1_index && transferred == amount
+    ));
+    assert!(matches!(
+        tx_type(acc1_index),
+        TxType::TransferredIn { from_account, amount: transferred }
+            if from_account == DEFAULT_ACCOUNT_INDEX && transferred == amount
+    ));
+}
 
-    let create_htlc_tx = wallet1
-        .create_htlc_tx(
-            DEFAULT_ACCOUNT_INDEX,
-            output_value.clone(),
-            htlc.clone(),
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-            FeeRate::from_amount_per_kb(Amount::ZERO),
-        )
-        .unwrap();
-    let create_htlc_tx_id = create_htlc_tx.transaction().get_id();
-    let (_, block2) = create_block(
-        &chain_config,
-        &mut wallet1,
-        vec![create_htlc_tx.clone()],
-        Amount::ZERO,
-        1,
-    );
-    scan_wallet(&mut wallet2, BlockHeight::new(0), vec![block2]);
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn export_and_import_keys(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
 
-    // Htlc is not accounted in balance
-    assert_eq!(get_coin_balance(&wallet1), Amount::ZERO);
-    assert_eq!(get_coin_balance(&wallet2), Amount::ZERO);
+    let mut wallet1 = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC);
+    let mut wallet2 = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC2);
 
-    // Htlc is available as utxo to spend
-    let wallet1_utxos = wallet1
-        .get_utxos(
-            DEFAULT_ACCOUNT_INDEX,
-            UtxoType::Htlc.into(),
-            UtxoState::Confirmed.into(),
-            WithLocked::Any,
-        )
-        .unwrap();
-    assert!(wallet1_utxos.is_empty());
-    let mut wallet2_utxos = wallet2
-        .get_utxos(
-            DEFAULT_ACCOUNT_INDEX,
-            UtxoType::Htlc.into(),
-            UtxoState::Confirmed.into(),
-            WithLocked::Any,
-        )
-        .unwrap();
-    assert_eq!(wallet2_utxos.len(), 1);
-    let (_, output, _) = wallet2_utxos.pop().unwrap();
-    match output {
-        TxOutput::Htlc(actual_output_value, actual_htlc) => {
-            assert_eq!(actual_output_value, output_value);
-            assert_eq!(htlc, *actual_htlc);
-        }
-        _ => panic!("wrong TxOutput type"),
-    };
+    // The exported private key controls the address
+    let (_, address) = wallet1.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    let private_key =
+        wallet1.export_private_key(DEFAULT_ACCOUNT_INDEX, address.as_object()).unwrap();
+    let public_key = crypto::key::PublicKey::from_private_key(&private_key);
+    assert_eq!(
+        *address.as_object(),
+        Destination::PublicKeyHash(PublicKeyHash::from(&public_key))
+    );
 
-    // spend with a secret
-    let spend_tx = Transaction::new(
-        0,
-        vec![TxInput::from_utxo(create_htlc_tx_id.into(), 0)],
-        vec![TxOutput::Transfer(output_value, address2.into_object())],
-    )
-    .unwrap();
-    let spend_utxos = vec![create_htlc_tx.transaction().outputs().first().cloned()];
-    let spend_ptx = PartiallySignedTransaction::new(
-        spend_tx,
-        vec![None],
-        spend_utxos,
-        vec![Some(spend_key.into_object())],
-        Some(vec![Some(secret)]),
-    )
-    .unwrap();
+    // Only the keys of the wallet can be exported
+    let (_, other_address) = wallet2.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
+    assert_eq!(
+        wallet1.export_private_key(DEFAULT_ACCOUNT_INDEX, other_address.as_object()),
+        Err(WalletError::DestinationNotFromThisWallet)
+    );
+
+    // The private keys must be unlocked
+    let password = gen_random_password(&mut rng);
+    wallet1.encrypt_wallet(&Some(password.clone())).unwrap();
+    wallet1.lock_wallet().unwrap();
+    assert_eq!(
+        wallet1.export_private_key(DEFAULT_ACCOUNT_INDEX, address.as_object()),
+        Err(WalletError::DatabaseError(
+            wallet_storage::Error::WalletLocked
+        ))
+    );
+    wallet1.unlock_wallet(&password).unwrap();
+    assert_eq!(
+        wallet1.export_private_key(DEFAULT_ACCOUNT_INDEX, address.as_object()),
+        Ok(private_key)
+    );
 
-    let (spend_ptx, _, new_statuses) = wallet2
-        .sign_raw_transaction(DEFAULT_ACCOUNT_INDEX, TransactionToSign::Partial(spend_ptx))
+    // Importing the extended public key watches the receiving and change addresses
+    // of the other wallet
+    let xpub = wallet1.account_extended_public_key(DEFAULT_ACCOUNT_INDEX).unwrap().clone();
+    let address_count = U31::from_u32(rng.gen_range(1..10)).unwrap();
+    let hashes = wallet2
+        .import_extended_public_key(DEFAULT_ACCOUNT_INDEX, &xpub, address_count, None)
         .unwrap();
-    assert_eq!(vec![SignatureStatus::FullySigned], new_statuses);
-
-    let spend_tx = spend_ptx.into_signed_tx().unwrap();
+    assert_eq!(hashes.len(), 2 * address_count.into_u32() as usize);
+    assert_eq!(Destination::PublicKeyHash(hashes[0]), *address.as_object());
+    let change_address = get_address(
+        &chain_config,
+        MNEMONIC,
+        DEFAULT_ACCOUNT_INDEX,
+        KeyPurpose::Change,
+        U31::ZERO,
+    );
+    assert_eq!(
+        Destination::PublicKeyHash(hashes[address_count.into_u32() as usize]),
+        *change_address.as_object()
+    );
 
-    let (_, block2) = create_block(&chain_config, &mut wallet2, vec![spend_tx], Amount::ZERO, 1);
-    scan_wallet(&mut wallet2, BlockHeight::new(0), vec![block2]);
+    let too_many = MAX_EXTENDED_PUBLIC_KEY_IMPORT_ADDRESS_COUNT + 1;
+    assert_eq!(
+        wallet2.import_extended_public_key(
+            DEFAULT_ACCOUNT_INDEX,
+            &xpub,
+            U31::from_u32(too_many).unwrap(),
+            None
+        ),
+        Err(WalletError::ExtendedPublicKeyImportAddressCountTooLarge(
+            too_many,
+            MAX_EXTENDED_PUBLIC_KEY_IMPORT_ADDRESS_COUNT
+        ))
+    );
 
-    // Coins from htlc successfully transferred
-    assert_eq!(get_coin_balance(&wallet1), Amount::ZERO);
-    assert_eq!(get_coin_balance(&wallet2), coin_balance);
+    assert_eq!(
+        wallet1.import_extended_public_key(DEFAULT_ACCOUNT_INDEX, &xpub, address_count, None),
+        Err(WalletError::CannotImportOwnExtendedPublicKey)
+    );
 }
 
 #[rstest]
 #[trace]
 #[case(Seed::from_entropy())]
-fn create_htlc_and_refund(#[case] seed: Seed) {
-    use common::chain::htlc::HtlcSecret;
-
+fn address_labels(#[case] seed: Seed) {
     let mut rng = make_seedable_rng(seed);
-    let chain_config = Arc::new(create_regtest());
+    let chain_config = Arc::new(create_mainnet());
 
-    let mut wallet1 = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC);
-    let mut wallet2 = create_wallet_with_mnemonic(chain_config.clone(), MNEMONIC2);
+    let mut wallet = create_wallet(chain_config.clone());
 
-    let coin_balance = get_coin_balance(&wallet1);
-    assert_eq!(coin_balance, Amount::ZERO);
+    let block1_amount = Amount::from_atoms(rng.gen_range(100000..1000000));
+    let (reward_address, _) = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+    let (_, address) = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
 
-    // Generate a new block which sends reward to the wallet
-    let block1_amount = Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000));
-    let _ = create_block(&chain_config, &mut wallet1, vec![], block1_amount, 0);
+    let foreign_address = Destination::PublicKeyHash(PublicKeyHash::from_low_u64_be(1));
+    assert!(matches!(
+        wallet.set_address_label(
+            DEFAULT_ACCOUNT_INDEX,
+            foreign_address,
+            Some("foreign".to_owned())
+        ),
+        Err(WalletError::AddressNotIssued(_))
+    ));
+    assert_eq!(
+        wallet.set_address_label(
+            DEFAULT_ACCOUNT_INDEX,
+            address.as_object().clone(),
+            Some(String::new())
+        ),
+        Err(WalletError::EmptyAddressLabel)
+    );
 
-    let coin_balance = get_coin_balance(&wallet1);
-    assert_eq!(coin_balance, block1_amount);
-    assert_eq!(get_coin_balance(&wallet2), Amount::ZERO);
+    for (address, label) in [(&reward_address, "mining"), (&address, "savings")] {
+        wallet
+            .set_address_label(
+                DEFAULT_ACCOUNT_INDEX,
+                address.as_object().clone(),
+                Some(label.to_owned()),
+            )
+            .unwrap();
+    }
 
-    let (_, address1) = wallet1.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
-    let pub_key1 = wallet1
-        .find_public_key(DEFAULT_ACCOUNT_INDEX, address1.clone().into_object())
+    // The labels of both the spent and the receiving addresses are shown in the transaction list
+    let feerate = FeeRate::from_amount_per_kb(Amount::ZERO);
+    let amount = Amount::from_atoms(rng.gen_range(1..block1_amount.into_atoms()));
+    let tx = wallet
+        .create_transaction_to_addresses(
+            DEFAULT_ACCOUNT_INDEX,
+            [make_address_output(address.clone(), amount)],
+            SelectedInputs::Utxos(vec![]),
+            BTreeMap::new(),
+            feerate,
+            feerate,
+        )
         .unwrap();
+    let tx_id = tx.transaction().get_id();
+    let _ = create_block(&chain_config, &mut wallet, vec![tx], Amount::ZERO, 1);
 
-    let (_, address2) = wallet2.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap();
-    let pub_key2 = wallet2.find_public_key(DEFAULT_ACCOUNT_INDEX, address2.into_object()).unwrap();
+    let tx_labels = |wallet: &DefaultWallet| {
+        let labels = wallet
+            .get_transaction_list(DEFAULT_ACCOUNT_INDEX, 0, 10)
+            .unwrap()
+            .txs
+            .into_iter()
+            .find(|tx| tx.txid == tx_id)
+            .unwrap()
+            .labels;
+        // The mainchain transaction list has the same labels
+        let mainchain_labels = wallet
+            .mainchain_transactions(DEFAULT_ACCOUNT_INDEX, None, 10)
+            .unwrap()
+            .into_iter()
+            .find(|tx| tx.id == tx_id)
+            .unwrap()
+            .labels;
+        assert_eq!(labels, mainchain_labels);
+        labels
+    };
+    assert_eq!(tx_labels(&wallet), ["mining", "savings"]);
 
-    let min_required_signatures = 2;
-    let challenge = ClassicMultisigChallenge::new(
-        &chain_config,
-        NonZeroU8::new(min_required_signatures).unwrap(),
-        vec![pub_key1, pub_key2],
+    wallet
+        .set_address_label(DEFAULT_ACCOUNT_INDEX, reward_address.into_object(), None)
+        .unwrap();
+
+    // The labels are persisted in the DB
+    let wallet = Wallet::load_wallet(
+        Arc::clone(&chain_config),
+        wallet.db,
+        None,
+        |_| Ok(()),
+        WalletType::Hot,
+        false,
     )
     .unwrap();
-    let multisig_hash = wallet1
-        .add_standalone_multisig(DEFAULT_ACCOUNT_INDEX, challenge.clone(), None)
-        .unwrap();
-    wallet2
-        .add_standalone_multisig(DEFAULT_ACCOUNT_INDEX, challenge.clone(), None)
-        .unwrap();
+    assert_eq!(
+        wallet.get_address_labels(DEFAULT_ACCOUNT_INDEX).unwrap(),
+        &BTreeMap::from([(address.into_object(), "savings".to_owned())])
+    );
+    assert_eq!(tx_labels(&wallet), ["savings"]);
+}
 
-    let secret = HtlcSecret::new_from_rng(&mut rng);
-    let spend_key = wallet2.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
-    let refund_key = Destination::ClassicMultisig(multisig_hash);
-    let htlc = HashedTimelockContract {
-        secret_hash: secret.hash(),
-        spend_key: spend_key.into_object(),
-        refund_timelock: OutputTimeLock::ForBlockCount(1),
-        refund_key: refund_key.clone(),
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn token_sweep(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let chain_config = Arc::new(create_regtest());
+
+    let mut wallet = create_wallet(chain_config.clone());
+
+    // Generate a new block which sends reward to the wallet
+    let block1_amount = (Amount::from_atoms(rng.gen_range(NETWORK_FEE + 100..NETWORK_FEE + 10000))
+        + chain_config.fungible_token_issuance_fee())
+    .unwrap();
+    let _ = create_block(&chain_config, &mut wallet, vec![], block1_amount, 0);
+
+    let fixed_max_amount = Amount::from_atoms(rng.gen_range(1..100000));
+    let address2 = wallet.get_new_address(DEFAULT_ACCOUNT_INDEX).unwrap().1;
+    let token_issuance = TokenIssuanceV1 {
+        token_ticker: "XXXX".as_bytes().to_vec(),
+        number_of_decimals: rng.gen_range(1..18),
+        metadata_uri: "http://uri".as_bytes().to_vec(),
+        total_supply: common::chain::tokens::TokenTotalSupply::Fixed(fixed_max_amount),
+        authority: address2.as_object().clone(),
+        is_freezable: common::chain::tokens::IsTokenFreezable::No,
     };
-    let output_value = OutputValue::Coin(coin_balance);
 
-    let create_htlc_tx = wallet1
-        .create_htlc_tx(
+    let (issued_token_id, token_issuance_transaction) = wallet
+        .issue_new_token(
             DEFAULT_ACCOUNT_INDEX,
-            output_value.clone(),
-            htlc.clone(),
+            TokenIssuance::V1(token_issuance.clone()),
             FeeRate::from_amount_per_kb(Amount::ZERO),
             FeeRate::from_amount_per_kb(Amount::ZERO),
         )
         .unwrap();
-    let create_htlc_tx_id = create_htlc_tx.transaction().get_id();
-
-    let refund_tx = Transaction::new(
-        0,
-        vec![TxInput::from_utxo(create_htlc_tx_id.into(), 0)],
-        vec![TxOutput::Transfer(output_value, address1.into_object())],
-    )
-    .unwrap();
-    let refund_utxos = vec![create_htlc_tx.transaction().outputs().first().cloned()];
-    let refund_ptx = PartiallySignedTransaction::new(
-        refund_tx,
-        vec![None],
-        refund_utxos,
-        vec![Some(refund_key)],
-        None,
-    )
-    .unwrap();
 
-    let (_, block2) = create_block(
+    let block2_amount = chain_config.token_supply_change_fee(BlockHeight::zero());
+    let _ = create_block(
         &chain_config,
-        &mut wallet1,
-        vec![create_htlc_tx],
-        Amount::ZERO,
+        &mut wallet,
+        vec![token_issuance_transaction],
+        block2_amount,
         1,
     );
-    scan_wallet(&mut wallet2, BlockHeight::new(0), vec![block2]);
 
-    // Htlc is not accounted in balance
-    assert_eq!(get_coin_balance(&wallet1), Amount::ZERO);
-    assert_eq!(get_coin_balance(&wallet2), Amount::ZERO);
+    let freezable = token_issuance.is_freezable.as_bool();
+    let token_info = RPCFungibleTokenInfo::new(
+        issued_token_id,
+        token_issuance.token_ticker,
+        token_issuance.number_of_decimals,
+        token_issuance.metadata_uri,
+        Amount::ZERO,
+        token_issuance.total_supply.into(),
+        false,
+        RPCIsTokenFrozen::NotFrozen { freezable },
+        token_issuance.authority,
+    );
+    let unconfirmed_token_info =
+        wallet.get_token_unconfirmed_info(DEFAULT_ACCOUNT_INDEX, &token_info).unwrap();
 
-    let wallet1_multisig_utxos = wallet1
-        .get_multisig_utxos(
-            DEFAULT_ACCOUNT_INDEX,
-            UtxoTypes::ALL,
-            UtxoStates::ALL,
-            WithLocked::Any,
-        )
-        .unwrap();
-    assert_eq!(wallet1_multisig_utxos.len(), 1);
-    let wallet2_multisig_utxos = wallet2
-        .get_multisig_utxos(
+    let amount_to_mint = Amount::from_atoms(rng.gen_range(1..=fixed_max_amount.into_atoms()));
+    let mint_tx = wallet
+        .mint_tokens(
             DEFAULT_ACCOUNT_INDEX,
-            UtxoTypes::ALL,
-            UtxoStates::ALL,
-            WithLocked::Any,
+            &unconfirmed_token_info,
+            amount_to_mint,
+            address2,
+            FeeRate::from_amount_per_kb(Amount::ZERO),
+            FeeRate::from_amount_per_kb(Amount::ZERO),
         )
         .unwrap();
-    assert_eq!(wallet2_multisig_utxos.len(), 1);
+    let _ = create_block(&chain_config, &mut wallet, vec![mint_tx], block2_amount, 2);
 
-    let (refund_ptx, prev_statuses, new_statuses) = wallet2
-        .sign_raw_transaction(
+    let token_utxos: Vec<_> = wallet
+        .get_utxos(
             DEFAULT_ACCOUNT_INDEX,
-            TransactionToSign::Partial(refund_ptx),
+            UtxoType::Transfer | UtxoType::LockThenTransfer,
+            UtxoState::Confirmed.into(),
+            WithLocked::Unlocked,
         )
-        .unwrap();
+        .unwrap()
+        .into_iter()
+        .filter(|(_, _, token_id)| *token_id == Some(issued_token_id))
+        .collect();
+    assert!(!token_utxos.is_empty());
 
-    assert_eq!(vec![SignatureStatus::NotSigned], prev_statuses);
-    assert_eq!(
-        vec![SignatureStatus::PartialMultisig {
-            required_signatures: 2,
-            num_signatures: 1
-        }],
-        new_statuses
+    let destination = Destination::PublicKey(
+        crypto::key::PrivateKey::new_from_rng(&mut rng, crypto::key::KeyKind::Secp256k1Schnorr).1,
     );
+    let feerate = FeeRate::from_amount_per_kb(Amount::from_atoms(1000));
 
-    let (refund_ptx, prev_statuses, new_statuses) = wallet1
-        .sign_raw_transaction(
+    let transaction = wallet
+        .create_token_sweep_transaction(
             DEFAULT_ACCOUNT_INDEX,
-            TransactionToSign::Partial(refund_ptx),
+            destination.clone(),
+            issued_token_id,
+            token_utxos.clone(),
+            feerate,
+            feerate,
         )
         .unwrap();
-    assert_eq!(
-        vec![SignatureStatus::PartialMultisig {
-            required_signatures: 2,
-            num_signatures: 1
-        }],
-        prev_statuses
-    );
-    assert_eq!(vec![SignatureStatus::FullySigned], new_statuses);
 
-    let refund_tx = refund_ptx.into_signed_tx().unwrap();
-
-    let (_, block3) = create_block(
-        &chain_config,
-        &mut wallet1,
-        vec![refund_tx],
-        Amount::ZERO,
-        2,
+    // All the token UTXOs are spent and their whole amount is sent, with no token change
+    let inputs = transaction.transaction().inputs();
+    for (outpoint, _, _) in &token_utxos {
+        assert!(inputs.contains(&TxInput::Utxo(outpoint.clone())));
+    }
+    let token_outputs: Vec<_> = transaction
+        .transaction()
+        .outputs()
+        .iter()
+        .filter(|output| matches!(output, TxOutput::Transfer(OutputValue::TokenV1(..), _)))
+        .collect();
+    assert_eq!(
+        token_outputs,
+        vec![&TxOutput::Transfer(
+            OutputValue::TokenV1(issued_token_id, amount_to_mint),
+            destination
+        )]
     );
-    scan_wallet(&mut wallet2, BlockHeight::new(0), vec![block3]);
 
-    // Refund can be seen in the wallet balance
-    assert_eq!(get_coin_balance(&wallet1), coin_balance);
-    assert_eq!(get_coin_balance(&wallet2), Amount::ZERO);
+    // The fee is paid with coins
+    assert!(inputs.len() > token_utxos.len());
 }
@@ -29,6 +29,9 @@ pub trait WalletEvents {
 
     /// The transaction is removed from the DB
     fn del_transaction(&self, id: U31, source_id: OutPointSourceId);
+
+    /// The wallet has switched to another node
+    fn node_switched(&self, address: &str);
 }
 
 pub struct WalletEventsNoOp;
@@ -37,4 +40,5 @@ impl WalletEvents for WalletEventsNoOp {
     fn new_block(&self) {}
     fn set_transaction(&self, _id: U31, _tx: &WalletTx) {}
     fn del_transaction(&self, _id: U31, _source: OutPointSourceId) {}
+    fn node_switched(&self, _address: &str) {}
 }
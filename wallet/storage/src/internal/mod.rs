@@ -21,7 +21,7 @@ use crate::{
 };
 
 mod password;
-use password::{challenge_to_sym_key, password_to_sym_key};
+pub use password::{challenge_to_sym_key, password_to_sym_key};
 
 mod store_tx;
 pub use store_tx::{StoreTxRo, StoreTxRoUnlocked, StoreTxRw, StoreTxRwUnlocked};
@@ -102,6 +102,7 @@ impl<B: storage::Backend> Store<B> {
         };
         tx.encrypt_root_keys(&sym_key)?;
         tx.encrypt_seed_phrase(&sym_key)?;
+        tx.encrypt_signing_log(&sym_key)?;
         tx.commit()?;
 
         self.encryption_state = EncryptionState::Unlocked(sym_key);
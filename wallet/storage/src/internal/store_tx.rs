@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::{
     schema::{self as db, Schema},
@@ -22,7 +22,11 @@ use crate::{
 };
 use common::{
     address::Address,
-    chain::{block::timestamp::BlockTimestamp, Destination, SignedTransaction},
+    chain::{
+        block::timestamp::BlockTimestamp, tokens::TokenId, Destination, PoolId, Transaction,
+        UtxoOutPoint,
+    },
+    primitives::Id,
 };
 use crypto::{
     kdf::KdfChallenge,
@@ -36,16 +40,22 @@ use utils::{
     maybe_encrypted::{MaybeEncrypted, MaybeEncryptedError},
 };
 use wallet_types::{
-    account_id::{AccountAddress, AccountPublicKey},
+    account_id::{AccountAddress, AccountPoolId, AccountPublicKey, AccountScheduledPaymentId},
     account_info::{
-        AccountVrfKeys, StandaloneMultisig, StandalonePrivateKey, StandaloneWatchOnlyKey,
+        AccountVrfKeys, ChangePolicy, DustProtection, ScheduledPayment, StandaloneMultisig,
+        StandalonePrivateKey, StandaloneWatchOnlyKey, WatchedPool,
     },
+    address_book::Payee,
+    broadcast_queue::BroadcastQueueEntry,
     chain_info::ChainInfo,
+    internal_transfer::InternalTransfer,
     keys::{RootKeyConstant, RootKeys},
     seed_phrase::{SeedPhraseConstant, SerializableSeedPhrase},
+    signing_log::SigningLogEntry,
+    token_trust::TokenTrust,
     wallet_type::WalletType,
-    AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId, AccountWalletCreatedTxId,
-    AccountWalletTxId, KeychainUsageState, WalletTx,
+    AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId, AccountWalletTxId,
+    KeychainUsageState, WalletTx,
 };
 mod well_known {
     use common::chain::block::timestamp::BlockTimestamp;
@@ -79,6 +89,8 @@ mod well_known {
     declare_entry!(LookaheadSize: u32);
     declare_entry!(LegacyVfrPubKey: AccountVrfKeys);
     declare_entry!(WalletType: wallet_type::WalletType);
+    declare_entry!(SigningLogLength: u64);
+    declare_entry!(WatchOnly: bool);
 }
 
 #[derive(PartialEq, Clone)]
@@ -171,6 +183,10 @@ macro_rules! impl_read_ops {
                     .and_then(|v| v.ok_or(crate::Error::WalletDbInconsistentState))
             }
 
+            fn is_watch_only(&self) -> crate::Result<bool> {
+                self.read_value::<well_known::WatchOnly>().map(|v| v.unwrap_or(false))
+            }
+
             fn get_transaction(&self, id: &AccountWalletTxId) -> crate::Result<Option<WalletTx>> {
                 self.read::<db::DBTxs, _, _>(id)
             }
@@ -221,15 +237,6 @@ macro_rules! impl_read_ops {
                     .map(Iterator::collect)
             }
 
-            /// Collect and return all signed transactions from the storage
-            fn get_user_transactions(&self) -> crate::Result<Vec<SignedTransaction>> {
-                self.storage
-                    .get::<db::DBUserTx, _>()
-                    .prefix_iter_decoded(&())
-                    .map_err(crate::Error::from)
-                    .map(|item| item.map(|item| item.1).collect())
-            }
-
             fn get_account_unconfirmed_tx_counter(
                 &self,
                 account_id: &AccountId,
@@ -286,6 +293,123 @@ macro_rules! impl_read_ops {
                     .map(|iter| iter.map(|(key, value)| (key, value.label)).collect())
             }
 
+            fn get_account_watched_pools(
+                &self,
+                account_id: &AccountId,
+            ) -> crate::Result<BTreeMap<PoolId, WatchedPool>> {
+                self.storage
+                    .get::<db::DBWatchedPools, _>()
+                    .prefix_iter_decoded(account_id)
+                    .map_err(crate::Error::from)
+                    .map(|iter| {
+                        iter.map(|(key, value): (AccountPoolId, WatchedPool)| {
+                            (key.into_item_id(), value)
+                        })
+                        .collect()
+                    })
+            }
+
+            fn get_account_scheduled_payments(
+                &self,
+                account_id: &AccountId,
+            ) -> crate::Result<BTreeMap<u64, ScheduledPayment>> {
+                self.storage
+                    .get::<db::DBScheduledPayments, _>()
+                    .prefix_iter_decoded(account_id)
+                    .map_err(crate::Error::from)
+                    .map(|iter| {
+                        iter.map(
+                            |(key, value): (AccountScheduledPaymentId, ScheduledPayment)| {
+                                (key.into_item_id(), value)
+                            },
+                        )
+                        .collect()
+                    })
+            }
+
+            fn get_account_next_scheduled_payment_id(
+                &self,
+                account_id: &AccountId,
+            ) -> crate::Result<Option<u64>> {
+                self.read::<db::DBScheduledPaymentCounters, _, _>(account_id)
+            }
+
+            fn get_account_dust_protection(
+                &self,
+                account_id: &AccountId,
+            ) -> crate::Result<Option<DustProtection>> {
+                self.read::<db::DBDustProtection, _, _>(account_id)
+            }
+
+            fn get_account_frozen_utxos(
+                &self,
+                account_id: &AccountId,
+            ) -> crate::Result<BTreeSet<UtxoOutPoint>> {
+                self.read::<db::DBFrozenUtxos, _, _>(account_id).map(Option::unwrap_or_default)
+            }
+
+            fn get_account_change_policy(
+                &self,
+                account_id: &AccountId,
+            ) -> crate::Result<Option<ChangePolicy>> {
+                self.read::<db::DBChangePolicy, _, _>(account_id)
+            }
+
+            fn get_account_address_labels(
+                &self,
+                account_id: &AccountId,
+            ) -> crate::Result<BTreeMap<Destination, String>> {
+                self.storage
+                    .get::<db::DBAddressLabels, _>()
+                    .prefix_iter_decoded(account_id)
+                    .map_err(crate::Error::from)
+                    .map(|iter| {
+                        iter.map(|(key, value): (AccountAddress, String)| {
+                            (key.into_item_id(), value)
+                        })
+                        .collect()
+                    })
+            }
+
+            fn get_token_trust_list(&self) -> crate::Result<BTreeMap<TokenId, TokenTrust>> {
+                Ok(self.storage.get::<db::DBTokenTrust, _>().prefix_iter_decoded(&())?.collect())
+            }
+
+            fn get_payees(&self) -> crate::Result<BTreeMap<String, Payee>> {
+                Ok(self.storage.get::<db::DBPayees, _>().prefix_iter_decoded(&())?.collect())
+            }
+
+            fn get_payee(&self, name: &str) -> crate::Result<Option<Payee>> {
+                self.read::<db::DBPayees, _, _>(name)
+            }
+
+            fn get_broadcast_queue(
+                &self,
+            ) -> crate::Result<BTreeMap<Id<Transaction>, BroadcastQueueEntry>> {
+                Ok(self
+                    .storage
+                    .get::<db::DBBroadcastQueue, _>()
+                    .prefix_iter_decoded(&())?
+                    .collect())
+            }
+
+            fn get_broadcast_queue_entry(
+                &self,
+                tx_id: &Id<Transaction>,
+            ) -> crate::Result<Option<BroadcastQueueEntry>> {
+                self.read::<db::DBBroadcastQueue, _, _>(tx_id)
+            }
+
+            fn get_internal_transfers(
+                &self,
+            ) -> crate::Result<BTreeMap<Id<Transaction>, InternalTransfer>> {
+                Ok(self
+                    .storage
+                    .get::<db::DBInternalTransfers, _>()
+                    .prefix_iter_decoded(&())?
+                    .collect())
+            }
+
             fn get_keychain_usage_state(
                 &self,
                 id: &AccountKeyPurposeId,
@@ -428,6 +552,22 @@ macro_rules! impl_read_unlocked_ops {
                     }),
                 )
             }
+
+            fn get_signing_log(&self) -> crate::Result<Vec<SigningLogEntry>> {
+                let mut entries: Vec<_> = self
+                    .storage
+                    .get::<db::DBSigningLog, _>()
+                    .prefix_iter_decoded(&())?
+                    .map(|(seq_no, v)| {
+                        let entry =
+                            v.try_take(self.encryption_key).expect("key was checked when unlocked");
+                        (seq_no, entry)
+                    })
+                    .collect();
+                // The keys are not ordered numerically in the DB
+                entries.sort_by_key(|(seq_no, _)| *seq_no);
+                Ok(entries.into_iter().map(|(_, entry)| entry).collect())
+            }
         }
     };
 }
@@ -451,6 +591,10 @@ macro_rules! impl_write_ops {
                 self.write_value::<well_known::StoreChainInfo>(chain_info)
             }
 
+            fn set_watch_only(&mut self, watch_only: bool) -> crate::Result<()> {
+                self.write_value::<well_known::WatchOnly>(&watch_only)
+            }
+
             fn set_transaction(
                 &mut self,
                 id: &AccountWalletTxId,
@@ -504,18 +648,6 @@ macro_rules! impl_write_ops {
                 self.write::<db::DBVRFPublicKeys, _, _, _>(id, account_vrf_keys)
             }
 
-            fn set_user_transaction(
-                &mut self,
-                id: &AccountWalletCreatedTxId,
-                tx: &SignedTransaction,
-            ) -> crate::Result<()> {
-                self.write::<db::DBUserTx, _, _, _>(id, tx)
-            }
-
-            fn del_user_transaction(&mut self, id: &AccountWalletCreatedTxId) -> crate::Result<()> {
-                self.storage.get_mut::<db::DBUserTx, _>().del(id).map_err(Into::into)
-            }
-
             fn set_standalone_watch_only_key(
                 &mut self,
                 id: &AccountAddress,
@@ -523,6 +655,14 @@ macro_rules! impl_write_ops {
             ) -> crate::Result<()> {
                 self.write::<db::DBStandaloneWatchOnlyKeys, _, _, _>(id, key)
             }
+
+            fn del_standalone_watch_only_key(&mut self, id: &AccountAddress) -> crate::Result<()> {
+                self.storage
+                    .get_mut::<db::DBStandaloneWatchOnlyKeys, _>()
+                    .del(id)
+                    .map_err(Into::into)
+            }
+
             fn set_standalone_multisig_key(
                 &mut self,
                 id: &AccountAddress,
@@ -531,6 +671,113 @@ macro_rules! impl_write_ops {
                 self.write::<db::DBStandaloneMultisigKeys, _, _, _>(id, key)
             }
 
+            fn set_watched_pool(
+                &mut self,
+                id: &AccountPoolId,
+                pool: &WatchedPool,
+            ) -> crate::Result<()> {
+                self.write::<db::DBWatchedPools, _, _, _>(id, pool)
+            }
+
+            fn del_watched_pool(&mut self, id: &AccountPoolId) -> crate::Result<()> {
+                self.storage.get_mut::<db::DBWatchedPools, _>().del(id).map_err(Into::into)
+            }
+
+            fn set_scheduled_payment(
+                &mut self,
+                id: &AccountScheduledPaymentId,
+                payment: &ScheduledPayment,
+            ) -> crate::Result<()> {
+                self.write::<db::DBScheduledPayments, _, _, _>(id, payment)
+            }
+
+            fn del_scheduled_payment(
+                &mut self,
+                id: &AccountScheduledPaymentId,
+            ) -> crate::Result<()> {
+                self.storage.get_mut::<db::DBScheduledPayments, _>().del(id).map_err(Into::into)
+            }
+
+            fn set_account_next_scheduled_payment_id(
+                &mut self,
+                account_id: &AccountId,
+                payment_id: u64,
+            ) -> crate::Result<()> {
+                self.write::<db::DBScheduledPaymentCounters, _, _, _>(account_id, payment_id)
+            }
+
+            fn set_account_dust_protection(
+                &mut self,
+                account_id: &AccountId,
+                dust_protection: &DustProtection,
+            ) -> crate::Result<()> {
+                self.write::<db::DBDustProtection, _, _, _>(account_id, dust_protection)
+            }
+
+            fn set_account_frozen_utxos(
+                &mut self,
+                account_id: &AccountId,
+                frozen_utxos: &BTreeSet<UtxoOutPoint>,
+            ) -> crate::Result<()> {
+                self.write::<db::DBFrozenUtxos, _, _, _>(account_id, frozen_utxos)
+            }
+
+            fn set_account_change_policy(
+                &mut self,
+                account_id: &AccountId,
+                change_policy: &ChangePolicy,
+            ) -> crate::Result<()> {
+                self.write::<db::DBChangePolicy, _, _, _>(account_id, change_policy)
+            }
+
+            fn set_address_label(&mut self, id: &AccountAddress, label: &str) -> crate::Result<()> {
+                self.write::<db::DBAddressLabels, _, _, _>(id, label)
+            }
+
+            fn del_address_label(&mut self, id: &AccountAddress) -> crate::Result<()> {
+                self.storage.get_mut::<db::DBAddressLabels, _>().del(id).map_err(Into::into)
+            }
+
+            fn set_token_trust(
+                &mut self,
+                token_id: &TokenId,
+                trust: TokenTrust,
+            ) -> crate::Result<()> {
+                self.write::<db::DBTokenTrust, _, _, _>(token_id, trust)
+            }
+
+            fn del_token_trust(&mut self, token_id: &TokenId) -> crate::Result<()> {
+                self.storage.get_mut::<db::DBTokenTrust, _>().del(token_id).map_err(Into::into)
+            }
+
+            fn set_payee(&mut self, name: &str, payee: &Payee) -> crate::Result<()> {
+                self.write::<db::DBPayees, _, _, _>(name, payee)
+            }
+
+            fn del_payee(&mut self, name: &str) -> crate::Result<()> {
+                self.storage.get_mut::<db::DBPayees, _>().del(name).map_err(Into::into)
+            }
+
+            fn set_broadcast_queue_entry(
+                &mut self,
+                tx_id: &Id<Transaction>,
+                entry: &BroadcastQueueEntry,
+            ) -> crate::Result<()> {
+                self.write::<db::DBBroadcastQueue, _, _, _>(tx_id, entry)
+            }
+
+            fn del_broadcast_queue_entry(&mut self, tx_id: &Id<Transaction>) -> crate::Result<()> {
+                self.storage.get_mut::<db::DBBroadcastQueue, _>().del(tx_id).map_err(Into::into)
+            }
+
+            fn set_internal_transfer(
+                &mut self,
+                tx_id: &Id<Transaction>,
+                transfer: &InternalTransfer,
+            ) -> crate::Result<()> {
+                self.write::<db::DBInternalTransfers, _, _, _>(tx_id, transfer)
+            }
+
             fn set_account(&mut self, id: &AccountId, tx: &AccountInfo) -> crate::Result<()> {
                 self.write::<db::DBAccounts, _, _, _>(id, tx)
             }
@@ -673,6 +920,26 @@ impl<'st, B: storage::Backend> WalletStorageEncryptionWrite for StoreTxRwUnlocke
             .into_iter()
             .try_for_each(|(k, v)| self.write::<db::DBSeedPhrase, _, _, _>(k, v))
     }
+
+    fn encrypt_signing_log(
+        &mut self,
+        new_encryption_key: &Option<SymmetricKey>,
+    ) -> crate::Result<()> {
+        let encrypted_entries: Vec<_> = self
+            .storage
+            .get::<db::DBSigningLog, _>()
+            .prefix_iter_decoded(&())?
+            .map(|(k, v)| {
+                let decrypted =
+                    v.try_take(self.encryption_key).expect("key was checked when unlocked");
+                (k, MaybeEncrypted::new(&decrypted, new_encryption_key))
+            })
+            .collect();
+
+        encrypted_entries
+            .into_iter()
+            .try_for_each(|(k, v)| self.write::<db::DBSigningLog, _, _, _>(k, v))
+    }
 }
 
 /// Wallet data storage transaction
@@ -717,6 +984,13 @@ impl<'st, B: storage::Backend> WalletStorageWriteUnlocked for StoreTxRwUnlocked<
         // TODO: probably will need to VACUUM the sqlite DB to make sure it is deleted
         Ok(phrase)
     }
+
+    fn append_signing_log_entry(&mut self, entry: &SigningLogEntry) -> crate::Result<()> {
+        let seq_no = self.read_value::<well_known::SigningLogLength>()?.unwrap_or(0);
+        let value = MaybeEncrypted::new(entry, self.encryption_key);
+        self.write::<db::DBSigningLog, _, _, _>(seq_no, value)?;
+        self.write_value::<well_known::SigningLogLength>(&(seq_no + 1))
+    }
 }
 
 impl<'st, B: storage::Backend> crate::TransactionRoLocked for StoreTxRo<'st, B> {
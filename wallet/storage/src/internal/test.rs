@@ -117,3 +117,52 @@ fn compare_encrypt_and_decrypt_root_key(#[case] seed: Seed) {
         }
     })
 }
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+fn signing_log_is_ordered_and_reencrypted(#[case] seed: Seed) {
+    use common::chain::{block::timestamp::BlockTimestamp, Transaction};
+    use crypto::key::hdkd::u31::U31;
+    use wallet_types::signing_log::{SigningLogEntry, SigningRequester};
+
+    utils::concurrency::model(move || {
+        let mut rng = make_seedable_rng(seed);
+        let mut store = Store::new(DefaultBackend::new_in_memory()).unwrap();
+
+        // More than 256 entries, so the little-endian encoded keys are not in numerical order
+        let entries: Vec<_> = (0..rng.gen_range(257..300))
+            .map(|i| {
+                let tx = Transaction::new(i, vec![], vec![]).unwrap();
+                SigningLogEntry::new(
+                    &tx,
+                    BlockTimestamp::from_int_seconds(rng.gen()),
+                    U31::ZERO,
+                    SigningRequester::Wallet,
+                    rng.gen(),
+                )
+            })
+            .collect();
+
+        let mut db_tx = store.transaction_rw_unlocked(None).unwrap();
+        for entry in &entries[..entries.len() / 2] {
+            db_tx.append_signing_log_entry(entry).unwrap();
+        }
+        db_tx.commit().unwrap();
+
+        let password = gen_random_password(&mut rng);
+        store.encrypt_private_keys(&Some(password.clone())).unwrap();
+
+        let mut db_tx = store.transaction_rw_unlocked(None).unwrap();
+        for entry in &entries[entries.len() / 2..] {
+            db_tx.append_signing_log_entry(entry).unwrap();
+        }
+        db_tx.commit().unwrap();
+
+        store.lock_private_keys().unwrap();
+        store.unlock_private_keys(&password).unwrap();
+
+        let db_tx = store.transaction_ro_unlocked().unwrap();
+        assert_eq!(db_tx.get_signing_log().unwrap(), entries);
+    })
+}
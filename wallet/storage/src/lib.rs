@@ -21,25 +21,40 @@ pub mod schema;
 
 use common::{
     address::{Address, AddressError},
-    chain::{block::timestamp::BlockTimestamp, Destination, SignedTransaction},
+    chain::{
+        block::timestamp::BlockTimestamp, tokens::TokenId, Destination, PoolId, Transaction,
+        UtxoOutPoint,
+    },
+    primitives::Id,
 };
 use crypto::{
     kdf::KdfChallenge,
     key::{extended::ExtendedPublicKey, PrivateKey},
     symkey::SymmetricKey,
 };
-pub use internal::{Store, StoreTxRo, StoreTxRoUnlocked, StoreTxRw, StoreTxRwUnlocked};
-use std::collections::BTreeMap;
+pub use internal::{
+    challenge_to_sym_key, password_to_sym_key, Store, StoreTxRo, StoreTxRoUnlocked, StoreTxRw,
+    StoreTxRwUnlocked,
+};
+use std::collections::{BTreeMap, BTreeSet};
 
 use wallet_types::{
-    account_id::{AccountAddress, AccountPublicKey},
-    account_info::{AccountVrfKeys, StandaloneMultisig, StandaloneWatchOnlyKey},
+    account_id::{AccountAddress, AccountPoolId, AccountPublicKey, AccountScheduledPaymentId},
+    account_info::{
+        AccountVrfKeys, ChangePolicy, DustProtection, ScheduledPayment, StandaloneMultisig,
+        StandaloneWatchOnlyKey, WatchedPool,
+    },
+    address_book::Payee,
+    broadcast_queue::BroadcastQueueEntry,
     chain_info::ChainInfo,
+    internal_transfer::InternalTransfer,
     keys::RootKeys,
     seed_phrase::SerializableSeedPhrase,
+    signing_log::SigningLogEntry,
+    token_trust::TokenTrust,
     wallet_type::WalletType,
-    AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId, AccountWalletCreatedTxId,
-    AccountWalletTxId, KeychainUsageState, WalletTx,
+    AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId, AccountWalletTxId,
+    KeychainUsageState, WalletTx,
 };
 
 /// Wallet Errors
@@ -74,12 +89,13 @@ pub trait WalletStorageReadLocked {
     fn get_storage_version(&self) -> Result<u32>;
     fn get_wallet_type(&self) -> Result<WalletType>;
     fn get_chain_info(&self) -> Result<ChainInfo>;
+    /// Whether the wallet is a watch-only replica without any private keys
+    fn is_watch_only(&self) -> Result<bool>;
     fn get_transaction(&self, id: &AccountWalletTxId) -> Result<Option<WalletTx>>;
     fn get_transactions(
         &self,
         account_id: &AccountId,
     ) -> Result<Vec<(AccountWalletTxId, WalletTx)>>;
-    fn get_user_transactions(&self) -> Result<Vec<SignedTransaction>>;
     fn get_account_unconfirmed_tx_counter(&self, account_id: &AccountId) -> Result<Option<u64>>;
     fn get_account_vrf_public_keys(&self, account_id: &AccountId)
         -> Result<Option<AccountVrfKeys>>;
@@ -95,6 +111,32 @@ pub trait WalletStorageReadLocked {
         &self,
         account_id: &AccountId,
     ) -> Result<Vec<(AccountPublicKey, Option<String>)>>;
+    fn get_account_watched_pools(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<BTreeMap<PoolId, WatchedPool>>;
+    fn get_account_scheduled_payments(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<BTreeMap<u64, ScheduledPayment>>;
+    fn get_account_next_scheduled_payment_id(&self, account_id: &AccountId) -> Result<Option<u64>>;
+    fn get_account_dust_protection(&self, account_id: &AccountId)
+        -> Result<Option<DustProtection>>;
+    fn get_account_frozen_utxos(&self, account_id: &AccountId) -> Result<BTreeSet<UtxoOutPoint>>;
+    fn get_account_change_policy(&self, account_id: &AccountId) -> Result<Option<ChangePolicy>>;
+    fn get_account_address_labels(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<BTreeMap<Destination, String>>;
+    fn get_token_trust_list(&self) -> Result<BTreeMap<TokenId, TokenTrust>>;
+    fn get_payees(&self) -> Result<BTreeMap<String, Payee>>;
+    fn get_payee(&self, name: &str) -> Result<Option<Payee>>;
+    fn get_broadcast_queue(&self) -> Result<BTreeMap<Id<Transaction>, BroadcastQueueEntry>>;
+    fn get_broadcast_queue_entry(
+        &self,
+        tx_id: &Id<Transaction>,
+    ) -> Result<Option<BroadcastQueueEntry>>;
+    fn get_internal_transfers(&self) -> Result<BTreeMap<Id<Transaction>, InternalTransfer>>;
     fn get_accounts_info(&self) -> crate::Result<BTreeMap<AccountId, AccountInfo>>;
     fn get_address(&self, id: &AccountDerivationPathId) -> Result<Option<String>>;
     fn get_addresses(
@@ -128,6 +170,8 @@ pub trait WalletStorageReadUnlocked: WalletStorageReadLocked {
         &self,
         account_pubkey: &AccountPublicKey,
     ) -> Result<Option<PrivateKey>>;
+    /// Get all the entries of the signing log in the order they were added
+    fn get_signing_log(&self) -> Result<Vec<SigningLogEntry>>;
 }
 
 /// Queries on persistent wallet data for encryption
@@ -142,6 +186,7 @@ pub trait WalletStorageWriteLocked: WalletStorageReadLocked {
     fn set_storage_version(&mut self, version: u32) -> Result<()>;
     fn set_wallet_type(&mut self, wallet_type: WalletType) -> Result<()>;
     fn set_chain_info(&mut self, chain_info: &ChainInfo) -> Result<()>;
+    fn set_watch_only(&mut self, watch_only: bool) -> Result<()>;
     fn set_transaction(&mut self, id: &AccountWalletTxId, tx: &WalletTx) -> Result<()>;
     fn del_transaction(&mut self, id: &AccountWalletTxId) -> Result<()>;
     fn clear_transactions(&mut self) -> Result<()>;
@@ -151,22 +196,62 @@ pub trait WalletStorageWriteLocked: WalletStorageReadLocked {
         id: &AccountId,
         vrf_public_keys: &AccountVrfKeys,
     ) -> Result<()>;
-    fn set_user_transaction(
-        &mut self,
-        id: &AccountWalletCreatedTxId,
-        tx: &SignedTransaction,
-    ) -> Result<()>;
-    fn del_user_transaction(&mut self, id: &AccountWalletCreatedTxId) -> crate::Result<()>;
     fn set_standalone_watch_only_key(
         &mut self,
         id: &AccountAddress,
         key: &StandaloneWatchOnlyKey,
     ) -> Result<()>;
+    fn del_standalone_watch_only_key(&mut self, id: &AccountAddress) -> Result<()>;
     fn set_standalone_multisig_key(
         &mut self,
         id: &AccountAddress,
         key: &StandaloneMultisig,
     ) -> Result<()>;
+    fn set_watched_pool(&mut self, id: &AccountPoolId, pool: &WatchedPool) -> Result<()>;
+    fn del_watched_pool(&mut self, id: &AccountPoolId) -> Result<()>;
+    fn set_scheduled_payment(
+        &mut self,
+        id: &AccountScheduledPaymentId,
+        payment: &ScheduledPayment,
+    ) -> Result<()>;
+    fn del_scheduled_payment(&mut self, id: &AccountScheduledPaymentId) -> Result<()>;
+    fn set_account_next_scheduled_payment_id(
+        &mut self,
+        account_id: &AccountId,
+        payment_id: u64,
+    ) -> Result<()>;
+    fn set_account_dust_protection(
+        &mut self,
+        account_id: &AccountId,
+        dust_protection: &DustProtection,
+    ) -> Result<()>;
+    fn set_account_frozen_utxos(
+        &mut self,
+        account_id: &AccountId,
+        frozen_utxos: &BTreeSet<UtxoOutPoint>,
+    ) -> Result<()>;
+    fn set_account_change_policy(
+        &mut self,
+        account_id: &AccountId,
+        change_policy: &ChangePolicy,
+    ) -> Result<()>;
+    fn set_address_label(&mut self, id: &AccountAddress, label: &str) -> Result<()>;
+    fn del_address_label(&mut self, id: &AccountAddress) -> Result<()>;
+    fn set_token_trust(&mut self, token_id: &TokenId, trust: TokenTrust) -> Result<()>;
+    fn del_token_trust(&mut self, token_id: &TokenId) -> Result<()>;
+    fn set_payee(&mut self, name: &str, payee: &Payee) -> Result<()>;
+    fn del_payee(&mut self, name: &str) -> Result<()>;
+    fn set_broadcast_queue_entry(
+        &mut self,
+        tx_id: &Id<Transaction>,
+        entry: &BroadcastQueueEntry,
+    ) -> Result<()>;
+    fn del_broadcast_queue_entry(&mut self, tx_id: &Id<Transaction>) -> Result<()>;
+    fn set_internal_transfer(
+        &mut self,
+        tx_id: &Id<Transaction>,
+        transfer: &InternalTransfer,
+    ) -> Result<()>;
     fn set_account(&mut self, id: &AccountId, content: &AccountInfo) -> Result<()>;
     fn del_account(&mut self, id: &AccountId) -> Result<()>;
     fn set_address(
@@ -211,6 +296,7 @@ pub trait WalletStorageWriteUnlocked: WalletStorageReadUnlocked + WalletStorageW
         key: &PrivateKey,
         label: Option<String>,
     ) -> Result<()>;
+    fn append_signing_log_entry(&mut self, entry: &SigningLogEntry) -> Result<()>;
 }
 
 /// Modifying operations on persistent wallet data for encryption
@@ -219,6 +305,7 @@ pub trait WalletStorageEncryptionWrite {
     fn del_encryption_kdf_challenge(&mut self) -> Result<()>;
     fn encrypt_root_keys(&mut self, new_encryption_key: &Option<SymmetricKey>) -> Result<()>;
     fn encrypt_seed_phrase(&mut self, new_encryption_key: &Option<SymmetricKey>) -> Result<()>;
+    fn encrypt_signing_log(&mut self, new_encryption_key: &Option<SymmetricKey>) -> Result<()>;
 }
 
 /// Marker trait for types where read/write operations are run in a transaction
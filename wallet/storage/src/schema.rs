@@ -15,18 +15,29 @@
 
 //! Wallet database schema
 
-use common::chain::SignedTransaction;
+use std::collections::BTreeSet;
+
+use common::{
+    chain::{tokens::TokenId, Transaction, UtxoOutPoint},
+    primitives::Id,
+};
 use crypto::key::extended::ExtendedPublicKey;
 use utils::maybe_encrypted::MaybeEncrypted;
 use wallet_types::{
-    account_id::{AccountAddress, AccountPublicKey},
+    account_id::{AccountAddress, AccountPoolId, AccountPublicKey, AccountScheduledPaymentId},
     account_info::{
-        AccountVrfKeys, StandaloneMultisig, StandalonePrivateKey, StandaloneWatchOnlyKey,
+        AccountVrfKeys, ChangePolicy, DustProtection, ScheduledPayment, StandaloneMultisig,
+        StandalonePrivateKey, StandaloneWatchOnlyKey, WatchedPool,
     },
+    address_book::Payee,
+    broadcast_queue::BroadcastQueueEntry,
+    internal_transfer::InternalTransfer,
     keys::{RootKeyConstant, RootKeys},
     seed_phrase::{SeedPhraseConstant, SerializableSeedPhrase},
-    AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId, AccountWalletCreatedTxId,
-    AccountWalletTxId, KeychainUsageState, WalletTx,
+    signing_log::SigningLogEntry,
+    token_trust::TokenTrust,
+    AccountDerivationPathId, AccountId, AccountInfo, AccountKeyPurposeId, AccountWalletTxId,
+    KeychainUsageState, WalletTx,
 };
 
 storage::decl_schema! {
@@ -48,8 +59,6 @@ storage::decl_schema! {
         pub DBAddresses: Map<AccountDerivationPathId, String>,
         /// Store for block/transaction entries
         pub DBTxs: Map<AccountWalletTxId, WalletTx>,
-        /// Store for wallet created transactions
-        pub DBUserTx: Map<AccountWalletCreatedTxId, SignedTransaction>,
         /// Store for the wallet's passphrase
         pub DBSeedPhrase: Map<SeedPhraseConstant, MaybeEncrypted<SerializableSeedPhrase>>,
         /// Store for each account's unconfirmed transaction order counter
@@ -62,5 +71,30 @@ storage::decl_schema! {
         pub DBStandaloneMultisigKeys: Map<AccountAddress, StandaloneMultisig>,
         /// Store for standalone private keys added to accounts
         pub DBStandalonePrivateKeys: Map<AccountPublicKey, StandalonePrivateKey>,
+        /// Store for stake pools watched by accounts without owning them
+        pub DBWatchedPools: Map<AccountPoolId, WatchedPool>,
+        /// Store for recurring payments scheduled by accounts
+        pub DBScheduledPayments: Map<AccountScheduledPaymentId, ScheduledPayment>,
+        /// Store for each account's id of the next scheduled payment, so that the ids
+        /// of cancelled payments are not reused
+        pub DBScheduledPaymentCounters: Map<AccountId, u64>,
+        /// Append-only log of the transactions signed by the wallet, indexed by sequence number
+        pub DBSigningLog: Map<u64, MaybeEncrypted<SigningLogEntry>>,
+        /// Store for each account's dust protection policy and released UTXOs
+        pub DBDustProtection: Map<AccountId, DustProtection>,
+        /// Store for each account's UTXOs that are frozen by the user and must not be spent
+        pub DBFrozenUtxos: Map<AccountId, BTreeSet<UtxoOutPoint>>,
+        /// Store for each account's policy for the destination of the change outputs
+        pub DBChangePolicy: Map<AccountId, ChangePolicy>,
+        /// Store for the tokens marked as trusted or hidden by the user
+        pub DBTokenTrust: Map<TokenId, TokenTrust>,
+        /// Store for the wallet's address book, by payee name
+        pub DBPayees: Map<String, Payee>,
+        /// Store for the transactions queued for broadcasting to the mempool and their status
+        pub DBBroadcastQueue: Map<Id<Transaction>, BroadcastQueueEntry>,
+        /// Store for the transactions that moved funds between the wallet's own accounts
+        pub DBInternalTransfers: Map<Id<Transaction>, InternalTransfer>,
+        /// Store for the user's labels of the addresses issued by the accounts' key chains
+        pub DBAddressLabels: Map<AccountAddress, String>,
     }
 }
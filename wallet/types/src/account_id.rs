@@ -16,8 +16,7 @@
 use crate::keys::KeyPurpose;
 use common::{
     address::pubkeyhash::PublicKeyHash,
-    chain::{Destination, OutPointSourceId, Transaction},
-    primitives::Id,
+    chain::{Destination, OutPointSourceId, PoolId},
 };
 use crypto::key::hdkd::derivation_path::DerivationPath;
 use crypto::key::{extended::ExtendedPublicKey, PublicKey};
@@ -77,9 +76,10 @@ impl<Id: Encode> AccountPrefixedId<Id> {
 
 impl<Id: Encode> HasPrefix<AccountId> for AccountPrefixedId<Id> {}
 
-pub type AccountWalletCreatedTxId = AccountPrefixedId<Id<Transaction>>;
 pub type AccountWalletTxId = AccountPrefixedId<OutPointSourceId>;
 pub type AccountDerivationPathId = AccountPrefixedId<DerivationPath>;
 pub type AccountKeyPurposeId = AccountPrefixedId<KeyPurpose>;
 pub type AccountAddress = AccountPrefixedId<Destination>;
 pub type AccountPublicKey = AccountPrefixedId<PublicKey>;
+pub type AccountPoolId = AccountPrefixedId<PoolId>;
+pub type AccountScheduledPaymentId = AccountPrefixedId<u64>;
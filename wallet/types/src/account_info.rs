@@ -13,9 +13,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeSet;
+
 use common::{
-    chain::{classic_multisig::ClassicMultisigChallenge, ChainConfig, Destination, GenBlock},
-    primitives::{BlockHeight, Id},
+    chain::{
+        block::timestamp::BlockTimestamp, classic_multisig::ClassicMultisigChallenge,
+        tokens::TokenId, ChainConfig, Destination, GenBlock, Transaction, UtxoOutPoint,
+    },
+    primitives::{Amount, BlockHeight, Id},
 };
 use crypto::{
     key::{extended::ExtendedPublicKey, hdkd::u31::U31, PrivateKey, PublicKey},
@@ -147,8 +152,211 @@ pub enum StandaloneAddressDetails {
     PrivateKey(Option<String>),
 }
 
+/// A stake pool that is not controlled by the account, but is monitored by it.
+/// Used by pool operators to observe a pool from a wallet that holds no keys for it.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct WatchedPool {
+    pub label: Option<String>,
+    pub decommission_key: Option<Destination>,
+    /// Blocks produced by the pool since the account started watching it
+    pub created_blocks: Vec<(BlockHeight, Id<GenBlock>)>,
+}
+
+impl WatchedPool {
+    pub fn new(label: Option<String>, decommission_key: Option<Destination>) -> Self {
+        Self {
+            label,
+            decommission_key,
+            created_blocks: Vec::new(),
+        }
+    }
+
+    pub fn add_created_block(&mut self, height: BlockHeight, block_id: Id<GenBlock>) {
+        self.created_blocks.push((height, block_id));
+    }
+
+    /// Remove all the created blocks above the specified height, e.g. after a reorg
+    pub fn remove_created_blocks_above(&mut self, height: BlockHeight) {
+        self.created_blocks.retain(|(block_height, _)| *block_height <= height);
+    }
+}
+
+/// The delay before retrying a failed scheduled payment for the first time,
+/// doubled on each subsequent failure
+const SCHEDULED_PAYMENT_INITIAL_RETRY_DELAY_SECS: u64 = 60;
+/// The maximum delay between retries of a failed scheduled payment
+const SCHEDULED_PAYMENT_MAX_RETRY_DELAY_SECS: u64 = 6 * 60 * 60;
+
+/// A payment template that is executed periodically by the wallet controller,
+/// e.g. for payroll-style recurring transfers.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct ScheduledPayment {
+    pub label: Option<String>,
+    pub destination: Destination,
+    /// The token to be sent, or `None` for coins
+    pub token_id: Option<TokenId>,
+    pub amount: Amount,
+    pub interval_secs: u64,
+    /// The time of the next regular run of the payment
+    pub next_run: BlockTimestamp,
+    /// The time of the next retry if the last attempt has failed
+    pub retry_at: Option<BlockTimestamp>,
+    /// The number of consecutive failed attempts
+    pub failed_attempts: u32,
+    pub last_error: Option<String>,
+    pub last_tx_id: Option<Id<Transaction>>,
+}
+
+impl ScheduledPayment {
+    pub fn new(
+        label: Option<String>,
+        destination: Destination,
+        token_id: Option<TokenId>,
+        amount: Amount,
+        interval_secs: u64,
+        first_run: BlockTimestamp,
+    ) -> Self {
+        Self {
+            label,
+            destination,
+            token_id,
+            amount,
+            interval_secs,
+            next_run: first_run,
+            retry_at: None,
+            failed_attempts: 0,
+            last_error: None,
+            last_tx_id: None,
+        }
+    }
+
+    pub fn is_due(&self, now: BlockTimestamp) -> bool {
+        self.retry_at.unwrap_or(self.next_run) <= now
+    }
+
+    /// Schedule the next run after a successful payment.
+    /// Runs missed while the wallet was not running are skipped, so only one payment is made
+    /// for them.
+    pub fn on_success(&mut self, now: BlockTimestamp, tx_id: Id<Transaction>) {
+        let next_run = self.next_run.as_int_seconds();
+        let now = now.as_int_seconds();
+        let interval = std::cmp::max(self.interval_secs, 1);
+        let missed_runs = now.saturating_sub(next_run) / interval + 1;
+        self.next_run = BlockTimestamp::from_int_seconds(
+            next_run.saturating_add(missed_runs.saturating_mul(interval)),
+        );
+        self.retry_at = None;
+        self.failed_attempts = 0;
+        self.last_error = None;
+        self.last_tx_id = Some(tx_id);
+    }
+
+    /// Schedule a retry with an exponential backoff after a failed payment
+    pub fn on_failure(&mut self, now: BlockTimestamp, error: String) {
+        self.failed_attempts = self.failed_attempts.saturating_add(1);
+        let delay = 2u64
+            .checked_pow(self.failed_attempts - 1)
+            .and_then(|multiplier| {
+                SCHEDULED_PAYMENT_INITIAL_RETRY_DELAY_SECS.checked_mul(multiplier)
+            })
+            .map_or(SCHEDULED_PAYMENT_MAX_RETRY_DELAY_SECS, |delay| {
+                delay.min(SCHEDULED_PAYMENT_MAX_RETRY_DELAY_SECS)
+            });
+        self.retry_at = Some(BlockTimestamp::from_int_seconds(
+            now.as_int_seconds().saturating_add(delay),
+        ));
+        self.last_error = Some(error);
+    }
+}
+
+/// Protection against dusting attacks: UTXOs received from other wallets that match the policy
+/// are not selected automatically when composing transactions, so that they are not merged with
+/// the user's own funds. Flagged UTXOs can still be spent if selected explicitly or released.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Encode, Decode)]
+pub struct DustProtection {
+    /// Incoming coin UTXOs with a value below this amount are flagged
+    pub min_coin_amount: Option<Amount>,
+    /// Flag incoming UTXOs of tokens that this account has never sent itself
+    pub flag_unknown_tokens: bool,
+    /// Flagged UTXOs explicitly released by the user for automatic selection
+    pub released_utxos: BTreeSet<UtxoOutPoint>,
+}
+
+impl DustProtection {
+    pub fn is_enabled(&self) -> bool {
+        self.min_coin_amount.is_some() || self.flag_unknown_tokens
+    }
+}
+
+/// The destination of the change outputs of the transactions composed by an account
+#[derive(Debug, Clone, Default, PartialEq, Eq, Encode, Decode)]
+pub enum ChangePolicy {
+    /// Send the change to the next unused change address of the account
+    #[default]
+    #[codec(index = 0)]
+    NewAddress,
+    /// Send the change back to the address of the first input paying the fee,
+    /// so that no new addresses of the account are linked to it
+    #[codec(index = 1)]
+    ReuseInputAddress,
+    /// Send the change to an address outside of the wallet, e.g. a cold wallet
+    #[codec(index = 2)]
+    External(Destination),
+}
+
 pub struct StandaloneAddresses {
     pub watch_only_addresses: Vec<(Destination, StandaloneWatchOnlyKey)>,
     pub multisig_addresses: Vec<(Destination, StandaloneMultisig)>,
     pub private_keys: Vec<(PublicKey, Option<String>)>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::primitives::H256;
+
+    #[test]
+    fn scheduled_payment_schedule() {
+        let ts = BlockTimestamp::from_int_seconds;
+        let tx_id = Id::<Transaction>::new(H256::zero());
+        let mut payment = ScheduledPayment::new(
+            None,
+            Destination::AnyoneCanSpend,
+            None,
+            Amount::from_atoms(1),
+            100,
+            ts(1000),
+        );
+        assert!(!payment.is_due(ts(999)));
+        assert!(payment.is_due(ts(1000)));
+
+        payment.on_success(ts(1000), tx_id);
+        assert_eq!(payment.next_run, ts(1100));
+        assert!(!payment.is_due(ts(1099)));
+
+        // The missed runs are skipped
+        payment.on_success(ts(1350), tx_id);
+        assert_eq!(payment.next_run, ts(1400));
+
+        payment.on_failure(ts(1400), "error".to_owned());
+        assert_eq!(payment.retry_at, Some(ts(1460)));
+        assert!(!payment.is_due(ts(1459)));
+        payment.on_failure(ts(1460), "error".to_owned());
+        assert_eq!(payment.retry_at, Some(ts(1580)));
+        assert_eq!(payment.failed_attempts, 2);
+
+        payment.failed_attempts = 100;
+        payment.on_failure(ts(2000), "error".to_owned());
+        assert_eq!(
+            payment.retry_at,
+            Some(ts(2000 + SCHEDULED_PAYMENT_MAX_RETRY_DELAY_SECS))
+        );
+
+        payment.on_success(ts(2000), tx_id);
+        assert_eq!(payment.next_run, ts(2100));
+        assert_eq!(payment.retry_at, None);
+        assert_eq!(payment.failed_attempts, 0);
+        assert_eq!(payment.last_error, None);
+    }
+}
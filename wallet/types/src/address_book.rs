@@ -0,0 +1,28 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::chain::{tokens::TokenId, Destination};
+use serialization::{Decode, Encode};
+
+/// An entry of the wallet's address book, stored under a name chosen by the user.
+///
+/// The address book is shared by all the accounts of the wallet.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct Payee {
+    pub address: Destination,
+    /// The token sent to the payee by default; coins are sent if not set
+    pub default_token: Option<TokenId>,
+    pub notes: Option<String>,
+}
@@ -0,0 +1,225 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::chain::{block::timestamp::BlockTimestamp, SignedTransaction};
+use serialization::{Decode, Encode};
+
+/// The delay before retrying a failed broadcast for the first time,
+/// doubled on each subsequent failure
+const BROADCAST_INITIAL_RETRY_DELAY_SECS: u64 = 30;
+/// The maximum delay between retries of a failed broadcast
+const BROADCAST_MAX_RETRY_DELAY_SECS: u64 = 60 * 60;
+/// The default number of consecutive failed attempts after which a broadcast is given up
+pub const DEFAULT_BROADCAST_MAX_FAILED_ATTEMPTS: u32 = 10;
+
+/// When the wallet should stop trying to broadcast a transaction
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct BroadcastPolicy {
+    /// The number of consecutive failed attempts after which the transaction is rejected
+    pub max_failed_attempts: u32,
+    /// The transaction is rejected if it hasn't been included in a block by this time
+    pub deadline: Option<BlockTimestamp>,
+}
+
+impl Default for BroadcastPolicy {
+    fn default() -> Self {
+        Self {
+            max_failed_attempts: DEFAULT_BROADCAST_MAX_FAILED_ATTEMPTS,
+            deadline: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum BroadcastStatus {
+    /// The transaction hasn't been submitted to the node successfully yet
+    #[codec(index = 0)]
+    Pending,
+    /// The node has accepted the transaction into its mempool;
+    /// it's resubmitted periodically until it's included in a block
+    #[codec(index = 1)]
+    Sent,
+    /// The transaction has been included in a block
+    #[codec(index = 2)]
+    Accepted,
+    /// The wallet has given up broadcasting the transaction, with the reason;
+    /// the transaction is abandoned to release its inputs unless it's in the mempool
+    #[codec(index = 3)]
+    Rejected(String),
+}
+
+/// A transaction in the wallet's outgoing broadcast queue, processed by the wallet controller
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct BroadcastQueueEntry {
+    pub tx: SignedTransaction,
+    pub policy: BroadcastPolicy,
+    pub status: BroadcastStatus,
+    /// The total number of submissions to the node
+    pub attempts: u32,
+    /// The number of consecutive failed submissions
+    pub failed_attempts: u32,
+    pub last_error: Option<String>,
+    /// The time of the next submission
+    pub next_attempt_at: BlockTimestamp,
+    /// The time of the last status change or submission
+    pub updated_at: BlockTimestamp,
+}
+
+impl BroadcastQueueEntry {
+    pub fn new(tx: SignedTransaction, policy: BroadcastPolicy, now: BlockTimestamp) -> Self {
+        Self {
+            tx,
+            policy,
+            status: BroadcastStatus::Pending,
+            attempts: 0,
+            failed_attempts: 0,
+            last_error: None,
+            next_attempt_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Accepted and rejected transactions are not broadcast anymore
+    pub fn is_finished(&self) -> bool {
+        match self.status {
+            BroadcastStatus::Pending | BroadcastStatus::Sent => false,
+            BroadcastStatus::Accepted | BroadcastStatus::Rejected(_) => true,
+        }
+    }
+
+    pub fn is_due(&self, now: BlockTimestamp) -> bool {
+        !self.is_finished() && self.next_attempt_at <= now
+    }
+
+    pub fn is_past_deadline(&self, now: BlockTimestamp) -> bool {
+        self.policy.deadline.is_some_and(|deadline| deadline < now)
+    }
+
+    /// The node has accepted the transaction, resubmit it after `resubmit_delay_secs`
+    /// in case it gets evicted from the mempool
+    pub fn on_sent(&mut self, now: BlockTimestamp, resubmit_delay_secs: u64) {
+        self.attempts = self.attempts.saturating_add(1);
+        self.failed_attempts = 0;
+        self.last_error = None;
+        self.status = BroadcastStatus::Sent;
+        self.next_attempt_at = BlockTimestamp::from_int_seconds(
+            now.as_int_seconds().saturating_add(resubmit_delay_secs),
+        );
+        self.updated_at = now;
+    }
+
+    /// Schedule a retry with an exponential backoff after a failed submission,
+    /// or reject the transaction if the policy's limit of failed attempts is reached
+    pub fn on_failure(&mut self, now: BlockTimestamp, error: String) {
+        self.attempts = self.attempts.saturating_add(1);
+        self.failed_attempts = self.failed_attempts.saturating_add(1);
+        self.updated_at = now;
+
+        if self.failed_attempts >= self.policy.max_failed_attempts {
+            self.status = BroadcastStatus::Rejected(error.clone());
+        } else {
+            let delay = 2u64
+                .checked_pow(self.failed_attempts - 1)
+                .and_then(|multiplier| BROADCAST_INITIAL_RETRY_DELAY_SECS.checked_mul(multiplier))
+                .map_or(BROADCAST_MAX_RETRY_DELAY_SECS, |delay| {
+                    delay.min(BROADCAST_MAX_RETRY_DELAY_SECS)
+                });
+            self.status = BroadcastStatus::Pending;
+            self.next_attempt_at =
+                BlockTimestamp::from_int_seconds(now.as_int_seconds().saturating_add(delay));
+        }
+        self.last_error = Some(error);
+    }
+
+    pub fn on_accepted(&mut self, now: BlockTimestamp) {
+        self.status = BroadcastStatus::Accepted;
+        self.updated_at = now;
+    }
+
+    pub fn on_rejected(&mut self, now: BlockTimestamp, reason: String) {
+        self.status = BroadcastStatus::Rejected(reason);
+        self.updated_at = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::chain::Transaction;
+
+    use super::*;
+
+    fn make_entry(policy: BroadcastPolicy) -> BroadcastQueueEntry {
+        let tx =
+            SignedTransaction::new(Transaction::new(0, vec![], vec![]).unwrap(), vec![]).unwrap();
+        BroadcastQueueEntry::new(tx, policy, BlockTimestamp::from_int_seconds(1000))
+    }
+
+    #[test]
+    fn broadcast_retry_backoff() {
+        let mut entry = make_entry(BroadcastPolicy {
+            max_failed_attempts: 3,
+            deadline: None,
+        });
+        assert!(entry.is_due(BlockTimestamp::from_int_seconds(1000)));
+
+        entry.on_failure(BlockTimestamp::from_int_seconds(1000), "error 1".to_owned());
+        assert_eq!(entry.status, BroadcastStatus::Pending);
+        assert_eq!(
+            entry.next_attempt_at,
+            BlockTimestamp::from_int_seconds(1000 + BROADCAST_INITIAL_RETRY_DELAY_SECS)
+        );
+        assert!(!entry.is_due(BlockTimestamp::from_int_seconds(1001)));
+
+        entry.on_failure(BlockTimestamp::from_int_seconds(1100), "error 2".to_owned());
+        assert_eq!(
+            entry.next_attempt_at,
+            BlockTimestamp::from_int_seconds(1100 + 2 * BROADCAST_INITIAL_RETRY_DELAY_SECS)
+        );
+
+        // A successful submission resets the failures
+        entry.on_sent(BlockTimestamp::from_int_seconds(1200), 300);
+        assert_eq!(entry.status, BroadcastStatus::Sent);
+        assert_eq!(entry.failed_attempts, 0);
+        assert_eq!(entry.last_error, None);
+        assert_eq!(
+            entry.next_attempt_at,
+            BlockTimestamp::from_int_seconds(1500)
+        );
+
+        for i in 0..3 {
+            assert!(!entry.is_finished());
+            entry.on_failure(BlockTimestamp::from_int_seconds(2000), format!("error {i}"));
+        }
+        assert_eq!(entry.attempts, 6);
+        assert_eq!(
+            entry.status,
+            BroadcastStatus::Rejected("error 2".to_owned())
+        );
+        assert!(!entry.is_due(BlockTimestamp::from_int_seconds(100000)));
+    }
+
+    #[test]
+    fn broadcast_deadline() {
+        let entry = make_entry(BroadcastPolicy {
+            max_failed_attempts: 3,
+            deadline: Some(BlockTimestamp::from_int_seconds(2000)),
+        });
+        assert!(!entry.is_past_deadline(BlockTimestamp::from_int_seconds(2000)));
+        assert!(entry.is_past_deadline(BlockTimestamp::from_int_seconds(2001)));
+
+        let entry = make_entry(BroadcastPolicy::default());
+        assert!(!entry.is_past_deadline(BlockTimestamp::from_int_seconds(u64::MAX)));
+    }
+}
@@ -0,0 +1,27 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crypto::key::hdkd::u31::U31;
+use serialization::{Decode, Encode};
+
+/// A transaction created by the wallet to move funds from one of its accounts to another.
+///
+/// It's recorded so that the transaction appears as a transfer in the history of both accounts
+/// instead of as a payment sent by one of them and received by the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub struct InternalTransfer {
+    pub from_account: U31,
+    pub to_account: U31,
+}
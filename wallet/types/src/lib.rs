@@ -15,19 +15,23 @@
 
 pub mod account_id;
 pub mod account_info;
+pub mod address_book;
+pub mod broadcast_queue;
 pub mod chain_info;
+pub mod internal_transfer;
 pub mod keys;
+pub mod multisig_account;
 pub mod seed_phrase;
 pub mod signature_status;
+pub mod signing_log;
+pub mod token_trust;
 pub mod utxo_types;
 pub mod wallet_tx;
 pub mod wallet_type;
+pub mod watch_only_export;
 pub mod with_locked;
 
-pub use account_id::{
-    AccountDerivationPathId, AccountId, AccountKeyPurposeId, AccountWalletCreatedTxId,
-    AccountWalletTxId,
-};
+pub use account_id::{AccountDerivationPathId, AccountId, AccountKeyPurposeId, AccountWalletTxId};
 pub use account_info::AccountInfo;
 pub use keys::{KeyPurpose, KeychainUsageState, RootKeys};
 pub use wallet_tx::{BlockInfo, WalletTx};
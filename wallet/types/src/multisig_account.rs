@@ -0,0 +1,222 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::num::NonZeroU8;
+
+use common::chain::{
+    classic_multisig::{ClassicMultisigChallenge, ClassicMultisigChallengeError},
+    ChainConfig,
+};
+use crypto::key::{
+    extended::ExtendedPublicKey,
+    hdkd::{
+        child_number::ChildNumber,
+        derivable::{Derivable, DerivationError},
+        u31::U31,
+    },
+};
+use serialization::{Decode, Encode};
+
+use crate::KeyPurpose;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum MultisigAccountDescriptorError {
+    #[error("Invalid multisig policy: {0}")]
+    InvalidPolicy(#[from] ClassicMultisigChallengeError),
+    #[error("The cosigner keys are not unique and sorted")]
+    CosignerKeysNotCanonical,
+    #[error("Key derivation error: {0}")]
+    Derivation(#[from] DerivationError),
+}
+
+/// How the public keys of the multisig addresses are derived from the cosigners' account keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum MultisigDerivationTemplate {
+    /// `<account key>/<key purpose>/<address index>`, the same as for the regular account addresses
+    #[codec(index = 0)]
+    KeyPurposeAndIndex,
+}
+
+/// The coordination data of a multisig account shared between the cosigners' wallets.
+///
+/// The descriptor contains everything needed to derive the same sequence of multisig addresses
+/// in every wallet: the account public keys of all the cosigners, the m-of-n policy and
+/// the derivation template. The cosigner keys are kept sorted, so the same set of cosigners
+/// always produces the same descriptor regardless of the order in which the keys were supplied.
+/// Like `ClassicMultisigChallenge`, an invalid object can still be constructed with
+/// deserialization, so `is_valid` must be checked for imported descriptors.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct MultisigAccountDescriptor {
+    min_required_signatures: u8,
+    cosigner_keys: Vec<ExtendedPublicKey>,
+    derivation_template: MultisigDerivationTemplate,
+}
+
+impl MultisigAccountDescriptor {
+    pub fn new(
+        chain_config: &ChainConfig,
+        min_required_signatures: NonZeroU8,
+        mut cosigner_keys: Vec<ExtendedPublicKey>,
+        derivation_template: MultisigDerivationTemplate,
+    ) -> Result<Self, MultisigAccountDescriptorError> {
+        cosigner_keys.sort();
+        let res = Self {
+            min_required_signatures: min_required_signatures.get(),
+            cosigner_keys,
+            derivation_template,
+        };
+        res.is_valid(chain_config)?;
+        Ok(res)
+    }
+
+    pub fn is_valid(
+        &self,
+        chain_config: &ChainConfig,
+    ) -> Result<(), MultisigAccountDescriptorError> {
+        if !self.cosigner_keys.windows(2).all(|keys| keys[0] < keys[1]) {
+            return Err(MultisigAccountDescriptorError::CosignerKeysNotCanonical);
+        }
+
+        // Deriving the first address checks the policy against the chain rules
+        // and that the keys are usable for derivation
+        self.challenge(chain_config, KeyPurpose::ReceiveFunds, U31::ZERO)?;
+
+        Ok(())
+    }
+
+    pub fn min_required_signatures(&self) -> u8 {
+        self.min_required_signatures
+    }
+
+    pub fn cosigner_keys(&self) -> &[ExtendedPublicKey] {
+        &self.cosigner_keys
+    }
+
+    pub fn derivation_template(&self) -> MultisigDerivationTemplate {
+        self.derivation_template
+    }
+
+    pub fn has_cosigner_key(&self, key: &ExtendedPublicKey) -> bool {
+        self.cosigner_keys.binary_search(key).is_ok()
+    }
+
+    /// Derive the multisig challenge of the address with the specified purpose and index
+    pub fn challenge(
+        &self,
+        chain_config: &ChainConfig,
+        purpose: KeyPurpose,
+        address_index: U31,
+    ) -> Result<ClassicMultisigChallenge, MultisigAccountDescriptorError> {
+        let public_keys = self
+            .cosigner_keys
+            .iter()
+            .map(|account_key| match self.derivation_template {
+                MultisigDerivationTemplate::KeyPurposeAndIndex => Ok(account_key
+                    .clone()
+                    .derive_child(purpose.get_deterministic_index())?
+                    .derive_child(ChildNumber::from_normal(address_index))?
+                    .into_public_key()),
+            })
+            .collect::<Result<Vec<_>, DerivationError>>()?;
+
+        let min_required_signatures = NonZeroU8::new(self.min_required_signatures)
+            .ok_or(ClassicMultisigChallengeError::MinRequiredSignaturesIsZero)?;
+
+        Ok(ClassicMultisigChallenge::new(
+            chain_config,
+            min_required_signatures,
+            public_keys,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::chain::config::create_regtest;
+    use crypto::key::extended::{ExtendedKeyKind, ExtendedPrivateKey};
+    use randomness::Rng;
+    use rstest::rstest;
+    use serialization::DecodeAll;
+    use test_utils::random::{make_seedable_rng, Seed};
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn deterministic_descriptor(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let chain_config = create_regtest();
+        let keys = (0..3)
+            .map(|_| {
+                ExtendedPrivateKey::new_from_rng(&mut rng, ExtendedKeyKind::Secp256k1Schnorr).1
+            })
+            .collect::<Vec<_>>();
+        let min_sigs = NonZeroU8::new(2).unwrap();
+        let template = MultisigDerivationTemplate::KeyPurposeAndIndex;
+
+        let descriptor =
+            MultisigAccountDescriptor::new(&chain_config, min_sigs, keys.clone(), template)
+                .unwrap();
+        let reversed_keys = keys.iter().rev().cloned().collect();
+        let other_descriptor =
+            MultisigAccountDescriptor::new(&chain_config, min_sigs, reversed_keys, template)
+                .unwrap();
+        assert_eq!(descriptor, other_descriptor);
+        assert!(keys.iter().all(|key| descriptor.has_cosigner_key(key)));
+
+        let decoded =
+            MultisigAccountDescriptor::decode_all(&mut descriptor.encode().as_slice()).unwrap();
+        assert_eq!(decoded, descriptor);
+        assert_eq!(decoded.is_valid(&chain_config), Ok(()));
+
+        let index = U31::from_u32(rng.gen_range(0..1000)).unwrap();
+        let receive = descriptor.challenge(&chain_config, KeyPurpose::ReceiveFunds, index).unwrap();
+        assert_eq!(
+            receive,
+            other_descriptor
+                .challenge(&chain_config, KeyPurpose::ReceiveFunds, index)
+                .unwrap()
+        );
+        assert_eq!(receive.min_required_signatures(), 2);
+        assert_eq!(receive.public_keys().len(), 3);
+        assert_ne!(
+            receive,
+            descriptor.challenge(&chain_config, KeyPurpose::Change, index).unwrap()
+        );
+
+        let not_canonical = MultisigAccountDescriptor {
+            min_required_signatures: 2,
+            cosigner_keys: vec![keys[0].clone(), keys[0].clone()],
+            derivation_template: template,
+        };
+        assert_eq!(
+            not_canonical.is_valid(&chain_config),
+            Err(MultisigAccountDescriptorError::CosignerKeysNotCanonical)
+        );
+
+        assert_eq!(
+            MultisigAccountDescriptor::new(
+                &chain_config,
+                NonZeroU8::new(4).unwrap(),
+                keys,
+                template
+            ),
+            Err(MultisigAccountDescriptorError::InvalidPolicy(
+                ClassicMultisigChallengeError::MoreRequiredSignaturesThanPublicKeys(4, 3)
+            ))
+        );
+    }
+}
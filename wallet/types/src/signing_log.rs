@@ -0,0 +1,124 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    chain::{
+        block::timestamp::BlockTimestamp, output_value::OutputValue, tokens::TokenId, Destination,
+        Transaction, TxOutput,
+    },
+    primitives::{Amount, Id, Idable},
+};
+use crypto::key::hdkd::u31::U31;
+use serialization::{Decode, Encode};
+
+/// What has requested the signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum SigningRequester {
+    /// A transaction composed by the wallet itself, e.g. by one of the send commands
+    #[codec(index = 0)]
+    Wallet,
+    /// A transaction supplied by the caller to be signed
+    #[codec(index = 1)]
+    External,
+}
+
+/// The value moved by a single transaction output
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct SigningLogOutput {
+    /// `None` if the value is not sent to an address, e.g. for burned coins or delegated stake
+    pub destination: Option<Destination>,
+    /// The token, or `None` for coins
+    pub token_id: Option<TokenId>,
+    pub amount: Amount,
+}
+
+impl SigningLogOutput {
+    fn from_output_value(value: &OutputValue, destination: Option<&Destination>) -> Option<Self> {
+        let (token_id, amount) = match value {
+            OutputValue::Coin(amount) => (None, *amount),
+            OutputValue::TokenV1(token_id, amount) => (Some(*token_id), *amount),
+            OutputValue::TokenV0(_) => return None,
+        };
+        Some(Self {
+            destination: destination.cloned(),
+            token_id,
+            amount,
+        })
+    }
+
+    fn from_tx_output(output: &TxOutput) -> Option<Self> {
+        match output {
+            TxOutput::Transfer(value, destination)
+            | TxOutput::LockThenTransfer(value, destination, _) => {
+                Self::from_output_value(value, Some(destination))
+            }
+            TxOutput::Burn(value) => Self::from_output_value(value, None),
+            TxOutput::Htlc(value, htlc) => Self::from_output_value(value, Some(&htlc.spend_key)),
+            TxOutput::AnyoneCanTake(order) => Self::from_output_value(order.give(), None),
+            TxOutput::CreateStakePool(_, pool_data) => Some(Self {
+                destination: None,
+                token_id: None,
+                amount: pool_data.pledge(),
+            }),
+            TxOutput::DelegateStaking(amount, _) => Some(Self {
+                destination: None,
+                token_id: None,
+                amount: *amount,
+            }),
+            TxOutput::IssueNft(token_id, _, destination) => Some(Self {
+                destination: Some(destination.clone()),
+                token_id: Some(*token_id),
+                amount: Amount::from_atoms(1),
+            }),
+            TxOutput::ProduceBlockFromStake(_, _)
+            | TxOutput::CreateDelegationId(_, _)
+            | TxOutput::IssueFungibleToken(_)
+            | TxOutput::DataDeposit(_) => None,
+        }
+    }
+}
+
+/// A record of the wallet having signed a transaction, kept for auditing purposes.
+/// The entries are stored encrypted with the wallet password and are never modified or removed.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct SigningLogEntry {
+    pub tx_id: Id<Transaction>,
+    /// The system time of the signing
+    pub timestamp: BlockTimestamp,
+    pub account_index: U31,
+    pub requester: SigningRequester,
+    /// Whether all the inputs had valid signatures after the signing
+    pub fully_signed: bool,
+    pub outputs: Vec<SigningLogOutput>,
+}
+
+impl SigningLogEntry {
+    pub fn new(
+        tx: &Transaction,
+        timestamp: BlockTimestamp,
+        account_index: U31,
+        requester: SigningRequester,
+        fully_signed: bool,
+    ) -> Self {
+        Self {
+            tx_id: tx.get_id(),
+            timestamp,
+            account_index,
+            requester,
+            fully_signed,
+            outputs: tx.outputs().iter().filter_map(SigningLogOutput::from_tx_output).collect(),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+// Copyright (c) 2023 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use rpc_description::HasValueHint;
+use serialization::{Decode, Encode};
+
+/// The user's decision about a token, persisted per wallet.
+///
+/// Anyone can send tokens to a wallet address, so the tokens that are neither issued nor sent
+/// by the wallet and that have no decision recorded are reported as unverified.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Encode,
+    Decode,
+    serde::Serialize,
+    serde::Deserialize,
+    HasValueHint,
+)]
+pub enum TokenTrust {
+    /// The token is shown together with the other tokens in the balance
+    #[codec(index = 0)]
+    Trusted,
+    /// The token is not shown in the balance at all
+    #[codec(index = 1)]
+    Hidden,
+}
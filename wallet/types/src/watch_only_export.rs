@@ -0,0 +1,77 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    chain::{tokens::TokenId, Destination, PoolId},
+    primitives::{BlockHeight, H256},
+};
+use crypto::kdf::KdfChallenge;
+use serialization::{Decode, Encode};
+use utils::maybe_encrypted::MaybeEncrypted;
+
+use crate::{
+    account_info::{AccountVrfKeys, StandaloneMultisig, StandaloneWatchOnlyKey, WatchedPool},
+    chain_info::ChainInfo,
+    token_trust::TokenTrust,
+    AccountInfo, KeychainUsageState, WalletTx,
+};
+
+/// The public data of a single account in a watch-only export
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct WatchOnlyAccount {
+    pub info: AccountInfo,
+    pub vrf_keys: AccountVrfKeys,
+    pub receive_usage: KeychainUsageState,
+    pub change_usage: KeychainUsageState,
+    pub vrf_usage: KeychainUsageState,
+    pub unconfirmed_tx_counter: u64,
+    /// Includes the public keys of the standalone private keys, which become watch-only
+    /// keys in the replica
+    pub standalone_watch_only_keys: Vec<(Destination, StandaloneWatchOnlyKey)>,
+    pub standalone_multisig_keys: Vec<(Destination, StandaloneMultisig)>,
+    pub watched_pools: Vec<(PoolId, WatchedPool)>,
+    pub transactions: Vec<WalletTx>,
+}
+
+/// A snapshot of everything a wallet can see, without any of its private keys,
+/// the seed phrase or the signing log.
+///
+/// If `since_height` is set, the snapshot is an incremental update that contains only the
+/// transactions confirmed above that height and the unconfirmed ones; all the other data is
+/// always exported in full, as it is small.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct WatchOnlyExport {
+    /// The id of the exported wallet, used to check that an update is applied to the right replica
+    pub wallet_id: H256,
+    pub chain_info: ChainInfo,
+    pub lookahead_size: u32,
+    pub since_height: Option<BlockHeight>,
+    pub accounts: Vec<WatchOnlyAccount>,
+    pub token_trust: Vec<(TokenId, TokenTrust)>,
+}
+
+impl WatchOnlyExport {
+    pub fn is_incremental(&self) -> bool {
+        self.since_height.is_some()
+    }
+}
+
+/// The format of the exported file; the export is encrypted with a key derived from
+/// a passphrase using the KDF challenge stored next to it
+#[derive(Encode, Decode)]
+pub struct EncryptedWatchOnlyExport {
+    pub kdf_challenge: KdfChallenge,
+    pub data: MaybeEncrypted<WatchOnlyExport>,
+}
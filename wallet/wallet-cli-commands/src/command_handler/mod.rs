@@ -20,11 +20,12 @@ use std::{fmt::Write, str::FromStr};
 use common::{
     address::Address,
     chain::{
+        block::timestamp::BlockTimestamp,
         config::checkpoints_data::print_block_heights_ids_as_checkpoints_data,
         partially_signed_transaction::PartiallySignedTransaction, ChainConfig, Destination,
         SignedTransaction, TxOutput, UtxoOutPoint,
     },
-    primitives::H256,
+    primitives::{BlockHeight, H256},
     text_summary::TextSummary,
 };
 use crypto::key::hdkd::u31::U31;
@@ -34,12 +35,12 @@ use node_comm::node_traits::NodeInterface;
 use serialization::{hex::HexEncode, hex_encoded::HexEncoded};
 use utils::qrcode::{QrCode, QrCodeError};
 use wallet::version::get_version;
-use wallet_controller::types::GenericTokenTransfer;
+use wallet_controller::types::{GenericTokenTransfer, TransactionPackage};
 use wallet_rpc_client::wallet_rpc_traits::{PartialOrSignedTx, WalletInterface};
 use wallet_rpc_lib::types::{
     Balances, ComposedTransaction, ControllerConfig, MnemonicInfo, NewTransaction, NftMetadata,
-    RpcInspectTransaction, RpcSignatureStats, RpcSignatureStatus, RpcStandaloneAddressDetails,
-    RpcValidatedSignatures, TokenMetadata,
+    RpcChangePolicy, RpcInspectTransaction, RpcSignatureStats, RpcSignatureStatus,
+    RpcStandaloneAddressDetails, RpcValidatedSignatures, SentTransaction, TokenMetadata,
 };
 
 use crate::{
@@ -51,8 +52,10 @@ use self::local_state::WalletWithState;
 
 use super::{
     helper_types::{
-        format_delegation_info, format_pool_info, parse_coin_output, parse_token_supply,
-        parse_utxo_outpoint, CliForceReduce, CliUtxoState,
+        format_broadcast_queue_entry_info, format_delegation_info, format_pool_info,
+        format_scheduled_payment_info, format_utxo_verification_info, format_watched_pool_info,
+        parse_coin_output, parse_recipient, parse_token_supply, parse_utxo_outpoint,
+        CliForceReduce, CliSigHashType, CliUtxoState,
     },
     ColdWalletCommand, ConsoleCommand, WalletCommand,
 };
@@ -124,6 +127,15 @@ where
         ConsoleCommand::Print(status_text)
     }
 
+    pub fn sent_tx_submitted_command(sent_tx: SentTransaction) -> ConsoleCommand {
+        let status_text = format!(
+            "The transaction was submitted successfully with ID:\n{}\nFee paid: {}",
+            id_to_hex_string(*sent_tx.tx_id.as_hash()),
+            sent_tx.fee.decimal()
+        );
+        ConsoleCommand::Print(status_text)
+    }
+
     async fn non_empty_wallet<N: NodeInterface>(&mut self) -> Result<&W, WalletCliCommandError<N>> {
         self.wallet.get_wallet_with_acc().await.map(|(w, _)| w)
     }
@@ -192,6 +204,8 @@ where
                 wallet_path,
                 encryption_password,
                 force_change_wallet_type,
+                deep_verify,
+                deep_verify_sample_size,
             } => {
                 self.wallet()
                     .await?
@@ -203,9 +217,38 @@ where
                     .await?;
                 self.wallet.update_wallet::<N>().await;
 
+                let print_message = if deep_verify {
+                    let wallet = self.wallet().await?;
+                    wallet.sync().await?;
+                    let info = wallet.verify_utxos(deep_verify_sample_size, false).await?;
+                    format!(
+                        "Wallet loaded successfully\n{}",
+                        format_utxo_verification_info(info)
+                    )
+                } else {
+                    "Wallet loaded successfully".to_owned()
+                };
+
+                Ok(ConsoleCommand::SetStatus {
+                    status: self.repl_status().await?,
+                    print_message,
+                })
+            }
+
+            WalletManagementCommand::CreateWalletFromWatchOnlyExport {
+                wallet_path,
+                data,
+                passphrase,
+            } => {
+                self.wallet()
+                    .await?
+                    .create_wallet_from_watch_only_export(wallet_path, data, passphrase)
+                    .await?;
+                self.wallet.update_wallet::<N>().await;
+
                 Ok(ConsoleCommand::SetStatus {
                     status: self.repl_status().await?,
-                    print_message: "Wallet loaded successfully".to_owned(),
+                    print_message: "Watch-only wallet created successfully".to_owned(),
                 })
             }
 
@@ -345,6 +388,27 @@ where
                 ))
             }
 
+            ColdWalletCommand::ExportWatchOnly {
+                passphrase,
+                since_height,
+            } => {
+                let data = self
+                    .non_empty_wallet()
+                    .await?
+                    .export_watch_only(passphrase, since_height.map(BlockHeight::new))
+                    .await?;
+
+                Ok(ConsoleCommand::Print(data))
+            }
+
+            ColdWalletCommand::ApplyWatchOnlyUpdate { data, passphrase } => {
+                self.non_empty_wallet().await?.apply_watch_only_update(data, passphrase).await?;
+
+                Ok(ConsoleCommand::Print(
+                    "Success. The watch-only wallet has been updated.".to_owned(),
+                ))
+            }
+
             ColdWalletCommand::AddressQRCode { address } => {
                 let addr: Address<Destination> = Address::from_string(chain_config, address)
                     .map_err(|_| {
@@ -389,11 +453,13 @@ where
                         "Index",
                         "Address",
                         "Is used in transaction history",
+                        "Label",
                     ]);
 
                     addresses_table.extend(addresses_with_usage.into_iter().map(|info| {
                         let is_used = if info.used { "Yes" } else { "No" };
-                        prettytable::row![info.index, info.address, is_used]
+                        let label = info.label.unwrap_or_default();
+                        prettytable::row![info.index, info.address, is_used, label]
                     }));
 
                     addresses_table
@@ -509,11 +575,13 @@ where
                         "Index",
                         "Address",
                         "Is used in transaction history",
+                        "Used by pools",
                     ]);
 
                     addresses_table.extend(addresses_with_usage.into_iter().map(|info| {
                         let is_used = if info.used { "Yes" } else { "No" };
-                        prettytable::row![info.child_number, info.vrf_public_key, is_used]
+                        let pool_ids = info.pool_ids.iter().map(|id| id.as_str()).join("\n");
+                        prettytable::row![info.child_number, info.vrf_public_key, is_used, pool_ids]
                     }));
 
                     addresses_table
@@ -524,7 +592,16 @@ where
             ColdWalletCommand::GetLegacyVrfPublicKey => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
                 let legacy_pubkey = wallet.get_legacy_vrf_public_key(selected_account).await?;
-                Ok(ConsoleCommand::Print(legacy_pubkey.vrf_public_key))
+                let output = if legacy_pubkey.pool_ids.is_empty() {
+                    legacy_pubkey.vrf_public_key
+                } else {
+                    format!(
+                        "{}\nUsed by pools: {}",
+                        legacy_pubkey.vrf_public_key,
+                        legacy_pubkey.pool_ids.iter().map(|id| id.as_str()).join(", ")
+                    )
+                };
+                Ok(ConsoleCommand::Print(output))
             }
 
             ColdWalletCommand::SignRawTransaction { transaction } => {
@@ -577,6 +654,47 @@ where
                 Ok(ConsoleCommand::Print(output_str))
             }
 
+            ColdWalletCommand::SignTransactionPackage { package } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let result =
+                    wallet.sign_transaction_package(selected_account, package, self.config).await?;
+
+                let hex = result.hex;
+                let qr_code_string = qrcode_or_error_string(&hex);
+
+                let output_str = if result.is_complete {
+                    format!(
+                        "The transaction package has been fully signed. \
+                         You can use the command `transaction-package-submit` in the wallet that created it \
+                         to broadcast it to the network. Pass the following data to that wallet:\n\n{hex}\n\n\
+                         Or scan the Qr code with it:\n\n{qr_code_string}"
+                    )
+                } else {
+                    let prev_sigs = result
+                        .previous_signatures
+                        .iter()
+                        .enumerate()
+                        .map(format_signature_status)
+                        .join(", ");
+                    let current_sigs = result
+                        .current_signatures
+                        .iter()
+                        .enumerate()
+                        .map(format_signature_status)
+                        .join(", ");
+
+                    format!(
+                        "Not all transaction inputs have been signed. This wallet does not have all the keys for that.\n\
+                         The signatures states before signing were:\n{prev_sigs}\n\
+                         and the current signature states are:\n{current_sigs}\n\
+                         Pass the following string into the wallet that has appropriate keys for the inputs to sign what is left:\n\n{hex}\n\n\
+                         Or scan the Qr code with it:\n\n{qr_code_string}"
+                    )
+                };
+
+                Ok(ConsoleCommand::Print(output_str))
+            }
+
             ColdWalletCommand::SignChallegeHex {
                 message: challenge,
                 address,
@@ -771,6 +889,16 @@ where
                 })
             }
 
+            WalletCommand::SetAddressLabel { address, label } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                wallet.set_address_label(selected_account, address, label).await?;
+
+                Ok(ConsoleCommand::SetStatus {
+                    status: self.repl_status().await?,
+                    print_message: "Success, the label has been changed.".into(),
+                })
+            }
+
             WalletCommand::StandaloneAddressLabelRename { address, label } => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
                 wallet.standalone_address_label_rename(selected_account, address, label).await?;
@@ -804,6 +932,16 @@ where
                 })
             }
 
+            WalletCommand::RemoveStandaloneKey { address } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                wallet.remove_standalone_address(selected_account, address).await?;
+
+                Ok(ConsoleCommand::SetStatus {
+                    status: self.repl_status().await?,
+                    print_message: "Success, the address has been removed from the account.".into(),
+                })
+            }
+
             WalletCommand::AddStandalonePrivateKey {
                 hex_private_key,
                 label,
@@ -827,6 +965,35 @@ where
                 })
             }
 
+            WalletCommand::ImportStandalonePrivateKey {
+                private_key,
+                label,
+                no_rescan,
+            } => {
+                let no_rescan = no_rescan.unwrap_or(false);
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                wallet
+                    .import_standalone_private_key(selected_account, private_key, label, no_rescan)
+                    .await?;
+
+                let output = if no_rescan {
+                    "Success, the private key has been imported to the account."
+                } else {
+                    "Success, the private key has been imported to the account.\nRescanning the blockchain to detect balance in added new addresses"
+                };
+
+                Ok(ConsoleCommand::SetStatus {
+                    status: self.repl_status().await?,
+                    print_message: output.into(),
+                })
+            }
+
+            WalletCommand::ExportPrivateKey { address, confirm } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let key = wallet.export_private_key(selected_account, address, confirm).await?;
+                Ok(ConsoleCommand::Print(key))
+            }
+
             WalletCommand::AddStandaloneMultisig {
                 min_required_signatures,
                 public_keys,
@@ -877,6 +1044,117 @@ where
                 ))
             }
 
+            WalletCommand::ListStandaloneAddressUtxo {
+                address,
+                utxo_type,
+                with_locked,
+                utxo_states,
+            } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let utxos = wallet
+                    .get_standalone_address_utxos(
+                        selected_account,
+                        address,
+                        utxo_type.to_wallet_types(),
+                        CliUtxoState::to_wallet_states(utxo_states),
+                        with_locked.to_wallet_type(),
+                    )
+                    .await
+                    .map(serde_json::Value::Array)?;
+                Ok(ConsoleCommand::Print(
+                    serde_json::to_string(&utxos).expect("ok"),
+                ))
+            }
+
+            WalletCommand::AccountExtendedPublicKey => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let key = wallet.account_extended_public_key(selected_account).await?;
+                Ok(ConsoleCommand::Print(key.to_string()))
+            }
+
+            WalletCommand::ExportAccountExtendedPublicKey => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let key = wallet.export_account_extended_public_key(selected_account).await?;
+                Ok(ConsoleCommand::Print(key))
+            }
+
+            WalletCommand::ImportExtendedPublicKey {
+                extended_public_key,
+                address_count,
+                label,
+                no_rescan,
+            } => {
+                let no_rescan = no_rescan.unwrap_or(false);
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let addresses = wallet
+                    .import_extended_public_key(
+                        selected_account,
+                        extended_public_key,
+                        address_count,
+                        label,
+                        no_rescan,
+                    )
+                    .await?;
+
+                let addresses = addresses.join("\n");
+                let output = if no_rescan {
+                    format!("Success. The following addresses have been added to the account\n{addresses}")
+                } else {
+                    format!("Success. The following addresses have been added to the account\n{addresses}\nRescanning the blockchain to detect balance in added new addresses")
+                };
+
+                Ok(ConsoleCommand::SetStatus {
+                    status: self.repl_status().await?,
+                    print_message: output,
+                })
+            }
+
+            WalletCommand::CreateMultisigAccountDescriptor {
+                min_required_signatures,
+                cosigner_keys,
+            } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let descriptor = wallet
+                    .create_multisig_account_descriptor(
+                        selected_account,
+                        min_required_signatures,
+                        cosigner_keys,
+                    )
+                    .await?;
+                Ok(ConsoleCommand::Print(descriptor.to_string()))
+            }
+
+            WalletCommand::ImportMultisigAccount {
+                descriptor,
+                address_count,
+                label,
+                no_rescan,
+            } => {
+                let no_rescan = no_rescan.unwrap_or(false);
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let addresses = wallet
+                    .import_multisig_account(
+                        selected_account,
+                        descriptor,
+                        address_count,
+                        label,
+                        no_rescan,
+                    )
+                    .await?;
+
+                let addresses = addresses.join("\n");
+                let output = if no_rescan {
+                    format!("Success. The following multisig account addresses have been added to the account\n{addresses}")
+                } else {
+                    format!("Success. The following multisig account addresses have been added to the account\n{addresses}\nRescanning the blockchain to detect balance in added new addresses")
+                };
+
+                Ok(ConsoleCommand::SetStatus {
+                    status: self.repl_status().await?,
+                    print_message: output,
+                })
+            }
+
             WalletCommand::SelectAccount { account_index } => {
                 self.set_selected_account(account_index).await?;
 
@@ -919,6 +1197,23 @@ where
                 }
             }
 
+            WalletCommand::StakingCalculator {
+                pledge,
+                pool_balance,
+            } => {
+                let estimate =
+                    self.wallet().await?.staking_calculator(pledge, pool_balance).await?;
+                Ok(ConsoleCommand::Print(format!(
+                    "Effective pool balance: {}\nBlock reward: {}\nExpected blocks per day: {}\nExpected blocks per month: {}\nAnnual return: {}%\n(based on the difficulty at height {})",
+                    estimate.effective_pool_balance.decimal(),
+                    estimate.block_reward.decimal(),
+                    estimate.expected_blocks_per_day,
+                    estimate.expected_blocks_per_month,
+                    estimate.annual_return_percent,
+                    estimate.block_height,
+                )))
+            }
+
             WalletCommand::SubmitBlock { block } => {
                 self.wallet().await?.submit_block(block).await?;
                 Ok(ConsoleCommand::Print(
@@ -945,6 +1240,7 @@ where
             WalletCommand::TransactionCompose {
                 outputs,
                 utxos,
+                sighash_types,
                 only_transaction,
             } => {
                 let outputs: Vec<TxOutput> = outputs
@@ -958,10 +1254,19 @@ where
                     .collect::<Result<Vec<_>, WalletCliCommandError<N>>>(
                 )?;
 
+                let sighash_types = (!sighash_types.is_empty())
+                    .then(|| sighash_types.into_iter().map(CliSigHashType::to_rpc_type).collect());
+
                 let ComposedTransaction { hex, fees } = self
                     .non_empty_wallet()
                     .await?
-                    .compose_transaction(input_utxos, outputs, None, only_transaction)
+                    .compose_transaction(
+                        input_utxos,
+                        outputs,
+                        None,
+                        sighash_types,
+                        only_transaction,
+                    )
                     .await?;
                 let mut output = format!("The hex encoded transaction is:\n{hex}\n");
 
@@ -1107,6 +1412,45 @@ where
                 Ok(Self::new_tx_submitted_command(new_tx))
             }
 
+            WalletCommand::RotateTokenAuthority {
+                token_id,
+                address,
+                dry_run,
+                force,
+            } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let rotation = wallet
+                    .rotate_token_authority(
+                        selected_account,
+                        token_id,
+                        address,
+                        dry_run,
+                        force,
+                        self.config,
+                    )
+                    .await?;
+
+                let control_text = match &rotation.new_authority_account {
+                    Some(account) => format!(
+                        "The new authority is controlled by account {} of this wallet.",
+                        account.0
+                    ),
+                    None => "WARNING: the new authority is not controlled by this wallet, the control over the token will be lost.".to_owned(),
+                };
+                let status_text = match rotation.tx_id {
+                    Some(tx_id) => format!(
+                        "{control_text}\nThe transaction was submitted successfully with ID:\n{}",
+                        id_to_hex_string(*tx_id.as_hash())
+                    ),
+                    None => format!(
+                        "Dry run: the authority of the token would change from {} to {}.\n{control_text}",
+                        rotation.current_authority, rotation.new_authority
+                    ),
+                };
+
+                Ok(ConsoleCommand::Print(status_text))
+            }
+
             WalletCommand::ChangeTokenMetadataUri {
                 token_id,
                 metadata_uri,
@@ -1136,28 +1480,74 @@ where
                 Ok(ConsoleCommand::Print("Success".to_owned()))
             }
 
+            WalletCommand::SyncStatus => {
+                let status = self.non_empty_wallet().await?.sync_status().await?;
+                let last_synced_at = status
+                    .last_synced_at
+                    .map_or_else(|| "never".to_owned(), |time| time.to_string());
+                Ok(ConsoleCommand::Print(format!(
+                    "Best synced block: {} at height {}\nMedian time: {}\nLast synced at: {}\nSync in progress: {}",
+                    status.best_synced_block.id.hex_encode(),
+                    status.best_synced_block.height,
+                    status.median_time,
+                    last_synced_at,
+                    status.sync_in_progress,
+                )))
+            }
+
+            WalletCommand::VerifyUtxos {
+                sample_size,
+                repair,
+            } => {
+                let info = self.non_empty_wallet().await?.verify_utxos(sample_size, repair).await?;
+                Ok(ConsoleCommand::Print(format_utxo_verification_info(info)))
+            }
+
+            WalletCommand::ExportSigningLog { from, to } => {
+                let entries = self
+                    .non_empty_wallet()
+                    .await?
+                    .export_signing_log(
+                        from.map(BlockTimestamp::from_int_seconds),
+                        to.map(BlockTimestamp::from_int_seconds),
+                    )
+                    .await?;
+                Ok(ConsoleCommand::Print(
+                    serde_json::to_string(&entries).expect("ok"),
+                ))
+            }
+
             WalletCommand::GetBalance {
                 utxo_states,
                 with_locked,
             } => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
-                let (coins, tokens) = wallet
+                let balances = wallet
                     .get_balance(
                         selected_account,
                         CliUtxoState::to_wallet_states(utxo_states),
                         with_locked.to_wallet_type(),
                     )
-                    .await?
-                    .into_coins_and_tokens();
+                    .await?;
 
-                let coins = coins.decimal();
+                let coins = balances.coins().decimal();
                 let mut output = format!("Coins amount: {coins}\n");
 
-                for (token_id, amount) in tokens {
+                for (token_id, amount) in balances.tokens() {
                     let amount = amount.decimal();
                     writeln!(&mut output, "Token: {token_id} amount: {amount}")
                         .expect("Writing to a memory buffer should not fail");
                 }
+
+                if !balances.unverified_tokens().is_empty() {
+                    writeln!(&mut output, "Unverified tokens:")
+                        .expect("Writing to a memory buffer should not fail");
+                    for (token_id, amount) in balances.unverified_tokens() {
+                        let amount = amount.decimal();
+                        writeln!(&mut output, "Token: {token_id} amount: {amount}")
+                            .expect("Writing to a memory buffer should not fail");
+                    }
+                }
                 output.pop();
 
                 Ok(ConsoleCommand::Print(output))
@@ -1183,6 +1573,130 @@ where
                 ))
             }
 
+            WalletCommand::SetDustProtection {
+                min_coin_amount,
+                flag_unknown_tokens,
+            } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                wallet
+                    .set_dust_protection(selected_account, min_coin_amount, flag_unknown_tokens)
+                    .await?;
+                Ok(ConsoleCommand::Print("Success".to_owned()))
+            }
+
+            WalletCommand::SetChangePolicy { policy, address } => {
+                let change_policy = policy.to_rpc_change_policy(address)?;
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                wallet.set_change_policy(selected_account, change_policy).await?;
+                Ok(ConsoleCommand::Print("Success".to_owned()))
+            }
+
+            WalletCommand::ShowChangePolicy => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let change_policy = match wallet.get_change_policy(selected_account).await? {
+                    RpcChangePolicy::NewAddress => "new-address".to_owned(),
+                    RpcChangePolicy::ReuseInputAddress => "reuse-input-address".to_owned(),
+                    RpcChangePolicy::ExternalAddress(address) => {
+                        format!("external {}", address.as_str())
+                    }
+                };
+                Ok(ConsoleCommand::Print(change_policy))
+            }
+
+            WalletCommand::ListDustUtxos => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let utxos =
+                    wallet.get_dust_utxos(selected_account).await.map(serde_json::Value::Array)?;
+                Ok(ConsoleCommand::Print(
+                    serde_json::to_string(&utxos).expect("ok"),
+                ))
+            }
+
+            WalletCommand::ReleaseDustUtxos { utxos } => {
+                let utxos: Vec<UtxoOutPoint> = utxos
+                    .iter()
+                    .map(|s| parse_utxo_outpoint(s))
+                    .collect::<Result<Vec<_>, WalletCliCommandError<N>>>(
+                )?;
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                wallet.release_dust_utxos(selected_account, utxos).await?;
+                Ok(ConsoleCommand::Print("Success".to_owned()))
+            }
+
+            WalletCommand::FreezeUtxos { utxos } => {
+                let utxos: Vec<UtxoOutPoint> = utxos
+                    .iter()
+                    .map(|s| parse_utxo_outpoint(s))
+                    .collect::<Result<Vec<_>, WalletCliCommandError<N>>>(
+                )?;
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                wallet.freeze_utxos(selected_account, utxos).await?;
+                Ok(ConsoleCommand::Print("Success".to_owned()))
+            }
+
+            WalletCommand::UnfreezeUtxos { utxos } => {
+                let utxos: Vec<UtxoOutPoint> = utxos
+                    .iter()
+                    .map(|s| parse_utxo_outpoint(s))
+                    .collect::<Result<Vec<_>, WalletCliCommandError<N>>>(
+                )?;
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                wallet.unfreeze_utxos(selected_account, utxos).await?;
+                Ok(ConsoleCommand::Print("Success".to_owned()))
+            }
+
+            WalletCommand::ListFrozenUtxos => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let utxos = wallet
+                    .get_frozen_utxos(selected_account)
+                    .await
+                    .map(serde_json::Value::Array)?;
+                Ok(ConsoleCommand::Print(
+                    serde_json::to_string(&utxos).expect("ok"),
+                ))
+            }
+
+            WalletCommand::TransactionBroadcastStatus { transaction_id } => {
+                let status = self
+                    .non_empty_wallet()
+                    .await?
+                    .transaction_broadcast_status(transaction_id.take())
+                    .await?;
+                let output = status.map_or_else(
+                    || "The transaction is not in the broadcast queue".to_owned(),
+                    format_broadcast_queue_entry_info,
+                );
+                Ok(ConsoleCommand::Print(output))
+            }
+
+            WalletCommand::TransactionBroadcastQueue => {
+                let queue: Vec<_> = self
+                    .non_empty_wallet()
+                    .await?
+                    .transaction_broadcast_queue()
+                    .await?
+                    .into_iter()
+                    .map(format_broadcast_queue_entry_info)
+                    .collect();
+                Ok(ConsoleCommand::Print(format!("{}\n", queue.join("\n"))))
+            }
+
+            WalletCommand::SetTransactionBroadcastPolicy {
+                transaction_id,
+                max_failed_attempts,
+                deadline,
+            } => {
+                self.non_empty_wallet()
+                    .await?
+                    .set_transaction_broadcast_policy(
+                        transaction_id.take(),
+                        max_failed_attempts,
+                        deadline.map(BlockTimestamp::from_int_seconds),
+                    )
+                    .await?;
+                Ok(ConsoleCommand::Print("Success".to_owned()))
+            }
+
             WalletCommand::ListPendingTransactions => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
                 let utxos = wallet.list_pending_transactions(selected_account).await?;
@@ -1196,13 +1710,21 @@ where
 
                 let table = {
                     let mut table = prettytable::Table::new();
-                    table.set_titles(prettytable::row!["Id", "BlockHeight", "BlockTimestamp",]);
+                    table.set_titles(prettytable::row![
+                        "Id",
+                        "BlockHeight",
+                        "BlockTimestamp",
+                        "Labels",
+                        "Description",
+                    ]);
 
                     table.extend(txs.into_iter().map(|info| {
                         prettytable::row![
                             id_to_hex_string(*info.id.as_hash()),
                             info.height,
-                            info.timestamp
+                            info.timestamp,
+                            info.labels.join(", "),
+                            info.description.join("; ")
                         ]
                     }));
 
@@ -1222,6 +1744,37 @@ where
                 Ok(ConsoleCommand::Print(tx))
             }
 
+            WalletCommand::DescribeTransaction { transaction_id } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let description =
+                    wallet.describe_transaction(selected_account, transaction_id.take()).await?;
+
+                let output = if description.entries.is_empty() {
+                    "The transaction doesn't affect this account".to_owned()
+                } else {
+                    description.entries.into_iter().map(|entry| entry.summary).join("\n")
+                };
+
+                Ok(ConsoleCommand::Print(output))
+            }
+
+            WalletCommand::ExportTransactionHistory { format, file_path } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let history = wallet
+                    .export_transaction_history(selected_account, format.to_rpc_type())
+                    .await?;
+                std::fs::write(&file_path, history).map_err(|err| {
+                    WalletCliCommandError::InvalidInput(format!(
+                        "Failed to write {}: {err}",
+                        file_path.display()
+                    ))
+                })?;
+                Ok(ConsoleCommand::Print(format!(
+                    "The transaction history has been exported to {}",
+                    file_path.display()
+                )))
+            }
+
             WalletCommand::GetRawTransaction { transaction_id } => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
                 let tx =
@@ -1253,7 +1806,75 @@ where
                 let new_tx = wallet
                     .send_coins(selected_account, address, amount, input_utxos, self.config)
                     .await?;
-                Ok(Self::new_tx_submitted_command(new_tx))
+                Ok(Self::sent_tx_submitted_command(new_tx))
+            }
+
+            WalletCommand::SendToMany { recipients } => {
+                let recipients = recipients.iter().map(|s| parse_recipient(s)).collect::<Result<
+                    Vec<_>,
+                    WalletCliCommandError<N>,
+                >>(
+                )?;
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let new_tx = wallet.send_to_many(selected_account, recipients, self.config).await?;
+                Ok(Self::sent_tx_submitted_command(new_tx))
+            }
+
+            WalletCommand::SendPayoutsCsv {
+                file_path,
+                max_outputs_per_tx,
+            } => {
+                let csv = std::fs::read_to_string(&file_path).map_err(|err| {
+                    WalletCliCommandError::InvalidInput(format!(
+                        "Failed to read {}: {err}",
+                        file_path.display()
+                    ))
+                })?;
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let report = wallet
+                    .send_payouts_csv(selected_account, csv, max_outputs_per_tx, self.config)
+                    .await?;
+
+                let mut output = format!(
+                    "{} payouts were submitted successfully in {} transactions:\n",
+                    report.payouts_count,
+                    report.transactions.len()
+                );
+                for tx in &report.transactions {
+                    writeln!(
+                        &mut output,
+                        "{} with {} payouts, fee paid: {}",
+                        id_to_hex_string(*tx.tx_id.as_hash()),
+                        tx.payouts_count,
+                        tx.fee.decimal()
+                    )
+                    .expect("Writing to a memory buffer should not fail");
+                }
+                for total in &report.totals {
+                    let amount = total.amount.decimal();
+                    match &total.token_id {
+                        Some(token_id) => writeln!(&mut output, "Token: {token_id} paid: {amount}"),
+                        None => writeln!(&mut output, "Coins paid: {amount}"),
+                    }
+                    .expect("Writing to a memory buffer should not fail");
+                }
+                write!(
+                    &mut output,
+                    "Total fee paid: {}",
+                    report.total_fee.decimal()
+                )
+                .expect("Writing to a memory buffer should not fail");
+                if let Some(failure) = &report.failure {
+                    write!(
+                        &mut output,
+                        "\nThe payouts have stopped because of an error: {}\nUnpaid lines: {}",
+                        failure.error,
+                        failure.unpaid_lines.iter().join(", ")
+                    )
+                    .expect("Writing to a memory buffer should not fail");
+                }
+
+                Ok(ConsoleCommand::Print(output))
             }
 
             WalletCommand::SweepFromAddress {
@@ -1274,6 +1895,83 @@ where
                 Ok(Self::new_tx_submitted_command(new_tx))
             }
 
+            WalletCommand::EstimateMaxSendable { address, token_id } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let amount =
+                    wallet.estimate_max_sendable(selected_account, address, token_id).await?;
+
+                Ok(ConsoleCommand::Print(amount.decimal().to_string()))
+            }
+
+            WalletCommand::SendAll { address, token_id } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let new_tx =
+                    wallet.send_all(selected_account, address, token_id, self.config).await?;
+
+                Ok(Self::new_tx_submitted_command(new_tx))
+            }
+
+            WalletCommand::TransferBetweenAccounts {
+                to_account,
+                amount,
+                token_id,
+            } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let new_tx = wallet
+                    .transfer_between_accounts(
+                        selected_account,
+                        to_account,
+                        amount,
+                        token_id,
+                        self.config,
+                    )
+                    .await?;
+
+                Ok(Self::new_tx_submitted_command(new_tx))
+            }
+
+            WalletCommand::AddScheduledPayment {
+                address,
+                amount,
+                interval_secs,
+                token_id,
+                first_run,
+                label,
+            } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let payment_id = wallet
+                    .add_scheduled_payment(
+                        selected_account,
+                        address,
+                        amount,
+                        token_id,
+                        interval_secs,
+                        first_run.map(BlockTimestamp::from_int_seconds),
+                        label,
+                    )
+                    .await?;
+                Ok(ConsoleCommand::Print(format!(
+                    "Success, the payment is scheduled with id: {payment_id}"
+                )))
+            }
+
+            WalletCommand::CancelScheduledPayment { payment_id } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                wallet.cancel_scheduled_payment(selected_account, payment_id).await?;
+                Ok(ConsoleCommand::Print("Success".to_owned()))
+            }
+
+            WalletCommand::ListScheduledPayments => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let payments: Vec<_> = wallet
+                    .list_scheduled_payments(selected_account)
+                    .await?
+                    .into_iter()
+                    .map(format_scheduled_payment_info)
+                    .collect();
+                Ok(ConsoleCommand::Print(format!("{}\n", payments.join("\n"))))
+            }
+
             WalletCommand::SweepFromDelegation {
                 destination_address,
                 delegation_id,
@@ -1328,6 +2026,56 @@ where
                 Ok(ConsoleCommand::Print(output_str))
             }
 
+            WalletCommand::TransactionPackageCreate {
+                outputs,
+                utxos,
+                change_address,
+            } => {
+                let outputs: Vec<TxOutput> = outputs
+                    .iter()
+                    .map(|input| parse_coin_output(input, chain_config))
+                    .collect::<Result<Vec<_>, WalletCliCommandError<N>>>()?;
+
+                let selected_utxos: Vec<UtxoOutPoint> = utxos
+                    .iter()
+                    .map(|s| parse_utxo_outpoint(s))
+                    .collect::<Result<Vec<_>, WalletCliCommandError<N>>>()?;
+
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let ComposedTransaction { hex, fees } = wallet
+                    .create_transaction_package(
+                        selected_account,
+                        outputs,
+                        selected_utxos,
+                        change_address,
+                        self.config,
+                    )
+                    .await?;
+
+                let package = HexEncoded::<TransactionPackage>::from_str(&hex).expect("ok").take();
+
+                let summary = package.tx().tx().text_summary(chain_config);
+
+                let qr_code_string = qrcode_or_error_string(&hex);
+
+                let mut output_str = format!(
+                    "Transaction package created. \
+                    Pass the following string into the cold wallet with private keys to sign:\n\n{hex}\n\n\
+                    Or scan the Qr code with it:\n\n{qr_code_string}\n\n{summary}\n"
+                );
+                format_fees(&mut output_str, &fees);
+
+                Ok(ConsoleCommand::Print(output_str))
+            }
+
+            WalletCommand::TransactionPackageSubmit { package } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let new_tx = wallet
+                    .submit_transaction_package(selected_account, package, self.config)
+                    .await?;
+                Ok(Self::new_tx_submitted_command(new_tx))
+            }
+
             WalletCommand::InspectTransaction { transaction } => {
                 let RpcInspectTransaction {
                     tx,
@@ -1397,6 +2145,96 @@ where
                     .send_tokens(selected_account, token_id, address, amount, self.config)
                     .await?;
 
+                Ok(Self::sent_tx_submitted_command(new_tx))
+            }
+
+            WalletCommand::SetTokenTrust { token_id, trust } => {
+                self.non_empty_wallet()
+                    .await?
+                    .set_token_trust(token_id, trust.to_wallet_type())
+                    .await?;
+                Ok(ConsoleCommand::Print("Success".to_owned()))
+            }
+
+            WalletCommand::ListTokenTrust => {
+                let trust_list = self.non_empty_wallet().await?.list_token_trust().await?;
+                let trust_list = trust_list
+                    .into_iter()
+                    .map(|info| format!("Token: {} trust: {:?}", info.token_id, info.trust))
+                    .collect::<Vec<_>>();
+                Ok(ConsoleCommand::Print(trust_list.join("\n")))
+            }
+
+            WalletCommand::AddPayee {
+                name,
+                address,
+                default_token,
+                notes,
+            } => {
+                self.non_empty_wallet()
+                    .await?
+                    .add_payee(name, address, default_token, notes)
+                    .await?;
+                Ok(ConsoleCommand::Print("Success".to_owned()))
+            }
+
+            WalletCommand::RemovePayee { name } => {
+                self.non_empty_wallet().await?.remove_payee(name).await?;
+                Ok(ConsoleCommand::Print("Success".to_owned()))
+            }
+
+            WalletCommand::ListPayees => {
+                let payees = self.non_empty_wallet().await?.list_payees().await?;
+                let payees = payees
+                    .into_iter()
+                    .map(|payee| {
+                        let mut line = format!("Payee: {} address: {}", payee.name, payee.address);
+                        if let Some(token_id) = payee.default_token {
+                            write!(&mut line, " default token: {token_id}")
+                                .expect("Writing to a memory buffer should not fail");
+                        }
+                        if let Some(notes) = payee.notes {
+                            write!(&mut line, " notes: {notes}")
+                                .expect("Writing to a memory buffer should not fail");
+                        }
+                        line
+                    })
+                    .collect::<Vec<_>>();
+                Ok(ConsoleCommand::Print(payees.join("\n")))
+            }
+
+            WalletCommand::ExportPayees { file_path } => {
+                let address_book = self.non_empty_wallet().await?.export_payees().await?;
+                std::fs::write(&file_path, address_book).map_err(|err| {
+                    WalletCliCommandError::InvalidInput(format!(
+                        "Failed to write {}: {err}",
+                        file_path.display()
+                    ))
+                })?;
+                Ok(ConsoleCommand::Print(format!(
+                    "The address book has been exported to {}",
+                    file_path.display()
+                )))
+            }
+
+            WalletCommand::ImportPayees { file_path } => {
+                let address_book = std::fs::read_to_string(&file_path).map_err(|err| {
+                    WalletCliCommandError::InvalidInput(format!(
+                        "Failed to read {}: {err}",
+                        file_path.display()
+                    ))
+                })?;
+                let count = self.non_empty_wallet().await?.import_payees(address_book).await?;
+                Ok(ConsoleCommand::Print(format!(
+                    "Success, {count} payees have been imported"
+                )))
+            }
+
+            WalletCommand::SendToPayee { name, amount } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let new_tx =
+                    wallet.send_to_payee(selected_account, name, amount, self.config).await?;
+
                 Ok(Self::new_tx_submitted_command(new_tx))
             }
 
@@ -1596,6 +2434,53 @@ where
                 Ok(ConsoleCommand::Print(format!("{}\n", pool_ids.join("\n"))))
             }
 
+            WalletCommand::WatchPool {
+                pool_id,
+                decommission_address,
+                label,
+                no_rescan,
+            } => {
+                let no_rescan = no_rescan.unwrap_or(false);
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                wallet
+                    .add_watched_pool(
+                        selected_account,
+                        pool_id,
+                        label,
+                        decommission_address,
+                        no_rescan,
+                    )
+                    .await?;
+
+                let output = if no_rescan {
+                    "Success, the pool is now being watched."
+                } else {
+                    "Success, the pool is now being watched.\nRescanning the blockchain to detect the blocks created by the pool"
+                };
+
+                Ok(ConsoleCommand::SetStatus {
+                    status: self.repl_status().await?,
+                    print_message: output.into(),
+                })
+            }
+
+            WalletCommand::UnwatchPool { pool_id } => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                wallet.remove_watched_pool(selected_account, pool_id).await?;
+                Ok(ConsoleCommand::Print("Success".to_owned()))
+            }
+
+            WalletCommand::ListWatchedPools => {
+                let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
+                let pools: Vec<_> = wallet
+                    .list_watched_pools(selected_account)
+                    .await?
+                    .into_iter()
+                    .map(format_watched_pool_info)
+                    .collect();
+                Ok(ConsoleCommand::Print(format!("{}\n", pools.join("\n"))))
+            }
+
             WalletCommand::ListOwnedPoolsForDecommission => {
                 let (wallet, selected_account) = wallet_and_selected_acc(&mut self.wallet).await?;
                 let pool_ids: Vec<_> = wallet
@@ -23,8 +23,13 @@ use common::{
     primitives::{DecimalAmount, Id, H256},
 };
 use wallet_controller::types::{GenericCurrencyTransfer, GenericTokenTransfer};
-use wallet_rpc_lib::types::{NodeInterface, PoolInfo, TokenTotalSupply};
+use wallet_rpc_lib::types::{
+    BroadcastQueueEntryInfo, NodeInterface, PoolInfo, Recipient, RpcBroadcastStatus,
+    RpcChangePolicy, RpcSigHashType, RpcUtxoDiscrepancyKind, ScheduledPaymentInfo,
+    TokenTotalSupply, TransactionHistoryFormat, UtxoVerificationInfo, WatchedPoolInfo,
+};
 use wallet_types::{
+    token_trust::TokenTrust,
     utxo_types::{UtxoState, UtxoType},
     with_locked::WithLocked,
 };
@@ -106,10 +111,149 @@ pub fn format_pool_info(pool_info: PoolInfo) -> String {
     )
 }
 
+pub fn format_watched_pool_info(pool_info: WatchedPoolInfo) -> String {
+    let format_opt = |value: Option<String>| value.unwrap_or_else(|| "None".to_owned());
+    let created_blocks = pool_info
+        .created_blocks
+        .iter()
+        .map(|block| format!("({}, {})", block.height, block.id))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "Pool Id: {}, Label: {}, Decommission Key: {}, Pledge: {}, Balance: {}, Delegations Balance: {}, Created Blocks: [{}]",
+        pool_info.pool_id,
+        format_opt(pool_info.label),
+        format_opt(pool_info.decommission_key.map(|key| key.to_string())),
+        format_opt(pool_info.pledge.map(|amount| amount.decimal().to_string())),
+        format_opt(pool_info.balance.map(|amount| amount.decimal().to_string())),
+        format_opt(pool_info.delegations_balance.map(|amount| amount.decimal().to_string())),
+        created_blocks,
+    )
+}
+
+pub fn format_scheduled_payment_info(payment: ScheduledPaymentInfo) -> String {
+    let format_opt = |value: Option<String>| value.unwrap_or_else(|| "None".to_owned());
+    format!(
+        "Payment Id: {}, Label: {}, Destination: {}, Token Id: {}, Amount: {}, Interval: {}s, Next Run: {}, Retry At: {}, Failed Attempts: {}, Last Error: {}, Last Tx Id: {}",
+        payment.payment_id,
+        format_opt(payment.label),
+        payment.destination,
+        format_opt(payment.token_id.map(|token_id| token_id.to_string())),
+        payment.amount.decimal(),
+        payment.interval_secs,
+        payment.next_run,
+        format_opt(payment.retry_at.map(|timestamp| timestamp.to_string())),
+        payment.failed_attempts,
+        format_opt(payment.last_error),
+        format_opt(payment.last_tx_id.map(|tx_id| format!("{tx_id:x}"))),
+    )
+}
+
+pub fn format_broadcast_queue_entry_info(info: BroadcastQueueEntryInfo) -> String {
+    let format_opt = |value: Option<String>| value.unwrap_or_else(|| "None".to_owned());
+    let status = match info.status {
+        RpcBroadcastStatus::Pending => "Pending".to_owned(),
+        RpcBroadcastStatus::Sent => "Sent".to_owned(),
+        RpcBroadcastStatus::Accepted => "Accepted".to_owned(),
+        RpcBroadcastStatus::Rejected { reason } => format!("Rejected ({reason})"),
+    };
+    format!(
+        "Tx Id: {:x}, Status: {}, Attempts: {}, Failed Attempts: {}/{}, Deadline: {}, Next Attempt At: {}, Last Error: {}",
+        info.tx_id,
+        status,
+        info.attempts,
+        info.failed_attempts,
+        info.max_failed_attempts,
+        format_opt(info.deadline.map(|timestamp| timestamp.to_string())),
+        info.next_attempt_at,
+        format_opt(info.last_error),
+    )
+}
+
 pub fn format_delegation_info(delegation_id: String, balance: String) -> String {
     format!("Delegation Id: {}, Balance: {}", delegation_id, balance,)
 }
 
+/// Format an outpoint the same way `parse_utxo_outpoint` expects it
+pub fn format_utxo_outpoint(outpoint: &UtxoOutPoint) -> String {
+    match outpoint.source_id() {
+        OutPointSourceId::Transaction(tx_id) => {
+            format!("tx({:x},{})", tx_id, outpoint.output_index())
+        }
+        OutPointSourceId::BlockReward(block_id) => {
+            format!("block({:x},{})", block_id, outpoint.output_index())
+        }
+    }
+}
+
+pub fn format_utxo_verification_info(info: UtxoVerificationInfo) -> String {
+    let mut lines = vec![format!(
+        "Checked {} of {} tracked outputs at height {}",
+        info.checked_count, info.tracked_count, info.checked_at
+    )];
+
+    let has_discrepancies = !info.discrepancies.is_empty();
+    if !has_discrepancies {
+        lines.push("No discrepancies found".to_owned());
+    }
+
+    for discrepancy in info.discrepancies {
+        let kind = match discrepancy.kind {
+            RpcUtxoDiscrepancyKind::SpentButTracked => "spent, but tracked as unspent",
+            RpcUtxoDiscrepancyKind::Missing => "unspent, but tracked as spent",
+        };
+        lines.push(format!(
+            "Account: {}, Outpoint: {}, Discrepancy: {}, Rescan From: {}",
+            discrepancy.account.0,
+            format_utxo_outpoint(&discrepancy.outpoint.into_outpoint()),
+            kind,
+            discrepancy.rescan_from,
+        ));
+    }
+
+    match info.rewound_to {
+        Some(block) => lines.push(format!(
+            "The blocks above height {} have been rescanned",
+            block.height
+        )),
+        None if has_discrepancies => lines
+            .push("Use `wallet-verify-utxos --repair` to rescan the affected blocks".to_owned()),
+        None => {}
+    }
+
+    lines.join("\n")
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliChangePolicy {
+    NewAddress,
+    ReuseInputAddress,
+    External,
+}
+
+impl CliChangePolicy {
+    pub fn to_rpc_change_policy<N: NodeInterface>(
+        self,
+        address: Option<String>,
+    ) -> Result<RpcChangePolicy, WalletCliCommandError<N>> {
+        match (self, address) {
+            (CliChangePolicy::NewAddress, None) => Ok(RpcChangePolicy::NewAddress),
+            (CliChangePolicy::ReuseInputAddress, None) => Ok(RpcChangePolicy::ReuseInputAddress),
+            (CliChangePolicy::External, Some(address)) => {
+                Ok(RpcChangePolicy::ExternalAddress(address.into()))
+            }
+            (CliChangePolicy::External, None) => Err(WalletCliCommandError::<N>::InvalidInput(
+                "The external change policy requires an address".into(),
+            )),
+            (CliChangePolicy::NewAddress | CliChangePolicy::ReuseInputAddress, Some(_)) => {
+                Err(WalletCliCommandError::<N>::InvalidInput(
+                    "An address can only be specified with the external change policy".into(),
+                ))
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum CliWithLocked {
     Any,
@@ -127,6 +271,57 @@ impl CliWithLocked {
     }
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliTokenTrust {
+    Trusted,
+    Hidden,
+    Unverified,
+}
+
+impl CliTokenTrust {
+    pub fn to_wallet_type(self) -> Option<TokenTrust> {
+        match self {
+            CliTokenTrust::Trusted => Some(TokenTrust::Trusted),
+            CliTokenTrust::Hidden => Some(TokenTrust::Hidden),
+            CliTokenTrust::Unverified => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliSigHashType {
+    All,
+    AllAnyoneCanPay,
+    Single,
+    SingleAnyoneCanPay,
+}
+
+impl CliSigHashType {
+    pub fn to_rpc_type(self) -> RpcSigHashType {
+        match self {
+            CliSigHashType::All => RpcSigHashType::All,
+            CliSigHashType::AllAnyoneCanPay => RpcSigHashType::AllAnyoneCanPay,
+            CliSigHashType::Single => RpcSigHashType::Single,
+            CliSigHashType::SingleAnyoneCanPay => RpcSigHashType::SingleAnyoneCanPay,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliTransactionHistoryFormat {
+    Csv,
+    Json,
+}
+
+impl CliTransactionHistoryFormat {
+    pub fn to_rpc_type(self) -> TransactionHistoryFormat {
+        match self {
+            CliTransactionHistoryFormat::Csv => TransactionHistoryFormat::Csv,
+            CliTransactionHistoryFormat::Json => TransactionHistoryFormat::Json,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum CliStoreSeedPhrase {
     StoreSeedPhrase,
@@ -241,6 +436,49 @@ pub fn parse_generic_currency_transfer<N: NodeInterface>(
     Ok(output)
 }
 
+/// Parses a string into a `Recipient` of a transaction with multiple outputs.
+/// The string format is expected to be `transfer(address,amount)` for coins
+/// or `transfer(address,amount,token_id)` for tokens
+/// e.g `transfer(tmt1qy7y8ra99sgmt97lu2kn249yds23pnp7xsv62p77,10.1)`.
+pub fn parse_recipient<N: NodeInterface>(
+    input: &str,
+) -> Result<Recipient, WalletCliCommandError<N>> {
+    let (name, mut args) = parse_funclike_expr(input).ok_or(
+        WalletCliCommandError::<N>::InvalidInput("Invalid input format".into()),
+    )?;
+
+    let (dest_str, amount_str, token_id_str) =
+        match (args.next(), args.next(), args.next(), args.next()) {
+            (Some(dest_str), Some(amount_str), token_id_str, None) => {
+                (dest_str, amount_str, token_id_str)
+            }
+            (_, _, _, _) => {
+                return Err(WalletCliCommandError::<N>::InvalidInput(
+                    "Invalid input format".into(),
+                ));
+            }
+        };
+
+    let amount = DecimalAmount::from_str(amount_str).map_err(|err| {
+        WalletCliCommandError::<N>::InvalidInput(format!("Invalid amount {amount_str} {err}"))
+    })?;
+
+    let recipient = match name {
+        "transfer" => Recipient {
+            address: dest_str.to_owned().into(),
+            amount: amount.into(),
+            token_id: token_id_str.map(|token_id| token_id.to_owned().into()),
+        },
+        _ => {
+            return Err(WalletCliCommandError::<N>::InvalidInput(
+                "Invalid input: unknown type".into(),
+            ));
+        }
+    };
+
+    Ok(recipient)
+}
+
 /// Parses a string into `GenericTokenTransfer`.
 /// The string format is expected to be `transfer(token_id,address,amount)`
 pub fn parse_generic_token_transfer<N: NodeInterface>(
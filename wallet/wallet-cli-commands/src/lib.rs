@@ -31,14 +31,16 @@ use common::{
     chain::{Block, SignedTransaction, Transaction},
     primitives::{BlockHeight, DecimalAmount, Id},
 };
-use crypto::key::{hdkd::u31::U31, PrivateKey, PublicKey};
+use crypto::key::{extended::ExtendedPublicKey, hdkd::u31::U31, PrivateKey, PublicKey};
 use p2p_types::{bannable_address::BannableAddress, PeerId};
 use serialization::hex_encoded::HexEncoded;
 use utils_networking::IpOrSocketAddress;
+use wallet_types::multisig_account::MultisigAccountDescriptor;
 
 use self::helper_types::{
-    CliForceReduce, CliIsFreezable, CliIsUnfreezable, CliStoreSeedPhrase, CliUtxoState,
-    CliUtxoTypes, CliWithLocked, EnableOrDisable,
+    CliChangePolicy, CliForceReduce, CliIsFreezable, CliIsUnfreezable, CliSigHashType,
+    CliStoreSeedPhrase, CliTokenTrust, CliTransactionHistoryFormat, CliUtxoState, CliUtxoTypes,
+    CliWithLocked, EnableOrDisable,
 };
 
 #[derive(Debug, Parser)]
@@ -73,6 +75,25 @@ pub enum WalletManagementCommand {
         /// Force change the wallet type from hot to cold or from cold to hot
         #[arg(long)]
         force_change_wallet_type: bool,
+        /// After opening, sync the wallet and check the outputs it tracks against the node's UTXO set
+        #[arg(long)]
+        deep_verify: bool,
+        /// Check only this many randomly chosen outputs with `--deep-verify` instead of all of them
+        #[arg(long, requires = "deep_verify")]
+        deep_verify_sample_size: Option<NonZeroUsize>,
+    },
+
+    /// Create a watch-only wallet from the data produced by `wallet-export-watch-only`
+    /// in another wallet. The new wallet has no private keys, so it can only be used
+    /// to track the balances and transactions of the original wallet.
+    #[clap(name = "wallet-create-from-watch-only-export")]
+    CreateWalletFromWatchOnlyExport {
+        /// File path of the new wallet file
+        wallet_path: PathBuf,
+        /// The exported data in hex encoding
+        data: String,
+        /// The passphrase used for the export
+        passphrase: String,
     },
 
     #[clap(name = "wallet-close")]
@@ -126,6 +147,28 @@ pub enum ColdWalletCommand {
         i_know_what_i_am_doing: Option<CliForceReduce>,
     },
 
+    /// Export the public data of this wallet (the accounts' public keys, labels and transactions,
+    /// but no private keys) encrypted with a passphrase, in hex encoding.
+    /// The data can be used to create a watch-only replica of this wallet on another device.
+    #[clap(name = "wallet-export-watch-only")]
+    ExportWatchOnly {
+        /// The passphrase to encrypt the exported data with
+        passphrase: String,
+        /// Only export the transactions confirmed above this block height, to update
+        /// a replica that is synced at least up to this height
+        #[arg(long = "since-height")]
+        since_height: Option<u64>,
+    },
+
+    /// Update this watch-only wallet with a newer export of the original wallet
+    #[clap(name = "wallet-apply-watch-only-update")]
+    ApplyWatchOnlyUpdate {
+        /// The exported data in hex encoding
+        data: String,
+        /// The passphrase used for the export
+        passphrase: String,
+    },
+
     /// Creates a QR code of the provided address
     #[clap(name = "address-qrcode")]
     AddressQRCode {
@@ -176,6 +219,15 @@ pub enum ColdWalletCommand {
         transaction: String,
     },
 
+    /// Sign the inputs of a transaction package created with `transaction-package-create`
+    /// by an online wallet. Once all the inputs are signed, the package can be submitted
+    /// with `transaction-package-submit` in the online wallet.
+    #[clap(name = "account-sign-transaction-package")]
+    SignTransactionPackage {
+        /// Hex encoded transaction package.
+        package: String,
+    },
+
     #[clap(name = "challenge-sign-hex")]
     #[clap(hide = true)]
     SignChallegeHex {
@@ -258,6 +310,76 @@ pub enum WalletCommand {
         utxo_states: Vec<CliUtxoState>,
     },
 
+    /// Set the dust protection policy of the selected account.
+    /// UTXOs received from other wallets that match the policy are not selected automatically
+    /// when composing transactions, which protects against dusting attacks.
+    /// Calling the command without options disables the protection.
+    #[clap(name = "account-set-dust-protection")]
+    SetDustProtection {
+        /// Flag incoming coin UTXOs with a value below this amount, in decimal format
+        #[arg(long = "min-coin-amount")]
+        min_coin_amount: Option<DecimalAmount>,
+        /// Flag incoming UTXOs of tokens that this account has never sent itself
+        #[arg(long = "flag-unknown-tokens", default_value_t = false)]
+        flag_unknown_tokens: bool,
+    },
+
+    /// Set where the change of the transactions composed by the selected account is sent to.
+    /// "new-address" (the default) sends the change to a new change address of the account,
+    /// "reuse-input-address" sends it back to the address of the first input paying the fee
+    /// and "external" sends it to the given address, e.g. of a cold wallet.
+    #[clap(name = "account-set-change-policy")]
+    SetChangePolicy {
+        /// The change policy
+        #[arg(value_enum)]
+        policy: CliChangePolicy,
+        /// The address to send the change to, only for the "external" policy
+        address: Option<String>,
+    },
+
+    /// Show where the change of the transactions composed by the selected account is sent to
+    #[clap(name = "account-change-policy")]
+    ShowChangePolicy,
+
+    /// List the UTXOs flagged by the dust protection policy of the selected account
+    #[clap(name = "account-dust-utxos")]
+    ListDustUtxos,
+
+    /// Release UTXOs flagged by the dust protection policy, so they can be selected automatically again
+    #[clap(name = "account-release-dust-utxos")]
+    ReleaseDustUtxos {
+        /// The UTXOs to be released (space separated). A utxo can be from a transaction output or a block reward output:
+        /// e.g tx(000000000000000000059fa50103b9683e51e5aba83b8a34c9b98ce67d66136c,1) or
+        /// block(000000000000000000059fa50103b9683e51e5aba83b8a34c9b98ce67d66136c,2)
+        #[arg(required = true)]
+        utxos: Vec<String>,
+    },
+
+    /// Freeze UTXOs of the selected account. Frozen UTXOs are not selected automatically when
+    /// composing transactions and cannot be spent by selecting them explicitly, until they are unfrozen
+    #[clap(name = "account-freeze-utxos")]
+    FreezeUtxos {
+        /// The UTXOs to be frozen (space separated). A utxo can be from a transaction output or a block reward output:
+        /// e.g tx(000000000000000000059fa50103b9683e51e5aba83b8a34c9b98ce67d66136c,1) or
+        /// block(000000000000000000059fa50103b9683e51e5aba83b8a34c9b98ce67d66136c,2)
+        #[arg(required = true)]
+        utxos: Vec<String>,
+    },
+
+    /// Unfreeze UTXOs of the selected account, so they can be spent again
+    #[clap(name = "account-unfreeze-utxos")]
+    UnfreezeUtxos {
+        /// The UTXOs to be unfrozen (space separated). A utxo can be from a transaction output or a block reward output:
+        /// e.g tx(000000000000000000059fa50103b9683e51e5aba83b8a34c9b98ce67d66136c,1) or
+        /// block(000000000000000000059fa50103b9683e51e5aba83b8a34c9b98ce67d66136c,2)
+        #[arg(required = true)]
+        utxos: Vec<String>,
+    },
+
+    /// List the unspent UTXOs of the selected account that are frozen
+    #[clap(name = "account-frozen-utxos")]
+    ListFrozenUtxos,
+
     #[clap(name = "account-balance")]
     GetBalance {
         /// Whether to include locked outputs (outputs that cannot be spend and need time to mature)
@@ -268,6 +390,18 @@ pub enum WalletCommand {
         utxo_states: Vec<CliUtxoState>,
     },
 
+    /// Set a label for a receive-address issued by the selected account, e.g. to identify
+    /// the payments it is used for. The labels are shown by `address-show`
+    #[clap(name = "address-set-label")]
+    SetAddressLabel {
+        /// The receive-address issued by the selected account
+        address: String,
+
+        /// The new label, not specifying a label will remove the existing one
+        #[arg(long = "label")]
+        label: Option<String>,
+    },
+
     #[clap(name = "standalone-address-label-rename")]
     StandaloneAddressLabelRename {
         /// The existing standalone address
@@ -292,6 +426,13 @@ pub enum WalletCommand {
         no_rescan: Option<bool>,
     },
 
+    /// Stop watching a standalone watch only address of the selected account
+    #[clap(name = "standalone-remove-watch-only-address")]
+    RemoveStandaloneKey {
+        /// The standalone watch only address to be removed from the selected account
+        address: String,
+    },
+
     #[clap(name = "standalone-add-private-key-from-hex")]
     AddStandalonePrivateKey {
         /// The new hex encoded standalone private key to be added to the selected account
@@ -306,6 +447,35 @@ pub enum WalletCommand {
         no_rescan: Option<bool>,
     },
 
+    /// Import a standalone private key encoded in the bech32m format produced by
+    /// `address-export-private-key`
+    #[clap(name = "standalone-import-private-key")]
+    ImportStandalonePrivateKey {
+        /// The bech32m encoded private key
+        private_key: String,
+
+        /// Optionally specify a label to the new address
+        #[arg(long = "label")]
+        label: Option<String>,
+
+        /// Skip the rescanning of the blockchain
+        #[arg(long = "no-rescan")]
+        no_rescan: Option<bool>,
+    },
+
+    /// Export the private key of an address of the selected account in the bech32m format.
+    /// Anyone who has the private key can spend the funds of the address, so the export
+    /// must be confirmed explicitly. The wallet must be unlocked.
+    #[clap(name = "address-export-private-key")]
+    ExportPrivateKey {
+        /// The address whose private key to export
+        address: String,
+
+        /// Confirm that the private key should be revealed
+        #[arg(long = "confirm", default_value_t = false)]
+        confirm: bool,
+    },
+
     #[clap(name = "standalone-add-multisig")]
     AddStandaloneMultisig {
         /// The minimum required signatures out of the specified public keys
@@ -336,6 +506,83 @@ pub enum WalletCommand {
         utxo_states: Vec<CliUtxoState>,
     },
 
+    /// Lists the utxos of a standalone address watched by the selected account
+    #[clap(name = "standalone-address-utxos")]
+    ListStandaloneAddressUtxo {
+        /// The standalone address
+        address: String,
+        /// The type of utxo to be listed. Default is "all".
+        #[arg(value_enum, default_value_t = CliUtxoTypes::All)]
+        utxo_type: CliUtxoTypes,
+        /// Whether to include locked outputs. Default is "unlocked"
+        #[arg(value_enum, default_value_t = CliWithLocked::Unlocked)]
+        with_locked: CliWithLocked,
+        /// The state of the utxos; e.g., confirmed, unconfirmed, etc.
+        #[arg(default_values_t = vec![CliUtxoState::Confirmed])]
+        utxo_states: Vec<CliUtxoState>,
+    },
+
+    /// Print the extended public key of the selected account, to be shared with the other
+    /// cosigners of a multisig account
+    #[clap(name = "account-extended-public-key")]
+    AccountExtendedPublicKey,
+
+    /// Print the extended public key of the selected account in the bech32m format,
+    /// to be imported into other wallets or tools
+    #[clap(name = "account-export-extended-public-key")]
+    ExportAccountExtendedPublicKey,
+
+    /// Import an extended public key in the bech32m format and start watching
+    /// its receiving and change addresses
+    #[clap(name = "standalone-import-extended-public-key")]
+    ImportExtendedPublicKey {
+        /// The bech32m encoded extended public key
+        extended_public_key: String,
+
+        /// The number of receiving addresses and of change addresses derived from the key to watch,
+        /// at most 1000
+        #[arg(long = "address-count", default_value_t = 20)]
+        address_count: u32,
+
+        /// Optionally specify a label to the new addresses
+        #[arg(long = "label")]
+        label: Option<String>,
+
+        /// Skip the rescanning of the blockchain
+        #[arg(long = "no-rescan")]
+        no_rescan: Option<bool>,
+    },
+
+    /// Create the descriptor of a multisig account shared by the selected account and the other
+    /// cosigners. The descriptor must be imported into the wallets of all the cosigners.
+    #[clap(name = "multisig-account-create-descriptor")]
+    CreateMultisigAccountDescriptor {
+        /// The minimum required signatures out of all the cosigners, including this account
+        min_required_signatures: u8,
+
+        /// The hex encoded account extended public keys of the other cosigners
+        cosigner_keys: Vec<HexEncoded<ExtendedPublicKey>>,
+    },
+
+    /// Import a multisig account descriptor and start watching its receiving addresses
+    #[clap(name = "multisig-account-import")]
+    ImportMultisigAccount {
+        /// The hex encoded multisig account descriptor
+        descriptor: HexEncoded<MultisigAccountDescriptor>,
+
+        /// The number of receiving addresses of the multisig account to watch
+        #[arg(long = "address-count", default_value_t = 20)]
+        address_count: u32,
+
+        /// Optionally specify a label to the new addresses
+        #[arg(long = "label")]
+        label: Option<String>,
+
+        /// Skip the rescanning of the blockchain
+        #[arg(long = "no-rescan")]
+        no_rescan: Option<bool>,
+    },
+
     #[clap(name = "token-nft-issue-new")]
     IssueNewNft {
         /// The receiver of the token
@@ -376,6 +623,24 @@ pub enum WalletCommand {
     #[clap(name = "token-change-authority")]
     ChangeTokenAuthority { token_id: String, address: String },
 
+    /// Change the authority of a token after checking that it is safe to do so.
+    ///
+    /// The change is refused if the new authority is not controlled by this wallet,
+    /// because the control over the token would be lost; use --force to change it anyway.
+    #[clap(name = "token-rotate-authority")]
+    RotateTokenAuthority {
+        /// The token id of the token whose authority is to be changed
+        token_id: String,
+        /// The address of the new authority
+        address: String,
+        /// Only check whether the new authority is controlled by this wallet, without creating a transaction
+        #[arg(long)]
+        dry_run: bool,
+        /// Change the authority even if the new authority is not controlled by this wallet
+        #[arg(long)]
+        force: bool,
+    },
+
     #[clap(name = "token-change-metadata-uri")]
     ChangeTokenMetadataUri {
         token_id: String,
@@ -430,6 +695,75 @@ pub enum WalletCommand {
         amount: DecimalAmount,
     },
 
+    /// Mark a token as trusted or hidden in this wallet.
+    ///
+    /// The tokens that were neither issued nor sent by the wallet are shown separately
+    /// as unverified in the balance, because anyone can send tokens to the wallet.
+    /// Trusted tokens are shown together with the other tokens and hidden tokens are not shown at all.
+    /// Use "unverified" to remove the mark.
+    #[clap(name = "token-set-trust")]
+    SetTokenTrust {
+        /// The token id of the token
+        token_id: String,
+        /// How the token should be treated
+        trust: CliTokenTrust,
+    },
+
+    /// List the tokens marked as trusted or hidden in this wallet
+    #[clap(name = "token-list-trust")]
+    ListTokenTrust,
+
+    /// Add a payee to the wallet's address book, or replace the payee with the same name.
+    /// The address book is shared by all the accounts of the wallet.
+    #[clap(name = "address-book-add")]
+    AddPayee {
+        /// The name of the payee
+        name: String,
+        /// The address of the payee
+        address: String,
+        /// The token id of the token to be sent to the payee by default instead of coins
+        #[arg(long = "default-token")]
+        default_token: Option<String>,
+        /// Optional notes about the payee
+        #[arg(long = "notes")]
+        notes: Option<String>,
+    },
+
+    /// Remove a payee from the wallet's address book
+    #[clap(name = "address-book-remove")]
+    RemovePayee {
+        /// The name of the payee
+        name: String,
+    },
+
+    /// List the payees in the wallet's address book
+    #[clap(name = "address-book-list")]
+    ListPayees,
+
+    /// Export the wallet's address book to a JSON file
+    #[clap(name = "address-book-export")]
+    ExportPayees {
+        /// The file to write the address book to
+        file_path: PathBuf,
+    },
+
+    /// Import an address book from a JSON file created by `address-book-export`.
+    /// The payees with the same names as the existing ones replace them.
+    #[clap(name = "address-book-import")]
+    ImportPayees {
+        /// The file to read the address book from
+        file_path: PathBuf,
+    },
+
+    /// Send coins, or the payee's default token if it has one, to a payee from the address book
+    #[clap(name = "address-book-send")]
+    SendToPayee {
+        /// The name of the payee
+        name: String,
+        /// The amount to be sent
+        amount: DecimalAmount,
+    },
+
     /// Create a transaction for sending tokens from a multisig address to other addresses, returning the change to
     /// the original multisig address.
     ///
@@ -464,6 +798,30 @@ pub enum WalletCommand {
         utxos: Vec<String>,
     },
 
+    /// Send coins and/or tokens to multiple recipients in a single transaction.
+    /// The inputs are selected automatically from the available ones in the wallet.
+    #[clap(name = "address-send-many")]
+    SendToMany {
+        /// The recipients (space separated), in the format `transfer(address,amount)` for coins
+        /// or `transfer(address,amount,token_id)` for tokens
+        /// e.g. transfer(tmt1q8lhgxhycm8e6yk9zpnetdwtn03h73z70c3ha4l7,0.9)
+        #[arg(required = true)]
+        recipients: Vec<String>,
+    },
+
+    /// Pay many recipients listed in a CSV file, e.g. for an airdrop or a payroll.
+    /// Each line of the file is in the format `address,amount,token_id`, where the token id
+    /// is left empty for coins. All the lines are validated before anything is sent, and
+    /// the payouts are split into as many transactions as needed.
+    #[clap(name = "address-send-many-csv")]
+    SendPayoutsCsv {
+        /// The CSV file to read the payouts from
+        file_path: PathBuf,
+        /// The maximum number of payouts in a single transaction, 100 by default
+        #[arg(long = "max-outputs-per-tx")]
+        max_outputs_per_tx: Option<usize>,
+    },
+
     #[clap(name = "address-sweep-spendable")]
     SweepFromAddress {
         /// The receiving address of the coins or tokens
@@ -472,6 +830,75 @@ pub enum WalletCommand {
         addresses: Vec<String>,
     },
 
+    /// Print the maximum amount of coins, or of tokens if a token id is given, that can be sent
+    /// to the given address in a single transaction, after paying the fees.
+    #[clap(name = "address-estimate-max-sendable")]
+    EstimateMaxSendable {
+        /// The receiving address of the coins or tokens
+        address: String,
+        /// The token id of the tokens to be sent, coins are assumed if not specified
+        #[arg(long = "token-id")]
+        token_id: Option<String>,
+    },
+
+    /// Send all the spendable coins, or all the spendable tokens if a token id is given, to the given address.
+    /// When sending coins, the fee is deducted from the sent amount.
+    #[clap(name = "address-send-all")]
+    SendAll {
+        /// The receiving address of the coins or tokens
+        address: String,
+        /// The token id of the tokens to be sent, coins are sent if not specified
+        #[arg(long = "token-id")]
+        token_id: Option<String>,
+    },
+
+    /// Transfer coins, or tokens if a token id is given, from the selected account to a new address
+    /// of another account of this wallet.
+    /// The transaction is shown as an internal transfer in the transaction list of both accounts.
+    #[clap(name = "account-transfer")]
+    TransferBetweenAccounts {
+        /// The index of the receiving account
+        to_account: U31,
+        /// The amount to be transferred, in decimal format
+        amount: DecimalAmount,
+        /// The token id of the tokens to be transferred, coins are transferred if not specified
+        #[arg(long = "token-id")]
+        token_id: Option<String>,
+    },
+
+    /// Schedule a recurring payment of coins, or of tokens if a token id is given.
+    /// The payment is sent by the wallet every interval while it is running and unlocked.
+    /// If the confirmed balance is too low or sending fails, the payment is retried later.
+    #[clap(name = "scheduled-payment-add")]
+    AddScheduledPayment {
+        /// The receiving address of the coins or tokens
+        address: String,
+        /// The amount to be sent each time, in decimal format
+        amount: DecimalAmount,
+        /// The interval between the payments in seconds
+        interval_secs: u64,
+        /// The token id of the tokens to be sent, coins are sent if not specified
+        #[arg(long = "token-id")]
+        token_id: Option<String>,
+        /// The time of the first payment as a UNIX timestamp, the first payment is sent immediately if not specified
+        #[arg(long = "first-run")]
+        first_run: Option<u64>,
+        /// Optionally specify a label for the payment
+        #[arg(long = "label")]
+        label: Option<String>,
+    },
+
+    /// Cancel a scheduled payment
+    #[clap(name = "scheduled-payment-cancel")]
+    CancelScheduledPayment {
+        /// The id of the scheduled payment
+        payment_id: u64,
+    },
+
+    /// List the payments scheduled by the selected account
+    #[clap(name = "scheduled-payment-list")]
+    ListScheduledPayments,
+
     #[clap(name = "staking-sweep-delegation")]
     SweepFromDelegation {
         /// The receiving address of the coins
@@ -495,6 +922,32 @@ pub enum WalletCommand {
         change_address: Option<String>,
     },
 
+    /// Create an unsigned transaction package, to be signed by an offline (cold) wallet
+    /// with `account-sign-transaction-package`. The package contains everything needed
+    /// to sign the transaction, along with its fees.
+    #[clap(name = "transaction-package-create")]
+    TransactionPackageCreate {
+        /// The transaction outputs, in the format `transfer(address,amount)`
+        /// e.g. transfer(tmt1q8lhgxhycm8e6yk9zpnetdwtn03h73z70c3ha4l7,0.9)
+        outputs: Vec<String>,
+        /// You can choose what utxos to spend (space separated as additional arguments). A utxo can be from a transaction output or a block reward output:
+        /// e.g tx(000000000000000000059fa50103b9683e51e5aba83b8a34c9b98ce67d66136c,1) or
+        /// block(000000000000000000059fa50103b9683e51e5aba83b8a34c9b98ce67d66136c,2)
+        /// If not specified, the utxos are selected automatically.
+        #[arg(long = "utxos", default_values_t = Vec::<String>::new())]
+        utxos: Vec<String>,
+        /// Optional change address, if not specified the change is sent to a new address of the selected account
+        #[arg(long = "change")]
+        change_address: Option<String>,
+    },
+
+    /// Submit the transaction of a fully signed transaction package to the node
+    #[clap(name = "transaction-package-submit")]
+    TransactionPackageSubmit {
+        /// Hex encoded transaction package, signed with `account-sign-transaction-package`.
+        package: String,
+    },
+
     #[clap(name = "transaction-inspect")]
     InspectTransaction {
         /// Hex encoded transaction or PartiallySignedTransaction.
@@ -554,9 +1007,52 @@ pub enum WalletCommand {
     #[clap(name = "staking-pool-balance")]
     StakePoolBalance { pool_id: String },
 
+    /// Estimate the expected number of blocks and the annual return of a pool with the given
+    /// pledge and total balance, based on the current network difficulty.
+    /// Transaction fees are not taken into account.
+    #[clap(name = "staking-calculator")]
+    StakingCalculator {
+        /// The amount pledged by the pool's owner
+        pledge: DecimalAmount,
+        /// The total balance of the pool, including the pledge and the delegations
+        pool_balance: DecimalAmount,
+    },
+
     #[clap(name = "staking-list-created-block-ids")]
     ListCreatedBlocksIds,
 
+    /// Start watching a stake pool that is not controlled by this wallet.
+    /// The pool's balance, pledge, delegations and created blocks can then be listed
+    /// with `staking-list-watched-pools`, without the wallet holding any of its keys.
+    #[clap(name = "staking-watch-pool")]
+    WatchPool {
+        /// The pool id of the pool to be watched
+        pool_id: String,
+
+        /// Optionally specify the decommission address of the pool
+        #[arg(long = "decommission-address")]
+        decommission_address: Option<String>,
+
+        /// Optionally specify a label for the pool
+        #[arg(long = "label")]
+        label: Option<String>,
+
+        /// Skip the rescanning of the blockchain
+        #[arg(long = "no-rescan")]
+        no_rescan: Option<bool>,
+    },
+
+    /// Stop watching a stake pool
+    #[clap(name = "staking-unwatch-pool")]
+    UnwatchPool {
+        /// The pool id of the watched pool
+        pool_id: String,
+    },
+
+    /// List the stake pools watched by the selected account
+    #[clap(name = "staking-list-watched-pools")]
+    ListWatchedPools,
+
     #[clap(name = "staking-create-pool")]
     CreateStakePool {
         /// The amount to be pledged to the pool. There is a minimum to be accepted.
@@ -604,6 +1100,36 @@ pub enum WalletCommand {
     #[clap(name = "wallet-sync")]
     SyncWallet,
 
+    /// Show the best block the wallet is synced to, when it was last in sync with the node
+    /// and whether a sync is in progress
+    #[clap(name = "wallet-sync-status")]
+    SyncStatus,
+
+    /// Check the outputs tracked by the wallet against the node's UTXO set and report the ones
+    /// the wallet considers unspent while the node doesn't have them, or the other way around.
+    /// The wallet must be in sync with the node.
+    #[clap(name = "wallet-verify-utxos")]
+    VerifyUtxos {
+        /// Check only this many randomly chosen outputs instead of all of them
+        #[arg(long)]
+        sample_size: Option<NonZeroUsize>,
+        /// Rescan the blocks starting from the earliest one affected by the found discrepancies
+        #[arg(long)]
+        repair: bool,
+    },
+
+    /// Export the log of the transactions signed by this wallet as JSON.
+    /// The wallet must be unlocked to read the log.
+    #[clap(name = "wallet-export-signing-log")]
+    ExportSigningLog {
+        /// Only include the transactions signed at or after this time, as a UNIX timestamp
+        #[arg(long = "from")]
+        from: Option<u64>,
+        /// Only include the transactions signed at or before this time, as a UNIX timestamp
+        #[arg(long = "to")]
+        to: Option<u64>,
+    },
+
     #[clap(name = "node-version")]
     NodeVersion,
 
@@ -747,6 +1273,12 @@ pub enum WalletCommand {
         #[arg(long = "utxos", default_values_t = Vec::<String>::new())]
         utxos: Vec<String>,
 
+        /// The sighash type to sign each of the utxos with, in the same order as the utxos.
+        /// If not specified, all inputs are signed with "all".
+        /// "all-anyone-can-pay" allows other parties to add their own inputs after signing.
+        #[arg(long = "sighash-types", value_enum)]
+        sighash_types: Vec<CliSigHashType>,
+
         #[arg(long = "only-transaction", default_value_t = false)]
         only_transaction: bool,
     },
@@ -760,6 +1292,31 @@ pub enum WalletCommand {
     #[clap(name = "transaction-list-pending")]
     ListPendingTransactions,
 
+    /// Show the broadcast status of a transaction sent by this wallet.
+    /// The wallet keeps resubmitting the transaction to the node until it's included in a block,
+    /// the limit of failed attempts is reached or the deadline passes.
+    #[clap(name = "transaction-broadcast-status")]
+    TransactionBroadcastStatus {
+        /// The id of the transaction, in hex.
+        transaction_id: HexEncoded<Id<Transaction>>,
+    },
+
+    /// List the transactions queued for broadcasting, including the recently accepted and rejected ones
+    #[clap(name = "transaction-broadcast-queue")]
+    TransactionBroadcastQueue,
+
+    /// Change when the wallet gives up broadcasting a queued transaction
+    #[clap(name = "transaction-set-broadcast-policy")]
+    SetTransactionBroadcastPolicy {
+        /// The id of the transaction, in hex.
+        transaction_id: HexEncoded<Id<Transaction>>,
+        /// The number of consecutive failed submissions after which the transaction is rejected
+        max_failed_attempts: u32,
+        /// The time, in seconds since the UNIX epoch, by which the transaction must be included in a block
+        #[arg(long = "deadline")]
+        deadline: Option<u64>,
+    },
+
     #[clap(name = "transaction-list-by-address")]
     ListMainchainTransactions {
         /// Address to filter by
@@ -775,6 +1332,25 @@ pub enum WalletCommand {
         transaction_id: HexEncoded<Id<Transaction>>,
     },
 
+    /// Print a human-friendly description of what a transaction from the wallet does
+    #[clap(name = "transaction-describe")]
+    DescribeTransaction {
+        /// Transaction id, encoded in hex
+        transaction_id: HexEncoded<Id<Transaction>>,
+    },
+
+    /// Export the whole transaction history of the selected account to a file,
+    /// e.g. to be imported into accounting tools.
+    /// The CSV format has a row per transaction and currency; the fee is only put into the first
+    /// row of a transaction.
+    #[clap(name = "transaction-history-export")]
+    ExportTransactionHistory {
+        /// The format of the exported history
+        format: CliTransactionHistoryFormat,
+        /// The file to write the history to
+        file_path: PathBuf,
+    },
+
     #[clap(name = "transaction-get-raw")]
     GetRawTransaction {
         /// Transaction id, encoded in hex
@@ -93,6 +93,7 @@ pub async fn run<N: NodeInterface + Clone + Send + Sync + Debug + 'static>(
                 ControllerConfig {
                     in_top_x_mb,
                     broadcast_to_mempool: true,
+                    fee_override: None,
                 },
                 wallet,
             )
@@ -124,6 +125,7 @@ pub async fn run<N: NodeInterface + Clone + Send + Sync + Debug + 'static>(
                 ControllerConfig {
                     in_top_x_mb,
                     broadcast_to_mempool: true,
+                    fee_override: None,
                 },
                 wallet,
             )
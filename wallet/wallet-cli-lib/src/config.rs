@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::path::PathBuf;
+use std::{num::NonZeroUsize, path::PathBuf};
 
 use clap::{Args, Parser, Subcommand};
 use common::chain::config::{regtest_options::ChainConfigOptions, ChainType};
@@ -84,6 +84,14 @@ pub struct CliArgs {
     #[clap(long, requires("wallet_file"))]
     pub force_change_wallet_type: bool,
 
+    /// After opening the wallet, sync it and check the outputs it tracks against the node's UTXO set
+    #[clap(long, requires("wallet_file"))]
+    pub deep_verify: bool,
+
+    /// Check only this many randomly chosen outputs with `--deep-verify` instead of all of them
+    #[clap(long, requires("deep_verify"))]
+    pub deep_verify_sample_size: Option<NonZeroUsize>,
+
     /// DEPRECATED: use start_staking_for_account instead!
     /// Start staking for the DEFAULT account after starting the wallet
     #[clap(long, requires("wallet_file"))]
@@ -97,6 +105,12 @@ pub struct CliArgs {
     #[clap(long)]
     pub node_rpc_address: Option<NetworkAddressWithPort>,
 
+    /// RPC address of a node to switch to if the node in use becomes unreachable or falls behind.
+    /// Can be specified multiple times, the nodes are tried in the given order.
+    /// The nodes must accept the same RPC credentials.
+    #[clap(long)]
+    pub node_rpc_fallback_address: Vec<NetworkAddressWithPort>,
+
     /// Path to the RPC cookie file. If not set, the value is read from the default cookie file location.
     #[clap(long)]
     pub node_rpc_cookie_file: Option<String>,
@@ -133,7 +147,7 @@ pub struct CliArgs {
     pub in_top_x_mb: usize,
 
     /// use the wallet without a connection to a node
-    #[arg(long, conflicts_with_all(["node_rpc_address", "node_rpc_cookie_file", "node_rpc_username", "node_rpc_password"]))]
+    #[arg(long, conflicts_with_all(["node_rpc_address", "node_rpc_fallback_address", "node_rpc_cookie_file", "node_rpc_username", "node_rpc_password"]))]
     pub cold_wallet: bool,
 
     /// enable the RPC interface of the wallet (i.e., run a wallet RPC server with the CLI)
@@ -164,7 +178,7 @@ pub struct CliArgs {
     /// So, you can start an RPC daemon, and make this CLI connect to it to control it.
     /// This is useful for servers, where the RPC wallet can be left staking,
     /// and the wallet CLI is used to control its state.
-    #[arg(long, requires("remote_rpc_auth"), conflicts_with_all(["wallet_file", "wallet_rpc_password", "wallet_rpc_username", "wallet_rpc_cookie_file", "wallet_rpc_no_authentication", "wallet_rpc_bind_address", "node_rpc_address", "node_rpc_cookie_file", "node_rpc_username", "node_rpc_password"]))]
+    #[arg(long, requires("remote_rpc_auth"), conflicts_with_all(["wallet_file", "wallet_rpc_password", "wallet_rpc_username", "wallet_rpc_cookie_file", "wallet_rpc_no_authentication", "wallet_rpc_bind_address", "node_rpc_address", "node_rpc_fallback_address", "node_rpc_cookie_file", "node_rpc_username", "node_rpc_password"]))]
     pub remote_rpc_wallet_address: Option<String>,
 
     /// For a remote RPC wallet, this is the path to the RPC cookie file. If not set, the value is read from the default cookie file location.
@@ -32,7 +32,9 @@ use common::chain::{
 use config::{CliArgs, Network};
 use console::{ConsoleInput, ConsoleOutput};
 use errors::WalletCliError;
-use node_comm::{make_cold_wallet_rpc_client, make_rpc_client, rpc_client::ColdWalletClient};
+use node_comm::{
+    make_cold_wallet_rpc_client, make_rpc_client_with_fallbacks, rpc_client::ColdWalletClient,
+};
 use rpc::RpcAuthData;
 use tokio::sync::mpsc;
 use utils::{cookie::COOKIE_FILENAME, default_data_dir::default_data_dir_for_chain, ensure};
@@ -154,11 +156,19 @@ async fn start_hot_wallet(
         .into();
         cli_args.node_rpc_address.clone().unwrap_or(default_addr)
     };
+    let fallback_rpc_addresses =
+        cli_args.node_rpc_fallback_address.iter().map(ToString::to_string).collect();
 
     let (repl_handle, wallet_rpc_config) =
         setup_events_and_repl(cli_args, mode, output, input, event_tx, chain_type)?;
 
-    let node_rpc = make_rpc_client(chain_config.clone(), rpc_address.to_string(), rpc_auth).await?;
+    let node_rpc = make_rpc_client_with_fallbacks(
+        chain_config.clone(),
+        rpc_address.to_string(),
+        fallback_rpc_addresses,
+        rpc_auth,
+    )
+    .await?;
     cli_event_loop::run(
         &chain_config.clone(),
         event_rx,
@@ -284,6 +294,8 @@ fn setup_events_and_repl<N: NodeInterface + Send + Sync + 'static>(
                         wallet_path,
                         encryption_password: args.wallet_password,
                         force_change_wallet_type: args.force_change_wallet_type,
+                        deep_verify: args.deep_verify,
+                        deep_verify_sample_size: args.deep_verify_sample_size,
                     },
                 ),
                 res_tx,
@@ -98,9 +98,12 @@ impl CliTestFramework {
                     wallet_file: None,
                     wallet_password: None,
                     force_change_wallet_type: false,
+                    deep_verify: false,
+                    deep_verify_sample_size: None,
                     start_staking: false,
                     start_staking_for_account: vec![],
                     node_rpc_address: Some(rpc_address.into()),
+                    node_rpc_fallback_address: vec![],
                     node_rpc_cookie_file: None,
                     node_rpc_username: Some(RPC_USERNAME.to_owned()),
                     node_rpc_password: Some(RPC_PASSWORD.to_owned()),
@@ -127,9 +130,12 @@ impl CliTestFramework {
                 wallet_file: None,
                 wallet_password: None,
                 force_change_wallet_type: false,
+                deep_verify: false,
+                deep_verify_sample_size: None,
                 start_staking: false,
                 start_staking_for_account: vec![],
                 node_rpc_address: Some(rpc_address.into()),
+                node_rpc_fallback_address: vec![],
                 node_rpc_cookie_file: None,
                 node_rpc_username: Some(RPC_USERNAME.to_owned()),
                 node_rpc_password: Some(RPC_PASSWORD.to_owned()),
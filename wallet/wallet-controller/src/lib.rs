@@ -23,13 +23,22 @@ pub mod types;
 
 const NORMAL_DELAY: Duration = Duration::from_secs(1);
 const ERROR_DELAY: Duration = Duration::from_secs(10);
+const NODE_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// How many blocks the tip of a node may be behind the best header received from its peers,
+/// or behind the tips of the other configured nodes, before another node is preferred
+const MAX_NODE_LAG_BLOCKS: u64 = 10;
+/// How often the broadcast queue is checked for transactions to be submitted
+const BROADCAST_QUEUE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// How long accepted and rejected transactions are kept in the broadcast queue
+const BROADCAST_QUEUE_RETENTION_SECS: u64 = 7 * 24 * 60 * 60;
+/// How many outputs are looked up in the node's UTXO set with a single call when verifying them
+const UTXO_VERIFICATION_BATCH_SIZE: usize = 1000;
 
 use blockprod::BlockProductionError;
 use chainstate::tx_verifier::{
     self, error::ScriptError, input_check::signature_only_check::SignatureOnlyVerifiable,
 };
 use futures::{
-    never::Never,
     stream::{FuturesOrdered, FuturesUnordered},
     TryStreamExt,
 };
@@ -37,30 +46,35 @@ use node_comm::rpc_client::ColdWalletClient;
 use std::{
     collections::{BTreeMap, BTreeSet},
     fs,
+    num::NonZeroUsize,
     ops::Add,
     path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
 use types::{
-    Balances, GenericCurrencyTransferToTxOutputConversionError, InspectTransaction,
-    SeedWithPassPhrase, SignatureStats, TransactionToInspect, ValidatedSignatures, WalletInfo,
+    Balances, BlockInfo, GenericCurrencyTransferToTxOutputConversionError, InspectTransaction,
+    SeedWithPassPhrase, SignatureStats, StakingEstimate, SyncStatus, TransactionToInspect,
+    UtxoDiscrepancy, UtxoDiscrepancyKind, UtxoVerificationReport, ValidatedSignatures, WalletInfo,
 };
 
 use read::ReadOnlyController;
-use sync::InSync;
+use sync::{InSync, SyncStep};
 use synced_controller::SyncedController;
 
 use common::{
-    address::{AddressError, RpcAddress},
+    address::{Address, AddressError, RpcAddress},
     chain::{
-        block::timestamp::BlockTimestamp,
+        block::{timestamp::BlockTimestamp, ConsensusData},
         htlc::HtlcSecret,
         partially_signed_transaction::PartiallySignedTransaction,
-        signature::{inputsig::InputWitness, DestinationSigError, Transactable},
+        signature::{
+            inputsig::InputWitness, sighash::sighashtype::SigHashType, DestinationSigError,
+            Transactable,
+        },
         tokens::{RPCTokenInfo, TokenId},
-        Block, ChainConfig, Destination, GenBlock, PoolId, SignedTransaction, Transaction, TxInput,
-        TxOutput, UtxoOutPoint,
+        Block, ChainConfig, Destination, GenBlock, GenBlockId, PoSStatus, PoolId,
+        RequiredConsensus, SignedTransaction, Transaction, TxInput, TxOutput, UtxoOutPoint,
     },
     primitives::{
         amount::RpcAmountOut,
@@ -68,34 +82,46 @@ use common::{
         Amount, BlockHeight, Id, Idable,
     },
 };
-use consensus::{GenerateBlockInputData, PoSTimestampSearchInputData};
+use consensus::{
+    ConsensusPoSError, EffectivePoolBalanceError, GenerateBlockInputData,
+    PoSTimestampSearchInputData,
+};
 use crypto::{ephemeral_e2e::EndToEndPrivateKey, key::hdkd::u31::U31};
 use logging::log;
-use mempool::tx_accumulator::PackingStrategy;
+use mempool::{tx_accumulator::PackingStrategy, FeeRate};
 pub use node_comm::node_traits::{ConnectedPeer, NodeInterface, PeerId};
 pub use node_comm::{
     handles_client::WalletHandlesClient, make_cold_wallet_rpc_client, make_rpc_client,
-    rpc_client::NodeRpcClient,
+    make_rpc_client_with_fallbacks, rpc_client::NodeRpcClient,
 };
-use randomness::{make_pseudo_rng, make_true_rng, Rng};
+use randomness::{make_pseudo_rng, make_true_rng, Rng, SliceRandom};
 use wallet::{
     account::{
         currency_grouper::{self, Currency},
         TransactionToSign,
     },
     destination_getters::{get_tx_output_destination, HtlcSpendingCondition},
-    wallet::WalletPoolsFilter,
+    signer::check_sighash_type,
+    wallet::{SalvageReport, WalletPoolsFilter},
     wallet_events::WalletEvents,
     DefaultWallet, WalletError, WalletResult,
 };
+use wallet_types::{
+    account_info::ScheduledPayment,
+    address_book::Payee,
+    broadcast_queue::{BroadcastPolicy, BroadcastQueueEntry, BroadcastStatus},
+    seed_phrase::StoreSeedPhrase,
+    signature_status::SignatureStatus,
+    signing_log::SigningLogEntry,
+    token_trust::TokenTrust,
+    wallet_tx::TxState,
+    wallet_type::WalletType,
+    with_locked::WithLocked,
+};
 pub use wallet_types::{
     account_info::DEFAULT_ACCOUNT_INDEX,
     utxo_types::{UtxoState, UtxoStates, UtxoType, UtxoTypes},
 };
-use wallet_types::{
-    seed_phrase::StoreSeedPhrase, signature_status::SignatureStatus, wallet_type::WalletType,
-    with_locked::WithLocked,
-};
 
 #[derive(thiserror::Error, Debug)]
 pub enum ControllerError<T: NodeInterface> {
@@ -141,6 +167,46 @@ pub enum ControllerError<T: NodeInterface> {
     InvalidTxOutput(GenericCurrencyTransferToTxOutputConversionError),
     #[error("The specified token {0} is not a fungible token")]
     NotFungibleToken(TokenId),
+    #[error("Sighash types can only be specified for a partially signed transaction")]
+    SigHashTypesWithOnlyTransaction,
+    #[error("Wallet file {0} is damaged and cannot be opened. The recoverable keys and accounts have been saved to {1}, open that file instead and let it rescan the blockchain. Data that could not be recovered: {2:?}")]
+    WalletFileSalvaged(PathBuf, PathBuf, Vec<String>),
+    #[error("Not enough confirmed balance for the scheduled payment: {0:?}, required: {1:?}")]
+    InsufficientScheduledPaymentBalance(Amount, Amount),
+    #[error("The new authority of token {0} is the same as the current one")]
+    TokenAuthorityUnchanged(TokenId),
+    #[error("The new authority of token {0} is not controlled by this wallet and the control over the token would be lost; use force to change it anyway")]
+    TokenAuthorityNotControlled(TokenId),
+    #[error("The chain does not use proof of stake at height {0}")]
+    NotProofOfStake(BlockHeight),
+    #[error("PoS consensus error: {0}")]
+    ConsensusPoSError(#[from] ConsensusPoSError),
+    #[error("Effective pool balance error: {0}")]
+    EffectivePoolBalanceError(#[from] EffectivePoolBalanceError),
+    #[error("Transaction {0} is not in the broadcast queue")]
+    TransactionNotInBroadcastQueue(Id<Transaction>),
+    #[error("The transaction package is not fully signed")]
+    TransactionPackageNotFullySigned,
+    #[error("The fee rate {0:?} is below the current mempool minimum fee rate {1:?}")]
+    FeeRateBelowMempoolMinimum(FeeRate, FeeRate),
+    #[error("The absolute fee {0:?} is below the fee {1:?} required by the current mempool minimum fee rate")]
+    AbsoluteFeeTooLow(Amount, Amount),
+    #[error("An absolute fee can only be specified when sending coins or tokens to an address")]
+    AbsoluteFeeNotSupported,
+    #[error("The wallet is not in sync with the node tip, the outputs cannot be verified")]
+    WalletNotInSyncWithNode,
+    #[error("The maximum number of payouts per transaction must be greater than zero")]
+    InvalidMaxPayoutsPerTx,
+}
+
+/// Overrides the fee the controller would otherwise derive from the mempool fee rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeOverride {
+    /// Use this fee rate instead of the one estimated by the node;
+    /// it must not be below the current mempool minimum fee rate
+    FeeRate(FeeRate),
+    /// Pay exactly this amount of coins as the transaction fee
+    AbsoluteFee(Amount),
 }
 
 #[derive(Clone, Copy)]
@@ -153,8 +219,19 @@ pub struct ControllerConfig {
     /// Should the controller broadcast the created transactions to the mempool
     /// Set to False by the GUI wallet to allow for a confirmation dialog before broadcasting
     pub broadcast_to_mempool: bool,
+
+    /// Custom fee for the created transactions, if not set the fee is derived from the fee rate
+    /// needed to get into the top `in_top_x_mb` MB of the mempool
+    pub fee_override: Option<FeeOverride>,
 }
 
+/// The scheduled payments aim for the top 5 MB of the mempool
+const SCHEDULED_PAYMENTS_CONFIG: ControllerConfig = ControllerConfig {
+    in_top_x_mb: 5,
+    broadcast_to_mempool: true,
+    fee_override: None,
+};
+
 pub struct Controller<T, W> {
     chain_config: Arc<ChainConfig>,
 
@@ -165,6 +242,18 @@ pub struct Controller<T, W> {
     staking_started: BTreeSet<U31>,
 
     wallet_events: W,
+
+    /// When the wallet was last in sync with the node
+    last_synced_at: Option<BlockTimestamp>,
+
+    /// Set while a stepped sync (see `sync_step`) hasn't reached the node tip yet
+    sync_in_progress: bool,
+
+    /// When the background task should next process the broadcast queue
+    broadcast_queue_timer: Time,
+
+    /// When the background task should next check the node health
+    node_health_check_timer: Time,
 }
 
 impl<T, WalletEvents> std::fmt::Debug for Controller<T, WalletEvents> {
@@ -190,6 +279,10 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
             wallet,
             staking_started: BTreeSet::new(),
             wallet_events,
+            last_synced_at: None,
+            sync_in_progress: false,
+            broadcast_queue_timer: get_time(),
+            node_health_check_timer: get_time(),
         };
 
         log::info!("Syncing the wallet...");
@@ -210,6 +303,10 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
             wallet,
             staking_started: BTreeSet::new(),
             wallet_events,
+            last_synced_at: None,
+            sync_in_progress: false,
+            broadcast_queue_timer: get_time(),
+            node_health_check_timer: get_time(),
         }
     }
 
@@ -277,6 +374,42 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         Ok(wallet)
     }
 
+    /// Create a watch-only replica of another wallet from its encrypted watch-only export
+    pub fn create_wallet_from_watch_only_export(
+        chain_config: Arc<ChainConfig>,
+        file_path: impl AsRef<Path>,
+        data: &[u8],
+        passphrase: &String,
+        wallet_type: WalletType,
+    ) -> Result<DefaultWallet, ControllerError<T>> {
+        utils::ensure!(
+            !file_path.as_ref().exists(),
+            ControllerError::WalletFileError(
+                file_path.as_ref().to_owned(),
+                "File already exists".to_owned()
+            )
+        );
+
+        let db = wallet::wallet::open_or_create_wallet_file(&file_path)
+            .map_err(ControllerError::WalletError)?;
+        match wallet::Wallet::create_from_watch_only_export(
+            Arc::clone(&chain_config),
+            db,
+            data,
+            passphrase,
+            wallet_type,
+        ) {
+            Ok(wallet) => Ok(wallet),
+            Err(err) => {
+                // Don't leave an empty wallet file behind
+                if let Err(remove_err) = fs::remove_file(&file_path) {
+                    logging::log::error!("Failed to remove the wallet file: {remove_err}");
+                }
+                Err(ControllerError::WalletError(err))
+            }
+        }
+    }
+
     fn make_backup_wallet_file(file_path: impl AsRef<Path>, version: u32) -> WalletResult<()> {
         let backup_name = file_path
             .as_ref()
@@ -325,14 +458,78 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         let wallet = wallet::Wallet::load_wallet(
             Arc::clone(&chain_config),
             db,
-            password,
+            password.clone(),
             |version| Self::make_backup_wallet_file(file_path.as_ref(), version),
             wallet_type,
             force_change_wallet_type,
-        )
-        .map_err(ControllerError::WalletError)?;
+        );
 
-        Ok(wallet)
+        match wallet {
+            Ok(wallet) => Ok(wallet),
+            Err(err) if err.is_db_corruption() => {
+                logging::log::error!("The wallet DB is damaged: {err}, trying to salvage it");
+                match Self::salvage_wallet_file(
+                    chain_config,
+                    file_path.as_ref(),
+                    password,
+                    wallet_type,
+                ) {
+                    Ok((salvaged_file_path, report)) => Err(ControllerError::WalletFileSalvaged(
+                        file_path.as_ref().to_owned(),
+                        salvaged_file_path,
+                        report.warnings,
+                    )),
+                    Err(salvage_err) => {
+                        logging::log::error!("Failed to salvage the wallet DB: {salvage_err}");
+                        Err(ControllerError::WalletError(err))
+                    }
+                }
+            }
+            Err(err) => Err(ControllerError::WalletError(err)),
+        }
+    }
+
+    /// Save whatever can be recovered from a damaged wallet file into a new file next to it.
+    ///
+    /// The original file is left untouched.
+    fn salvage_wallet_file(
+        chain_config: Arc<ChainConfig>,
+        file_path: &Path,
+        password: Option<String>,
+        wallet_type: WalletType,
+    ) -> Result<(PathBuf, SalvageReport), ControllerError<T>> {
+        let salvaged_name = file_path
+            .file_name()
+            .map(|file_name| {
+                let mut file_name = file_name.to_os_string();
+                file_name.push("_salvaged");
+                file_name
+            })
+            .ok_or(ControllerError::WalletFileError(
+                file_path.to_owned(),
+                "File path is not a file".to_owned(),
+            ))?;
+        let salvaged_file_path = file_path.with_file_name(salvaged_name);
+        utils::ensure!(
+            !salvaged_file_path.exists(),
+            ControllerError::WalletFileError(salvaged_file_path, "File already exists".to_owned())
+        );
+
+        let db = wallet::wallet::open_or_create_wallet_file(file_path)
+            .map_err(ControllerError::WalletError)?;
+        let new_db = wallet::wallet::open_or_create_wallet_file(&salvaged_file_path)
+            .map_err(ControllerError::WalletError)?;
+
+        match wallet::Wallet::salvage_wallet(chain_config, db, new_db, password, wallet_type) {
+            Ok((_wallet, report)) => Ok((salvaged_file_path, report)),
+            Err(err) => {
+                // Don't leave a half-written wallet file behind
+                if let Err(remove_err) = fs::remove_file(&salvaged_file_path) {
+                    logging::log::error!("Failed to remove the salvaged wallet file: {remove_err}");
+                }
+                Err(ControllerError::WalletError(err))
+            }
+        }
     }
 
     pub fn seed_phrase(&self) -> Result<Option<SeedWithPassPhrase>, ControllerError<T>> {
@@ -350,10 +547,211 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
             .map_err(ControllerError::WalletError)
     }
 
+    /// Get the entries of the signing log with the timestamps in the specified range
+    pub fn signing_log(
+        &self,
+        from: Option<BlockTimestamp>,
+        to: Option<BlockTimestamp>,
+    ) -> Result<Vec<SigningLogEntry>, ControllerError<T>> {
+        self.wallet.get_signing_log(from, to).map_err(ControllerError::WalletError)
+    }
+
+    /// Export the public data of the wallet encrypted with the passphrase, see
+    /// [wallet::Wallet::export_watch_only]
+    pub fn export_watch_only(
+        &self,
+        passphrase: &String,
+        since_height: Option<BlockHeight>,
+    ) -> Result<Vec<u8>, ControllerError<T>> {
+        self.wallet
+            .export_watch_only(passphrase, since_height)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Update this watch-only replica with a newer export of the original wallet
+    pub fn apply_watch_only_update(
+        &mut self,
+        data: &[u8],
+        passphrase: &String,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .apply_watch_only_update(data, passphrase)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Mark the token as trusted or hidden, or remove the mark if `trust` is `None`
+    pub fn set_token_trust(
+        &mut self,
+        token_id: TokenId,
+        trust: Option<TokenTrust>,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .set_token_trust(token_id, trust)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn token_trust_list(&self) -> Result<BTreeMap<TokenId, TokenTrust>, ControllerError<T>> {
+        self.wallet.get_token_trust_list().map_err(ControllerError::WalletError)
+    }
+
+    /// Add the payees to the address book, replacing the existing ones with the same names
+    pub fn set_payees(
+        &mut self,
+        payees: BTreeMap<String, Payee>,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet.set_payees(payees).map_err(ControllerError::WalletError)
+    }
+
+    pub fn remove_payee(&mut self, name: &str) -> Result<(), ControllerError<T>> {
+        self.wallet.remove_payee(name).map_err(ControllerError::WalletError)
+    }
+
+    pub fn payee(&self, name: &str) -> Result<Payee, ControllerError<T>> {
+        self.wallet.get_payee(name).map_err(ControllerError::WalletError)
+    }
+
+    pub fn payees(&self) -> Result<BTreeMap<String, Payee>, ControllerError<T>> {
+        self.wallet.get_payees().map_err(ControllerError::WalletError)
+    }
+
+    /// The transactions queued for broadcasting, including the recently accepted or rejected ones
+    pub fn broadcast_queue(
+        &self,
+    ) -> Result<BTreeMap<Id<Transaction>, BroadcastQueueEntry>, ControllerError<T>> {
+        self.wallet.get_broadcast_queue().map_err(ControllerError::WalletError)
+    }
+
+    pub fn broadcast_queue_entry(
+        &self,
+        tx_id: Id<Transaction>,
+    ) -> Result<Option<BroadcastQueueEntry>, ControllerError<T>> {
+        self.wallet
+            .get_broadcast_queue_entry(tx_id)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Change the policy of a queued transaction, the new limits apply from the next attempt
+    pub fn set_broadcast_policy(
+        &mut self,
+        tx_id: Id<Transaction>,
+        policy: BroadcastPolicy,
+    ) -> Result<(), ControllerError<T>> {
+        let mut entry = self
+            .wallet
+            .get_broadcast_queue_entry(tx_id)
+            .map_err(ControllerError::WalletError)?
+            .ok_or(ControllerError::TransactionNotInBroadcastQueue(tx_id))?;
+        entry.policy = policy;
+        self.wallet
+            .update_broadcast_queue_entry(tx_id, &entry)
+            .map_err(ControllerError::WalletError)
+    }
+
     /// Rescan the blockchain
     /// Resets the wallet to the genesis block
     pub fn reset_wallet_to_genesis(&mut self) -> Result<(), ControllerError<T>> {
-        self.wallet.reset_wallet_to_genesis().map_err(ControllerError::WalletError)
+        self.wallet.reset_wallet_to_genesis().map_err(ControllerError::WalletError)?;
+        self.last_synced_at = None;
+        Ok(())
+    }
+
+    /// Check the outputs tracked by the wallet against the node's UTXO set.
+    ///
+    /// If `sample_size` is set, only that many randomly chosen outputs are checked, otherwise
+    /// all of them are. The wallet must be in sync with the node tip for the states to be comparable.
+    /// If `repair` is set and discrepancies are found, the wallet is rewound to just below
+    /// the earliest affected block, so the affected blocks are scanned again with the next sync.
+    pub async fn verify_utxos(
+        &mut self,
+        sample_size: Option<NonZeroUsize>,
+        repair: bool,
+    ) -> Result<UtxoVerificationReport, ControllerError<T>> {
+        let (best_block_id, best_block_height) = self.best_block();
+        self.ensure_in_sync_with_node(best_block_id).await?;
+
+        let mut utxos = self.wallet.get_confirmed_utxos();
+        let tracked_count = utxos.len();
+        if let Some(sample_size) = sample_size {
+            utxos = utxos
+                .choose_multiple(&mut make_true_rng(), sample_size.get())
+                .cloned()
+                .collect();
+        }
+
+        let mut discrepancies = Vec::new();
+        for batch in utxos.chunks(UTXO_VERIFICATION_BATCH_SIZE) {
+            let outpoints = batch.iter().map(|(_, utxo)| utxo.outpoint.clone()).collect();
+            let node_outputs = self
+                .rpc_client
+                .get_utxos(outpoints)
+                .await
+                .map_err(ControllerError::NodeCallError)?;
+
+            for ((account, utxo), node_output) in batch.iter().zip(node_outputs) {
+                let discrepancy = match (utxo.spent_at, node_output.is_some()) {
+                    // The spending transaction could be in any block after the output was created
+                    (None, false) => Some((UtxoDiscrepancyKind::SpentButTracked, utxo.created_at)),
+                    (Some(spent_at), true) => Some((UtxoDiscrepancyKind::Missing, spent_at)),
+                    (None, true) | (Some(_), false) => None,
+                };
+
+                if let Some((kind, rescan_from)) = discrepancy {
+                    discrepancies.push(UtxoDiscrepancy {
+                        account: *account,
+                        outpoint: utxo.outpoint.clone(),
+                        kind,
+                        rescan_from,
+                    });
+                }
+            }
+        }
+
+        // The node could have moved on while the outputs were being checked
+        self.ensure_in_sync_with_node(best_block_id).await?;
+
+        let rewound_to = match discrepancies.iter().map(|d| d.rescan_from).min() {
+            Some(rescan_from) if repair => {
+                let height = rescan_from.prev_height().unwrap_or(BlockHeight::zero());
+                let block_id = self
+                    .rpc_client
+                    .get_block_id_at_height(height)
+                    .await
+                    .map_err(ControllerError::NodeCallError)?
+                    .ok_or(ControllerError::WalletNotInSyncWithNode)?;
+
+                self.wallet
+                    .rewind_to_height(height, block_id, &self.wallet_events)
+                    .map_err(ControllerError::WalletError)?;
+                self.last_synced_at = None;
+
+                Some(BlockInfo::from_tuple((block_id, height)))
+            }
+            Some(_) | None => None,
+        };
+
+        Ok(UtxoVerificationReport {
+            checked_at: best_block_height,
+            tracked_count,
+            checked_count: utxos.len(),
+            discrepancies,
+            rewound_to,
+        })
+    }
+
+    async fn ensure_in_sync_with_node(
+        &self,
+        best_block_id: Id<GenBlock>,
+    ) -> Result<(), ControllerError<T>> {
+        let node_best_block_id = self
+            .rpc_client
+            .get_best_block_id()
+            .await
+            .map_err(ControllerError::NodeCallError)?;
+        utils::ensure!(
+            node_best_block_id == best_block_id,
+            ControllerError::WalletNotInSyncWithNode
+        );
+        Ok(())
     }
 
     /// Encrypts the wallet using the specified `password`, or removes the existing encryption if `password` is `None`.
@@ -593,6 +991,18 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
             .map_err(ControllerError::WalletError)
     }
 
+    /// Set the label of an address issued by the account, or remove it if the label is None
+    pub fn set_address_label(
+        &mut self,
+        account_index: U31,
+        address: Destination,
+        label: Option<String>,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .set_address_label(account_index, address, label)
+            .map_err(ControllerError::WalletError)
+    }
+
     pub fn stop_staking(&mut self, account_index: U31) -> Result<(), ControllerError<T>> {
         log::info!("Stop staking, account_index: {}", account_index);
         self.staking_started.remove(&account_index);
@@ -662,26 +1072,82 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
             &mut self.wallet,
             &self.wallet_events,
         )
-        .await?;
+        .await;
+        self.update_sync_status(res.as_ref().ok());
 
-        match res {
+        match res? {
             InSync::Synced => Ok(()),
             InSync::NodeOutOfSync => Err(ControllerError::NodeNotInSyncYet),
         }
     }
 
     pub async fn try_sync_once(&mut self) -> Result<(), ControllerError<T>> {
-        sync::sync_once(
+        let res = sync::sync_once(
             &self.chain_config,
             &self.rpc_client,
             &mut self.wallet,
             &self.wallet_events,
         )
-        .await?;
+        .await;
+        self.update_sync_status(res.as_ref().ok());
+        res?;
 
         Ok(())
     }
 
+    /// Scan the next batch of blocks and return whether the wallet is synced to the node tip.
+    ///
+    /// Should be called repeatedly until it returns true. Other requests can be handled between
+    /// the calls, e.g. the read-only ones during a long rescan. Until the sync is done,
+    /// `sync_status` reports that the sync is in progress.
+    pub async fn sync_step(&mut self) -> Result<bool, ControllerError<T>> {
+        let res = sync::sync_step(
+            &self.chain_config,
+            &self.rpc_client,
+            &mut self.wallet,
+            &self.wallet_events,
+        )
+        .await;
+
+        match res {
+            Ok(SyncStep::Done(in_sync)) => {
+                self.update_sync_status(Some(&in_sync));
+                match in_sync {
+                    InSync::Synced => Ok(true),
+                    InSync::NodeOutOfSync => Err(ControllerError::NodeNotInSyncYet),
+                }
+            }
+            Ok(SyncStep::InProgress { .. }) => {
+                self.sync_in_progress = true;
+                Ok(false)
+            }
+            Err(err) => {
+                self.update_sync_status(None);
+                Err(err)
+            }
+        }
+    }
+
+    fn update_sync_status(&mut self, res: Option<&InSync>) {
+        self.sync_in_progress = false;
+        match res {
+            Some(InSync::Synced) => {
+                self.last_synced_at = Some(BlockTimestamp::from_time(get_time()));
+            }
+            Some(InSync::NodeOutOfSync) | None => {}
+        }
+    }
+
+    /// How up to date the wallet data is
+    pub fn sync_status(&self) -> SyncStatus {
+        SyncStatus {
+            best_synced_block: BlockInfo::from_tuple(self.best_block()),
+            median_time: self.wallet.latest_median_time(),
+            last_synced_at: self.last_synced_at,
+            sync_in_progress: self.sync_in_progress,
+        }
+    }
+
     pub async fn synced_controller(
         &mut self,
         account_index: U31,
@@ -705,6 +1171,7 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
             self.rpc_client.clone(),
             self.chain_config.as_ref(),
             account_index,
+            self.sync_status(),
         )
     }
 
@@ -913,6 +1380,7 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         inputs: Vec<UtxoOutPoint>,
         outputs: Vec<TxOutput>,
         htlc_secrets: Option<Vec<Option<HtlcSecret>>>,
+        sighash_types: Option<Vec<SigHashType>>,
         only_transaction: bool,
     ) -> Result<(TransactionToSign, Balances), ControllerError<T>> {
         let input_utxos = self.fetch_utxos(&inputs).await?;
@@ -924,6 +1392,19 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         let tx = Transaction::new(0, inputs, outputs)
             .map_err(|err| ControllerError::WalletError(WalletError::TransactionCreation(err)))?;
 
+        if let Some(sighash_types) = &sighash_types {
+            // A plain transaction can't carry the sighash types, they would be silently lost
+            utils::ensure!(
+                !only_transaction,
+                ControllerError::SigHashTypesWithOnlyTransaction
+            );
+
+            for (input_index, sighash_type) in sighash_types.iter().enumerate() {
+                check_sighash_type(&tx, input_index, *sighash_type)
+                    .map_err(|err| ControllerError::WalletError(err.into()))?;
+            }
+        }
+
         let tx = if only_transaction {
             TransactionToSign::Tx(tx)
         } else {
@@ -951,6 +1432,7 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
                 input_utxos.into_iter().map(Option::Some).collect(),
                 destinations.into_iter().map(Option::Some).collect(),
                 htlc_secrets,
+                sighash_types,
             )
             .map_err(WalletError::TransactionCreation)?;
 
@@ -960,6 +1442,39 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         Ok((tx, fees))
     }
 
+    /// Calculate the coin fee paid by a transaction created by this wallet.
+    /// The inputs are looked up among the outputs known to the wallet first, because they are
+    /// already spent if the transaction has been broadcast.
+    pub async fn get_transaction_coin_fee(
+        &self,
+        tx: &Transaction,
+    ) -> Result<Amount, ControllerError<T>> {
+        let mut input_utxos = Vec::with_capacity(tx.inputs().len());
+        for input in tx.inputs() {
+            match input {
+                TxInput::Utxo(outpoint) => {
+                    let utxo = match self.wallet.find_txo(outpoint) {
+                        Some(utxo) => utxo,
+                        None => self.fetch_utxo(outpoint).await?,
+                    };
+                    input_utxos.push(utxo);
+                }
+                TxInput::Account(_) | TxInput::AccountCommand(_, _) => {}
+            }
+        }
+
+        let input_coins =
+            self.group_inputs(&input_utxos)?.remove(&Currency::Coin).unwrap_or(Amount::ZERO);
+        let output_coins = self
+            .group_outputs(tx.outputs())?
+            .remove(&Currency::Coin)
+            .unwrap_or(Amount::ZERO);
+
+        (input_coins - output_coins).ok_or(ControllerError::WalletError(
+            WalletError::NotEnoughUtxo(input_coins, output_coins),
+        ))
+    }
+
     async fn get_fees(
         &self,
         inputs: &[TxOutput],
@@ -1057,80 +1572,303 @@ impl<T: NodeInterface + Clone + Send + Sync + 'static, W: WalletEvents> Controll
         }
     }
 
-    /// Synchronize the wallet in the background from the node's blockchain.
-    /// Try staking new blocks if staking was started.
-    pub async fn run(&mut self) -> Result<Never, ControllerError<T>> {
-        let mut rebroadcast_txs_timer = get_time();
-        let staking_started = self.staking_started.clone();
+    /// Synchronize the wallet in the background from the node's blockchain, one batch of blocks
+    /// per call. Once synced, try staking new blocks if staking was started and process
+    /// the due maintenance tasks.
+    ///
+    /// Should be called repeatedly, waiting for the returned delay in between. The controller
+    /// isn't borrowed in the meantime, so it can serve e.g. the read-only requests during
+    /// a long sync.
+    pub async fn run_step(&mut self) -> Duration {
+        match self.sync_step().await {
+            Ok(true) => {}
+            Ok(false) => return Duration::ZERO,
+            Err(e) => {
+                log::error!("Wallet sync error: {e}");
+                self.check_node_health().await;
+                return ERROR_DELAY;
+            }
+        }
+
+        for account_index in self.staking_started.clone() {
+            let generate_res = self
+                .generate_block(
+                    account_index,
+                    vec![],
+                    vec![],
+                    PackingStrategy::FillSpaceFromMempool,
+                )
+                .await;
+
+            if let Ok(block) = generate_res {
+                log::info!(
+                    "New block generated successfully, with block id: {:x}",
+                    block.get_id()
+                );
 
-        'outer: loop {
-            let sync_res = self.sync_once().await;
+                let submit_res = self.rpc_client.submit_block(block).await;
+                if let Err(e) = submit_res {
+                    log::error!("Block submit failed: {e}");
+                    return ERROR_DELAY;
+                }
 
-            if let Err(e) = sync_res {
-                log::error!("Wallet sync error: {e}");
-                tokio::time::sleep(ERROR_DELAY).await;
-                continue;
+                return Duration::ZERO;
             }
+        }
 
-            for account_index in staking_started.iter() {
-                let generate_res = self
-                    .generate_block(
-                        *account_index,
-                        vec![],
-                        vec![],
-                        PackingStrategy::FillSpaceFromMempool,
-                    )
-                    .await;
-
-                if let Ok(block) = generate_res {
+        if get_time() >= self.broadcast_queue_timer {
+            self.process_broadcast_queue().await;
+            self.broadcast_queue_timer = (get_time() + BROADCAST_QUEUE_CHECK_INTERVAL)
+                .expect("Broadcast queue check interval cannot be this large");
+        }
+
+        self.process_scheduled_payments().await;
+
+        if get_time() >= self.node_health_check_timer {
+            self.check_node_health().await;
+            self.node_health_check_timer = (get_time() + NODE_HEALTH_CHECK_INTERVAL)
+                .expect("Health check interval cannot be this large");
+        }
+
+        NORMAL_DELAY
+    }
+
+    /// Execute the scheduled payments that are due.
+    /// Failed payments are retried later with an exponential backoff.
+    async fn process_scheduled_payments(&mut self) {
+        let now = BlockTimestamp::from_time(get_time());
+        let due_payments = self.wallet.get_due_scheduled_payments(now);
+
+        for (account_index, payment_id, mut payment) in due_payments {
+            // On success the advanced schedule is saved together with the transaction
+            match self.execute_scheduled_payment(account_index, payment_id, &payment, now).await {
+                Ok(tx) => {
                     log::info!(
-                        "New block generated successfully, with block id: {:x}",
-                        block.get_id()
+                        "Scheduled payment {payment_id} of account {account_index} sent in transaction {}",
+                        tx.transaction().get_id()
                     );
-
-                    let submit_res = self.rpc_client.submit_block(block).await;
-                    if let Err(e) = submit_res {
-                        log::error!("Block submit failed: {e}");
-                        tokio::time::sleep(ERROR_DELAY).await;
+                }
+                Err(error) => {
+                    log::warn!(
+                        "Scheduled payment {payment_id} of account {account_index} failed: {error}"
+                    );
+                    payment.on_failure(now, error.to_string());
+                    if let Err(error) =
+                        self.wallet.update_scheduled_payment(account_index, payment_id, payment)
+                    {
+                        log::error!("Updating scheduled payment {payment_id} failed: {error}");
                     }
-
-                    continue 'outer;
                 }
             }
+        }
+    }
+
+    async fn execute_scheduled_payment(
+        &mut self,
+        account_index: U31,
+        payment_id: u64,
+        payment: &ScheduledPayment,
+        now: BlockTimestamp,
+    ) -> Result<SignedTransaction, ControllerError<T>> {
+        let currency = payment.token_id.map_or(Currency::Coin, Currency::Token);
+        let balance = self
+            .wallet
+            .get_balance(
+                account_index,
+                UtxoState::Confirmed.into(),
+                WithLocked::Unlocked,
+            )?
+            .remove(&currency)
+            .unwrap_or(Amount::ZERO);
+        utils::ensure!(
+            balance >= payment.amount,
+            ControllerError::InsufficientScheduledPaymentBalance(balance, payment.amount)
+        );
+
+        let address = Address::new(&self.chain_config, payment.destination.clone())?;
+        let token_info = match payment.token_id {
+            Some(token_id) => Some(fetch_token_info(&self.rpc_client, token_id).await?),
+            None => None,
+        };
+
+        // The transaction is broadcast once the schedule is advanced
+        let config = ControllerConfig {
+            broadcast_to_mempool: false,
+            ..SCHEDULED_PAYMENTS_CONFIG
+        };
+        let mut synced_controller = self.synced_controller(account_index, config).await?;
+        let tx = match token_info {
+            None => synced_controller.send_to_address(address, payment.amount, vec![]).await?,
+            Some(token_info) => {
+                synced_controller
+                    .send_tokens_to_address(token_info, address, payment.amount)
+                    .await?
+            }
+        };
+
+        let mut payment = payment.clone();
+        payment.on_success(now, tx.transaction().get_id());
+        synced_controller.broadcast_scheduled_payment(payment_id, payment, tx).await
+    }
+
+    /// Check the configured nodes and switch to the most preferred one that is usable.
+    ///
+    /// A node is usable if it is reachable, its tip keeps up with the best header received
+    /// from its peers and it isn't behind the tips of the other configured nodes.
+    /// So the wallet fails over when the node in use stops being usable, and fails back
+    /// to a more preferred node once it is usable again.
+    /// The wallet is then re-synced from the new node in the following sync rounds.
+    async fn check_node_health(&mut self) {
+        let addresses = self.rpc_client.configured_node_addresses();
+        if addresses.len() < 2 {
+            return;
+        }
+
+        let mut tip_heights = Vec::with_capacity(addresses.len());
+        for index in 0..addresses.len() {
+            let tip_height = match self.rpc_client.p2p_get_node_sync_progress(index).await {
+                Ok(progress) => {
+                    let lag = progress.best_known_header_height.map_or(0, |height| {
+                        height.into_int().saturating_sub(progress.best_block_height.into_int())
+                    });
+                    if lag > MAX_NODE_LAG_BLOCKS {
+                        Err(format!(
+                            "is {lag} blocks behind the tip reported by its peers"
+                        ))
+                    } else {
+                        Ok(progress.best_block_height)
+                    }
+                }
+                Err(e) => Err(format!("is unreachable: {e}")),
+            };
+            tip_heights.push(tip_height);
+        }
+
+        let Some(best_tip_height) = tip_heights.iter().filter_map(|h| h.as_ref().ok()).max() else {
+            log::warn!("None of the configured nodes is usable");
+            return;
+        };
+        let best_tip_height = *best_tip_height;
+
+        let statuses = tip_heights
+            .into_iter()
+            .map(|tip_height| {
+                tip_height.and_then(|tip_height| {
+                    let lag = best_tip_height.into_int().saturating_sub(tip_height.into_int());
+                    if lag > MAX_NODE_LAG_BLOCKS {
+                        Err(format!("is {lag} blocks behind the other configured nodes"))
+                    } else {
+                        Ok(())
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
 
-            tokio::time::sleep(NORMAL_DELAY).await;
+        let preferred_index = statuses
+            .iter()
+            .position(Result::is_ok)
+            .expect("the node with the best tip is usable");
+        let active_index = self
+            .rpc_client
+            .active_node_address()
+            .and_then(|active| addresses.iter().position(|address| *address == active));
+        if active_index == Some(preferred_index) {
+            return;
+        }
 
-            self.rebroadcast_txs(&mut rebroadcast_txs_timer).await;
+        if let Some(active_index) = active_index {
+            match &statuses[active_index] {
+                Err(problem) => log::warn!("Node {} {problem}", addresses[active_index]),
+                Ok(()) => log::info!(
+                    "Node {} is usable again and preferred to node {}",
+                    addresses[preferred_index],
+                    addresses[active_index]
+                ),
+            }
+        }
+
+        if let Some(new_address) = self.rpc_client.switch_to_node(preferred_index) {
+            log::info!("Switched to node {new_address}");
+            self.wallet_events.node_switched(&new_address);
         }
     }
 
-    /// Rebroadcast not confirmed transactions
-    async fn rebroadcast_txs(&mut self, rebroadcast_txs_again_at: &mut Time) {
-        if get_time() >= *rebroadcast_txs_again_at {
-            let txs = self.wallet.get_transactions_to_be_broadcast();
-            match txs {
-                Err(error) => {
-                    log::error!("Fetching transactions for rebroadcasting failed: {error}");
+    /// Submit the due transactions of the broadcast queue to the node and update the status
+    /// of the queued transactions according to the state of the wallet.
+    async fn process_broadcast_queue(&mut self) {
+        let queue = match self.wallet.get_broadcast_queue() {
+            Ok(queue) => queue,
+            Err(error) => {
+                log::error!("Fetching the broadcast queue failed: {error}");
+                return;
+            }
+        };
+        let now = BlockTimestamp::from_time(get_time());
+
+        for (tx_id, mut entry) in queue {
+            if entry.is_finished() {
+                let expires_at = entry.updated_at.add_int_seconds(BROADCAST_QUEUE_RETENTION_SECS);
+                if expires_at.is_some_and(|expires_at| expires_at < now) {
+                    if let Err(error) = self.wallet.remove_broadcast_queue_entry(tx_id) {
+                        log::error!("Removing tx {tx_id} from the broadcast queue failed: {error}");
+                    }
+                }
+                continue;
+            }
+
+            match self.wallet.find_transaction_state(tx_id) {
+                Some(TxState::Confirmed(_, _, _)) => entry.on_accepted(now),
+                Some(TxState::Conflicted(_)) => entry.on_rejected(
+                    now,
+                    "The transaction conflicts with a confirmed transaction".to_owned(),
+                ),
+                Some(TxState::Abandoned) => {
+                    entry.on_rejected(now, "The transaction has been abandoned".to_owned())
                 }
-                Ok(txs) => {
-                    for tx in txs {
-                        let tx_id = tx.transaction().get_id();
-                        let res = self.rpc_client.submit_transaction(tx, Default::default()).await;
-                        if let Err(e) = res {
-                            log::warn!("Rebroadcasting for tx {tx_id} failed: {e}");
+                Some(TxState::InMempool(_) | TxState::Inactive(_)) | None => {
+                    if entry.is_past_deadline(now) {
+                        entry.on_rejected(
+                            now,
+                            "The transaction was not included in a block before the deadline"
+                                .to_owned(),
+                        );
+                    } else if entry.is_due(now) {
+                        let res = self
+                            .rpc_client
+                            .submit_transaction(entry.tx.clone(), Default::default())
+                            .await;
+                        match res {
+                            Ok(()) => entry.on_sent(now, make_resubmit_delay_secs()),
+                            Err(error) => {
+                                log::warn!("Broadcasting tx {tx_id} failed: {error}");
+                                entry.on_failure(now, error.to_string());
+                            }
                         }
+                    } else {
+                        continue;
                     }
                 }
             }
 
-            // Reset the timer with a new random interval between 2 and 5 minutes
-            let sleep_interval_sec = make_pseudo_rng().gen_range(120..=300);
-            *rebroadcast_txs_again_at = (get_time() + Duration::from_secs(sleep_interval_sec))
-                .expect("Sleep intervals cannot be this large");
+            if let BroadcastStatus::Rejected(_) = entry.status {
+                if let Err(error) = self.wallet.abandon_unbroadcast_transaction(tx_id) {
+                    log::error!("Abandoning the rejected tx {tx_id} failed: {error}");
+                }
+            }
+
+            if let Err(error) = self.wallet.update_broadcast_queue_entry(tx_id, &entry) {
+                log::error!("Updating the broadcast status of tx {tx_id} failed: {error}");
+            }
         }
     }
 }
 
+/// A random delay between 2 and 5 minutes before resubmitting a transaction accepted by the node,
+/// in case it has been evicted from the mempool
+fn make_resubmit_delay_secs() -> u64 {
+    make_pseudo_rng().gen_range(120..=300)
+}
+
 pub async fn fetch_token_info<T: NodeInterface>(
     rpc_client: &T,
     token_id: TokenId,
@@ -1144,6 +1882,63 @@ pub async fn fetch_token_info<T: NodeInterface>(
         )))
 }
 
+/// Estimate the staking outcome of a pool with the given pledge and balance,
+/// using the difficulty of the node's current tip
+pub async fn fetch_staking_estimate<T: NodeInterface>(
+    rpc_client: &T,
+    chain_config: &ChainConfig,
+    pledge: Amount,
+    pool_balance: Amount,
+) -> Result<StakingEstimate, ControllerError<T>> {
+    let chain_info = rpc_client.chainstate_info().await.map_err(ControllerError::NodeCallError)?;
+    let next_height = chain_info.best_block_height.next_height();
+
+    let pos_status = match chain_config.consensus_upgrades().consensus_status(next_height) {
+        RequiredConsensus::PoS(status) => status,
+        RequiredConsensus::PoW(_) | RequiredConsensus::IgnoreConsensus => {
+            return Err(ControllerError::NotProofOfStake(next_height))
+        }
+    };
+
+    let tip_target = match chain_info.best_block_id.classify(chain_config) {
+        GenBlockId::Genesis(_) => None,
+        GenBlockId::Block(block_id) => rpc_client
+            .get_block(block_id)
+            .await
+            .map_err(ControllerError::NodeCallError)?
+            .and_then(|block| match block.consensus_data() {
+                ConsensusData::PoS(pos_data) => Some(pos_data.compact_target()),
+                ConsensusData::None | ConsensusData::PoW(_) => None,
+            }),
+    };
+    // Right after the switch to PoS the initial difficulty is used
+    let target = match tip_target {
+        Some(compact_target) => consensus::compact_target_to_target(compact_target)?,
+        None => match &pos_status {
+            PoSStatus::Threshold {
+                initial_difficulty: Some(compact_target),
+                config: _,
+            } => consensus::compact_target_to_target(*compact_target)?,
+            PoSStatus::Ongoing(config)
+            | PoSStatus::Threshold {
+                initial_difficulty: None,
+                config,
+            } => config.target_limit(),
+        },
+    };
+
+    let estimate = StakingEstimate::new(
+        chain_config,
+        pos_status.get_chain_config().consensus_version(),
+        target,
+        chain_info.best_block_height,
+        pledge,
+        pool_balance,
+    )?;
+
+    Ok(estimate)
+}
+
 pub async fn into_balances<T: NodeInterface>(
     rpc_client: &T,
     chain_config: &ChainConfig,
@@ -1152,14 +1947,28 @@ pub async fn into_balances<T: NodeInterface>(
     let coins = balances.remove(&Currency::Coin).unwrap_or(Amount::ZERO);
     let coins = RpcAmountOut::from_amount_no_padding(coins, chain_config.coin_decimals());
 
+    let tokens = balances.into_iter().map(|(currency, amount)| {
+        let token_id = match currency {
+            Currency::Coin => panic!("Removed just above"),
+            Currency::Token(token_id) => token_id,
+        };
+        (token_id, amount)
+    });
+
+    Ok(Balances::new(
+        coins,
+        into_token_balances(rpc_client, chain_config, tokens).await?,
+    ))
+}
+
+pub async fn into_token_balances<T: NodeInterface>(
+    rpc_client: &T,
+    chain_config: &ChainConfig,
+    balances: impl IntoIterator<Item = (TokenId, Amount)>,
+) -> Result<BTreeMap<RpcAddress<TokenId>, RpcAmountOut>, ControllerError<T>> {
     let tasks: FuturesUnordered<_> = balances
         .into_iter()
-        .map(|(currency, amount)| async move {
-            let token_id = match currency {
-                Currency::Coin => panic!("Removed just above"),
-                Currency::Token(token_id) => token_id,
-            };
-
+        .map(|(token_id, amount)| async move {
             fetch_token_info(rpc_client, token_id).await.map(|info| {
                 let decimals = info.token_number_of_decimals();
                 let amount = RpcAmountOut::from_amount_no_padding(amount, decimals);
@@ -1169,5 +1978,5 @@ pub async fn into_balances<T: NodeInterface>(
         })
         .collect();
 
-    Ok(Balances::new(coins, tasks.try_collect().await?))
+    tasks.try_collect().await
 }
@@ -15,30 +15,45 @@
 
 //! Read operations for the wallet
 
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    num::NonZeroU8,
+};
 
 use common::{
     address::Address,
-    chain::{ChainConfig, DelegationId, Destination, PoolId, Transaction, TxOutput, UtxoOutPoint},
-    primitives::{id::WithId, Amount, Id},
+    chain::{
+        ChainConfig, DelegationId, Destination, GenBlockId, PoolId, Transaction, TxOutput,
+        UtxoOutPoint,
+    },
+    primitives::{id::WithId, Amount, BlockHeight, Id},
 };
 use crypto::{
-    key::hdkd::{child_number::ChildNumber, u31::U31},
+    key::{
+        extended::ExtendedPublicKey,
+        hdkd::{child_number::ChildNumber, u31::U31},
+        PrivateKey,
+    },
     vrf::VRFPublicKey,
 };
-use futures::{stream::FuturesUnordered, FutureExt, TryStreamExt};
+use futures::{
+    stream::{FuturesOrdered, FuturesUnordered},
+    FutureExt, TryStreamExt,
+};
 use node_comm::node_traits::NodeInterface;
 use utils::tap_log::TapLog;
 use wallet::{
     account::{
         currency_grouper::Currency, transaction_list::TransactionList, DelegationData, PoolData,
-        TxInfo,
+        StakedBlock, TxChainPosition, TxInfo,
     },
     wallet::WalletPoolsFilter,
     DefaultWallet,
 };
 use wallet_types::{
-    account_info::StandaloneAddresses,
+    account_info::{ChangePolicy, ScheduledPayment, StandaloneAddresses, WatchedPool},
+    multisig_account::MultisigAccountDescriptor,
+    token_trust::TokenTrust,
     utxo_types::{UtxoStates, UtxoTypes},
     wallet_tx::TxData,
     with_locked::WithLocked,
@@ -46,7 +61,11 @@ use wallet_types::{
 };
 
 use crate::{
-    types::{AccountStandaloneKeyDetails, Balances, CreatedBlockInfo},
+    types::{
+        referenced_tokens, AccountStandaloneKeyDetails, Balances, CreatedBlockInfo, CreditedReward,
+        MainchainTxInfo, StakingReward, SyncStatus, TransactionDescription,
+        TransactionHistoryEntry,
+    },
     ControllerError,
 };
 
@@ -55,6 +74,7 @@ pub struct ReadOnlyController<'a, T> {
     rpc_client: T,
     chain_config: &'a ChainConfig,
     account_index: U31,
+    sync_status: SyncStatus,
 }
 
 /// A Map between the derived child number and the Address with whether it is marked as used or not
@@ -66,12 +86,14 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
         rpc_client: T,
         chain_config: &'a ChainConfig,
         account_index: U31,
+        sync_status: SyncStatus,
     ) -> Self {
         Self {
             wallet,
             rpc_client,
             chain_config,
             account_index,
+            sync_status,
         }
     }
 
@@ -79,6 +101,11 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
         self.account_index
     }
 
+    /// How up to date the data returned by this controller is
+    pub fn sync_status(&self) -> &SyncStatus {
+        &self.sync_status
+    }
+
     pub fn get_balance(
         &self,
         utxo_states: UtxoStates,
@@ -94,8 +121,35 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
         utxo_states: UtxoStates,
         with_locked: WithLocked,
     ) -> Result<Balances, ControllerError<T>> {
-        let balances = self.get_balance(utxo_states, with_locked)?;
-        super::into_balances(&self.rpc_client, self.chain_config, balances).await
+        let mut balances = self.get_balance(utxo_states, with_locked)?;
+
+        // Hidden tokens are dropped and the tokens that the user hasn't dealt with are reported
+        // separately, because anyone can send tokens to the wallet
+        let trust_list =
+            self.wallet.get_token_trust_list().map_err(ControllerError::WalletError)?;
+        let known_tokens = self
+            .wallet
+            .get_known_token_ids(self.account_index)
+            .map_err(ControllerError::WalletError)?;
+        let mut unverified_tokens = BTreeMap::new();
+        balances.retain(|currency, amount| match currency {
+            Currency::Coin => true,
+            Currency::Token(token_id) => match trust_list.get(token_id) {
+                Some(TokenTrust::Trusted) => true,
+                Some(TokenTrust::Hidden) => false,
+                None if known_tokens.contains(token_id) => true,
+                None => {
+                    unverified_tokens.insert(*token_id, *amount);
+                    false
+                }
+            },
+        });
+
+        let balances = super::into_balances(&self.rpc_client, self.chain_config, balances).await?;
+        let unverified_tokens =
+            super::into_token_balances(&self.rpc_client, self.chain_config, unverified_tokens)
+                .await?;
+        Ok(balances.with_unverified_tokens(unverified_tokens))
     }
 
     pub fn get_multisig_utxos(
@@ -112,6 +166,28 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
             .map_err(ControllerError::WalletError)
     }
 
+    /// Get the utxos of a standalone address watched by this account
+    pub fn get_standalone_address_utxos(
+        &self,
+        address: Destination,
+        utxo_types: UtxoTypes,
+        utxo_states: UtxoStates,
+        with_locked: WithLocked,
+    ) -> Result<Vec<(UtxoOutPoint, TxOutput)>, ControllerError<T>> {
+        self.wallet
+            .get_standalone_address_utxos(
+                self.account_index,
+                address,
+                utxo_types,
+                utxo_states,
+                with_locked,
+            )
+            .map(|utxos| {
+                utxos.into_iter().map(|(outpoint, output, _)| (outpoint, output)).collect()
+            })
+            .map_err(ControllerError::WalletError)
+    }
+
     pub fn get_utxos(
         &self,
         utxo_types: UtxoTypes,
@@ -126,20 +202,95 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
             .map_err(ControllerError::WalletError)
     }
 
+    /// Get the UTXOs flagged by the dust protection policy of the account, which are not
+    /// selected automatically when composing transactions
+    pub fn get_dust_utxos(&self) -> Result<Vec<(UtxoOutPoint, TxOutput)>, ControllerError<T>> {
+        self.wallet
+            .get_dust_utxos(self.account_index)
+            .map(|utxos| {
+                utxos.into_iter().map(|(outpoint, output, _)| (outpoint, output)).collect()
+            })
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Get where the change of the transactions composed by the account is sent to
+    pub fn get_change_policy(&self) -> Result<ChangePolicy, ControllerError<T>> {
+        self.wallet
+            .get_change_policy(self.account_index)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Get the unspent UTXOs of the account that are frozen and must not be spent
+    pub fn get_frozen_utxos(&self) -> Result<Vec<(UtxoOutPoint, TxOutput)>, ControllerError<T>> {
+        self.wallet
+            .get_frozen_utxos(self.account_index)
+            .map_err(ControllerError::WalletError)
+    }
+
     pub fn pending_transactions(&self) -> Result<Vec<WithId<&'a Transaction>>, ControllerError<T>> {
         self.wallet
             .pending_transactions(self.account_index)
             .map_err(ControllerError::WalletError)
     }
 
-    pub fn mainchain_transactions(
+    pub async fn mainchain_transactions(
         &self,
         destination: Option<Destination>,
         limit: usize,
-    ) -> Result<Vec<TxInfo>, ControllerError<T>> {
-        self.wallet
+    ) -> Result<Vec<MainchainTxInfo>, ControllerError<T>> {
+        let txs = self
+            .wallet
             .mainchain_transactions(self.account_index, destination, limit)
-            .map_err(ControllerError::WalletError)
+            .map_err(ControllerError::WalletError)?;
+        self.describe_mainchain_transactions(txs).await
+    }
+
+    pub async fn mainchain_transactions_page(
+        &self,
+        destination: Option<Destination>,
+        after: Option<TxChainPosition>,
+        newest_first: bool,
+        limit: usize,
+    ) -> Result<Vec<MainchainTxInfo>, ControllerError<T>> {
+        let txs = self
+            .wallet
+            .mainchain_transactions_page(
+                self.account_index,
+                destination,
+                after,
+                newest_first,
+                limit,
+            )
+            .map_err(ControllerError::WalletError)?;
+        self.describe_mainchain_transactions(txs).await
+    }
+
+    async fn describe_mainchain_transactions(
+        &self,
+        txs: Vec<TxInfo>,
+    ) -> Result<Vec<MainchainTxInfo>, ControllerError<T>> {
+        let descriptions = txs
+            .iter()
+            .map(|tx| self.wallet.describe_transaction(self.account_index, tx.id))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ControllerError::WalletError)?;
+
+        let token_ids: BTreeSet<_> =
+            descriptions.iter().flat_map(|items| referenced_tokens(items)).collect();
+        let tasks: FuturesUnordered<_> = token_ids
+            .into_iter()
+            .map(|token_id| {
+                super::fetch_token_info(&self.rpc_client, token_id)
+                    .map(move |info| info.map(|info| (token_id, info)))
+            })
+            .collect();
+        let token_infos = tasks.try_collect().await?;
+
+        Ok(txs
+            .into_iter()
+            .zip(descriptions)
+            .map(|(tx, items)| MainchainTxInfo::new(self.chain_config, tx, items, &token_infos))
+            .collect())
     }
 
     pub fn get_transaction_list(
@@ -161,6 +312,59 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
             .map_err(ControllerError::WalletError)
     }
 
+    /// Describe what the transaction does from the point of view of this account
+    pub async fn describe_transaction(
+        &self,
+        transaction_id: Id<Transaction>,
+    ) -> Result<TransactionDescription, ControllerError<T>> {
+        let items = self
+            .wallet
+            .describe_transaction(self.account_index, transaction_id)
+            .map_err(ControllerError::WalletError)?;
+
+        let tasks: FuturesUnordered<_> = referenced_tokens(&items)
+            .into_iter()
+            .map(|token_id| {
+                super::fetch_token_info(&self.rpc_client, token_id)
+                    .map(move |info| info.map(|info| (token_id, info)))
+            })
+            .collect();
+        let token_infos = tasks.try_collect().await?;
+
+        Ok(TransactionDescription::new(
+            self.chain_config,
+            transaction_id,
+            items,
+            &token_infos,
+        ))
+    }
+
+    /// Get the whole transaction history of the account, the oldest transactions first
+    pub async fn get_transaction_history(
+        &self,
+    ) -> Result<Vec<TransactionHistoryEntry>, ControllerError<T>> {
+        let txs = self
+            .wallet
+            .get_transaction_history(self.account_index)
+            .map_err(ControllerError::WalletError)?;
+
+        let token_ids: BTreeSet<_> =
+            txs.iter().flat_map(|tx| referenced_tokens(&tx.description)).collect();
+        let tasks: FuturesUnordered<_> = token_ids
+            .into_iter()
+            .map(|token_id| {
+                super::fetch_token_info(&self.rpc_client, token_id)
+                    .map(move |info| info.map(|info| (token_id, info)))
+            })
+            .collect();
+        let token_infos = tasks.try_collect().await?;
+
+        Ok(txs
+            .into_iter()
+            .map(|tx| TransactionHistoryEntry::new(self.chain_config, tx, &token_infos))
+            .collect())
+    }
+
     pub fn get_all_issued_addresses(
         &self,
     ) -> Result<BTreeMap<ChildNumber, Address<Destination>>, ControllerError<T>> {
@@ -183,6 +387,15 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
             .map_err(ControllerError::WalletError)
     }
 
+    /// Get the ids of the pools of this account grouped by the VRF public key they use
+    pub fn get_pool_ids_by_vrf_public_key(
+        &self,
+    ) -> Result<BTreeMap<VRFPublicKey, Vec<PoolId>>, ControllerError<T>> {
+        self.wallet
+            .get_pool_ids_by_vrf_public_key(self.account_index)
+            .map_err(ControllerError::WalletError)
+    }
+
     pub fn get_addresses_usage(&self) -> Result<&'a KeychainUsageState, ControllerError<T>> {
         self.wallet
             .get_addresses_usage(self.account_index)
@@ -209,6 +422,60 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
             .collect())
     }
 
+    /// Get all addresses with the labels set by the user, if any
+    pub fn get_addresses_with_labels(
+        &self,
+    ) -> Result<BTreeMap<ChildNumber, (Address<Destination>, Option<String>)>, ControllerError<T>>
+    {
+        let addresses = self.get_all_issued_addresses()?;
+        let labels = self
+            .wallet
+            .get_address_labels(self.account_index)
+            .map_err(ControllerError::WalletError)?;
+
+        Ok(addresses
+            .into_iter()
+            .map(|(child_number, address)| {
+                let label = labels.get(address.as_object()).cloned();
+                (child_number, (address, label))
+            })
+            .collect())
+    }
+
+    /// Get the extended public key of the account, e.g. to be shared with the other cosigners
+    /// of a multisig account
+    pub fn account_extended_public_key(&self) -> Result<&ExtendedPublicKey, ControllerError<T>> {
+        self.wallet
+            .account_extended_public_key(self.account_index)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Get the private key of an address of the account, e.g. to migrate it to other tooling.
+    /// The private keys of the wallet must be unlocked.
+    pub fn export_private_key(
+        &self,
+        destination: &Destination,
+    ) -> Result<PrivateKey, ControllerError<T>> {
+        self.wallet
+            .export_private_key(self.account_index, destination)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Create the descriptor of a multisig account shared by this account and the other cosigners
+    pub fn create_multisig_account_descriptor(
+        &self,
+        min_required_signatures: NonZeroU8,
+        cosigner_keys: Vec<ExtendedPublicKey>,
+    ) -> Result<MultisigAccountDescriptor, ControllerError<T>> {
+        self.wallet
+            .create_multisig_account_descriptor(
+                self.account_index,
+                min_required_signatures,
+                cosigner_keys,
+            )
+            .map_err(ControllerError::WalletError)
+    }
+
     /// Get all standalone addresses with their labels
     pub fn get_standalone_addresses(&self) -> Result<StandaloneAddresses, ControllerError<T>> {
         self.wallet
@@ -309,6 +576,45 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
             .log_err()
     }
 
+    /// Get all pools watched by this account together with their current balance and pledge.
+    /// The balance and pledge are `None` if the pool no longer exists in the node.
+    pub async fn get_watched_pools(
+        &self,
+    ) -> Result<Vec<(PoolId, WatchedPool, Option<Amount>, Option<Amount>)>, ControllerError<T>>
+    {
+        let pools = self
+            .wallet
+            .get_watched_pools(self.account_index)
+            .map_err(ControllerError::WalletError)?;
+
+        let tasks: FuturesUnordered<_> = pools
+            .into_iter()
+            .map(|(pool_id, watched_pool)| async move {
+                let balance = self
+                    .rpc_client
+                    .get_stake_pool_balance(pool_id)
+                    .await
+                    .map_err(ControllerError::NodeCallError)?;
+                let pledge = self
+                    .rpc_client
+                    .get_staker_balance(pool_id)
+                    .await
+                    .map_err(ControllerError::NodeCallError)?;
+                Ok((pool_id, watched_pool, balance, pledge))
+            })
+            .collect();
+
+        tasks.try_collect().await
+    }
+
+    pub fn get_scheduled_payments(
+        &self,
+    ) -> Result<Vec<(u64, ScheduledPayment)>, ControllerError<T>> {
+        self.wallet
+            .get_scheduled_payments(self.account_index)
+            .map_err(ControllerError::WalletError)
+    }
+
     pub async fn get_delegations(
         &self,
     ) -> Result<Vec<(DelegationId, PoolId, Amount)>, ControllerError<T>> {
@@ -355,6 +661,79 @@ impl<'a, T: NodeInterface> ReadOnlyController<'a, T> {
             })
     }
 
+    /// The rewards of the blocks produced by the account's pools in the specified height range
+    /// (inclusive), as credited by the node to the pools and their delegations
+    pub async fn get_staking_rewards(
+        &self,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Result<Vec<StakingReward>, ControllerError<T>> {
+        let staked_blocks = self
+            .wallet
+            .get_staked_blocks(self.account_index, from_height, to_height)
+            .map_err(ControllerError::WalletError)?;
+        let own_delegations: BTreeSet<DelegationId> = self
+            .wallet
+            .get_delegations(self.account_index)
+            .map_err(ControllerError::WalletError)?
+            .map(|(delegation_id, _)| *delegation_id)
+            .collect();
+
+        let tasks: FuturesOrdered<_> = staked_blocks
+            .into_iter()
+            .map(|staked_block| self.get_staking_reward(staked_block, &own_delegations))
+            .collect();
+
+        tasks.try_collect().await
+    }
+
+    async fn get_staking_reward(
+        &self,
+        staked_block: StakedBlock,
+        own_delegations: &BTreeSet<DelegationId>,
+    ) -> Result<StakingReward, ControllerError<T>> {
+        let credited = match staked_block.block_id.classify(self.chain_config) {
+            GenBlockId::Genesis(_) => None,
+            GenBlockId::Block(block_id) => self
+                .rpc_client
+                .get_block_reward_distribution(block_id)
+                .await
+                .map_err(ControllerError::NodeCallError)?
+                .map(|distribution| {
+                    let delegations_reward = distribution
+                        .delegation_rewards()
+                        .values()
+                        .copied()
+                        .sum::<Option<Amount>>()
+                        .expect("cannot exceed the coin supply");
+                    let own_delegations_reward = distribution
+                        .delegation_rewards()
+                        .iter()
+                        .filter(|(delegation_id, _)| own_delegations.contains(delegation_id))
+                        .map(|(_, reward)| *reward)
+                        .sum::<Option<Amount>>()
+                        .expect("cannot exceed the coin supply");
+
+                    CreditedReward {
+                        total_reward: distribution
+                            .total_reward()
+                            .expect("cannot exceed the coin supply"),
+                        staker_reward: distribution.staker_reward(),
+                        delegations_reward,
+                        own_delegations_reward,
+                    }
+                }),
+        };
+
+        Ok(StakingReward {
+            pool_id: staked_block.pool_id,
+            block_id: staked_block.block_id,
+            block_height: staked_block.block_height,
+            block_timestamp: staked_block.block_timestamp,
+            credited,
+        })
+    }
+
     async fn get_delegation_share(
         &self,
         delegation_data: &DelegationData,
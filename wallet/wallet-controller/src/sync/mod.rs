@@ -113,6 +113,18 @@ pub enum InSync {
     NodeOutOfSync,
 }
 
+/// The outcome of a single syncing step
+pub enum SyncStep {
+    /// Nothing is left to sync for now
+    Done(InSync),
+    /// Some blocks have been scanned, but the wallet is not synced with the node yet
+    InProgress {
+        /// The lowest best block height among the accounts
+        wallet_height: BlockHeight,
+        node_height: BlockHeight,
+    },
+}
+
 pub async fn sync_once<T: NodeInterface>(
     chain_config: &ChainConfig,
     rpc_client: &T,
@@ -123,94 +135,114 @@ pub async fn sync_once<T: NodeInterface>(
     let mut _log_on_exit = None;
 
     loop {
-        let chain_info =
-            rpc_client.chainstate_info().await.map_err(ControllerError::NodeCallError)?;
-
-        let WalletSyncingState {
-            account_best_blocks,
-            unused_account_best_block,
-        } = wallet.syncing_state();
-        if account_best_blocks
-            .values()
-            .chain(iter::once(&unused_account_best_block))
-            .all(|wallet_best_block| chain_info.best_block_id == wallet_best_block.0)
-        {
-            // if all accounts are on the latest tip nothing to sync
-            return Ok(InSync::Synced);
-        }
-
-        if account_best_blocks
-            .values()
-            .chain(iter::once(&unused_account_best_block))
-            .any(|wallet_best_block| chain_info.best_block_height < wallet_best_block.1)
-        {
-            // If the wallet's block height is > node block height wait for the node to sync first
-            log::info!("Wallet syncing paused until the node syncs up to the height of the wallet");
-            return Ok(InSync::NodeOutOfSync);
+        match sync_step(chain_config, rpc_client, wallet, wallet_events).await? {
+            SyncStep::Done(in_sync) => return Ok(in_sync),
+            SyncStep::InProgress {
+                wallet_height,
+                node_height,
+            } => {
+                // Print the log message informing about the syncing process only once
+                if !print_flag.test_and_set() {
+                    _log_on_exit = Some(OnceDestructor::new(move || {
+                        log::info!("Wallet syncing done to height {}", node_height)
+                    }));
+
+                    log::info!(
+                        "Syncing wallet from height {} to {}",
+                        wallet_height,
+                        node_height
+                    );
+                }
+            }
         }
+    }
+}
 
-        wallet
-            .update_median_time(chain_info.median_time)
-            .map_err(ControllerError::WalletError)?;
-
-        // Group accounts in the same state
-        let mut accounts_grouped = group_accounts_by_mainchain_blocks(
-            chain_config,
-            rpc_client,
-            chain_info.best_block_id,
-            chain_info.best_block_height,
-            account_best_blocks,
-            unused_account_best_block,
-        )
-        .await?;
-
-        // Print the log message informing about the syncing process only once
-        if !print_flag.test_and_set() {
-            _log_on_exit = Some(OnceDestructor::new(move || {
-                log::info!(
-                    "Wallet syncing done to height {}",
-                    chain_info.best_block_height
-                )
-            }));
-
-            let lowest_acc_height =
-                accounts_grouped.first().expect("empty accounts").0.common_block_height;
-            log::info!(
-                "Syncing wallet from height {} to {}",
-                lowest_acc_height,
-                chain_info.best_block_height
-            );
-        }
+/// Scan the next batch of blocks.
+///
+/// Unlike `sync_once`, this returns after every batch, which allows the caller to do something
+/// else in between, e.g. to serve the requests that only read the wallet during a long rescan.
+pub async fn sync_step<T: NodeInterface>(
+    chain_config: &ChainConfig,
+    rpc_client: &T,
+    wallet: &mut impl SyncingWallet,
+    wallet_events: &impl WalletEvents,
+) -> Result<SyncStep, ControllerError<T>> {
+    let chain_info = rpc_client.chainstate_info().await.map_err(ControllerError::NodeCallError)?;
+
+    let WalletSyncingState {
+        account_best_blocks,
+        unused_account_best_block,
+    } = wallet.syncing_state();
+    if account_best_blocks
+        .values()
+        .chain(iter::once(&unused_account_best_block))
+        .all(|wallet_best_block| chain_info.best_block_id == wallet_best_block.0)
+    {
+        // if all accounts are on the latest tip nothing to sync
+        return Ok(SyncStep::Done(InSync::Synced));
+    }
 
-        // Sync all account groups together from last to first,
-        // where the last has the lowest block height.
-        // Once a group is synced with the next one, merge them,
-        // and continue with the other groups until there's only one group left containing all the accounts
-        let mut current = accounts_grouped.pop().expect("empty accounts");
-        while let Some(next) = accounts_grouped.pop() {
-            // fetch blocks up to the next account group and merge the two groups
-            current = fetch_and_sync_to_next_group(
-                &mut current,
-                next.0,
-                next.1,
-                rpc_client,
-                wallet,
-                wallet_events,
-            )
-            .await?;
-        }
+    if account_best_blocks
+        .values()
+        .chain(iter::once(&unused_account_best_block))
+        .any(|wallet_best_block| chain_info.best_block_height < wallet_best_block.1)
+    {
+        // If the wallet's block height is > node block height wait for the node to sync first
+        log::info!("Wallet syncing paused until the node syncs up to the height of the wallet");
+        return Ok(SyncStep::Done(InSync::NodeOutOfSync));
+    }
 
-        // At this point, all accounts have the same best block,
-        // and we sync them all together to the global best block
-        fetch_and_sync(
-            &current,
-            MAX_FETCH_BLOCK_COUNT,
+    wallet
+        .update_median_time(chain_info.median_time)
+        .map_err(ControllerError::WalletError)?;
+
+    // Group accounts in the same state
+    let mut accounts_grouped = group_accounts_by_mainchain_blocks(
+        chain_config,
+        rpc_client,
+        chain_info.best_block_id,
+        chain_info.best_block_height,
+        account_best_blocks,
+        unused_account_best_block,
+    )
+    .await?;
+
+    let wallet_height = accounts_grouped.first().expect("empty accounts").0.common_block_height;
+
+    // Sync all account groups together from last to first,
+    // where the last has the lowest block height.
+    // Once a group is synced with the next one, merge them,
+    // and continue with the other groups until there's only one group left containing all the accounts
+    let mut current = accounts_grouped.pop().expect("empty accounts");
+    while let Some(next) = accounts_grouped.pop() {
+        // fetch blocks up to the next account group and merge the two groups
+        current = fetch_and_sync_to_next_group(
+            &mut current,
+            next.0,
+            next.1,
             rpc_client,
             wallet,
             wallet_events,
         )
         .await?;
     }
+
+    // At this point, all accounts have the same best block,
+    // and we sync them all together to the global best block
+    fetch_and_sync(
+        &current,
+        MAX_FETCH_BLOCK_COUNT,
+        rpc_client,
+        wallet,
+        wallet_events,
+    )
+    .await?;
+
+    Ok(SyncStep::InProgress {
+        wallet_height,
+        node_height: chain_info.best_block_height,
+    })
 }
 
 async fn fetch_and_sync_to_next_group<T: NodeInterface>(
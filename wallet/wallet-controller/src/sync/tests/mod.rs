@@ -20,7 +20,7 @@ use std::{
 };
 
 use blockprod::TimestampSearchData;
-use chainstate::ChainInfo;
+use chainstate::{rpc::types::block_stats::RpcBlockStats, BlockRewardDistribution, ChainInfo};
 use chainstate_test_framework::TestFramework;
 use common::{
     chain::{
@@ -36,7 +36,7 @@ use logging::log;
 use mempool::{tx_accumulator::PackingStrategy, FeeRate};
 use mempool_types::tx_options::TxOptionsOverrides;
 use node_comm::{
-    node_traits::{ConnectedPeer, PeerId},
+    node_traits::{ConnectedPeer, PeerId, SyncProgress},
     rpc_client::NodeRpcError,
 };
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress};
@@ -206,12 +206,30 @@ impl NodeInterface for MockNode {
         WalletType::Hot
     }
 
+    fn active_node_address(&self) -> Option<String> {
+        None
+    }
+
+    fn configured_node_addresses(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn switch_to_node(&self, _index: usize) -> Option<String> {
+        None
+    }
+
     async fn chainstate_info(&self) -> Result<ChainInfo, Self::Error> {
         Ok(self.tf.lock().unwrap().chainstate.info().unwrap())
     }
     async fn get_best_block_id(&self) -> Result<Id<GenBlock>, Self::Error> {
         unreachable!()
     }
+    async fn chainstate_recent_block_stats(
+        &self,
+        _count: usize,
+    ) -> Result<Vec<RpcBlockStats>, Self::Error> {
+        unreachable!()
+    }
     async fn get_block(&self, block_id: Id<Block>) -> Result<Option<Block>, Self::Error> {
         Ok(self.tf.lock().unwrap().chainstate.get_block(block_id).unwrap())
     }
@@ -283,6 +301,13 @@ impl NodeInterface for MockNode {
         unreachable!()
     }
 
+    async fn get_block_reward_distribution(
+        &self,
+        _block_id: Id<Block>,
+    ) -> Result<Option<BlockRewardDistribution>, Self::Error> {
+        unreachable!()
+    }
+
     async fn get_token_info(
         &self,
         _token_id: TokenId,
@@ -323,6 +348,13 @@ impl NodeInterface for MockNode {
         unreachable!()
     }
 
+    async fn get_utxos(
+        &self,
+        _outpoints: Vec<common::chain::UtxoOutPoint>,
+    ) -> Result<Vec<Option<common::chain::TxOutput>>, Self::Error> {
+        unreachable!()
+    }
+
     async fn generate_block(
         &self,
         _input_data: GenerateBlockInputData,
@@ -381,6 +413,12 @@ impl NodeInterface for MockNode {
     async fn p2p_get_connected_peers(&self) -> Result<Vec<ConnectedPeer>, Self::Error> {
         unreachable!()
     }
+    async fn p2p_get_sync_progress(&self) -> Result<SyncProgress, Self::Error> {
+        unreachable!()
+    }
+    async fn p2p_get_node_sync_progress(&self, _index: usize) -> Result<SyncProgress, Self::Error> {
+        unreachable!()
+    }
     async fn p2p_get_reserved_nodes(&self) -> Result<Vec<SocketAddress>, Self::Error> {
         unreachable!()
     }
@@ -404,6 +442,17 @@ impl NodeInterface for MockNode {
             FeeRate::from_amount_per_kb(Amount::from_atoms(1)),
         )])
     }
+
+    async fn mempool_get_min_fee_rate(&self) -> Result<FeeRate, Self::Error> {
+        Ok(FeeRate::from_amount_per_kb(Amount::ZERO))
+    }
+
+    async fn mempool_estimate_fee_rate(
+        &self,
+        _target_blocks: usize,
+    ) -> Result<Option<FeeRate>, Self::Error> {
+        Ok(None)
+    }
 }
 
 fn create_chain(node: &MockNode, rng: &mut (impl Rng + CryptoRng), parent: u64, count: usize) {
@@ -592,3 +641,55 @@ async fn account_out_of_sync(#[case] seed: Seed) {
         }
     }
 }
+
+#[rstest]
+#[trace]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test]
+async fn sync_in_steps(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let node = MockNode::new(&mut rng);
+    let chain_config = Arc::clone(node.tf.lock().unwrap().chainstate.get_chain_config());
+    let (new_tip_tx, _new_tip_rx) = mpsc::channel(1000);
+    let mut wallet = MockWallet::new(&chain_config, new_tip_tx);
+
+    let block_count = rng.gen_range(MAX_FETCH_BLOCK_COUNT + 1..MAX_FETCH_BLOCK_COUNT * 3);
+    create_chain(&node, &mut rng, 0, block_count);
+
+    // Every step scans at most MAX_FETCH_BLOCK_COUNT blocks and reports the progress
+    let mut expected_wallet_height = 0;
+    while expected_wallet_height < block_count {
+        let step = sync_step(&chain_config, &node, &mut wallet, &WalletEventsNoOp).await.unwrap();
+        match step {
+            SyncStep::InProgress {
+                wallet_height,
+                node_height,
+            } => {
+                assert_eq!(
+                    wallet_height,
+                    BlockHeight::new(expected_wallet_height as u64)
+                );
+                assert_eq!(node_height, BlockHeight::new(block_count as u64));
+            }
+            SyncStep::Done(_) => panic!("synced too early"),
+        }
+
+        expected_wallet_height =
+            std::cmp::min(expected_wallet_height + MAX_FETCH_BLOCK_COUNT, block_count);
+        assert_eq!(
+            wallet.get_block_height(),
+            BlockHeight::new(expected_wallet_height as u64)
+        );
+        assert_eq!(
+            wallet.get_unused_acc_block_height(),
+            BlockHeight::new(expected_wallet_height as u64)
+        );
+    }
+
+    let step = sync_step(&chain_config, &node, &mut wallet, &WalletEventsNoOp).await.unwrap();
+    assert!(matches!(step, SyncStep::Done(InSync::Synced)));
+    assert_eq!(
+        wallet.get_best_block_id(),
+        node.tf.lock().unwrap().best_block_id()
+    );
+}
@@ -18,6 +18,7 @@ use std::collections::{BTreeMap, BTreeSet};
 use common::{
     address::{pubkeyhash::PublicKeyHash, Address},
     chain::{
+        block::timestamp::BlockTimestamp,
         classic_multisig::ClassicMultisigChallenge,
         htlc::HashedTimelockContract,
         output_value::OutputValue,
@@ -30,10 +31,11 @@ use common::{
         ChainConfig, DelegationId, Destination, PoolId, SignedTransaction, Transaction, TxOutput,
         UtxoOutPoint,
     },
-    primitives::{per_thousand::PerThousand, Amount, Id},
+    primitives::{per_thousand::PerThousand, time::get_time, Amount, Id, Idable},
 };
 use crypto::{
     key::{
+        extended::ExtendedPublicKey,
         hdkd::{child_number::ChildNumber, u31::U31},
         PrivateKey, PublicKey,
     },
@@ -43,10 +45,12 @@ use futures::{stream::FuturesUnordered, TryStreamExt};
 use logging::log;
 use mempool::FeeRate;
 use node_comm::node_traits::NodeInterface;
+use serialization::Encode;
 use utils::ensure;
 use wallet::{
     account::{
-        currency_grouper::Currency, CoinSelectionAlgo, TransactionToSign, UnconfirmedTokenInfo,
+        currency_grouper::Currency, CoinSelectionAlgo, FeeBumpKind, TransactionToSign,
+        UnconfirmedTokenInfo,
     },
     destination_getters::{get_tx_output_destination, HtlcSpendingCondition},
     send_request::{
@@ -58,17 +62,31 @@ use wallet::{
     DefaultWallet, WalletError, WalletResult,
 };
 use wallet_types::{
+    account_info::{ChangePolicy, ScheduledPayment},
+    broadcast_queue::{BroadcastPolicy, BroadcastQueueEntry, BroadcastStatus},
+    multisig_account::MultisigAccountDescriptor,
     signature_status::SignatureStatus,
     utxo_types::{UtxoState, UtxoType},
     with_locked::WithLocked,
 };
 
 use crate::{
-    into_balances,
-    types::{Balances, GenericCurrencyTransfer},
-    ControllerConfig, ControllerError,
+    into_balances, make_resubmit_delay_secs,
+    types::{
+        split_payouts, Balances, GenericCurrencyTransfer, TokenAuthorityRotation,
+        TransactionPackage,
+    },
+    ControllerConfig, ControllerError, FeeOverride,
 };
 
+/// The number of blocks within which the created transactions should get confirmed,
+/// according to the node's fee rate estimate
+const FEE_ESTIMATE_TARGET_BLOCKS: usize = 6;
+
+/// The number of recent blocks whose median fee rates make the consolidation fee rate,
+/// i.e. the rate expected to be paid when the selected UTXOs or the change are spent later
+const CONSOLIDATION_FEE_RATE_BLOCKS: usize = 100;
+
 pub struct SyncedController<'a, T, W> {
     wallet: &'a mut DefaultWallet,
     rpc_client: T,
@@ -214,6 +232,129 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
             .map_err(ControllerError::WalletError)
     }
 
+    pub fn remove_standalone_address(
+        &mut self,
+        address: PublicKeyHash,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .remove_standalone_address(self.account_index, address)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn add_watched_pool(
+        &mut self,
+        pool_id: PoolId,
+        label: Option<String>,
+        decommission_key: Option<Destination>,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .add_watched_pool(self.account_index, pool_id, label, decommission_key)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn remove_watched_pool(&mut self, pool_id: PoolId) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .remove_watched_pool(self.account_index, pool_id)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Add a recurring payment that will be executed by the controller when it's due
+    pub fn add_scheduled_payment(
+        &mut self,
+        payment: ScheduledPayment,
+    ) -> Result<u64, ControllerError<T>> {
+        self.wallet
+            .add_scheduled_payment(self.account_index, payment)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Broadcast the transaction made for a scheduled payment.
+    ///
+    /// The transaction is saved together with the advanced schedule of the payment before it's
+    /// submitted, so the payment can't be made twice. A failed submission is only logged,
+    /// the transaction stays queued and is rebroadcast later by the controller.
+    pub async fn broadcast_scheduled_payment(
+        &mut self,
+        payment_id: u64,
+        payment: ScheduledPayment,
+        tx: SignedTransaction,
+    ) -> Result<SignedTransaction, ControllerError<T>> {
+        let now = BlockTimestamp::from_time(get_time());
+        let entry = BroadcastQueueEntry::new(tx, BroadcastPolicy::default(), now);
+        self.wallet
+            .add_scheduled_payment_tx(
+                self.account_index,
+                payment_id,
+                payment,
+                &entry,
+                self.wallet_events,
+            )
+            .map_err(ControllerError::WalletError)?;
+
+        let tx = entry.tx.clone();
+        if let Err(error) = self.submit_queued_tx(entry, now).await {
+            log::warn!(
+                "Submitting the transaction {} of scheduled payment {payment_id} failed, it will be rebroadcast: {error}",
+                tx.transaction().get_id()
+            );
+        }
+
+        Ok(tx)
+    }
+
+    pub fn cancel_scheduled_payment(&mut self, payment_id: u64) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .cancel_scheduled_payment(self.account_index, payment_id)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn set_dust_protection_policy(
+        &mut self,
+        min_coin_amount: Option<Amount>,
+        flag_unknown_tokens: bool,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .set_dust_protection_policy(self.account_index, min_coin_amount, flag_unknown_tokens)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Set where the change of the transactions composed by the account is sent to
+    pub fn set_change_policy(
+        &mut self,
+        change_policy: ChangePolicy,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .set_change_policy(self.account_index, change_policy)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Allow the UTXOs flagged by the dust protection to be selected automatically again
+    pub fn release_dust_utxos(
+        &mut self,
+        utxos: Vec<UtxoOutPoint>,
+    ) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .release_dust_utxos(self.account_index, utxos)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Mark the UTXOs as "do not spend". Frozen UTXOs are never selected automatically and
+    /// are rejected if selected explicitly, until they are unfrozen.
+    /// To spend exactly a specific set of UTXOs, pass them as `selected_utxos` to
+    /// `send_to_address`.
+    pub fn freeze_utxos(&mut self, utxos: Vec<UtxoOutPoint>) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .freeze_utxos(self.account_index, utxos)
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Allow the frozen UTXOs to be spent again
+    pub fn unfreeze_utxos(&mut self, utxos: Vec<UtxoOutPoint>) -> Result<(), ControllerError<T>> {
+        self.wallet
+            .unfreeze_utxos(self.account_index, utxos)
+            .map_err(ControllerError::WalletError)
+    }
+
     pub fn add_standalone_private_key(
         &mut self,
         private_key: PrivateKey,
@@ -234,6 +375,33 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
             .map_err(ControllerError::WalletError)
     }
 
+    pub fn import_multisig_account(
+        &mut self,
+        descriptor: &MultisigAccountDescriptor,
+        address_count: U31,
+        label: Option<String>,
+    ) -> Result<Vec<PublicKeyHash>, ControllerError<T>> {
+        self.wallet
+            .import_multisig_account(self.account_index, descriptor, address_count, label)
+            .map_err(ControllerError::WalletError)
+    }
+
+    pub fn import_extended_public_key(
+        &mut self,
+        extended_public_key: &ExtendedPublicKey,
+        address_count: U31,
+        label: Option<String>,
+    ) -> Result<Vec<PublicKeyHash>, ControllerError<T>> {
+        self.wallet
+            .import_extended_public_key(
+                self.account_index,
+                extended_public_key,
+                address_count,
+                label,
+            )
+            .map_err(ControllerError::WalletError)
+    }
+
     pub fn new_address(
         &mut self,
     ) -> Result<(ChildNumber, Address<Destination>), ControllerError<T>> {
@@ -462,6 +630,57 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
         .await
     }
 
+    /// Change the authority of the token after checking that it is safe to do so.
+    ///
+    /// Unless `force` is set, the change is refused if the new authority is not controlled
+    /// by this wallet, as the control over the token would be lost.
+    /// In a dry run only the checks are made and no transaction is created.
+    pub async fn rotate_token_authority(
+        &mut self,
+        token_info: RPCTokenInfo,
+        address: Address<Destination>,
+        dry_run: bool,
+        force: bool,
+    ) -> Result<TokenAuthorityRotation, ControllerError<T>> {
+        let token_id = token_info.token_id();
+        let unconfirmed_info = match &token_info {
+            RPCTokenInfo::FungibleToken(info) => self
+                .wallet
+                .get_token_unconfirmed_info(self.account_index, info)
+                .map_err(ControllerError::WalletError)?,
+            RPCTokenInfo::NonFungibleToken(info) => {
+                UnconfirmedTokenInfo::NonFungibleToken(info.token_id)
+            }
+        };
+        let current_authority =
+            unconfirmed_info.authority().map_err(ControllerError::WalletError)?.clone();
+        let new_authority = address.as_object().clone();
+        ensure!(
+            current_authority != new_authority,
+            ControllerError::TokenAuthorityUnchanged(token_id)
+        );
+
+        let new_authority_account = self.wallet.find_account_by_destination(&new_authority);
+
+        let transaction = if dry_run {
+            None
+        } else {
+            ensure!(
+                new_authority_account.is_some() || force,
+                ControllerError::TokenAuthorityNotControlled(token_id)
+            );
+            Some(self.change_token_authority(token_info, address).await?)
+        };
+
+        Ok(TokenAuthorityRotation {
+            token_id,
+            current_authority,
+            new_authority,
+            new_authority_account,
+            transaction,
+        })
+    }
+
     pub async fn change_token_metadata_uri(
         &mut self,
         token_info: RPCTokenInfo,
@@ -524,6 +743,16 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
         self.check_tokens_in_selected_utxo(&selected_utxos).await?;
 
         let output = make_address_output(address, amount);
+        if let Some(FeeOverride::AbsoluteFee(fee)) = self.config.fee_override {
+            return self
+                .create_and_send_tx_with_absolute_fee(
+                    fee,
+                    [output],
+                    SelectedInputs::Utxos(selected_utxos),
+                )
+                .await;
+        }
+
         self.create_and_send_tx(
             move |current_fee_rate: FeeRate,
                   consolidate_fee_rate: FeeRate,
@@ -585,6 +814,110 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
         .await
     }
 
+    /// Get the spendable UTXOs of the account that hold only the specified currency
+    async fn get_spendable_utxos_of_currency(
+        &self,
+        currency: &Currency,
+    ) -> Result<Vec<(UtxoOutPoint, TxOutput, Option<TokenId>)>, ControllerError<T>> {
+        let utxos = self
+            .wallet
+            .get_utxos(
+                self.account_index,
+                UtxoType::Transfer | UtxoType::LockThenTransfer,
+                UtxoState::Confirmed | UtxoState::InMempool | UtxoState::Inactive,
+                WithLocked::Unlocked,
+            )
+            .map_err(ControllerError::WalletError)?;
+
+        let utxos = self
+            .filter_out_utxos_with_frozen_tokens(utxos)
+            .await?
+            .into_iter()
+            .filter(|(_, _, token_id)| match currency {
+                Currency::Coin => token_id.is_none(),
+                Currency::Token(id) => token_id.as_ref() == Some(id),
+            })
+            .collect();
+        Ok(utxos)
+    }
+
+    /// Compute the largest amount of the currency that can be sent to the destination in a
+    /// single transaction, i.e. the spendable balance minus the fee when sending coins.
+    pub async fn estimate_max_sendable(
+        &mut self,
+        destination: Destination,
+        currency: Currency,
+    ) -> Result<Amount, ControllerError<T>> {
+        let utxos = self.get_spendable_utxos_of_currency(&currency).await?;
+        let (current_fee_rate, _) = self.get_current_and_consolidation_fee_rate().await?;
+
+        self.wallet
+            .get_max_sendable_amount(
+                self.account_index,
+                destination,
+                &currency,
+                utxos,
+                current_fee_rate,
+            )
+            .map_err(ControllerError::WalletError)
+    }
+
+    /// Create a transaction that transfers the largest possible amount of the currency to the
+    /// destination address and broadcast it to the mempool.
+    /// All the spendable UTXOs of the currency are spent and their whole amount is sent.
+    /// When sending coins the fee is deducted from the sent amount; when sending tokens,
+    /// the fee is paid with other coin UTXOs.
+    pub async fn send_all(
+        &mut self,
+        address: Address<Destination>,
+        currency: Currency,
+    ) -> Result<SignedTransaction, ControllerError<T>> {
+        let utxos = self.get_spendable_utxos_of_currency(&currency).await?;
+
+        match currency {
+            Currency::Coin => {
+                let destination = address.into_object();
+                self.create_and_send_tx(
+                    move |current_fee_rate: FeeRate,
+                          _consolidate_fee_rate: FeeRate,
+                          wallet: &mut DefaultWallet,
+                          account_index: U31| {
+                        wallet.create_sweep_transaction(
+                            account_index,
+                            destination,
+                            utxos,
+                            current_fee_rate,
+                        )
+                    },
+                )
+                .await
+            }
+            Currency::Token(token_id) => {
+                let token_info = self.get_token_info(token_id).await?;
+                let destination = address.into_object();
+                self.create_and_send_token_tx(
+                    &token_info,
+                    move |current_fee_rate: FeeRate,
+                          consolidate_fee_rate: FeeRate,
+                          wallet: &mut DefaultWallet,
+                          account_index: U31,
+                          token_info: &UnconfirmedTokenInfo| {
+                        token_info.check_can_be_used()?;
+                        wallet.create_token_sweep_transaction(
+                            account_index,
+                            destination,
+                            token_id,
+                            utxos,
+                            current_fee_rate,
+                            consolidate_fee_rate,
+                        )
+                    },
+                )
+                .await
+            }
+        }
+    }
+
     /// Create a transaction that transfers all the coins from a delegation to the destination address
     /// and broadcast it to the mempool.
     pub async fn sweep_delegation(
@@ -924,6 +1257,13 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
         amount: Amount,
     ) -> Result<SignedTransaction, ControllerError<T>> {
         let output = make_address_output_token(address, amount, token_info.token_id());
+        if let Some(FeeOverride::AbsoluteFee(fee)) = self.config.fee_override {
+            self.get_unconfirmed_token_info(&token_info)?.check_can_be_used()?;
+            return self
+                .create_and_send_tx_with_absolute_fee(fee, [output], SelectedInputs::Utxos(vec![]))
+                .await;
+        }
+
         self.create_and_send_token_tx(
             &token_info,
             move |current_fee_rate: FeeRate,
@@ -945,6 +1285,147 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
         .await
     }
 
+    /// Creates a single transaction that pays each recipient the specified amount of coins, or of
+    /// tokens if token info is given, and broadcasts it to the mempool.
+    /// The inputs are selected from the available ones in the wallet.
+    pub async fn send_to_many(
+        &mut self,
+        recipients: Vec<(Address<Destination>, Amount, Option<RPCTokenInfo>)>,
+    ) -> Result<SignedTransaction, ControllerError<T>> {
+        let mut outputs = Vec::with_capacity(recipients.len());
+        for (address, amount, token_info) in recipients {
+            let output = match token_info {
+                Some(token_info) => {
+                    // make sure we can use the token before create an tx using it
+                    self.get_unconfirmed_token_info(&token_info)?.check_can_be_used()?;
+                    make_address_output_token(address, amount, token_info.token_id())
+                }
+                None => make_address_output(address, amount),
+            };
+            outputs.push(output);
+        }
+
+        if let Some(FeeOverride::AbsoluteFee(fee)) = self.config.fee_override {
+            return self
+                .create_and_send_tx_with_absolute_fee(fee, outputs, SelectedInputs::Utxos(vec![]))
+                .await;
+        }
+
+        self.create_and_send_tx(
+            move |current_fee_rate: FeeRate,
+                  consolidate_fee_rate: FeeRate,
+                  wallet: &mut DefaultWallet,
+                  account_index: U31| {
+                wallet.create_transaction_to_addresses(
+                    account_index,
+                    outputs,
+                    SelectedInputs::Utxos(vec![]),
+                    BTreeMap::new(),
+                    current_fee_rate,
+                    consolidate_fee_rate,
+                )
+            },
+        )
+        .await
+    }
+
+    /// Split the payouts to many recipients into batches, each to be paid by a separate
+    /// transaction with `send_to_many`, so that no transaction has more than
+    /// `max_payouts_per_tx` outputs or exceeds the maximum transaction size.
+    ///
+    /// The batches have to be sent one by one, so that the transactions don't spend
+    /// the same outputs.
+    pub fn payout_batches(
+        &self,
+        payouts: Vec<(Address<Destination>, Amount, Option<RPCTokenInfo>)>,
+        max_payouts_per_tx: usize,
+    ) -> Result<Vec<Vec<(Address<Destination>, Amount, Option<RPCTokenInfo>)>>, ControllerError<T>>
+    {
+        ensure!(
+            max_payouts_per_tx > 0,
+            ControllerError::InvalidMaxPayoutsPerTx
+        );
+
+        let batches = split_payouts(
+            payouts,
+            max_payouts_per_tx,
+            self.chain_config.max_tx_size_for_mempool(),
+            |(address, amount, token_info)| {
+                let output = match token_info {
+                    Some(token_info) => {
+                        make_address_output_token(address.clone(), *amount, token_info.token_id())
+                    }
+                    None => make_address_output(address.clone(), *amount),
+                };
+                output.encoded_size()
+            },
+        );
+
+        Ok(batches)
+    }
+
+    /// Create a transaction that transfers the amount of the currency from this account to a new
+    /// address of another account of the wallet and broadcast it to the mempool.
+    /// The transaction is recorded as an internal transfer and is shown as such in the
+    /// transaction list of both accounts.
+    pub async fn transfer_between_accounts(
+        &mut self,
+        to_account: U31,
+        amount: Amount,
+        currency: Currency,
+    ) -> Result<SignedTransaction, ControllerError<T>> {
+        let tx = match currency {
+            Currency::Coin => {
+                self.create_and_send_tx(
+                    move |current_fee_rate: FeeRate,
+                          consolidate_fee_rate: FeeRate,
+                          wallet: &mut DefaultWallet,
+                          account_index: U31| {
+                        wallet.create_internal_transfer_tx(
+                            account_index,
+                            to_account,
+                            &Currency::Coin,
+                            amount,
+                            current_fee_rate,
+                            consolidate_fee_rate,
+                        )
+                    },
+                )
+                .await?
+            }
+            Currency::Token(token_id) => {
+                let token_info = self.get_token_info(token_id).await?;
+                self.create_and_send_token_tx(
+                    &token_info,
+                    move |current_fee_rate: FeeRate,
+                          consolidate_fee_rate: FeeRate,
+                          wallet: &mut DefaultWallet,
+                          account_index: U31,
+                          token_info: &UnconfirmedTokenInfo| {
+                        token_info.check_can_be_used()?;
+                        wallet.create_internal_transfer_tx(
+                            account_index,
+                            to_account,
+                            &Currency::Token(token_id),
+                            amount,
+                            current_fee_rate,
+                            consolidate_fee_rate,
+                        )
+                    },
+                )
+                .await?
+            }
+        };
+
+        if self.config.broadcast_to_mempool {
+            self.wallet
+                .add_account_unconfirmed_tx(to_account, tx.clone(), self.wallet_events)
+                .map_err(ControllerError::WalletError)?;
+        }
+
+        Ok(tx)
+    }
+
     /// Creates a transaction that creates a new stake pool and broadcasts it to the mempool.
     pub async fn create_stake_pool_tx(
         &mut self,
@@ -1084,6 +1565,85 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
             .map_err(ControllerError::WalletError)
     }
 
+    /// Create an unsigned transaction with the specified outputs, packaged together with
+    /// everything an offline wallet needs to sign it (the spent utxos, their destinations and
+    /// the fees). If no utxos are selected, the inputs are selected automatically.
+    /// The change is sent to the specified address or to a new address of this account.
+    /// Returns the package and the fees that will be paid by the transaction.
+    pub async fn create_transaction_package(
+        &mut self,
+        outputs: Vec<TxOutput>,
+        selected_utxos: Vec<UtxoOutPoint>,
+        change_address: Option<Address<Destination>>,
+    ) -> Result<(TransactionPackage, Balances), ControllerError<T>> {
+        ensure!(
+            !outputs.is_empty(),
+            ControllerError::<T>::ExpectingNonEmptyOutputs
+        );
+
+        let (current_fee_rate, consolidate_fee_rate) =
+            self.get_current_and_consolidation_fee_rate().await?;
+
+        let change_addresses = change_address
+            .map(|address| [(Currency::Coin, address)].into())
+            .unwrap_or_default();
+
+        let (tx, fees) = self
+            .wallet
+            .create_unsigned_transaction_to_addresses(
+                self.account_index,
+                outputs,
+                SelectedInputs::Utxos(selected_utxos),
+                None,
+                change_addresses,
+                current_fee_rate,
+                consolidate_fee_rate,
+            )
+            .map_err(ControllerError::WalletError)?;
+
+        let best_block = self
+            .wallet
+            .get_best_block_for_account(self.account_index)
+            .map_err(ControllerError::WalletError)?;
+        let package = TransactionPackage::new(tx, fees.clone(), best_block);
+
+        let fees = into_balances(&self.rpc_client, self.chain_config, fees).await?;
+
+        Ok((package, fees))
+    }
+
+    /// Sign the inputs of the package's transaction with the private keys in this wallet.
+    /// This doesn't need a connection to the node, so it can be done on an air-gapped wallet.
+    /// Returns the updated package and the signature statuses before and after signing.
+    pub fn sign_transaction_package(
+        &mut self,
+        package: TransactionPackage,
+    ) -> Result<
+        (
+            TransactionPackage,
+            Vec<SignatureStatus>,
+            Vec<SignatureStatus>,
+        ),
+        ControllerError<T>,
+    > {
+        let tx = TransactionToSign::Partial(package.tx().clone());
+        let (tx, prev_signatures, cur_signatures) = self.sign_raw_transaction(tx)?;
+
+        Ok((package.with_tx(tx), prev_signatures, cur_signatures))
+    }
+
+    /// Broadcast the transaction of a fully signed package to the mempool.
+    pub async fn broadcast_transaction_package(
+        &mut self,
+        package: TransactionPackage,
+    ) -> Result<SignedTransaction, ControllerError<T>> {
+        let tx = package
+            .into_signed_tx()
+            .ok_or(ControllerError::<T>::TransactionPackageNotFullySigned)?;
+
+        self.broadcast_to_mempool(tx).await
+    }
+
     pub fn sign_challenge(
         &mut self,
         challenge: Vec<u8>,
@@ -1094,42 +1654,218 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
             .map_err(ControllerError::WalletError)
     }
 
+    /// Add a transaction already submitted to the node to the wallet, it's also queued
+    /// for rebroadcasting until it's included in a block
     pub fn add_unconfirmed_tx(&mut self, tx: SignedTransaction) -> Result<(), ControllerError<T>> {
         self.wallet
-            .add_unconfirmed_tx(tx, self.wallet_events)
+            .add_unconfirmed_tx(tx.clone(), self.wallet_events)
+            .map_err(ControllerError::WalletError)?;
+
+        let now = BlockTimestamp::from_time(get_time());
+        let mut entry = BroadcastQueueEntry::new(tx, BroadcastPolicy::default(), now);
+        entry.on_sent(now, make_resubmit_delay_secs());
+        self.wallet
+            .update_broadcast_queue_entry(entry.tx.transaction().get_id(), &entry)
             .map_err(ControllerError::WalletError)
     }
 
+    /// Make a stuck unconfirmed transaction of the account pay at least the new fee rate.
+    ///
+    /// If possible, the transaction is replaced by one spending the same inputs with a higher
+    /// fee, and once the replacement is accepted by the mempool the original transaction
+    /// is marked as conflicted and no longer rebroadcast. Otherwise, a child transaction
+    /// spending its outputs pays the missing fee (child pays for parent).
+    /// As the node may not accept replacements, a rejected replacement falls back to
+    /// a child transaction.
+    /// The new transaction is returned in both cases.
+    pub async fn bump_fee(
+        &mut self,
+        tx_id: Id<Transaction>,
+        new_fee_rate: FeeRate,
+    ) -> Result<SignedTransaction, ControllerError<T>> {
+        let min_fee_rate = self.get_min_fee_rate().await?;
+        ensure!(
+            new_fee_rate >= min_fee_rate,
+            ControllerError::FeeRateBelowMempoolMinimum(new_fee_rate, min_fee_rate)
+        );
+
+        let (tx, fee_bump_kind) = self
+            .wallet
+            .create_fee_bump_transaction(
+                self.account_index,
+                tx_id,
+                new_fee_rate,
+                min_fee_rate,
+                true,
+            )
+            .map_err(ControllerError::WalletError)?;
+
+        match fee_bump_kind {
+            FeeBumpKind::ReplaceByFee => {
+                // The original transaction stays valid until the replacement is accepted
+                let submit_result =
+                    self.rpc_client.submit_transaction(tx.clone(), Default::default()).await;
+                if let Err(err) = submit_result {
+                    log::warn!(
+                        "Replacement of transaction {} rejected: {}, paying with a child transaction instead",
+                        tx_id,
+                        err
+                    );
+                    let (tx, _) = self
+                        .wallet
+                        .create_fee_bump_transaction(
+                            self.account_index,
+                            tx_id,
+                            new_fee_rate,
+                            min_fee_rate,
+                            false,
+                        )
+                        .map_err(ControllerError::WalletError)?;
+                    return self.broadcast_to_mempool(tx).await;
+                }
+
+                self.wallet
+                    .mark_transaction_replaced(self.account_index, tx_id, self.wallet_events)
+                    .map_err(ControllerError::WalletError)?;
+                self.wallet
+                    .remove_broadcast_queue_entry(tx_id)
+                    .map_err(ControllerError::WalletError)?;
+                self.add_unconfirmed_tx(tx.clone())?;
+
+                Ok(tx)
+            }
+            FeeBumpKind::ChildPaysForParent => self.broadcast_to_mempool(tx).await,
+        }
+    }
+
     async fn get_current_and_consolidation_fee_rate(
         &mut self,
     ) -> Result<(mempool::FeeRate, mempool::FeeRate), ControllerError<T>> {
-        let current_fee_rate = self
+        match self.config.fee_override {
+            None => {}
+            Some(FeeOverride::FeeRate(fee_rate)) => {
+                let min_fee_rate = self.get_min_fee_rate().await?;
+                ensure!(
+                    fee_rate >= min_fee_rate,
+                    ControllerError::FeeRateBelowMempoolMinimum(fee_rate, min_fee_rate)
+                );
+                return Ok((fee_rate, fee_rate));
+            }
+            Some(FeeOverride::AbsoluteFee(_)) => {
+                return Err(ControllerError::AbsoluteFeeNotSupported)
+            }
+        }
+
+        let top_x_mb_fee_rate = self
             .rpc_client
             .mempool_get_fee_rate(self.config.in_top_x_mb)
             .await
             .map_err(ControllerError::NodeCallError)?;
-        let consolidate_fee_rate = current_fee_rate;
+        // The node's estimate is based on how quickly the recent transactions have been
+        // confirmed, so pay at least that much to avoid getting stuck in the mempool
+        let estimated_fee_rate = self
+            .rpc_client
+            .mempool_estimate_fee_rate(FEE_ESTIMATE_TARGET_BLOCKS)
+            .await
+            .map_err(ControllerError::NodeCallError)?;
+        // During congestion the mempool may require a higher rate than the one needed to get
+        // into the top X MB, so never go below the current mempool floor
+        let min_fee_rate = self.get_min_fee_rate().await?;
+        let current_fee_rate = std::cmp::max(top_x_mb_fee_rate, min_fee_rate);
+        let current_fee_rate = estimated_fee_rate.map_or(current_fee_rate, |fee_rate| {
+            std::cmp::max(fee_rate, current_fee_rate)
+        });
+        let consolidate_fee_rate =
+            self.get_recent_median_fee_rate().await?.map_or(current_fee_rate, |fee_rate| {
+                fee_rate.clamp(min_fee_rate, current_fee_rate)
+            });
         Ok((current_fee_rate, consolidate_fee_rate))
     }
 
+    /// The median of the median fee rates of the recent blocks that have transactions,
+    /// or None if there are no such blocks
+    async fn get_recent_median_fee_rate(
+        &self,
+    ) -> Result<Option<mempool::FeeRate>, ControllerError<T>> {
+        let mut fee_rates = self
+            .rpc_client
+            .chainstate_recent_block_stats(CONSOLIDATION_FEE_RATE_BLOCKS)
+            .await
+            .map_err(ControllerError::NodeCallError)?
+            .into_iter()
+            .filter_map(|stats| stats.median_fee_rate)
+            .collect::<Vec<_>>();
+        fee_rates.sort();
+
+        Ok(fee_rates
+            .get(fee_rates.len() / 2)
+            .copied()
+            .map(mempool::FeeRate::from_amount_per_kb))
+    }
+
+    async fn get_min_fee_rate(&self) -> Result<mempool::FeeRate, ControllerError<T>> {
+        self.rpc_client
+            .mempool_get_min_fee_rate()
+            .await
+            .map_err(ControllerError::NodeCallError)
+    }
+
     /// Broadcast a singed transaction to the mempool and update the wallets state if the
     /// transaction has been added to the mempool
     pub async fn broadcast_to_mempool(
         &mut self,
         tx: SignedTransaction,
+    ) -> Result<SignedTransaction, ControllerError<T>> {
+        self.broadcast_to_mempool_with_policy(tx, BroadcastPolicy::default()).await
+    }
+
+    /// Broadcast a signed transaction to the mempool and queue it for rebroadcasting
+    /// according to the policy until it's included in a block.
+    /// If the first submission fails the error is returned, but the transaction stays queued
+    /// and is retried later by the controller.
+    pub async fn broadcast_to_mempool_with_policy(
+        &mut self,
+        tx: SignedTransaction,
+        policy: BroadcastPolicy,
     ) -> Result<SignedTransaction, ControllerError<T>> {
         self.wallet
             .add_account_unconfirmed_tx(self.account_index, tx.clone(), self.wallet_events)
             .map_err(ControllerError::WalletError)?;
 
-        self.rpc_client
-            .submit_transaction(tx.clone(), Default::default())
-            .await
-            .map_err(ControllerError::NodeCallError)?;
+        let now = BlockTimestamp::from_time(get_time());
+        let entry = BroadcastQueueEntry::new(tx.clone(), policy, now);
+        self.submit_queued_tx(entry, now).await?;
 
         Ok(tx)
     }
 
+    /// Submit a transaction that has been saved in the wallet to the mempool and record
+    /// the outcome in its broadcast queue entry
+    async fn submit_queued_tx(
+        &mut self,
+        mut entry: BroadcastQueueEntry,
+        now: BlockTimestamp,
+    ) -> Result<(), ControllerError<T>> {
+        let tx_id = entry.tx.transaction().get_id();
+
+        let res = self.rpc_client.submit_transaction(entry.tx.clone(), Default::default()).await;
+        match &res {
+            Ok(()) => entry.on_sent(now, make_resubmit_delay_secs()),
+            Err(error) => entry.on_failure(now, error.to_string()),
+        }
+        // The policy may allow a single attempt only, release the inputs right away then
+        if let BroadcastStatus::Rejected(_) = entry.status {
+            self.wallet
+                .abandon_unbroadcast_transaction(tx_id)
+                .map_err(ControllerError::WalletError)?;
+        }
+        self.wallet
+            .update_broadcast_queue_entry(tx_id, &entry)
+            .map_err(ControllerError::WalletError)?;
+
+        res.map_err(ControllerError::NodeCallError)
+    }
+
     /// Broadcast to the mempool if specified by the controller config
     /// sometimes broadcasting is disabled when a prior confirmation is needed
     async fn broadcast_to_mempool_if_needed(
@@ -1180,15 +1916,7 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
         tx_maker: F,
     ) -> Result<SignedTransaction, ControllerError<T>> {
         // make sure we can use the token before create an tx using it
-        let token_freezable_info = match token_info {
-            RPCTokenInfo::FungibleToken(token_info) => self
-                .wallet
-                .get_token_unconfirmed_info(self.account_index, token_info)
-                .map_err(ControllerError::WalletError)?,
-            RPCTokenInfo::NonFungibleToken(info) => {
-                UnconfirmedTokenInfo::NonFungibleToken(info.token_id)
-            }
-        };
+        let token_freezable_info = self.get_unconfirmed_token_info(token_info)?;
 
         let (current_fee_rate, consolidate_fee_rate) =
             self.get_current_and_consolidation_fee_rate().await?;
@@ -1205,6 +1933,54 @@ impl<'a, T: NodeInterface, W: WalletEvents> SyncedController<'a, T, W> {
         self.broadcast_to_mempool_if_needed(tx).await
     }
 
+    /// Create a transaction that pays exactly `fee` in coins and broadcast it, provided the fee
+    /// is not below the one required by the current mempool minimum fee rate
+    async fn create_and_send_tx_with_absolute_fee(
+        &mut self,
+        fee: Amount,
+        outputs: impl IntoIterator<Item = TxOutput>,
+        inputs: SelectedInputs,
+    ) -> Result<SignedTransaction, ControllerError<T>> {
+        let min_fee_rate = self.get_min_fee_rate().await?;
+
+        let tx = self
+            .wallet
+            .create_transaction_to_addresses_with_absolute_fee(
+                self.account_index,
+                outputs,
+                inputs,
+                BTreeMap::new(),
+                fee,
+            )
+            .map_err(ControllerError::WalletError)?;
+
+        let required_fee: Amount = min_fee_rate
+            .compute_fee(tx.encoded_size())
+            .map_err(|_| ControllerError::WalletError(WalletError::FeeAmountOverflow))?
+            .into();
+        ensure!(
+            fee >= required_fee,
+            ControllerError::AbsoluteFeeTooLow(fee, required_fee)
+        );
+
+        self.broadcast_to_mempool_if_needed(tx).await
+    }
+
+    fn get_unconfirmed_token_info(
+        &self,
+        token_info: &RPCTokenInfo,
+    ) -> Result<UnconfirmedTokenInfo, ControllerError<T>> {
+        match token_info {
+            RPCTokenInfo::FungibleToken(token_info) => self
+                .wallet
+                .get_token_unconfirmed_info(self.account_index, token_info)
+                .map_err(ControllerError::WalletError),
+            RPCTokenInfo::NonFungibleToken(info) => {
+                Ok(UnconfirmedTokenInfo::NonFungibleToken(info.token_id))
+            }
+        }
+    }
+
     /// Similar to create_and_send_tx but some transactions also create an ID
     /// e.g. newly issued token, nft or delegation id
     async fn create_and_send_tx_with_id<
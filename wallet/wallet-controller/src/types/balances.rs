@@ -22,11 +22,25 @@ use common::{address::RpcAddress, chain::tokens::TokenId, primitives::amount::Rp
 pub struct Balances {
     coins: RpcAmountOut,
     tokens: BTreeMap<RpcAddress<TokenId>, RpcAmountOut>,
+    /// Tokens that were neither issued nor sent by the wallet and are not marked as trusted
+    unverified_tokens: BTreeMap<RpcAddress<TokenId>, RpcAmountOut>,
 }
 
 impl Balances {
     pub fn new(coins: RpcAmountOut, tokens: BTreeMap<RpcAddress<TokenId>, RpcAmountOut>) -> Self {
-        Self { coins, tokens }
+        Self {
+            coins,
+            tokens,
+            unverified_tokens: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_unverified_tokens(
+        mut self,
+        unverified_tokens: BTreeMap<RpcAddress<TokenId>, RpcAmountOut>,
+    ) -> Self {
+        self.unverified_tokens = unverified_tokens;
+        self
     }
 
     pub fn coins(&self) -> &RpcAmountOut {
@@ -37,10 +51,18 @@ impl Balances {
         &self.tokens
     }
 
+    pub fn unverified_tokens(&self) -> &BTreeMap<RpcAddress<TokenId>, RpcAmountOut> {
+        &self.unverified_tokens
+    }
+
     pub fn into_coins_and_tokens(
         self,
     ) -> (RpcAmountOut, BTreeMap<RpcAddress<TokenId>, RpcAmountOut>) {
-        let Self { coins, tokens } = self;
+        let Self {
+            coins,
+            tokens,
+            unverified_tokens: _,
+        } = self;
         (coins, tokens)
     }
 }
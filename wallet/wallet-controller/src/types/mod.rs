@@ -17,9 +17,17 @@
 
 mod balances;
 mod block_info;
+mod payouts;
 mod seed_phrase;
+mod staking_estimate;
+mod staking_reward;
 mod standalone_key;
+mod sync_status;
 mod transaction;
+mod transaction_package;
+mod tx_description;
+mod tx_history;
+mod utxo_verification;
 
 pub use balances::Balances;
 pub use block_info::{BlockInfo, CreatedBlockInfo};
@@ -28,16 +36,44 @@ use common::{
     chain::{
         output_value::OutputValue,
         tokens::{RPCTokenInfo, TokenId},
-        ChainConfig, Destination, TxOutput,
+        ChainConfig, Destination, SignedTransaction, TxOutput,
     },
     primitives::{DecimalAmount, H256},
 };
+use crypto::key::hdkd::u31::U31;
+pub use payouts::{
+    parse_payouts_csv, split_payouts, PayoutRow, PayoutsCsvError, DEFAULT_MAX_PAYOUTS_PER_TX,
+    PAYOUTS_CSV_HEADER,
+};
 pub use seed_phrase::SeedWithPassPhrase;
+pub use staking_estimate::StakingEstimate;
+pub use staking_reward::{CreditedReward, StakingReward};
 pub use standalone_key::AccountStandaloneKeyDetails;
+pub use sync_status::SyncStatus;
 pub use transaction::{
     InspectTransaction, SignatureStats, TransactionToInspect, ValidatedSignatures,
 };
+pub use transaction_package::TransactionPackage;
+pub use tx_description::{
+    referenced_tokens, CurrencyAmount, MainchainTxInfo, TransactionDescription,
+    TxDescriptionDetails, TxDescriptionEntry,
+};
+pub use tx_history::{transaction_history_to_csv, TransactionHistoryEntry};
 use utils::ensure;
+pub use utxo_verification::{UtxoDiscrepancy, UtxoDiscrepancyKind, UtxoVerificationReport};
+
+/// The outcome of a token authority rotation, or of its dry run
+#[derive(Debug, Clone)]
+pub struct TokenAuthorityRotation {
+    pub token_id: TokenId,
+    /// The authority that controls the token, including any unconfirmed changes
+    pub current_authority: Destination,
+    pub new_authority: Destination,
+    /// The account of this wallet that controls the new authority, if any
+    pub new_authority_account: Option<U31>,
+    /// The transaction changing the authority; None for a dry run
+    pub transaction: Option<SignedTransaction>,
+}
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
 pub struct WalletInfo {
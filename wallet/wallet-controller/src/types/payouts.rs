@@ -0,0 +1,200 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bulk payouts read from CSV, e.g. for airdrops and payroll
+
+use std::str::FromStr;
+
+use common::primitives::DecimalAmount;
+
+/// The optional first line of a payouts CSV
+pub const PAYOUTS_CSV_HEADER: &str = "address,amount,token";
+
+/// The maximum number of payouts put into a single transaction by default
+pub const DEFAULT_MAX_PAYOUTS_PER_TX: usize = 100;
+
+/// A single row of a payouts CSV
+#[derive(Debug, Clone)]
+pub struct PayoutRow {
+    /// The line of the CSV the payout is read from, starting from 1
+    pub line: usize,
+    pub address: String,
+    /// The amount in coins or in the token's units, in decimal format
+    pub amount: DecimalAmount,
+    /// The token to be paid, coins are paid if absent
+    pub token_id: Option<String>,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum PayoutsCsvError {
+    #[error("The CSV contains no payouts")]
+    NoPayouts,
+    #[error("Line {0}: expected the address, the amount and optionally the token id")]
+    WrongFieldCount(usize),
+    #[error("Line {0}: invalid address {1}")]
+    InvalidAddress(usize, String),
+    #[error("Line {0}: invalid amount {1}")]
+    InvalidAmount(usize, String),
+    #[error("Line {0}: invalid token id {1}")]
+    InvalidTokenId(usize, String),
+}
+
+/// Parse a CSV with the `address,amount,token` rows, the token being empty for coins.
+///
+/// The header line is optional and empty lines are ignored. Only the format of the amounts
+/// is checked here, the addresses, the token ids and the number of decimals of the amounts
+/// have to be validated against the chain and the tokens by the caller.
+pub fn parse_payouts_csv(csv: &str) -> Result<Vec<PayoutRow>, PayoutsCsvError> {
+    let mut rows = Vec::new();
+
+    for (line_index, line) in csv.lines().enumerate() {
+        let line_number = line_index + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if rows.is_empty() && line.replace(' ', "").eq_ignore_ascii_case(PAYOUTS_CSV_HEADER) {
+            continue;
+        }
+
+        let fields = line.split(',').map(str::trim).collect::<Vec<_>>();
+        let (address, amount, token_id) = match fields.as_slice() {
+            [address, amount] => (address, amount, None),
+            [address, amount, token_id] => {
+                (address, amount, Some(token_id).filter(|id| !id.is_empty()))
+            }
+            _ => return Err(PayoutsCsvError::WrongFieldCount(line_number)),
+        };
+
+        let decimal_amount = DecimalAmount::from_str(amount)
+            .map_err(|_| PayoutsCsvError::InvalidAmount(line_number, amount.to_string()))?;
+
+        rows.push(PayoutRow {
+            line: line_number,
+            address: address.to_string(),
+            amount: decimal_amount,
+            token_id: token_id.map(|id| id.to_string()),
+        });
+    }
+
+    if rows.is_empty() {
+        return Err(PayoutsCsvError::NoPayouts);
+    }
+
+    Ok(rows)
+}
+
+/// Split the payouts into batches, each of which is paid by a single transaction.
+///
+/// A batch has at most `max_payouts_per_tx` payouts, and its outputs take at most half
+/// of `max_tx_size`, leaving the rest for the inputs, their signatures and the change.
+pub fn split_payouts<T>(
+    payouts: Vec<T>,
+    max_payouts_per_tx: usize,
+    max_tx_size: usize,
+    output_size: impl Fn(&T) -> usize,
+) -> Vec<Vec<T>> {
+    let max_outputs_size = max_tx_size / 2;
+
+    let mut batches = Vec::new();
+    let mut batch = Vec::new();
+    let mut batch_size = 0;
+    for payout in payouts {
+        let size = output_size(&payout);
+        if !batch.is_empty()
+            && (batch.len() >= max_payouts_per_tx || batch_size + size > max_outputs_size)
+        {
+            batches.push(std::mem::take(&mut batch));
+            batch_size = 0;
+        }
+        batch_size += size;
+        batch.push(payout);
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+
+    batches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let csv = "Address, Amount, Token\n\
+                   tmt1qaddr1,1.5\n\
+                   \n\
+                   tmt1qaddr2, 10 ,\n\
+                   tmt1qaddr3,0.001,tmltk1token\n";
+        let rows = parse_payouts_csv(csv).unwrap();
+        let expected = [
+            (2, "tmt1qaddr1", "1.5", None),
+            (4, "tmt1qaddr2", "10", None),
+            (5, "tmt1qaddr3", "0.001", Some("tmltk1token")),
+        ];
+        assert_eq!(rows.len(), expected.len());
+        for (row, (line, address, amount, token_id)) in rows.iter().zip(expected) {
+            assert_eq!(row.line, line);
+            assert_eq!(row.address, address);
+            assert!(row.amount.is_same(&DecimalAmount::from_str(amount).unwrap()));
+            assert_eq!(row.token_id.as_deref(), token_id);
+        }
+
+        // The header is optional
+        assert_eq!(parse_payouts_csv("tmt1qaddr1,1.5").unwrap().len(), 1);
+
+        assert_eq!(
+            parse_payouts_csv(PAYOUTS_CSV_HEADER).unwrap_err(),
+            PayoutsCsvError::NoPayouts
+        );
+        assert_eq!(
+            parse_payouts_csv("tmt1qaddr1,1.5\ntmt1qaddr2").unwrap_err(),
+            PayoutsCsvError::WrongFieldCount(2)
+        );
+        assert_eq!(
+            parse_payouts_csv("tmt1qaddr1,1.5,token,extra").unwrap_err(),
+            PayoutsCsvError::WrongFieldCount(1)
+        );
+        assert_eq!(
+            parse_payouts_csv("tmt1qaddr1,1.5.1").unwrap_err(),
+            PayoutsCsvError::InvalidAmount(1, "1.5.1".to_owned())
+        );
+    }
+
+    #[test]
+    fn split() {
+        let payouts = (1..=10).collect::<Vec<usize>>();
+
+        // Limited by the number of payouts
+        assert_eq!(
+            split_payouts(payouts.clone(), 4, 1000, |_| 10),
+            vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8], vec![9, 10]]
+        );
+
+        // Limited by the size of the outputs, half of the max tx size
+        assert_eq!(
+            split_payouts(payouts.clone(), 100, 100, |&payout| payout * 5),
+            vec![vec![1, 2, 3, 4], vec![5, 6, 7], vec![8], vec![9], vec![10]]
+        );
+
+        // A payout that doesn't fit is still put into its own batch
+        assert_eq!(
+            split_payouts(vec![1, 2], 100, 10, |_| 100),
+            vec![vec![1], vec![2]]
+        );
+    }
+}
@@ -0,0 +1,219 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    chain::{ChainConfig, PoSConsensusVersion},
+    primitives::{amount::RpcAmountOut, Amount, BlockHeight, DecimalAmount},
+    Uint256, Uint512,
+};
+use consensus::EffectivePoolBalanceError;
+
+const SECONDS_PER_DAY: u128 = 24 * 60 * 60;
+const DAYS_PER_MONTH: u128 = 30;
+const DAYS_PER_YEAR: u128 = 365;
+
+/// The number of decimal digits in the expected block counts
+const BLOCK_COUNT_DECIMALS: u8 = 4;
+/// The number of decimal digits in the annual return
+const RETURN_PERCENT_DECIMALS: u8 = 2;
+
+/// An estimate of the staking outcome of a pool with the given pledge and balance,
+/// based on the current network difficulty.
+///
+/// The estimate assumes that the difficulty and the block subsidy stay the same,
+/// and that the pool is online all the time. Transaction fees are not taken into account.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
+pub struct StakingEstimate {
+    pub effective_pool_balance: RpcAmountOut,
+    /// The height of the block whose difficulty the estimate is based on
+    pub block_height: BlockHeight,
+    /// The block subsidy at the next height
+    pub block_reward: RpcAmountOut,
+    pub expected_blocks_per_day: DecimalAmount,
+    /// The expected number of blocks in 30 days
+    pub expected_blocks_per_month: DecimalAmount,
+    /// The expected yearly reward of the whole pool relative to its balance, in percent
+    pub annual_return_percent: DecimalAmount,
+}
+
+impl StakingEstimate {
+    /// Make the estimate for the PoS target of the block at the specified height
+    pub fn new(
+        chain_config: &ChainConfig,
+        consensus_version: PoSConsensusVersion,
+        target: Uint256,
+        block_height: BlockHeight,
+        pledge: Amount,
+        pool_balance: Amount,
+    ) -> Result<Self, EffectivePoolBalanceError> {
+        let final_supply = chain_config
+            .final_supply()
+            .map_or(Amount::MAX, |final_supply| final_supply.to_amount_atoms());
+        let effective_pool_balance =
+            consensus::calculate_effective_pool_balance(pledge, pool_balance, final_supply)?;
+
+        // The same balance that is checked against the target when a block is produced
+        let staking_balance = if consensus_version == PoSConsensusVersion::V0 {
+            pool_balance
+        } else {
+            effective_pool_balance
+        };
+
+        // A new timestamp is tried every second, and the chance of producing a block with it
+        // is `staking_balance * target / 2^256`, which is capped at 1
+        let win_chance = std::cmp::min(
+            (Uint512::from(staking_balance) * target.into())
+                .expect("cannot overflow because both are converted from smaller types"),
+            Uint512::from(Uint256::MAX),
+        );
+
+        let block_reward = chain_config.block_subsidy_at_height(&block_height.next_height());
+
+        let blocks_per_day = expected_blocks(win_chance, SECONDS_PER_DAY, BLOCK_COUNT_DECIMALS);
+        let blocks_per_month = expected_blocks(
+            win_chance,
+            SECONDS_PER_DAY * DAYS_PER_MONTH,
+            BLOCK_COUNT_DECIMALS,
+        );
+
+        // Two extra decimal digits for the conversion into percent
+        let yearly_reward = expected_blocks(
+            win_chance,
+            SECONDS_PER_DAY * DAYS_PER_YEAR,
+            RETURN_PERCENT_DECIMALS + 2,
+        )
+        .saturating_mul(block_reward.into_atoms());
+        let annual_return = yearly_reward.checked_div(pool_balance.into_atoms()).unwrap_or(0);
+
+        let decimals = chain_config.coin_decimals();
+        Ok(Self {
+            effective_pool_balance: RpcAmountOut::from_amount(effective_pool_balance, decimals),
+            block_height,
+            block_reward: RpcAmountOut::from_amount(block_reward, decimals),
+            expected_blocks_per_day: DecimalAmount::from_uint_decimal(
+                blocks_per_day,
+                BLOCK_COUNT_DECIMALS,
+            ),
+            expected_blocks_per_month: DecimalAmount::from_uint_decimal(
+                blocks_per_month,
+                BLOCK_COUNT_DECIMALS,
+            ),
+            annual_return_percent: DecimalAmount::from_uint_decimal(
+                annual_return,
+                RETURN_PERCENT_DECIMALS,
+            ),
+        })
+    }
+}
+
+/// The expected number of blocks produced in the given number of seconds,
+/// with the given number of decimal digits
+fn expected_blocks(win_chance: Uint512, seconds: u128, decimals: u8) -> u128 {
+    let multiplier = seconds * 10u128.pow(decimals as u32);
+    let blocks = (win_chance * Uint512::from(multiplier))
+        .expect("cannot overflow because the chance is at most 2^256")
+        >> 256;
+
+    Uint256::try_from(blocks)
+        .ok()
+        .and_then(|blocks| u128::try_from(blocks).ok())
+        .expect("cannot be greater than the multiplier")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::chain::config::create_mainnet;
+
+    #[test]
+    fn staking_estimate() {
+        let chain_config = create_mainnet();
+        let coin = 10u128.pow(chain_config.coin_decimals() as u32);
+        let pledge = Amount::from_atoms(40_000 * coin);
+        let pool_balance = Amount::from_atoms(100_000 * coin);
+        let block_height = BlockHeight::new(1000);
+
+        // With the target of 2^256 / (pool_balance * 120) the pool is expected to produce a block
+        // every 120 seconds, i.e. 720 blocks per day
+        let target = (Uint256::MAX / Uint256::from_amount(pool_balance))
+            .and_then(|target| target / Uint256::from_u64(120))
+            .unwrap();
+        let estimate = StakingEstimate::new(
+            &chain_config,
+            PoSConsensusVersion::V0,
+            target,
+            block_height,
+            pledge,
+            pool_balance,
+        )
+        .unwrap();
+        assert_eq!(estimate.expected_blocks_per_day.to_string(), "719.9999");
+        assert_eq!(estimate.expected_blocks_per_month.to_string(), "21599.9999");
+
+        let block_reward = chain_config.block_subsidy_at_height(&block_height.next_height());
+        assert_eq!(estimate.block_reward.amount(), block_reward);
+        let expected_return =
+            720 * 365 * block_reward.into_atoms() * 10_000 / pool_balance.into_atoms();
+        let annual_return = estimate.annual_return_percent.mantissa();
+        assert!(annual_return <= expected_return && annual_return + 1 >= expected_return);
+
+        // The effective balance is used starting from V1, and it's lower than the pool balance
+        // with a pledge below the saturation level
+        let estimate_v1 = StakingEstimate::new(
+            &chain_config,
+            PoSConsensusVersion::V1,
+            target,
+            block_height,
+            pledge,
+            pool_balance,
+        )
+        .unwrap();
+        assert_eq!(
+            estimate_v1.effective_pool_balance.amount(),
+            estimate.effective_pool_balance.amount()
+        );
+        assert!(estimate_v1.effective_pool_balance.amount() < pool_balance);
+        assert!(
+            estimate_v1.expected_blocks_per_day.mantissa()
+                < estimate.expected_blocks_per_day.mantissa()
+        );
+
+        // The chance to produce a block is capped
+        let estimate = StakingEstimate::new(
+            &chain_config,
+            PoSConsensusVersion::V1,
+            Uint256::MAX,
+            block_height,
+            pledge,
+            pool_balance,
+        )
+        .unwrap();
+        assert_eq!(estimate.expected_blocks_per_day.to_string(), "86399.9999");
+
+        assert_eq!(
+            StakingEstimate::new(
+                &chain_config,
+                PoSConsensusVersion::V1,
+                target,
+                block_height,
+                pool_balance,
+                pledge,
+            )
+            .unwrap_err(),
+            EffectivePoolBalanceError::PoolPledgeGreaterThanBalance(pool_balance, pledge)
+        );
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    chain::{block::timestamp::BlockTimestamp, GenBlock, PoolId},
+    primitives::{Amount, BlockHeight, Id},
+};
+
+/// The amounts credited by the node to a pool and its delegations for producing a block
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct CreditedReward {
+    /// The block subsidy plus the transaction fees of the block
+    pub total_reward: Amount,
+    /// The part of the total reward that went to the pool's staker
+    pub staker_reward: Amount,
+    /// The part of the total reward that went to the pool's delegations
+    pub delegations_reward: Amount,
+    /// The part of the delegations reward that went to the delegations owned by the account
+    pub own_delegations_reward: Amount,
+}
+
+/// A block produced by one of the pools of the account
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct StakingReward {
+    pub pool_id: PoolId,
+    pub block_id: Id<GenBlock>,
+    pub block_height: BlockHeight,
+    pub block_timestamp: BlockTimestamp,
+    /// `None` if the node doesn't know the reward distribution of the block anymore
+    pub credited: Option<CreditedReward>,
+}
@@ -0,0 +1,34 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::chain::block::timestamp::BlockTimestamp;
+use rpc_description::HasValueHint;
+
+use super::BlockInfo;
+
+/// How up to date the wallet data is.
+///
+/// The data returned by the read-only requests is only as fresh as the best synced block,
+/// which may lag behind the node tip while a sync or a rescan is in progress.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct SyncStatus {
+    /// The lowest best block among all the accounts
+    pub best_synced_block: BlockInfo,
+    /// The median time of the node's tip at the moment of the last sync
+    pub median_time: BlockTimestamp,
+    /// When the wallet was last in sync with the node, if it has been since it was opened
+    pub last_synced_at: Option<BlockTimestamp>,
+    pub sync_in_progress: bool,
+}
@@ -0,0 +1,196 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use common::{
+    chain::{
+        partially_signed_transaction::PartiallySignedTransaction, tokens::TokenId, GenBlock,
+        SignedTransaction,
+    },
+    primitives::{Amount, BlockHeight, Id},
+};
+use serialization::{Decode, Encode};
+use wallet::account::currency_grouper::Currency;
+
+/// A transaction prepared by an online wallet, to be signed by an offline (air-gapped) wallet
+/// and then brought back to the online one to be broadcast.
+///
+/// The partially signed transaction carries everything the offline wallet needs to sign
+/// the inputs: the utxos being spent and their destinations. The rest of the data is there
+/// to let the user check what is being signed.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct TransactionPackage {
+    tx: PartiallySignedTransaction,
+    /// The fee in coins, as calculated by the wallet that created the package
+    coin_fee: Amount,
+    /// The fees in tokens, if any tokens are left unspent by the transaction
+    token_fees: BTreeMap<TokenId, Amount>,
+    /// The best block of the online wallet when the package was created
+    best_block_id: Id<GenBlock>,
+    best_block_height: BlockHeight,
+}
+
+impl TransactionPackage {
+    pub fn new(
+        tx: PartiallySignedTransaction,
+        fees: BTreeMap<Currency, Amount>,
+        (best_block_id, best_block_height): (Id<GenBlock>, BlockHeight),
+    ) -> Self {
+        let mut coin_fee = Amount::ZERO;
+        let mut token_fees = BTreeMap::new();
+        for (currency, amount) in fees {
+            match currency {
+                Currency::Coin => coin_fee = amount,
+                Currency::Token(token_id) => {
+                    token_fees.insert(token_id, amount);
+                }
+            }
+        }
+
+        Self {
+            tx,
+            coin_fee,
+            token_fees,
+            best_block_id,
+            best_block_height,
+        }
+    }
+
+    pub fn tx(&self) -> &PartiallySignedTransaction {
+        &self.tx
+    }
+
+    pub fn take_tx(self) -> PartiallySignedTransaction {
+        self.tx
+    }
+
+    /// Replace the transaction with its newly signed version
+    pub fn with_tx(self, tx: PartiallySignedTransaction) -> Self {
+        Self { tx, ..self }
+    }
+
+    pub fn coin_fee(&self) -> Amount {
+        self.coin_fee
+    }
+
+    pub fn token_fees(&self) -> &BTreeMap<TokenId, Amount> {
+        &self.token_fees
+    }
+
+    pub fn fees(&self) -> BTreeMap<Currency, Amount> {
+        std::iter::once((Currency::Coin, self.coin_fee))
+            .chain(
+                self.token_fees
+                    .iter()
+                    .map(|(token_id, amount)| (Currency::Token(*token_id), *amount)),
+            )
+            .collect()
+    }
+
+    pub fn best_block(&self) -> (Id<GenBlock>, BlockHeight) {
+        (self.best_block_id, self.best_block_height)
+    }
+
+    pub fn is_fully_signed(&self) -> bool {
+        self.tx.all_signatures_available()
+    }
+
+    pub fn into_signed_tx(self) -> Option<SignedTransaction> {
+        self.tx.into_signed_tx().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{
+        chain::{
+            output_value::OutputValue,
+            signature::{
+                inputsig::{standard_signature::StandardInputSignature, InputWitness},
+                sighash::sighashtype::SigHashType,
+            },
+            Destination, OutPointSourceId, Transaction, TxInput, TxOutput,
+        },
+        primitives::H256,
+    };
+    use randomness::Rng;
+    use rstest::rstest;
+    use serialization::DecodeAll;
+    use test_utils::random::{make_seedable_rng, Seed};
+
+    use super::*;
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn encode_and_sign(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+
+        let utxo = TxOutput::Transfer(
+            OutputValue::Coin(Amount::from_atoms(rng.gen_range(1000..2000))),
+            Destination::AnyoneCanSpend,
+        );
+        let tx = Transaction::new(
+            0,
+            vec![TxInput::from_utxo(
+                OutPointSourceId::Transaction(Id::new(H256::random_using(&mut rng))),
+                0,
+            )],
+            vec![TxOutput::Burn(OutputValue::Coin(Amount::from_atoms(100)))],
+        )
+        .unwrap();
+        let ptx = PartiallySignedTransaction::new(
+            tx,
+            vec![None],
+            vec![Some(utxo)],
+            vec![Some(Destination::AnyoneCanSpend)],
+            None,
+            None,
+        )
+        .unwrap();
+
+        let token_id = TokenId::new(H256::random_using(&mut rng));
+        let fees = BTreeMap::from([
+            (Currency::Coin, Amount::from_atoms(rng.gen_range(1..1000))),
+            (
+                Currency::Token(token_id),
+                Amount::from_atoms(rng.gen_range(1..1000)),
+            ),
+        ]);
+        let best_block = (
+            Id::new(H256::random_using(&mut rng)),
+            BlockHeight::new(rng.gen_range(1..1000)),
+        );
+
+        let package = TransactionPackage::new(ptx.clone(), fees.clone(), best_block);
+        assert_eq!(package.fees(), fees);
+        assert_eq!(package.best_block(), best_block);
+        assert!(!package.is_fully_signed());
+
+        let decoded = TransactionPackage::decode_all(&mut package.encode().as_slice()).unwrap();
+        assert_eq!(decoded, package);
+        assert_eq!(decoded.clone().into_signed_tx(), None);
+
+        let witness = InputWitness::Standard(StandardInputSignature::new(
+            SigHashType::default(),
+            vec![1, 2, 3],
+        ));
+        let signed = decoded.with_tx(ptx.with_witnesses(vec![Some(witness)]));
+        assert!(signed.is_fully_signed());
+        assert_eq!(signed.fees(), fees);
+        assert!(signed.into_signed_tx().is_some());
+    }
+}
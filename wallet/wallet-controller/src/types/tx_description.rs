@@ -0,0 +1,503 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use common::{
+    address::{Addressable, RpcAddress},
+    chain::{
+        block::timestamp::BlockTimestamp,
+        tokens::{RPCTokenInfo, TokenId},
+        ChainConfig, DelegationId, Destination, OrderId, PoolId, Transaction,
+    },
+    primitives::{amount::RpcAmountOut, Amount, BlockHeight, Id},
+};
+use wallet::account::{currency_grouper::Currency, transaction_list::TxDescriptionItem, TxInfo};
+
+/// An amount of coins or of some token
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
+pub struct CurrencyAmount {
+    /// The token the amount is denominated in, coins if absent
+    pub token_id: Option<RpcAddress<TokenId>>,
+    pub amount: RpcAmountOut,
+}
+
+/// Structured part of a transaction description entry
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
+#[serde(tag = "type", content = "content")]
+pub enum TxDescriptionDetails {
+    Sent {
+        amount: CurrencyAmount,
+        destination: RpcAddress<Destination>,
+    },
+    Received {
+        amount: CurrencyAmount,
+        destination: RpcAddress<Destination>,
+    },
+    Burned {
+        amount: CurrencyAmount,
+    },
+    CreatedStakePool {
+        pool_id: RpcAddress<PoolId>,
+        pledge: RpcAmountOut,
+    },
+    DecommissionedStakePool {
+        pool_id: RpcAddress<PoolId>,
+    },
+    CreatedDelegation {
+        pool_id: RpcAddress<PoolId>,
+    },
+    Delegated {
+        delegation_id: RpcAddress<DelegationId>,
+        pool_id: Option<RpcAddress<PoolId>>,
+        amount: RpcAmountOut,
+    },
+    WithdrewFromDelegation {
+        delegation_id: RpcAddress<DelegationId>,
+        pool_id: Option<RpcAddress<PoolId>>,
+        amount: RpcAmountOut,
+    },
+    IssuedToken {
+        ticker: String,
+    },
+    IssuedNft {
+        token_id: RpcAddress<TokenId>,
+    },
+    MintedTokens {
+        amount: CurrencyAmount,
+    },
+    UnmintedTokens {
+        token_id: RpcAddress<TokenId>,
+    },
+    LockedTokenSupply {
+        token_id: RpcAddress<TokenId>,
+    },
+    FrozeToken {
+        token_id: RpcAddress<TokenId>,
+    },
+    UnfrozeToken {
+        token_id: RpcAddress<TokenId>,
+    },
+    ChangedTokenAuthority {
+        token_id: RpcAddress<TokenId>,
+        authority: RpcAddress<Destination>,
+    },
+    ChangedTokenMetadataUri {
+        token_id: RpcAddress<TokenId>,
+    },
+    CreatedOrder {
+        ask: CurrencyAmount,
+        give: CurrencyAmount,
+    },
+    FilledOrder {
+        order_id: RpcAddress<OrderId>,
+        amount: CurrencyAmount,
+    },
+    ConcludedOrder {
+        order_id: RpcAddress<OrderId>,
+    },
+    DataDeposit,
+}
+
+/// A single action performed by a transaction, e.g. "Sent 10 ML to tmt1q..."
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
+pub struct TxDescriptionEntry {
+    pub summary: String,
+    pub details: TxDescriptionDetails,
+}
+
+/// Human-friendly description of a wallet transaction
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
+pub struct TransactionDescription {
+    pub tx_id: Id<Transaction>,
+    pub entries: Vec<TxDescriptionEntry>,
+}
+
+/// Tokens whose info is needed to describe the given items
+pub fn referenced_tokens(items: &[TxDescriptionItem]) -> BTreeSet<TokenId> {
+    let currency_token = |currency: &Currency| match currency {
+        Currency::Coin => None,
+        Currency::Token(token_id) => Some(*token_id),
+    };
+
+    items
+        .iter()
+        .flat_map(|item| match item {
+            TxDescriptionItem::Sent { currency, .. }
+            | TxDescriptionItem::Received { currency, .. }
+            | TxDescriptionItem::Burned { currency, .. }
+            | TxDescriptionItem::FilledOrder { currency, .. } => vec![currency_token(currency)],
+            TxDescriptionItem::CreatedOrder {
+                ask_currency,
+                give_currency,
+                ..
+            } => vec![currency_token(ask_currency), currency_token(give_currency)],
+            TxDescriptionItem::IssuedNft { token_id }
+            | TxDescriptionItem::MintedTokens { token_id, .. }
+            | TxDescriptionItem::UnmintedTokens { token_id }
+            | TxDescriptionItem::LockedTokenSupply { token_id }
+            | TxDescriptionItem::FrozeToken { token_id }
+            | TxDescriptionItem::UnfrozeToken { token_id }
+            | TxDescriptionItem::ChangedTokenAuthority { token_id, .. }
+            | TxDescriptionItem::ChangedTokenMetadataUri { token_id } => vec![Some(*token_id)],
+            TxDescriptionItem::CreatedStakePool { .. }
+            | TxDescriptionItem::DecommissionedStakePool { .. }
+            | TxDescriptionItem::CreatedDelegation { .. }
+            | TxDescriptionItem::Delegated { .. }
+            | TxDescriptionItem::WithdrewFromDelegation { .. }
+            | TxDescriptionItem::IssuedToken { .. }
+            | TxDescriptionItem::DataDeposit {} => vec![],
+        })
+        .flatten()
+        .collect()
+}
+
+fn token_ticker(info: &RPCTokenInfo) -> String {
+    let ticker = match info {
+        RPCTokenInfo::FungibleToken(info) => &info.token_ticker,
+        RPCTokenInfo::NonFungibleToken(info) => &info.metadata.ticker,
+    };
+    String::from_utf8_lossy(ticker.as_ref()).into_owned()
+}
+
+/// Renders description items into user-facing entries, using the token infos to get the tickers
+/// and the numbers of decimals of the referenced tokens
+pub(super) struct Renderer<'a> {
+    pub(super) chain_config: &'a ChainConfig,
+    pub(super) token_infos: &'a BTreeMap<TokenId, RPCTokenInfo>,
+}
+
+impl Renderer<'_> {
+    pub(super) fn address<A: Addressable>(&self, object: A) -> RpcAddress<A> {
+        RpcAddress::new(self.chain_config, object).expect("addressable")
+    }
+
+    fn token_info(&self, token_id: &TokenId) -> &RPCTokenInfo {
+        self.token_infos.get(token_id).expect("token info must be fetched")
+    }
+
+    fn token_name(&self, token_id: TokenId) -> String {
+        let ticker = token_ticker(self.token_info(&token_id));
+        format!("{ticker} ({})", self.address(token_id))
+    }
+
+    pub(super) fn coins(&self, amount: Amount) -> (RpcAmountOut, String) {
+        let amount =
+            RpcAmountOut::from_amount_no_padding(amount, self.chain_config.coin_decimals());
+        let text = format!("{} {}", amount.decimal(), self.chain_config.coin_ticker());
+        (amount, text)
+    }
+
+    pub(super) fn currency_amount(
+        &self,
+        currency: Currency,
+        amount: Amount,
+    ) -> (CurrencyAmount, String) {
+        match currency {
+            Currency::Coin => {
+                let (amount, text) = self.coins(amount);
+                let amount = CurrencyAmount {
+                    token_id: None,
+                    amount,
+                };
+                (amount, text)
+            }
+            Currency::Token(token_id) => {
+                let info = self.token_info(&token_id);
+                let amount =
+                    RpcAmountOut::from_amount_no_padding(amount, info.token_number_of_decimals());
+                let text = format!("{} {}", amount.decimal(), token_ticker(info));
+                let amount = CurrencyAmount {
+                    token_id: Some(self.address(token_id)),
+                    amount,
+                };
+                (amount, text)
+            }
+        }
+    }
+
+    fn delegation(&self, delegation_id: DelegationId, pool_id: Option<PoolId>) -> String {
+        match pool_id {
+            Some(pool_id) => format!(
+                "delegation {} (pool {})",
+                self.address(delegation_id),
+                self.address(pool_id)
+            ),
+            None => format!("delegation {}", self.address(delegation_id)),
+        }
+    }
+
+    pub(super) fn render(&self, item: TxDescriptionItem) -> TxDescriptionEntry {
+        let (summary, details) = match item {
+            TxDescriptionItem::Sent {
+                currency,
+                amount,
+                destination,
+            } => {
+                let (amount, text) = self.currency_amount(currency, amount);
+                let destination = self.address(destination);
+                (
+                    format!("Sent {text} to {destination}"),
+                    TxDescriptionDetails::Sent {
+                        amount,
+                        destination,
+                    },
+                )
+            }
+            TxDescriptionItem::Received {
+                currency,
+                amount,
+                destination,
+            } => {
+                let (amount, text) = self.currency_amount(currency, amount);
+                let destination = self.address(destination);
+                (
+                    format!("Received {text} to {destination}"),
+                    TxDescriptionDetails::Received {
+                        amount,
+                        destination,
+                    },
+                )
+            }
+            TxDescriptionItem::Burned { currency, amount } => {
+                let (amount, text) = self.currency_amount(currency, amount);
+                (
+                    format!("Burned {text}"),
+                    TxDescriptionDetails::Burned { amount },
+                )
+            }
+            TxDescriptionItem::CreatedStakePool { pool_id, pledge } => {
+                let (pledge, text) = self.coins(pledge);
+                let pool_id = self.address(pool_id);
+                (
+                    format!("Created stake pool {pool_id} with a pledge of {text}"),
+                    TxDescriptionDetails::CreatedStakePool { pool_id, pledge },
+                )
+            }
+            TxDescriptionItem::DecommissionedStakePool { pool_id } => {
+                let pool_id = self.address(pool_id);
+                (
+                    format!("Decommissioned stake pool {pool_id}"),
+                    TxDescriptionDetails::DecommissionedStakePool { pool_id },
+                )
+            }
+            TxDescriptionItem::CreatedDelegation { pool_id } => {
+                let pool_id = self.address(pool_id);
+                (
+                    format!("Created a delegation to pool {pool_id}"),
+                    TxDescriptionDetails::CreatedDelegation { pool_id },
+                )
+            }
+            TxDescriptionItem::Delegated {
+                delegation_id,
+                pool_id,
+                amount,
+            } => {
+                let (amount, text) = self.coins(amount);
+                (
+                    format!(
+                        "Delegated {text} to {}",
+                        self.delegation(delegation_id, pool_id)
+                    ),
+                    TxDescriptionDetails::Delegated {
+                        delegation_id: self.address(delegation_id),
+                        pool_id: pool_id.map(|pool_id| self.address(pool_id)),
+                        amount,
+                    },
+                )
+            }
+            TxDescriptionItem::WithdrewFromDelegation {
+                delegation_id,
+                pool_id,
+                amount,
+            } => {
+                let (amount, text) = self.coins(amount);
+                (
+                    format!(
+                        "Withdrew {text} from {}",
+                        self.delegation(delegation_id, pool_id)
+                    ),
+                    TxDescriptionDetails::WithdrewFromDelegation {
+                        delegation_id: self.address(delegation_id),
+                        pool_id: pool_id.map(|pool_id| self.address(pool_id)),
+                        amount,
+                    },
+                )
+            }
+            TxDescriptionItem::IssuedToken { ticker } => {
+                let ticker = String::from_utf8_lossy(&ticker).into_owned();
+                (
+                    format!("Issued token {ticker}"),
+                    TxDescriptionDetails::IssuedToken { ticker },
+                )
+            }
+            TxDescriptionItem::IssuedNft { token_id } => (
+                format!("Issued NFT {}", self.token_name(token_id)),
+                TxDescriptionDetails::IssuedNft {
+                    token_id: self.address(token_id),
+                },
+            ),
+            TxDescriptionItem::MintedTokens { token_id, amount } => {
+                let (amount, text) = self.currency_amount(Currency::Token(token_id), amount);
+                (
+                    format!("Minted {text}"),
+                    TxDescriptionDetails::MintedTokens { amount },
+                )
+            }
+            TxDescriptionItem::UnmintedTokens { token_id } => (
+                format!("Unminted tokens of {}", self.token_name(token_id)),
+                TxDescriptionDetails::UnmintedTokens {
+                    token_id: self.address(token_id),
+                },
+            ),
+            TxDescriptionItem::LockedTokenSupply { token_id } => (
+                format!("Locked the supply of {}", self.token_name(token_id)),
+                TxDescriptionDetails::LockedTokenSupply {
+                    token_id: self.address(token_id),
+                },
+            ),
+            TxDescriptionItem::FrozeToken { token_id } => (
+                format!("Froze token {}", self.token_name(token_id)),
+                TxDescriptionDetails::FrozeToken {
+                    token_id: self.address(token_id),
+                },
+            ),
+            TxDescriptionItem::UnfrozeToken { token_id } => (
+                format!("Unfroze token {}", self.token_name(token_id)),
+                TxDescriptionDetails::UnfrozeToken {
+                    token_id: self.address(token_id),
+                },
+            ),
+            TxDescriptionItem::ChangedTokenAuthority {
+                token_id,
+                authority,
+            } => {
+                let authority = self.address(authority);
+                (
+                    format!(
+                        "Changed the authority of {} to {authority}",
+                        self.token_name(token_id)
+                    ),
+                    TxDescriptionDetails::ChangedTokenAuthority {
+                        token_id: self.address(token_id),
+                        authority,
+                    },
+                )
+            }
+            TxDescriptionItem::ChangedTokenMetadataUri { token_id } => (
+                format!("Changed the metadata URI of {}", self.token_name(token_id)),
+                TxDescriptionDetails::ChangedTokenMetadataUri {
+                    token_id: self.address(token_id),
+                },
+            ),
+            TxDescriptionItem::CreatedOrder {
+                ask_currency,
+                ask_amount,
+                give_currency,
+                give_amount,
+            } => {
+                let (ask, ask_text) = self.currency_amount(ask_currency, ask_amount);
+                let (give, give_text) = self.currency_amount(give_currency, give_amount);
+                (
+                    format!("Created an order giving {give_text} for {ask_text}"),
+                    TxDescriptionDetails::CreatedOrder { ask, give },
+                )
+            }
+            TxDescriptionItem::FilledOrder {
+                order_id,
+                currency,
+                amount,
+            } => {
+                let (amount, text) = self.currency_amount(currency, amount);
+                let order_id = self.address(order_id);
+                (
+                    format!("Filled order {order_id} with {text}"),
+                    TxDescriptionDetails::FilledOrder { order_id, amount },
+                )
+            }
+            TxDescriptionItem::ConcludedOrder { order_id } => {
+                let order_id = self.address(order_id);
+                (
+                    format!("Concluded order {order_id}"),
+                    TxDescriptionDetails::ConcludedOrder { order_id },
+                )
+            }
+            TxDescriptionItem::DataDeposit {} => (
+                "Deposited data".to_owned(),
+                TxDescriptionDetails::DataDeposit,
+            ),
+        };
+
+        TxDescriptionEntry { summary, details }
+    }
+}
+
+impl TransactionDescription {
+    /// Build the description; `token_infos` must contain all the [referenced_tokens]
+    pub fn new(
+        chain_config: &ChainConfig,
+        tx_id: Id<Transaction>,
+        items: Vec<TxDescriptionItem>,
+        token_infos: &BTreeMap<TokenId, RPCTokenInfo>,
+    ) -> Self {
+        let renderer = Renderer {
+            chain_config,
+            token_infos,
+        };
+        let entries = items.into_iter().map(|item| renderer.render(item)).collect();
+        Self { tx_id, entries }
+    }
+}
+
+/// A mainchain transaction of the account with a human-friendly description of what it does
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
+pub struct MainchainTxInfo {
+    pub id: Id<Transaction>,
+    pub height: BlockHeight,
+    pub timestamp: BlockTimestamp,
+    /// The user's labels of the account's addresses the transaction spends from or pays to
+    pub labels: Vec<String>,
+    /// What the transaction did, e.g. "Sent 10 ML to tmt1q..."
+    pub description: Vec<String>,
+}
+
+impl MainchainTxInfo {
+    /// Build the info; `token_infos` must contain all the [referenced_tokens]
+    pub fn new(
+        chain_config: &ChainConfig,
+        tx: TxInfo,
+        items: Vec<TxDescriptionItem>,
+        token_infos: &BTreeMap<TokenId, RPCTokenInfo>,
+    ) -> Self {
+        let renderer = Renderer {
+            chain_config,
+            token_infos,
+        };
+        let TxInfo {
+            id,
+            height,
+            timestamp,
+            labels,
+        } = tx;
+
+        Self {
+            id,
+            height,
+            timestamp,
+            labels,
+            description: items.into_iter().map(|item| renderer.render(item).summary).collect(),
+        }
+    }
+}
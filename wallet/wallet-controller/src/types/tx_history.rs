@@ -0,0 +1,239 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use common::{
+    address::RpcAddress,
+    chain::{
+        block::timestamp::BlockTimestamp,
+        tokens::{RPCTokenInfo, TokenId},
+        ChainConfig, Destination, Transaction,
+    },
+    primitives::{amount::RpcAmountOut, Amount, BlockHeight, Id},
+};
+use wallet::account::{
+    currency_grouper::Currency,
+    transaction_list::{TransactionInfo, TxDescriptionItem, TxType},
+};
+
+use super::{tx_description::Renderer, CurrencyAmount};
+
+/// A transaction of the account, in the form suitable for accounting tools
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, rpc_description::HasValueHint)]
+pub struct TransactionHistoryEntry {
+    pub tx_id: Id<Transaction>,
+    /// The kind of the transaction from the point of view of the account, e.g. "Sent"
+    pub tx_type: String,
+    /// The state of the transaction, e.g. "Confirmed" or "InMempool"
+    pub state: String,
+    pub block_height: Option<BlockHeight>,
+    pub timestamp: Option<BlockTimestamp>,
+    /// Coins and tokens received by the account from others, per currency
+    pub received: Vec<CurrencyAmount>,
+    /// Coins and tokens sent by the account to others, per currency
+    pub sent: Vec<CurrencyAmount>,
+    /// Coins and tokens received from another account of the same wallet, per currency
+    pub transferred_in: Vec<CurrencyAmount>,
+    /// Coins and tokens sent to another account of the same wallet, per currency
+    pub transferred_out: Vec<CurrencyAmount>,
+    /// The network fee in coins, if it's been paid by the account and is known;
+    /// for internal transfers it's only reported for the sending account
+    pub fee: Option<RpcAmountOut>,
+    /// The addresses the account has sent coins or tokens to
+    pub counterparties: Vec<RpcAddress<Destination>>,
+    /// The user's labels of the account's addresses the transaction spends from or pays to
+    pub labels: Vec<String>,
+    /// What the transaction did, e.g. "Sent 10 ML to tmt1q..."
+    pub description: Vec<String>,
+}
+
+impl TransactionHistoryEntry {
+    /// Build the entry; `token_infos` must contain all the tokens referenced by the description
+    /// of the transaction
+    pub fn new(
+        chain_config: &ChainConfig,
+        tx: TransactionInfo,
+        token_infos: &BTreeMap<TokenId, RPCTokenInfo>,
+    ) -> Self {
+        let renderer = Renderer {
+            chain_config,
+            token_infos,
+        };
+
+        let mut received = BTreeMap::<Currency, Amount>::new();
+        let mut sent = BTreeMap::<Currency, Amount>::new();
+        let mut counterparties = Vec::new();
+
+        // Internal transfers are kept apart, so that adding up the received and sent amounts
+        // of all the accounts of the wallet doesn't count them twice
+        let is_internal_transfer = matches!(
+            tx.tx_type,
+            TxType::TransferredIn { .. } | TxType::TransferredOut { .. }
+        );
+        let is_transferred_in = matches!(tx.tx_type, TxType::TransferredIn { .. });
+
+        for item in &tx.description {
+            let (totals, currency, amount) = match item {
+                TxDescriptionItem::Received {
+                    currency,
+                    amount,
+                    destination: _,
+                } => (&mut received, currency, amount),
+                TxDescriptionItem::Sent {
+                    currency,
+                    amount,
+                    destination,
+                } => {
+                    let destination = renderer.address(destination.clone());
+                    if !counterparties.contains(&destination) {
+                        counterparties.push(destination);
+                    }
+                    (&mut sent, currency, amount)
+                }
+                _ => continue,
+            };
+            let total = totals.entry(currency.clone()).or_insert(Amount::ZERO);
+            *total = (*total + *amount).expect("amounts of a valid transaction can't overflow");
+        }
+
+        let currency_amounts = |totals: BTreeMap<Currency, Amount>| -> Vec<CurrencyAmount> {
+            totals
+                .into_iter()
+                .map(|(currency, amount)| renderer.currency_amount(currency, amount).0)
+                .collect()
+        };
+
+        let (received, sent) = (currency_amounts(received), currency_amounts(sent));
+        let (received, sent, transferred_in, transferred_out) = if is_internal_transfer {
+            (Vec::new(), Vec::new(), received, sent)
+        } else {
+            (received, sent, Vec::new(), Vec::new())
+        };
+
+        Self {
+            tx_id: tx.txid,
+            tx_type: tx.tx_type.type_name().to_owned(),
+            state: tx.state.short_name().to_owned(),
+            block_height: tx.state.block_height(),
+            timestamp: tx.timestamp,
+            received,
+            sent,
+            transferred_in,
+            transferred_out,
+            fee: tx.fee.filter(|_| !is_transferred_in).map(|fee| renderer.coins(fee).0),
+            counterparties,
+            labels: tx.labels,
+            description: tx
+                .description
+                .into_iter()
+                .map(|item| renderer.render(item).summary)
+                .collect(),
+        }
+    }
+}
+
+const CSV_HEADER: &str = "timestamp,block_height,tx_id,type,state,currency,received,sent,\
+    transferred_in,transferred_out,fee,counterparties,labels,description";
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+fn csv_timestamp(timestamp: BlockTimestamp) -> String {
+    timestamp
+        .into_time()
+        .as_absolute_time()
+        .map_or_else(|| timestamp.to_string(), |time| time.to_rfc3339())
+}
+
+/// Format the transaction history as CSV.
+///
+/// There is a row for every currency received, sent or transferred by a transaction, or a single
+/// row if there are none; the fee and the description are only put into the first row of
+/// a transaction, so that they are not counted twice.
+pub fn transaction_history_to_csv(
+    chain_config: &ChainConfig,
+    entries: &[TransactionHistoryEntry],
+) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+
+    for entry in entries {
+        // The received, sent, transferred in and transferred out amounts per currency
+        let mut amounts = BTreeMap::<Option<&str>, [Option<String>; 4]>::new();
+        let columns = [&entry.received, &entry.sent, &entry.transferred_in, &entry.transferred_out];
+        for (column, column_amounts) in columns.into_iter().enumerate() {
+            for amount in column_amounts {
+                amounts.entry(amount.token_id.as_ref().map(RpcAddress::as_str)).or_default()
+                    [column] = Some(amount.amount.decimal().to_string());
+            }
+        }
+        if amounts.is_empty() {
+            amounts.insert(None, Default::default());
+        }
+
+        for (row_index, (token_id, row_amounts)) in amounts.into_iter().enumerate() {
+            let is_first_row = row_index == 0;
+            let has_amounts = row_amounts.iter().any(Option::is_some);
+            let [received, sent, transferred_in, transferred_out] = row_amounts;
+
+            let currency = match token_id {
+                Some(token_id) => token_id,
+                None if has_amounts => chain_config.coin_ticker(),
+                None => "",
+            };
+            let fee =
+                entry.fee.as_ref().filter(|_| is_first_row).map(|fee| fee.decimal().to_string());
+            let counterparties = entry
+                .counterparties
+                .iter()
+                .map(RpcAddress::as_str)
+                .collect::<Vec<_>>()
+                .join(" ");
+            let labels = entry.labels.join("; ");
+            let description = if is_first_row {
+                entry.description.join("; ")
+            } else {
+                String::new()
+            };
+
+            let row = [
+                entry.timestamp.map(csv_timestamp).unwrap_or_default(),
+                entry.block_height.map(|height| height.to_string()).unwrap_or_default(),
+                format!("{:x}", entry.tx_id),
+                entry.tx_type.clone(),
+                entry.state.clone(),
+                currency.to_owned(),
+                received.unwrap_or_default(),
+                sent.unwrap_or_default(),
+                transferred_in.unwrap_or_default(),
+                transferred_out.unwrap_or_default(),
+                fee.unwrap_or_default(),
+                counterparties,
+                labels,
+                description,
+            ];
+            csv.push_str(&row.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(","));
+            csv.push('\n');
+        }
+    }
+
+    csv
+}
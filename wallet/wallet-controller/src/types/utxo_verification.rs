@@ -0,0 +1,53 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::chain::block::timestamp::BlockTimestamp;
+use common::{chain::UtxoOutPoint, primitives::BlockHeight};
+use crypto::key::hdkd::u31::U31;
+
+use super::BlockInfo;
+
+/// How the wallet and the node disagree about an output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UtxoDiscrepancyKind {
+    /// The wallet considers the output unspent, but it's not in the node's UTXO set
+    SpentButTracked,
+    /// The output is in the node's UTXO set, but the wallet considers it spent
+    Missing,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UtxoDiscrepancy {
+    pub account: U31,
+    pub outpoint: UtxoOutPoint,
+    pub kind: UtxoDiscrepancyKind,
+    /// The height from which the blocks have to be scanned again to fix the discrepancy
+    pub rescan_from: BlockHeight,
+}
+
+/// The outcome of checking the outputs tracked by the wallet against the node's UTXO set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UtxoVerificationReport {
+    /// The height of the best block at which the check was done
+    pub checked_at: BlockHeight,
+    /// The number of outputs that are tracked by the wallet
+    pub tracked_count: usize,
+    /// The number of outputs that were checked, less than the tracked ones if sampled
+    pub checked_count: usize,
+    pub discrepancies: Vec<UtxoDiscrepancy>,
+    /// The best block the wallet was rewound to so the affected blocks are scanned again,
+    /// if a repair was requested and needed
+    pub rewound_to: Option<BlockInfo>,
+}
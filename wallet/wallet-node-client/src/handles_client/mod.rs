@@ -16,7 +16,10 @@
 use std::{num::NonZeroUsize, time::Duration};
 
 use blockprod::{BlockProductionError, BlockProductionHandle, TimestampSearchData};
-use chainstate::{BlockSource, ChainInfo, ChainstateError, ChainstateHandle};
+use chainstate::{
+    rpc::types::block_stats::RpcBlockStats, BlockRewardDistribution, BlockSource, ChainInfo,
+    ChainstateError, ChainstateHandle,
+};
 use common::{
     chain::{
         tokens::{RPCTokenInfo, TokenId},
@@ -27,11 +30,13 @@ use common::{
 use consensus::GenerateBlockInputData;
 use crypto::ephemeral_e2e::EndToEndPublicKey;
 use mempool::{
-    tx_accumulator::PackingStrategy, tx_options::TxOptionsOverrides, FeeRate, MempoolHandle,
+    tx_accumulator::{PackingStrategy, TxAccumulatorPolicy},
+    tx_options::TxOptionsOverrides,
+    FeeRate, MempoolHandle,
 };
 use p2p::{
     error::P2pError,
-    interface::types::ConnectedPeer,
+    interface::types::{ConnectedPeer, SyncProgress},
     types::{bannable_address::BannableAddress, peer_id::PeerId, socket_address::SocketAddress},
     P2pHandle,
 };
@@ -106,6 +111,18 @@ impl NodeInterface for WalletHandlesClient {
         WalletType::Hot
     }
 
+    fn active_node_address(&self) -> Option<String> {
+        None
+    }
+
+    fn configured_node_addresses(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn switch_to_node(&self, _index: usize) -> Option<String> {
+        None
+    }
+
     async fn chainstate_info(&self) -> Result<ChainInfo, Self::Error> {
         let result = self.chainstate.call(move |this| this.info()).await??;
         Ok(result)
@@ -116,6 +133,27 @@ impl NodeInterface for WalletHandlesClient {
         Ok(result)
     }
 
+    async fn chainstate_recent_block_stats(
+        &self,
+        count: usize,
+    ) -> Result<Vec<RpcBlockStats>, Self::Error> {
+        let result = self
+            .chainstate
+            .call(move |this| {
+                let max_txs_size = this.get_chain_config().max_block_size_from_std_scripts() as u64;
+                this.get_recent_block_stats(count).map(|stats| {
+                    stats
+                        .iter()
+                        .map(|(block_index, stats)| {
+                            RpcBlockStats::new(block_index, stats, max_txs_size)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .await??;
+        Ok(result)
+    }
+
     async fn get_block(&self, block_id: Id<Block>) -> Result<Option<Block>, Self::Error> {
         let result = self.chainstate.call(move |this| this.get_block(block_id)).await??;
         Ok(result)
@@ -207,6 +245,17 @@ impl NodeInterface for WalletHandlesClient {
         Ok(result)
     }
 
+    async fn get_block_reward_distribution(
+        &self,
+        block_id: Id<Block>,
+    ) -> Result<Option<BlockRewardDistribution>, Self::Error> {
+        let result = self
+            .chainstate
+            .call(move |this| this.get_block_reward_distribution(&block_id))
+            .await??;
+        Ok(result)
+    }
+
     async fn get_token_info(&self, token_id: TokenId) -> Result<Option<RPCTokenInfo>, Self::Error> {
         let result = self
             .chainstate
@@ -238,6 +287,7 @@ impl NodeInterface for WalletHandlesClient {
                     transactions,
                     transaction_ids,
                     packing_strategy,
+                    TxAccumulatorPolicy::default(),
                 )
             })
             .await??;
@@ -279,7 +329,13 @@ impl NodeInterface for WalletHandlesClient {
         let block = self
             .block_prod
             .call_async_mut(move |this| {
-                this.generate_block(input_data, transactions, transaction_ids, packing_strategy)
+                this.generate_block(
+                    input_data,
+                    transactions,
+                    transaction_ids,
+                    packing_strategy,
+                    TxAccumulatorPolicy::default(),
+                )
             })
             .await??;
 
@@ -305,6 +361,24 @@ impl NodeInterface for WalletHandlesClient {
         Ok(output)
     }
 
+    async fn get_utxos(
+        &self,
+        outpoints: Vec<common::chain::UtxoOutPoint>,
+    ) -> Result<Vec<Option<common::chain::TxOutput>>, Self::Error> {
+        let outputs = self
+            .chainstate
+            .call_mut(move |this| {
+                outpoints
+                    .iter()
+                    .map(|outpoint| {
+                        this.utxo(outpoint).map(|utxo| utxo.map(|utxo| utxo.take_output()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .await??;
+        Ok(outputs)
+    }
+
     async fn submit_transaction(
         &self,
         tx: SignedTransaction,
@@ -366,6 +440,14 @@ impl NodeInterface for WalletHandlesClient {
         let peers = self.p2p.call_async(move |this| this.get_connected_peers()).await??;
         Ok(peers)
     }
+    async fn p2p_get_sync_progress(&self) -> Result<SyncProgress, Self::Error> {
+        let progress = self.p2p.call_async(move |this| this.get_sync_progress()).await??;
+        Ok(progress)
+    }
+    async fn p2p_get_node_sync_progress(&self, _index: usize) -> Result<SyncProgress, Self::Error> {
+        // There is only the node the handles belong to
+        self.p2p_get_sync_progress().await
+    }
     async fn p2p_get_reserved_nodes(&self) -> Result<Vec<SocketAddress>, Self::Error> {
         let peers = self.p2p.call_async(move |this| this.get_reserved_nodes()).await??;
         Ok(peers)
@@ -395,4 +477,17 @@ impl NodeInterface for WalletHandlesClient {
         let res = self.mempool.call(move |this| this.get_fee_rate_points(NUM_POINTS)).await??;
         Ok(res)
     }
+
+    async fn mempool_get_min_fee_rate(&self) -> Result<FeeRate, Self::Error> {
+        let res = self.mempool.call(move |this| this.get_min_fee_rate()).await?;
+        Ok(res)
+    }
+
+    async fn mempool_estimate_fee_rate(
+        &self,
+        target_blocks: usize,
+    ) -> Result<Option<FeeRate>, Self::Error> {
+        let res = self.mempool.call(move |this| this.estimate_fee_rate(target_blocks)).await?;
+        Ok(res)
+    }
 }
@@ -37,6 +37,21 @@ pub async fn make_rpc_client(
     rpc_client::NodeRpcClient::new(chain_config, remote_socket_address, rpc_auth).await
 }
 
+pub async fn make_rpc_client_with_fallbacks(
+    chain_config: Arc<ChainConfig>,
+    remote_socket_address: String,
+    fallback_socket_addresses: Vec<String>,
+    rpc_auth: RpcAuthData,
+) -> Result<rpc_client::NodeRpcClient, NodeRpcError> {
+    rpc_client::NodeRpcClient::new_with_fallbacks(
+        chain_config,
+        remote_socket_address,
+        fallback_socket_addresses,
+        rpc_auth,
+    )
+    .await
+}
+
 pub fn make_cold_wallet_rpc_client(chain_config: Arc<ChainConfig>) -> rpc_client::ColdWalletClient {
     rpc_client::ColdWalletClient::new(chain_config)
 }
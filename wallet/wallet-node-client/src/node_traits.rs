@@ -15,7 +15,7 @@
 
 use std::{num::NonZeroUsize, time::Duration};
 
-use chainstate::ChainInfo;
+use chainstate::{rpc::types::block_stats::RpcBlockStats, BlockRewardDistribution, ChainInfo};
 use common::{
     chain::{
         tokens::{RPCTokenInfo, TokenId},
@@ -29,7 +29,10 @@ use consensus::GenerateBlockInputData;
 use crypto::ephemeral_e2e::EndToEndPublicKey;
 use mempool::{tx_accumulator::PackingStrategy, tx_options::TxOptionsOverrides, FeeRate};
 use p2p::types::{bannable_address::BannableAddress, socket_address::SocketAddress};
-pub use p2p::{interface::types::ConnectedPeer, types::peer_id::PeerId};
+pub use p2p::{
+    interface::types::{ConnectedPeer, SyncProgress},
+    types::peer_id::PeerId,
+};
 use utils_networking::IpOrSocketAddress;
 use wallet_types::wallet_type::WalletType;
 
@@ -39,8 +42,25 @@ pub trait NodeInterface {
 
     fn is_cold_wallet_node(&self) -> WalletType;
 
+    /// The RPC address of the node in use, if the client talks to the node over RPC
+    fn active_node_address(&self) -> Option<String>;
+
+    /// The RPC addresses of all the configured nodes, ordered by preference.
+    /// Empty if the client doesn't talk to the nodes over RPC.
+    fn configured_node_addresses(&self) -> Vec<String>;
+
+    /// Switch to the node with the given index in `configured_node_addresses`.
+    /// Returns the address of the new node, or None if there is no such node.
+    fn switch_to_node(&self, index: usize) -> Option<String>;
+
     async fn chainstate_info(&self) -> Result<ChainInfo, Self::Error>;
     async fn get_best_block_id(&self) -> Result<Id<GenBlock>, Self::Error>;
+    /// The fee and fullness statistics of up to `count` most recent mainchain blocks,
+    /// starting from the tip
+    async fn chainstate_recent_block_stats(
+        &self,
+        count: usize,
+    ) -> Result<Vec<RpcBlockStats>, Self::Error>;
     async fn get_block(&self, block_id: Id<Block>) -> Result<Option<Block>, Self::Error>;
     async fn get_mainchain_blocks(
         &self,
@@ -70,6 +90,12 @@ pub trait NodeInterface {
         pool_id: PoolId,
         delegation_id: DelegationId,
     ) -> Result<Option<Amount>, Self::Error>;
+    /// How the reward of a PoS block was credited to the pool that produced it
+    /// and to its delegations, if the node still has this information
+    async fn get_block_reward_distribution(
+        &self,
+        block_id: Id<Block>,
+    ) -> Result<Option<BlockRewardDistribution>, Self::Error>;
     async fn get_token_info(&self, token_id: TokenId) -> Result<Option<RPCTokenInfo>, Self::Error>;
     async fn blockprod_e2e_public_key(&self) -> Result<EndToEndPublicKey, Self::Error>;
     async fn generate_block(
@@ -118,6 +144,10 @@ pub trait NodeInterface {
     async fn p2p_list_discouraged(&self) -> Result<Vec<(BannableAddress, Time)>, Self::Error>;
     async fn p2p_get_peer_count(&self) -> Result<usize, Self::Error>;
     async fn p2p_get_connected_peers(&self) -> Result<Vec<ConnectedPeer>, Self::Error>;
+    async fn p2p_get_sync_progress(&self) -> Result<SyncProgress, Self::Error>;
+    /// Same as `p2p_get_sync_progress`, but asks the node with the given index
+    /// in `configured_node_addresses` instead of the node in use
+    async fn p2p_get_node_sync_progress(&self, index: usize) -> Result<SyncProgress, Self::Error>;
     async fn p2p_get_reserved_nodes(&self) -> Result<Vec<SocketAddress>, Self::Error>;
     async fn p2p_add_reserved_node(&self, address: IpOrSocketAddress) -> Result<(), Self::Error>;
     async fn p2p_remove_reserved_node(&self, address: IpOrSocketAddress)
@@ -125,6 +155,16 @@ pub trait NodeInterface {
 
     async fn mempool_get_fee_rate(&self, in_top_x_mb: usize) -> Result<FeeRate, Self::Error>;
     async fn mempool_get_fee_rate_points(&self) -> Result<Vec<(usize, FeeRate)>, Self::Error>;
+    async fn mempool_get_min_fee_rate(&self) -> Result<FeeRate, Self::Error>;
+    async fn mempool_estimate_fee_rate(
+        &self,
+        target_blocks: usize,
+    ) -> Result<Option<FeeRate>, Self::Error>;
 
     async fn get_utxo(&self, outpoint: UtxoOutPoint) -> Result<Option<TxOutput>, Self::Error>;
+    /// Same as `get_utxo`, but for many outpoints at once; the outputs are in the same order
+    async fn get_utxos(
+        &self,
+        outpoints: Vec<UtxoOutPoint>,
+    ) -> Result<Vec<Option<TxOutput>>, Self::Error>;
 }
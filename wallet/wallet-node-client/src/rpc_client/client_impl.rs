@@ -16,7 +16,10 @@
 use std::{num::NonZeroUsize, time::Duration};
 
 use blockprod::{rpc::BlockProductionRpcClient, TimestampSearchData};
-use chainstate::{rpc::ChainstateRpcClient, ChainInfo};
+use chainstate::{
+    rpc::{types::block_stats::RpcBlockStats, ChainstateRpcClient},
+    BlockRewardDistribution, ChainInfo,
+};
 use common::{
     address::Address,
     chain::{
@@ -32,7 +35,7 @@ use mempool::{
     rpc::MempoolRpcClient, tx_accumulator::PackingStrategy, tx_options::TxOptionsOverrides, FeeRate,
 };
 use p2p::{
-    interface::types::ConnectedPeer,
+    interface::types::{ConnectedPeer, SyncProgress},
     rpc::P2pRpcClient,
     types::{bannable_address::BannableAddress, peer_id::PeerId, socket_address::SocketAddress},
 };
@@ -52,14 +55,38 @@ impl NodeInterface for NodeRpcClient {
         WalletType::Hot
     }
 
+    fn active_node_address(&self) -> Option<String> {
+        Some(self.active_address().to_owned())
+    }
+
+    fn configured_node_addresses(&self) -> Vec<String> {
+        self.endpoints.iter().map(|endpoint| endpoint.address.clone()).collect()
+    }
+
+    fn switch_to_node(&self, index: usize) -> Option<String> {
+        self.switch_to_endpoint(index).map(str::to_owned)
+    }
+
     async fn chainstate_info(&self) -> Result<ChainInfo, Self::Error> {
-        ChainstateRpcClient::info(&self.http_client)
+        ChainstateRpcClient::info(self.http_client())
             .await
             .map_err(NodeRpcError::ResponseError)
     }
 
+    async fn chainstate_recent_block_stats(
+        &self,
+        count: usize,
+    ) -> Result<Vec<RpcBlockStats>, Self::Error> {
+        match ChainstateRpcClient::get_recent_block_stats(self.http_client(), count).await {
+            Ok(stats) => Ok(stats),
+            // Older nodes don't have this method, which is the same as having no statistics
+            Err(err) if rpc::is_method_not_found(&err) => Ok(Vec::new()),
+            Err(err) => Err(NodeRpcError::ResponseError(err)),
+        }
+    }
+
     async fn get_block(&self, block_id: Id<Block>) -> Result<Option<Block>, Self::Error> {
-        ChainstateRpcClient::get_block(&self.http_client, block_id)
+        ChainstateRpcClient::get_block(self.http_client(), block_id)
             .await
             .map_err(NodeRpcError::ResponseError)
             .map(|block_opt| block_opt.map(HexEncoded::take))
@@ -70,7 +97,7 @@ impl NodeInterface for NodeRpcClient {
         from: BlockHeight,
         max_count: usize,
     ) -> Result<Vec<Block>, Self::Error> {
-        ChainstateRpcClient::get_mainchain_blocks(&self.http_client, from, max_count)
+        ChainstateRpcClient::get_mainchain_blocks(self.http_client(), from, max_count)
             .await
             .map_err(NodeRpcError::ResponseError)
             .map(|blocks| blocks.into_iter().map(HexEncoded::take).collect())
@@ -83,7 +110,7 @@ impl NodeInterface for NodeRpcClient {
         step: NonZeroUsize,
     ) -> Result<Vec<(BlockHeight, Id<GenBlock>)>, Self::Error> {
         ChainstateRpcClient::get_block_ids_as_checkpoints(
-            &self.http_client,
+            self.http_client(),
             start_height,
             end_height,
             step,
@@ -93,13 +120,13 @@ impl NodeInterface for NodeRpcClient {
     }
 
     async fn get_best_block_id(&self) -> Result<Id<GenBlock>, Self::Error> {
-        ChainstateRpcClient::best_block_id(&self.http_client)
+        ChainstateRpcClient::best_block_id(self.http_client())
             .await
             .map_err(NodeRpcError::ResponseError)
     }
 
     async fn get_best_block_height(&self) -> Result<common::primitives::BlockHeight, Self::Error> {
-        ChainstateRpcClient::best_block_height(&self.http_client)
+        ChainstateRpcClient::best_block_height(self.http_client())
             .await
             .map_err(NodeRpcError::ResponseError)
     }
@@ -108,7 +135,7 @@ impl NodeInterface for NodeRpcClient {
         &self,
         height: BlockHeight,
     ) -> Result<Option<Id<GenBlock>>, Self::Error> {
-        ChainstateRpcClient::block_id_at_height(&self.http_client, height)
+        ChainstateRpcClient::block_id_at_height(self.http_client(), height)
             .await
             .map_err(NodeRpcError::ResponseError)
     }
@@ -119,7 +146,7 @@ impl NodeInterface for NodeRpcClient {
         second_block: Id<GenBlock>,
     ) -> Result<Option<(Id<GenBlock>, BlockHeight)>, Self::Error> {
         ChainstateRpcClient::last_common_ancestor_by_id(
-            &self.http_client,
+            self.http_client(),
             first_block,
             second_block,
         )
@@ -129,14 +156,14 @@ impl NodeInterface for NodeRpcClient {
 
     async fn get_stake_pool_balance(&self, pool_id: PoolId) -> Result<Option<Amount>, Self::Error> {
         let pool_address = Address::new(&self.chain_config, pool_id)?;
-        ChainstateRpcClient::stake_pool_balance(&self.http_client, pool_address.into_string())
+        ChainstateRpcClient::stake_pool_balance(self.http_client(), pool_address.into_string())
             .await
             .map_err(NodeRpcError::ResponseError)
     }
 
     async fn get_staker_balance(&self, pool_id: PoolId) -> Result<Option<Amount>, Self::Error> {
         let pool_address = Address::new(&self.chain_config, pool_id)?;
-        ChainstateRpcClient::staker_balance(&self.http_client, pool_address.into_string())
+        ChainstateRpcClient::staker_balance(self.http_client(), pool_address.into_string())
             .await
             .map_err(NodeRpcError::ResponseError)
     }
@@ -148,20 +175,50 @@ impl NodeInterface for NodeRpcClient {
     ) -> Result<Option<Amount>, Self::Error> {
         let pool_address = Address::new(&self.chain_config, pool_id)?.into_string();
         let delegation_address = Address::new(&self.chain_config, delegation_id)?.into_string();
-        ChainstateRpcClient::delegation_share(&self.http_client, pool_address, delegation_address)
+        ChainstateRpcClient::delegation_share(self.http_client(), pool_address, delegation_address)
             .await
             .map_err(NodeRpcError::ResponseError)
     }
 
+    async fn get_block_reward_distribution(
+        &self,
+        block_id: Id<Block>,
+    ) -> Result<Option<BlockRewardDistribution>, Self::Error> {
+        let distribution =
+            ChainstateRpcClient::block_reward_distribution(self.http_client(), block_id)
+                .await
+                .map_err(NodeRpcError::ResponseError)?;
+        let distribution = match distribution {
+            Some(distribution) => distribution,
+            None => return Ok(None),
+        };
+
+        let delegation_rewards = distribution
+            .delegation_rewards
+            .iter()
+            .map(|delegation_reward| {
+                let delegation_id =
+                    delegation_reward.delegation_id.decode_object(&self.chain_config)?;
+                Ok((delegation_id, delegation_reward.reward))
+            })
+            .collect::<Result<_, NodeRpcError>>()?;
+
+        Ok(Some(BlockRewardDistribution::new(
+            distribution.pool_id.decode_object(&self.chain_config)?,
+            distribution.staker_reward,
+            delegation_rewards,
+        )))
+    }
+
     async fn get_token_info(&self, token_id: TokenId) -> Result<Option<RPCTokenInfo>, Self::Error> {
         let token_id = Address::new(&self.chain_config, token_id)?.into_string();
-        ChainstateRpcClient::token_info(&self.http_client, token_id)
+        ChainstateRpcClient::token_info(self.http_client(), token_id)
             .await
             .map_err(NodeRpcError::ResponseError)
     }
 
     async fn blockprod_e2e_public_key(&self) -> Result<EndToEndPublicKey, Self::Error> {
-        BlockProductionRpcClient::e2e_public_key(&self.http_client)
+        BlockProductionRpcClient::e2e_public_key(self.http_client())
             .await
             .map(HexEncoded::take)
             .map_err(NodeRpcError::ResponseError)
@@ -177,12 +234,13 @@ impl NodeInterface for NodeRpcClient {
     ) -> Result<Block, Self::Error> {
         let transactions = transactions.into_iter().map(HexEncoded::new).collect::<Vec<_>>();
         BlockProductionRpcClient::generate_block_e2e(
-            &self.http_client,
+            self.http_client(),
             encrypted_input_data,
             public_key.into(),
             transactions,
             transaction_ids,
             packing_strategy,
+            None,
         )
         .await
         .map(HexEncoded::take)
@@ -198,7 +256,7 @@ impl NodeInterface for NodeRpcClient {
         all_timestamps_between_blocks: bool,
     ) -> Result<TimestampSearchData, Self::Error> {
         BlockProductionRpcClient::collect_timestamp_search_data(
-            &self.http_client,
+            self.http_client(),
             pool_id,
             min_height,
             max_height,
@@ -219,11 +277,12 @@ impl NodeInterface for NodeRpcClient {
     ) -> Result<Block, Self::Error> {
         let transactions = transactions.into_iter().map(HexEncoded::new).collect::<Vec<_>>();
         BlockProductionRpcClient::generate_block(
-            &self.http_client,
+            self.http_client(),
             input_data.into(),
             transactions,
             transaction_ids,
             packing_strategy,
+            None,
         )
         .await
         .map(HexEncoded::take)
@@ -231,7 +290,7 @@ impl NodeInterface for NodeRpcClient {
     }
 
     async fn submit_block(&self, block: Block) -> Result<(), Self::Error> {
-        ChainstateRpcClient::submit_block(&self.http_client, block.into())
+        ChainstateRpcClient::submit_block(self.http_client(), block.into())
             .await
             .map_err(NodeRpcError::ResponseError)
     }
@@ -241,41 +300,41 @@ impl NodeInterface for NodeRpcClient {
         tx: SignedTransaction,
         options: TxOptionsOverrides,
     ) -> Result<(), Self::Error> {
-        let status = P2pRpcClient::submit_transaction(&self.http_client, tx.into(), options)
+        let status = P2pRpcClient::submit_transaction(self.http_client(), tx.into(), options)
             .await
             .map_err(NodeRpcError::ResponseError)?;
         Ok(status)
     }
 
     async fn node_shutdown(&self) -> Result<(), Self::Error> {
-        node_lib::rpc::NodeRpcClient::shutdown(&self.http_client)
+        node_lib::rpc::NodeRpcClient::shutdown(self.http_client())
             .await
             .map_err(NodeRpcError::ResponseError)
     }
     async fn node_enable_networking(&self, enable: bool) -> Result<(), Self::Error> {
-        P2pRpcClient::enable_networking(&self.http_client, enable)
+        P2pRpcClient::enable_networking(self.http_client(), enable)
             .await
             .map_err(NodeRpcError::ResponseError)
     }
     async fn node_version(&self) -> Result<String, Self::Error> {
-        node_lib::rpc::NodeRpcClient::version(&self.http_client)
+        node_lib::rpc::NodeRpcClient::version(self.http_client())
             .await
             .map_err(NodeRpcError::ResponseError)
     }
 
     async fn p2p_connect(&self, address: IpOrSocketAddress) -> Result<(), Self::Error> {
-        P2pRpcClient::connect(&self.http_client, address)
+        P2pRpcClient::connect(self.http_client(), address)
             .await
             .map_err(NodeRpcError::ResponseError)
     }
     async fn p2p_disconnect(&self, peer_id: PeerId) -> Result<(), Self::Error> {
-        P2pRpcClient::disconnect(&self.http_client, peer_id)
+        P2pRpcClient::disconnect(self.http_client(), peer_id)
             .await
             .map_err(NodeRpcError::ResponseError)
     }
 
     async fn p2p_list_banned(&self) -> Result<Vec<(BannableAddress, Time)>, Self::Error> {
-        P2pRpcClient::list_banned(&self.http_client)
+        P2pRpcClient::list_banned(self.http_client())
             .await
             .map_err(NodeRpcError::ResponseError)
     }
@@ -284,40 +343,53 @@ impl NodeInterface for NodeRpcClient {
         address: BannableAddress,
         duration: Duration,
     ) -> Result<(), Self::Error> {
-        P2pRpcClient::ban(&self.http_client, address, duration)
+        P2pRpcClient::ban(self.http_client(), address, duration)
             .await
             .map_err(NodeRpcError::ResponseError)
     }
     async fn p2p_unban(&self, address: BannableAddress) -> Result<(), Self::Error> {
-        P2pRpcClient::unban(&self.http_client, address)
+        P2pRpcClient::unban(self.http_client(), address)
             .await
             .map_err(NodeRpcError::ResponseError)
     }
 
     async fn p2p_list_discouraged(&self) -> Result<Vec<(BannableAddress, Time)>, Self::Error> {
-        P2pRpcClient::list_discouraged(&self.http_client)
+        P2pRpcClient::list_discouraged(self.http_client())
             .await
             .map_err(NodeRpcError::ResponseError)
     }
 
     async fn p2p_get_peer_count(&self) -> Result<usize, Self::Error> {
-        P2pRpcClient::get_peer_count(&self.http_client)
+        P2pRpcClient::get_peer_count(self.http_client())
             .await
             .map_err(NodeRpcError::ResponseError)
     }
     async fn p2p_get_connected_peers(&self) -> Result<Vec<ConnectedPeer>, Self::Error> {
-        P2pRpcClient::get_connected_peers(&self.http_client)
+        P2pRpcClient::get_connected_peers(self.http_client())
+            .await
+            .map_err(NodeRpcError::ResponseError)
+    }
+
+    async fn p2p_get_sync_progress(&self) -> Result<SyncProgress, Self::Error> {
+        P2pRpcClient::get_sync_progress(self.http_client())
+            .await
+            .map_err(NodeRpcError::ResponseError)
+    }
+
+    async fn p2p_get_node_sync_progress(&self, index: usize) -> Result<SyncProgress, Self::Error> {
+        let endpoint = self.endpoints.get(index).ok_or(NodeRpcError::UnknownNode(index))?;
+        P2pRpcClient::get_sync_progress(&endpoint.http_client)
             .await
             .map_err(NodeRpcError::ResponseError)
     }
 
     async fn p2p_get_reserved_nodes(&self) -> Result<Vec<SocketAddress>, Self::Error> {
-        P2pRpcClient::get_reserved_nodes(&self.http_client)
+        P2pRpcClient::get_reserved_nodes(self.http_client())
             .await
             .map_err(NodeRpcError::ResponseError)
     }
     async fn p2p_add_reserved_node(&self, address: IpOrSocketAddress) -> Result<(), Self::Error> {
-        P2pRpcClient::add_reserved_node(&self.http_client, address)
+        P2pRpcClient::add_reserved_node(self.http_client(), address)
             .await
             .map_err(NodeRpcError::ResponseError)
     }
@@ -325,26 +397,67 @@ impl NodeInterface for NodeRpcClient {
         &self,
         address: IpOrSocketAddress,
     ) -> Result<(), Self::Error> {
-        P2pRpcClient::remove_reserved_node(&self.http_client, address)
+        P2pRpcClient::remove_reserved_node(self.http_client(), address)
             .await
             .map_err(NodeRpcError::ResponseError)
     }
 
     async fn mempool_get_fee_rate(&self, in_top_x_mb: usize) -> Result<FeeRate, Self::Error> {
-        MempoolRpcClient::get_fee_rate(&self.http_client, in_top_x_mb)
+        MempoolRpcClient::get_fee_rate(self.http_client(), in_top_x_mb)
             .await
             .map_err(NodeRpcError::ResponseError)
     }
 
     async fn mempool_get_fee_rate_points(&self) -> Result<Vec<(usize, FeeRate)>, Self::Error> {
-        MempoolRpcClient::get_fee_rate_points(&self.http_client)
+        MempoolRpcClient::get_fee_rate_points(self.http_client())
             .await
             .map_err(NodeRpcError::ResponseError)
     }
 
+    async fn mempool_get_min_fee_rate(&self) -> Result<FeeRate, Self::Error> {
+        match MempoolRpcClient::get_min_fee_rate(self.http_client()).await {
+            Ok(fee_rate) => Ok(fee_rate),
+            // Older nodes don't have this method; a zero rate makes the wallet rely
+            // on the other fee rates reported by the node, as it did before
+            Err(err) if rpc::is_method_not_found(&err) => Ok(FeeRate::from_atoms_per_kb(0)),
+            Err(err) => Err(NodeRpcError::ResponseError(err)),
+        }
+    }
+
+    async fn mempool_estimate_fee_rate(
+        &self,
+        target_blocks: usize,
+    ) -> Result<Option<FeeRate>, Self::Error> {
+        match MempoolRpcClient::estimate_fee_rate(self.http_client(), target_blocks).await {
+            Ok(fee_rate) => Ok(fee_rate),
+            // Older nodes don't have this method, which is the same as having no estimate
+            Err(err) if rpc::is_method_not_found(&err) => Ok(None),
+            Err(err) => Err(NodeRpcError::ResponseError(err)),
+        }
+    }
+
     async fn get_utxo(&self, outpoint: UtxoOutPoint) -> Result<Option<TxOutput>, Self::Error> {
-        ChainstateRpcClient::get_utxo(&self.http_client, outpoint.into())
+        ChainstateRpcClient::get_utxo(self.http_client(), outpoint.into())
             .await
             .map_err(NodeRpcError::ResponseError)
     }
+
+    async fn get_utxos(
+        &self,
+        outpoints: Vec<UtxoOutPoint>,
+    ) -> Result<Vec<Option<TxOutput>>, Self::Error> {
+        let rpc_outpoints = outpoints.iter().cloned().map(Into::into).collect();
+        match ChainstateRpcClient::get_utxos(self.http_client(), rpc_outpoints).await {
+            Ok(outputs) => Ok(outputs),
+            // Older nodes don't have this method, so look the outpoints up one by one
+            Err(err) if rpc::is_method_not_found(&err) => {
+                let mut outputs = Vec::with_capacity(outpoints.len());
+                for outpoint in outpoints {
+                    outputs.push(self.get_utxo(outpoint).await?);
+                }
+                Ok(outputs)
+            }
+            Err(err) => Err(NodeRpcError::ResponseError(err)),
+        }
+    }
 }
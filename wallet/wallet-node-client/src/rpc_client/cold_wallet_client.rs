@@ -16,7 +16,7 @@
 use std::{num::NonZeroUsize, time::Duration};
 
 use blockprod::TimestampSearchData;
-use chainstate::ChainInfo;
+use chainstate::{rpc::types::block_stats::RpcBlockStats, BlockRewardDistribution, ChainInfo};
 use common::{
     chain::{
         tokens::{RPCTokenInfo, TokenId},
@@ -28,7 +28,7 @@ use consensus::GenerateBlockInputData;
 use crypto::ephemeral_e2e::EndToEndPublicKey;
 use mempool::{tx_accumulator::PackingStrategy, tx_options::TxOptionsOverrides, FeeRate};
 use p2p::{
-    interface::types::ConnectedPeer,
+    interface::types::{ConnectedPeer, SyncProgress},
     types::{bannable_address::BannableAddress, socket_address::SocketAddress, PeerId},
 };
 use utils_networking::IpOrSocketAddress;
@@ -52,6 +52,18 @@ impl NodeInterface for ColdWalletClient {
         WalletType::Cold
     }
 
+    fn active_node_address(&self) -> Option<String> {
+        None
+    }
+
+    fn configured_node_addresses(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn switch_to_node(&self, _index: usize) -> Option<String> {
+        None
+    }
+
     async fn chainstate_info(&self) -> Result<ChainInfo, Self::Error> {
         let genesis = self.chain_config.genesis_block();
         Ok(ChainInfo {
@@ -60,9 +72,18 @@ impl NodeInterface for ColdWalletClient {
             best_block_timestamp: genesis.timestamp(),
             median_time: genesis.timestamp(),
             is_initial_block_download: false,
+            best_header_id: self.chain_config.genesis_block_id(),
+            best_header_height: BlockHeight::zero(),
         })
     }
 
+    async fn chainstate_recent_block_stats(
+        &self,
+        _count: usize,
+    ) -> Result<Vec<RpcBlockStats>, Self::Error> {
+        Err(ColdWalletRpcError::NotAvailable)
+    }
+
     async fn get_block(&self, _block_id: Id<Block>) -> Result<Option<Block>, Self::Error> {
         Err(ColdWalletRpcError::NotAvailable)
     }
@@ -126,6 +147,13 @@ impl NodeInterface for ColdWalletClient {
         Err(ColdWalletRpcError::NotAvailable)
     }
 
+    async fn get_block_reward_distribution(
+        &self,
+        _block_id: Id<Block>,
+    ) -> Result<Option<BlockRewardDistribution>, Self::Error> {
+        Err(ColdWalletRpcError::NotAvailable)
+    }
+
     async fn get_token_info(
         &self,
         _token_id: TokenId,
@@ -229,6 +257,14 @@ impl NodeInterface for ColdWalletClient {
         Err(ColdWalletRpcError::NotAvailable)
     }
 
+    async fn p2p_get_sync_progress(&self) -> Result<SyncProgress, Self::Error> {
+        Err(ColdWalletRpcError::NotAvailable)
+    }
+
+    async fn p2p_get_node_sync_progress(&self, _index: usize) -> Result<SyncProgress, Self::Error> {
+        Err(ColdWalletRpcError::NotAvailable)
+    }
+
     async fn p2p_get_reserved_nodes(&self) -> Result<Vec<SocketAddress>, Self::Error> {
         Err(ColdWalletRpcError::NotAvailable)
     }
@@ -252,10 +288,28 @@ impl NodeInterface for ColdWalletClient {
         Err(ColdWalletRpcError::NotAvailable)
     }
 
+    async fn mempool_get_min_fee_rate(&self) -> Result<FeeRate, Self::Error> {
+        Err(ColdWalletRpcError::NotAvailable)
+    }
+
+    async fn mempool_estimate_fee_rate(
+        &self,
+        _target_blocks: usize,
+    ) -> Result<Option<FeeRate>, Self::Error> {
+        Err(ColdWalletRpcError::NotAvailable)
+    }
+
     async fn get_utxo(
         &self,
         _outpoint: common::chain::UtxoOutPoint,
     ) -> Result<Option<common::chain::TxOutput>, Self::Error> {
         Err(ColdWalletRpcError::NotAvailable)
     }
+
+    async fn get_utxos(
+        &self,
+        _outpoints: Vec<common::chain::UtxoOutPoint>,
+    ) -> Result<Vec<Option<common::chain::TxOutput>>, Self::Error> {
+        Err(ColdWalletRpcError::NotAvailable)
+    }
 }
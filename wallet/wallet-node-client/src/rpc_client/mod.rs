@@ -16,10 +16,14 @@
 pub mod client_impl;
 pub mod cold_wallet_client;
 
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 
 use common::address::AddressError;
 use common::chain::ChainConfig;
+use logging::log;
 use rpc::new_http_client;
 use rpc::ClientError;
 use rpc::RpcAuthData;
@@ -39,6 +43,8 @@ pub enum NodeRpcError {
     ResponseError(ClientError),
     #[error("Address error: {0}")]
     AddressError(#[from] AddressError),
+    #[error("No node with index {0} is configured")]
+    UnknownNode(usize),
 }
 
 #[derive(Clone, Debug)]
@@ -53,8 +59,18 @@ impl ColdWalletClient {
 }
 
 #[derive(Clone, Debug)]
-pub struct NodeRpcClient {
+struct NodeEndpoint {
+    address: String,
     http_client: RpcHttpClient,
+}
+
+/// Node RPC client that can be configured with several nodes, ordered by preference.
+/// Only one node is in use at a time; the clones of the client share the node in use,
+/// so switching to another node affects all of them.
+#[derive(Clone, Debug)]
+pub struct NodeRpcClient {
+    endpoints: Arc<Vec<NodeEndpoint>>,
+    active_endpoint: Arc<AtomicUsize>,
     chain_config: Arc<ChainConfig>,
 }
 
@@ -64,21 +80,73 @@ impl NodeRpcClient {
         remote_socket_address: String,
         rpc_auth: RpcAuthData,
     ) -> Result<Self, NodeRpcError> {
-        let host = format!("http://{remote_socket_address}");
+        Self::new_with_fallbacks(chain_config, remote_socket_address, Vec::new(), rpc_auth).await
+    }
 
-        let http_client =
-            new_http_client(host, rpc_auth).map_err(NodeRpcError::ClientCreationError)?;
+    /// Create a client that switches to the fallback nodes, in the given order,
+    /// if the primary node is not usable.
+    /// The first node that responds is used initially.
+    pub async fn new_with_fallbacks(
+        chain_config: Arc<ChainConfig>,
+        remote_socket_address: String,
+        fallback_socket_addresses: Vec<String>,
+        rpc_auth: RpcAuthData,
+    ) -> Result<Self, NodeRpcError> {
+        let endpoints = std::iter::once(remote_socket_address)
+            .chain(fallback_socket_addresses)
+            .map(|address| {
+                let http_client = new_http_client(format!("http://{address}"), rpc_auth.clone())
+                    .map_err(NodeRpcError::ClientCreationError)?;
+                Ok(NodeEndpoint {
+                    address,
+                    http_client,
+                })
+            })
+            .collect::<Result<Vec<_>, NodeRpcError>>()?;
 
         let client = Self {
-            http_client,
+            endpoints: Arc::new(endpoints),
+            active_endpoint: Arc::new(AtomicUsize::new(0)),
             chain_config,
         };
 
-        client
-            .get_best_block_id()
-            .await
-            .map_err(|e| NodeRpcError::InitializationError(Box::new(e)))?;
+        let mut first_error = None;
+        for _ in 0..client.endpoints.len() {
+            match client.get_best_block_id().await {
+                Ok(_) => return Ok(client),
+                Err(e) => {
+                    log::warn!("Node {} is not reachable: {e}", client.active_address());
+                    first_error.get_or_insert(e);
+                    client.switch_to_next_endpoint();
+                }
+            }
+        }
+
+        Err(NodeRpcError::InitializationError(Box::new(
+            first_error.expect("there is at least one endpoint"),
+        )))
+    }
+
+    fn http_client(&self) -> &RpcHttpClient {
+        &self.endpoints[self.active_endpoint.load(Ordering::Relaxed)].http_client
+    }
+
+    fn active_address(&self) -> &str {
+        &self.endpoints[self.active_endpoint.load(Ordering::Relaxed)].address
+    }
+
+    fn switch_to_next_endpoint(&self) -> Option<&str> {
+        if self.endpoints.len() < 2 {
+            return None;
+        }
+
+        let next = (self.active_endpoint.load(Ordering::Relaxed) + 1) % self.endpoints.len();
+        self.switch_to_endpoint(next)
+    }
 
-        Ok(client)
+    fn switch_to_endpoint(&self, index: usize) -> Option<&str> {
+        let endpoint = self.endpoints.get(index)?;
+        self.active_endpoint.store(index, Ordering::Relaxed);
+        Some(&endpoint.address)
     }
 }
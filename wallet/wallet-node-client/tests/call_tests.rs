@@ -28,7 +28,10 @@ use common::{
     primitives::{Idable, H256},
 };
 use mempool::{MempoolConfig, MempoolHandle};
-use node_comm::{make_handles_client, make_rpc_client, node_traits::NodeInterface};
+use node_comm::{
+    make_handles_client, make_rpc_client, make_rpc_client_with_fallbacks,
+    node_traits::NodeInterface,
+};
 use p2p::P2pHandle;
 use rpc::RpcAuthData;
 use subsystem::ShutdownTrigger;
@@ -54,6 +57,7 @@ pub async fn start_subsystems(
 
         socks5_proxy: Default::default(),
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -69,6 +73,7 @@ pub async fn start_subsystems(
         sync_stalling_timeout: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     };
     let mempool_config = MempoolConfig::new();
 
@@ -238,6 +243,82 @@ async fn node_rpc_communication() {
     manager_task_handle.await.unwrap();
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn node_rpc_failover() {
+    let chain_config = Arc::new(common::chain::config::create_unit_test_config());
+
+    let (
+        shutdown_trigger,
+        chainstate,
+        _mempool,
+        _block_prod,
+        _p2p,
+        rpc_bind_address,
+        manager_task_handle,
+    ) = start_subsystems(chain_config.clone(), "127.0.0.1:0".to_string()).await;
+
+    // Nothing listens on the primary address, so the fallback node is used from the start
+    let unreachable_address = "127.0.0.1:1".to_string();
+    let rpc_client = make_rpc_client_with_fallbacks(
+        Arc::clone(&chain_config),
+        unreachable_address.clone(),
+        vec![rpc_bind_address.to_string()],
+        RpcAuthData::None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        rpc_client.active_node_address(),
+        Some(rpc_bind_address.to_string())
+    );
+
+    assert_eq!(
+        rpc_client.configured_node_addresses(),
+        vec![unreachable_address.clone(), rpc_bind_address.to_string()]
+    );
+
+    // Every configured node can be asked, whichever is in use
+    assert!(rpc_client.p2p_get_node_sync_progress(0).await.is_err());
+    assert!(rpc_client.p2p_get_node_sync_progress(1).await.is_ok());
+    assert!(rpc_client.p2p_get_node_sync_progress(2).await.is_err());
+
+    // The clones share the node in use
+    let rpc_client_clone = rpc_client.clone();
+    assert_eq!(
+        rpc_client.switch_to_node(0),
+        Some(unreachable_address.clone())
+    );
+    assert_eq!(
+        rpc_client_clone.active_node_address(),
+        Some(unreachable_address)
+    );
+    assert!(rpc_client_clone.get_best_block_id().await.is_err());
+
+    assert_eq!(rpc_client.switch_to_node(2), None);
+    assert_eq!(
+        rpc_client.switch_to_node(1),
+        Some(rpc_bind_address.to_string())
+    );
+    test_wallet_node_communication(chain_config.clone(), chainstate, rpc_client_clone).await;
+
+    // A single node to switch between
+    let rpc_client = make_rpc_client(
+        Arc::clone(&chain_config),
+        rpc_bind_address.to_string(),
+        RpcAuthData::None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        rpc_client.configured_node_addresses(),
+        vec![rpc_bind_address.to_string()]
+    );
+    assert_eq!(rpc_client.switch_to_node(1), None);
+
+    shutdown_trigger.initiate();
+    manager_task_handle.await.unwrap();
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn node_handle_communication() {
     let chain_config = Arc::new(common::chain::config::create_unit_test_config());
@@ -25,31 +25,39 @@ use common::{
     },
     primitives::{BlockHeight, DecimalAmount, Id, Idable, H256},
 };
-use crypto::key::{hdkd::u31::U31, PrivateKey};
+use crypto::key::{extended::ExtendedPublicKey, hdkd::u31::U31, PrivateKey};
 use node_comm::node_traits::NodeInterface;
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, PeerId};
 use rpc::types::RpcHexString;
 use serialization::{hex::HexEncode, hex_encoded::HexEncoded, json_encoded::JsonEncoded};
 use utils_networking::IpOrSocketAddress;
-use wallet::{account::TxInfo, version::get_version};
+use wallet::version::get_version;
 use wallet_controller::{
-    types::{CreatedBlockInfo, GenericTokenTransfer, SeedWithPassPhrase, WalletInfo},
+    types::{
+        CreatedBlockInfo, GenericTokenTransfer, MainchainTxInfo, SeedWithPassPhrase, SyncStatus,
+        TransactionDescription, WalletInfo,
+    },
     ConnectedPeer, ControllerConfig, UtxoState, UtxoType,
 };
 use wallet_rpc_lib::{
     types::{
-        AddressInfo, AddressWithUsageInfo, Balances, BlockInfo, ComposedTransaction, CreatedWallet,
-        DelegationInfo, LegacyVrfPublicKeyInfo, NewAccountInfo, NewDelegation, NewTransaction,
-        NftMetadata, NodeVersion, PoolInfo, PublicKeyInfo, RpcHashedTimelockContract,
-        RpcInspectTransaction, RpcStandaloneAddresses, RpcTokenId,
-        SendTokensFromMultisigAddressResult, StakePoolBalance, StakingStatus,
-        StandaloneAddressWithDetails, TokenMetadata, TxOptionsOverrides, UtxoInfo,
-        VrfPublicKeyInfo,
+        AddressInfo, AddressPage, AddressWithUsageInfo, Balances, BlockInfo,
+        BroadcastQueueEntryInfo, ComposedTransaction, CreatedWallet, DelegationInfo,
+        LegacyVrfPublicKeyInfo, MaybeSignedTransaction, NewAccountInfo, NewDelegation,
+        NewTransaction, NftMetadata, NodeVersion, PageRequest, PayeeInfo, PayoutsReport, PoolInfo,
+        PublicKeyInfo, Recipient, RpcAmountOut, RpcChangePolicy, RpcHashedTimelockContract,
+        RpcInspectTransaction, RpcSigHashType, RpcStandaloneAddresses, RpcTokenId,
+        ScheduledPaymentInfo, SendTokensFromMultisigAddressResult, SentTransaction,
+        SigningLogEntryInfo, StakePoolBalance, StakingEstimate, StakingStatus,
+        StandaloneAddressWithDetails, TokenAuthorityRotationInfo, TokenMetadata, TokenTrustInfo,
+        TransactionHistoryFormat, TransactionPage, TxOptionsOverrides, UtxoInfo, UtxoPage,
+        UtxoSortKey, UtxoVerificationInfo, VrfPublicKeyInfo, WatchedPoolInfo,
     },
     RpcError, WalletRpc,
 };
 use wallet_types::{
-    seed_phrase::StoreSeedPhrase, signature_status::SignatureStatus, utxo_types::UtxoTypes,
+    multisig_account::MultisigAccountDescriptor, seed_phrase::StoreSeedPhrase,
+    signature_status::SignatureStatus, token_trust::TokenTrust, utxo_types::UtxoTypes,
     with_locked::WithLocked,
 };
 
@@ -202,6 +210,45 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn export_watch_only(
+        &self,
+        passphrase: String,
+        since_height: Option<BlockHeight>,
+    ) -> Result<String, Self::Error> {
+        self.wallet_rpc
+            .export_watch_only(passphrase, since_height)
+            .await
+            .map(|data| data.to_string())
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn create_wallet_from_watch_only_export(
+        &self,
+        path: PathBuf,
+        data: String,
+        passphrase: String,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .create_wallet_from_watch_only_export(
+                path,
+                RpcHexString::from_str(&data)?.into_bytes(),
+                passphrase,
+            )
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn apply_watch_only_update(
+        &self,
+        data: String,
+        passphrase: String,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .apply_watch_only_update(RpcHexString::from_str(&data)?.into_bytes(), passphrase)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn encrypt_private_keys(&self, password: String) -> Result<(), Self::Error> {
         self.wallet_rpc
             .encrypt_private_keys(password)
@@ -237,6 +284,35 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn sync_status(&self) -> Result<SyncStatus, Self::Error> {
+        self.wallet_rpc
+            .sync_status()
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn verify_utxos(
+        &self,
+        sample_size: Option<NonZeroUsize>,
+        repair: bool,
+    ) -> Result<UtxoVerificationInfo, Self::Error> {
+        self.wallet_rpc
+            .verify_utxos(sample_size, repair)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn export_signing_log(
+        &self,
+        from: Option<BlockTimestamp>,
+        to: Option<BlockTimestamp>,
+    ) -> Result<Vec<SigningLogEntryInfo>, Self::Error> {
+        self.wallet_rpc
+            .export_signing_log(from, to)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn create_account(&self, name: Option<String>) -> Result<NewAccountInfo, Self::Error> {
         self.wallet_rpc
             .create_account(name)
@@ -255,6 +331,18 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn set_address_label(
+        &self,
+        account_index: U31,
+        address: String,
+        label: Option<String>,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .set_address_label(account_index, address.into(), label)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn standalone_address_label_rename(
         &self,
         account_index: U31,
@@ -280,6 +368,17 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn remove_standalone_address(
+        &self,
+        account_index: U31,
+        address: String,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .remove_standalone_watch_only_address(account_index, address.into())
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn add_standalone_private_key(
         &self,
         account_index: U31,
@@ -293,6 +392,32 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn import_standalone_private_key(
+        &self,
+        account_index: U31,
+        private_key: String,
+        label: Option<String>,
+        no_rescan: bool,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .import_standalone_private_key(account_index, private_key.into(), label, no_rescan)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn export_private_key(
+        &self,
+        account_index: U31,
+        address: String,
+        confirm: bool,
+    ) -> Result<String, Self::Error> {
+        self.wallet_rpc
+            .export_private_key(account_index, address.into(), confirm)
+            .await
+            .map(|key| key.into_string())
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn add_standalone_multisig(
         &self,
         account_index: U31,
@@ -313,6 +438,85 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn account_extended_public_key(
+        &self,
+        account_index: U31,
+    ) -> Result<HexEncoded<ExtendedPublicKey>, Self::Error> {
+        self.wallet_rpc
+            .account_extended_public_key(account_index)
+            .await
+            .map(HexEncoded::new)
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn export_account_extended_public_key(
+        &self,
+        account_index: U31,
+    ) -> Result<String, Self::Error> {
+        self.wallet_rpc
+            .export_account_extended_public_key(account_index)
+            .await
+            .map(|key| key.into_string())
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn import_extended_public_key(
+        &self,
+        account_index: U31,
+        extended_public_key: String,
+        address_count: u32,
+        label: Option<String>,
+        no_rescan: bool,
+    ) -> Result<Vec<String>, Self::Error> {
+        self.wallet_rpc
+            .import_extended_public_key(
+                account_index,
+                extended_public_key.into(),
+                address_count,
+                label,
+                no_rescan,
+            )
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn create_multisig_account_descriptor(
+        &self,
+        account_index: U31,
+        min_required_signatures: u8,
+        cosigner_keys: Vec<HexEncoded<ExtendedPublicKey>>,
+    ) -> Result<HexEncoded<MultisigAccountDescriptor>, Self::Error> {
+        self.wallet_rpc
+            .create_multisig_account_descriptor(
+                account_index,
+                min_required_signatures,
+                cosigner_keys.into_iter().map(HexEncoded::take).collect(),
+            )
+            .await
+            .map(HexEncoded::new)
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn import_multisig_account(
+        &self,
+        account_index: U31,
+        descriptor: HexEncoded<MultisigAccountDescriptor>,
+        address_count: u32,
+        label: Option<String>,
+        no_rescan: bool,
+    ) -> Result<Vec<String>, Self::Error> {
+        self.wallet_rpc
+            .import_multisig_account(
+                account_index,
+                descriptor.take(),
+                address_count,
+                label,
+                no_rescan,
+            )
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn get_issued_addresses(
         &self,
         account_index: U31,
@@ -323,6 +527,18 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn list_issued_addresses(
+        &self,
+        account_index: U31,
+        used: Option<bool>,
+        page: PageRequest,
+    ) -> Result<AddressPage, Self::Error> {
+        self.wallet_rpc
+            .list_issued_addresses(account_index, used, page)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn get_standalone_addresses(
         &self,
         account_index: U31,
@@ -407,6 +623,37 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::SerializationError)
     }
 
+    async fn get_standalone_address_utxos(
+        &self,
+        account_index: U31,
+        address: String,
+        utxo_types: Vec<UtxoType>,
+        utxo_states: Vec<UtxoState>,
+        with_locked: WithLocked,
+    ) -> Result<Vec<serde_json::Value>, Self::Error> {
+        let utxos = self
+            .wallet_rpc
+            .get_standalone_address_utxos(
+                account_index,
+                address.into(),
+                (&utxo_types).try_into().unwrap_or(UtxoTypes::ALL),
+                (&utxo_states).try_into().unwrap_or(UtxoState::Confirmed.into()),
+                with_locked,
+            )
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)?;
+
+        utxos
+            .into_iter()
+            .map(|(utxo_outpoint, tx_ouput)| {
+                UtxoInfo::new(utxo_outpoint, tx_ouput, self.wallet_rpc.chain_config())
+                    .map(serde_json::to_value)
+            })
+            .collect::<Result<Result<Vec<_>, _>, _>>()
+            .map_err(WalletRpcHandlesClientError::AddressError)?
+            .map_err(WalletRpcHandlesClientError::SerializationError)
+    }
+
     async fn get_utxos(
         &self,
         account_index: U31,
@@ -436,6 +683,154 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::SerializationError)
     }
 
+    async fn list_utxos(
+        &self,
+        account_index: U31,
+        utxo_types: Vec<UtxoType>,
+        utxo_states: Vec<UtxoState>,
+        with_locked: WithLocked,
+        sort_by: UtxoSortKey,
+        page: PageRequest,
+    ) -> Result<UtxoPage, Self::Error> {
+        let (utxos, next_cursor, best_synced_block) = self
+            .wallet_rpc
+            .list_utxos(
+                account_index,
+                (&utxo_types).try_into().unwrap_or(UtxoTypes::ALL),
+                (&utxo_states).try_into().unwrap_or(UtxoState::Confirmed.into()),
+                with_locked,
+                sort_by,
+                page,
+            )
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)?;
+
+        let utxos = utxos
+            .into_iter()
+            .map(|(utxo_outpoint, tx_ouput)| {
+                UtxoInfo::new(utxo_outpoint, tx_ouput, self.wallet_rpc.chain_config())
+                    .map(serde_json::to_value)
+            })
+            .collect::<Result<Result<Vec<_>, _>, _>>()
+            .map_err(WalletRpcHandlesClientError::AddressError)?
+            .map_err(WalletRpcHandlesClientError::SerializationError)?;
+
+        Ok(UtxoPage {
+            utxos,
+            next_cursor,
+            best_synced_block,
+        })
+    }
+
+    async fn set_dust_protection(
+        &self,
+        account_index: U31,
+        min_coin_amount: Option<DecimalAmount>,
+        flag_unknown_tokens: bool,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .set_dust_protection(
+                account_index,
+                min_coin_amount.map(Into::into),
+                flag_unknown_tokens,
+            )
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn set_change_policy(
+        &self,
+        account_index: U31,
+        change_policy: RpcChangePolicy,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .set_change_policy(account_index, change_policy)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn get_change_policy(&self, account_index: U31) -> Result<RpcChangePolicy, Self::Error> {
+        self.wallet_rpc
+            .get_change_policy(account_index)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn get_dust_utxos(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<serde_json::Value>, Self::Error> {
+        let utxos = self
+            .wallet_rpc
+            .get_dust_utxos(account_index)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)?;
+
+        utxos
+            .into_iter()
+            .map(|(utxo_outpoint, tx_ouput)| {
+                UtxoInfo::new(utxo_outpoint, tx_ouput, self.wallet_rpc.chain_config())
+                    .map(serde_json::to_value)
+            })
+            .collect::<Result<Result<Vec<_>, _>, _>>()
+            .map_err(WalletRpcHandlesClientError::AddressError)?
+            .map_err(WalletRpcHandlesClientError::SerializationError)
+    }
+
+    async fn release_dust_utxos(
+        &self,
+        account_index: U31,
+        utxos: Vec<UtxoOutPoint>,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .release_dust_utxos(account_index, utxos.into_iter().map(Into::into).collect())
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn freeze_utxos(
+        &self,
+        account_index: U31,
+        utxos: Vec<UtxoOutPoint>,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .freeze_utxos(account_index, utxos.into_iter().map(Into::into).collect())
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn unfreeze_utxos(
+        &self,
+        account_index: U31,
+        utxos: Vec<UtxoOutPoint>,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .unfreeze_utxos(account_index, utxos.into_iter().map(Into::into).collect())
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn get_frozen_utxos(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<serde_json::Value>, Self::Error> {
+        let utxos = self
+            .wallet_rpc
+            .get_frozen_utxos(account_index)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)?;
+
+        utxos
+            .into_iter()
+            .map(|(utxo_outpoint, tx_ouput)| {
+                UtxoInfo::new(utxo_outpoint, tx_ouput, self.wallet_rpc.chain_config())
+                    .map(serde_json::to_value)
+            })
+            .collect::<Result<Result<Vec<_>, _>, _>>()
+            .map_err(WalletRpcHandlesClientError::AddressError)?
+            .map_err(WalletRpcHandlesClientError::SerializationError)
+    }
+
     async fn submit_raw_transaction(
         &self,
         tx: HexEncoded<SignedTransaction>,
@@ -487,6 +882,42 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn create_transaction_package(
+        &self,
+        account_index: U31,
+        outputs: Vec<TxOutput>,
+        selected_utxos: Vec<UtxoOutPoint>,
+        change_address: Option<String>,
+        config: ControllerConfig,
+    ) -> Result<ComposedTransaction, Self::Error> {
+        self.wallet_rpc
+            .create_transaction_package(
+                account_index,
+                outputs,
+                selected_utxos,
+                change_address.map(Into::into),
+                config,
+            )
+            .await
+            .map(|(package, fees)| ComposedTransaction {
+                hex: package.hex_encode(),
+                fees,
+            })
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn submit_transaction_package(
+        &self,
+        account_index: U31,
+        package: String,
+        config: ControllerConfig,
+    ) -> Result<NewTransaction, Self::Error> {
+        self.wallet_rpc
+            .submit_transaction_package(account_index, RpcHexString::from_str(&package)?, config)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn transaction_inspect(
         &self,
         transaction: String,
@@ -544,6 +975,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
         inputs: Vec<UtxoOutPoint>,
         outputs: Vec<TxOutput>,
         htlc_secrets: Option<Vec<Option<String>>>,
+        sighash_types: Option<Vec<RpcSigHashType>>,
         only_transaction: bool,
     ) -> Result<ComposedTransaction, Self::Error> {
         let inputs = inputs.into_iter().map(Into::into).collect();
@@ -551,7 +983,13 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map(|s| s.into_iter().map(|s| s.map(|s| s.parse()).transpose()).collect())
             .transpose()?;
         self.wallet_rpc
-            .compose_transaction(inputs, outputs, htlc_secrets, only_transaction)
+            .compose_transaction(
+                inputs,
+                outputs,
+                htlc_secrets,
+                sighash_types,
+                only_transaction,
+            )
             .await
             .map(|(tx, fees)| ComposedTransaction {
                 hex: tx.to_hex(),
@@ -567,7 +1005,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
         amount: DecimalAmount,
         selected_utxos: Vec<UtxoOutPoint>,
         config: ControllerConfig,
-    ) -> Result<NewTransaction, Self::Error> {
+    ) -> Result<SentTransaction, Self::Error> {
         self.wallet_rpc
             .send_coins(
                 account_index,
@@ -578,7 +1016,33 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             )
             .await
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
-            .map(NewTransaction::new)
+            .map(|(tx, fee)| SentTransaction::new(&tx, fee, self.wallet_rpc.chain_config()))
+    }
+
+    async fn send_to_many(
+        &self,
+        account_index: U31,
+        recipients: Vec<Recipient>,
+        config: ControllerConfig,
+    ) -> Result<SentTransaction, Self::Error> {
+        self.wallet_rpc
+            .send_to_many(account_index, recipients, config)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+            .map(|(tx, fee)| SentTransaction::new(&tx, fee, self.wallet_rpc.chain_config()))
+    }
+
+    async fn send_payouts_csv(
+        &self,
+        account_index: U31,
+        csv: String,
+        max_outputs_per_tx: Option<usize>,
+        config: ControllerConfig,
+    ) -> Result<PayoutsReport, Self::Error> {
+        self.wallet_rpc
+            .send_payouts_csv(account_index, csv, max_outputs_per_tx, config)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
     async fn sweep_addresses(
@@ -599,6 +1063,56 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn estimate_max_sendable(
+        &self,
+        account_index: U31,
+        address: String,
+        token_id: Option<String>,
+    ) -> Result<RpcAmountOut, Self::Error> {
+        self.wallet_rpc
+            .estimate_max_sendable(account_index, address.into(), token_id.map(Into::into))
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn send_all(
+        &self,
+        account_index: U31,
+        address: String,
+        token_id: Option<String>,
+        config: ControllerConfig,
+    ) -> Result<NewTransaction, Self::Error> {
+        self.wallet_rpc
+            .send_all(
+                account_index,
+                address.into(),
+                token_id.map(Into::into),
+                config,
+            )
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn transfer_between_accounts(
+        &self,
+        account_index: U31,
+        to_account: U31,
+        amount: DecimalAmount,
+        token_id: Option<String>,
+        config: ControllerConfig,
+    ) -> Result<NewTransaction, Self::Error> {
+        self.wallet_rpc
+            .transfer_between_accounts(
+                account_index,
+                to_account,
+                amount.into(),
+                token_id.map(Into::into),
+                config,
+            )
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn sweep_delegation(
         &self,
         account_index: U31,
@@ -776,6 +1290,103 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn staking_calculator(
+        &self,
+        pledge: DecimalAmount,
+        pool_balance: DecimalAmount,
+    ) -> Result<StakingEstimate, Self::Error> {
+        self.wallet_rpc
+            .staking_calculator(pledge.into(), pool_balance.into())
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn add_watched_pool(
+        &self,
+        account_index: U31,
+        pool_id: String,
+        label: Option<String>,
+        decommission_address: Option<String>,
+        no_rescan: bool,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .add_watched_pool(
+                account_index,
+                pool_id.into(),
+                label,
+                decommission_address.map(Into::into),
+                no_rescan,
+            )
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn remove_watched_pool(
+        &self,
+        account_index: U31,
+        pool_id: String,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .remove_watched_pool(account_index, pool_id.into())
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn list_watched_pools(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<WatchedPoolInfo>, Self::Error> {
+        self.wallet_rpc
+            .list_watched_pools(account_index)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn add_scheduled_payment(
+        &self,
+        account_index: U31,
+        address: String,
+        amount: DecimalAmount,
+        token_id: Option<String>,
+        interval_secs: u64,
+        first_run: Option<BlockTimestamp>,
+        label: Option<String>,
+    ) -> Result<u64, Self::Error> {
+        self.wallet_rpc
+            .add_scheduled_payment(
+                account_index,
+                address.into(),
+                amount.into(),
+                token_id.map(Into::into),
+                interval_secs,
+                first_run,
+                label,
+            )
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn cancel_scheduled_payment(
+        &self,
+        account_index: U31,
+        payment_id: u64,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .cancel_scheduled_payment(account_index, payment_id)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn list_scheduled_payments(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<ScheduledPaymentInfo>, Self::Error> {
+        self.wallet_rpc
+            .list_scheduled_payments(account_index)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn list_delegation_ids(
         &self,
         account_index: U31,
@@ -881,6 +1492,28 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn rotate_token_authority(
+        &self,
+        account_index: U31,
+        token_id: String,
+        address: String,
+        dry_run: bool,
+        force: bool,
+        config: ControllerConfig,
+    ) -> Result<TokenAuthorityRotationInfo, Self::Error> {
+        self.wallet_rpc
+            .rotate_token_authority(
+                account_index,
+                token_id.into(),
+                address.into(),
+                dry_run,
+                force,
+                config,
+            )
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn change_token_metadata_uri(
         &self,
         account_index: U31,
@@ -981,7 +1614,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
         address: String,
         amount: DecimalAmount,
         config: ControllerConfig,
-    ) -> Result<NewTransaction, Self::Error> {
+    ) -> Result<SentTransaction, Self::Error> {
         self.wallet_rpc
             .send_tokens(
                 account_index,
@@ -992,6 +1625,79 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             )
             .await
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
+            .map(|(tx, fee)| SentTransaction::new(&tx, fee, self.wallet_rpc.chain_config()))
+    }
+
+    async fn set_token_trust(
+        &self,
+        token_id: String,
+        trust: Option<TokenTrust>,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .set_token_trust(token_id.into(), trust)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn list_token_trust(&self) -> Result<Vec<TokenTrustInfo>, Self::Error> {
+        self.wallet_rpc
+            .list_token_trust()
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn add_payee(
+        &self,
+        name: String,
+        address: String,
+        default_token: Option<String>,
+        notes: Option<String>,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .add_payee(name, address.into(), default_token.map(Into::into), notes)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn remove_payee(&self, name: String) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .remove_payee(name)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn list_payees(&self) -> Result<Vec<PayeeInfo>, Self::Error> {
+        self.wallet_rpc
+            .list_payees()
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn export_payees(&self) -> Result<String, Self::Error> {
+        self.wallet_rpc
+            .export_payees()
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn import_payees(&self, address_book: String) -> Result<usize, Self::Error> {
+        self.wallet_rpc
+            .import_payees(address_book)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn send_to_payee(
+        &self,
+        account_index: U31,
+        name: String,
+        amount: DecimalAmount,
+        config: ControllerConfig,
+    ) -> Result<NewTransaction, Self::Error> {
+        self.wallet_rpc
+            .send_to_payee(account_index, name, amount.into(), config)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
     async fn make_tx_to_send_tokens_from_multisig_address(
@@ -1188,6 +1894,37 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn transaction_broadcast_status(
+        &self,
+        transaction_id: Id<Transaction>,
+    ) -> Result<Option<BroadcastQueueEntryInfo>, Self::Error> {
+        self.wallet_rpc
+            .transaction_broadcast_status(transaction_id)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn transaction_broadcast_queue(
+        &self,
+    ) -> Result<Vec<BroadcastQueueEntryInfo>, Self::Error> {
+        self.wallet_rpc
+            .transaction_broadcast_queue()
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn set_transaction_broadcast_policy(
+        &self,
+        transaction_id: Id<Transaction>,
+        max_failed_attempts: u32,
+        deadline: Option<BlockTimestamp>,
+    ) -> Result<(), Self::Error> {
+        self.wallet_rpc
+            .set_transaction_broadcast_policy(transaction_id, max_failed_attempts, deadline)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn list_pending_transactions(
         &self,
         account_index: U31,
@@ -1204,13 +1941,25 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
         account_index: U31,
         address: Option<String>,
         limit: usize,
-    ) -> Result<Vec<TxInfo>, Self::Error> {
+    ) -> Result<Vec<MainchainTxInfo>, Self::Error> {
         self.wallet_rpc
             .mainchain_transactions(account_index, address.map(Into::into), limit)
             .await
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn list_transactions(
+        &self,
+        account_index: U31,
+        address: Option<String>,
+        page: PageRequest,
+    ) -> Result<TransactionPage, Self::Error> {
+        self.wallet_rpc
+            .list_mainchain_transactions(account_index, address.map(Into::into), page)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn get_transaction(
         &self,
         account_index: U31,
@@ -1228,6 +1977,28 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             })
     }
 
+    async fn describe_transaction(
+        &self,
+        account_index: U31,
+        transaction_id: Id<Transaction>,
+    ) -> Result<TransactionDescription, Self::Error> {
+        self.wallet_rpc
+            .describe_transaction(account_index, transaction_id)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
+    async fn export_transaction_history(
+        &self,
+        account_index: U31,
+        format: TransactionHistoryFormat,
+    ) -> Result<String, Self::Error> {
+        self.wallet_rpc
+            .export_transaction_history(account_index, format)
+            .await
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn get_raw_transaction(
         &self,
         account_index: U31,
@@ -1282,6 +2053,31 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletInterface
             .map_err(WalletRpcHandlesClientError::WalletRpcError)
     }
 
+    async fn sign_transaction_package(
+        &self,
+        account_index: U31,
+        package: String,
+        config: ControllerConfig,
+    ) -> Result<MaybeSignedTransaction, Self::Error> {
+        self.wallet_rpc
+            .sign_transaction_package(account_index, RpcHexString::from_str(&package)?, config)
+            .await
+            .map(|(package, prev_signatures, cur_signatures)| {
+                let is_complete = package.is_fully_signed()
+                    && cur_signatures.iter().all(|s| *s == SignatureStatus::FullySigned);
+                let previous_signatures = prev_signatures.into_iter().map(Into::into).collect();
+                let current_signatures = cur_signatures.into_iter().map(Into::into).collect();
+
+                MaybeSignedTransaction {
+                    hex: package.hex_encode(),
+                    is_complete,
+                    previous_signatures,
+                    current_signatures,
+                }
+            })
+            .map_err(WalletRpcHandlesClientError::WalletRpcError)
+    }
+
     async fn node_best_block_id(&self) -> Result<Id<GenBlock>, Self::Error> {
         self.wallet_rpc
             .node_best_block_id()
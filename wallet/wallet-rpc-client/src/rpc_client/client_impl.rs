@@ -27,30 +27,38 @@ use common::{
     },
     primitives::{BlockHeight, DecimalAmount, Id},
 };
-use crypto::key::{hdkd::u31::U31, PrivateKey};
+use crypto::key::{extended::ExtendedPublicKey, hdkd::u31::U31, PrivateKey};
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, PeerId};
 use rpc::types::RpcHexString;
 use serialization::hex_encoded::HexEncoded;
 use serialization::DecodeAll;
 use utils_networking::IpOrSocketAddress;
-use wallet::account::TxInfo;
 use wallet_controller::{
-    types::{Balances, CreatedBlockInfo, GenericTokenTransfer, SeedWithPassPhrase, WalletInfo},
+    types::{
+        Balances, CreatedBlockInfo, GenericTokenTransfer, MainchainTxInfo, SeedWithPassPhrase,
+        SyncStatus, TransactionDescription, WalletInfo,
+    },
     ConnectedPeer, ControllerConfig, UtxoState, UtxoType,
 };
 use wallet_rpc_lib::{
     types::{
-        AddressInfo, AddressWithUsageInfo, BlockInfo, ComposedTransaction, CreatedWallet,
-        DelegationInfo, LegacyVrfPublicKeyInfo, NewAccountInfo, NewDelegation, NewTransaction,
-        NftMetadata, NodeVersion, PoolInfo, PublicKeyInfo, RpcHashedTimelockContract,
-        RpcInspectTransaction, RpcStandaloneAddresses, RpcTokenId,
-        SendTokensFromMultisigAddressResult, StakePoolBalance, StakingStatus,
-        StandaloneAddressWithDetails, TokenMetadata, TransactionOptions, TxOptionsOverrides,
-        VrfPublicKeyInfo,
+        AddressInfo, AddressPage, AddressWithUsageInfo, BlockInfo, BroadcastQueueEntryInfo,
+        ComposedTransaction, CreatedWallet, DelegationInfo, LegacyVrfPublicKeyInfo,
+        MaybeSignedTransaction, NewAccountInfo, NewDelegation, NewTransaction, NftMetadata,
+        NodeVersion, PageRequest, PayeeInfo, PayoutsReport, PoolInfo, PublicKeyInfo, Recipient,
+        RpcAmountOut, RpcChangePolicy, RpcHashedTimelockContract, RpcInspectTransaction,
+        RpcSigHashType, RpcStandaloneAddresses, RpcTokenId, ScheduledPaymentInfo,
+        SendTokensFromMultisigAddressResult, SentTransaction, SigningLogEntryInfo,
+        StakePoolBalance, StakingEstimate, StakingStatus, StandaloneAddressWithDetails,
+        TokenAuthorityRotationInfo, TokenMetadata, TokenTrustInfo, TransactionHistoryFormat,
+        TransactionOptions, TransactionPage, TxOptionsOverrides, UtxoPage, UtxoSortKey,
+        UtxoVerificationInfo, VrfPublicKeyInfo, WatchedPoolInfo,
     },
     ColdWalletRpcClient, WalletRpcClient,
 };
-use wallet_types::with_locked::WithLocked;
+use wallet_types::{
+    multisig_account::MultisigAccountDescriptor, token_trust::TokenTrust, with_locked::WithLocked,
+};
 
 #[async_trait::async_trait]
 impl WalletInterface for ClientWalletRpc {
@@ -160,6 +168,43 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn export_watch_only(
+        &self,
+        passphrase: String,
+        since_height: Option<BlockHeight>,
+    ) -> Result<String, Self::Error> {
+        ColdWalletRpcClient::export_watch_only(&self.http_client, passphrase, since_height)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+            .map(|data| data.to_string())
+    }
+
+    async fn create_wallet_from_watch_only_export(
+        &self,
+        path: PathBuf,
+        data: String,
+        passphrase: String,
+    ) -> Result<(), Self::Error> {
+        ColdWalletRpcClient::create_wallet_from_watch_only_export(
+            &self.http_client,
+            path.to_string_lossy().to_string(),
+            data.parse()?,
+            passphrase,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn apply_watch_only_update(
+        &self,
+        data: String,
+        passphrase: String,
+    ) -> Result<(), Self::Error> {
+        ColdWalletRpcClient::apply_watch_only_update(&self.http_client, data.parse()?, passphrase)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn encrypt_private_keys(&self, password: String) -> Result<(), Self::Error> {
         ColdWalletRpcClient::encrypt_private_keys(&self.http_client, password)
             .await
@@ -190,6 +235,32 @@ impl WalletInterface for ClientWalletRpc {
             .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn sync_status(&self) -> Result<SyncStatus, Self::Error> {
+        WalletRpcClient::sync_status(&self.http_client)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn verify_utxos(
+        &self,
+        sample_size: Option<NonZeroUsize>,
+        repair: bool,
+    ) -> Result<UtxoVerificationInfo, Self::Error> {
+        WalletRpcClient::verify_utxos(&self.http_client, sample_size, repair)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn export_signing_log(
+        &self,
+        from: Option<BlockTimestamp>,
+        to: Option<BlockTimestamp>,
+    ) -> Result<Vec<SigningLogEntryInfo>, Self::Error> {
+        WalletRpcClient::export_signing_log(&self.http_client, from, to)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn create_account(&self, name: Option<String>) -> Result<NewAccountInfo, Self::Error> {
         WalletRpcClient::create_account(&self.http_client, name)
             .await
@@ -206,6 +277,22 @@ impl WalletInterface for ClientWalletRpc {
             .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn set_address_label(
+        &self,
+        account_index: U31,
+        address: String,
+        label: Option<String>,
+    ) -> Result<(), Self::Error> {
+        WalletRpcClient::set_address_label(
+            &self.http_client,
+            account_index.into(),
+            address.into(),
+            label,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn standalone_address_label_rename(
         &self,
         account_index: U31,
@@ -240,6 +327,20 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn remove_standalone_address(
+        &self,
+        account_index: U31,
+        address: String,
+    ) -> Result<(), Self::Error> {
+        WalletRpcClient::remove_standalone_address(
+            &self.http_client,
+            account_index.into(),
+            address.into(),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn add_standalone_private_key(
         &self,
         account_index: U31,
@@ -258,6 +359,41 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn import_standalone_private_key(
+        &self,
+        account_index: U31,
+        private_key: String,
+        label: Option<String>,
+        no_rescan: bool,
+    ) -> Result<(), Self::Error> {
+        WalletRpcClient::import_standalone_private_key(
+            &self.http_client,
+            account_index.into(),
+            private_key.into(),
+            label,
+            Some(no_rescan),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn export_private_key(
+        &self,
+        account_index: U31,
+        address: String,
+        confirm: bool,
+    ) -> Result<String, Self::Error> {
+        WalletRpcClient::export_private_key(
+            &self.http_client,
+            account_index.into(),
+            address.into(),
+            confirm,
+        )
+        .await
+        .map(|key| key.into_string())
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn add_standalone_multisig(
         &self,
         account_index: U31,
@@ -278,6 +414,81 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn account_extended_public_key(
+        &self,
+        account_index: U31,
+    ) -> Result<HexEncoded<ExtendedPublicKey>, Self::Error> {
+        WalletRpcClient::account_extended_public_key(&self.http_client, account_index.into())
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn export_account_extended_public_key(
+        &self,
+        account_index: U31,
+    ) -> Result<String, Self::Error> {
+        WalletRpcClient::export_account_extended_public_key(&self.http_client, account_index.into())
+            .await
+            .map(|key| key.into_string())
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn import_extended_public_key(
+        &self,
+        account_index: U31,
+        extended_public_key: String,
+        address_count: u32,
+        label: Option<String>,
+        no_rescan: bool,
+    ) -> Result<Vec<String>, Self::Error> {
+        WalletRpcClient::import_extended_public_key(
+            &self.http_client,
+            account_index.into(),
+            extended_public_key.into(),
+            address_count,
+            label,
+            Some(no_rescan),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn create_multisig_account_descriptor(
+        &self,
+        account_index: U31,
+        min_required_signatures: u8,
+        cosigner_keys: Vec<HexEncoded<ExtendedPublicKey>>,
+    ) -> Result<HexEncoded<MultisigAccountDescriptor>, Self::Error> {
+        WalletRpcClient::create_multisig_account_descriptor(
+            &self.http_client,
+            account_index.into(),
+            min_required_signatures,
+            cosigner_keys,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn import_multisig_account(
+        &self,
+        account_index: U31,
+        descriptor: HexEncoded<MultisigAccountDescriptor>,
+        address_count: u32,
+        label: Option<String>,
+        no_rescan: bool,
+    ) -> Result<Vec<String>, Self::Error> {
+        WalletRpcClient::import_multisig_account(
+            &self.http_client,
+            account_index.into(),
+            descriptor,
+            address_count,
+            label,
+            Some(no_rescan),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn get_issued_addresses(
         &self,
         account_index: U31,
@@ -287,6 +498,22 @@ impl WalletInterface for ClientWalletRpc {
             .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn list_issued_addresses(
+        &self,
+        account_index: U31,
+        used: Option<bool>,
+        page: PageRequest,
+    ) -> Result<AddressPage, Self::Error> {
+        ColdWalletRpcClient::list_issued_addresses(
+            &self.http_client,
+            account_index.into(),
+            used,
+            Some(page),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn get_standalone_addresses(
         &self,
         account_index: U31,
@@ -364,6 +591,26 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn get_standalone_address_utxos(
+        &self,
+        account_index: U31,
+        address: String,
+        utxo_types: Vec<UtxoType>,
+        utxo_states: Vec<UtxoState>,
+        with_locked: WithLocked,
+    ) -> Result<Vec<serde_json::Value>, Self::Error> {
+        WalletRpcClient::get_standalone_address_utxos(
+            &self.http_client,
+            account_index.into(),
+            address.into(),
+            utxo_types.iter().map(Into::into).collect(),
+            utxo_states.iter().map(Into::into).collect(),
+            Some(with_locked),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn get_utxos(
         &self,
         account_index: U31,
@@ -376,6 +623,111 @@ impl WalletInterface for ClientWalletRpc {
             .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn list_utxos(
+        &self,
+        account_index: U31,
+        utxo_types: Vec<UtxoType>,
+        utxo_states: Vec<UtxoState>,
+        with_locked: WithLocked,
+        sort_by: UtxoSortKey,
+        page: PageRequest,
+    ) -> Result<UtxoPage, Self::Error> {
+        WalletRpcClient::list_utxos(
+            &self.http_client,
+            account_index.into(),
+            utxo_types.iter().map(Into::into).collect(),
+            utxo_states.iter().map(Into::into).collect(),
+            Some(with_locked),
+            Some(sort_by),
+            Some(page),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn set_dust_protection(
+        &self,
+        account_index: U31,
+        min_coin_amount: Option<DecimalAmount>,
+        flag_unknown_tokens: bool,
+    ) -> Result<(), Self::Error> {
+        WalletRpcClient::set_dust_protection(
+            &self.http_client,
+            account_index.into(),
+            min_coin_amount.map(Into::into),
+            flag_unknown_tokens,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn set_change_policy(
+        &self,
+        account_index: U31,
+        change_policy: RpcChangePolicy,
+    ) -> Result<(), Self::Error> {
+        WalletRpcClient::set_change_policy(&self.http_client, account_index.into(), change_policy)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn get_change_policy(&self, account_index: U31) -> Result<RpcChangePolicy, Self::Error> {
+        WalletRpcClient::get_change_policy(&self.http_client, account_index.into())
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn get_dust_utxos(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<serde_json::Value>, Self::Error> {
+        WalletRpcClient::get_dust_utxos(&self.http_client, account_index.into())
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn release_dust_utxos(
+        &self,
+        account_index: U31,
+        utxos: Vec<UtxoOutPoint>,
+    ) -> Result<(), Self::Error> {
+        let utxos = utxos.into_iter().map(Into::into).collect();
+        WalletRpcClient::release_dust_utxos(&self.http_client, account_index.into(), utxos)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn freeze_utxos(
+        &self,
+        account_index: U31,
+        utxos: Vec<UtxoOutPoint>,
+    ) -> Result<(), Self::Error> {
+        let utxos = utxos.into_iter().map(Into::into).collect();
+        WalletRpcClient::freeze_utxos(&self.http_client, account_index.into(), utxos)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn unfreeze_utxos(
+        &self,
+        account_index: U31,
+        utxos: Vec<UtxoOutPoint>,
+    ) -> Result<(), Self::Error> {
+        let utxos = utxos.into_iter().map(Into::into).collect();
+        WalletRpcClient::unfreeze_utxos(&self.http_client, account_index.into(), utxos)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn get_frozen_utxos(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<serde_json::Value>, Self::Error> {
+        WalletRpcClient::get_frozen_utxos(&self.http_client, account_index.into())
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn submit_raw_transaction(
         &self,
         tx: HexEncoded<SignedTransaction>,
@@ -387,41 +739,128 @@ impl WalletInterface for ClientWalletRpc {
             .map_err(WalletRpcError::ResponseError)
     }
 
-    async fn send_coins(
+    async fn send_coins(
+        &self,
+        account_index: U31,
+        address: String,
+        amount: DecimalAmount,
+        selected_utxos: Vec<UtxoOutPoint>,
+        config: ControllerConfig,
+    ) -> Result<SentTransaction, Self::Error> {
+        let options = TransactionOptions::from_controller_config(&config);
+        let selected_utxos = selected_utxos.into_iter().map(Into::into).collect();
+        WalletRpcClient::send_coins(
+            &self.http_client,
+            account_index.into(),
+            address.into(),
+            amount.into(),
+            selected_utxos,
+            options,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn send_to_many(
+        &self,
+        account_index: U31,
+        recipients: Vec<Recipient>,
+        config: ControllerConfig,
+    ) -> Result<SentTransaction, Self::Error> {
+        let options = TransactionOptions::from_controller_config(&config);
+        WalletRpcClient::send_to_many(&self.http_client, account_index.into(), recipients, options)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn send_payouts_csv(
+        &self,
+        account_index: U31,
+        csv: String,
+        max_outputs_per_tx: Option<usize>,
+        config: ControllerConfig,
+    ) -> Result<PayoutsReport, Self::Error> {
+        let options = TransactionOptions::from_controller_config(&config);
+        WalletRpcClient::send_payouts_csv(
+            &self.http_client,
+            account_index.into(),
+            csv,
+            max_outputs_per_tx,
+            options,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn sweep_addresses(
+        &self,
+        account_index: U31,
+        destination_address: String,
+        from_addresses: Vec<String>,
+        config: ControllerConfig,
+    ) -> Result<NewTransaction, Self::Error> {
+        let options = TransactionOptions::from_controller_config(&config);
+        WalletRpcClient::sweep_addresses(
+            &self.http_client,
+            account_index.into(),
+            destination_address.into(),
+            from_addresses.into_iter().map(Into::into).collect(),
+            options,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn estimate_max_sendable(
+        &self,
+        account_index: U31,
+        address: String,
+        token_id: Option<String>,
+    ) -> Result<RpcAmountOut, Self::Error> {
+        WalletRpcClient::estimate_max_sendable(
+            &self.http_client,
+            account_index.into(),
+            address.into(),
+            token_id.map(Into::into),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn send_all(
         &self,
         account_index: U31,
         address: String,
-        amount: DecimalAmount,
-        selected_utxos: Vec<UtxoOutPoint>,
+        token_id: Option<String>,
         config: ControllerConfig,
     ) -> Result<NewTransaction, Self::Error> {
         let options = TransactionOptions::from_controller_config(&config);
-        let selected_utxos = selected_utxos.into_iter().map(Into::into).collect();
-        WalletRpcClient::send_coins(
+        WalletRpcClient::send_all(
             &self.http_client,
             account_index.into(),
             address.into(),
-            amount.into(),
-            selected_utxos,
+            token_id.map(Into::into),
             options,
         )
         .await
         .map_err(WalletRpcError::ResponseError)
     }
 
-    async fn sweep_addresses(
+    async fn transfer_between_accounts(
         &self,
         account_index: U31,
-        destination_address: String,
-        from_addresses: Vec<String>,
+        to_account: U31,
+        amount: DecimalAmount,
+        token_id: Option<String>,
         config: ControllerConfig,
     ) -> Result<NewTransaction, Self::Error> {
         let options = TransactionOptions::from_controller_config(&config);
-        WalletRpcClient::sweep_addresses(
+        WalletRpcClient::transfer_between_accounts(
             &self.http_client,
             account_index.into(),
-            destination_address.into(),
-            from_addresses.into_iter().map(Into::into).collect(),
+            to_account.into(),
+            amount.into(),
+            token_id.map(Into::into),
             options,
         )
         .await
@@ -470,6 +909,44 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn create_transaction_package(
+        &self,
+        account_index: U31,
+        outputs: Vec<TxOutput>,
+        selected_utxos: Vec<UtxoOutPoint>,
+        change_address: Option<String>,
+        config: ControllerConfig,
+    ) -> Result<ComposedTransaction, Self::Error> {
+        let options = TransactionOptions::from_controller_config(&config);
+        WalletRpcClient::create_transaction_package(
+            &self.http_client,
+            account_index.into(),
+            outputs,
+            selected_utxos.into_iter().map(Into::into).collect(),
+            change_address.map(Into::into),
+            options,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn submit_transaction_package(
+        &self,
+        account_index: U31,
+        package: String,
+        config: ControllerConfig,
+    ) -> Result<NewTransaction, Self::Error> {
+        let options = TransactionOptions::from_controller_config(&config);
+        WalletRpcClient::submit_transaction_package(
+            &self.http_client,
+            account_index.into(),
+            package.parse()?,
+            options,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn transaction_inspect(
         &self,
         transaction: String,
@@ -638,6 +1115,106 @@ impl WalletInterface for ClientWalletRpc {
             .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn staking_calculator(
+        &self,
+        pledge: DecimalAmount,
+        pool_balance: DecimalAmount,
+    ) -> Result<StakingEstimate, Self::Error> {
+        WalletRpcClient::staking_calculator(&self.http_client, pledge.into(), pool_balance.into())
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn add_watched_pool(
+        &self,
+        account_index: U31,
+        pool_id: String,
+        label: Option<String>,
+        decommission_address: Option<String>,
+        no_rescan: bool,
+    ) -> Result<(), Self::Error> {
+        WalletRpcClient::add_watched_pool(
+            &self.http_client,
+            account_index.into(),
+            pool_id.into(),
+            label,
+            decommission_address.map(Into::into),
+            Some(no_rescan),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn remove_watched_pool(
+        &self,
+        account_index: U31,
+        pool_id: String,
+    ) -> Result<(), Self::Error> {
+        WalletRpcClient::remove_watched_pool(
+            &self.http_client,
+            account_index.into(),
+            pool_id.into(),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn list_watched_pools(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<WatchedPoolInfo>, Self::Error> {
+        WalletRpcClient::list_watched_pools(&self.http_client, account_index.into())
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn add_scheduled_payment(
+        &self,
+        account_index: U31,
+        address: String,
+        amount: DecimalAmount,
+        token_id: Option<String>,
+        interval_secs: u64,
+        first_run: Option<BlockTimestamp>,
+        label: Option<String>,
+    ) -> Result<u64, Self::Error> {
+        WalletRpcClient::add_scheduled_payment(
+            &self.http_client,
+            account_index.into(),
+            address.into(),
+            amount.into(),
+            token_id.map(Into::into),
+            interval_secs,
+            first_run,
+            label,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn cancel_scheduled_payment(
+        &self,
+        account_index: U31,
+        payment_id: u64,
+    ) -> Result<(), Self::Error> {
+        WalletRpcClient::cancel_scheduled_payment(
+            &self.http_client,
+            account_index.into(),
+            payment_id,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn list_scheduled_payments(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<ScheduledPaymentInfo>, Self::Error> {
+        WalletRpcClient::list_scheduled_payments(&self.http_client, account_index.into())
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn list_delegation_ids(
         &self,
         account_index: U31,
@@ -740,6 +1317,29 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn rotate_token_authority(
+        &self,
+        account_index: U31,
+        token_id: String,
+        address: String,
+        dry_run: bool,
+        force: bool,
+        config: ControllerConfig,
+    ) -> Result<TokenAuthorityRotationInfo, Self::Error> {
+        let options = TransactionOptions::from_controller_config(&config);
+        WalletRpcClient::rotate_token_authority(
+            &self.http_client,
+            account_index.into(),
+            token_id.into(),
+            address.into(),
+            dry_run,
+            force,
+            options,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn change_token_metadata_uri(
         &self,
         account_index: U31,
@@ -859,7 +1459,7 @@ impl WalletInterface for ClientWalletRpc {
         address: String,
         amount: DecimalAmount,
         config: ControllerConfig,
-    ) -> Result<NewTransaction, Self::Error> {
+    ) -> Result<SentTransaction, Self::Error> {
         let options = TransactionOptions::from_controller_config(&config);
         WalletRpcClient::send_tokens(
             &self.http_client,
@@ -873,6 +1473,83 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn set_token_trust(
+        &self,
+        token_id: String,
+        trust: Option<TokenTrust>,
+    ) -> Result<(), Self::Error> {
+        WalletRpcClient::set_token_trust(&self.http_client, token_id.into(), trust)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn list_token_trust(&self) -> Result<Vec<TokenTrustInfo>, Self::Error> {
+        WalletRpcClient::list_token_trust(&self.http_client)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn add_payee(
+        &self,
+        name: String,
+        address: String,
+        default_token: Option<String>,
+        notes: Option<String>,
+    ) -> Result<(), Self::Error> {
+        WalletRpcClient::add_payee(
+            &self.http_client,
+            name,
+            address.into(),
+            default_token.map(Into::into),
+            notes,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn remove_payee(&self, name: String) -> Result<(), Self::Error> {
+        WalletRpcClient::remove_payee(&self.http_client, name)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn list_payees(&self) -> Result<Vec<PayeeInfo>, Self::Error> {
+        WalletRpcClient::list_payees(&self.http_client)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn export_payees(&self) -> Result<String, Self::Error> {
+        WalletRpcClient::export_payees(&self.http_client)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn import_payees(&self, address_book: String) -> Result<usize, Self::Error> {
+        WalletRpcClient::import_payees(&self.http_client, address_book)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn send_to_payee(
+        &self,
+        account_index: U31,
+        name: String,
+        amount: DecimalAmount,
+        config: ControllerConfig,
+    ) -> Result<NewTransaction, Self::Error> {
+        let options = TransactionOptions::from_controller_config(&config);
+        WalletRpcClient::send_to_payee(
+            &self.http_client,
+            account_index.into(),
+            name,
+            amount.into(),
+            options,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn make_tx_to_send_tokens_from_multisig_address(
         &self,
         account_index: U31,
@@ -1050,6 +1727,42 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn transaction_broadcast_status(
+        &self,
+        transaction_id: Id<Transaction>,
+    ) -> Result<Option<BroadcastQueueEntryInfo>, Self::Error> {
+        WalletRpcClient::transaction_broadcast_status(
+            &self.http_client,
+            HexEncoded::new(transaction_id),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn transaction_broadcast_queue(
+        &self,
+    ) -> Result<Vec<BroadcastQueueEntryInfo>, Self::Error> {
+        WalletRpcClient::transaction_broadcast_queue(&self.http_client)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn set_transaction_broadcast_policy(
+        &self,
+        transaction_id: Id<Transaction>,
+        max_failed_attempts: u32,
+        deadline: Option<BlockTimestamp>,
+    ) -> Result<(), Self::Error> {
+        WalletRpcClient::set_transaction_broadcast_policy(
+            &self.http_client,
+            HexEncoded::new(transaction_id),
+            max_failed_attempts,
+            deadline,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn list_pending_transactions(
         &self,
         account_index: U31,
@@ -1064,7 +1777,7 @@ impl WalletInterface for ClientWalletRpc {
         account_index: U31,
         address: Option<String>,
         limit: usize,
-    ) -> Result<Vec<TxInfo>, Self::Error> {
+    ) -> Result<Vec<MainchainTxInfo>, Self::Error> {
         WalletRpcClient::list_transactions_by_address(
             &self.http_client,
             account_index.into(),
@@ -1075,6 +1788,22 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn list_transactions(
+        &self,
+        account_index: U31,
+        address: Option<String>,
+        page: PageRequest,
+    ) -> Result<TransactionPage, Self::Error> {
+        WalletRpcClient::list_transactions(
+            &self.http_client,
+            account_index.into(),
+            address.map(Into::into),
+            Some(page),
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn get_transaction(
         &self,
         account_index: U31,
@@ -1085,6 +1814,30 @@ impl WalletInterface for ClientWalletRpc {
             .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn describe_transaction(
+        &self,
+        account_index: U31,
+        transaction_id: Id<Transaction>,
+    ) -> Result<TransactionDescription, Self::Error> {
+        WalletRpcClient::describe_transaction(
+            &self.http_client,
+            account_index.into(),
+            transaction_id,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
+    async fn export_transaction_history(
+        &self,
+        account_index: U31,
+        format: TransactionHistoryFormat,
+    ) -> Result<String, Self::Error> {
+        WalletRpcClient::export_transaction_history(&self.http_client, account_index.into(), format)
+            .await
+            .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn get_raw_transaction(
         &self,
         account_index: U31,
@@ -1151,6 +1904,23 @@ impl WalletInterface for ClientWalletRpc {
         .map_err(WalletRpcError::ResponseError)
     }
 
+    async fn sign_transaction_package(
+        &self,
+        account_index: U31,
+        package: String,
+        config: ControllerConfig,
+    ) -> Result<MaybeSignedTransaction, Self::Error> {
+        let options = TransactionOptions::from_controller_config(&config);
+        ColdWalletRpcClient::sign_transaction_package(
+            &self.http_client,
+            account_index.into(),
+            package.parse()?,
+            options,
+        )
+        .await
+        .map_err(WalletRpcError::ResponseError)
+    }
+
     async fn sign_challenge(
         &self,
         account_index: U31,
@@ -1222,6 +1992,7 @@ impl WalletInterface for ClientWalletRpc {
         inputs: Vec<UtxoOutPoint>,
         outputs: Vec<TxOutput>,
         htlc_secrets: Option<Vec<Option<String>>>,
+        sighash_types: Option<Vec<RpcSigHashType>>,
         only_transaction: bool,
     ) -> Result<ComposedTransaction, Self::Error> {
         let inputs = inputs.into_iter().map(Into::into).collect();
@@ -1233,6 +2004,7 @@ impl WalletInterface for ClientWalletRpc {
             inputs,
             outputs,
             htlc_secrets,
+            sighash_types,
             only_transaction,
         )
         .await
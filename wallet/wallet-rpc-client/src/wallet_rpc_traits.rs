@@ -23,24 +23,32 @@ use common::{
     },
     primitives::{BlockHeight, DecimalAmount, Id},
 };
-use crypto::key::{hdkd::u31::U31, PrivateKey};
+use crypto::key::{extended::ExtendedPublicKey, hdkd::u31::U31, PrivateKey};
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, PeerId};
 use serialization::hex_encoded::HexEncoded;
 use utils_networking::IpOrSocketAddress;
-use wallet::account::TxInfo;
 use wallet_controller::{
-    types::{CreatedBlockInfo, GenericTokenTransfer, SeedWithPassPhrase, WalletInfo},
+    types::{
+        CreatedBlockInfo, GenericTokenTransfer, MainchainTxInfo, SeedWithPassPhrase, SyncStatus,
+        TransactionDescription, WalletInfo,
+    },
     ConnectedPeer, ControllerConfig, UtxoState, UtxoType,
 };
 use wallet_rpc_lib::types::{
-    AddressInfo, AddressWithUsageInfo, Balances, BlockInfo, ComposedTransaction, CreatedWallet,
-    DelegationInfo, LegacyVrfPublicKeyInfo, NewAccountInfo, NewDelegation, NewTransaction,
-    NftMetadata, NodeVersion, PoolInfo, PublicKeyInfo, RpcHashedTimelockContract,
-    RpcInspectTransaction, RpcSignatureStatus, RpcStandaloneAddresses, RpcTokenId,
-    SendTokensFromMultisigAddressResult, StakePoolBalance, StakingStatus,
-    StandaloneAddressWithDetails, TokenMetadata, TxOptionsOverrides, VrfPublicKeyInfo,
+    AddressInfo, AddressPage, AddressWithUsageInfo, Balances, BlockInfo, BroadcastQueueEntryInfo,
+    ComposedTransaction, CreatedWallet, DelegationInfo, LegacyVrfPublicKeyInfo,
+    MaybeSignedTransaction, NewAccountInfo, NewDelegation, NewTransaction, NftMetadata,
+    NodeVersion, PageRequest, PayeeInfo, PayoutsReport, PoolInfo, PublicKeyInfo, Recipient,
+    RpcAmountOut, RpcChangePolicy, RpcHashedTimelockContract, RpcInspectTransaction,
+    RpcSigHashType, RpcSignatureStatus, RpcStandaloneAddresses, RpcTokenId, ScheduledPaymentInfo,
+    SendTokensFromMultisigAddressResult, SentTransaction, SigningLogEntryInfo, StakePoolBalance,
+    StakingEstimate, StakingStatus, StandaloneAddressWithDetails, TokenAuthorityRotationInfo,
+    TokenMetadata, TokenTrustInfo, TransactionHistoryFormat, TransactionPage, TxOptionsOverrides,
+    UtxoPage, UtxoSortKey, UtxoVerificationInfo, VrfPublicKeyInfo, WatchedPoolInfo,
+};
+use wallet_types::{
+    multisig_account::MultisigAccountDescriptor, token_trust::TokenTrust, with_locked::WithLocked,
 };
-use wallet_types::with_locked::WithLocked;
 
 pub enum PartialOrSignedTx {
     Partial(PartiallySignedTransaction),
@@ -98,6 +106,25 @@ pub trait WalletInterface {
         i_know_what_i_am_doing: bool,
     ) -> Result<(), Self::Error>;
 
+    async fn export_watch_only(
+        &self,
+        passphrase: String,
+        since_height: Option<BlockHeight>,
+    ) -> Result<String, Self::Error>;
+
+    async fn create_wallet_from_watch_only_export(
+        &self,
+        path: PathBuf,
+        data: String,
+        passphrase: String,
+    ) -> Result<(), Self::Error>;
+
+    async fn apply_watch_only_update(
+        &self,
+        data: String,
+        passphrase: String,
+    ) -> Result<(), Self::Error>;
+
     async fn encrypt_private_keys(&self, password: String) -> Result<(), Self::Error>;
 
     async fn remove_private_key_encryption(&self) -> Result<(), Self::Error>;
@@ -108,6 +135,20 @@ pub trait WalletInterface {
 
     async fn best_block(&self) -> Result<BlockInfo, Self::Error>;
 
+    async fn sync_status(&self) -> Result<SyncStatus, Self::Error>;
+
+    async fn verify_utxos(
+        &self,
+        sample_size: Option<NonZeroUsize>,
+        repair: bool,
+    ) -> Result<UtxoVerificationInfo, Self::Error>;
+
+    async fn export_signing_log(
+        &self,
+        from: Option<BlockTimestamp>,
+        to: Option<BlockTimestamp>,
+    ) -> Result<Vec<SigningLogEntryInfo>, Self::Error>;
+
     async fn create_account(&self, name: Option<String>) -> Result<NewAccountInfo, Self::Error>;
 
     async fn rename_account(
@@ -116,6 +157,13 @@ pub trait WalletInterface {
         name: Option<String>,
     ) -> Result<NewAccountInfo, Self::Error>;
 
+    async fn set_address_label(
+        &self,
+        account_index: U31,
+        address: String,
+        label: Option<String>,
+    ) -> Result<(), Self::Error>;
+
     async fn standalone_address_label_rename(
         &self,
         account_index: U31,
@@ -131,6 +179,12 @@ pub trait WalletInterface {
         no_rescan: bool,
     ) -> Result<(), Self::Error>;
 
+    async fn remove_standalone_address(
+        &self,
+        account_index: U31,
+        address: String,
+    ) -> Result<(), Self::Error>;
+
     async fn add_standalone_private_key(
         &self,
         account_index: U31,
@@ -139,6 +193,21 @@ pub trait WalletInterface {
         no_rescan: bool,
     ) -> Result<(), Self::Error>;
 
+    async fn import_standalone_private_key(
+        &self,
+        account_index: U31,
+        private_key: String,
+        label: Option<String>,
+        no_rescan: bool,
+    ) -> Result<(), Self::Error>;
+
+    async fn export_private_key(
+        &self,
+        account_index: U31,
+        address: String,
+        confirm: bool,
+    ) -> Result<String, Self::Error>;
+
     async fn add_standalone_multisig(
         &self,
         account_index: U31,
@@ -148,11 +217,53 @@ pub trait WalletInterface {
         no_rescan: bool,
     ) -> Result<String, Self::Error>;
 
+    async fn account_extended_public_key(
+        &self,
+        account_index: U31,
+    ) -> Result<HexEncoded<ExtendedPublicKey>, Self::Error>;
+
+    async fn export_account_extended_public_key(
+        &self,
+        account_index: U31,
+    ) -> Result<String, Self::Error>;
+
+    async fn import_extended_public_key(
+        &self,
+        account_index: U31,
+        extended_public_key: String,
+        address_count: u32,
+        label: Option<String>,
+        no_rescan: bool,
+    ) -> Result<Vec<String>, Self::Error>;
+
+    async fn create_multisig_account_descriptor(
+        &self,
+        account_index: U31,
+        min_required_signatures: u8,
+        cosigner_keys: Vec<HexEncoded<ExtendedPublicKey>>,
+    ) -> Result<HexEncoded<MultisigAccountDescriptor>, Self::Error>;
+
+    async fn import_multisig_account(
+        &self,
+        account_index: U31,
+        descriptor: HexEncoded<MultisigAccountDescriptor>,
+        address_count: u32,
+        label: Option<String>,
+        no_rescan: bool,
+    ) -> Result<Vec<String>, Self::Error>;
+
     async fn get_issued_addresses(
         &self,
         options: U31,
     ) -> Result<Vec<AddressWithUsageInfo>, Self::Error>;
 
+    async fn list_issued_addresses(
+        &self,
+        account_index: U31,
+        used: Option<bool>,
+        page: PageRequest,
+    ) -> Result<AddressPage, Self::Error>;
+
     async fn get_standalone_addresses(
         &self,
         account_index: U31,
@@ -187,6 +298,15 @@ pub trait WalletInterface {
         with_locked: WithLocked,
     ) -> Result<Vec<serde_json::Value>, Self::Error>;
 
+    async fn get_standalone_address_utxos(
+        &self,
+        account_index: U31,
+        address: String,
+        utxo_types: Vec<UtxoType>,
+        utxo_states: Vec<UtxoState>,
+        with_locked: WithLocked,
+    ) -> Result<Vec<serde_json::Value>, Self::Error>;
+
     async fn get_utxos(
         &self,
         account_index: U31,
@@ -195,6 +315,59 @@ pub trait WalletInterface {
         with_locked: WithLocked,
     ) -> Result<Vec<serde_json::Value>, Self::Error>;
 
+    async fn list_utxos(
+        &self,
+        account_index: U31,
+        utxo_types: Vec<UtxoType>,
+        utxo_states: Vec<UtxoState>,
+        with_locked: WithLocked,
+        sort_by: UtxoSortKey,
+        page: PageRequest,
+    ) -> Result<UtxoPage, Self::Error>;
+
+    async fn set_dust_protection(
+        &self,
+        account_index: U31,
+        min_coin_amount: Option<DecimalAmount>,
+        flag_unknown_tokens: bool,
+    ) -> Result<(), Self::Error>;
+
+    async fn set_change_policy(
+        &self,
+        account_index: U31,
+        change_policy: RpcChangePolicy,
+    ) -> Result<(), Self::Error>;
+
+    async fn get_change_policy(&self, account_index: U31) -> Result<RpcChangePolicy, Self::Error>;
+
+    async fn get_dust_utxos(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<serde_json::Value>, Self::Error>;
+
+    async fn release_dust_utxos(
+        &self,
+        account_index: U31,
+        utxos: Vec<UtxoOutPoint>,
+    ) -> Result<(), Self::Error>;
+
+    async fn freeze_utxos(
+        &self,
+        account_index: U31,
+        utxos: Vec<UtxoOutPoint>,
+    ) -> Result<(), Self::Error>;
+
+    async fn unfreeze_utxos(
+        &self,
+        account_index: U31,
+        utxos: Vec<UtxoOutPoint>,
+    ) -> Result<(), Self::Error>;
+
+    async fn get_frozen_utxos(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<serde_json::Value>, Self::Error>;
+
     async fn submit_raw_transaction(
         &self,
         tx: HexEncoded<SignedTransaction>,
@@ -235,6 +408,7 @@ pub trait WalletInterface {
         inputs: Vec<UtxoOutPoint>,
         outputs: Vec<TxOutput>,
         htlc_secrets: Option<Vec<Option<String>>>,
+        sighash_types: Option<Vec<RpcSigHashType>>,
         only_transaction: bool,
     ) -> Result<ComposedTransaction, Self::Error>;
 
@@ -245,7 +419,22 @@ pub trait WalletInterface {
         amount: DecimalAmount,
         selected_utxos: Vec<UtxoOutPoint>,
         config: ControllerConfig,
-    ) -> Result<NewTransaction, Self::Error>;
+    ) -> Result<SentTransaction, Self::Error>;
+
+    async fn send_to_many(
+        &self,
+        account_index: U31,
+        recipients: Vec<Recipient>,
+        config: ControllerConfig,
+    ) -> Result<SentTransaction, Self::Error>;
+
+    async fn send_payouts_csv(
+        &self,
+        account_index: U31,
+        csv: String,
+        max_outputs_per_tx: Option<usize>,
+        config: ControllerConfig,
+    ) -> Result<PayoutsReport, Self::Error>;
 
     async fn sweep_addresses(
         &self,
@@ -255,6 +444,30 @@ pub trait WalletInterface {
         config: ControllerConfig,
     ) -> Result<NewTransaction, Self::Error>;
 
+    async fn estimate_max_sendable(
+        &self,
+        account_index: U31,
+        address: String,
+        token_id: Option<String>,
+    ) -> Result<RpcAmountOut, Self::Error>;
+
+    async fn send_all(
+        &self,
+        account_index: U31,
+        address: String,
+        token_id: Option<String>,
+        config: ControllerConfig,
+    ) -> Result<NewTransaction, Self::Error>;
+
+    async fn transfer_between_accounts(
+        &self,
+        account_index: U31,
+        to_account: U31,
+        amount: DecimalAmount,
+        token_id: Option<String>,
+        config: ControllerConfig,
+    ) -> Result<NewTransaction, Self::Error>;
+
     async fn sweep_delegation(
         &self,
         account_index: U31,
@@ -273,6 +486,22 @@ pub trait WalletInterface {
         config: ControllerConfig,
     ) -> Result<ComposedTransaction, Self::Error>;
 
+    async fn create_transaction_package(
+        &self,
+        account_index: U31,
+        outputs: Vec<TxOutput>,
+        selected_utxos: Vec<UtxoOutPoint>,
+        change_address: Option<String>,
+        config: ControllerConfig,
+    ) -> Result<ComposedTransaction, Self::Error>;
+
+    async fn submit_transaction_package(
+        &self,
+        account_index: U31,
+        package: String,
+        config: ControllerConfig,
+    ) -> Result<NewTransaction, Self::Error>;
+
     async fn transaction_inspect(
         &self,
         transaction: String,
@@ -344,6 +573,55 @@ pub trait WalletInterface {
 
     async fn stake_pool_balance(&self, pool_id: String) -> Result<StakePoolBalance, Self::Error>;
 
+    async fn staking_calculator(
+        &self,
+        pledge: DecimalAmount,
+        pool_balance: DecimalAmount,
+    ) -> Result<StakingEstimate, Self::Error>;
+
+    async fn add_watched_pool(
+        &self,
+        account_index: U31,
+        pool_id: String,
+        label: Option<String>,
+        decommission_address: Option<String>,
+        no_rescan: bool,
+    ) -> Result<(), Self::Error>;
+
+    async fn remove_watched_pool(
+        &self,
+        account_index: U31,
+        pool_id: String,
+    ) -> Result<(), Self::Error>;
+
+    async fn list_watched_pools(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<WatchedPoolInfo>, Self::Error>;
+
+    #[allow(clippy::too_many_arguments)]
+    async fn add_scheduled_payment(
+        &self,
+        account_index: U31,
+        address: String,
+        amount: DecimalAmount,
+        token_id: Option<String>,
+        interval_secs: u64,
+        first_run: Option<BlockTimestamp>,
+        label: Option<String>,
+    ) -> Result<u64, Self::Error>;
+
+    async fn cancel_scheduled_payment(
+        &self,
+        account_index: U31,
+        payment_id: u64,
+    ) -> Result<(), Self::Error>;
+
+    async fn list_scheduled_payments(
+        &self,
+        account_index: U31,
+    ) -> Result<Vec<ScheduledPaymentInfo>, Self::Error>;
+
     async fn list_delegation_ids(
         &self,
         account_index: U31,
@@ -391,6 +669,16 @@ pub trait WalletInterface {
         config: ControllerConfig,
     ) -> Result<NewTransaction, Self::Error>;
 
+    async fn rotate_token_authority(
+        &self,
+        account_index: U31,
+        token_id: String,
+        address: String,
+        dry_run: bool,
+        force: bool,
+        config: ControllerConfig,
+    ) -> Result<TokenAuthorityRotationInfo, Self::Error>;
+
     async fn change_token_metadata_uri(
         &self,
         account_index: U31,
@@ -445,6 +733,38 @@ pub trait WalletInterface {
         address: String,
         amount: DecimalAmount,
         config: ControllerConfig,
+    ) -> Result<SentTransaction, Self::Error>;
+
+    async fn set_token_trust(
+        &self,
+        token_id: String,
+        trust: Option<TokenTrust>,
+    ) -> Result<(), Self::Error>;
+
+    async fn list_token_trust(&self) -> Result<Vec<TokenTrustInfo>, Self::Error>;
+
+    async fn add_payee(
+        &self,
+        name: String,
+        address: String,
+        default_token: Option<String>,
+        notes: Option<String>,
+    ) -> Result<(), Self::Error>;
+
+    async fn remove_payee(&self, name: String) -> Result<(), Self::Error>;
+
+    async fn list_payees(&self) -> Result<Vec<PayeeInfo>, Self::Error>;
+
+    async fn export_payees(&self) -> Result<String, Self::Error>;
+
+    async fn import_payees(&self, address_book: String) -> Result<usize, Self::Error>;
+
+    async fn send_to_payee(
+        &self,
+        account_index: U31,
+        name: String,
+        amount: DecimalAmount,
+        config: ControllerConfig,
     ) -> Result<NewTransaction, Self::Error>;
 
     async fn make_tx_to_send_tokens_from_multisig_address(
@@ -523,12 +843,35 @@ pub trait WalletInterface {
         account_index: U31,
     ) -> Result<Vec<Id<Transaction>>, Self::Error>;
 
+    async fn transaction_broadcast_status(
+        &self,
+        transaction_id: Id<Transaction>,
+    ) -> Result<Option<BroadcastQueueEntryInfo>, Self::Error>;
+
+    async fn transaction_broadcast_queue(
+        &self,
+    ) -> Result<Vec<BroadcastQueueEntryInfo>, Self::Error>;
+
+    async fn set_transaction_broadcast_policy(
+        &self,
+        transaction_id: Id<Transaction>,
+        max_failed_attempts: u32,
+        deadline: Option<BlockTimestamp>,
+    ) -> Result<(), Self::Error>;
+
     async fn list_transactions_by_address(
         &self,
         account_index: U31,
         address: Option<String>,
         limit: usize,
-    ) -> Result<Vec<TxInfo>, Self::Error>;
+    ) -> Result<Vec<MainchainTxInfo>, Self::Error>;
+
+    async fn list_transactions(
+        &self,
+        account_index: U31,
+        address: Option<String>,
+        page: PageRequest,
+    ) -> Result<TransactionPage, Self::Error>;
 
     async fn get_transaction(
         &self,
@@ -536,6 +879,18 @@ pub trait WalletInterface {
         transaction_id: Id<Transaction>,
     ) -> Result<serde_json::Value, Self::Error>;
 
+    async fn describe_transaction(
+        &self,
+        account_index: U31,
+        transaction_id: Id<Transaction>,
+    ) -> Result<TransactionDescription, Self::Error>;
+
+    async fn export_transaction_history(
+        &self,
+        account_index: U31,
+        format: TransactionHistoryFormat,
+    ) -> Result<String, Self::Error>;
+
     async fn get_raw_transaction(
         &self,
         account_index: U31,
@@ -555,6 +910,13 @@ pub trait WalletInterface {
         config: ControllerConfig,
     ) -> Result<SignRawTransactionResult, Self::Error>;
 
+    async fn sign_transaction_package(
+        &self,
+        account_index: U31,
+        package: String,
+        config: ControllerConfig,
+    ) -> Result<MaybeSignedTransaction, Self::Error>;
+
     async fn node_best_block_id(&self) -> Result<Id<GenBlock>, Self::Error>;
 
     async fn node_best_block_height(&self) -> Result<BlockHeight, Self::Error>;
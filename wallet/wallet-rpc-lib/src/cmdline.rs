@@ -103,13 +103,19 @@ pub struct WalletRpcDaemonChainArgs {
     start_staking_for_account: Vec<U31>,
 
     /// use the wallet without a connection to a node
-    #[arg(long, conflicts_with_all(["start_staking_for_account", "node_rpc_address", "node_rpc_cookie_file", "node_rpc_username", "node_rpc_password"]))]
+    #[arg(long, conflicts_with_all(["start_staking_for_account", "node_rpc_address", "node_rpc_fallback_address", "node_rpc_cookie_file", "node_rpc_username", "node_rpc_password"]))]
     pub cold_wallet: bool,
 
     /// RPC address of the node to connect to
     #[arg(long, value_name("ADDR"))]
     node_rpc_address: Option<NetworkAddressWithPort>,
 
+    /// RPC address of a node to switch to if the node in use becomes unreachable or falls behind.
+    /// Can be specified multiple times, the nodes are tried in the given order.
+    /// The nodes must accept the same RPC credentials.
+    #[arg(long, value_name("ADDR"))]
+    node_rpc_fallback_address: Vec<NetworkAddressWithPort>,
+
     /// Node RPC authentication cookie file path
     #[arg(
         long,
@@ -164,6 +170,7 @@ impl WalletRpcDaemonChainArgs {
             rpc_bind_address,
             start_staking_for_account,
             node_rpc_address,
+            node_rpc_fallback_address,
             node_rpc_cookie_file,
             node_rpc_username,
             node_rpc_password,
@@ -212,6 +219,9 @@ impl WalletRpcDaemonChainArgs {
                         WalletServiceConfig::with_node_rpc_address,
                         node_rpc_address.map(|addr| addr.to_string()),
                     )
+                    .with_node_rpc_fallback_addresses(
+                        node_rpc_fallback_address.iter().map(ToString::to_string).collect(),
+                    )
                     .with_node_credentials(node_credentials)
             }
         };
@@ -29,6 +29,10 @@ pub enum NodeRpc {
         /// RPC address of the node to connect to
         node_rpc_address: Option<String>,
 
+        /// RPC addresses of the nodes to switch to, in the given order,
+        /// if the node in use becomes unusable
+        node_rpc_fallback_addresses: Vec<String>,
+
         /// Node RPC authentication
         node_auth_data: RpcAuthData,
     },
@@ -81,13 +85,36 @@ impl WalletServiceConfig {
         self.node_rpc = match self.node_rpc {
             NodeRpc::ColdWallet => NodeRpc::HotWallet {
                 node_rpc_address: Some(node_rpc_address),
+                node_rpc_fallback_addresses: Vec::new(),
                 node_auth_data: RpcAuthData::None,
             },
             NodeRpc::HotWallet {
                 node_rpc_address: _,
+                node_rpc_fallback_addresses,
                 node_auth_data,
             } => NodeRpc::HotWallet {
                 node_rpc_address: Some(node_rpc_address),
+                node_rpc_fallback_addresses,
+                node_auth_data,
+            },
+        };
+        self
+    }
+
+    pub fn with_node_rpc_fallback_addresses(mut self, fallback_addresses: Vec<String>) -> Self {
+        self.node_rpc = match self.node_rpc {
+            NodeRpc::ColdWallet => NodeRpc::HotWallet {
+                node_rpc_address: None,
+                node_rpc_fallback_addresses: fallback_addresses,
+                node_auth_data: RpcAuthData::None,
+            },
+            NodeRpc::HotWallet {
+                node_rpc_address,
+                node_rpc_fallback_addresses: _,
+                node_auth_data,
+            } => NodeRpc::HotWallet {
+                node_rpc_address,
+                node_rpc_fallback_addresses: fallback_addresses,
                 node_auth_data,
             },
         };
@@ -106,13 +133,16 @@ impl WalletServiceConfig {
         self.node_rpc = match self.node_rpc {
             NodeRpc::ColdWallet => NodeRpc::HotWallet {
                 node_rpc_address: None,
+                node_rpc_fallback_addresses: Vec::new(),
                 node_auth_data: creds,
             },
             NodeRpc::HotWallet {
                 node_rpc_address,
+                node_rpc_fallback_addresses,
                 node_auth_data: _,
             } => NodeRpc::HotWallet {
                 node_rpc_address,
+                node_rpc_fallback_addresses,
                 node_auth_data: creds,
             },
         };
@@ -55,6 +55,7 @@ pub async fn run(
     match wallet_config.node_rpc.clone() {
         NodeRpc::HotWallet {
             node_rpc_address,
+            node_rpc_fallback_addresses,
             node_auth_data,
         } => {
             let rpc_address = {
@@ -66,9 +67,10 @@ pub async fn run(
                 };
                 node_rpc_address.unwrap_or_else(default_addr)
             };
-            let node_rpc = wallet_controller::make_rpc_client(
+            let node_rpc = wallet_controller::make_rpc_client_with_fallbacks(
                 wallet_config.chain_config.clone(),
                 rpc_address,
+                node_rpc_fallback_addresses,
                 node_auth_data,
             )
             .await
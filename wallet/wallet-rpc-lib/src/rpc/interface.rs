@@ -24,29 +24,39 @@ use common::{
     },
     primitives::{BlockHeight, Id},
 };
-use crypto::key::PrivateKey;
+use crypto::key::{extended::ExtendedPublicKey, PrivateKey};
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress};
 use rpc::types::RpcHexString;
-use wallet::account::TxInfo;
 use wallet_controller::{
-    types::{BlockInfo, CreatedBlockInfo, GenericTokenTransfer, SeedWithPassPhrase, WalletInfo},
+    types::{
+        BlockInfo, CreatedBlockInfo, GenericTokenTransfer, MainchainTxInfo, SeedWithPassPhrase,
+        SyncStatus, TransactionDescription, WalletInfo,
+    },
     ConnectedPeer,
 };
-use wallet_types::with_locked::WithLocked;
+use wallet_types::{
+    multisig_account::MultisigAccountDescriptor, token_trust::TokenTrust, with_locked::WithLocked,
+};
 
 use crate::types::{
-    AccountArg, AddressInfo, AddressWithUsageInfo, Balances, ChainInfo, ComposedTransaction,
-    CreatedWallet, DelegationInfo, HexEncoded, JsonValue, LegacyVrfPublicKeyInfo,
-    MaybeSignedTransaction, NewAccountInfo, NewDelegation, NewTransaction, NftMetadata,
-    NodeVersion, PoolInfo, PublicKeyInfo, RpcAmountIn, RpcHashedTimelockContract,
-    RpcInspectTransaction, RpcStandaloneAddresses, RpcTokenId, RpcUtxoOutpoint, RpcUtxoState,
-    RpcUtxoType, SendTokensFromMultisigAddressResult, StakePoolBalance, StakingStatus,
-    StandaloneAddressWithDetails, TokenMetadata, TransactionOptions, TxOptionsOverrides,
-    VrfPublicKeyInfo,
+    AccountArg, AddressInfo, AddressPage, AddressWithUsageInfo, Balances, BroadcastQueueEntryInfo,
+    ChainInfo, ComposedTransaction, CreatedWallet, DelegationInfo, HexEncoded, JsonValue,
+    LegacyVrfPublicKeyInfo, MaybeSignedTransaction, NewAccountInfo, NewDelegation, NewTransaction,
+    NftMetadata, NodeVersion, PageRequest, PayeeInfo, PayoutsReport, PoolInfo,
+    PoolStakingRewardsInfo, PublicKeyInfo, Recipient, RpcAmountIn, RpcAmountOut, RpcChangePolicy,
+    RpcHashedTimelockContract, RpcInspectTransaction, RpcSigHashType, RpcStandaloneAddresses,
+    RpcTokenId, RpcUtxoOutpoint, RpcUtxoState, RpcUtxoType, ScheduledPaymentInfo,
+    SendTokensFromMultisigAddressResult, SentTransaction, SigningLogEntryInfo, StakePoolBalance,
+    StakingEstimate, StakingStatus, StandaloneAddressWithDetails, TokenAuthorityRotationInfo,
+    TokenMetadata, TokenTrustInfo, TransactionHistoryFormat, TransactionOptions, TransactionPage,
+    TxOptionsOverrides, UtxoPage, UtxoSortKey, UtxoVerificationInfo, VrfPublicKeyInfo,
+    WatchedPoolInfo,
 };
 
 #[rpc::rpc(server)]
 trait WalletEventsRpc {
+    /// Subscribe to wallet events: transactions being added or updated, balance changes
+    /// and blocks produced by the pools of the wallet.
     #[subscription(name = "subscribe_wallet_events", item = Event)]
     async fn subscribe_wallet_events(&self) -> rpc::subscription::Reply;
 }
@@ -127,17 +137,63 @@ trait ColdWalletRpc {
         i_know_what_i_am_doing: bool,
     ) -> rpc::RpcResult<()>;
 
+    /// Export the public data of the wallet (the accounts' public keys, labels and transactions,
+    /// but no private keys), encrypted with the passphrase.
+    /// The export can be used to create a watch-only replica of this wallet on another device.
+    /// If `since_height` is specified, only the transactions confirmed above that height are
+    /// exported, which can be used to update an existing replica.
+    #[method(name = "wallet_export_watch_only")]
+    async fn export_watch_only(
+        &self,
+        passphrase: String,
+        since_height: Option<BlockHeight>,
+    ) -> rpc::RpcResult<RpcHexString>;
+
+    /// Create a new watch-only wallet from the data produced by `wallet_export_watch_only`.
+    /// The new wallet can track the balances and transactions of the original wallet,
+    /// but it has no private keys, so it cannot sign transactions.
+    #[method(name = "wallet_create_from_watch_only_export")]
+    async fn create_wallet_from_watch_only_export(
+        &self,
+        path: String,
+        data: RpcHexString,
+        passphrase: String,
+    ) -> rpc::RpcResult<()>;
+
+    /// Update the loaded watch-only wallet with a newer export of the original wallet.
+    /// An incremental export can only be applied if this wallet is synced at least up to its
+    /// starting height.
+    #[method(name = "wallet_apply_watch_only_update")]
+    async fn apply_watch_only_update(
+        &self,
+        data: RpcHexString,
+        passphrase: String,
+    ) -> rpc::RpcResult<()>;
+
     /// Show receive-addresses with their usage state.
     /// Note that whether an address is used isn't based on the wallet,
     /// but on the blockchain. So if an address is used in a transaction,
     /// it will be marked as used only when the transaction is included
     /// in a block.
+    ///
+    /// Deprecated: use `address_list`, which returns the addresses page by page.
+    /// This method will be removed in a future release.
     #[method(name = "address_show")]
     async fn get_issued_addresses(
         &self,
         account: AccountArg,
     ) -> rpc::RpcResult<Vec<AddressWithUsageInfo>>;
 
+    /// List receive-addresses with their usage state page by page, sorted by their index.
+    /// Optionally, only the used or only the unused addresses are listed.
+    #[method(name = "address_list")]
+    async fn list_issued_addresses(
+        &self,
+        account: AccountArg,
+        used: Option<bool>,
+        page: Option<PageRequest>,
+    ) -> rpc::RpcResult<AddressPage>;
+
     /// Show standalone added addresses with their labels.
     #[method(name = "standalone_address_show")]
     async fn get_standalone_addresses(
@@ -177,6 +233,7 @@ trait ColdWalletRpc {
 
     /// Shows the legacy VRF key that uses an abandoned derivation mechanism.
     /// This will not be used for new pools and should be avoided
+    /// The pools of this account that still use the key are listed along with it.
     #[method(name = "staking_show_legacy_vrf_key")]
     async fn get_legacy_vrf_public_key(
         &self,
@@ -187,6 +244,7 @@ trait ColdWalletRpc {
     /// These keys are generated when pools are created.
     /// VRF keys are used as a trustless mechanism to ensure the randomness of the staking process,
     /// where no one can control the possible outcomes, to ensure decentralization.
+    /// Each key is listed with its derivation index and the pools of this account that use it.
     #[method(name = "staking_show_vrf_public_keys")]
     async fn get_vrf_public_key(
         &self,
@@ -205,6 +263,18 @@ trait ColdWalletRpc {
         options: TransactionOptions,
     ) -> rpc::RpcResult<MaybeSignedTransaction>;
 
+    #[method(name = "account_sign_transaction_package")]
+    /// Signs the inputs of a transaction package created with transaction-package-create
+    /// by an online wallet. This doesn't need a connection to the node, so it can be used
+    /// on an air-gapped wallet. The result is the updated package encoded in hex, which can be
+    /// submitted with transaction-package-submit once all signatures are complete.
+    async fn sign_transaction_package(
+        &self,
+        account: AccountArg,
+        package: RpcHexString,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<MaybeSignedTransaction>;
+
     #[method(name = "challenge_sign_plain")]
     /// Signs a challenge with a private key corresponding to the provided address destination.
     async fn sign_challenge(
@@ -257,6 +327,36 @@ trait WalletRpc {
     #[method(name = "wallet_best_block")]
     async fn best_block(&self) -> rpc::RpcResult<BlockInfo>;
 
+    /// Show how up to date the wallet data is: the best block all the accounts are synced to,
+    /// when the wallet was last in sync with the node and whether a sync is in progress.
+    /// The other requests can be served during a long sync or rescan, and they return the data
+    /// as of the best synced block.
+    #[method(name = "wallet_sync_status")]
+    async fn sync_status(&self) -> rpc::RpcResult<SyncStatus>;
+
+    /// Check the outputs tracked by the wallet against the node's UTXO set and report the ones
+    /// the wallet considers unspent while the node doesn't have them, or the other way around.
+    /// If `sample_size` is set, only that many randomly chosen outputs are checked.
+    /// The wallet must be in sync with the node tip.
+    /// If `repair` is set and discrepancies are found, the blocks starting from the earliest
+    /// affected one are scanned again.
+    #[method(name = "wallet_verify_utxos")]
+    async fn verify_utxos(
+        &self,
+        sample_size: Option<NonZeroUsize>,
+        repair: bool,
+    ) -> rpc::RpcResult<UtxoVerificationInfo>;
+
+    /// Export the log of the transactions signed by this wallet, optionally limited to those
+    /// signed between the `from` and `to` timestamps (inclusive).
+    /// The log is stored encrypted, so the wallet must be unlocked.
+    #[method(name = "wallet_export_signing_log")]
+    async fn export_signing_log(
+        &self,
+        from: Option<BlockTimestamp>,
+        to: Option<BlockTimestamp>,
+    ) -> rpc::RpcResult<Vec<SigningLogEntryInfo>>;
+
     /// Creates a new account with an optional name.
     /// Returns an error if the last created account does not have a transaction history.
     #[method(name = "account_create")]
@@ -271,6 +371,16 @@ trait WalletRpc {
         name: Option<String>,
     ) -> rpc::RpcResult<NewAccountInfo>;
 
+    /// Set the label of a receive-address issued by the selected account, e.g. to identify
+    /// the payments it is used for. Not specifying a label will remove the existing one.
+    #[method(name = "address_set_label")]
+    async fn set_address_label(
+        &self,
+        account: AccountArg,
+        address: RpcAddress<Destination>,
+        label: Option<String>,
+    ) -> rpc::RpcResult<()>;
+
     /// Add, rename or delete a label to an already added standalone address.
     /// Specifying a label will add or replace the existing one,
     /// and not specifying a label will remove the existing one.
@@ -292,6 +402,16 @@ trait WalletRpc {
         no_rescan: Option<bool>,
     ) -> rpc::RpcResult<()>;
 
+    /// Stop watching a standalone watch only address previously added to the selected account.
+    /// The transactions of the address already seen by the wallet are kept,
+    /// but its utxos are no longer reported.
+    #[method(name = "standalone_remove_watch_only_address")]
+    async fn remove_standalone_address(
+        &self,
+        account: AccountArg,
+        address: RpcAddress<Destination>,
+    ) -> rpc::RpcResult<()>;
+
     /// Add a new standalone private key not derived from the selected account's key chain to be watched
     #[method(name = "standalone_add_private_key_from_hex")]
     async fn add_standalone_private_key(
@@ -302,6 +422,28 @@ trait WalletRpc {
         no_rescan: Option<bool>,
     ) -> rpc::RpcResult<()>;
 
+    /// Import a standalone private key encoded in the bech32m format used by `address_export_private_key`.
+    /// The key is not derived from the selected account's key chain and its address is watched.
+    #[method(name = "standalone_import_private_key")]
+    async fn import_standalone_private_key(
+        &self,
+        account: AccountArg,
+        private_key: RpcAddress<PrivateKey>,
+        label: Option<String>,
+        no_rescan: Option<bool>,
+    ) -> rpc::RpcResult<()>;
+
+    /// Export the private key of an address belonging to the selected account, encoded in the bech32m format
+    /// with the network's private key prefix (e.g. `mprv` on mainnet).
+    /// The wallet must be unlocked and `confirm` must be set to true, as anyone who has the key can spend the funds.
+    #[method(name = "address_export_private_key")]
+    async fn export_private_key(
+        &self,
+        account: AccountArg,
+        address: RpcAddress<Destination>,
+        confirm: bool,
+    ) -> rpc::RpcResult<RpcAddress<PrivateKey>>;
+
     /// Add a new standalone multi signature address
     /// Use the `transaction_compose` command to use the new multisig address as input or output
     #[method(name = "standalone_add_multisig")]
@@ -324,6 +466,73 @@ trait WalletRpc {
         with_locked: Option<WithLocked>,
     ) -> rpc::RpcResult<Vec<JsonValue>>;
 
+    /// Lists all the utxos of a standalone address watched by the selected account
+    #[method(name = "standalone_address_utxos")]
+    async fn get_standalone_address_utxos(
+        &self,
+        account: AccountArg,
+        address: RpcAddress<Destination>,
+        utxo_types: Vec<RpcUtxoType>,
+        utxo_states: Vec<RpcUtxoState>,
+        with_locked: Option<WithLocked>,
+    ) -> rpc::RpcResult<Vec<JsonValue>>;
+
+    /// Get the extended public key of the selected account.
+    /// It can be shared with other wallets to create a multisig account together.
+    #[method(name = "account_extended_public_key")]
+    async fn account_extended_public_key(
+        &self,
+        account: AccountArg,
+    ) -> rpc::RpcResult<HexEncoded<ExtendedPublicKey>>;
+
+    /// Export the extended public key of the selected account, encoded in the bech32m format
+    /// with the network's extended public key prefix (e.g. `mxpub` on mainnet).
+    #[method(name = "account_export_extended_public_key")]
+    async fn export_account_extended_public_key(
+        &self,
+        account: AccountArg,
+    ) -> rpc::RpcResult<RpcAddress<ExtendedPublicKey>>;
+
+    /// Import an extended public key exported with `account_export_extended_public_key`.
+    /// The first `address_count` receiving addresses and the first `address_count` change addresses
+    /// derived from the key (at most 1000 each) are added as standalone watch-only addresses
+    /// and returned, the receiving addresses first.
+    #[method(name = "standalone_import_extended_public_key")]
+    async fn import_extended_public_key(
+        &self,
+        account: AccountArg,
+        extended_public_key: RpcAddress<ExtendedPublicKey>,
+        address_count: u32,
+        label: Option<String>,
+        no_rescan: Option<bool>,
+    ) -> rpc::RpcResult<Vec<String>>;
+
+    /// Create the descriptor of a multisig account shared by the selected account and the other
+    /// cosigners, specified by their account extended public keys.
+    /// The descriptor must be imported with `multisig_account_import` into the wallets of all
+    /// the cosigners. The result doesn't depend on the order of the keys, so it can also be
+    /// created independently by each of the cosigners.
+    #[method(name = "multisig_account_create_descriptor")]
+    async fn create_multisig_account_descriptor(
+        &self,
+        account: AccountArg,
+        min_required_signatures: u8,
+        cosigner_keys: Vec<HexEncoded<ExtendedPublicKey>>,
+    ) -> rpc::RpcResult<HexEncoded<MultisigAccountDescriptor>>;
+
+    /// Import a multisig account descriptor that the selected account is a cosigner of.
+    /// The first `address_count` receiving addresses of the multisig account are added
+    /// as standalone multisig addresses and returned.
+    #[method(name = "multisig_account_import")]
+    async fn import_multisig_account(
+        &self,
+        account: AccountArg,
+        descriptor: HexEncoded<MultisigAccountDescriptor>,
+        address_count: u32,
+        label: Option<String>,
+        no_rescan: Option<bool>,
+    ) -> rpc::RpcResult<Vec<String>>;
+
     /// Get the total balance in the selected account in this wallet. See available options to include more categories, like locked coins.
     #[method(name = "account_balance")]
     async fn get_balance(
@@ -334,9 +543,88 @@ trait WalletRpc {
     ) -> rpc::RpcResult<Balances>;
 
     /// Lists all the utxos owned by this account
+    ///
+    /// Deprecated: use `account_list_utxos`, which returns the utxos page by page.
+    /// This method will be removed in a future release.
     #[method(name = "account_utxos")]
     async fn get_utxos(&self, account: AccountArg) -> rpc::RpcResult<Vec<JsonValue>>;
 
+    /// Lists the utxos owned by this account page by page, filtered by their type and state.
+    /// The utxos are sorted by their outpoint, unless a different sort key is specified.
+    #[method(name = "account_list_utxos")]
+    async fn list_utxos(
+        &self,
+        account: AccountArg,
+        utxo_types: Vec<RpcUtxoType>,
+        utxo_states: Vec<RpcUtxoState>,
+        with_locked: Option<WithLocked>,
+        sort_by: Option<UtxoSortKey>,
+        page: Option<PageRequest>,
+    ) -> rpc::RpcResult<UtxoPage>;
+
+    /// Set the dust protection policy of the account. UTXOs received from other wallets
+    /// with a coin value below `min_coin_amount`, or holding tokens that this account has never
+    /// sent itself if `flag_unknown_tokens` is set, are not selected automatically when composing
+    /// transactions. This prevents outputs of dusting attacks from being merged with the user's
+    /// own funds. Flagged UTXOs can still be spent by selecting them explicitly.
+    #[method(name = "account_set_dust_protection")]
+    async fn set_dust_protection(
+        &self,
+        account: AccountArg,
+        min_coin_amount: Option<RpcAmountIn>,
+        flag_unknown_tokens: bool,
+    ) -> rpc::RpcResult<()>;
+
+    /// Set where the change of the transactions composed by this account is sent to.
+    /// By default a new change address of the account is used for each transaction.
+    /// With `ReuseInputAddress` the change goes back to the address of the first input paying
+    /// the fee, which avoids linking new addresses to the spent funds when consolidating.
+    /// With `ExternalAddress` the change is sent to the given address, e.g. of a cold wallet.
+    #[method(name = "account_set_change_policy")]
+    async fn set_change_policy(
+        &self,
+        account: AccountArg,
+        change_policy: RpcChangePolicy,
+    ) -> rpc::RpcResult<()>;
+
+    /// Get where the change of the transactions composed by this account is sent to.
+    #[method(name = "account_change_policy")]
+    async fn get_change_policy(&self, account: AccountArg) -> rpc::RpcResult<RpcChangePolicy>;
+
+    /// Lists the utxos of this account flagged by the dust protection policy
+    #[method(name = "account_dust_utxos")]
+    async fn get_dust_utxos(&self, account: AccountArg) -> rpc::RpcResult<Vec<JsonValue>>;
+
+    /// Release utxos flagged by the dust protection policy, so they can be selected automatically again
+    #[method(name = "account_release_dust_utxos")]
+    async fn release_dust_utxos(
+        &self,
+        account: AccountArg,
+        utxos: Vec<RpcUtxoOutpoint>,
+    ) -> rpc::RpcResult<()>;
+
+    /// Freeze utxos of this account. Frozen utxos are not selected automatically when composing
+    /// transactions and cannot be spent by selecting them explicitly, until they are unfrozen.
+    /// The frozen utxos are stored in the wallet file and survive restarts.
+    #[method(name = "account_freeze_utxos")]
+    async fn freeze_utxos(
+        &self,
+        account: AccountArg,
+        utxos: Vec<RpcUtxoOutpoint>,
+    ) -> rpc::RpcResult<()>;
+
+    /// Unfreeze utxos of this account, so they can be spent again
+    #[method(name = "account_unfreeze_utxos")]
+    async fn unfreeze_utxos(
+        &self,
+        account: AccountArg,
+        utxos: Vec<RpcUtxoOutpoint>,
+    ) -> rpc::RpcResult<()>;
+
+    /// Lists the unspent utxos of this account that are frozen
+    #[method(name = "account_frozen_utxos")]
+    async fn get_frozen_utxos(&self, account: AccountArg) -> rpc::RpcResult<Vec<JsonValue>>;
+
     /// Submits a transaction to mempool, and if it is valid, broadcasts it to the network
     #[method(name = "node_submit_transaction")]
     async fn submit_raw_transaction(
@@ -356,7 +644,37 @@ trait WalletRpc {
         amount: RpcAmountIn,
         selected_utxos: Vec<RpcUtxoOutpoint>,
         options: TransactionOptions,
-    ) -> rpc::RpcResult<NewTransaction>;
+    ) -> rpc::RpcResult<SentTransaction>;
+
+    /// Send coins and/or tokens to multiple recipients in a single transaction.
+    /// Each recipient is given an address, an amount and an optional token id; coins are sent if no token id is given.
+    /// The wallet will automatically select the inputs and calculate the fee.
+    #[method(name = "address_send_many")]
+    async fn send_to_many(
+        &self,
+        account: AccountArg,
+        recipients: Vec<Recipient>,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<SentTransaction>;
+
+    /// Pay many recipients listed in a CSV, e.g. for an airdrop or a payroll.
+    /// Each line of the CSV consists of an address, an amount and an optional token id,
+    /// i.e. `address,amount,token`; coins are sent if the token id is empty.
+    /// The optional header line and empty lines are ignored.
+    /// All the lines are validated against the network and the number of decimals of the tokens
+    /// before anything is sent, and the payouts are split into as many transactions as needed,
+    /// each of which has at most `max_outputs_per_tx` payouts (100 by default).
+    /// Returns the signed transactions that were broadcast and a summary of the payouts.
+    /// If a transaction can't be sent, the payouts stop there and the report lists the lines
+    /// of the CSV that have not been paid, so that only those can be retried.
+    #[method(name = "address_send_many_csv")]
+    async fn send_payouts_csv(
+        &self,
+        account: AccountArg,
+        csv: String,
+        max_outputs_per_tx: Option<usize>,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<PayoutsReport>;
 
     /// Sweep all spendable coins or tokens from an address or addresses to a given address.
     /// Spendable coins are any coins that are not locked, and tokens that are not frozen or locked.
@@ -370,6 +688,41 @@ trait WalletRpc {
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction>;
 
+    /// Estimate the maximum amount of coins, or of the given token, that can be sent
+    /// to the given address in a single transaction, after paying the fees.
+    #[method(name = "address_estimate_max_sendable")]
+    async fn estimate_max_sendable(
+        &self,
+        account: AccountArg,
+        address: RpcAddress<Destination>,
+        token_id: Option<RpcAddress<TokenId>>,
+    ) -> rpc::RpcResult<RpcAmountOut>;
+
+    /// Send all the spendable coins, or all the spendable amount of the given token, to the given address.
+    /// For coins, the fee is deducted from the sent amount, so nothing is left in the account.
+    /// For tokens, the fee is paid in coins, as usual.
+    #[method(name = "address_send_all")]
+    async fn send_all(
+        &self,
+        account: AccountArg,
+        address: RpcAddress<Destination>,
+        token_id: Option<RpcAddress<TokenId>>,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<NewTransaction>;
+
+    /// Transfer a given amount of coins, or of the given token, to a new address of another
+    /// account of this wallet. The transaction is shown as an internal transfer in the
+    /// transaction list of both accounts, rather than as sent and received.
+    #[method(name = "account_transfer")]
+    async fn transfer_between_accounts(
+        &self,
+        account: AccountArg,
+        to_account: AccountArg,
+        amount: RpcAmountIn,
+        token_id: Option<RpcAddress<TokenId>>,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<NewTransaction>;
+
     /// Sweep all the coins from a delegation to a given address.
     /// The wallet will automatically calculate the required fees
     #[method(name = "staking_sweep_delegation")]
@@ -400,6 +753,31 @@ trait WalletRpc {
         options: TransactionOptions,
     ) -> rpc::RpcResult<ComposedTransaction>;
 
+    /// Create an unsigned transaction package with the given outputs, to be signed by an offline wallet.
+    /// The package contains the transaction along with the utxos it spends and their destinations,
+    /// which are needed for signing, and the fees. If no utxos are selected, the wallet will select
+    /// them automatically. The change is sent to the change address, if specified.
+    /// Once created, the package can be signed using account-sign-transaction-package in the cold wallet
+    /// and then submitted using transaction-package-submit.
+    #[method(name = "transaction_package_create")]
+    async fn create_transaction_package(
+        &self,
+        account: AccountArg,
+        outputs: Vec<TxOutput>,
+        selected_utxos: Vec<RpcUtxoOutpoint>,
+        change_address: Option<RpcAddress<Destination>>,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<ComposedTransaction>;
+
+    /// Submit the transaction of a fully signed transaction package to the mempool
+    #[method(name = "transaction_package_submit")]
+    async fn submit_transaction_package(
+        &self,
+        account: AccountArg,
+        package: RpcHexString,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<NewTransaction>;
+
     /// Print the summary of the transaction
     #[method(name = "transaction_inspect")]
     async fn transaction_inspect(
@@ -515,6 +893,78 @@ trait WalletRpc {
         pool_id: RpcAddress<PoolId>,
     ) -> rpc::RpcResult<StakePoolBalance>;
 
+    /// Estimate the expected number of blocks and the annual return of a pool with the given pledge
+    /// and total balance, based on the current network difficulty reported by the node.
+    /// The estimate assumes that the difficulty and the block subsidy stay the same and
+    /// doesn't take transaction fees into account.
+    #[method(name = "staking_calculator")]
+    async fn staking_calculator(
+        &self,
+        pledge: RpcAmountIn,
+        pool_balance: RpcAmountIn,
+    ) -> rpc::RpcResult<StakingEstimate>;
+
+    /// Start watching a stake pool that is not controlled by this wallet, e.g. to monitor it from a
+    /// machine that holds no keys. The pool's balance, pledge, delegations and created blocks
+    /// are then reported by `staking_list_watched_pools`.
+    /// Optionally a decommission address can be provided to be reported together with the pool.
+    #[method(name = "staking_watch_pool")]
+    async fn add_watched_pool(
+        &self,
+        account: AccountArg,
+        pool_id: RpcAddress<PoolId>,
+        label: Option<String>,
+        decommission_address: Option<RpcAddress<Destination>>,
+        no_rescan: Option<bool>,
+    ) -> rpc::RpcResult<()>;
+
+    /// Stop watching a stake pool
+    #[method(name = "staking_unwatch_pool")]
+    async fn remove_watched_pool(
+        &self,
+        account: AccountArg,
+        pool_id: RpcAddress<PoolId>,
+    ) -> rpc::RpcResult<()>;
+
+    /// List the stake pools watched by the selected account in this wallet
+    #[method(name = "staking_list_watched_pools")]
+    async fn list_watched_pools(&self, account: AccountArg)
+        -> rpc::RpcResult<Vec<WatchedPoolInfo>>;
+
+    /// Schedule a recurring payment of coins, or of tokens if a token id is given, to the given address.
+    /// The payment is sent by the wallet every `interval_secs` seconds, starting at `first_run`
+    /// or immediately if it's not specified, as long as the wallet is running and unlocked.
+    /// A payment is not sent if the account's confirmed balance is too low; failed payments are
+    /// retried later with an increasing delay.
+    /// Returns the id of the new scheduled payment.
+    #[method(name = "scheduled_payment_add")]
+    async fn add_scheduled_payment(
+        &self,
+        account: AccountArg,
+        address: RpcAddress<Destination>,
+        amount: RpcAmountIn,
+        token_id: Option<RpcAddress<TokenId>>,
+        interval_secs: u64,
+        first_run: Option<BlockTimestamp>,
+        label: Option<String>,
+    ) -> rpc::RpcResult<u64>;
+
+    /// Cancel a scheduled payment
+    #[method(name = "scheduled_payment_cancel")]
+    async fn cancel_scheduled_payment(
+        &self,
+        account: AccountArg,
+        payment_id: u64,
+    ) -> rpc::RpcResult<()>;
+
+    /// List the payments scheduled by the selected account in this wallet, with the state of their
+    /// last execution
+    #[method(name = "scheduled_payment_list")]
+    async fn list_scheduled_payments(
+        &self,
+        account: AccountArg,
+    ) -> rpc::RpcResult<Vec<ScheduledPaymentInfo>>;
+
     /// List delegation ids controlled by the selected account in this wallet with their balances
     #[method(name = "delegation_list_ids")]
     async fn list_delegation_ids(&self, account: AccountArg)
@@ -527,6 +977,20 @@ trait WalletRpc {
         account: AccountArg,
     ) -> rpc::RpcResult<Vec<CreatedBlockInfo>>;
 
+    /// Get the rewards of the blocks created by the pools of the selected account
+    /// in the specified block height range (inclusive), grouped by pool.
+    ///
+    /// The reward of a block is its subsidy plus its transaction fees, as credited by the node
+    /// to the pool's staker and its delegations. The reward is not reported for the blocks
+    /// whose reward distribution the node doesn't know anymore.
+    #[method(name = "staking_rewards")]
+    async fn get_staking_rewards(
+        &self,
+        account: AccountArg,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> rpc::RpcResult<Vec<PoolStakingRewardsInfo>>;
+
     /// Issue a new non-fungible token (NFT) from scratch
     #[method(name = "token_nft_issue_new")]
     async fn issue_new_nft(
@@ -559,6 +1023,23 @@ trait WalletRpc {
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction>;
 
+    /// Change the authority of a token after checking that the change is safe.
+    ///
+    /// The change is refused if the new authority is not controlled by this wallet,
+    /// unless `force` is set, as the control over the token would be lost.
+    /// With `dry_run`, only the checks are made and no transaction is created;
+    /// the result then tells whether the new authority is controlled by this wallet.
+    #[method(name = "token_rotate_authority")]
+    async fn rotate_token_authority(
+        &self,
+        account: AccountArg,
+        token_id: RpcAddress<TokenId>,
+        address: RpcAddress<Destination>,
+        dry_run: bool,
+        force: bool,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<TokenAuthorityRotationInfo>;
+
     /// Change the metadata URI of a token
     #[method(name = "token_change_metadata_uri")]
     async fn change_token_metadata_uri(
@@ -636,6 +1117,66 @@ trait WalletRpc {
         address: RpcAddress<Destination>,
         amount: RpcAmountIn,
         options: TransactionOptions,
+    ) -> rpc::RpcResult<SentTransaction>;
+
+    /// Mark a token as trusted or hidden in this wallet.
+    ///
+    /// Anyone can send tokens to the wallet, so the tokens that were neither issued nor sent
+    /// by the wallet are listed separately as unverified in the balance. Trusted tokens are listed
+    /// together with the other tokens, and hidden tokens are not listed at all.
+    /// If `trust` is null, the mark is removed.
+    #[method(name = "token_set_trust")]
+    async fn set_token_trust(
+        &self,
+        token_id: RpcAddress<TokenId>,
+        trust: Option<TokenTrust>,
+    ) -> rpc::RpcResult<()>;
+
+    /// List the tokens marked as trusted or hidden in this wallet
+    #[method(name = "token_list_trust")]
+    async fn list_token_trust(&self) -> rpc::RpcResult<Vec<TokenTrustInfo>>;
+
+    /// Add a payee to the wallet's address book, or replace the payee with the same name.
+    /// The address book is shared by all the accounts of the wallet.
+    /// If a default token is specified, `address_book_send` sends that token instead of coins.
+    #[method(name = "address_book_add_payee")]
+    async fn add_payee(
+        &self,
+        name: String,
+        address: RpcAddress<Destination>,
+        default_token: Option<RpcAddress<TokenId>>,
+        notes: Option<String>,
+    ) -> rpc::RpcResult<()>;
+
+    /// Remove a payee from the wallet's address book
+    #[method(name = "address_book_remove_payee")]
+    async fn remove_payee(&self, name: String) -> rpc::RpcResult<()>;
+
+    /// List the payees in the wallet's address book
+    #[method(name = "address_book_list")]
+    async fn list_payees(&self) -> rpc::RpcResult<Vec<PayeeInfo>>;
+
+    /// Export the wallet's address book as a JSON string, which can be imported into another wallet
+    /// with `address_book_import`
+    #[method(name = "address_book_export")]
+    async fn export_payees(&self) -> rpc::RpcResult<String>;
+
+    /// Import an address book exported with `address_book_export`.
+    /// The payees with the same names as the existing ones replace them.
+    /// Nothing is imported if any of the addresses is invalid or belongs to a different network.
+    /// Returns the number of imported payees.
+    #[method(name = "address_book_import")]
+    async fn import_payees(&self, address_book: String) -> rpc::RpcResult<usize>;
+
+    /// Send a given amount to a payee from the wallet's address book.
+    /// The payee's default token is sent if it has one, otherwise coins are sent.
+    #[method(name = "address_book_send")]
+    async fn send_to_payee(
+        &self,
+        account: AccountArg,
+        name: String,
+        amount: RpcAmountIn,
+        options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction>;
 
     /// Create a transaction for sending tokens from a multisig address to other addresses, returning the change to
@@ -758,6 +1299,31 @@ trait WalletRpc {
         transaction_id: HexEncoded<Id<Transaction>>,
     ) -> rpc::RpcResult<()>;
 
+    /// Get the broadcast status of a transaction sent by this wallet.
+    ///
+    /// The transactions are queued for broadcasting when they are sent and resubmitted to the node
+    /// until they are included in a block, the limit of failed attempts is reached
+    /// or the deadline passes. Returns null if the transaction is not in the queue.
+    #[method(name = "transaction_broadcast_status")]
+    async fn transaction_broadcast_status(
+        &self,
+        transaction_id: HexEncoded<Id<Transaction>>,
+    ) -> rpc::RpcResult<Option<BroadcastQueueEntryInfo>>;
+
+    /// List the transactions in the broadcast queue, including the recently accepted and rejected ones
+    #[method(name = "transaction_broadcast_queue")]
+    async fn transaction_broadcast_queue(&self) -> rpc::RpcResult<Vec<BroadcastQueueEntryInfo>>;
+
+    /// Set the number of consecutive failed submissions after which the wallet stops broadcasting
+    /// a queued transaction, and optionally a deadline by which it must be included in a block
+    #[method(name = "transaction_set_broadcast_policy")]
+    async fn set_transaction_broadcast_policy(
+        &self,
+        transaction_id: HexEncoded<Id<Transaction>>,
+        max_failed_attempts: u32,
+        deadline: Option<BlockTimestamp>,
+    ) -> rpc::RpcResult<()>;
+
     /// List the pending transactions that can be abandoned
     #[method(name = "transaction_list_pending")]
     async fn list_pending_transactions(
@@ -766,13 +1332,39 @@ trait WalletRpc {
     ) -> rpc::RpcResult<Vec<Id<Transaction>>>;
 
     /// List mainchain transactions with optional address filter
+    ///
+    /// Deprecated: use `transaction_list`, which returns the transactions page by page.
+    /// This method will be removed in a future release.
     #[method(name = "transaction_list_by_address")]
     async fn list_transactions_by_address(
         &self,
         account: AccountArg,
         address: Option<RpcAddress<Destination>>,
         limit: usize,
-    ) -> rpc::RpcResult<Vec<TxInfo>>;
+    ) -> rpc::RpcResult<Vec<MainchainTxInfo>>;
+
+    /// List mainchain transactions page by page, with optional address filter.
+    /// The transactions are sorted by the height of their block.
+    /// Each transaction comes with a human-friendly description of what it did.
+    #[method(name = "transaction_list")]
+    async fn list_transactions(
+        &self,
+        account: AccountArg,
+        address: Option<RpcAddress<Destination>>,
+        page: Option<PageRequest>,
+    ) -> rpc::RpcResult<TransactionPage>;
+
+    /// Export the whole transaction history of the account, the oldest transactions first,
+    /// with the timestamps, the amounts received and sent per currency, the fees,
+    /// the counterparties and the block heights, in a form suitable for accounting tools.
+    /// Transfers between the accounts of the wallet are reported separately from the amounts
+    /// received and sent, and their fee is only reported for the sending account.
+    #[method(name = "transaction_history_export")]
+    async fn export_transaction_history(
+        &self,
+        account: AccountArg,
+        format: TransactionHistoryFormat,
+    ) -> rpc::RpcResult<String>;
 
     /// Get a transaction from the wallet, if present
     #[method(name = "transaction_get")]
@@ -782,6 +1374,15 @@ trait WalletRpc {
         transaction_id: Id<Transaction>,
     ) -> rpc::RpcResult<serde_json::Value>;
 
+    /// Describe in a human-friendly way what a transaction from the wallet does,
+    /// e.g. which amounts were sent or received, delegated or staked, which tokens were minted
+    #[method(name = "transaction_describe")]
+    async fn describe_transaction(
+        &self,
+        account: AccountArg,
+        transaction_id: Id<Transaction>,
+    ) -> rpc::RpcResult<TransactionDescription>;
+
     /// Get a transaction from the wallet, if present, as hex encoded raw transaction
     #[method(name = "transaction_get_raw")]
     async fn get_raw_transaction(
@@ -801,12 +1402,15 @@ trait WalletRpc {
     /// Compose a new transaction from the specified outputs and selected utxos
     /// The transaction is returned in a hex encoded form that can be passed to account-sign-raw-transaction
     /// and also prints the fees that will be paid by the transaction
+    /// Optionally, a sighash type can be specified for each input, e.g. AllAnyoneCanPay
+    /// allows other parties to add their own inputs to the transaction after it has been signed
     #[method(name = "transaction_compose")]
     async fn compose_transaction(
         &self,
         inputs: Vec<RpcUtxoOutpoint>,
         outputs: Vec<TxOutput>,
         htlc_secrets: Option<Vec<Option<RpcHexString>>>,
+        sighash_types: Option<Vec<RpcSigHashType>>,
         only_transaction: bool,
     ) -> rpc::RpcResult<ComposedTransaction>;
 
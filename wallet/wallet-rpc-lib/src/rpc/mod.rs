@@ -27,7 +27,11 @@ use std::{
 };
 
 use chainstate::{tx_verifier::check_transaction, ChainInfo, TokenIssuanceError};
-use crypto::key::{hdkd::u31::U31, PrivateKey, PublicKey};
+use crypto::key::{
+    extended::ExtendedPublicKey,
+    hdkd::{child_number::ChildNumber, u31::U31},
+    PrivateKey, PublicKey,
+};
 use mempool::tx_accumulator::PackingStrategy;
 use mempool_types::tx_options::TxOptionsOverrides;
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, PeerId};
@@ -38,7 +42,6 @@ use utils_networking::IpOrSocketAddress;
 use wallet::{
     account::{
         currency_grouper::Currency, transaction_list::TransactionList, PoolData, TransactionToSign,
-        TxInfo,
     },
     WalletError,
 };
@@ -51,15 +54,19 @@ use common::{
         htlc::{HashedTimelockContract, HtlcSecret, HtlcSecretHash},
         output_value::OutputValue,
         partially_signed_transaction::PartiallySignedTransaction,
-        signature::inputsig::arbitrary_message::{
-            produce_message_challenge, ArbitraryMessageSignature,
+        signature::{
+            inputsig::arbitrary_message::{produce_message_challenge, ArbitraryMessageSignature},
+            sighash::sighashtype::SigHashType,
         },
         tokens::{IsTokenFreezable, IsTokenUnfreezable, Metadata, TokenId, TokenTotalSupply},
         Block, ChainConfig, DelegationId, Destination, GenBlock, PoolId, SignedTransaction,
         Transaction, TxOutput, UtxoOutPoint,
     },
     primitives::{
-        id::WithId, per_thousand::PerThousand, time::Time, Amount, BlockHeight, Id, Idable,
+        id::WithId,
+        per_thousand::PerThousand,
+        time::{get_time, Time},
+        Amount, BlockHeight, Id, Idable,
     },
 };
 pub use interface::{
@@ -69,26 +76,42 @@ pub use interface::{
 pub use rpc::{rpc_creds::RpcCreds, Rpc};
 use wallet_controller::{
     types::{
-        Balances, BlockInfo, CreatedBlockInfo, GenericTokenTransfer, InspectTransaction,
-        SeedWithPassPhrase, TransactionToInspect, WalletInfo,
+        parse_payouts_csv, transaction_history_to_csv, Balances, BlockInfo, CreatedBlockInfo,
+        CurrencyAmount, GenericTokenTransfer, InspectTransaction, MainchainTxInfo, PayoutsCsvError,
+        SeedWithPassPhrase, StakingEstimate, SyncStatus, TransactionDescription,
+        TransactionPackage, TransactionToInspect, WalletInfo, DEFAULT_MAX_PAYOUTS_PER_TX,
     },
     ConnectedPeer, ControllerConfig, ControllerError, NodeInterface, UtxoState, UtxoStates,
     UtxoType, UtxoTypes, DEFAULT_ACCOUNT_INDEX,
 };
 use wallet_types::{
-    account_info::StandaloneAddressDetails, seed_phrase::StoreSeedPhrase,
-    signature_status::SignatureStatus, wallet_tx::TxData, with_locked::WithLocked,
+    account_info::{ChangePolicy, ScheduledPayment, StandaloneAddressDetails},
+    address_book::Payee,
+    broadcast_queue::BroadcastPolicy,
+    multisig_account::MultisigAccountDescriptor,
+    seed_phrase::StoreSeedPhrase,
+    signature_status::SignatureStatus,
+    token_trust::TokenTrust,
+    wallet_tx::TxData,
+    with_locked::WithLocked,
 };
 
-use crate::{service::CreatedWallet, WalletHandle, WalletRpcConfig};
+use crate::{
+    service::{CreatedWallet, WalletController},
+    WalletHandle, WalletRpcConfig,
+};
 
 pub use self::types::RpcError;
 use self::types::{
-    AddressInfo, AddressWithUsageInfo, DelegationInfo, LegacyVrfPublicKeyInfo, NewAccountInfo,
-    NewTransaction, PoolInfo, PublicKeyInfo, RpcAddress, RpcAmountIn, RpcHexString,
-    RpcStandaloneAddress, RpcStandaloneAddressDetails, RpcStandaloneAddresses,
-    RpcStandalonePrivateKeyAddress, RpcTokenId, RpcUtxoOutpoint, StakingStatus,
-    StandaloneAddressWithDetails, VrfPublicKeyInfo,
+    AddressInfo, AddressPage, AddressWithUsageInfo, BroadcastQueueEntryInfo, DelegationInfo,
+    LegacyVrfPublicKeyInfo, NewAccountInfo, NewTransaction, PageRequest, PayeeInfo,
+    PayoutTransaction, PayoutsFailure, PayoutsReport, PoolInfo, PoolStakingRewardsInfo,
+    PublicKeyInfo, Recipient, RpcAddress, RpcAmountIn, RpcAmountOut, RpcChangePolicy, RpcHexString,
+    RpcSigHashType, RpcStandaloneAddress, RpcStandaloneAddressDetails, RpcStandaloneAddresses,
+    RpcStandalonePrivateKeyAddress, RpcTokenId, RpcUtxoOutpoint, ScheduledPaymentInfo,
+    SigningLogEntryInfo, SortOrder, StakingStatus, StandaloneAddressWithDetails,
+    TokenAuthorityRotationInfo, TokenTrustInfo, TransactionHistoryFormat, TransactionPage,
+    UtxoSortKey, UtxoVerificationInfo, VrfPublicKeyInfo, WatchedPoolInfo,
 };
 
 #[derive(Clone)]
@@ -162,7 +185,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         Ok(self
             .wallet
             .manage_async(move |wallet_manager| {
-                Box::pin(async move { wallet_manager.close_wallet() })
+                Box::pin(async move { wallet_manager.close_wallet().await })
             })
             .await??)
     }
@@ -177,6 +200,44 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             .await?
     }
 
+    pub async fn create_wallet_from_watch_only_export(
+        &self,
+        path: PathBuf,
+        data: Vec<u8>,
+        passphrase: String,
+    ) -> WRpcResult<(), N> {
+        Ok(self
+            .wallet
+            .manage_async(move |wallet_manager| {
+                Box::pin(async move {
+                    wallet_manager
+                        .create_wallet_from_watch_only_export(path, data, passphrase)
+                        .await
+                })
+            })
+            .await??)
+    }
+
+    pub async fn export_watch_only(
+        &self,
+        passphrase: String,
+        since_height: Option<BlockHeight>,
+    ) -> WRpcResult<RpcHexString, N> {
+        self.wallet
+            .call(move |w| {
+                w.export_watch_only(&passphrase, since_height).map(RpcHexString::from_bytes)
+            })
+            .await?
+    }
+
+    pub async fn apply_watch_only_update(
+        &self,
+        data: Vec<u8>,
+        passphrase: String,
+    ) -> WRpcResult<(), N> {
+        self.wallet.call(move |w| w.apply_watch_only_update(&data, &passphrase)).await?
+    }
+
     pub async fn encrypt_private_keys(&self, password: String) -> WRpcResult<(), N> {
         self.wallet.call(|w| w.encrypt_wallet(&Some(password))).await?
     }
@@ -194,7 +255,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
     }
 
     pub async fn best_block(&self) -> WRpcResult<BlockInfo, N> {
-        let res = self.wallet.call(|w| Ok::<_, RpcError<N>>(w.best_block())).await??;
+        let res = self.wallet.call_read(|w| Ok::<_, RpcError<N>>(w.best_block())).await??;
         Ok(BlockInfo::from_tuple(res))
     }
 
@@ -268,6 +329,21 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         Ok(NewAccountInfo::new(num, name))
     }
 
+    pub async fn set_address_label(
+        &self,
+        account_index: U31,
+        address: RpcAddress<Destination>,
+        label: Option<String>,
+    ) -> WRpcResult<(), N> {
+        let dest = address
+            .decode_object(&self.chain_config)
+            .map_err(|_| RpcError::InvalidAddress)?;
+        self.wallet
+            .call(move |w| w.set_address_label(account_index, dest, label))
+            .await??;
+        Ok(())
+    }
+
     pub async fn standalone_address_label_rename(
         &self,
         account_index: U31,
@@ -280,6 +356,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            fee_override: None,
         }; // irrelevant for issuing addresses
         self.wallet
             .call_async(move |w| {
@@ -314,6 +391,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            fee_override: None,
         }; // irrelevant for issuing addresses
         self.wallet
             .call_async(move |w| {
@@ -334,6 +412,37 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         Ok(())
     }
 
+    pub async fn remove_standalone_watch_only_address(
+        &self,
+        account_index: U31,
+        address: RpcAddress<Destination>,
+    ) -> WRpcResult<(), N> {
+        let dest = address
+            .decode_object(&self.chain_config)
+            .map_err(|_| RpcError::InvalidAddress)?;
+        let pkh = match dest {
+            Destination::PublicKeyHash(pkh) => pkh,
+            Destination::PublicKey(pk) => (&pk).into(),
+            Destination::ScriptHash(_)
+            | Destination::ClassicMultisig(_)
+            | Destination::AnyoneCanSpend => return Err(RpcError::InvalidAddress),
+        };
+
+        let config = ControllerConfig {
+            in_top_x_mb: 5,
+            broadcast_to_mempool: true,
+            fee_override: None,
+        }; // irrelevant for removing addresses
+        self.wallet
+            .call_async(move |w| {
+                Box::pin(async move {
+                    w.synced_controller(account_index, config).await?.remove_standalone_address(pkh)
+                })
+            })
+            .await??;
+        Ok(())
+    }
+
     pub async fn add_standalone_private_key(
         &self,
         account_index: U31,
@@ -344,6 +453,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            fee_override: None,
         }; // irrelevant for issuing addresses
         self.wallet
             .call_async(move |w| {
@@ -364,6 +474,40 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         Ok(())
     }
 
+    pub async fn import_standalone_private_key(
+        &self,
+        account_index: U31,
+        private_key: RpcAddress<PrivateKey>,
+        label: Option<String>,
+        no_rescan: bool,
+    ) -> WRpcResult<(), N> {
+        let private_key = private_key
+            .decode_object(&self.chain_config)
+            .map_err(|_| RpcError::InvalidPrivateKey)?;
+        self.add_standalone_private_key(account_index, private_key, label, no_rescan)
+            .await
+    }
+
+    pub async fn export_private_key(
+        &self,
+        account_index: U31,
+        address: RpcAddress<Destination>,
+        confirm: bool,
+    ) -> WRpcResult<RpcAddress<PrivateKey>, N> {
+        ensure!(confirm, RpcError::PrivateKeyExportNotConfirmed);
+        let dest = address
+            .decode_object(&self.chain_config)
+            .map_err(|_| RpcError::InvalidAddress)?;
+
+        let private_key = self
+            .wallet
+            .call_read(move |controller| {
+                controller.readonly_controller(account_index).export_private_key(&dest)
+            })
+            .await??;
+        Ok(RpcAddress::new(&self.chain_config, private_key)?)
+    }
+
     pub async fn add_standalone_multisig(
         &self,
         account_index: U31,
@@ -375,6 +519,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            fee_override: None,
         }; // irrelevant for issuing addresses
         let min_required_signatures =
             NonZeroU8::new(min_required_signatures).ok_or(RpcError::InvalidMultisigMinSignature)?;
@@ -429,10 +574,153 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         Ok(address.to_string())
     }
 
+    pub async fn account_extended_public_key(
+        &self,
+        account_index: U31,
+    ) -> WRpcResult<ExtendedPublicKey, N> {
+        let key = self
+            .wallet
+            .call_read(move |controller| {
+                controller
+                    .readonly_controller(account_index)
+                    .account_extended_public_key()
+                    .cloned()
+            })
+            .await??;
+        Ok(key)
+    }
+
+    pub async fn export_account_extended_public_key(
+        &self,
+        account_index: U31,
+    ) -> WRpcResult<RpcAddress<ExtendedPublicKey>, N> {
+        let key = self.account_extended_public_key(account_index).await?;
+        Ok(RpcAddress::new(&self.chain_config, key)?)
+    }
+
+    pub async fn import_extended_public_key(
+        &self,
+        account_index: U31,
+        extended_public_key: RpcAddress<ExtendedPublicKey>,
+        address_count: u32,
+        label: Option<String>,
+        no_rescan: bool,
+    ) -> WRpcResult<Vec<String>, N> {
+        let config = ControllerConfig {
+            in_top_x_mb: 5,
+            broadcast_to_mempool: true,
+            fee_override: None,
+        }; // irrelevant for issuing addresses
+        let extended_public_key = extended_public_key
+            .decode_object(&self.chain_config)
+            .map_err(|_| RpcError::InvalidExtendedPublicKey)?;
+        let address_count =
+            U31::from_u32(address_count).ok_or(RpcError::ImportAddressCountOutOfRange)?;
+
+        let public_key_hashes = self
+            .wallet
+            .call_async(move |w| {
+                Box::pin(async move {
+                    let public_key_hashes = w
+                        .synced_controller(account_index, config)
+                        .await?
+                        .import_extended_public_key(&extended_public_key, address_count, label)?;
+
+                    if !no_rescan {
+                        w.reset_wallet_to_genesis()?;
+                    }
+
+                    Ok::<_, ControllerError<N>>(public_key_hashes)
+                })
+            })
+            .await??;
+
+        let addresses = public_key_hashes
+            .into_iter()
+            .map(|pkh| {
+                Address::new(&self.chain_config, Destination::PublicKeyHash(pkh))
+                    .expect("addressable")
+                    .to_string()
+            })
+            .collect();
+
+        Ok(addresses)
+    }
+
+    pub async fn create_multisig_account_descriptor(
+        &self,
+        account_index: U31,
+        min_required_signatures: u8,
+        cosigner_keys: Vec<ExtendedPublicKey>,
+    ) -> WRpcResult<MultisigAccountDescriptor, N> {
+        let min_required_signatures =
+            NonZeroU8::new(min_required_signatures).ok_or(RpcError::InvalidMultisigMinSignature)?;
+
+        let descriptor = self
+            .wallet
+            .call_read(move |controller| {
+                controller
+                    .readonly_controller(account_index)
+                    .create_multisig_account_descriptor(min_required_signatures, cosigner_keys)
+            })
+            .await??;
+        Ok(descriptor)
+    }
+
+    pub async fn import_multisig_account(
+        &self,
+        account_index: U31,
+        descriptor: MultisigAccountDescriptor,
+        address_count: u32,
+        label: Option<String>,
+        no_rescan: bool,
+    ) -> WRpcResult<Vec<String>, N> {
+        let config = ControllerConfig {
+            in_top_x_mb: 5,
+            broadcast_to_mempool: true,
+            fee_override: None,
+        }; // irrelevant for issuing addresses
+        let address_count =
+            U31::from_u32(address_count).ok_or(RpcError::MultisigAddressCountOutOfRange)?;
+
+        let multisig_addresses = self
+            .wallet
+            .call_async(move |w| {
+                Box::pin(async move {
+                    let res = w
+                        .synced_controller(account_index, config)
+                        .await?
+                        .import_multisig_account(&descriptor, address_count, label);
+
+                    if !no_rescan {
+                        w.reset_wallet_to_genesis()?;
+                    }
+
+                    res
+                })
+            })
+            .await??;
+
+        let addresses = multisig_addresses
+            .into_iter()
+            .map(|multisig_address| {
+                Address::new(
+                    &self.chain_config,
+                    Destination::ClassicMultisig(multisig_address),
+                )
+                .expect("addressable")
+                .to_string()
+            })
+            .collect();
+
+        Ok(addresses)
+    }
+
     pub async fn issue_address(&self, account_index: U31) -> WRpcResult<AddressInfo, N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            fee_override: None,
         }; // irrelevant for issuing addresses
         let (child_number, destination) = self
             .wallet
@@ -453,6 +741,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            fee_override: None,
         }; // irrelevant for issuing addresses
         let address = address
             .decode_object(&self.chain_config)
@@ -473,22 +762,32 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         &self,
         account_index: U31,
     ) -> WRpcResult<LegacyVrfPublicKeyInfo, N> {
-        self.wallet
-            .call_async(move |w| {
-                Box::pin(
-                    async move { w.readonly_controller(account_index).get_legacy_vrf_public_key() },
-                )
-            })
-            .await?
-            .map(|vrf_public_key| LegacyVrfPublicKeyInfo {
-                vrf_public_key: vrf_public_key.to_string(),
+        let (vrf_public_key, mut pool_ids_by_key) = self
+            .wallet
+            .call_read(move |w| {
+                let controller = w.readonly_controller(account_index);
+                Ok::<_, ControllerError<N>>((
+                    controller.get_legacy_vrf_public_key()?,
+                    controller.get_pool_ids_by_vrf_public_key()?,
+                ))
             })
+            .await??;
+
+        let pool_ids = self.pool_ids_to_rpc(
+            pool_ids_by_key.remove(vrf_public_key.as_object()).unwrap_or_default(),
+        )?;
+
+        Ok(LegacyVrfPublicKeyInfo {
+            vrf_public_key: vrf_public_key.to_string(),
+            pool_ids,
+        })
     }
 
     pub async fn issue_vrf_key(&self, account_index: U31) -> WRpcResult<VrfPublicKeyInfo, N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            fee_override: None,
         }; // irrelevant for issuing addresses
         self.wallet
             .call_async(move |w| {
@@ -497,27 +796,41 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
                 )
             })
             .await?
-            .map(|(child_number, vrf_key)| VrfPublicKeyInfo::new(vrf_key, child_number, false))
+            .map(|(child_number, vrf_key)| {
+                VrfPublicKeyInfo::new(vrf_key, child_number, false, vec![])
+            })
     }
 
     pub async fn get_vrf_key_usage(
         &self,
         account_index: U31,
     ) -> WRpcResult<Vec<VrfPublicKeyInfo>, N> {
-        self.wallet
-            .call_async(move |w| {
-                Box::pin(async move {
-                    w.readonly_controller(account_index).get_all_issued_vrf_public_keys()
-                })
+        let (keys, mut pool_ids_by_key) = self
+            .wallet
+            .call_read(move |w| {
+                let controller = w.readonly_controller(account_index);
+                Ok::<_, ControllerError<N>>((
+                    controller.get_all_issued_vrf_public_keys()?,
+                    controller.get_pool_ids_by_vrf_public_key()?,
+                ))
             })
-            .await?
-            .map(|keys| {
-                keys.into_iter()
-                    .map(|(child_number, (pub_key, used))| {
-                        VrfPublicKeyInfo::new(pub_key, child_number, used)
-                    })
-                    .collect()
+            .await??;
+
+        keys.into_iter()
+            .map(|(child_number, (pub_key, used))| {
+                let pool_ids = self.pool_ids_to_rpc(
+                    pool_ids_by_key.remove(pub_key.as_object()).unwrap_or_default(),
+                )?;
+                Ok(VrfPublicKeyInfo::new(pub_key, child_number, used, pool_ids))
             })
+            .collect()
+    }
+
+    fn pool_ids_to_rpc(&self, pool_ids: Vec<PoolId>) -> WRpcResult<Vec<RpcAddress<PoolId>>, N> {
+        pool_ids
+            .into_iter()
+            .map(|pool_id| Ok(RpcAddress::new(&self.chain_config, pool_id)?))
+            .collect()
     }
 
     pub async fn get_transaction_list(
@@ -528,28 +841,61 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
     ) -> WRpcResult<TransactionList, N> {
         let txs = self
             .wallet
-            .call(move |controller| {
+            .call_read(move |controller| {
                 controller.readonly_controller(account_index).get_transaction_list(skip, count)
             })
             .await??;
         Ok(txs)
     }
 
-    pub async fn get_issued_addresses(
+    async fn get_addresses_with_usage_and_labels(
         &self,
         account_index: U31,
-    ) -> WRpcResult<Vec<AddressWithUsageInfo>, N> {
-        let addresses: BTreeMap<_, _> = self
+    ) -> WRpcResult<BTreeMap<ChildNumber, AddressWithUsageInfo>, N> {
+        let (addresses, mut labels) = self
             .wallet
-            .call(move |controller| {
-                controller.readonly_controller(account_index).get_addresses_with_usage()
+            .call_read(move |controller| {
+                let controller = controller.readonly_controller(account_index);
+                controller.get_addresses_with_usage().and_then(|addresses| {
+                    let labels = controller.get_addresses_with_labels()?;
+                    Ok((addresses, labels))
+                })
             })
             .await??;
-        let result = addresses
+        Ok(addresses
             .into_iter()
-            .map(|(num, (addr, used))| AddressWithUsageInfo::new(num, addr, used))
-            .collect();
-        Ok(result)
+            .map(|(num, (addr, used))| {
+                let label = labels.remove(&num).and_then(|(_, label)| label);
+                (num, AddressWithUsageInfo::new(num, addr, used, label))
+            })
+            .collect())
+    }
+
+    pub async fn get_issued_addresses(
+        &self,
+        account_index: U31,
+    ) -> WRpcResult<Vec<AddressWithUsageInfo>, N> {
+        let addresses = self.get_addresses_with_usage_and_labels(account_index).await?;
+        Ok(addresses.into_values().collect())
+    }
+
+    pub async fn list_issued_addresses(
+        &self,
+        account_index: U31,
+        used: Option<bool>,
+        page: PageRequest,
+    ) -> WRpcResult<AddressPage, N> {
+        let addresses = self.get_addresses_with_usage_and_labels(account_index).await?;
+        let addresses = addresses
+            .into_iter()
+            .filter(|(_, info)| used.map_or(true, |used| info.used == used));
+        let (addresses, next_cursor) =
+            page.paginate(addresses, |(num, _)| num.get_index().into_u32())?;
+
+        Ok(AddressPage {
+            addresses: addresses.into_iter().map(|(_, info)| info).collect(),
+            next_cursor,
+        })
     }
 
     pub async fn get_standalone_addresses(
@@ -558,7 +904,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
     ) -> WRpcResult<RpcStandaloneAddresses, N> {
         let addresses = self
             .wallet
-            .call(move |controller| {
+            .call_read(move |controller| {
                 controller.readonly_controller(account_index).get_standalone_addresses()
             })
             .await??;
@@ -597,7 +943,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let chain_config = self.chain_config.clone();
         let result = self
             .wallet
-            .call_async(move |w| {
+            .call_read_async(move |w| {
                 Box::pin(async move {
                     w.readonly_controller(account_index)
                         .get_standalone_address_details(address)
@@ -664,7 +1010,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
     ) -> WRpcResult<Balances, N> {
         let balances: Balances = self
             .wallet
-            .call_async(move |w| {
+            .call_read_async(move |w| {
                 Box::pin(async move {
                     let c = w.readonly_controller(account_index);
                     c.get_decimal_balance(utxo_states, with_locked).await
@@ -682,7 +1028,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         with_locked: WithLocked,
     ) -> WRpcResult<Vec<(UtxoOutPoint, TxOutput)>, N> {
         self.wallet
-            .call(move |w| {
+            .call_read(move |w| {
                 w.readonly_controller(account_index).get_multisig_utxos(
                     utxo_types,
                     utxo_states,
@@ -692,42 +1038,306 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             .await?
     }
 
-    pub async fn get_utxos(
+    pub async fn get_standalone_address_utxos(
         &self,
         account_index: U31,
+        address: RpcAddress<Destination>,
         utxo_types: UtxoTypes,
         utxo_states: UtxoStates,
         with_locked: WithLocked,
     ) -> WRpcResult<Vec<(UtxoOutPoint, TxOutput)>, N> {
+        let address = address
+            .decode_object(&self.chain_config)
+            .map_err(|_| RpcError::InvalidAddress)?;
         self.wallet
-            .call(move |w| {
-                w.readonly_controller(account_index)
-                    .get_utxos(utxo_types, utxo_states, with_locked)
+            .call_read(move |w| {
+                w.readonly_controller(account_index).get_standalone_address_utxos(
+                    address,
+                    utxo_types,
+                    utxo_states,
+                    with_locked,
+                )
             })
             .await?
     }
 
-    pub async fn get_transaction(
+    pub async fn get_utxos(
         &self,
         account_index: U31,
-        transaction_id: Id<Transaction>,
-    ) -> WRpcResult<TxData, N> {
+        utxo_types: UtxoTypes,
+        utxo_states: UtxoStates,
+        with_locked: WithLocked,
+    ) -> WRpcResult<Vec<(UtxoOutPoint, TxOutput)>, N> {
         self.wallet
-            .call(move |controller| {
-                controller
-                    .readonly_controller(account_index)
-                    .get_transaction(transaction_id)
-                    .cloned()
+            .call_read(move |w| {
+                w.readonly_controller(account_index)
+                    .get_utxos(utxo_types, utxo_states, with_locked)
             })
             .await?
     }
 
-    pub async fn pending_transactions(
+    pub async fn list_utxos(
+        &self,
+        account_index: U31,
+        utxo_types: UtxoTypes,
+        utxo_states: UtxoStates,
+        with_locked: WithLocked,
+        sort_by: UtxoSortKey,
+        page: PageRequest,
+    ) -> WRpcResult<
+        (
+            Vec<(UtxoOutPoint, TxOutput)>,
+            Option<RpcHexString>,
+            BlockInfo,
+        ),
+        N,
+    > {
+        // The utxos and the block they are read at come from the same wallet state
+        let (utxos, best_synced_block) = self
+            .wallet
+            .call_read(move |w| {
+                let controller = w.readonly_controller(account_index);
+                let utxos = controller.get_utxos(utxo_types, utxo_states, with_locked)?;
+                Ok::<_, ControllerError<N>>((
+                    utxos,
+                    controller.sync_status().best_synced_block.clone(),
+                ))
+            })
+            .await??;
+
+        let (utxos, next_cursor) = match sort_by {
+            UtxoSortKey::Outpoint => page.paginate(utxos, |(outpoint, _)| outpoint.clone())?,
+            UtxoSortKey::CoinAmount => page.paginate(utxos, |(outpoint, output)| {
+                (utxo_coin_amount(output), outpoint.clone())
+            })?,
+        };
+        Ok((utxos, next_cursor, best_synced_block))
+    }
+
+    pub async fn set_dust_protection(
+        &self,
+        account_index: U31,
+        min_coin_amount: Option<RpcAmountIn>,
+        flag_unknown_tokens: bool,
+    ) -> WRpcResult<(), N> {
+        let min_coin_amount = min_coin_amount
+            .map(|amount| {
+                amount
+                    .to_amount(self.chain_config.coin_decimals())
+                    .ok_or(RpcError::InvalidCoinAmount)
+            })
+            .transpose()?;
+
+        let config = ControllerConfig {
+            in_top_x_mb: 5,
+            broadcast_to_mempool: true,
+            fee_override: None,
+        }; // irrelevant for changing the policy
+        self.wallet
+            .call_async(move |w| {
+                Box::pin(async move {
+                    w.synced_controller(account_index, config)
+                        .await?
+                        .set_dust_protection_policy(min_coin_amount, flag_unknown_tokens)
+                })
+            })
+            .await??;
+        Ok(())
+    }
+
+    pub async fn set_change_policy(
+        &self,
+        account_index: U31,
+        change_policy: RpcChangePolicy,
+    ) -> WRpcResult<(), N> {
+        let change_policy = match change_policy {
+            RpcChangePolicy::NewAddress => ChangePolicy::NewAddress,
+            RpcChangePolicy::ReuseInputAddress => ChangePolicy::ReuseInputAddress,
+            RpcChangePolicy::ExternalAddress(address) => ChangePolicy::External(
+                address
+                    .decode_object(&self.chain_config)
+                    .map_err(|_| RpcError::InvalidAddress)?,
+            ),
+        };
+
+        let config = ControllerConfig {
+            in_top_x_mb: 5,
+            broadcast_to_mempool: true,
+            fee_override: None,
+        }; // irrelevant for changing the policy
+        self.wallet
+            .call_async(move |w| {
+                Box::pin(async move {
+                    w.synced_controller(account_index, config)
+                        .await?
+                        .set_change_policy(change_policy)
+                })
+            })
+            .await??;
+        Ok(())
+    }
+
+    pub async fn get_change_policy(&self, account_index: U31) -> WRpcResult<RpcChangePolicy, N> {
+        let change_policy = self
+            .wallet
+            .call_read(move |w| w.readonly_controller(account_index).get_change_policy())
+            .await??;
+
+        let change_policy = match change_policy {
+            ChangePolicy::NewAddress => RpcChangePolicy::NewAddress,
+            ChangePolicy::ReuseInputAddress => RpcChangePolicy::ReuseInputAddress,
+            ChangePolicy::External(destination) => {
+                RpcChangePolicy::ExternalAddress(RpcAddress::new(&self.chain_config, destination)?)
+            }
+        };
+        Ok(change_policy)
+    }
+
+    pub async fn get_dust_utxos(
+        &self,
+        account_index: U31,
+    ) -> WRpcResult<Vec<(UtxoOutPoint, TxOutput)>, N> {
+        self.wallet
+            .call_read(move |w| w.readonly_controller(account_index).get_dust_utxos())
+            .await?
+    }
+
+    pub async fn release_dust_utxos(
+        &self,
+        account_index: U31,
+        utxos: Vec<RpcUtxoOutpoint>,
+    ) -> WRpcResult<(), N> {
+        let utxos = utxos.into_iter().map(|utxo| utxo.into_outpoint()).collect();
+
+        let config = ControllerConfig {
+            in_top_x_mb: 5,
+            broadcast_to_mempool: true,
+            fee_override: None,
+        }; // irrelevant for releasing UTXOs
+        self.wallet
+            .call_async(move |w| {
+                Box::pin(async move {
+                    w.synced_controller(account_index, config).await?.release_dust_utxos(utxos)
+                })
+            })
+            .await??;
+        Ok(())
+    }
+
+    pub async fn freeze_utxos(
+        &self,
+        account_index: U31,
+        utxos: Vec<RpcUtxoOutpoint>,
+    ) -> WRpcResult<(), N> {
+        let utxos = utxos.into_iter().map(|utxo| utxo.into_outpoint()).collect();
+
+        let config = ControllerConfig {
+            in_top_x_mb: 5,
+            broadcast_to_mempool: true,
+            fee_override: None,
+        }; // irrelevant for freezing UTXOs
+        self.wallet
+            .call_async(move |w| {
+                Box::pin(async move {
+                    w.synced_controller(account_index, config).await?.freeze_utxos(utxos)
+                })
+            })
+            .await??;
+        Ok(())
+    }
+
+    pub async fn unfreeze_utxos(
+        &self,
+        account_index: U31,
+        utxos: Vec<RpcUtxoOutpoint>,
+    ) -> WRpcResult<(), N> {
+        let utxos = utxos.into_iter().map(|utxo| utxo.into_outpoint()).collect();
+
+        let config = ControllerConfig {
+            in_top_x_mb: 5,
+            broadcast_to_mempool: true,
+            fee_override: None,
+        }; // irrelevant for unfreezing UTXOs
+        self.wallet
+            .call_async(move |w| {
+                Box::pin(async move {
+                    w.synced_controller(account_index, config).await?.unfreeze_utxos(utxos)
+                })
+            })
+            .await??;
+        Ok(())
+    }
+
+    pub async fn get_frozen_utxos(
+        &self,
+        account_index: U31,
+    ) -> WRpcResult<Vec<(UtxoOutPoint, TxOutput)>, N> {
+        self.wallet
+            .call_read(move |w| w.readonly_controller(account_index).get_frozen_utxos())
+            .await?
+    }
+
+    pub async fn get_transaction(
+        &self,
+        account_index: U31,
+        transaction_id: Id<Transaction>,
+    ) -> WRpcResult<TxData, N> {
+        self.wallet
+            .call_read(move |controller| {
+                controller
+                    .readonly_controller(account_index)
+                    .get_transaction(transaction_id)
+                    .cloned()
+            })
+            .await?
+    }
+
+    pub async fn describe_transaction(
+        &self,
+        account_index: U31,
+        transaction_id: Id<Transaction>,
+    ) -> WRpcResult<TransactionDescription, N> {
+        self.wallet
+            .call_read_async(move |w| {
+                Box::pin(async move {
+                    w.readonly_controller(account_index).describe_transaction(transaction_id).await
+                })
+            })
+            .await?
+    }
+
+    /// Export the whole transaction history of the account in the given format
+    pub async fn export_transaction_history(
+        &self,
+        account_index: U31,
+        format: TransactionHistoryFormat,
+    ) -> WRpcResult<String, N> {
+        let entries = self
+            .wallet
+            .call_read_async(move |w| {
+                Box::pin(async move {
+                    w.readonly_controller(account_index).get_transaction_history().await
+                })
+            })
+            .await??;
+
+        let exported = match format {
+            TransactionHistoryFormat::Csv => {
+                transaction_history_to_csv(&self.chain_config, &entries)
+            }
+            TransactionHistoryFormat::Json => {
+                serde_json::to_string_pretty(&entries).expect("serialization can't fail")
+            }
+        };
+        Ok(exported)
+    }
+
+    pub async fn pending_transactions(
         &self,
         account_index: U31,
     ) -> WRpcResult<Vec<WithId<Transaction>>, N> {
         self.wallet
-            .call(move |w| {
+            .call_read(move |w| {
                 w.readonly_controller(account_index).pending_transactions().map(|txs| {
                     txs.into_iter().map(|tx| WithId::new(WithId::take(tx).clone())).collect()
                 })
@@ -740,19 +1350,63 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         account_index: U31,
         address: Option<RpcAddress<Destination>>,
         limit: usize,
-    ) -> WRpcResult<Vec<TxInfo>, N> {
+    ) -> WRpcResult<Vec<MainchainTxInfo>, N> {
         let address = address
             .map(|a| a.decode_object(&self.chain_config))
             .transpose()
             .map_err(|_| RpcError::InvalidAddress)?;
 
         self.wallet
-            .call(move |w| {
-                w.readonly_controller(account_index).mainchain_transactions(address, limit)
+            .call_read_async(move |w| {
+                Box::pin(async move {
+                    w.readonly_controller(account_index)
+                        .mainchain_transactions(address, limit)
+                        .await
+                })
             })
             .await?
     }
 
+    pub async fn list_mainchain_transactions(
+        &self,
+        account_index: U31,
+        address: Option<RpcAddress<Destination>>,
+        page: PageRequest,
+    ) -> WRpcResult<TransactionPage, N> {
+        let address = address
+            .map(|a| a.decode_object(&self.chain_config))
+            .transpose()
+            .map_err(|_| RpcError::InvalidAddress)?;
+        let limit = page.limit()?;
+        let after = page.after()?;
+        let newest_first = page.order() == SortOrder::Descending;
+
+        // One more transaction than requested tells whether there is a next page
+        let (transactions, best_synced_block) = self
+            .wallet
+            .call_read_async(move |w| {
+                Box::pin(async move {
+                    let controller = w.readonly_controller(account_index);
+                    let transactions = controller
+                        .mainchain_transactions_page(address, after, newest_first, limit + 1)
+                        .await?;
+                    Ok::<_, ControllerError<N>>((
+                        transactions,
+                        controller.sync_status().best_synced_block.clone(),
+                    ))
+                })
+            })
+            .await??;
+        let (transactions, next_cursor) =
+            PageRequest::finish_page(transactions, limit, |tx| (tx.height, tx.id));
+
+        Ok(TransactionPage {
+            transactions,
+            next_cursor,
+            best_synced_block,
+        })
+    }
+
     pub async fn submit_raw_transaction(
         &self,
         tx: HexEncoded<SignedTransaction>,
@@ -777,6 +1431,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             let config = ControllerConfig {
                 in_top_x_mb: 5,
                 broadcast_to_mempool: true,
+                fee_override: None,
             }; // irrelevant for issuing addresses
             self.wallet
                 .call_async(move |controller| {
@@ -831,6 +1486,86 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             .await?
     }
 
+    pub async fn create_transaction_package(
+        &self,
+        account_index: U31,
+        outputs: Vec<TxOutput>,
+        selected_utxos: Vec<UtxoOutPoint>,
+        change_address: Option<RpcAddress<Destination>>,
+        config: ControllerConfig,
+    ) -> WRpcResult<(TransactionPackage, Balances), N> {
+        let change_address = change_address
+            .map(|change| change.into_address(&self.chain_config))
+            .transpose()
+            .map_err(|_| RpcError::InvalidAddress)?;
+
+        self.wallet
+            .call_async(move |controller| {
+                Box::pin(async move {
+                    controller
+                        .synced_controller(account_index, config)
+                        .await?
+                        .create_transaction_package(outputs, selected_utxos, change_address)
+                        .await
+                        .map_err(RpcError::Controller)
+                })
+            })
+            .await?
+    }
+
+    pub async fn sign_transaction_package(
+        &self,
+        account_index: U31,
+        package: RpcHexString,
+        config: ControllerConfig,
+    ) -> WRpcResult<
+        (
+            TransactionPackage,
+            Vec<SignatureStatus>,
+            Vec<SignatureStatus>,
+        ),
+        N,
+    > {
+        let package = TransactionPackage::decode_all(&mut package.as_ref())
+            .map_err(|_| RpcError::InvalidTransactionPackage)?;
+
+        self.wallet
+            .call_async(move |controller| {
+                Box::pin(async move {
+                    controller
+                        .synced_controller(account_index, config)
+                        .await?
+                        .sign_transaction_package(package)
+                        .map_err(RpcError::Controller)
+                })
+            })
+            .await?
+    }
+
+    pub async fn submit_transaction_package(
+        &self,
+        account_index: U31,
+        package: RpcHexString,
+        config: ControllerConfig,
+    ) -> WRpcResult<NewTransaction, N> {
+        let package = TransactionPackage::decode_all(&mut package.as_ref())
+            .map_err(|_| RpcError::InvalidTransactionPackage)?;
+
+        self.wallet
+            .call_async(move |controller| {
+                Box::pin(async move {
+                    controller
+                        .synced_controller(account_index, config)
+                        .await?
+                        .broadcast_transaction_package(package)
+                        .await
+                        .map_err(RpcError::Controller)
+                        .map(NewTransaction::new)
+                })
+            })
+            .await?
+    }
+
     pub async fn sign_challenge(
         &self,
         account_index: U31,
@@ -840,6 +1575,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            fee_override: None,
         }; // irrelevant for issuing addresses
         let destination = address
             .decode_object(&self.chain_config)
@@ -942,7 +1678,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         amount: RpcAmountIn,
         selected_utxos: Vec<UtxoOutPoint>,
         config: ControllerConfig,
-    ) -> WRpcResult<SignedTransaction, N> {
+    ) -> WRpcResult<(SignedTransaction, Amount), N> {
         let decimals = self.chain_config.coin_decimals();
         let amount = amount.to_amount(decimals).ok_or(RpcError::InvalidCoinAmount)?;
         let address =
@@ -951,12 +1687,256 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         self.wallet
             .call_async(move |controller| {
                 Box::pin(async move {
-                    controller
-                        .synced_controller(account_index, config)
+                    let tx = controller
+                        .synced_controller(account_index, without_broadcast(config))
                         .await?
                         .send_to_address(address, amount, selected_utxos)
                         .await
-                        .map_err(RpcError::Controller)
+                        .map_err(RpcError::Controller)?;
+                    broadcast_with_fee(controller, account_index, config, tx).await
+                })
+            })
+            .await?
+    }
+
+    pub async fn send_to_many(
+        &self,
+        account_index: U31,
+        recipients: Vec<Recipient>,
+        config: ControllerConfig,
+    ) -> WRpcResult<(SignedTransaction, Amount), N> {
+        ensure!(!recipients.is_empty(), RpcError::NoOutputsSpecified);
+
+        let recipients = recipients
+            .into_iter()
+            .map(|recipient| {
+                let address = recipient
+                    .address
+                    .into_address(&self.chain_config)
+                    .map_err(|_| RpcError::InvalidAddress)?;
+                let token_id = recipient
+                    .token_id
+                    .map(|token_id| token_id.decode_object(&self.chain_config))
+                    .transpose()
+                    .map_err(|_| RpcError::InvalidTokenId)?;
+                Ok((address, recipient.amount, token_id))
+            })
+            .collect::<WRpcResult<Vec<_>, N>>()?;
+        let coin_decimals = self.chain_config.coin_decimals();
+
+        self.wallet
+            .call_async(move |controller| {
+                Box::pin(async move {
+                    let mut token_infos = BTreeMap::new();
+                    let mut outputs = Vec::with_capacity(recipients.len());
+                    for (address, amount, token_id) in recipients {
+                        let token_info = match token_id {
+                            Some(token_id) => {
+                                if !token_infos.contains_key(&token_id) {
+                                    let token_info = controller.get_token_info(token_id).await?;
+                                    token_infos.insert(token_id, token_info);
+                                }
+                                token_infos.get(&token_id).cloned()
+                            }
+                            None => None,
+                        };
+                        let decimals = token_info
+                            .as_ref()
+                            .map_or(coin_decimals, |info| info.token_number_of_decimals());
+                        let amount =
+                            amount.to_amount(decimals).ok_or(RpcError::InvalidCoinAmount)?;
+                        outputs.push((address, amount, token_info));
+                    }
+
+                    let tx = controller
+                        .synced_controller(account_index, without_broadcast(config))
+                        .await?
+                        .send_to_many(outputs)
+                        .await
+                        .map_err(RpcError::Controller)?;
+                    broadcast_with_fee(controller, account_index, config, tx).await
+                })
+            })
+            .await?
+    }
+
+    /// Pay the recipients listed in the CSV, in as many transactions as needed.
+    /// All the rows are validated before anything is sent.
+    pub async fn send_payouts_csv(
+        &self,
+        account_index: U31,
+        csv: String,
+        max_payouts_per_tx: Option<usize>,
+        config: ControllerConfig,
+    ) -> WRpcResult<PayoutsReport, N> {
+        let max_payouts_per_tx = max_payouts_per_tx.unwrap_or(DEFAULT_MAX_PAYOUTS_PER_TX);
+
+        let rows = parse_payouts_csv(&csv)?
+            .into_iter()
+            .map(|row| {
+                let address = RpcAddress::<Destination>::from_string(row.address.clone())
+                    .into_address(&self.chain_config)
+                    .map_err(|_| PayoutsCsvError::InvalidAddress(row.line, row.address))?;
+                let token_id = row
+                    .token_id
+                    .map(|token_id| {
+                        RpcAddress::<TokenId>::from_string(token_id.clone())
+                            .decode_object(&self.chain_config)
+                            .map_err(|_| PayoutsCsvError::InvalidTokenId(row.line, token_id))
+                    })
+                    .transpose()?;
+                Ok((row.line, address, row.amount, token_id))
+            })
+            .collect::<Result<Vec<_>, PayoutsCsvError>>()?;
+        let chain_config = self.chain_config.clone();
+
+        self.wallet
+            .call_async(move |controller| {
+                Box::pin(async move {
+                    let coin_decimals = chain_config.coin_decimals();
+                    let mut token_infos = BTreeMap::new();
+                    let mut token_addresses = BTreeMap::new();
+                    let mut checked_totals = BTreeMap::<Option<TokenId>, Amount>::new();
+                    let mut rows_info = Vec::with_capacity(rows.len());
+                    let mut payouts = Vec::with_capacity(rows.len());
+                    for (line, address, amount, token_id) in rows {
+                        let token_info = match token_id {
+                            Some(token_id) => {
+                                if !token_infos.contains_key(&token_id) {
+                                    let token_info = controller.get_token_info(token_id).await?;
+                                    token_infos.insert(token_id, token_info);
+                                    token_addresses.insert(
+                                        token_id,
+                                        RpcAddress::new(&chain_config, token_id)?,
+                                    );
+                                }
+                                token_infos.get(&token_id).cloned()
+                            }
+                            None => None,
+                        };
+                        let decimals = token_info
+                            .as_ref()
+                            .map_or(coin_decimals, |info| info.token_number_of_decimals());
+                        let invalid_amount =
+                            || PayoutsCsvError::InvalidAmount(line, amount.to_string());
+                        let amount = amount
+                            .to_amount(decimals)
+                            .filter(|amount| *amount != Amount::ZERO)
+                            .ok_or_else(invalid_amount)?;
+
+                        // Only the paid rows are summed up in the report, but an overflow
+                        // is caught before anything is sent
+                        let currency_total = checked_totals.entry(token_id).or_insert(Amount::ZERO);
+                        *currency_total = (*currency_total + amount).ok_or_else(invalid_amount)?;
+
+                        rows_info.push((line, token_id, amount, decimals));
+                        payouts.push((address, amount, token_info));
+                    }
+
+                    let batches = controller
+                        .synced_controller(account_index, config)
+                        .await?
+                        .payout_batches(payouts, max_payouts_per_tx)
+                        .map_err(RpcError::Controller)?;
+
+                    // Each transaction is created and its fee is calculated before it's
+                    // broadcast, and the batches that have been sent are reported even if
+                    // a later one fails, so that retrying the rest doesn't pay anyone twice.
+                    let no_broadcast_config = without_broadcast(config);
+                    let mut transactions = Vec::with_capacity(batches.len());
+                    let mut paid_count = 0;
+                    let mut failure = None;
+                    for batch in batches {
+                        let batch_len = batch.len();
+
+                        let prepared = async {
+                            let tx = controller
+                                .synced_controller(account_index, no_broadcast_config)
+                                .await?
+                                .send_to_many(batch)
+                                .await
+                                .map_err(RpcError::Controller)?;
+                            let fee = controller
+                                .get_transaction_coin_fee(tx.transaction())
+                                .await
+                                .map_err(RpcError::Controller)?;
+                            Ok::<_, RpcError<N>>((tx, fee))
+                        }
+                        .await;
+                        let (tx, fee) = match prepared {
+                            Ok(prepared) => prepared,
+                            Err(err) => {
+                                failure = Some(err);
+                                break;
+                            }
+                        };
+
+                        // A transaction whose submission fails stays queued in the wallet and
+                        // is rebroadcast later, so it counts as sent
+                        let broadcast_result = if config.broadcast_to_mempool {
+                            async {
+                                controller
+                                    .synced_controller(account_index, config)
+                                    .await?
+                                    .broadcast_to_mempool(tx.clone())
+                                    .await
+                                    .map_err(RpcError::Controller)
+                            }
+                            .await
+                            .map(|_| ())
+                        } else {
+                            Ok(())
+                        };
+
+                        transactions.push(PayoutTransaction::new(
+                            tx,
+                            batch_len,
+                            fee,
+                            &chain_config,
+                        ));
+                        paid_count += batch_len;
+
+                        if let Err(err) = broadcast_result {
+                            failure = Some(err);
+                            break;
+                        }
+                    }
+
+                    let mut totals = BTreeMap::<Option<TokenId>, (Amount, u8)>::new();
+                    for (_, token_id, amount, decimals) in &rows_info[..paid_count] {
+                        let currency_total =
+                            totals.entry(*token_id).or_insert((Amount::ZERO, *decimals));
+                        currency_total.0 = (currency_total.0 + *amount)
+                            .expect("The total of all the payouts has been checked above");
+                    }
+                    let totals = totals
+                        .into_iter()
+                        .map(|(token_id, (amount, decimals))| CurrencyAmount {
+                            token_id: token_id.map(|token_id| token_addresses[&token_id].clone()),
+                            amount: RpcAmountOut::from_amount_no_padding(amount, decimals),
+                        })
+                        .collect();
+                    let total_fee = transactions
+                        .iter()
+                        .map(|tx| tx.fee.amount())
+                        .sum::<Option<Amount>>()
+                        .expect("The fees are paid from the coin supply and can't overflow");
+
+                    let failure = failure.map(|err| PayoutsFailure {
+                        unpaid_lines: rows_info[paid_count..]
+                            .iter()
+                            .map(|(line, _, _, _)| *line)
+                            .collect(),
+                        error: err.to_string(),
+                    });
+
+                    Ok(PayoutsReport {
+                        transactions,
+                        payouts_count: paid_count,
+                        totals,
+                        total_fee: RpcAmountOut::from_amount_no_padding(total_fee, coin_decimals),
+                        failure,
+                    })
                 })
             })
             .await?
@@ -1031,7 +2011,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         address: RpcAddress<Destination>,
         amount: RpcAmountIn,
         config: ControllerConfig,
-    ) -> WRpcResult<NewTransaction, N> {
+    ) -> WRpcResult<(SignedTransaction, Amount), N> {
         let token_id = token_id
             .decode_object(&self.chain_config)
             .map_err(|_| RpcError::InvalidTokenId)?;
@@ -1046,10 +2026,124 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
                         .to_amount(token_info.token_number_of_decimals())
                         .ok_or(RpcError::InvalidCoinAmount)?;
 
+                    let tx = controller
+                        .synced_controller(account_index, without_broadcast(config))
+                        .await?
+                        .send_tokens_to_address(token_info, address, amount)
+                        .await
+                        .map_err(RpcError::Controller)?;
+                    broadcast_with_fee(controller, account_index, config, tx).await
+                })
+            })
+            .await?
+    }
+
+    pub async fn estimate_max_sendable(
+        &self,
+        account_index: U31,
+        address: RpcAddress<Destination>,
+        token_id: Option<RpcAddress<TokenId>>,
+    ) -> WRpcResult<RpcAmountOut, N> {
+        let destination = address
+            .decode_object(&self.chain_config)
+            .map_err(|_| RpcError::InvalidAddress)?;
+        let token_id = token_id
+            .map(|token_id| token_id.decode_object(&self.chain_config))
+            .transpose()
+            .map_err(|_| RpcError::InvalidTokenId)?;
+        let coin_decimals = self.chain_config.coin_decimals();
+
+        let config = ControllerConfig {
+            in_top_x_mb: 5,
+            broadcast_to_mempool: true,
+            fee_override: None,
+        }; // irrelevant for estimating the amount
+        self.wallet
+            .call_async(move |controller| {
+                Box::pin(async move {
+                    let (currency, decimals) = match token_id {
+                        Some(token_id) => (
+                            Currency::Token(token_id),
+                            controller.get_token_info(token_id).await?.token_number_of_decimals(),
+                        ),
+                        None => (Currency::Coin, coin_decimals),
+                    };
+
                     controller
                         .synced_controller(account_index, config)
                         .await?
-                        .send_tokens_to_address(token_info, address, amount)
+                        .estimate_max_sendable(destination, currency)
+                        .await
+                        .map_err(RpcError::Controller)
+                        .map(|amount| RpcAmountOut::from_amount_no_padding(amount, decimals))
+                })
+            })
+            .await?
+    }
+
+    pub async fn transfer_between_accounts(
+        &self,
+        account_index: U31,
+        to_account: U31,
+        amount: RpcAmountIn,
+        token_id: Option<RpcAddress<TokenId>>,
+        config: ControllerConfig,
+    ) -> WRpcResult<NewTransaction, N> {
+        let token_id = token_id
+            .map(|token_id| token_id.decode_object(&self.chain_config))
+            .transpose()
+            .map_err(|_| RpcError::InvalidTokenId)?;
+        let coin_decimals = self.chain_config.coin_decimals();
+
+        self.wallet
+            .call_async(move |controller| {
+                Box::pin(async move {
+                    let (currency, decimals) = match token_id {
+                        Some(token_id) => (
+                            Currency::Token(token_id),
+                            controller.get_token_info(token_id).await?.token_number_of_decimals(),
+                        ),
+                        None => (Currency::Coin, coin_decimals),
+                    };
+                    let amount = amount.to_amount(decimals).ok_or(RpcError::InvalidCoinAmount)?;
+
+                    controller
+                        .synced_controller(account_index, config)
+                        .await?
+                        .transfer_between_accounts(to_account, amount, currency)
+                        .await
+                        .map_err(RpcError::Controller)
+                        .map(NewTransaction::new)
+                })
+            })
+            .await?
+    }
+
+    pub async fn send_all(
+        &self,
+        account_index: U31,
+        address: RpcAddress<Destination>,
+        token_id: Option<RpcAddress<TokenId>>,
+        config: ControllerConfig,
+    ) -> WRpcResult<NewTransaction, N> {
+        let address =
+            address.into_address(&self.chain_config).map_err(|_| RpcError::InvalidAddress)?;
+        let currency = match token_id {
+            Some(token_id) => Currency::Token(
+                token_id
+                    .decode_object(&self.chain_config)
+                    .map_err(|_| RpcError::InvalidTokenId)?,
+            ),
+            None => Currency::Coin,
+        };
+
+        self.wallet
+            .call_async(move |controller| {
+                Box::pin(async move {
+                    controller
+                        .synced_controller(account_index, config)
+                        .await?
+                        .send_all(address, currency)
                         .await
                         .map_err(RpcError::Controller)
                         .map(NewTransaction::new)
@@ -1369,6 +2463,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            fee_override: None,
         }; // irrelevant for issuing addresses
 
         self.wallet
@@ -1467,6 +2562,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         inputs: Vec<RpcUtxoOutpoint>,
         outputs: Vec<TxOutput>,
         htlc_secrets: Option<Vec<Option<RpcHexString>>>,
+        sighash_types: Option<Vec<RpcSigHashType>>,
         only_transaction: bool,
     ) -> WRpcResult<(TransactionToSign, Balances), N> {
         ensure!(!inputs.is_empty(), RpcError::ComposeTransactionEmptyInputs);
@@ -1490,10 +2586,20 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             })
             .transpose()?;
 
+        let sighash_types =
+            sighash_types.map(|types| types.into_iter().map(SigHashType::from).collect());
+
         self.wallet
             .call_async(move |w| {
                 Box::pin(async move {
-                    w.compose_transaction(inputs, outputs, htlc_secrets, only_transaction).await
+                    w.compose_transaction(
+                        inputs,
+                        outputs,
+                        htlc_secrets,
+                        sighash_types,
+                        only_transaction,
+                    )
+                    .await
                 })
             })
             .await?
@@ -1507,6 +2613,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         let config = ControllerConfig {
             in_top_x_mb: 5,
             broadcast_to_mempool: true,
+            fee_override: None,
         }; // irrelevant for issuing addresses
         self.wallet
             .call_async(move |w| {
@@ -1782,6 +2889,37 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             .await?
     }
 
+    pub async fn rotate_token_authority(
+        &self,
+        account_index: U31,
+        token_id: RpcAddress<TokenId>,
+        address: RpcAddress<Destination>,
+        dry_run: bool,
+        force: bool,
+        config: ControllerConfig,
+    ) -> WRpcResult<TokenAuthorityRotationInfo, N> {
+        let token_id = token_id
+            .decode_object(&self.chain_config)
+            .map_err(|_| RpcError::InvalidTokenId)?;
+        let address =
+            address.into_address(&self.chain_config).map_err(|_| RpcError::InvalidAddress)?;
+        let chain_config = self.chain_config.clone();
+        self.wallet
+            .call_async(move |w| {
+                Box::pin(async move {
+                    let token_info = w.get_token_info(token_id).await?;
+
+                    w.synced_controller(account_index, config)
+                        .await?
+                        .rotate_token_authority(token_info, address, dry_run, force)
+                        .await
+                        .map_err(RpcError::Controller)
+                        .map(|rotation| TokenAuthorityRotationInfo::new(rotation, &chain_config))
+                })
+            })
+            .await?
+    }
+
     pub async fn change_token_metadata_uri(
         &self,
         account_index: U31,
@@ -1809,25 +2947,58 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
     }
 
     pub async fn rescan(&self) -> WRpcResult<(), N> {
-        self.wallet
+        self.wallet.call(|controller| controller.reset_wallet_to_genesis()).await??;
+        self.sync_in_steps().await
+    }
+
+    pub async fn sync(&self) -> WRpcResult<(), N> {
+        self.sync_in_steps().await
+    }
+
+    /// Sync the wallet one batch of blocks per call to the wallet worker, so that the other
+    /// requests, e.g. the read-only ones from a GUI, are served in between during a long sync
+    async fn sync_in_steps(&self) -> WRpcResult<(), N> {
+        loop {
+            let synced = self
+                .wallet
+                .call_async(|controller| Box::pin(async move { controller.sync_step().await }))
+                .await??;
+            if synced {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Check the outputs tracked by the wallet against the node's UTXO set.
+    /// If a repair was done, the rewound blocks are scanned again before returning.
+    pub async fn verify_utxos(
+        &self,
+        sample_size: Option<NonZeroUsize>,
+        repair: bool,
+    ) -> WRpcResult<UtxoVerificationInfo, N> {
+        let report = self
+            .wallet
             .call_async(move |controller| {
-                Box::pin(async move {
-                    controller.reset_wallet_to_genesis()?;
-                    controller.sync_once().await
-                })
+                Box::pin(async move { controller.verify_utxos(sample_size, repair).await })
             })
-            .await?
+            .await??;
+
+        if report.rewound_to.is_some() {
+            self.sync_in_steps().await?;
+        }
+
+        Ok(report.into())
     }
 
-    pub async fn sync(&self) -> WRpcResult<(), N> {
+    pub async fn sync_status(&self) -> WRpcResult<SyncStatus, N> {
         self.wallet
-            .call_async(move |controller| Box::pin(async move { controller.sync_once().await }))
+            .call_read(|controller| Ok::<_, RpcError<N>>(controller.sync_status()))
             .await?
     }
 
     pub async fn list_staking_pools(&self, account_index: U31) -> WRpcResult<Vec<PoolInfo>, N> {
         self.wallet
-            .call_async(move |controller| {
+            .call_read_async(move |controller| {
                 Box::pin(async move {
                     controller.readonly_controller(account_index).get_staking_pools().await
                 })
@@ -1848,7 +3019,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         account_index: U31,
     ) -> WRpcResult<Vec<PoolInfo>, N> {
         self.wallet
-            .call_async(move |controller| {
+            .call_read_async(move |controller| {
                 Box::pin(async move {
                     controller.readonly_controller(account_index).get_pools_for_decommission().await
                 })
@@ -1864,12 +3035,212 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             })
     }
 
+    pub async fn add_watched_pool(
+        &self,
+        account_index: U31,
+        pool_id: RpcAddress<PoolId>,
+        label: Option<String>,
+        decommission_address: Option<RpcAddress<Destination>>,
+        no_rescan: bool,
+    ) -> WRpcResult<(), N> {
+        let pool_id =
+            pool_id.decode_object(&self.chain_config).map_err(|_| RpcError::InvalidPoolId)?;
+        let decommission_key = decommission_address
+            .map(|address| address.decode_object(&self.chain_config))
+            .transpose()
+            .map_err(|_| RpcError::InvalidAddress)?;
+
+        let config = ControllerConfig {
+            in_top_x_mb: 5,
+            broadcast_to_mempool: true,
+            fee_override: None,
+        }; // irrelevant for watching pools
+        self.wallet
+            .call_async(move |w| {
+                Box::pin(async move {
+                    let res = w.synced_controller(account_index, config).await?.add_watched_pool(
+                        pool_id,
+                        label,
+                        decommission_key,
+                    );
+
+                    if !no_rescan {
+                        w.reset_wallet_to_genesis()?;
+                    }
+
+                    res
+                })
+            })
+            .await??;
+        Ok(())
+    }
+
+    pub async fn remove_watched_pool(
+        &self,
+        account_index: U31,
+        pool_id: RpcAddress<PoolId>,
+    ) -> WRpcResult<(), N> {
+        let pool_id =
+            pool_id.decode_object(&self.chain_config).map_err(|_| RpcError::InvalidPoolId)?;
+
+        let config = ControllerConfig {
+            in_top_x_mb: 5,
+            broadcast_to_mempool: true,
+            fee_override: None,
+        }; // irrelevant for watching pools
+        self.wallet
+            .call_async(move |w| {
+                Box::pin(async move {
+                    w.synced_controller(account_index, config).await?.remove_watched_pool(pool_id)
+                })
+            })
+            .await??;
+        Ok(())
+    }
+
+    pub async fn list_watched_pools(
+        &self,
+        account_index: U31,
+    ) -> WRpcResult<Vec<WatchedPoolInfo>, N> {
+        self.wallet
+            .call_read_async(move |controller| {
+                Box::pin(async move {
+                    controller.readonly_controller(account_index).get_watched_pools().await
+                })
+            })
+            .await?
+            .map(|pools| {
+                pools
+                    .into_iter()
+                    .map(|(pool_id, watched_pool, balance, pledge)| {
+                        WatchedPoolInfo::new(
+                            pool_id,
+                            watched_pool,
+                            balance,
+                            pledge,
+                            &self.chain_config,
+                        )
+                    })
+                    .collect()
+            })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn add_scheduled_payment(
+        &self,
+        account_index: U31,
+        address: RpcAddress<Destination>,
+        amount: RpcAmountIn,
+        token_id: Option<RpcAddress<TokenId>>,
+        interval_secs: u64,
+        first_run: Option<BlockTimestamp>,
+        label: Option<String>,
+    ) -> WRpcResult<u64, N> {
+        let destination = address
+            .decode_object(&self.chain_config)
+            .map_err(|_| RpcError::InvalidAddress)?;
+        let token_id = token_id
+            .map(|token_id| token_id.decode_object(&self.chain_config))
+            .transpose()
+            .map_err(|_| RpcError::InvalidTokenId)?;
+        let first_run = first_run.unwrap_or_else(|| BlockTimestamp::from_time(get_time()));
+        let coin_decimals = self.chain_config.coin_decimals();
+
+        let config = ControllerConfig {
+            in_top_x_mb: 5,
+            broadcast_to_mempool: true,
+            fee_override: None,
+        }; // irrelevant for scheduling payments
+        self.wallet
+            .call_async(move |controller| {
+                Box::pin(async move {
+                    let decimals = match token_id {
+                        Some(token_id) => {
+                            controller.get_token_info(token_id).await?.token_number_of_decimals()
+                        }
+                        None => coin_decimals,
+                    };
+                    let amount = amount.to_amount(decimals).ok_or(RpcError::InvalidCoinAmount)?;
+
+                    let payment = ScheduledPayment::new(
+                        label,
+                        destination,
+                        token_id,
+                        amount,
+                        interval_secs,
+                        first_run,
+                    );
+                    controller
+                        .synced_controller(account_index, config)
+                        .await?
+                        .add_scheduled_payment(payment)
+                        .map_err(RpcError::Controller)
+                })
+            })
+            .await?
+    }
+
+    pub async fn cancel_scheduled_payment(
+        &self,
+        account_index: U31,
+        payment_id: u64,
+    ) -> WRpcResult<(), N> {
+        let config = ControllerConfig {
+            in_top_x_mb: 5,
+            broadcast_to_mempool: true,
+            fee_override: None,
+        }; // irrelevant for scheduling payments
+        self.wallet
+            .call_async(move |w| {
+                Box::pin(async move {
+                    w.synced_controller(account_index, config)
+                        .await?
+                        .cancel_scheduled_payment(payment_id)
+                })
+            })
+            .await??;
+        Ok(())
+    }
+
+    pub async fn list_scheduled_payments(
+        &self,
+        account_index: U31,
+    ) -> WRpcResult<Vec<ScheduledPaymentInfo>, N> {
+        let chain_config = self.chain_config.clone();
+        self.wallet
+            .call_read_async(move |controller| {
+                Box::pin(async move {
+                    let payments =
+                        controller.readonly_controller(account_index).get_scheduled_payments()?;
+
+                    let mut result = Vec::with_capacity(payments.len());
+                    for (payment_id, payment) in payments {
+                        let decimals = match payment.token_id {
+                            Some(token_id) => controller
+                                .get_token_info(token_id)
+                                .await?
+                                .token_number_of_decimals(),
+                            None => chain_config.coin_decimals(),
+                        };
+                        result.push(ScheduledPaymentInfo::new(
+                            payment_id,
+                            payment,
+                            decimals,
+                            &chain_config,
+                        ));
+                    }
+                    Ok::<_, RpcError<N>>(result)
+                })
+            })
+            .await?
+    }
+
     pub async fn list_delegation_ids(
         &self,
         account_index: U31,
     ) -> WRpcResult<Vec<DelegationInfo>, N> {
         self.wallet
-            .call_async(move |controller| {
+            .call_read_async(move |controller| {
                 Box::pin(async move {
                     controller.readonly_controller(account_index).get_delegations().await
                 })
@@ -1890,12 +3261,36 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         account_index: U31,
     ) -> WRpcResult<Vec<CreatedBlockInfo>, N> {
         self.wallet
-            .call(move |controller| {
+            .call_read(move |controller| {
                 controller.readonly_controller(account_index).get_created_blocks()
             })
             .await?
     }
 
+    pub async fn get_staking_rewards(
+        &self,
+        account_index: U31,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> WRpcResult<Vec<PoolStakingRewardsInfo>, N> {
+        let rewards = self
+            .wallet
+            .call_read_async(move |controller| {
+                Box::pin(async move {
+                    controller
+                        .readonly_controller(account_index)
+                        .get_staking_rewards(from_height, to_height)
+                        .await
+                })
+            })
+            .await??;
+
+        Ok(PoolStakingRewardsInfo::from_rewards(
+            rewards,
+            &self.chain_config,
+        ))
+    }
+
     pub async fn get_seed_phrase(&self) -> WRpcResult<Option<SeedWithPassPhrase>, N> {
         self.wallet.call(move |controller| controller.seed_phrase()).await?
     }
@@ -1904,6 +3299,233 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
         self.wallet.call(move |controller| controller.delete_seed_phrase()).await?
     }
 
+    pub async fn export_signing_log(
+        &self,
+        from: Option<BlockTimestamp>,
+        to: Option<BlockTimestamp>,
+    ) -> WRpcResult<Vec<SigningLogEntryInfo>, N> {
+        let chain_config = self.chain_config.clone();
+        self.wallet
+            .call_async(move |controller| {
+                Box::pin(async move {
+                    let entries = controller.signing_log(from, to)?;
+
+                    let mut token_decimals = BTreeMap::new();
+                    for token_id in entries
+                        .iter()
+                        .flat_map(|entry| entry.outputs.iter().filter_map(|out| out.token_id))
+                    {
+                        if !token_decimals.contains_key(&token_id) {
+                            let decimals = controller
+                                .get_token_info(token_id)
+                                .await?
+                                .token_number_of_decimals();
+                            token_decimals.insert(token_id, decimals);
+                        }
+                    }
+
+                    let result = entries
+                        .into_iter()
+                        .map(|entry| {
+                            SigningLogEntryInfo::new(entry, &token_decimals, &chain_config)
+                        })
+                        .collect();
+                    Ok::<_, RpcError<N>>(result)
+                })
+            })
+            .await?
+    }
+
+    pub async fn set_token_trust(
+        &self,
+        token_id: RpcAddress<TokenId>,
+        trust: Option<TokenTrust>,
+    ) -> WRpcResult<(), N> {
+        let token_id = token_id
+            .decode_object(&self.chain_config)
+            .map_err(|_| RpcError::InvalidTokenId)?;
+
+        self.wallet
+            .call(move |controller| controller.set_token_trust(token_id, trust))
+            .await??;
+        Ok(())
+    }
+
+    pub async fn list_token_trust(&self) -> WRpcResult<Vec<TokenTrustInfo>, N> {
+        let trust_list =
+            self.wallet.call(move |controller| controller.token_trust_list()).await??;
+
+        let result = trust_list
+            .into_iter()
+            .map(|(token_id, trust)| TokenTrustInfo {
+                token_id: RpcAddress::new(&self.chain_config, token_id).expect("addressable"),
+                trust,
+            })
+            .collect();
+        Ok(result)
+    }
+
+    pub async fn transaction_broadcast_status(
+        &self,
+        transaction_id: Id<Transaction>,
+    ) -> WRpcResult<Option<BroadcastQueueEntryInfo>, N> {
+        let entry = self
+            .wallet
+            .call(move |controller| controller.broadcast_queue_entry(transaction_id))
+            .await??;
+        Ok(entry.map(|entry| BroadcastQueueEntryInfo::new(transaction_id, entry)))
+    }
+
+    pub async fn transaction_broadcast_queue(&self) -> WRpcResult<Vec<BroadcastQueueEntryInfo>, N> {
+        let queue = self.wallet.call(move |controller| controller.broadcast_queue()).await??;
+        Ok(queue
+            .into_iter()
+            .map(|(tx_id, entry)| BroadcastQueueEntryInfo::new(tx_id, entry))
+            .collect())
+    }
+
+    pub async fn set_transaction_broadcast_policy(
+        &self,
+        transaction_id: Id<Transaction>,
+        max_failed_attempts: u32,
+        deadline: Option<BlockTimestamp>,
+    ) -> WRpcResult<(), N> {
+        let policy = BroadcastPolicy {
+            max_failed_attempts,
+            deadline,
+        };
+        self.wallet
+            .call(move |controller| controller.set_broadcast_policy(transaction_id, policy))
+            .await??;
+        Ok(())
+    }
+
+    pub async fn add_payee(
+        &self,
+        name: String,
+        address: RpcAddress<Destination>,
+        default_token: Option<RpcAddress<TokenId>>,
+        notes: Option<String>,
+    ) -> WRpcResult<(), N> {
+        let payee = PayeeInfo {
+            name,
+            address,
+            default_token,
+            notes,
+        };
+        let payees = self.decode_payees([payee])?;
+
+        self.wallet.call(move |controller| controller.set_payees(payees)).await??;
+        Ok(())
+    }
+
+    pub async fn remove_payee(&self, name: String) -> WRpcResult<(), N> {
+        self.wallet.call(move |controller| controller.remove_payee(&name)).await??;
+        Ok(())
+    }
+
+    pub async fn list_payees(&self) -> WRpcResult<Vec<PayeeInfo>, N> {
+        let payees = self.wallet.call(move |controller| controller.payees()).await??;
+
+        let result = payees
+            .into_iter()
+            .map(|(name, payee)| PayeeInfo::new(&self.chain_config, name, payee))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(result)
+    }
+
+    /// Export the address book as a JSON array of payees
+    pub async fn export_payees(&self) -> WRpcResult<String, N> {
+        let payees = self.list_payees().await?;
+        Ok(serde_json::to_string_pretty(&payees).expect("serialization can't fail"))
+    }
+
+    /// Import the payees exported by `export_payees`, replacing the ones with the same names.
+    /// All the addresses are checked before anything is imported.
+    pub async fn import_payees(&self, json: String) -> WRpcResult<usize, N> {
+        let payees: Vec<PayeeInfo> = serde_json::from_str(&json)
+            .map_err(|err| RpcError::InvalidAddressBook(err.to_string()))?;
+        let payees = self.decode_payees(payees)?;
+        let count = payees.len();
+
+        self.wallet.call(move |controller| controller.set_payees(payees)).await??;
+        Ok(count)
+    }
+
+    fn decode_payees(
+        &self,
+        payees: impl IntoIterator<Item = PayeeInfo>,
+    ) -> WRpcResult<BTreeMap<String, Payee>, N> {
+        payees
+            .into_iter()
+            .map(|info| {
+                let address = info
+                    .address
+                    .decode_object(&self.chain_config)
+                    .map_err(|_| RpcError::InvalidAddressWithAddr(info.address.into_string()))?;
+                let default_token = info
+                    .default_token
+                    .map(|token_id| token_id.decode_object(&self.chain_config))
+                    .transpose()
+                    .map_err(|_| RpcError::InvalidTokenId)?;
+                let payee = Payee {
+                    address,
+                    default_token,
+                    notes: info.notes,
+                };
+                Ok((info.name, payee))
+            })
+            .collect()
+    }
+
+    /// Send coins, or the payee's default token if it has one, to a payee from the address book
+    pub async fn send_to_payee(
+        &self,
+        account_index: U31,
+        name: String,
+        amount: RpcAmountIn,
+        config: ControllerConfig,
+    ) -> WRpcResult<NewTransaction, N> {
+        let chain_config = self.chain_config.clone();
+
+        self.wallet
+            .call_async(move |controller| {
+                Box::pin(async move {
+                    let payee = controller.payee(&name)?;
+                    let address = Address::new(&chain_config, payee.address)?;
+
+                    let result = match payee.default_token {
+                        Some(token_id) => {
+                            let token_info = controller.get_token_info(token_id).await?;
+                            let amount = amount
+                                .to_amount(token_info.token_number_of_decimals())
+                                .ok_or(RpcError::InvalidCoinAmount)?;
+
+                            controller
+                                .synced_controller(account_index, config)
+                                .await?
+                                .send_tokens_to_address(token_info, address, amount)
+                                .await
+                        }
+                        None => {
+                            let amount = amount
+                                .to_amount(chain_config.coin_decimals())
+                                .ok_or(RpcError::InvalidCoinAmount)?;
+
+                            controller
+                                .synced_controller(account_index, config)
+                                .await?
+                                .send_to_address(address, amount, vec![])
+                                .await
+                        }
+                    };
+
+                    result.map_err(RpcError::Controller).map(NewTransaction::new)
+                })
+            })
+            .await?
+    }
+
     pub async fn wallet_info(&self) -> WRpcResult<WalletInfo, N> {
         self.wallet
             .call(move |controller| Ok::<_, RpcError<N>>(controller.wallet_info()))
@@ -1924,6 +3546,26 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
             .map(|balance| balance.into_fixedpoint_str(self.chain_config.coin_decimals())))
     }
 
+    pub async fn staking_calculator(
+        &self,
+        pledge: RpcAmountIn,
+        pool_balance: RpcAmountIn,
+    ) -> WRpcResult<StakingEstimate, N> {
+        let decimals = self.chain_config.coin_decimals();
+        let pledge = pledge.to_amount(decimals).ok_or(RpcError::InvalidCoinAmount)?;
+        let pool_balance = pool_balance.to_amount(decimals).ok_or(RpcError::InvalidCoinAmount)?;
+
+        let estimate = wallet_controller::fetch_staking_estimate(
+            &self.node,
+            &self.chain_config,
+            pledge,
+            pool_balance,
+        )
+        .await?;
+
+        Ok(estimate)
+    }
+
     pub async fn node_version(&self) -> WRpcResult<String, N> {
         self.node.node_version().await.map_err(RpcError::RpcError)
     }
@@ -2024,6 +3666,60 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletRpc<N> {
     }
 }
 
+/// The config for creating a transaction that is broadcast separately, once its fee is known
+fn without_broadcast(config: ControllerConfig) -> ControllerConfig {
+    ControllerConfig {
+        broadcast_to_mempool: false,
+        ..config
+    }
+}
+
+/// Calculate the coin fee of a transaction created with `without_broadcast` and then broadcast it
+/// if the config says so.
+///
+/// A failure to calculate the fee must not hide a transaction that has already been sent,
+/// otherwise a retry would pay twice.
+async fn broadcast_with_fee<N: NodeInterface + Clone + Send + Sync + 'static>(
+    controller: &mut WalletController<N>,
+    account_index: U31,
+    config: ControllerConfig,
+    tx: SignedTransaction,
+) -> WRpcResult<(SignedTransaction, Amount), N> {
+    let fee = controller
+        .get_transaction_coin_fee(tx.transaction())
+        .await
+        .map_err(RpcError::Controller)?;
+
+    if config.broadcast_to_mempool {
+        controller
+            .synced_controller(account_index, config)
+            .await?
+            .broadcast_to_mempool(tx.clone())
+            .await
+            .map_err(RpcError::Controller)?;
+    }
+
+    Ok((tx, fee))
+}
+
+/// The amount of coins in a utxo, used to sort the utxos by value
+fn utxo_coin_amount(output: &TxOutput) -> Amount {
+    match output {
+        TxOutput::Transfer(value, _)
+        | TxOutput::LockThenTransfer(value, _, _)
+        | TxOutput::Htlc(value, _) => value.coin_amount().unwrap_or(Amount::ZERO),
+        TxOutput::CreateStakePool(_, pool_data) => pool_data.pledge(),
+        TxOutput::Burn(_)
+        | TxOutput::ProduceBlockFromStake(_, _)
+        | TxOutput::CreateDelegationId(_, _)
+        | TxOutput::DelegateStaking(_, _)
+        | TxOutput::IssueFungibleToken(_)
+        | TxOutput::IssueNft(_, _, _)
+        | TxOutput::DataDeposit(_)
+        | TxOutput::AnyoneCanTake(_) => Amount::ZERO,
+    }
+}
+
 pub async fn start<N: NodeInterface + Clone + Send + Sync + Debug + 'static>(
     wallet_handle: WalletHandle<N>,
     node_rpc: N,
@@ -26,30 +26,38 @@ use common::{
     },
     primitives::{time::Time, BlockHeight, Id, Idable},
 };
-use crypto::key::PrivateKey;
+use crypto::key::{extended::ExtendedPublicKey, PrivateKey};
 use p2p_types::{bannable_address::BannableAddress, socket_address::SocketAddress, PeerId};
 use serialization::{hex::HexEncode, json_encoded::JsonEncoded};
 use utils_networking::IpOrSocketAddress;
-use wallet::{account::TxInfo, version::get_version};
+use wallet::version::get_version;
 use wallet_controller::{
-    types::{BlockInfo, CreatedBlockInfo, GenericTokenTransfer, SeedWithPassPhrase, WalletInfo},
-    ConnectedPeer, ControllerConfig, NodeInterface, UtxoState, UtxoStates, UtxoType, UtxoTypes,
+    types::{
+        BlockInfo, CreatedBlockInfo, GenericTokenTransfer, MainchainTxInfo, SeedWithPassPhrase,
+        SyncStatus, TransactionDescription, WalletInfo,
+    },
+    ConnectedPeer, NodeInterface, UtxoState, UtxoStates, UtxoType, UtxoTypes,
 };
 use wallet_types::{
-    seed_phrase::StoreSeedPhrase, signature_status::SignatureStatus, with_locked::WithLocked,
+    multisig_account::MultisigAccountDescriptor, seed_phrase::StoreSeedPhrase,
+    signature_status::SignatureStatus, token_trust::TokenTrust, with_locked::WithLocked,
 };
 
 use crate::{
     rpc::{ColdWalletRpcServer, WalletEventsRpcServer, WalletRpc, WalletRpcServer},
     types::{
-        AccountArg, AddressInfo, AddressWithUsageInfo, Balances, ChainInfo, ComposedTransaction,
-        CreatedWallet, DelegationInfo, HexEncoded, JsonValue, LegacyVrfPublicKeyInfo,
-        MaybeSignedTransaction, NewAccountInfo, NewDelegation, NewTransaction, NftMetadata,
-        NodeVersion, PoolInfo, PublicKeyInfo, RpcAddress, RpcAmountIn, RpcHexString,
-        RpcInspectTransaction, RpcStandaloneAddresses, RpcTokenId, RpcUtxoOutpoint, RpcUtxoState,
-        RpcUtxoType, SendTokensFromMultisigAddressResult, StakePoolBalance, StakingStatus,
-        StandaloneAddressWithDetails, TokenMetadata, TransactionOptions, TxOptionsOverrides,
-        UtxoInfo, VrfPublicKeyInfo,
+        AccountArg, AddressInfo, AddressPage, AddressWithUsageInfo, Balances,
+        BroadcastQueueEntryInfo, ChainInfo, ComposedTransaction, CreatedWallet, DelegationInfo,
+        HexEncoded, JsonValue, LegacyVrfPublicKeyInfo, MaybeSignedTransaction, NewAccountInfo,
+        NewDelegation, NewTransaction, NftMetadata, NodeVersion, PageRequest, PayeeInfo,
+        PayoutsReport, PoolInfo, PoolStakingRewardsInfo, PublicKeyInfo, Recipient, RpcAddress,
+        RpcAmountIn, RpcAmountOut, RpcChangePolicy, RpcHexString, RpcInspectTransaction,
+        RpcSigHashType, RpcStandaloneAddresses, RpcTokenId, RpcUtxoOutpoint, RpcUtxoState,
+        RpcUtxoType, ScheduledPaymentInfo, SendTokensFromMultisigAddressResult, SentTransaction,
+        SigningLogEntryInfo, StakePoolBalance, StakingEstimate, StakingStatus,
+        StandaloneAddressWithDetails, TokenAuthorityRotationInfo, TokenMetadata, TokenTrustInfo,
+        TransactionHistoryFormat, TransactionOptions, TransactionPage, TxOptionsOverrides,
+        UtxoInfo, UtxoPage, UtxoSortKey, UtxoVerificationInfo, VrfPublicKeyInfo, WatchedPoolInfo,
     },
     RpcError,
 };
@@ -146,6 +154,34 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> ColdWalletRpcServ
         rpc::handle_result(self.set_lookahead_size(lookahead_size, i_know_what_i_am_doing).await)
     }
 
+    async fn export_watch_only(
+        &self,
+        passphrase: String,
+        since_height: Option<BlockHeight>,
+    ) -> rpc::RpcResult<RpcHexString> {
+        rpc::handle_result(self.export_watch_only(passphrase, since_height).await)
+    }
+
+    async fn create_wallet_from_watch_only_export(
+        &self,
+        path: String,
+        data: RpcHexString,
+        passphrase: String,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(
+            self.create_wallet_from_watch_only_export(path.into(), data.into_bytes(), passphrase)
+                .await,
+        )
+    }
+
+    async fn apply_watch_only_update(
+        &self,
+        data: RpcHexString,
+        passphrase: String,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(self.apply_watch_only_update(data.into_bytes(), passphrase).await)
+    }
+
     async fn encrypt_private_keys(&self, password: String) -> rpc::RpcResult<()> {
         rpc::handle_result(self.encrypt_private_keys(password).await)
     }
@@ -198,6 +234,18 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> ColdWalletRpcServ
         rpc::handle_result(self.get_issued_addresses(account_arg.index::<N>()?).await)
     }
 
+    async fn list_issued_addresses(
+        &self,
+        account_arg: AccountArg,
+        used: Option<bool>,
+        page: Option<PageRequest>,
+    ) -> rpc::RpcResult<AddressPage> {
+        rpc::handle_result(
+            self.list_issued_addresses(account_arg.index::<N>()?, used, page.unwrap_or_default())
+                .await,
+        )
+    }
+
     async fn new_vrf_public_key(
         &self,
         account_arg: AccountArg,
@@ -225,10 +273,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> ColdWalletRpcServ
         raw_tx: RpcHexString,
         options: TransactionOptions,
     ) -> rpc::RpcResult<MaybeSignedTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
         rpc::handle_result(
             self.sign_raw_transaction(account_arg.index::<N>()?, raw_tx, config).await.map(
                 |(tx, prev_signatures, cur_signatures)| {
@@ -255,6 +300,33 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> ColdWalletRpcServ
         )
     }
 
+    async fn sign_transaction_package(
+        &self,
+        account_arg: AccountArg,
+        package: RpcHexString,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<MaybeSignedTransaction> {
+        let config = options.controller_config::<N>(&self.chain_config)?;
+        rpc::handle_result(
+            self.sign_transaction_package(account_arg.index::<N>()?, package, config)
+                .await
+                .map(|(package, prev_signatures, cur_signatures)| {
+                    let is_complete = package.is_fully_signed()
+                        && cur_signatures.iter().all(|s| *s == SignatureStatus::FullySigned);
+
+                    let previous_signatures = prev_signatures.into_iter().map(Into::into).collect();
+                    let current_signatures = cur_signatures.into_iter().map(Into::into).collect();
+
+                    MaybeSignedTransaction {
+                        hex: package.hex_encode(),
+                        is_complete,
+                        previous_signatures,
+                        current_signatures,
+                    }
+                }),
+        )
+    }
+
     async fn sign_challenge(
         &self,
         account_arg: AccountArg,
@@ -316,6 +388,26 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         rpc::handle_result(self.best_block().await)
     }
 
+    async fn sync_status(&self) -> rpc::RpcResult<SyncStatus> {
+        rpc::handle_result(self.sync_status().await)
+    }
+
+    async fn verify_utxos(
+        &self,
+        sample_size: Option<NonZeroUsize>,
+        repair: bool,
+    ) -> rpc::RpcResult<UtxoVerificationInfo> {
+        rpc::handle_result(self.verify_utxos(sample_size, repair).await)
+    }
+
+    async fn export_signing_log(
+        &self,
+        from: Option<BlockTimestamp>,
+        to: Option<BlockTimestamp>,
+    ) -> rpc::RpcResult<Vec<SigningLogEntryInfo>> {
+        rpc::handle_result(self.export_signing_log(from, to).await)
+    }
+
     async fn create_account(&self, name: Option<String>) -> rpc::RpcResult<NewAccountInfo> {
         rpc::handle_result(self.create_account(name).await)
     }
@@ -328,6 +420,15 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         rpc::handle_result(self.update_account_name(account_arg.index::<N>()?, name).await)
     }
 
+    async fn set_address_label(
+        &self,
+        account_arg: AccountArg,
+        address: RpcAddress<Destination>,
+        label: Option<String>,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(self.set_address_label(account_arg.index::<N>()?, address, label).await)
+    }
+
     async fn standalone_address_label_rename(
         &self,
         account_arg: AccountArg,
@@ -358,6 +459,17 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         )
     }
 
+    async fn remove_standalone_address(
+        &self,
+        account_arg: AccountArg,
+        address: RpcAddress<Destination>,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(
+            self.remove_standalone_watch_only_address(account_arg.index::<N>()?, address)
+                .await,
+        )
+    }
+
     async fn add_standalone_private_key(
         &self,
         account_arg: AccountArg,
@@ -376,6 +488,35 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         )
     }
 
+    async fn import_standalone_private_key(
+        &self,
+        account_arg: AccountArg,
+        private_key: RpcAddress<PrivateKey>,
+        label: Option<String>,
+        no_rescan: Option<bool>,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(
+            self.import_standalone_private_key(
+                account_arg.index::<N>()?,
+                private_key,
+                label,
+                no_rescan.unwrap_or(false),
+            )
+            .await,
+        )
+    }
+
+    async fn export_private_key(
+        &self,
+        account_arg: AccountArg,
+        address: RpcAddress<Destination>,
+        confirm: bool,
+    ) -> rpc::RpcResult<RpcAddress<PrivateKey>> {
+        rpc::handle_result(
+            self.export_private_key(account_arg.index::<N>()?, address, confirm).await,
+        )
+    }
+
     async fn add_standalone_multisig(
         &self,
         account_arg: AccountArg,
@@ -452,6 +593,119 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         rpc::handle_result(result)
     }
 
+    async fn get_standalone_address_utxos(
+        &self,
+        account_arg: AccountArg,
+        address: RpcAddress<Destination>,
+        utxo_types: Vec<RpcUtxoType>,
+        utxo_states: Vec<RpcUtxoState>,
+        with_locked: Option<WithLocked>,
+    ) -> rpc::RpcResult<Vec<JsonValue>> {
+        let utxo_types = (&utxo_types.iter().map(UtxoType::from).collect::<Vec<_>>())
+            .try_into()
+            .unwrap_or(UtxoTypes::ALL);
+
+        let utxo_states = (&utxo_states.iter().map(UtxoState::from).collect::<Vec<_>>())
+            .try_into()
+            .unwrap_or(UtxoState::Confirmed.into());
+
+        let utxos = self
+            .get_standalone_address_utxos(
+                account_arg.index::<N>()?,
+                address,
+                utxo_types,
+                utxo_states,
+                with_locked.unwrap_or(WithLocked::Unlocked),
+            )
+            .await?;
+
+        let result = utxos
+            .into_iter()
+            .map(|(utxo_outpoint, tx_ouput)| {
+                let result = UtxoInfo::new(utxo_outpoint, tx_ouput, &self.chain_config)
+                    .map(serde_json::to_value);
+                rpc::handle_result(result)
+            })
+            .collect::<Result<Vec<_>, _>>();
+
+        rpc::handle_result(result)
+    }
+
+    async fn account_extended_public_key(
+        &self,
+        account_arg: AccountArg,
+    ) -> rpc::RpcResult<HexEncoded<ExtendedPublicKey>> {
+        rpc::handle_result(
+            self.account_extended_public_key(account_arg.index::<N>()?)
+                .await
+                .map(HexEncoded::new),
+        )
+    }
+
+    async fn export_account_extended_public_key(
+        &self,
+        account_arg: AccountArg,
+    ) -> rpc::RpcResult<RpcAddress<ExtendedPublicKey>> {
+        rpc::handle_result(self.export_account_extended_public_key(account_arg.index::<N>()?).await)
+    }
+
+    async fn import_extended_public_key(
+        &self,
+        account_arg: AccountArg,
+        extended_public_key: RpcAddress<ExtendedPublicKey>,
+        address_count: u32,
+        label: Option<String>,
+        no_rescan: Option<bool>,
+    ) -> rpc::RpcResult<Vec<String>> {
+        rpc::handle_result(
+            self.import_extended_public_key(
+                account_arg.index::<N>()?,
+                extended_public_key,
+                address_count,
+                label,
+                no_rescan.unwrap_or(false),
+            )
+            .await,
+        )
+    }
+
+    async fn create_multisig_account_descriptor(
+        &self,
+        account_arg: AccountArg,
+        min_required_signatures: u8,
+        cosigner_keys: Vec<HexEncoded<ExtendedPublicKey>>,
+    ) -> rpc::RpcResult<HexEncoded<MultisigAccountDescriptor>> {
+        rpc::handle_result(
+            self.create_multisig_account_descriptor(
+                account_arg.index::<N>()?,
+                min_required_signatures,
+                cosigner_keys.into_iter().map(HexEncoded::take).collect(),
+            )
+            .await
+            .map(HexEncoded::new),
+        )
+    }
+
+    async fn import_multisig_account(
+        &self,
+        account_arg: AccountArg,
+        descriptor: HexEncoded<MultisigAccountDescriptor>,
+        address_count: u32,
+        label: Option<String>,
+        no_rescan: Option<bool>,
+    ) -> rpc::RpcResult<Vec<String>> {
+        rpc::handle_result(
+            self.import_multisig_account(
+                account_arg.index::<N>()?,
+                descriptor.take(),
+                address_count,
+                label,
+                no_rescan.unwrap_or(false),
+            )
+            .await,
+        )
+    }
+
     async fn get_utxos(&self, account_arg: AccountArg) -> rpc::RpcResult<Vec<JsonValue>> {
         let utxos = self
             .get_utxos(
@@ -474,6 +728,132 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         rpc::handle_result(result)
     }
 
+    async fn list_utxos(
+        &self,
+        account_arg: AccountArg,
+        utxo_types: Vec<RpcUtxoType>,
+        utxo_states: Vec<RpcUtxoState>,
+        with_locked: Option<WithLocked>,
+        sort_by: Option<UtxoSortKey>,
+        page: Option<PageRequest>,
+    ) -> rpc::RpcResult<UtxoPage> {
+        let utxo_types = (&utxo_types.iter().map(UtxoType::from).collect::<Vec<_>>())
+            .try_into()
+            .unwrap_or(UtxoTypes::ALL);
+
+        let utxo_states = (&utxo_states.iter().map(UtxoState::from).collect::<Vec<_>>())
+            .try_into()
+            .unwrap_or(UtxoState::Confirmed.into());
+
+        let (utxos, next_cursor, best_synced_block) = self
+            .list_utxos(
+                account_arg.index::<N>()?,
+                utxo_types,
+                utxo_states,
+                with_locked.unwrap_or(WithLocked::Unlocked),
+                sort_by.unwrap_or_default(),
+                page.unwrap_or_default(),
+            )
+            .await?;
+
+        let utxos = utxos
+            .into_iter()
+            .map(|(utxo_outpoint, tx_ouput)| {
+                let result = UtxoInfo::new(utxo_outpoint, tx_ouput, &self.chain_config)
+                    .map(serde_json::to_value);
+                rpc::handle_result(result)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(UtxoPage {
+            utxos,
+            next_cursor,
+            best_synced_block,
+        })
+    }
+
+    async fn set_dust_protection(
+        &self,
+        account_arg: AccountArg,
+        min_coin_amount: Option<RpcAmountIn>,
+        flag_unknown_tokens: bool,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(
+            self.set_dust_protection(
+                account_arg.index::<N>()?,
+                min_coin_amount,
+                flag_unknown_tokens,
+            )
+            .await,
+        )
+    }
+
+    async fn set_change_policy(
+        &self,
+        account_arg: AccountArg,
+        change_policy: RpcChangePolicy,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(self.set_change_policy(account_arg.index::<N>()?, change_policy).await)
+    }
+
+    async fn get_change_policy(&self, account_arg: AccountArg) -> rpc::RpcResult<RpcChangePolicy> {
+        rpc::handle_result(self.get_change_policy(account_arg.index::<N>()?).await)
+    }
+
+    async fn get_dust_utxos(&self, account_arg: AccountArg) -> rpc::RpcResult<Vec<JsonValue>> {
+        let utxos = self.get_dust_utxos(account_arg.index::<N>()?).await?;
+
+        let result = utxos
+            .into_iter()
+            .map(|(utxo_outpoint, tx_ouput)| {
+                let result = UtxoInfo::new(utxo_outpoint, tx_ouput, &self.chain_config)
+                    .map(serde_json::to_value);
+                rpc::handle_result(result)
+            })
+            .collect::<Result<Vec<_>, _>>();
+
+        rpc::handle_result(result)
+    }
+
+    async fn release_dust_utxos(
+        &self,
+        account_arg: AccountArg,
+        utxos: Vec<RpcUtxoOutpoint>,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(self.release_dust_utxos(account_arg.index::<N>()?, utxos).await)
+    }
+
+    async fn freeze_utxos(
+        &self,
+        account_arg: AccountArg,
+        utxos: Vec<RpcUtxoOutpoint>,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(self.freeze_utxos(account_arg.index::<N>()?, utxos).await)
+    }
+
+    async fn unfreeze_utxos(
+        &self,
+        account_arg: AccountArg,
+        utxos: Vec<RpcUtxoOutpoint>,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(self.unfreeze_utxos(account_arg.index::<N>()?, utxos).await)
+    }
+
+    async fn get_frozen_utxos(&self, account_arg: AccountArg) -> rpc::RpcResult<Vec<JsonValue>> {
+        let utxos = self.get_frozen_utxos(account_arg.index::<N>()?).await?;
+
+        let result = utxos
+            .into_iter()
+            .map(|(utxo_outpoint, tx_ouput)| {
+                let result = UtxoInfo::new(utxo_outpoint, tx_ouput, &self.chain_config)
+                    .map(serde_json::to_value);
+                rpc::handle_result(result)
+            })
+            .collect::<Result<Vec<_>, _>>();
+
+        rpc::handle_result(result)
+    }
+
     async fn submit_raw_transaction(
         &self,
         tx: HexEncoded<SignedTransaction>,
@@ -490,21 +870,46 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         amount: RpcAmountIn,
         selected_utxos: Vec<RpcUtxoOutpoint>,
         options: TransactionOptions,
-    ) -> rpc::RpcResult<NewTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+    ) -> rpc::RpcResult<SentTransaction> {
+        let config = options.controller_config::<N>(&self.chain_config)?;
+        rpc::handle_result(
+            self.send_coins(
+                account_arg.index::<N>()?,
+                address,
+                amount,
+                selected_utxos.into_iter().map(|o| o.into_outpoint()).collect(),
+                config,
+            )
+            .await
+            .map(|(tx, fee)| SentTransaction::new(&tx, fee, &self.chain_config)),
+        )
+    }
+
+    async fn send_to_many(
+        &self,
+        account_arg: AccountArg,
+        recipients: Vec<Recipient>,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<SentTransaction> {
+        let config = options.controller_config::<N>(&self.chain_config)?;
+        rpc::handle_result(
+            self.send_to_many(account_arg.index::<N>()?, recipients, config)
+                .await
+                .map(|(tx, fee)| SentTransaction::new(&tx, fee, &self.chain_config)),
+        )
+    }
+
+    async fn send_payouts_csv(
+        &self,
+        account_arg: AccountArg,
+        csv: String,
+        max_outputs_per_tx: Option<usize>,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<PayoutsReport> {
+        let config = options.controller_config::<N>(&self.chain_config)?;
         rpc::handle_result(
-            self.send_coins(
-                account_arg.index::<N>()?,
-                address,
-                amount,
-                selected_utxos.into_iter().map(|o| o.into_outpoint()).collect(),
-                config,
-            )
-            .await
-            .map(NewTransaction::new),
+            self.send_payouts_csv(account_arg.index::<N>()?, csv, max_outputs_per_tx, config)
+                .await,
         )
     }
 
@@ -515,10 +920,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         from_addresses: Vec<RpcAddress<Destination>>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
         rpc::handle_result(
             self.sweep_addresses(
                 account.index::<N>()?,
@@ -530,6 +932,49 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         )
     }
 
+    async fn estimate_max_sendable(
+        &self,
+        account: AccountArg,
+        address: RpcAddress<Destination>,
+        token_id: Option<RpcAddress<TokenId>>,
+    ) -> rpc::RpcResult<RpcAmountOut> {
+        rpc::handle_result(
+            self.estimate_max_sendable(account.index::<N>()?, address, token_id).await,
+        )
+    }
+
+    async fn send_all(
+        &self,
+        account: AccountArg,
+        address: RpcAddress<Destination>,
+        token_id: Option<RpcAddress<TokenId>>,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<NewTransaction> {
+        let config = options.controller_config::<N>(&self.chain_config)?;
+        rpc::handle_result(self.send_all(account.index::<N>()?, address, token_id, config).await)
+    }
+
+    async fn transfer_between_accounts(
+        &self,
+        account: AccountArg,
+        to_account: AccountArg,
+        amount: RpcAmountIn,
+        token_id: Option<RpcAddress<TokenId>>,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<NewTransaction> {
+        let config = options.controller_config::<N>(&self.chain_config)?;
+        rpc::handle_result(
+            self.transfer_between_accounts(
+                account.index::<N>()?,
+                to_account.index::<N>()?,
+                amount,
+                token_id,
+                config,
+            )
+            .await,
+        )
+    }
+
     async fn sweep_delegation(
         &self,
         account: AccountArg,
@@ -537,10 +982,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         delegation_id: RpcAddress<DelegationId>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
         rpc::handle_result(
             self.sweep_delegation(
                 account.index::<N>()?,
@@ -561,10 +1003,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         change_address: Option<RpcAddress<Destination>>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<ComposedTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
         rpc::handle_result(
             self.request_send_coins(
                 account_arg.index::<N>()?,
@@ -582,6 +1021,45 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         )
     }
 
+    async fn create_transaction_package(
+        &self,
+        account_arg: AccountArg,
+        outputs: Vec<TxOutput>,
+        selected_utxos: Vec<RpcUtxoOutpoint>,
+        change_address: Option<RpcAddress<Destination>>,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<ComposedTransaction> {
+        let config = options.controller_config::<N>(&self.chain_config)?;
+        let selected_utxos = selected_utxos.into_iter().map(|o| o.into_outpoint()).collect();
+        rpc::handle_result(
+            self.create_transaction_package(
+                account_arg.index::<N>()?,
+                outputs,
+                selected_utxos,
+                change_address,
+                config,
+            )
+            .await
+            .map(|(package, fees)| ComposedTransaction {
+                hex: package.hex_encode(),
+                fees,
+            }),
+        )
+    }
+
+    async fn submit_transaction_package(
+        &self,
+        account_arg: AccountArg,
+        package: RpcHexString,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<NewTransaction> {
+        let config = options.controller_config::<N>(&self.chain_config)?;
+        rpc::handle_result(
+            self.submit_transaction_package(account_arg.index::<N>()?, package, config)
+                .await,
+        )
+    }
+
     async fn transaction_inspect(
         &self,
         transaction: RpcHexString,
@@ -600,10 +1078,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         decommission_address: RpcAddress<Destination>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
         rpc::handle_result(
             self.create_stake_pool(
                 account_arg.index::<N>()?,
@@ -625,10 +1100,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         output_address: Option<RpcAddress<Destination>>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
         rpc::handle_result(
             self.decommission_stake_pool(
                 account_arg.index::<N>()?,
@@ -648,10 +1120,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         output_address: Option<RpcAddress<Destination>>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<HexEncoded<PartiallySignedTransaction>> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
         rpc::handle_result(
             self.decommission_stake_pool_request(
                 account_arg.index::<N>()?,
@@ -671,10 +1140,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         pool_id: RpcAddress<PoolId>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewDelegation> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
         rpc::handle_result(
             self.create_delegation(account_arg.index::<N>()?, address, pool_id, config)
                 .await
@@ -692,10 +1158,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         delegation_id: RpcAddress<DelegationId>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
         rpc::handle_result(
             self.delegate_staking(account_arg.index::<N>()?, amount, delegation_id, config)
                 .await
@@ -711,10 +1174,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         delegation_id: RpcAddress<DelegationId>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
         rpc::handle_result(
             self.withdraw_from_delegation(
                 account_arg.index::<N>()?,
@@ -751,6 +1211,82 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         rpc::handle_result(self.list_pools_for_decommission(account_arg.index::<N>()?).await)
     }
 
+    async fn add_watched_pool(
+        &self,
+        account_arg: AccountArg,
+        pool_id: RpcAddress<PoolId>,
+        label: Option<String>,
+        decommission_address: Option<RpcAddress<Destination>>,
+        no_rescan: Option<bool>,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(
+            self.add_watched_pool(
+                account_arg.index::<N>()?,
+                pool_id,
+                label,
+                decommission_address,
+                no_rescan.unwrap_or(false),
+            )
+            .await,
+        )
+    }
+
+    async fn remove_watched_pool(
+        &self,
+        account_arg: AccountArg,
+        pool_id: RpcAddress<PoolId>,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(self.remove_watched_pool(account_arg.index::<N>()?, pool_id).await)
+    }
+
+    async fn list_watched_pools(
+        &self,
+        account_arg: AccountArg,
+    ) -> rpc::RpcResult<Vec<WatchedPoolInfo>> {
+        rpc::handle_result(self.list_watched_pools(account_arg.index::<N>()?).await)
+    }
+
+    async fn add_scheduled_payment(
+        &self,
+        account_arg: AccountArg,
+        address: RpcAddress<Destination>,
+        amount: RpcAmountIn,
+        token_id: Option<RpcAddress<TokenId>>,
+        interval_secs: u64,
+        first_run: Option<BlockTimestamp>,
+        label: Option<String>,
+    ) -> rpc::RpcResult<u64> {
+        rpc::handle_result(
+            self.add_scheduled_payment(
+                account_arg.index::<N>()?,
+                address,
+                amount,
+                token_id,
+                interval_secs,
+                first_run,
+                label,
+            )
+            .await,
+        )
+    }
+
+    async fn cancel_scheduled_payment(
+        &self,
+        account_arg: AccountArg,
+        payment_id: u64,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(
+            self.cancel_scheduled_payment(account_arg.index::<N>()?, payment_id).await,
+        )
+    }
+
+    async fn list_scheduled_payments(
+        &self,
+        account_arg: AccountArg,
+    ) -> rpc::RpcResult<Vec<ScheduledPaymentInfo>> {
+        rpc::handle_result(self.list_scheduled_payments(account_arg.index::<N>()?).await)
+    }
+
     async fn list_delegation_ids(
         &self,
         account_arg: AccountArg,
@@ -765,6 +1301,18 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         rpc::handle_result(self.list_created_blocks_ids(account_arg.index::<N>()?).await)
     }
 
+    async fn get_staking_rewards(
+        &self,
+        account_arg: AccountArg,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> rpc::RpcResult<Vec<PoolStakingRewardsInfo>> {
+        rpc::handle_result(
+            self.get_staking_rewards(account_arg.index::<N>()?, from_height, to_height)
+                .await,
+        )
+    }
+
     async fn issue_new_nft(
         &self,
         account_arg: AccountArg,
@@ -772,10 +1320,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         metadata: NftMetadata,
         options: TransactionOptions,
     ) -> rpc::RpcResult<RpcTokenId> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
 
         rpc::handle_result(
             self.issue_new_nft(
@@ -795,10 +1340,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         metadata: TokenMetadata,
         options: TransactionOptions,
     ) -> rpc::RpcResult<RpcTokenId> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
 
         let token_supply = metadata.token_supply::<N>()?;
         let is_freezable = metadata.is_freezable();
@@ -824,10 +1366,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         address: RpcAddress<Destination>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
 
         rpc::handle_result(
             self.change_token_authority(account_arg.index::<N>()?, token_id, address, config)
@@ -835,6 +1374,30 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         )
     }
 
+    async fn rotate_token_authority(
+        &self,
+        account_arg: AccountArg,
+        token_id: RpcAddress<TokenId>,
+        address: RpcAddress<Destination>,
+        dry_run: bool,
+        force: bool,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<TokenAuthorityRotationInfo> {
+        let config = options.controller_config::<N>(&self.chain_config)?;
+
+        rpc::handle_result(
+            self.rotate_token_authority(
+                account_arg.index::<N>()?,
+                token_id,
+                address,
+                dry_run,
+                force,
+                config,
+            )
+            .await,
+        )
+    }
+
     async fn change_token_metadata_uri(
         &self,
         account_arg: AccountArg,
@@ -842,10 +1405,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         metadata_uri: RpcHexString,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
 
         rpc::handle_result(
             self.change_token_metadata_uri(
@@ -866,10 +1426,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         amount: RpcAmountIn,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
 
         rpc::handle_result(
             self.mint_tokens(account_arg.index::<N>()?, token_id, address, amount, config)
@@ -884,10 +1441,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         amount: RpcAmountIn,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
 
         rpc::handle_result(
             self.unmint_tokens(account_arg.index::<N>()?, token_id, amount, config).await,
@@ -900,10 +1454,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         token_id: RpcAddress<TokenId>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
 
         rpc::handle_result(
             self.lock_token_supply(account_arg.index::<N>()?, token_id, config).await,
@@ -917,10 +1468,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         is_unfreezable: bool,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
 
         let is_unfreezable = if is_unfreezable {
             IsTokenUnfreezable::Yes
@@ -940,10 +1488,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         token_id: RpcAddress<TokenId>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
 
         rpc::handle_result(self.unfreeze_token(account_arg.index::<N>()?, token_id, config).await)
     }
@@ -955,15 +1500,65 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         address: RpcAddress<Destination>,
         amount: RpcAmountIn,
         options: TransactionOptions,
-    ) -> rpc::RpcResult<NewTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+    ) -> rpc::RpcResult<SentTransaction> {
+        let config = options.controller_config::<N>(&self.chain_config)?;
 
         rpc::handle_result(
             self.send_tokens(account_arg.index::<N>()?, token_id, address, amount, config)
-                .await,
+                .await
+                .map(|(tx, fee)| SentTransaction::new(&tx, fee, &self.chain_config)),
+        )
+    }
+
+    async fn set_token_trust(
+        &self,
+        token_id: RpcAddress<TokenId>,
+        trust: Option<TokenTrust>,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(self.set_token_trust(token_id, trust).await)
+    }
+
+    async fn list_token_trust(&self) -> rpc::RpcResult<Vec<TokenTrustInfo>> {
+        rpc::handle_result(self.list_token_trust().await)
+    }
+
+    async fn add_payee(
+        &self,
+        name: String,
+        address: RpcAddress<Destination>,
+        default_token: Option<RpcAddress<TokenId>>,
+        notes: Option<String>,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(self.add_payee(name, address, default_token, notes).await)
+    }
+
+    async fn remove_payee(&self, name: String) -> rpc::RpcResult<()> {
+        rpc::handle_result(self.remove_payee(name).await)
+    }
+
+    async fn list_payees(&self) -> rpc::RpcResult<Vec<PayeeInfo>> {
+        rpc::handle_result(self.list_payees().await)
+    }
+
+    async fn export_payees(&self) -> rpc::RpcResult<String> {
+        rpc::handle_result(self.export_payees().await)
+    }
+
+    async fn import_payees(&self, address_book: String) -> rpc::RpcResult<usize> {
+        rpc::handle_result(self.import_payees(address_book).await)
+    }
+
+    async fn send_to_payee(
+        &self,
+        account_arg: AccountArg,
+        name: String,
+        amount: RpcAmountIn,
+        options: TransactionOptions,
+    ) -> rpc::RpcResult<NewTransaction> {
+        let config = options.controller_config::<N>(&self.chain_config)?;
+
+        rpc::handle_result(
+            self.send_to_payee(account_arg.index::<N>()?, name, amount, config).await,
         )
     }
 
@@ -975,10 +1570,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         outputs: Vec<GenericTokenTransfer>,
         options: TransactionOptions,
     ) -> rpc::RpcResult<SendTokensFromMultisigAddressResult> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
         rpc::handle_result(
             self.make_tx_to_send_tokens_from_multisig_address(
                 account_arg.index::<N>()?,
@@ -1004,10 +1596,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         data: RpcHexString,
         options: TransactionOptions,
     ) -> rpc::RpcResult<NewTransaction> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
 
         rpc::handle_result(
             self.deposit_data(account_arg.index::<N>()?, data.into_bytes(), config).await,
@@ -1022,10 +1611,7 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         htlc: RpcHashedTimelockContract,
         options: TransactionOptions,
     ) -> rpc::RpcResult<HexEncoded<SignedTransaction>> {
-        let config = ControllerConfig {
-            in_top_x_mb: options.in_top_x_mb(),
-            broadcast_to_mempool: true,
-        };
+        let config = options.controller_config::<N>(&self.chain_config)?;
 
         rpc::handle_result(
             self.create_htlc_transaction(account_arg.index::<N>()?, amount, token_id, htlc, config)
@@ -1045,6 +1631,14 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         )
     }
 
+    async fn staking_calculator(
+        &self,
+        pledge: RpcAmountIn,
+        pool_balance: RpcAmountIn,
+    ) -> rpc::RpcResult<StakingEstimate> {
+        rpc::handle_result(self.staking_calculator(pledge, pool_balance).await)
+    }
+
     async fn node_version(&self) -> rpc::RpcResult<NodeVersion> {
         rpc::handle_result(self.node_version().await.map(|version| NodeVersion { version }))
     }
@@ -1129,6 +1723,33 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         )
     }
 
+    async fn transaction_broadcast_status(
+        &self,
+        transaction_id: HexEncoded<Id<Transaction>>,
+    ) -> rpc::RpcResult<Option<BroadcastQueueEntryInfo>> {
+        rpc::handle_result(self.transaction_broadcast_status(transaction_id.take()).await)
+    }
+
+    async fn transaction_broadcast_queue(&self) -> rpc::RpcResult<Vec<BroadcastQueueEntryInfo>> {
+        rpc::handle_result(self.transaction_broadcast_queue().await)
+    }
+
+    async fn set_transaction_broadcast_policy(
+        &self,
+        transaction_id: HexEncoded<Id<Transaction>>,
+        max_failed_attempts: u32,
+        deadline: Option<BlockTimestamp>,
+    ) -> rpc::RpcResult<()> {
+        rpc::handle_result(
+            self.set_transaction_broadcast_policy(
+                transaction_id.take(),
+                max_failed_attempts,
+                deadline,
+            )
+            .await,
+        )
+    }
+
     async fn list_pending_transactions(
         &self,
         account_arg: AccountArg,
@@ -1145,12 +1766,28 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         account_arg: AccountArg,
         address: Option<RpcAddress<Destination>>,
         limit: usize,
-    ) -> rpc::RpcResult<Vec<TxInfo>> {
+    ) -> rpc::RpcResult<Vec<MainchainTxInfo>> {
         rpc::handle_result(
             self.mainchain_transactions(account_arg.index::<N>()?, address, limit).await,
         )
     }
 
+    async fn list_transactions(
+        &self,
+        account_arg: AccountArg,
+        address: Option<RpcAddress<Destination>>,
+        page: Option<PageRequest>,
+    ) -> rpc::RpcResult<TransactionPage> {
+        rpc::handle_result(
+            self.list_mainchain_transactions(
+                account_arg.index::<N>()?,
+                address,
+                page.unwrap_or_default(),
+            )
+            .await,
+        )
+    }
+
     async fn get_transaction(
         &self,
         account_arg: AccountArg,
@@ -1165,6 +1802,24 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         )
     }
 
+    async fn export_transaction_history(
+        &self,
+        account_arg: AccountArg,
+        format: TransactionHistoryFormat,
+    ) -> rpc::RpcResult<String> {
+        rpc::handle_result(self.export_transaction_history(account_arg.index::<N>()?, format).await)
+    }
+
+    async fn describe_transaction(
+        &self,
+        account_arg: AccountArg,
+        transaction_id: Id<Transaction>,
+    ) -> rpc::RpcResult<TransactionDescription> {
+        rpc::handle_result(
+            self.describe_transaction(account_arg.index::<N>()?, transaction_id).await,
+        )
+    }
+
     async fn get_raw_transaction(
         &self,
         account_arg: AccountArg,
@@ -1194,15 +1849,22 @@ impl<N: NodeInterface + Clone + Send + Sync + Debug + 'static> WalletRpcServer f
         inputs: Vec<RpcUtxoOutpoint>,
         outputs: Vec<TxOutput>,
         htlc_secrets: Option<Vec<Option<RpcHexString>>>,
+        sighash_types: Option<Vec<RpcSigHashType>>,
         only_transaction: bool,
     ) -> rpc::RpcResult<ComposedTransaction> {
         rpc::handle_result(
-            self.compose_transaction(inputs, outputs, htlc_secrets, only_transaction)
-                .await
-                .map(|(tx, fees)| ComposedTransaction {
-                    hex: tx.to_hex(),
-                    fees,
-                }),
+            self.compose_transaction(
+                inputs,
+                outputs,
+                htlc_secrets,
+                sighash_types,
+                only_transaction,
+            )
+            .await
+            .map(|(tx, fees)| ComposedTransaction {
+                hex: tx.to_hex(),
+                fees,
+            }),
         )
     }
 
@@ -15,17 +15,19 @@
 
 //! Types supporting the RPC interface
 
+use std::{cmp::Ordering, collections::BTreeMap};
+
 use common::{
     address::{pubkeyhash::PublicKeyHash, Address, AddressError},
     chain::{
         block::timestamp::BlockTimestamp,
         classic_multisig::ClassicMultisigChallengeError,
         partially_signed_transaction::PartiallySignedTransaction,
-        signature::DestinationSigError,
+        signature::{sighash::sighashtype::SigHashType, DestinationSigError},
         timelock::OutputTimeLock,
         tokens::{self, IsTokenFreezable, Metadata, TokenCreator, TokenId},
-        ChainConfig, DelegationId, Destination, PoolId, SignedTransaction, Transaction, TxOutput,
-        UtxoOutPoint,
+        ChainConfig, DelegationId, Destination, GenBlock, PoolId, SignedTransaction, Transaction,
+        TxOutput, UtxoOutPoint,
     },
     primitives::{per_thousand::PerThousand, Amount, BlockHeight, Id, Idable},
 };
@@ -36,7 +38,10 @@ use crypto::{
     },
     vrf::VRFPublicKey,
 };
+use mempool::FeeRate;
 use rpc::description::HasValueHint;
+use serialization::{Decode, DecodeAll, Encode};
+use utils::ensure;
 use wallet::account::PoolData;
 
 pub use chainstate::{
@@ -52,11 +57,23 @@ pub use rpc::types::{RpcHexString, RpcString};
 pub use serde_json::Value as JsonValue;
 pub use serialization::hex_encoded::HexEncoded;
 pub use wallet_controller::types::{
-    Balances, BlockInfo, InspectTransaction, SignatureStats, ValidatedSignatures,
+    Balances, BlockInfo, CurrencyAmount, InspectTransaction, MainchainTxInfo, SignatureStats,
+    StakingEstimate, TokenAuthorityRotation, UtxoDiscrepancyKind, UtxoVerificationReport,
+    ValidatedSignatures,
+};
+use wallet_controller::{
+    types::{CreditedReward, PayoutsCsvError, StakingReward},
+    UtxoState, UtxoType,
+};
+pub use wallet_controller::{ControllerConfig, FeeOverride, NodeInterface};
+use wallet_types::{
+    account_info::{ScheduledPayment, WatchedPool},
+    address_book::Payee,
+    broadcast_queue::{BroadcastQueueEntry, BroadcastStatus},
+    signature_status::SignatureStatus,
+    signing_log::{SigningLogEntry, SigningLogOutput, SigningRequester},
+    token_trust::TokenTrust,
 };
-pub use wallet_controller::{ControllerConfig, NodeInterface};
-use wallet_controller::{UtxoState, UtxoType};
-use wallet_types::signature_status::SignatureStatus;
 
 use crate::service::SubmitError;
 
@@ -114,6 +131,12 @@ pub enum RpcError<N: NodeInterface> {
     #[error("Invalid hex encoded partially signed transaction")]
     InvalidPartialTransaction,
 
+    #[error("Invalid hex encoded transaction package")]
+    InvalidTransactionPackage,
+
+    #[error("{0}")]
+    Paging(#[from] PagingError),
+
     #[error("{0}")]
     DestinationSigError(#[from] DestinationSigError),
 
@@ -132,6 +155,21 @@ pub enum RpcError<N: NodeInterface> {
     #[error("Minimum number of signatures can't be 0")]
     InvalidMultisigMinSignature,
 
+    #[error("The number of multisig account addresses is out of the supported range")]
+    MultisigAddressCountOutOfRange,
+
+    #[error("The number of addresses to import is out of the supported range")]
+    ImportAddressCountOutOfRange,
+
+    #[error("Exporting a private key must be explicitly confirmed")]
+    PrivateKeyExportNotConfirmed,
+
+    #[error("Invalid private key")]
+    InvalidPrivateKey,
+
+    #[error("Invalid extended public key")]
+    InvalidExtendedPublicKey,
+
     #[error(transparent)]
     Address(#[from] AddressError),
 
@@ -151,6 +189,15 @@ pub enum RpcError<N: NodeInterface> {
 
     #[error("Invalid HTLC secret hash")]
     InvalidHtlcSecretHash,
+
+    #[error("Invalid address book: {0}")]
+    InvalidAddressBook(String),
+
+    #[error("Invalid payouts: {0}")]
+    InvalidPayouts(#[from] PayoutsCsvError),
+
+    #[error("Only one of fee_rate and absolute_fee can be specified")]
+    ConflictingFeeOptions,
 }
 
 impl<N: NodeInterface> From<RpcError<N>> for rpc::Error {
@@ -264,14 +311,21 @@ pub struct AddressWithUsageInfo {
     pub address: RpcAddress<Destination>,
     pub index: String,
     pub used: bool,
+    pub label: Option<String>,
 }
 
 impl AddressWithUsageInfo {
-    pub fn new(child_number: ChildNumber, address: Address<Destination>, used: bool) -> Self {
+    pub fn new(
+        child_number: ChildNumber,
+        address: Address<Destination>,
+        used: bool,
+        label: Option<String>,
+    ) -> Self {
         Self {
             address: address.into(),
             index: child_number.to_string(),
             used,
+            label,
         }
     }
 }
@@ -297,6 +351,8 @@ impl PublicKeyInfo {
 #[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
 pub struct LegacyVrfPublicKeyInfo {
     pub vrf_public_key: String,
+    /// The pools of the account that still use the legacy key
+    pub pool_ids: Vec<RpcAddress<PoolId>>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
@@ -304,14 +360,22 @@ pub struct VrfPublicKeyInfo {
     pub vrf_public_key: RpcAddress<VRFPublicKey>,
     pub child_number: u32,
     pub used: bool,
+    /// The pools of the account that use the key
+    pub pool_ids: Vec<RpcAddress<PoolId>>,
 }
 
 impl VrfPublicKeyInfo {
-    pub fn new(pub_key: Address<VRFPublicKey>, child_number: ChildNumber, used: bool) -> Self {
+    pub fn new(
+        pub_key: Address<VRFPublicKey>,
+        child_number: ChildNumber,
+        used: bool,
+        pool_ids: Vec<RpcAddress<PoolId>>,
+    ) -> Self {
         Self {
             vrf_public_key: pub_key.into(),
             child_number: child_number.get_index().into_u32(),
             used,
+            pool_ids,
         }
     }
 }
@@ -348,9 +412,22 @@ impl NewAccountInfo {
     }
 }
 
+/// A single payment in a transaction that pays multiple recipients
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct Recipient {
+    pub address: RpcAddress<Destination>,
+    pub amount: RpcAmountIn,
+    /// The token to be sent, coins are sent if not specified
+    pub token_id: Option<RpcAddress<TokenId>>,
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
 pub struct TransactionOptions {
     pub in_top_x_mb: Option<usize>,
+    /// Fee rate in coins per 1000 bytes, overriding the one estimated by the node
+    pub fee_rate: Option<RpcAmountIn>,
+    /// Exact fee in coins the transaction pays
+    pub absolute_fee: Option<RpcAmountIn>,
 }
 
 impl TransactionOptions {
@@ -358,12 +435,55 @@ impl TransactionOptions {
 
     pub fn from_controller_config(config: &ControllerConfig) -> Self {
         let in_top_x_mb = Some(config.in_top_x_mb);
-        Self { in_top_x_mb }
+        let (fee_rate, absolute_fee) = match config.fee_override {
+            None => (None, None),
+            Some(FeeOverride::FeeRate(fee_rate)) => (
+                Some(RpcAmountIn::from_atoms(Amount::from_atoms(
+                    fee_rate.atoms_per_kb(),
+                ))),
+                None,
+            ),
+            Some(FeeOverride::AbsoluteFee(fee)) => (None, Some(RpcAmountIn::from_atoms(fee))),
+        };
+        Self {
+            in_top_x_mb,
+            fee_rate,
+            absolute_fee,
+        }
     }
 
     pub fn in_top_x_mb(&self) -> usize {
         self.in_top_x_mb.unwrap_or(Self::DEFAULT_IN_TOP_X_MB)
     }
+
+    pub fn fee_override<N: NodeInterface>(
+        &self,
+        chain_config: &ChainConfig,
+    ) -> Result<Option<FeeOverride>, RpcError<N>> {
+        let decimals = chain_config.coin_decimals();
+        let to_amount =
+            |amount: RpcAmountIn| amount.to_amount(decimals).ok_or(RpcError::InvalidCoinAmount);
+
+        match (self.fee_rate, self.absolute_fee) {
+            (None, None) => Ok(None),
+            (Some(fee_rate), None) => Ok(Some(FeeOverride::FeeRate(FeeRate::from_amount_per_kb(
+                to_amount(fee_rate)?,
+            )))),
+            (None, Some(fee)) => Ok(Some(FeeOverride::AbsoluteFee(to_amount(fee)?))),
+            (Some(_), Some(_)) => Err(RpcError::ConflictingFeeOptions),
+        }
+    }
+
+    pub fn controller_config<N: NodeInterface>(
+        &self,
+        chain_config: &ChainConfig,
+    ) -> Result<ControllerConfig, RpcError<N>> {
+        Ok(ControllerConfig {
+            in_top_x_mb: self.in_top_x_mb(),
+            broadcast_to_mempool: true,
+            fee_override: self.fee_override(chain_config)?,
+        })
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
@@ -443,6 +563,278 @@ impl DelegationInfo {
     }
 }
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct WatchedPoolInfo {
+    pub pool_id: RpcAddress<PoolId>,
+    pub label: Option<String>,
+    pub decommission_key: Option<RpcAddress<Destination>>,
+    /// The total balance of the pool, `None` if the pool no longer exists
+    pub balance: Option<RpcAmountOut>,
+    /// The pledge of the pool owner, `None` if the pool no longer exists
+    pub pledge: Option<RpcAmountOut>,
+    /// The part of the balance that comes from delegations, `None` if the pool no longer exists
+    pub delegations_balance: Option<RpcAmountOut>,
+    pub created_blocks: Vec<BlockInfo>,
+}
+
+impl WatchedPoolInfo {
+    pub fn new(
+        pool_id: PoolId,
+        watched_pool: WatchedPool,
+        balance: Option<Amount>,
+        pledge: Option<Amount>,
+        chain_config: &ChainConfig,
+    ) -> Self {
+        let decimals = chain_config.coin_decimals();
+        let delegations_balance = balance
+            .zip(pledge)
+            .and_then(|(balance, pledge)| balance - pledge)
+            .map(|amount| RpcAmountOut::from_amount_no_padding(amount, decimals));
+        let balance = balance.map(|amount| RpcAmountOut::from_amount_no_padding(amount, decimals));
+        let pledge = pledge.map(|amount| RpcAmountOut::from_amount_no_padding(amount, decimals));
+
+        Self {
+            pool_id: RpcAddress::new(chain_config, pool_id).expect("addressable"),
+            label: watched_pool.label,
+            decommission_key: watched_pool
+                .decommission_key
+                .map(|key| RpcAddress::new(chain_config, key).expect("addressable")),
+            balance,
+            pledge,
+            delegations_balance,
+            created_blocks: watched_pool
+                .created_blocks
+                .into_iter()
+                .map(|(height, id)| BlockInfo { id, height })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct CreditedRewardInfo {
+    /// The block subsidy plus the transaction fees of the block
+    pub total_reward: RpcAmountOut,
+    pub staker_reward: RpcAmountOut,
+    pub delegations_reward: RpcAmountOut,
+    /// The part of the delegations reward that went to the delegations owned by the account
+    pub own_delegations_reward: RpcAmountOut,
+}
+
+impl CreditedRewardInfo {
+    fn new(reward: &CreditedReward, decimals: u8) -> Self {
+        Self {
+            total_reward: RpcAmountOut::from_amount_no_padding(reward.total_reward, decimals),
+            staker_reward: RpcAmountOut::from_amount_no_padding(reward.staker_reward, decimals),
+            delegations_reward: RpcAmountOut::from_amount_no_padding(
+                reward.delegations_reward,
+                decimals,
+            ),
+            own_delegations_reward: RpcAmountOut::from_amount_no_padding(
+                reward.own_delegations_reward,
+                decimals,
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct BlockRewardInfo {
+    pub block_id: Id<GenBlock>,
+    pub height: BlockHeight,
+    pub timestamp: BlockTimestamp,
+    /// The amounts credited to the pool and its delegations, `None` if the node doesn't know
+    /// the reward distribution of the block anymore
+    pub reward: Option<CreditedRewardInfo>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct PoolStakingRewardsInfo {
+    pub pool_id: RpcAddress<PoolId>,
+    /// The sum of the rewards of the blocks with a known reward distribution
+    pub total_reward: CreditedRewardInfo,
+    pub blocks: Vec<BlockRewardInfo>,
+}
+
+impl PoolStakingRewardsInfo {
+    /// Group the rewards by pool
+    pub fn from_rewards(rewards: Vec<StakingReward>, chain_config: &ChainConfig) -> Vec<Self> {
+        let decimals = chain_config.coin_decimals();
+
+        let mut pools = BTreeMap::<PoolId, (CreditedReward, Vec<BlockRewardInfo>)>::new();
+        for reward in rewards {
+            let (total_reward, blocks) = pools.entry(reward.pool_id).or_insert_with(|| {
+                (
+                    CreditedReward {
+                        total_reward: Amount::ZERO,
+                        staker_reward: Amount::ZERO,
+                        delegations_reward: Amount::ZERO,
+                        own_delegations_reward: Amount::ZERO,
+                    },
+                    Vec::new(),
+                )
+            });
+            if let Some(credited) = &reward.credited {
+                let add = |total: Amount, amount: Amount| {
+                    (total + amount).expect("cannot exceed the coin supply")
+                };
+                *total_reward = CreditedReward {
+                    total_reward: add(total_reward.total_reward, credited.total_reward),
+                    staker_reward: add(total_reward.staker_reward, credited.staker_reward),
+                    delegations_reward: add(
+                        total_reward.delegations_reward,
+                        credited.delegations_reward,
+                    ),
+                    own_delegations_reward: add(
+                        total_reward.own_delegations_reward,
+                        credited.own_delegations_reward,
+                    ),
+                };
+            }
+            blocks.push(BlockRewardInfo {
+                block_id: reward.block_id,
+                height: reward.block_height,
+                timestamp: reward.block_timestamp,
+                reward: reward
+                    .credited
+                    .as_ref()
+                    .map(|credited| CreditedRewardInfo::new(credited, decimals)),
+            });
+        }
+
+        pools
+            .into_iter()
+            .map(|(pool_id, (total_reward, blocks))| Self {
+                pool_id: RpcAddress::new(chain_config, pool_id).expect("addressable"),
+                total_reward: CreditedRewardInfo::new(&total_reward, decimals),
+                blocks,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct ScheduledPaymentInfo {
+    pub payment_id: u64,
+    pub label: Option<String>,
+    pub destination: RpcAddress<Destination>,
+    /// The token to be sent, `None` for coins
+    pub token_id: Option<RpcAddress<TokenId>>,
+    pub amount: RpcAmountOut,
+    pub interval_secs: u64,
+    pub next_run: BlockTimestamp,
+    /// The time of the next retry if the last attempt has failed
+    pub retry_at: Option<BlockTimestamp>,
+    pub failed_attempts: u32,
+    pub last_error: Option<String>,
+    pub last_tx_id: Option<Id<Transaction>>,
+}
+
+impl ScheduledPaymentInfo {
+    pub fn new(
+        payment_id: u64,
+        payment: ScheduledPayment,
+        decimals: u8,
+        chain_config: &ChainConfig,
+    ) -> Self {
+        Self {
+            payment_id,
+            label: payment.label,
+            destination: RpcAddress::new(chain_config, payment.destination).expect("addressable"),
+            token_id: payment
+                .token_id
+                .map(|token_id| RpcAddress::new(chain_config, token_id).expect("addressable")),
+            amount: RpcAmountOut::from_amount_no_padding(payment.amount, decimals),
+            interval_secs: payment.interval_secs,
+            next_run: payment.next_run,
+            retry_at: payment.retry_at,
+            failed_attempts: payment.failed_attempts,
+            last_error: payment.last_error,
+            last_tx_id: payment.last_tx_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub enum RpcSigningRequester {
+    /// A transaction composed by the wallet itself
+    Wallet,
+    /// A transaction supplied by the caller to be signed
+    External,
+}
+
+impl From<SigningRequester> for RpcSigningRequester {
+    fn from(value: SigningRequester) -> Self {
+        match value {
+            SigningRequester::Wallet => Self::Wallet,
+            SigningRequester::External => Self::External,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct SigningLogOutputInfo {
+    /// `None` if the value is not sent to an address, e.g. for burned coins or delegated stake
+    pub destination: Option<RpcAddress<Destination>>,
+    /// The token, `None` for coins
+    pub token_id: Option<RpcAddress<TokenId>>,
+    pub amount: RpcAmountOut,
+}
+
+impl SigningLogOutputInfo {
+    fn new(output: SigningLogOutput, decimals: u8, chain_config: &ChainConfig) -> Self {
+        Self {
+            destination: output
+                .destination
+                .map(|dest| RpcAddress::new(chain_config, dest).expect("addressable")),
+            token_id: output
+                .token_id
+                .map(|token_id| RpcAddress::new(chain_config, token_id).expect("addressable")),
+            amount: RpcAmountOut::from_amount_no_padding(output.amount, decimals),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct SigningLogEntryInfo {
+    pub tx_id: Id<Transaction>,
+    pub timestamp: BlockTimestamp,
+    pub account: u32,
+    pub requester: RpcSigningRequester,
+    /// Whether all the inputs had valid signatures after the signing
+    pub fully_signed: bool,
+    pub outputs: Vec<SigningLogOutputInfo>,
+}
+
+impl SigningLogEntryInfo {
+    pub fn new(
+        entry: SigningLogEntry,
+        token_decimals: &BTreeMap<TokenId, u8>,
+        chain_config: &ChainConfig,
+    ) -> Self {
+        let outputs = entry
+            .outputs
+            .into_iter()
+            .map(|output| {
+                let decimals = match output.token_id {
+                    Some(token_id) => token_decimals[&token_id],
+                    None => chain_config.coin_decimals(),
+                };
+                SigningLogOutputInfo::new(output, decimals, chain_config)
+            })
+            .collect();
+
+        Self {
+            tx_id: entry.tx_id,
+            timestamp: entry.timestamp,
+            account: entry.account_index.into_u32(),
+            requester: entry.requester.into(),
+            fully_signed: entry.fully_signed,
+            outputs,
+        }
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
 pub struct NftMetadata {
     pub media_hash: String,
@@ -472,6 +864,15 @@ impl NftMetadata {
     }
 }
 
+/// The format of an exported transaction history
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub enum TransactionHistoryFormat {
+    /// One row per transaction and currency, with the fee in the first row of a transaction
+    Csv,
+    /// An array of transaction objects
+    Json,
+}
+
 #[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, HasValueHint)]
 pub enum RpcUtxoType {
     Transfer,
@@ -543,6 +944,17 @@ impl From<&UtxoState> for RpcUtxoState {
     }
 }
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+#[serde(tag = "type", content = "content")]
+pub enum RpcChangePolicy {
+    /// Send the change to the next unused change address of the account
+    NewAddress,
+    /// Send the change back to the address of the first input paying the fee
+    ReuseInputAddress,
+    /// Send the change to an address outside of the wallet, e.g. a cold wallet
+    ExternalAddress(RpcAddress<Destination>),
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
 #[serde(tag = "type", content = "content")]
 pub enum TokenTotalSupply {
@@ -597,6 +1009,92 @@ pub struct RpcTokenId {
     pub tx_id: Id<Transaction>,
 }
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct TokenTrustInfo {
+    pub token_id: RpcAddress<TokenId>,
+    pub trust: TokenTrust,
+}
+
+/// An address book entry; a list of these is also the format of the exported address book
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct PayeeInfo {
+    pub name: String,
+    pub address: RpcAddress<Destination>,
+    pub default_token: Option<RpcAddress<TokenId>>,
+    pub notes: Option<String>,
+}
+
+impl PayeeInfo {
+    pub fn new(
+        chain_config: &ChainConfig,
+        name: String,
+        payee: Payee,
+    ) -> Result<Self, AddressError> {
+        Ok(Self {
+            name,
+            address: RpcAddress::new(chain_config, payee.address)?,
+            default_token: payee
+                .default_token
+                .map(|token_id| RpcAddress::new(chain_config, token_id))
+                .transpose()?,
+            notes: payee.notes,
+        })
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+#[serde(tag = "type", content = "content")]
+pub enum RpcBroadcastStatus {
+    /// The transaction hasn't been submitted to the node successfully yet
+    Pending,
+    /// The node has accepted the transaction, it's resubmitted until it's included in a block
+    Sent,
+    /// The transaction has been included in a block
+    Accepted,
+    /// The wallet has given up broadcasting the transaction
+    Rejected { reason: String },
+}
+
+impl From<BroadcastStatus> for RpcBroadcastStatus {
+    fn from(value: BroadcastStatus) -> Self {
+        match value {
+            BroadcastStatus::Pending => Self::Pending,
+            BroadcastStatus::Sent => Self::Sent,
+            BroadcastStatus::Accepted => Self::Accepted,
+            BroadcastStatus::Rejected(reason) => Self::Rejected { reason },
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct BroadcastQueueEntryInfo {
+    pub tx_id: Id<Transaction>,
+    pub status: RpcBroadcastStatus,
+    pub max_failed_attempts: u32,
+    pub deadline: Option<BlockTimestamp>,
+    pub attempts: u32,
+    pub failed_attempts: u32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: BlockTimestamp,
+    pub updated_at: BlockTimestamp,
+}
+
+impl BroadcastQueueEntryInfo {
+    pub fn new(tx_id: Id<Transaction>, entry: BroadcastQueueEntry) -> Self {
+        Self {
+            tx_id,
+            status: entry.status.into(),
+            max_failed_attempts: entry.policy.max_failed_attempts,
+            deadline: entry.policy.deadline,
+            attempts: entry.attempts,
+            failed_attempts: entry.failed_attempts,
+            last_error: entry.last_error,
+            next_attempt_at: entry.next_attempt_at,
+            updated_at: entry.updated_at,
+        }
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
 pub struct NewTransaction {
     pub tx_id: Id<Transaction>,
@@ -610,6 +1108,158 @@ impl NewTransaction {
     }
 }
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct SentTransaction {
+    pub tx_id: Id<Transaction>,
+    /// The coin fee paid by the transaction
+    pub fee: RpcAmountOut,
+}
+
+impl SentTransaction {
+    pub fn new(tx: &SignedTransaction, fee: Amount, chain_config: &ChainConfig) -> Self {
+        Self {
+            tx_id: tx.transaction().get_id(),
+            fee: RpcAmountOut::from_amount_no_padding(fee, chain_config.coin_decimals()),
+        }
+    }
+}
+
+/// One of the transactions paying a batch of the bulk payouts
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct PayoutTransaction {
+    pub tx_id: Id<Transaction>,
+    pub tx: HexEncoded<SignedTransaction>,
+    /// The number of payouts made by the transaction
+    pub payouts_count: usize,
+    /// The coin fee paid by the transaction
+    pub fee: RpcAmountOut,
+}
+
+impl PayoutTransaction {
+    pub fn new(
+        tx: SignedTransaction,
+        payouts_count: usize,
+        fee: Amount,
+        chain_config: &ChainConfig,
+    ) -> Self {
+        Self {
+            tx_id: tx.transaction().get_id(),
+            tx: HexEncoded::new(tx),
+            payouts_count,
+            fee: RpcAmountOut::from_amount_no_padding(fee, chain_config.coin_decimals()),
+        }
+    }
+}
+
+/// The bulk payouts that have not been made because sending one of the transactions failed
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct PayoutsFailure {
+    /// The lines of the CSV that have not been paid, to be retried
+    pub unpaid_lines: Vec<usize>,
+    pub error: String,
+}
+
+/// The summary of the bulk payouts
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct PayoutsReport {
+    /// The transactions that have been sent, including the ones that are going to be
+    /// rebroadcast because their submission to the mempool failed
+    pub transactions: Vec<PayoutTransaction>,
+    /// The total number of payouts made
+    pub payouts_count: usize,
+    /// The total amount paid in each currency
+    pub totals: Vec<CurrencyAmount>,
+    /// The coin fee paid by all the transactions together
+    pub total_fee: RpcAmountOut,
+    /// Set if the payouts have stopped at a transaction that couldn't be sent
+    pub failure: Option<PayoutsFailure>,
+}
+
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub enum RpcUtxoDiscrepancyKind {
+    /// The wallet considers the output unspent, but it's not in the node's UTXO set
+    SpentButTracked,
+    /// The output is in the node's UTXO set, but the wallet considers it spent
+    Missing,
+}
+
+impl From<UtxoDiscrepancyKind> for RpcUtxoDiscrepancyKind {
+    fn from(value: UtxoDiscrepancyKind) -> Self {
+        match value {
+            UtxoDiscrepancyKind::SpentButTracked => Self::SpentButTracked,
+            UtxoDiscrepancyKind::Missing => Self::Missing,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct UtxoDiscrepancyInfo {
+    pub account: AccountArg,
+    pub outpoint: RpcUtxoOutpoint,
+    pub kind: RpcUtxoDiscrepancyKind,
+    /// The height from which the blocks have to be scanned again to fix the discrepancy
+    pub rescan_from: BlockHeight,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct UtxoVerificationInfo {
+    /// The height of the best block at which the check was done
+    pub checked_at: BlockHeight,
+    /// The number of outputs that are tracked by the wallet
+    pub tracked_count: usize,
+    /// The number of outputs that were checked, less than the tracked ones if sampled
+    pub checked_count: usize,
+    pub discrepancies: Vec<UtxoDiscrepancyInfo>,
+    /// The block the wallet was rewound to and rescanned from, if a repair was done
+    pub rewound_to: Option<BlockInfo>,
+}
+
+impl From<UtxoVerificationReport> for UtxoVerificationInfo {
+    fn from(report: UtxoVerificationReport) -> Self {
+        Self {
+            checked_at: report.checked_at,
+            tracked_count: report.tracked_count,
+            checked_count: report.checked_count,
+            discrepancies: report
+                .discrepancies
+                .into_iter()
+                .map(|discrepancy| UtxoDiscrepancyInfo {
+                    account: discrepancy.account.into(),
+                    outpoint: RpcUtxoOutpoint::new(discrepancy.outpoint),
+                    kind: discrepancy.kind.into(),
+                    rescan_from: discrepancy.rescan_from,
+                })
+                .collect(),
+            rewound_to: report.rewound_to,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct TokenAuthorityRotationInfo {
+    pub token_id: RpcAddress<TokenId>,
+    pub current_authority: RpcAddress<Destination>,
+    pub new_authority: RpcAddress<Destination>,
+    /// The account of this wallet that controls the new authority, if any
+    pub new_authority_account: Option<AccountArg>,
+    /// The transaction changing the authority, not set for a dry run
+    pub tx_id: Option<Id<Transaction>>,
+}
+
+impl TokenAuthorityRotationInfo {
+    pub fn new(rotation: TokenAuthorityRotation, chain_config: &ChainConfig) -> Self {
+        Self {
+            token_id: RpcAddress::new(chain_config, rotation.token_id).expect("addressable"),
+            current_authority: RpcAddress::new(chain_config, rotation.current_authority)
+                .expect("addressable"),
+            new_authority: RpcAddress::new(chain_config, rotation.new_authority)
+                .expect("addressable"),
+            new_authority_account: rotation.new_authority_account.map(AccountArg::from),
+            tx_id: rotation.transaction.map(|tx| tx.transaction().get_id()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
 pub struct NodeVersion {
     pub version: String,
@@ -660,6 +1310,30 @@ impl From<crate::CreatedWallet> for CreatedWallet {
     }
 }
 
+/// The sighash types the wallet is willing to sign with.
+///
+/// The ANYONECANPAY variants only commit to the input being signed, so other parties can add
+/// their own inputs to the transaction afterwards.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub enum RpcSigHashType {
+    All,
+    AllAnyoneCanPay,
+    Single,
+    SingleAnyoneCanPay,
+}
+
+impl From<RpcSigHashType> for SigHashType {
+    fn from(value: RpcSigHashType) -> Self {
+        let sighash_byte = match value {
+            RpcSigHashType::All => SigHashType::ALL,
+            RpcSigHashType::AllAnyoneCanPay => SigHashType::ALL | SigHashType::ANYONECANPAY,
+            RpcSigHashType::Single => SigHashType::SINGLE,
+            RpcSigHashType::SingleAnyoneCanPay => SigHashType::SINGLE | SigHashType::ANYONECANPAY,
+        };
+        SigHashType::try_from(sighash_byte).expect("valid sighash type")
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
 pub struct ComposedTransaction {
     pub hex: String,
@@ -771,6 +1445,155 @@ pub struct RpcHashedTimelockContract {
     pub refund_timelock: OutputTimeLock,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PagingError {
+    #[error("Invalid page cursor")]
+    InvalidCursor,
+
+    #[error("Invalid page limit, it must be between 1 and {MAX_PAGE_LIMIT}")]
+    InvalidLimit,
+}
+
+/// The maximum number of items that can be requested in a single page of a list method
+pub const MAX_PAGE_LIMIT: u32 = 1000;
+/// The number of items returned in a page if no limit is specified
+pub const DEFAULT_PAGE_LIMIT: u32 = 100;
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, HasValueHint,
+)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    fn apply(self, ordering: Ordering) -> Ordering {
+        match self {
+            SortOrder::Ascending => ordering,
+            SortOrder::Descending => ordering.reverse(),
+        }
+    }
+}
+
+/// The paging parameters shared by all the paginated list methods.
+///
+/// The items are sorted by a key specific to each method, and the cursor is the opaque key
+/// of the last item of the previous page, so items added or removed in the meantime don't
+/// make the following pages skip or repeat any items.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct PageRequest {
+    /// The `next_cursor` returned with the previous page; the first page is returned if not set
+    pub cursor: Option<RpcHexString>,
+    /// The maximum number of items to return, `DEFAULT_PAGE_LIMIT` if not set
+    pub limit: Option<u32>,
+    /// Ascending if not set
+    pub order: Option<SortOrder>,
+}
+
+impl PageRequest {
+    pub fn limit(&self) -> Result<usize, PagingError> {
+        let limit = self.limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+        ensure!(
+            (1..=MAX_PAGE_LIMIT).contains(&limit),
+            PagingError::InvalidLimit
+        );
+        Ok(limit as usize)
+    }
+
+    pub fn order(&self) -> SortOrder {
+        self.order.unwrap_or_default()
+    }
+
+    /// The key of the last item of the previous page, decoded from the cursor
+    pub fn after<K: Decode>(&self) -> Result<Option<K>, PagingError> {
+        self.cursor
+            .as_ref()
+            .map(|cursor| K::decode_all(&mut cursor.as_ref()))
+            .transpose()
+            .map_err(|_| PagingError::InvalidCursor)
+    }
+
+    /// Sort the items by the specified key and return the requested page of them,
+    /// along with the cursor of the next page if there are more items
+    pub fn paginate<T, K: Ord + Encode + Decode>(
+        &self,
+        items: impl IntoIterator<Item = T>,
+        key: impl Fn(&T) -> K,
+    ) -> Result<(Vec<T>, Option<RpcHexString>), PagingError> {
+        let limit = self.limit()?;
+        let order = self.order();
+        let after = self.after::<K>()?;
+
+        let mut items = items.into_iter().map(|item| (key(&item), item)).collect::<Vec<_>>();
+        items.sort_by(|(key1, _), (key2, _)| order.apply(key1.cmp(key2)));
+
+        let page = items
+            .into_iter()
+            .filter(|(key, _)| {
+                after.as_ref().map_or(true, |after| {
+                    order.apply(key.cmp(after)) == Ordering::Greater
+                })
+            })
+            .take(limit + 1)
+            .map(|(_, item)| item)
+            .collect();
+
+        Ok(Self::finish_page(page, limit, key))
+    }
+
+    /// Take the first `limit + 1` items following the cursor in the requested order and return
+    /// the first `limit` of them, along with the cursor of the next page if there are more items
+    pub fn finish_page<T, K: Encode>(
+        mut items: Vec<T>,
+        limit: usize,
+        key: impl Fn(&T) -> K,
+    ) -> (Vec<T>, Option<RpcHexString>) {
+        let next_cursor = if items.len() > limit {
+            items.truncate(limit);
+            items.last().map(|item| RpcHexString::from_bytes(key(item).encode()))
+        } else {
+            None
+        };
+
+        (items, next_cursor)
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct AddressPage {
+    pub addresses: Vec<AddressWithUsageInfo>,
+    pub next_cursor: Option<RpcHexString>,
+}
+
+/// The key by which the utxos are sorted when listing them page by page
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, HasValueHint,
+)]
+pub enum UtxoSortKey {
+    #[default]
+    Outpoint,
+    /// The amount of coins in the utxo; utxos without coins are treated as having zero coins
+    CoinAmount,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct UtxoPage {
+    pub utxos: Vec<JsonValue>,
+    pub next_cursor: Option<RpcHexString>,
+    /// The best block the wallet was synced to when the utxos were read
+    pub best_synced_block: BlockInfo,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, HasValueHint)]
+pub struct TransactionPage {
+    pub transactions: Vec<MainchainTxInfo>,
+    pub next_cursor: Option<RpcHexString>,
+    /// The best block the wallet was synced to when the transactions were read
+    pub best_synced_block: BlockInfo,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -781,4 +1604,109 @@ mod test {
         let opts = serde_json::from_value::<TransactionOptions>(empty_obj).unwrap();
         assert_eq!(opts.in_top_x_mb(), 5);
     }
+
+    #[test]
+    fn transaction_options_fee_override() {
+        type N = node_comm::rpc_client::ColdWalletClient;
+        let chain_config = common::chain::config::create_regtest();
+        let parse = |json: serde_json::Value| {
+            serde_json::from_value::<TransactionOptions>(json)
+                .unwrap()
+                .fee_override::<N>(&chain_config)
+        };
+
+        assert_eq!(parse(serde_json::json!({})).unwrap(), None);
+        assert_eq!(
+            parse(serde_json::json!({ "fee_rate": { "atoms": "1000" } })).unwrap(),
+            Some(FeeOverride::FeeRate(FeeRate::from_atoms_per_kb(1000)))
+        );
+        assert_eq!(
+            parse(serde_json::json!({ "absolute_fee": { "decimal": "0.5" } })).unwrap(),
+            Some(FeeOverride::AbsoluteFee(Amount::from_atoms(
+                10u128.pow(chain_config.coin_decimals() as u32) / 2
+            )))
+        );
+        assert!(matches!(
+            parse(serde_json::json!({
+                "fee_rate": { "atoms": "1000" },
+                "absolute_fee": { "atoms": "1000" },
+            })),
+            Err(RpcError::ConflictingFeeOptions)
+        ));
+
+        let config = ControllerConfig {
+            in_top_x_mb: 3,
+            broadcast_to_mempool: true,
+            fee_override: Some(FeeOverride::AbsoluteFee(Amount::from_atoms(1234))),
+        };
+        let opts = TransactionOptions::from_controller_config(&config);
+        assert_eq!(
+            opts.fee_override::<N>(&chain_config).unwrap(),
+            config.fee_override
+        );
+    }
+
+    #[test]
+    fn paginate() {
+        let collect_pages = |order: Option<SortOrder>, limit: u32| {
+            let mut request = PageRequest {
+                cursor: None,
+                limit: Some(limit),
+                order,
+            };
+            let mut pages = Vec::new();
+            loop {
+                let (page, next_cursor) =
+                    request.paginate([3u32, 1, 4, 5, 9, 2, 6], |item| *item).unwrap();
+                pages.push(page);
+                match next_cursor {
+                    Some(cursor) => request.cursor = Some(cursor),
+                    None => break pages,
+                }
+            }
+        };
+
+        assert_eq!(
+            collect_pages(None, 3),
+            vec![vec![1, 2, 3], vec![4, 5, 6], vec![9]]
+        );
+        assert_eq!(
+            collect_pages(Some(SortOrder::Descending), 4),
+            vec![vec![9, 6, 5, 4], vec![3, 2, 1]]
+        );
+        assert_eq!(collect_pages(None, 7), vec![vec![1, 2, 3, 4, 5, 6, 9]]);
+
+        // The item of the cursor doesn't have to be present anymore
+        let request = PageRequest {
+            cursor: Some(RpcHexString::from_bytes(7u32.encode())),
+            limit: None,
+            order: Some(SortOrder::Descending),
+        };
+        assert_eq!(
+            request.paginate([3u32, 1, 9, 6, 8], |item| *item).unwrap(),
+            (vec![6, 3, 1], None)
+        );
+
+        let request = PageRequest {
+            cursor: Some(RpcHexString::from_bytes(vec![1, 2])),
+            limit: None,
+            order: None,
+        };
+        assert_eq!(
+            request.paginate([1u32], |item| *item),
+            Err(PagingError::InvalidCursor)
+        );
+
+        for limit in [0, MAX_PAGE_LIMIT + 1] {
+            let request = PageRequest {
+                cursor: None,
+                limit: Some(limit),
+                order: None,
+            };
+            assert_eq!(
+                request.paginate([1u32], |item| *item),
+                Err(PagingError::InvalidLimit)
+            );
+        }
+    }
 }
@@ -13,13 +13,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{BTreeMap, BTreeSet};
+
 use common::{
-    chain::{block::timestamp::BlockTimestamp, GenBlock, OutPointSourceId, Transaction},
-    primitives::{BlockHeight, Id, Idable},
+    chain::{
+        block::timestamp::BlockTimestamp, GenBlock, OutPointSourceId, PoolId, Transaction, TxOutput,
+    },
+    primitives::{Amount, BlockDistance, BlockHeight, Id, Idable},
 };
 use crypto::key::hdkd::u31::U31;
 use logging::log;
 use tokio::sync::mpsc;
+use wallet::account::currency_grouper::Currency;
 use wallet_types::wallet_tx::{self, BlockData};
 
 /// Events that can be emitted.
@@ -54,6 +59,40 @@ pub enum Event {
         account_idx: u32,
         block_id: Id<GenBlock>,
     },
+
+    /// A block has been produced by a pool that belongs to the account
+    BlockProduced {
+        account_idx: u32,
+        pool_id: PoolId,
+        block_id: Id<GenBlock>,
+        height: BlockHeight,
+    },
+
+    /// The total balance of the account in the given currency has changed
+    BalanceChanged {
+        account_idx: u32,
+        currency: Currency,
+        old_balance: Amount,
+        new_balance: Amount,
+    },
+
+    /// The wallet has switched to another node, e.g. because the previous one became unreachable
+    NodeSwitched { address: String },
+}
+
+impl Event {
+    /// The account the event is related to, if any
+    pub fn account_idx(&self) -> Option<u32> {
+        match self {
+            Event::NewBlock {} | Event::NodeSwitched { .. } => None,
+            Event::TxUpdated { account_idx, .. }
+            | Event::TxDropped { account_idx, .. }
+            | Event::RewardAdded { account_idx, .. }
+            | Event::RewardDropped { account_idx, .. }
+            | Event::BlockProduced { account_idx, .. }
+            | Event::BalanceChanged { account_idx, .. } => Some(*account_idx),
+        }
+    }
 }
 
 /// Transaction state
@@ -153,4 +192,134 @@ impl wallet::wallet_events::WalletEvents for WalletServiceEvents {
 
         self.emit(event);
     }
+
+    fn node_switched(&self, address: &str) {
+        self.emit(Event::NodeSwitched {
+            address: address.to_owned(),
+        })
+    }
+}
+
+/// Keeps track of the blocks reported as produced, so that rescanning the blockchain doesn't
+/// report the same blocks again.
+///
+/// Only the blocks within the max reorg depth of the latest reported one are remembered;
+/// the older ones can't be reorged out and are treated as scanned.
+pub struct ProducedBlockTracker {
+    max_reorg_depth: BlockDistance,
+    scanned_height: BlockHeight,
+    reported: BTreeSet<(BlockHeight, u32, Id<GenBlock>)>,
+}
+
+impl ProducedBlockTracker {
+    pub fn new(max_reorg_depth: BlockDistance) -> Self {
+        Self {
+            max_reorg_depth,
+            scanned_height: BlockHeight::zero(),
+            reported: BTreeSet::new(),
+        }
+    }
+
+    /// Forget the reported blocks; the blocks up to the given height have already been
+    /// scanned by the wallet and are not reported
+    pub fn reset(&mut self, scanned_height: BlockHeight) {
+        self.scanned_height = scanned_height;
+        self.reported.clear();
+    }
+
+    /// Return the produced block events for a reward that has been added to the wallet,
+    /// if the block hasn't been scanned or reported before
+    pub fn produced_blocks(&mut self, event: &Event) -> Vec<Event> {
+        let (account_idx, data) = match event {
+            Event::RewardAdded { account_idx, data } => (*account_idx, data),
+            _ => return Vec::new(),
+        };
+
+        if data.height() <= self.scanned_height
+            || !self.reported.insert((data.height(), account_idx, *data.block_id()))
+        {
+            return Vec::new();
+        }
+
+        if let Some(final_height) = data.height() - self.max_reorg_depth {
+            if final_height > self.scanned_height {
+                self.scanned_height = final_height;
+                self.reported.retain(|(height, _, _)| *height > final_height);
+            }
+        }
+
+        data.reward()
+            .iter()
+            .filter_map(|output| match output {
+                TxOutput::ProduceBlockFromStake(_, pool_id) => Some(Event::BlockProduced {
+                    account_idx,
+                    pool_id: *pool_id,
+                    block_id: *data.block_id(),
+                    height: data.height(),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Keeps track of the last reported account balances, to turn wallet updates into balance change
+/// events.
+#[derive(Default)]
+pub struct BalanceTracker {
+    balances: BTreeMap<U31, BTreeMap<Currency, Amount>>,
+    dirty_accounts: BTreeSet<U31>,
+}
+
+impl BalanceTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget everything, e.g. when the wallet is closed
+    pub fn reset(&mut self) {
+        self.balances.clear();
+        self.dirty_accounts.clear();
+    }
+
+    /// Set the balance to compare against without generating any events
+    pub fn set_balance(&mut self, account_idx: U31, balance: BTreeMap<Currency, Amount>) {
+        self.balances.insert(account_idx, balance);
+    }
+
+    /// Remember that the balance of the account may have changed
+    pub fn mark_dirty(&mut self, account_idx: U31) {
+        self.dirty_accounts.insert(account_idx);
+    }
+
+    pub fn take_dirty(&mut self) -> BTreeSet<U31> {
+        std::mem::take(&mut self.dirty_accounts)
+    }
+
+    /// Store the new balance of the account and return the events for the currencies that changed
+    pub fn update(
+        &mut self,
+        account_idx: U31,
+        new_balance: BTreeMap<Currency, Amount>,
+    ) -> Vec<Event> {
+        let old_balance =
+            self.balances.insert(account_idx, new_balance.clone()).unwrap_or_default();
+
+        let currencies: BTreeSet<&Currency> =
+            old_balance.keys().chain(new_balance.keys()).collect();
+
+        currencies
+            .into_iter()
+            .filter_map(|currency| {
+                let old = old_balance.get(currency).copied().unwrap_or(Amount::ZERO);
+                let new = new_balance.get(currency).copied().unwrap_or(Amount::ZERO);
+                (old != new).then(|| Event::BalanceChanged {
+                    account_idx: account_idx.into_u32(),
+                    currency: currency.clone(),
+                    old_balance: old,
+                    new_balance: new,
+                })
+            })
+            .collect()
+    }
 }
@@ -21,7 +21,7 @@ use utils::shallow_clone::ShallowClone;
 use wallet_controller::NodeInterface;
 
 use crate::{
-    service::worker::{self, WalletCommand, WalletController, WalletWorker},
+    service::worker::{self, SharedController, WalletCommand, WalletController, WalletWorker},
     types::RpcError,
 };
 
@@ -29,7 +29,10 @@ pub use crate::service::worker::EventStream;
 
 /// Wallet handle allows the user to control the wallet service, perform queries etc.
 #[derive(Clone)]
-pub struct WalletHandle<N: Clone>(worker::CommandSender<N>);
+pub struct WalletHandle<N: Clone> {
+    command_tx: worker::CommandSender<N>,
+    controller: SharedController<N>,
+}
 
 impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletHandle<N> {
     /// Asynchronous wallet service call
@@ -55,6 +58,40 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletHandle<N> {
         }
     }
 
+    /// Read-only wallet service call.
+    ///
+    /// Unlike the other calls, it doesn't go through the worker's command queue. It only waits
+    /// for the worker to finish the current command or sync step, and it is served concurrently
+    /// with the other read-only calls.
+    pub async fn call_read<R, E: Into<RpcError<N>>>(
+        &self,
+        action: impl FnOnce(&WalletController<N>) -> Result<R, E>,
+    ) -> Result<Result<R, RpcError<N>>, SubmitError> {
+        utils::ensure!(self.is_running(), SubmitError::Send);
+
+        let controller = self.controller.read().await;
+        let result = match controller.as_ref() {
+            Some(controller) => action(controller).map_err(|e| e.into()),
+            None => Err(RpcError::NoWalletOpened),
+        };
+        Ok(result)
+    }
+
+    /// Asynchronous read-only wallet service call, see `call_read`
+    pub async fn call_read_async<R, E: Into<RpcError<N>>>(
+        &self,
+        action: impl FnOnce(&WalletController<N>) -> BoxFuture<Result<R, E>>,
+    ) -> Result<Result<R, RpcError<N>>, SubmitError> {
+        utils::ensure!(self.is_running(), SubmitError::Send);
+
+        let controller = self.controller.read().await;
+        let result = match controller.as_ref() {
+            Some(controller) => action(controller).await.map_err(|e| e.into()),
+            None => Err(RpcError::NoWalletOpened),
+        };
+        Ok(result)
+    }
+
     /// Wallet service call
     pub fn call<R: Send + 'static, E: Into<RpcError<N>> + Send + 'static>(
         &self,
@@ -98,26 +135,35 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletHandle<N> {
 
     /// Check if the wallet service is currently running
     pub fn is_running(&self) -> bool {
-        !self.0.is_closed()
+        !self.command_tx.is_closed()
     }
 
     /// Completes when the receiver has dropped
     pub async fn closed(&self) {
-        self.0.closed().await
+        self.command_tx.closed().await
     }
 
     fn send_raw(&self, cmd: WalletCommand<N>) -> Result<(), SubmitError> {
-        self.0.send(cmd).map_err(|_| SubmitError::Send)
+        self.command_tx.send(cmd).map_err(|_| SubmitError::Send)
     }
 }
 
-pub fn create<N: Clone>(sender: worker::CommandSender<N>) -> WalletHandle<N> {
-    WalletHandle(sender)
+pub fn create<N: Clone>(
+    command_tx: worker::CommandSender<N>,
+    controller: SharedController<N>,
+) -> WalletHandle<N> {
+    WalletHandle {
+        command_tx,
+        controller,
+    }
 }
 
 impl<N: Clone> ShallowClone for WalletHandle<N> {
     fn shallow_clone(&self) -> Self {
-        Self(worker::CommandSender::clone(&self.0))
+        Self {
+            command_tx: worker::CommandSender::clone(&self.command_tx),
+            controller: SharedController::clone(&self.controller),
+        }
     }
 }
 
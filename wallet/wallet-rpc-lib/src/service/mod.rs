@@ -28,7 +28,7 @@ pub use handle::{EventStream, SubmitError, WalletHandle};
 use wallet_controller::{ControllerConfig, NodeInterface};
 pub use worker::{CreatedWallet, WalletController, WalletControllerError};
 
-use events::WalletServiceEvents;
+use events::{BalanceTracker, ProducedBlockTracker, WalletServiceEvents};
 
 // pub type WalletResult<T> = Result<T, WalletControllerError>;
 
@@ -36,6 +36,7 @@ use events::WalletServiceEvents;
 pub struct WalletService<N> {
     task: tokio::task::JoinHandle<()>,
     command_tx: worker::CommandSender<N>,
+    controller: worker::SharedController<N>,
     node_rpc: N,
     chain_config: Arc<ChainConfig>,
 }
@@ -87,6 +88,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletService<N> {
                 let config = ControllerConfig {
                     in_top_x_mb: 5,
                     broadcast_to_mempool: true,
+                    fee_override: None,
                 };
                 controller.synced_controller(account_index, config).await?.start_staking()?;
             }
@@ -96,8 +98,9 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletService<N> {
             None
         };
 
+        let controller = Arc::new(tokio::sync::RwLock::new(controller));
         let task = worker::WalletWorker::spawn(
-            controller,
+            Arc::clone(&controller),
             chain_config.clone(),
             node_rpc.clone(),
             command_rx,
@@ -108,6 +111,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletService<N> {
         Ok(WalletService {
             task,
             command_tx,
+            controller,
             node_rpc,
             chain_config,
         })
@@ -123,7 +127,10 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletService<N> {
 
     /// Get wallet service handle
     pub fn handle(&self) -> WalletHandle<N> {
-        handle::create(worker::CommandSender::clone(&self.command_tx))
+        handle::create(
+            worker::CommandSender::clone(&self.command_tx),
+            Arc::clone(&self.controller),
+        )
     }
 
     /// Wait for the service to shut down
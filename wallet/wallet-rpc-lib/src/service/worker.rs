@@ -13,23 +13,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{ops::ControlFlow, path::PathBuf, sync::Arc};
+use std::{collections::BTreeMap, ops::ControlFlow, path::PathBuf, sync::Arc};
 
-use common::chain::ChainConfig;
+use common::{
+    chain::ChainConfig,
+    primitives::{Amount, BlockHeight},
+};
+use crypto::key::hdkd::u31::U31;
 use futures::{future::BoxFuture, never::Never};
-use tokio::{sync::mpsc, task::JoinHandle};
+use tokio::{
+    sync::{mpsc, RwLock},
+    task::JoinHandle,
+};
 
 use logging::log;
 use utils_networking::broadcaster::Broadcaster;
-use wallet::wallet::Mnemonic;
-use wallet_controller::{ControllerError, NodeInterface};
-use wallet_types::seed_phrase::StoreSeedPhrase;
+use wallet::{account::currency_grouper::Currency, wallet::Mnemonic};
+use wallet_controller::{ControllerError, NodeInterface, UtxoState};
+use wallet_types::{seed_phrase::StoreSeedPhrase, with_locked::WithLocked};
 
 use crate::types::RpcError;
 
 use crate::Event;
 
-use super::WalletServiceEvents;
+use super::{BalanceTracker, ProducedBlockTracker, WalletServiceEvents};
 
 pub type WalletController<N> = wallet_controller::RpcController<N, super::WalletServiceEvents>;
 pub type WalletControllerError<N> = wallet_controller::ControllerError<N>;
@@ -37,6 +44,13 @@ pub type CommandReceiver<N> = mpsc::UnboundedReceiver<WalletCommand<N>>;
 pub type CommandSender<N> = mpsc::UnboundedSender<WalletCommand<N>>;
 pub type EventStream = utils_networking::broadcaster::Receiver<Event>;
 
+/// The controller shared by the worker and the handles.
+///
+/// The commands sent to the worker take the write lock, while the read-only requests take
+/// the read lock directly from the handles, so they are served concurrently with each other
+/// and in between the sync steps instead of waiting in the command queue.
+pub type SharedController<N> = Arc<RwLock<Option<WalletController<N>>>>;
+
 type CommandFn<N> = dyn Send + FnOnce(&mut Option<WalletController<N>>) -> BoxFuture<()>;
 type ManageFn<N> = dyn Send + FnOnce(&mut WalletWorker<N>) -> BoxFuture<()>;
 
@@ -59,18 +73,20 @@ pub enum CreatedWallet {
 
 /// Represents the wallet worker task. It handles external commands and keeps the wallet in sync.
 pub struct WalletWorker<N> {
-    controller: Option<WalletController<N>>,
+    controller: SharedController<N>,
     command_rx: CommandReceiver<N>,
     chain_config: Arc<ChainConfig>,
     node_rpc: N,
     events_bcast: Broadcaster<Event>,
     events_rx: mpsc::UnboundedReceiver<Event>,
     wallet_events: WalletServiceEvents,
+    balance_tracker: BalanceTracker,
+    produced_block_tracker: ProducedBlockTracker,
 }
 
 impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
     fn new(
-        controller: Option<WalletController<N>>,
+        controller: SharedController<N>,
         chain_config: Arc<ChainConfig>,
         node_rpc: N,
         command_rx: CommandReceiver<N>,
@@ -78,6 +94,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
         wallet_events: WalletServiceEvents,
     ) -> Self {
         let events_bcast = Broadcaster::new();
+        let produced_block_tracker = ProducedBlockTracker::new(chain_config.max_depth_for_reorg());
         Self {
             controller,
             command_rx,
@@ -86,11 +103,13 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
             events_bcast,
             events_rx,
             wallet_events,
+            balance_tracker: BalanceTracker::new(),
+            produced_block_tracker,
         }
     }
 
     pub fn spawn(
-        controller: Option<WalletController<N>>,
+        controller: SharedController<N>,
         chain_config: Arc<ChainConfig>,
         node_rpc: N,
         command_rx: CommandReceiver<N>,
@@ -125,18 +144,13 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
                 // Forward events to subscribers
                 event = self.events_rx.recv() => {
                     match event {
-                        Some(event) => self.events_bcast.broadcast(&event),
+                        Some(event) => self.forward_event(event).await,
                         None => log::warn!("Events channel closed unexpectedly"),
                     }
                 }
 
                 // Background wallet sync if there's nothing else to do
-                result = Self::background_task(&mut self.controller) => {
-                    match result {
-                        Ok(never) => match never {},
-                        Err(err) => log::error!("Wallet syncing error: {err}"),
-                    }
-                },
+                never = Self::background_task(&self.controller) => match never {},
             }
         }
     }
@@ -144,7 +158,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
     pub async fn process_command(&mut self, command: Option<WalletCommand<N>>) -> ControlFlow<()> {
         match command {
             Some(WalletCommand::Call(call)) => {
-                call(&mut self.controller).await;
+                call(&mut *self.controller.write().await).await;
                 ControlFlow::Continue(())
             }
             Some(WalletCommand::Manage(call)) => {
@@ -162,9 +176,82 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
         }
     }
 
-    pub fn close_wallet(&mut self) -> Result<(), ControllerError<N>> {
-        utils::ensure!(self.controller.is_some(), ControllerError::NoWallet);
-        self.controller = None;
+    async fn forward_event(&mut self, event: Event) {
+        self.events_bcast.broadcast(&event);
+
+        for produced in self.produced_block_tracker.produced_blocks(&event) {
+            self.events_bcast.broadcast(&produced);
+        }
+
+        if let Some(account_idx) = event.account_idx() {
+            self.balance_tracker.mark_dirty(U31::from_u32_with_msb(account_idx).0);
+        }
+
+        // Report the balance changes once the pending wallet updates have been forwarded,
+        // so a scanned batch of blocks doesn't produce an event for every single transaction
+        if self.events_rx.is_empty() {
+            self.forward_balance_changes().await;
+        }
+    }
+
+    async fn forward_balance_changes(&mut self) {
+        let controller = self.controller.read().await;
+        let controller = match controller.as_ref() {
+            Some(controller) => controller,
+            None => return,
+        };
+
+        for account_idx in self.balance_tracker.take_dirty() {
+            match Self::account_total_balance(controller, account_idx) {
+                Ok(balance) => {
+                    for event in self.balance_tracker.update(account_idx, balance) {
+                        self.events_bcast.broadcast(&event);
+                    }
+                }
+                Err(err) => {
+                    log::error!("Failed to get the balance of account {account_idx}: {err}")
+                }
+            }
+        }
+    }
+
+    fn account_total_balance(
+        controller: &WalletController<N>,
+        account_idx: U31,
+    ) -> Result<BTreeMap<Currency, Amount>, ControllerError<N>> {
+        controller.readonly_controller(account_idx).get_balance(
+            UtxoState::Confirmed | UtxoState::InMempool | UtxoState::Inactive,
+            WithLocked::Any,
+        )
+    }
+
+    /// Remember the current balances of all accounts and the scanned height of the wallet
+    /// so that only the later changes are reported
+    async fn reset_balance_tracker(&mut self) {
+        self.balance_tracker.reset();
+        self.produced_block_tracker.reset(BlockHeight::zero());
+
+        if let Some(controller) = self.controller.read().await.as_ref() {
+            self.produced_block_tracker.reset(controller.best_block().1);
+
+            let accounts = controller.wallet_info().account_names.len();
+            for account_idx in (0..accounts as u32).map(|idx| U31::from_u32_with_msb(idx).0) {
+                match Self::account_total_balance(controller, account_idx) {
+                    Ok(balance) => self.balance_tracker.set_balance(account_idx, balance),
+                    Err(err) => {
+                        log::error!("Failed to get the balance of account {account_idx}: {err}")
+                    }
+                }
+            }
+        }
+    }
+
+    pub async fn close_wallet(&mut self) -> Result<(), ControllerError<N>> {
+        let mut controller = self.controller.write().await;
+        utils::ensure!(controller.is_some(), ControllerError::NoWallet);
+        *controller = None;
+        drop(controller);
+        self.reset_balance_tracker().await;
         Ok(())
     }
 
@@ -175,7 +262,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
         force_migrate_wallet_type: bool,
     ) -> Result<(), ControllerError<N>> {
         utils::ensure!(
-            self.controller.is_none(),
+            self.controller.read().await.is_none(),
             ControllerError::WalletFileAlreadyOpen
         );
 
@@ -194,7 +281,40 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
             self.wallet_events.clone(),
         )
         .await?;
-        self.controller.replace(controller);
+        self.controller.write().await.replace(controller);
+        self.reset_balance_tracker().await;
+
+        Ok(())
+    }
+
+    pub async fn create_wallet_from_watch_only_export(
+        &mut self,
+        wallet_path: PathBuf,
+        data: Vec<u8>,
+        passphrase: String,
+    ) -> Result<(), ControllerError<N>> {
+        utils::ensure!(
+            self.controller.read().await.is_none(),
+            ControllerError::WalletFileAlreadyOpen
+        );
+
+        let wallet = WalletController::create_wallet_from_watch_only_export(
+            self.chain_config.clone(),
+            wallet_path,
+            &data,
+            &passphrase,
+            self.node_rpc.is_cold_wallet_node(),
+        )?;
+
+        let controller = WalletController::new(
+            self.chain_config.clone(),
+            self.node_rpc.clone(),
+            wallet,
+            self.wallet_events.clone(),
+        )
+        .await?;
+        self.controller.write().await.replace(controller);
+        self.reset_balance_tracker().await;
 
         Ok(())
     }
@@ -208,7 +328,7 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
         skip_syncing: bool,
     ) -> Result<CreatedWallet, RpcError<N>> {
         utils::ensure!(
-            self.controller.is_none(),
+            self.controller.read().await.is_none(),
             ControllerError::WalletFileAlreadyOpen
         );
         // TODO: Support other languages
@@ -253,7 +373,8 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
         .await
         .map_err(RpcError::Controller)?;
 
-        self.controller.replace(controller);
+        self.controller.write().await.replace(controller);
+        self.reset_balance_tracker().await;
 
         let result = match newly_generated_mnemonic {
             true => CreatedWallet::NewlyGeneratedMnemonic(mnemonic, passphrase),
@@ -266,12 +387,16 @@ impl<N: NodeInterface + Clone + Send + Sync + 'static> WalletWorker<N> {
         self.events_bcast.subscribe()
     }
 
-    async fn background_task(
-        controller_opt: &mut Option<WalletController<N>>,
-    ) -> Result<Never, WalletControllerError<N>> {
-        match controller_opt.as_mut() {
-            Some(controller) => controller.run().await,
-            None => std::future::pending().await,
+    /// Run the background steps of the controller, holding the write lock only for each step
+    async fn background_task(controller: &SharedController<N>) -> Never {
+        loop {
+            let delay = match controller.write().await.as_mut() {
+                Some(controller) => controller.run_step().await,
+                None => break,
+            };
+            tokio::time::sleep(delay).await;
         }
+
+        std::future::pending().await
     }
 }
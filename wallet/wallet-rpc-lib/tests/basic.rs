@@ -23,13 +23,13 @@ use common::{
     primitives::{Amount, BlockHeight, Id},
 };
 use utils::{
-    make_seedable_rng, ClientT, JsonValue, Seed, Subscription, SubscriptionClientT, ACCOUNT0_ARG,
-    ACCOUNT1_ARG,
+    make_seedable_rng, ClientT, JsonValue, Rng, Seed, Subscription, SubscriptionClientT,
+    ACCOUNT0_ARG, ACCOUNT1_ARG,
 };
 use wallet_rpc_lib::{
     types::{
-        AddressInfo, Balances, BlockInfo, NewAccountInfo, NewTransaction, RpcAmountIn,
-        RpcUtxoState, TransactionOptions,
+        AddressInfo, Balances, BlockInfo, NewAccountInfo, NewTransaction, PayoutsReport, Recipient,
+        RpcAmountIn, RpcUtxoState, SentTransaction, TransactionOptions,
     },
     TxState,
 };
@@ -61,6 +61,9 @@ enum EventInfo {
     TxUpdated { id: Id<Transaction>, state: TxState },
     TxDropped { id: Id<Transaction> },
     RewardAdded {},
+    BlockProduced {},
+    BalanceChanged { old: Amount, new: Amount },
+    NewBlock {},
 }
 
 impl EventInfo {
@@ -80,6 +83,13 @@ impl EventInfo {
                 EventInfo::TxDropped { id }
             }
             "RewardAdded" => Self::RewardAdded {},
+            "BlockProduced" => Self::BlockProduced {},
+            "BalanceChanged" => {
+                let old = serde_json::from_value(obj["old_balance"].clone()).unwrap();
+                let new = serde_json::from_value(obj["new_balance"].clone()).unwrap();
+                EventInfo::BalanceChanged { old, new }
+            }
+            "NewBlock" => Self::NewBlock {},
             _ => panic!("Unrecognized event"),
         }
     }
@@ -88,7 +98,24 @@ impl EventInfo {
         match self {
             EventInfo::TxUpdated { id, state: _ } => *id,
             EventInfo::TxDropped { id } => *id,
-            EventInfo::RewardAdded {} => panic!("Not a transaction event"),
+            EventInfo::RewardAdded {}
+            | EventInfo::BlockProduced {}
+            | EventInfo::BalanceChanged { .. }
+            | EventInfo::NewBlock {} => panic!("Not a transaction event"),
+        }
+    }
+}
+
+/// Get the next transaction or block reward event, collecting the balance changes on the way
+async fn next_event(
+    wallet_events: &mut Subscription<JsonValue>,
+    balance_changes: &mut Vec<EventInfo>,
+) -> EventInfo {
+    loop {
+        match EventInfo::from_json(wallet_events.next().await.unwrap().unwrap()) {
+            EventInfo::NewBlock {} => {}
+            evt @ EventInfo::BalanceChanged { .. } => balance_changes.push(evt),
+            evt => return evt,
         }
     }
 }
@@ -168,6 +195,8 @@ async fn stake_and_send_coins_to_acct1(#[case] seed: Seed) {
         let send_to_addr = acct1_addr.address;
         let options = TransactionOptions {
             in_top_x_mb: Some(3),
+            fee_rate: None,
+            absolute_fee: None,
         };
         let params = (
             ACCOUNT0_ARG,
@@ -185,7 +214,8 @@ async fn stake_and_send_coins_to_acct1(#[case] seed: Seed) {
         .unwrap();
 
     tokio::time::sleep(std::time::Duration::from_millis(300)).await;
-    let evt1 = EventInfo::from_json(wallet_events.next().await.unwrap().unwrap());
+    let mut balance_changes = Vec::new();
+    let evt1 = next_event(&mut wallet_events, &mut balance_changes).await;
 
     assert!(matches!(
         evt1,
@@ -215,10 +245,13 @@ async fn stake_and_send_coins_to_acct1(#[case] seed: Seed) {
     let _: () = wallet_rpc.request("staking_start", [ACCOUNT0_ARG]).await.unwrap();
 
     tokio::time::sleep(std::time::Duration::from_millis(300)).await;
-    let evt3 = EventInfo::from_json(wallet_events.next().await.unwrap().unwrap());
+    let evt3 = next_event(&mut wallet_events, &mut balance_changes).await;
     assert_eq!(evt3, EventInfo::RewardAdded {});
 
-    let evt4 = EventInfo::from_json(wallet_events.next().await.unwrap().unwrap());
+    let evt_produced = next_event(&mut wallet_events, &mut balance_changes).await;
+    assert_eq!(evt_produced, EventInfo::BlockProduced {});
+
+    let evt4 = next_event(&mut wallet_events, &mut balance_changes).await;
     assert!(matches!(
         evt4,
         EventInfo::TxUpdated {
@@ -228,6 +261,11 @@ async fn stake_and_send_coins_to_acct1(#[case] seed: Seed) {
     ));
     assert_eq!(evt4.tx_id(), evt1.tx_id());
 
+    // Sending the coins away has decreased the balance of account 0
+    assert!(balance_changes
+        .iter()
+        .any(|evt| matches!(evt, EventInfo::BalanceChanged { old, new } if new < old)));
+
     std::mem::drop(wallet_rpc);
     tf.stop().await;
 }
@@ -252,3 +290,135 @@ async fn no_hexified_destination(#[case] seed: Seed) {
 
     tf.stop().await;
 }
+
+// A single transaction pays all the recipients; the balance of the sending account goes down
+// by the sum of the amounts and the reported fee.
+#[rstest]
+#[trace]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn send_to_many(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = utils::TestFramework::start(&mut rng).await;
+
+    let wallet_rpc = tf.rpc_client_http();
+
+    let _: NewAccountInfo = wallet_rpc.request("account_create", Vec::<u32>::new()).await.unwrap();
+    let coin_atoms = 10u128.pow(tf.chain_config().coin_decimals().into());
+    let mut recipients = Vec::new();
+    let mut total_sent = Amount::ZERO;
+    for _ in 0..rng.gen_range(2..=5) {
+        let address: AddressInfo = wallet_rpc.request("address_new", [ACCOUNT1_ARG]).await.unwrap();
+        let amount = Amount::from_atoms(rng.gen_range(1..=10) * coin_atoms);
+        total_sent = (total_sent + amount).unwrap();
+        recipients.push(Recipient {
+            address: address.address.into(),
+            amount: RpcAmountIn::from_atoms(amount),
+            token_id: None,
+        });
+    }
+
+    let all_utxo_states = enum_iterator::all::<RpcUtxoState>().collect::<Vec<_>>();
+    let balances: Balances = wallet_rpc
+        .request("account_balance", (ACCOUNT0_ARG, &all_utxo_states))
+        .await
+        .unwrap();
+    let coins_before = balances.coins().amount();
+
+    let options = TransactionOptions {
+        in_top_x_mb: Some(3),
+        fee_rate: None,
+        absolute_fee: None,
+    };
+    let sent: SentTransaction = wallet_rpc
+        .request(
+            "address_send_many",
+            (ACCOUNT0_ARG, recipients, options.clone()),
+        )
+        .await
+        .unwrap();
+
+    let balances: Balances = wallet_rpc
+        .request("account_balance", (ACCOUNT0_ARG, &all_utxo_states))
+        .await
+        .unwrap();
+    let coins_after = balances.coins().amount();
+    assert_eq!(
+        Some(coins_before),
+        (coins_after + total_sent).and_then(|coins| coins + sent.fee.amount())
+    );
+
+    // At least one recipient is needed
+    let result: Result<SentTransaction, _> = wallet_rpc
+        .request(
+            "address_send_many",
+            (ACCOUNT0_ARG, Vec::<Recipient>::new(), options),
+        )
+        .await;
+    assert!(result.is_err());
+
+    tf.stop().await;
+}
+
+// The payouts are split into several transactions; when a later one can't be made, the report
+// has the transactions that have been sent and the lines of the CSV that have not been paid.
+#[rstest]
+#[trace]
+#[case(test_utils::random::Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn send_payouts_csv_with_failing_batch(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let tf = utils::TestFramework::start(&mut rng).await;
+
+    let wallet_rpc = tf.rpc_client_http();
+
+    let _: NewAccountInfo = wallet_rpc.request("account_create", Vec::<u32>::new()).await.unwrap();
+    let mut addresses = Vec::new();
+    for _ in 0..5 {
+        let address: AddressInfo = wallet_rpc.request("address_new", [ACCOUNT1_ARG]).await.unwrap();
+        addresses.push(address.address);
+    }
+
+    let all_utxo_states = enum_iterator::all::<RpcUtxoState>().collect::<Vec<_>>();
+    let balances: Balances = wallet_rpc
+        .request("account_balance", (ACCOUNT0_ARG, &all_utxo_states))
+        .await
+        .unwrap();
+    let coin_atoms = 10u128.pow(tf.chain_config().coin_decimals().into());
+    let too_much = balances.coins().amount().into_atoms() / coin_atoms + 1;
+
+    // Two payouts per transaction: lines 2-3 and 4-5 are paid, line 6 is more than the balance
+    let csv = format!(
+        "address,amount,token\n{},1\n{},2\n{},3\n{},4\n{},{too_much}\n",
+        addresses[0], addresses[1], addresses[2], addresses[3], addresses[4]
+    );
+    let options = TransactionOptions {
+        in_top_x_mb: Some(3),
+        fee_rate: None,
+        absolute_fee: None,
+    };
+    let report: PayoutsReport = wallet_rpc
+        .request(
+            "address_send_many_csv",
+            (ACCOUNT0_ARG, csv, Some(2), options),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(report.transactions.len(), 2);
+    assert!(report.transactions.iter().all(|tx| tx.payouts_count == 2));
+    assert_eq!(report.payouts_count, 4);
+    assert_eq!(report.totals.len(), 1);
+    assert!(report.totals[0].token_id.is_none());
+    assert_eq!(
+        report.totals[0].amount.amount(),
+        Amount::from_atoms(10 * coin_atoms)
+    );
+    let total_fee = report.transactions.iter().map(|tx| tx.fee.amount()).sum::<Option<Amount>>();
+    assert_eq!(total_fee, Some(report.total_fee.amount()));
+
+    let failure = report.failure.unwrap();
+    assert_eq!(failure.unpaid_lines, [6]);
+
+    tf.stop().await;
+}
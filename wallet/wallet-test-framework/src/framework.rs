@@ -0,0 +1,286 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    address::Address,
+    chain::{ChainConfig, Destination, SignedTransaction},
+    primitives::{Amount, BlockHeight},
+};
+use crypto::key::hdkd::u31::U31;
+use randomness::{CryptoRng, Rng};
+use subsystem::{ManagerJoinHandle, ShutdownTrigger};
+use test_utils::{random::TestRng, test_dir::TestRoot};
+use wallet::{account::currency_grouper::Currency, wallet_events::WalletEventsNoOp};
+use wallet_controller::{
+    mnemonic::{self, Language, Mnemonic},
+    ControllerConfig, HandlesController, NodeInterface, UtxoState, UtxoTypes, WalletHandlesClient,
+    DEFAULT_ACCOUNT_INDEX,
+};
+use wallet_types::{
+    seed_phrase::{StoreSeedPhrase, MNEMONIC_24_WORDS_ENTROPY_SIZE},
+    wallet_type::WalletType,
+    with_locked::WithLocked,
+};
+
+use crate::framework_builder::TestFrameworkBuilder;
+
+pub type TestWallet = HandlesController<WalletEventsNoOp>;
+
+/// Position of a wallet in the framework, in the order of creation
+pub type WalletIndex = usize;
+
+/// The wallet that owns the genesis coins and the genesis stake pool, it is always created first
+pub const GENESIS_WALLET: WalletIndex = 0;
+
+const CONTROLLER_CONFIG: ControllerConfig = ControllerConfig {
+    in_top_x_mb: 5,
+    broadcast_to_mempool: true,
+    fee_override: None,
+};
+
+/// An in-process node with a set of wallets connected to it
+pub struct TestFramework {
+    chain_config: Arc<ChainConfig>,
+    node: WalletHandlesClient,
+    wallets: Vec<TestWallet>,
+    rng: TestRng,
+    shutdown_trigger: ShutdownTrigger,
+    manager_task: ManagerJoinHandle,
+    test_root: TestRoot,
+}
+
+impl TestFramework {
+    pub fn builder(rng: &mut (impl Rng + CryptoRng)) -> TestFrameworkBuilder {
+        TestFrameworkBuilder::new(rng)
+    }
+
+    pub(crate) async fn start(
+        chain_config: Arc<ChainConfig>,
+        rng: TestRng,
+        test_root: TestRoot,
+    ) -> Self {
+        let (manager, _rpc_address, handles) =
+            wallet_test_node::start_node_with_handles(Arc::clone(&chain_config)).await;
+        let shutdown_trigger = manager.make_shutdown_trigger();
+        let manager_task = manager.main_in_task();
+
+        let node = WalletHandlesClient::new(
+            handles.chainstate,
+            handles.mempool,
+            handles.block_prod,
+            handles.p2p,
+        )
+        .await
+        .unwrap();
+
+        let mut tf = TestFramework {
+            chain_config,
+            node,
+            wallets: Vec::new(),
+            rng,
+            shutdown_trigger,
+            manager_task,
+            test_root,
+        };
+
+        let genesis_mnemonic =
+            mnemonic::parse_mnemonic(Language::English, wallet_test_node::MNEMONIC).unwrap();
+        let genesis_wallet = tf.add_wallet(genesis_mnemonic).await;
+        assert_eq!(genesis_wallet, GENESIS_WALLET);
+
+        tf
+    }
+
+    pub fn chain_config(&self) -> &Arc<ChainConfig> {
+        &self.chain_config
+    }
+
+    pub fn node(&self) -> &WalletHandlesClient {
+        &self.node
+    }
+
+    pub fn wallet(&self, wallet: WalletIndex) -> &TestWallet {
+        &self.wallets[wallet]
+    }
+
+    pub fn wallet_mut(&mut self, wallet: WalletIndex) -> &mut TestWallet {
+        &mut self.wallets[wallet]
+    }
+
+    pub fn wallet_count(&self) -> usize {
+        self.wallets.len()
+    }
+
+    /// Create a new synced wallet from a mnemonic derived from the framework seed
+    pub async fn create_wallet(&mut self) -> WalletIndex {
+        let mut entropy = [0u8; MNEMONIC_24_WORDS_ENTROPY_SIZE];
+        self.rng.fill(&mut entropy);
+        let mnemonic = Mnemonic::from_entropy_in(Language::English, &entropy).unwrap();
+
+        self.add_wallet(mnemonic).await
+    }
+
+    async fn add_wallet(&mut self, mnemonic: Mnemonic) -> WalletIndex {
+        let index = self.wallets.len();
+        let wallet_path = self
+            .test_root
+            .fresh_test_dir(format!("wallet_{index}"))
+            .as_ref()
+            .join("wallet.sqlite");
+
+        let wallet = TestWallet::create_wallet(
+            Arc::clone(&self.chain_config),
+            wallet_path,
+            mnemonic,
+            None,
+            StoreSeedPhrase::DoNotStore,
+            (BlockHeight::new(0), self.chain_config.genesis_block_id()),
+            WalletType::Hot,
+        )
+        .unwrap();
+
+        let controller = TestWallet::new(
+            Arc::clone(&self.chain_config),
+            self.node.clone(),
+            wallet,
+            WalletEventsNoOp,
+        )
+        .await
+        .unwrap();
+
+        self.wallets.push(controller);
+        index
+    }
+
+    pub async fn best_block_height(&self) -> BlockHeight {
+        self.node.get_best_block_height().await.unwrap()
+    }
+
+    /// Bring all the wallets in sync with the node
+    pub async fn sync_wallets(&mut self) {
+        for wallet in &mut self.wallets {
+            wallet.sync_once().await.unwrap();
+        }
+    }
+
+    /// Advance the chain by staking with the genesis pool
+    pub async fn generate_blocks(&mut self, block_count: u32) {
+        self.generate_blocks_by(GENESIS_WALLET, DEFAULT_ACCOUNT_INDEX, block_count)
+            .await
+    }
+
+    /// Advance the chain by staking with the pools of the given account,
+    /// the transactions in the mempool are included in the new blocks
+    pub async fn generate_blocks_by(
+        &mut self,
+        wallet: WalletIndex,
+        account_index: U31,
+        block_count: u32,
+    ) {
+        self.wallets[wallet].generate_blocks(account_index, block_count).await.unwrap();
+        self.sync_wallets().await;
+    }
+
+    pub async fn new_address(
+        &mut self,
+        wallet: WalletIndex,
+        account_index: U31,
+    ) -> Address<Destination> {
+        let mut controller = self.wallets[wallet]
+            .synced_controller(account_index, CONTROLLER_CONFIG)
+            .await
+            .unwrap();
+        let (_child_number, address) = controller.new_address().unwrap();
+        address
+    }
+
+    /// Send coins to the address and broadcast the transaction to the mempool
+    pub async fn send_coins(
+        &mut self,
+        wallet: WalletIndex,
+        account_index: U31,
+        address: Address<Destination>,
+        amount: Amount,
+    ) -> SignedTransaction {
+        let mut controller = self.wallets[wallet]
+            .synced_controller(account_index, CONTROLLER_CONFIG)
+            .await
+            .unwrap();
+        controller.send_to_address(address, amount, vec![]).await.unwrap()
+    }
+
+    /// Spendable coins of the account, confirmed in the mainchain
+    pub fn coin_balance(&self, wallet: WalletIndex, account_index: U31) -> Amount {
+        self.wallets[wallet]
+            .readonly_controller(account_index)
+            .get_balance(UtxoState::Confirmed.into(), WithLocked::Unlocked)
+            .unwrap()
+            .get(&Currency::Coin)
+            .copied()
+            .unwrap_or(Amount::ZERO)
+    }
+
+    /// Number of the confirmed UTXOs of the account, including the locked ones
+    pub fn utxo_count(&self, wallet: WalletIndex, account_index: U31) -> usize {
+        self.wallets[wallet]
+            .readonly_controller(account_index)
+            .get_utxos(UtxoTypes::ALL, UtxoState::Confirmed.into(), WithLocked::Any)
+            .unwrap()
+            .len()
+    }
+
+    #[track_caller]
+    pub fn assert_balance_eq(&self, wallet: WalletIndex, account_index: U31, expected: Amount) {
+        let balance = self.coin_balance(wallet, account_index);
+        assert_eq!(
+            balance, expected,
+            "Unexpected coin balance of wallet {wallet} account {account_index}"
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_utxo_count(&self, wallet: WalletIndex, account_index: U31, expected: usize) {
+        let utxo_count = self.utxo_count(wallet, account_index);
+        assert_eq!(
+            utxo_count, expected,
+            "Unexpected UTXO count of wallet {wallet} account {account_index}"
+        );
+    }
+
+    pub async fn stop(self) {
+        let TestFramework {
+            chain_config: _,
+            node: _,
+            wallets,
+            rng: _,
+            shutdown_trigger,
+            manager_task,
+            test_root,
+        } = self;
+
+        // The wallet databases must be closed before the test directory is removed
+        drop(wallets);
+
+        let shutdown_sequence = async {
+            shutdown_trigger.initiate();
+            manager_task.join().await;
+        };
+        tokio::time::timeout(Duration::from_secs(10), shutdown_sequence).await.unwrap();
+
+        test_root.delete();
+    }
+}
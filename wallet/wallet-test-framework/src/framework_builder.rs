@@ -0,0 +1,78 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common::chain::config::regtest_options::ChainConfigOptions;
+use randomness::{CryptoRng, Rng};
+use test_utils::{
+    random::{Seed, TestRng},
+    test_dir::TestRoot,
+};
+
+use crate::framework::TestFramework;
+
+pub struct TestFrameworkBuilder {
+    seed: Seed,
+    chain_config_options: ChainConfigOptions,
+    wallet_count: usize,
+}
+
+impl TestFrameworkBuilder {
+    /// Constructs a builder instance with values appropriate for most of the tests.
+    pub fn new(rng: &mut (impl Rng + CryptoRng)) -> Self {
+        TestFrameworkBuilder {
+            seed: Seed::from_u64(rng.next_u64()),
+            chain_config_options: wallet_test_node::default_chain_config_options(),
+            wallet_count: 0,
+        }
+    }
+
+    pub fn with_chain_config_options(mut self, options: ChainConfigOptions) -> Self {
+        self.chain_config_options = options;
+        self
+    }
+
+    /// The number of fresh wallets to create in addition to the genesis one
+    pub fn with_wallets(mut self, wallet_count: usize) -> Self {
+        self.wallet_count = wallet_count;
+        self
+    }
+
+    pub async fn build(self) -> TestFramework {
+        let TestFrameworkBuilder {
+            seed,
+            chain_config_options,
+            wallet_count,
+        } = self;
+
+        logging::init_logging();
+
+        let mut rng = TestRng::new(seed);
+        let chain_config = Arc::new(wallet_test_node::create_chain_config(
+            &mut rng,
+            &chain_config_options,
+        ));
+
+        let test_root =
+            TestRoot::create(std::env::temp_dir().join(env!("CARGO_PKG_NAME"))).unwrap();
+
+        let mut tf = TestFramework::start(chain_config, rng, test_root).await;
+        for _ in 0..wallet_count {
+            tf.create_wallet().await;
+        }
+        tf
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! End-to-end testing framework for the wallet.
+//!
+//! Runs a regtest node in-process, connects wallet controllers to it through the subsystem
+//! handles and provides helpers to advance the chain and check the state of the wallets.
+
+#![allow(clippy::unwrap_used)]
+
+mod framework;
+mod framework_builder;
+
+pub use {
+    framework::{TestFramework, TestWallet, WalletIndex, GENESIS_WALLET},
+    framework_builder::TestFrameworkBuilder,
+};
@@ -0,0 +1,78 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::primitives::{Amount, BlockHeight};
+use rstest::rstest;
+use test_utils::random::{make_seedable_rng, Rng, Seed};
+use wallet_controller::DEFAULT_ACCOUNT_INDEX;
+use wallet_test_framework::{TestFramework, GENESIS_WALLET};
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn genesis_wallet_stakes(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let mut tf = TestFramework::builder(&mut rng).build().await;
+
+    let genesis_balance = tf.coin_balance(GENESIS_WALLET, DEFAULT_ACCOUNT_INDEX);
+    assert_ne!(genesis_balance, Amount::ZERO);
+
+    let block_count = rng.gen_range(1..10);
+    tf.generate_blocks(block_count).await;
+    assert_eq!(
+        tf.best_block_height().await,
+        BlockHeight::new(block_count.into())
+    );
+
+    // The block rewards go to the pool, the wallet balance stays the same
+    tf.assert_balance_eq(GENESIS_WALLET, DEFAULT_ACCOUNT_INDEX, genesis_balance);
+
+    tf.stop().await;
+}
+
+#[rstest]
+#[trace]
+#[case(Seed::from_entropy())]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn send_coins_between_wallets(#[case] seed: Seed) {
+    let mut rng = make_seedable_rng(seed);
+    let mut tf = TestFramework::builder(&mut rng).with_wallets(1).build().await;
+    let receiver = GENESIS_WALLET + 1;
+
+    let genesis_balance = tf.coin_balance(GENESIS_WALLET, DEFAULT_ACCOUNT_INDEX);
+    tf.assert_balance_eq(receiver, DEFAULT_ACCOUNT_INDEX, Amount::ZERO);
+    tf.assert_utxo_count(receiver, DEFAULT_ACCOUNT_INDEX, 0);
+
+    let tx_count = rng.gen_range(1..5);
+    let mut total_sent = Amount::ZERO;
+    for _ in 0..tx_count {
+        let amount = Amount::from_atoms(rng.gen_range(1..1_000_000_000));
+        let address = tf.new_address(receiver, DEFAULT_ACCOUNT_INDEX).await;
+        tf.send_coins(GENESIS_WALLET, DEFAULT_ACCOUNT_INDEX, address, amount).await;
+        total_sent = (total_sent + amount).unwrap();
+
+        tf.generate_blocks(1).await;
+    }
+
+    tf.assert_balance_eq(receiver, DEFAULT_ACCOUNT_INDEX, total_sent);
+    tf.assert_utxo_count(receiver, DEFAULT_ACCOUNT_INDEX, tx_count);
+
+    // The sender also paid the fees
+    let sender_balance = tf.coin_balance(GENESIS_WALLET, DEFAULT_ACCOUNT_INDEX);
+    assert!(sender_balance < (genesis_balance - total_sent).unwrap());
+
+    tf.stop().await;
+}
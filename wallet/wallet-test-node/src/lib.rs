@@ -23,9 +23,9 @@ use crypto::{key::PublicKey, vrf::VRFPublicKey};
 use hex::FromHex;
 use randomness::Rng;
 
-use blockprod::{rpc::BlockProductionRpcServer, test_blockprod_config};
+use blockprod::{rpc::BlockProductionRpcServer, test_blockprod_config, BlockProductionHandle};
 use chainstate::{
-    make_chainstate, rpc::ChainstateRpcServer, ChainstateConfig,
+    make_chainstate, rpc::ChainstateRpcServer, ChainstateConfig, ChainstateHandle,
     DefaultTransactionVerificationStrategy,
 };
 use common::{
@@ -42,8 +42,8 @@ use common::{
     },
     primitives::{per_thousand::PerThousand, Amount, BlockHeight, H256},
 };
-use mempool::{rpc::MempoolRpcServer, MempoolConfig};
-use p2p::rpc::P2pRpcServer;
+use mempool::{rpc::MempoolRpcServer, MempoolConfig, MempoolHandle};
+use p2p::{rpc::P2pRpcServer, P2pHandle};
 use rpc::rpc_creds::RpcCreds;
 
 pub const RPC_USERNAME: &str = "username";
@@ -159,12 +159,29 @@ pub fn create_chain_config(
         .build()
 }
 
+/// Subsystem handles of a running test node, for talking to it in-process
+#[derive(Clone)]
+pub struct NodeHandles {
+    pub chainstate: ChainstateHandle,
+    pub mempool: MempoolHandle,
+    pub block_prod: BlockProductionHandle,
+    pub p2p: P2pHandle,
+}
+
 pub async fn start_node(chain_config: Arc<ChainConfig>) -> (subsystem::Manager, SocketAddr) {
+    let (manager, rpc_http_address, _handles) = start_node_with_handles(chain_config).await;
+    (manager, rpc_http_address)
+}
+
+pub async fn start_node_with_handles(
+    chain_config: Arc<ChainConfig>,
+) -> (subsystem::Manager, SocketAddr, NodeHandles) {
     let p2p_config = p2p::config::P2pConfig {
         bind_addresses: vec!["127.0.0.1:0".parse().unwrap()],
 
         socks5_proxy: Default::default(),
         disable_noise: Default::default(),
+        multiplexed_transport: Default::default(),
         boot_nodes: Default::default(),
         reserved_nodes: Default::default(),
         whitelisted_addresses: Default::default(),
@@ -180,6 +197,7 @@ pub async fn start_node(chain_config: Arc<ChainConfig>) -> (subsystem::Manager,
         sync_stalling_timeout: Default::default(),
         peer_manager_config: Default::default(),
         protocol_config: Default::default(),
+        local_tx_broadcast_config: Default::default(),
     };
     let rpc_creds = RpcCreds::basic(RPC_USERNAME, RPC_PASSWORD).unwrap();
 
@@ -256,5 +274,12 @@ pub async fn start_node(chain_config: Arc<ChainConfig>) -> (subsystem::Manager,
     let rpc_http_address = *rpc.http_address();
     manager.add_subsystem("rpc", rpc);
 
-    (manager, rpc_http_address)
+    let handles = NodeHandles {
+        chainstate,
+        mempool,
+        block_prod,
+        p2p,
+    };
+
+    (manager, rpc_http_address, handles)
 }
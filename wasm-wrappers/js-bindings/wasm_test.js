@@ -11,6 +11,8 @@ import {
   sign_message_for_spending,
   verify_signature_for_spending,
   make_default_account_privkey,
+  make_account_privkey,
+  derive_path,
   make_receiving_address,
   make_change_address,
   pubkey_to_pubkeyhash_address,
@@ -35,11 +37,16 @@ import {
   extract_htlc_secret,
   SignatureHashType,
   encode_input_for_withdraw_from_delegation,
+  encode_input_for_mint_tokens,
   estimate_transaction_size,
   staking_pool_spend_maturity_block_count,
+  delegation_spend_maturity_block_count,
+  block_count_to_estimated_seconds,
   get_transaction_id,
   effective_pool_balance,
   Amount,
+  amount_to_decimal_string,
+  coin_amount_to_decimal_string,
   encode_output_issue_nft,
   sign_challenge,
   verify_challenge,
@@ -204,6 +211,34 @@ export async function run_test() {
     }
   }
 
+  {
+    // Test deriving keys for a non-default account
+    const account_privkey = make_account_privkey(mnemonic, Network.Mainnet, 1);
+    const receiving_privkey = make_receiving_address(account_privkey, 2);
+    const derived_privkey = derive_path(account_privkey, "m/44'/19788'/1'/0/2");
+    assert_eq_arrays(receiving_privkey, derived_privkey);
+
+    try {
+      derive_path(account_privkey, "m/44'/19788'/0'/0/2");
+      throw new Error("Derivation from a different account worked somehow!");
+    } catch (e) {
+      if (!e.includes("Invalid derivation path")) {
+        throw e;
+      }
+      console.log("Tested derivation path of a different account successfully");
+    }
+
+    try {
+      make_account_privkey(mnemonic, Network.Mainnet, 1 << 31);
+      throw new Error("Invalid account index worked somehow!");
+    } catch (e) {
+      if (!e.includes("Invalid account index, MSB bit set")) {
+        throw e;
+      }
+      console.log("Tested invalid account index with set MSB bit successfully");
+    }
+  }
+
   {
     // Test generating an address for Testnet
     const account_pubkey = make_default_account_privkey(
@@ -236,6 +271,22 @@ export async function run_test() {
     }
   }
 
+  {
+    const lock_for_blocks = delegation_spend_maturity_block_count(
+      BigInt(1000),
+      Network.Mainnet
+    );
+    if (lock_for_blocks != 7200) {
+      throw new Error("Incorrect delegation lock for blocks");
+    }
+
+    const seconds = block_count_to_estimated_seconds(lock_for_blocks, Network.Mainnet);
+    console.log(`lock for seconds ${seconds}`);
+    if (seconds != 7200 * 120) {
+      throw new Error("Incorrect estimated seconds for blocks");
+    }
+  }
+
   {
     try {
       encode_input_for_utxo("asd", 1);
@@ -260,6 +311,20 @@ export async function run_test() {
       }
       console.log("Tested invalid delegation id in account successfully");
     }
+    try {
+      encode_input_for_mint_tokens(
+        "invalid token id",
+        Amount.from_atoms("1"),
+        BigInt(1),
+        Network.Mainnet
+      );
+      throw new Error("Invalid token id encoding worked somehow!");
+    } catch (e) {
+      if (!e.includes("Invalid addressable encoding")) {
+        throw e;
+      }
+      console.log("Tested invalid token id in account command successfully");
+    }
 
     // Test encoding full transaction
     const tx_outpoint = new Uint8Array(33).fill(0);
@@ -281,6 +346,35 @@ export async function run_test() {
 
     assert_eq_arrays(inputs, expected_inputs);
 
+    {
+      const amount = Amount.from_coins_decimal_string("12.345", Network.Testnet);
+      if (amount.atoms() != "1234500000000") {
+        throw new Error("Invalid decimal coin amount");
+      }
+      const formatted = coin_amount_to_decimal_string(
+        Amount.from_atoms("1234500000000"),
+        Network.Testnet
+      );
+      if (formatted != "12.345") {
+        throw new Error(`Invalid formatted coin amount ${formatted}`);
+      }
+      if (
+        amount_to_decimal_string(Amount.from_decimal_string("7.5", 2), 2) !=
+        "7.5"
+      ) {
+        throw new Error("Invalid decimal token amount");
+      }
+      try {
+        Amount.from_decimal_string("0.001", 2);
+        throw new Error("Too many decimal places worked somehow!");
+      } catch (e) {
+        if (!e.includes("Invalid amount")) {
+          throw e;
+        }
+      }
+      console.log("Tested decimal amounts successfully");
+    }
+
     const token_id =
       "tmltk15tgfrs49rv88v8utcllqh0nvpaqtgvn26vdxhuner5m6ewg9c3msn9fxns";
     try {
@@ -0,0 +1,357 @@
+// Copyright (c) 2024 RBB S.r.l
+// opensource@mintlayer.org
+// SPDX-License-Identifier: MIT
+// Licensed under the MIT License;
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://github.com/mintlayer/mintlayer-core/blob/master/LICENSE
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion of decoded chain objects into a JSON description suitable for displaying in web apps.
+
+use common::{
+    address::{traits::Addressable, Address},
+    chain::{
+        output_value::OutputValue,
+        signature::inputsig::InputWitness,
+        stakelock::StakePoolData,
+        timelock::OutputTimeLock,
+        tokens::{
+            IsTokenFreezable, IsTokenUnfreezable, NftIssuance, TokenIssuance, TokenTotalSupply,
+        },
+        AccountCommand, AccountSpending, ChainConfig, OutPointSourceId, SignedTransaction,
+        Transaction, TxInput, TxOutput,
+    },
+    primitives::{Amount, Idable},
+};
+use serde_json::{json, Value};
+use serialization::Encode;
+
+use crate::error::Error;
+
+fn address<T: Addressable>(chain_config: &ChainConfig, object: T) -> Result<Value, Error> {
+    let address = Address::new(chain_config, object).map_err(|_| Error::InvalidAddressable)?;
+    Ok(Value::String(address.into_string()))
+}
+
+fn coin_amount(chain_config: &ChainConfig, amount: Amount) -> Value {
+    json!({
+        "atoms": amount.into_atoms().to_string(),
+        "decimal": amount.into_fixedpoint_str(chain_config.coin_decimals()),
+    })
+}
+
+/// Token amounts are given in atoms only, since the number of decimals of a token
+/// is not known without querying the chain.
+fn output_value(chain_config: &ChainConfig, value: &OutputValue) -> Result<Value, Error> {
+    let result = match value {
+        OutputValue::Coin(amount) => json!({
+            "type": "Coin",
+            "amount": coin_amount(chain_config, *amount),
+        }),
+        OutputValue::TokenV0(_) => json!({
+            "type": "TokenV0",
+        }),
+        OutputValue::TokenV1(token_id, amount) => json!({
+            "type": "TokenV1",
+            "token_id": address(chain_config, *token_id)?,
+            "amount": { "atoms": amount.into_atoms().to_string() },
+        }),
+    };
+    Ok(result)
+}
+
+fn timelock(lock: &OutputTimeLock) -> Value {
+    match lock {
+        OutputTimeLock::UntilHeight(height) => json!({
+            "type": "UntilHeight",
+            "content": height.into_int(),
+        }),
+        OutputTimeLock::UntilTime(time) => json!({
+            "type": "UntilTime",
+            "content": time.as_int_seconds(),
+        }),
+        OutputTimeLock::ForBlockCount(count) => json!({
+            "type": "ForBlockCount",
+            "content": count,
+        }),
+        OutputTimeLock::ForSeconds(seconds) => json!({
+            "type": "ForSeconds",
+            "content": seconds,
+        }),
+    }
+}
+
+fn stake_pool_data(chain_config: &ChainConfig, data: &StakePoolData) -> Result<Value, Error> {
+    Ok(json!({
+        "pledge": coin_amount(chain_config, data.pledge()),
+        "staker": address(chain_config, data.staker().clone())?,
+        "vrf_public_key": address(chain_config, data.vrf_public_key().clone())?,
+        "decommission_key": address(chain_config, data.decommission_key().clone())?,
+        "margin_ratio_per_thousand": data.margin_ratio_per_thousand().to_percentage_str(),
+        "cost_per_block": coin_amount(chain_config, data.cost_per_block()),
+    }))
+}
+
+fn token_issuance(chain_config: &ChainConfig, issuance: &TokenIssuance) -> Result<Value, Error> {
+    match issuance {
+        TokenIssuance::V1(issuance) => {
+            let total_supply = match issuance.total_supply {
+                TokenTotalSupply::Fixed(amount) => json!({
+                    "type": "Fixed",
+                    "amount": {
+                        "atoms": amount.into_atoms().to_string(),
+                        "decimal": amount.into_fixedpoint_str(issuance.number_of_decimals),
+                    },
+                }),
+                TokenTotalSupply::Lockable => json!({ "type": "Lockable" }),
+                TokenTotalSupply::Unlimited => json!({ "type": "Unlimited" }),
+            };
+            Ok(json!({
+                "token_ticker": String::from_utf8_lossy(&issuance.token_ticker),
+                "number_of_decimals": issuance.number_of_decimals,
+                "metadata_uri": String::from_utf8_lossy(&issuance.metadata_uri),
+                "total_supply": total_supply,
+                "authority": address(chain_config, issuance.authority.clone())?,
+                "is_freezable": match issuance.is_freezable {
+                    IsTokenFreezable::No => false,
+                    IsTokenFreezable::Yes => true,
+                },
+            }))
+        }
+    }
+}
+
+fn nft_issuance(issuance: &NftIssuance) -> Value {
+    let optional_str = |data: Option<&Vec<u8>>| {
+        data.map_or(Value::Null, |data| {
+            Value::String(String::from_utf8_lossy(data).into_owned())
+        })
+    };
+
+    match issuance {
+        NftIssuance::V0(issuance) => {
+            let metadata = &issuance.metadata;
+            json!({
+                "creator": metadata
+                    .creator
+                    .as_ref()
+                    .map(|creator| hex::encode(creator.public_key.encode())),
+                "name": String::from_utf8_lossy(&metadata.name),
+                "description": String::from_utf8_lossy(&metadata.description),
+                "ticker": String::from_utf8_lossy(&metadata.ticker),
+                "icon_uri": optional_str(metadata.icon_uri.as_ref().as_ref()),
+                "additional_metadata_uri":
+                    optional_str(metadata.additional_metadata_uri.as_ref().as_ref()),
+                "media_uri": optional_str(metadata.media_uri.as_ref().as_ref()),
+                "media_hash": hex::encode(&metadata.media_hash),
+            })
+        }
+    }
+}
+
+pub fn tx_output_to_json(chain_config: &ChainConfig, output: &TxOutput) -> Result<Value, Error> {
+    let result = match output {
+        TxOutput::Transfer(value, dest) => json!({
+            "type": "Transfer",
+            "value": output_value(chain_config, value)?,
+            "destination": address(chain_config, dest.clone())?,
+        }),
+        TxOutput::LockThenTransfer(value, dest, lock) => json!({
+            "type": "LockThenTransfer",
+            "value": output_value(chain_config, value)?,
+            "destination": address(chain_config, dest.clone())?,
+            "timelock": timelock(lock),
+        }),
+        TxOutput::Burn(value) => json!({
+            "type": "Burn",
+            "value": output_value(chain_config, value)?,
+        }),
+        TxOutput::CreateStakePool(pool_id, data) => json!({
+            "type": "CreateStakePool",
+            "pool_id": address(chain_config, *pool_id)?,
+            "data": stake_pool_data(chain_config, data)?,
+        }),
+        TxOutput::ProduceBlockFromStake(dest, pool_id) => json!({
+            "type": "ProduceBlockFromStake",
+            "destination": address(chain_config, dest.clone())?,
+            "pool_id": address(chain_config, *pool_id)?,
+        }),
+        TxOutput::CreateDelegationId(owner, pool_id) => json!({
+            "type": "CreateDelegationId",
+            "destination": address(chain_config, owner.clone())?,
+            "pool_id": address(chain_config, *pool_id)?,
+        }),
+        TxOutput::DelegateStaking(amount, delegation_id) => json!({
+            "type": "DelegateStaking",
+            "amount": coin_amount(chain_config, *amount),
+            "delegation_id": address(chain_config, *delegation_id)?,
+        }),
+        TxOutput::IssueFungibleToken(issuance) => json!({
+            "type": "IssueFungibleToken",
+            "issuance": token_issuance(chain_config, issuance)?,
+        }),
+        TxOutput::IssueNft(token_id, issuance, dest) => json!({
+            "type": "IssueNft",
+            "token_id": address(chain_config, *token_id)?,
+            "issuance": nft_issuance(issuance),
+            "destination": address(chain_config, dest.clone())?,
+        }),
+        TxOutput::DataDeposit(data) => json!({
+            "type": "DataDeposit",
+            "data": hex::encode(data),
+        }),
+        TxOutput::Htlc(value, htlc) => json!({
+            "type": "Htlc",
+            "value": output_value(chain_config, value)?,
+            "htlc": {
+                "secret_hash": hex::encode(htlc.secret_hash),
+                "spend_key": address(chain_config, htlc.spend_key.clone())?,
+                "refund_timelock": timelock(&htlc.refund_timelock),
+                "refund_key": address(chain_config, htlc.refund_key.clone())?,
+            },
+        }),
+        TxOutput::AnyoneCanTake(order) => json!({
+            "type": "AnyoneCanTake",
+            "conclude_key": address(chain_config, order.conclude_key().clone())?,
+            "ask": output_value(chain_config, order.ask())?,
+            "give": output_value(chain_config, order.give())?,
+        }),
+    };
+    Ok(result)
+}
+
+fn account_command(chain_config: &ChainConfig, command: &AccountCommand) -> Result<Value, Error> {
+    let result = match command {
+        AccountCommand::MintTokens(token_id, amount) => json!({
+            "type": "MintTokens",
+            "token_id": address(chain_config, *token_id)?,
+            "amount": { "atoms": amount.into_atoms().to_string() },
+        }),
+        AccountCommand::UnmintTokens(token_id) => json!({
+            "type": "UnmintTokens",
+            "token_id": address(chain_config, *token_id)?,
+        }),
+        AccountCommand::LockTokenSupply(token_id) => json!({
+            "type": "LockTokenSupply",
+            "token_id": address(chain_config, *token_id)?,
+        }),
+        AccountCommand::FreezeToken(token_id, unfreezable) => json!({
+            "type": "FreezeToken",
+            "token_id": address(chain_config, *token_id)?,
+            "is_unfreezable": match unfreezable {
+                IsTokenUnfreezable::No => false,
+                IsTokenUnfreezable::Yes => true,
+            },
+        }),
+        AccountCommand::UnfreezeToken(token_id) => json!({
+            "type": "UnfreezeToken",
+            "token_id": address(chain_config, *token_id)?,
+        }),
+        AccountCommand::ChangeTokenAuthority(token_id, dest) => json!({
+            "type": "ChangeTokenAuthority",
+            "token_id": address(chain_config, *token_id)?,
+            "new_authority": address(chain_config, dest.clone())?,
+        }),
+        AccountCommand::ConcludeOrder(order_id) => json!({
+            "type": "ConcludeOrder",
+            "order_id": address(chain_config, *order_id)?,
+        }),
+        AccountCommand::FillOrder(order_id, fill, dest) => json!({
+            "type": "FillOrder",
+            "order_id": address(chain_config, *order_id)?,
+            "fill_value": output_value(chain_config, fill)?,
+            "destination": address(chain_config, dest.clone())?,
+        }),
+        AccountCommand::ChangeTokenMetadataUri(token_id, metadata_uri) => json!({
+            "type": "ChangeTokenMetadataUri",
+            "token_id": address(chain_config, *token_id)?,
+            "metadata_uri": String::from_utf8_lossy(metadata_uri),
+        }),
+    };
+    Ok(result)
+}
+
+fn tx_input_to_json(chain_config: &ChainConfig, input: &TxInput) -> Result<Value, Error> {
+    let result = match input {
+        TxInput::Utxo(outpoint) => {
+            let (source_type, source_id) = match outpoint.source_id() {
+                OutPointSourceId::Transaction(id) => ("Transaction", id.to_hash()),
+                OutPointSourceId::BlockReward(id) => ("BlockReward", id.to_hash()),
+            };
+            json!({
+                "type": "Utxo",
+                "source_type": source_type,
+                "source_id": format!("{source_id:x}"),
+                "index": outpoint.output_index(),
+            })
+        }
+        TxInput::Account(outpoint) => match outpoint.account() {
+            AccountSpending::DelegationBalance(delegation_id, amount) => json!({
+                "type": "Account",
+                "nonce": outpoint.nonce().value(),
+                "account": {
+                    "type": "DelegationBalance",
+                    "delegation_id": address(chain_config, *delegation_id)?,
+                    "amount": coin_amount(chain_config, *amount),
+                },
+            }),
+        },
+        TxInput::AccountCommand(nonce, command) => json!({
+            "type": "AccountCommand",
+            "nonce": nonce.value(),
+            "command": account_command(chain_config, command)?,
+        }),
+    };
+    Ok(result)
+}
+
+fn witness_to_json(witness: &InputWitness) -> Value {
+    match witness {
+        InputWitness::NoSignature(data) => json!({
+            "type": "NoSignature",
+            "data": data.as_ref().map(hex::encode),
+        }),
+        InputWitness::Standard(sig) => json!({
+            "type": "Standard",
+            "sighash_type": sig.sighash_type().get(),
+            "signature": hex::encode(sig.raw_signature()),
+        }),
+    }
+}
+
+pub fn transaction_to_json(chain_config: &ChainConfig, tx: &Transaction) -> Result<Value, Error> {
+    let inputs = tx
+        .inputs()
+        .iter()
+        .map(|input| tx_input_to_json(chain_config, input))
+        .collect::<Result<Vec<_>, _>>()?;
+    let outputs = tx
+        .outputs()
+        .iter()
+        .map(|output| tx_output_to_json(chain_config, output))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(json!({
+        "id": format!("{:x}", tx.get_id()),
+        "version_byte": tx.version_byte(),
+        "flags": tx.flags().to_string(),
+        "inputs": inputs,
+        "outputs": outputs,
+    }))
+}
+
+pub fn signed_transaction_to_json(
+    chain_config: &ChainConfig,
+    tx: &SignedTransaction,
+) -> Result<Value, Error> {
+    let mut result = transaction_to_json(chain_config, tx.transaction())?;
+    result["signatures"] = tx.signatures().iter().map(witness_to_json).collect();
+    Ok(result)
+}
@@ -30,6 +30,10 @@ pub enum Error {
     InvalidMnemonic,
     #[error("Invalid key index, MSB bit set")]
     InvalidKeyIndex,
+    #[error("Invalid account index, MSB bit set")]
+    InvalidAccountIndex,
+    #[error("Invalid derivation path: {0}")]
+    InvalidDerivationPath(String),
     #[error("Invalid outpoint ID encoding")]
     InvalidOutpointId,
     #[error("Invalid addressable encoding")]
@@ -76,6 +80,8 @@ pub enum Error {
     TransactionCreationError(#[from] TransactionCreationError),
     #[error("Produce signature error: {0}")]
     ProduceSignatureError(#[from] DestinationSigError),
+    #[error("Failed to convert the decoded object to a JavaScript value")]
+    JsonConversionFailed,
 }
 
 // This is required to make an error readable in JavaScript
@@ -42,11 +42,11 @@ use common::{
         stakelock::StakePoolData,
         timelock::OutputTimeLock,
         tokens::{
-            IsTokenFreezable, Metadata, NftIssuance, NftIssuanceV0, TokenCreator, TokenId,
-            TokenIssuance, TokenIssuanceV1, TokenTotalSupply,
+            IsTokenFreezable, IsTokenUnfreezable, Metadata, NftIssuance, NftIssuanceV0,
+            TokenCreator, TokenId, TokenIssuance, TokenIssuanceV1, TokenTotalSupply,
         },
-        AccountNonce, AccountOutPoint, AccountSpending, ChainConfig, Destination, OutPointSourceId,
-        SignedTransaction, Transaction, TxInput, TxOutput, UtxoOutPoint,
+        AccountCommand, AccountNonce, AccountOutPoint, AccountSpending, ChainConfig, Destination,
+        OutPointSourceId, SignedTransaction, Transaction, TxInput, TxOutput, UtxoOutPoint,
     },
     primitives::{
         self, amount::UnsignedIntType, per_thousand::PerThousand, BlockHeight, Idable, H256,
@@ -55,13 +55,16 @@ use common::{
 };
 use crypto::key::{
     extended::{ExtendedKeyKind, ExtendedPrivateKey},
-    hdkd::{child_number::ChildNumber, derivable::Derivable, u31::U31},
+    hdkd::{
+        child_number::ChildNumber, derivable::Derivable, derivation_path::DerivationPath, u31::U31,
+    },
     KeyKind, PrivateKey, PublicKey, Signature,
 };
 use error::Error;
 use serialization::{Decode, DecodeAll, Encode};
 use wasm_bindgen::prelude::*;
 
+mod decode;
 pub mod error;
 
 #[wasm_bindgen]
@@ -69,6 +72,8 @@ pub mod error;
 /// since JavaScript number type cannot fit 128-bit integers.
 /// The amount is given as an integer in units of "atoms".
 /// Atoms are the smallest, indivisible amount of a coin or token.
+/// Use `from_decimal_string` or `from_coins_decimal_string` to create it from a decimal
+/// number of coins or tokens instead, e.g. "12.345".
 pub struct Amount {
     atoms: String,
 }
@@ -85,6 +90,23 @@ impl Amount {
         self.atoms
     }
 
+    /// Create an amount from a decimal string, e.g. "12.345", given the number of decimals
+    /// of the token or coin. Fails if the value has more decimal places than that.
+    #[wasm_bindgen]
+    pub fn from_decimal_string(value: &str, decimals: u8) -> Result<Amount, Error> {
+        primitives::Amount::from_fixedpoint_str(value, decimals)
+            .map(Self::from_internal_amount)
+            .ok_or(Error::InvalidAmount)
+    }
+
+    /// Create a coin amount from a decimal string, e.g. "12.345",
+    /// using the number of coin decimals of the network.
+    #[wasm_bindgen]
+    pub fn from_coins_decimal_string(value: &str, network: Network) -> Result<Amount, Error> {
+        let chain_config = Builder::new(network.into()).build();
+        Self::from_decimal_string(value, chain_config.coin_decimals())
+    }
+
     fn as_internal_amount(&self) -> Result<primitives::Amount, Error> {
         UnsignedIntType::from_str(&self.atoms)
             .ok()
@@ -99,6 +121,22 @@ impl Amount {
     }
 }
 
+/// Format an amount as a decimal string, e.g. "12.345", given the number of decimals
+/// of the token or coin. The trailing zeros after the decimal point are omitted.
+#[wasm_bindgen]
+pub fn amount_to_decimal_string(amount: Amount, decimals: u8) -> Result<String, Error> {
+    let amount = amount.as_internal_amount()?;
+    Ok(amount.into_fixedpoint_str(decimals))
+}
+
+/// Format a coin amount as a decimal string, e.g. "12.345",
+/// using the number of coin decimals of the network.
+#[wasm_bindgen]
+pub fn coin_amount_to_decimal_string(amount: Amount, network: Network) -> Result<String, Error> {
+    let chain_config = Builder::new(network.into()).build();
+    amount_to_decimal_string(amount, chain_config.coin_decimals())
+}
+
 #[wasm_bindgen]
 /// The network, for which an operation to be done. Mainnet, testnet, etc.
 pub enum Network {
@@ -135,6 +173,22 @@ impl From<FreezableToken> for IsTokenFreezable {
     }
 }
 
+/// Indicates whether a token can be unfrozen after being frozen
+#[wasm_bindgen]
+pub enum TokenUnfreezable {
+    No,
+    Yes,
+}
+
+impl From<TokenUnfreezable> for IsTokenUnfreezable {
+    fn from(value: TokenUnfreezable) -> Self {
+        match value {
+            TokenUnfreezable::No => IsTokenUnfreezable::No,
+            TokenUnfreezable::Yes => IsTokenUnfreezable::Yes,
+        }
+    }
+}
+
 /// The token supply of a specific token, set on issuance
 #[wasm_bindgen]
 pub enum TotalSupply {
@@ -214,6 +268,19 @@ pub fn make_private_key() -> Vec<u8> {
 /// derivation path: 44'/mintlayer_coin_type'/0'
 #[wasm_bindgen]
 pub fn make_default_account_privkey(mnemonic: &str, network: Network) -> Result<Vec<u8>, Error> {
+    make_account_privkey(mnemonic, network, 0)
+}
+
+/// Create the extended private key of the account with the given index for a given mnemonic
+/// derivation path: 44'/mintlayer_coin_type'/account_index'
+#[wasm_bindgen]
+pub fn make_account_privkey(
+    mnemonic: &str,
+    network: Network,
+    account_index: u32,
+) -> Result<Vec<u8>, Error> {
+    let account_index = U31::from_u32(account_index).ok_or(Error::InvalidAccountIndex)?;
+
     let mnemonic = bip39::Mnemonic::parse_in(Language::English, mnemonic)
         .map_err(|_| Error::InvalidMnemonic)?;
     let seed = mnemonic.to_seed("");
@@ -223,7 +290,6 @@ pub fn make_default_account_privkey(mnemonic: &str, network: Network) -> Result<
 
     let chain_config = Builder::new(network.into()).build();
 
-    let account_index = U31::ZERO;
     let path = vec![
         BIP44_PATH,
         chain_config.bip44_coin_type(),
@@ -237,6 +303,26 @@ pub fn make_default_account_privkey(mnemonic: &str, network: Network) -> Result<
     Ok(account_privkey.encode())
 }
 
+/// From an extended private key derive the private key with the given derivation path.
+/// The path is absolute and must start with the path of the extended key,
+/// e.g. for an account key created with `make_account_privkey` the path could be
+/// "m/44'/19788'/1'/0/5" (hardened path components are marked with an apostrophe).
+#[wasm_bindgen]
+pub fn derive_path(private_key_bytes: &[u8], path: &str) -> Result<Vec<u8>, Error> {
+    let extended_privkey = ExtendedPrivateKey::decode_all(&mut &private_key_bytes[..])
+        .map_err(|_| Error::InvalidPrivateKeyEncoding)?;
+
+    let path =
+        DerivationPath::from_str(path).map_err(|e| Error::InvalidDerivationPath(e.to_string()))?;
+
+    let private_key: PrivateKey = extended_privkey
+        .derive_absolute_path(&path)
+        .map_err(|e| Error::InvalidDerivationPath(e.to_string()))?
+        .private_key();
+
+    Ok(private_key.encode())
+}
+
 /// From an extended private key create a receiving private key for a given key index
 /// derivation path: 44'/mintlayer_coin_type'/0'/0/key_index
 #[wasm_bindgen]
@@ -432,6 +518,36 @@ pub fn staking_pool_spend_maturity_block_count(current_block_height: u64, networ
         .to_int()
 }
 
+/// Given the current block height and a network type (mainnet, testnet, etc),
+/// this function returns the number of blocks, after which the coins withdrawn from a delegation
+/// will be unlocked and available for spending.
+/// The current block height information is used in case a network upgrade changed the value.
+#[wasm_bindgen]
+pub fn delegation_spend_maturity_block_count(current_block_height: u64, network: Network) -> u64 {
+    let chain_config = Builder::new(network.into()).build();
+    chain_config
+        .staking_pool_spend_maturity_block_count(BlockHeight::new(current_block_height))
+        .to_int()
+}
+
+/// Given a network type (mainnet, testnet, etc), this function returns the target time
+/// between blocks in seconds.
+#[wasm_bindgen]
+pub fn target_block_spacing_seconds(network: Network) -> u64 {
+    let chain_config = Builder::new(network.into()).build();
+    chain_config.target_block_spacing().as_secs()
+}
+
+/// Given a number of blocks and a network type (mainnet, testnet, etc),
+/// this function returns the estimated number of seconds it will take for that many blocks to be produced,
+/// based on the target time between blocks. This can be used to display the time remaining
+/// until a locked output can be spent.
+#[wasm_bindgen]
+pub fn block_count_to_estimated_seconds(block_count: u64, network: Network) -> u64 {
+    let chain_config = Builder::new(network.into()).build();
+    chain_config.target_block_spacing().as_secs().saturating_mul(block_count)
+}
+
 /// Given a number of blocks, this function returns the output timelock
 /// which is used in locked outputs to lock an output for a given number of blocks
 /// since that output's transaction is included the blockchain
@@ -888,6 +1004,117 @@ pub fn encode_input_for_withdraw_from_delegation(
     Ok(input.encode())
 }
 
+fn encode_input_for_account_command(nonce: u64, command: AccountCommand) -> Vec<u8> {
+    TxInput::AccountCommand(AccountNonce::new(nonce), command).encode()
+}
+
+/// Given a token id, an amount of tokens to mint and a network type (mainnet, testnet, etc),
+/// this function creates an input that mints new tokens.
+/// A nonce is needed because this spends from an account. The nonce must be in sequence for everything in that account.
+#[wasm_bindgen]
+pub fn encode_input_for_mint_tokens(
+    token_id: &str,
+    amount: Amount,
+    nonce: u64,
+    network: Network,
+) -> Result<Vec<u8>, Error> {
+    let chain_config = Builder::new(network.into()).build();
+    let amount = amount.as_internal_amount()?;
+    let token_id = parse_addressable(&chain_config, token_id)?;
+    Ok(encode_input_for_account_command(
+        nonce,
+        AccountCommand::MintTokens(token_id, amount),
+    ))
+}
+
+/// Given a token id and a network type (mainnet, testnet, etc),
+/// this function creates an input that unmints the tokens burned in the same transaction.
+/// A nonce is needed because this spends from an account. The nonce must be in sequence for everything in that account.
+#[wasm_bindgen]
+pub fn encode_input_for_unmint_tokens(
+    token_id: &str,
+    nonce: u64,
+    network: Network,
+) -> Result<Vec<u8>, Error> {
+    let chain_config = Builder::new(network.into()).build();
+    let token_id = parse_addressable(&chain_config, token_id)?;
+    Ok(encode_input_for_account_command(
+        nonce,
+        AccountCommand::UnmintTokens(token_id),
+    ))
+}
+
+/// Given a token id and a network type (mainnet, testnet, etc),
+/// this function creates an input that permanently locks the token's supply.
+/// A nonce is needed because this spends from an account. The nonce must be in sequence for everything in that account.
+#[wasm_bindgen]
+pub fn encode_input_for_lock_token_supply(
+    token_id: &str,
+    nonce: u64,
+    network: Network,
+) -> Result<Vec<u8>, Error> {
+    let chain_config = Builder::new(network.into()).build();
+    let token_id = parse_addressable(&chain_config, token_id)?;
+    Ok(encode_input_for_account_command(
+        nonce,
+        AccountCommand::LockTokenSupply(token_id),
+    ))
+}
+
+/// Given a token id, whether the token can be unfrozen later and a network type (mainnet, testnet, etc),
+/// this function creates an input that freezes the token.
+/// A nonce is needed because this spends from an account. The nonce must be in sequence for everything in that account.
+#[wasm_bindgen]
+pub fn encode_input_for_freeze_token(
+    token_id: &str,
+    is_token_unfreezable: TokenUnfreezable,
+    nonce: u64,
+    network: Network,
+) -> Result<Vec<u8>, Error> {
+    let chain_config = Builder::new(network.into()).build();
+    let token_id = parse_addressable(&chain_config, token_id)?;
+    Ok(encode_input_for_account_command(
+        nonce,
+        AccountCommand::FreezeToken(token_id, is_token_unfreezable.into()),
+    ))
+}
+
+/// Given a token id and a network type (mainnet, testnet, etc),
+/// this function creates an input that unfreezes the token.
+/// A nonce is needed because this spends from an account. The nonce must be in sequence for everything in that account.
+#[wasm_bindgen]
+pub fn encode_input_for_unfreeze_token(
+    token_id: &str,
+    nonce: u64,
+    network: Network,
+) -> Result<Vec<u8>, Error> {
+    let chain_config = Builder::new(network.into()).build();
+    let token_id = parse_addressable(&chain_config, token_id)?;
+    Ok(encode_input_for_account_command(
+        nonce,
+        AccountCommand::UnfreezeToken(token_id),
+    ))
+}
+
+/// Given a token id, the address of the new authority and a network type (mainnet, testnet, etc),
+/// this function creates an input that changes the token's authority.
+/// A nonce is needed because this spends from an account. The nonce must be in sequence for everything in that account.
+#[wasm_bindgen]
+pub fn encode_input_for_change_token_authority(
+    token_id: &str,
+    new_authority: &str,
+    nonce: u64,
+    network: Network,
+) -> Result<Vec<u8>, Error> {
+    let chain_config = Builder::new(network.into()).build();
+    let token_id = parse_addressable(&chain_config, token_id)?;
+    let new_authority = parse_addressable(&chain_config, new_authority)?;
+    Ok(encode_input_for_account_command(
+        nonce,
+        AccountCommand::ChangeTokenAuthority(token_id, new_authority),
+    ))
+}
+
 /// Given the inputs, along each input's destination that can spend that input
 /// (e.g. If we are spending a UTXO in input number 1 and it is owned by address mtc1xxxx, then it is mtc1xxxx in element number 2 in the vector/list.
 /// for Account inputs that spend from a delegation it is the owning address of that delegation,
@@ -1180,6 +1407,51 @@ pub fn encode_signed_transaction(
     Ok(tx.encode())
 }
 
+/// Given a `Transaction` encoded in bytes and a network type (mainnet, testnet, etc),
+/// this function returns a JSON object describing the transaction: its id, inputs and outputs.
+/// Coin amounts are given both in atoms and in decimal form, destinations, token ids, pool ids etc.
+/// are given as bech32 addresses of the given network.
+#[wasm_bindgen]
+pub fn decode_transaction(transaction_bytes: &[u8], network: Network) -> Result<JsValue, Error> {
+    let chain_config = Builder::new(network.into()).build();
+
+    let tx = Transaction::decode_all(&mut &transaction_bytes[..])
+        .map_err(|_| Error::InvalidTransaction)?;
+
+    json_to_js_value(decode::transaction_to_json(&chain_config, &tx)?)
+}
+
+/// Given a `SignedTransaction` encoded in bytes and a network type (mainnet, testnet, etc),
+/// this function returns a JSON object describing the transaction in the same way as `decode_transaction`,
+/// with the witnesses of the inputs included in the `signatures` field.
+#[wasm_bindgen]
+pub fn decode_signed_transaction(
+    transaction_bytes: &[u8],
+    network: Network,
+) -> Result<JsValue, Error> {
+    let chain_config = Builder::new(network.into()).build();
+
+    let tx = SignedTransaction::decode_all(&mut &transaction_bytes[..])
+        .map_err(|_| Error::InvalidTransaction)?;
+
+    json_to_js_value(decode::signed_transaction_to_json(&chain_config, &tx)?)
+}
+
+/// Given a transaction output encoded in bytes and a network type (mainnet, testnet, etc),
+/// this function returns a JSON object describing the output.
+#[wasm_bindgen]
+pub fn decode_tx_output(output_bytes: &[u8], network: Network) -> Result<JsValue, Error> {
+    let chain_config = Builder::new(network.into()).build();
+
+    let output = TxOutput::decode_all(&mut &output_bytes[..]).map_err(|_| Error::InvalidOutput)?;
+
+    json_to_js_value(decode::tx_output_to_json(&chain_config, &output)?)
+}
+
+fn json_to_js_value(value: serde_json::Value) -> Result<JsValue, Error> {
+    js_sys::JSON::parse(&value.to_string()).map_err(|_| Error::JsonConversionFailed)
+}
+
 /// Given a `Transaction` encoded in bytes (not a signed transaction, but a signed transaction is tolerated by ignoring the extra bytes, by choice)
 /// this function will return the transaction id.
 ///
@@ -1235,6 +1507,36 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn decimal_amounts() {
+        let amount = Amount::from_decimal_string("12.345", 4).unwrap();
+        assert_eq!(amount.atoms(), "123450");
+
+        let amount = Amount::from_decimal_string("7", 2).unwrap();
+        assert_eq!(amount.atoms(), "700");
+
+        // More decimal places than the token has
+        assert!(Amount::from_decimal_string("0.001", 2).is_err());
+        assert!(Amount::from_decimal_string("1,5", 2).is_err());
+
+        let amount = Amount::from_coins_decimal_string("12.345", Network::Mainnet).unwrap();
+        assert_eq!(amount.atoms(), "1234500000000");
+
+        assert_eq!(
+            amount_to_decimal_string(Amount::from_atoms("123450".to_owned()), 4).unwrap(),
+            "12.345"
+        );
+        assert_eq!(
+            coin_amount_to_decimal_string(
+                Amount::from_atoms("1234500000000".to_owned()),
+                Network::Testnet
+            )
+            .unwrap(),
+            "12.345"
+        );
+        assert!(amount_to_decimal_string(Amount::from_atoms("1.5".to_owned()), 4).is_err());
+    }
+
     #[rstest]
     #[trace]
     #[case(Seed::from_entropy())]
@@ -1305,4 +1607,215 @@ mod tests {
             expected_tx_id
         );
     }
+
+    #[test]
+    fn transaction_decode() {
+        let expected_tx_id = "35a7938c2a2aad5ae324e7d0536de245bf9e439169aa3c16f1492be117e5d0e0";
+        let tx_signed_hex = "0100040000ff5d9a94390ee97208d31aa5c3b5ddbd8df9d308069df2ebf5283f7ce3e4261401000000080340f9924e4da0af7dc8c5be71a9c9e05962c7bf4ef96127fde7a7b4e1469e48620f0080e03779c31102000365807e3b4147cb978b78715e60606092f89dc769586e98456850bd3b449c87b400203015e9ef9fc142569e0f966bc0188464fa712a841e14002e0fe952a076a26c01e539c5f0ceba927ab8f8f55f274af739ce4eef3700000b00204aa9d10100000b409e4c355d010199e4ec3a5b176140ef9cd58c7d3579fdb0ecb21a0401018d010002eddd003bfb6333123e682abe6923da1d38faa4f0e0d9e2ee42d5aa46c152a34800a749a30c8c9c33696ce407fc145ebc9824e17b778d0d9ccc8129be52f37b74160e60f6689ac2f481071e1a63d9cf0f6eab84c2703b5e9f229cd8188ce092edd4";
+
+        let chain_config = Builder::new(ChainType::Testnet).build();
+        let tx_signed_bin = hex::decode(tx_signed_hex).unwrap();
+        let tx = SignedTransaction::decode_all(&mut &tx_signed_bin[..]).unwrap();
+
+        let json = decode::signed_transaction_to_json(&chain_config, &tx).unwrap();
+        assert_eq!(json["id"], expected_tx_id);
+        assert_eq!(json["inputs"].as_array().unwrap().len(), 1);
+        assert_eq!(json["inputs"][0]["type"], "Utxo");
+        assert_eq!(json["outputs"].as_array().unwrap().len(), 2);
+        assert_eq!(json["outputs"][0]["type"], "CreateStakePool");
+        assert_eq!(json["signatures"].as_array().unwrap().len(), 1);
+        assert_eq!(json["signatures"][0]["type"], "Standard");
+
+        for (output, output_json) in tx.outputs().iter().zip(json["outputs"].as_array().unwrap()) {
+            assert_eq!(
+                &decode::tx_output_to_json(&chain_config, output).unwrap(),
+                output_json
+            );
+        }
+
+        let tx_json = decode::transaction_to_json(&chain_config, tx.transaction()).unwrap();
+        assert_eq!(tx_json["id"], json["id"]);
+        assert_eq!(tx_json["outputs"], json["outputs"]);
+        assert!(tx_json.get("signatures").is_none());
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn encode_nft_issuance(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let chain_config = Builder::new(ChainType::Testnet).build();
+
+        let (_, creator_public_key) = PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let (_, authority_public_key) =
+            PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let token_id = TokenId::new(H256::random_using(&mut rng));
+        let authority = Destination::PublicKeyHash(PublicKeyHash::from(&authority_public_key));
+        let creator = Destination::PublicKey(creator_public_key.clone());
+
+        let encode = |creator: &Destination| {
+            encode_output_issue_nft(
+                Address::new(&chain_config, token_id).unwrap().as_str(),
+                Address::new(&chain_config, authority.clone()).unwrap().as_str(),
+                "nft",
+                "XXX",
+                "description",
+                b"media hash",
+                Some(Address::new(&chain_config, creator.clone()).unwrap().into_string()),
+                Some(b"https://media".to_vec()),
+                Some(b"https://icon".to_vec()),
+                Some(b"https://metadata".to_vec()),
+                1,
+                Network::Testnet,
+            )
+        };
+
+        let encoded = encode(&creator).unwrap();
+        let expected_output = TxOutput::IssueNft(
+            token_id,
+            Box::new(NftIssuance::V0(NftIssuanceV0 {
+                metadata: Metadata {
+                    creator: Some(TokenCreator {
+                        public_key: creator_public_key,
+                    }),
+                    name: b"nft".to_vec(),
+                    description: b"description".to_vec(),
+                    ticker: b"XXX".to_vec(),
+                    icon_uri: b"https://icon".to_vec().into(),
+                    additional_metadata_uri: b"https://metadata".to_vec().into(),
+                    media_uri: b"https://media".to_vec().into(),
+                    media_hash: b"media hash".to_vec(),
+                },
+            })),
+            authority.clone(),
+        );
+        assert_eq!(
+            TxOutput::decode_all(&mut encoded.as_slice()).unwrap(),
+            expected_output
+        );
+
+        // The creator must be a public key
+        assert_eq!(
+            encode(&authority).unwrap_err().to_string(),
+            Error::InvalidCreatorPublicKey.to_string()
+        );
+    }
+
+    #[rstest]
+    #[trace]
+    #[case(Seed::from_entropy())]
+    fn encode_token_account_commands(#[case] seed: Seed) {
+        let mut rng = make_seedable_rng(seed);
+        let chain_config = Builder::new(ChainType::Testnet).build();
+
+        let token_id = TokenId::new(H256::random_using(&mut rng));
+        let token_id_str = Address::new(&chain_config, token_id).unwrap().into_string();
+        let (_, authority_public_key) =
+            PrivateKey::new_from_rng(&mut rng, KeyKind::Secp256k1Schnorr);
+        let new_authority = Destination::PublicKeyHash(PublicKeyHash::from(&authority_public_key));
+        let nonce = rng.gen::<u64>();
+        let amount = primitives::Amount::from_atoms(rng.gen_range(1..1_000_000));
+
+        let check = |encoded: Vec<u8>, expected_command: AccountCommand| {
+            assert_eq!(
+                TxInput::decode_all(&mut encoded.as_slice()).unwrap(),
+                TxInput::AccountCommand(AccountNonce::new(nonce), expected_command)
+            );
+        };
+
+        check(
+            encode_input_for_mint_tokens(
+                &token_id_str,
+                Amount::from_internal_amount(amount),
+                nonce,
+                Network::Testnet,
+            )
+            .unwrap(),
+            AccountCommand::MintTokens(token_id, amount),
+        );
+        check(
+            encode_input_for_unmint_tokens(&token_id_str, nonce, Network::Testnet).unwrap(),
+            AccountCommand::UnmintTokens(token_id),
+        );
+        check(
+            encode_input_for_lock_token_supply(&token_id_str, nonce, Network::Testnet).unwrap(),
+            AccountCommand::LockTokenSupply(token_id),
+        );
+        check(
+            encode_input_for_freeze_token(
+                &token_id_str,
+                TokenUnfreezable::Yes,
+                nonce,
+                Network::Testnet,
+            )
+            .unwrap(),
+            AccountCommand::FreezeToken(token_id, IsTokenUnfreezable::Yes),
+        );
+        check(
+            encode_input_for_unfreeze_token(&token_id_str, nonce, Network::Testnet).unwrap(),
+            AccountCommand::UnfreezeToken(token_id),
+        );
+        check(
+            encode_input_for_change_token_authority(
+                &token_id_str,
+                Address::new(&chain_config, new_authority.clone()).unwrap().as_str(),
+                nonce,
+                Network::Testnet,
+            )
+            .unwrap(),
+            AccountCommand::ChangeTokenAuthority(token_id, new_authority),
+        );
+
+        // The token id must be for the same network
+        encode_input_for_unmint_tokens(&token_id_str, nonce, Network::Mainnet).unwrap_err();
+    }
+
+    #[test]
+    fn derive_account_keys() {
+        let mnemonic = "walk exile faculty near leg neutral license matrix maple invite cupboard hat opinion excess coffee leopard latin regret document core limb crew dizzy movie";
+
+        let default_account = make_default_account_privkey(mnemonic, Network::Mainnet).unwrap();
+        assert_eq!(
+            make_account_privkey(mnemonic, Network::Mainnet, 0).unwrap(),
+            default_account
+        );
+
+        let account1 = make_account_privkey(mnemonic, Network::Mainnet, 1).unwrap();
+        assert_ne!(account1, default_account);
+        assert_eq!(
+            make_account_privkey(mnemonic, Network::Mainnet, 1 << 31)
+                .unwrap_err()
+                .to_string(),
+            Error::InvalidAccountIndex.to_string()
+        );
+
+        // derive_path must produce the same keys as the dedicated functions
+        let coin_type = Builder::new(ChainType::Mainnet).build().bip44_coin_type();
+        for (account_index, account_privkey) in [(0, &default_account), (1, &account1)] {
+            let receiving_path = format!("m/44'/{coin_type}/{account_index}'/0/5");
+            assert_eq!(
+                derive_path(account_privkey, &receiving_path).unwrap(),
+                make_receiving_address(account_privkey, 5).unwrap()
+            );
+            let change_path = format!("m/44'/{coin_type}/{account_index}'/1/3");
+            assert_eq!(
+                derive_path(account_privkey, &change_path).unwrap(),
+                make_change_address(account_privkey, 3).unwrap()
+            );
+        }
+
+        // The path must extend the path of the key
+        let wrong_account_path = format!("m/44'/{coin_type}/1'/0/5");
+        assert!(matches!(
+            derive_path(&default_account, &wrong_account_path),
+            Err(Error::InvalidDerivationPath(_))
+        ));
+        // Malformed paths
+        for path in ["", "44'/0'", "m/44'/x", "m/44''"] {
+            assert!(matches!(
+                derive_path(&default_account, path),
+                Err(Error::InvalidDerivationPath(_))
+            ));
+        }
+    }
 }